@@ -28,7 +28,7 @@ use alloc::{
 };
 use core::{
     borrow::Borrow,
-    fmt::Debug,
+    fmt::{self, Debug},
     hash::Hash,
     iter::{self, FusedIterator},
     ops::{Add, AddAssign, Deref, Sub, SubAssign},
@@ -36,7 +36,6 @@ use core::{
 };
 use derive_more::{Display, From};
 use manta_crypto::{
-    constraint::{HasInput, Input},
     eclair::{
         self,
         alloc::{
@@ -48,11 +47,12 @@ use manta_crypto::{
         ops::BitAnd,
         Has,
     },
+    impl_input,
     rand::{Rand, RngCore, Sample},
 };
 use manta_util::{
     codec::{Decode, DecodeError, Encode, Write},
-    num::CheckedSub,
+    num::{CheckedAdd, CheckedSub, SaturatingAdd},
     SizeLimit,
 };
 
@@ -346,14 +346,165 @@ where
     }
 }
 
-impl<I, V, P> Input<P> for Asset<I, V>
+impl_input! {
+    impl<I, V> Input<P> for Asset<I, V>
+    |asset| {
+        I => &asset.id,
+        V => &asset.value,
+    }
+}
+
+/// Balance Error
+///
+/// This `enum` is the error state of the checked arithmetic of [`CheckedBalance`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum BalanceError {
+    /// Overflow
+    ///
+    /// The result is larger than the maximum asset value.
+    Overflow,
+
+    /// Underflow
+    ///
+    /// The result is smaller than zero.
+    Underflow,
+}
+
+/// Checked Balance
+///
+/// Asset value whose arithmetic is checked, so that aggregating many asset values returns a
+/// [`BalanceError`] instead of silently wrapping around. Balances which are only displayed can use
+/// [`saturating_add`](Self::saturating_add) instead, which stops at the maximum asset value and
+/// marks the balance as a lower bound of the true sum.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct CheckedBalance<V> {
+    /// Balance Value
+    value: V,
+
+    /// Saturation Flag
+    saturated: bool,
+}
+
+impl<V> CheckedBalance<V> {
+    /// Builds a new [`CheckedBalance`] from `value`.
+    #[inline]
+    pub const fn new(value: V) -> Self {
+        Self {
+            value,
+            saturated: false,
+        }
+    }
+
+    /// Returns the value of `self`.
+    #[inline]
+    pub fn value(&self) -> &V {
+        &self.value
+    }
+
+    /// Returns the value of `self`, dropping the saturation flag.
+    #[inline]
+    pub fn into_inner(self) -> V {
+        self.value
+    }
+
+    /// Returns `true` if an addition to `self` saturated, in which case its value is only a lower
+    /// bound of the true sum.
+    #[inline]
+    pub fn is_saturated(&self) -> bool {
+        self.saturated
+    }
+
+    /// Adds `rhs` to `self`, returning [`BalanceError::Overflow`] and leaving `self` unchanged if
+    /// the sum is larger than the maximum asset value.
+    #[inline]
+    pub fn checked_add(&mut self, rhs: V) -> Result<&mut Self, BalanceError>
+    where
+        V: Clone + CheckedAdd<Output = V>,
+    {
+        self.value = self
+            .value
+            .clone()
+            .checked_add(rhs)
+            .ok_or(BalanceError::Overflow)?;
+        Ok(self)
+    }
+
+    /// Subtracts `rhs` from `self`, returning [`BalanceError::Underflow`] and leaving `self`
+    /// unchanged if `rhs` is larger than `self`.
+    #[inline]
+    pub fn checked_sub(&mut self, rhs: V) -> Result<&mut Self, BalanceError>
+    where
+        V: Clone + CheckedSub<Output = V>,
+    {
+        self.value = self
+            .value
+            .clone()
+            .checked_sub(rhs)
+            .ok_or(BalanceError::Underflow)?;
+        Ok(self)
+    }
+
+    /// Adds `rhs` to `self`, stopping at the maximum asset value and marking `self` as saturated
+    /// if the sum is larger.
+    #[inline]
+    pub fn saturating_add(&mut self, rhs: V) -> &mut Self
+    where
+        V: Clone + CheckedAdd<Output = V> + SaturatingAdd<Output = V>,
+    {
+        match self.value.clone().checked_add(rhs.clone()) {
+            Some(value) => self.value = value,
+            _ => {
+                self.value = self.value.clone().saturating_add(rhs);
+                self.saturated = true;
+            }
+        }
+        self
+    }
+
+    /// Sums all the `values`, returning [`BalanceError::Overflow`] if the sum is larger than the
+    /// maximum asset value.
+    #[inline]
+    pub fn try_sum<A>(values: A) -> Result<V, BalanceError>
+    where
+        V: Clone + CheckedAdd<Output = V> + Default,
+        A: IntoIterator<Item = V>,
+    {
+        let mut sum = Self::default();
+        for value in values {
+            sum.checked_add(value)?;
+        }
+        Ok(sum.into_inner())
+    }
+}
+
+impl<V> From<V> for CheckedBalance<V> {
+    #[inline]
+    fn from(value: V) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<V> fmt::Display for CheckedBalance<V>
 where
-    P: HasInput<I> + HasInput<V> + ?Sized,
+    V: fmt::Display,
 {
     #[inline]
-    fn extend(&self, input: &mut P::Input) {
-        P::extend(input, &self.id);
-        P::extend(input, &self.value);
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.saturated {
+            write!(f, ">= {}", self.value)
+        } else {
+            write!(f, "{}", self.value)
+        }
     }
 }
 
@@ -470,6 +621,28 @@ impl<I, V> AssetList<I, V> {
         }
     }
 
+    /// Inserts `asset` into `self` like [`deposit`](Self::deposit), returning a [`BalanceError`]
+    /// and leaving `self` unchanged if the value at `asset.id` would overflow.
+    #[inline]
+    pub fn try_deposit(&mut self, asset: Asset<I, V>) -> Result<(), BalanceError>
+    where
+        I: Ord,
+        V: Clone + CheckedAdd<Output = V> + Default + PartialEq,
+    {
+        if asset.value == Default::default() {
+            return Ok(());
+        }
+        match self.find(&asset.id) {
+            Ok(index) => {
+                let mut balance = CheckedBalance::new(self.map[index].value.clone());
+                balance.checked_add(asset.value)?;
+                self.map[index].value = balance.into_inner();
+            }
+            Err(index) => self.map.insert(index, asset),
+        }
+        Ok(())
+    }
+
     /// Sets the value at the `index` to `value` or removes the entry at `index` if `value == 0`.
     #[inline]
     fn set_or_remove(&mut self, index: usize, value: V)
@@ -702,6 +875,16 @@ pub trait AssetMap<I, V>: Default {
     /// Returns at most `n` zero assets with the given `id`.
     fn zeroes(&self, n: usize, id: &I) -> Vec<Self::Key>;
 
+    /// Returns the keys and values of at most `n` nonzero assets with the given `id`, in
+    /// decreasing order of value.
+    fn largest(&self, n: usize, id: &I) -> Vec<(Self::Key, V)>;
+
+    /// Returns `true` if `asset` is stored at the `key` in the map.
+    fn contains(&self, key: &Self::Key, asset: &Asset<I, V>) -> bool;
+
+    /// Returns every asset stored in the map together with its key.
+    fn entries(&self) -> Vec<(Self::Key, Asset<I, V>)>;
+
     /// Inserts `asset` at the `key` in the map.
     fn insert(&mut self, key: Self::Key, asset: Asset<I, V>);
 
@@ -775,7 +958,7 @@ macro_rules! impl_asset_map_for_maps_body {
             if asset.value == Default::default() {
                 return Selection::default();
             }
-            let mut sum = Asset::<$I, $V>::zero(asset.id.clone());
+            let mut sum = CheckedBalance::<$V>::default();
             let mut values = Vec::new();
             let mut min_max_asset = Option::<(&$K, &$V)>::None;
             let map = self
@@ -797,9 +980,17 @@ macro_rules! impl_asset_map_for_maps_body {
                     });
                 } else if value == &asset.value {
                     return Selection::new(Default::default(), vec![(key.clone(), value.clone())]);
+                } else if sum.checked_add(value.clone()).is_ok() {
+                    values.push((key.clone(), value.clone()));
                 } else {
-                    sum.value.add_assign(value.clone());
+                    // NOTE: Adding `value` overflows the sum of the selected values, so the sum
+                    //       reaches `asset.value` either without `value` or with the part of
+                    //       `value` which is not returned as change.
+                    if sum.value() >= &asset.value {
+                        return Selection::new(sum.value() - &asset.value, values);
+                    }
                     values.push((key.clone(), value.clone()));
+                    return Selection::new(value - &(&asset.value - sum.value()), values);
                 }
             }
             if let Some((best_key, best_value)) = min_max_asset {
@@ -808,10 +999,10 @@ macro_rules! impl_asset_map_for_maps_body {
                     vec![(best_key.clone(), best_value.clone())],
                 );
             }
-            if sum.value < asset.value {
+            if sum.value() < &asset.value {
                 Selection::default()
             } else {
-                Selection::new(&sum.value - &asset.value, values)
+                Selection::new(sum.value() - &asset.value, values)
             }
         }
 
@@ -828,6 +1019,37 @@ macro_rules! impl_asset_map_for_maps_body {
                 .collect()
         }
 
+        #[inline]
+        fn largest(&self, n: usize, id: &$I) -> Vec<(Self::Key, $V)> {
+            let mut values = self
+                .iter()
+                .flat_map(move |(key, assets)| assets.iter().map(move |asset| (key, asset)))
+                .filter_map(move |(key, asset)| {
+                    (&asset.id == id && asset.value != Default::default())
+                        .then(move || (key.clone(), asset.value.clone()))
+                })
+                .collect::<Vec<_>>();
+            values.sort_by(|lhs, rhs| rhs.1.cmp(&lhs.1));
+            values.truncate(n);
+            values
+        }
+
+        #[inline]
+        fn contains(&self, key: &Self::Key, asset: &Asset<$I, $V>) -> bool {
+            self.get(key)
+                .map(move |assets| assets.binary_search(asset).is_ok())
+                .unwrap_or(false)
+        }
+
+        #[inline]
+        fn entries(&self) -> Vec<(Self::Key, Asset<$I, $V>)> {
+            self.iter()
+                .flat_map(move |(key, assets)| {
+                    assets.iter().map(move |asset| (key.clone(), asset.clone()))
+                })
+                .collect()
+        }
+
         #[inline]
         fn insert(&mut self, key: Self::Key, asset: Asset<$I, $V>) {
             match self.entry(key) {
@@ -875,7 +1097,13 @@ impl<K, I, V> AssetMap<I, V> for BTreeAssetMap<K, I, V>
 where
     K: Clone + Ord,
     I: Clone + Ord,
-    V: AddAssign + Clone + Default + Ord + Sub<Output = V> + for<'v> AddAssign<&'v V>,
+    V: AddAssign
+        + CheckedAdd<Output = V>
+        + Clone
+        + Default
+        + Ord
+        + Sub<Output = V>
+        + for<'v> AddAssign<&'v V>,
     for<'v> &'v V: Sub<Output = V>,
 {
     impl_asset_map_for_maps_body! { K, I, V, BTreeMapEntry }
@@ -892,7 +1120,13 @@ impl<K, I, V, S> AssetMap<I, V> for HashAssetMap<K, I, V, S>
 where
     K: Clone + Hash + Eq,
     I: Clone + Ord,
-    V: AddAssign + Clone + Default + Ord + Sub<Output = V> + for<'v> AddAssign<&'v V>,
+    V: AddAssign
+        + CheckedAdd<Output = V>
+        + Clone
+        + Default
+        + Ord
+        + Sub<Output = V>
+        + for<'v> AddAssign<&'v V>,
     for<'v> &'v V: Sub<Output = V>,
     S: BuildHasher + Default,
 {
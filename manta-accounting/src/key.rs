@@ -250,7 +250,7 @@ pub trait DeriveAddresses {
 #[derivative(
     Clone(bound = "H: Clone"),
     Copy(bound = "H: Copy"),
-    Debug(bound = "H: Debug"),
+    Debug(bound = ""),
     Eq(bound = "H: Eq"),
     Hash(bound = "H: Hash"),
     PartialEq(bound = "H: PartialEq")
@@ -260,6 +260,7 @@ where
     H: AccountCollection,
 {
     /// Account Collection
+    #[derivative(Debug(format_with = "manta_util::fmt::redact"))]
     key: H,
 
     /// Index
@@ -316,7 +317,7 @@ where
 #[derivative(
     Clone(bound = "H: Clone, M: Clone"),
     Copy(bound = "H: Copy, M: Copy"),
-    Debug(bound = "H: Debug, M: Debug"),
+    Debug(bound = "M: Debug"),
     Eq(bound = "H: Eq, M: Eq"),
     Hash(bound = "H: Hash, M: Hash"),
     PartialEq(bound = "H: PartialEq, M: PartialEq")
@@ -327,6 +328,7 @@ where
     M: AccountMap<Account = AccountIndex>,
 {
     /// Account Collection
+    #[derivative(Debug(format_with = "manta_util::fmt::redact"))]
     keys: H,
 
     /// Account Map
@@ -15,6 +15,12 @@
 // along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Batched Transfers
+//!
+//! When the notes selected for a payment do not fit into a single transfer, they are merged by
+//! rounds of [`Join`]s until few enough of them are left. The joins of the same round do not
+//! depend on each other and can be proven in parallel, while every round has to wait for the
+//! previous one. The [`BatchPlanner`] uses the measured proving time of every available join
+//! circuit to choose the join sizes and the number of parallel provers for such a consolidation.
 
 // TODO: Move more of the batching algorithm here to improve library interfaces.
 
@@ -23,7 +29,7 @@ use crate::transfer::{
     Parameters, PreSender, Receiver, UtxoAccumulatorItem, UtxoAccumulatorModel,
 };
 use alloc::vec::Vec;
-use core::{fmt::Debug, hash::Hash};
+use core::{fmt::Debug, hash::Hash, time::Duration};
 use manta_crypto::{
     accumulator::Accumulator,
     rand::{CryptoRng, RngCore},
@@ -121,3 +127,219 @@ where
         }
     }
 }
+
+/// Join Profile
+///
+/// Measured proving time of a join circuit which merges up to `senders` notes into one, for
+/// example with the benchmarking harness. Joins of fewer notes are padded with zero notes, so
+/// they take the same time.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct JoinProfile {
+    /// Number of Senders
+    pub senders: usize,
+
+    /// Proving Time of a Single Join
+    pub proving_time: Duration,
+}
+
+impl JoinProfile {
+    /// Builds a new [`JoinProfile`] from `senders` and `proving_time`.
+    #[inline]
+    pub fn new(senders: usize, proving_time: Duration) -> Self {
+        Self {
+            senders,
+            proving_time,
+        }
+    }
+}
+
+/// Batch Round
+///
+/// Joins which can be proven in parallel because none of them spends the output of another.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct BatchRound {
+    /// Join Profile
+    pub profile: JoinProfile,
+
+    /// Number of Joins
+    pub joins: usize,
+
+    /// Number of Notes Spent by the Joins
+    pub inputs: usize,
+}
+
+impl BatchRound {
+    /// Returns the number of notes left after `self` when starting from `notes` notes.
+    #[inline]
+    pub fn remaining(&self, notes: usize) -> usize {
+        notes - self.inputs + self.joins
+    }
+
+    /// Returns the wall-clock time of `self` when the joins are distributed over `parallelism`
+    /// provers.
+    #[inline]
+    pub fn estimated_time(&self, parallelism: usize) -> Duration {
+        self.profile.proving_time * self.joins.div_ceil(parallelism) as u32
+    }
+}
+
+/// Batch Plan
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct BatchPlan {
+    /// Rounds of Joins in Execution Order
+    pub rounds: Vec<BatchRound>,
+
+    /// Number of Parallel Provers
+    pub parallelism: usize,
+
+    /// Estimated Wall-Clock Time
+    pub estimated_time: Duration,
+}
+
+impl BatchPlan {
+    /// Returns the total number of joins in `self`.
+    #[inline]
+    pub fn joins(&self) -> usize {
+        self.rounds.iter().map(|round| round.joins).sum()
+    }
+
+    /// Returns the total proving time of `self`, summed over all the provers.
+    #[inline]
+    pub fn proving_time(&self) -> Duration {
+        self.rounds
+            .iter()
+            .map(|round| round.profile.proving_time * round.joins as u32)
+            .sum()
+    }
+}
+
+/// Batch Planner
+///
+/// Plans the consolidation of notes with the join circuits described by a set of
+/// [`JoinProfile`]s, using at most `maximum_parallelism` parallel provers. Every round of a plan
+/// only uses one join circuit, and only as many joins as are needed to reach the target number of
+/// notes, so the last round may leave some notes untouched.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct BatchPlanner {
+    /// Join Profiles
+    profiles: Vec<JoinProfile>,
+
+    /// Maximum Number of Parallel Provers
+    maximum_parallelism: usize,
+}
+
+impl BatchPlanner {
+    /// Builds a new [`BatchPlanner`] from `profiles` and `maximum_parallelism`, dropping the
+    /// profiles which cannot merge notes because they have fewer than two senders.
+    #[inline]
+    pub fn new(mut profiles: Vec<JoinProfile>, maximum_parallelism: usize) -> Self {
+        profiles.retain(|profile| profile.senders >= 2);
+        Self {
+            profiles,
+            maximum_parallelism: maximum_parallelism.max(1),
+        }
+    }
+
+    /// Returns the join profiles of `self`.
+    #[inline]
+    pub fn profiles(&self) -> &[JoinProfile] {
+        &self.profiles
+    }
+
+    /// Returns the maximum number of parallel provers of `self`.
+    #[inline]
+    pub fn maximum_parallelism(&self) -> usize {
+        self.maximum_parallelism
+    }
+
+    /// Returns the rounds which merge `notes` notes into at most `target` notes using only the
+    /// join circuit of `profile`.
+    #[inline]
+    fn rounds(profile: JoinProfile, mut notes: usize, target: usize) -> Vec<BatchRound> {
+        let mut rounds = Vec::new();
+        while notes > target {
+            let round = if notes < profile.senders {
+                BatchRound {
+                    profile,
+                    joins: 1,
+                    inputs: notes,
+                }
+            } else {
+                let joins =
+                    (notes / profile.senders).min((notes - target).div_ceil(profile.senders - 1));
+                BatchRound {
+                    profile,
+                    joins,
+                    inputs: joins * profile.senders,
+                }
+            };
+            notes = round.remaining(notes);
+            rounds.push(round);
+        }
+        rounds
+    }
+
+    /// Returns all the plans which merge `notes` notes into at most `target` notes, for every
+    /// join profile and every useful number of parallel provers.
+    #[inline]
+    fn candidates(&self, notes: usize, target: usize) -> impl Iterator<Item = BatchPlan> + '_ {
+        let target = target.max(1);
+        self.profiles.iter().flat_map(move |profile| {
+            let rounds = Self::rounds(*profile, notes, target);
+            let widest = rounds
+                .iter()
+                .map(|round| round.joins)
+                .max()
+                .unwrap_or(1)
+                .min(self.maximum_parallelism);
+            (1..=widest).map(move |parallelism| BatchPlan {
+                estimated_time: rounds
+                    .iter()
+                    .map(|round| round.estimated_time(parallelism))
+                    .sum(),
+                rounds: rounds.clone(),
+                parallelism,
+            })
+        })
+    }
+
+    /// Returns the plan which merges `notes` notes into at most `target` notes in the shortest
+    /// wall-clock time, preferring fewer parallel provers and then less total proving time
+    /// among equally fast plans. Returns `None` if `self` has no join profiles.
+    #[inline]
+    pub fn fastest(&self, notes: usize, target: usize) -> Option<BatchPlan> {
+        self.candidates(notes, target)
+            .min_by_key(|plan| (plan.estimated_time, plan.parallelism, plan.proving_time()))
+    }
+
+    /// Returns the plan which merges `notes` notes into at most `target` notes within `budget`
+    /// using the fewest parallel provers, preferring shorter wall-clock time among plans with
+    /// the same number of provers. Returns `None` if no plan fits into `budget`, in which case
+    /// [`fastest`](Self::fastest) returns the plan which exceeds it the least.
+    #[inline]
+    pub fn plan(&self, notes: usize, target: usize, budget: Duration) -> Option<BatchPlan> {
+        self.candidates(notes, target)
+            .filter(|plan| plan.estimated_time <= budget)
+            .min_by_key(|plan| (plan.parallelism, plan.estimated_time, plan.proving_time()))
+    }
+}
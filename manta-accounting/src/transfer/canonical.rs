@@ -21,17 +21,22 @@
 use crate::{
     asset::{self, AssetMap},
     transfer::{
-        has_public_participants, internal_pair, requires_authorization, utxo::UtxoReconstruct,
-        Address, Asset, AssociatedData, Authorization, AuthorizationContext, Configuration,
-        FullParametersRef, Identifier, Parameters, PreSender, ProofSystemError,
-        ProofSystemPublicParameters, ProvingContext, Receiver, Sender, Transfer, TransferLedger,
-        TransferPost, TransferPostingKeyRef, Utxo, VerifyingContext,
+        dynamic, extend_input, has_public_participants, internal_pair, requires_authorization,
+        utxo::UtxoReconstruct, Address, Asset, AssociatedData, Authorization, AuthorizationContext,
+        Configuration, FullParametersRef, Identifier, Parameters, PreSender, Proof, ProofInput,
+        ProofSystemError, ProofSystemPublicParameters, ProvingContext, Receiver, Sender,
+        SpendingKey, Transfer, TransferLedger, TransferPost, TransferPostBody,
+        TransferPostingKeyRef, TransferVar, Utxo, VerifyingContext,
     },
 };
 use alloc::vec::Vec;
 use core::{fmt::Debug, hash::Hash};
-use manta_crypto::rand::{CryptoRng, RngCore};
-use manta_util::{create_seal, seal};
+use manta_crypto::{
+    constraint::{Input, ProofSystem},
+    eclair::alloc::Allocate,
+    rand::{CryptoRng, RngCore},
+};
+use manta_util::{create_seal, into_array_unchecked, seal};
 
 #[cfg(feature = "serde")]
 use manta_util::serde::{Deserialize, Serialize};
@@ -42,8 +47,13 @@ create_seal! {}
 ///
 /// This trait identifies a transfer shape, i.e. the number and type of participants on the input
 /// and output sides of the transaction. This trait is sealed and can only be used with the
-/// existing canonical implementations.
+/// existing canonical implementations, whose arities are declared in the canonical shape table.
 pub trait Shape: sealed::Sealed {
+    /// Shape Identifier
+    ///
+    /// Name of the proving and verifying contexts of the circuit with this shape.
+    const IDENTIFIER: &'static str;
+
     /// Number of Sources
     const SOURCES: usize;
 
@@ -59,9 +69,10 @@ pub trait Shape: sealed::Sealed {
 
 /// Implements [`Shape`] for a given shape type.
 macro_rules! impl_shape {
-    ($shape:ty, $sources:expr, $senders:expr, $receivers:expr, $sinks:expr) => {
+    ($shape:ty, $identifier:expr, $sources:expr, $senders:expr, $receivers:expr, $sinks:expr) => {
         seal!($shape);
         impl Shape for $shape {
+            const IDENTIFIER: &'static str = $identifier;
             const SOURCES: usize = $sources;
             const SENDERS: usize = $senders;
             const RECEIVERS: usize = $receivers;
@@ -70,6 +81,63 @@ macro_rules! impl_shape {
     };
 }
 
+/// Declares the canonical shape table.
+///
+/// Every row of the table assigns an identifier and the arities `(sources, senders, receivers,
+/// sinks)` to the shape type of a [`TransferShape`] variant. The table implements [`Shape`] for
+/// the shape types and derives [`TransferShape::select`], [`TransferShape::identifier`] and
+/// [`TransferShape::arity`] from the same rows, so that the transfer aliases, the proving and
+/// verifying contexts, and the signer, which only depend on these, stay consistent with each
+/// other.
+macro_rules! shape_table {
+    ($($variant:ident => $shape:ident: $identifier:literal ($sources:expr, $senders:expr, $receivers:expr, $sinks:expr);)*) => {
+        $(impl_shape!($shape, $identifier, $sources, $senders, $receivers, $sinks);)*
+
+        impl TransferShape {
+            /// Selects the [`TransferShape`] for the given shape if it matches a canonical shape.
+            #[inline]
+            pub fn select(
+                has_authorization: bool,
+                has_visible_asset_id: bool,
+                sources: usize,
+                senders: usize,
+                receivers: usize,
+                sinks: usize,
+            ) -> Option<Self> {
+                $(
+                    if has_authorization == requires_authorization($shape::SENDERS)
+                        && has_visible_asset_id
+                            == has_public_participants($shape::SOURCES, $shape::SINKS)
+                        && (sources, senders, receivers, sinks)
+                            == ($shape::SOURCES, $shape::SENDERS, $shape::RECEIVERS, $shape::SINKS)
+                    {
+                        return Some(Self::$variant);
+                    }
+                )*
+                None
+            }
+
+            /// Returns the identifier of the proving and verifying contexts of the circuit with
+            /// shape `self`.
+            #[inline]
+            pub const fn identifier(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $shape::IDENTIFIER,)*
+                }
+            }
+
+            /// Returns the number of sources, senders, receivers and sinks of `self`, in this
+            /// order.
+            #[inline]
+            pub const fn arity(&self) -> (usize, usize, usize, usize) {
+                match self {
+                    $(Self::$variant => ($shape::SOURCES, $shape::SENDERS, $shape::RECEIVERS, $shape::SINKS),)*
+                }
+            }
+        }
+    };
+}
+
 /// Builds a new alias using the given shape type.
 macro_rules! alias_type {
     ($type:tt, $t:ident, $shape:tt) => {
@@ -84,6 +152,38 @@ macro_rules! transfer_alias {
     };
 }
 
+// Canonical Shape Table
+//
+// A deployment which needs other arities, for instance a `4`-in/`4`-out `PrivateTransfer`, only
+// changes the rows of this table, giving new identifiers to the shapes it changes:
+//
+//     PrivateTransfer => PrivateTransferShape: "private-transfer-4x4" (0, 4, 4, 0);
+//     ToPublic => ToPublicShape: "to-public-4x3" (...);
+//
+// and generates new proving and verifying contexts for them. The `ToPublic` row is defined in
+// terms of the `PrivateTransfer` row, so it has the same number of senders and one secret receiver
+// turned into a public sink, and the `PrivateSwap` row has the senders and receivers of two
+// `PrivateTransfer`s, one for each asset. The rows are matched in order, so a `PrivateTransfer`
+// row with the arities of the `PrivateSwap` row shadows it. The signer completes the transfers it
+// builds with zero-value senders and receivers to match the arities of the table, but the payment
+// test helpers of `manta-pay` assume the default arities below.
+shape_table! {
+    ToPrivate => ToPrivateShape: "to-private" (1, 0, 1, 0);
+    PrivateTransfer => PrivateTransferShape: "private-transfer" (0, 2, 2, 0);
+    ToPublic => ToPublicShape: "to-public" (
+        PrivateTransferShape::SOURCES,
+        PrivateTransferShape::SENDERS,
+        PrivateTransferShape::RECEIVERS - 1,
+        PrivateTransferShape::SINKS + 1
+    );
+    PrivateSwap => PrivateSwapShape: "private-swap" (
+        0,
+        MULTI_ASSET_COUNT * PrivateTransferShape::SENDERS,
+        MULTI_ASSET_COUNT * PrivateTransferShape::RECEIVERS,
+        0
+    );
+}
+
 /// [`ToPrivate`] Transfer Shape
 ///
 /// ```text
@@ -92,8 +192,6 @@ macro_rules! transfer_alias {
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Ord, PartialOrd)]
 pub struct ToPrivateShape;
 
-impl_shape!(ToPrivateShape, 1, 0, 1, 0);
-
 /// [`ToPrivate`] Transfer Type
 pub type ToPrivate<C> = transfer_alias!(C, ToPrivateShape);
 
@@ -104,7 +202,15 @@ where
     /// Builds a [`ToPrivate`] from `asset` and `receiver`.
     #[inline]
     pub fn build(asset: Asset<C>, receiver: Receiver<C>) -> Self {
-        Self::new_unchecked(None, Some(asset.id), [asset.value], [], [receiver], [])
+        Self::new_unchecked(
+            None,
+            Some(asset.id),
+            [asset.value],
+            [],
+            [receiver],
+            [],
+            None,
+        )
     }
 
     /// Builds a new [`ToPrivate`] from `address` and `asset`.
@@ -158,8 +264,6 @@ where
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Ord, PartialOrd)]
 pub struct PrivateTransferShape;
 
-impl_shape!(PrivateTransferShape, 0, 2, 2, 0);
-
 /// [`PrivateTransfer`] Transfer Type
 pub type PrivateTransfer<C> = transfer_alias!(C, PrivateTransferShape);
 
@@ -174,7 +278,7 @@ where
         senders: [Sender<C>; PrivateTransferShape::SENDERS],
         receivers: [Receiver<C>; PrivateTransferShape::RECEIVERS],
     ) -> Self {
-        Self::new_unchecked(Some(authorization), None, [], senders, receivers, [])
+        Self::new_unchecked(Some(authorization), None, [], senders, receivers, [], None)
     }
 }
 
@@ -189,14 +293,6 @@ where
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Ord, PartialOrd)]
 pub struct ToPublicShape;
 
-impl_shape!(
-    ToPublicShape,
-    PrivateTransferShape::SOURCES,
-    PrivateTransferShape::SENDERS,
-    PrivateTransferShape::RECEIVERS - 1,
-    PrivateTransferShape::SINKS + 1
-);
-
 /// [`ToPublic`] Transfer
 pub type ToPublic<C> = transfer_alias!(C, ToPublicShape);
 
@@ -219,6 +315,267 @@ where
             senders,
             receivers,
             [asset.value],
+            None,
+        )
+    }
+}
+
+/// Number of Assets of a [`MultiAssetTransfer`]
+pub const MULTI_ASSET_COUNT: usize = 2;
+
+/// Multi-Asset Transfer
+///
+/// A [`Transfer`] moves a single asset, so all of its senders and receivers share the same asset
+/// id. A [`MultiAssetTransfer`] moves [`MULTI_ASSET_COUNT`]-many assets in one proof, which makes
+/// it possible to swap an asset for another one atomically. Its `SENDERS` and `RECEIVERS` are
+/// split into consecutive groups of the same size, one for each asset, and the circuit has a
+/// balance equation for every group, asserting that the senders and the receivers of the group
+/// have the same secret asset id and add up to the same value. The asset ids of different groups
+/// are not related to each other. A multi-asset transfer has no public participants, so it never
+/// reveals any of its asset ids.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Authorization<C>: Clone, Sender<C>: Clone, Receiver<C>: Clone"),
+    Copy(bound = "Authorization<C>: Copy, Sender<C>: Copy, Receiver<C>: Copy"),
+    Debug(bound = "Authorization<C>: Debug, Sender<C>: Debug, Receiver<C>: Debug"),
+    Eq(bound = "Authorization<C>: Eq, Sender<C>: Eq, Receiver<C>: Eq"),
+    Hash(bound = "Authorization<C>: Hash, Sender<C>: Hash, Receiver<C>: Hash"),
+    PartialEq(bound = "Authorization<C>: PartialEq, Sender<C>: PartialEq, Receiver<C>: PartialEq")
+)]
+pub struct MultiAssetTransfer<C, const SENDERS: usize, const RECEIVERS: usize>
+where
+    C: Configuration,
+{
+    /// Authorization
+    authorization: Authorization<C>,
+
+    /// Senders
+    senders: [Sender<C>; SENDERS],
+
+    /// Receivers
+    receivers: [Receiver<C>; RECEIVERS],
+}
+
+impl<C, const SENDERS: usize, const RECEIVERS: usize> MultiAssetTransfer<C, SENDERS, RECEIVERS>
+where
+    C: Configuration,
+{
+    /// Asserts at compile time that every asset of the [`MultiAssetTransfer`] has at least one
+    /// sender and one receiver, and that they all have the same number of them. Evaluating this
+    /// constant fails to compile if `SENDERS` or `RECEIVERS` is zero or not a multiple of
+    /// [`MULTI_ASSET_COUNT`].
+    const SHAPE_CHECK: () = {
+        assert!(
+            SENDERS != 0 && SENDERS % MULTI_ASSET_COUNT == 0,
+            "The senders must be split evenly between the assets."
+        );
+        assert!(
+            RECEIVERS != 0 && RECEIVERS % MULTI_ASSET_COUNT == 0,
+            "The receivers must be split evenly between the assets."
+        );
+    };
+
+    /// Builds a new [`MultiAssetTransfer`] from its component parts, where the senders and the
+    /// receivers of every asset are consecutive.
+    #[inline]
+    pub fn new(
+        authorization: Authorization<C>,
+        senders: [Sender<C>; SENDERS],
+        receivers: [Receiver<C>; RECEIVERS],
+    ) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::SHAPE_CHECK;
+        Self {
+            authorization,
+            senders,
+            receivers,
+        }
+    }
+
+    /// Returns the [`Shape`](dynamic::Shape) of the posts of this transfer shape.
+    #[inline]
+    pub const fn shape() -> dynamic::Shape {
+        dynamic::Shape::new(0, SENDERS, RECEIVERS, 0)
+    }
+
+    /// Generates the public input for the [`MultiAssetTransfer`] validation proof.
+    #[inline]
+    pub fn generate_proof_input(&self) -> ProofInput<C> {
+        let mut input = Default::default();
+        self.extend(&mut input);
+        input
+    }
+
+    /// Builds a constraint system which asserts constraints against unknown variables.
+    #[inline]
+    pub fn unknown_constraints(parameters: FullParametersRef<C>) -> C::Compiler {
+        let mut compiler = C::ProofSystem::context_compiler();
+        Self::build_unknown_constraints(parameters, &mut compiler);
+        compiler
+    }
+
+    /// Asserts the validity constraints against unknown variables in `compiler`.
+    #[inline]
+    pub fn build_unknown_constraints(parameters: FullParametersRef<C>, compiler: &mut C::Compiler) {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::SHAPE_CHECK;
+        TransferVar::<C>::new_unknown(Self::shape(), compiler)
+            .build_multi_asset_validity_constraints(
+                MULTI_ASSET_COUNT,
+                &parameters.as_constant(compiler),
+                compiler,
+            );
+    }
+
+    /// Builds a constraint system which asserts constraints against known variables.
+    #[inline]
+    pub fn known_constraints(&self, parameters: FullParametersRef<C>) -> C::Compiler {
+        let mut compiler = C::ProofSystem::proof_compiler();
+        TransferVar::<C>::new_known(
+            Some(&self.authorization),
+            None,
+            &[],
+            &self.senders,
+            &self.receivers,
+            &[],
+            None,
+            &mut compiler,
+        )
+        .build_multi_asset_validity_constraints(
+            MULTI_ASSET_COUNT,
+            &parameters.as_constant(&mut compiler),
+            &mut compiler,
+        );
+        compiler
+    }
+
+    /// Generates a proving and verifying context for this transfer shape.
+    #[inline]
+    pub fn generate_context<R>(
+        public_parameters: &ProofSystemPublicParameters<C>,
+        parameters: FullParametersRef<C>,
+        rng: &mut R,
+    ) -> Result<(ProvingContext<C>, VerifyingContext<C>), ProofSystemError<C>>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        C::ProofSystem::compile(
+            public_parameters,
+            Self::unknown_constraints(parameters),
+            rng,
+        )
+    }
+
+    /// Converts `self` into its [`TransferPost`] by building the [`MultiAssetTransfer`] validity
+    /// proof and signing the [`TransferPostBody`] payload with `spending_key`.
+    ///
+    /// Returns `Ok(None)` when the authorization of this [`MultiAssetTransfer`] is invalid.
+    /// Returns `Err` when proof generation fails.
+    #[inline]
+    pub fn into_post<R>(
+        self,
+        parameters: FullParametersRef<C>,
+        proving_context: &ProvingContext<C>,
+        spending_key: &SpendingKey<C>,
+        rng: &mut R,
+    ) -> Result<Option<TransferPost<C>>, ProofSystemError<C>>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let proof =
+            C::ProofSystem::prove(proving_context, self.known_constraints(parameters), rng)?;
+        Ok(self.into_post_with_proof(parameters.base, proof, spending_key, rng))
+    }
+
+    /// Converts `self` into its [`TransferPost`] with the validity `proof` built elsewhere,
+    /// signing the [`TransferPostBody`] payload with `spending_key`. The proof is not checked
+    /// against `self`.
+    ///
+    /// Returns `None` when the authorization of this [`MultiAssetTransfer`] is invalid.
+    #[inline]
+    pub fn into_post_with_proof<R>(
+        self,
+        parameters: &Parameters<C>,
+        proof: Proof<C>,
+        spending_key: &SpendingKey<C>,
+        rng: &mut R,
+    ) -> Option<TransferPost<C>>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        TransferPost::sign(
+            parameters,
+            TransferPostBody::build(proof, None, [], self.senders, self.receivers, [], None),
+            Some(self.authorization),
+            Some(spending_key),
+            Vec::new(),
+            rng,
+        )
+    }
+}
+
+impl<C, const SENDERS: usize, const RECEIVERS: usize> Input<C::ProofSystem>
+    for MultiAssetTransfer<C, SENDERS, RECEIVERS>
+where
+    C: Configuration,
+{
+    #[inline]
+    fn extend(&self, input: &mut ProofInput<C>) {
+        extend_input::<C>(
+            input,
+            Some(&self.authorization),
+            None,
+            &[],
+            &self.senders,
+            &self.receivers,
+            &[],
+            None,
+        )
+    }
+}
+
+/// [`PrivateSwap`] Transfer Shape
+///
+/// ```text
+/// <0, 4, 4, 0>
+/// ```
+///
+/// The [`PrivateSwapShape`] is defined in terms of the [`PrivateTransferShape`]. It has the
+/// senders and the receivers of a [`PrivateTransfer`] for each of the [`MULTI_ASSET_COUNT`]-many
+/// assets of a [`MultiAssetTransfer`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Ord, PartialOrd)]
+pub struct PrivateSwapShape;
+
+/// [`PrivateSwap`] Transfer Type
+pub type PrivateSwap<C> =
+    MultiAssetTransfer<C, { PrivateSwapShape::SENDERS }, { PrivateSwapShape::RECEIVERS }>;
+
+// The `PrivateSwap` row of the canonical shape table must split its senders and receivers evenly
+// between the assets of a `MultiAssetTransfer` and have no public participants.
+const _: () = {
+    assert!(PrivateSwapShape::SENDERS != 0);
+    assert!(PrivateSwapShape::SENDERS % MULTI_ASSET_COUNT == 0);
+    assert!(PrivateSwapShape::RECEIVERS != 0);
+    assert!(PrivateSwapShape::RECEIVERS % MULTI_ASSET_COUNT == 0);
+    assert!(PrivateSwapShape::SOURCES == 0 && PrivateSwapShape::SINKS == 0);
+};
+
+impl<C> PrivateSwap<C>
+where
+    C: Configuration,
+{
+    /// Builds a [`PrivateSwap`] which spends `senders` and creates `receivers`, where the first
+    /// senders and receivers have one asset id and the second ones have the other asset id.
+    #[inline]
+    pub fn build(
+        authorization: Authorization<C>,
+        senders: [[Sender<C>; PrivateTransferShape::SENDERS]; MULTI_ASSET_COUNT],
+        receivers: [[Receiver<C>; PrivateTransferShape::RECEIVERS]; MULTI_ASSET_COUNT],
+    ) -> Self {
+        Self::new(
+            authorization,
+            into_array_unchecked(senders.into_iter().flatten().collect::<Vec<_>>()),
+            into_array_unchecked(receivers.into_iter().flatten().collect::<Vec<_>>()),
         )
     }
 }
@@ -239,71 +596,22 @@ pub enum TransferShape {
 
     /// [`ToPublic`] Transfer
     ToPublic,
+
+    /// [`PrivateSwap`] Transfer
+    PrivateSwap,
 }
 
 impl TransferShape {
-    /// Selects the [`TransferShape`] for the given shape if it matches a canonical shape.
-    #[inline]
-    pub fn select(
-        has_authorization: bool,
-        has_visible_asset_id: bool,
-        sources: usize,
-        senders: usize,
-        receivers: usize,
-        sinks: usize,
-    ) -> Option<Self> {
-        const TO_PRIVATE_HAS_AUTHORIZATION: bool = requires_authorization(ToPrivateShape::SENDERS);
-        const TO_PRIVATE_HAS_VISIBLE_ASSET_ID: bool =
-            has_public_participants(ToPrivateShape::SOURCES, ToPrivateShape::SINKS);
-        const PRIVATE_TRANSFER_HAS_AUTHORIZATION: bool =
-            requires_authorization(PrivateTransferShape::SENDERS);
-        const PRIVATE_TRANSFER_HAS_VISIBLE_ASSET_ID: bool =
-            has_public_participants(PrivateTransferShape::SOURCES, PrivateTransferShape::SINKS);
-        const TO_PUBLIC_HAS_AUTHORIZATION: bool = requires_authorization(ToPublicShape::SENDERS);
-        const TO_PUBLIC_HAS_VISIBLE_ASSET_ID: bool =
-            has_public_participants(ToPublicShape::SOURCES, ToPublicShape::SINKS);
-        match (
-            has_authorization,
-            has_visible_asset_id,
-            sources,
-            senders,
-            receivers,
-            sinks,
-        ) {
-            (
-                TO_PRIVATE_HAS_AUTHORIZATION,
-                TO_PRIVATE_HAS_VISIBLE_ASSET_ID,
-                ToPrivateShape::SOURCES,
-                ToPrivateShape::SENDERS,
-                ToPrivateShape::RECEIVERS,
-                ToPrivateShape::SINKS,
-            ) => Some(Self::ToPrivate),
-            (
-                PRIVATE_TRANSFER_HAS_AUTHORIZATION,
-                PRIVATE_TRANSFER_HAS_VISIBLE_ASSET_ID,
-                PrivateTransferShape::SOURCES,
-                PrivateTransferShape::SENDERS,
-                PrivateTransferShape::RECEIVERS,
-                PrivateTransferShape::SINKS,
-            ) => Some(Self::PrivateTransfer),
-            (
-                TO_PUBLIC_HAS_AUTHORIZATION,
-                TO_PUBLIC_HAS_VISIBLE_ASSET_ID,
-                ToPublicShape::SOURCES,
-                ToPublicShape::SENDERS,
-                ToPublicShape::RECEIVERS,
-                ToPublicShape::SINKS,
-            ) => Some(Self::ToPublic),
-            _ => None,
-        }
-    }
-
-    /// Selects the [`TransferShape`] from `post`.
+    /// Selects the [`TransferShape`] from `post`. Posts which pay a fee do not have a canonical
+    /// shape.
     #[inline]
     pub fn from_post<C>(post: &TransferPost<C>) -> Option<Self>
     where
         C: Configuration,
     {
+        if post.body.fee.is_some() {
+            return None;
+        }
         Self::select(
             post.authorization_signature.is_some(),
             post.body.asset_id.is_some(),
@@ -314,13 +622,17 @@ impl TransferShape {
         )
     }
 
-    /// Selects the [`TransferShape`] from `posting_key`.
+    /// Selects the [`TransferShape`] from `posting_key`. Posts which pay a fee do not have a
+    /// canonical shape.
     #[inline]
     pub fn from_posting_key_ref<C, L>(posting_key: &TransferPostingKeyRef<C, L>) -> Option<Self>
     where
         C: Configuration,
         L: TransferLedger<C>,
     {
+        if posting_key.fee.is_some() {
+            return None;
+        }
         Self::select(
             posting_key.authorization_key.is_some(),
             posting_key.asset_id.is_some(),
@@ -565,6 +877,13 @@ where
 
     /// [`ToPublic`] Proving Context
     pub to_public: ProvingContext<C>,
+
+    /// [`PrivateSwap`] Proving Context
+    ///
+    /// Private swaps are optional, so this context is only present if it was generated or loaded
+    /// separately.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub private_swap: Option<ProvingContext<C>>,
 }
 
 impl<C> MultiProvingContext<C>
@@ -572,12 +891,27 @@ where
     C: Configuration + ?Sized,
 {
     /// Selects a [`ProvingContext`] based on `shape`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `shape` is [`PrivateSwap`](TransferShape::PrivateSwap) and `self`
+    /// does not have a [`PrivateSwap`] context. See [`try_select`](Self::try_select) for a
+    /// non-panicking version.
     #[inline]
     pub fn select(&self, shape: TransferShape) -> &ProvingContext<C> {
+        self.try_select(shape)
+            .expect("The private swap context is only present if it was generated or loaded.")
+    }
+
+    /// Selects a [`ProvingContext`] based on `shape`, returning `None` if `self` does not have
+    /// one for `shape`.
+    #[inline]
+    pub fn try_select(&self, shape: TransferShape) -> Option<&ProvingContext<C>> {
         match shape {
-            TransferShape::ToPrivate => &self.to_private,
-            TransferShape::PrivateTransfer => &self.private_transfer,
-            TransferShape::ToPublic => &self.to_public,
+            TransferShape::ToPrivate => Some(&self.to_private),
+            TransferShape::PrivateTransfer => Some(&self.private_transfer),
+            TransferShape::ToPublic => Some(&self.to_public),
+            TransferShape::PrivateSwap => self.private_swap.as_ref(),
         }
     }
 }
@@ -617,6 +951,13 @@ where
 
     /// [`ToPublic`] Verifying Context
     pub to_public: VerifyingContext<C>,
+
+    /// [`PrivateSwap`] Verifying Context
+    ///
+    /// Private swaps are optional, so this context is only present if it was generated or loaded
+    /// separately.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub private_swap: Option<VerifyingContext<C>>,
 }
 
 impl<C> MultiVerifyingContext<C>
@@ -624,17 +965,34 @@ where
     C: Configuration + ?Sized,
 {
     /// Selects a [`VerifyingContext`] based on `shape`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `shape` is [`PrivateSwap`](TransferShape::PrivateSwap) and `self`
+    /// does not have a [`PrivateSwap`] context. See [`try_select`](Self::try_select) for a
+    /// non-panicking version.
     #[inline]
     pub fn select(&self, shape: TransferShape) -> &VerifyingContext<C> {
+        self.try_select(shape)
+            .expect("The private swap context is only present if it was generated or loaded.")
+    }
+
+    /// Selects a [`VerifyingContext`] based on `shape`, returning `None` if `self` does not have
+    /// one for `shape`.
+    #[inline]
+    pub fn try_select(&self, shape: TransferShape) -> Option<&VerifyingContext<C>> {
         match shape {
-            TransferShape::ToPrivate => &self.to_private,
-            TransferShape::PrivateTransfer => &self.private_transfer,
-            TransferShape::ToPublic => &self.to_public,
+            TransferShape::ToPrivate => Some(&self.to_private),
+            TransferShape::PrivateTransfer => Some(&self.private_transfer),
+            TransferShape::ToPublic => Some(&self.to_public),
+            TransferShape::PrivateSwap => self.private_swap.as_ref(),
         }
     }
 }
 
-/// Generates proving and verifying multi-contexts for the canonical transfer shapes.
+/// Generates proving and verifying multi-contexts for the canonical transfer shapes, except for
+/// the optional [`PrivateSwap`] shape whose contexts can be generated with
+/// [`PrivateSwap::generate_context`].
 #[inline]
 pub fn generate_context<C, R>(
     public_parameters: &ProofSystemPublicParameters<C>,
@@ -654,15 +1012,35 @@ where
             to_private: to_private.0,
             private_transfer: private_transfer.0,
             to_public: to_public.0,
+            private_swap: None,
         },
         MultiVerifyingContext {
             to_private: to_private.1,
             private_transfer: private_transfer.1,
             to_public: to_public.1,
+            private_swap: None,
         },
     ))
 }
 
+/// Caches the structure of the circuits of the canonical transfer shapes in `proving_context`,
+/// see [`Transfer::cache_synthesis`] for more, except for the optional [`PrivateSwap`] context.
+/// Returns `true` if the circuits of all the other shapes were cached.
+#[inline]
+pub fn cache_synthesis<C>(
+    parameters: FullParametersRef<C>,
+    proving_context: &mut MultiProvingContext<C>,
+) -> bool
+where
+    C: Configuration,
+{
+    let to_private = ToPrivate::<C>::cache_synthesis(parameters, &mut proving_context.to_private);
+    let private_transfer =
+        PrivateTransfer::<C>::cache_synthesis(parameters, &mut proving_context.private_transfer);
+    let to_public = ToPublic::<C>::cache_synthesis(parameters, &mut proving_context.to_public);
+    to_private && private_transfer && to_public
+}
+
 /// Transaction Data
 #[cfg_attr(
     feature = "serde",
@@ -696,6 +1074,9 @@ where
 
     /// To Public Transaction Data
     ToPublic(Identifier<C>, Asset<C>),
+
+    /// Private Swap Transaction Data
+    PrivateSwap(Vec<(Identifier<C>, Asset<C>)>),
 }
 
 impl<C> TransactionData<C>
@@ -709,7 +1090,8 @@ where
             TransactionData::ToPrivate(identifier, asset) => {
                 [(identifier.clone(), asset.clone())].to_vec()
             }
-            TransactionData::PrivateTransfer(identified_assets) => identified_assets.clone(),
+            TransactionData::PrivateTransfer(identified_assets)
+            | TransactionData::PrivateSwap(identified_assets) => identified_assets.clone(),
             TransactionData::ToPublic(identifier, asset) => {
                 [(identifier.clone(), asset.clone())].to_vec()
             }
@@ -750,6 +1132,7 @@ where
             Self::ToPrivate(_, _) => TransferShape::ToPrivate,
             Self::PrivateTransfer(_) => TransferShape::PrivateTransfer,
             Self::ToPublic(_, _) => TransferShape::ToPublic,
+            Self::PrivateSwap(_) => TransferShape::PrivateSwap,
         }
     }
 
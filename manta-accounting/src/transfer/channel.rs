@@ -0,0 +1,725 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Payment Channels
+//!
+//! This module is an experimental implementation of two-party payment channels over private notes.
+//! A [`Channel`] is funded by a [`ToPrivate`] of its capacity to a note owned by the channel
+//! address, after which the two parties pay each other off-chain by signing new
+//! [`ChannelState`]s with increasing sequence numbers. The channel is closed by a single
+//! [`PrivateTransfer`] which spends the funding note and pays each party its balance in the latest
+//! state, either after both parties signed a final state (cooperative close) or from the latest
+//! state signed by both parties (unilateral close).
+//!
+//! # Limitations
+//!
+//! The ledger has no notion of a note controlled by two keys, so the funding note is owned by a
+//! single channel spending key which the parties must control jointly, for instance by sharing it
+//! out-of-band. The party which closes the channel needs the whole key, and nothing prevents it
+//! from spending the funding note with another transfer, so this module should not be used to
+//! protect real assets until the transfer protocol supports threshold authorization.
+
+use crate::transfer::{
+    canonical::{PrivateTransfer, PrivateTransferShape, Shape, ToPrivate},
+    Address, Asset, Authorization, Identifier, Parameters, PreSender, Receiver, SpendingKey,
+    UtxoAccumulatorItem, UtxoAccumulatorModel,
+};
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+use manta_crypto::{
+    accumulator::Accumulator,
+    rand::{CryptoRng, Rand, RngCore, Sample},
+    signature::{self, Sign, Verify},
+};
+use manta_util::{
+    into_array_unchecked,
+    num::{CheckedAdd, CheckedSub},
+};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Payment Channel Configuration
+pub trait Configuration: super::Configuration {
+    /// State Signature Scheme Type
+    ///
+    /// Both parties sign every [`ChannelState`] with this scheme before it replaces the previous
+    /// one.
+    type StateSignatureScheme: Sign + Verify<Verification = bool>;
+
+    /// Computes the message which is signed by both parties for `state`.
+    fn state_message(state: &ChannelState<Self>) -> StateMessage<Self>;
+}
+
+/// State Signing Key Type
+pub type StateSigningKey<C> = signature::SigningKey<<C as Configuration>::StateSignatureScheme>;
+
+/// State Verifying Key Type
+pub type StateVerifyingKey<C> = signature::VerifyingKey<<C as Configuration>::StateSignatureScheme>;
+
+/// State Message Type
+pub type StateMessage<C> = signature::Message<<C as Configuration>::StateSignatureScheme>;
+
+/// State Signature Randomness Type
+pub type StateRandomness<C> = signature::Randomness<<C as Configuration>::StateSignatureScheme>;
+
+/// State Signature Type
+pub type StateSignature<C> = signature::Signature<<C as Configuration>::StateSignatureScheme>;
+
+/// Channel Identifier
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ChannelId(pub [u8; 32]);
+
+impl Sample for ChannelId {
+    #[inline]
+    fn sample<R>(_: (), rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        let mut id = [0; 32];
+        rng.fill_bytes(&mut id);
+        Self(id)
+    }
+}
+
+/// Channel Party
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Party {
+    /// Left Party
+    ///
+    /// The left party funds the channel, so it owns the whole capacity in the initial state.
+    Left,
+
+    /// Right Party
+    Right,
+}
+
+impl Party {
+    /// Returns the index of `self` in the per-party arrays of a [`Channel`].
+    #[inline]
+    pub const fn index(self) -> usize {
+        match self {
+            Self::Left => 0,
+            Self::Right => 1,
+        }
+    }
+
+    /// Returns the other party of the channel.
+    #[inline]
+    pub const fn counterparty(self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+}
+
+/// Channel Direction
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Direction {
+    /// Unidirectional Channel
+    ///
+    /// Only the [`Left`](Party::Left) party pays, so the balance of the right party never
+    /// decreases.
+    #[default]
+    Unidirectional,
+
+    /// Bidirectional Channel
+    ///
+    /// Both parties can pay each other.
+    Bidirectional,
+}
+
+/// Channel Error
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ChannelError {
+    /// Channel Already Funded
+    AlreadyFunded,
+
+    /// Channel Not Funded
+    Unfunded,
+
+    /// Missing Funding Note
+    ///
+    /// The funding note is not in the UTXO accumulator, so it was not posted to the ledger yet.
+    MissingFundingNote,
+
+    /// Channel Closed
+    ///
+    /// The latest state is final, so it cannot be updated anymore.
+    Closed,
+
+    /// Non-Final State
+    ///
+    /// The channel can only be closed cooperatively after both parties signed a final state.
+    NonFinalState,
+
+    /// Wrong Channel
+    WrongChannel,
+
+    /// Stale State
+    ///
+    /// The sequence number of the new state does not follow the one of the latest state.
+    StaleState,
+
+    /// Value Not Conserved
+    ///
+    /// The balances of the new state do not add up to the capacity of the channel.
+    ValueNotConserved,
+
+    /// Wrong Direction
+    ///
+    /// The [`Right`](Party::Right) party paid on a unidirectional channel.
+    WrongDirection,
+
+    /// Insufficient Balance
+    InsufficientBalance,
+
+    /// Invalid Signature
+    ///
+    /// The signature of the given party is not valid for the new state.
+    InvalidSignature(Party),
+}
+
+/// Channel State
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "C::AssetValue: Deserialize<'de>",
+            serialize = "C::AssetValue: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "C::AssetValue: Clone"),
+    Debug(bound = "C::AssetValue: Debug"),
+    Eq(bound = "C::AssetValue: Eq"),
+    Hash(bound = "C::AssetValue: Hash"),
+    PartialEq(bound = "C::AssetValue: PartialEq")
+)]
+pub struct ChannelState<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Channel Identifier
+    pub channel: ChannelId,
+
+    /// Sequence Number
+    pub sequence: u64,
+
+    /// Balances of the [`Left`](Party::Left) and [`Right`](Party::Right) Parties
+    pub balances: [C::AssetValue; 2],
+
+    /// Final State Flag
+    ///
+    /// A final state cannot be replaced, it is only used to close the channel.
+    pub is_final: bool,
+}
+
+impl<C> ChannelState<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Builds the initial state of the channel `channel` where the [`Left`](Party::Left) party
+    /// owns the whole `capacity`.
+    #[inline]
+    pub fn initial(channel: ChannelId, capacity: C::AssetValue) -> Self {
+        Self {
+            channel,
+            sequence: 0,
+            balances: [capacity, Default::default()],
+            is_final: false,
+        }
+    }
+
+    /// Returns the balance of `party` in `self`.
+    #[inline]
+    pub fn balance(&self, party: Party) -> &C::AssetValue {
+        &self.balances[party.index()]
+    }
+
+    /// Signs `self` with `signing_key` using `randomness` to hide the signature.
+    #[inline]
+    pub fn sign(
+        &self,
+        scheme: &C::StateSignatureScheme,
+        signing_key: &StateSigningKey<C>,
+        randomness: &StateRandomness<C>,
+    ) -> StateSignature<C> {
+        scheme.sign(signing_key, randomness, &C::state_message(self), &mut ())
+    }
+
+    /// Verifies that `signature` is a valid signature of `self` under `verifying_key`.
+    #[inline]
+    pub fn verify(
+        &self,
+        scheme: &C::StateSignatureScheme,
+        verifying_key: &StateVerifyingKey<C>,
+        signature: &StateSignature<C>,
+    ) -> bool {
+        scheme.verify(verifying_key, &C::state_message(self), signature, &mut ())
+    }
+}
+
+/// Payment Channel
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = r"
+                C::AssetId: Deserialize<'de>,
+                C::AssetValue: Deserialize<'de>,
+                Address<C>: Deserialize<'de>,
+                Identifier<C>: Deserialize<'de>,
+                StateVerifyingKey<C>: Deserialize<'de>,
+                StateSignature<C>: Deserialize<'de>,
+            ",
+            serialize = r"
+                C::AssetId: Serialize,
+                C::AssetValue: Serialize,
+                Address<C>: Serialize,
+                Identifier<C>: Serialize,
+                StateVerifyingKey<C>: Serialize,
+                StateSignature<C>: Serialize,
+            ",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = r"
+        C::AssetId: Clone,
+        C::AssetValue: Clone,
+        Identifier<C>: Clone,
+        StateVerifyingKey<C>: Clone,
+        StateSignature<C>: Clone,
+    "),
+    Debug(bound = r"
+        C::AssetId: Debug,
+        C::AssetValue: Debug,
+        Address<C>: Debug,
+        Identifier<C>: Debug,
+        StateVerifyingKey<C>: Debug,
+        StateSignature<C>: Debug,
+    ")
+)]
+pub struct Channel<C>
+where
+    C: Configuration,
+{
+    /// Channel Identifier
+    id: ChannelId,
+
+    /// Channel Direction
+    direction: Direction,
+
+    /// Asset Id
+    asset_id: C::AssetId,
+
+    /// Channel Capacity
+    capacity: C::AssetValue,
+
+    /// Channel Address
+    ///
+    /// Address owning the funding note, see the [module-level documentation](self) for the
+    /// control of its spending key.
+    channel_address: Address<C>,
+
+    /// Payout Addresses of the [`Left`](Party::Left) and [`Right`](Party::Right) Parties
+    addresses: [Address<C>; 2],
+
+    /// State Verifying Keys of the [`Left`](Party::Left) and [`Right`](Party::Right) Parties
+    verifying_keys: [StateVerifyingKey<C>; 2],
+
+    /// Funding Note Identifier
+    funding: Option<Identifier<C>>,
+
+    /// Latest State
+    state: ChannelState<C>,
+
+    /// Signatures of the Latest State
+    ///
+    /// The initial state is fixed by the terms of the channel, so it is not signed.
+    signatures: Option<[StateSignature<C>; 2]>,
+}
+
+impl<C> Channel<C>
+where
+    C: Configuration,
+{
+    /// Builds a new [`Channel`] in its initial state, where the [`Left`](Party::Left) party owns
+    /// the whole `capacity` of `asset_id`.
+    #[inline]
+    pub fn new(
+        id: ChannelId,
+        direction: Direction,
+        asset_id: C::AssetId,
+        capacity: C::AssetValue,
+        channel_address: Address<C>,
+        addresses: [Address<C>; 2],
+        verifying_keys: [StateVerifyingKey<C>; 2],
+    ) -> Self {
+        Self {
+            id,
+            direction,
+            asset_id,
+            capacity: capacity.clone(),
+            channel_address,
+            addresses,
+            verifying_keys,
+            funding: None,
+            state: ChannelState::initial(id, capacity),
+            signatures: None,
+        }
+    }
+
+    /// Returns the identifier of `self`.
+    #[inline]
+    pub fn id(&self) -> ChannelId {
+        self.id
+    }
+
+    /// Returns the direction of `self`.
+    #[inline]
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Returns the asset id of `self`.
+    #[inline]
+    pub fn asset_id(&self) -> &C::AssetId {
+        &self.asset_id
+    }
+
+    /// Returns the capacity of `self`.
+    #[inline]
+    pub fn capacity(&self) -> &C::AssetValue {
+        &self.capacity
+    }
+
+    /// Returns the address owning the funding note of `self`.
+    #[inline]
+    pub fn channel_address(&self) -> &Address<C> {
+        &self.channel_address
+    }
+
+    /// Returns the identifier of the funding note of `self`, if it was funded.
+    #[inline]
+    pub fn funding(&self) -> Option<&Identifier<C>> {
+        self.funding.as_ref()
+    }
+
+    /// Returns the latest state of `self`.
+    #[inline]
+    pub fn state(&self) -> &ChannelState<C> {
+        &self.state
+    }
+
+    /// Returns the signatures of the latest state of `self`, or `None` if `self` is still in its
+    /// initial state.
+    #[inline]
+    pub fn signatures(&self) -> Option<&[StateSignature<C>; 2]> {
+        self.signatures.as_ref()
+    }
+
+    /// Returns the asset owned by `party` in the latest state of `self`.
+    #[inline]
+    pub fn balance(&self, party: Party) -> Asset<C> {
+        Asset::<C>::new(self.asset_id.clone(), self.state.balance(party).clone())
+    }
+
+    /// Builds the [`ToPrivate`] which funds `self` with its capacity, recording the identifier of
+    /// the funding note. The transfer is posted from the public account of the
+    /// [`Left`](Party::Left) party.
+    #[inline]
+    pub fn fund<R>(
+        &mut self,
+        parameters: &Parameters<C>,
+        rng: &mut R,
+    ) -> Result<ToPrivate<C>, ChannelError>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        if self.funding.is_some() {
+            return Err(ChannelError::AlreadyFunded);
+        }
+        let asset = Asset::<C>::new(self.asset_id.clone(), self.capacity.clone());
+        let receiver = Receiver::<C>::sample(
+            parameters,
+            self.channel_address.clone(),
+            asset.clone(),
+            Default::default(),
+            rng,
+        );
+        self.funding = Some(receiver.identifier());
+        Ok(ToPrivate::build(asset, receiver))
+    }
+
+    /// Records the identifier of the funding note of `self`, which is how the party that did not
+    /// build the funding transfer learns about it.
+    #[inline]
+    pub fn record_funding(&mut self, identifier: Identifier<C>) -> Result<(), ChannelError> {
+        if self.funding.is_some() {
+            return Err(ChannelError::AlreadyFunded);
+        }
+        self.funding = Some(identifier);
+        Ok(())
+    }
+
+    /// Builds the state which follows the latest state of `self` with `balances`.
+    #[inline]
+    fn next_state(&self, balances: [C::AssetValue; 2], is_final: bool) -> ChannelState<C> {
+        ChannelState {
+            channel: self.id,
+            sequence: self.state.sequence.saturating_add(1),
+            balances,
+            is_final,
+        }
+    }
+
+    /// Proposes the state in which `payer` pays `value` to the other party, which both parties
+    /// need to sign before it can [`update`](Self::update) `self`.
+    #[inline]
+    pub fn pay(&self, payer: Party, value: C::AssetValue) -> Result<ChannelState<C>, ChannelError>
+    where
+        C::AssetValue: CheckedSub<Output = C::AssetValue>,
+    {
+        if self.state.is_final {
+            return Err(ChannelError::Closed);
+        }
+        if self.direction == Direction::Unidirectional && payer == Party::Right {
+            return Err(ChannelError::WrongDirection);
+        }
+        let payee = payer.counterparty();
+        let mut balances = self.state.balances.clone();
+        balances[payer.index()] = self
+            .state
+            .balance(payer)
+            .clone()
+            .checked_sub(value.clone())
+            .ok_or(ChannelError::InsufficientBalance)?;
+        balances[payee.index()] = self
+            .state
+            .balance(payee)
+            .clone()
+            .checked_add(value)
+            .ok_or(ChannelError::ValueNotConserved)?;
+        Ok(self.next_state(balances, false))
+    }
+
+    /// Proposes the final state of `self`, with the balances of the latest state, which both
+    /// parties need to sign before they can close `self` cooperatively.
+    #[inline]
+    pub fn propose_close(&self) -> Result<ChannelState<C>, ChannelError> {
+        if self.state.is_final {
+            return Err(ChannelError::Closed);
+        }
+        Ok(self.next_state(self.state.balances.clone(), true))
+    }
+
+    /// Checks that `state` signed with `signatures` can replace the latest state of `self`.
+    #[inline]
+    pub fn check(
+        &self,
+        scheme: &C::StateSignatureScheme,
+        state: &ChannelState<C>,
+        signatures: &[StateSignature<C>; 2],
+    ) -> Result<(), ChannelError> {
+        if self.state.is_final {
+            return Err(ChannelError::Closed);
+        }
+        if state.channel != self.id {
+            return Err(ChannelError::WrongChannel);
+        }
+        if self.state.sequence.checked_add(1) != Some(state.sequence) {
+            return Err(ChannelError::StaleState);
+        }
+        match state.balances[0]
+            .clone()
+            .checked_add(state.balances[1].clone())
+        {
+            Some(total) if total == self.capacity => {}
+            _ => return Err(ChannelError::ValueNotConserved),
+        }
+        if self.direction == Direction::Unidirectional
+            && state.balance(Party::Right) < self.state.balance(Party::Right)
+        {
+            return Err(ChannelError::WrongDirection);
+        }
+        for party in [Party::Left, Party::Right] {
+            if !state.verify(
+                scheme,
+                &self.verifying_keys[party.index()],
+                &signatures[party.index()],
+            ) {
+                return Err(ChannelError::InvalidSignature(party));
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the latest state of `self` with `state` signed by both parties with `signatures`,
+    /// after [`check`](Self::check)ing it.
+    #[inline]
+    pub fn update(
+        &mut self,
+        scheme: &C::StateSignatureScheme,
+        state: ChannelState<C>,
+        signatures: [StateSignature<C>; 2],
+    ) -> Result<(), ChannelError> {
+        self.check(scheme, &state, &signatures)?;
+        self.state = state;
+        self.signatures = Some(signatures);
+        Ok(())
+    }
+
+    /// Builds the [`PrivateTransfer`] which spends the funding note of `self` with the channel
+    /// `spending_key` and pays each party its balance in the latest state.
+    #[inline]
+    fn close<A, R>(
+        &self,
+        parameters: &Parameters<C>,
+        spending_key: &SpendingKey<C>,
+        utxo_accumulator: &A,
+        rng: &mut R,
+    ) -> Result<PrivateTransfer<C>, ChannelError>
+    where
+        A: Accumulator<Item = UtxoAccumulatorItem<C>, Model = UtxoAccumulatorModel<C>>,
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let funding = self.funding.clone().ok_or(ChannelError::Unfunded)?;
+        let mut authorization =
+            Authorization::<C>::from_spending_key(parameters, spending_key, rng);
+        let sender = PreSender::<C>::sample(
+            parameters,
+            &mut authorization.context,
+            funding,
+            Asset::<C>::new(self.asset_id.clone(), self.capacity.clone()),
+            rng,
+        )
+        .try_upgrade(parameters, utxo_accumulator)
+        .ok_or(ChannelError::MissingFundingNote)?;
+        let mut senders = Vec::from([sender]);
+        while senders.len() < PrivateTransferShape::SENDERS {
+            senders.push(
+                PreSender::<C>::sample(
+                    parameters,
+                    &mut authorization.context,
+                    rng.gen(),
+                    Asset::<C>::zero(self.asset_id.clone()),
+                    rng,
+                )
+                .upgrade_unchecked(Default::default()),
+            );
+        }
+        let mut receivers = [Party::Left, Party::Right]
+            .into_iter()
+            .map(|party| {
+                Receiver::<C>::sample(
+                    parameters,
+                    self.addresses[party.index()].clone(),
+                    self.balance(party),
+                    Default::default(),
+                    rng,
+                )
+            })
+            .collect::<Vec<_>>();
+        while receivers.len() < PrivateTransferShape::RECEIVERS {
+            receivers.push(Receiver::<C>::sample(
+                parameters,
+                self.channel_address.clone(),
+                Asset::<C>::zero(self.asset_id.clone()),
+                Default::default(),
+                rng,
+            ));
+        }
+        Ok(PrivateTransfer::build(
+            authorization,
+            into_array_unchecked(senders),
+            into_array_unchecked(receivers),
+        ))
+    }
+
+    /// Builds the [`PrivateTransfer`] which closes `self` cooperatively, after both parties signed
+    /// the final state built by [`propose_close`](Self::propose_close).
+    ///
+    /// See the [module-level documentation](self) for the `spending_key` of the channel.
+    #[inline]
+    pub fn cooperative_close<A, R>(
+        &self,
+        parameters: &Parameters<C>,
+        spending_key: &SpendingKey<C>,
+        utxo_accumulator: &A,
+        rng: &mut R,
+    ) -> Result<PrivateTransfer<C>, ChannelError>
+    where
+        A: Accumulator<Item = UtxoAccumulatorItem<C>, Model = UtxoAccumulatorModel<C>>,
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        if !self.state.is_final {
+            return Err(ChannelError::NonFinalState);
+        }
+        self.close(parameters, spending_key, utxo_accumulator, rng)
+    }
+
+    /// Builds the [`PrivateTransfer`] which closes `self` from the latest state signed by both
+    /// parties, without the cooperation of the other party. A channel which was never updated is
+    /// closed by refunding its capacity to the [`Left`](Party::Left) party.
+    ///
+    /// See the [module-level documentation](self) for the `spending_key` of the channel.
+    #[inline]
+    pub fn unilateral_close<A, R>(
+        &self,
+        parameters: &Parameters<C>,
+        spending_key: &SpendingKey<C>,
+        utxo_accumulator: &A,
+        rng: &mut R,
+    ) -> Result<PrivateTransfer<C>, ChannelError>
+    where
+        A: Accumulator<Item = UtxoAccumulatorItem<C>, Model = UtxoAccumulatorModel<C>>,
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        self.close(parameters, spending_key, utxo_accumulator, rng)
+    }
+}
@@ -0,0 +1,417 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Checked Ledgers
+//!
+//! A [`CheckedLedger`] decorates a [`TransferLedger`] and asserts at runtime that the ledger keeps
+//! the invariants of the transfer protocol, for use in staging environments and fuzzers, where an
+//! integration bug should stop the ledger right away instead of corrupting its state:
+//!
+//! 1. No nullifier is spent twice, and the ledger never reports a spent nullifier as unspent.
+//! 2. The UTXO accumulator only grows: no UTXO is registered twice, and the ledger never reports a
+//!    registered UTXO as not registered.
+//! 3. The public balance updates conserve value: the value withdrawn from the shielded pool by the
+//!    sinks and the fees of every asset never exceeds the value deposited into it by the sources.
+//!
+//! Every violation panics with a description of the broken invariant.
+//!
+//! # Limitations
+//!
+//! The checks only know about the posts made through the [`CheckedLedger`], so it should wrap the
+//! ledger before its first post, and it does not support ledgers which roll back their posts. The
+//! spent nullifiers and the registered UTXOs are compared with a linear scan, which is fine for
+//! tests and staging environments but not for production ledgers.
+
+use crate::transfer::{
+    diff::BalanceDelta,
+    fee::{FeeSchedule, InsufficientFee},
+    receiver::{ReceiverLedger, ReceiverPostingKey, Registration, RegistrationError},
+    sender::{SenderLedger, SenderPostingKey},
+    utxo::{NullifierIndependence, UtxoIndependence},
+    Configuration, InvalidSinkAccount, InvalidSourceAccount, Note, NotePolicy, Parameters,
+    SinkPostingKey, SourcePostingKey, TransferLedger, TransferLedgerSuperPostingKey,
+    TransferPostingKeyRef, UtxoAccumulatorOutput,
+};
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::fmt::Debug;
+use manta_util::{
+    cmp::Independence,
+    num::{CheckedAdd, CheckedSub},
+};
+
+/// Checked Ledger
+///
+/// Decorator of a [`TransferLedger`] which asserts the invariants of the transfer protocol. See
+/// the [module-level documentation](self) for more.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "L: Clone, C::Nullifier: Clone, C::Utxo: Clone"),
+    Debug(bound = r"
+        L: Debug,
+        C::Nullifier: Debug,
+        C::Utxo: Debug,
+        C::AssetId: Debug,
+        C::AssetValue: Debug")
+)]
+pub struct CheckedLedger<C, L>
+where
+    C: Configuration + ?Sized,
+{
+    /// Ledger
+    ledger: L,
+
+    /// Spent Nullifiers
+    nullifiers: Vec<C::Nullifier>,
+
+    /// Registered UTXOs
+    utxos: Vec<C::Utxo>,
+
+    /// Shielded Pool Balances
+    ///
+    /// Value deposited into the shielded pool by the sources minus the value withdrawn from it by
+    /// the sinks, for every asset.
+    shielded_balances: BTreeMap<C::AssetId, C::AssetValue>,
+}
+
+impl<C, L> CheckedLedger<C, L>
+where
+    C: Configuration + ?Sized,
+{
+    /// Builds a new [`CheckedLedger`] which decorates `ledger`.
+    #[inline]
+    pub fn new(ledger: L) -> Self {
+        Self {
+            ledger,
+            nullifiers: Vec::new(),
+            utxos: Vec::new(),
+            shielded_balances: BTreeMap::new(),
+        }
+    }
+
+    /// Returns a shared reference to the underlying ledger.
+    #[inline]
+    pub fn ledger(&self) -> &L {
+        &self.ledger
+    }
+
+    /// Drops the checks and returns the underlying ledger.
+    #[inline]
+    pub fn into_inner(self) -> L {
+        self.ledger
+    }
+
+    /// Returns the nullifiers spent through `self`, in the order they were spent.
+    #[inline]
+    pub fn spent_nullifiers(&self) -> &[C::Nullifier] {
+        &self.nullifiers
+    }
+
+    /// Returns the UTXOs registered through `self`, in the order they were registered.
+    #[inline]
+    pub fn registered_utxos(&self) -> &[C::Utxo] {
+        &self.utxos
+    }
+
+    /// Returns the balance of the shielded pool in `asset_id`, as tracked by `self`.
+    #[inline]
+    pub fn shielded_balance(&self, asset_id: &C::AssetId) -> C::AssetValue {
+        self.shielded_balances
+            .get(asset_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns `true` if `nullifier` is related to one of the spent nullifiers.
+    #[inline]
+    fn is_spent(&self, nullifier: &C::Nullifier) -> bool {
+        self.nullifiers
+            .iter()
+            .any(|spent| Independence::<NullifierIndependence>::is_related(spent, nullifier))
+    }
+
+    /// Returns `true` if `utxo` is related to one of the registered UTXOs.
+    #[inline]
+    fn is_registered(&self, utxo: &C::Utxo) -> bool {
+        self.utxos
+            .iter()
+            .any(|registered| Independence::<UtxoIndependence>::is_related(registered, utxo))
+    }
+}
+
+impl<C, L> SenderLedger<Parameters<C>> for CheckedLedger<C, L>
+where
+    C: Configuration + ?Sized,
+    L: TransferLedger<C>,
+    C::Nullifier: Clone,
+{
+    type SuperPostingKey = (L::ValidProof, TransferLedgerSuperPostingKey<C, L>);
+    type ValidUtxoAccumulatorOutput =
+        <L as SenderLedger<Parameters<C>>>::ValidUtxoAccumulatorOutput;
+    type ValidNullifier = <L as SenderLedger<Parameters<C>>>::ValidNullifier;
+    type Error = <L as SenderLedger<Parameters<C>>>::Error;
+
+    #[inline]
+    fn is_unspent(&self, nullifier: C::Nullifier) -> Result<Self::ValidNullifier, Self::Error> {
+        let nullifier = self.ledger.is_unspent(nullifier)?;
+        assert!(
+            !self.is_spent(nullifier.as_ref()),
+            "The ledger reported a spent nullifier as unspent."
+        );
+        Ok(nullifier)
+    }
+
+    #[inline]
+    fn has_matching_utxo_accumulator_output(
+        &self,
+        output: UtxoAccumulatorOutput<C>,
+    ) -> Result<Self::ValidUtxoAccumulatorOutput, Self::Error> {
+        self.ledger.has_matching_utxo_accumulator_output(output)
+    }
+
+    #[inline]
+    fn spend_all<I>(
+        &mut self,
+        super_key: &Self::SuperPostingKey,
+        iter: I,
+    ) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = (Self::ValidUtxoAccumulatorOutput, Self::ValidNullifier)>,
+    {
+        let iter = iter.into_iter().collect::<Vec<_>>();
+        let len = self.nullifiers.len();
+        for (_, nullifier) in &iter {
+            assert!(
+                !self.is_spent(nullifier.as_ref()),
+                "The ledger is spending a nullifier which was already spent."
+            );
+            self.nullifiers.push(nullifier.as_ref().clone());
+        }
+        let result = self.ledger.spend_all(super_key, iter);
+        if result.is_err() {
+            self.nullifiers.truncate(len);
+        }
+        result
+    }
+}
+
+impl<C, L> ReceiverLedger<Parameters<C>> for CheckedLedger<C, L>
+where
+    C: Configuration + ?Sized,
+    L: TransferLedger<C>,
+    C::Utxo: Clone,
+{
+    type SuperPostingKey = (L::ValidProof, TransferLedgerSuperPostingKey<C, L>);
+    type ValidUtxo = <L as ReceiverLedger<Parameters<C>>>::ValidUtxo;
+    type Error = <L as ReceiverLedger<Parameters<C>>>::Error;
+
+    #[inline]
+    fn is_not_registered(&self, utxo: C::Utxo) -> Result<Self::ValidUtxo, Self::Error> {
+        let utxo = self.ledger.is_not_registered(utxo)?;
+        assert!(
+            !self.is_registered(utxo.as_ref()),
+            "The ledger reported a registered UTXO as not registered."
+        );
+        Ok(utxo)
+    }
+
+    #[inline]
+    fn check_registration(&self, registration: Registration) -> Result<(), RegistrationError> {
+        self.ledger.check_registration(registration)
+    }
+
+    #[inline]
+    fn register_all<I>(
+        &mut self,
+        super_key: &Self::SuperPostingKey,
+        iter: I,
+    ) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = (Self::ValidUtxo, Note<C>)>,
+    {
+        let iter = iter.into_iter().collect::<Vec<_>>();
+        let len = self.utxos.len();
+        for (utxo, _) in &iter {
+            assert!(
+                !self.is_registered(utxo.as_ref()),
+                "The ledger is registering a UTXO which was already registered."
+            );
+            self.utxos.push(utxo.as_ref().clone());
+        }
+        let result = self.ledger.register_all(super_key, iter);
+        if result.is_err() {
+            self.utxos.truncate(len);
+        }
+        result
+    }
+}
+
+impl<C, L> TransferLedger<C> for CheckedLedger<C, L>
+where
+    C: Configuration + ?Sized,
+    L: TransferLedger<C>,
+    L::ValidUtxoAccumulatorOutput: Clone,
+    L::ValidNullifier: Clone,
+    L::ValidUtxo: Clone,
+    C::Nullifier: Clone,
+    C::Utxo: Clone,
+    Note<C>: Clone,
+{
+    type SuperPostingKey = TransferLedgerSuperPostingKey<C, L>;
+    type Event = L::Event;
+    type ValidSourceAccount = SourcePostingKey<C, L>;
+    type ValidSinkAccount = SinkPostingKey<C, L>;
+    type ValidProof = L::ValidProof;
+    type Error = <L as TransferLedger<C>>::Error;
+
+    #[inline]
+    fn check_source_accounts<I>(
+        &self,
+        asset_id: &C::AssetId,
+        sources: I,
+    ) -> Result<Vec<Self::ValidSourceAccount>, InvalidSourceAccount<C, C::AccountId>>
+    where
+        I: Iterator<Item = (C::AccountId, C::AssetValue)>,
+    {
+        self.ledger.check_source_accounts(asset_id, sources)
+    }
+
+    #[inline]
+    fn check_sink_accounts<I>(
+        &self,
+        asset_id: &C::AssetId,
+        sinks: I,
+    ) -> Result<Vec<Self::ValidSinkAccount>, InvalidSinkAccount<C, C::AccountId>>
+    where
+        I: Iterator<Item = (C::AccountId, C::AssetValue)>,
+    {
+        self.ledger.check_sink_accounts(asset_id, sinks)
+    }
+
+    #[inline]
+    fn is_valid(
+        &self,
+        posting_key: TransferPostingKeyRef<C, Self>,
+    ) -> Result<(Self::ValidProof, Self::Event), <Self as TransferLedger<C>>::Error> {
+        let senders = posting_key
+            .senders
+            .iter()
+            .cloned()
+            .map(SenderPostingKey::into_ledger)
+            .collect::<Vec<_>>();
+        let receivers = posting_key
+            .receivers
+            .iter()
+            .cloned()
+            .map(ReceiverPostingKey::into_ledger)
+            .collect::<Vec<_>>();
+        self.ledger.is_valid(TransferPostingKeyRef {
+            epoch: posting_key.epoch,
+            authorization_key: posting_key.authorization_key,
+            asset_id: posting_key.asset_id,
+            sources: posting_key.sources,
+            senders: &senders,
+            receivers: &receivers,
+            sinks: posting_key.sinks,
+            fee: posting_key.fee,
+            proof: posting_key.proof,
+        })
+    }
+
+    #[inline]
+    fn update_public_balances(
+        &mut self,
+        super_key: &TransferLedgerSuperPostingKey<C, Self>,
+        asset_id: C::AssetId,
+        sources: Vec<SourcePostingKey<C, Self>>,
+        sinks: Vec<SinkPostingKey<C, Self>>,
+        proof: Self::ValidProof,
+    ) -> Result<Vec<BalanceDelta<C>>, <Self as TransferLedger<C>>::Error> {
+        let mut balance = self.shielded_balance(&asset_id);
+        for source in &sources {
+            balance = balance
+                .checked_add(source.as_ref().clone())
+                .expect("The sources of the post overflowed the shielded pool.");
+        }
+        for sink in &sinks {
+            balance = balance
+                .checked_sub(sink.as_ref().clone())
+                .expect("The sinks of the post withdrew more value than the shielded pool holds.");
+        }
+        let deltas = self.ledger.update_public_balances(
+            super_key,
+            asset_id.clone(),
+            sources,
+            sinks,
+            proof,
+        )?;
+        self.shielded_balances.insert(asset_id, balance);
+        Ok(deltas)
+    }
+
+    #[inline]
+    fn credit_fee(
+        &mut self,
+        super_key: &TransferLedgerSuperPostingKey<C, Self>,
+        asset_id: &C::AssetId,
+        fee: C::AssetValue,
+        proof: Self::ValidProof,
+    ) -> Result<Vec<BalanceDelta<C>>, <Self as TransferLedger<C>>::Error> {
+        let balance = self
+            .shielded_balance(asset_id)
+            .checked_sub(fee.clone())
+            .expect("The fee of the post withdrew more value than the shielded pool holds.");
+        let deltas = self.ledger.credit_fee(super_key, asset_id, fee, proof)?;
+        self.shielded_balances.insert(asset_id.clone(), balance);
+        Ok(deltas)
+    }
+
+    #[inline]
+    fn rollback_public_balances<I>(
+        &mut self,
+        super_key: &TransferLedgerSuperPostingKey<C, Self>,
+        rollback: I,
+    ) where
+        I: IntoIterator<Item = BalanceDelta<C>>,
+    {
+        let rollback = rollback.into_iter().collect::<Vec<_>>();
+        for delta in &rollback {
+            let balance = self.shielded_balance(&delta.asset().id);
+            let balance = match delta {
+                BalanceDelta::Withdraw { asset, .. } => balance.checked_add(asset.value.clone()),
+                BalanceDelta::Deposit { asset, .. } => balance.checked_sub(asset.value.clone()),
+            }
+            .expect("Rolling back public balances cannot overflow the shielded pool.");
+            self.shielded_balances
+                .insert(delta.asset().id.clone(), balance);
+        }
+        self.ledger.rollback_public_balances(super_key, rollback);
+    }
+
+    #[inline]
+    fn note_policy(&self) -> Option<&dyn NotePolicy<C>> {
+        self.ledger.note_policy()
+    }
+
+    #[inline]
+    fn fee_schedule(&self) -> Option<&FeeSchedule<C::AssetId, C::AssetValue>> {
+        self.ledger.fee_schedule()
+    }
+
+    #[inline]
+    fn check_fee(&self, fee: &C::AssetValue) -> Result<(), InsufficientFee<C::AssetValue>> {
+        self.ledger.check_fee(fee)
+    }
+}
@@ -0,0 +1,183 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Compliance Tags
+//!
+//! Regulated pools can attach an opaque compliance tag to their notes, for instance the identifier
+//! of the issuer or of the jurisdiction which admitted the funds into the pool. The tag travels in
+//! the associated data of a note as a [`Tagged`] value and is bound into its UTXO by a
+//! [`TagBinding`], which derives the UTXO commitment randomness from the randomness of the note and
+//! its tag. Since circuits recompute the UTXO commitments of the notes they spend and create, a
+//! note can only be opened with the tag it was created with.
+//!
+//! A [`TagPolicy`] is a predicate over the tags of the inputs of a transfer and the tag of one of
+//! its outputs, which [`TagPolicy::assert_propagation`] enforces in-circuit for every output. The
+//! policies of this module are [`Uniform`], under which tags never mix, and [`Inherit`], under
+//! which outputs can take the tag of any of the inputs, but custom predicates can be used to
+//! experiment with other pools without changing the transfer protocol.
+
+use crate::transfer::utxo::AssociatedDataType;
+use manta_crypto::eclair::{
+    alloc::Constant,
+    bool::{Assert, Bool},
+    cmp::PartialEq,
+    ops::{BitAnd, BitOr},
+    Has,
+};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Tagged Associated Data
+///
+/// Associated data `D` of a note extended with its compliance tag `T`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Tagged<D, T> {
+    /// Associated Data
+    pub data: D,
+
+    /// Compliance Tag
+    pub tag: T,
+}
+
+impl<D, T> Tagged<D, T> {
+    /// Builds a new [`Tagged`] value from `data` and `tag`.
+    #[inline]
+    pub fn new(data: D, tag: T) -> Self {
+        Self { data, tag }
+    }
+
+    /// Splits `self` into its associated data and its tag.
+    #[inline]
+    pub fn split(self) -> (D, T) {
+        (self.data, self.tag)
+    }
+}
+
+/// Tagged Associated Data Type
+pub type TaggedAssociatedData<M, T> = Tagged<<M as AssociatedDataType>::AssociatedData, T>;
+
+/// Tag Binding
+///
+/// Binds compliance tags into UTXOs by deriving the randomness of their commitments from the
+/// randomness of the note and its tag. Bindings must be collision-resistant in the tag, otherwise
+/// the same UTXO can be opened with several tags.
+pub trait TagBinding<COM = ()> {
+    /// Compliance Tag Type
+    type Tag;
+
+    /// UTXO Commitment Randomness Type
+    type Randomness;
+
+    /// Returns the UTXO commitment randomness which binds `tag` to the note with `randomness`.
+    fn bind(
+        &self,
+        randomness: &Self::Randomness,
+        tag: &Self::Tag,
+        compiler: &mut COM,
+    ) -> Self::Randomness;
+}
+
+/// Tag Policy
+///
+/// Predicate over the compliance tags of the inputs of a transfer and the tag of one of its outputs.
+pub trait TagPolicy<T, COM = ()>
+where
+    COM: Has<bool>,
+{
+    /// Returns `true` if an output with tag `output` can be created from inputs with `inputs` tags.
+    fn permits(&self, inputs: &[T], output: &T, compiler: &mut COM) -> Bool<COM>;
+
+    /// Asserts that every tag in `outputs` is permitted by `self` for the tags in `inputs`.
+    #[inline]
+    fn assert_propagation<'t, I>(&self, inputs: &[T], outputs: I, compiler: &mut COM)
+    where
+        COM: Assert,
+        T: 't,
+        I: IntoIterator<Item = &'t T>,
+    {
+        for output in outputs {
+            let permitted = self.permits(inputs, output, compiler);
+            compiler.assert(&permitted);
+        }
+    }
+}
+
+/// Uniform Tag Policy
+///
+/// Permits an output only if its tag is equal to the tags of all the inputs, so that notes of
+/// different tags are never merged. Outputs of transfers without inputs are always permitted, so
+/// the tags of new notes are decided when they enter the pool.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Uniform;
+
+impl<T, COM> TagPolicy<T, COM> for Uniform
+where
+    COM: Has<bool>,
+    T: PartialEq<T, COM>,
+    Bool<COM>: Constant<COM, Type = bool> + BitAnd<Bool<COM>, COM, Output = Bool<COM>>,
+{
+    #[inline]
+    fn permits(&self, inputs: &[T], output: &T, compiler: &mut COM) -> Bool<COM> {
+        let mut permitted = Bool::<COM>::new_constant(&true, compiler);
+        for input in inputs {
+            let is_equal = input.eq(output, compiler);
+            permitted = permitted.bitand(is_equal, compiler);
+        }
+        permitted
+    }
+}
+
+/// Inheriting Tag Policy
+///
+/// Permits an output if its tag is equal to the tag of at least one of the inputs, so that tags
+/// can be mixed but never made up. Transfers without inputs cannot create tagged outputs under
+/// this policy, so the pool has to admit new notes by some other means, for instance with a
+/// [`Uniform`] policy for deposits.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Inherit;
+
+impl<T, COM> TagPolicy<T, COM> for Inherit
+where
+    COM: Has<bool>,
+    T: PartialEq<T, COM>,
+    Bool<COM>: Constant<COM, Type = bool> + BitOr<Bool<COM>, COM, Output = Bool<COM>>,
+{
+    #[inline]
+    fn permits(&self, inputs: &[T], output: &T, compiler: &mut COM) -> Bool<COM> {
+        let mut permitted = Bool::<COM>::new_constant(&false, compiler);
+        for input in inputs {
+            let is_equal = input.eq(output, compiler);
+            permitted = permitted.bitor(is_equal, compiler);
+        }
+        permitted
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Ledger State Differences
+//!
+//! A [`StateDiff`] records every change that posting a batch of [`TransferPost`]s makes to the
+//! ledger state. Ledgers can use it to apply the same batch on another node without re-validating
+//! it, or to revert the batch when rolling back to an earlier snapshot.
+//!
+//! [`TransferPost`]: crate::transfer::TransferPost
+
+use crate::transfer::{Asset, Configuration, Note, Nullifier, Utxo};
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Inserted UTXO
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "I: Deserialize<'de>, Utxo<C>: Deserialize<'de>, Note<C>: Deserialize<'de>",
+            serialize = "I: Serialize, Utxo<C>: Serialize, Note<C>: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "I: Clone, Utxo<C>: Clone, Note<C>: Clone"),
+    Debug(bound = "I: Debug, Utxo<C>: Debug, Note<C>: Debug"),
+    Eq(bound = "I: Eq, Utxo<C>: Eq, Note<C>: Eq"),
+    Hash(bound = "I: Hash, Utxo<C>: Hash, Note<C>: Hash"),
+    PartialEq(bound = "I: PartialEq, Utxo<C>: PartialEq, Note<C>: PartialEq")
+)]
+pub struct InsertedUtxo<C, I>
+where
+    C: Configuration + ?Sized,
+{
+    /// Ledger Index
+    pub index: I,
+
+    /// UTXO
+    pub utxo: Utxo<C>,
+
+    /// Note
+    pub note: Note<C>,
+}
+
+/// Public Balance Delta
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "C::AccountId: Deserialize<'de>, Asset<C>: Deserialize<'de>",
+            serialize = "C::AccountId: Serialize, Asset<C>: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "C::AccountId: Clone, Asset<C>: Clone"),
+    Debug(bound = "C::AccountId: Debug, Asset<C>: Debug"),
+    Eq(bound = "C::AccountId: Eq, Asset<C>: Eq"),
+    Hash(bound = "C::AccountId: Hash, Asset<C>: Hash"),
+    PartialEq(bound = "C::AccountId: PartialEq, Asset<C>: PartialEq")
+)]
+pub enum BalanceDelta<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Withdrawal of `asset` from `account`
+    Withdraw {
+        /// Account
+        account: C::AccountId,
+
+        /// Withdrawn Asset
+        asset: Asset<C>,
+    },
+
+    /// Deposit of `asset` into `account`
+    Deposit {
+        /// Account
+        account: C::AccountId,
+
+        /// Deposited Asset
+        asset: Asset<C>,
+    },
+}
+
+impl<C> BalanceDelta<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Returns the account whose balance changes.
+    #[inline]
+    pub fn account(&self) -> &C::AccountId {
+        match self {
+            Self::Withdraw { account, .. } | Self::Deposit { account, .. } => account,
+        }
+    }
+
+    /// Returns the asset which is added to or removed from the account.
+    #[inline]
+    pub fn asset(&self) -> &Asset<C> {
+        match self {
+            Self::Withdraw { asset, .. } | Self::Deposit { asset, .. } => asset,
+        }
+    }
+
+    /// Returns the delta which undoes `self`.
+    #[inline]
+    pub fn inverse(self) -> Self {
+        match self {
+            Self::Withdraw { account, asset } => Self::Deposit { account, asset },
+            Self::Deposit { account, asset } => Self::Withdraw { account, asset },
+        }
+    }
+}
+
+/// Ledger State Difference
+///
+/// The entries of each field are stored in the order in which they were applied to the ledger, so
+/// reverting a [`StateDiff`] must undo them in reverse order.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = r"
+                InsertedUtxo<C, I>: Deserialize<'de>,
+                Nullifier<C>: Deserialize<'de>,
+                BalanceDelta<C>: Deserialize<'de>
+            ",
+            serialize = r"
+                InsertedUtxo<C, I>: Serialize,
+                Nullifier<C>: Serialize,
+                BalanceDelta<C>: Serialize
+            ",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "InsertedUtxo<C, I>: Clone, Nullifier<C>: Clone, BalanceDelta<C>: Clone"),
+    Debug(bound = "InsertedUtxo<C, I>: Debug, Nullifier<C>: Debug, BalanceDelta<C>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "InsertedUtxo<C, I>: Eq, Nullifier<C>: Eq, BalanceDelta<C>: Eq"),
+    Hash(bound = "InsertedUtxo<C, I>: Hash, Nullifier<C>: Hash, BalanceDelta<C>: Hash"),
+    PartialEq(
+        bound = "InsertedUtxo<C, I>: PartialEq, Nullifier<C>: PartialEq, BalanceDelta<C>: PartialEq"
+    )
+)]
+pub struct StateDiff<C, I>
+where
+    C: Configuration + ?Sized,
+{
+    /// Inserted UTXOs
+    pub utxos: Vec<InsertedUtxo<C, I>>,
+
+    /// Inserted Nullifiers
+    pub nullifiers: Vec<Nullifier<C>>,
+
+    /// Public Balance Deltas
+    pub balance_deltas: Vec<BalanceDelta<C>>,
+}
+
+impl<C, I> StateDiff<C, I>
+where
+    C: Configuration + ?Sized,
+{
+    /// Returns `true` if `self` does not change the ledger state.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.utxos.is_empty() && self.nullifiers.is_empty() && self.balance_deltas.is_empty()
+    }
+
+    /// Returns the public balance deltas which undo the ones of `self`, in the order in which they
+    /// have to be applied.
+    ///
+    /// Ledgers use this to roll back the balance changes of a post which was rejected while its
+    /// public balances were being updated, and
+    /// [`TransferPostingKey::post`](crate::transfer::TransferPostingKey::post) uses it to roll them
+    /// back when a later step of posting fails. Inserted UTXOs and nullifiers have no inverse
+    /// delta.
+    #[inline]
+    pub fn balance_rollback(&self) -> impl Iterator<Item = BalanceDelta<C>> + '_
+    where
+        BalanceDelta<C>: Clone,
+    {
+        self.balance_deltas
+            .iter()
+            .rev()
+            .map(|delta| delta.clone().inverse())
+    }
+
+    /// Appends all the changes in `other` to `self`, so that applying `self` is equivalent to
+    /// applying the old value of `self` followed by `other`.
+    #[inline]
+    pub fn extend(&mut self, other: Self) {
+        self.utxos.extend(other.utxos);
+        self.nullifiers.extend(other.nullifiers);
+        self.balance_deltas.extend(other.balance_deltas);
+    }
+}
@@ -0,0 +1,956 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Dynamic Transfers
+//!
+//! The [`Transfer`] type fixes the number of its participants with const generics, so every shape
+//! is a distinct type whose circuit is known at compile time. A [`DynamicTransfer`] instead stores
+//! its participants in vectors and carries its [`Shape`] at runtime, which lets a ledger accept
+//! any shape it has a proving context for. The shape checks of [`Transfer::check_shape`] are
+//! performed by [`Shape::check`] and reported as a [`ShapeError`] instead of a panic, and the
+//! proving contexts are looked up by shape in a [`ProvingContextRegistry`]. On the verifying side,
+//! a `VerifyingContextProvider` loads the verifying contexts of the shapes it sees on demand, so
+//! that a ledger does not pay for the shapes it never verifies.
+
+use crate::transfer::{
+    canonical::TransferShape, extend_input, has_public_participants, requires_authorization, Asset,
+    Authorization, Configuration, FullParametersRef, Parameters, Proof, ProofInput,
+    ProofSystemError, ProofSystemPublicParameters, ProvingContext, Receiver, Sender, SpendingKey,
+    Transfer, TransferPost, TransferPostBody, TransferVar, VerifyingContext, MAX_POST_PARTICIPANTS,
+};
+use alloc::collections::BTreeMap;
+use core::{fmt::Debug, hash::Hash};
+use manta_crypto::{
+    constraint::{Input, ProofSystem},
+    eclair::alloc::Allocate,
+    rand::{CryptoRng, RngCore},
+};
+use manta_util::vec::Vec;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+#[cfg(feature = "std")]
+use {
+    alloc::{
+        collections::{BTreeSet, VecDeque},
+        sync::Arc,
+    },
+    std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+/// Transfer Shape
+///
+/// Number of sources, senders, receivers and sinks of a transfer, and whether it pays a fee.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Shape {
+    /// Number of Sources
+    pub sources: usize,
+
+    /// Number of Senders
+    pub senders: usize,
+
+    /// Number of Receivers
+    pub receivers: usize,
+
+    /// Number of Sinks
+    pub sinks: usize,
+
+    /// Fee Flag
+    ///
+    /// Transfers which pay a fee have it as an extra public output, and reveal their asset id.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fee: bool,
+}
+
+impl Shape {
+    /// Builds a new [`Shape`] from its participant counts.
+    #[inline]
+    pub const fn new(sources: usize, senders: usize, receivers: usize, sinks: usize) -> Self {
+        Self {
+            sources,
+            senders,
+            receivers,
+            sinks,
+            fee: false,
+        }
+    }
+
+    /// Returns the [`Shape`] of the transfers with the participants of `self` which pay a fee.
+    #[inline]
+    pub const fn with_fee(self) -> Self {
+        Self { fee: true, ..self }
+    }
+
+    /// Returns the [`Shape`] of the [`Transfer`] with the given const generic parameters.
+    #[inline]
+    pub const fn of<
+        const SOURCES: usize,
+        const SENDERS: usize,
+        const RECEIVERS: usize,
+        const SINKS: usize,
+    >() -> Self {
+        Self::new(SOURCES, SENDERS, RECEIVERS, SINKS)
+    }
+
+    /// Returns the [`Shape`] of `post`.
+    #[inline]
+    pub fn from_post<C>(post: &TransferPost<C>) -> Self
+    where
+        C: Configuration,
+    {
+        Self {
+            sources: post.body.sources.len(),
+            senders: post.body.sender_posts.len(),
+            receivers: post.body.receiver_posts.len(),
+            sinks: post.body.sinks.len(),
+            fee: post.body.fee.is_some(),
+        }
+    }
+
+    /// Returns the [`TransferShape`] of `self` if it is one of the canonical shapes.
+    #[inline]
+    pub fn canonical(&self) -> Option<TransferShape> {
+        if self.fee {
+            return None;
+        }
+        TransferShape::select(
+            self.requires_authorization(),
+            self.has_visible_asset_id(),
+            self.sources,
+            self.senders,
+            self.receivers,
+            self.sinks,
+        )
+    }
+
+    /// Returns `true` if a transfer with shape `self` requires an authorization.
+    #[inline]
+    pub const fn requires_authorization(&self) -> bool {
+        requires_authorization(self.senders)
+    }
+
+    /// Returns `true` if a transfer with shape `self` has public participants, and so requires a
+    /// visible asset id.
+    #[inline]
+    pub const fn has_public_participants(&self) -> bool {
+        has_public_participants(self.sources, self.sinks)
+    }
+
+    /// Returns `true` if a transfer with shape `self` requires a visible asset id, which is the
+    /// case when it has public participants or pays a fee.
+    #[inline]
+    pub const fn has_visible_asset_id(&self) -> bool {
+        self.has_public_participants() || self.fee
+    }
+
+    /// Checks that a transfer with shape `self` is valid, and that it is given an authorization
+    /// and a visible asset id exactly when they are required.
+    #[inline]
+    pub fn check(
+        &self,
+        has_authorization: bool,
+        has_visible_asset_id: bool,
+    ) -> Result<(), ShapeError> {
+        if self.sources + self.senders == 0 {
+            return Err(ShapeError::EmptyInput);
+        }
+        if self.receivers + self.sinks == 0 {
+            return Err(ShapeError::EmptyOutput);
+        }
+        if [self.sources, self.senders, self.receivers, self.sinks]
+            .into_iter()
+            .any(|count| count > MAX_POST_PARTICIPANTS)
+        {
+            return Err(ShapeError::TooManyParticipants);
+        }
+        match (self.requires_authorization(), has_authorization) {
+            (true, false) => return Err(ShapeError::MissingAuthorization),
+            (false, true) => return Err(ShapeError::UnexpectedAuthorization),
+            _ => {}
+        }
+        match (self.has_visible_asset_id(), has_visible_asset_id) {
+            (true, false) => Err(ShapeError::MissingAssetId),
+            (false, true) => Err(ShapeError::UnexpectedAssetId),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl From<TransferShape> for Shape {
+    #[inline]
+    fn from(shape: TransferShape) -> Self {
+        let (sources, senders, receivers, sinks) = shape.arity();
+        Self::new(sources, senders, receivers, sinks)
+    }
+}
+
+/// Shape Error
+///
+/// This `enum` is the error state of [`Shape::check`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ShapeError {
+    /// Empty Input
+    ///
+    /// Not enough participants on the input side.
+    EmptyInput,
+
+    /// Empty Output
+    ///
+    /// Not enough participants on the output side.
+    EmptyOutput,
+
+    /// Too Many Participants
+    ///
+    /// More participants of one kind than a [`TransferPost`] can hold.
+    TooManyParticipants,
+
+    /// Missing Authorization
+    ///
+    /// Missing authorization when required.
+    MissingAuthorization,
+
+    /// Unexpected Authorization
+    ///
+    /// Given authorization when not required.
+    UnexpectedAuthorization,
+
+    /// Missing Asset Id
+    ///
+    /// Missing public asset id when required.
+    MissingAssetId,
+
+    /// Unexpected Asset Id
+    ///
+    /// Given public asset id when not required.
+    UnexpectedAssetId,
+}
+
+/// Dynamic Transfer
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = r"
+        Authorization<C>: Clone,
+        C::AssetId: Clone,
+        C::AssetValue: Clone,
+        Sender<C>: Clone,
+        Receiver<C>: Clone"),
+    Debug(bound = r"
+        Authorization<C>: Debug,
+        C::AssetId: Debug,
+        C::AssetValue: Debug,
+        Sender<C>: Debug,
+        Receiver<C>: Debug"),
+    Eq(bound = r"
+        Authorization<C>: Eq,
+        C::AssetId: Eq,
+        C::AssetValue: Eq,
+        Sender<C>: Eq,
+        Receiver<C>: Eq"),
+    Hash(bound = r"
+        Authorization<C>: Hash,
+        C::AssetId: Hash,
+        C::AssetValue: Hash,
+        Sender<C>: Hash,
+        Receiver<C>: Hash"),
+    PartialEq(bound = r"
+        Authorization<C>: PartialEq,
+        C::AssetId: PartialEq,
+        C::AssetValue: PartialEq,
+        Sender<C>: PartialEq,
+        Receiver<C>: PartialEq")
+)]
+pub struct DynamicTransfer<C>
+where
+    C: Configuration,
+{
+    /// Authorization
+    authorization: Option<Authorization<C>>,
+
+    /// Asset Id
+    asset_id: Option<C::AssetId>,
+
+    /// Sources
+    sources: Vec<C::AssetValue>,
+
+    /// Senders
+    senders: Vec<Sender<C>>,
+
+    /// Receivers
+    receivers: Vec<Receiver<C>>,
+
+    /// Sinks
+    sinks: Vec<C::AssetValue>,
+
+    /// Fee
+    fee: Option<C::AssetValue>,
+}
+
+impl<C> DynamicTransfer<C>
+where
+    C: Configuration,
+{
+    /// Builds a new [`DynamicTransfer`] from its component parts, checking that they have a valid
+    /// [`Shape`].
+    #[inline]
+    pub fn new(
+        authorization: impl Into<Option<Authorization<C>>>,
+        asset_id: impl Into<Option<C::AssetId>>,
+        sources: Vec<C::AssetValue>,
+        senders: Vec<Sender<C>>,
+        receivers: Vec<Receiver<C>>,
+        sinks: Vec<C::AssetValue>,
+    ) -> Result<Self, ShapeError> {
+        Self::new_with_fee(
+            authorization,
+            asset_id,
+            sources,
+            senders,
+            receivers,
+            sinks,
+            None,
+        )
+    }
+
+    /// Builds a new [`DynamicTransfer`] from its component parts which pays `fee` to the ledger,
+    /// checking that they have a valid [`Shape`]. See [`Transfer::new_with_fee`] for more.
+    #[inline]
+    pub fn new_with_fee(
+        authorization: impl Into<Option<Authorization<C>>>,
+        asset_id: impl Into<Option<C::AssetId>>,
+        sources: Vec<C::AssetValue>,
+        senders: Vec<Sender<C>>,
+        receivers: Vec<Receiver<C>>,
+        sinks: Vec<C::AssetValue>,
+        fee: Option<C::AssetValue>,
+    ) -> Result<Self, ShapeError> {
+        let this = Self {
+            authorization: authorization.into(),
+            asset_id: asset_id.into(),
+            sources,
+            senders,
+            receivers,
+            sinks,
+            fee,
+        };
+        this.shape()
+            .check(this.authorization.is_some(), this.asset_id.is_some())?;
+        Ok(this)
+    }
+
+    /// Returns the [`Shape`] of `self`.
+    #[inline]
+    pub fn shape(&self) -> Shape {
+        let shape = Shape::new(
+            self.sources.len(),
+            self.senders.len(),
+            self.receivers.len(),
+            self.sinks.len(),
+        );
+        if self.fee.is_some() {
+            shape.with_fee()
+        } else {
+            shape
+        }
+    }
+
+    /// Constructs an [`Asset`] against the `asset_id` of `self` and `value`.
+    #[inline]
+    fn construct_asset(&self, value: &C::AssetValue) -> Option<Asset<C>> {
+        Some(Asset::<C>::new(self.asset_id.clone()?, value.clone()))
+    }
+
+    /// Returns the `k`-th source in the transfer.
+    #[inline]
+    pub fn source(&self, k: usize) -> Option<Asset<C>> {
+        self.sources
+            .get(k)
+            .and_then(|value| self.construct_asset(value))
+    }
+
+    /// Returns the `k`-th sink in the transfer.
+    #[inline]
+    pub fn sink(&self, k: usize) -> Option<Asset<C>> {
+        self.sinks
+            .get(k)
+            .and_then(|value| self.construct_asset(value))
+    }
+
+    /// Returns the fee paid by the transfer.
+    #[inline]
+    pub fn fee(&self) -> Option<Asset<C>> {
+        self.fee
+            .as_ref()
+            .and_then(|value| self.construct_asset(value))
+    }
+
+    /// Generates the public input for the [`DynamicTransfer`] validation proof.
+    #[inline]
+    pub fn generate_proof_input(&self) -> ProofInput<C> {
+        let mut input = Default::default();
+        self.extend(&mut input);
+        input
+    }
+
+    /// Builds a constraint system for transfers with the given `shape` which asserts constraints
+    /// against unknown variables.
+    #[inline]
+    pub fn unknown_constraints(shape: Shape, parameters: FullParametersRef<C>) -> C::Compiler {
+        let mut compiler = C::ProofSystem::context_compiler();
+        TransferVar::<C>::new_unknown(shape, &mut compiler)
+            .build_validity_constraints(&parameters.as_constant(&mut compiler), &mut compiler);
+        compiler
+    }
+
+    /// Asserts the validity constraints against the known variables of `self` in `compiler`.
+    #[inline]
+    fn build_known_constraints(
+        &self,
+        parameters: FullParametersRef<C>,
+        compiler: &mut C::Compiler,
+    ) {
+        TransferVar::<C>::new_known(
+            self.authorization.as_ref(),
+            self.asset_id.as_ref(),
+            &self.sources,
+            &self.senders,
+            &self.receivers,
+            &self.sinks,
+            self.fee.as_ref(),
+            compiler,
+        )
+        .build_validity_constraints(&parameters.as_constant(compiler), compiler);
+    }
+
+    /// Builds a constraint system which asserts constraints against known variables.
+    #[inline]
+    pub fn known_constraints(&self, parameters: FullParametersRef<C>) -> C::Compiler {
+        let mut compiler = C::ProofSystem::proof_compiler();
+        self.build_known_constraints(parameters, &mut compiler);
+        compiler
+    }
+
+    /// Generates a proving and verifying context for transfers with the given `shape`.
+    #[inline]
+    pub fn generate_context<R>(
+        shape: Shape,
+        public_parameters: &ProofSystemPublicParameters<C>,
+        parameters: FullParametersRef<C>,
+        rng: &mut R,
+    ) -> Result<(ProvingContext<C>, VerifyingContext<C>), ProofSystemError<C>>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        C::ProofSystem::compile(
+            public_parameters,
+            Self::unknown_constraints(shape, parameters),
+            rng,
+        )
+    }
+
+    /// Converts `self` into its [`TransferPost`] by building the validity proof against the
+    /// `proving_context` generated for the [`Shape`] of `self`, and signing the
+    /// [`TransferPostBody`] payload.
+    ///
+    /// Returns `Ok(None)` when the authorization required by this [`DynamicTransfer`] is invalid
+    /// or not provided. Returns `Err` when proof generation fails.
+    #[inline]
+    pub fn into_post<R>(
+        self,
+        parameters: FullParametersRef<C>,
+        proving_context: &ProvingContext<C>,
+        spending_key: Option<&SpendingKey<C>>,
+        sink_accounts: Vec<C::AccountId>,
+        rng: &mut R,
+    ) -> Result<Option<TransferPost<C>>, ProofSystemError<C>>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        match (
+            self.shape().requires_authorization(),
+            self.authorization.is_some(),
+            spending_key,
+        ) {
+            (true, true, Some(_)) | (false, false, None) => {
+                let mut compiler = C::ProofSystem::cached_proof_compiler(proving_context);
+                self.build_known_constraints(parameters, &mut compiler);
+                let proof = C::ProofSystem::prove(proving_context, compiler, rng)?;
+                Ok(self.into_post_with_proof(
+                    parameters.base,
+                    proof,
+                    spending_key,
+                    sink_accounts,
+                    rng,
+                ))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Converts `self` into its [`TransferPost`] with the validity `proof` built elsewhere,
+    /// signing the [`TransferPostBody`] payload. The proof is not checked against `self`.
+    ///
+    /// Returns `None` when the authorization required by this [`DynamicTransfer`] is invalid or
+    /// not provided.
+    #[inline]
+    pub fn into_post_with_proof<R>(
+        self,
+        parameters: &Parameters<C>,
+        proof: Proof<C>,
+        spending_key: Option<&SpendingKey<C>>,
+        sink_accounts: Vec<C::AccountId>,
+        rng: &mut R,
+    ) -> Option<TransferPost<C>>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        TransferPost::sign(
+            parameters,
+            TransferPostBody::build(
+                proof,
+                self.asset_id,
+                self.sources,
+                self.senders,
+                self.receivers,
+                self.sinks,
+                self.fee,
+            ),
+            self.authorization,
+            spending_key,
+            sink_accounts,
+            rng,
+        )
+    }
+}
+
+impl<C, const SOURCES: usize, const SENDERS: usize, const RECEIVERS: usize, const SINKS: usize>
+    From<Transfer<C, SOURCES, SENDERS, RECEIVERS, SINKS>> for DynamicTransfer<C>
+where
+    C: Configuration,
+{
+    #[inline]
+    fn from(transfer: Transfer<C, SOURCES, SENDERS, RECEIVERS, SINKS>) -> Self {
+        Self {
+            authorization: transfer.authorization,
+            asset_id: transfer.asset_id,
+            sources: transfer.sources.into(),
+            senders: transfer.senders.into(),
+            receivers: transfer.receivers.into(),
+            sinks: transfer.sinks.into(),
+            fee: transfer.fee,
+        }
+    }
+}
+
+impl<C> Input<C::ProofSystem> for DynamicTransfer<C>
+where
+    C: Configuration,
+{
+    #[inline]
+    fn extend(&self, input: &mut ProofInput<C>) {
+        extend_input::<C>(
+            input,
+            self.authorization.as_ref(),
+            self.asset_id.as_ref(),
+            &self.sources,
+            &self.senders,
+            &self.receivers,
+            &self.sinks,
+            self.fee.as_ref(),
+        )
+    }
+}
+
+/// Proving Error
+///
+/// This `enum` is the error state of [`ProvingContextRegistry::into_post`].
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "ProofSystemError<C>: Clone"),
+    Debug(bound = "ProofSystemError<C>: Debug"),
+    Eq(bound = "ProofSystemError<C>: Eq"),
+    Hash(bound = "ProofSystemError<C>: Hash"),
+    PartialEq(bound = "ProofSystemError<C>: PartialEq")
+)]
+pub enum ProvingError<C>
+where
+    C: Configuration,
+{
+    /// Missing Proving Context
+    ///
+    /// No proving context was registered for the shape of the transfer.
+    MissingContext(Shape),
+
+    /// Proof System Error
+    ProofSystemError(ProofSystemError<C>),
+}
+
+/// Proving Context Registry
+///
+/// Proving contexts of the transfer circuits indexed by their [`Shape`].
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "ProvingContext<C>: Clone"),
+    Debug(bound = "ProvingContext<C>: Debug"),
+    Default(bound = "")
+)]
+pub struct ProvingContextRegistry<C>
+where
+    C: Configuration,
+{
+    /// Proving Contexts
+    contexts: BTreeMap<Shape, ProvingContext<C>>,
+}
+
+impl<C> ProvingContextRegistry<C>
+where
+    C: Configuration,
+{
+    /// Builds a new empty [`ProvingContextRegistry`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of shapes registered in `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.contexts.len()
+    }
+
+    /// Returns `true` if `self` has no registered shapes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.contexts.is_empty()
+    }
+
+    /// Returns an iterator over the shapes registered in `self`.
+    #[inline]
+    pub fn shapes(&self) -> impl Iterator<Item = &Shape> {
+        self.contexts.keys()
+    }
+
+    /// Returns the proving context registered for `shape`.
+    #[inline]
+    pub fn get(&self, shape: &Shape) -> Option<&ProvingContext<C>> {
+        self.contexts.get(shape)
+    }
+
+    /// Registers `proving_context` for `shape`, returning the proving context it replaces.
+    #[inline]
+    pub fn insert(
+        &mut self,
+        shape: Shape,
+        proving_context: ProvingContext<C>,
+    ) -> Option<ProvingContext<C>> {
+        self.contexts.insert(shape, proving_context)
+    }
+
+    /// Removes the proving context registered for `shape`, returning it if there was one.
+    #[inline]
+    pub fn remove(&mut self, shape: &Shape) -> Option<ProvingContext<C>> {
+        self.contexts.remove(shape)
+    }
+
+    /// Generates the proving and verifying contexts for `shape`, registering the proving context
+    /// in `self` and returning the verifying context.
+    #[inline]
+    pub fn generate<R>(
+        &mut self,
+        shape: Shape,
+        public_parameters: &ProofSystemPublicParameters<C>,
+        parameters: FullParametersRef<C>,
+        rng: &mut R,
+    ) -> Result<VerifyingContext<C>, ProofSystemError<C>>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let (proving_context, verifying_context) =
+            DynamicTransfer::<C>::generate_context(shape, public_parameters, parameters, rng)?;
+        self.insert(shape, proving_context);
+        Ok(verifying_context)
+    }
+
+    /// Converts `transfer` into its [`TransferPost`] with the proving context registered for its
+    /// [`Shape`]. See [`DynamicTransfer::into_post`] for more.
+    #[inline]
+    pub fn into_post<R>(
+        &self,
+        transfer: DynamicTransfer<C>,
+        parameters: FullParametersRef<C>,
+        spending_key: Option<&SpendingKey<C>>,
+        sink_accounts: Vec<C::AccountId>,
+        rng: &mut R,
+    ) -> Result<Option<TransferPost<C>>, ProvingError<C>>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let shape = transfer.shape();
+        transfer
+            .into_post(
+                parameters,
+                self.get(&shape)
+                    .ok_or(ProvingError::MissingContext(shape))?,
+                spending_key,
+                sink_accounts,
+                rng,
+            )
+            .map_err(ProvingError::ProofSystemError)
+    }
+}
+
+/// Verifying Error
+///
+/// This `enum` is the error state of [`VerifyingContextProvider::has_valid_proof`].
+#[cfg(feature = "std")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "ProofSystemError<C>: Clone"),
+    Debug(bound = "ProofSystemError<C>: Debug"),
+    Eq(bound = "ProofSystemError<C>: Eq"),
+    Hash(bound = "ProofSystemError<C>: Hash"),
+    PartialEq(bound = "ProofSystemError<C>: PartialEq")
+)]
+pub enum VerifyingError<C>
+where
+    C: Configuration,
+{
+    /// Missing Verifying Context
+    ///
+    /// The loader of the provider has no verifying context for the shape of the post.
+    MissingContext(Shape),
+
+    /// Proof System Error
+    ProofSystemError(ProofSystemError<C>),
+}
+
+/// Loaded Verifying Contexts
+#[cfg(feature = "std")]
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = "VerifyingContext<C>: Debug"), Default(bound = ""))]
+struct LoadedContexts<C>
+where
+    C: Configuration,
+{
+    /// Verifying Contexts
+    contexts: BTreeMap<Shape, Arc<VerifyingContext<C>>>,
+
+    /// Pinned Shapes
+    pinned: BTreeSet<Shape>,
+
+    /// Unpinned Shapes in Loading Order
+    unpinned: VecDeque<Shape>,
+}
+
+#[cfg(feature = "std")]
+impl<C> LoadedContexts<C>
+where
+    C: Configuration,
+{
+    /// Inserts `context` for `shape` unless another one was inserted in the meantime, pinning it
+    /// if `pin` is `true`, and returns the verifying context stored for `shape`.
+    #[inline]
+    fn insert(
+        &mut self,
+        shape: Shape,
+        context: Arc<VerifyingContext<C>>,
+        pin: bool,
+        capacity: Option<usize>,
+    ) -> Arc<VerifyingContext<C>> {
+        let context = self.contexts.entry(shape).or_insert(context).clone();
+        if pin {
+            if self.pinned.insert(shape) {
+                self.unpinned.retain(|unpinned| unpinned != &shape);
+            }
+        } else if !self.pinned.contains(&shape) && !self.unpinned.contains(&shape) {
+            self.unpinned.push_back(shape);
+            self.shrink(capacity);
+        }
+        context
+    }
+
+    /// Evicts the unpinned contexts in loading order until at most `capacity` of them are left.
+    #[inline]
+    fn shrink(&mut self, capacity: Option<usize>) {
+        if let Some(capacity) = capacity {
+            while self.unpinned.len() > capacity {
+                if let Some(shape) = self.unpinned.pop_front() {
+                    self.contexts.remove(&shape);
+                }
+            }
+        }
+    }
+}
+
+/// Verifying Context Provider
+///
+/// Verifying contexts of the transfer circuits indexed by their [`Shape`], which are loaded with
+/// the loader of the provider the first time they are requested instead of all at once. Pinned
+/// shapes stay loaded until they are unpinned, while the other shapes are evicted in loading order
+/// once more than the capacity of the provider are loaded. Contexts are handed out behind an
+/// [`Arc`], so an eviction never invalidates a verification which is in progress.
+///
+/// The provider can be shared between threads. A context is loaded without holding the lock of
+/// the provider, so that a slow load does not stall the verification of the other shapes, which
+/// means that two threads requesting the same missing shape can both load it. Only one of the
+/// loaded contexts is kept.
+#[cfg(feature = "std")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = "VerifyingContext<C>: Debug"))]
+pub struct VerifyingContextProvider<C, L>
+where
+    C: Configuration,
+    L: Fn(&Shape) -> Option<VerifyingContext<C>>,
+{
+    /// Verifying Context Loader
+    #[derivative(Debug = "ignore")]
+    loader: L,
+
+    /// Capacity for Unpinned Contexts
+    capacity: Option<usize>,
+
+    /// Loaded Contexts
+    loaded: RwLock<LoadedContexts<C>>,
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+impl<C, L> VerifyingContextProvider<C, L>
+where
+    C: Configuration,
+    L: Fn(&Shape) -> Option<VerifyingContext<C>>,
+{
+    /// Builds a new [`VerifyingContextProvider`] which loads verifying contexts with `loader` and
+    /// never evicts them.
+    #[inline]
+    pub fn new(loader: L) -> Self {
+        Self {
+            loader,
+            capacity: None,
+            loaded: Default::default(),
+        }
+    }
+
+    /// Builds a new [`VerifyingContextProvider`] which loads verifying contexts with `loader` and
+    /// keeps at most `capacity` unpinned contexts loaded.
+    #[inline]
+    pub fn with_capacity(loader: L, capacity: usize) -> Self {
+        Self {
+            loader,
+            capacity: Some(capacity),
+            loaded: Default::default(),
+        }
+    }
+
+    /// Returns a read guard of the loaded contexts.
+    #[inline]
+    fn read(&self) -> RwLockReadGuard<'_, LoadedContexts<C>> {
+        self.loaded.read().expect("Lock is not poisoned.")
+    }
+
+    /// Returns a write guard of the loaded contexts.
+    #[inline]
+    fn write(&self) -> RwLockWriteGuard<'_, LoadedContexts<C>> {
+        self.loaded.write().expect("Lock is not poisoned.")
+    }
+
+    /// Returns the verifying context for `shape`, loading it if it is not loaded yet. Returns
+    /// `None` if the loader has no verifying context for `shape`.
+    #[inline]
+    pub fn get(&self, shape: &Shape) -> Option<Arc<VerifyingContext<C>>> {
+        if let Some(context) = self.read().contexts.get(shape) {
+            return Some(context.clone());
+        }
+        let context = Arc::new((self.loader)(shape)?);
+        Some(self.write().insert(*shape, context, false, self.capacity))
+    }
+
+    /// Loads the verifying context for `shape` if it is not loaded yet and pins it, so that it is
+    /// never evicted. Returns `None` if the loader has no verifying context for `shape`.
+    #[inline]
+    pub fn pin(&self, shape: Shape) -> Option<Arc<VerifyingContext<C>>> {
+        let context = self.get(&shape)?;
+        Some(self.write().insert(shape, context, true, self.capacity))
+    }
+
+    /// Unpins the verifying context for `shape`, which can then be evicted like the contexts which
+    /// were never pinned. Returns `false` if `shape` was not pinned.
+    #[inline]
+    pub fn unpin(&self, shape: &Shape) -> bool {
+        let mut loaded = self.write();
+        if !loaded.pinned.remove(shape) {
+            return false;
+        }
+        loaded.unpinned.push_back(*shape);
+        loaded.shrink(self.capacity);
+        true
+    }
+
+    /// Returns `true` if `shape` is pinned in `self`.
+    #[inline]
+    pub fn is_pinned(&self, shape: &Shape) -> bool {
+        self.read().pinned.contains(shape)
+    }
+
+    /// Returns `true` if the verifying context for `shape` is loaded in `self`.
+    #[inline]
+    pub fn is_loaded(&self, shape: &Shape) -> bool {
+        self.read().contexts.contains_key(shape)
+    }
+
+    /// Returns the shapes whose verifying contexts are loaded in `self`.
+    #[inline]
+    pub fn loaded_shapes(&self) -> Vec<Shape> {
+        self.read().contexts.keys().copied().collect()
+    }
+
+    /// Evicts the verifying context for `shape` unless it is pinned, returning `true` if it was
+    /// loaded and is now evicted.
+    #[inline]
+    pub fn evict(&self, shape: &Shape) -> bool {
+        let mut loaded = self.write();
+        if loaded.pinned.contains(shape) {
+            return false;
+        }
+        loaded.unpinned.retain(|unpinned| unpinned != shape);
+        loaded.contexts.remove(shape).is_some()
+    }
+
+    /// Verifies the validity proof of `post` with the verifying context for its [`Shape`],
+    /// loading it if it is not loaded yet.
+    #[inline]
+    pub fn has_valid_proof(&self, post: &TransferPost<C>) -> Result<bool, VerifyingError<C>> {
+        let shape = Shape::from_post(post);
+        post.has_valid_proof(
+            &*self
+                .get(&shape)
+                .ok_or(VerifyingError::MissingContext(shape))?,
+        )
+        .map_err(VerifyingError::ProofSystemError)
+    }
+}
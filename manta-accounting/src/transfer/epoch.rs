@@ -0,0 +1,215 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Parameter Epochs
+//!
+//! Upgrading the transfer circuits changes both the verifying contexts and, possibly, the
+//! parameters used to build and validate a [`TransferPost`](super::TransferPost). To let wallets
+//! switch to the new circuits at their own pace, a ledger keeps several epochs active at the same
+//! time in an [`EpochRegistry`] and every [`TransferPost`](super::TransferPost) is tagged with the
+//! [`Epoch`] it was built for. Once an epoch is retired, posts tagged with it are still accepted
+//! until its grace window ends.
+
+use alloc::collections::btree_map::BTreeMap;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Epoch Tag
+pub type Epoch = u32;
+
+/// Ledger Height
+///
+/// Grace windows are measured in units of ledger height, which is increased by the ledger every
+/// time it accepts a new batch of posts.
+pub type Height = u64;
+
+/// Epoch Error
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum EpochError {
+    /// Unknown Epoch
+    ///
+    /// The epoch was never activated on the ledger or it was already pruned.
+    UnknownEpoch,
+
+    /// Retired Epoch
+    ///
+    /// The epoch was retired and its grace window has ended.
+    RetiredEpoch,
+}
+
+/// Epoch Entry
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(deserialize = "T: Deserialize<'de>", serialize = "T: Serialize"),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct EpochEntry<T> {
+    /// Epoch Value
+    value: T,
+
+    /// Retirement Height
+    retired_at: Option<Height>,
+}
+
+impl<T> EpochEntry<T> {
+    /// Returns `true` if `self` can still be used at `height` given the `grace_window`.
+    #[inline]
+    fn is_usable(&self, height: Height, grace_window: Height) -> bool {
+        match self.retired_at {
+            Some(retired_at) => height < retired_at.saturating_add(grace_window),
+            _ => true,
+        }
+    }
+}
+
+/// Epoch Registry
+///
+/// An [`EpochRegistry`] maps every active [`Epoch`] to its value, usually a pair of verifying
+/// context and parameters. Retired epochs stay usable for `grace_window`-many heights after their
+/// retirement.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(deserialize = "T: Deserialize<'de>", serialize = "T: Serialize"),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct EpochRegistry<T> {
+    /// Epochs
+    epochs: BTreeMap<Epoch, EpochEntry<T>>,
+
+    /// Grace Window
+    grace_window: Height,
+}
+
+impl<T> EpochRegistry<T> {
+    /// Builds a new empty [`EpochRegistry`] which keeps retired epochs usable for
+    /// `grace_window`-many heights.
+    #[inline]
+    pub fn new(grace_window: Height) -> Self {
+        Self {
+            epochs: Default::default(),
+            grace_window,
+        }
+    }
+
+    /// Returns the grace window of `self`.
+    #[inline]
+    pub fn grace_window(&self) -> Height {
+        self.grace_window
+    }
+
+    /// Sets the grace window of `self`, which also applies to the epochs that were already
+    /// retired.
+    #[inline]
+    pub fn set_grace_window(&mut self, grace_window: Height) {
+        self.grace_window = grace_window;
+    }
+
+    /// Activates `epoch` with the given `value`, returning the previous value of `epoch` if it
+    /// was already registered. Activating a retired epoch makes it active again.
+    #[inline]
+    pub fn activate(&mut self, epoch: Epoch, value: T) -> Option<T> {
+        self.epochs
+            .insert(
+                epoch,
+                EpochEntry {
+                    value,
+                    retired_at: None,
+                },
+            )
+            .map(|entry| entry.value)
+    }
+
+    /// Retires `epoch` at `height`, returning `false` if `epoch` is not registered or was already
+    /// retired.
+    #[inline]
+    pub fn retire(&mut self, epoch: Epoch, height: Height) -> bool {
+        match self.epochs.get_mut(&epoch) {
+            Some(entry) if entry.retired_at.is_none() => {
+                entry.retired_at = Some(height);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the value of `epoch` if it can still be used at `height`.
+    #[inline]
+    pub fn get(&self, epoch: Epoch, height: Height) -> Result<&T, EpochError> {
+        let entry = self.epochs.get(&epoch).ok_or(EpochError::UnknownEpoch)?;
+        if entry.is_usable(height, self.grace_window) {
+            Ok(&entry.value)
+        } else {
+            Err(EpochError::RetiredEpoch)
+        }
+    }
+
+    /// Returns `true` if `epoch` is registered and was not retired.
+    #[inline]
+    pub fn is_active(&self, epoch: Epoch) -> bool {
+        matches!(
+            self.epochs.get(&epoch),
+            Some(EpochEntry {
+                retired_at: None,
+                ..
+            })
+        )
+    }
+
+    /// Returns the latest epoch which was not retired and its value. New posts should be tagged
+    /// with this epoch.
+    #[inline]
+    pub fn latest(&self) -> Option<(Epoch, &T)> {
+        self.epochs
+            .iter()
+            .rev()
+            .find(|(_, entry)| entry.retired_at.is_none())
+            .map(|(epoch, entry)| (*epoch, &entry.value))
+    }
+
+    /// Removes the retired epochs whose grace window has ended at `height`, returning the number
+    /// of epochs which were removed.
+    #[inline]
+    pub fn prune(&mut self, height: Height) -> usize {
+        let grace_window = self.grace_window;
+        let count = self.epochs.len();
+        self.epochs
+            .retain(|_, entry| entry.is_usable(height, grace_window));
+        count - self.epochs.len()
+    }
+}
+
+impl<T> Default for EpochRegistry<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
@@ -0,0 +1,178 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fee Schedules
+//!
+//! A [`TransferPost`] can pay a fee inside its proof, in the asset it transfers, which the ledger
+//! credits with [`TransferLedger::credit_fee`]. Otherwise, the fees are charged by the ledger, in
+//! one of its assets, for every post it accepts. A ledger which charges fees publishes its
+//! [`FeeSchedule`], so that wallets can estimate the fees of their transactions before signing
+//! them, and rejects the posts whose fee cannot be paid with an [`InsufficientFee`] error.
+//!
+//! [`TransferPost`]: super::TransferPost
+//! [`TransferLedger::credit_fee`]: super::TransferLedger::credit_fee
+
+use crate::transfer::{canonical::TransferShape, Configuration, TransferPost};
+use manta_util::num::CheckedAdd;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Shape Fee
+///
+/// Fee of a post of a given shape: a base fee plus a weight for every sender and for every
+/// receiver, which approximates the cost of checking the nullifiers and storing the notes.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ShapeFee<V> {
+    /// Base Fee
+    pub base: V,
+
+    /// Fee per Sender
+    pub per_sender: V,
+
+    /// Fee per Receiver
+    pub per_receiver: V,
+}
+
+impl<V> ShapeFee<V>
+where
+    V: CheckedAdd<Output = V> + Clone,
+{
+    /// Returns the fee of a post with `senders`-many senders and `receivers`-many receivers,
+    /// returning `None` if it overflows.
+    #[inline]
+    pub fn fee(&self, senders: usize, receivers: usize) -> Option<V> {
+        let fee = add_weight(self.base.clone(), &self.per_sender, senders)?;
+        add_weight(fee, &self.per_receiver, receivers)
+    }
+}
+
+/// Fee Schedule
+///
+/// Fees charged by a ledger for every canonical [`TransferShape`], in the asset with id
+/// [`asset_id`](Self::asset_id).
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct FeeSchedule<I, V> {
+    /// Fee Asset Id
+    pub asset_id: I,
+
+    /// [`ToPrivate`](TransferShape::ToPrivate) Fee
+    pub to_private: ShapeFee<V>,
+
+    /// [`PrivateTransfer`](TransferShape::PrivateTransfer) Fee
+    pub private_transfer: ShapeFee<V>,
+
+    /// [`ToPublic`](TransferShape::ToPublic) Fee
+    pub to_public: ShapeFee<V>,
+
+    /// [`PrivateSwap`](TransferShape::PrivateSwap) Fee
+    pub private_swap: ShapeFee<V>,
+}
+
+impl<I, V> FeeSchedule<I, V>
+where
+    V: CheckedAdd<Output = V> + Clone,
+{
+    /// Returns the [`ShapeFee`] of `shape`.
+    #[inline]
+    pub fn shape_fee(&self, shape: TransferShape) -> &ShapeFee<V> {
+        match shape {
+            TransferShape::ToPrivate => &self.to_private,
+            TransferShape::PrivateTransfer => &self.private_transfer,
+            TransferShape::ToPublic => &self.to_public,
+            TransferShape::PrivateSwap => &self.private_swap,
+        }
+    }
+
+    /// Returns the fee of a post of `shape`, returning `None` if it overflows.
+    #[inline]
+    pub fn fee(&self, shape: TransferShape) -> Option<V> {
+        let (_, senders, receivers, _) = shape.arity();
+        self.shape_fee(shape).fee(senders, receivers)
+    }
+
+    /// Returns the fee of `post`, returning `None` if `post` does not have a canonical shape or if
+    /// the fee overflows.
+    #[inline]
+    pub fn post_fee<C>(&self, post: &TransferPost<C>) -> Option<V>
+    where
+        C: Configuration<AssetValue = V> + ?Sized,
+    {
+        let shape = TransferShape::select(
+            post.authorization_signature.is_some(),
+            post.body.asset_id.is_some(),
+            post.body.sources.len(),
+            post.body.sender_posts.len(),
+            post.body.receiver_posts.len(),
+            post.body.sinks.len(),
+        )?;
+        self.fee(shape)
+    }
+
+    /// Returns the fee of a transaction of `shape` which is signed into `posts`-many posts, where
+    /// all the posts but the last one are private transfers, as is the case for the transfers
+    /// which merge or split notes before the last one. Returns `None` if the fee overflows.
+    #[inline]
+    pub fn transaction_fee(&self, shape: TransferShape, posts: usize) -> Option<V> {
+        let fee = self.fee(shape)?;
+        match posts.checked_sub(1) {
+            Some(extra_posts) => {
+                add_weight(fee, &self.fee(TransferShape::PrivateTransfer)?, extra_posts)
+            }
+            _ => Some(fee),
+        }
+    }
+}
+
+/// Insufficient Fee Error
+///
+/// This `struct` is the error state of the fee checks of a ledger which charges the fees of its
+/// [`FeeSchedule`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct InsufficientFee<V> {
+    /// Required Fee
+    pub required: V,
+
+    /// Value Available to Pay the Fee
+    pub available: V,
+}
+
+/// Adds `count`-many copies of `weight` to `value`, returning `None` if it overflows.
+#[inline]
+fn add_weight<V>(mut value: V, weight: &V, count: usize) -> Option<V>
+where
+    V: CheckedAdd<Output = V> + Clone,
+{
+    for _ in 0..count {
+        value = value.checked_add(weight.clone())?;
+    }
+    Some(value)
+}
@@ -0,0 +1,451 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Proofs of Holdings
+//!
+//! A [`HoldingsProof`] proves in zero knowledge that its prover owns an unspent note of at least
+//! some threshold value of a given asset, which is a member of the UTXO accumulator with a given
+//! output. Unlike an [`IdentityProof`](super::IdentityProof), it is not a [`TransferPost`] and the
+//! note is not spent, so no nullifier is revealed and the proof can be used to gate access, for
+//! example to a DAO vote or to an airdrop, without touching the ledger.
+//!
+//! The [`Holdings`] circuit proves that the note belongs to the authorization context behind the
+//! public [`AuthorizationKey`], which is randomized for every proof, that the note is in the UTXO
+//! accumulator and that its value is the threshold plus a secret excess. Since the asset values
+//! are range-checked, the excess cannot wrap around and the value of the note is at least the
+//! threshold.
+//!
+//! # Limitations
+//!
+//! Verifiers are responsible for checking that the UTXO accumulator output of a [`HoldingsProof`]
+//! is a recent output of the ledger. Since no nullifier is revealed, nothing prevents the same
+//! note from being used in several proofs, so applications which count the proofs, such as votes,
+//! need another mechanism to rule out double counting, for instance a snapshot of the ledger.
+//!
+//! [`TransferPost`]: super::TransferPost
+
+use crate::transfer::{
+    utxo::{DeriveSpend, Spend},
+    AssetVar, Authorization, AuthorizationKey, AuthorizationVar, Configuration, FullParametersRef,
+    FullParametersVar, IdentifiedAsset, Parameters, Proof, ProofInput, ProofSystemError,
+    ProofSystemPublicParameters, ProvingContext, SpendingKey, Utxo, UtxoAccumulatorItem,
+    UtxoAccumulatorModel, UtxoAccumulatorModelVar, UtxoAccumulatorOutput, UtxoMembershipProof,
+    VerifyingContext,
+};
+use core::{fmt::Debug, hash::Hash};
+use manta_crypto::{
+    accumulator::{Accumulator, ItemHashFunction, MembershipProof},
+    constraint::{measure::Profile, HasInput, Input, ProofSystem},
+    eclair::{
+        alloc::{
+            mode::{Derived, Public, Secret},
+            Allocate, Allocator, Variable,
+        },
+        bool::AssertEq,
+        ops::Add,
+    },
+    rand::{CryptoRng, RngCore},
+};
+use manta_util::{convert::Field, num::CheckedSub};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Holdings
+///
+/// Witness of the [`Holdings`] circuit, which proves that the owner of the note in
+/// [`utxo`](Self::utxo) holds at least [`threshold`](Self::threshold) of the asset with id
+/// [`asset_id`](Self::asset_id).
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = r"
+        Authorization<C>: Clone,
+        C::AssetId: Clone,
+        C::AssetValue: Clone,
+        C::SpendSecret: Clone,
+        Utxo<C>: Clone,
+        UtxoMembershipProof<C>: Clone"),
+    Debug(bound = r"
+        Authorization<C>: Debug,
+        C::AssetId: Debug,
+        C::AssetValue: Debug,
+        C::SpendSecret: Debug,
+        Utxo<C>: Debug,
+        UtxoMembershipProof<C>: Debug")
+)]
+pub struct Holdings<C>
+where
+    C: Configuration,
+{
+    /// Authorization
+    authorization: Authorization<C>,
+
+    /// Asset Id
+    asset_id: C::AssetId,
+
+    /// Threshold
+    threshold: C::AssetValue,
+
+    /// Excess Value over the Threshold
+    excess: C::AssetValue,
+
+    /// Spending Secret
+    secret: C::SpendSecret,
+
+    /// Unspent Transaction Output
+    utxo: Utxo<C>,
+
+    /// UTXO Membership Proof
+    utxo_membership_proof: UtxoMembershipProof<C>,
+}
+
+impl<C> Holdings<C>
+where
+    C: Configuration,
+{
+    /// Builds the [`Holdings`] of at least `threshold` for the note of `identified_asset` owned
+    /// by `spending_key`, returning `None` if the value of the note is smaller than `threshold` or
+    /// if its [`Utxo`] is not provable in `utxo_accumulator`.
+    #[inline]
+    pub fn build<A, R>(
+        parameters: &Parameters<C>,
+        spending_key: &SpendingKey<C>,
+        utxo_accumulator: &A,
+        identified_asset: IdentifiedAsset<C>,
+        threshold: C::AssetValue,
+        rng: &mut R,
+    ) -> Option<Self>
+    where
+        A: Accumulator<Item = UtxoAccumulatorItem<C>, Model = UtxoAccumulatorModel<C>>,
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let IdentifiedAsset::<C> { identifier, asset } = identified_asset;
+        let excess = asset.value.clone().checked_sub(threshold.clone())?;
+        let mut authorization =
+            Authorization::<C>::from_spending_key(parameters, spending_key, rng);
+        let (secret, utxo, _) =
+            parameters.derive_spend(&mut authorization.context, identifier, asset.clone(), rng);
+        let utxo_membership_proof = utxo_accumulator.prove(
+            &parameters
+                .utxo_accumulator_item_hash()
+                .item_hash(&utxo, &mut ()),
+        )?;
+        Some(Self {
+            authorization,
+            asset_id: asset.id,
+            threshold,
+            excess,
+            secret,
+            utxo,
+            utxo_membership_proof,
+        })
+    }
+
+    /// Generates the public input for the [`Holdings`] proof.
+    #[inline]
+    pub fn generate_proof_input(&self) -> ProofInput<C> {
+        let mut input = Default::default();
+        self.extend(&mut input);
+        input
+    }
+
+    /// Builds a constraint system which asserts constraints against unknown variables.
+    #[inline]
+    pub fn unknown_constraints(parameters: FullParametersRef<C>) -> C::Compiler {
+        let mut compiler = C::ProofSystem::context_compiler();
+        HoldingsVar::<C>::new_unknown(&mut compiler)
+            .build_validity_constraints(&parameters.as_constant(&mut compiler), &mut compiler);
+        compiler
+    }
+
+    /// Builds a constraint system which asserts constraints against known variables.
+    #[inline]
+    pub fn known_constraints(&self, parameters: FullParametersRef<C>) -> C::Compiler {
+        let mut compiler = C::ProofSystem::proof_compiler();
+        let holdings: HoldingsVar<C> = self.as_known(&mut compiler);
+        holdings.build_validity_constraints(&parameters.as_constant(&mut compiler), &mut compiler);
+        compiler
+    }
+
+    /// Generates a proving and verifying context for the [`Holdings`] circuit.
+    #[inline]
+    pub fn generate_context<R>(
+        public_parameters: &ProofSystemPublicParameters<C>,
+        parameters: FullParametersRef<C>,
+        rng: &mut R,
+    ) -> Result<(ProvingContext<C>, VerifyingContext<C>), ProofSystemError<C>>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        C::ProofSystem::compile(
+            public_parameters,
+            Self::unknown_constraints(parameters),
+            rng,
+        )
+    }
+
+    /// Converts `self` into its [`HoldingsProof`] by proving the [`Holdings`] circuit.
+    #[inline]
+    pub fn into_proof<R>(
+        self,
+        parameters: FullParametersRef<C>,
+        proving_context: &ProvingContext<C>,
+        rng: &mut R,
+    ) -> Result<HoldingsProof<C>, ProofSystemError<C>>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let proof =
+            C::ProofSystem::prove(proving_context, self.known_constraints(parameters), rng)?;
+        Ok(HoldingsProof {
+            authorization_key: Field::into(self.authorization),
+            asset_id: self.asset_id,
+            threshold: self.threshold,
+            utxo_accumulator_output: self.utxo_membership_proof.into_output(),
+            proof,
+        })
+    }
+}
+
+impl<C> Input<C::ProofSystem> for Holdings<C>
+where
+    C: Configuration,
+{
+    #[inline]
+    fn extend(&self, input: &mut ProofInput<C>) {
+        C::ProofSystem::extend(input, Field::get(&self.authorization));
+        C::ProofSystem::extend(input, &self.asset_id);
+        C::ProofSystem::extend(input, &self.threshold);
+        C::ProofSystem::extend(input, self.utxo_membership_proof.output());
+    }
+}
+
+/// Holdings Variable
+struct HoldingsVar<C>
+where
+    C: Configuration,
+{
+    /// Authorization
+    authorization: AuthorizationVar<C>,
+
+    /// Asset Id
+    asset_id: C::AssetIdVar,
+
+    /// Threshold
+    threshold: C::AssetValueVar,
+
+    /// Excess Value over the Threshold
+    excess: C::AssetValueVar,
+
+    /// Spending Secret
+    secret: C::SpendSecretVar,
+
+    /// Unspent Transaction Output
+    utxo: C::UtxoVar,
+
+    /// UTXO Membership Proof
+    utxo_membership_proof: MembershipProof<UtxoAccumulatorModelVar<C>>,
+}
+
+impl<C> HoldingsVar<C>
+where
+    C: Configuration,
+{
+    /// Builds constraints for the [`Holdings`] proof.
+    #[inline]
+    fn build_validity_constraints(
+        mut self,
+        parameters: &FullParametersVar<C>,
+        compiler: &mut C::Compiler,
+    ) {
+        compiler.region("authorization", |compiler| {
+            self.authorization
+                .assert_authorized(&parameters.base, compiler)
+        });
+        let asset: AssetVar<C> = compiler.region("holding", |compiler| {
+            parameters
+                .base
+                .well_formed_asset(
+                    &parameters.utxo_accumulator_model,
+                    &mut self.authorization.context,
+                    &self.secret,
+                    &self.utxo,
+                    &self.utxo_membership_proof,
+                    compiler,
+                )
+                .0
+        });
+        compiler.region("threshold", |compiler| {
+            compiler.assert_eq(&self.asset_id, &asset.id);
+            let value = Add::add(self.threshold, self.excess, compiler);
+            compiler.assert_eq(&value, &asset.value);
+        });
+    }
+}
+
+impl<C> Variable<Derived, C::Compiler> for HoldingsVar<C>
+where
+    C: Configuration,
+{
+    type Type = Holdings<C>;
+
+    #[inline]
+    fn new_unknown(compiler: &mut C::Compiler) -> Self {
+        Self {
+            authorization: compiler.allocate_unknown(),
+            asset_id: compiler.allocate_unknown::<Public, _>(),
+            threshold: compiler.allocate_unknown::<Public, _>(),
+            excess: compiler.allocate_unknown::<Secret, _>(),
+            secret: compiler.allocate_unknown(),
+            utxo: compiler.allocate_unknown::<Secret, _>(),
+            utxo_membership_proof: compiler.allocate_unknown::<Derived<(Secret, Public)>, _>(),
+        }
+    }
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut C::Compiler) -> Self {
+        Self {
+            authorization: this.authorization.as_known(compiler),
+            asset_id: this.asset_id.as_known::<Public, _>(compiler),
+            threshold: this.threshold.as_known::<Public, _>(compiler),
+            excess: this.excess.as_known::<Secret, _>(compiler),
+            secret: this.secret.as_known(compiler),
+            utxo: this.utxo.as_known::<Secret, _>(compiler),
+            utxo_membership_proof: this
+                .utxo_membership_proof
+                .as_known::<Derived<(Secret, Public)>, _>(compiler),
+        }
+    }
+}
+
+/// Holdings Proof
+///
+/// Proof that its prover owns an unspent note of at least [`threshold`](Self::threshold) of the
+/// asset with id [`asset_id`](Self::asset_id) in the UTXO accumulator with output
+/// [`utxo_accumulator_output`](Self::utxo_accumulator_output). See the [module-level
+/// documentation](self) for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = r"
+                AuthorizationKey<C>: Deserialize<'de>,
+                C::AssetId: Deserialize<'de>,
+                C::AssetValue: Deserialize<'de>,
+                UtxoAccumulatorOutput<C>: Deserialize<'de>,
+                Proof<C>: Deserialize<'de>
+            ",
+            serialize = r"
+                AuthorizationKey<C>: Serialize,
+                C::AssetId: Serialize,
+                C::AssetValue: Serialize,
+                UtxoAccumulatorOutput<C>: Serialize,
+                Proof<C>: Serialize
+            ",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = r"
+        AuthorizationKey<C>: Clone,
+        C::AssetId: Clone,
+        C::AssetValue: Clone,
+        UtxoAccumulatorOutput<C>: Clone,
+        Proof<C>: Clone"),
+    Debug(bound = r"
+        AuthorizationKey<C>: Debug,
+        C::AssetId: Debug,
+        C::AssetValue: Debug,
+        UtxoAccumulatorOutput<C>: Debug,
+        Proof<C>: Debug"),
+    Eq(bound = r"
+        AuthorizationKey<C>: Eq,
+        C::AssetId: Eq,
+        C::AssetValue: Eq,
+        UtxoAccumulatorOutput<C>: Eq,
+        Proof<C>: Eq"),
+    Hash(bound = r"
+        AuthorizationKey<C>: Hash,
+        C::AssetId: Hash,
+        C::AssetValue: Hash,
+        UtxoAccumulatorOutput<C>: Hash,
+        Proof<C>: Hash"),
+    PartialEq(bound = r"
+        AuthorizationKey<C>: PartialEq,
+        C::AssetId: PartialEq,
+        C::AssetValue: PartialEq,
+        UtxoAccumulatorOutput<C>: PartialEq,
+        Proof<C>: PartialEq")
+)]
+pub struct HoldingsProof<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Authorization Key
+    pub authorization_key: AuthorizationKey<C>,
+
+    /// Asset Id
+    pub asset_id: C::AssetId,
+
+    /// Threshold
+    pub threshold: C::AssetValue,
+
+    /// UTXO Accumulator Output
+    pub utxo_accumulator_output: UtxoAccumulatorOutput<C>,
+
+    /// Validity Proof
+    pub proof: Proof<C>,
+}
+
+impl<C> HoldingsProof<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Generates the public input for the [`Holdings`] proof of `self`.
+    #[inline]
+    pub fn generate_proof_input(&self) -> ProofInput<C> {
+        let mut input = Default::default();
+        self.extend(&mut input);
+        input
+    }
+
+    /// Verifies the validity proof of `self` against `verifying_context`. The UTXO accumulator
+    /// output of `self` must be checked against the ledger separately.
+    #[inline]
+    pub fn has_valid_proof(
+        &self,
+        verifying_context: &VerifyingContext<C>,
+    ) -> Result<bool, ProofSystemError<C>> {
+        C::ProofSystem::verify(verifying_context, &self.generate_proof_input(), &self.proof)
+    }
+}
+
+impl<C> Input<C::ProofSystem> for HoldingsProof<C>
+where
+    C: Configuration + ?Sized,
+{
+    #[inline]
+    fn extend(&self, input: &mut ProofInput<C>) {
+        C::ProofSystem::extend(input, &self.authorization_key);
+        C::ProofSystem::extend(input, &self.asset_id);
+        C::ProofSystem::extend(input, &self.threshold);
+        C::ProofSystem::extend(input, &self.utxo_accumulator_output);
+    }
+}
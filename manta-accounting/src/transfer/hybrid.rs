@@ -0,0 +1,349 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Hybrid Authorization
+//!
+//! This module is an experimental extension of transfer authorization which pairs the classical
+//! [`AuthorizationSignature`](super::AuthorizationSignature) with a post-quantum signature over a
+//! digest of the same transfer body, so that deployments can pilot the migration of the
+//! authorization layer. Ledgers choose which of the two signatures they require with an
+//! [`AuthorizationPolicy`].
+//!
+//! # Limitations
+//!
+//! The post-quantum verifying key is carried in the post and is not yet bound to the authorization
+//! key by the transfer circuit, so a post-quantum signature alone only proves that the body was
+//! not modified after signing. The [`AuthorizationPolicy::PostQuantum`] and
+//! [`AuthorizationPolicy::Either`] policies are meant for testing the migration and should not be
+//! used to protect real assets.
+
+use crate::transfer::{
+    requires_authorization, AuthorizationKey, BodyWithAccountsRef, InvalidAuthorizationSignature,
+    SpendingKey, TransferLedger, TransferLedgerPostError, TransferLedgerSuperPostingKey,
+    TransferPost, TransferPostError, TransferPostingKey,
+};
+use core::{fmt::Debug, hash::Hash};
+use manta_crypto::signature::{self, Derive, Sign, Verify};
+use manta_util::vec::Vec;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Hybrid Authorization Configuration
+pub trait Configuration: super::Configuration {
+    /// Post-Quantum Signature Scheme Type
+    ///
+    /// The signing keys of this scheme are derived once per transfer, so one-time signature
+    /// schemes are allowed.
+    type PostQuantumSignatureScheme: Derive + Sign<Randomness = ()> + Verify<Verification = bool>;
+
+    /// Derives the post-quantum signing key for the transfer authorized by `authorization_key`
+    /// from `spending_key`.
+    ///
+    /// # Implementation Note
+    ///
+    /// The authorization key is randomized for every transfer, so deriving the signing key from
+    /// both inputs gives a fresh key for every transfer.
+    fn derive_post_quantum_signing_key(
+        spending_key: &SpendingKey<Self>,
+        authorization_key: &AuthorizationKey<Self>,
+    ) -> PostQuantumSigningKey<Self>;
+
+    /// Computes the digest of `body_with_accounts` and `authorization_key` which is signed by the
+    /// post-quantum signature.
+    fn post_quantum_message(
+        body_with_accounts: &BodyWithAccountsRef<Self>,
+        authorization_key: &AuthorizationKey<Self>,
+    ) -> PostQuantumMessage<Self>;
+}
+
+/// Post-Quantum Signing Key Type
+pub type PostQuantumSigningKey<C> =
+    signature::SigningKey<<C as Configuration>::PostQuantumSignatureScheme>;
+
+/// Post-Quantum Verifying Key Type
+pub type PostQuantumVerifyingKey<C> =
+    signature::VerifyingKey<<C as Configuration>::PostQuantumSignatureScheme>;
+
+/// Post-Quantum Message Type
+pub type PostQuantumMessage<C> =
+    signature::Message<<C as Configuration>::PostQuantumSignatureScheme>;
+
+/// Post-Quantum Signature Type
+pub type PostQuantumSignatureType<C> =
+    signature::Signature<<C as Configuration>::PostQuantumSignatureScheme>;
+
+/// Authorization Policy
+///
+/// The policy chooses which signatures of a [`HybridTransferPost`] the ledger requires to be
+/// valid. Posts without senders need no authorization under every policy.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum AuthorizationPolicy {
+    /// Classical Signature Only
+    ///
+    /// The post-quantum signature is ignored. This is the behavior of [`TransferPost::validate`].
+    #[default]
+    Classical,
+
+    /// Post-Quantum Signature Only
+    ///
+    /// The classical signature is ignored.
+    PostQuantum,
+
+    /// Either Signature
+    ///
+    /// At least one of the two signatures must be valid.
+    Either,
+
+    /// Both Signatures
+    ///
+    /// The classical and the post-quantum signatures must both be valid.
+    Both,
+}
+
+/// Post-Quantum Signature
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "PostQuantumVerifyingKey<C>: Deserialize<'de>, PostQuantumSignatureType<C>: Deserialize<'de>",
+            serialize = "PostQuantumVerifyingKey<C>: Serialize, PostQuantumSignatureType<C>: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "PostQuantumVerifyingKey<C>: Clone, PostQuantumSignatureType<C>: Clone"),
+    Debug(bound = "PostQuantumVerifyingKey<C>: Debug, PostQuantumSignatureType<C>: Debug"),
+    Eq(bound = "PostQuantumVerifyingKey<C>: Eq, PostQuantumSignatureType<C>: Eq"),
+    Hash(bound = "PostQuantumVerifyingKey<C>: Hash, PostQuantumSignatureType<C>: Hash"),
+    PartialEq(
+        bound = "PostQuantumVerifyingKey<C>: PartialEq, PostQuantumSignatureType<C>: PartialEq"
+    )
+)]
+pub struct PostQuantumSignature<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Verifying Key
+    pub verifying_key: PostQuantumVerifyingKey<C>,
+
+    /// Signature
+    pub signature: PostQuantumSignatureType<C>,
+}
+
+/// Hybrid Transfer Post
+///
+/// A [`TransferPost`] together with an optional post-quantum signature over the same body.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "TransferPost<C>: Deserialize<'de>, PostQuantumSignature<C>: Deserialize<'de>",
+            serialize = "TransferPost<C>: Serialize, PostQuantumSignature<C>: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "TransferPost<C>: Clone, PostQuantumSignature<C>: Clone"),
+    Debug(bound = "TransferPost<C>: Debug, PostQuantumSignature<C>: Debug"),
+    Eq(bound = "TransferPost<C>: Eq, PostQuantumSignature<C>: Eq"),
+    Hash(bound = "TransferPost<C>: Hash, PostQuantumSignature<C>: Hash"),
+    PartialEq(bound = "TransferPost<C>: PartialEq, PostQuantumSignature<C>: PartialEq")
+)]
+pub struct HybridTransferPost<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Transfer Post
+    pub post: TransferPost<C>,
+
+    /// Post-Quantum Signature
+    pub post_quantum_signature: Option<PostQuantumSignature<C>>,
+}
+
+impl<C> HybridTransferPost<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Builds a new [`HybridTransferPost`] from `post` and `post_quantum_signature` without
+    /// checking the signature.
+    #[inline]
+    pub fn new_unchecked(
+        post: TransferPost<C>,
+        post_quantum_signature: Option<PostQuantumSignature<C>>,
+    ) -> Self {
+        Self {
+            post,
+            post_quantum_signature,
+        }
+    }
+
+    /// Adds a post-quantum signature to `post` with the signing key derived from `spending_key`,
+    /// if `post` requires authorization.
+    #[inline]
+    pub fn sign(
+        scheme: &C::PostQuantumSignatureScheme,
+        spending_key: &SpendingKey<C>,
+        post: TransferPost<C>,
+    ) -> Self {
+        let post_quantum_signature = match &post.authorization_signature {
+            Some(authorization_signature)
+                if requires_authorization(post.body.sender_posts.len()) =>
+            {
+                let authorization_key = &authorization_signature.authorization_key;
+                let signing_key =
+                    C::derive_post_quantum_signing_key(spending_key, authorization_key);
+                let message = C::post_quantum_message(
+                    &BodyWithAccountsRef::new(&post.body, &post.sink_accounts),
+                    authorization_key,
+                );
+                Some(PostQuantumSignature {
+                    verifying_key: scheme.derive(&signing_key, &mut ()),
+                    signature: scheme.sign(&signing_key, &(), &message, &mut ()),
+                })
+            }
+            _ => None,
+        };
+        Self::new_unchecked(post, post_quantum_signature)
+    }
+
+    /// Verifies that the post-quantum signature of `self` is valid under `scheme`.
+    #[inline]
+    pub fn has_valid_post_quantum_signature(
+        &self,
+        scheme: &C::PostQuantumSignatureScheme,
+    ) -> Result<(), InvalidAuthorizationSignature> {
+        match (
+            &self.post_quantum_signature,
+            &self.post.authorization_signature,
+            requires_authorization(self.post.body.sender_posts.len()),
+        ) {
+            (Some(post_quantum_signature), Some(authorization_signature), true) => {
+                let message = C::post_quantum_message(
+                    &BodyWithAccountsRef::new(&self.post.body, &self.post.sink_accounts),
+                    &authorization_signature.authorization_key,
+                );
+                if scheme.verify(
+                    &post_quantum_signature.verifying_key,
+                    &message,
+                    &post_quantum_signature.signature,
+                    &mut (),
+                ) {
+                    Ok(())
+                } else {
+                    Err(InvalidAuthorizationSignature::BadSignature)
+                }
+            }
+            (Some(_), _, false) | (_, None, true) => {
+                Err(InvalidAuthorizationSignature::InvalidShape)
+            }
+            (None, _, true) => Err(InvalidAuthorizationSignature::MissingSignature),
+            (None, _, false) => Ok(()),
+        }
+    }
+
+    /// Verifies that the signatures of `self` required by `policy` are valid under `parameters`
+    /// and `scheme`. When `policy` is [`AuthorizationPolicy::Either`] and both signatures are
+    /// invalid, the error of the classical signature is returned.
+    #[inline]
+    pub fn has_valid_authorization(
+        &self,
+        parameters: &C::Parameters,
+        scheme: &C::PostQuantumSignatureScheme,
+        policy: AuthorizationPolicy,
+    ) -> Result<(), InvalidAuthorizationSignature> {
+        match policy {
+            AuthorizationPolicy::Classical => {
+                self.post.has_valid_authorization_signature(parameters)
+            }
+            AuthorizationPolicy::PostQuantum => self.has_valid_post_quantum_signature(scheme),
+            AuthorizationPolicy::Either => self
+                .post
+                .has_valid_authorization_signature(parameters)
+                .or_else(|err| {
+                    self.has_valid_post_quantum_signature(scheme)
+                        .map_err(|_| err)
+                }),
+            AuthorizationPolicy::Both => {
+                self.post.has_valid_authorization_signature(parameters)?;
+                self.has_valid_post_quantum_signature(scheme)
+            }
+        }
+    }
+
+    /// Validates `self` on the transfer `ledger`, checking its authorization according to
+    /// `policy`.
+    #[allow(clippy::type_complexity)] // FIXME: Use a better abstraction for this.
+    #[inline]
+    pub fn validate<L>(
+        self,
+        parameters: &C::Parameters,
+        scheme: &C::PostQuantumSignatureScheme,
+        policy: AuthorizationPolicy,
+        ledger: &L,
+        source_accounts: Vec<C::AccountId>,
+        sink_accounts: Vec<C::AccountId>,
+    ) -> Result<TransferPostingKey<C, L>, TransferLedgerPostError<C, L>>
+    where
+        L: TransferLedger<C>,
+    {
+        self.has_valid_authorization(parameters, scheme, policy)?;
+        self.post
+            .validate_authorized(ledger, source_accounts, sink_accounts)
+    }
+
+    /// Validates `self` on the transfer `ledger` and then posts the updated state to the `ledger`
+    /// if validation succeeded. See [`validate`](Self::validate) for more.
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    pub fn post<L>(
+        self,
+        parameters: &C::Parameters,
+        scheme: &C::PostQuantumSignatureScheme,
+        policy: AuthorizationPolicy,
+        ledger: &mut L,
+        super_key: &TransferLedgerSuperPostingKey<C, L>,
+        source_accounts: Vec<C::AccountId>,
+        sink_accounts: Vec<C::AccountId>,
+    ) -> Result<L::Event, TransferLedgerPostError<C, L>>
+    where
+        L: TransferLedger<C>,
+    {
+        self.validate(
+            parameters,
+            scheme,
+            policy,
+            ledger,
+            source_accounts,
+            sink_accounts,
+        )?
+        .post(ledger, super_key)
+        .map_err(TransferPostError::UnexpectedError)
+    }
+}
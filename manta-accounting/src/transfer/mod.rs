@@ -24,7 +24,9 @@
 //! - Receiver Abstraction: [`Receiver`], [`ReceiverPost`], [`ReceiverLedger`]
 //! - Transfer Abstraction: [`Transfer`], [`TransferPost`], [`TransferLedger`]
 //! - Canonical Transactions: [`canonical`]
+//! - Ledger State Differences: [`diff`]
 //! - Batched Transactions: [`batch`]
+//! - Runtime-Sized Transfers: [`dynamic`]
 //!
 //! See the [`crate::wallet`] module for more on how this transfer protocol is used in a wallet
 //! protocol for the keeping of accounts for private assets.
@@ -33,7 +35,9 @@ use crate::{
     asset,
     transfer::{
         canonical::TransferShape,
-        receiver::{ReceiverLedger, ReceiverPostError},
+        diff::{BalanceDelta, StateDiff},
+        fee::{FeeSchedule, InsufficientFee},
+        receiver::{ReceiverLedger, ReceiverPostError, Registration},
         sender::{SenderLedger, SenderPostError},
         utxo::{auth, Mint, NullifierIndependence, Spend, UtxoIndependence, UtxoReconstruct},
     },
@@ -41,7 +45,7 @@ use crate::{
 use core::{fmt::Debug, hash::Hash, iter::Sum, ops::AddAssign};
 use manta_crypto::{
     accumulator::{self, Accumulator, ItemHashFunction, MembershipProof, Model},
-    constraint::{HasInput, Input, ProofSystem},
+    constraint::{measure::Profile, HasInput, Input, ProofSystem},
     eclair::{
         self,
         alloc::{
@@ -55,9 +59,11 @@ use manta_crypto::{
 };
 use manta_util::{
     cmp::Independence,
-    codec::{Encode, Write},
+    codec::{Decode, DecodeError, Encode, Read, Write},
     convert::Field,
-    vec::{all_unequal, Vec},
+    num::{CheckedAdd, CheckedSub},
+    vec::{all_unequal, BoundedVec, CapacityError, Vec},
+    zeroize::Zeroize,
 };
 
 #[cfg(feature = "serde")]
@@ -65,8 +71,27 @@ use manta_util::serde::{Deserialize, Serialize};
 
 pub mod batch;
 pub mod canonical;
+pub mod checked;
+pub mod compliance;
+
+#[cfg(feature = "payment-channels")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "payment-channels")))]
+pub mod channel;
+
+pub mod diff;
+pub mod dynamic;
+pub mod epoch;
+pub mod fee;
+pub mod holdings;
+
+#[cfg(feature = "hybrid-authorization")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "hybrid-authorization")))]
+pub mod hybrid;
+
+pub mod ownership;
 pub mod receiver;
 pub mod sender;
+pub mod upgrade;
 pub mod utxo;
 
 #[cfg(feature = "test")]
@@ -75,6 +100,16 @@ pub mod test;
 
 #[doc(inline)]
 pub use canonical::Shape;
+pub use epoch::{Epoch, EpochError};
+
+/// Maximum Number of Participants of Each Kind in a [`TransferPost`]
+///
+/// This bound is checked when deserializing the sources, senders, receivers, sinks, and sink
+/// accounts of a [`TransferPost`] so that untrusted posts cannot force large allocations.
+pub const MAX_POST_PARTICIPANTS: usize = 16;
+
+/// Participants of One Kind in a [`TransferPost`]
+pub type PostParticipants<T> = BoundedVec<T, MAX_POST_PARTICIPANTS>;
 
 /// Returns `true` if the [`Transfer`] with this shape would have public participants.
 #[inline]
@@ -103,13 +138,19 @@ pub const fn has_sinks(sinks: usize) -> bool {
 /// Configuration
 pub trait Configuration {
     /// Compiler Type
-    type Compiler: Assert;
+    type Compiler: Assert + Profile;
 
     /// Asset Id Type
     type AssetId: Clone + Ord;
 
     /// Asset Value Type
-    type AssetValue: AddAssign + Clone + Default + PartialOrd + Sum;
+    type AssetValue: AddAssign
+        + CheckedAdd<Output = Self::AssetValue>
+        + CheckedSub<Output = Self::AssetValue>
+        + Clone
+        + Default
+        + PartialOrd
+        + Sum;
 
     /// Account Identifier
     type AccountId: Clone;
@@ -124,7 +165,7 @@ pub trait Configuration {
     type Nullifier: Independence<NullifierIndependence>;
 
     /// Identifier Type
-    type Identifier: Clone + Sample;
+    type Identifier: Clone + PartialEq + Sample;
 
     /// Address Type
     type Address: Clone;
@@ -147,10 +188,24 @@ pub trait Configuration {
     /// [`Utxo`] Accumulator Item Hash Type
     type UtxoAccumulatorItemHash: ItemHashFunction<Utxo<Self>, Item = UtxoAccumulatorItem<Self>>;
 
+    /// Transaction Id Function Type
+    type TransactionIdFunction: TransactionIdFunction<Self>;
+
+    /// Spending Key Type
+    type SpendingKey: Zeroize;
+
+    /// Authorization Context Type
+    type AuthorizationContext: Zeroize;
+
+    /// Decryption Key Type
+    type DecryptionKey: Zeroize;
+
     /// Parameters Type
     type Parameters: Clone
-        + auth::DeriveContext
-        + auth::ProveAuthorization
+        + auth::DeriveContext<
+            SpendingKey = Self::SpendingKey,
+            AuthorizationContext = Self::AuthorizationContext,
+        > + auth::ProveAuthorization
         + auth::VerifyAuthorization
         + auth::DeriveSigningKey
         + for<'a> auth::Sign<BodyWithAccountsRef<'a, Self>>
@@ -169,7 +224,9 @@ pub trait Configuration {
             Secret = Self::SpendSecret,
             Nullifier = Self::Nullifier,
             Identifier = Self::Identifier,
-        > + utxo::UtxoReconstruct;
+        > + utxo::UtxoReconstruct<DecryptionKey = Self::DecryptionKey>
+        + utxo::NoteMemo
+        + utxo::ProveOwnership;
 
     /// Authorization Context Variable Type
     type AuthorizationContextVar: Variable<
@@ -376,6 +433,12 @@ pub type BaseNote<C> = Var<<C as Configuration>::NoteVar, Public, <C as Configur
 /// Note Type
 pub type Note<C> = utxo::Note<Parameters<C>>;
 
+/// Encrypted Memo Type
+pub type Memo<C> = utxo::Memo<Parameters<C>>;
+
+/// Memo Plaintext Type
+pub type MemoPlaintext<C> = utxo::MemoPlaintext<Parameters<C>>;
+
 /// Nullifier Type
 pub type Nullifier<C> = utxo::Nullifier<Parameters<C>>;
 
@@ -456,6 +519,37 @@ where
     )
 }
 
+/// Generates an external receiver for `asset` owned by `address`, returning the [`PaymentOpening`]
+/// from which the [`PaymentReceipt`] of the payment is built once its transaction is signed.
+///
+/// Unlike [`internal_pair`], no [`PreSender`] is built, since the spending authority behind
+/// `address` need not be the one signing the transfer, as is the case for payments or for change
+/// sent to a sub-account with its own spending key.
+#[inline]
+pub fn external_receiver<C, R>(
+    parameters: &Parameters<C>,
+    address: Address<C>,
+    asset: Asset<C>,
+    associated_data: AssociatedData<C>,
+    rng: &mut R,
+) -> (Receiver<C>, PaymentOpening<C>)
+where
+    C: Configuration,
+    R: CryptoRng + RngCore + ?Sized,
+{
+    let receiver = Receiver::<C>::sample(
+        parameters,
+        address.clone(),
+        asset.clone(),
+        associated_data,
+        rng,
+    );
+    let identifier = receiver.identifier();
+    let utxo = parameters.utxo_reconstruct(&asset, &identifier, &address);
+    let opening = PaymentOpening::new(address, IdentifiedAsset::<C>::new(identifier, asset), utxo);
+    (receiver, opening)
+}
+
 /// Transfer
 #[derive(derivative::Derivative)]
 #[derivative(
@@ -522,6 +616,9 @@ pub struct Transfer<
 
     /// Sinks
     sinks: [C::AssetValue; SINKS],
+
+    /// Fee
+    fee: Option<C::AssetValue>,
 }
 
 impl<C, const SOURCES: usize, const SENDERS: usize, const RECEIVERS: usize, const SINKS: usize>
@@ -542,16 +639,75 @@ where
         let authorization = authorization.into();
         let asset_id = asset_id.into();
         Self::check_shape(authorization.is_some(), asset_id.is_some());
-        Self::new_unchecked(authorization, asset_id, sources, senders, receivers, sinks)
+        Self::new_unchecked(
+            authorization,
+            asset_id,
+            sources,
+            senders,
+            receivers,
+            sinks,
+            None,
+        )
+    }
+
+    /// Builds a new [`Transfer`] from its component parts which pays `fee` to the ledger in the
+    /// asset with the given `asset_id`. The fee is an output of the transfer, so the inputs must
+    /// add up to the receivers, the sinks and the fee.
+    ///
+    /// A transfer with a fee reveals its asset id even if it has no public participants, and is
+    /// proven against the circuit of [`Shape::with_fee`](dynamic::Shape::with_fee) instead of the
+    /// circuit of its shape without a fee, whose proving context can be generated with
+    /// [`DynamicTransfer::generate_context`](dynamic::DynamicTransfer::generate_context).
+    #[inline]
+    pub fn new_with_fee(
+        authorization: impl Into<Option<Authorization<C>>>,
+        asset_id: impl Into<Option<C::AssetId>>,
+        sources: [C::AssetValue; SOURCES],
+        senders: [Sender<C>; SENDERS],
+        receivers: [Receiver<C>; RECEIVERS],
+        sinks: [C::AssetValue; SINKS],
+        fee: impl Into<Option<C::AssetValue>>,
+    ) -> Self {
+        let authorization = authorization.into();
+        let asset_id = asset_id.into();
+        let fee = fee.into();
+        Self::check_shape_with_fee(authorization.is_some(), asset_id.is_some(), fee.is_some());
+        Self::new_unchecked(
+            authorization,
+            asset_id,
+            sources,
+            senders,
+            receivers,
+            sinks,
+            fee,
+        )
     }
 
     /// Checks that the [`Transfer`] has a valid shape.
     #[inline]
     pub fn check_shape(has_authorization: bool, has_visible_asset_id: bool) {
+        Self::check_shape_with_fee(has_authorization, has_visible_asset_id, false)
+    }
+
+    /// Checks that the [`Transfer`] has a valid shape when it pays a fee exactly when `has_fee`
+    /// is `true`.
+    #[inline]
+    pub fn check_shape_with_fee(
+        has_authorization: bool,
+        has_visible_asset_id: bool,
+        has_fee: bool,
+    ) {
         Self::has_nonempty_input_shape();
         Self::has_nonempty_output_shape();
         Self::has_authorization_when_required(has_authorization);
-        Self::has_visible_asset_id_when_required(has_visible_asset_id);
+        if has_fee {
+            assert!(
+                has_visible_asset_id,
+                "Missing public asset id when paying a fee."
+            );
+        } else {
+            Self::has_visible_asset_id_when_required(has_visible_asset_id);
+        }
     }
 
     /// Checks that the input side of the transfer is not empty.
@@ -611,6 +767,7 @@ where
         senders: [Sender<C>; SENDERS],
         receivers: [Receiver<C>; RECEIVERS],
         sinks: [C::AssetValue; SINKS],
+        fee: Option<C::AssetValue>,
     ) -> Self {
         Self {
             authorization,
@@ -619,6 +776,7 @@ where
             senders,
             receivers,
             sinks,
+            fee,
         }
     }
 
@@ -644,6 +802,14 @@ where
             .and_then(|value| self.construct_asset(value))
     }
 
+    /// Returns the fee paid by the transfer.
+    #[inline]
+    pub fn fee(&self) -> Option<Asset<C>> {
+        self.fee
+            .as_ref()
+            .and_then(|value| self.construct_asset(value))
+    }
+
     /// Generates the public input for the [`Transfer`] validation proof.
     #[inline]
     pub fn generate_proof_input(&self) -> ProofInput<C> {
@@ -656,21 +822,79 @@ where
     #[inline]
     pub fn unknown_constraints(parameters: FullParametersRef<C>) -> C::Compiler {
         let mut compiler = C::ProofSystem::context_compiler();
-        TransferVar::<C, SOURCES, SENDERS, RECEIVERS, SINKS>::new_unknown(&mut compiler)
-            .build_validity_constraints(&parameters.as_constant(&mut compiler), &mut compiler);
+        Self::build_unknown_constraints(parameters, &mut compiler);
         compiler
     }
 
+    /// Asserts the validity constraints against unknown variables in `compiler`. Use this method
+    /// instead of [`unknown_constraints`](Self::unknown_constraints) to build the constraints in
+    /// a compiler which was set up by the caller, for example in an instrumented mode.
+    #[inline]
+    pub fn build_unknown_constraints(parameters: FullParametersRef<C>, compiler: &mut C::Compiler) {
+        TransferVar::<C>::new_unknown(
+            dynamic::Shape::of::<SOURCES, SENDERS, RECEIVERS, SINKS>(),
+            compiler,
+        )
+        .build_validity_constraints(&parameters.as_constant(compiler), compiler);
+    }
+
     /// Builds a constraint system which asserts constraints against known variables.
     #[inline]
     pub fn known_constraints(&self, parameters: FullParametersRef<C>) -> C::Compiler {
         let mut compiler = C::ProofSystem::proof_compiler();
-        let transfer: TransferVar<C, SOURCES, SENDERS, RECEIVERS, SINKS> =
-            self.as_known(&mut compiler);
-        transfer.build_validity_constraints(&parameters.as_constant(&mut compiler), &mut compiler);
+        self.build_known_constraints(parameters, &mut compiler);
+        compiler
+    }
+
+    /// Asserts the validity constraints against the known variables of `self` in `compiler`. Use
+    /// this method instead of [`known_constraints`](Self::known_constraints) to build the
+    /// constraints in a compiler which was set up by the caller, for example in an instrumented
+    /// mode.
+    #[inline]
+    pub fn build_known_constraints(
+        &self,
+        parameters: FullParametersRef<C>,
+        compiler: &mut C::Compiler,
+    ) {
+        TransferVar::<C>::new_known(
+            self.authorization.as_ref(),
+            self.asset_id.as_ref(),
+            &self.sources,
+            &self.senders,
+            &self.receivers,
+            &self.sinks,
+            self.fee.as_ref(),
+            compiler,
+        )
+        .build_validity_constraints(&parameters.as_constant(compiler), compiler);
+    }
+
+    /// Builds a constraint system which asserts constraints against known variables, reusing the
+    /// structure of the circuit cached in `proving_context` by
+    /// [`cache_synthesis`](Self::cache_synthesis) if there is one.
+    #[inline]
+    pub fn cached_known_constraints(
+        &self,
+        parameters: FullParametersRef<C>,
+        proving_context: &ProvingContext<C>,
+    ) -> C::Compiler {
+        let mut compiler = C::ProofSystem::cached_proof_compiler(proving_context);
+        self.build_known_constraints(parameters, &mut compiler);
         compiler
     }
 
+    /// Caches the structure of the circuit of this transfer shape in `proving_context`, so that
+    /// the proofs it builds for transfers of this shape only assign their witness instead of
+    /// synthesizing the whole circuit again. Returns `false` if the proof system does not support
+    /// cached synthesis or if `proving_context` was not generated for this transfer shape.
+    #[inline]
+    pub fn cache_synthesis(
+        parameters: FullParametersRef<C>,
+        proving_context: &mut ProvingContext<C>,
+    ) -> bool {
+        C::ProofSystem::cache_synthesis(proving_context, Self::unknown_constraints(parameters))
+    }
+
     /// Generates a proving and verifying context for this transfer shape.
     #[inline]
     pub fn generate_context<R>(
@@ -702,12 +926,17 @@ where
     {
         Ok((
             TransferPostBody::build(
-                C::ProofSystem::prove(proving_context, self.known_constraints(parameters), rng)?,
+                C::ProofSystem::prove(
+                    proving_context,
+                    self.cached_known_constraints(parameters, proving_context),
+                    rng,
+                )?,
                 self.asset_id,
                 self.sources,
                 self.senders,
                 self.receivers,
                 self.sinks,
+                self.fee,
             ),
             self.authorization,
         ))
@@ -751,41 +980,59 @@ where
             self.authorization.is_some(),
             spending_key,
         ) {
-            (true, true, Some(spending_key)) => {
-                let (body, authorization) =
-                    self.into_post_body_with_authorization(parameters, proving_context, rng)?;
-                let body_with_accounts = BodyWithAccountsRef::new(&body, &sink_accounts);
-                match auth::sign(
+            (true, true, Some(_)) | (false, false, None) => {
+                let proof = C::ProofSystem::prove(
+                    proving_context,
+                    self.cached_known_constraints(parameters, proving_context),
+                    rng,
+                )?;
+                Ok(self.into_post_with_proof(
                     parameters.base,
+                    proof,
                     spending_key,
-                    authorization.expect("It is known to be `Some` from the check above."),
-                    &body_with_accounts,
+                    sink_accounts,
                     rng,
-                ) {
-                    Some(authorization_signature) => {
-                        if has_sinks(SINKS) {
-                            Ok(Some(TransferPost::new_unchecked_with_sinks(
-                                Some(authorization_signature),
-                                body,
-                                sink_accounts,
-                            )))
-                        } else {
-                            Ok(Some(TransferPost::new_unchecked(
-                                Some(authorization_signature),
-                                body,
-                            )))
-                        }
-                    }
-                    _ => Ok(None),
-                }
+                ))
             }
-            (false, false, None) => Ok(Some(TransferPost::new_unchecked(
-                None,
-                self.into_post_body(parameters, proving_context, rng)?,
-            ))),
             _ => Ok(None),
         }
     }
+
+    /// Converts `self` into its [`TransferPost`] with the validity `proof` built elsewhere, for
+    /// instance by a remote prover, signing the [`TransferPostBody`] payload. The proof is not
+    /// checked against `self`.
+    ///
+    /// Returns `None` when the authorization required by this [`Transfer`] is invalid or not
+    /// provided.
+    #[inline]
+    pub fn into_post_with_proof<R>(
+        self,
+        parameters: &Parameters<C>,
+        proof: Proof<C>,
+        spending_key: Option<&SpendingKey<C>>,
+        sink_accounts: Vec<C::AccountId>,
+        rng: &mut R,
+    ) -> Option<TransferPost<C>>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        TransferPost::sign(
+            parameters,
+            TransferPostBody::build(
+                proof,
+                self.asset_id,
+                self.sources,
+                self.senders,
+                self.receivers,
+                self.sinks,
+                self.fee,
+            ),
+            self.authorization,
+            spending_key,
+            sink_accounts,
+            rng,
+        )
+    }
 }
 
 impl<C, const SOURCES: usize, const SENDERS: usize, const RECEIVERS: usize, const SINKS: usize>
@@ -795,24 +1042,54 @@ where
 {
     #[inline]
     fn extend(&self, input: &mut ProofInput<C>) {
-        if let Some(authorization) = &self.authorization {
-            C::ProofSystem::extend(input, Field::get(authorization))
-        }
-        if let Some(asset_id) = &self.asset_id {
-            C::ProofSystem::extend(input, asset_id);
-        }
-        self.sources
-            .iter()
-            .for_each(|source| C::ProofSystem::extend(input, source));
-        self.senders
-            .iter()
-            .for_each(|sender| C::ProofSystem::extend(input, sender));
-        self.receivers
-            .iter()
-            .for_each(|receiver| C::ProofSystem::extend(input, receiver));
-        self.sinks
-            .iter()
-            .for_each(|sink| C::ProofSystem::extend(input, sink));
+        extend_input::<C>(
+            input,
+            self.authorization.as_ref(),
+            self.asset_id.as_ref(),
+            &self.sources,
+            &self.senders,
+            &self.receivers,
+            &self.sinks,
+            self.fee.as_ref(),
+        )
+    }
+}
+
+/// Extends `input` with the public input of the transfer with the given participants.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+fn extend_input<C>(
+    input: &mut ProofInput<C>,
+    authorization: Option<&Authorization<C>>,
+    asset_id: Option<&C::AssetId>,
+    sources: &[C::AssetValue],
+    senders: &[Sender<C>],
+    receivers: &[Receiver<C>],
+    sinks: &[C::AssetValue],
+    fee: Option<&C::AssetValue>,
+) where
+    C: Configuration,
+{
+    if let Some(authorization) = authorization {
+        C::ProofSystem::extend(input, Field::get(authorization))
+    }
+    if let Some(asset_id) = asset_id {
+        C::ProofSystem::extend(input, asset_id);
+    }
+    sources
+        .iter()
+        .for_each(|source| C::ProofSystem::extend(input, source));
+    senders
+        .iter()
+        .for_each(|sender| C::ProofSystem::extend(input, sender));
+    receivers
+        .iter()
+        .for_each(|receiver| C::ProofSystem::extend(input, receiver));
+    sinks
+        .iter()
+        .for_each(|sink| C::ProofSystem::extend(input, sink));
+    if let Some(fee) = fee {
+        C::ProofSystem::extend(input, fee);
     }
 }
 
@@ -850,13 +1127,8 @@ where
             SenderVar<C>: PartialEq,
             ReceiverVar<C>: PartialEq")
 )]
-struct TransferVar<
-    C,
-    const SOURCES: usize,
-    const SENDERS: usize,
-    const RECEIVERS: usize,
-    const SINKS: usize,
-> where
+struct TransferVar<C>
+where
     C: Configuration,
 {
     /// Authorization
@@ -876,21 +1148,86 @@ struct TransferVar<
 
     /// Sinks
     sinks: Vec<C::AssetValueVar>,
+
+    /// Fee
+    fee: Option<C::AssetValueVar>,
 }
 
-impl<C, const SOURCES: usize, const SENDERS: usize, const RECEIVERS: usize, const SINKS: usize>
-    TransferVar<C, SOURCES, SENDERS, RECEIVERS, SINKS>
+impl<C> TransferVar<C>
 where
     C: Configuration,
 {
-    /// Builds constraints for the [`Transfer`] validity proof.
+    /// Allocates the unknown variables of a transfer with the given `shape` in `compiler`.
+    #[inline]
+    fn new_unknown(shape: dynamic::Shape, compiler: &mut C::Compiler) -> Self {
+        Self {
+            authorization: shape
+                .requires_authorization()
+                .then(|| compiler.allocate_unknown()),
+            asset_id: shape
+                .has_visible_asset_id()
+                .then(|| compiler.allocate_unknown::<Public, _>()),
+            sources: (0..shape.sources)
+                .map(|_| compiler.allocate_unknown::<Public, _>())
+                .collect(),
+            senders: (0..shape.senders)
+                .map(|_| compiler.allocate_unknown())
+                .collect(),
+            receivers: (0..shape.receivers)
+                .map(|_| compiler.allocate_unknown())
+                .collect(),
+            sinks: (0..shape.sinks)
+                .map(|_| compiler.allocate_unknown::<Public, _>())
+                .collect(),
+            fee: shape.fee.then(|| compiler.allocate_unknown::<Public, _>()),
+        }
+    }
+
+    /// Allocates the known variables of a transfer with the given participants in `compiler`.
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    fn new_known(
+        authorization: Option<&Authorization<C>>,
+        asset_id: Option<&C::AssetId>,
+        sources: &[C::AssetValue],
+        senders: &[Sender<C>],
+        receivers: &[Receiver<C>],
+        sinks: &[C::AssetValue],
+        fee: Option<&C::AssetValue>,
+        compiler: &mut C::Compiler,
+    ) -> Self {
+        Self {
+            authorization: authorization.map(|authorization| authorization.as_known(compiler)),
+            asset_id: asset_id.map(|id| id.as_known::<Public, _>(compiler)),
+            sources: sources
+                .iter()
+                .map(|source| source.as_known::<Public, _>(compiler))
+                .collect(),
+            senders: senders
+                .iter()
+                .map(|sender| sender.as_known(compiler))
+                .collect(),
+            receivers: receivers
+                .iter()
+                .map(|receiver| receiver.as_known(compiler))
+                .collect(),
+            sinks: sinks
+                .iter()
+                .map(|sink| sink.as_known::<Public, _>(compiler))
+                .collect(),
+            fee: fee.map(|fee| fee.as_known::<Public, _>(compiler)),
+        }
+    }
+
+    /// Builds constraints for the [`Transfer`] validity proof. The fee, if any, is an output of
+    /// the transfer, so it is added to the output side of the balance equation.
     #[inline]
     fn build_validity_constraints(
         self,
         parameters: &FullParametersVar<C>,
         compiler: &mut C::Compiler,
     ) {
-        let mut secret_asset_ids = Vec::with_capacity(SENDERS + RECEIVERS);
+        let mut secret_asset_ids = Vec::with_capacity(self.senders.len() + self.receivers.len());
         let input_sum = Self::input_sum(
             parameters,
             &mut secret_asset_ids,
@@ -903,13 +1240,70 @@ where
             parameters,
             &mut secret_asset_ids,
             self.receivers,
-            self.sinks,
+            self.sinks.into_iter().chain(self.fee).collect(),
             compiler,
         );
-        compiler.assert_eq(&input_sum, &output_sum);
-        match self.asset_id {
-            Some(asset_id) => compiler.assert_all_eq_to_base(&asset_id, secret_asset_ids.iter()),
-            _ => compiler.assert_all_eq(secret_asset_ids.iter()),
+        compiler.region("balance", |compiler| {
+            compiler.assert_eq(&input_sum, &output_sum);
+            match self.asset_id {
+                Some(asset_id) => {
+                    compiler.assert_all_eq_to_base(&asset_id, secret_asset_ids.iter())
+                }
+                _ => compiler.assert_all_eq(secret_asset_ids.iter()),
+            }
+        });
+    }
+
+    /// Builds constraints for the [`MultiAssetTransfer`](canonical::MultiAssetTransfer) validity
+    /// proof. The senders and the receivers are split into `assets`-many consecutive groups of
+    /// the same size, and every group has its own balance equation and its own secret asset id.
+    /// A multi-asset transfer has no public participants, so it always has an authorization.
+    #[inline]
+    fn build_multi_asset_validity_constraints(
+        self,
+        assets: usize,
+        parameters: &FullParametersVar<C>,
+        compiler: &mut C::Compiler,
+    ) {
+        let mut authorization = self
+            .authorization
+            .expect("Multi-asset transfers always have an authorization.");
+        compiler.region("authorization", |compiler| {
+            authorization.assert_authorized(&parameters.base, compiler)
+        });
+        let senders_per_asset = self.senders.len() / assets;
+        let receivers_per_asset = self.receivers.len() / assets;
+        let mut senders = self.senders.into_iter();
+        let mut receivers = self.receivers.into_iter();
+        for _ in 0..assets {
+            let mut secret_asset_ids = Vec::with_capacity(senders_per_asset + receivers_per_asset);
+            let input_sum = Self::value_sum(
+                senders
+                    .by_ref()
+                    .take(senders_per_asset)
+                    .map(|s| {
+                        Self::sender_value(
+                            parameters,
+                            &mut secret_asset_ids,
+                            &mut authorization,
+                            s,
+                            compiler,
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+                compiler,
+            );
+            let output_sum = Self::output_sum(
+                parameters,
+                &mut secret_asset_ids,
+                receivers.by_ref().take(receivers_per_asset).collect(),
+                Vec::new(),
+                compiler,
+            );
+            compiler.region("balance", |compiler| {
+                compiler.assert_eq(&input_sum, &output_sum);
+                compiler.assert_all_eq(secret_asset_ids.iter());
+            });
         }
     }
 
@@ -924,19 +1318,20 @@ where
         compiler: &mut C::Compiler,
     ) -> C::AssetValueVar {
         if let Some(mut authorization) = authorization {
-            authorization.assert_authorized(&parameters.base, compiler);
+            compiler.region("authorization", |compiler| {
+                authorization.assert_authorized(&parameters.base, compiler)
+            });
             Self::value_sum(
                 senders
                     .into_iter()
                     .map(|s| {
-                        let asset = s.well_formed_asset(
-                            &parameters.base,
-                            &parameters.utxo_accumulator_model,
-                            &mut authorization.context,
+                        Self::sender_value(
+                            parameters,
+                            secret_asset_ids,
+                            &mut authorization,
+                            s,
                             compiler,
-                        );
-                        secret_asset_ids.push(asset.id);
-                        asset.value
+                        )
                     })
                     .chain(sources)
                     .collect::<Vec<_>>(),
@@ -947,6 +1342,28 @@ where
         }
     }
 
+    /// Asserts that `sender` is well-formed under `authorization`, pushing its asset id to
+    /// `secret_asset_ids` and returning its value.
+    #[inline]
+    fn sender_value(
+        parameters: &FullParametersVar<C>,
+        secret_asset_ids: &mut Vec<C::AssetIdVar>,
+        authorization: &mut AuthorizationVar<C>,
+        sender: SenderVar<C>,
+        compiler: &mut C::Compiler,
+    ) -> C::AssetValueVar {
+        let asset = compiler.region("sender", |compiler| {
+            sender.well_formed_asset(
+                &parameters.base,
+                &parameters.utxo_accumulator_model,
+                &mut authorization.context,
+                compiler,
+            )
+        });
+        secret_asset_ids.push(asset.id);
+        asset.value
+    }
+
     /// Computes the sum over all the output assets, asserting that they are all well-formed.
     #[inline]
     fn output_sum(
@@ -960,7 +1377,9 @@ where
             receivers
                 .into_iter()
                 .map(|r| {
-                    let asset = r.well_formed_asset(&parameters.base, compiler);
+                    let asset = compiler.region("receiver", |compiler| {
+                        r.well_formed_asset(&parameters.base, compiler)
+                    });
                     secret_asset_ids.push(asset.id);
                     asset.value
                 })
@@ -982,67 +1401,6 @@ where
     }
 }
 
-impl<C, const SOURCES: usize, const SENDERS: usize, const RECEIVERS: usize, const SINKS: usize>
-    Variable<Derived, C::Compiler> for TransferVar<C, SOURCES, SENDERS, RECEIVERS, SINKS>
-where
-    C: Configuration,
-{
-    type Type = Transfer<C, SOURCES, SENDERS, RECEIVERS, SINKS>;
-
-    #[inline]
-    fn new_unknown(compiler: &mut C::Compiler) -> Self {
-        Self {
-            authorization: requires_authorization(SENDERS).then(|| compiler.allocate_unknown()),
-            asset_id: has_public_participants(SOURCES, SINKS)
-                .then(|| compiler.allocate_unknown::<Public, _>()),
-            sources: (0..SOURCES)
-                .map(|_| compiler.allocate_unknown::<Public, _>())
-                .collect(),
-            senders: (0..SENDERS).map(|_| compiler.allocate_unknown()).collect(),
-            receivers: (0..RECEIVERS)
-                .map(|_| compiler.allocate_unknown())
-                .collect(),
-            sinks: (0..SINKS)
-                .map(|_| compiler.allocate_unknown::<Public, _>())
-                .collect(),
-        }
-    }
-
-    #[inline]
-    fn new_known(this: &Self::Type, compiler: &mut C::Compiler) -> Self {
-        Self {
-            authorization: this
-                .authorization
-                .as_ref()
-                .map(|authorization| authorization.as_known(compiler)),
-            asset_id: this
-                .asset_id
-                .as_ref()
-                .map(|id| id.as_known::<Public, _>(compiler)),
-            sources: this
-                .sources
-                .iter()
-                .map(|source| source.as_known::<Public, _>(compiler))
-                .collect(),
-            senders: this
-                .senders
-                .iter()
-                .map(|sender| sender.as_known(compiler))
-                .collect(),
-            receivers: this
-                .receivers
-                .iter()
-                .map(|receiver| receiver.as_known(compiler))
-                .collect(),
-            sinks: this
-                .sinks
-                .iter()
-                .map(|sink| sink.as_known::<Public, _>(compiler))
-                .collect(),
-        }
-    }
-}
-
 /// Transfer Ledger
 ///
 /// This is the validation trait for ensuring that a particular instance of [`Transfer`] is valid
@@ -1134,9 +1492,18 @@ where
         posting_key: TransferPostingKeyRef<C, Self>,
     ) -> Result<(Self::ValidProof, Self::Event), <Self as TransferLedger<C>>::Error>;
 
-    /// Updates the public balances in the ledger, finishing the transaction.
+    /// Updates the public balances in the ledger, which is the first step of posting the
+    /// transaction, returning the [`BalanceDelta`]s which were applied.
     ///
-    /// # Crypto Safety
+    /// # Atomicity
+    ///
+    /// Implementations must either apply all the balance changes or none of them, for instance by
+    /// undoing the changes they already applied with [`StateDiff::balance_rollback`] when a later
+    /// one is rejected. The returned deltas are given back to
+    /// [`rollback_public_balances`](Self::rollback_public_balances) if a later step of posting the
+    /// transaction fails. See [`TransferPostingKey::post`] for more.
+    ///
+    /// # Crypto Safety
     ///
     /// This method can only be called once we check that `proof` is a valid proof and that
     /// `senders` and `receivers` are valid participants in the transaction. See
@@ -1148,7 +1515,147 @@ where
         sources: Vec<SourcePostingKey<C, Self>>,
         sinks: Vec<SinkPostingKey<C, Self>>,
         proof: Self::ValidProof,
-    ) -> Result<(), <Self as TransferLedger<C>>::Error>;
+    ) -> Result<Vec<BalanceDelta<C>>, <Self as TransferLedger<C>>::Error>;
+
+    /// Credits the `fee` of the post being posted in the asset with the given `asset_id`, for
+    /// example to the author of the block which includes it, returning the [`BalanceDelta`]s which
+    /// were applied. By default, the fee is burned and no delta is returned.
+    ///
+    /// # Atomicity
+    ///
+    /// Like [`update_public_balances`](Self::update_public_balances), implementations must either
+    /// credit the whole fee or leave the balances untouched when they fail.
+    ///
+    /// # Crypto Safety
+    ///
+    /// This method can only be called once we check that `proof` is a valid proof, which binds the
+    /// `fee` as one of its public inputs. See [`is_valid`](Self::is_valid) for more. It is called
+    /// right after [`update_public_balances`](Self::update_public_balances).
+    #[inline]
+    fn credit_fee(
+        &mut self,
+        super_key: &TransferLedgerSuperPostingKey<C, Self>,
+        asset_id: &C::AssetId,
+        fee: C::AssetValue,
+        proof: Self::ValidProof,
+    ) -> Result<Vec<BalanceDelta<C>>, <Self as TransferLedger<C>>::Error> {
+        let _ = (super_key, asset_id, fee, proof);
+        Ok(Vec::new())
+    }
+
+    /// Applies the `rollback` deltas, which undo the public balance changes of
+    /// [`update_public_balances`](Self::update_public_balances) and
+    /// [`credit_fee`](Self::credit_fee) in reverse order, when a later step of posting the same
+    /// transaction fails. See [`TransferPostingKey::post`] for more.
+    ///
+    /// # Crypto Safety
+    ///
+    /// This method can only be called with the inverses of the deltas returned by the calls to
+    /// [`update_public_balances`](Self::update_public_balances) and
+    /// [`credit_fee`](Self::credit_fee) for the posting key being posted.
+    fn rollback_public_balances<I>(
+        &mut self,
+        super_key: &TransferLedgerSuperPostingKey<C, Self>,
+        rollback: I,
+    ) where
+        I: IntoIterator<Item = BalanceDelta<C>>;
+
+    /// Returns the [`NotePolicy`] which new notes must satisfy to be accepted by the ledger, if
+    /// any.
+    #[inline]
+    fn note_policy(&self) -> Option<&dyn NotePolicy<C>> {
+        None
+    }
+
+    /// Returns the [`FeeSchedule`] of the fees which the ledger charges for every post, if any.
+    #[inline]
+    fn fee_schedule(&self) -> Option<&FeeSchedule<C::AssetId, C::AssetValue>> {
+        None
+    }
+
+    /// Checks that the payer of the post being validated can pay its `fee`, which is only called
+    /// when the ledger has a [`FeeSchedule`]. The ledger charges the fee when it posts the
+    /// transfer.
+    #[inline]
+    fn check_fee(&self, fee: &C::AssetValue) -> Result<(), InsufficientFee<C::AssetValue>> {
+        let _ = fee;
+        Ok(())
+    }
+}
+
+/// Note Policy
+///
+/// Limits which a [`TransferLedger`] can impose on the notes created by a [`TransferPost`], for
+/// example to keep dust notes from inflating the UTXO accumulator. Since the values of the
+/// receivers are private, the minimum value can only be enforced when it is implied by the public
+/// participants, that is for posts without senders and sinks, where the receivers share the value
+/// of the sources.
+pub trait NotePolicy<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Returns the maximum number of receivers of a single [`TransferPost`], if any.
+    #[inline]
+    fn maximum_receivers(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns the minimum value of the notes of `asset_id`, if any.
+    #[inline]
+    fn minimum_value(&self, asset_id: &C::AssetId) -> Option<C::AssetValue> {
+        let _ = asset_id;
+        None
+    }
+}
+
+/// Note Policy Violation
+///
+/// This `enum` is the error state of the [`NotePolicy`] checks in [`TransferPost::validate`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "C::AssetId: Deserialize<'de>, C::AssetValue: Deserialize<'de>",
+            serialize = "C::AssetId: Serialize, C::AssetValue: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "C::AssetId: Clone, C::AssetValue: Clone"),
+    Copy(bound = "C::AssetId: Copy, C::AssetValue: Copy"),
+    Debug(bound = "C::AssetId: Debug, C::AssetValue: Debug"),
+    Eq(bound = "C::AssetId: Eq, C::AssetValue: Eq"),
+    Hash(bound = "C::AssetId: Hash, C::AssetValue: Hash"),
+    PartialEq(bound = "C::AssetId: PartialEq, C::AssetValue: PartialEq")
+)]
+pub enum NotePolicyViolation<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Too Many Receivers
+    TooManyReceivers {
+        /// Number of Receivers
+        receivers: usize,
+
+        /// Maximum Number of Receivers
+        maximum: usize,
+    },
+
+    /// Value Below Minimum
+    ValueBelowMinimum {
+        /// Asset Id
+        asset_id: C::AssetId,
+
+        /// Value of the New Notes
+        value: C::AssetValue,
+
+        /// Minimum Value
+        minimum: C::AssetValue,
+    },
 }
 
 /// Transfer Source Posting Key Type
@@ -1341,12 +1848,60 @@ where
     /// Validity of the transfer could not be proved by the ledger.
     InvalidProof,
 
+    /// Invalid Epoch Error
+    ///
+    /// The parameter epoch of the [`TransferPost`] is unknown to the ledger or was retired.
+    InvalidEpoch(EpochError),
+
+    /// Note Policy Violation
+    ///
+    /// The notes created by the [`TransferPost`] violate the [`NotePolicy`] of the ledger.
+    NotePolicy(NotePolicyViolation<C>),
+
+    /// Insufficient Fee Error
+    ///
+    /// The fee of the [`TransferPost`] in the [`FeeSchedule`] of the ledger cannot be paid.
+    InsufficientFee(InsufficientFee<C::AssetValue>),
+
     /// Unexpected Error
     ///
     /// An unexpected error occured.
     UnexpectedError(Error),
 }
 
+impl<C, AccountId, SenderError, ReceiverError, Error> From<EpochError>
+    for TransferPostError<C, AccountId, SenderError, ReceiverError, Error>
+where
+    C: Configuration + ?Sized,
+{
+    #[inline]
+    fn from(err: EpochError) -> Self {
+        Self::InvalidEpoch(err)
+    }
+}
+
+impl<C, AccountId, SenderError, ReceiverError, Error> From<NotePolicyViolation<C>>
+    for TransferPostError<C, AccountId, SenderError, ReceiverError, Error>
+where
+    C: Configuration + ?Sized,
+{
+    #[inline]
+    fn from(err: NotePolicyViolation<C>) -> Self {
+        Self::NotePolicy(err)
+    }
+}
+
+impl<C, AccountId, SenderError, ReceiverError, Error> From<InsufficientFee<C::AssetValue>>
+    for TransferPostError<C, AccountId, SenderError, ReceiverError, Error>
+where
+    C: Configuration + ?Sized,
+{
+    #[inline]
+    fn from(err: InsufficientFee<C::AssetValue>) -> Self {
+        Self::InsufficientFee(err)
+    }
+}
+
 impl<C, AccountId, SenderError, ReceiverError, Error> From<InvalidAuthorizationSignature>
     for TransferPostError<C, AccountId, SenderError, ReceiverError, Error>
 where
@@ -1473,16 +2028,23 @@ where
     pub asset_id: Option<C::AssetId>,
 
     /// Sources
-    pub sources: Vec<C::AssetValue>,
+    pub sources: PostParticipants<C::AssetValue>,
 
     /// Sender Posts
-    pub sender_posts: Vec<SenderPost<C>>,
+    pub sender_posts: PostParticipants<SenderPost<C>>,
 
     /// Receiver Posts
-    pub receiver_posts: Vec<ReceiverPost<C>>,
+    pub receiver_posts: PostParticipants<ReceiverPost<C>>,
 
     /// Sinks
-    pub sinks: Vec<C::AssetValue>,
+    pub sinks: PostParticipants<C::AssetValue>,
+
+    /// Fee
+    ///
+    /// Fee paid to the ledger in the asset with id [`asset_id`](Self::asset_id), which is bound
+    /// by the proof as its last public input. See [`TransferLedger::credit_fee`] for more.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fee: Option<C::AssetValue>,
 
     /// Proof
     pub proof: Proof<C>,
@@ -1494,28 +2056,30 @@ where
 {
     /// Builds a new [`TransferPostBody`].
     #[inline]
-    fn build<
-        const SOURCES: usize,
-        const SENDERS: usize,
-        const RECEIVERS: usize,
-        const SINKS: usize,
-    >(
+    fn build<SO, SE, RE, SI>(
         proof: Proof<C>,
         asset_id: Option<C::AssetId>,
-        sources: [C::AssetValue; SOURCES],
-        senders: [Sender<C>; SENDERS],
-        receivers: [Receiver<C>; RECEIVERS],
-        sinks: [C::AssetValue; SINKS],
-    ) -> Self {
+        sources: SO,
+        senders: SE,
+        receivers: RE,
+        sinks: SI,
+        fee: Option<C::AssetValue>,
+    ) -> Self
+    where
+        SO: IntoIterator<Item = C::AssetValue>,
+        SE: IntoIterator<Item = Sender<C>>,
+        RE: IntoIterator<Item = Receiver<C>>,
+        SI: IntoIterator<Item = C::AssetValue>,
+    {
         Self {
             asset_id,
-            sources: sources.into(),
-            sender_posts: senders.into_iter().map(Sender::<C>::into_post).collect(),
-            receiver_posts: receivers
-                .into_iter()
-                .map(Receiver::<C>::into_post)
-                .collect(),
-            sinks: sinks.into(),
+            sources: collect_participants(sources),
+            sender_posts: collect_participants(senders.into_iter().map(Sender::<C>::into_post)),
+            receiver_posts: collect_participants(
+                receivers.into_iter().map(Receiver::<C>::into_post),
+            ),
+            sinks: collect_participants(sinks),
+            fee,
             proof,
         }
     }
@@ -1541,8 +2105,42 @@ where
             .get(k)
             .and_then(|value| self.construct_asset(value))
     }
+
+    /// Returns the fee paid by the transfer.
+    #[inline]
+    pub fn fee_asset(&self) -> Option<Asset<C>> {
+        self.fee
+            .as_ref()
+            .and_then(|value| self.construct_asset(value))
+    }
 }
 
+/// Header of an encoded [`TransferPostBody`] without an asset id.
+///
+/// The header takes the place of the [`Option`] tag of the asset id, so the posts without a fee
+/// are encoded in the same way as before fees were introduced.
+const POST_HEADER_EMPTY: u8 = 0;
+
+/// Header of an encoded [`TransferPostBody`] with an asset id but without a fee.
+const POST_HEADER_ASSET_ID: u8 = 1;
+
+/// Header of an encoded [`TransferPostBody`] with an asset id followed by a fee.
+const POST_HEADER_FEE: u8 = 2;
+
+/// Header of an encoded [`TransferPostBody`] with a fee but without an asset id.
+///
+/// A fee is only ever paid in the asset of the post, so such a body is never valid. Its fee is
+/// still encoded so that the body is rejected by [`TransferPost::validate`] after an encoding
+/// round trip, instead of reading as a valid body without a fee.
+const POST_HEADER_FEE_WITHOUT_ASSET_ID: u8 = 3;
+
+/// Flag of the header of an encoded [`TransferPostBody`] whose receiver posts carry memos.
+///
+/// The memos are not part of the encoding of the notes, so they are encoded after the receiver
+/// posts, as one optional memo for every receiver post. Bodies without memos do not set this flag
+/// and are encoded in the same way as before memos were introduced.
+const POST_HEADER_MEMOS: u8 = 4;
+
 impl<C> Encode for TransferPostBody<C>
 where
     C: Configuration + ?Sized,
@@ -1550,6 +2148,7 @@ where
     C::AssetValue: Encode,
     SenderPost<C>: Encode,
     ReceiverPost<C>: Encode,
+    Memo<C>: Encode,
     Proof<C>: Encode,
 {
     #[inline]
@@ -1557,16 +2156,137 @@ where
     where
         W: Write,
     {
-        self.asset_id.encode(&mut writer)?;
+        let memos = if self.receiver_posts.iter().any(|post| post.memo().is_some()) {
+            POST_HEADER_MEMOS
+        } else {
+            0
+        };
+        match (&self.asset_id, &self.fee) {
+            (Some(asset_id), Some(fee)) => {
+                (POST_HEADER_FEE | memos).encode(&mut writer)?;
+                asset_id.encode(&mut writer)?;
+                fee.encode(&mut writer)?;
+            }
+            (Some(asset_id), None) => {
+                (POST_HEADER_ASSET_ID | memos).encode(&mut writer)?;
+                asset_id.encode(&mut writer)?;
+            }
+            (None, Some(fee)) => {
+                (POST_HEADER_FEE_WITHOUT_ASSET_ID | memos).encode(&mut writer)?;
+                fee.encode(&mut writer)?;
+            }
+            (None, None) => (POST_HEADER_EMPTY | memos).encode(&mut writer)?,
+        }
         self.sources.encode(&mut writer)?;
         self.sender_posts.encode(&mut writer)?;
         self.receiver_posts.encode(&mut writer)?;
+        if memos != 0 {
+            for post in &self.receiver_posts {
+                match post.memo() {
+                    Some(memo) => {
+                        1u8.encode(&mut writer)?;
+                        memo.encode(&mut writer)?;
+                    }
+                    _ => 0u8.encode(&mut writer)?,
+                }
+            }
+        }
         self.sinks.encode(&mut writer)?;
         self.proof.encode(&mut writer)?;
         Ok(())
     }
 }
 
+impl<C> Decode for TransferPostBody<C>
+where
+    C: Configuration + ?Sized,
+    C::AssetId: Decode,
+    C::AssetValue: Decode,
+    SenderPost<C>: Decode,
+    ReceiverPost<C>: Decode,
+    Memo<C>: Decode,
+    Proof<C>: Decode,
+{
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        let header = u8::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?;
+        let has_memos = header & POST_HEADER_MEMOS != 0;
+        let header = header & !POST_HEADER_MEMOS;
+        let (asset_id, fee) = match header {
+            POST_HEADER_EMPTY => (None, None),
+            POST_HEADER_ASSET_ID | POST_HEADER_FEE => {
+                let asset_id = Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?;
+                let fee = if header == POST_HEADER_FEE {
+                    Some(Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?)
+                } else {
+                    None
+                };
+                (Some(asset_id), fee)
+            }
+            POST_HEADER_FEE_WITHOUT_ASSET_ID => (
+                None,
+                Some(Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?),
+            ),
+            _ => return Err(DecodeError::Decode(())),
+        };
+        let sources = decode_participants(&mut reader)?;
+        let sender_posts = decode_participants(&mut reader)?;
+        let mut receiver_posts: PostParticipants<ReceiverPost<C>> =
+            decode_participants(&mut reader)?;
+        if has_memos {
+            let mut has_memo = false;
+            for post in &mut receiver_posts {
+                let memo =
+                    Option::<Memo<C>>::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?;
+                has_memo |= memo.is_some();
+                post.set_memo(memo);
+            }
+            if !has_memo {
+                return Err(DecodeError::Decode(()));
+            }
+        }
+        Ok(Self {
+            asset_id,
+            sources,
+            sender_posts,
+            receiver_posts,
+            sinks: decode_participants(&mut reader)?,
+            fee,
+            proof: Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+        })
+    }
+}
+
+/// Decodes at most [`MAX_POST_PARTICIPANTS`]-many elements from `reader`.
+#[inline]
+fn decode_participants<T, R>(reader: R) -> Result<PostParticipants<T>, DecodeError<R::Error, ()>>
+where
+    T: Decode,
+    R: Read,
+{
+    PostParticipants::decode(reader).map_err(|err| err.map_decode(|_| ()))
+}
+
+/// Collects the participants yielded by `iter`.
+///
+/// # Panics
+///
+/// This function panics if `iter` yields more than [`MAX_POST_PARTICIPANTS`]-many elements.
+#[inline]
+fn collect_participants<T, I>(iter: I) -> PostParticipants<T>
+where
+    I: IntoIterator<Item = T>,
+{
+    PostParticipants::try_from_iter(iter)
+        .map_err(CapacityError::forget)
+        .expect("Transfers are not allowed to have more than `MAX_POST_PARTICIPANTS` participants of each kind.")
+}
+
 impl<C> Input<C::ProofSystem> for TransferPostBody<C>
 where
     C: Configuration + ?Sized,
@@ -1588,6 +2308,9 @@ where
         self.sinks
             .iter()
             .for_each(|sink| C::ProofSystem::extend(input, sink));
+        if let Some(fee) = &self.fee {
+            C::ProofSystem::extend(input, fee);
+        }
     }
 }
 
@@ -1600,7 +2323,7 @@ where
     pub body: &'p TransferPostBody<C>,
 
     /// Sink Accounts
-    pub sink_accounts: &'p Vec<C::AccountId>,
+    pub sink_accounts: &'p [C::AccountId],
 }
 
 impl<'p, C> BodyWithAccountsRef<'p, C>
@@ -1609,7 +2332,7 @@ where
 {
     /// Builds a new [`BodyWithAccountsRef`] from `body` and `sink_accounts`.
     #[inline]
-    pub fn new(body: &'p TransferPostBody<C>, sink_accounts: &'p Vec<C::AccountId>) -> Self {
+    pub fn new(body: &'p TransferPostBody<C>, sink_accounts: &'p [C::AccountId]) -> Self {
         Self {
             body,
             sink_accounts,
@@ -1680,7 +2403,13 @@ where
     pub body: TransferPostBody<C>,
 
     /// Sink Accounts
-    pub sink_accounts: Vec<C::AccountId>,
+    pub sink_accounts: PostParticipants<C::AccountId>,
+
+    /// Parameter Epoch
+    ///
+    /// The ledger verifies this post with the verifying context and parameters of this epoch.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub epoch: Epoch,
 }
 
 impl<C> TransferPost<C>
@@ -1693,12 +2422,13 @@ where
     fn new_unchecked_with_sinks(
         authorization_signature: Option<AuthorizationSignature<C>>,
         body: TransferPostBody<C>,
-        sink_accounts: Vec<C::AccountId>,
+        sink_accounts: PostParticipants<C::AccountId>,
     ) -> Self {
         Self {
             authorization_signature,
             body,
             sink_accounts,
+            epoch: Default::default(),
         }
     }
 
@@ -1709,7 +2439,59 @@ where
         authorization_signature: Option<AuthorizationSignature<C>>,
         body: TransferPostBody<C>,
     ) -> Self {
-        Self::new_unchecked_with_sinks(authorization_signature, body, Vec::new())
+        Self::new_unchecked_with_sinks(authorization_signature, body, PostParticipants::new())
+    }
+
+    /// Builds a new [`TransferPost`] out of `body` by signing it with `spending_key` when the
+    /// shape of `body` requires an `authorization`.
+    ///
+    /// Returns `None` when the required `authorization` is invalid or not provided, or when it is
+    /// provided but not required.
+    #[inline]
+    fn sign<R>(
+        parameters: &Parameters<C>,
+        body: TransferPostBody<C>,
+        authorization: Option<Authorization<C>>,
+        spending_key: Option<&SpendingKey<C>>,
+        sink_accounts: Vec<C::AccountId>,
+        rng: &mut R,
+    ) -> Option<Self>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        match (
+            requires_authorization(body.sender_posts.len()),
+            authorization,
+            spending_key,
+        ) {
+            (true, Some(authorization), Some(spending_key)) => {
+                let authorization_signature = auth::sign(
+                    parameters,
+                    spending_key,
+                    authorization,
+                    &BodyWithAccountsRef::new(&body, &sink_accounts),
+                    rng,
+                )?;
+                if has_sinks(body.sinks.len()) {
+                    Some(Self::new_unchecked_with_sinks(
+                        Some(authorization_signature),
+                        body,
+                        collect_participants(sink_accounts),
+                    ))
+                } else {
+                    Some(Self::new_unchecked(Some(authorization_signature), body))
+                }
+            }
+            (false, None, None) => Some(Self::new_unchecked(None, body)),
+            _ => None,
+        }
+    }
+
+    /// Tags `self` with the parameter `epoch` it was built for.
+    #[inline]
+    pub fn with_epoch(mut self, epoch: Epoch) -> Self {
+        self.epoch = epoch;
+        self
     }
 
     /// Returns the `k`-th source in the transfer.
@@ -1724,6 +2506,12 @@ where
         self.body.sink(k)
     }
 
+    /// Returns the fee paid by the transfer.
+    #[inline]
+    pub fn fee_asset(&self) -> Option<Asset<C>> {
+        self.body.fee_asset()
+    }
+
     /// Generates the public input for the [`Transfer`] validation proof.
     #[inline]
     pub fn generate_proof_input(&self) -> ProofInput<C> {
@@ -1791,6 +2579,7 @@ where
     #[inline]
     fn check_public_participants<L>(
         asset_id: &Option<C::AssetId>,
+        has_fee: bool,
         source_accounts: Vec<C::AccountId>,
         source_values: Vec<C::AssetValue>,
         sink_accounts: Vec<C::AccountId>,
@@ -1802,7 +2591,7 @@ where
     {
         let sources = source_values.len();
         let sinks = sink_values.len();
-        if has_public_participants(sources, sinks) != asset_id.is_some() {
+        if (has_public_participants(sources, sinks) || has_fee) != asset_id.is_some() {
             return Err(TransferPostError::InvalidShape);
         }
         if source_accounts.len() != sources {
@@ -1830,6 +2619,36 @@ where
         Ok((sources, sinks))
     }
 
+    /// Checks that the receivers of `body` satisfy the note `policy`.
+    #[inline]
+    fn check_note_policy(
+        body: &TransferPostBody<C>,
+        policy: &dyn NotePolicy<C>,
+    ) -> Result<(), NotePolicyViolation<C>> {
+        let receivers = body.receiver_posts.len();
+        if let Some(maximum) = policy.maximum_receivers() {
+            if receivers > maximum {
+                return Err(NotePolicyViolation::TooManyReceivers { receivers, maximum });
+            }
+        }
+        if receivers == 0 || !body.sender_posts.is_empty() || !body.sinks.is_empty() {
+            return Ok(());
+        }
+        if let Some(asset_id) = &body.asset_id {
+            if let Some(minimum) = policy.minimum_value(asset_id) {
+                let value = body.sources.iter().cloned().sum::<C::AssetValue>();
+                if value < minimum {
+                    return Err(NotePolicyViolation::ValueBelowMinimum {
+                        asset_id: asset_id.clone(),
+                        value,
+                        minimum,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Validates `self` on the transfer `ledger`.
     #[allow(clippy::type_complexity)] // FIXME: Use a better abstraction for this.
     #[inline]
@@ -1844,12 +2663,48 @@ where
         L: TransferLedger<C>,
     {
         self.has_valid_authorization_signature(parameters)?;
+        self.validate_authorized(ledger, source_accounts, sink_accounts)
+    }
+
+    /// Validates `self` on the transfer `ledger` assuming that its authorization was already
+    /// checked.
+    #[allow(clippy::type_complexity)] // FIXME: Use a better abstraction for this.
+    #[inline]
+    fn validate_authorized<L>(
+        self,
+        ledger: &L,
+        source_accounts: Vec<C::AccountId>,
+        sink_accounts: Vec<C::AccountId>,
+    ) -> Result<TransferPostingKey<C, L>, TransferLedgerPostError<C, L>>
+    where
+        L: TransferLedger<C>,
+    {
+        if let Some(policy) = ledger.note_policy() {
+            Self::check_note_policy(&self.body, policy)?;
+        }
+        if let Some(schedule) = ledger.fee_schedule() {
+            let fee = schedule
+                .post_fee(&self)
+                .ok_or(TransferPostError::InvalidShape)?;
+            ledger.check_fee(&fee)?;
+        }
+        if !self.body.receiver_posts.is_empty() {
+            ledger
+                .check_registration(Registration {
+                    sources: self.body.sources.len(),
+                    senders: self.body.sender_posts.len(),
+                    receivers: self.body.receiver_posts.len(),
+                    sinks: self.body.sinks.len(),
+                })
+                .map_err(ReceiverPostError::RegistrationRejected)?;
+        }
         let (source_posting_keys, sink_posting_keys) = Self::check_public_participants(
             &self.body.asset_id,
+            self.body.fee.is_some(),
             source_accounts,
-            self.body.sources,
+            self.body.sources.into_inner(),
             sink_accounts,
-            self.body.sinks,
+            self.body.sinks.into_inner(),
             ledger,
         )?;
         if !all_unequal(&self.body.sender_posts, |p, q| {
@@ -1874,12 +2729,14 @@ where
             .collect::<Result<Vec<_>, _>>()?;
         let (proof, event) = ledger
             .is_valid(TransferPostingKeyRef {
+                epoch: self.epoch,
                 authorization_key: &self.authorization_signature.map(|s| s.authorization_key),
                 asset_id: &self.body.asset_id,
                 sources: &source_posting_keys,
                 senders: &sender_posting_keys,
                 receivers: &receiver_posting_keys,
                 sinks: &sink_posting_keys,
+                fee: &self.body.fee,
                 proof: self.body.proof,
             })
             .map_err(|x| x.into())?;
@@ -1889,6 +2746,7 @@ where
             sender_posting_keys,
             receiver_posting_keys,
             sink_posting_keys,
+            fee: self.body.fee,
             proof,
             event,
         })
@@ -1914,11 +2772,36 @@ where
     }
 }
 
+impl<C> Decode for TransferPost<C>
+where
+    C: Configuration + ?Sized,
+    AuthorizationSignature<C>: Decode,
+    TransferPostBody<C>: Decode,
+    C::AccountId: Decode,
+{
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Ok(Self {
+            authorization_signature: Decode::decode(&mut reader)
+                .map_err(|err| err.map_decode(|_| ()))?,
+            body: Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+            sink_accounts: decode_participants(&mut reader)?,
+            epoch: Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+        })
+    }
+}
+
 impl<C> Encode for TransferPost<C>
 where
     C: Configuration + ?Sized,
     AuthorizationSignature<C>: Encode,
     TransferPostBody<C>: Encode,
+    C::AccountId: Encode,
 {
     #[inline]
     fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
@@ -1927,6 +2810,8 @@ where
     {
         self.authorization_signature.encode(&mut writer)?;
         self.body.encode(&mut writer)?;
+        self.sink_accounts.encode(&mut writer)?;
+        self.epoch.encode(&mut writer)?;
         Ok(())
     }
 }
@@ -1956,6 +2841,7 @@ where
                 SenderPostingKey<C, L>: Deserialize<'de>,
                 ReceiverPostingKey<C, L>: Deserialize<'de>,
                 SinkPostingKey<C, L>: Deserialize<'de>,
+                C::AssetValue: Deserialize<'de>,
                 L::ValidProof: Deserialize<'de>,
                 L::Event: Deserialize<'de>",
             serialize = r"
@@ -1964,6 +2850,7 @@ where
                 SenderPostingKey<C, L>: Serialize,
                 ReceiverPostingKey<C, L>: Serialize,
                 SinkPostingKey<C, L>: Serialize,
+                C::AssetValue: Serialize,
                 L::ValidProof: Serialize,
                 L::Event: Serialize",
         ),
@@ -1979,6 +2866,7 @@ where
             SenderPostingKey<C, L>: Clone,
             ReceiverPostingKey<C, L>: Clone,
             SinkPostingKey<C, L>: Clone,
+            C::AssetValue: Clone,
             L::ValidProof: Clone,
             L::Event: Clone"),
     Debug(bound = r"
@@ -1987,6 +2875,7 @@ where
             SenderPostingKey<C, L>: Debug,
             ReceiverPostingKey<C, L>: Debug,
             SinkPostingKey<C, L>: Debug,
+            C::AssetValue: Debug,
             L::ValidProof: Debug,
             L::Event: Debug"),
     Default(bound = r"
@@ -1995,6 +2884,7 @@ where
             SenderPostingKey<C, L>: Default,
             ReceiverPostingKey<C, L>: Default,
             SinkPostingKey<C, L>: Default,
+            C::AssetValue: Default,
             L::ValidProof: Default,
             L::Event: Default"),
     Eq(bound = r"
@@ -2003,6 +2893,7 @@ where
             SenderPostingKey<C, L>: Eq,
             ReceiverPostingKey<C, L>: Eq,
             SinkPostingKey<C, L>: Eq,
+            C::AssetValue: Eq,
             L::ValidProof: Eq,
             L::Event: Eq"),
     Hash(bound = r"
@@ -2011,6 +2902,7 @@ where
             SenderPostingKey<C, L>: Hash,
             ReceiverPostingKey<C, L>: Hash,
             SinkPostingKey<C, L>: Hash,
+            C::AssetValue: Hash,
             L::ValidProof: Hash,
             L::Event: Hash"),
     PartialEq(bound = r"
@@ -2019,6 +2911,7 @@ where
             SenderPostingKey<C, L>: PartialEq,
             ReceiverPostingKey<C, L>: PartialEq,
             SinkPostingKey<C, L>: PartialEq,
+            C::AssetValue: PartialEq,
             L::ValidProof: PartialEq,
             L::Event: PartialEq")
 )]
@@ -2042,6 +2935,9 @@ where
     /// Sink Posting Keys
     sink_posting_keys: Vec<SinkPostingKey<C, L>>,
 
+    /// Fee
+    fee: Option<C::AssetValue>,
+
     /// Proof Posting Key
     proof: L::ValidProof,
 
@@ -2056,11 +2952,22 @@ where
 {
     /// Posts `self` to the transfer `ledger`.
     ///
+    /// # Posting Order
+    ///
+    /// The public balances are updated and the fee is credited before any sender is spent or any
+    /// receiver is registered. The public balance step is the only one which a ledger can reject
+    /// for reasons that are not checked during validation, like a frozen sink account, so a
+    /// [`ToPublic`](canonical::ToPublic) post which is rejected there leaves its nullifiers
+    /// unspent and its input notes can be spent again. This relies on
+    /// [`TransferLedger::update_public_balances`] being atomic. If any later step fails, the
+    /// balance changes which were already applied are undone with
+    /// [`TransferLedger::rollback_public_balances`] before returning the error.
+    ///
     /// # Crypto Safety
     ///
-    /// This method assumes that posting `self` to `ledger` is atomic and cannot fail. See
-    /// [`SenderLedger::spend`] and [`ReceiverLedger::register`] for more information on the
-    /// contract for this method.
+    /// This method assumes that posting the senders and receivers of `self` to `ledger` cannot
+    /// fail. See [`SenderLedger::spend`] and [`ReceiverLedger::register`] for more information on
+    /// the contract for this method.
     #[inline]
     pub fn post(
         self,
@@ -2068,22 +2975,50 @@ where
         super_key: &TransferLedgerSuperPostingKey<C, L>,
     ) -> Result<L::Event, <L as TransferLedger<C>>::Error> {
         let proof = self.proof;
-        SenderPostingKey::<C, _>::post_all(self.sender_posting_keys, ledger, &(proof, *super_key))?;
-        ReceiverPostingKey::<C, _>::post_all(
-            self.receiver_posting_keys,
-            ledger,
-            &(proof, *super_key),
-        )?;
+        let mut applied = StateDiff::<C, ()>::default();
         if let Some(asset_id) = self.asset_id {
-            ledger.update_public_balances(
+            applied.balance_deltas = ledger.update_public_balances(
                 super_key,
-                asset_id,
+                asset_id.clone(),
                 self.source_posting_keys,
                 self.sink_posting_keys,
                 proof,
             )?;
+            if let Some(fee) = self.fee {
+                match ledger.credit_fee(super_key, &asset_id, fee, proof) {
+                    Ok(deltas) => applied.balance_deltas.extend(deltas),
+                    Err(err) => {
+                        ledger.rollback_public_balances(super_key, applied.balance_rollback());
+                        return Err(err);
+                    }
+                }
+            }
+        }
+        match Self::post_participants(
+            self.sender_posting_keys,
+            self.receiver_posting_keys,
+            ledger,
+            &(proof, *super_key),
+        ) {
+            Ok(()) => Ok(self.event),
+            Err(err) => {
+                ledger.rollback_public_balances(super_key, applied.balance_rollback());
+                Err(err)
+            }
         }
-        Ok(self.event)
+    }
+
+    /// Spends the senders and registers the receivers of a transfer in `ledger`.
+    #[inline]
+    fn post_participants(
+        sender_posting_keys: Vec<SenderPostingKey<C, L>>,
+        receiver_posting_keys: Vec<ReceiverPostingKey<C, L>>,
+        ledger: &mut L,
+        super_key: &(L::ValidProof, TransferLedgerSuperPostingKey<C, L>),
+    ) -> Result<(), <L as TransferLedger<C>>::Error> {
+        SenderPostingKey::<C, _>::post_all(sender_posting_keys, ledger, super_key)?;
+        ReceiverPostingKey::<C, _>::post_all(receiver_posting_keys, ledger, super_key)?;
+        Ok(())
     }
 }
 
@@ -2097,6 +3032,7 @@ where
         SenderPostingKey<C, L>: Debug,
         ReceiverPostingKey<C, L>: Debug,
         SinkPostingKey<C, L>: Debug,
+        C::AssetValue: Debug,
         Proof<C>: Debug"),
     Eq(bound = r"
         AuthorizationKey<C>: Eq,
@@ -2105,6 +3041,7 @@ where
         SenderPostingKey<C, L>: Eq,
         ReceiverPostingKey<C, L>: Eq,
         SinkPostingKey<C, L>: Eq,
+        C::AssetValue: Eq,
         Proof<C>: Eq"),
     Hash(bound = r"
         AuthorizationKey<C>: Hash,
@@ -2113,6 +3050,7 @@ where
         SenderPostingKey<C, L>: Hash,
         ReceiverPostingKey<C, L>: Hash,
         SinkPostingKey<C, L>: Hash,
+        C::AssetValue: Hash,
         Proof<C>: Hash"),
     PartialEq(bound = r"
         AuthorizationKey<C>: PartialEq,
@@ -2121,6 +3059,7 @@ where
         SenderPostingKey<C, L>: PartialEq,
         ReceiverPostingKey<C, L>: PartialEq,
         SinkPostingKey<C, L>: PartialEq,
+        C::AssetValue: PartialEq,
         Proof<C>: PartialEq")
 )]
 pub struct TransferPostingKeyRef<'k, C, L>
@@ -2128,6 +3067,9 @@ where
     C: Configuration + ?Sized,
     L: TransferLedger<C> + ?Sized,
 {
+    /// Parameter Epoch
+    pub epoch: Epoch,
+
     /// Authorization Key
     pub authorization_key: &'k Option<AuthorizationKey<C>>,
 
@@ -2146,6 +3088,9 @@ where
     /// Sinks
     pub sinks: &'k [SinkPostingKey<C, L>],
 
+    /// Fee
+    pub fee: &'k Option<C::AssetValue>,
+
     /// Proof
     pub proof: Proof<C>,
 }
@@ -2198,6 +3143,9 @@ where
         self.sinks
             .iter()
             .for_each(|sink| C::ProofSystem::extend(input, sink.as_ref()));
+        if let Some(fee) = &self.fee {
+            C::ProofSystem::extend(input, fee);
+        }
     }
 }
 
@@ -2227,6 +3175,82 @@ pub enum IdentityVerificationError {
 
     /// Invalid Sink Account
     InvalidSinkAccount,
+
+    /// Missing Ledger Anchor
+    MissingAnchor,
+
+    /// Unbound Ledger Anchor
+    ///
+    /// The root of the ledger anchor is not the accumulator output of any sender of the proof, so
+    /// the anchor is not covered by the authorization signature.
+    UnboundAnchor,
+
+    /// Invalid Ledger Anchor Signature
+    InvalidAnchorSignature,
+
+    /// Unaccepted Ledger Anchor Root
+    ///
+    /// The root of the ledger anchor is not one of the accumulator outputs accepted by the ledger
+    /// at the height of the anchor.
+    UnacceptedAnchorRoot,
+}
+
+/// Ledger Anchor
+///
+/// Reference to a finalized checkpoint of the ledger, made of one of the roots of the UTXO
+/// accumulator of the ledger at some height and the signature of the ledger on them.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "UtxoAccumulatorOutput<C>: Deserialize<'de>",
+            serialize = "UtxoAccumulatorOutput<C>: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "UtxoAccumulatorOutput<C>: Clone"),
+    Debug(bound = "UtxoAccumulatorOutput<C>: Debug"),
+    Eq(bound = "UtxoAccumulatorOutput<C>: Eq"),
+    Hash(bound = "UtxoAccumulatorOutput<C>: Hash"),
+    PartialEq(bound = "UtxoAccumulatorOutput<C>: PartialEq")
+)]
+pub struct LedgerAnchor<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// UTXO Accumulator Root
+    pub root: UtxoAccumulatorOutput<C>,
+
+    /// Ledger Height
+    pub height: u64,
+
+    /// Checkpoint Signature
+    ///
+    /// Encoded signature of the ledger on the root and the height, in the signature scheme of the
+    /// ledger, which is checked by an [`AnchorVerifier`].
+    pub signature: Vec<u8>,
+}
+
+/// Ledger Anchor Verifier
+///
+/// Verifiers of anchored [`IdentityProof`]s implement this `trait` with the public key of the
+/// ledger and a view of its root history, for instance through a
+/// [`RootHistoryQuery`](crate::wallet::ledger::RootHistoryQuery).
+pub trait AnchorVerifier<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Returns `true` if the signature of `anchor` is a valid checkpoint signature of the ledger.
+    fn has_valid_signature(&self, anchor: &LedgerAnchor<C>) -> bool;
+
+    /// Returns `true` if `root` is one of the roots of the UTXO accumulator accepted by the ledger
+    /// at `height`.
+    fn is_accepted_root(&self, root: &UtxoAccumulatorOutput<C>, height: u64) -> bool;
 }
 
 /// Identity Proof
@@ -2241,8 +3265,8 @@ pub enum IdentityVerificationError {
     derive(Deserialize, Serialize),
     serde(
         bound(
-            deserialize = "TransferPost<C>: Deserialize<'de>, UtxoMembershipProof<C>: Deserialize<'de>",
-            serialize = "TransferPost<C>: Serialize, UtxoMembershipProof<C>: Serialize",
+            deserialize = "TransferPost<C>: Deserialize<'de>, LedgerAnchor<C>: Deserialize<'de>",
+            serialize = "TransferPost<C>: Serialize, LedgerAnchor<C>: Serialize",
         ),
         crate = "manta_util::serde",
         deny_unknown_fields
@@ -2250,12 +3274,11 @@ pub enum IdentityVerificationError {
 )]
 #[derive(derivative::Derivative)]
 #[derivative(
-    Clone(bound = "TransferPost<C>: Clone, UtxoMembershipProof<C>: Clone"),
-    Copy(bound = "TransferPost<C>: Copy, UtxoMembershipProof<C>: Copy"),
-    Debug(bound = "TransferPost<C>: Debug, UtxoMembershipProof<C>: Debug"),
-    Eq(bound = "TransferPost<C>: Eq, UtxoMembershipProof<C>: Eq"),
-    Hash(bound = "TransferPost<C>: Hash, UtxoMembershipProof<C>: Hash"),
-    PartialEq(bound = "TransferPost<C>: PartialEq, UtxoMembershipProof<C>: PartialEq")
+    Clone(bound = "TransferPost<C>: Clone, LedgerAnchor<C>: Clone"),
+    Debug(bound = "TransferPost<C>: Debug, LedgerAnchor<C>: Debug"),
+    Eq(bound = "TransferPost<C>: Eq, LedgerAnchor<C>: Eq"),
+    Hash(bound = "TransferPost<C>: Hash, LedgerAnchor<C>: Hash"),
+    PartialEq(bound = "TransferPost<C>: PartialEq, LedgerAnchor<C>: PartialEq")
 )]
 pub struct IdentityProof<C>
 where
@@ -2263,6 +3286,14 @@ where
 {
     /// TransferPost
     pub transfer_post: TransferPost<C>,
+
+    /// Ledger Anchor
+    ///
+    /// Anchored proofs bind the root of the anchor into [`transfer_post`](Self::transfer_post)
+    /// as the accumulator output of a zero-value sender, so that they can only be verified
+    /// against a root which the ledger accepted. See
+    /// [`anchored_identity_verification`](Self::anchored_identity_verification) for more.
+    pub anchor: Option<LedgerAnchor<C>>,
 }
 
 impl<C> IdentityProof<C>
@@ -2315,11 +3346,7 @@ where
                 _ => Err(IdentityVerificationError::InvalidShape),
             },
         )?;
-        if !self
-            .transfer_post
-            .sink_accounts
-            .eq(&Vec::from([public_account]))
-        {
+        if self.transfer_post.sink_accounts[..] != [public_account] {
             return Err(IdentityVerificationError::InvalidSinkAccount);
         }
         self.transfer_post
@@ -2358,4 +3385,284 @@ where
         }
         Ok(())
     }
+
+    /// Verifies `self` for `address` against `virtual_asset` and `public_account` like
+    /// [`identity_verification`](Self::identity_verification), and checks the ledger anchor of
+    /// `self` with `anchor_verifier`.
+    ///
+    /// # Note
+    ///
+    /// On top of the checks of [`identity_verification`](Self::identity_verification), it checks
+    /// that:
+    ///
+    /// 1) `self` has a ledger anchor, whose root is the [`UtxoAccumulatorOutput`] of one of the
+    ///    senders of `identity_proof.transfer_post`, so that it is covered by the authorization
+    ///    signature.
+    ///
+    /// 2) The anchor has a valid checkpoint signature of the ledger.
+    ///
+    /// 3) The root of the anchor was accepted by the ledger at the height of the anchor.
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    pub fn anchored_identity_verification<A, V>(
+        &self,
+        parameters: &Parameters<C>,
+        verifying_context: &VerifyingContext<C>,
+        utxo_accumulator_model: &UtxoAccumulatorModel<C>,
+        virtual_asset: IdentifiedAsset<C>,
+        address: Address<C>,
+        public_account: C::AccountId,
+        anchor_verifier: &V,
+    ) -> Result<(), IdentityVerificationError>
+    where
+        C::UtxoAccumulatorOutput: PartialEq,
+        C::AccountId: PartialEq,
+        UtxoAccumulatorModel<C>: Model<Verification = bool>,
+        A: Accumulator<
+            Item = UtxoAccumulatorItem<C>,
+            Model = UtxoAccumulatorModel<C>,
+            Output = UtxoAccumulatorOutput<C>,
+        >,
+        Asset<C>: Default,
+        V: AnchorVerifier<C>,
+    {
+        self.identity_verification::<A>(
+            parameters,
+            verifying_context,
+            utxo_accumulator_model,
+            virtual_asset,
+            address,
+            public_account,
+        )?;
+        let anchor = self
+            .anchor
+            .as_ref()
+            .ok_or(IdentityVerificationError::MissingAnchor)?;
+        if !self
+            .transfer_post
+            .body
+            .sender_posts
+            .iter()
+            .any(|sender_post| sender_post.utxo_accumulator_output == anchor.root)
+        {
+            return Err(IdentityVerificationError::UnboundAnchor);
+        }
+        if !anchor_verifier.has_valid_signature(anchor) {
+            return Err(IdentityVerificationError::InvalidAnchorSignature);
+        }
+        if !anchor_verifier.is_accepted_root(&anchor.root, anchor.height) {
+            return Err(IdentityVerificationError::UnacceptedAnchorRoot);
+        }
+        Ok(())
+    }
+}
+
+/// Transaction Id Function
+///
+/// The id of a transaction is derived from the [`TransferPost`]s which are submitted together to
+/// the ledger, so the sender knows it as soon as the transaction is signed.
+pub trait TransactionIdFunction<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Transaction Id Type
+    type TransactionId: Clone;
+
+    /// Returns the id of the transaction made of `posts`.
+    fn transaction_id(posts: &[TransferPost<C>]) -> Self::TransactionId;
+}
+
+/// Transaction Id Type
+pub type TransactionId<C> =
+    <<C as Configuration>::TransactionIdFunction as TransactionIdFunction<C>>::TransactionId;
+
+/// Payment Opening
+///
+/// Opening of the [`Utxo`] registered for the recipient of a payment, which is turned into a
+/// [`PaymentReceipt`] once the [`TransferPost`]s of the payment are built.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Address<C>: Clone, IdentifiedAsset<C>: Clone, Utxo<C>: Clone"),
+    Debug(bound = "Address<C>: Debug, IdentifiedAsset<C>: Debug, Utxo<C>: Debug")
+)]
+pub struct PaymentOpening<C>
+where
+    C: Configuration,
+{
+    /// Recipient Address
+    pub address: Address<C>,
+
+    /// Paid Asset and its Opening Randomness
+    pub identified_asset: IdentifiedAsset<C>,
+
+    /// Unspent Transaction Output
+    pub utxo: Utxo<C>,
+}
+
+impl<C> PaymentOpening<C>
+where
+    C: Configuration,
+{
+    /// Builds a new [`PaymentOpening`] from `address`, `identified_asset`, and `utxo`.
+    #[inline]
+    pub fn new(address: Address<C>, identified_asset: IdentifiedAsset<C>, utxo: Utxo<C>) -> Self {
+        Self {
+            address,
+            identified_asset,
+            utxo,
+        }
+    }
+
+    /// Builds the [`PaymentReceipt`] of `self` for the transaction with the given
+    /// `transaction_id`, committing to the recipient address with fresh randomness.
+    #[inline]
+    pub fn into_receipt<R>(
+        self,
+        parameters: &Parameters<C>,
+        transaction_id: TransactionId<C>,
+        rng: &mut R,
+    ) -> PaymentReceipt<C>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let address_opening = Identifier::<C>::gen(rng);
+        let address_commitment = PaymentReceipt::<C>::commit_address(
+            parameters,
+            &self.identified_asset.asset.id,
+            &address_opening,
+            &self.address,
+        );
+        PaymentReceipt {
+            transaction_id,
+            address_commitment,
+            address_opening,
+            identified_asset: self.identified_asset,
+            utxo: self.utxo,
+        }
+    }
+}
+
+/// Payment Receipt
+///
+/// A [`PaymentReceipt`] is emitted by the sender when building a [`TransferPost`] which pays an
+/// [`Address`]. It stores the opening of the [`Utxo`] registered for the recipient, so the sender
+/// can later prove the payment to the recipient, or to a third party, without re-deriving the
+/// circuit witnesses. The recipient [`Address`] itself is only stored as a hiding commitment, so
+/// a leaked receipt does not reveal who was paid.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "TransactionId<C>: Deserialize<'de>, Identifier<C>: Deserialize<'de>, IdentifiedAsset<C>: Deserialize<'de>, Utxo<C>: Deserialize<'de>",
+            serialize = "TransactionId<C>: Serialize, Identifier<C>: Serialize, IdentifiedAsset<C>: Serialize, Utxo<C>: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(
+        bound = "TransactionId<C>: Clone, Identifier<C>: Clone, IdentifiedAsset<C>: Clone, Utxo<C>: Clone"
+    ),
+    Copy(
+        bound = "TransactionId<C>: Copy, Identifier<C>: Copy, IdentifiedAsset<C>: Copy, Utxo<C>: Copy"
+    ),
+    Debug(
+        bound = "TransactionId<C>: Debug, Identifier<C>: Debug, IdentifiedAsset<C>: Debug, Utxo<C>: Debug"
+    ),
+    Eq(bound = "TransactionId<C>: Eq, Identifier<C>: Eq, IdentifiedAsset<C>: Eq, Utxo<C>: Eq"),
+    Hash(
+        bound = "TransactionId<C>: Hash, Identifier<C>: Hash, IdentifiedAsset<C>: Hash, Utxo<C>: Hash"
+    ),
+    PartialEq(
+        bound = "TransactionId<C>: PartialEq, Identifier<C>: PartialEq, IdentifiedAsset<C>: PartialEq, Utxo<C>: PartialEq"
+    )
+)]
+pub struct PaymentReceipt<C>
+where
+    C: Configuration,
+{
+    /// Transaction Id
+    ///
+    /// Id of the transaction whose posts register [`utxo`](Self::utxo) on the ledger.
+    pub transaction_id: TransactionId<C>,
+
+    /// Recipient Address Commitment
+    ///
+    /// This is the [`Utxo`] of the zero asset owned by the recipient under
+    /// [`address_opening`](Self::address_opening), which is never posted to the ledger.
+    pub address_commitment: Utxo<C>,
+
+    /// Recipient Address Commitment Opening Randomness
+    pub address_opening: Identifier<C>,
+
+    /// Paid Asset and its Opening Randomness
+    pub identified_asset: IdentifiedAsset<C>,
+
+    /// Unspent Transaction Output
+    ///
+    /// This is the UTXO registered on the ledger for the recipient, which identifies the payment
+    /// on-chain.
+    pub utxo: Utxo<C>,
+}
+
+impl<C> PaymentReceipt<C>
+where
+    C: Configuration,
+{
+    /// Commits to `address` with the `opening` randomness, as the [`Utxo`] of the zero asset with
+    /// the given `asset_id` owned by `address`.
+    #[inline]
+    fn commit_address(
+        parameters: &Parameters<C>,
+        asset_id: &C::AssetId,
+        opening: &Identifier<C>,
+        address: &Address<C>,
+    ) -> Utxo<C> {
+        parameters.utxo_reconstruct(&Asset::<C>::zero(asset_id.clone()), opening, address)
+    }
+
+    /// Returns `true` if [`self.address_commitment`](Self::address_commitment) is a commitment to
+    /// `address`.
+    #[inline]
+    pub fn is_addressed_to(&self, parameters: &Parameters<C>, address: &Address<C>) -> bool
+    where
+        Utxo<C>: PartialEq,
+    {
+        Self::commit_address(
+            parameters,
+            &self.identified_asset.asset.id,
+            &self.address_opening,
+            address,
+        ) == self.address_commitment
+    }
+
+    /// Returns `true` if `self` is addressed to `address` and [`self.utxo`](Self::utxo) is the
+    /// commitment to [`self.identified_asset`](Self::identified_asset) owned by `address`.
+    #[inline]
+    pub fn verify(&self, parameters: &Parameters<C>, address: &Address<C>) -> bool
+    where
+        Utxo<C>: PartialEq,
+    {
+        self.is_addressed_to(parameters, address)
+            && parameters.utxo_reconstruct(
+                &self.identified_asset.asset,
+                &self.identified_asset.identifier,
+                address,
+            ) == self.utxo
+    }
+
+    /// Returns `true` if `post` registers [`self.utxo`](Self::utxo) on the ledger.
+    #[inline]
+    pub fn is_paid_by(&self, post: &TransferPost<C>) -> bool
+    where
+        Utxo<C>: PartialEq,
+    {
+        post.body
+            .receiver_posts
+            .iter()
+            .any(|receiver_post| receiver_post.utxo == self.utxo)
+    }
 }
@@ -0,0 +1,132 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Address Ownership Proofs
+//!
+//! Before sending funds to a withdrawal address, an exchange can check that its user controls the
+//! address with a challenge-response protocol: the exchange samples a fresh
+//! [`OwnershipChallenge`], and the signer of the user answers it with an [`OwnershipProof`], which
+//! the exchange verifies against the address. In the current UTXO protocol, the proof is a Schnorr
+//! signature of the challenge under the receiving key of the address, so it is much cheaper to
+//! generate and to verify than an [`IdentityProof`](super::IdentityProof), which needs a SNARK.
+//!
+//! # Limitations
+//!
+//! An ownership proof shows knowledge of the decryption key behind the address, which is what is
+//! needed to find and open the notes sent to it. The decryption key is derived from the spending
+//! key, but does not reveal it, so an ownership proof does not show that its prover can spend
+//! these notes. Verifiers which need that guarantee should request an
+//! [`IdentityProof`](super::IdentityProof) instead.
+//!
+//! Proofs are not bound to the verifier, so verifiers have to sample a new challenge for every
+//! request and never accept an answer to a challenge twice.
+
+use crate::transfer::{
+    utxo::{self, ProveOwnership},
+    Address, Configuration, Parameters,
+};
+use core::{fmt::Debug, hash::Hash};
+use manta_crypto::rand::{RngCore, Sample};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Ownership Challenge
+///
+/// Random bytes sampled by the verifier of an [`OwnershipProof`] for every request.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct OwnershipChallenge(pub [u8; 32]);
+
+impl Sample for OwnershipChallenge {
+    #[inline]
+    fn sample<R>(_: (), rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        let mut challenge = [0; 32];
+        rng.fill_bytes(&mut challenge);
+        Self(challenge)
+    }
+}
+
+/// Ownership Proof
+///
+/// Answer to an [`OwnershipChallenge`] which proves that its prover controls an [`Address`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "utxo::OwnershipProof<Parameters<C>>: Deserialize<'de>",
+            serialize = "utxo::OwnershipProof<Parameters<C>>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "utxo::OwnershipProof<Parameters<C>>: Clone"),
+    Copy(bound = "utxo::OwnershipProof<Parameters<C>>: Copy"),
+    Debug(bound = "utxo::OwnershipProof<Parameters<C>>: Debug"),
+    Eq(bound = "utxo::OwnershipProof<Parameters<C>>: Eq"),
+    Hash(bound = "utxo::OwnershipProof<Parameters<C>>: Hash"),
+    PartialEq(bound = "utxo::OwnershipProof<Parameters<C>>: PartialEq")
+)]
+pub struct OwnershipProof<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Proof
+    pub proof: utxo::OwnershipProof<Parameters<C>>,
+}
+
+impl<C> OwnershipProof<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Answers `challenge` for the address derived from `decryption_key`.
+    #[inline]
+    pub fn prove<R>(
+        parameters: &Parameters<C>,
+        decryption_key: &C::DecryptionKey,
+        challenge: &OwnershipChallenge,
+        rng: &mut R,
+    ) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        Self {
+            proof: parameters.prove_ownership(decryption_key, &challenge.0, rng),
+        }
+    }
+
+    /// Verifies that `self` answers `challenge` for `address`.
+    #[inline]
+    pub fn verify(
+        &self,
+        parameters: &Parameters<C>,
+        address: &Address<C>,
+        challenge: &OwnershipChallenge,
+    ) -> bool {
+        parameters.verify_ownership(address, &challenge.0, &self.proof)
+    }
+}
@@ -16,18 +16,20 @@
 
 //! Transfer Receiver
 
-use crate::transfer::utxo::{DeriveMint, Identifier, Mint, Note, QueryIdentifier};
+use crate::transfer::utxo::{
+    DeriveMint, Identifier, Memo, MemoPlaintext, Mint, Note, NoteMemo, QueryIdentifier,
+};
 use core::{fmt::Debug, hash::Hash, iter};
 use manta_crypto::{
     accumulator::{Accumulator, ItemHashFunction},
-    constraint::{HasInput, Input},
     eclair::alloc::{
         mode::{Derived, Public, Secret},
         Allocate, Allocator, Constant, Var, Variable,
     },
+    impl_input,
     rand::RngCore,
 };
-use manta_util::codec::{Encode, Write};
+use manta_util::codec::{Decode, DecodeError, Encode, Read, Write};
 
 #[cfg(feature = "serde")]
 use manta_util::serde::{Deserialize, Serialize};
@@ -129,6 +131,23 @@ where
         self.secret.query_identifier(&self.utxo)
     }
 
+    /// Encrypts `memo` to `address` and attaches it to the note of `self`, replacing its memo.
+    /// The memo can only be decrypted by the owner of `address`, so it should be the address
+    /// `self` was sampled for.
+    #[inline]
+    pub fn attach_memo<R>(
+        &mut self,
+        parameters: &M,
+        address: &M::Address,
+        memo: &MemoPlaintext<M>,
+        rng: &mut R,
+    ) where
+        M: NoteMemo,
+        R: RngCore + ?Sized,
+    {
+        parameters.attach_memo(&mut self.note, address, memo, rng)
+    }
+
     /// Extracts the ledger posting data from `self`.
     #[inline]
     pub fn into_post(self) -> ReceiverPost<M> {
@@ -136,15 +155,12 @@ where
     }
 }
 
-impl<M, P> Input<P> for Receiver<M>
-where
-    M: Mint,
-    P: HasInput<M::Utxo> + HasInput<M::Note> + ?Sized,
-{
-    #[inline]
-    fn extend(&self, input: &mut P::Input) {
-        P::extend(input, &self.utxo);
-        P::extend(input, &self.note);
+impl_input! {
+    impl<M> Input<P> for Receiver<M>
+    where [M: Mint]
+    |receiver| {
+        M::Utxo => &receiver.utxo,
+        M::Note => &receiver.note,
     }
 }
 
@@ -178,6 +194,62 @@ where
     }
 }
 
+/// Note Registration
+///
+/// Shape of a post which asks a [`ReceiverLedger`] to register new notes, as given to
+/// [`ReceiverLedger::check_registration`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Registration {
+    /// Number of Sources
+    pub sources: usize,
+
+    /// Number of Senders
+    pub senders: usize,
+
+    /// Number of Receivers
+    ///
+    /// This is the number of notes the post registers.
+    pub receivers: usize,
+
+    /// Number of Sinks
+    pub sinks: usize,
+}
+
+/// Registration Error
+///
+/// This `enum` is the error state of [`ReceiverLedger::check_registration`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RegistrationError {
+    /// Rate Limited
+    ///
+    /// The registering party can only register `remaining`-many more notes for now.
+    RateLimited {
+        /// Number of Notes to Register
+        requested: usize,
+
+        /// Number of Notes the Party can still Register
+        remaining: usize,
+    },
+
+    /// Insufficient Deposit
+    ///
+    /// The registering party has not put down the deposit required to register the notes.
+    InsufficientDeposit {
+        /// Number of Notes to Register
+        requested: usize,
+    },
+}
+
 /// Receiver Ledger
 ///
 /// This is the validation trait for ensuring that a particular instance of [`Receiver`] is valid
@@ -214,6 +286,22 @@ where
     /// Existence of such a UTXO could indicate a possible double-spend.
     fn is_not_registered(&self, utxo: M::Utxo) -> Result<Self::ValidUtxo, Self::Error>;
 
+    /// Checks that the party submitting a post with the given `registration` shape is allowed to
+    /// register its new notes.
+    ///
+    /// # Implementation Note
+    ///
+    /// This hook lets ledgers throttle mass note creation, which would otherwise bloat the UTXO
+    /// accumulator at little cost, for example by rate limiting each party or by requiring a
+    /// deposit per note. The registering party is not part of the post, so the ledger identifies
+    /// it from the origin of the transaction it is validating. By default, every registration is
+    /// allowed.
+    #[inline]
+    fn check_registration(&self, registration: Registration) -> Result<(), RegistrationError> {
+        let _ = registration;
+        Ok(())
+    }
+
     /// Posts the `utxo` and `note` to the ledger, registering the asset.
     ///
     /// # Crypto Safety
@@ -293,6 +381,11 @@ pub enum ReceiverPostError<Error> {
     /// The asset has already been registered with the ledger.
     AssetRegistered,
 
+    /// Registration Rejected Error
+    ///
+    /// The ledger refused to register the new notes. See [`ReceiverLedger::check_registration`].
+    RegistrationRejected(RegistrationError),
+
     /// Unexpected Error
     UnexpectedError(Error),
 }
@@ -304,8 +397,13 @@ pub enum ReceiverPostError<Error> {
 /// required to verify a [`Transfer`] is stored in the [`TransferPost`] which includes the [`Proof`]
 /// of validity.
 ///
+/// The note of a receiver post can carry a [`memo`](Self::memo) for the receiver. Memos are not
+/// part of the [`Encode`] representation of receiver posts, but of the [`TransferPostBody`] which
+/// holds them, so that they are covered by its authorization signature.
+///
 /// [`Transfer`]: crate::transfer::Transfer
 /// [`TransferPost`]: crate::transfer::TransferPost
+/// [`TransferPostBody`]: crate::transfer::TransferPostBody
 /// [`Proof`]: crate::transfer::Proof
 #[cfg_attr(
     feature = "serde",
@@ -363,6 +461,44 @@ where
             note: self.note,
         })
     }
+
+    /// Returns the memo attached to the note of `self`, if any.
+    #[inline]
+    pub fn memo(&self) -> Option<&Memo<M>>
+    where
+        M: NoteMemo,
+    {
+        M::memo(&self.note)
+    }
+
+    /// Replaces the memo attached to the note of `self` with `memo`.
+    #[inline]
+    pub fn set_memo(&mut self, memo: Option<Memo<M>>)
+    where
+        M: NoteMemo,
+    {
+        M::set_memo(&mut self.note, memo)
+    }
+}
+
+impl<M> Decode for ReceiverPost<M>
+where
+    M: Mint,
+    M::Utxo: Decode,
+    M::Note: Decode,
+{
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Ok(Self::new(
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+        ))
+    }
 }
 
 impl<M> Encode for ReceiverPost<M>
@@ -382,15 +518,12 @@ where
     }
 }
 
-impl<M, P> Input<P> for ReceiverPost<M>
-where
-    M: Mint,
-    P: HasInput<M::Utxo> + HasInput<M::Note> + ?Sized,
-{
-    #[inline]
-    fn extend(&self, input: &mut P::Input) {
-        P::extend(input, &self.utxo);
-        P::extend(input, &self.note);
+impl_input! {
+    impl<M> Input<P> for ReceiverPost<M>
+    where [M: Mint]
+    |post| {
+        M::Utxo => &post.utxo,
+        M::Note => &post.note,
     }
 }
 
@@ -452,17 +585,26 @@ where
     {
         ledger.register_all(super_key, iter.into_iter().map(move |k| (k.utxo, k.note)))
     }
-}
 
-impl<M, L, P> Input<P> for ReceiverPostingKey<M, L>
-where
-    M: Mint,
-    L: ReceiverLedger<M> + ?Sized,
-    P: HasInput<M::Utxo> + HasInput<M::Note> + ?Sized,
-{
+    /// Converts `self` into a posting key for the ledger `N`, which accepts the same valid posting
+    /// keys as `L`, like a ledger which decorates `L`.
     #[inline]
-    fn extend(&self, input: &mut P::Input) {
-        P::extend(input, self.utxo.as_ref());
-        P::extend(input, &self.note);
+    pub fn into_ledger<N>(self) -> ReceiverPostingKey<M, N>
+    where
+        N: ReceiverLedger<M, ValidUtxo = L::ValidUtxo> + ?Sized,
+    {
+        ReceiverPostingKey {
+            utxo: self.utxo,
+            note: self.note,
+        }
+    }
+}
+
+impl_input! {
+    impl<M, L> Input<P> for ReceiverPostingKey<M, L>
+    where [M: Mint, L: ReceiverLedger<M> + ?Sized]
+    |key| {
+        M::Utxo => key.utxo.as_ref(),
+        M::Note => &key.note,
     }
 }
@@ -22,14 +22,14 @@ use crate::transfer::utxo::{
 use core::{fmt::Debug, hash::Hash, iter};
 use manta_crypto::{
     accumulator::{self, Accumulator, ItemHashFunction},
-    constraint::{HasInput, Input},
     eclair::alloc::{
         mode::{Derived, Public, Secret},
         Allocate, Allocator, Const, Constant, Var, Variable,
     },
+    impl_input,
     rand::RngCore,
 };
-use manta_util::codec::{Encode, Write};
+use manta_util::codec::{Decode, DecodeError, Encode, Read, Write};
 
 #[cfg(feature = "serde")]
 use manta_util::serde::{Deserialize, Serialize};
@@ -384,15 +384,12 @@ where
     }
 }
 
-impl<S, P> Input<P> for Sender<S>
-where
-    S: Spend,
-    P: HasInput<UtxoAccumulatorOutput<S>> + HasInput<S::Nullifier> + ?Sized,
-{
-    #[inline]
-    fn extend(&self, input: &mut P::Input) {
-        P::extend(input, self.utxo_membership_proof.output());
-        P::extend(input, &self.nullifier);
+impl_input! {
+    impl<S> Input<P> for Sender<S>
+    where [S: Spend]
+    |sender| {
+        UtxoAccumulatorOutput<S> => sender.utxo_membership_proof.output(),
+        S::Nullifier => &sender.nullifier,
     }
 }
 
@@ -590,6 +587,12 @@ pub enum SenderPostError<Error> {
     /// The asset has already been spent.
     AssetSpent,
 
+    /// Legacy Nullifier Error
+    ///
+    /// The nullifier belongs to a note minted before a nullifier migration, which the ledger no
+    /// longer accepts.
+    LegacyNullifier,
+
     /// Unexpected Error
     UnexpectedError(Error),
 }
@@ -671,6 +674,26 @@ where
     }
 }
 
+impl<S> Decode for SenderPost<S>
+where
+    S: Spend,
+    UtxoAccumulatorOutput<S>: Decode,
+    S::Nullifier: Decode,
+{
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Ok(Self::new(
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+        ))
+    }
+}
+
 impl<S> Encode for SenderPost<S>
 where
     S: Spend,
@@ -688,15 +711,12 @@ where
     }
 }
 
-impl<S, P> Input<P> for SenderPost<S>
-where
-    S: Spend,
-    P: HasInput<UtxoAccumulatorOutput<S>> + HasInput<S::Nullifier> + ?Sized,
-{
-    #[inline]
-    fn extend(&self, input: &mut P::Input) {
-        P::extend(input, &self.utxo_accumulator_output);
-        P::extend(input, &self.nullifier);
+impl_input! {
+    impl<S> Input<P> for SenderPost<S>
+    where [S: Spend]
+    |post| {
+        UtxoAccumulatorOutput<S> => &post.utxo_accumulator_output,
+        S::Nullifier => &post.nullifier,
     }
 }
 
@@ -766,17 +786,30 @@ where
                 .map(move |k| (k.utxo_accumulator_output, k.nullifier)),
         )
     }
-}
 
-impl<S, L, P> Input<P> for SenderPostingKey<S, L>
-where
-    S: Spend,
-    L: SenderLedger<S> + ?Sized,
-    P: HasInput<UtxoAccumulatorOutput<S>> + HasInput<S::Nullifier> + ?Sized,
-{
+    /// Converts `self` into a posting key for the ledger `M`, which accepts the same valid posting
+    /// keys as `L`, like a ledger which decorates `L`.
     #[inline]
-    fn extend(&self, input: &mut P::Input) {
-        P::extend(input, self.utxo_accumulator_output.as_ref());
-        P::extend(input, self.nullifier.as_ref());
+    pub fn into_ledger<M>(self) -> SenderPostingKey<S, M>
+    where
+        M: SenderLedger<
+                S,
+                ValidUtxoAccumulatorOutput = L::ValidUtxoAccumulatorOutput,
+                ValidNullifier = L::ValidNullifier,
+            > + ?Sized,
+    {
+        SenderPostingKey {
+            utxo_accumulator_output: self.utxo_accumulator_output,
+            nullifier: self.nullifier,
+        }
+    }
+}
+
+impl_input! {
+    impl<S, L> Input<P> for SenderPostingKey<S, L>
+    where [S: Spend, L: SenderLedger<S> + ?Sized]
+    |key| {
+        UtxoAccumulatorOutput<S> => key.utxo_accumulator_output.as_ref(),
+        S::Nullifier => key.nullifier.as_ref(),
     }
 }
@@ -17,11 +17,12 @@
 //! Transfer Protocol Testing Framework
 
 use crate::transfer::{
-    canonical::ToPrivate, has_public_participants, requires_authorization, Address, Asset,
-    AssociatedData, Authorization, AuthorizationContext, Configuration, FullParametersRef,
-    Parameters, PreSender, ProofInput, ProofSystemError, ProofSystemPublicParameters,
-    ProvingContext, Receiver, Sender, SpendingKey, Transfer, TransferPost, UtxoAccumulatorItem,
-    UtxoAccumulatorModel, VerifyingContext,
+    canonical::ToPrivate,
+    dynamic::{DynamicTransfer, Shape, ShapeError},
+    has_public_participants, requires_authorization, Address, Asset, AssociatedData, Authorization,
+    AuthorizationContext, Configuration, FullParametersRef, Parameters, PreSender, ProofInput,
+    ProofSystemError, ProofSystemPublicParameters, ProvingContext, Receiver, Sender, SpendingKey,
+    Transfer, TransferPost, UtxoAccumulatorItem, UtxoAccumulatorModel, VerifyingContext,
 };
 use alloc::vec::Vec;
 use core::{
@@ -503,6 +504,61 @@ where
     }
 }
 
+impl<C> DynamicTransfer<C>
+where
+    C: Configuration,
+    C::AssetId: Sample,
+    C::AssetValue: Default + Ord + Sample,
+    for<'v> &'v C::AssetValue: Rem<Output = C::AssetValue> + Sub<Output = C::AssetValue>,
+    Address<C>: Sample,
+    AssociatedData<C>: Sample,
+{
+    /// Samples a new [`DynamicTransfer`] with the given `shape` from `distribution`, which must
+    /// have an authorization exactly when `shape` requires one. When `shape` pays a fee, the fee
+    /// is sampled as one more output of the transfer.
+    #[inline]
+    pub fn sample_with_shape<A, R>(
+        shape: Shape,
+        mut distribution: TransferDistribution<'_, C, A>,
+        rng: &mut R,
+    ) -> Result<Self, ShapeError>
+    where
+        A: Accumulator<Item = UtxoAccumulatorItem<C>, Model = UtxoAccumulatorModel<C>>,
+        R: RngCore + ?Sized,
+    {
+        shape.check(shape.requires_authorization(), shape.has_visible_asset_id())?;
+        let authorization_context = distribution.authorization.as_mut().map(|k| &mut k.context);
+        let asset = Asset::<C>::gen(rng);
+        let mut input = value_distribution(shape.sources + shape.senders, asset.value.clone(), rng);
+        let mut output = value_distribution(
+            shape.receivers + shape.sinks + usize::from(shape.fee),
+            asset.value,
+            rng,
+        );
+        let fee = if shape.fee { output.pop() } else { None };
+        let secret_input = input.split_off(shape.sources);
+        let public_output = output.split_off(shape.receivers);
+        let (senders, receivers) = sample_senders_and_receivers::<C, _, _>(
+            distribution.parameters,
+            authorization_context,
+            asset.id.clone(),
+            secret_input,
+            output,
+            distribution.utxo_accumulator,
+            rng,
+        );
+        Self::new_with_fee(
+            distribution.authorization,
+            shape.has_visible_asset_id().then_some(asset.id),
+            input,
+            senders,
+            receivers,
+            public_output,
+            fee,
+        )
+    }
+}
+
 /// Samples a [`ToPrivate`] transfers and returns the corresponding [`TransferPost`]
 /// and [`PreSender`].
 #[inline]
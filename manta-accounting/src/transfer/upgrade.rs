@@ -0,0 +1,206 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Protocol Upgrades
+//!
+//! A ledger announces every upgrade of the transfer protocol ahead of time with an
+//! [`UpgradeDescriptor`], which lists the [`TransferShape`]s enabled by the upgrade, the hash of
+//! the parameters needed to build posts after it, and the [`ProtocolFeature`]s it deprecates, from
+//! the height at which the upgrade activates. Wallets keep the descriptors of the ledger in an
+//! [`UpgradeSchedule`] which they refresh when they synchronize, and consult it before using a
+//! feature, so that they stop building posts that the ledger rejects and download the new
+//! parameters before they need them.
+
+use crate::transfer::{
+    canonical::TransferShape,
+    epoch::{Epoch, Height},
+};
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Parameters Hash
+pub type ParametersHash = [u8; 32];
+
+/// Protocol Feature
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ProtocolFeature {
+    /// Transfer Shape
+    Shape(TransferShape),
+
+    /// Parameter Epoch
+    ///
+    /// Posts tagged with the epoch, see [`epoch`](super::epoch) for more.
+    Epoch(Epoch),
+
+    /// Legacy Nullifiers
+    ///
+    /// Nullifiers of the notes created before the nullifier migration.
+    LegacyNullifiers,
+}
+
+/// Upgrade Descriptor
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct UpgradeDescriptor {
+    /// Parameter Epoch
+    ///
+    /// Epoch which is activated by the upgrade and which the posts built after it are tagged with.
+    pub epoch: Epoch,
+
+    /// Activation Height
+    pub activation_height: Height,
+
+    /// New Transfer Shapes
+    ///
+    /// Shapes which are only accepted by the ledger once the upgrade is active.
+    pub new_shapes: Vec<TransferShape>,
+
+    /// Parameters Hash
+    ///
+    /// Hash of the parameters and proving contexts needed to build posts after the upgrade, if
+    /// the upgrade changes them.
+    pub parameters_hash: Option<ParametersHash>,
+
+    /// Deprecated Features
+    ///
+    /// Features which are no longer accepted by the ledger once the upgrade is active.
+    pub deprecated_features: Vec<ProtocolFeature>,
+}
+
+impl UpgradeDescriptor {
+    /// Returns `true` if `self` is active at `height`.
+    #[inline]
+    pub fn is_active(&self, height: Height) -> bool {
+        height >= self.activation_height
+    }
+
+    /// Returns `true` if `self` deprecates `feature`.
+    #[inline]
+    pub fn deprecates(&self, feature: &ProtocolFeature) -> bool {
+        self.deprecated_features.contains(feature)
+    }
+
+    /// Returns `true` if `self` enables `shape`.
+    #[inline]
+    pub fn enables(&self, shape: TransferShape) -> bool {
+        self.new_shapes.contains(&shape)
+    }
+}
+
+/// Upgrade Schedule
+///
+/// Upgrades announced by a ledger together with the height of the ledger when they were
+/// retrieved, ordered by activation height.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct UpgradeSchedule {
+    /// Ledger Height
+    height: Height,
+
+    /// Upgrade Descriptors
+    upgrades: Vec<UpgradeDescriptor>,
+}
+
+impl UpgradeSchedule {
+    /// Builds a new [`UpgradeSchedule`] of a ledger at `height` which announced `upgrades`.
+    #[inline]
+    pub fn new(height: Height, mut upgrades: Vec<UpgradeDescriptor>) -> Self {
+        upgrades.sort_by_key(|upgrade| upgrade.activation_height);
+        Self { height, upgrades }
+    }
+
+    /// Returns the height of the ledger when `self` was retrieved.
+    #[inline]
+    pub fn height(&self) -> Height {
+        self.height
+    }
+
+    /// Returns all the upgrades in `self`, ordered by activation height.
+    #[inline]
+    pub fn upgrades(&self) -> &[UpgradeDescriptor] {
+        &self.upgrades
+    }
+
+    /// Returns an iterator over the upgrades which are active at the height of `self`.
+    #[inline]
+    pub fn active(&self) -> impl Iterator<Item = &UpgradeDescriptor> {
+        self.upgrades
+            .iter()
+            .filter(move |upgrade| upgrade.is_active(self.height))
+    }
+
+    /// Returns an iterator over the upgrades which are not active yet at the height of `self`.
+    #[inline]
+    pub fn pending(&self) -> impl Iterator<Item = &UpgradeDescriptor> {
+        self.upgrades
+            .iter()
+            .filter(move |upgrade| !upgrade.is_active(self.height))
+    }
+
+    /// Returns `true` if `feature` was deprecated by an active upgrade.
+    #[inline]
+    pub fn is_deprecated(&self, feature: &ProtocolFeature) -> bool {
+        self.active().any(|upgrade| upgrade.deprecates(feature))
+    }
+
+    /// Returns `true` if `feature` is still accepted but will be deprecated by a pending upgrade.
+    #[inline]
+    pub fn is_retiring(&self, feature: &ProtocolFeature) -> bool {
+        !self.is_deprecated(feature) && self.pending().any(|upgrade| upgrade.deprecates(feature))
+    }
+
+    /// Returns `true` if the ledger accepts posts of `shape`, which is the case when no active
+    /// upgrade deprecates it and, if it was introduced by an upgrade, that upgrade is active.
+    #[inline]
+    pub fn is_enabled(&self, shape: TransferShape) -> bool {
+        if self.is_deprecated(&ProtocolFeature::Shape(shape)) {
+            return false;
+        }
+        let mut introduced = self
+            .upgrades
+            .iter()
+            .filter(|upgrade| upgrade.enables(shape))
+            .peekable();
+        introduced.peek().is_none() || introduced.any(|upgrade| upgrade.is_active(self.height))
+    }
+
+    /// Returns the latest upgrade which changes the parameters, if its parameters hash is not
+    /// `current`, so that the wallet can prompt its user to download the new parameters before
+    /// the upgrade activates.
+    #[inline]
+    pub fn parameters_update(&self, current: &ParametersHash) -> Option<&UpgradeDescriptor> {
+        self.upgrades
+            .iter()
+            .rev()
+            .find(|upgrade| upgrade.parameters_hash.is_some())
+            .filter(|upgrade| upgrade.parameters_hash.as_ref() != Some(current))
+    }
+}
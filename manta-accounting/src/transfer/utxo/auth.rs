@@ -22,7 +22,7 @@ use manta_crypto::{
     rand::RngCore,
 };
 use manta_util::{
-    codec::{Encode, Write},
+    codec::{Decode, DecodeError, Encode, Read, Write},
     convert::Field,
 };
 
@@ -402,6 +402,26 @@ where
     }
 }
 
+impl<T> Decode for AuthorizationSignature<T>
+where
+    T: AuthorizationKeyType + SignatureType,
+    T::AuthorizationKey: Decode,
+    T::Signature: Decode,
+{
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Ok(Self::new_unchecked(
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+        ))
+    }
+}
+
 impl<T> Encode for AuthorizationSignature<T>
 where
     T: AuthorizationKeyType + SignatureType,
@@ -232,19 +232,76 @@ pub trait DeriveDecryptionKey: AuthorizationContextType {
     ) -> Self::DecryptionKey;
 }
 
+/// Decryption Key Type
+pub type DecryptionKey<T> = <T as DeriveDecryptionKey>::DecryptionKey;
+
+/// Note Opening Error
+///
+/// Reason why a note could not be opened with a decryption key.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum NoteOpenError {
+    /// Undetected Note
+    ///
+    /// The note does not match the detection heuristics of the decryption key, so it was sent to
+    /// another address.
+    Undetected,
+
+    /// Decryption Failure
+    ///
+    /// The note matches the detection heuristics of the decryption key but its ciphertext could
+    /// not be decrypted.
+    DecryptionFailure,
+
+    /// Inconsistent UTXO
+    ///
+    /// The note was decrypted but its plaintext is not consistent with the UTXO it was posted
+    /// with.
+    InconsistentUtxo,
+}
+
+impl NoteOpenError {
+    /// Returns `true` if the note matched the detection heuristics of the decryption key, which
+    /// means that it was most likely sent to its owner but is malformed, or that the opening
+    /// procedure has a bug.
+    #[inline]
+    pub fn is_detected(&self) -> bool {
+        !matches!(self, Self::Undetected)
+    }
+}
+
 /// Note Opening
 pub trait NoteOpen: AssetType + DeriveDecryptionKey + IdentifierType + NoteType + UtxoType {
+    /// Tries to open `note` with `decryption_key`, returning a note [`Identifier`] and its stored
+    /// [`Asset`], or the reason why `note` could not be opened.
+    ///
+    /// [`Identifier`]: IdentifierType::Identifier
+    /// [`Asset`]: AssetType::Asset
+    fn try_open(
+        &self,
+        decryption_key: &Self::DecryptionKey,
+        utxo: &Self::Utxo,
+        note: &Self::Note,
+    ) -> Result<(Self::Identifier, Self::Asset), NoteOpenError>;
+
     /// Tries to open `note` with `decryption_key`, returning a note [`Identifier`] and its stored
     /// [`Asset`].
     ///
     /// [`Identifier`]: IdentifierType::Identifier
     /// [`Asset`]: AssetType::Asset
+    #[inline]
     fn open(
         &self,
         decryption_key: &Self::DecryptionKey,
         utxo: &Self::Utxo,
         note: Self::Note,
-    ) -> Option<(Self::Identifier, Self::Asset)>;
+    ) -> Option<(Self::Identifier, Self::Asset)> {
+        self.try_open(decryption_key, utxo, &note).ok()
+    }
 
     /// Tries to open `note` with `decryption_key`, returning an [`IdentifiedAsset`].
     #[inline]
@@ -268,6 +325,38 @@ pub trait DeriveAddress: AddressType {
     fn derive_address(&self, secret_key: &Self::SecretKey) -> Self::Address;
 }
 
+/// Address Ownership Proofs
+///
+/// Proves that the owner of a decryption key controls the address derived from it, by answering
+/// a challenge of the verifier, without a transfer or a SNARK.
+pub trait ProveOwnership:
+    DeriveAddress<SecretKey = Self::DecryptionKey> + DeriveDecryptionKey
+{
+    /// Ownership Proof Type
+    type OwnershipProof;
+
+    /// Proves that the owner of `decryption_key` controls its address, answering `challenge`.
+    fn prove_ownership<R>(
+        &self,
+        decryption_key: &Self::DecryptionKey,
+        challenge: &[u8],
+        rng: &mut R,
+    ) -> Self::OwnershipProof
+    where
+        R: RngCore + ?Sized;
+
+    /// Verifies that `proof` answers `challenge` for `address`.
+    fn verify_ownership(
+        &self,
+        address: &Self::Address,
+        challenge: &[u8],
+        proof: &Self::OwnershipProof,
+    ) -> bool;
+}
+
+/// Ownership Proof Type
+pub type OwnershipProof<T> = <T as ProveOwnership>::OwnershipProof;
+
 /// Utxo Reconstruction
 pub trait UtxoReconstruct: NoteOpen + DeriveAddress<SecretKey = Self::DecryptionKey> {
     /// Builds a [`Utxo`] from `asset`, `identifier` and `address`.
@@ -287,6 +376,24 @@ pub trait UtxoReconstruct: NoteOpen + DeriveAddress<SecretKey = Self::Decryption
         decryption_key: &Self::DecryptionKey,
     ) -> bool;
 
+    /// Opens `note` and checks if `utxo` is consistent with it, returning the reason of the
+    /// failure when it fails to open the `note` or when the `note` is inconsistent with the
+    /// `utxo`.
+    #[inline]
+    fn try_open_with_check(
+        &self,
+        decryption_key: &Self::DecryptionKey,
+        utxo: &Self::Utxo,
+        note: &Self::Note,
+    ) -> Result<(Self::Identifier, Self::Asset), NoteOpenError> {
+        let (identifier, asset) = self.try_open(decryption_key, utxo, note)?;
+        if self.utxo_check(utxo, &asset, &identifier, decryption_key) {
+            Ok((identifier, asset))
+        } else {
+            Err(NoteOpenError::InconsistentUtxo)
+        }
+    }
+
     /// Opens `note` and checks if `utxo` is consistent with it. Returns `None`
     /// when it fails to open the `note` or when the `note` is inconsistent with the `utxo`.
     #[inline]
@@ -296,16 +403,89 @@ pub trait UtxoReconstruct: NoteOpen + DeriveAddress<SecretKey = Self::Decryption
         utxo: &Self::Utxo,
         note: Self::Note,
     ) -> Option<(Self::Identifier, Self::Asset)> {
-        let (identifier, asset) = self.open(decryption_key, utxo, note)?;
+        self.try_open_with_check(decryption_key, utxo, &note).ok()
+    }
+}
 
-        if self.utxo_check(utxo, &asset, &identifier, decryption_key) {
-            Some((identifier, asset))
-        } else {
-            None
-        }
+/// Note Memos
+///
+/// A memo is a small payload which the sender of a note attaches to it, encrypted to the address
+/// of its receiver. Memos are not part of the UTXO, so they are not bound by the transfer proofs,
+/// and they are only authenticated by the signature of the post which carries them.
+pub trait NoteMemo: UtxoReconstruct {
+    /// Memo Plaintext Type
+    type MemoPlaintext;
+
+    /// Encrypted Memo Type
+    type Memo;
+
+    /// Returns the memo attached to `note`, if any.
+    fn memo(note: &Self::Note) -> Option<&Self::Memo>;
+
+    /// Replaces the memo attached to `note` with `memo`.
+    fn set_memo(note: &mut Self::Note, memo: Option<Self::Memo>);
+
+    /// Encrypts `plaintext` to `address`.
+    fn encrypt_memo<R>(
+        &self,
+        address: &Self::Address,
+        plaintext: &Self::MemoPlaintext,
+        rng: &mut R,
+    ) -> Self::Memo
+    where
+        R: RngCore + ?Sized;
+
+    /// Decrypts `memo` with `decryption_key`, returning `None` if it was not encrypted to the
+    /// address of `decryption_key`.
+    fn decrypt_memo(
+        &self,
+        decryption_key: &Self::DecryptionKey,
+        memo: &Self::Memo,
+    ) -> Option<Self::MemoPlaintext>;
+
+    /// Encrypts `plaintext` to `address` and attaches it to `note`, replacing its memo.
+    #[inline]
+    fn attach_memo<R>(
+        &self,
+        note: &mut Self::Note,
+        address: &Self::Address,
+        plaintext: &Self::MemoPlaintext,
+        rng: &mut R,
+    ) where
+        R: RngCore + ?Sized,
+    {
+        Self::set_memo(note, Some(self.encrypt_memo(address, plaintext, rng)));
+    }
+
+    /// Opens `note` and checks if `utxo` is consistent with it as in
+    /// [`try_open_with_check`](UtxoReconstruct::try_open_with_check), and decrypts the memo
+    /// attached to it. The memo is `None` if `note` has no memo or if its memo was not encrypted
+    /// to the address of `decryption_key`.
+    #[inline]
+    fn try_open_memo_with_check(
+        &self,
+        decryption_key: &Self::DecryptionKey,
+        utxo: &Self::Utxo,
+        note: &Self::Note,
+    ) -> Result<OpenedMemoNote<Self>, NoteOpenError> {
+        let (identifier, asset) = self.try_open_with_check(decryption_key, utxo, note)?;
+        let memo = Self::memo(note).and_then(|memo| self.decrypt_memo(decryption_key, memo));
+        Ok((identifier, asset, memo))
     }
 }
 
+/// Memo Plaintext Type
+pub type MemoPlaintext<T> = <T as NoteMemo>::MemoPlaintext;
+
+/// Encrypted Memo Type
+pub type Memo<T> = <T as NoteMemo>::Memo;
+
+/// Opened Note Type
+///
+/// Identifier, asset and decrypted memo of a note opened with
+/// [`try_open_memo_with_check`](NoteMemo::try_open_memo_with_check).
+pub type OpenedMemoNote<T> = (Identifier<T>, Asset<T>, Option<MemoPlaintext<T>>);
+
 /// Query Identifier Value
 pub trait QueryIdentifier: IdentifierType + UtxoType {
     /// Queries the underlying identifier from `self` and `utxo`.
@@ -32,7 +32,7 @@ use manta_crypto::{
         diffie_hellman::StandardDiffieHellman, security::ComputationalDiffieHellmanHardness,
         HasGenerator, Ring, ScalarMul, ScalarMulGroup,
     },
-    constraint::{HasInput, Input},
+    constraint::{measure::Profile, HasInput, Input},
     eclair::{
         alloc::{
             mode::{Derived, Public, Secret},
@@ -49,13 +49,15 @@ use manta_crypto::{
         hybrid::{Hybrid, Randomness},
         Decrypt, Encrypt, EncryptedMessage,
     },
+    impl_input,
     rand::{Rand, RngCore, Sample},
     signature::{self, schnorr, Sign, Verify},
 };
 use manta_util::{
     cmp::Independence,
-    codec::{Encode, Write},
+    codec::{Decode, DecodeError, Encode, Read, Write},
     convert::Field,
+    zeroize::Zeroize,
 };
 
 #[cfg(feature = "serde")]
@@ -146,7 +148,7 @@ where
     type Randomness: Clone;
 
     /// UTXO Commitment Type
-    type Commitment: PartialEq<Self::Commitment, COM>;
+    type Commitment: ConditionalSelect<COM> + PartialEq<Self::Commitment, COM>;
 
     /// Commits to the UTXO data `asset_id`, `asset_value`, and `receiving_key`.
     fn commit(
@@ -157,6 +159,22 @@ where
         receiving_key: &Self::ReceivingKey,
         compiler: &mut COM,
     ) -> Self::Commitment;
+
+    /// Commits to the UTXO data `asset_id`, `asset_value`, and `receiving_key` of a current note.
+    ///
+    /// # Crypto Safety
+    ///
+    /// This commitment must be domain-separated from [`commit`](Self::commit), so that no UTXO
+    /// commitment can be opened both as a legacy note and as a current note. See
+    /// [`NULLIFIER_MIGRATION`](BaseConfiguration::NULLIFIER_MIGRATION) for more.
+    fn commit_current(
+        &self,
+        randomness: &Self::Randomness,
+        asset_id: &Self::AssetId,
+        asset_value: &Self::AssetValue,
+        receiving_key: &Self::ReceivingKey,
+        compiler: &mut COM,
+    ) -> Self::Commitment;
 }
 
 /// Viewing Key Derivation Function
@@ -186,8 +204,11 @@ where
     /// UTXO Accumulator Item
     type UtxoAccumulatorItem;
 
+    /// Asset Id
+    type AssetId;
+
     /// Nullifier Commitment
-    type Commitment: PartialEq<Self::Commitment, COM>;
+    type Commitment: ConditionalSelect<COM> + PartialEq<Self::Commitment, COM>;
 
     /// Commits to the `item` using `proof_authorization_key`.
     fn commit(
@@ -196,6 +217,16 @@ where
         item: &Self::UtxoAccumulatorItem,
         compiler: &mut COM,
     ) -> Self::Commitment;
+
+    /// Commits to the `item` of an asset with the given `asset_id` using
+    /// `proof_authorization_key`, so that the commitment is scoped to `asset_id`.
+    fn commit_with_asset_id(
+        &self,
+        proof_authorization_key: &Self::ProofAuthorizationKey,
+        item: &Self::UtxoAccumulatorItem,
+        asset_id: &Self::AssetId,
+        compiler: &mut COM,
+    ) -> Self::Commitment;
 }
 
 /// UTXO Configuration
@@ -204,7 +235,8 @@ where
     COM: Has<bool, Type = Self::Bool>,
 {
     /// Boolean Type
-    type Bool: Constant<COM, Type = bool>
+    type Bool: Clone
+        + Constant<COM, Type = bool>
         + BitAnd<Self::Bool, COM, Output = Self::Bool>
         + BitOr<Self::Bool, COM, Output = Self::Bool>
         + PartialEq<Self::Bool, COM>;
@@ -294,8 +326,33 @@ where
         COM,
         ProofAuthorizationKey = Self::Group,
         UtxoAccumulatorItem = UtxoAccumulatorItem<Self, COM>,
+        AssetId = Self::AssetId,
     >;
 
+    /// Asset-Scoped Nullifiers Flag
+    ///
+    /// If this flag is set, the asset id of a UTXO is mixed into its nullifier commitment, so that
+    /// nullifiers are scoped per asset and can be pruned asset by asset. This changes every
+    /// nullifier, so it must be set in the same way for the native and the in-circuit
+    /// configurations and cannot be changed on a live ledger.
+    const ASSET_SCOPED_NULLIFIERS: bool = false;
+
+    /// Nullifier Migration Flag
+    ///
+    /// If this flag is set, the configuration migrates a ledger whose notes were minted without
+    /// this flag and without [`ASSET_SCOPED_NULLIFIERS`](Self::ASSET_SCOPED_NULLIFIERS) to the
+    /// nullifiers of this configuration. The notes minted before the migration are legacy notes
+    /// and the notes minted from now on are current notes, whose UTXO commitments are computed
+    /// with [`commit_current`](UtxoCommitmentScheme::commit_current). Every note opens under
+    /// exactly one of the two versions, so the spend circuit selects the UTXO commitment and the
+    /// nullifier commitment of a note with a secret version bit without allowing the same note to
+    /// be spent once with each nullifier. The version bit is revealed next to the nullifier
+    /// commitment, so that the ledger can keep accepting the nullifiers of legacy notes during a
+    /// transition epoch and reject them afterwards.
+    ///
+    /// Setting this flag changes the transfer circuits and the public input of the senders.
+    const NULLIFIER_MIGRATION: bool = false;
+
     /// Outgoing Header
     type OutgoingHeader: Default + PartialEq<Self::OutgoingHeader, COM>;
 
@@ -333,6 +390,24 @@ pub trait Configuration: BaseConfiguration<Bool = bool> {
     /// Schnorr Hash Function
     type SchnorrHashFunction: Clone
         + schnorr::HashFunction<Scalar = Self::Scalar, Group = Self::Group, Message = Vec<u8>>;
+
+    /// Memo Plaintext Type
+    type MemoPlaintext;
+
+    /// Memo Header
+    type MemoHeader: Default;
+
+    /// Base Encryption Scheme for [`Memo`]s
+    ///
+    /// Memos are never opened in the transfer circuits, so this scheme only has a native version
+    /// and is built with its [`Default`] value instead of being part of the [`Parameters`].
+    type MemoBaseEncryptionScheme: Default
+        + Encrypt<
+            EncryptionKey = Self::Group,
+            Header = Self::MemoHeader,
+            Plaintext = Self::MemoPlaintext,
+            Randomness = (),
+        > + Decrypt<DecryptionKey = Self::Group, DecryptedPlaintext = Option<Self::MemoPlaintext>>;
 }
 
 /// Asset Type
@@ -424,6 +499,15 @@ pub type OutgoingNote<C, COM = ()> = EncryptedMessage<OutgoingEncryptionScheme<C
 pub type AddressPartition<C> =
     <<C as Configuration>::AddressPartitionFunction as AddressPartitionFunction>::Partition;
 
+/// Memo Encryption Scheme
+pub type MemoEncryptionScheme<C> = Hybrid<
+    StandardDiffieHellman<<C as BaseConfiguration>::Scalar, <C as BaseConfiguration>::Group>,
+    <C as Configuration>::MemoBaseEncryptionScheme,
+>;
+
+/// Encrypted Memo
+pub type Memo<C> = EncryptedMessage<MemoEncryptionScheme<C>>;
+
 /// Signature Scheme
 pub type SignatureScheme<C> = schnorr::Schnorr<<C as Configuration>::SchnorrHashFunction>;
 
@@ -557,6 +641,80 @@ where
     pub outgoing_base_encryption_scheme: C::OutgoingBaseEncryptionScheme,
 }
 
+impl<C, COM> BaseParameters<C, COM>
+where
+    C: BaseConfiguration<COM>,
+    COM: Has<bool, Type = C::Bool>,
+{
+    /// Computes the UTXO commitment of a note with the given `randomness`, `asset_id`,
+    /// `asset_value` and `receiving_key`, committing to it as a legacy note if `is_legacy` is set
+    /// and [`NULLIFIER_MIGRATION`](BaseConfiguration::NULLIFIER_MIGRATION) is set.
+    #[inline]
+    pub fn utxo_commitment(
+        &self,
+        is_legacy: &C::Bool,
+        randomness: &UtxoCommitmentRandomness<C, COM>,
+        asset_id: &C::AssetId,
+        asset_value: &C::AssetValue,
+        receiving_key: &C::Group,
+        compiler: &mut COM,
+    ) -> UtxoCommitment<C, COM> {
+        let legacy = self.utxo_commitment_scheme.commit(
+            randomness,
+            asset_id,
+            asset_value,
+            receiving_key,
+            compiler,
+        );
+        if C::NULLIFIER_MIGRATION {
+            let current = self.utxo_commitment_scheme.commit_current(
+                randomness,
+                asset_id,
+                asset_value,
+                receiving_key,
+                compiler,
+            );
+            ConditionalSelect::select(is_legacy, &legacy, &current, compiler)
+        } else {
+            legacy
+        }
+    }
+
+    /// Computes the nullifier commitment of `item` for `proof_authorization_key`, mixing in the
+    /// `asset_id` if [`ASSET_SCOPED_NULLIFIERS`](BaseConfiguration::ASSET_SCOPED_NULLIFIERS) is
+    /// set. If [`NULLIFIER_MIGRATION`](BaseConfiguration::NULLIFIER_MIGRATION) is set and
+    /// `is_legacy` is set, the legacy nullifier commitment is computed instead.
+    #[inline]
+    pub fn nullifier_commitment(
+        &self,
+        is_legacy: &C::Bool,
+        proof_authorization_key: &C::Group,
+        item: &UtxoAccumulatorItem<C, COM>,
+        asset_id: &C::AssetId,
+        compiler: &mut COM,
+    ) -> NullifierCommitment<C, COM> {
+        let current = if C::ASSET_SCOPED_NULLIFIERS {
+            self.nullifier_commitment_scheme.commit_with_asset_id(
+                proof_authorization_key,
+                item,
+                asset_id,
+                compiler,
+            )
+        } else {
+            self.nullifier_commitment_scheme
+                .commit(proof_authorization_key, item, compiler)
+        };
+        if C::NULLIFIER_MIGRATION && C::ASSET_SCOPED_NULLIFIERS {
+            let legacy =
+                self.nullifier_commitment_scheme
+                    .commit(proof_authorization_key, item, compiler);
+            ConditionalSelect::select(is_legacy, &legacy, &current, compiler)
+        } else {
+            current
+        }
+    }
+}
+
 impl<C, COM> auth::AuthorizationContextType for BaseParameters<C, COM>
 where
     C: BaseConfiguration<COM>,
@@ -692,7 +850,7 @@ where
 impl<C, COM> utxo::Spend<COM> for BaseParameters<C, COM>
 where
     C: BaseConfiguration<COM>,
-    COM: Assert + Has<bool, Type = C::Bool>,
+    COM: Assert + Has<bool, Type = C::Bool> + Profile,
 {
     type UtxoAccumulatorWitness = utxo::UtxoAccumulatorWitness<Self, COM>;
     type UtxoAccumulatorOutput = utxo::UtxoAccumulatorOutput<Self, COM>;
@@ -890,6 +1048,33 @@ where
         )
     }
 
+    /// Verifies that `signature` is a valid signature of `message` under `verifying_key`,
+    /// rejecting the signatures whose nonce point is the generator times their scalar, which
+    /// verify under the identity for any message.
+    #[inline]
+    fn verify_signature(
+        &self,
+        verifying_key: &C::Group,
+        message: Vec<u8>,
+        signature: &signature::Signature<SignatureScheme<C>>,
+    ) -> bool
+    where
+        C::Group: cmp::PartialEq,
+    {
+        if self
+            .base
+            .group_generator
+            .generator()
+            .scalar_mul(&signature.scalar, &mut ())
+            == signature.nonce_point
+        {
+            false
+        } else {
+            self.signature_scheme()
+                .verify(verifying_key, &message, signature, &mut ())
+        }
+    }
+
     /// Computes the [`Address`] corresponding to `spending_key`.
     #[inline]
     pub fn address_from_spending_key(&self, spending_key: &SpendingKey<Self>) -> Address<C>
@@ -1105,18 +1290,7 @@ where
         message: &M,
         signature: &Self::Signature,
     ) -> bool {
-        if self
-            .base
-            .group_generator
-            .generator()
-            .scalar_mul(&signature.scalar, &mut ())
-            == signature.nonce_point
-        {
-            false
-        } else {
-            self.signature_scheme()
-                .verify(authorization_key, &message.to_vec(), signature, &mut ())
-        }
+        self.verify_signature(authorization_key, message.to_vec(), signature)
     }
 }
 
@@ -1165,13 +1339,7 @@ where
             rng.gen(),
             IncomingPlaintext::new(rng.gen(), associated_data.secret(&asset)),
         );
-        let utxo_commitment = self.base.utxo_commitment_scheme.commit(
-            &secret.plaintext.utxo_commitment_randomness,
-            &secret.plaintext.asset.id,
-            &secret.plaintext.asset.value,
-            &secret.receiving_key,
-            &mut (),
-        );
+        let utxo_commitment = secret.utxo_commitment(&self.base.utxo_commitment_scheme, &mut ());
         let incoming_note = Hybrid::new(
             StandardDiffieHellman::new(self.base.group_generator.generator().clone()),
             self.base.incoming_base_encryption_scheme.clone(),
@@ -1309,19 +1477,14 @@ where
                 identifier.utxo_commitment_randomness,
                 associated_data.secret(&asset),
             ),
+            identifier.is_legacy,
         );
         let receiving_key = authorization_context.receiving_key(
             self.base.group_generator.generator(),
             &self.base.viewing_key_derivation_function,
             &mut (),
         );
-        let utxo_commitment = self.base.utxo_commitment_scheme.commit(
-            &secret.plaintext.utxo_commitment_randomness,
-            &secret.plaintext.asset.id,
-            &secret.plaintext.asset.value,
-            receiving_key,
-            &mut (),
-        );
+        let utxo_commitment = secret.utxo_commitment(&self.base, receiving_key, &mut ());
         let utxo = Utxo::<C>::new(
             identifier.is_transparent,
             associated_data.public(&asset),
@@ -1338,15 +1501,20 @@ where
             &asset,
             &mut (),
         );
-        let nullifier_commitment = self.base.nullifier_commitment_scheme.commit(
+        let nullifier_commitment = self.base.nullifier_commitment(
+            &identifier.is_legacy,
             &authorization_context.proof_authorization_key,
             &self.item_hash(&utxo, &mut ()),
+            &asset.id,
             &mut (),
         );
         (
             secret,
             utxo,
-            FullNullifier::new(Nullifier::new(nullifier_commitment), outgoing_note),
+            FullNullifier::new(
+                Nullifier::new(nullifier_commitment, identifier.is_legacy),
+                outgoing_note,
+            ),
         )
     }
 }
@@ -1375,36 +1543,48 @@ where
         Decrypt<DecryptionKey = C::Group, DecryptedPlaintext = Option<IncomingPlaintext<C>>>,
 {
     #[inline]
-    fn open(
+    fn try_open(
         &self,
         decryption_key: &Self::DecryptionKey,
         utxo: &Self::Utxo,
-        note: Self::Note,
-    ) -> Option<(Self::Identifier, Self::Asset)> {
-        let address_partition = self.address_partition_function.partition(&Address::new(
-            self.base
-                .group_generator
-                .generator()
-                .scalar_mul(decryption_key, &mut ()),
-        ));
-        if address_partition == note.address_partition {
-            let plaintext = Hybrid::new(
-                StandardDiffieHellman::new(self.base.group_generator.generator().clone()),
-                self.base.light_incoming_base_encryption_scheme.clone(),
-            )
-            .decrypt(
-                decryption_key,
-                &C::LightIncomingHeader::default(),
-                &note.light_incoming_note.ciphertext,
-                &mut (),
-            )?;
-            Some((
-                Identifier::new(utxo.is_transparent, plaintext.utxo_commitment_randomness),
-                plaintext.asset,
-            ))
-        } else {
-            None
+        note: &Self::Note,
+    ) -> Result<(Self::Identifier, Self::Asset), utxo::NoteOpenError> {
+        let address = self.derive_address(decryption_key);
+        let address_partition = self.address_partition_function.partition(&address);
+        if address_partition != note.address_partition {
+            return Err(utxo::NoteOpenError::Undetected);
         }
+        let plaintext = Hybrid::new(
+            StandardDiffieHellman::new(self.base.group_generator.generator().clone()),
+            self.base.light_incoming_base_encryption_scheme.clone(),
+        )
+        .decrypt(
+            decryption_key,
+            &C::LightIncomingHeader::default(),
+            &note.light_incoming_note.ciphertext,
+            &mut (),
+        )
+        .ok_or(utxo::NoteOpenError::DecryptionFailure)?;
+        let is_legacy = C::NULLIFIER_MIGRATION
+            && self
+                .base
+                .utxo_commitment_scheme
+                .commit_current(
+                    &plaintext.utxo_commitment_randomness,
+                    &plaintext.asset.id,
+                    &plaintext.asset.value,
+                    &address.receiving_key,
+                    &mut (),
+                )
+                .ne(&utxo.commitment, &mut ());
+        Ok((
+            Identifier::new(
+                utxo.is_transparent,
+                is_legacy,
+                plaintext.utxo_commitment_randomness,
+            ),
+            plaintext.asset,
+        ))
     }
 }
 
@@ -1425,6 +1605,53 @@ where
     }
 }
 
+/// Ownership Proof Domain Tag
+///
+/// Prefix of the challenges signed by ownership proofs, so that they are never valid as the
+/// signature of anything else.
+pub const OWNERSHIP_PROOF_DOMAIN_TAG: &[u8] = b"manta/utxo/address-ownership-proof";
+
+impl<C> utxo::ProveOwnership for Parameters<C>
+where
+    C: Configuration<Bool = bool>,
+    C::Scalar: Sample,
+    C::Group: cmp::PartialEq,
+{
+    type OwnershipProof = signature::Signature<SignatureScheme<C>>;
+
+    #[inline]
+    fn prove_ownership<R>(
+        &self,
+        decryption_key: &Self::DecryptionKey,
+        challenge: &[u8],
+        rng: &mut R,
+    ) -> Self::OwnershipProof
+    where
+        R: RngCore + ?Sized,
+    {
+        self.signature_scheme().sign(
+            decryption_key,
+            &rng.gen(),
+            &[OWNERSHIP_PROOF_DOMAIN_TAG, challenge].concat(),
+            &mut (),
+        )
+    }
+
+    #[inline]
+    fn verify_ownership(
+        &self,
+        address: &Self::Address,
+        challenge: &[u8],
+        proof: &Self::OwnershipProof,
+    ) -> bool {
+        self.verify_signature(
+            &address.receiving_key,
+            [OWNERSHIP_PROOF_DOMAIN_TAG, challenge].concat(),
+            proof,
+        )
+    }
+}
+
 impl<C> utxo::UtxoReconstruct for Parameters<C>
 where
     C: Configuration<Bool = bool>,
@@ -1447,7 +1674,8 @@ where
         } else {
             Visibility::Opaque
         };
-        let new_utxo_commitment = self.base.utxo_commitment_scheme.commit(
+        let new_utxo_commitment = self.base.utxo_commitment(
+            &identifier.is_legacy,
             &identifier.utxo_commitment_randomness,
             &associated_data.secret(asset).id,
             &associated_data.secret(asset).value,
@@ -1474,6 +1702,75 @@ where
     }
 }
 
+impl<C> Parameters<C>
+where
+    C: Configuration<Bool = bool>,
+{
+    /// Returns the [`MemoEncryptionScheme`] for `self`.
+    #[inline]
+    pub fn memo_encryption_scheme(&self) -> MemoEncryptionScheme<C> {
+        Hybrid::new(
+            StandardDiffieHellman::new(self.base.group_generator.generator().clone()),
+            Default::default(),
+        )
+    }
+}
+
+impl<C> utxo::NoteMemo for Parameters<C>
+where
+    C: Configuration<Bool = bool>,
+    C::LightIncomingBaseEncryptionScheme:
+        Decrypt<DecryptionKey = C::Group, DecryptedPlaintext = Option<IncomingPlaintext<C>>>,
+    C::Scalar: Sample,
+    Asset<C>: Clone + Default,
+{
+    type MemoPlaintext = C::MemoPlaintext;
+    type Memo = Memo<C>;
+
+    #[inline]
+    fn memo(note: &Self::Note) -> Option<&Self::Memo> {
+        note.memo.as_ref()
+    }
+
+    #[inline]
+    fn set_memo(note: &mut Self::Note, memo: Option<Self::Memo>) {
+        note.memo = memo;
+    }
+
+    #[inline]
+    fn encrypt_memo<R>(
+        &self,
+        address: &Self::Address,
+        plaintext: &Self::MemoPlaintext,
+        rng: &mut R,
+    ) -> Self::Memo
+    where
+        R: RngCore + ?Sized,
+    {
+        self.memo_encryption_scheme().encrypt_into(
+            &address.receiving_key,
+            &Randomness::new(rng.gen(), ()),
+            Default::default(),
+            plaintext,
+            &mut (),
+        )
+    }
+
+    #[inline]
+    fn decrypt_memo(
+        &self,
+        decryption_key: &Self::DecryptionKey,
+        memo: &Self::Memo,
+    ) -> Option<Self::MemoPlaintext> {
+        self.memo_encryption_scheme().decrypt(
+            decryption_key,
+            &memo.header,
+            &memo.ciphertext,
+            &mut (),
+        )
+    }
+}
+
 impl<C, DBP, DAPF, DSHF> Sample<(DBP, DAPF, DSHF)> for Parameters<C>
 where
     C: Configuration<Bool = bool>,
@@ -1634,13 +1931,16 @@ where
 }
 
 /// Full Incoming Note
+///
+/// The [`memo`](Self::memo) of a note is not part of its [`Encode`] representation, which is the
+/// same for all the notes. Memos are encoded by the posts which carry them instead.
 #[cfg_attr(
     feature = "serde",
     derive(Deserialize, Serialize),
     serde(
         bound(
-            deserialize = "AddressPartition<C>: Deserialize<'de>, IncomingNote<C>: Deserialize<'de>, LightIncomingNote<C>: Deserialize<'de>",
-            serialize = "AddressPartition<C>: Serialize, IncomingNote<C>: Serialize, LightIncomingNote<C>: Serialize",
+            deserialize = "AddressPartition<C>: Deserialize<'de>, IncomingNote<C>: Deserialize<'de>, LightIncomingNote<C>: Deserialize<'de>, Memo<C>: Deserialize<'de>",
+            serialize = "AddressPartition<C>: Serialize, IncomingNote<C>: Serialize, LightIncomingNote<C>: Serialize, Memo<C>: Serialize",
         ),
         crate = "manta_util::serde",
         deny_unknown_fields
@@ -1649,19 +1949,25 @@ where
 #[derive(derivative::Derivative)]
 #[derivative(
     Clone(
-        bound = "AddressPartition<C>: Clone, IncomingNote<C>: Clone, LightIncomingNote<C>: Clone"
+        bound = "AddressPartition<C>: Clone, IncomingNote<C>: Clone, LightIncomingNote<C>: Clone, Memo<C>: Clone"
+    ),
+    Copy(
+        bound = "AddressPartition<C>: Copy, IncomingNote<C>: Copy, LightIncomingNote<C>: Copy, Memo<C>: Copy"
     ),
-    Copy(bound = "AddressPartition<C>: Copy, IncomingNote<C>: Copy, LightIncomingNote<C>: Copy"),
     Debug(
-        bound = "AddressPartition<C>: Debug, IncomingNote<C>: Debug, LightIncomingNote<C>: Debug"
+        bound = "AddressPartition<C>: Debug, IncomingNote<C>: Debug, LightIncomingNote<C>: Debug, Memo<C>: Debug"
     ),
     Default(
         bound = "AddressPartition<C>: Default, IncomingNote<C>: Default, LightIncomingNote<C>: Default"
     ),
-    Eq(bound = "AddressPartition<C>: Eq, IncomingNote<C>: Eq, LightIncomingNote<C>: Eq"),
-    Hash(bound = "AddressPartition<C>: Hash, IncomingNote<C>: Hash, LightIncomingNote<C>: Hash"),
+    Eq(
+        bound = "AddressPartition<C>: Eq, IncomingNote<C>: Eq, LightIncomingNote<C>: Eq, Memo<C>: Eq"
+    ),
+    Hash(
+        bound = "AddressPartition<C>: Hash, IncomingNote<C>: Hash, LightIncomingNote<C>: Hash, Memo<C>: Hash"
+    ),
     PartialEq(
-        bound = "AddressPartition<C>: cmp::PartialEq, IncomingNote<C>: cmp::PartialEq, LightIncomingNote<C>: cmp::PartialEq"
+        bound = "AddressPartition<C>: cmp::PartialEq, IncomingNote<C>: cmp::PartialEq, LightIncomingNote<C>: cmp::PartialEq, Memo<C>: cmp::PartialEq"
     )
 )]
 pub struct FullIncomingNote<C>
@@ -1676,6 +1982,12 @@ where
 
     /// Light Incoming Note
     pub light_incoming_note: LightIncomingNote<C>,
+
+    /// Memo
+    ///
+    /// Memo encrypted to the address of the receiver of the note, if the sender attached one.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub memo: Option<Memo<C>>,
 }
 
 impl<C> FullIncomingNote<C>
@@ -1693,10 +2005,33 @@ where
             address_partition,
             incoming_note,
             light_incoming_note,
+            memo: None,
         }
     }
 }
 
+impl<C> Decode for FullIncomingNote<C>
+where
+    C: Configuration<Bool = bool> + ?Sized,
+    AddressPartition<C>: Decode,
+    IncomingNote<C>: Decode,
+    LightIncomingNote<C>: Decode,
+{
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Ok(Self::new(
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+        ))
+    }
+}
+
 impl<C> Encode for FullIncomingNote<C>
 where
     C: Configuration<Bool = bool> + ?Sized,
@@ -1716,14 +2051,11 @@ where
     }
 }
 
-impl<C, P> Input<P> for FullIncomingNote<C>
-where
-    C: Configuration<Bool = bool>,
-    P: HasInput<IncomingNote<C>> + ?Sized,
-{
-    #[inline]
-    fn extend(&self, input: &mut P::Input) {
-        P::extend(input, &self.incoming_note);
+impl_input! {
+    impl<C> Input<P> for FullIncomingNote<C>
+    where [C: Configuration<Bool = bool>]
+    |note| {
+        IncomingNote<C> => &note.incoming_note,
     }
 }
 
@@ -1880,6 +2212,28 @@ where
     }
 }
 
+impl<C> Decode for Utxo<C>
+where
+    C: BaseConfiguration<Bool = bool>,
+    C::AssetId: Decode,
+    C::AssetValue: Decode,
+    UtxoCommitment<C>: Decode,
+{
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Ok(Self {
+            is_transparent: Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+            public_asset: Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+            commitment: Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+        })
+    }
+}
+
 impl<C> Encode for Utxo<C>
 where
     C: BaseConfiguration<Bool = bool>,
@@ -1899,16 +2253,13 @@ where
     }
 }
 
-impl<C, P> Input<P> for Utxo<C>
-where
-    C: BaseConfiguration<Bool = bool>,
-    P: HasInput<C::Bool> + HasInput<Asset<C>> + HasInput<UtxoCommitment<C>> + ?Sized,
-{
-    #[inline]
-    fn extend(&self, input: &mut P::Input) {
-        P::extend(input, &self.is_transparent);
-        P::extend(input, &self.public_asset);
-        P::extend(input, &self.commitment);
+impl_input! {
+    impl<C> Input<P> for Utxo<C>
+    where [C: BaseConfiguration<Bool = bool>]
+    |utxo| {
+        C::Bool => &utxo.is_transparent,
+        Asset<C> => &utxo.public_asset,
+        UtxoCommitment<C> => &utxo.commitment,
     }
 }
 
@@ -1981,20 +2332,31 @@ where
         }
     }
 
-    /// Returns the UTXO commitment for `self` under `utxo_commitment_scheme`.
+    /// Returns the UTXO commitment for `self` under `utxo_commitment_scheme`. New notes are
+    /// current notes if [`NULLIFIER_MIGRATION`](BaseConfiguration::NULLIFIER_MIGRATION) is set.
     #[inline]
     pub fn utxo_commitment(
         &self,
         utxo_commitment_scheme: &C::UtxoCommitmentScheme,
         compiler: &mut COM,
     ) -> UtxoCommitment<C, COM> {
-        utxo_commitment_scheme.commit(
-            &self.plaintext.utxo_commitment_randomness,
-            &self.plaintext.asset.id,
-            &self.plaintext.asset.value,
-            &self.receiving_key,
-            compiler,
-        )
+        if C::NULLIFIER_MIGRATION {
+            utxo_commitment_scheme.commit_current(
+                &self.plaintext.utxo_commitment_randomness,
+                &self.plaintext.asset.id,
+                &self.plaintext.asset.value,
+                &self.receiving_key,
+                compiler,
+            )
+        } else {
+            utxo_commitment_scheme.commit(
+                &self.plaintext.utxo_commitment_randomness,
+                &self.plaintext.asset.id,
+                &self.plaintext.asset.value,
+                &self.receiving_key,
+                compiler,
+            )
+        }
     }
 
     /// Returns the incoming note for `self` under `encryption_scheme`.
@@ -2108,6 +2470,7 @@ where
     fn query_identifier(&self, utxo: &Self::Utxo) -> Self::Identifier {
         Identifier::new(
             utxo.is_transparent,
+            false,
             self.plaintext.utxo_commitment_randomness.clone(),
         )
     }
@@ -2263,6 +2626,21 @@ where
 {
 }
 
+impl<C, COM> Zeroize for AuthorizationContext<C, COM>
+where
+    C: BaseConfiguration<COM> + ?Sized,
+    COM: Has<bool, Type = C::Bool>,
+    C::Group: Zeroize,
+    C::Scalar: Zeroize,
+{
+    #[inline]
+    fn zeroize(&mut self) {
+        self.proof_authorization_key.zeroize();
+        self.viewing_key.zeroize();
+        self.receiving_key.zeroize();
+    }
+}
+
 impl<C, COM> Variable<Secret, COM> for AuthorizationContext<C, COM>
 where
     COM: Has<bool, Type = C::Bool>,
@@ -2417,6 +2795,16 @@ where
     /// Transparency Flag
     pub is_transparent: bool,
 
+    /// Legacy Flag
+    ///
+    /// This flag is only set for the notes minted before a nullifier migration. See
+    /// [`NULLIFIER_MIGRATION`](BaseConfiguration::NULLIFIER_MIGRATION) for more.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "core::ops::Not::not")
+    )]
+    pub is_legacy: bool,
+
     /// UTXO Commitment Randomness
     pub utxo_commitment_randomness: UtxoCommitmentRandomness<C>,
 }
@@ -2425,14 +2813,17 @@ impl<C> Identifier<C>
 where
     C: BaseConfiguration<Bool = bool>,
 {
-    /// Builds a new [`Identifier`] from `is_transparent` and `utxo_commitment_randomness`.
+    /// Builds a new [`Identifier`] from `is_transparent`, `is_legacy` and
+    /// `utxo_commitment_randomness`.
     #[inline]
     pub fn new(
         is_transparent: bool,
+        is_legacy: bool,
         utxo_commitment_randomness: UtxoCommitmentRandomness<C>,
     ) -> Self {
         Self {
             is_transparent,
+            is_legacy,
             utxo_commitment_randomness,
         }
     }
@@ -2448,7 +2839,52 @@ where
     where
         R: RngCore + ?Sized,
     {
-        Self::new(rng.gen(), rng.gen())
+        Self::new(rng.gen(), C::NULLIFIER_MIGRATION && rng.gen(), rng.gen())
+    }
+}
+
+impl<C> Decode for Identifier<C>
+where
+    C: BaseConfiguration<Bool = bool>,
+    UtxoCommitmentRandomness<C>: Decode,
+{
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        let is_transparent = Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?;
+        let is_legacy = if C::NULLIFIER_MIGRATION {
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?
+        } else {
+            false
+        };
+        Ok(Self::new(
+            is_transparent,
+            is_legacy,
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+        ))
+    }
+}
+
+impl<C> Encode for Identifier<C>
+where
+    C: BaseConfiguration<Bool = bool>,
+    UtxoCommitmentRandomness<C>: Encode,
+{
+    #[inline]
+    fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        self.is_transparent.encode(&mut writer)?;
+        if C::NULLIFIER_MIGRATION {
+            self.is_legacy.encode(&mut writer)?;
+        }
+        self.utxo_commitment_randomness.encode(&mut writer)?;
+        Ok(())
     }
 }
 
@@ -2458,8 +2894,8 @@ where
     derive(Deserialize, Serialize),
     serde(
         bound(
-            deserialize = "OutgoingRandomness<C, COM>: Deserialize<'de>, IncomingPlaintext<C, COM>: Deserialize<'de>",
-            serialize = "OutgoingRandomness<C, COM>: Serialize, IncomingPlaintext<C, COM>: Serialize",
+            deserialize = "OutgoingRandomness<C, COM>: Deserialize<'de>, IncomingPlaintext<C, COM>: Deserialize<'de>, C::Bool: Deserialize<'de>",
+            serialize = "OutgoingRandomness<C, COM>: Serialize, IncomingPlaintext<C, COM>: Serialize, C::Bool: Serialize",
         ),
         crate = "manta_util::serde",
         deny_unknown_fields
@@ -2467,14 +2903,24 @@ where
 )]
 #[derive(derivative::Derivative)]
 #[derivative(
-    Clone(bound = "OutgoingRandomness<C, COM>: Clone, IncomingPlaintext<C, COM>: Clone"),
-    Copy(bound = "OutgoingRandomness<C, COM>: Copy, IncomingPlaintext<C, COM>: Copy"),
-    Debug(bound = "OutgoingRandomness<C, COM>: Debug, IncomingPlaintext<C, COM>: Debug"),
-    Default(bound = "OutgoingRandomness<C, COM>: Default, IncomingPlaintext<C, COM>: Default"),
-    Eq(bound = "OutgoingRandomness<C, COM>: Eq, IncomingPlaintext<C, COM>: Eq"),
-    Hash(bound = "OutgoingRandomness<C, COM>: Hash, IncomingPlaintext<C, COM>: Hash"),
+    Clone(
+        bound = "OutgoingRandomness<C, COM>: Clone, IncomingPlaintext<C, COM>: Clone, C::Bool: Clone"
+    ),
+    Copy(
+        bound = "OutgoingRandomness<C, COM>: Copy, IncomingPlaintext<C, COM>: Copy, C::Bool: Copy"
+    ),
+    Debug(
+        bound = "OutgoingRandomness<C, COM>: Debug, IncomingPlaintext<C, COM>: Debug, C::Bool: Debug"
+    ),
+    Default(
+        bound = "OutgoingRandomness<C, COM>: Default, IncomingPlaintext<C, COM>: Default, C::Bool: Default"
+    ),
+    Eq(bound = "OutgoingRandomness<C, COM>: Eq, IncomingPlaintext<C, COM>: Eq, C::Bool: Eq"),
+    Hash(
+        bound = "OutgoingRandomness<C, COM>: Hash, IncomingPlaintext<C, COM>: Hash, C::Bool: Hash"
+    ),
     PartialEq(
-        bound = "OutgoingRandomness<C, COM>: cmp::PartialEq, IncomingPlaintext<C, COM>: cmp::PartialEq"
+        bound = "OutgoingRandomness<C, COM>: cmp::PartialEq, IncomingPlaintext<C, COM>: cmp::PartialEq, C::Bool: cmp::PartialEq"
     )
 )]
 
@@ -2488,6 +2934,9 @@ where
 
     /// Plaintext
     plaintext: IncomingPlaintext<C, COM>,
+
+    /// Legacy Flag
+    is_legacy: C::Bool,
 }
 
 impl<C, COM> SpendSecret<C, COM>
@@ -2495,28 +2944,31 @@ where
     C: BaseConfiguration<COM>,
     COM: Has<bool, Type = C::Bool>,
 {
-    /// Builds a new [`SpendSecret`] from `outgoing_randomness`, and `plaintext`.
+    /// Builds a new [`SpendSecret`] from `outgoing_randomness`, `plaintext`, and `is_legacy`.
     #[inline]
     pub fn new(
         outgoing_randomness: OutgoingRandomness<C, COM>,
         plaintext: IncomingPlaintext<C, COM>,
+        is_legacy: C::Bool,
     ) -> Self {
         Self {
             outgoing_randomness,
             plaintext,
+            is_legacy,
         }
     }
 
-    /// Returns the UTXO commitment for `self` with the given `receiving_key` under
-    /// `utxo_commitment_scheme`.
+    /// Returns the UTXO commitment for `self` with the given `receiving_key` under the UTXO
+    /// commitment scheme in `parameters`, selecting the legacy or current commitment.
     #[inline]
     pub fn utxo_commitment(
         &self,
-        utxo_commitment_scheme: &C::UtxoCommitmentScheme,
+        parameters: &BaseParameters<C, COM>,
         receiving_key: &C::Group,
         compiler: &mut COM,
     ) -> UtxoCommitment<C, COM> {
-        utxo_commitment_scheme.commit(
+        parameters.utxo_commitment(
+            &self.is_legacy,
             &self.plaintext.utxo_commitment_randomness,
             &self.plaintext.asset.id,
             &self.plaintext.asset.value,
@@ -2562,7 +3014,7 @@ where
         compiler: &mut COM,
     ) -> (Asset<C, COM>, Nullifier<C, COM>)
     where
-        COM: AssertEq,
+        COM: AssertEq + Profile,
     {
         let is_transparent = self.plaintext.asset.is_empty(compiler);
         compiler.assert_eq(&utxo.is_transparent, &is_transparent);
@@ -2577,21 +3029,28 @@ where
             &parameters.viewing_key_derivation_function,
             compiler,
         );
-        let utxo_commitment =
-            self.utxo_commitment(&parameters.utxo_commitment_scheme, receiving_key, compiler);
+        let utxo_commitment = self.utxo_commitment(parameters, receiving_key, compiler);
         compiler.assert_eq(&utxo.commitment, &utxo_commitment);
-        let item = parameters.item_hash(utxo, compiler);
-        let has_valid_membership = &asset.value.is_zero(compiler).bitor(
-            utxo_membership_proof.verify(utxo_accumulator_model, &item, compiler),
-            compiler,
-        );
-        compiler.assert(has_valid_membership);
-        let nullifier_commitment = parameters.nullifier_commitment_scheme.commit(
+        let item = compiler.region("membership", |compiler| {
+            let item = parameters.item_hash(utxo, compiler);
+            let has_valid_membership = &asset.value.is_zero(compiler).bitor(
+                utxo_membership_proof.verify(utxo_accumulator_model, &item, compiler),
+                compiler,
+            );
+            compiler.assert(has_valid_membership);
+            item
+        });
+        let nullifier_commitment = parameters.nullifier_commitment(
+            &self.is_legacy,
             &authorization_context.proof_authorization_key,
             &item,
+            &asset.id,
             compiler,
         );
-        (asset, Nullifier::new(nullifier_commitment))
+        (
+            asset,
+            Nullifier::new(nullifier_commitment, self.is_legacy.clone()),
+        )
     }
 }
 
@@ -2632,6 +3091,7 @@ where
     C: BaseConfiguration<COM> + Constant<COM>,
     C::Type: BaseConfiguration<Bool = bool>,
     COM: Has<bool, Type = C::Bool>,
+    C::Bool: Variable<Secret, COM, Type = bool>,
     OutgoingRandomness<C, COM>: Variable<Secret, COM, Type = OutgoingRandomness<C::Type>>,
     IncomingPlaintext<C, COM>: Variable<Secret, COM, Type = IncomingPlaintext<C::Type>>,
 {
@@ -2639,7 +3099,15 @@ where
 
     #[inline]
     fn new_unknown(compiler: &mut COM) -> Self {
-        Self::new(compiler.allocate_unknown(), compiler.allocate_unknown())
+        Self::new(
+            compiler.allocate_unknown(),
+            compiler.allocate_unknown(),
+            if C::NULLIFIER_MIGRATION {
+                compiler.allocate_unknown()
+            } else {
+                false.as_constant(compiler)
+            },
+        )
     }
 
     #[inline]
@@ -2647,6 +3115,11 @@ where
         Self::new(
             this.outgoing_randomness.as_known(compiler),
             this.plaintext.as_known(compiler),
+            if C::NULLIFIER_MIGRATION {
+                this.is_legacy.as_known(compiler)
+            } else {
+                false.as_constant(compiler)
+            },
         )
     }
 }
@@ -2657,8 +3130,8 @@ where
     derive(Deserialize, Serialize),
     serde(
         bound(
-            deserialize = "NullifierCommitment<C, COM>: Deserialize<'de>",
-            serialize = "NullifierCommitment<C, COM>: Serialize"
+            deserialize = "NullifierCommitment<C, COM>: Deserialize<'de>, C::Bool: Default + Deserialize<'de>",
+            serialize = "NullifierCommitment<C, COM>: Serialize, C::Bool: Serialize"
         ),
         crate = "manta_util::serde",
         deny_unknown_fields
@@ -2666,13 +3139,13 @@ where
 )]
 #[derive(derivative::Derivative)]
 #[derivative(
-    Clone(bound = "NullifierCommitment<C, COM>: Clone"),
-    Copy(bound = "NullifierCommitment<C, COM>: Copy"),
-    Debug(bound = "NullifierCommitment<C, COM>: Debug"),
-    Default(bound = "NullifierCommitment<C, COM>: Default"),
-    Eq(bound = "NullifierCommitment<C, COM>: cmp::Eq"),
-    Hash(bound = "NullifierCommitment<C, COM>: Hash"),
-    PartialEq(bound = "NullifierCommitment<C, COM>: cmp::PartialEq")
+    Clone(bound = "NullifierCommitment<C, COM>: Clone, C::Bool: Clone"),
+    Copy(bound = "NullifierCommitment<C, COM>: Copy, C::Bool: Copy"),
+    Debug(bound = "NullifierCommitment<C, COM>: Debug, C::Bool: Debug"),
+    Default(bound = "NullifierCommitment<C, COM>: Default, C::Bool: Default"),
+    Eq(bound = "NullifierCommitment<C, COM>: cmp::Eq, C::Bool: cmp::Eq"),
+    Hash(bound = "NullifierCommitment<C, COM>: Hash, C::Bool: Hash"),
+    PartialEq(bound = "NullifierCommitment<C, COM>: cmp::PartialEq, C::Bool: cmp::PartialEq")
 )]
 pub struct Nullifier<C, COM = ()>
 where
@@ -2681,6 +3154,13 @@ where
 {
     /// Nullifier Commitment
     pub commitment: NullifierCommitment<C, COM>,
+
+    /// Legacy Flag
+    ///
+    /// This flag is only revealed during a nullifier migration, otherwise it is always `false`.
+    /// See [`NULLIFIER_MIGRATION`](BaseConfiguration::NULLIFIER_MIGRATION) for more.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub is_legacy: C::Bool,
 }
 
 impl<C, COM> Nullifier<C, COM>
@@ -2688,10 +3168,13 @@ where
     C: BaseConfiguration<COM> + ?Sized,
     COM: Has<bool, Type = C::Bool>,
 {
-    /// Builds a new [`Nullifier`] from `commitment`.
+    /// Builds a new [`Nullifier`] from `commitment` and `is_legacy`.
     #[inline]
-    pub fn new(commitment: NullifierCommitment<C, COM>) -> Self {
-        Self { commitment }
+    pub fn new(commitment: NullifierCommitment<C, COM>, is_legacy: C::Bool) -> Self {
+        Self {
+            commitment,
+            is_legacy,
+        }
     }
 }
 
@@ -2702,7 +3185,12 @@ where
 {
     #[inline]
     fn eq(&self, rhs: &Self, compiler: &mut COM) -> Bool<COM> {
-        self.commitment.eq(&rhs.commitment, compiler)
+        let commitment_eq = self.commitment.eq(&rhs.commitment, compiler);
+        if C::NULLIFIER_MIGRATION {
+            commitment_eq.bitand(self.is_legacy.eq(&rhs.is_legacy, compiler), compiler)
+        } else {
+            commitment_eq
+        }
     }
 
     #[inline]
@@ -2711,6 +3199,9 @@ where
         COM: Assert,
     {
         compiler.assert_eq(&self.commitment, &rhs.commitment);
+        if C::NULLIFIER_MIGRATION {
+            compiler.assert_eq(&self.is_legacy, &rhs.is_legacy);
+        }
     }
 }
 
@@ -2788,18 +3279,15 @@ where
 {
     #[inline]
     fn eq(&self, rhs: &Self, compiler: &mut ()) -> bool {
-        self.nullifier
-            .commitment
-            .eq(&rhs.nullifier.commitment, compiler)
-            .bitand(
-                self.outgoing_note.eq(&rhs.outgoing_note, compiler),
-                compiler,
-            )
+        self.nullifier.eq(&rhs.nullifier, compiler).bitand(
+            self.outgoing_note.eq(&rhs.outgoing_note, compiler),
+            compiler,
+        )
     }
 
     #[inline]
     fn assert_equal(&self, rhs: &Self, compiler: &mut ()) {
-        compiler.assert_eq(&self.nullifier.commitment, &rhs.nullifier.commitment);
+        compiler.assert_eq(&self.nullifier, &rhs.nullifier);
         compiler.assert_eq(&self.outgoing_note, &rhs.outgoing_note);
     }
 }
@@ -2809,18 +3297,33 @@ where
     C: BaseConfiguration<COM> + Constant<COM>,
     C::Type: Configuration<Bool = bool>,
     COM: Has<bool, Type = C::Bool>,
+    C::Bool: Variable<Public, COM, Type = bool>,
     NullifierCommitment<C, COM>: Variable<Public, COM, Type = NullifierCommitment<C::Type>>,
 {
     type Type = FullNullifier<C::Type>;
 
     #[inline]
     fn new_unknown(compiler: &mut COM) -> Self {
-        Self::new(compiler.allocate_unknown())
+        Self::new(
+            compiler.allocate_unknown(),
+            if C::NULLIFIER_MIGRATION {
+                compiler.allocate_unknown()
+            } else {
+                false.as_constant(compiler)
+            },
+        )
     }
 
     #[inline]
     fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
-        Self::new(this.nullifier.commitment.as_known(compiler))
+        Self::new(
+            this.nullifier.commitment.as_known(compiler),
+            if C::NULLIFIER_MIGRATION {
+                this.nullifier.is_legacy.as_known(compiler)
+            } else {
+                false.as_constant(compiler)
+            },
+        )
     }
 }
 
@@ -2836,6 +3339,133 @@ where
     }
 }
 
+/// Nullifier Lookup
+///
+/// Read access to a set of spent [`FullNullifier`]s. Two nullifiers with the same commitment spend
+/// the same note, so lookups only compare the commitments of the nullifiers.
+pub trait NullifierLookup<C>
+where
+    C: Configuration<Bool = bool>,
+{
+    /// Returns `true` if a nullifier with the same commitment as `nullifier` is in `self`.
+    fn contains(&self, nullifier: &FullNullifier<C>) -> bool;
+
+    /// Forks `self`, returning a [`NullifierOverlay`] which inserts nullifiers without modifying
+    /// `self`.
+    #[inline]
+    fn fork(&self) -> NullifierOverlay<'_, C, Self> {
+        NullifierOverlay::new(self)
+    }
+}
+
+/// Nullifier Overlay
+///
+/// Copy-on-write view of a [`NullifierLookup`] which reads through to the base set and keeps the
+/// nullifiers inserted into it on the side. An overlay is built with [`NullifierLookup::fork`] and
+/// is either [`commit`](Self::commit)ted or [`discard`](Self::discard)ed.
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = "S: Debug, FullNullifier<C>: Debug"))]
+pub struct NullifierOverlay<'s, C, S>
+where
+    C: Configuration<Bool = bool>,
+    S: ?Sized,
+{
+    /// Base Nullifier Set
+    base: &'s S,
+
+    /// Inserted Nullifiers in Insertion Order
+    inserted: Vec<FullNullifier<C>>,
+}
+
+impl<'s, C, S> NullifierOverlay<'s, C, S>
+where
+    C: Configuration<Bool = bool>,
+    S: NullifierLookup<C> + ?Sized,
+{
+    /// Builds a new [`NullifierOverlay`] on top of `base` with no inserted nullifiers.
+    #[inline]
+    fn new(base: &'s S) -> Self {
+        Self {
+            base,
+            inserted: Vec::new(),
+        }
+    }
+
+    /// Returns the base nullifier set of `self`.
+    #[inline]
+    pub fn base(&self) -> &'s S {
+        self.base
+    }
+
+    /// Returns the nullifiers inserted into `self`, in insertion order.
+    #[inline]
+    pub fn inserted(&self) -> &[FullNullifier<C>] {
+        &self.inserted
+    }
+
+    /// Inserts `nullifier` into `self`, returning `false` if [`contains`](NullifierLookup::contains)
+    /// was already `true` for it.
+    #[inline]
+    pub fn insert(&mut self, nullifier: FullNullifier<C>) -> bool {
+        if self.contains(&nullifier) {
+            return false;
+        }
+        self.inserted.push(nullifier);
+        true
+    }
+
+    /// Finishes the overlay, returning the nullifiers inserted into `self` in insertion order so
+    /// that they can be inserted into the base set.
+    #[inline]
+    pub fn commit(self) -> Vec<FullNullifier<C>> {
+        self.inserted
+    }
+
+    /// Drops the nullifiers inserted into `self`, leaving the base set untouched.
+    #[inline]
+    pub fn discard(self) {}
+}
+
+impl<'s, C, S> NullifierLookup<C> for NullifierOverlay<'s, C, S>
+where
+    C: Configuration<Bool = bool>,
+    S: NullifierLookup<C> + ?Sized,
+{
+    #[inline]
+    fn contains(&self, nullifier: &FullNullifier<C>) -> bool {
+        self.inserted
+            .iter()
+            .any(|inserted| !inserted.is_independent(nullifier))
+            || self.base.contains(nullifier)
+    }
+}
+
+impl<C> Decode for FullNullifier<C>
+where
+    C: Configuration<Bool = bool>,
+    NullifierCommitment<C>: Decode,
+    OutgoingNote<C>: Decode,
+{
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        let commitment = Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?;
+        let is_legacy = if C::NULLIFIER_MIGRATION {
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?
+        } else {
+            false
+        };
+        Ok(Self::new(
+            Nullifier::new(commitment, is_legacy),
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+        ))
+    }
+}
+
 impl<C> Encode for FullNullifier<C>
 where
     C: Configuration<Bool = bool>,
@@ -2848,6 +3478,9 @@ where
         W: Write,
     {
         self.nullifier.commitment.encode(&mut writer)?;
+        if C::NULLIFIER_MIGRATION {
+            self.nullifier.is_legacy.encode(&mut writer)?;
+        }
         self.outgoing_note.encode(&mut writer)?;
         Ok(())
     }
@@ -2856,10 +3489,13 @@ where
 impl<C, P> Input<P> for FullNullifier<C>
 where
     C: Configuration<Bool = bool>,
-    P: HasInput<NullifierCommitment<C>> + ?Sized,
+    P: HasInput<NullifierCommitment<C>> + HasInput<bool> + ?Sized,
 {
     #[inline]
     fn extend(&self, input: &mut P::Input) {
-        P::extend(input, &self.nullifier.commitment);
+        <P as HasInput<NullifierCommitment<C>>>::extend(input, &self.nullifier.commitment);
+        if C::NULLIFIER_MIGRATION {
+            <P as HasInput<bool>>::extend(input, &self.nullifier.is_legacy);
+        }
     }
 }
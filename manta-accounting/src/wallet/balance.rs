@@ -20,12 +20,12 @@
 //! protocol. Applications which define balances beyond fungible assets should extend these
 //! abstractions.
 
-use crate::asset::{Asset, AssetList};
+use crate::asset::{Asset, AssetList, BalanceError, CheckedBalance};
 use alloc::collections::btree_map::{BTreeMap, Entry as BTreeMapEntry};
 use core::ops::AddAssign;
 use manta_util::{
     iter::{ConvertItemRef, ExactSizeIterable, RefItem},
-    num::CheckedSub,
+    num::{CheckedAdd, CheckedSub},
 };
 
 #[cfg(feature = "std")]
@@ -64,6 +64,23 @@ pub trait BalanceState<I, V>:
         assets.into_iter().for_each(move |a| self.deposit(a));
     }
 
+    /// Deposits `asset` into the balance state like [`deposit`](Self::deposit), returning a
+    /// [`BalanceError`] and leaving the balance state unchanged if the balance of the asset stored
+    /// at `asset.id` would overflow.
+    fn try_deposit(&mut self, asset: Asset<I, V>) -> Result<(), BalanceError>;
+
+    /// Deposits every asset in `assets` into the balance state, stopping at the first asset whose
+    /// deposit would overflow the balance.
+    #[inline]
+    fn try_deposit_all<A>(&mut self, assets: A) -> Result<(), BalanceError>
+    where
+        A: IntoIterator<Item = Asset<I, V>>,
+    {
+        assets
+            .into_iter()
+            .try_for_each(move |asset| self.try_deposit(asset))
+    }
+
     /// Withdraws `asset` from the balance state returning `false` if it would overdraw the balance.
     fn withdraw(&mut self, asset: Asset<I, V>) -> bool;
 
@@ -80,7 +97,7 @@ pub trait BalanceState<I, V>:
 impl<I, V> BalanceState<I, V> for AssetList<I, V>
 where
     I: Ord,
-    V: AddAssign + Clone + Default + PartialEq,
+    V: AddAssign + CheckedAdd<Output = V> + Clone + Default + PartialEq,
     for<'v> &'v V: CheckedSub<Output = V>,
 {
     #[inline]
@@ -93,6 +110,11 @@ where
         self.deposit(asset);
     }
 
+    #[inline]
+    fn try_deposit(&mut self, asset: Asset<I, V>) -> Result<(), BalanceError> {
+        self.try_deposit(asset)
+    }
+
     #[inline]
     fn withdraw(&mut self, asset: Asset<I, V>) -> bool {
         self.withdraw(&asset)
@@ -138,6 +160,25 @@ macro_rules! impl_balance_state_map_body {
             }
         }
 
+        #[inline]
+        fn try_deposit(&mut self, asset: Asset<$I, $V>) -> Result<(), BalanceError> {
+            if asset.value == Default::default() {
+                return Ok(());
+            }
+            match self.entry(asset.id) {
+                $entry::Vacant(entry) => {
+                    entry.insert(asset.value);
+                }
+                $entry::Occupied(entry) => {
+                    let balance = entry.into_mut();
+                    let mut sum = CheckedBalance::new(balance.clone());
+                    sum.checked_add(asset.value)?;
+                    *balance = sum.into_inner();
+                }
+            }
+            Ok(())
+        }
+
         #[inline]
         fn withdraw(&mut self, asset: Asset<$I, $V>) -> bool {
             if asset.value != Default::default() {
@@ -184,7 +225,7 @@ pub type BTreeMapBalanceState<I, V> = BTreeMap<I, V>;
 impl<I, V> BalanceState<I, V> for BTreeMapBalanceState<I, V>
 where
     I: Ord,
-    V: AddAssign + Clone + Default + PartialEq,
+    V: AddAssign + CheckedAdd<Output = V> + Clone + Default + PartialEq,
     for<'v> &'v V: CheckedSub<Output = V>,
 {
     impl_balance_state_map_body! { I, V, BTreeMapEntry }
@@ -200,7 +241,7 @@ pub type HashMapBalanceState<I, V, S = RandomState> = HashMap<I, V, S>;
 impl<I, V, S> BalanceState<I, V> for HashMapBalanceState<I, V, S>
 where
     I: Eq + Hash + Ord,
-    V: AddAssign + Clone + Default + PartialEq,
+    V: AddAssign + CheckedAdd<Output = V> + Clone + Default + PartialEq,
     for<'v> &'v V: CheckedSub<Output = V>,
     S: BuildHasher + Default,
 {
@@ -211,7 +252,10 @@ where
 #[cfg(any(feature = "test", test))]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
 pub mod test {
-    use crate::{asset::Asset, wallet::BalanceState};
+    use crate::{
+        asset::{Asset, BalanceError},
+        wallet::BalanceState,
+    };
     use core::{fmt::Debug, ops::Add};
     use manta_crypto::rand::{CryptoRng, RngCore, Sample};
 
@@ -239,6 +283,32 @@ pub mod test {
             "Initial and final balances should have been equal."
         );
     }
+
+    /// Asserts that depositing the non-zero value `max` twice into the same asset overflows
+    /// and leaves the balance of that asset unchanged.
+    #[inline]
+    pub fn assert_overflowing_deposit_should_fail<I, V, S, R>(max: V, rng: &mut R)
+    where
+        I: Clone + Sample,
+        V: Clone + Debug + PartialEq,
+        S: BalanceState<I, V>,
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let mut state = S::default();
+        let id = I::gen(rng);
+        state.deposit(Asset::new(id.clone(), max.clone()));
+        assert_eq!(
+            state.try_deposit(Asset::new(id.clone(), max.clone())),
+            Err(BalanceError::Overflow),
+            "Depositing past the maximum asset value should have overflowed."
+        );
+        assert_eq!(
+            state.balance(&id),
+            max,
+            "Balance should not have changed after an overflowing deposit."
+        );
+    }
+
     /// Asserts that a maximal withdraw that leaves the state with no value should delete its memory
     /// for this process.
     #[inline]
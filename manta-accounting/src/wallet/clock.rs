@@ -0,0 +1,155 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Ledger Clock
+//!
+//! The signer has no clock of its own, so every time-dependent feature, like leases, capabilities,
+//! backup reminders and retention policies, takes the current time as an argument. Taking it from
+//! the clock of the device lets a badly set or tampered clock expire leases early or keep them
+//! alive forever. The [`LedgerClock`] instead observes the [`LedgerTime`] reported by the ledger
+//! during synchronization, compares it against the local clock to warn the host application about
+//! [`ClockSkew`], and only uses the local clock to measure the time elapsed since the last
+//! observation. Host applications should pass [`LedgerClock::now`] to the signer wherever it asks
+//! for the current time.
+
+use crate::wallet::ledger::LedgerTime;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Clock Skew
+///
+/// Difference between the local clock and the ledger time, in milliseconds, when it is larger
+/// than the tolerance of the [`LedgerClock`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ClockSkew {
+    /// Local Clock is Ahead of the Ledger
+    Ahead(u64),
+
+    /// Local Clock is Behind the Ledger
+    Behind(u64),
+}
+
+impl ClockSkew {
+    /// Computes the [`ClockSkew`] of the `local` time against the ledger `timestamp`, returning
+    /// `None` if they are at most `tolerance` milliseconds apart.
+    #[inline]
+    pub fn between(local: u64, timestamp: u64, tolerance: u64) -> Option<Self> {
+        if local > timestamp.saturating_add(tolerance) {
+            Some(Self::Ahead(local - timestamp))
+        } else if timestamp > local.saturating_add(tolerance) {
+            Some(Self::Behind(timestamp - local))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the size of the skew in milliseconds.
+    #[inline]
+    pub fn millis(&self) -> u64 {
+        match self {
+            Self::Ahead(millis) | Self::Behind(millis) => *millis,
+        }
+    }
+}
+
+/// Ledger Clock
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct LedgerClock {
+    /// Skew Tolerance in Milliseconds
+    tolerance: u64,
+
+    /// Last Observed Ledger Time and the Local Time it was Observed at
+    observation: Option<(LedgerTime, u64)>,
+}
+
+impl LedgerClock {
+    /// Default Skew Tolerance
+    ///
+    /// Five minutes, which covers the block times of the ledgers we know of and the latency of a
+    /// synchronization.
+    pub const DEFAULT_TOLERANCE: u64 = 5 * 60 * 1000;
+
+    /// Builds a new [`LedgerClock`] which warns about skews larger than `tolerance` milliseconds.
+    #[inline]
+    pub fn new(tolerance: u64) -> Self {
+        Self {
+            tolerance,
+            observation: None,
+        }
+    }
+
+    /// Returns the skew tolerance of `self` in milliseconds.
+    #[inline]
+    pub fn tolerance(&self) -> u64 {
+        self.tolerance
+    }
+
+    /// Returns the last [`LedgerTime`] observed by `self`.
+    #[inline]
+    pub fn ledger_time(&self) -> Option<LedgerTime> {
+        self.observation.map(|(time, _)| time)
+    }
+
+    /// Returns the [`ClockSkew`] of the local clock as of the last observation.
+    #[inline]
+    pub fn skew(&self) -> Option<ClockSkew> {
+        let (time, local) = self.observation?;
+        ClockSkew::between(local, time.timestamp, self.tolerance)
+    }
+
+    /// Records `time` as reported by the ledger at `local` time, returning the [`ClockSkew`] of
+    /// the local clock if it is outside the tolerance. Observations which go back in ledger height
+    /// are ignored, since they can only come from a stale or misbehaving ledger connection.
+    #[inline]
+    pub fn observe(&mut self, time: LedgerTime, local: u64) -> Option<ClockSkew> {
+        match self.observation {
+            Some((last, _)) if time.height < last.height || time.timestamp < last.timestamp => {}
+            _ => self.observation = Some((time, local)),
+        }
+        self.skew()
+    }
+
+    /// Returns the current time according to the ledger, extrapolated from the last observation
+    /// using the time elapsed on the `local` clock since then, or `None` if `self` has not
+    /// observed the ledger yet. A local clock which was turned back since the last observation
+    /// does not move the time before the observed ledger time.
+    #[inline]
+    pub fn now(&self, local: u64) -> Option<u64> {
+        let (time, observed) = self.observation?;
+        Some(
+            time.timestamp
+                .saturating_add(local.saturating_sub(observed)),
+        )
+    }
+}
+
+impl Default for LedgerClock {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TOLERANCE)
+    }
+}
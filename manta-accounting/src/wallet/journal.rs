@@ -0,0 +1,805 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Accounting Journal
+//!
+//! A [`Journal`] turns the [`BalanceUpdate`]s returned by the signer into per-asset entries which
+//! can be exported for accounting and tax tools. Every update is netted per asset, so a payment
+//! shows up as one debit of the paid value instead of the notes it spent and the change it
+//! received. The transaction id and the counterparty of the entries are attached by the caller
+//! whenever they are known, for instance after submitting a transaction to the ledger, and the
+//! users of the tools can label and categorize the entries with [`Annotation`]s which are
+//! imported back into the journal.
+//!
+//! # Layouts
+//!
+//! The entries and balances are exported as lists of records, which are written either as CSV
+//! with a header row, by [`write_entries_csv`] and [`write_balances_csv`], or as JSON arrays of
+//! objects with the same field names, by serializing the records. Asset ids, asset values,
+//! transaction ids and counterparties are written as text by an [`Encoding`]. Missing values are
+//! empty in CSV and `null` in JSON.
+//!
+//! | Entry Field         | Contents                                                       |
+//! |---------------------|----------------------------------------------------------------|
+//! | `index`             | Position of the entry in the journal, used to annotate it      |
+//! | `timestamp`         | Time of the update, in milliseconds since the Unix epoch       |
+//! | `asset_id`          | Asset id                                                       |
+//! | `kind`              | `Credit` or `Debit`                                            |
+//! | `value`             | Credited or debited value                                      |
+//! | `transaction_id`    | Transaction id, if known                                       |
+//! | `counterparty_kind` | `Address` for shielded addresses and `Account` for public ones |
+//! | `counterparty`      | Counterparty, if known                                         |
+//! | `label`             | Label of the entry                                             |
+//! | `category`          | Category of the entry                                          |
+//!
+//! Balances only have the `asset_id` and `value` fields. Annotations are imported from records
+//! with the `index`, `label` and `category` fields, and any other field is ignored, so an
+//! exported list of entries can be edited and imported back as it is.
+
+use crate::{
+    transfer::{Address, Asset, Configuration},
+    wallet::signer::BalanceUpdate,
+};
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Debug, Write},
+    hash::Hash,
+    mem,
+    ops::Range,
+};
+use manta_util::num::CheckedSub;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Entry Kind
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum EntryKind {
+    /// Credit to the Balance of the Wallet
+    Credit,
+
+    /// Debit from the Balance of the Wallet
+    Debit,
+}
+
+impl EntryKind {
+    /// Returns the name of `self` in the exported layouts.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Credit => "Credit",
+            Self::Debit => "Debit",
+        }
+    }
+}
+
+/// Counterparty Kind
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum CounterpartyKind {
+    /// Shielded Address
+    Address,
+
+    /// Public Account
+    Account,
+}
+
+impl CounterpartyKind {
+    /// Returns the name of `self` in the exported layouts.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Address => "Address",
+            Self::Account => "Account",
+        }
+    }
+}
+
+/// Counterparty
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Address<C>: Deserialize<'de>, C::AccountId: Deserialize<'de>",
+            serialize = "Address<C>: Serialize, C::AccountId: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Address<C>: Clone, C::AccountId: Clone"),
+    Copy(bound = "Address<C>: Copy, C::AccountId: Copy"),
+    Debug(bound = "Address<C>: Debug, C::AccountId: Debug"),
+    Eq(bound = "Address<C>: Eq, C::AccountId: Eq"),
+    Hash(bound = "Address<C>: Hash, C::AccountId: Hash"),
+    PartialEq(bound = "Address<C>: PartialEq, C::AccountId: PartialEq")
+)]
+pub enum Counterparty<C>
+where
+    C: Configuration,
+{
+    /// Shielded Address, for private transfers
+    Address(Address<C>),
+
+    /// Public Account, for transfers in and out of the shielded pool
+    Account(C::AccountId),
+}
+
+impl<C> Counterparty<C>
+where
+    C: Configuration,
+{
+    /// Returns the [`CounterpartyKind`] of `self`.
+    #[inline]
+    pub fn kind(&self) -> CounterpartyKind {
+        match self {
+            Self::Address(_) => CounterpartyKind::Address,
+            Self::Account(_) => CounterpartyKind::Account,
+        }
+    }
+}
+
+/// Entry Annotation
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Annotation {
+    /// Label
+    pub label: Option<String>,
+
+    /// Category
+    pub category: Option<String>,
+}
+
+impl Annotation {
+    /// Returns `true` if `self` has neither a label nor a category.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.label.is_none() && self.category.is_none()
+    }
+}
+
+/// Journal Entry
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Asset<C>: Deserialize<'de>, T: Deserialize<'de>, Counterparty<C>: Deserialize<'de>",
+            serialize = "Asset<C>: Serialize, T: Serialize, Counterparty<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Asset<C>: Clone, T: Clone, Counterparty<C>: Clone"),
+    Debug(bound = "Asset<C>: Debug, T: Debug, Counterparty<C>: Debug"),
+    Eq(bound = "Asset<C>: Eq, T: Eq, Counterparty<C>: Eq"),
+    Hash(bound = "Asset<C>: Hash, T: Hash, Counterparty<C>: Hash"),
+    PartialEq(bound = "Asset<C>: PartialEq, T: PartialEq, Counterparty<C>: PartialEq")
+)]
+pub struct JournalEntry<C, T>
+where
+    C: Configuration,
+{
+    /// Time of the Update in Milliseconds
+    pub timestamp: Option<u64>,
+
+    /// Entry Kind
+    pub kind: EntryKind,
+
+    /// Credited or Debited Asset
+    pub asset: Asset<C>,
+
+    /// Transaction Id
+    pub transaction_id: Option<T>,
+
+    /// Counterparty
+    pub counterparty: Option<Counterparty<C>>,
+
+    /// Annotation
+    pub annotation: Annotation,
+}
+
+/// Journal Encoding
+///
+/// Text encoding of the values of the [`Journal`] in the exported layouts.
+pub trait Encoding<C, T>
+where
+    C: Configuration,
+{
+    /// Encodes the asset `id`.
+    fn asset_id(&self, id: &C::AssetId) -> String;
+
+    /// Encodes the asset `value`.
+    fn asset_value(&self, value: &C::AssetValue) -> String;
+
+    /// Encodes the transaction `id`.
+    fn transaction_id(&self, id: &T) -> String;
+
+    /// Encodes the shielded `address`.
+    fn address(&self, address: &Address<C>) -> String;
+
+    /// Encodes the public `account`.
+    fn account(&self, account: &C::AccountId) -> String;
+
+    /// Encodes `counterparty`.
+    #[inline]
+    fn counterparty(&self, counterparty: &Counterparty<C>) -> String {
+        match counterparty {
+            Counterparty::Address(address) => self.address(address),
+            Counterparty::Account(account) => self.account(account),
+        }
+    }
+}
+
+/// Entry Record
+///
+/// Exported form of a [`JournalEntry`], see the [module documentation](self) for its layout.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct EntryRecord {
+    /// Entry Index
+    pub index: u64,
+
+    /// Time of the Update in Milliseconds
+    pub timestamp: Option<u64>,
+
+    /// Asset Id
+    pub asset_id: String,
+
+    /// Entry Kind
+    pub kind: EntryKind,
+
+    /// Credited or Debited Value
+    pub value: String,
+
+    /// Transaction Id
+    pub transaction_id: Option<String>,
+
+    /// Counterparty Kind
+    pub counterparty_kind: Option<CounterpartyKind>,
+
+    /// Counterparty
+    pub counterparty: Option<String>,
+
+    /// Label
+    pub label: Option<String>,
+
+    /// Category
+    pub category: Option<String>,
+}
+
+/// Balance Record
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct BalanceRecord {
+    /// Asset Id
+    pub asset_id: String,
+
+    /// Balance
+    pub value: String,
+}
+
+/// Annotation Record
+///
+/// Unlike the other records, annotation records ignore unknown fields, so that [`EntryRecord`]s
+/// can be imported as annotation records.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde")
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct AnnotationRecord {
+    /// Entry Index
+    pub index: u64,
+
+    /// Label
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub label: Option<String>,
+
+    /// Category
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub category: Option<String>,
+}
+
+impl From<EntryRecord> for AnnotationRecord {
+    #[inline]
+    fn from(record: EntryRecord) -> Self {
+        Self {
+            index: record.index,
+            label: record.label,
+            category: record.category,
+        }
+    }
+}
+
+/// Import Error
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ImportError {
+    /// Missing Header
+    ///
+    /// The CSV input does not have a header row.
+    MissingHeader,
+
+    /// Missing Column
+    ///
+    /// The header row of the CSV input does not have the given column.
+    MissingColumn(&'static str),
+
+    /// Malformed Row
+    ///
+    /// The row at the given line of the CSV input has an unterminated quoted field or a
+    /// different number of fields than the header row.
+    MalformedRow(usize),
+
+    /// Invalid Index
+    ///
+    /// The index of the row at the given line of the CSV input is not an integer.
+    InvalidIndex(usize),
+
+    /// Unknown Entry
+    ///
+    /// The journal has no entry at the given index.
+    UnknownEntry(u64),
+}
+
+/// Accounting Journal
+///
+/// Entries are only ever appended to the journal, so their indices never change.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "JournalEntry<C, T>: Deserialize<'de>",
+            serialize = "JournalEntry<C, T>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "JournalEntry<C, T>: Clone"),
+    Debug(bound = "JournalEntry<C, T>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "JournalEntry<C, T>: Eq"),
+    Hash(bound = "JournalEntry<C, T>: Hash"),
+    PartialEq(bound = "JournalEntry<C, T>: PartialEq")
+)]
+pub struct Journal<C, T>
+where
+    C: Configuration,
+{
+    /// Entries
+    entries: Vec<JournalEntry<C, T>>,
+}
+
+impl<C, T> Journal<C, T>
+where
+    C: Configuration,
+    C::AssetValue: CheckedSub<Output = C::AssetValue>,
+{
+    /// Returns the entries of `self`.
+    #[inline]
+    pub fn entries(&self) -> &[JournalEntry<C, T>] {
+        &self.entries
+    }
+
+    /// Returns the entry of `self` at `index`.
+    #[inline]
+    pub fn get(&self, index: u64) -> Option<&JournalEntry<C, T>> {
+        self.entries.get(usize::try_from(index).ok()?)
+    }
+
+    /// Returns the number of entries in `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if `self` has no entries.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the balances of all the assets of `self`, ordered by asset id, as the sum of
+    /// their credits minus the sum of their debits.
+    #[inline]
+    pub fn balances(&self) -> BTreeMap<C::AssetId, C::AssetValue> {
+        let mut balances = BTreeMap::<_, C::AssetValue>::new();
+        for entry in &self.entries {
+            let balance = balances.entry(entry.asset.id.clone()).or_default();
+            match entry.kind {
+                EntryKind::Credit => *balance += entry.asset.value.clone(),
+                EntryKind::Debit => {
+                    *balance = balance
+                        .clone()
+                        .checked_sub(entry.asset.value.clone())
+                        .unwrap_or_default()
+                }
+            }
+        }
+        balances
+    }
+
+    /// Appends an entry of `kind` for `asset` at `timestamp` to `self`.
+    #[inline]
+    fn push(&mut self, timestamp: Option<u64>, kind: EntryKind, asset: Asset<C>) {
+        self.entries.push(JournalEntry {
+            timestamp,
+            kind,
+            asset,
+            transaction_id: None,
+            counterparty: None,
+            annotation: Default::default(),
+        });
+    }
+
+    /// Records the balance `update` returned by the signer at `timestamp`, returning the range of
+    /// indices of the new entries, one for every asset whose balance changed.
+    ///
+    /// Partial updates are netted per asset, and full updates are compared against the
+    /// [`balances`](Self::balances) of `self`.
+    #[inline]
+    pub fn record(&mut self, update: &BalanceUpdate<C>, timestamp: Option<u64>) -> Range<u64> {
+        let start = self.entries.len() as u64;
+        let mut changes = BTreeMap::<_, (C::AssetValue, C::AssetValue)>::new();
+        match update {
+            BalanceUpdate::Partial { deposit, withdraw } => {
+                for asset in deposit {
+                    changes.entry(asset.id.clone()).or_default().0 += asset.value.clone();
+                }
+                for asset in withdraw {
+                    changes.entry(asset.id.clone()).or_default().1 += asset.value.clone();
+                }
+            }
+            BalanceUpdate::Full { assets } => {
+                for (id, value) in self.balances() {
+                    changes.entry(id).or_default().1 = value;
+                }
+                for asset in assets {
+                    changes.entry(asset.id.clone()).or_default().0 += asset.value.clone();
+                }
+            }
+        }
+        for (id, (credit, debit)) in changes {
+            if credit > debit {
+                if let Some(value) = credit.checked_sub(debit) {
+                    self.push(timestamp, EntryKind::Credit, Asset::<C>::new(id, value));
+                }
+            } else if debit > credit {
+                if let Some(value) = debit.checked_sub(credit) {
+                    self.push(timestamp, EntryKind::Debit, Asset::<C>::new(id, value));
+                }
+            }
+        }
+        start..self.entries.len() as u64
+    }
+
+    /// Attaches `transaction_id` and `counterparty` to the entries of `self` at `indices`, for
+    /// instance to the entries recorded for the update which followed the submission of a
+    /// transaction.
+    #[inline]
+    pub fn attribute(
+        &mut self,
+        indices: Range<u64>,
+        transaction_id: T,
+        counterparty: Option<Counterparty<C>>,
+    ) where
+        T: Clone,
+    {
+        let start = usize::try_from(indices.start).unwrap_or(usize::MAX);
+        let end = usize::try_from(indices.end).unwrap_or(usize::MAX);
+        for entry in self.entries.iter_mut().take(end).skip(start) {
+            entry.transaction_id = Some(transaction_id.clone());
+            entry.counterparty = counterparty.clone();
+        }
+    }
+
+    /// Replaces the annotation of the entry at `index` with `annotation`, returning the previous
+    /// annotation, or `None` if `self` has no entry at `index`.
+    #[inline]
+    pub fn annotate(&mut self, index: u64, annotation: Annotation) -> Option<Annotation> {
+        let entry = self.entries.get_mut(usize::try_from(index).ok()?)?;
+        Some(mem::replace(&mut entry.annotation, annotation))
+    }
+
+    /// Imports the annotations in `records`, returning the number of imported annotations.
+    ///
+    /// Either all the annotations are imported or none of them, when one of the records points
+    /// at an entry which is not in `self`.
+    #[inline]
+    pub fn import_annotations<I>(&mut self, records: I) -> Result<usize, ImportError>
+    where
+        I: IntoIterator<Item = AnnotationRecord>,
+    {
+        let records = records.into_iter().collect::<Vec<_>>();
+        if let Some(record) = records
+            .iter()
+            .find(|record| self.get(record.index).is_none())
+        {
+            return Err(ImportError::UnknownEntry(record.index));
+        }
+        let count = records.len();
+        for record in records {
+            self.annotate(
+                record.index,
+                Annotation {
+                    label: record.label,
+                    category: record.category,
+                },
+            );
+        }
+        Ok(count)
+    }
+
+    /// Exports the entries of `self` with `encoding`.
+    #[inline]
+    pub fn entry_records<E>(&self, encoding: &E) -> Vec<EntryRecord>
+    where
+        E: Encoding<C, T>,
+    {
+        self.entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| EntryRecord {
+                index: index as u64,
+                timestamp: entry.timestamp,
+                asset_id: encoding.asset_id(&entry.asset.id),
+                kind: entry.kind,
+                value: encoding.asset_value(&entry.asset.value),
+                transaction_id: entry
+                    .transaction_id
+                    .as_ref()
+                    .map(|id| encoding.transaction_id(id)),
+                counterparty_kind: entry.counterparty.as_ref().map(Counterparty::kind),
+                counterparty: entry
+                    .counterparty
+                    .as_ref()
+                    .map(|counterparty| encoding.counterparty(counterparty)),
+                label: entry.annotation.label.clone(),
+                category: entry.annotation.category.clone(),
+            })
+            .collect()
+    }
+
+    /// Exports the [`balances`](Self::balances) of `self` with `encoding`.
+    #[inline]
+    pub fn balance_records<E>(&self, encoding: &E) -> Vec<BalanceRecord>
+    where
+        E: Encoding<C, T>,
+    {
+        self.balances()
+            .iter()
+            .map(|(id, value)| BalanceRecord {
+                asset_id: encoding.asset_id(id),
+                value: encoding.asset_value(value),
+            })
+            .collect()
+    }
+}
+
+/// Entry Record Columns
+const ENTRY_COLUMNS: [&str; 10] = [
+    "index",
+    "timestamp",
+    "asset_id",
+    "kind",
+    "value",
+    "transaction_id",
+    "counterparty_kind",
+    "counterparty",
+    "label",
+    "category",
+];
+
+/// Writes `field` to `writer`, quoting it if it contains a separator, a quote or a line break.
+#[inline]
+fn write_csv_field<W>(writer: &mut W, field: &str) -> fmt::Result
+where
+    W: Write,
+{
+    if field.contains([',', '"', '\n', '\r']) {
+        writer.write_char('"')?;
+        for c in field.chars() {
+            if c == '"' {
+                writer.write_char('"')?;
+            }
+            writer.write_char(c)?;
+        }
+        writer.write_char('"')
+    } else {
+        writer.write_str(field)
+    }
+}
+
+/// Writes a CSV row with `fields` to `writer`.
+#[inline]
+fn write_csv_row<'f, W, I>(writer: &mut W, fields: I) -> fmt::Result
+where
+    W: Write,
+    I: IntoIterator<Item = &'f str>,
+{
+    for (i, field) in fields.into_iter().enumerate() {
+        if i > 0 {
+            writer.write_char(',')?;
+        }
+        write_csv_field(writer, field)?;
+    }
+    writer.write_char('\n')
+}
+
+/// Writes `records` to `writer` as CSV with a header row.
+#[inline]
+pub fn write_entries_csv<W>(writer: &mut W, records: &[EntryRecord]) -> fmt::Result
+where
+    W: Write,
+{
+    write_csv_row(writer, ENTRY_COLUMNS)?;
+    for record in records {
+        let index = record.index.to_string();
+        let timestamp = record
+            .timestamp
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        write_csv_row(
+            writer,
+            [
+                index.as_str(),
+                timestamp.as_str(),
+                record.asset_id.as_str(),
+                record.kind.as_str(),
+                record.value.as_str(),
+                record.transaction_id.as_deref().unwrap_or_default(),
+                record
+                    .counterparty_kind
+                    .as_ref()
+                    .map(CounterpartyKind::as_str)
+                    .unwrap_or_default(),
+                record.counterparty.as_deref().unwrap_or_default(),
+                record.label.as_deref().unwrap_or_default(),
+                record.category.as_deref().unwrap_or_default(),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes `records` to `writer` as CSV with a header row.
+#[inline]
+pub fn write_balances_csv<W>(writer: &mut W, records: &[BalanceRecord]) -> fmt::Result
+where
+    W: Write,
+{
+    write_csv_row(writer, ["asset_id", "value"])?;
+    for record in records {
+        write_csv_row(writer, [record.asset_id.as_str(), record.value.as_str()])?;
+    }
+    Ok(())
+}
+
+/// Parses the CSV `input` into rows of fields, paired with the line at which they start.
+#[inline]
+fn parse_csv(input: &str) -> Result<Vec<(usize, Vec<String>)>, ImportError> {
+    let mut rows = Vec::new();
+    let mut chars = input.chars().peekable();
+    let mut line = 1;
+    while chars.peek().is_some() {
+        let start = line;
+        let mut row = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        loop {
+            match chars.next() {
+                Some('"') if quoted => {
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        field.push('"');
+                    } else {
+                        quoted = false;
+                    }
+                }
+                Some('"') if field.is_empty() => quoted = true,
+                Some(',') if !quoted => row.push(mem::take(&mut field)),
+                Some('\r') if !quoted && chars.peek() == Some(&'\n') => {}
+                Some('\n') if !quoted => {
+                    line += 1;
+                    break;
+                }
+                Some(c) => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+                None if quoted => return Err(ImportError::MalformedRow(start)),
+                None => break,
+            }
+        }
+        row.push(field);
+        if row.len() > 1 || !row[0].is_empty() {
+            rows.push((start, row));
+        }
+    }
+    Ok(rows)
+}
+
+/// Reads the annotation records from the CSV `input`, which needs a header row with at least the
+/// `index`, `label` and `category` columns. Empty labels and categories are read as missing.
+#[inline]
+pub fn read_annotations_csv(input: &str) -> Result<Vec<AnnotationRecord>, ImportError> {
+    let mut rows = parse_csv(input)?.into_iter();
+    let (_, header) = rows.next().ok_or(ImportError::MissingHeader)?;
+    let column = |name| {
+        header
+            .iter()
+            .position(|column| column == name)
+            .ok_or(ImportError::MissingColumn(name))
+    };
+    let index = column("index")?;
+    let label = column("label")?;
+    let category = column("category")?;
+    rows.map(|(line, mut row)| {
+        if row.len() != header.len() {
+            return Err(ImportError::MalformedRow(line));
+        }
+        let index = row[index]
+            .parse()
+            .map_err(|_| ImportError::InvalidIndex(line))?;
+        let mut take =
+            |column: usize| Some(mem::take(&mut row[column])).filter(|field| !field.is_empty());
+        Ok(AnnotationRecord {
+            index,
+            label: take(label),
+            category: take(category),
+        })
+    })
+    .collect()
+}
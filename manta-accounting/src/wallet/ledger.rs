@@ -16,8 +16,13 @@
 
 //! Ledger Connection
 
-use core::{fmt::Debug, hash::Hash};
-use manta_util::future::LocalBoxFutureResult;
+use crate::transfer::{fee::FeeSchedule, upgrade::UpgradeSchedule};
+use core::{
+    fmt::{self, Debug, Display},
+    hash::Hash,
+    time::Duration,
+};
+use manta_util::{future::LocalBoxFutureResult, vec::Vec};
 
 #[cfg(feature = "serde")]
 use manta_util::serde::{Deserialize, Serialize};
@@ -48,6 +53,95 @@ pub trait Connection {
 /// (but not necessarily efficient) to copy a checkpoint value and share it across threads.
 pub trait Checkpoint: Clone + Default + PartialOrd + Send + Sync {}
 
+/// Implements a typed ledger index `$type` over the integer type `$int`.
+macro_rules! impl_ledger_index {
+    ($(#[$meta:meta])* $type:ident, $int:ty) => {
+        $(#[$meta])*
+        #[cfg_attr(
+            feature = "serde",
+            derive(Deserialize, Serialize),
+            serde(crate = "manta_util::serde", transparent)
+        )]
+        #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        pub struct $type($int);
+
+        impl $type {
+            /// Builds a new index from `index`.
+            #[inline]
+            pub const fn new(index: $int) -> Self {
+                Self(index)
+            }
+
+            /// Returns the underlying integer of `self`.
+            #[inline]
+            pub const fn get(self) -> $int {
+                self.0
+            }
+
+            /// Returns the index which comes `count`-many positions after `self`.
+            #[inline]
+            pub const fn advance(self, count: $int) -> Self {
+                Self(self.0 + count)
+            }
+
+            /// Returns the index which comes `count`-many positions before `self`, stopping at
+            /// zero.
+            #[inline]
+            pub const fn rewind(self, count: $int) -> Self {
+                Self(self.0.saturating_sub(count))
+            }
+
+            /// Returns the number of positions from `origin` to `self`, returning `None` if
+            /// `origin` comes after `self`.
+            #[inline]
+            pub const fn since(self, origin: Self) -> Option<$int> {
+                self.0.checked_sub(origin.0)
+            }
+        }
+
+        impl Display for $type {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                Display::fmt(&self.0, f)
+            }
+        }
+
+        impl From<$int> for $type {
+            #[inline]
+            fn from(index: $int) -> Self {
+                Self::new(index)
+            }
+        }
+
+        impl From<$type> for $int {
+            #[inline]
+            fn from(index: $type) -> Self {
+                index.get()
+            }
+        }
+    };
+}
+
+impl_ledger_index!(
+    /// Leaf Index
+    ///
+    /// Position of a UTXO in one of the trees of the UTXO accumulator, which is also the number of
+    /// UTXOs inserted into that tree before it. Checkpoints store the leaf index of the next UTXO
+    /// of every tree.
+    LeafIndex,
+    usize
+);
+
+impl_ledger_index!(
+    /// Note Index
+    ///
+    /// Position of a spent note in the sequence of nullifiers revealed by the ledger, which is also
+    /// the number of nullifiers revealed before it. Checkpoints store the note index of the next
+    /// nullifier.
+    NoteIndex,
+    usize
+);
+
 /// Ledger Data
 ///
 /// In order to keep track of updates from [`read`] calls through the [`Read`] `trait`, all data
@@ -122,3 +216,465 @@ pub trait Write<R>: Connection {
     /// Sends the `request` to the ledger, returning its [`Response`](Self::Response).
     fn write(&mut self, request: R) -> LocalBoxFutureResult<Self::Response, Self::Error>;
 }
+
+/// Post Receipt
+///
+/// Receipt of a batch of posts which was accepted by the ledger through [`Submit`]. Ledgers which
+/// apply the posts right away know where their new UTXOs end up, so the receipt carries enough
+/// information for the wallet to update itself without a separate [`read`](Read::read).
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PostReceipt<T, C, I> {
+    /// Transaction Id
+    pub transaction_id: T,
+
+    /// Origin Checkpoint
+    ///
+    /// Checkpoint of the ledger right before the posts were applied. If the wallet is synchronized
+    /// up to this checkpoint, the posts are the only ledger data it is missing.
+    pub origin_checkpoint: C,
+
+    /// Accepted Checkpoint
+    ///
+    /// Checkpoint of the ledger right after the posts were applied.
+    pub checkpoint: C,
+
+    /// Leaf Indices
+    ///
+    /// Indices assigned to the new UTXOs of the posts, in the order of their receivers.
+    pub leaf_indices: Vec<I>,
+}
+
+/// Ledger Connection Submission
+///
+/// Unlike [`Write`], which lets the ledger customize its response, submissions are answered with
+/// a [`PostReceipt`] whenever the ledger accepts the posts right away.
+pub trait Submit<R>: Connection {
+    /// Transaction Id Type
+    type TransactionId;
+
+    /// Checkpoint Type
+    type Checkpoint: Checkpoint;
+
+    /// Leaf Index Type
+    type LeafIndex;
+
+    /// Sends the `request` to the ledger, returning the [`PostReceipt`] of the posts if they were
+    /// accepted right away, and `None` if they were rejected or left pending for a later block.
+    fn submit(
+        &mut self,
+        request: R,
+    ) -> LocalBoxFutureResult<'_, Option<SubmitReceipt<Self, R>>, Self::Error>;
+}
+
+/// Submission Receipt Type
+pub type SubmitReceipt<L, R> = PostReceipt<
+    <L as Submit<R>>::TransactionId,
+    <L as Submit<R>>::Checkpoint,
+    <L as Submit<R>>::LeafIndex,
+>;
+
+/// Shielded Pool Statistics
+///
+/// These statistics describe the health of the anonymity set of the shielded pool. They are
+/// accumulated by the ledger over batches of posts, and are returned by the
+/// [`pool_stats`](PoolStatsQuery::pool_stats) method on [`PoolStatsQuery`].
+///
+/// # Limitations
+///
+/// The asset of a note is only known to the ledger when the transfer which minted it has a public
+/// asset id, so notes minted by private transfers are only counted in [`total_notes`] and are
+/// reported by [`unattributed_notes`].
+///
+/// [`total_notes`]: Self::total_notes
+/// [`unattributed_notes`]: Self::unattributed_notes
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PoolStats<I> {
+    /// Total Number of Notes
+    pub total_notes: u64,
+
+    /// Total Number of Spends
+    pub total_spends: u64,
+
+    /// Notes per Asset
+    ///
+    /// Number of notes minted for each asset id by transfers with a public asset id, sorted by
+    /// asset id.
+    pub notes_per_asset: Vec<(I, u64)>,
+
+    /// Spend Frequency Histogram
+    ///
+    /// The `k`-th entry is the number of batches which spent exactly `k` notes.
+    pub spend_frequency: Vec<u64>,
+
+    /// Number of Root Updates
+    ///
+    /// Number of times the root of one of the accumulator trees changed, counted at most once per
+    /// tree and per batch.
+    pub root_updates: u64,
+
+    /// Number of Batches
+    pub batches: u64,
+}
+
+impl<I> PoolStats<I> {
+    /// Records a batch which minted `notes`-many notes, spent `spends`-many notes, and changed
+    /// the roots of `updated_roots`-many accumulator trees.
+    #[inline]
+    pub fn record_batch(&mut self, notes: u64, spends: u64, updated_roots: u64) {
+        self.total_notes += notes;
+        self.total_spends += spends;
+        let spends = spends as usize;
+        if self.spend_frequency.len() <= spends {
+            self.spend_frequency.resize(spends + 1, 0);
+        }
+        self.spend_frequency[spends] += 1;
+        self.root_updates += updated_roots;
+        self.batches += 1;
+    }
+
+    /// Records that `notes`-many notes of the asset with the given `asset_id` were minted. The
+    /// notes themselves are counted by [`record_batch`](Self::record_batch).
+    #[inline]
+    pub fn record_asset_notes(&mut self, asset_id: I, notes: u64)
+    where
+        I: Ord,
+    {
+        match self
+            .notes_per_asset
+            .binary_search_by(|(id, _)| id.cmp(&asset_id))
+        {
+            Ok(index) => self.notes_per_asset[index].1 += notes,
+            Err(index) => self.notes_per_asset.insert(index, (asset_id, notes)),
+        }
+    }
+
+    /// Returns the number of notes minted for `asset_id` by transfers with a public asset id.
+    #[inline]
+    pub fn notes(&self, asset_id: &I) -> u64
+    where
+        I: Ord,
+    {
+        self.notes_per_asset
+            .binary_search_by(|(id, _)| id.cmp(asset_id))
+            .map(|index| self.notes_per_asset[index].1)
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of notes whose asset is not known to the ledger.
+    #[inline]
+    pub fn unattributed_notes(&self) -> u64 {
+        self.total_notes
+            - self
+                .notes_per_asset
+                .iter()
+                .map(|(_, notes)| notes)
+                .sum::<u64>()
+    }
+
+    /// Returns the average fraction of the `trees`-many accumulator trees whose root changed in
+    /// each batch. A high root turnover makes membership proofs go stale faster.
+    #[inline]
+    pub fn root_turnover(&self, trees: usize) -> f64 {
+        if self.batches == 0 || trees == 0 {
+            return 0.0;
+        }
+        self.root_updates as f64 / (self.batches as f64 * trees as f64)
+    }
+}
+
+impl<I> Default for PoolStats<I> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            total_notes: 0,
+            total_spends: 0,
+            notes_per_asset: Vec::new(),
+            spend_frequency: Vec::new(),
+            root_updates: 0,
+            batches: 0,
+        }
+    }
+}
+
+/// Shielded Pool Statistics Query
+pub trait PoolStatsQuery<I>: Connection {
+    /// Returns the current [`PoolStats`] of the ledger.
+    fn pool_stats(&mut self) -> LocalBoxFutureResult<'_, PoolStats<I>, Self::Error>;
+}
+
+impl_ledger_index!(
+    /// Block Height
+    ///
+    /// Blocks are numbered from the genesis block, which has height zero and holds all the data
+    /// which was on the ledger before it started producing blocks. The genesis block is always
+    /// confirmed.
+    BlockHeight,
+    u64
+);
+
+/// Block Time Model
+///
+/// Ledgers which order posts into blocks do not apply posts as soon as they are written. Instead,
+/// the posts wait until the next block is produced, which happens every [`interval`], and are only
+/// reported by [`read`](Read::read) once [`confirmation_depth`]-many blocks were produced on top
+/// of the block which included them. Until then, the assets they create are not spendable and the
+/// assets they spend are still part of the balance of the wallet.
+///
+/// [`interval`]: Self::interval
+/// [`confirmation_depth`]: Self::confirmation_depth
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BlockTime {
+    /// Block Interval
+    pub interval: Duration,
+
+    /// Confirmation Depth
+    ///
+    /// Number of blocks which must be produced on top of the block including a post before the
+    /// post is confirmed. A depth of zero confirms posts as soon as they are included in a block.
+    pub confirmation_depth: u64,
+}
+
+impl BlockTime {
+    /// Builds a new [`BlockTime`] from `interval` and `confirmation_depth`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `interval` is zero.
+    #[inline]
+    pub fn new(interval: Duration, confirmation_depth: u64) -> Self {
+        assert!(!interval.is_zero(), "The block interval must not be zero.");
+        Self {
+            interval,
+            confirmation_depth,
+        }
+    }
+
+    /// Returns the number of blocks produced during `elapsed` time together with the time left
+    /// until the next block.
+    #[inline]
+    pub fn blocks_in(&self, elapsed: Duration) -> (u64, Duration) {
+        let interval = self.interval.as_nanos();
+        let elapsed = elapsed.as_nanos();
+        let remainder = (elapsed % interval) as u64;
+        (
+            (elapsed / interval) as u64,
+            self.interval - Duration::from_nanos(remainder),
+        )
+    }
+
+    /// Returns the latest block whose posts are confirmed when the latest produced block is
+    /// `block_number`.
+    #[inline]
+    pub fn confirmed_block(&self, block_number: BlockHeight) -> BlockHeight {
+        block_number.rewind(self.confirmation_depth)
+    }
+
+    /// Returns the longest time between writing a post and its confirmation, which is spent
+    /// waiting for the block including it and then for the confirmation depth.
+    #[inline]
+    pub fn confirmation_time(&self) -> Duration {
+        self.interval.saturating_mul(
+            u32::try_from(self.confirmation_depth.saturating_add(1)).unwrap_or(u32::MAX),
+        )
+    }
+}
+
+/// Block Status
+///
+/// This `struct` is returned by the [`block_status`](BlockStatusQuery::block_status) method on
+/// [`BlockStatusQuery`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct BlockStatus {
+    /// Latest Block Number
+    pub block_number: BlockHeight,
+
+    /// Latest Confirmed Block Number
+    ///
+    /// Blocks which were produced after the last block changing the ledger state do not need to
+    /// wait for the confirmation depth, so this number is equal to the latest block number once
+    /// every write is confirmed.
+    pub confirmed_block_number: BlockHeight,
+
+    /// Number of Pending Batches
+    ///
+    /// Number of batches of posts which were written to the ledger and wait for the next block.
+    pub pending_batches: u64,
+}
+
+impl BlockStatus {
+    /// Returns the number of produced blocks which are not confirmed yet.
+    #[inline]
+    pub fn unconfirmed_blocks(&self) -> u64 {
+        self.block_number
+            .since(self.confirmed_block_number)
+            .expect("The confirmed block is never ahead of the latest block.")
+    }
+
+    /// Returns `true` if every post written to the ledger is confirmed, so that the balances
+    /// reported by reads are final.
+    #[inline]
+    pub fn is_settled(&self) -> bool {
+        self.pending_batches == 0 && self.unconfirmed_blocks() == 0
+    }
+}
+
+/// Block Status Query
+pub trait BlockStatusQuery: Connection {
+    /// Returns the current [`BlockStatus`] of the ledger.
+    fn block_status(&mut self) -> LocalBoxFutureResult<'_, BlockStatus, Self::Error>;
+}
+
+/// Root History Error
+///
+/// This `enum` is returned by the [`root_at`](RootHistoryQuery::root_at) method on
+/// [`RootHistoryQuery`] when the ledger cannot return the accumulator output at the requested
+/// checkpoint.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum RootHistoryError {
+    /// Pruned History Error
+    ///
+    /// The checkpoint is older than the oldest accumulator output kept by the ledger.
+    Pruned,
+
+    /// Future Checkpoint Error
+    ///
+    /// The checkpoint is ahead of the current state of the ledger.
+    Future,
+}
+
+/// Accumulator Root History Query
+///
+/// Wallets which build posts against a slightly old view of the ledger use this query to fetch the
+/// accumulator output that the ledger had at the checkpoint of their view.
+pub trait RootHistoryQuery: Connection {
+    /// Checkpoint Type
+    type Checkpoint: Checkpoint;
+
+    /// Accumulator Output Type
+    type Output;
+
+    /// Returns the accumulator output of the ledger when it was at `checkpoint`, or a
+    /// [`RootHistoryError`] if `checkpoint` is out of the bounds of the history kept by the ledger.
+    fn root_at<'s>(
+        &'s mut self,
+        checkpoint: &'s Self::Checkpoint,
+    ) -> LocalBoxFutureResult<'s, Result<Self::Output, RootHistoryError>, Self::Error>;
+}
+
+/// Fee Schedule Query
+///
+/// Ledgers which charge fees publish their [`FeeSchedule`] through this query, so that wallets can
+/// estimate the fees of their transactions against the schedule which is currently enforced.
+pub trait FeeScheduleQuery<I, V>: Connection {
+    /// Returns the current [`FeeSchedule`] of the ledger, or `None` if the ledger does not charge
+    /// any fee.
+    fn fee_schedule(&mut self) -> LocalBoxFutureResult<'_, Option<FeeSchedule<I, V>>, Self::Error>;
+}
+
+/// Upgrade Query
+///
+/// Ledgers publish the protocol upgrades they announced through this query, so that wallets can
+/// stop using the features which the upgrades deprecate. See [`upgrade`](crate::transfer::upgrade)
+/// for more.
+pub trait UpgradeQuery: Connection {
+    /// Returns the [`UpgradeSchedule`] of the ledger at its current height.
+    fn upgrades(&mut self) -> LocalBoxFutureResult<'_, UpgradeSchedule, Self::Error>;
+}
+
+/// Checkpoint Header
+///
+/// Summary of a new state of the ledger, pushed by the ledger to its subscribers through a
+/// [`CheckpointStream`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CheckpointHeader<C, R> {
+    /// Checkpoint
+    ///
+    /// Checkpoint of the data which a [`read`](Read::read) returns, which counts the UTXOs and the
+    /// nullifiers of the ledger.
+    pub checkpoint: C,
+
+    /// Accumulator Root
+    ///
+    /// Accumulator output of the ledger at [`checkpoint`](Self::checkpoint).
+    pub root: R,
+
+    /// Ledger Height
+    pub height: BlockHeight,
+}
+
+/// Checkpoint Stream
+///
+/// Ledgers which push their new checkpoints to their subscribers, in the style of server-sent
+/// events, let wallets pull new data as soon as it is available instead of polling the ledger at
+/// a fixed interval.
+pub trait CheckpointStream: Connection {
+    /// Checkpoint Type
+    type Checkpoint: Checkpoint;
+
+    /// Accumulator Output Type
+    type Output;
+
+    /// Returns the next [`CheckpointHeader`] pushed by the ledger. The first call returns the
+    /// header of the current state of the ledger right away, and every later call waits until the
+    /// ledger pushes a new header.
+    fn next_header(
+        &mut self,
+    ) -> LocalBoxFutureResult<'_, CheckpointHeader<Self::Checkpoint, Self::Output>, Self::Error>;
+}
+
+/// Ledger Time
+///
+/// Height of the ledger together with its time, in milliseconds since the Unix epoch, as reported
+/// by the ledger itself. See [`clock`](crate::wallet::clock) for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LedgerTime {
+    /// Ledger Height
+    pub height: BlockHeight,
+
+    /// Timestamp in Milliseconds since the Unix Epoch
+    pub timestamp: u64,
+}
+
+/// Ledger Time Query
+///
+/// Ledgers publish their time through this query, so that wallets can use it as a trusted time
+/// source instead of the clock of the device they run on.
+pub trait LedgerTimeQuery: Connection {
+    /// Returns the current [`LedgerTime`] of the ledger.
+    fn ledger_time(&mut self) -> LocalBoxFutureResult<'_, LedgerTime, Self::Error>;
+}
@@ -28,31 +28,39 @@
 //! [`Ledger`]: ledger::Connection
 
 use crate::{
-    asset::AssetList,
+    asset::{AssetList, BalanceError},
     transfer::{
-        canonical::{Transaction, TransactionKind},
-        Address, Asset, Configuration, IdentifiedAsset, TransferPost, UtxoAccumulatorModel,
+        canonical::{Transaction, TransactionKind, TransferShape},
+        upgrade::UpgradeSchedule,
+        Address, Asset, Configuration, IdentifiedAsset, Note, Nullifier, TransferPost, Utxo,
+        UtxoAccumulatorModel,
     },
     wallet::{
         balance::{BTreeMapBalanceState, BalanceState},
+        clock::{ClockSkew, LedgerClock},
         ledger::ReadResponse,
+        operation::{OperationId, OperationTable},
         signer::{
             BalanceUpdate, Checkpoint, IdentityRequest, IdentityResponse, InitialSyncData,
-            InitialSyncRequest, SignError, SignRequest, SignResponse,
-            SignWithTransactionDataResponse, SyncData, SyncError, SyncRequest, SyncResponse,
-            SyncResult, TransactionDataRequest, TransactionDataResponse,
+            InitialSyncRequest, SignBatchRequest, SignBatchResponse, SignError, SignRequest,
+            SignResponse, SignWithTransactionDataResponse, SyncData, SyncError, SyncRequest,
+            SyncResponse, SyncResult, TransactionDataRequest, TransactionDataResponse,
         },
     },
 };
 use alloc::vec::Vec;
-use core::{fmt::Debug, hash::Hash, marker::PhantomData, ops::AddAssign};
-use manta_util::ops::ControlFlow;
+use core::{fmt::Debug, hash::Hash, marker::PhantomData, mem, ops::AddAssign};
+use manta_util::{num::CheckedSub, ops::ControlFlow};
 
 #[cfg(feature = "serde")]
 use manta_util::serde::{Deserialize, Serialize};
 
 pub mod balance;
+pub mod clock;
+pub mod journal;
 pub mod ledger;
+pub mod operation;
+pub mod prefetch;
 pub mod signer;
 
 #[cfg(feature = "test")]
@@ -65,8 +73,8 @@ pub mod test;
     derive(Deserialize, Serialize),
     serde(
         bound(
-            deserialize = "L: Deserialize<'de>, S::Checkpoint: Deserialize<'de>, S: Deserialize<'de>, B: Deserialize<'de>",
-            serialize = "L: Serialize, S::Checkpoint: Serialize, S: Serialize, B: Serialize",
+            deserialize = "L: Deserialize<'de>, S::Checkpoint: Deserialize<'de>, S: Deserialize<'de>, B: Deserialize<'de>, OperationTable<C>: Deserialize<'de>",
+            serialize = "L: Serialize, S::Checkpoint: Serialize, S: Serialize, B: Serialize, OperationTable<C>: Serialize",
         ),
         crate = "manta_util::serde",
         deny_unknown_fields
@@ -74,13 +82,14 @@ pub mod test;
 )]
 #[derive(derivative::Derivative)]
 #[derivative(
-    Clone(bound = "L: Clone, S::Checkpoint: Clone, S: Clone, B: Clone"),
-    Copy(bound = "L: Copy, S::Checkpoint: Copy, S: Copy, B: Copy"),
-    Debug(bound = "L: Debug, S::Checkpoint: Debug, S: Debug, B: Debug"),
+    Clone(bound = "L: Clone, S::Checkpoint: Clone, S: Clone, B: Clone, OperationTable<C>: Clone"),
+    Debug(bound = "L: Debug, S::Checkpoint: Debug, S: Debug, B: Debug, OperationTable<C>: Debug"),
     Default(bound = "L: Default, S::Checkpoint: Default, S: Default, B: Default"),
-    Eq(bound = "L: Eq, S::Checkpoint: Eq, S: Eq, B: Eq"),
-    Hash(bound = "L: Hash, S::Checkpoint: Hash, S: Hash, B: Hash"),
-    PartialEq(bound = "L: PartialEq, S::Checkpoint: PartialEq, S: PartialEq, B: PartialEq")
+    Eq(bound = "L: Eq, S::Checkpoint: Eq, S: Eq, B: Eq, OperationTable<C>: Eq"),
+    Hash(bound = "L: Hash, S::Checkpoint: Hash, S: Hash, B: Hash, OperationTable<C>: Hash"),
+    PartialEq(
+        bound = "L: PartialEq, S::Checkpoint: PartialEq, S: PartialEq, B: PartialEq, OperationTable<C>: PartialEq"
+    )
 )]
 pub struct Wallet<
     C,
@@ -105,6 +114,18 @@ pub struct Wallet<
     /// Balance State
     assets: B,
 
+    /// Upgrade Schedule
+    #[cfg_attr(feature = "serde", serde(default))]
+    upgrades: UpgradeSchedule,
+
+    /// Ledger Clock
+    #[cfg_attr(feature = "serde", serde(default))]
+    clock: LedgerClock,
+
+    /// Multi-Asset Operations
+    #[cfg_attr(feature = "serde", serde(default))]
+    operations: OperationTable<C>,
+
     /// Type Parameter Marker
     __: PhantomData<C>,
 }
@@ -125,6 +146,9 @@ where
             checkpoint,
             signer,
             assets,
+            upgrades: Default::default(),
+            clock: Default::default(),
+            operations: Default::default(),
             __: PhantomData,
         }
     }
@@ -206,6 +230,34 @@ where
             .all(|asset| self.contains(&asset))
     }
 
+    /// Returns the balance associated with this `id` which is not reserved by a pending
+    /// [`Operation`](operation::Operation) of `self`.
+    #[inline]
+    pub fn available_balance(&self, id: &C::AssetId) -> C::AssetValue
+    where
+        C::AssetValue: CheckedSub<Output = C::AssetValue>,
+    {
+        self.balance(id)
+            .checked_sub(self.operations.reserved(id))
+            .unwrap_or_default()
+    }
+
+    /// Returns true if `self` contains at least `asset.value` of the asset of kind `asset.id`
+    /// which is not reserved by a pending [`Operation`](operation::Operation) of `self`.
+    #[inline]
+    pub fn contains_available(&self, asset: &Asset<C>) -> bool
+    where
+        C::AssetValue: CheckedSub<Output = C::AssetValue>,
+    {
+        self.available_balance(&asset.id) >= asset.value
+    }
+
+    /// Returns the [`OperationTable`] of `self`, with the multi-asset operations it signed.
+    #[inline]
+    pub fn operations(&self) -> &OperationTable<C> {
+        &self.operations
+    }
+
     /// Returns a shared reference to the balance state associated to `self`.
     #[inline]
     pub fn assets(&self) -> &B {
@@ -225,6 +277,27 @@ where
         &self.checkpoint
     }
 
+    /// Returns the [`UpgradeSchedule`] of the ledger as of the last call to
+    /// [`sync_upgrades`](Self::sync_upgrades).
+    #[inline]
+    pub fn upgrades(&self) -> &UpgradeSchedule {
+        &self.upgrades
+    }
+
+    /// Returns the [`LedgerClock`] of `self`, updated on every call to
+    /// [`sync_time`](Self::sync_time).
+    #[inline]
+    pub fn clock(&self) -> &LedgerClock {
+        &self.clock
+    }
+
+    /// Returns the current time according to the ledger, given the `local` time in milliseconds
+    /// since the Unix epoch. See [`LedgerClock::now`] for more.
+    #[inline]
+    pub fn now(&self, local: u64) -> Option<u64> {
+        self.clock.now(local)
+    }
+
     /// Restarts `self` with an empty state and performs a synchronization against the signer and
     /// ledger to catch up to the current checkpoint and balance state.
     ///
@@ -253,6 +326,30 @@ where
         self.signer_sync(Default::default()).await
     }
 
+    /// Reloads the balance state of `self` from the full asset distribution of the signer,
+    /// healing the balances which were double-counted by older versions of the wallet when they
+    /// synchronized the same data twice. Returns `true` if the balance state of `self` differed
+    /// from the one of the signer.
+    ///
+    /// # Failure Conditions
+    ///
+    /// This method returns an element of type [`Error`] on failure, in which case the balance
+    /// state of `self` is left unchanged.
+    #[inline]
+    pub async fn reconcile(&mut self) -> Result<bool, Error<C, L, S>>
+    where
+        B: PartialEq,
+    {
+        let assets = mem::take(&mut self.assets);
+        match self.signer_sync(Default::default()).await {
+            Ok(()) => Ok(self.assets != assets),
+            Err(err) => {
+                self.assets = assets;
+                Err(err)
+            }
+        }
+    }
+
     /// Pulls data from the ledger, synchronizing the wallet and balance state. This method loops
     /// continuously calling [`sync_partial`](Self::sync_partial) until all the ledger data has
     /// arrived at and has been synchronized with the wallet.
@@ -272,6 +369,67 @@ where
         Ok(())
     }
 
+    /// Waits until the ledger pushes a [`CheckpointHeader`](ledger::CheckpointHeader) ahead of
+    /// the checkpoint of `self`, and then synchronizes with the ledger like [`sync`](Self::sync),
+    /// returning the header. Host applications call this method in a loop instead of polling the
+    /// ledger at a fixed interval.
+    #[inline]
+    pub async fn sync_on_push(
+        &mut self,
+    ) -> Result<ledger::CheckpointHeader<S::Checkpoint, L::Output>, Error<C, L, S>>
+    where
+        L: ledger::Read<SyncData<C>, Checkpoint = S::Checkpoint>
+            + ledger::CheckpointStream<Checkpoint = S::Checkpoint>,
+    {
+        loop {
+            let header = self
+                .ledger
+                .next_header()
+                .await
+                .map_err(Error::LedgerConnectionError)?;
+            if header.checkpoint > self.checkpoint {
+                self.sync().await?;
+                return Ok(header);
+            }
+        }
+    }
+
+    /// Pulls the [`UpgradeSchedule`] from the ledger. After this call, `self` refuses to sign
+    /// transactions whose shape is not enabled on the ledger, and the host application can use
+    /// [`upgrades`](Self::upgrades) to warn its user about retiring features or to prompt for a
+    /// parameter download. Ledgers which announce upgrades should be queried along with every
+    /// [`sync`](Self::sync).
+    #[inline]
+    pub async fn sync_upgrades(&mut self) -> Result<(), Error<C, L, S>>
+    where
+        L: ledger::UpgradeQuery,
+    {
+        self.upgrades = self
+            .ledger
+            .upgrades()
+            .await
+            .map_err(Error::LedgerConnectionError)?;
+        Ok(())
+    }
+
+    /// Pulls the [`LedgerTime`](ledger::LedgerTime) from the ledger and records it in the
+    /// [`clock`](Self::clock) of `self` against the `local` time in milliseconds since the Unix
+    /// epoch, returning the [`ClockSkew`] of the local clock if it is too far from the ledger
+    /// time. Host applications should surface the skew to their user, and pass
+    /// [`now`](Self::now) instead of the local time to the time-dependent methods of the signer.
+    #[inline]
+    pub async fn sync_time(&mut self, local: u64) -> Result<Option<ClockSkew>, Error<C, L, S>>
+    where
+        L: ledger::LedgerTimeQuery,
+    {
+        let time = self
+            .ledger
+            .ledger_time()
+            .await
+            .map_err(Error::LedgerConnectionError)?;
+        Ok(self.clock.observe(time, local))
+    }
+
     /// Pulls data from the ledger, synchronizing the wallet and balance state. This method loops
     /// continuously calling [`sbt_sync_partial`](Self::sbt_sync_partial) until all the ledger data has
     /// arrived at and has been synchronized with the wallet.
@@ -449,17 +607,18 @@ where
             Ok(SyncResponse {
                 checkpoint,
                 balance_update,
+                ..
             }) => {
                 match balance_update {
                     BalanceUpdate::Partial { deposit, withdraw } => {
-                        self.assets.deposit_all(deposit);
+                        self.assets.try_deposit_all(deposit)?;
                         if !self.assets.withdraw_all(withdraw) {
                             return Err(Error::Inconsistency(InconsistencyError::WalletBalance));
                         }
                     }
                     BalanceUpdate::Full { assets } => {
                         self.assets.clear();
-                        self.assets.deposit_all(assets);
+                        self.assets.try_deposit_all(assets)?;
                     }
                 }
                 self.checkpoint = checkpoint;
@@ -535,10 +694,22 @@ where
         transaction: &'s Transaction<C>,
     ) -> Result<TransactionKind<C>, Asset<C>> {
         transaction
-            .check(move |a| self.contains(a))
+            .check(move |a| self.contains_available(a))
             .map_err(Clone::clone)
     }
 
+    /// Checks that the shape of `transaction` is still accepted by the ledger according to the
+    /// [`UpgradeSchedule`] of `self`.
+    #[inline]
+    fn check_shape(&self, transaction: &Transaction<C>) -> Result<(), Error<C, L, S>> {
+        let shape = transaction.shape();
+        if self.upgrades.is_enabled(shape) {
+            Ok(())
+        } else {
+            Err(Error::DisabledShape(shape))
+        }
+    }
+
     /// Signs the `transaction` using the signer connection, sending `metadata` for context. This
     /// method _does not_ automatically sychronize with the ledger. To do this, call the
     /// [`sync`](Self::sync) method separately.
@@ -548,6 +719,7 @@ where
         transaction: Transaction<C>,
         metadata: Option<S::AssetMetadata>,
     ) -> Result<SignResponse<C>, Error<C, L, S>> {
+        self.check_shape(&transaction)?;
         self.check(&transaction)
             .map_err(Error::InsufficientBalance)?;
         self.signer
@@ -626,6 +798,234 @@ where
             .map_err(Error::LedgerConnectionError)
     }
 
+    /// Submits a transaction to the ledger like [`post`](Self::post), returning the
+    /// [`PostReceipt`](ledger::PostReceipt) of its posts if the ledger accepted them right away.
+    ///
+    /// Unlike [`post`](Self::post), this method also synchronizes with the ledger after the
+    /// submission. Whenever the receipt shows that the posts were applied right after the
+    /// checkpoint of `self`, the signer is synchronized against the posts themselves, so no
+    /// separate [`read`](ledger::Read::read) is needed. Otherwise, or if the posts exceed the
+    /// synchronization data bound, `self` falls back on [`sync`](Self::sync).
+    #[inline]
+    pub async fn submit(
+        &mut self,
+        transaction: Transaction<C>,
+        metadata: Option<S::AssetMetadata>,
+    ) -> Result<Option<ledger::SubmitReceipt<L, Vec<TransferPost<C>>>>, Error<C, L, S>>
+    where
+        L: ledger::Read<SyncData<C>, Checkpoint = S::Checkpoint>
+            + ledger::Submit<Vec<TransferPost<C>>, Checkpoint = S::Checkpoint>,
+        Utxo<C>: Clone,
+        Note<C>: Clone,
+        Nullifier<C>: Clone,
+    {
+        self.sync().await?;
+        let SignResponse { posts } = self.sign(transaction, metadata).await?;
+        let data = SyncData::from_posts(&posts).ok();
+        let receipt = self
+            .ledger
+            .submit(posts)
+            .await
+            .map_err(Error::LedgerConnectionError)?;
+        match (&receipt, data) {
+            (Some(receipt), Some(data)) if receipt.origin_checkpoint == self.checkpoint => {
+                self.signer_sync(SyncRequest {
+                    origin_checkpoint: self.checkpoint.clone(),
+                    data,
+                })
+                .await?
+            }
+            _ => self.sync().await?,
+        }
+        Ok(receipt)
+    }
+
+    /// Signs the `transactions` of a multi-asset operation jointly using the signer connection,
+    /// sending the metadata paired with each of them for context, and opens a pending
+    /// [`Operation`](operation::Operation) for them in the [`OperationTable`] of `self`. The
+    /// transactions never spend the same note and each of them gets the change of its own asset.
+    /// The assets they send stay reserved until the operation is settled with
+    /// [`settle_operation`](Self::settle_operation) or aborted with
+    /// [`abort_operation`](Self::abort_operation). This method _does not_ automatically
+    /// synchronize with the ledger or post the transactions, see
+    /// [`submit_operation`](Self::submit_operation) for that.
+    ///
+    /// On ledgers which order posts into blocks, this method should be used instead of
+    /// [`submit_operation`](Self::submit_operation), settling or aborting the operation once the
+    /// blocks with its posts are settled.
+    #[inline]
+    pub async fn sign_operation<I>(
+        &mut self,
+        transactions: I,
+    ) -> Result<(OperationId, SignBatchResponse<C>), Error<C, L, S>>
+    where
+        I: IntoIterator<Item = (Transaction<C>, Option<S::AssetMetadata>)>,
+        Transaction<C>: Clone,
+    {
+        let requests = transactions
+            .into_iter()
+            .map(|(transaction, metadata)| SignRequest {
+                transaction,
+                metadata,
+            })
+            .collect::<Vec<_>>();
+        let mut sent = AssetList::new();
+        for request in &requests {
+            self.check_shape(&request.transaction)?;
+            match &request.transaction {
+                Transaction::PrivateTransfer(asset, _) | Transaction::ToPublic(asset, _) => {
+                    sent.try_deposit(asset.clone())?
+                }
+                Transaction::ToPrivate(_) => {}
+            }
+        }
+        if let Some(asset) = sent.iter().find(|asset| !self.contains_available(asset)) {
+            return Err(Error::InsufficientBalance(asset.clone()));
+        }
+        let transactions = requests
+            .iter()
+            .map(|request| request.transaction.clone())
+            .collect();
+        let response = self
+            .signer
+            .sign_batch(SignBatchRequest(requests))
+            .await
+            .map_err(Error::SignerConnectionError)?
+            .map_err(|err| {
+                let (index, err) = err
+                    .failures
+                    .into_iter()
+                    .next()
+                    .expect("A failed batch always has at least one failure.");
+                Error::OperationSignError(index, err)
+            })?;
+        Ok((self.operations.open(transactions), response))
+    }
+
+    /// Signs the `transactions` of a multi-asset operation with
+    /// [`sign_operation`](Self::sign_operation) and submits their posts to the ledger one
+    /// transaction at a time, returning the [`OperationId`] of the operation and the
+    /// [`PostReceipt`](ledger::PostReceipt)s of its transactions if the ledger accepted all of
+    /// them. This method synchronizes with the ledger before signing and after submitting.
+    ///
+    /// # Failure Conditions
+    ///
+    /// If the ledger rejects one of the transactions, the transactions after it are not submitted,
+    /// the operation is aborted, releasing the reservations of all of its transactions at once,
+    /// and [`Error::OperationRejected`] is returned with the number of transactions which were
+    /// accepted before the rejection. The operation is also aborted if the ledger connection fails
+    /// while submitting.
+    #[inline]
+    pub async fn submit_operation<I>(
+        &mut self,
+        transactions: I,
+    ) -> Result<
+        (
+            OperationId,
+            Vec<ledger::SubmitReceipt<L, Vec<TransferPost<C>>>>,
+        ),
+        Error<C, L, S>,
+    >
+    where
+        I: IntoIterator<Item = (Transaction<C>, Option<S::AssetMetadata>)>,
+        L: ledger::Read<SyncData<C>, Checkpoint = S::Checkpoint>
+            + ledger::Submit<Vec<TransferPost<C>>, Checkpoint = S::Checkpoint>,
+        Transaction<C>: Clone,
+    {
+        self.sync().await?;
+        let (id, response) = self.sign_operation(transactions).await?;
+        let mut receipts = Vec::with_capacity(response.0.len());
+        for SignResponse { posts } in response.0 {
+            match self.ledger.submit(posts).await {
+                Ok(Some(receipt)) => receipts.push(receipt),
+                Ok(None) => {
+                    self.operations.abort(&id, receipts.len());
+                    self.sync().await?;
+                    return Err(Error::OperationRejected(id, receipts.len()));
+                }
+                Err(err) => {
+                    self.operations.abort(&id, receipts.len());
+                    return Err(Error::LedgerConnectionError(err));
+                }
+            }
+        }
+        self.operations.settle(&id);
+        self.sync().await?;
+        Ok((id, receipts))
+    }
+
+    /// Settles the pending operation with the given `id` once the ledger accepted all of its
+    /// transactions, releasing its reservations. Returns `false` if there is no such pending
+    /// operation.
+    #[inline]
+    pub fn settle_operation(&mut self, id: &OperationId) -> bool {
+        self.operations.settle(id)
+    }
+
+    /// Aborts the pending operation with the given `id` after the ledger accepted its first
+    /// `accepted` transactions, releasing the reservations of all of its transactions. Returns
+    /// `false` if there is no such pending operation.
+    #[inline]
+    pub fn abort_operation(&mut self, id: &OperationId, accepted: usize) -> bool {
+        self.operations.abort(id, accepted)
+    }
+
+    /// Returns the [`BlockStatus`](ledger::BlockStatus) of the ledger.
+    ///
+    /// On ledgers which order posts into blocks, the balance of `self` only accounts for the
+    /// posts which are confirmed, so the transactions posted by [`post`](Self::post) are pending
+    /// until the status [`is_settled`](ledger::BlockStatus::is_settled) and `self` is
+    /// synchronized again.
+    #[inline]
+    pub async fn block_status(&mut self) -> Result<ledger::BlockStatus, Error<C, L, S>>
+    where
+        L: ledger::BlockStatusQuery,
+    {
+        self.ledger
+            .block_status()
+            .await
+            .map_err(Error::LedgerConnectionError)
+    }
+
+    /// Estimates the fee which the ledger charges for `transaction`, sending `metadata` to the
+    /// signer for context. The signer plans the posts of `transaction` without signing them, as in
+    /// [`review`](signer::Connection::review), and the fee of every post is taken from the current
+    /// [`FeeSchedule`](crate::transfer::fee::FeeSchedule) of the ledger. Returns `None` if the
+    /// ledger does not charge any fee.
+    #[inline]
+    pub async fn estimate_fee(
+        &mut self,
+        transaction: Transaction<C>,
+        metadata: Option<S::AssetMetadata>,
+    ) -> Result<Option<Asset<C>>, Error<C, L, S>>
+    where
+        L: ledger::FeeScheduleQuery<C::AssetId, C::AssetValue>,
+    {
+        let schedule = match self
+            .ledger
+            .fee_schedule()
+            .await
+            .map_err(Error::LedgerConnectionError)?
+        {
+            Some(schedule) => schedule,
+            _ => return Ok(None),
+        };
+        let shape = transaction.shape();
+        let summary = self
+            .signer
+            .review(SignRequest {
+                transaction,
+                metadata,
+            })
+            .await
+            .map_err(Error::SignerConnectionError)?
+            .map_err(Error::SignError)?;
+        let fee = schedule
+            .transaction_fee(shape, summary.posts)
+            .ok_or(BalanceError::Overflow)?;
+        Ok(Some(Asset::<C>::new(schedule.asset_id, fee)))
+    }
+
     /// Returns the address.
     #[inline]
     pub async fn address(&mut self) -> Result<Option<Address<C>>, S::Error> {
@@ -643,6 +1043,7 @@ where
     where
         TransferPost<C>: Clone,
     {
+        self.check_shape(&transaction)?;
         self.check(&transaction)
             .map_err(Error::InsufficientBalance)?;
         self.signer
@@ -746,6 +1147,11 @@ where
     /// Signing Error
     SignError(SignError<C>),
 
+    /// Balance Error
+    ///
+    /// The balance of an asset synchronized from the signer does not fit in an asset value.
+    BalanceError(BalanceError),
+
     /// Signer Connection Error
     SignerConnectionError(S::Error),
 
@@ -757,6 +1163,38 @@ where
 
     /// Missing Proof Authorization Key Error
     MissingProofAuthorizationKey,
+
+    /// Disabled Shape Error
+    ///
+    /// The ledger does not accept transactions of this shape, because it was deprecated by an
+    /// active upgrade or was introduced by an upgrade which is not active yet. See
+    /// [`UpgradeSchedule`] for more.
+    DisabledShape(TransferShape),
+
+    /// Operation Signing Error
+    ///
+    /// The transaction at the given index of a multi-asset operation could not be signed, so no
+    /// operation was opened. See [`sign_operation`](Wallet::sign_operation) for more.
+    OperationSignError(usize, SignError<C>),
+
+    /// Operation Rejected Error
+    ///
+    /// The ledger rejected a transaction of the operation after accepting the given number of its
+    /// transactions, so the operation was aborted. See
+    /// [`submit_operation`](Wallet::submit_operation) for more.
+    OperationRejected(OperationId, usize),
+}
+
+impl<C, L, S> From<BalanceError> for Error<C, L, S>
+where
+    C: Configuration,
+    L: ledger::Connection,
+    S: signer::Connection<C>,
+{
+    #[inline]
+    fn from(err: BalanceError) -> Self {
+        Self::BalanceError(err)
+    }
 }
 
 impl<C, L, S> From<InconsistencyError> for Error<C, L, S>
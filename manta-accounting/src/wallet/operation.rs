@@ -0,0 +1,300 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Multi-Asset Operations
+//!
+//! A transfer only moves one asset, so a user action like "send 10 A and 5 B to X" takes one
+//! [`Transaction`] for every asset. The [`Wallet`](super::Wallet) signs the transactions of such an
+//! [`Operation`] jointly, so that they never spend the same note and each of them gets the change
+//! of its own asset, and tracks them under one [`OperationId`] in its [`OperationTable`]. While an
+//! operation is pending, the assets it sends are reserved, so that other transactions do not count
+//! on them. When the ledger rejects one of the transactions of an operation, the reservations of
+//! all of them are released at once, and the operation stays in the table with the number of
+//! transactions which were accepted before the rejection, since those cannot be undone.
+
+use crate::{
+    asset::AssetList,
+    transfer::{canonical::Transaction, Address, Asset, Configuration},
+};
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Operation Identifier
+///
+/// Position of the operation in the [`OperationTable`] of the wallet which signed it.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct OperationId(pub u64);
+
+/// Operation Status
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum OperationStatus {
+    /// Pending Operation
+    ///
+    /// The transactions of the operation were signed but not all of them were accepted by the
+    /// ledger yet, so the assets they send are reserved.
+    Pending,
+
+    /// Settled Operation
+    ///
+    /// All the transactions of the operation were accepted by the ledger.
+    Settled,
+
+    /// Aborted Operation
+    ///
+    /// One of the transactions of the operation was rejected by the ledger, or the operation was
+    /// dropped before reaching it. Holds the number of transactions which were accepted before.
+    Aborted(usize),
+}
+
+/// Operation
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Transaction<C>: Deserialize<'de>",
+            serialize = "Transaction<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Transaction<C>: Clone"),
+    Debug(bound = "Transaction<C>: Debug"),
+    Eq(bound = "Transaction<C>: Eq"),
+    Hash(bound = "Transaction<C>: Hash"),
+    PartialEq(bound = "Transaction<C>: PartialEq")
+)]
+pub struct Operation<C>
+where
+    C: Configuration,
+{
+    /// Operation Identifier
+    id: OperationId,
+
+    /// Transactions
+    transactions: Vec<Transaction<C>>,
+
+    /// Operation Status
+    status: OperationStatus,
+}
+
+impl<C> Operation<C>
+where
+    C: Configuration,
+{
+    /// Returns the [`OperationId`] of `self`.
+    #[inline]
+    pub fn id(&self) -> OperationId {
+        self.id
+    }
+
+    /// Returns the transactions of `self`, in signing order.
+    #[inline]
+    pub fn transactions(&self) -> &[Transaction<C>] {
+        &self.transactions
+    }
+
+    /// Returns the [`OperationStatus`] of `self`.
+    #[inline]
+    pub fn status(&self) -> OperationStatus {
+        self.status
+    }
+
+    /// Returns `true` if `self` is still pending.
+    #[inline]
+    pub fn is_pending(&self) -> bool {
+        self.status == OperationStatus::Pending
+    }
+
+    /// Returns the assets sent by the transactions of `self`, which are reserved while it is
+    /// pending.
+    #[inline]
+    pub fn sent_assets(&self) -> impl Iterator<Item = &Asset<C>> {
+        self.transactions
+            .iter()
+            .filter_map(|transaction| match transaction {
+                Transaction::PrivateTransfer(asset, _) | Transaction::ToPublic(asset, _) => {
+                    Some(asset)
+                }
+                Transaction::ToPrivate(_) => None,
+            })
+    }
+}
+
+/// Operation Table
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Operation<C>: Deserialize<'de>",
+            serialize = "Operation<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Operation<C>: Clone"),
+    Debug(bound = "Operation<C>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "Operation<C>: Eq"),
+    Hash(bound = "Operation<C>: Hash"),
+    PartialEq(bound = "Operation<C>: PartialEq")
+)]
+pub struct OperationTable<C>
+where
+    C: Configuration,
+{
+    /// Operations
+    operations: Vec<Operation<C>>,
+}
+
+impl<C> OperationTable<C>
+where
+    C: Configuration,
+{
+    /// Returns all the operations of `self`, in signing order.
+    #[inline]
+    pub fn operations(&self) -> &[Operation<C>] {
+        &self.operations
+    }
+
+    /// Returns `true` if `self` has no operations.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Returns the operation of `self` with the given `id`.
+    #[inline]
+    pub fn get(&self, id: &OperationId) -> Option<&Operation<C>> {
+        self.operations.get(usize::try_from(id.0).ok()?)
+    }
+
+    /// Returns an iterator over the pending operations of `self`.
+    #[inline]
+    pub fn pending(&self) -> impl Iterator<Item = &Operation<C>> {
+        self.operations
+            .iter()
+            .filter(|operation| operation.is_pending())
+    }
+
+    /// Returns the value of the asset with the given `id` which is reserved by the pending
+    /// operations of `self`.
+    #[inline]
+    pub fn reserved(&self, id: &C::AssetId) -> C::AssetValue {
+        let mut reserved = C::AssetValue::default();
+        for asset in self.pending().flat_map(Operation::sent_assets) {
+            if &asset.id == id {
+                reserved += asset.value.clone();
+            }
+        }
+        reserved
+    }
+
+    /// Opens a pending operation for `transactions`, returning its [`OperationId`].
+    #[inline]
+    pub fn open(&mut self, transactions: Vec<Transaction<C>>) -> OperationId {
+        let id = OperationId(self.operations.len() as u64);
+        self.operations.push(Operation {
+            id,
+            transactions,
+            status: OperationStatus::Pending,
+        });
+        id
+    }
+
+    /// Moves the pending operation with the given `id` to `status`, returning `false` if there is
+    /// no such pending operation.
+    #[inline]
+    fn close(&mut self, id: &OperationId, status: OperationStatus) -> bool {
+        match usize::try_from(id.0)
+            .ok()
+            .and_then(|index| self.operations.get_mut(index))
+        {
+            Some(operation) if operation.is_pending() => {
+                operation.status = status;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Settles the pending operation with the given `id` once all of its transactions were
+    /// accepted by the ledger, releasing its reservations. Returns `false` if there is no such
+    /// pending operation.
+    #[inline]
+    pub fn settle(&mut self, id: &OperationId) -> bool {
+        self.close(id, OperationStatus::Settled)
+    }
+
+    /// Aborts the pending operation with the given `id` after the first `accepted` transactions
+    /// were accepted by the ledger, releasing the reservations of all of its transactions at once.
+    /// Returns `false` if there is no such pending operation.
+    #[inline]
+    pub fn abort(&mut self, id: &OperationId, accepted: usize) -> bool {
+        self.close(id, OperationStatus::Aborted(accepted))
+    }
+}
+
+/// Builds the [`PrivateTransfer`](Transaction::PrivateTransfer)s which send `assets` to
+/// `address`, one for every asset id, adding up the values of the assets with the same id and
+/// skipping the ones with zero value.
+#[inline]
+pub fn plan_send<C, A>(assets: A, address: Address<C>) -> Vec<Transaction<C>>
+where
+    C: Configuration,
+    Address<C>: Clone,
+    A: IntoIterator<Item = Asset<C>>,
+{
+    AssetList::from_iter(assets)
+        .into_iter()
+        .map(|asset| Transaction::PrivateTransfer(asset, address.clone()))
+        .collect()
+}
+
+/// Builds the [`ToPublic`](Transaction::ToPublic) transactions which withdraw `assets` to
+/// `account`, one for every asset id, adding up the values of the assets with the same id and
+/// skipping the ones with zero value.
+#[inline]
+pub fn plan_withdraw<C, A>(assets: A, account: C::AccountId) -> Vec<Transaction<C>>
+where
+    C: Configuration,
+    A: IntoIterator<Item = Asset<C>>,
+{
+    AssetList::from_iter(assets)
+        .into_iter()
+        .map(|asset| Transaction::ToPublic(asset, account.clone()))
+        .collect()
+}
@@ -0,0 +1,422 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Cold-Start Estimation and Prefetch Planning
+//!
+//! Before a new wallet can sign, its signer has to download part of the ledger. The signer
+//! supports three ways of doing so, described by [`SyncMode`], which trade what the wallet can
+//! discover against how much it has to download, store and compute. A [`SyncCostModel`] combines
+//! the totals of the ledger with the encoded sizes of the ledger data and the measured throughput
+//! of the device to estimate every mode, and [`SyncCostModel::plan`] picks the one which fits the
+//! device.
+
+use crate::wallet::ledger::PoolStats;
+use core::time::Duration;
+use manta_util::vec::Vec;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Synchronization Mode
+///
+/// The modes are ordered by what they let the wallet discover, from the least to the most.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SyncMode {
+    /// Frontier Only
+    ///
+    /// Downloads the frontier of the accumulator and the membership proofs of the notes already
+    /// known to the signer, as in [`frontier_sync`]. New notes are not discovered and have to be
+    /// imported.
+    ///
+    /// [`frontier_sync`]: crate::wallet::signer::Signer::frontier_sync
+    FrontierOnly,
+
+    /// Filters
+    ///
+    /// Downloads the UTXOs without their notes to build the accumulator, as in [`initial_sync`],
+    /// and tracks spent nullifiers in the filters of the signer from then on. Notes received after
+    /// the synchronization are discovered, but the notes already on the ledger cannot be spent.
+    ///
+    /// [`initial_sync`]: crate::wallet::signer::Signer::initial_sync
+    Filters,
+
+    /// Full Pull
+    ///
+    /// Downloads every UTXO with its note and every nullifier, as in [`sync`] from the default
+    /// checkpoint, trying to decrypt every note. This is the only mode which recovers the notes
+    /// already on the ledger.
+    ///
+    /// [`sync`]: crate::wallet::signer::Signer::sync
+    FullPull,
+}
+
+impl SyncMode {
+    /// All the synchronization modes, from the least to the most capable.
+    pub const ALL: [Self; 3] = [Self::FrontierOnly, Self::Filters, Self::FullPull];
+
+    /// Returns `true` if `self` recovers the notes which are already on the ledger.
+    #[inline]
+    pub fn recovers_existing_notes(&self) -> bool {
+        matches!(self, Self::FullPull)
+    }
+
+    /// Returns `true` if `self` discovers the notes received after the synchronization.
+    #[inline]
+    pub fn discovers_new_notes(&self) -> bool {
+        !matches!(self, Self::FrontierOnly)
+    }
+}
+
+/// Ledger Totals
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct LedgerTotals {
+    /// Number of Leaves in the UTXO Accumulator
+    pub leaf_count: u64,
+
+    /// Number of Notes on the Ledger
+    pub note_count: u64,
+
+    /// Number of Nullifiers on the Ledger
+    pub nullifier_count: u64,
+}
+
+impl<I> From<&PoolStats<I>> for LedgerTotals {
+    #[inline]
+    fn from(stats: &PoolStats<I>) -> Self {
+        Self {
+            leaf_count: stats.total_notes,
+            note_count: stats.total_notes,
+            nullifier_count: stats.total_spends,
+        }
+    }
+}
+
+/// Item Sizes
+///
+/// Sizes in bytes of the encodings of the ledger data, which depend on the configuration of the
+/// protocol.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ItemSizes {
+    /// UTXO Size
+    pub utxo: u64,
+
+    /// Note Size
+    pub note: u64,
+
+    /// Nullifier Size
+    pub nullifier: u64,
+
+    /// Accumulator Leaf Size
+    ///
+    /// Bytes stored by the signer for every leaf of its accumulator.
+    pub leaf: u64,
+
+    /// Membership Proof Size
+    pub path: u64,
+}
+
+/// Device Throughput
+///
+/// Throughput measured on the device running the signer. A zero rate means that the device cannot
+/// do the corresponding work at all.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DeviceThroughput {
+    /// Bandwidth in Bytes per Second
+    pub bandwidth: u64,
+
+    /// Trial Decryptions per Second
+    pub decryptions: u64,
+
+    /// Accumulator Insertions per Second
+    pub insertions: u64,
+}
+
+/// Synchronization Estimate
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SyncEstimate {
+    /// Bytes Downloaded from the Ledger
+    pub download: u64,
+
+    /// Bytes Stored by the Signer
+    pub storage: u64,
+
+    /// Time to Download and Process the Data
+    pub duration: Duration,
+}
+
+impl SyncEstimate {
+    /// Returns `true` if `self` stays within `budget`.
+    #[inline]
+    pub fn fits(&self, budget: &SyncBudget) -> bool {
+        budget
+            .download
+            .is_none_or(|download| self.download <= download)
+            && budget.storage.is_none_or(|storage| self.storage <= storage)
+            && budget
+                .duration
+                .is_none_or(|duration| self.duration <= duration)
+    }
+}
+
+/// Synchronization Budget
+///
+/// Limits of the device for the initial synchronization, where `None` means that there is no
+/// limit.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SyncBudget {
+    /// Maximum Number of Bytes Downloaded
+    pub download: Option<u64>,
+
+    /// Maximum Number of Bytes Stored
+    pub storage: Option<u64>,
+
+    /// Maximum Synchronization Time
+    pub duration: Option<Duration>,
+}
+
+/// Synchronization Requirements
+///
+/// What the wallet needs from its initial synchronization.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SyncRequirements {
+    /// Recover the Notes Already on the Ledger
+    ///
+    /// Wallets restored from a mnemonic need this to find their funds.
+    pub recover_existing_notes: bool,
+
+    /// Discover the Notes Received after the Synchronization
+    pub discover_new_notes: bool,
+
+    /// Number of Notes Already Known to the Signer
+    pub known_notes: u64,
+}
+
+impl SyncRequirements {
+    /// Returns the least capable [`SyncMode`] which satisfies `self`.
+    #[inline]
+    pub fn minimum_mode(&self) -> SyncMode {
+        if self.recover_existing_notes {
+            SyncMode::FullPull
+        } else if self.discover_new_notes {
+            SyncMode::Filters
+        } else {
+            SyncMode::FrontierOnly
+        }
+    }
+}
+
+/// Prefetch Plan
+///
+/// This `struct` is created by the [`plan`](SyncCostModel::plan) method on [`SyncCostModel`].
+/// See its documentation for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PrefetchPlan {
+    /// Selected Synchronization Mode
+    pub mode: SyncMode,
+
+    /// Estimate of the Selected Mode
+    pub estimate: SyncEstimate,
+
+    /// Within Budget Flag
+    ///
+    /// This flag is `false` when no mode satisfying the requirements fits the budget, in which case
+    /// the cheapest of them is selected anyway.
+    pub within_budget: bool,
+
+    /// Estimates of Every Mode
+    ///
+    /// Estimates of all the modes, from the least to the most capable, for applications which
+    /// want to show the alternatives to the user.
+    pub estimates: Vec<(SyncMode, SyncEstimate)>,
+}
+
+/// Synchronization Cost Model
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SyncCostModel {
+    /// Ledger Totals
+    pub totals: LedgerTotals,
+
+    /// Item Sizes
+    pub sizes: ItemSizes,
+
+    /// Number of Trees in the UTXO Accumulator Forest
+    pub forest_width: u64,
+
+    /// Device Throughput
+    pub throughput: DeviceThroughput,
+}
+
+impl SyncCostModel {
+    /// Estimates the initial synchronization of a signer which already knows `known_notes`-many
+    /// notes with `mode`.
+    ///
+    /// # Cost Model
+    ///
+    /// Every mode downloads the data described in [`SyncMode`], and the time to process it is the
+    /// sum of the download time, one trial decryption per downloaded note and one accumulator
+    /// insertion per downloaded UTXO. The storage only counts the accumulator of the signer: a full
+    /// accumulator for [`Filters`] and [`FullPull`], and a membership proof for every tree and
+    /// known note for [`FrontierOnly`], since the nullifiers only go through fixed-size filters.
+    ///
+    /// [`Filters`]: SyncMode::Filters
+    /// [`FullPull`]: SyncMode::FullPull
+    /// [`FrontierOnly`]: SyncMode::FrontierOnly
+    #[inline]
+    pub fn estimate(&self, mode: SyncMode, known_notes: u64) -> SyncEstimate {
+        let LedgerTotals {
+            leaf_count,
+            note_count,
+            nullifier_count,
+        } = self.totals;
+        let sizes = &self.sizes;
+        let (download, storage, decryptions, insertions) = match mode {
+            SyncMode::FrontierOnly => {
+                let paths = self.forest_width.saturating_add(known_notes);
+                (
+                    paths
+                        .saturating_mul(sizes.utxo.saturating_add(sizes.path))
+                        .saturating_add(nullifier_count.saturating_mul(sizes.nullifier)),
+                    paths.saturating_mul(sizes.path),
+                    0,
+                    paths,
+                )
+            }
+            SyncMode::Filters => (
+                leaf_count
+                    .saturating_mul(sizes.utxo)
+                    .saturating_add(self.forest_width.saturating_mul(sizes.path)),
+                leaf_count.saturating_mul(sizes.leaf),
+                0,
+                leaf_count,
+            ),
+            SyncMode::FullPull => (
+                leaf_count
+                    .saturating_mul(sizes.utxo)
+                    .saturating_add(note_count.saturating_mul(sizes.note))
+                    .saturating_add(nullifier_count.saturating_mul(sizes.nullifier)),
+                leaf_count.saturating_mul(sizes.leaf),
+                note_count,
+                leaf_count,
+            ),
+        };
+        let seconds = work_seconds(download, self.throughput.bandwidth)
+            + work_seconds(decryptions, self.throughput.decryptions)
+            + work_seconds(insertions, self.throughput.insertions);
+        SyncEstimate {
+            download,
+            storage,
+            duration: Duration::try_from_secs_f64(seconds).unwrap_or(Duration::MAX),
+        }
+    }
+
+    /// Plans the initial synchronization of a signer with `requirements` on a device with
+    /// `budget`.
+    ///
+    /// Among the modes which satisfy `requirements`, this method selects the most capable one
+    /// which fits `budget`, so that devices with enough resources also recover or discover the
+    /// notes they did not strictly ask for. If none of them fits, the cheapest of them is selected
+    /// and the plan is marked as over budget.
+    #[inline]
+    pub fn plan(&self, requirements: &SyncRequirements, budget: &SyncBudget) -> PrefetchPlan {
+        let estimates = SyncMode::ALL
+            .into_iter()
+            .map(|mode| (mode, self.estimate(mode, requirements.known_notes)))
+            .collect::<Vec<_>>();
+        let minimum_mode = requirements.minimum_mode();
+        let candidates = estimates
+            .iter()
+            .filter(|(mode, _)| *mode >= minimum_mode)
+            .copied()
+            .collect::<Vec<_>>();
+        let (mode, estimate, within_budget) = match candidates
+            .iter()
+            .rev()
+            .find(|(_, estimate)| estimate.fits(budget))
+        {
+            Some((mode, estimate)) => (*mode, *estimate, true),
+            _ => {
+                let (mode, estimate) = candidates
+                    .iter()
+                    .min_by_key(|(_, estimate)| estimate.duration)
+                    .copied()
+                    .expect("The full pull satisfies every requirement.");
+                (mode, estimate, false)
+            }
+        };
+        PrefetchPlan {
+            mode,
+            estimate,
+            within_budget,
+            estimates,
+        }
+    }
+}
+
+/// Returns the number of seconds needed to do `work` at `rate` units per second, which is
+/// infinite if there is some work and the rate is zero.
+#[inline]
+fn work_seconds(work: u64, rate: u64) -> f64 {
+    match (work, rate) {
+        (0, _) => 0.0,
+        (_, 0) => f64::INFINITY,
+        _ => work as f64 / rate as f64,
+    }
+}
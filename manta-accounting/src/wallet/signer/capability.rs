@@ -0,0 +1,477 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signer Capabilities
+//!
+//! Every caller which can reach the [`Connection`] of a [`Signer`] can sign with it. Host
+//! applications which expose the signer to plugins or web frontends can instead grant them a
+//! [`CapabilityToken`] with a [`Scope`] which restricts the requests they can make, and serve
+//! their requests through the [`ScopedSigner`] returned by [`Signer::scoped`], which rejects the
+//! requests outside of the scope of the token with an [`AuthorizationError`].
+//!
+//! Tokens are bearer secrets: anyone who knows a token can use it until it expires or is revoked
+//! with [`Signer::revoke_capability`], so tokens should only be sent over authenticated channels.
+//!
+//! # Clocks
+//!
+//! The signer has no clock, so the expiration times of the tokens are given by the caller, in
+//! milliseconds since the Unix epoch.
+
+use crate::{
+    transfer::{Address, Parameters, TransferPost},
+    wallet::signer::{
+        Configuration, Connection, IdentityRequest, IdentityResponse, InitialSyncRequest,
+        ReviewResult, SignBatchRequest, SignBatchResult, SignRequest, SignResult,
+        SignWithTransactionDataResult, Signer, SyncRequest, SyncResult, TransactionDataRequest,
+        TransactionDataResponse,
+    },
+};
+use alloc::{boxed::Box, vec::Vec};
+use manta_crypto::rand::{RngCore, Sample};
+use manta_util::future::LocalBoxFutureResult;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Capability Token
+///
+/// Random secret which identifies a capability granted by a signer.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct CapabilityToken(pub [u8; 32]);
+
+impl Sample for CapabilityToken {
+    #[inline]
+    fn sample<R>(_: (), rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        let mut token = [0; 32];
+        rng.fill_bytes(&mut token);
+        Self(token)
+    }
+}
+
+/// Signer Operation
+///
+/// Each variant corresponds to one of the methods of [`Connection`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Operation {
+    /// [`sync`](Connection::sync) and [`sbt_sync`](Connection::sbt_sync)
+    Sync,
+
+    /// [`initial_sync`](Connection::initial_sync)
+    InitialSync,
+
+    /// [`sign`](Connection::sign), [`sign_batch`](Connection::sign_batch) and
+    /// [`sign_with_transaction_data`](Connection::sign_with_transaction_data)
+    Sign,
+
+    /// [`review`](Connection::review)
+    Review,
+
+    /// [`address`](Connection::address)
+    Address,
+
+    /// [`transaction_data`](Connection::transaction_data)
+    TransactionData,
+
+    /// [`identity_proof`](Connection::identity_proof)
+    IdentityProof,
+
+    /// [`transfer_parameters`](Connection::transfer_parameters)
+    TransferParameters,
+}
+
+/// Capability Scope
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Scope {
+    /// Receive-Only Scope
+    ///
+    /// Allows getting the [`Address`] of the signer and the transfer [`Parameters`], which is
+    /// enough to request payments to the signer.
+    ReceiveOnly,
+
+    /// Read-Only Scope
+    ///
+    /// Allows everything allowed by [`ReceiveOnly`](Self::ReceiveOnly), synchronizing the signer
+    /// with the ledger, reviewing transactions, and reading the
+    /// [`TransactionData`](crate::transfer::canonical::TransactionData) of posts, which reveals the
+    /// balances and the history of the signer.
+    ReadOnly,
+
+    /// Full-Sign Scope
+    ///
+    /// Allows every request, including signing transactions and identity proofs.
+    FullSign,
+}
+
+impl Scope {
+    /// Returns `true` if `self` allows `operation`.
+    #[inline]
+    pub fn permits(&self, operation: Operation) -> bool {
+        match self {
+            Self::ReceiveOnly => matches!(
+                operation,
+                Operation::Address | Operation::TransferParameters
+            ),
+            Self::ReadOnly => !matches!(operation, Operation::Sign | Operation::IdentityProof),
+            Self::FullSign => true,
+        }
+    }
+}
+
+/// Capability
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Capability {
+    /// Scope
+    pub scope: Scope,
+
+    /// Expiration Time
+    ///
+    /// Time in milliseconds since the Unix epoch after which the capability cannot be used
+    /// anymore, or `None` if it only ends when it is revoked.
+    pub expires_at: Option<u64>,
+}
+
+impl Capability {
+    /// Builds a new [`Capability`] from `scope` and `expires_at`.
+    #[inline]
+    pub fn new(scope: Scope, expires_at: Option<u64>) -> Self {
+        Self { scope, expires_at }
+    }
+
+    /// Returns `true` if `self` has expired at time `now`.
+    #[inline]
+    pub fn is_expired(&self, now: u64) -> bool {
+        matches!(self.expires_at, Some(expires_at) if expires_at <= now)
+    }
+}
+
+/// Authorization Error
+///
+/// This `enum` is the error state of [`Signer::scoped`] and of the methods of [`ScopedSigner`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AuthorizationError {
+    /// Unknown Token
+    ///
+    /// The token was never granted by the signer or it was revoked.
+    UnknownToken,
+
+    /// Expired Token
+    Expired,
+
+    /// Out of Scope
+    ///
+    /// The scope of the token does not allow the requested operation.
+    OutOfScope {
+        /// Scope of the Token
+        scope: Scope,
+
+        /// Requested Operation
+        operation: Operation,
+    },
+}
+
+/// Capability Table
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CapabilityTable {
+    /// Granted Capabilities
+    ///
+    /// The capabilities are kept in the order they were granted.
+    capabilities: Vec<(CapabilityToken, Capability)>,
+}
+
+impl CapabilityTable {
+    /// Returns the number of capabilities in `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.capabilities.len()
+    }
+
+    /// Returns `true` if there are no capabilities in `self`.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.capabilities.is_empty()
+    }
+
+    /// Returns an iterator over the capabilities in `self`, from the oldest to the latest.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &(CapabilityToken, Capability)> {
+        self.capabilities.iter()
+    }
+
+    /// Returns the capability granted with `token`, if it was not revoked.
+    #[inline]
+    pub fn get(&self, token: &CapabilityToken) -> Option<&Capability> {
+        self.capabilities
+            .iter()
+            .find(|(granted, _)| granted == token)
+            .map(|(_, capability)| capability)
+    }
+
+    /// Grants `capability`, returning a new token for it sampled from `rng`.
+    #[inline]
+    pub fn grant<R>(&mut self, capability: Capability, rng: &mut R) -> CapabilityToken
+    where
+        R: RngCore + ?Sized,
+    {
+        let token = CapabilityToken::gen(rng);
+        self.capabilities.push((token, capability));
+        token
+    }
+
+    /// Revokes the capability granted with `token`, returning it if it was not revoked yet.
+    #[inline]
+    pub fn revoke(&mut self, token: &CapabilityToken) -> Option<Capability> {
+        let index = self
+            .capabilities
+            .iter()
+            .position(|(granted, _)| granted == token)?;
+        Some(self.capabilities.remove(index).1)
+    }
+
+    /// Drops the capabilities which have expired at time `now`, returning their number.
+    #[inline]
+    pub fn prune(&mut self, now: u64) -> usize {
+        let len = self.capabilities.len();
+        self.capabilities
+            .retain(|(_, capability)| !capability.is_expired(now));
+        len - self.capabilities.len()
+    }
+
+    /// Returns the [`Scope`] of the capability granted with `token` if it can still be used at
+    /// time `now`.
+    #[inline]
+    pub fn authorize(
+        &self,
+        token: &CapabilityToken,
+        now: u64,
+    ) -> Result<Scope, AuthorizationError> {
+        let capability = self.get(token).ok_or(AuthorizationError::UnknownToken)?;
+        if capability.is_expired(now) {
+            return Err(AuthorizationError::Expired);
+        }
+        Ok(capability.scope)
+    }
+}
+
+/// Scoped Signer
+///
+/// [`Signer`] handle returned by [`Signer::scoped`] which only serves the requests allowed by the
+/// [`Scope`] of a [`CapabilityToken`].
+pub struct ScopedSigner<'s, C>
+where
+    C: Configuration,
+{
+    /// Signer
+    signer: &'s mut Signer<C>,
+
+    /// Scope
+    scope: Scope,
+}
+
+impl<'s, C> ScopedSigner<'s, C>
+where
+    C: Configuration,
+{
+    /// Builds a new [`ScopedSigner`] which serves the requests allowed by `scope` with `signer`.
+    #[inline]
+    pub(super) fn new(signer: &'s mut Signer<C>, scope: Scope) -> Self {
+        Self { signer, scope }
+    }
+
+    /// Returns the [`Scope`] of `self`.
+    #[inline]
+    pub fn scope(&self) -> Scope {
+        self.scope
+    }
+
+    /// Checks that the [`Scope`] of `self` allows `operation`.
+    #[inline]
+    fn check(&self, operation: Operation) -> Result<(), AuthorizationError> {
+        if self.scope.permits(operation) {
+            Ok(())
+        } else {
+            Err(AuthorizationError::OutOfScope {
+                scope: self.scope,
+                operation,
+            })
+        }
+    }
+}
+
+impl<'s, C> Connection<C> for ScopedSigner<'s, C>
+where
+    C: Configuration,
+{
+    type AssetMetadata = C::AssetMetadata;
+    type Checkpoint = C::Checkpoint;
+    type Error = AuthorizationError;
+
+    #[inline]
+    fn sync(
+        &mut self,
+        request: SyncRequest<C, C::Checkpoint>,
+    ) -> LocalBoxFutureResult<'_, SyncResult<C, C::Checkpoint>, Self::Error> {
+        Box::pin(async move {
+            self.check(Operation::Sync)?;
+            Ok(self.signer.sync(request))
+        })
+    }
+
+    #[inline]
+    fn sbt_sync(
+        &mut self,
+        request: SyncRequest<C, Self::Checkpoint>,
+    ) -> LocalBoxFutureResult<'_, SyncResult<C, Self::Checkpoint>, Self::Error> {
+        Box::pin(async move {
+            self.check(Operation::Sync)?;
+            Ok(self.signer.sbt_sync(request))
+        })
+    }
+
+    #[inline]
+    fn initial_sync(
+        &mut self,
+        request: InitialSyncRequest<C>,
+    ) -> LocalBoxFutureResult<'_, SyncResult<C, Self::Checkpoint>, Self::Error> {
+        Box::pin(async move {
+            self.check(Operation::InitialSync)?;
+            Ok(self.signer.initial_sync(request))
+        })
+    }
+
+    #[inline]
+    fn sign(
+        &mut self,
+        request: SignRequest<Self::AssetMetadata, C>,
+    ) -> LocalBoxFutureResult<'_, SignResult<C>, Self::Error> {
+        Box::pin(async move {
+            self.check(Operation::Sign)?;
+            Ok(self.signer.sign(request.transaction))
+        })
+    }
+
+    #[inline]
+    fn sign_batch(
+        &mut self,
+        request: SignBatchRequest<Self::AssetMetadata, C>,
+    ) -> LocalBoxFutureResult<'_, SignBatchResult<C>, Self::Error> {
+        Box::pin(async move {
+            self.check(Operation::Sign)?;
+            Ok(self.signer.sign_batch(
+                request
+                    .0
+                    .into_iter()
+                    .map(|request| request.transaction)
+                    .collect(),
+            ))
+        })
+    }
+
+    #[inline]
+    fn review(
+        &mut self,
+        request: SignRequest<Self::AssetMetadata, C>,
+    ) -> LocalBoxFutureResult<'_, ReviewResult<C>, Self::Error> {
+        Box::pin(async move {
+            self.check(Operation::Review)?;
+            Ok(Signer::review(self.signer, request.transaction))
+        })
+    }
+
+    #[inline]
+    fn address(&mut self) -> LocalBoxFutureResult<'_, Option<Address<C>>, Self::Error> {
+        Box::pin(async move {
+            self.check(Operation::Address)?;
+            Ok(self.signer.address())
+        })
+    }
+
+    #[inline]
+    fn transaction_data(
+        &mut self,
+        request: TransactionDataRequest<C>,
+    ) -> LocalBoxFutureResult<'_, TransactionDataResponse<C>, Self::Error> {
+        Box::pin(async move {
+            self.check(Operation::TransactionData)?;
+            Ok(self.signer.batched_transaction_data(request.0))
+        })
+    }
+
+    #[inline]
+    fn identity_proof(
+        &mut self,
+        request: IdentityRequest<C>,
+    ) -> LocalBoxFutureResult<'_, IdentityResponse<C>, Self::Error> {
+        Box::pin(async move {
+            self.check(Operation::IdentityProof)?;
+            Ok(self.signer.batched_identity_proof(request.0))
+        })
+    }
+
+    #[inline]
+    fn sign_with_transaction_data(
+        &mut self,
+        request: SignRequest<Self::AssetMetadata, C>,
+    ) -> LocalBoxFutureResult<'_, SignWithTransactionDataResult<C>, Self::Error>
+    where
+        TransferPost<C>: Clone,
+    {
+        Box::pin(async move {
+            self.check(Operation::Sign)?;
+            Ok(self.signer.sign_with_transaction_data(request.transaction))
+        })
+    }
+
+    #[inline]
+    fn transfer_parameters(&mut self) -> LocalBoxFutureResult<'_, Parameters<C>, Self::Error> {
+        Box::pin(async move {
+            self.check(Operation::TransferParameters)?;
+            Ok(Signer::transfer_parameters(self.signer).clone())
+        })
+    }
+}
@@ -17,15 +17,19 @@
 //! Signer Functions
 
 use crate::{
-    asset::AssetMap,
+    asset::{self, AssetMap, CheckedBalance},
     key::{Account, DeriveAddress},
     transfer::{
         self,
         batch::Join,
         canonical::{
             MultiProvingContext, PrivateTransfer, PrivateTransferShape, Selection, ToPrivate,
-            ToPublic, Transaction, TransactionData, TransferShape,
+            ToPublic, ToPublicShape, Transaction, TransactionData, TransferShape,
         },
+        external_receiver,
+        holdings::{Holdings, HoldingsProof},
+        internal_pair,
+        ownership::{OwnershipChallenge, OwnershipProof},
         receiver::ReceiverPost,
         requires_authorization,
         utxo::{
@@ -33,14 +37,23 @@ use crate::{
             UtxoReconstruct,
         },
         Address, Asset, AssociatedData, Authorization, AuthorizationContext, FullParametersRef,
-        IdentifiedAsset, Identifier, IdentityProof, Note, Nullifier, Parameters, PreSender,
-        ProvingContext, Receiver, Sender, Shape, SpendingKey, Transfer, TransferPost, Utxo,
-        UtxoAccumulatorItem, UtxoAccumulatorModel, UtxoAccumulatorWitness,
+        IdentifiedAsset, Identifier, IdentityProof, Note, Nullifier, Parameters, PaymentOpening,
+        PaymentReceipt, PreSender, ProvingContext, Receiver, Sender, Shape, SpendingKey,
+        TransactionIdFunction, Transfer, TransferPost, Utxo, UtxoAccumulatorItem,
+        UtxoAccumulatorModel, UtxoAccumulatorOutput, UtxoAccumulatorWitness, UtxoMembershipProof,
     },
     wallet::signer::{
-        AccountTable, BalanceUpdate, Checkpoint, Configuration, InitialSyncRequest, SignError,
-        SignResponse, SignWithTransactionDataResponse, SignWithTransactionDataResult,
-        SignerParameters, SyncData, SyncError, SyncRequest, SyncResponse,
+        lease::{ConflictPolicy, LeaseConfiguration, LeaseTable},
+        privacy::{PrivacyStrategy, SplittingStrategy},
+        quarantine::{Quarantine, ReprocessResponse},
+        review::{BalanceChange, PrivacyNote, Recipient, TransactionSummary},
+        watch::{Digest, NullifierWatchList, WatchListHash, WatchListSalt},
+        AccountTable, BalanceUpdate, Checkpoint, Configuration, FrontierSyncData,
+        FrontierSyncRequest, ImportRequest, ImportResponse, ImportStatus, InitialSyncRequest,
+        LedgerSnapshot, LedgerSnapshotError, MembershipProofCache, SignBatchError,
+        SignBatchResponse, SignBatchResult, SignError, SignResponse,
+        SignWithTransactionDataResponse, SignWithTransactionDataResult, SignerParameters, SyncData,
+        SyncError, SyncRequest, SyncResponse,
     },
 };
 use alloc::{vec, vec::Vec};
@@ -51,8 +64,8 @@ use manta_crypto::{
     rand::Rand,
 };
 use manta_util::{
-    array_map, cmp::Independence, into_array_unchecked, iter::IteratorExt, persistence::Rollback,
-    vec::VecExt,
+    array_map, cmp::Independence, into_array_unchecked, iter::IteratorExt, num::CheckedSub,
+    persistence::Rollback, vec::VecExt, zeroize::Zeroizing,
 };
 
 /// Returns the default account for `accounts`.
@@ -64,14 +77,17 @@ where
     accounts.get_default()
 }
 
-/// Returns the default spending key for `accounts`.
+/// Returns the default spending key for `accounts`, which is zeroized when it is dropped.
 #[inline]
-fn default_spending_key<C>(accounts: &AccountTable<C>, parameters: &C::Parameters) -> SpendingKey<C>
+fn default_spending_key<C>(
+    accounts: &AccountTable<C>,
+    parameters: &C::Parameters,
+) -> Zeroizing<SpendingKey<C>>
 where
     C: Configuration,
 {
     let _ = parameters;
-    accounts.get_default().spending_key()
+    Zeroizing::new(accounts.get_default().spending_key())
 }
 
 /// Returns the default authorization context for `accounts`.
@@ -121,7 +137,9 @@ fn address_from_authorization_context<C>(
 where
     C: Configuration,
 {
-    parameters.derive_address(&parameters.derive_decryption_key(authorization_context))
+    parameters.derive_address(&Zeroizing::new(
+        parameters.derive_decryption_key(authorization_context),
+    ))
 }
 
 /// Hashes `utxo` using the [`UtxoAccumulatorItemHash`](transfer::Configuration::UtxoAccumulatorItemHash)
@@ -136,7 +154,10 @@ where
         .item_hash(utxo, &mut ())
 }
 
-/// Inserts the hash of `utxo` in `utxo_accumulator`.
+/// Inserts the hash of `utxo` in `utxo_accumulator`. If `avoid_zero_notes` is set, zero-value
+/// notes are inserted as non-provable and are not added to the spendable `assets`. Notes which are
+/// already in `assets`, as happens when the same data is synchronized twice, are not deposited
+/// again, and this function returns `true` for them.
 #[allow(clippy::too_many_arguments)]
 #[inline]
 fn insert_next_item<C>(
@@ -147,8 +168,10 @@ fn insert_next_item<C>(
     identified_asset: IdentifiedAsset<C>,
     nullifiers: &mut Vec<Nullifier<C>>,
     deposit: &mut Vec<Asset<C>>,
+    avoid_zero_notes: bool,
     rng: &mut C::Rng,
-) where
+) -> bool
+where
     C: Configuration,
 {
     let IdentifiedAsset::<C> { identifier, asset } = identified_asset;
@@ -163,15 +186,39 @@ fn insert_next_item<C>(
         .position(move |n| n.is_related(&nullifier))
     {
         nullifiers.remove(index);
-    } else {
+    } else if !(avoid_zero_notes && asset.is_zero()) {
         utxo_accumulator.insert(&item_hash::<C>(parameters, &utxo));
+        if assets.contains(&identifier, &asset) {
+            return true;
+        }
         if !asset.is_zero() {
             deposit.push(asset.clone());
         }
         assets.insert(identifier, asset);
-        return;
+        return false;
     }
     utxo_accumulator.insert_nonprovable(&item_hash::<C>(parameters, &utxo));
+    false
+}
+
+/// Returns the [`Utxo`] that `identified_asset` is stored in.
+#[inline]
+fn identified_asset_utxo<C>(
+    authorization_context: &mut AuthorizationContext<C>,
+    parameters: &Parameters<C>,
+    identified_asset: &IdentifiedAsset<C>,
+    rng: &mut C::Rng,
+) -> Utxo<C>
+where
+    C: Configuration,
+{
+    let (_, utxo, _) = parameters.derive_spend(
+        authorization_context,
+        identified_asset.identifier.clone(),
+        identified_asset.asset.clone(),
+        rng,
+    );
+    utxo
 }
 
 /// Checks if `asset` matches with `nullifier`, removing it from the `utxo_accumulator` and
@@ -216,10 +263,13 @@ fn sync_with<C, I>(
     assets: &mut C::AssetMap,
     checkpoint: &mut C::Checkpoint,
     utxo_accumulator: &mut C::UtxoAccumulator,
+    pending_imports: &mut Vec<IdentifiedAsset<C>>,
+    quarantine: &mut Quarantine<C>,
     parameters: &Parameters<C>,
     inserts: I,
     mut nullifiers: Vec<Nullifier<C>>,
     is_partial: bool,
+    avoid_zero_notes: bool,
     rng: &mut C::Rng,
 ) -> SyncResponse<C, C::Checkpoint>
 where
@@ -229,27 +279,52 @@ where
     let nullifier_count = nullifiers.len();
     let mut deposit = Vec::new();
     let mut withdraw = Vec::new();
-    let decryption_key = parameters.derive_decryption_key(authorization_context);
+    let decryption_key = Zeroizing::new(parameters.derive_decryption_key(authorization_context));
+    let mut pending_utxos = pending_imports
+        .iter()
+        .map(|identified_asset| {
+            identified_asset_utxo::<C>(authorization_context, parameters, identified_asset, rng)
+        })
+        .collect::<Vec<_>>();
     let mut nonprovable_inserts = Vec::new();
+    let mut quarantined = 0;
+    let mut duplicates = 0;
     for (utxo, note) in inserts {
-        if let Some((identifier, asset)) = parameters.open_with_check(&decryption_key, &utxo, note)
-        {
-            if !nonprovable_inserts.is_empty() {
-                utxo_accumulator.batch_insert_nonprovable(&nonprovable_inserts);
-                nonprovable_inserts.clear();
-            }
-            insert_next_item::<C>(
-                authorization_context,
-                utxo_accumulator,
-                assets,
-                parameters,
-                transfer::utxo::IdentifiedAsset::new(identifier, asset),
-                &mut nullifiers,
-                &mut deposit,
-                rng,
-            );
-        } else {
-            nonprovable_inserts.push(item_hash::<C>(parameters, &utxo));
+        let identified_asset = match parameters.try_open_with_check(&decryption_key, &utxo, &note) {
+            Ok((identifier, asset)) => transfer::utxo::IdentifiedAsset::new(identifier, asset),
+            Err(reason) => match pending_utxos
+                .iter()
+                .position(|pending| pending.is_related(&utxo))
+            {
+                Some(index) => {
+                    pending_utxos.remove(index);
+                    pending_imports.remove(index)
+                }
+                _ => {
+                    nonprovable_inserts.push(item_hash::<C>(parameters, &utxo));
+                    if reason.is_detected() && quarantine.insert(utxo, note, reason) {
+                        quarantined += 1;
+                    }
+                    continue;
+                }
+            },
+        };
+        if !nonprovable_inserts.is_empty() {
+            utxo_accumulator.batch_insert_nonprovable(&nonprovable_inserts);
+            nonprovable_inserts.clear();
+        }
+        if insert_next_item::<C>(
+            authorization_context,
+            utxo_accumulator,
+            assets,
+            parameters,
+            identified_asset,
+            &mut nullifiers,
+            &mut deposit,
+            avoid_zero_notes,
+            rng,
+        ) {
+            duplicates += 1;
         }
     }
     if !nonprovable_inserts.is_empty() {
@@ -283,9 +358,229 @@ where
                 assets: assets.assets().into(),
             }
         },
+        quarantined,
+        duplicates,
+    }
+}
+
+/// Removes the copies of the assets which are stored more than once under the same identifier in
+/// `assets`, as older versions of the signer could do when synchronizing the same data twice,
+/// returning the number of copies which were removed.
+#[inline]
+pub fn reconcile_assets<C>(assets: &mut C::AssetMap) -> usize
+where
+    C: Configuration,
+{
+    let mut duplicates = 0;
+    assets.retain(|_, assets| {
+        let mut unique = Vec::<Asset<C>>::with_capacity(assets.len());
+        for asset in assets.drain(..) {
+            if unique.contains(&asset) {
+                duplicates += 1;
+            } else {
+                unique.push(asset);
+            }
+        }
+        *assets = unique;
+        !assets.is_empty()
+    });
+    duplicates
+}
+
+/// Imports the notes in `request` into `assets` if their [`Utxo`] is provable in
+/// `utxo_accumulator`, or into `pending_imports` otherwise, skipping the notes which are already
+/// in either of them.
+#[inline]
+pub fn import_notes<C>(
+    parameters: &Parameters<C>,
+    authorization_context: &mut AuthorizationContext<C>,
+    assets: &mut C::AssetMap,
+    utxo_accumulator: &C::UtxoAccumulator,
+    pending_imports: &mut Vec<IdentifiedAsset<C>>,
+    request: ImportRequest<C>,
+    rng: &mut C::Rng,
+) -> ImportResponse
+where
+    C: Configuration,
+{
+    ImportResponse(
+        request
+            .0
+            .into_iter()
+            .map(|identified_asset| {
+                if assets.contains(&identified_asset.identifier, &identified_asset.asset)
+                    || pending_imports.contains(&identified_asset)
+                {
+                    return ImportStatus::Duplicate;
+                }
+                let utxo = identified_asset_utxo::<C>(
+                    authorization_context,
+                    parameters,
+                    &identified_asset,
+                    rng,
+                );
+                if utxo_accumulator
+                    .prove(&item_hash::<C>(parameters, &utxo))
+                    .is_some()
+                {
+                    assets.insert(identified_asset.identifier, identified_asset.asset);
+                    ImportStatus::Imported
+                } else {
+                    pending_imports.push(identified_asset);
+                    ImportStatus::Pending
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Tries to open the notes in `quarantine` again, importing the ones which can now be opened
+/// with [`import_notes`]. The notes which still match the detection heuristics of
+/// `authorization_context` stay in `quarantine` with their new reason, and the other ones are
+/// dropped.
+#[inline]
+pub fn reprocess_quarantine<C>(
+    parameters: &Parameters<C>,
+    authorization_context: &mut AuthorizationContext<C>,
+    assets: &mut C::AssetMap,
+    utxo_accumulator: &C::UtxoAccumulator,
+    pending_imports: &mut Vec<IdentifiedAsset<C>>,
+    quarantine: &mut Quarantine<C>,
+    rng: &mut C::Rng,
+) -> ReprocessResponse
+where
+    C: Configuration,
+{
+    let decryption_key = Zeroizing::new(parameters.derive_decryption_key(authorization_context));
+    let mut recovered = Vec::new();
+    for quarantined in quarantine.take() {
+        match parameters.try_open_with_check(&decryption_key, &quarantined.utxo, &quarantined.note)
+        {
+            Ok((identifier, asset)) => recovered.push(IdentifiedAsset::<C>::new(identifier, asset)),
+            Err(reason) if reason.is_detected() => {
+                quarantine.insert(quarantined.utxo, quarantined.note, reason);
+            }
+            _ => {}
+        }
+    }
+    ReprocessResponse {
+        recovered: import_notes(
+            parameters,
+            authorization_context,
+            assets,
+            utxo_accumulator,
+            pending_imports,
+            ImportRequest::<C>(recovered),
+            rng,
+        ),
+        remaining: quarantine.len(),
     }
 }
 
+/// Returns the [`Utxo`]s of the notes in `assets` and `pending_imports`.
+#[inline]
+pub fn owned_utxos<C>(
+    parameters: &Parameters<C>,
+    authorization_context: &mut AuthorizationContext<C>,
+    assets: &C::AssetMap,
+    pending_imports: &[IdentifiedAsset<C>],
+    rng: &mut C::Rng,
+) -> Vec<Utxo<C>>
+where
+    C: Configuration,
+{
+    assets
+        .entries()
+        .into_iter()
+        .map(|(identifier, asset)| IdentifiedAsset::<C>::new(identifier, asset))
+        .chain(pending_imports.iter().cloned())
+        .map(|identified_asset| {
+            identified_asset_utxo::<C>(authorization_context, parameters, &identified_asset, rng)
+        })
+        .collect()
+}
+
+/// Returns the notes in `assets` and `pending_imports` together with their [`Nullifier`]s.
+#[inline]
+fn owned_nullifiers<C>(
+    parameters: &Parameters<C>,
+    authorization_context: &mut AuthorizationContext<C>,
+    assets: &C::AssetMap,
+    pending_imports: &[IdentifiedAsset<C>],
+    rng: &mut C::Rng,
+) -> Vec<(IdentifiedAsset<C>, Nullifier<C>)>
+where
+    C: Configuration,
+{
+    assets
+        .entries()
+        .into_iter()
+        .map(|(identifier, asset)| IdentifiedAsset::<C>::new(identifier, asset))
+        .chain(pending_imports.iter().cloned())
+        .map(|identified_asset| {
+            let (_, _, nullifier) = parameters.derive_spend(
+                authorization_context,
+                identified_asset.identifier.clone(),
+                identified_asset.asset.clone(),
+                rng,
+            );
+            (identified_asset, nullifier)
+        })
+        .collect()
+}
+
+/// Returns the [`NullifierWatchList`] of the notes in `assets` and `pending_imports`, hashed with
+/// `salt`.
+#[inline]
+pub fn nullifier_watch_list<C>(
+    parameters: &Parameters<C>,
+    authorization_context: &mut AuthorizationContext<C>,
+    assets: &C::AssetMap,
+    pending_imports: &[IdentifiedAsset<C>],
+    salt: WatchListSalt,
+    rng: &mut C::Rng,
+) -> NullifierWatchList<C>
+where
+    C: Configuration,
+{
+    let nullifiers = owned_nullifiers::<C>(
+        parameters,
+        authorization_context,
+        assets,
+        pending_imports,
+        rng,
+    );
+    NullifierWatchList::new(salt, nullifiers.iter().map(|(_, nullifier)| nullifier))
+}
+
+/// Returns the note in `assets` or `pending_imports` whose [`Nullifier`] hashes to `digest` with
+/// `salt`.
+#[inline]
+pub fn watched_note<C>(
+    parameters: &Parameters<C>,
+    authorization_context: &mut AuthorizationContext<C>,
+    assets: &C::AssetMap,
+    pending_imports: &[IdentifiedAsset<C>],
+    salt: &WatchListSalt,
+    digest: &Digest<C>,
+    rng: &mut C::Rng,
+) -> Option<IdentifiedAsset<C>>
+where
+    C: Configuration,
+{
+    owned_nullifiers::<C>(
+        parameters,
+        authorization_context,
+        assets,
+        pending_imports,
+        rng,
+    )
+    .into_iter()
+    .find_map(|(identified_asset, nullifier)| {
+        (&C::WatchListHash::hash(salt, &nullifier) == digest).then_some(identified_asset)
+    })
+}
+
 /// Updates the internal ledger state, returning the new asset distribution.
 #[allow(clippy::too_many_arguments)]
 #[inline]
@@ -293,6 +588,7 @@ fn sbt_sync_with<C, I>(
     authorization_context: &mut AuthorizationContext<C>,
     assets: &mut C::AssetMap,
     checkpoint: &mut C::Checkpoint,
+    quarantine: &mut Quarantine<C>,
     parameters: &Parameters<C>,
     inserts: I,
     utxo_count: Vec<usize>,
@@ -304,14 +600,26 @@ where
     I: Iterator<Item = (Utxo<C>, Note<C>)>,
 {
     let mut deposit = Vec::new();
-    let decryption_key = parameters.derive_decryption_key(authorization_context);
+    let decryption_key = Zeroizing::new(parameters.derive_decryption_key(authorization_context));
+    let mut quarantined = 0;
+    let mut duplicates = 0;
     for (utxo, note) in inserts {
-        if let Some((identifier, asset)) = parameters.open_with_check(&decryption_key, &utxo, note)
-        {
-            if !asset.is_zero() {
-                deposit.push(asset.clone());
+        match parameters.try_open_with_check(&decryption_key, &utxo, &note) {
+            Ok((identifier, asset)) => {
+                if assets.contains(&identifier, &asset) {
+                    duplicates += 1;
+                    continue;
+                }
+                if !asset.is_zero() {
+                    deposit.push(asset.clone());
+                }
+                assets.insert(identifier, asset);
+            }
+            Err(reason) => {
+                if reason.is_detected() && quarantine.insert(utxo, note, reason) {
+                    quarantined += 1;
+                }
             }
-            assets.insert(identifier, asset);
         }
     }
     checkpoint.update_from_nullifiers(nullifier_count);
@@ -330,6 +638,8 @@ where
                 assets: assets.assets().into(),
             }
         },
+        quarantined,
+        duplicates,
     }
 }
 
@@ -384,6 +694,30 @@ where
     receiver::<C>(parameters, default_address, asset, Default::default(), rng)
 }
 
+/// Completes `receivers` with zero-value [`Receiver`]s of the default address until there are
+/// `N`-many of them, which is the number of receivers of the canonical shape they are built for.
+#[inline]
+fn pad_receivers<C, const N: usize>(
+    accounts: &AccountTable<C>,
+    parameters: &Parameters<C>,
+    asset_id: &C::AssetId,
+    mut receivers: Vec<Receiver<C>>,
+    rng: &mut C::Rng,
+) -> [Receiver<C>; N]
+where
+    C: Configuration,
+{
+    while receivers.len() < N {
+        receivers.push(default_receiver::<C>(
+            accounts,
+            parameters,
+            Asset::<C>::zero(asset_id.clone()),
+            rng,
+        ));
+    }
+    into_array_unchecked(receivers)
+}
+
 /// Builds the [`Receiver`] associated with `authorization_context` and `asset`.
 #[inline]
 fn receiver_from_authorization_context<C>(
@@ -399,19 +733,54 @@ where
     receiver::<C>(parameters, address, asset, Default::default(), rng)
 }
 
-/// Selects the pre-senders which collectively own at least `asset`, returning any change.
+/// Adds the smallest note of `asset.id` which is not in `selection` to `selection` if its change
+/// is zero and it leaves a sender of the transfer free, so that the change note is not a
+/// zero-value note. The shape of the transfer does not change, since the free sender would
+/// otherwise be filled with a zero-value note.
+#[inline]
+fn require_change<C>(
+    assets: &C::AssetMap,
+    asset: &Asset<C>,
+    selection: &mut asset::Selection<C::AssetId, C::AssetValue, C::AssetMap>,
+) where
+    C: Configuration,
+{
+    if asset.is_zero()
+        || selection.is_empty()
+        || selection.values.len() >= PrivateTransferShape::SENDERS
+        || selection.change != Default::default()
+    {
+        return;
+    }
+    if let Some((key, value)) = assets
+        .largest(usize::MAX, &asset.id)
+        .into_iter()
+        .rev()
+        .find(|(key, value)| !selection.values.iter().any(|(k, v)| k == key && v == value))
+    {
+        selection.change = value.clone();
+        selection.values.push((key, value));
+    }
+}
+
+/// Selects the pre-senders which collectively own at least `asset`, returning any change. If
+/// `avoid_zero_notes` is set, the change is made non-zero whenever the transfer shape permits.
 #[inline]
 fn select<C>(
     accounts: &AccountTable<C>,
     assets: &C::AssetMap,
     parameters: &Parameters<C>,
     asset: &Asset<C>,
+    avoid_zero_notes: bool,
     rng: &mut C::Rng,
 ) -> Result<Selection<C>, SignError<C>>
 where
     C: Configuration,
 {
-    let selection = assets.select(asset);
+    let mut selection = assets.select(asset);
+    if avoid_zero_notes {
+        require_change::<C>(assets, asset, &mut selection);
+    }
     if !asset.is_zero() && selection.is_empty() {
         return Err(SignError::InsufficientBalance(asset.clone()));
     }
@@ -426,6 +795,90 @@ where
     })
 }
 
+/// Selects the precomputed [`Sender`]s in `membership_proofs` which collectively own at least
+/// `asset`, returning any change. Returns `None` if the selection does not fit into a single
+/// transfer or if any of the selected assets has no fresh membership proof at `checkpoint`, in
+/// which case the proofs have to be computed from the accumulator instead. If `avoid_zero_notes`
+/// is set, the selection is extended as in [`select`].
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)] // NOTE: Clippy is too harsh here.
+#[inline]
+fn select_cached<C>(
+    accounts: &AccountTable<C>,
+    assets: &C::AssetMap,
+    checkpoint: &C::Checkpoint,
+    membership_proofs: &mut MembershipProofCache<C>,
+    parameters: &Parameters<C>,
+    asset: &Asset<C>,
+    avoid_zero_notes: bool,
+    rng: &mut C::Rng,
+) -> Option<(C::AssetValue, [Sender<C>; PrivateTransferShape::SENDERS])>
+where
+    C: Configuration,
+{
+    if asset.is_zero() {
+        return None;
+    }
+    let mut selection = assets.select(asset);
+    if avoid_zero_notes {
+        require_change::<C>(assets, asset, &mut selection);
+    }
+    if selection.is_empty() || selection.values.len() > PrivateTransferShape::SENDERS {
+        return None;
+    }
+    let mut senders = membership_proofs.take(checkpoint, &asset.id, selection.keys())?;
+    while senders.len() < PrivateTransferShape::SENDERS {
+        let identifier = rng.gen();
+        senders.push(
+            build_pre_sender::<C>(
+                accounts,
+                parameters,
+                identifier,
+                Asset::<C>::new(asset.id.clone(), Default::default()),
+                rng,
+            )
+            .upgrade_unchecked(Default::default()),
+        );
+    }
+    Some((selection.change, into_array_unchecked(senders)))
+}
+
+/// Recomputes `membership_proofs` at `checkpoint` for the largest spendable assets of each asset
+/// id in `assets`.
+#[inline]
+pub fn precompute_membership_proofs<C>(
+    accounts: &AccountTable<C>,
+    parameters: &Parameters<C>,
+    assets: &C::AssetMap,
+    checkpoint: &C::Checkpoint,
+    utxo_accumulator: &C::UtxoAccumulator,
+    membership_proofs: &mut MembershipProofCache<C>,
+    rng: &mut C::Rng,
+) where
+    C: Configuration,
+{
+    membership_proofs.reset(Some(checkpoint.clone()));
+    let capacity = membership_proofs.capacity();
+    if capacity == 0 {
+        return;
+    }
+    for id in assets.assets().into_iter().map(|asset| asset.id) {
+        for (identifier, value) in assets.largest(capacity, &id) {
+            let sender = build_pre_sender::<C>(
+                accounts,
+                parameters,
+                identifier.clone(),
+                Asset::<C>::new(id.clone(), value),
+                rng,
+            )
+            .try_upgrade(parameters, utxo_accumulator);
+            if let Some(sender) = sender {
+                membership_proofs.push(id.clone(), identifier, sender);
+            }
+        }
+    }
+}
+
 /// Builds a [`TransferPost`] for the given `transfer`.
 #[inline]
 fn build_post_inner<
@@ -488,7 +941,7 @@ where
     build_post_inner(
         FullParametersRef::<C>::new(parameters, utxo_accumulator_model),
         proving_context,
-        spending_key.as_ref(),
+        spending_key.as_deref(),
         transfer,
         sink_accounts,
         rng,
@@ -590,7 +1043,8 @@ where
     Ok(senders)
 }
 
-/// Builds two virtual [`Sender`]s for `pre_sender`.
+/// Builds the virtual [`Sender`]s for `pre_sender`, completed with zero-value senders, the first
+/// of which carries `anchor_root` as its accumulator output.
 #[inline]
 fn virtual_senders<C>(
     accounts: &AccountTable<C>,
@@ -598,6 +1052,7 @@ fn virtual_senders<C>(
     parameters: &Parameters<C>,
     asset_id: &C::AssetId,
     pre_sender: PreSender<C>,
+    anchor_root: Option<UtxoAccumulatorOutput<C>>,
     rng: &mut C::Rng,
 ) -> Result<[Sender<C>; PrivateTransferShape::SENDERS], SignError<C>>
 where
@@ -607,41 +1062,151 @@ where
     let sender = pre_sender
         .insert_and_upgrade(parameters, &mut utxo_accumulator)
         .expect("Unable to upgrade expected UTXO.");
-    let mut senders = Vec::new();
-    senders.push(sender);
-    let identifier = rng.gen();
-    senders.push(
-        build_pre_sender::<C>(
-            accounts,
-            parameters,
-            identifier,
-            Asset::<C>::new(asset_id.clone(), Default::default()),
-            rng,
-        )
-        .upgrade_unchecked(Default::default()),
-    );
-    Ok(into_array_unchecked(senders))
+    Ok(pad_senders::<C>(
+        accounts,
+        parameters,
+        asset_id,
+        sender,
+        anchor_root,
+        rng,
+    ))
 }
 
-/// Computes the batched transactions for rebalancing before a final transfer.
-#[allow(clippy::too_many_arguments)]
+/// Completes `sender` with fresh zero-value [`Sender`]s of `asset_id`, which are never inserted
+/// into the accumulator and so cannot be spent twice. The membership proof of the first of them
+/// carries `anchor_root` as its accumulator output, which the circuit does not check for
+/// zero-value senders, so that `anchor_root` is bound into the proof and the signature.
 #[inline]
-fn compute_batched_transactions<C>(
+fn pad_senders<C>(
     accounts: &AccountTable<C>,
-    assets: &C::AssetMap,
-    utxo_accumulator: &mut C::UtxoAccumulator,
     parameters: &Parameters<C>,
-    proving_context: &MultiProvingContext<C>,
     asset_id: &C::AssetId,
-    mut pre_senders: Vec<PreSender<C>>,
-    posts: &mut Vec<TransferPost<C>>,
+    sender: Sender<C>,
+    mut anchor_root: Option<UtxoAccumulatorOutput<C>>,
     rng: &mut C::Rng,
-) -> Result<[Sender<C>; PrivateTransferShape::SENDERS], SignError<C>>
+) -> [Sender<C>; PrivateTransferShape::SENDERS]
+where
+    C: Configuration,
+{
+    let mut senders = Vec::new();
+    senders.push(sender);
+    while senders.len() < PrivateTransferShape::SENDERS {
+        let identifier = rng.gen();
+        senders.push(
+            build_pre_sender::<C>(
+                accounts,
+                parameters,
+                identifier,
+                Asset::<C>::new(asset_id.clone(), Default::default()),
+                rng,
+            )
+            .upgrade_unchecked(UtxoMembershipProof::<C>::new(
+                Default::default(),
+                anchor_root.take().unwrap_or_default(),
+            )),
+        );
+    }
+    into_array_unchecked(senders)
+}
+
+/// Pays every piece of `pieces` but the last one to `address` with its own private transfer,
+/// starting from `senders` and spending the change of each transfer in the next one. The posts
+/// are appended to `posts` and the openings of the payments to `openings`, and the senders which
+/// are left for the last piece are returned.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+fn split_payment<C>(
+    parameters: &SignerParameters<C>,
+    accounts: &AccountTable<C>,
+    utxo_accumulator: &mut C::UtxoAccumulator,
+    privacy_strategy: &PrivacyStrategy,
+    address: &Address<C>,
+    asset_id: &C::AssetId,
+    pieces: &[C::AssetValue],
+    mut senders: [Sender<C>; PrivateTransferShape::SENDERS],
+    posts: &mut Vec<TransferPost<C>>,
+    openings: &mut Vec<PaymentOpening<C>>,
+    rng: &mut C::Rng,
+) -> Result<[Sender<C>; PrivateTransferShape::SENDERS], SignError<C>>
+where
+    C: Configuration,
+{
+    let parameters_ref = &parameters.parameters;
+    for piece in pieces {
+        let total = CheckedBalance::try_sum(senders.iter().map(|s| s.asset().value))
+            .map_err(SignError::BalanceError)?;
+        let remainder = total
+            .checked_sub(piece.clone())
+            .expect("The senders of a split payment cover every piece of it.");
+        let (receiver, opening) = external_receiver::<C, _>(
+            parameters_ref,
+            address.clone(),
+            Asset::<C>::new(asset_id.clone(), piece.clone()),
+            Default::default(),
+            rng,
+        );
+        openings.push(opening);
+        let (change, pre_sender) = internal_pair::<C, _>(
+            parameters_ref,
+            &mut default_authorization_context::<C>(accounts, parameters_ref),
+            default_address::<C>(accounts, parameters_ref),
+            Asset::<C>::new(asset_id.clone(), remainder),
+            Default::default(),
+            rng,
+        );
+        let mut receivers = pad_receivers::<C, { PrivateTransferShape::RECEIVERS }>(
+            accounts,
+            parameters_ref,
+            asset_id,
+            Vec::from([change, receiver]),
+            rng,
+        );
+        privacy_strategy.order_receivers(&mut receivers, rng);
+        let authorization =
+            authorization_for_default_spending_key::<C>(accounts, parameters_ref, rng);
+        let post = build_post(
+            Some(accounts),
+            utxo_accumulator.model(),
+            parameters_ref,
+            &parameters.proving_context.private_transfer,
+            PrivateTransfer::build(authorization, senders, receivers),
+            Vec::new(),
+            rng,
+        )?;
+        for receiver in &post.body.receiver_posts {
+            utxo_accumulator.insert(&item_hash::<C>(parameters_ref, &receiver.utxo));
+        }
+        posts.push(post);
+        let sender = pre_sender
+            .try_upgrade(parameters_ref, utxo_accumulator)
+            .expect("Unable to upgrade expected UTXO.");
+        senders = pad_senders::<C>(accounts, parameters_ref, asset_id, sender, None, rng);
+        privacy_strategy.order_senders(&mut senders, rng);
+    }
+    Ok(senders)
+}
+
+/// Computes the batched transactions for rebalancing before a final transfer.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+fn compute_batched_transactions<C>(
+    accounts: &AccountTable<C>,
+    assets: &C::AssetMap,
+    utxo_accumulator: &mut C::UtxoAccumulator,
+    parameters: &Parameters<C>,
+    proving_context: &MultiProvingContext<C>,
+    asset_id: &C::AssetId,
+    mut pre_senders: Vec<PreSender<C>>,
+    privacy_strategy: &PrivacyStrategy,
+    posts: &mut Vec<TransferPost<C>>,
+    rng: &mut C::Rng,
+) -> Result<[Sender<C>; PrivateTransferShape::SENDERS], SignError<C>>
 where
     C: Configuration,
 {
     let mut new_zeroes = Vec::new();
     while pre_senders.len() > PrivateTransferShape::SENDERS {
+        privacy_strategy.order_senders(&mut pre_senders, rng);
         let mut joins = Vec::new();
         let mut iter = pre_senders
             .into_iter()
@@ -655,7 +1220,8 @@ where
                 accounts,
                 parameters,
                 asset_id,
-                senders.iter().map(|s| s.asset().value).sum(),
+                CheckedBalance::try_sum(senders.iter().map(|s| s.asset().value))
+                    .map_err(SignError::BalanceError)?,
                 rng,
             )?;
             let authorization =
@@ -676,7 +1242,7 @@ where
         joins.append(&mut iter.remainder());
         pre_senders = joins;
     }
-    let final_presenders = prepare_final_pre_senders(
+    let mut final_presenders = prepare_final_pre_senders(
         accounts,
         assets,
         utxo_accumulator,
@@ -686,6 +1252,7 @@ where
         pre_senders,
         rng,
     )?;
+    privacy_strategy.order_senders(&mut final_presenders, rng);
     Ok(into_array_unchecked(final_presenders))
 }
 
@@ -737,6 +1304,7 @@ pub fn sbt_sync<C>(
     authorization_context: &mut AuthorizationContext<C>,
     assets: &mut C::AssetMap,
     checkpoint: &mut C::Checkpoint,
+    quarantine: &mut Quarantine<C>,
     request: SyncRequest<C, C::Checkpoint>,
 ) -> Result<SyncResponse<C, C::Checkpoint>, SyncError<C::Checkpoint>>
 where
@@ -754,6 +1322,7 @@ where
         authorization_context,
         assets,
         checkpoint,
+        quarantine,
         &parameters.parameters,
         utxo_note_data.into_iter(),
         utxo_count,
@@ -763,6 +1332,10 @@ where
 }
 
 /// Updates `assets`, `checkpoint` and `utxo_accumulator`, returning the new asset distribution.
+/// If `avoid_zero_notes` is set, the zero-value notes are not added to the spendable `assets`.
+/// The notes which match the detection heuristics of `authorization_context` but cannot be opened
+/// are added to `quarantine`.
+#[allow(clippy::too_many_arguments)]
 #[inline]
 pub fn sync<C>(
     parameters: &SignerParameters<C>,
@@ -770,7 +1343,10 @@ pub fn sync<C>(
     assets: &mut C::AssetMap,
     checkpoint: &mut C::Checkpoint,
     utxo_accumulator: &mut C::UtxoAccumulator,
+    pending_imports: &mut Vec<IdentifiedAsset<C>>,
+    quarantine: &mut Quarantine<C>,
     request: SyncRequest<C, C::Checkpoint>,
+    avoid_zero_notes: bool,
     rng: &mut C::Rng,
 ) -> Result<SyncResponse<C, C::Checkpoint>, SyncError<C::Checkpoint>>
 where
@@ -788,87 +1364,190 @@ where
         assets,
         checkpoint,
         utxo_accumulator,
+        pending_imports,
+        quarantine,
         &parameters.parameters,
         utxo_note_data.into_iter(),
-        nullifier_data,
+        nullifier_data.into_inner(),
         !has_pruned,
+        avoid_zero_notes,
         rng,
     );
     utxo_accumulator.commit();
     Ok(response)
 }
 
-/// Signs a withdraw transaction for `asset` sent to `address`.
+/// Signs a withdraw transaction for `asset` sent to `address`, returning a [`PaymentReceipt`] if
+/// `address` is not `None`. The change is sent to `change_address` if it is not `None`, in which
+/// case a [`PaymentReceipt`] is also returned for any non-zero change, and to the default address
+/// otherwise. If `address` is not `None` and `splitting_strategy` splits `asset` into several
+/// pieces, each of them is paid by its own transfer and gets its own [`PaymentReceipt`].
 #[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)] // NOTE: Clippy is too harsh here.
 #[inline]
 fn sign_withdraw<C>(
     parameters: &SignerParameters<C>,
     accounts: &AccountTable<C>,
     assets: &C::AssetMap,
     utxo_accumulator: &mut C::UtxoAccumulator,
+    checkpoint: &C::Checkpoint,
+    membership_proofs: &mut MembershipProofCache<C>,
+    privacy_strategy: &PrivacyStrategy,
+    splitting_strategy: Option<&SplittingStrategy<C::AssetValue>>,
+    avoid_zero_notes: bool,
     asset: Asset<C>,
     address: Option<Address<C>>,
+    change_address: Option<Address<C>>,
     sink_accounts: Vec<C::AccountId>,
     rng: &mut C::Rng,
-) -> Result<SignResponse<C>, SignError<C>>
+) -> Result<(SignResponse<C>, Vec<PaymentReceipt<C>>), SignError<C>>
 where
     C: Configuration,
 {
-    let selection = select(accounts, assets, &parameters.parameters, &asset, rng)?;
     let mut posts = Vec::new();
-    let senders = compute_batched_transactions(
+    let cached_selection = select_cached(
         accounts,
         assets,
-        utxo_accumulator,
-        &parameters.parameters,
-        &parameters.proving_context,
-        &asset.id,
-        selection.pre_senders,
-        &mut posts,
-        rng,
-    )?;
-    let change = default_receiver::<C>(
-        accounts,
+        checkpoint,
+        membership_proofs,
         &parameters.parameters,
-        Asset::<C>::new(asset.id.clone(), selection.change),
+        &asset,
+        avoid_zero_notes,
         rng,
     );
+    let (change, senders) = match cached_selection {
+        Some((change, mut senders)) => {
+            privacy_strategy.order_senders(&mut senders, rng);
+            (change, senders)
+        }
+        _ => {
+            let selection = select(
+                accounts,
+                assets,
+                &parameters.parameters,
+                &asset,
+                avoid_zero_notes,
+                rng,
+            )?;
+            let senders = compute_batched_transactions(
+                accounts,
+                assets,
+                utxo_accumulator,
+                &parameters.parameters,
+                &parameters.proving_context,
+                &asset.id,
+                selection.pre_senders,
+                privacy_strategy,
+                &mut posts,
+                rng,
+            )?;
+            (selection.change, senders)
+        }
+    };
+    let mut openings = Vec::new();
+    let (asset, senders) = match (&address, splitting_strategy) {
+        (Some(address), Some(splitting_strategy)) => {
+            let mut pieces = splitting_strategy.split(asset.value.clone());
+            let last = pieces.pop().expect("Every split has at least one piece.");
+            let senders = split_payment(
+                parameters,
+                accounts,
+                utxo_accumulator,
+                privacy_strategy,
+                address,
+                &asset.id,
+                &pieces,
+                senders,
+                &mut posts,
+                &mut openings,
+                rng,
+            )?;
+            (Asset::<C>::new(asset.id, last), senders)
+        }
+        _ => (asset, senders),
+    };
+    let change = Asset::<C>::new(asset.id.clone(), change);
+    let change = match change_address {
+        Some(change_address) => {
+            let is_zero = change.is_zero();
+            let (receiver, opening) = external_receiver::<C, _>(
+                &parameters.parameters,
+                change_address,
+                change,
+                Default::default(),
+                rng,
+            );
+            if !is_zero {
+                openings.push(opening);
+            }
+            receiver
+        }
+        _ => default_receiver::<C>(accounts, &parameters.parameters, change, rng),
+    };
     let authorization =
         authorization_for_default_spending_key::<C>(accounts, &parameters.parameters, rng);
     let final_post = match address {
         Some(address) => {
-            let receiver = receiver::<C>(
+            let (receiver, opening) = external_receiver::<C, _>(
                 &parameters.parameters,
                 address,
-                asset,
+                asset.clone(),
                 Default::default(),
                 rng,
             );
+            openings.push(opening);
+            let mut receivers = pad_receivers::<C, { PrivateTransferShape::RECEIVERS }>(
+                accounts,
+                &parameters.parameters,
+                &asset.id,
+                Vec::from([change, receiver]),
+                rng,
+            );
+            privacy_strategy.order_receivers(&mut receivers, rng);
             build_post(
                 Some(accounts),
                 utxo_accumulator.model(),
                 &parameters.parameters,
                 &parameters.proving_context.private_transfer,
-                PrivateTransfer::build(authorization, senders, [change, receiver]),
+                PrivateTransfer::build(authorization, senders, receivers),
                 Vec::new(),
                 rng,
             )?
         }
-        _ => build_post(
-            Some(accounts),
-            utxo_accumulator.model(),
-            &parameters.parameters,
-            &parameters.proving_context.to_public,
-            ToPublic::build(authorization, senders, [change], asset),
-            sink_accounts,
-            rng,
-        )?,
+        _ => {
+            let receivers = pad_receivers::<C, { ToPublicShape::RECEIVERS }>(
+                accounts,
+                &parameters.parameters,
+                &asset.id,
+                Vec::from([change]),
+                rng,
+            );
+            build_post(
+                Some(accounts),
+                utxo_accumulator.model(),
+                &parameters.parameters,
+                &parameters.proving_context.to_public,
+                ToPublic::build(authorization, senders, receivers, asset),
+                sink_accounts,
+                rng,
+            )?
+        }
     };
     posts.push(final_post);
-    Ok(SignResponse::new(posts))
+    let transaction_id = C::TransactionIdFunction::transaction_id(&posts);
+    let receipts = openings
+        .into_iter()
+        .map(|opening| opening.into_receipt(&parameters.parameters, transaction_id.clone(), rng))
+        .collect();
+    Ok((SignResponse::new(posts), receipts))
 }
 
-/// Signs the `transaction`, generating transfer posts without releasing resources.
+/// Signs the `transaction`, generating transfer posts without releasing resources, and the
+/// [`PaymentReceipt`]s for the assets sent to other addresses, including the change if it is sent
+/// to `change_address`. A [`ToPrivate`] transaction has no change, so `change_address` is ignored
+/// for it.
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)] // NOTE: Clippy is too harsh here.
 #[inline]
 fn sign_internal<C>(
     parameters: &SignerParameters<C>,
@@ -876,9 +1555,15 @@ fn sign_internal<C>(
     authorization_context: Option<&mut AuthorizationContext<C>>,
     assets: &C::AssetMap,
     utxo_accumulator: &mut C::UtxoAccumulator,
+    checkpoint: &C::Checkpoint,
+    membership_proofs: &mut MembershipProofCache<C>,
+    privacy_strategy: &PrivacyStrategy,
+    splitting_strategy: Option<&SplittingStrategy<C::AssetValue>>,
+    avoid_zero_notes: bool,
     transaction: Transaction<C>,
+    change_address: Option<Address<C>>,
     rng: &mut C::Rng,
-) -> Result<SignResponse<C>, SignError<C>>
+) -> Result<(SignResponse<C>, Vec<PaymentReceipt<C>>), SignError<C>>
 where
     C: Configuration,
 {
@@ -890,23 +1575,32 @@ where
                 asset.clone(),
                 rng,
             );
-            Ok(SignResponse::new(vec![build_post(
-                None,
-                utxo_accumulator.model(),
-                &parameters.parameters,
-                &parameters.proving_context.to_private,
-                ToPrivate::build(asset, receiver),
+            Ok((
+                SignResponse::new(vec![build_post(
+                    None,
+                    utxo_accumulator.model(),
+                    &parameters.parameters,
+                    &parameters.proving_context.to_private,
+                    ToPrivate::build(asset, receiver),
+                    Vec::new(),
+                    rng,
+                )?]),
                 Vec::new(),
-                rng,
-            )?]))
+            ))
         }
         Transaction::PrivateTransfer(asset, address) => sign_withdraw(
             parameters,
             accounts.ok_or(SignError::MissingSpendingKey)?,
             assets,
             utxo_accumulator,
+            checkpoint,
+            membership_proofs,
+            privacy_strategy,
+            splitting_strategy,
+            avoid_zero_notes,
             asset,
             Some(address),
+            change_address,
             Vec::new(),
             rng,
         ),
@@ -915,15 +1609,23 @@ where
             accounts.ok_or(SignError::MissingSpendingKey)?,
             assets,
             utxo_accumulator,
+            checkpoint,
+            membership_proofs,
+            privacy_strategy,
+            None,
+            avoid_zero_notes,
             asset,
             None,
+            change_address,
             Vec::from([public_account]),
             rng,
         ),
     }
 }
 
-/// Signs the `transaction`, generating transfer posts.
+/// Signs the `transaction`, generating transfer posts. If the `transaction` pays another
+/// address, the [`PaymentReceipt`] for the payment is appended to `receipts`.
+#[allow(clippy::too_many_arguments)]
 #[inline]
 pub fn sign<C>(
     parameters: &SignerParameters<C>,
@@ -931,27 +1633,471 @@ pub fn sign<C>(
     authorization_context: Option<&mut AuthorizationContext<C>>,
     assets: &C::AssetMap,
     utxo_accumulator: &mut C::UtxoAccumulator,
+    checkpoint: &C::Checkpoint,
+    membership_proofs: &mut MembershipProofCache<C>,
+    privacy_strategy: &PrivacyStrategy,
+    splitting_strategy: Option<&SplittingStrategy<C::AssetValue>>,
+    avoid_zero_notes: bool,
+    transaction: Transaction<C>,
+    receipts: &mut Vec<PaymentReceipt<C>>,
+    rng: &mut C::Rng,
+) -> Result<SignResponse<C>, SignError<C>>
+where
+    C: Configuration,
+{
+    sign_with_change_address(
+        parameters,
+        accounts,
+        authorization_context,
+        assets,
+        utxo_accumulator,
+        checkpoint,
+        membership_proofs,
+        privacy_strategy,
+        splitting_strategy,
+        avoid_zero_notes,
+        transaction,
+        None,
+        receipts,
+        rng,
+    )
+}
+
+/// Signs the `transaction`, generating transfer posts which send the change to `change_address`
+/// instead of the default address. The [`PaymentReceipt`]s for the payment and for any non-zero
+/// change are appended to `receipts`, so that the change can be handed over to the owner of
+/// `change_address`.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub fn sign_with_change_address<C>(
+    parameters: &SignerParameters<C>,
+    accounts: Option<&AccountTable<C>>,
+    authorization_context: Option<&mut AuthorizationContext<C>>,
+    assets: &C::AssetMap,
+    utxo_accumulator: &mut C::UtxoAccumulator,
+    checkpoint: &C::Checkpoint,
+    membership_proofs: &mut MembershipProofCache<C>,
+    privacy_strategy: &PrivacyStrategy,
+    splitting_strategy: Option<&SplittingStrategy<C::AssetValue>>,
+    avoid_zero_notes: bool,
     transaction: Transaction<C>,
+    change_address: Option<Address<C>>,
+    receipts: &mut Vec<PaymentReceipt<C>>,
     rng: &mut C::Rng,
 ) -> Result<SignResponse<C>, SignError<C>>
 where
     C: Configuration,
 {
-    let result = sign_internal(
+    let (result, new_receipts) = sign_internal(
         parameters,
         accounts,
         authorization_context,
         assets,
         utxo_accumulator,
+        checkpoint,
+        membership_proofs,
+        privacy_strategy,
+        splitting_strategy,
+        avoid_zero_notes,
         transaction,
+        change_address,
         rng,
     )?;
     utxo_accumulator.rollback();
+    receipts.extend(new_receipts);
     Ok(result)
 }
 
+/// Returns the number of transfer posts which spend `notes`-many notes, including the transfers
+/// which merge them when they do not fit into a single transfer, as in
+/// [`compute_batched_transactions`].
+#[inline]
+fn post_count(mut notes: usize) -> usize {
+    let mut posts = 1;
+    while notes > PrivateTransferShape::SENDERS {
+        posts += notes / PrivateTransferShape::SENDERS;
+        notes = notes / PrivateTransferShape::SENDERS + notes % PrivateTransferShape::SENDERS;
+    }
+    posts
+}
+
+/// Plans the `transaction` against `assets` without signing it, returning the
+/// [`TransactionSummary`] of the posts which [`sign`] generates for it in the current state.
+#[inline]
+pub fn review<C>(
+    accounts: Option<&AccountTable<C>>,
+    authorization_context: Option<&AuthorizationContext<C>>,
+    assets: &C::AssetMap,
+    splitting_strategy: Option<&SplittingStrategy<C::AssetValue>>,
+    avoid_zero_notes: bool,
+    transaction: Transaction<C>,
+) -> Result<TransactionSummary<C>, SignError<C>>
+where
+    C: Configuration,
+{
+    let (asset, recipient) = match transaction {
+        Transaction::ToPrivate(asset) => {
+            authorization_context.ok_or(SignError::MissingProofAuthorizationKey)?;
+            let mut summary = TransactionSummary {
+                inputs: Vec::new(),
+                recipients: Vec::new(),
+                posts: 1,
+                balance_changes: Vec::new(),
+                privacy_notes: vec![
+                    PrivacyNote::RevealsAssetId,
+                    PrivacyNote::RevealsAmount,
+                    PrivacyNote::RevealsPublicAccount,
+                ],
+            };
+            if !asset.is_zero() {
+                summary.balance_changes.push((
+                    asset.id.clone(),
+                    BalanceChange::Increase(asset.value.clone()),
+                ));
+                summary.recipients.push((Recipient::Signer, asset));
+            }
+            return Ok(summary);
+        }
+        Transaction::PrivateTransfer(asset, address) => (asset, Recipient::Address(address)),
+        Transaction::ToPublic(asset, account) => (asset, Recipient::PublicAccount(account)),
+    };
+    accounts.ok_or(SignError::MissingSpendingKey)?;
+    let mut selection = assets.select(&asset);
+    if avoid_zero_notes {
+        require_change::<C>(assets, &asset, &mut selection);
+    }
+    if !asset.is_zero() && selection.is_empty() {
+        return Err(SignError::InsufficientBalance(asset));
+    }
+    let pieces = match (&recipient, splitting_strategy) {
+        (Recipient::Address(_), Some(splitting_strategy)) => {
+            splitting_strategy.split(asset.value.clone())
+        }
+        _ => Vec::from([asset.value.clone()]),
+    };
+    let merging_posts = post_count(selection.values.len());
+    let mut privacy_notes = Vec::new();
+    if matches!(recipient, Recipient::PublicAccount(_)) {
+        privacy_notes.extend([
+            PrivacyNote::RevealsAssetId,
+            PrivacyNote::RevealsAmount,
+            PrivacyNote::RevealsPublicAccount,
+        ]);
+    }
+    if merging_posts > 1 {
+        privacy_notes.push(PrivacyNote::LinksNotes);
+    }
+    let posts = merging_posts + pieces.len() - 1;
+    let mut recipients = Vec::new();
+    let mut balance_changes = Vec::new();
+    if !asset.is_zero() {
+        balance_changes.push((
+            asset.id.clone(),
+            BalanceChange::Decrease(asset.value.clone()),
+        ));
+        recipients.extend(
+            pieces
+                .into_iter()
+                .map(|piece| (recipient.clone(), Asset::<C>::new(asset.id.clone(), piece))),
+        );
+    }
+    let change = Asset::<C>::new(asset.id.clone(), selection.change);
+    if !change.is_zero() {
+        recipients.push((Recipient::Signer, change));
+    }
+    Ok(TransactionSummary {
+        inputs: selection
+            .values
+            .into_iter()
+            .map(|(key, value)| (key, Asset::<C>::new(asset.id.clone(), value)))
+            .collect(),
+        recipients,
+        posts,
+        balance_changes,
+        privacy_notes,
+    })
+}
+
+/// Returns the [`Identifier`]s of the notes in `assets` which are spent by the senders of
+/// `posts`.
+#[inline]
+fn spent_identifiers<C>(
+    authorization_context: &mut AuthorizationContext<C>,
+    parameters: &Parameters<C>,
+    assets: &C::AssetMap,
+    posts: &[TransferPost<C>],
+    rng: &mut C::Rng,
+) -> Vec<Identifier<C>>
+where
+    C: Configuration,
+{
+    spent_entries(authorization_context, parameters, assets, posts, rng)
+        .into_iter()
+        .map(|(identifier, _)| identifier)
+        .collect()
+}
+
+/// Returns the entries of `assets` which are spent by the senders of `posts`.
+#[inline]
+fn spent_entries<C>(
+    authorization_context: &mut AuthorizationContext<C>,
+    parameters: &Parameters<C>,
+    assets: &C::AssetMap,
+    posts: &[TransferPost<C>],
+    rng: &mut C::Rng,
+) -> Vec<(Identifier<C>, Asset<C>)>
+where
+    C: Configuration,
+{
+    let nullifiers = posts
+        .iter()
+        .flat_map(|post| {
+            post.body
+                .sender_posts
+                .iter()
+                .map(|sender| &sender.nullifier)
+        })
+        .collect::<Vec<_>>();
+    if nullifiers.is_empty() {
+        return Vec::new();
+    }
+    assets
+        .entries()
+        .into_iter()
+        .filter_map(|(identifier, asset)| {
+            let (_, _, nullifier) = parameters.derive_spend(
+                authorization_context,
+                identifier.clone(),
+                asset.clone(),
+                rng,
+            );
+            nullifiers
+                .iter()
+                .any(|n| (*n).is_related(&nullifier))
+                .then_some((identifier, asset))
+        })
+        .collect()
+}
+
+/// Signs the `transaction` like [`sign`], but leases the spent notes to the device of
+/// `configuration` in `leases` at time `now`. The notes leased by other devices are handled
+/// according to the [`ConflictPolicy`] of `configuration`.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub fn sign_with_lease<C>(
+    parameters: &SignerParameters<C>,
+    accounts: Option<&AccountTable<C>>,
+    mut authorization_context: Option<&mut AuthorizationContext<C>>,
+    assets: &C::AssetMap,
+    utxo_accumulator: &mut C::UtxoAccumulator,
+    checkpoint: &C::Checkpoint,
+    membership_proofs: &mut MembershipProofCache<C>,
+    privacy_strategy: &PrivacyStrategy,
+    splitting_strategy: Option<&SplittingStrategy<C::AssetValue>>,
+    avoid_zero_notes: bool,
+    leases: &mut LeaseTable<C>,
+    configuration: &LeaseConfiguration,
+    transaction: Transaction<C>,
+    receipts: &mut Vec<PaymentReceipt<C>>,
+    now: u64,
+    rng: &mut C::Rng,
+) -> Result<SignResponse<C>, SignError<C>>
+where
+    C: Configuration,
+{
+    leases.expire(now);
+    let device = configuration.device;
+    let mut available = C::AssetMap::default();
+    let spendable = match configuration.policy {
+        ConflictPolicy::Avoid => {
+            available.insert_all(assets.entries().into_iter().filter(|(identifier, _)| {
+                leases.foreign_lease(identifier, &device, now).is_none()
+            }));
+            &available
+        }
+        _ => assets,
+    };
+    let mut new_receipts = Vec::new();
+    let response = sign(
+        parameters,
+        accounts,
+        authorization_context.as_deref_mut(),
+        spendable,
+        utxo_accumulator,
+        checkpoint,
+        membership_proofs,
+        privacy_strategy,
+        splitting_strategy,
+        avoid_zero_notes,
+        transaction,
+        &mut new_receipts,
+        rng,
+    )?;
+    let spent = match authorization_context {
+        Some(authorization_context) => spent_identifiers(
+            authorization_context,
+            &parameters.parameters,
+            spendable,
+            &response.posts,
+            rng,
+        ),
+        _ => Vec::new(),
+    };
+    if configuration.policy == ConflictPolicy::Wait {
+        if let Some(lease) = spent
+            .iter()
+            .find_map(|identifier| leases.foreign_lease(identifier, &device, now))
+        {
+            return Err(SignError::LeaseConflict(lease.device));
+        }
+    }
+    leases.acquire(device, spent, now, configuration.duration);
+    receipts.extend(new_receipts);
+    Ok(response)
+}
+
+/// Signs the `transactions` jointly over `assets`, so that no note is spent by more than one of
+/// them. The notes spent by each transaction are taken out of the notes available to the
+/// transactions after it. If any of the `transactions` fails, the posts of the others are
+/// discarded and every failure is returned together with the index of its transaction. Otherwise,
+/// the [`PaymentReceipt`]s of all the `transactions` are appended to `receipts`.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub fn sign_batch<C>(
+    parameters: &SignerParameters<C>,
+    accounts: Option<&AccountTable<C>>,
+    mut authorization_context: Option<&mut AuthorizationContext<C>>,
+    assets: &C::AssetMap,
+    utxo_accumulator: &mut C::UtxoAccumulator,
+    checkpoint: &C::Checkpoint,
+    membership_proofs: &mut MembershipProofCache<C>,
+    privacy_strategy: &PrivacyStrategy,
+    splitting_strategy: Option<&SplittingStrategy<C::AssetValue>>,
+    avoid_zero_notes: bool,
+    transactions: Vec<Transaction<C>>,
+    receipts: &mut Vec<PaymentReceipt<C>>,
+    rng: &mut C::Rng,
+) -> SignBatchResult<C>
+where
+    C: Configuration,
+{
+    let mut available = C::AssetMap::default();
+    available.insert_all(assets.entries());
+    let mut responses = Vec::with_capacity(transactions.len());
+    let mut failures = Vec::new();
+    let mut new_receipts = Vec::new();
+    for (index, transaction) in transactions.into_iter().enumerate() {
+        match sign(
+            parameters,
+            accounts,
+            authorization_context.as_deref_mut(),
+            &available,
+            utxo_accumulator,
+            checkpoint,
+            membership_proofs,
+            privacy_strategy,
+            splitting_strategy,
+            avoid_zero_notes,
+            transaction,
+            &mut new_receipts,
+            rng,
+        ) {
+            Ok(response) => {
+                if let Some(authorization_context) = authorization_context.as_deref_mut() {
+                    let spent = spent_entries(
+                        authorization_context,
+                        &parameters.parameters,
+                        &available,
+                        &response.posts,
+                        rng,
+                    );
+                    available.remove_all(spent);
+                }
+                responses.push(response);
+            }
+            Err(err) => failures.push((index, err)),
+        }
+    }
+    if !failures.is_empty() {
+        return Err(SignBatchError { failures });
+    }
+    receipts.extend(new_receipts);
+    Ok(SignBatchResponse(responses))
+}
+
+/// Checks `posts` against `snapshot` in order, tracking the spent nullifiers and inserting the
+/// receivers of each post into `utxo_accumulator` so that the senders of the later posts are
+/// checked against the accumulator outputs that the ledger would have after the earlier ones.
+#[inline]
+fn check_posts_with<C>(
+    parameters: &Parameters<C>,
+    snapshot: &mut C::LedgerSnapshot,
+    utxo_accumulator: &mut C::UtxoAccumulator,
+    posts: &[TransferPost<C>],
+) -> Result<(), LedgerSnapshotError>
+where
+    C: Configuration,
+{
+    let mut nullifiers = Vec::<&Nullifier<C>>::new();
+    for (post_index, post) in posts.iter().enumerate() {
+        for (sender_index, sender) in post.body.sender_posts.iter().enumerate() {
+            if !snapshot.has_utxo_accumulator_output(&sender.utxo_accumulator_output) {
+                return Err(LedgerSnapshotError::UnknownUtxoAccumulatorOutput {
+                    post: post_index,
+                    sender: sender_index,
+                });
+            }
+            if nullifiers
+                .iter()
+                .any(|n| (*n).is_related(&sender.nullifier))
+            {
+                return Err(LedgerSnapshotError::DuplicateNullifier {
+                    post: post_index,
+                    sender: sender_index,
+                });
+            }
+            if snapshot.may_contain_nullifier(&sender.nullifier) {
+                return Err(LedgerSnapshotError::AssetSpent {
+                    post: post_index,
+                    sender: sender_index,
+                });
+            }
+            if snapshot.may_contain_pending_nullifier(&sender.nullifier) {
+                return Err(LedgerSnapshotError::AssetPending {
+                    post: post_index,
+                    sender: sender_index,
+                });
+            }
+            nullifiers.push(&sender.nullifier);
+        }
+        for receiver in &post.body.receiver_posts {
+            utxo_accumulator.insert_nonprovable(&item_hash::<C>(parameters, &receiver.utxo));
+        }
+        snapshot.update_from_utxo_accumulator(utxo_accumulator);
+    }
+    Ok(())
+}
+
+/// Checks that the ledger described by `snapshot` would accept `posts`, leaving
+/// `utxo_accumulator` unchanged.
+#[inline]
+pub fn check_posts<C>(
+    parameters: &Parameters<C>,
+    snapshot: &C::LedgerSnapshot,
+    utxo_accumulator: &mut C::UtxoAccumulator,
+    posts: &[TransferPost<C>],
+) -> Result<(), LedgerSnapshotError>
+where
+    C: Configuration,
+{
+    let result = check_posts_with::<C>(parameters, &mut snapshot.clone(), utxo_accumulator, posts);
+    utxo_accumulator.rollback();
+    result
+}
+
 /// Generates an [`IdentityProof`] for `identified_asset` by
-/// signing a virtual [`ToPublic`] transaction.
+/// signing a virtual [`ToPublic`] transaction. If `anchor_root` is given, it is bound into the
+/// transaction as the root of a [`LedgerAnchor`](crate::transfer::LedgerAnchor), which the caller attaches to the proof.
 #[inline]
 pub fn identity_proof<C>(
     parameters: &SignerParameters<C>,
@@ -959,6 +2105,7 @@ pub fn identity_proof<C>(
     utxo_accumulator_model: &UtxoAccumulatorModel<C>,
     identified_asset: IdentifiedAsset<C>,
     public_account: C::AccountId,
+    anchor_root: Option<UtxoAccumulatorOutput<C>>,
     rng: &mut C::Rng,
 ) -> Option<IdentityProof<C>>
 where
@@ -977,13 +2124,15 @@ where
         &parameters.parameters,
         &identified_asset.asset.id,
         presender,
+        anchor_root,
         rng,
     )
     .ok()?;
-    let change = default_receiver::<C>(
+    let receivers = pad_receivers::<C, { ToPublicShape::RECEIVERS }>(
         accounts,
         &parameters.parameters,
-        Asset::<C>::new(identified_asset.asset.id.clone(), Default::default()),
+        &identified_asset.asset.id,
+        Vec::new(),
         rng,
     );
     let authorization =
@@ -993,12 +2142,66 @@ where
         utxo_accumulator_model,
         &parameters.parameters,
         &parameters.proving_context.to_public,
-        ToPublic::build(authorization, senders, [change], identified_asset.asset),
+        ToPublic::build(authorization, senders, receivers, identified_asset.asset),
         Vec::from([public_account]),
         rng,
     )
     .ok()?;
-    Some(IdentityProof { transfer_post })
+    Some(IdentityProof {
+        transfer_post,
+        anchor: None,
+    })
+}
+
+/// Generates a [`HoldingsProof`] that the default account of `accounts` owns a note of at least
+/// `threshold` of the asset with `asset_id`, proving it with the largest such note in `assets`.
+/// Returns `None` if there is no such note or if the proof cannot be generated.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub fn holdings_proof<C>(
+    parameters: &Parameters<C>,
+    proving_context: &ProvingContext<C>,
+    accounts: &AccountTable<C>,
+    assets: &C::AssetMap,
+    utxo_accumulator: &C::UtxoAccumulator,
+    asset_id: C::AssetId,
+    threshold: C::AssetValue,
+    rng: &mut C::Rng,
+) -> Option<HoldingsProof<C>>
+where
+    C: Configuration,
+{
+    let (identifier, value) = assets.largest(1, &asset_id).pop()?;
+    Holdings::<C>::build(
+        parameters,
+        &default_spending_key::<C>(accounts, parameters),
+        utxo_accumulator,
+        IdentifiedAsset::<C>::new(identifier, Asset::<C>::new(asset_id, value)),
+        threshold,
+        rng,
+    )?
+    .into_proof(
+        FullParametersRef::<C>::new(parameters, utxo_accumulator.model()),
+        proving_context,
+        rng,
+    )
+    .ok()
+}
+
+/// Answers `challenge` with an [`OwnershipProof`] for the [`Address`] of `authorization_context`.
+#[inline]
+pub fn ownership_proof<C>(
+    parameters: &SignerParameters<C>,
+    authorization_context: &mut AuthorizationContext<C>,
+    challenge: &OwnershipChallenge,
+    rng: &mut C::Rng,
+) -> OwnershipProof<C>
+where
+    C: Configuration,
+{
+    let parameters = &parameters.parameters;
+    let decryption_key = Zeroizing::new(parameters.derive_decryption_key(authorization_context));
+    OwnershipProof::<C>::prove(parameters, &decryption_key, challenge, rng)
 }
 
 /// Returns the associated [`TransactionData`] of `post`, namely the [`Asset`] and the
@@ -1015,14 +2218,14 @@ where
 {
     let shape = TransferShape::from_post(&post)?;
     let parameters = &parameters.parameters;
-    let decryption_key = parameters.derive_decryption_key(authorization_context);
+    let decryption_key = Zeroizing::new(parameters.derive_decryption_key(authorization_context));
     match shape {
         TransferShape::ToPrivate => {
-            let ReceiverPost { utxo, note } = post.body.receiver_posts.take_first();
+            let ReceiverPost { utxo, note } = post.body.receiver_posts.into_inner().take_first();
             let (identifier, asset) = parameters.open_with_check(&decryption_key, &utxo, note)?;
             Some(TransactionData::<C>::ToPrivate(identifier, asset))
         }
-        TransferShape::PrivateTransfer => {
+        TransferShape::PrivateTransfer | TransferShape::PrivateSwap => {
             let mut transaction_data = Vec::new();
             let receiver_posts = post.body.receiver_posts;
             for receiver_post in receiver_posts.into_iter() {
@@ -1035,12 +2238,14 @@ where
             }
             if transaction_data.is_empty() {
                 None
+            } else if shape == TransferShape::PrivateSwap {
+                Some(TransactionData::<C>::PrivateSwap(transaction_data))
             } else {
                 Some(TransactionData::<C>::PrivateTransfer(transaction_data))
             }
         }
         TransferShape::ToPublic => {
-            let ReceiverPost { utxo, note } = post.body.receiver_posts.take_first();
+            let ReceiverPost { utxo, note } = post.body.receiver_posts.into_inner().take_first();
             let (identifier, asset) = parameters.open_with_check(&decryption_key, &utxo, note)?;
             Some(TransactionData::<C>::ToPublic(identifier, asset))
         }
@@ -1049,6 +2254,7 @@ where
 
 /// Signs the `transaction`, generating transfer posts
 /// and returning their [`TransactionData`].
+#[allow(clippy::too_many_arguments)]
 #[inline]
 pub fn sign_with_transaction_data<C>(
     parameters: &SignerParameters<C>,
@@ -1056,7 +2262,13 @@ pub fn sign_with_transaction_data<C>(
     authorization_context: &mut AuthorizationContext<C>,
     assets: &C::AssetMap,
     utxo_accumulator: &mut C::UtxoAccumulator,
+    checkpoint: &C::Checkpoint,
+    membership_proofs: &mut MembershipProofCache<C>,
+    privacy_strategy: &PrivacyStrategy,
+    splitting_strategy: Option<&SplittingStrategy<C::AssetValue>>,
+    avoid_zero_notes: bool,
     transaction: Transaction<C>,
+    receipts: &mut Vec<PaymentReceipt<C>>,
     rng: &mut C::Rng,
 ) -> SignWithTransactionDataResult<C>
 where
@@ -1070,7 +2282,13 @@ where
             Some(authorization_context),
             assets,
             utxo_accumulator,
+            checkpoint,
+            membership_proofs,
+            privacy_strategy,
+            splitting_strategy,
+            avoid_zero_notes,
             transaction,
+            receipts,
             rng,
         )?
         .posts
@@ -1144,6 +2362,108 @@ where
             balance_update: BalanceUpdate::Full {
                 assets: assets.assets().into(),
             },
+            quarantined: 0,
+            duplicates: 0,
         },
     )
 }
+
+/// Rebuilds `utxo_accumulator` from the frontier and the membership proofs in `request`, and
+/// updates `assets`, `pending_imports` and `checkpoint`, returning the new asset distribution.
+///
+/// The notes in `assets` whose [`Utxo`] has no membership proof in `request` are moved to
+/// `pending_imports`, and the notes in `pending_imports` whose [`Utxo`] has one are moved to
+/// `assets`.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub fn frontier_sync<C>(
+    parameters: &Parameters<C>,
+    authorization_context: &mut AuthorizationContext<C>,
+    assets: &mut C::AssetMap,
+    checkpoint: &mut C::Checkpoint,
+    utxo_accumulator: &mut C::UtxoAccumulator,
+    pending_imports: &mut Vec<IdentifiedAsset<C>>,
+    request: FrontierSyncRequest<C, C::Checkpoint>,
+    rng: &mut C::Rng,
+) -> Result<SyncResponse<C, C::Checkpoint>, SyncError<C::Checkpoint>>
+where
+    C: Configuration,
+{
+    if checkpoint != &request.origin_checkpoint {
+        return Err(SyncError::InconsistentSynchronization {
+            checkpoint: checkpoint.clone(),
+        });
+    }
+    let FrontierSyncData {
+        frontier_data,
+        membership_proof_data,
+        nullifier_data,
+    } = request.data;
+    let mut nullifier_data = nullifier_data.into_inner();
+    let nullifier_count = nullifier_data.len();
+    let accumulator = C::UtxoAccumulator::from_frontier_and_witnesses(
+        utxo_accumulator.model(),
+        frontier_data
+            .into_iter()
+            .map(|entry| entry.map(|(utxo, witness)| (item_hash::<C>(parameters, &utxo), witness)))
+            .collect(),
+        membership_proof_data
+            .into_iter()
+            .map(|(utxo, witness)| (item_hash::<C>(parameters, &utxo), witness))
+            .collect(),
+    );
+    let mut is_provable = |identified_asset: &IdentifiedAsset<C>| {
+        let utxo =
+            identified_asset_utxo::<C>(authorization_context, parameters, identified_asset, rng);
+        accumulator
+            .prove(&item_hash::<C>(parameters, &utxo))
+            .is_some()
+    };
+    let previous_pending_imports = core::mem::take(pending_imports);
+    assets.retain(|identifier, assets| {
+        assets.retain(|asset| {
+            let identified_asset = IdentifiedAsset::<C>::new(identifier.clone(), asset.clone());
+            if is_provable(&identified_asset) {
+                true
+            } else {
+                pending_imports.push(identified_asset);
+                false
+            }
+        });
+        !assets.is_empty()
+    });
+    for identified_asset in previous_pending_imports {
+        if is_provable(&identified_asset) {
+            assets.insert(identified_asset.identifier, identified_asset.asset);
+        } else {
+            pending_imports.push(identified_asset);
+        }
+    }
+    *utxo_accumulator = accumulator;
+    assets.retain(|identifier, assets| {
+        assets.retain(|asset| {
+            is_asset_unspent::<C>(
+                authorization_context,
+                utxo_accumulator,
+                parameters,
+                identifier.clone(),
+                asset.clone(),
+                &mut nullifier_data,
+                &mut Vec::new(),
+                rng,
+            )
+        });
+        !assets.is_empty()
+    });
+    utxo_accumulator.commit();
+    checkpoint.update_from_nullifiers(nullifier_count);
+    checkpoint.update_from_utxo_accumulator(utxo_accumulator);
+    Ok(SyncResponse {
+        checkpoint: checkpoint.clone(),
+        balance_update: BalanceUpdate::Full {
+            assets: assets.assets().into(),
+        },
+        quarantined: 0,
+        duplicates: 0,
+    })
+}
@@ -0,0 +1,330 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signer Health
+//!
+//! The state of a signer can only be restored from its seed by synchronizing with the whole
+//! ledger, and some of it, like the notes imported out-of-band or the payment receipts, cannot be
+//! restored at all, so host applications should remind their users to back up the signer state.
+//! The [`HealthRecord`] of a signer tracks its backups, its synchronizations and the value it
+//! received since the last backup, and reports them as a [`SignerHealth`] whose flags are raised
+//! according to the [`HealthSettings`] of the signer.
+//!
+//! # Clocks
+//!
+//! The signer has no clock, so the times of the backups and synchronizations are given by the
+//! caller, in milliseconds since the Unix epoch, as for [`leases`](super::lease).
+
+use crate::{
+    asset::{AssetList, AssetMap},
+    transfer::Asset,
+    wallet::signer::{BalanceUpdate, Configuration},
+};
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Parameter Versions
+///
+/// Versions of the transfer parameters and proving contexts loaded into a signer, which are
+/// compared against the versions expected by the host application.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ParameterVersions {
+    /// Transfer Parameters Version
+    pub parameters: u32,
+
+    /// Proving Context Version
+    pub proving_context: u32,
+}
+
+impl ParameterVersions {
+    /// Builds a new [`ParameterVersions`] from the `parameters` and `proving_context` versions.
+    #[inline]
+    pub fn new(parameters: u32, proving_context: u32) -> Self {
+        Self {
+            parameters,
+            proving_context,
+        }
+    }
+
+    /// Returns `true` if any of the versions in `self` is older than the one in `minimum`.
+    #[inline]
+    pub fn is_older_than(&self, minimum: &Self) -> bool {
+        self.parameters < minimum.parameters || self.proving_context < minimum.proving_context
+    }
+}
+
+/// Health Settings
+///
+/// Thresholds after which a [`SignerHealth`] report raises its flags. Thresholds which are not set
+/// never raise their flags.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct HealthSettings<V> {
+    /// Backup Interval in Milliseconds
+    ///
+    /// Time after the last backup when a new backup is due.
+    pub backup_interval: Option<u64>,
+
+    /// Unbacked Value Limit
+    ///
+    /// Value of any single asset which can be received after the last backup before a new backup
+    /// is due.
+    pub unbacked_value_limit: Option<V>,
+
+    /// Synchronization Interval in Milliseconds
+    ///
+    /// Time after the last synchronization when the UTXO accumulator of the signer is stale.
+    pub sync_interval: Option<u64>,
+
+    /// Minimum Parameter Versions
+    pub minimum_parameter_versions: Option<ParameterVersions>,
+}
+
+/// Health Record
+///
+/// Backups and synchronizations of a signer, together with the value it received since the last
+/// backup.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "C::AssetId: Deserialize<'de>, C::AssetValue: Deserialize<'de>",
+            serialize = "C::AssetId: Serialize, C::AssetValue: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "C::AssetId: Clone, C::AssetValue: Clone"),
+    Debug(bound = "C::AssetId: Debug, C::AssetValue: Debug"),
+    Default(bound = ""),
+    Eq(bound = "C::AssetId: Eq, C::AssetValue: Eq"),
+    Hash(bound = "C::AssetId: Hash, C::AssetValue: Hash"),
+    PartialEq(bound = "C::AssetId: PartialEq, C::AssetValue: PartialEq")
+)]
+pub struct HealthRecord<C>
+where
+    C: Configuration,
+{
+    /// Health Settings
+    settings: HealthSettings<C::AssetValue>,
+
+    /// Time of the Last Backup
+    last_backup: Option<u64>,
+
+    /// Time of the Last Synchronization
+    last_sync: Option<u64>,
+
+    /// Value Received since the Last Backup
+    unbacked_value: AssetList<C::AssetId, C::AssetValue>,
+}
+
+impl<C> HealthRecord<C>
+where
+    C: Configuration,
+{
+    /// Returns the [`HealthSettings`] of `self`.
+    #[inline]
+    pub fn settings(&self) -> &HealthSettings<C::AssetValue> {
+        &self.settings
+    }
+
+    /// Replaces the [`HealthSettings`] of `self` with `settings`.
+    #[inline]
+    pub fn set_settings(&mut self, settings: HealthSettings<C::AssetValue>) {
+        self.settings = settings;
+    }
+
+    /// Returns the time of the last backup recorded in `self`.
+    #[inline]
+    pub fn last_backup(&self) -> Option<u64> {
+        self.last_backup
+    }
+
+    /// Returns the time of the last synchronization recorded in `self`.
+    #[inline]
+    pub fn last_sync(&self) -> Option<u64> {
+        self.last_sync
+    }
+
+    /// Returns the value received since the last backup recorded in `self`.
+    #[inline]
+    pub fn unbacked_value(&self) -> &AssetList<C::AssetId, C::AssetValue> {
+        &self.unbacked_value
+    }
+
+    /// Records a backup of the signer state at time `now`, after which no value is unbacked.
+    #[inline]
+    pub fn record_backup(&mut self, now: u64) {
+        self.last_backup = Some(now);
+        self.unbacked_value.clear();
+    }
+
+    /// Records a synchronization at time `now` which reported `balance_update` for the signer
+    /// with `assets`.
+    ///
+    /// Partial updates add their deposits to the unbacked value. Full updates replace the balance
+    /// of the signer, so the whole balance is considered unbacked until the next backup.
+    #[inline]
+    pub fn record_sync(
+        &mut self,
+        now: u64,
+        balance_update: &BalanceUpdate<C>,
+        assets: &C::AssetMap,
+    ) {
+        self.last_sync = Some(now);
+        match balance_update {
+            BalanceUpdate::Partial { deposit, .. } => {
+                for asset in deposit {
+                    self.unbacked_value.deposit(asset.clone());
+                }
+            }
+            BalanceUpdate::Full { .. } => self.unbacked_value = assets.assets(),
+        }
+    }
+
+    /// Returns the [`SignerHealth`] at time `now` of a signer with `self` as its record, which is
+    /// at `checkpoint` with `parameter_versions`, and whose membership proofs are fresh whenever
+    /// `membership_proofs_fresh` is `true`.
+    #[inline]
+    pub fn report(
+        &self,
+        now: u64,
+        checkpoint: C::Checkpoint,
+        parameter_versions: ParameterVersions,
+        membership_proofs_fresh: bool,
+    ) -> SignerHealth<C> {
+        let elapsed = |last: Option<u64>, interval: Option<u64>| match (last, interval) {
+            (Some(last), Some(interval)) => now.saturating_sub(last) > interval,
+            (None, Some(_)) => true,
+            _ => false,
+        };
+        let over_limit = match &self.settings.unbacked_value_limit {
+            Some(limit) => self.unbacked_value.iter().any(|asset| &asset.value > limit),
+            _ => false,
+        };
+        SignerHealth {
+            last_backup: self.last_backup,
+            unbacked_value: self.unbacked_value.iter().cloned().collect(),
+            backup_due: over_limit || elapsed(self.last_backup, self.settings.backup_interval),
+            last_sync: self.last_sync,
+            checkpoint,
+            accumulator_stale: elapsed(self.last_sync, self.settings.sync_interval),
+            membership_proofs_stale: !membership_proofs_fresh,
+            parameter_versions,
+            parameters_outdated: self
+                .settings
+                .minimum_parameter_versions
+                .map(|minimum| parameter_versions.is_older_than(&minimum))
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Signer Health
+///
+/// Report of the state of a signer returned by [`Signer::health`](super::Signer::health), which
+/// host applications can use to prompt their users to back up or synchronize the signer, or to
+/// update its parameters.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Asset<C>: Deserialize<'de>, C::Checkpoint: Deserialize<'de>",
+            serialize = "Asset<C>: Serialize, C::Checkpoint: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Asset<C>: Clone"),
+    Debug(bound = "Asset<C>: Debug, C::Checkpoint: Debug"),
+    Eq(bound = "Asset<C>: Eq, C::Checkpoint: Eq"),
+    Hash(bound = "Asset<C>: Hash, C::Checkpoint: Hash"),
+    PartialEq(bound = "Asset<C>: PartialEq")
+)]
+pub struct SignerHealth<C>
+where
+    C: Configuration,
+{
+    /// Time of the Last Backup
+    pub last_backup: Option<u64>,
+
+    /// Value Received since the Last Backup
+    pub unbacked_value: Vec<Asset<C>>,
+
+    /// Backup Due Flag
+    ///
+    /// Raised when the backup interval has elapsed or when the value received since the last
+    /// backup exceeds the unbacked value limit.
+    pub backup_due: bool,
+
+    /// Time of the Last Synchronization
+    pub last_sync: Option<u64>,
+
+    /// Current Checkpoint
+    pub checkpoint: C::Checkpoint,
+
+    /// Stale Accumulator Flag
+    ///
+    /// Raised when the synchronization interval has elapsed.
+    pub accumulator_stale: bool,
+
+    /// Stale Membership Proofs Flag
+    ///
+    /// Raised when the precomputed membership proofs were not computed at the current checkpoint.
+    pub membership_proofs_stale: bool,
+
+    /// Parameter Versions
+    pub parameter_versions: ParameterVersions,
+
+    /// Outdated Parameters Flag
+    ///
+    /// Raised when the parameter versions are older than the minimum parameter versions.
+    pub parameters_outdated: bool,
+}
+
+impl<C> SignerHealth<C>
+where
+    C: Configuration,
+{
+    /// Returns `true` if any of the flags of `self` is raised.
+    #[inline]
+    pub fn needs_attention(&self) -> bool {
+        self.backup_due
+            || self.accumulator_stale
+            || self.membership_proofs_stale
+            || self.parameters_outdated
+    }
+}
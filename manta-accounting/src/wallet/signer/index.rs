@@ -0,0 +1,545 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Blinded Note Indexing
+//!
+//! Every note posted to the ledger is tagged with a public partition of the address it was sent
+//! to, see [`NoteDetection`]. A mobile wallet which does not want to download every note can hand
+//! a [`BlindedDetectionKey`] to a semi-trusted [`NoteIndexer`]: the key lists the partition of the
+//! wallet hidden among decoy partitions, so the indexer learns that the wallet is one of the
+//! addresses in the union of those partitions, but not which partition is the real one. The
+//! indexer keeps the positions of the notes of every partition and serves the notes matching the
+//! key over a range of the UTXO stream of the ledger on demand, as an [`IndexResponse`].
+//!
+//! The indexer is not trusted to be complete. For every range, the ledger publishes a
+//! [`RangeSummary`] with the number of notes of every partition and a [`BloomFilter`] of the
+//! UTXOs in the range. The wallet [`verify`](RangeSummary::verify)s the response against the
+//! summary: the counts catch the notes withheld by the indexer and the filter catches the notes
+//! it fabricates to make up for them, so a dishonest indexer can at most delay the wallet, but
+//! cannot hide notes from it without being detected.
+
+use crate::{
+    transfer::{self, Address, Note, Parameters, Utxo},
+    wallet::signer::{privacy::shuffle, Configuration},
+};
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{fmt::Debug, hash::Hash, ops::Range};
+use manta_crypto::rand::RngCore;
+use manta_util::collections::filter::{BloomFilter, Filter};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Note Detection
+pub trait NoteDetection<C>
+where
+    C: transfer::Configuration + ?Sized,
+{
+    /// Partition Type
+    type Partition: Copy + Debug + Hash + Ord;
+
+    /// Returns every partition that an address can belong to, from which the decoys of the
+    /// [`BlindedDetectionKey`]s are drawn.
+    fn partitions() -> Vec<Self::Partition>;
+
+    /// Returns the partition of `address`.
+    fn address_partition(parameters: &Parameters<C>, address: &Address<C>) -> Self::Partition;
+
+    /// Returns the partition of the address that `note` was sent to.
+    fn note_partition(note: &Note<C>) -> Self::Partition;
+}
+
+/// Partition Type
+pub type Partition<C> = <<C as Configuration>::NoteDetection as NoteDetection<C>>::Partition;
+
+/// Blinded Detection Key
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Partition<C>: Deserialize<'de>",
+            serialize = "Partition<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Debug(bound = ""),
+    Eq(bound = ""),
+    Hash(bound = ""),
+    PartialEq(bound = "")
+)]
+pub struct BlindedDetectionKey<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Partitions
+    ///
+    /// The partitions are sorted, so that their order does not reveal which one is the partition
+    /// of the wallet.
+    partitions: Vec<Partition<C>>,
+}
+
+impl<C> BlindedDetectionKey<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Builds a new [`BlindedDetectionKey`] which hides `partition` among the `decoys`.
+    #[inline]
+    pub fn new<I>(partition: Partition<C>, decoys: I) -> Self
+    where
+        I: IntoIterator<Item = Partition<C>>,
+    {
+        let mut partitions = decoys.into_iter().collect::<Vec<_>>();
+        partitions.push(partition);
+        partitions.sort();
+        partitions.dedup();
+        Self { partitions }
+    }
+
+    /// Samples a new [`BlindedDetectionKey`] which hides `partition` among uniformly random decoys,
+    /// so that the key matches `size`-many partitions in total. If `size` is larger than the
+    /// number of [`partitions`](NoteDetection::partitions), the key matches every partition.
+    #[inline]
+    pub fn sample<R>(partition: Partition<C>, size: usize, rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        let mut decoys = C::NoteDetection::partitions();
+        decoys.retain(|decoy| *decoy != partition);
+        shuffle(&mut decoys, rng);
+        decoys.truncate(size.saturating_sub(1));
+        Self::new(partition, decoys)
+    }
+
+    /// Returns the partitions matched by `self`, in increasing order.
+    #[inline]
+    pub fn partitions(&self) -> &[Partition<C>] {
+        &self.partitions
+    }
+
+    /// Returns the number of partitions matched by `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.partitions.len()
+    }
+
+    /// Returns `true` if `self` does not match any partition.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.partitions.is_empty()
+    }
+
+    /// Returns `true` if `self` matches `partition`.
+    #[inline]
+    pub fn contains(&self, partition: &Partition<C>) -> bool {
+        self.partitions.binary_search(partition).is_ok()
+    }
+
+    /// Returns `true` if `self` matches the partition of `note`.
+    #[inline]
+    pub fn matches(&self, note: &Note<C>) -> bool {
+        self.contains(&C::NoteDetection::note_partition(note))
+    }
+}
+
+/// Index Request
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "BlindedDetectionKey<C>: Deserialize<'de>",
+            serialize = "BlindedDetectionKey<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Debug(bound = ""),
+    Eq(bound = ""),
+    Hash(bound = ""),
+    PartialEq(bound = "")
+)]
+pub struct IndexRequest<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Blinded Detection Key
+    pub key: BlindedDetectionKey<C>,
+
+    /// Range
+    ///
+    /// Positions of the requested notes in the UTXO stream of the ledger, which is the order in
+    /// which the ledger returns its UTXOs during synchronization.
+    pub range: Range<usize>,
+}
+
+/// Indexed Note
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Utxo<C>: Deserialize<'de>, Note<C>: Deserialize<'de>",
+            serialize = "Utxo<C>: Serialize, Note<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Utxo<C>: Clone, Note<C>: Clone"),
+    Debug(bound = "Utxo<C>: Debug, Note<C>: Debug"),
+    Eq(bound = "Utxo<C>: Eq, Note<C>: Eq"),
+    Hash(bound = "Utxo<C>: Hash, Note<C>: Hash"),
+    PartialEq(bound = "Utxo<C>: PartialEq, Note<C>: PartialEq")
+)]
+pub struct IndexedNote<C>
+where
+    C: transfer::Configuration + ?Sized,
+{
+    /// Position in the UTXO Stream
+    pub position: usize,
+
+    /// UTXO
+    pub utxo: Utxo<C>,
+
+    /// Note
+    pub note: Note<C>,
+}
+
+/// Index Response
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "IndexedNote<C>: Deserialize<'de>",
+            serialize = "IndexedNote<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "IndexedNote<C>: Clone"),
+    Debug(bound = "IndexedNote<C>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "IndexedNote<C>: Eq"),
+    Hash(bound = "IndexedNote<C>: Hash"),
+    PartialEq(bound = "IndexedNote<C>: PartialEq")
+)]
+pub struct IndexResponse<C>
+where
+    C: transfer::Configuration + ?Sized,
+{
+    /// Notes
+    ///
+    /// The notes of the requested range which match the key of the request, in increasing order
+    /// of their positions.
+    pub notes: Vec<IndexedNote<C>>,
+}
+
+impl<C> IndexResponse<C>
+where
+    C: transfer::Configuration + ?Sized,
+{
+    /// Returns the UTXOs and notes of `self` in the layout of the
+    /// [`SyncData`](super::SyncData) returned by the ledger.
+    #[inline]
+    pub fn into_utxo_note_data(self) -> Vec<(Utxo<C>, Note<C>)> {
+        self.notes
+            .into_iter()
+            .map(|note| (note.utxo, note.note))
+            .collect()
+    }
+}
+
+/// Note Indexer
+///
+/// The indexer precomputes, for every partition, the positions of the notes sent to the addresses
+/// of that partition, so serving an [`IndexRequest`] only touches the notes it returns.
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Utxo<C>: Clone, Note<C>: Clone"),
+    Debug(bound = "Utxo<C>: Debug, Note<C>: Debug"),
+    Default(bound = "")
+)]
+pub struct NoteIndexer<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// UTXO Stream
+    notes: Vec<(Utxo<C>, Note<C>)>,
+
+    /// Note Positions by Partition
+    positions: BTreeMap<Partition<C>, Vec<usize>>,
+}
+
+impl<C> NoteIndexer<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Appends the `notes` to the UTXO stream indexed by `self`. They are expected in the order
+    /// returned by the ledger.
+    #[inline]
+    pub fn extend<I>(&mut self, notes: I)
+    where
+        I: IntoIterator<Item = (Utxo<C>, Note<C>)>,
+    {
+        for (utxo, note) in notes {
+            self.positions
+                .entry(C::NoteDetection::note_partition(&note))
+                .or_default()
+                .push(self.notes.len());
+            self.notes.push((utxo, note));
+        }
+    }
+
+    /// Returns the number of notes indexed by `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.notes.len()
+    }
+
+    /// Returns `true` if `self` has not indexed any note.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty()
+    }
+
+    /// Serves `request`, returning the indexed notes in the requested range which match the key
+    /// of `request`.
+    #[inline]
+    pub fn serve(&self, request: &IndexRequest<C>) -> IndexResponse<C>
+    where
+        Utxo<C>: Clone,
+        Note<C>: Clone,
+    {
+        let mut positions = request
+            .key
+            .partitions()
+            .iter()
+            .filter_map(|partition| self.positions.get(partition))
+            .flat_map(|positions| {
+                let start = positions.partition_point(|p| *p < request.range.start);
+                let end = positions.partition_point(|p| *p < request.range.end);
+                positions[start..end].iter().copied()
+            })
+            .collect::<Vec<_>>();
+        positions.sort_unstable();
+        IndexResponse {
+            notes: positions
+                .into_iter()
+                .map(|position| {
+                    let (utxo, note) = self.notes[position].clone();
+                    IndexedNote {
+                        position,
+                        utxo,
+                        note,
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Completeness Error
+///
+/// This `enum` is the error state of the [`verify`](RangeSummary::verify) method on
+/// [`RangeSummary`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum CompletenessError {
+    /// Out of Range Error
+    ///
+    /// The note at the given position is outside of the range of the summary.
+    OutOfRange(usize),
+
+    /// Unordered Error
+    ///
+    /// The note at the given position does not come strictly after the note served before it.
+    Unordered(usize),
+
+    /// Unrequested Partition Error
+    ///
+    /// The note at the given position does not match the blinded detection key.
+    UnrequestedPartition(usize),
+
+    /// Unknown UTXO Error
+    ///
+    /// The UTXO of the note at the given position is not in the filter of the summary, so it was
+    /// not posted to the ledger in the range of the summary.
+    UnknownUtxo(usize),
+
+    /// Incomplete Response Error
+    ///
+    /// The indexer served a different number of notes than the ledger reports for the partitions
+    /// of the blinded detection key.
+    Incomplete {
+        /// Number of Notes Reported by the Ledger
+        expected: u64,
+
+        /// Number of Notes Served by the Indexer
+        served: u64,
+    },
+}
+
+/// Range Summary
+///
+/// Summary of a range of the UTXO stream published by the ledger, against which the wallets check
+/// the [`IndexResponse`]s of the indexers.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Partition<C>: Deserialize<'de>",
+            serialize = "Partition<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Debug(bound = ""),
+    Eq(bound = ""),
+    PartialEq(bound = "")
+)]
+pub struct RangeSummary<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Range
+    range: Range<usize>,
+
+    /// Note Counts by Partition
+    counts: BTreeMap<Partition<C>, u64>,
+
+    /// UTXO Filter
+    utxos: BloomFilter,
+}
+
+impl<C> RangeSummary<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Builds the [`RangeSummary`] of the `notes` which start at position `start` in the UTXO
+    /// stream, with a UTXO filter whose false positive rate is at most `rate`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `rate` is not strictly between zero and one.
+    #[inline]
+    pub fn new(start: usize, notes: &[(Utxo<C>, Note<C>)], rate: f64) -> Self
+    where
+        Utxo<C>: Hash,
+    {
+        let mut counts = BTreeMap::<_, u64>::new();
+        let mut utxos = BloomFilter::with_false_positive_rate(notes.len().max(1), rate);
+        for (utxo, note) in notes {
+            *counts
+                .entry(C::NoteDetection::note_partition(note))
+                .or_default() += 1;
+            utxos.insert(utxo);
+        }
+        Self {
+            range: start..start + notes.len(),
+            counts,
+            utxos,
+        }
+    }
+
+    /// Returns the range of the UTXO stream summarized by `self`.
+    #[inline]
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns the number of notes in the range of `self` which were sent to addresses of
+    /// `partition`.
+    #[inline]
+    pub fn count(&self, partition: &Partition<C>) -> u64 {
+        self.counts.get(partition).copied().unwrap_or_default()
+    }
+
+    /// Returns the number of notes in the range of `self` which match `key`.
+    #[inline]
+    pub fn expected_count(&self, key: &BlindedDetectionKey<C>) -> u64 {
+        key.partitions()
+            .iter()
+            .map(|partition| self.count(partition))
+            .sum()
+    }
+
+    /// Checks that `response` holds every note in the range of `self` which matches `key`, and no
+    /// other note.
+    ///
+    /// # Soundness
+    ///
+    /// A note fabricated by the indexer is only caught if its UTXO is not a false positive of the
+    /// filter of `self`, so an indexer can get a fabricated note through with a probability
+    /// bounded by the false positive rate of the filter. Wallets still decrypt every served note,
+    /// so a fabricated note never becomes spendable, it can only hide a withheld one.
+    #[inline]
+    pub fn verify(
+        &self,
+        key: &BlindedDetectionKey<C>,
+        response: &IndexResponse<C>,
+    ) -> Result<(), CompletenessError>
+    where
+        Utxo<C>: Hash,
+    {
+        let mut previous = None;
+        for note in &response.notes {
+            if !self.range.contains(&note.position) {
+                return Err(CompletenessError::OutOfRange(note.position));
+            }
+            if previous.is_some_and(|previous| note.position <= previous) {
+                return Err(CompletenessError::Unordered(note.position));
+            }
+            if !key.matches(&note.note) {
+                return Err(CompletenessError::UnrequestedPartition(note.position));
+            }
+            if !self.utxos.may_contain(&note.utxo) {
+                return Err(CompletenessError::UnknownUtxo(note.position));
+            }
+            previous = Some(note.position);
+        }
+        let expected = self.expected_count(key);
+        let served = response.notes.len() as u64;
+        if served != expected {
+            return Err(CompletenessError::Incomplete { expected, served });
+        }
+        Ok(())
+    }
+}
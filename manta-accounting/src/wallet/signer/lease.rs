@@ -0,0 +1,315 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Note Leases
+//!
+//! Signers restored from the same seed on different devices own the same notes, so two devices
+//! signing at the same time can spend the same note, and the ledger rejects whichever post comes
+//! second. To avoid this, each device reserves the notes it spends with a [`Lease`] which expires
+//! after some time, and records it in a [`LeaseTable`]. The devices exchange their tables and
+//! [`merge`](LeaseTable::merge) them, so that every device knows which notes the others are about
+//! to spend and a [`ConflictPolicy`] decides what to do with them.
+//!
+//! The [`LeaseTable`] reveals which notes are about to be spent, so it should only be shared
+//! between the devices in encrypted form, for instance by saving it to an encrypted
+//! [`File`](crate::fs::File) in a synchronized directory.
+//!
+//! # Clocks
+//!
+//! The signer has no clock, so the times of the leases are given by the caller, in milliseconds
+//! since the Unix epoch. The devices only need to agree on the time up to the duration of the
+//! leases.
+
+use crate::{transfer::Identifier, wallet::signer::Configuration};
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+use manta_crypto::rand::{RngCore, Sample};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Device Identifier
+///
+/// Random identifier of a device which shares its seed with other devices.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DeviceId(pub [u8; 16]);
+
+impl Sample for DeviceId {
+    #[inline]
+    fn sample<R>(_: (), rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        let mut id = [0; 16];
+        rng.fill_bytes(&mut id);
+        Self(id)
+    }
+}
+
+/// Conflict Policy
+///
+/// Decides how a signer deals with the notes leased by other devices.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum ConflictPolicy {
+    /// Avoids the leased notes, spending only the other ones. Transactions which cannot be paid
+    /// without the leased notes fail with an insufficient balance until the leases expire.
+    #[default]
+    Avoid,
+
+    /// Fails with a [`LeaseConflict`](super::SignError::LeaseConflict) error for transactions
+    /// which would spend a leased note, so that the caller can wait for the lease to expire.
+    Wait,
+
+    /// Ignores the leases of the other devices and takes them over, for instance when the other
+    /// devices are known to be offline.
+    Override,
+}
+
+/// Lease Configuration
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct LeaseConfiguration {
+    /// Device Identifier
+    pub device: DeviceId,
+
+    /// Lease Duration in Milliseconds
+    ///
+    /// Time after which the notes reserved by a signed transaction are released. It should be
+    /// longer than the time it takes for the transaction to be included in the ledger.
+    pub duration: u64,
+
+    /// Conflict Policy
+    pub policy: ConflictPolicy,
+}
+
+impl LeaseConfiguration {
+    /// Builds a new [`LeaseConfiguration`] from `device`, `duration` and `policy`.
+    #[inline]
+    pub fn new(device: DeviceId, duration: u64, policy: ConflictPolicy) -> Self {
+        Self {
+            device,
+            duration,
+            policy,
+        }
+    }
+}
+
+/// Note Lease
+///
+/// Reservation of the note stored at `identifier` by `device`, from `acquired_at` until
+/// `expires_at`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Identifier<C>: Deserialize<'de>",
+            serialize = "Identifier<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Identifier<C>: Clone"),
+    Copy(bound = "Identifier<C>: Copy"),
+    Debug(bound = "Identifier<C>: Debug"),
+    Eq(bound = "Identifier<C>: Eq"),
+    Hash(bound = "Identifier<C>: Hash"),
+    PartialEq(bound = "Identifier<C>: PartialEq")
+)]
+pub struct Lease<C>
+where
+    C: Configuration,
+{
+    /// Note Identifier
+    pub identifier: Identifier<C>,
+
+    /// Leasing Device
+    pub device: DeviceId,
+
+    /// Acquisition Time
+    pub acquired_at: u64,
+
+    /// Expiration Time
+    pub expires_at: u64,
+}
+
+impl<C> Lease<C>
+where
+    C: Configuration,
+{
+    /// Returns `true` if `self` has not expired at time `now`.
+    #[inline]
+    pub fn is_active(&self, now: u64) -> bool {
+        now < self.expires_at
+    }
+
+    /// Returns `true` if `self` takes precedence over `other` when two devices lease the same
+    /// note. The lease acquired first wins, and ties are broken by the device identifier, so that
+    /// all the devices agree on the winner.
+    #[inline]
+    pub fn precedes(&self, other: &Self) -> bool {
+        (self.acquired_at, self.device) < (other.acquired_at, other.device)
+    }
+}
+
+/// Lease Table
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Lease<C>: Deserialize<'de>",
+            serialize = "Lease<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Lease<C>: Clone"),
+    Debug(bound = "Lease<C>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "Lease<C>: Eq"),
+    Hash(bound = "Lease<C>: Hash"),
+    PartialEq(bound = "Lease<C>: PartialEq")
+)]
+pub struct LeaseTable<C>
+where
+    C: Configuration,
+{
+    /// Leases
+    ///
+    /// There is at most one lease for each note.
+    leases: Vec<Lease<C>>,
+}
+
+impl<C> LeaseTable<C>
+where
+    C: Configuration,
+{
+    /// Returns the leases stored in `self`.
+    #[inline]
+    pub fn leases(&self) -> &[Lease<C>] {
+        &self.leases
+    }
+
+    /// Returns `true` if `self` does not store any leases.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.leases.is_empty()
+    }
+
+    /// Returns the lease of the note stored at `identifier`, if it is leased.
+    #[inline]
+    pub fn get(&self, identifier: &Identifier<C>) -> Option<&Lease<C>> {
+        self.leases
+            .iter()
+            .find(|lease| &lease.identifier == identifier)
+    }
+
+    /// Returns the lease of the note stored at `identifier` if it is held by a device other than
+    /// `device` at time `now`.
+    #[inline]
+    pub fn foreign_lease(
+        &self,
+        identifier: &Identifier<C>,
+        device: &DeviceId,
+        now: u64,
+    ) -> Option<&Lease<C>> {
+        self.get(identifier)
+            .filter(|lease| &lease.device != device && lease.is_active(now))
+    }
+
+    /// Leases the notes stored at `identifiers` to `device` from `now` for `duration`
+    /// milliseconds, replacing any lease they already have.
+    #[inline]
+    pub fn acquire<I>(&mut self, device: DeviceId, identifiers: I, now: u64, duration: u64)
+    where
+        I: IntoIterator<Item = Identifier<C>>,
+    {
+        for identifier in identifiers {
+            self.leases.retain(|lease| lease.identifier != identifier);
+            self.leases.push(Lease {
+                identifier,
+                device,
+                acquired_at: now,
+                expires_at: now.saturating_add(duration),
+            });
+        }
+    }
+
+    /// Releases all the leases held by `device`.
+    #[inline]
+    pub fn release(&mut self, device: &DeviceId) {
+        self.leases.retain(|lease| &lease.device != device);
+    }
+
+    /// Removes the leases which have expired at time `now`.
+    #[inline]
+    pub fn expire(&mut self, now: u64) {
+        self.leases.retain(|lease| lease.is_active(now));
+    }
+
+    /// Merges the leases of `other` into `self`, keeping the lease which
+    /// [`precedes`](Lease::precedes) the other one whenever both tables lease the same note to
+    /// different devices. Returns the leases of `device` which were lost to another device, so
+    /// that the transactions which spend those notes can be dropped before they are rejected by
+    /// the ledger.
+    #[inline]
+    pub fn merge(&mut self, other: Self, device: &DeviceId) -> Vec<Lease<C>> {
+        let mut lost = Vec::new();
+        for lease in other.leases {
+            match self
+                .leases
+                .iter()
+                .position(|current| current.identifier == lease.identifier)
+            {
+                Some(index) => {
+                    let current = &mut self.leases[index];
+                    if current.device == lease.device {
+                        current.acquired_at = current.acquired_at.min(lease.acquired_at);
+                        current.expires_at = current.expires_at.max(lease.expires_at);
+                    } else if lease.precedes(current) {
+                        let replaced = core::mem::replace(current, lease);
+                        if &replaced.device == device {
+                            lost.push(replaced);
+                        }
+                    }
+                }
+                _ => self.leases.push(lease),
+            }
+        }
+        lost
+    }
+}
@@ -26,32 +26,65 @@
 //        internally.
 
 use crate::{
-    asset::AssetMap,
+    asset::{AssetMap, BalanceError},
     key::{self, Account, AccountCollection, DeriveAddresses},
     transfer::{
         self,
         canonical::{MultiProvingContext, Transaction, TransactionData},
-        Address, Asset, AuthorizationContext, IdentifiedAsset, Identifier, IdentityProof, Note,
-        Nullifier, Parameters, ProofSystemError, SpendingKey, TransferPost, Utxo,
-        UtxoAccumulatorItem, UtxoAccumulatorModel, UtxoAccumulatorWitness, UtxoMembershipProof,
+        holdings::HoldingsProof,
+        ownership::{OwnershipChallenge, OwnershipProof},
+        Address, Asset, AuthorizationContext, FullParametersRef, IdentifiedAsset, Identifier,
+        IdentityProof, LedgerAnchor, Note, Nullifier, Parameters, PaymentReceipt, ProofSystemError,
+        ProvingContext, Sender, SpendingKey, TransferPost, Utxo, UtxoAccumulatorItem,
+        UtxoAccumulatorModel, UtxoAccumulatorOutput, UtxoAccumulatorWitness, UtxoMembershipProof,
+    },
+    wallet::{
+        ledger::{self, Data},
+        signer::{
+            capability::{
+                AuthorizationError, Capability, CapabilityTable, CapabilityToken, Scope,
+                ScopedSigner,
+            },
+            health::{HealthRecord, HealthSettings, ParameterVersions, SignerHealth},
+            lease::{DeviceId, Lease, LeaseConfiguration, LeaseTable},
+            privacy::{PrivacyStrategy, SplitEstimate, SplittingStrategy},
+            quarantine::{Quarantine, ReprocessResponse},
+            retention::{PruneSummary, ReceiptArchive, ReceiptMetadata, RetentionPolicy},
+            review::TransactionSummary,
+        },
     },
-    wallet::ledger::{self, Data},
 };
-use alloc::{boxed::Box, vec::Vec};
+use alloc::{boxed::Box, collections::BTreeMap, vec::Vec};
 use core::{convert::Infallible, fmt::Debug, hash::Hash};
 use manta_crypto::{
     accumulator::{
         Accumulator, BatchInsertion, ExactSizeAccumulator, FromItemsAndWitnesses, ItemHashFunction,
         OptimizedAccumulator,
     },
-    rand::{CryptoRng, FromEntropy, RngCore},
+    rand::{CryptoRng, FromEntropy, Rand, RngCore},
+};
+use manta_util::{
+    codec::{Decode, DecodeError, Encode, Read, Write},
+    future::LocalBoxFutureResult,
+    persistence::Rollback,
+    vec::{BoundedVec, CapacityError},
+    zeroize::Zeroize,
 };
-use manta_util::{future::LocalBoxFutureResult, persistence::Rollback};
 
 #[cfg(feature = "serde")]
 use manta_util::serde::{Deserialize, Serialize};
 
+pub mod capability;
 pub mod functions;
+pub mod health;
+pub mod index;
+pub mod lease;
+pub mod privacy;
+pub mod quarantine;
+pub mod retention;
+pub mod review;
+pub mod store;
+pub mod watch;
 
 /// Signer Connection
 pub trait Connection<C>
@@ -116,6 +149,23 @@ where
         request: SignRequest<Self::AssetMetadata, C>,
     ) -> LocalBoxFutureResult<SignResult<C>, Self::Error>;
 
+    /// Signs all the transactions in `request` jointly over the same set of notes, so that no
+    /// note is spent twice, and returns the ledger transfer posts of each of them if all of them
+    /// could be signed. Otherwise, returns the failure of each transaction which could not be
+    /// signed.
+    fn sign_batch(
+        &mut self,
+        request: SignBatchRequest<Self::AssetMetadata, C>,
+    ) -> LocalBoxFutureResult<'_, SignBatchResult<C>, Self::Error>;
+
+    /// Plans the transaction in `request` against the current assets without signing it, and
+    /// returns the [`TransactionSummary`] of the posts which [`sign`](Self::sign) would generate
+    /// for it, so that wallets can ask the user to confirm the transaction before signing it.
+    fn review(
+        &mut self,
+        request: SignRequest<Self::AssetMetadata, C>,
+    ) -> LocalBoxFutureResult<'_, ReviewResult<C>, Self::Error>;
+
     /// Returns the [`Address`] corresponding to `self`.
     fn address(&mut self) -> LocalBoxFutureResult<Option<Address<C>>, Self::Error>;
 
@@ -313,6 +363,15 @@ where
     }
 }
 
+/// Maximum Number of Entries in Each Field of [`SyncData`]
+///
+/// This bound is checked when deserializing [`SyncData`] so that a malicious ledger cannot force
+/// the signer into large allocations. Ledgers should page their responses below this bound.
+pub const MAX_SYNC_DATA_LENGTH: usize = 1 << 20;
+
+/// Entries of One Field of the Synchronization Data
+pub type SyncEntries<T> = BoundedVec<T, MAX_SYNC_DATA_LENGTH>;
+
 /// Signer Synchronization Data
 #[cfg_attr(
     feature = "serde",
@@ -348,10 +407,101 @@ where
     C: transfer::Configuration + ?Sized,
 {
     /// UTXO-Note Data
-    pub utxo_note_data: Vec<(Utxo<C>, Note<C>)>,
+    pub utxo_note_data: SyncEntries<(Utxo<C>, Note<C>)>,
 
     /// Nullifier Data
-    pub nullifier_data: Vec<Nullifier<C>>,
+    pub nullifier_data: SyncEntries<Nullifier<C>>,
+}
+
+impl<C> SyncData<C>
+where
+    C: transfer::Configuration + ?Sized,
+{
+    /// Builds the [`SyncData`] which `posts` add to the ledger, in the order in which the ledger
+    /// applies them.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if `posts` add more than [`MAX_SYNC_DATA_LENGTH`]-many entries
+    /// to either field of the [`SyncData`].
+    #[inline]
+    pub fn from_posts<'p, I>(posts: I) -> Result<Self, CapacityError<()>>
+    where
+        C: 'p,
+        I: IntoIterator<Item = &'p TransferPost<C>>,
+        Utxo<C>: Clone,
+        Note<C>: Clone,
+        Nullifier<C>: Clone,
+    {
+        let mut data = Self::default();
+        for post in posts {
+            data.utxo_note_data
+                .try_extend(
+                    post.body.receiver_posts.iter().map(|receiver_post| {
+                        (receiver_post.utxo.clone(), receiver_post.note.clone())
+                    }),
+                )
+                .map_err(CapacityError::forget)?;
+            data.nullifier_data
+                .try_extend(
+                    post.body
+                        .sender_posts
+                        .iter()
+                        .map(|sender_post| sender_post.nullifier.clone()),
+                )
+                .map_err(CapacityError::forget)?;
+        }
+        Ok(data)
+    }
+}
+
+impl<C> Decode for SyncData<C>
+where
+    C: transfer::Configuration + ?Sized,
+    Utxo<C>: Decode,
+    Note<C>: Decode,
+    Nullifier<C>: Decode,
+{
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Ok(Self {
+            utxo_note_data: decode_sync_data(&mut reader)?,
+            nullifier_data: decode_sync_data(&mut reader)?,
+        })
+    }
+}
+
+impl<C> Encode for SyncData<C>
+where
+    C: transfer::Configuration + ?Sized,
+    Utxo<C>: Encode,
+    Note<C>: Encode,
+    Nullifier<C>: Encode,
+{
+    #[inline]
+    fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        self.utxo_note_data.encode(&mut writer)?;
+        self.nullifier_data.encode(&mut writer)?;
+        Ok(())
+    }
+}
+
+/// Decodes at most [`MAX_SYNC_DATA_LENGTH`]-many elements from `reader`.
+#[inline]
+fn decode_sync_data<T, R>(reader: R) -> Result<SyncEntries<T>, DecodeError<R::Error, ()>>
+where
+    T: Decode,
+    R: Read,
+{
+    SyncEntries::decode(reader).map_err(|err| err.map_decode(|_| ()))
 }
 
 impl<C> Data<C::Checkpoint> for SyncData<C>
@@ -408,6 +558,43 @@ where
     pub data: SyncData<C>,
 }
 
+impl<C, T> Decode for SyncRequest<C, T>
+where
+    C: transfer::Configuration,
+    T: ledger::Checkpoint + Decode,
+    SyncData<C>: Decode,
+{
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Ok(Self {
+            origin_checkpoint: Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+            data: Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+        })
+    }
+}
+
+impl<C, T> Encode for SyncRequest<C, T>
+where
+    C: transfer::Configuration,
+    T: ledger::Checkpoint + Encode,
+    SyncData<C>: Encode,
+{
+    #[inline]
+    fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        self.origin_checkpoint.encode(&mut writer)?;
+        self.data.encode(&mut writer)?;
+        Ok(())
+    }
+}
+
 impl<C, T> SyncRequest<C, T>
 where
     C: transfer::Configuration,
@@ -450,6 +637,106 @@ where
     }
 }
 
+/// Signer Frontier Synchronization Data
+///
+/// Ledger data for the [`frontier_sync`](Signer::frontier_sync) method of [`Signer`], which only
+/// keeps the frontier of the [`UtxoAccumulator`](Configuration::UtxoAccumulator) and the membership
+/// proofs of the [`Utxo`]s owned by the signer instead of every [`Utxo`] on the ledger.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = r"
+                Utxo<C>: Deserialize<'de>,
+                UtxoAccumulatorWitness<C>: Deserialize<'de>,
+                Nullifier<C>: Deserialize<'de>,
+            ",
+            serialize = r"
+                Utxo<C>: Serialize,
+                UtxoAccumulatorWitness<C>: Serialize,
+                Nullifier<C>: Serialize,
+            ",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Utxo<C>: Clone, UtxoAccumulatorWitness<C>: Clone, Nullifier<C>: Clone"),
+    Debug(bound = "Utxo<C>: Debug, UtxoAccumulatorWitness<C>: Debug, Nullifier<C>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "Utxo<C>: Eq, UtxoAccumulatorWitness<C>: Eq, Nullifier<C>: Eq"),
+    Hash(bound = "Utxo<C>: Hash, UtxoAccumulatorWitness<C>: Hash, Nullifier<C>: Hash"),
+    PartialEq(
+        bound = "Utxo<C>: PartialEq, UtxoAccumulatorWitness<C>: PartialEq, Nullifier<C>: PartialEq"
+    )
+)]
+pub struct FrontierSyncData<C>
+where
+    C: transfer::Configuration + ?Sized,
+{
+    /// Frontier Data
+    ///
+    /// # Note
+    ///
+    /// There is one entry for each subaccumulator in the
+    /// [`UtxoAccumulator`](Configuration::UtxoAccumulator), which is either `None` if the
+    /// subaccumulator is empty, or its last [`Utxo`] together with the membership proof of that
+    /// [`Utxo`].
+    pub frontier_data: Vec<Option<(Utxo<C>, UtxoAccumulatorWitness<C>)>>,
+
+    /// Membership Proof Data
+    ///
+    /// # Note
+    ///
+    /// These are the [`Utxo`]s requested by the signer which are stored on the ledger, together
+    /// with their membership proofs. They must be computed against the same state of the ledger as
+    /// the [`frontier_data`](Self::frontier_data).
+    pub membership_proof_data: SyncEntries<(Utxo<C>, UtxoAccumulatorWitness<C>)>,
+
+    /// Nullifier Data
+    pub nullifier_data: SyncEntries<Nullifier<C>>,
+}
+
+/// Signer Frontier Synchronization Request
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "T: Deserialize<'de>, FrontierSyncData<C>: Deserialize<'de>",
+            serialize = "T: Serialize, FrontierSyncData<C>: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "T: Clone, FrontierSyncData<C>: Clone"),
+    Debug(bound = "T: Debug, FrontierSyncData<C>: Debug"),
+    Default(bound = "T: Default, FrontierSyncData<C>: Default"),
+    Eq(bound = "T: Eq, FrontierSyncData<C>: Eq"),
+    Hash(bound = "T: Hash, FrontierSyncData<C>: Hash"),
+    PartialEq(bound = "T: PartialEq, FrontierSyncData<C>: PartialEq")
+)]
+pub struct FrontierSyncRequest<C, T>
+where
+    C: transfer::Configuration,
+    T: ledger::Checkpoint,
+{
+    /// Origin Checkpoint
+    ///
+    /// This checkpoint was the one that was used to retrieve the [`data`](Self::data) from the
+    /// ledger. It must be equal to the checkpoint of the signer.
+    pub origin_checkpoint: T,
+
+    /// Ledger Frontier Synchronization Data
+    pub data: FrontierSyncData<C>,
+}
+
 /// Signer Synchronization Response
 ///
 /// This `struct` is created by the [`sync`](Connection::sync) method on [`Connection`].
@@ -486,6 +773,59 @@ where
 
     /// Balance Update
     pub balance_update: BalanceUpdate<C>,
+
+    /// Number of Quarantined Notes
+    ///
+    /// Number of notes added to the [`Quarantine`] of the signer by this synchronization, because
+    /// they match its detection heuristics but cannot be opened.
+    pub quarantined: usize,
+
+    /// Number of Duplicate Notes
+    ///
+    /// Number of notes received by this synchronization which were already in the assets of the
+    /// signer, and which were not deposited again.
+    pub duplicates: usize,
+}
+
+impl<C, T> Decode for SyncResponse<C, T>
+where
+    C: transfer::Configuration,
+    T: ledger::Checkpoint + Decode,
+    BalanceUpdate<C>: Decode,
+{
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Ok(Self {
+            checkpoint: Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+            balance_update: Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+            quarantined: u64::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))? as usize,
+            duplicates: u64::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))? as usize,
+        })
+    }
+}
+
+impl<C, T> Encode for SyncResponse<C, T>
+where
+    C: transfer::Configuration,
+    T: ledger::Checkpoint + Encode,
+    BalanceUpdate<C>: Encode,
+{
+    #[inline]
+    fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        self.checkpoint.encode(&mut writer)?;
+        self.balance_update.encode(&mut writer)?;
+        (self.quarantined as u64).encode(&mut writer)?;
+        (self.duplicates as u64).encode(&mut writer)?;
+        Ok(())
+    }
 }
 
 /// Transaction Data Request
@@ -613,6 +953,56 @@ where
     },
 }
 
+impl<C> Decode for BalanceUpdate<C>
+where
+    C: transfer::Configuration,
+    Asset<C>: Decode,
+{
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        match u8::decode(&mut reader)? {
+            0 => Ok(Self::Partial {
+                deposit: decode_sync_data(&mut reader)?.into(),
+                withdraw: decode_sync_data(&mut reader)?.into(),
+            }),
+            1 => Ok(Self::Full {
+                assets: decode_sync_data(&mut reader)?.into(),
+            }),
+            _ => Err(DecodeError::Decode(())),
+        }
+    }
+}
+
+impl<C> Encode for BalanceUpdate<C>
+where
+    C: transfer::Configuration,
+    Asset<C>: Encode,
+{
+    #[inline]
+    fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        match self {
+            Self::Partial { deposit, withdraw } => {
+                0u8.encode(&mut writer)?;
+                deposit.encode(&mut writer)?;
+                withdraw.encode(&mut writer)?;
+            }
+            Self::Full { assets } => {
+                1u8.encode(&mut writer)?;
+                assets.encode(&mut writer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Signer Synchronization Error
 ///
 /// This `enum` is the error state for the [`sync`](Connection::sync) method on [`Connection`].
@@ -747,8 +1137,12 @@ where
     derive(Deserialize, Serialize),
     serde(
         bound(
-            deserialize = "TransferPost<C>: Deserialize<'de>, UtxoMembershipProof<C>: Deserialize<'de>",
-            serialize = "TransferPost<C>: Serialize, UtxoMembershipProof<C>: Serialize"
+            deserialize = r"TransferPost<C>: Deserialize<'de>,
+                UtxoMembershipProof<C>: Deserialize<'de>,
+                UtxoAccumulatorOutput<C>: Deserialize<'de>",
+            serialize = r"TransferPost<C>: Serialize,
+                UtxoMembershipProof<C>: Serialize,
+                UtxoAccumulatorOutput<C>: Serialize"
         ),
         crate = "manta_util::serde",
         deny_unknown_fields
@@ -756,11 +1150,19 @@ where
 )]
 #[derive(derivative::Derivative)]
 #[derivative(
-    Clone(bound = "TransferPost<C>: Clone, UtxoMembershipProof<C>: Clone"),
-    Debug(bound = "TransferPost<C>: Debug, UtxoMembershipProof<C>: Debug"),
-    Eq(bound = "TransferPost<C>: Eq, UtxoMembershipProof<C>: Eq"),
-    Hash(bound = "TransferPost<C>: Hash, UtxoMembershipProof<C>: Hash"),
-    PartialEq(bound = "TransferPost<C>: PartialEq, UtxoMembershipProof<C>: PartialEq")
+    Clone(bound = r"TransferPost<C>: Clone,
+        UtxoMembershipProof<C>: Clone,
+        UtxoAccumulatorOutput<C>: Clone"),
+    Debug(bound = r"TransferPost<C>: Debug,
+        UtxoMembershipProof<C>: Debug,
+        UtxoAccumulatorOutput<C>: Debug"),
+    Eq(bound = "TransferPost<C>: Eq, UtxoMembershipProof<C>: Eq, UtxoAccumulatorOutput<C>: Eq"),
+    Hash(bound = r"TransferPost<C>: Hash,
+        UtxoMembershipProof<C>: Hash,
+        UtxoAccumulatorOutput<C>: Hash"),
+    PartialEq(bound = r"TransferPost<C>: PartialEq,
+        UtxoMembershipProof<C>: PartialEq,
+        UtxoAccumulatorOutput<C>: PartialEq")
 )]
 pub struct IdentityResponse<C>(pub Vec<Option<IdentityProof<C>>>)
 where
@@ -776,6 +1178,85 @@ where
     }
 }
 
+/// Note Import Request
+///
+/// Secrets of notes which were created out-of-band, like printed paper wallets or promotional
+/// claims, and should be added to the spendable assets of the signer. The notes must be owned by
+/// the default account of the signer.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Asset<C>: Deserialize<'de>, Identifier<C>: Deserialize<'de>",
+            serialize = "Asset<C>: Serialize, Identifier<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Asset<C>: Clone, Identifier<C>: Clone"),
+    Debug(bound = "Asset<C>: Debug, Identifier<C>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "Asset<C>: Eq, Identifier<C>: Eq"),
+    Hash(bound = "Asset<C>: Hash, Identifier<C>: Hash"),
+    PartialEq(bound = "Asset<C>: PartialEq, Identifier<C>: PartialEq")
+)]
+pub struct ImportRequest<C>(pub Vec<IdentifiedAsset<C>>)
+where
+    C: transfer::Configuration;
+
+/// Note Import Status
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ImportStatus {
+    /// Imported Note
+    ///
+    /// The [`Utxo`] of the note was found in the UTXO accumulator and the note is now spendable.
+    Imported,
+
+    /// Pending Note
+    ///
+    /// The [`Utxo`] of the note was not found in the UTXO accumulator. The note becomes spendable
+    /// as soon as its [`Utxo`] is synchronized. Notes whose [`Utxo`] was synchronized before the
+    /// import are only recovered when the signer is synchronized again from an empty state.
+    Pending,
+
+    /// Duplicate Note
+    ///
+    /// The note was already spendable or pending.
+    Duplicate,
+}
+
+/// Note Import Response
+///
+/// The [`ImportStatus`] of every note in the [`ImportRequest`], in the same order.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ImportResponse(pub Vec<ImportStatus>);
+
+/// Note Import Error
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ImportError {
+    /// Missing Proof Authorization Key Error
+    MissingProofAuthorizationKey,
+}
+
 impl<C> SignResponse<C>
 where
     C: transfer::Configuration,
@@ -787,17 +1268,17 @@ where
     }
 }
 
-/// Signer Signing Error
+/// Signer Batch Signing Request
 ///
-/// This `enum` is the error state for the [`sign`](Connection::sign) method on [`Connection`].
+/// This `struct` is used by the [`sign_batch`](Connection::sign_batch) method on [`Connection`].
 /// See its documentation for more.
 #[cfg_attr(
     feature = "serde",
     derive(Deserialize, Serialize),
     serde(
         bound(
-            deserialize = "Asset<C>: Deserialize<'de>, ProofSystemError<C>: Deserialize<'de>",
-            serialize = "Asset<C>: Serialize, ProofSystemError<C>: Serialize"
+            deserialize = "SignRequest<A, C>: Deserialize<'de>",
+            serialize = "SignRequest<A, C>: Serialize",
         ),
         crate = "manta_util::serde",
         deny_unknown_fields
@@ -805,32 +1286,161 @@ where
 )]
 #[derive(derivative::Derivative)]
 #[derivative(
-    Clone(bound = "Asset<C>: Clone, ProofSystemError<C>: Clone"),
-    Copy(bound = "Asset<C>: Copy, ProofSystemError<C>: Copy"),
-    Debug(bound = "Asset<C>: Debug, ProofSystemError<C>: Debug"),
-    Eq(bound = "Asset<C>: Eq, ProofSystemError<C>: Eq"),
-    Hash(bound = "Asset<C>: Hash, ProofSystemError<C>: Hash"),
-    PartialEq(bound = "Asset<C>: PartialEq, ProofSystemError<C>: PartialEq")
+    Clone(bound = "SignRequest<A, C>: Clone"),
+    Debug(bound = "SignRequest<A, C>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "SignRequest<A, C>: Eq"),
+    Hash(bound = "SignRequest<A, C>: Hash"),
+    PartialEq(bound = "SignRequest<A, C>: PartialEq")
 )]
-pub enum SignError<C>
+pub struct SignBatchRequest<A, C>(pub Vec<SignRequest<A, C>>)
 where
-    C: transfer::Configuration,
-{
-    /// Insufficient Balance
-    InsufficientBalance(Asset<C>),
-
-    /// Proof System Error
-    ProofSystemError(ProofSystemError<C>),
-
-    /// Missing Spending Key
-    MissingSpendingKey,
-
-    /// Missing Proof Authorization Key
-    MissingProofAuthorizationKey,
-}
+    C: transfer::Configuration;
 
-/// Signing Result
-pub type SignResult<C> = Result<SignResponse<C>, SignError<C>>;
+/// Signer Batch Signing Response
+///
+/// This `struct` is created by the [`sign_batch`](Connection::sign_batch) method on
+/// [`Connection`]. It holds one [`SignResponse`] for each transaction of the request, in the same
+/// order. See its documentation for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "SignResponse<C>: Deserialize<'de>",
+            serialize = "SignResponse<C>: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "SignResponse<C>: Clone"),
+    Debug(bound = "SignResponse<C>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "SignResponse<C>: Eq"),
+    Hash(bound = "SignResponse<C>: Hash"),
+    PartialEq(bound = "SignResponse<C>: PartialEq")
+)]
+pub struct SignBatchResponse<C>(pub Vec<SignResponse<C>>)
+where
+    C: transfer::Configuration;
+
+impl<C> SignBatchResponse<C>
+where
+    C: transfer::Configuration,
+{
+    /// Returns the transfer posts of all the transactions in order.
+    #[inline]
+    pub fn posts(self) -> Vec<TransferPost<C>> {
+        self.0
+            .into_iter()
+            .flat_map(|response| response.posts)
+            .collect()
+    }
+}
+
+/// Signer Signing Error
+///
+/// This `enum` is the error state for the [`sign`](Connection::sign) method on [`Connection`].
+/// See its documentation for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Asset<C>: Deserialize<'de>, ProofSystemError<C>: Deserialize<'de>",
+            serialize = "Asset<C>: Serialize, ProofSystemError<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Asset<C>: Clone, ProofSystemError<C>: Clone"),
+    Copy(bound = "Asset<C>: Copy, ProofSystemError<C>: Copy"),
+    Debug(bound = "Asset<C>: Debug, ProofSystemError<C>: Debug"),
+    Eq(bound = "Asset<C>: Eq, ProofSystemError<C>: Eq"),
+    Hash(bound = "Asset<C>: Hash, ProofSystemError<C>: Hash"),
+    PartialEq(bound = "Asset<C>: PartialEq, ProofSystemError<C>: PartialEq")
+)]
+pub enum SignError<C>
+where
+    C: transfer::Configuration,
+{
+    /// Insufficient Balance
+    InsufficientBalance(Asset<C>),
+
+    /// Proof System Error
+    ProofSystemError(ProofSystemError<C>),
+
+    /// Missing Spending Key
+    MissingSpendingKey,
+
+    /// Missing Proof Authorization Key
+    MissingProofAuthorizationKey,
+
+    /// Ledger Snapshot Error
+    LedgerSnapshotError(LedgerSnapshotError),
+
+    /// Lease Conflict
+    ///
+    /// The transaction would spend a note which is leased by the given device. See
+    /// [`ConflictPolicy::Wait`](lease::ConflictPolicy::Wait) for more.
+    LeaseConflict(DeviceId),
+
+    /// Balance Error
+    ///
+    /// The total value of the notes joined to pay for the transaction does not fit in an asset
+    /// value.
+    BalanceError(BalanceError),
+}
+
+/// Signing Result
+pub type SignResult<C> = Result<SignResponse<C>, SignError<C>>;
+
+/// Review Result
+pub type ReviewResult<C> = Result<TransactionSummary<C>, SignError<C>>;
+
+/// Signer Batch Signing Error
+///
+/// This `struct` is the error state for the [`sign_batch`](Connection::sign_batch) method on
+/// [`Connection`]. See its documentation for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "SignError<C>: Deserialize<'de>",
+            serialize = "SignError<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "SignError<C>: Clone"),
+    Debug(bound = "SignError<C>: Debug"),
+    Eq(bound = "SignError<C>: Eq"),
+    Hash(bound = "SignError<C>: Hash"),
+    PartialEq(bound = "SignError<C>: PartialEq")
+)]
+pub struct SignBatchError<C>
+where
+    C: transfer::Configuration,
+{
+    /// Failures
+    ///
+    /// Index in the request of each transaction which could not be signed, together with its
+    /// error, in increasing order of index.
+    pub failures: Vec<(usize, SignError<C>)>,
+}
+
+/// Batch Signing Result
+pub type SignBatchResult<C> = Result<SignBatchResponse<C>, SignBatchError<C>>;
 
 /// Signing with Transaction Data Error
 pub type SignWithTransactionDataResult<C> =
@@ -877,6 +1487,121 @@ where
     ) -> bool;
 }
 
+/// Ledger Snapshot
+///
+/// A lightweight view of the ledger which the signer maintains from the synchronization data, made
+/// of a window of recent UTXO accumulator outputs and a filter of the spent nullifiers. It also
+/// tracks the pending spends, that is, the nullifiers revealed by transactions which the signer
+/// has signed but which have not reached the ledger yet. The signer uses it to check freshly built
+/// [`TransferPost`]s before they are broadcast. See [`Signer::check_posts`] for more.
+pub trait LedgerSnapshot<C>: Clone + Default
+where
+    C: transfer::Configuration + ?Sized,
+{
+    /// UTXO Accumulator Type
+    type UtxoAccumulator: Accumulator<
+        Item = UtxoAccumulatorItem<C>,
+        Model = UtxoAccumulatorModel<C>,
+    >;
+
+    /// Updates the recent UTXO accumulator outputs of `self` from `utxo_accumulator`.
+    fn update_from_utxo_accumulator(&mut self, utxo_accumulator: &Self::UtxoAccumulator);
+
+    /// Records that `nullifier` has been spent on the ledger, so that it is no longer pending.
+    fn insert_nullifier(&mut self, nullifier: &Nullifier<C>);
+
+    /// Records that `nullifier` was revealed by a transaction which has been signed but has not
+    /// reached the ledger yet.
+    fn insert_pending_nullifier(&mut self, nullifier: &Nullifier<C>);
+
+    /// Forgets all the pending spends of `self`, for instance when the pending transactions were
+    /// dropped before reaching the ledger.
+    fn clear_pending_nullifiers(&mut self);
+
+    /// Forgets the pending spend of `nullifier`, for instance when the transaction which revealed
+    /// it was rejected by the ledger.
+    fn remove_pending_nullifier(&mut self, nullifier: &Nullifier<C>);
+
+    /// Returns `true` if `output` is one of the recent UTXO accumulator outputs of `self`.
+    fn has_utxo_accumulator_output(&self, output: &UtxoAccumulatorOutput<C>) -> bool;
+
+    /// Returns `true` if `nullifier` may have been spent on the ledger.
+    ///
+    /// # Implementation Note
+    ///
+    /// This method is allowed to return false positives, like in the case of filters with a fixed
+    /// size, but it must return `true` for every nullifier inserted with
+    /// [`insert_nullifier`](Self::insert_nullifier).
+    fn may_contain_nullifier(&self, nullifier: &Nullifier<C>) -> bool;
+
+    /// Returns `true` if `nullifier` may belong to a pending spend.
+    ///
+    /// # Implementation Note
+    ///
+    /// Like [`may_contain_nullifier`](Self::may_contain_nullifier), this method is allowed to
+    /// return false positives.
+    fn may_contain_pending_nullifier(&self, nullifier: &Nullifier<C>) -> bool;
+}
+
+/// Ledger Snapshot Error
+///
+/// This `enum` is the error state for the [`check_posts`](Signer::check_posts) method on
+/// [`Signer`]. Each variant points at the sender which would make the ledger reject the
+/// transaction, by its index in the list of posts and its index in the post.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LedgerSnapshotError {
+    /// Unknown UTXO Accumulator Output
+    ///
+    /// The sender was built against a UTXO accumulator output which is not one of the recent
+    /// outputs of the ledger, usually because the signer is out of sync.
+    UnknownUtxoAccumulatorOutput {
+        /// Post Index
+        post: usize,
+
+        /// Sender Index
+        sender: usize,
+    },
+
+    /// Asset Spent
+    ///
+    /// The nullifier of the sender may already be stored on the ledger.
+    AssetSpent {
+        /// Post Index
+        post: usize,
+
+        /// Sender Index
+        sender: usize,
+    },
+
+    /// Asset Pending
+    ///
+    /// The nullifier of the sender may already be revealed by a transaction which was signed
+    /// earlier but has not reached the ledger yet.
+    AssetPending {
+        /// Post Index
+        post: usize,
+
+        /// Sender Index
+        sender: usize,
+    },
+
+    /// Duplicate Nullifier
+    ///
+    /// The nullifier of the sender was already revealed by an earlier sender of the transaction.
+    DuplicateNullifier {
+        /// Post Index
+        post: usize,
+
+        /// Sender Index
+        sender: usize,
+    },
+}
+
 /// Signer Configuration
 pub trait Configuration: transfer::Configuration {
     /// Checkpoint Type
@@ -905,6 +1630,15 @@ pub trait Configuration: transfer::Configuration {
     /// Asset Map Type
     type AssetMap: AssetMap<Self::AssetId, Self::AssetValue, Key = Identifier<Self>>;
 
+    /// Ledger Snapshot Type
+    type LedgerSnapshot: LedgerSnapshot<Self, UtxoAccumulator = Self::UtxoAccumulator>;
+
+    /// Nullifier Watch-List Hash Function Type
+    type WatchListHash: watch::WatchListHash<Self>;
+
+    /// Note Detection Type
+    type NoteDetection: index::NoteDetection<Self>;
+
     /// Asset Metadata Type
     type AssetMetadata;
 
@@ -945,6 +1679,10 @@ where
 
     /// Proving Context
     pub proving_context: MultiProvingContext<C>,
+
+    /// Parameter Versions
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub versions: ParameterVersions,
 }
 
 impl<C> SignerParameters<C>
@@ -957,7 +1695,147 @@ where
         Self {
             parameters,
             proving_context,
+            versions: Default::default(),
+        }
+    }
+
+    /// Sets the versions of the parameters in `self` to `versions`.
+    #[inline]
+    pub fn with_versions(mut self, versions: ParameterVersions) -> Self {
+        self.versions = versions;
+        self
+    }
+}
+
+/// Membership Proof Cache
+///
+/// Stores [`Sender`]s with precomputed [`UtxoMembershipProof`]s for the largest spendable assets
+/// of each asset id, so that signing a transaction which only spends those assets does not need to
+/// walk the [`UtxoAccumulator`](Configuration::UtxoAccumulator). The proofs are only valid against
+/// the accumulator at the [`Checkpoint`] where they were computed, so the cache is refreshed by the
+/// [`Signer`] whenever it synchronizes with the ledger.
+pub struct MembershipProofCache<C>
+where
+    C: Configuration,
+{
+    /// Number of Assets Cached for each Asset Id
+    capacity: usize,
+
+    /// Checkpoint of the Cached Proofs
+    checkpoint: Option<C::Checkpoint>,
+
+    /// Cached Senders
+    ///
+    /// For each asset id, the senders are stored with their [`Identifier`]s in decreasing order of
+    /// value.
+    #[allow(clippy::type_complexity)] // NOTE: Clippy is too harsh here.
+    senders: BTreeMap<C::AssetId, Vec<(Identifier<C>, Sender<C>)>>,
+}
+
+impl<C> MembershipProofCache<C>
+where
+    C: Configuration,
+{
+    /// Builds a new empty [`MembershipProofCache`] which stores at most `capacity` senders for each
+    /// asset id.
+    #[inline]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            checkpoint: None,
+            senders: Default::default(),
+        }
+    }
+
+    /// Returns the number of senders cached for each asset id.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of senders stored in `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.senders.values().map(Vec::len).sum()
+    }
+
+    /// Returns `true` if `self` does not store any senders.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.senders.values().all(Vec::is_empty)
+    }
+
+    /// Returns `true` if the proofs in `self` were computed at `checkpoint`.
+    #[inline]
+    pub fn is_fresh(&self, checkpoint: &C::Checkpoint) -> bool {
+        self.checkpoint.as_ref() == Some(checkpoint)
+    }
+
+    /// Removes all the senders from `self` and marks the cache as computed at `checkpoint`.
+    #[inline]
+    pub fn reset(&mut self, checkpoint: Option<C::Checkpoint>) {
+        self.checkpoint = checkpoint;
+        self.senders.clear();
+    }
+
+    /// Appends `sender` stored at `identifier` to the cached senders of the asset with the given
+    /// `id`.
+    #[inline]
+    pub fn push(&mut self, id: C::AssetId, identifier: Identifier<C>, sender: Sender<C>) {
+        self.senders
+            .entry(id)
+            .or_default()
+            .push((identifier, sender));
+    }
+
+    /// Removes and returns the cached senders of the asset with the given `id` stored at each of
+    /// the `identifiers`, if `self` is fresh at `checkpoint` and all of them are cached. Otherwise,
+    /// returns `None` and leaves `self` unchanged.
+    #[inline]
+    pub fn take<'i, I>(
+        &mut self,
+        checkpoint: &C::Checkpoint,
+        id: &C::AssetId,
+        identifiers: I,
+    ) -> Option<Vec<Sender<C>>>
+    where
+        I: IntoIterator<Item = &'i Identifier<C>>,
+        Identifier<C>: 'i,
+    {
+        if !self.is_fresh(checkpoint) {
+            return None;
         }
+        let cached = self.senders.get_mut(id)?;
+        let indices = identifiers
+            .into_iter()
+            .map(|identifier| cached.iter().position(|(key, _)| key == identifier))
+            .collect::<Option<Vec<_>>>()?;
+        let mut distinct = indices.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        if distinct.len() != indices.len() {
+            return None;
+        }
+        let mut slots = core::mem::take(cached)
+            .into_iter()
+            .map(Some)
+            .collect::<Vec<_>>();
+        let senders = indices
+            .into_iter()
+            .map(|index| slots[index].take().expect("The indices are distinct.").1)
+            .collect();
+        *cached = slots.into_iter().flatten().collect();
+        Some(senders)
+    }
+}
+
+impl<C> Default for MembershipProofCache<C>
+where
+    C: Configuration,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new(0)
     }
 }
 
@@ -973,7 +1851,15 @@ where
                 C::UtxoAccumulator: Deserialize<'de>,
                 C::AssetMap: Deserialize<'de>,
                 C::Checkpoint: Deserialize<'de>,
+                C::LedgerSnapshot: Deserialize<'de>,
                 C::AccountId: Deserialize<'de>,
+                PaymentReceipt<C>: Deserialize<'de>,
+                IdentifiedAsset<C>: Deserialize<'de>,
+                LeaseTable<C>: Deserialize<'de>,
+                Quarantine<C>: Deserialize<'de>,
+                HealthRecord<C>: Deserialize<'de>,
+                ReceiptArchive<C>: Deserialize<'de>,
+                C::AssetValue: Deserialize<'de>,
             ",
             serialize = r"
                 AccountTable<C>: Serialize,
@@ -981,7 +1867,15 @@ where
                 C::UtxoAccumulator: Serialize,
                 C::AssetMap: Serialize,
                 C::Checkpoint: Serialize,
+                C::LedgerSnapshot: Serialize,
                 C::AccountId: Serialize,
+                PaymentReceipt<C>: Serialize,
+                IdentifiedAsset<C>: Serialize,
+                LeaseTable<C>: Serialize,
+                Quarantine<C>: Serialize,
+                HealthRecord<C>: Serialize,
+                ReceiptArchive<C>: Serialize,
+                C::AssetValue: Serialize,
             ",
         ),
         crate = "manta_util::serde",
@@ -996,6 +1890,14 @@ where
         C::UtxoAccumulator: Debug,
         C::AssetMap: Debug,
         C::Checkpoint: Debug,
+        C::LedgerSnapshot: Debug,
+        PaymentReceipt<C>: Debug,
+        IdentifiedAsset<C>: Debug,
+        LeaseTable<C>: Debug,
+        Quarantine<C>: Debug,
+        HealthRecord<C>: Debug,
+        ReceiptArchive<C>: Debug,
+        C::AssetValue: Debug,
         C::Rng: Debug
     "),
     Default(bound = r"
@@ -1004,6 +1906,7 @@ where
         C::UtxoAccumulator: Default,
         C::AssetMap: Default,
         C::Checkpoint: Default,
+        C::LedgerSnapshot: Default,
         C::Rng: Default
     "),
     Eq(bound = r"
@@ -1012,6 +1915,14 @@ where
         C::UtxoAccumulator: Eq,
         C::AssetMap: Eq,
         C::Checkpoint: Eq,
+        C::LedgerSnapshot: Eq,
+        PaymentReceipt<C>: Eq,
+        IdentifiedAsset<C>: Eq,
+        LeaseTable<C>: Eq,
+        Quarantine<C>: Eq,
+        HealthRecord<C>: Eq,
+        ReceiptArchive<C>: Eq,
+        C::AssetValue: Eq,
         C::Rng: Eq
     "),
     Hash(bound = r"
@@ -1020,6 +1931,14 @@ where
         C::UtxoAccumulator: Hash,
         C::AssetMap: Hash,
         C::Checkpoint: Hash,
+        C::LedgerSnapshot: Hash,
+        PaymentReceipt<C>: Hash,
+        IdentifiedAsset<C>: Hash,
+        LeaseTable<C>: Hash,
+        Quarantine<C>: Hash,
+        HealthRecord<C>: Hash,
+        ReceiptArchive<C>: Hash,
+        C::AssetValue: Hash,
         C::Rng: Hash
     "),
     PartialEq(bound = r"
@@ -1028,6 +1947,14 @@ where
         C::UtxoAccumulator: PartialEq,
         C::AssetMap: PartialEq,
         C::Checkpoint: PartialEq,
+        C::LedgerSnapshot: PartialEq,
+        PaymentReceipt<C>: PartialEq,
+        IdentifiedAsset<C>: PartialEq,
+        LeaseTable<C>: PartialEq,
+        Quarantine<C>: PartialEq,
+        HealthRecord<C>: PartialEq,
+        ReceiptArchive<C>: PartialEq,
+        C::AssetValue: PartialEq,
         C::Rng: PartialEq
     ")
 )]
@@ -1056,6 +1983,87 @@ where
     /// Current Checkpoint
     checkpoint: C::Checkpoint,
 
+    /// Payment Receipts
+    ///
+    /// Receipts for the payments to other addresses signed by this signer, in signing order.
+    #[cfg_attr(feature = "serde", serde(default))]
+    receipts: Vec<PaymentReceipt<C>>,
+
+    /// Ledger Snapshot
+    #[cfg_attr(feature = "serde", serde(default))]
+    ledger_snapshot: C::LedgerSnapshot,
+
+    /// Pending Imports
+    ///
+    /// Notes imported with [`Signer::import_notes`] whose [`Utxo`] was not synchronized yet.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pending_imports: Vec<IdentifiedAsset<C>>,
+
+    /// Privacy Strategy
+    #[cfg_attr(feature = "serde", serde(default))]
+    privacy_strategy: PrivacyStrategy,
+
+    /// Splitting Strategy
+    ///
+    /// Payments to other addresses are sent in one piece when there is no splitting strategy.
+    #[cfg_attr(feature = "serde", serde(default))]
+    splitting_strategy: Option<SplittingStrategy<C::AssetValue>>,
+
+    /// Zero Note Avoidance
+    ///
+    /// Requires a non-zero change whenever the transfer shape permits it, and keeps the
+    /// zero-value notes which could not be avoided out of the spendable assets.
+    #[cfg_attr(feature = "serde", serde(default))]
+    avoid_zero_notes: bool,
+
+    /// Lease Configuration
+    ///
+    /// Notes are only leased by signers which share their seed with other devices.
+    #[cfg_attr(feature = "serde", serde(default))]
+    lease_configuration: Option<LeaseConfiguration>,
+
+    /// Lease Table
+    #[cfg_attr(feature = "serde", serde(default))]
+    leases: LeaseTable<C>,
+
+    /// Note Quarantine
+    ///
+    /// Notes which match the detection heuristics of this signer but cannot be opened.
+    #[cfg_attr(feature = "serde", serde(default))]
+    quarantine: Quarantine<C>,
+
+    /// Capability Table
+    ///
+    /// Capabilities granted to the callers which can only make some of the signer requests.
+    #[cfg_attr(feature = "serde", serde(default))]
+    capabilities: CapabilityTable,
+
+    /// Health Record
+    ///
+    /// Backups and synchronizations of the signer reported by [`Signer::health`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    health: HealthRecord<C>,
+
+    /// Retention Policy
+    ///
+    /// Payment receipts are kept forever when there is no retention policy.
+    #[cfg_attr(feature = "serde", serde(default))]
+    retention_policy: Option<RetentionPolicy>,
+
+    /// Receipt Archive
+    ///
+    /// Dates of the payment receipts and metadata of the ones pruned by the retention policy.
+    #[cfg_attr(feature = "serde", serde(default))]
+    receipt_archive: ReceiptArchive<C>,
+
+    /// Membership Proof Cache
+    ///
+    /// The cached proofs can always be recomputed from the rest of the state, so they are not
+    /// saved to the file system.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[derivative(Debug = "ignore", Hash = "ignore", PartialEq = "ignore")]
+    membership_proofs: MembershipProofCache<C>,
+
     /// Random Number Generator
     ///
     /// We use this entropy source to add randomness to various cryptographic constructions. The
@@ -1076,6 +2084,20 @@ where
             accounts: None,
             authorization_context: None,
             checkpoint: C::Checkpoint::from_utxo_accumulator(&utxo_accumulator),
+            receipts: Vec::new(),
+            ledger_snapshot: Default::default(),
+            pending_imports: Vec::new(),
+            privacy_strategy: Default::default(),
+            splitting_strategy: None,
+            avoid_zero_notes: false,
+            lease_configuration: None,
+            leases: Default::default(),
+            quarantine: Default::default(),
+            capabilities: Default::default(),
+            health: Default::default(),
+            retention_policy: None,
+            receipt_archive: Default::default(),
+            membership_proofs: Default::default(),
             utxo_accumulator,
             assets,
             rng,
@@ -1120,13 +2142,14 @@ where
     /// Loads `authorization_context` to `self`.
     #[inline]
     pub fn load_authorization_context(&mut self, authorization_context: AuthorizationContext<C>) {
+        self.authorization_context.zeroize();
         self.authorization_context = Some(authorization_context)
     }
 
-    /// Drops `self.authorization_context`.
+    /// Zeroizes and drops `self.authorization_context`.
     #[inline]
     pub fn drop_authorization_context(&mut self) {
-        self.authorization_context = None
+        self.authorization_context.zeroize()
     }
 
     /// Returns the [`AccountTable`].
@@ -1141,40 +2164,166 @@ where
         self.authorization_context.as_ref()
     }
 
-    /// Returns the default account for `self`.
+    /// Returns the payment receipts stored in `self`, in signing order.
     #[inline]
-    pub fn default_account(&self) -> Option<Account<C::Account>> {
-        Some(self.accounts.as_ref()?.get_default())
+    pub fn receipts(&self) -> &[PaymentReceipt<C>] {
+        &self.receipts
     }
-}
 
-impl<C> Clone for SignerState<C>
-where
-    C: Configuration,
-    AccountTable<C>: Clone,
-    AuthorizationContext<C>: Clone,
-    C::UtxoAccumulator: Clone,
-    C::AssetMap: Clone,
-{
+    /// Returns the [`AssetMap`](Configuration::AssetMap) of `self`.
     #[inline]
-    fn clone(&self) -> Self {
-        let mut signer_state = Self::build(
-            self.utxo_accumulator.clone(),
-            self.assets.clone(),
-            FromEntropy::from_entropy(),
-        );
-        if self.accounts.is_some() {
-            signer_state.load_accounts(self.accounts.as_ref().unwrap().clone());
-        }
-        if self.authorization_context.is_some() {
-            signer_state
-                .load_authorization_context(self.authorization_context.as_ref().unwrap().clone());
-        }
-        signer_state
+    pub fn assets(&self) -> &C::AssetMap {
+        &self.assets
     }
-}
 
-/// Signer
+    /// Returns the [`UtxoAccumulator`](Configuration::UtxoAccumulator) of `self`.
+    #[inline]
+    pub fn utxo_accumulator(&self) -> &C::UtxoAccumulator {
+        &self.utxo_accumulator
+    }
+
+    /// Returns the [`LedgerSnapshot`] of `self`.
+    #[inline]
+    pub fn ledger_snapshot(&self) -> &C::LedgerSnapshot {
+        &self.ledger_snapshot
+    }
+
+    /// Returns the notes imported into `self` whose [`Utxo`] was not synchronized yet.
+    #[inline]
+    pub fn pending_imports(&self) -> &[IdentifiedAsset<C>] {
+        &self.pending_imports
+    }
+
+    /// Returns the [`PrivacyStrategy`] which `self` signs transactions with.
+    #[inline]
+    pub fn privacy_strategy(&self) -> &PrivacyStrategy {
+        &self.privacy_strategy
+    }
+
+    /// Returns the [`SplittingStrategy`] which `self` splits the payments to other addresses
+    /// with, if any.
+    #[inline]
+    pub fn splitting_strategy(&self) -> Option<&SplittingStrategy<C::AssetValue>> {
+        self.splitting_strategy.as_ref()
+    }
+
+    /// Returns `true` if `self` avoids creating and spending zero-value notes.
+    #[inline]
+    pub fn avoid_zero_notes(&self) -> bool {
+        self.avoid_zero_notes
+    }
+
+    /// Returns the [`LeaseConfiguration`] of `self`, if it leases the notes it spends.
+    #[inline]
+    pub fn lease_configuration(&self) -> Option<&LeaseConfiguration> {
+        self.lease_configuration.as_ref()
+    }
+
+    /// Returns the [`LeaseTable`] of `self`.
+    #[inline]
+    pub fn leases(&self) -> &LeaseTable<C> {
+        &self.leases
+    }
+
+    /// Returns the [`Quarantine`] of `self`.
+    #[inline]
+    pub fn quarantine(&self) -> &Quarantine<C> {
+        &self.quarantine
+    }
+
+    /// Returns the [`CapabilityTable`] of `self`.
+    #[inline]
+    pub fn capabilities(&self) -> &CapabilityTable {
+        &self.capabilities
+    }
+
+    /// Returns the [`HealthRecord`] of `self`.
+    #[inline]
+    pub fn health_record(&self) -> &HealthRecord<C> {
+        &self.health
+    }
+
+    /// Returns the [`RetentionPolicy`] of `self`, if it prunes its payment receipts.
+    #[inline]
+    pub fn retention_policy(&self) -> Option<&RetentionPolicy> {
+        self.retention_policy.as_ref()
+    }
+
+    /// Returns the [`ReceiptArchive`] of `self`.
+    #[inline]
+    pub fn receipt_archive(&self) -> &ReceiptArchive<C> {
+        &self.receipt_archive
+    }
+
+    /// Returns the [`MembershipProofCache`] of `self`.
+    #[inline]
+    pub fn membership_proofs(&self) -> &MembershipProofCache<C> {
+        &self.membership_proofs
+    }
+
+    /// Returns the default account for `self`.
+    #[inline]
+    pub fn default_account(&self) -> Option<Account<C::Account>> {
+        Some(self.accounts.as_ref()?.get_default())
+    }
+}
+
+impl<C> Clone for SignerState<C>
+where
+    C: Configuration,
+    AccountTable<C>: Clone,
+    AuthorizationContext<C>: Clone,
+    C::UtxoAccumulator: Clone,
+    C::AssetMap: Clone,
+    PaymentReceipt<C>: Clone,
+    LeaseTable<C>: Clone,
+    Quarantine<C>: Clone,
+    HealthRecord<C>: Clone,
+    ReceiptArchive<C>: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        let mut signer_state = Self::build(
+            self.utxo_accumulator.clone(),
+            self.assets.clone(),
+            FromEntropy::from_entropy(),
+        );
+        if self.accounts.is_some() {
+            signer_state.load_accounts(self.accounts.as_ref().unwrap().clone());
+        }
+        if self.authorization_context.is_some() {
+            signer_state
+                .load_authorization_context(self.authorization_context.as_ref().unwrap().clone());
+        }
+        signer_state.receipts = self.receipts.clone();
+        signer_state.ledger_snapshot = self.ledger_snapshot.clone();
+        signer_state.pending_imports = self.pending_imports.clone();
+        signer_state.privacy_strategy = self.privacy_strategy;
+        signer_state.splitting_strategy = self.splitting_strategy.clone();
+        signer_state.avoid_zero_notes = self.avoid_zero_notes;
+        signer_state.lease_configuration = self.lease_configuration;
+        signer_state.leases = self.leases.clone();
+        signer_state.quarantine = self.quarantine.clone();
+        signer_state.capabilities = self.capabilities.clone();
+        signer_state.health = self.health.clone();
+        signer_state.retention_policy = self.retention_policy;
+        signer_state.receipt_archive = self.receipt_archive.clone();
+        signer_state.membership_proofs = MembershipProofCache::new(self.membership_proofs.capacity);
+        signer_state
+    }
+}
+
+impl<C> Drop for SignerState<C>
+where
+    C: Configuration,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.authorization_context.zeroize();
+    }
+}
+
+/// Signer
 #[cfg_attr(
     feature = "serde",
     derive(Deserialize, Serialize),
@@ -1226,10 +2375,7 @@ where
         rng: C::Rng,
     ) -> Self {
         Self::from_parts(
-            SignerParameters {
-                parameters,
-                proving_context,
-            },
+            SignerParameters::new(parameters, proving_context),
             SignerState::build(utxo_accumulator, assets, rng),
         )
     }
@@ -1263,11 +2409,39 @@ where
         &self.state
     }
 
+    /// Caches the structure of the transfer circuits in the proving contexts of `self`, so that
+    /// signing a transaction only assigns the witness of its proofs instead of synthesizing their
+    /// whole circuits again, which pays off for signers which sign transactions frequently.
+    /// Returns `true` if the circuits of all the transfer shapes were cached.
+    #[inline]
+    pub fn cache_synthesis(&mut self) -> bool {
+        transfer::canonical::cache_synthesis(
+            FullParametersRef::<C>::new(
+                &self.parameters.parameters,
+                self.state.utxo_accumulator.model(),
+            ),
+            &mut self.parameters.proving_context,
+        )
+    }
+
+    /// Returns a mutable reference to the asset map of the signer, to persist it to a
+    /// [`AssetMapStore`](store::AssetMapStore) or restore it from one.
+    ///
+    /// # Warning
+    ///
+    /// The asset map must stay in sync with the UTXO accumulator of the signer, so it should only
+    /// be replaced by a map persisted from the same signer state.
+    #[inline]
+    pub fn assets_mut(&mut self) -> &mut C::AssetMap {
+        &mut self.state.assets
+    }
+
     /// Loads `accounts` to `self` and updates the authorization context.
     #[inline]
     pub fn load_accounts(&mut self, accounts: AccountTable<C>) {
         self.state.load_accounts(accounts);
         self.update_authorization_context();
+        self.refresh_membership_proofs();
     }
 
     /// Drops `self.state.accounts`
@@ -1320,7 +2494,10 @@ where
         &mut self,
         request: SyncRequest<C, C::Checkpoint>,
     ) -> Result<SyncResponse<C, C::Checkpoint>, SyncError<C::Checkpoint>> {
-        functions::sync(
+        for nullifier in &request.data.nullifier_data {
+            self.state.ledger_snapshot.insert_nullifier(nullifier);
+        }
+        let response = functions::sync(
             &self.parameters,
             self.state
                 .authorization_context
@@ -1329,11 +2506,244 @@ where
             &mut self.state.assets,
             &mut self.state.checkpoint,
             &mut self.state.utxo_accumulator,
+            &mut self.state.pending_imports,
+            &mut self.state.quarantine,
             request,
+            self.state.avoid_zero_notes,
+            &mut self.state.rng,
+        )?;
+        self.state
+            .ledger_snapshot
+            .update_from_utxo_accumulator(&self.state.utxo_accumulator);
+        self.refresh_membership_proofs();
+        Ok(response)
+    }
+
+    /// Updates the internal ledger state like [`sync`](Self::sync), recording the synchronization
+    /// at time `now` in the [`HealthRecord`] of `self`.
+    #[inline]
+    pub fn sync_at(
+        &mut self,
+        request: SyncRequest<C, C::Checkpoint>,
+        now: u64,
+    ) -> Result<SyncResponse<C, C::Checkpoint>, SyncError<C::Checkpoint>> {
+        let response = self.sync(request)?;
+        self.state
+            .health
+            .record_sync(now, &response.balance_update, &self.state.assets);
+        Ok(response)
+    }
+
+    /// Records a backup of the state of `self` at time `now`, for instance after saving the
+    /// [`StorageStateOption`] returned by [`get_storage`](Self::get_storage).
+    #[inline]
+    pub fn record_backup(&mut self, now: u64) {
+        self.state.health.record_backup(now);
+    }
+
+    /// Sets the thresholds of the [`SignerHealth`] reports of `self` to `settings`.
+    #[inline]
+    pub fn set_health_settings(&mut self, settings: HealthSettings<C::AssetValue>) {
+        self.state.health.set_settings(settings);
+    }
+
+    /// Returns the [`SignerHealth`] of `self` at time `now`.
+    #[inline]
+    pub fn health(&self, now: u64) -> SignerHealth<C> {
+        let membership_proofs = &self.state.membership_proofs;
+        self.state.health.report(
+            now,
+            self.state.checkpoint.clone(),
+            self.parameters.versions,
+            membership_proofs.capacity() == 0 || membership_proofs.is_fresh(&self.state.checkpoint),
+        )
+    }
+
+    /// Imports the notes in `request`, which were created out-of-band, into the spendable assets
+    /// of `self`, returning the [`ImportStatus`] of every note.
+    ///
+    /// Notes whose [`Utxo`] is not in the UTXO accumulator yet stay pending until the [`Utxo`] is
+    /// found by [`sync`](Self::sync).
+    #[inline]
+    pub fn import_notes(
+        &mut self,
+        request: ImportRequest<C>,
+    ) -> Result<ImportResponse, ImportError> {
+        let response = functions::import_notes(
+            &self.parameters.parameters,
+            self.state
+                .authorization_context
+                .as_mut()
+                .ok_or(ImportError::MissingProofAuthorizationKey)?,
+            &mut self.state.assets,
+            &self.state.utxo_accumulator,
+            &mut self.state.pending_imports,
+            request,
+            &mut self.state.rng,
+        );
+        self.refresh_membership_proofs();
+        Ok(response)
+    }
+
+    /// Tries to open the notes in the [`Quarantine`] of `self` again, for instance after an
+    /// upgrade of the opening procedure, importing the ones which can now be opened like
+    /// [`import_notes`](Self::import_notes) does.
+    ///
+    /// The recovered notes were synchronized before, so they stay pending until `self` is
+    /// synchronized again from an empty state or with [`frontier_sync`](Self::frontier_sync).
+    #[inline]
+    pub fn reprocess_quarantine(&mut self) -> Result<ReprocessResponse, ImportError> {
+        let response = functions::reprocess_quarantine(
+            &self.parameters.parameters,
+            self.state
+                .authorization_context
+                .as_mut()
+                .ok_or(ImportError::MissingProofAuthorizationKey)?,
+            &mut self.state.assets,
+            &self.state.utxo_accumulator,
+            &mut self.state.pending_imports,
+            &mut self.state.quarantine,
+            &mut self.state.rng,
+        );
+        self.refresh_membership_proofs();
+        Ok(response)
+    }
+
+    /// Removes the copies of the assets which are stored more than once in `self`, as older
+    /// versions of the signer could do when synchronizing the same data twice, returning the
+    /// number of copies which were removed.
+    ///
+    /// The balance state of a wallet synchronized against such a signer is healed with
+    /// [`Wallet::reconcile`](crate::wallet::Wallet::reconcile).
+    #[inline]
+    pub fn reconcile_assets(&mut self) -> usize {
+        let duplicates = functions::reconcile_assets::<C>(&mut self.state.assets);
+        if duplicates != 0 {
+            self.refresh_membership_proofs();
+        }
+        duplicates
+    }
+
+    /// Returns the [`Utxo`]s of the notes owned by `self`, both spendable and pending, or `None`
+    /// if `self` has no proof authorization key.
+    ///
+    /// These are the [`Utxo`]s whose membership proofs should be requested from the ledger to
+    /// build a [`FrontierSyncRequest`] for [`frontier_sync`](Self::frontier_sync).
+    #[inline]
+    pub fn owned_utxos(&mut self) -> Option<Vec<Utxo<C>>> {
+        Some(functions::owned_utxos::<C>(
+            &self.parameters.parameters,
+            self.state.authorization_context.as_mut()?,
+            &self.state.assets,
+            &self.state.pending_imports,
+            &mut self.state.rng,
+        ))
+    }
+
+    /// Returns the [`NullifierWatchList`](watch::NullifierWatchList) of the notes owned by
+    /// `self`, both spendable and pending, hashed with a fresh salt, or `None` if `self` has no
+    /// proof authorization key.
+    ///
+    /// The watch-list can be handed to an external monitoring service, which reports the digests
+    /// of the nullifiers it detects on the ledger. The watch-list should be kept, since its salt
+    /// is needed to map those digests back to the spent notes with
+    /// [`watched_note`](Self::watched_note).
+    #[inline]
+    pub fn nullifier_watch_list(&mut self) -> Option<watch::NullifierWatchList<C>> {
+        let salt = self.state.rng.gen();
+        Some(functions::nullifier_watch_list::<C>(
+            &self.parameters.parameters,
+            self.state.authorization_context.as_mut()?,
+            &self.state.assets,
+            &self.state.pending_imports,
+            salt,
+            &mut self.state.rng,
+        ))
+    }
+
+    /// Returns the note owned by `self` whose nullifier hashes to `digest` with `salt`, as
+    /// reported by the monitoring service watching a
+    /// [`NullifierWatchList`](watch::NullifierWatchList) of `self`. Returns `None` if `self` has
+    /// no proof authorization key or if no note matches `digest`, for instance because the note
+    /// was already removed by a synchronization.
+    #[inline]
+    pub fn watched_note(
+        &mut self,
+        salt: &watch::WatchListSalt,
+        digest: &watch::Digest<C>,
+    ) -> Option<IdentifiedAsset<C>> {
+        functions::watched_note::<C>(
+            &self.parameters.parameters,
+            self.state.authorization_context.as_mut()?,
+            &self.state.assets,
+            &self.state.pending_imports,
+            salt,
+            digest,
             &mut self.state.rng,
         )
     }
 
+    /// Returns a [`BlindedDetectionKey`](index::BlindedDetectionKey) which hides the partition of
+    /// the address of `self` among random decoys, so that it matches `size`-many partitions, or
+    /// `None` if `self` has no proof authorization key.
+    ///
+    /// The key can be handed to a [`NoteIndexer`](index::NoteIndexer), which only learns that
+    /// `self` owns one of the addresses of those partitions. Larger keys hide `self` among more
+    /// addresses, at the cost of more notes to download and decrypt.
+    #[inline]
+    pub fn blinded_detection_key(&mut self, size: usize) -> Option<index::BlindedDetectionKey<C>> {
+        let address = self.address()?;
+        let partition = <C::NoteDetection as index::NoteDetection<C>>::address_partition(
+            &self.parameters.parameters,
+            &address,
+        );
+        Some(index::BlindedDetectionKey::sample(
+            partition,
+            size,
+            &mut self.state.rng,
+        ))
+    }
+
+    /// Synchronizes `self` with the ledger frontier in `request`, returning the new asset
+    /// distribution.
+    ///
+    /// # Frontier Synchronization
+    ///
+    /// Instead of every [`Utxo`] on the ledger, the UTXO accumulator of `self` is rebuilt from the
+    /// frontier of the ledger and the membership proofs of the [`owned_utxos`](Self::owned_utxos),
+    /// so its size only depends on the number of notes owned by `self`. This suits wallets which
+    /// only send from notes they already know, since new notes sent to `self` are not discovered:
+    /// they have to be added with [`import_notes`](Self::import_notes) or recovered with a full
+    /// [`sync`](Self::sync) from an empty state. Owned notes whose membership proof is missing
+    /// from `request` stay pending until it is served.
+    #[inline]
+    pub fn frontier_sync(
+        &mut self,
+        request: FrontierSyncRequest<C, C::Checkpoint>,
+    ) -> Result<SyncResponse<C, C::Checkpoint>, SyncError<C::Checkpoint>> {
+        for nullifier in &request.data.nullifier_data {
+            self.state.ledger_snapshot.insert_nullifier(nullifier);
+        }
+        let response = functions::frontier_sync(
+            &self.parameters.parameters,
+            self.state
+                .authorization_context
+                .as_mut()
+                .ok_or(SyncError::MissingProofAuthorizationKey)?,
+            &mut self.state.assets,
+            &mut self.state.checkpoint,
+            &mut self.state.utxo_accumulator,
+            &mut self.state.pending_imports,
+            request,
+            &mut self.state.rng,
+        )?;
+        self.state
+            .ledger_snapshot
+            .update_from_utxo_accumulator(&self.state.utxo_accumulator);
+        self.refresh_membership_proofs();
+        Ok(response)
+    }
+
     /// Performs the initial synchronization of a new signer with the ledger data.
     ///
     /// # Implementation Note
@@ -1347,12 +2757,17 @@ where
         &mut self,
         request: InitialSyncRequest<C>,
     ) -> Result<SyncResponse<C, C::Checkpoint>, SyncError<C::Checkpoint>> {
-        functions::intial_sync(
+        let response = functions::intial_sync(
             &mut self.state.assets,
             &mut self.state.checkpoint,
             &mut self.state.utxo_accumulator,
             request,
-        )
+        )?;
+        self.state
+            .ledger_snapshot
+            .update_from_utxo_accumulator(&self.state.utxo_accumulator);
+        self.refresh_membership_proofs();
+        Ok(response)
     }
 
     /// Generates an [`IdentityProof`] for `identified_asset` by
@@ -1369,10 +2784,77 @@ where
             self.state.utxo_accumulator.model(),
             identified_asset,
             public_account,
+            None,
             &mut self.state.rng,
         )
     }
 
+    /// Generates an [`IdentityProof`] for `identified_asset` like
+    /// [`identity_proof`](Self::identity_proof), anchored to the checkpoint of the ledger referenced
+    /// by `anchor`.
+    ///
+    /// The root of `anchor` is bound into the proof, so it can only be verified with
+    /// [`anchored_identity_verification`](IdentityProof::anchored_identity_verification) against
+    /// a root which the ledger signed and accepted.
+    #[inline]
+    pub fn anchored_identity_proof(
+        &mut self,
+        identified_asset: IdentifiedAsset<C>,
+        public_account: C::AccountId,
+        anchor: LedgerAnchor<C>,
+    ) -> Option<IdentityProof<C>>
+    where
+        UtxoAccumulatorOutput<C>: Clone,
+    {
+        let mut identity_proof = functions::identity_proof(
+            &self.parameters,
+            self.state.accounts.as_ref()?,
+            self.state.utxo_accumulator.model(),
+            identified_asset,
+            public_account,
+            Some(anchor.root.clone()),
+            &mut self.state.rng,
+        )?;
+        identity_proof.anchor = Some(anchor);
+        Some(identity_proof)
+    }
+
+    /// Generates a [`HoldingsProof`] that `self` owns a note
+    /// of at least `threshold` of the asset with `asset_id`, without spending it. The proof is
+    /// generated with `proving_context`, which is built for the
+    /// [`Holdings`](transfer::holdings::Holdings) circuit. Returns `None` if `self` has no
+    /// accounts loaded or no such note.
+    #[inline]
+    pub fn holdings_proof(
+        &mut self,
+        proving_context: &ProvingContext<C>,
+        asset_id: C::AssetId,
+        threshold: C::AssetValue,
+    ) -> Option<HoldingsProof<C>> {
+        functions::holdings_proof(
+            &self.parameters.parameters,
+            proving_context,
+            self.state.accounts.as_ref()?,
+            &self.state.assets,
+            &self.state.utxo_accumulator,
+            asset_id,
+            threshold,
+            &mut self.state.rng,
+        )
+    }
+
+    /// Answers the `challenge` of a verifier with an [`OwnershipProof`] that `self` controls its
+    /// [`Address`], without a transfer. Returns `None` if `self` has no accounts loaded.
+    #[inline]
+    pub fn ownership_proof(&mut self, challenge: &OwnershipChallenge) -> Option<OwnershipProof<C>> {
+        Some(functions::ownership_proof(
+            &self.parameters,
+            self.state.authorization_context.as_mut()?,
+            challenge,
+            &mut self.state.rng,
+        ))
+    }
+
     /// Signs the `transaction`, generating transfer posts.
     #[inline]
     pub fn sign(&mut self, transaction: Transaction<C>) -> Result<SignResponse<C>, SignError<C>> {
@@ -1382,11 +2864,352 @@ where
             self.state.authorization_context.as_mut(),
             &self.state.assets,
             &mut self.state.utxo_accumulator,
+            &self.state.checkpoint,
+            &mut self.state.membership_proofs,
+            &self.state.privacy_strategy,
+            self.state.splitting_strategy.as_ref(),
+            self.state.avoid_zero_notes,
+            transaction,
+            &mut self.state.receipts,
+            &mut self.state.rng,
+        )
+    }
+
+    /// Plans the `transaction` without signing it, returning the [`TransactionSummary`] of the
+    /// posts which [`sign`](Self::sign) would generate for it. See [`review`](Connection::review)
+    /// for more.
+    #[inline]
+    pub fn review(&self, transaction: Transaction<C>) -> ReviewResult<C> {
+        functions::review(
+            self.state.accounts.as_ref(),
+            self.state.authorization_context.as_ref(),
+            &self.state.assets,
+            self.state.splitting_strategy.as_ref(),
+            self.state.avoid_zero_notes,
+            transaction,
+        )
+    }
+
+    /// Signs the `transactions` jointly, generating the transfer posts of each of them. See
+    /// [`sign_batch`](Connection::sign_batch) for more.
+    #[inline]
+    pub fn sign_batch(&mut self, transactions: Vec<Transaction<C>>) -> SignBatchResult<C> {
+        functions::sign_batch(
+            &self.parameters,
+            self.state.accounts.as_ref(),
+            self.state.authorization_context.as_mut(),
+            &self.state.assets,
+            &mut self.state.utxo_accumulator,
+            &self.state.checkpoint,
+            &mut self.state.membership_proofs,
+            &self.state.privacy_strategy,
+            self.state.splitting_strategy.as_ref(),
+            self.state.avoid_zero_notes,
+            transactions,
+            &mut self.state.receipts,
+            &mut self.state.rng,
+        )
+    }
+
+    /// Signs the `transaction` like [`sign`](Self::sign), but sends the change to `change_address`
+    /// instead of the address of `self`. This lets the change of a withdraw go to a different
+    /// spending authority, like a sub-account with its own spending key. The change can then be
+    /// handed over to that spending authority from its receipt in [`receipts`](Self::receipts).
+    #[inline]
+    pub fn sign_with_change_address(
+        &mut self,
+        transaction: Transaction<C>,
+        change_address: Address<C>,
+    ) -> Result<SignResponse<C>, SignError<C>> {
+        functions::sign_with_change_address(
+            &self.parameters,
+            self.state.accounts.as_ref(),
+            self.state.authorization_context.as_mut(),
+            &self.state.assets,
+            &mut self.state.utxo_accumulator,
+            &self.state.checkpoint,
+            &mut self.state.membership_proofs,
+            &self.state.privacy_strategy,
+            self.state.splitting_strategy.as_ref(),
+            self.state.avoid_zero_notes,
             transaction,
+            Some(change_address),
+            &mut self.state.receipts,
             &mut self.state.rng,
         )
     }
 
+    /// Checks that the ledger described by the [`LedgerSnapshot`] of `self` would accept `posts`,
+    /// as returned by [`sign`](Self::sign), so that transactions built against a stale ledger
+    /// state or spending assets which are already spent are caught before they are broadcast.
+    #[inline]
+    pub fn check_posts(&mut self, posts: &[TransferPost<C>]) -> Result<(), LedgerSnapshotError> {
+        functions::check_posts(
+            &self.parameters.parameters,
+            &self.state.ledger_snapshot,
+            &mut self.state.utxo_accumulator,
+            posts,
+        )
+    }
+
+    /// Signs the `transaction`, generating transfer posts, and checks them against the local
+    /// [`LedgerSnapshot`] using [`check_posts`](Self::check_posts). If the check succeeds, the
+    /// nullifiers of the posts are recorded as pending spends until they are synchronized from the
+    /// ledger, so that later transactions which spend the same assets are rejected.
+    #[inline]
+    pub fn sign_and_check(
+        &mut self,
+        transaction: Transaction<C>,
+    ) -> Result<SignResponse<C>, SignError<C>> {
+        let response = self.sign(transaction)?;
+        self.check_posts(&response.posts)
+            .map_err(SignError::LedgerSnapshotError)?;
+        for post in &response.posts {
+            for sender in &post.body.sender_posts {
+                self.state
+                    .ledger_snapshot
+                    .insert_pending_nullifier(&sender.nullifier);
+            }
+        }
+        Ok(response)
+    }
+
+    /// Forgets the pending spends recorded by [`sign_and_check`](Self::sign_and_check), for
+    /// instance when the signed transactions were dropped before reaching the ledger.
+    #[inline]
+    pub fn clear_pending_spends(&mut self) {
+        self.state.ledger_snapshot.clear_pending_nullifiers();
+    }
+
+    /// Forgets the pending spends of `posts` which were rejected by the ledger, so that the notes
+    /// they spend can be spent again, returning the number of released spends.
+    ///
+    /// This method should only be called once `self` is synchronized past the rejection. A spend
+    /// is only released if its nullifier is not spent on the ledger according to the
+    /// [`LedgerSnapshot`], since a ledger which does not post transfers atomically may have spent
+    /// it before rejecting the post, in which case the note is gone and must not be spent again.
+    #[inline]
+    pub fn release_rejected_spends(&mut self, posts: &[TransferPost<C>]) -> usize {
+        let mut released = 0;
+        for sender in posts.iter().flat_map(|post| &post.body.sender_posts) {
+            let snapshot = &mut self.state.ledger_snapshot;
+            if snapshot.may_contain_pending_nullifier(&sender.nullifier)
+                && !snapshot.may_contain_nullifier(&sender.nullifier)
+            {
+                snapshot.remove_pending_nullifier(&sender.nullifier);
+                released += 1;
+            }
+        }
+        released
+    }
+
+    /// Sets the [`LeaseConfiguration`] which [`sign_with_lease`](Self::sign_with_lease) uses to
+    /// lease the spent notes. Without a configuration, the leases of the other devices are
+    /// ignored.
+    #[inline]
+    pub fn set_lease_configuration(&mut self, configuration: Option<LeaseConfiguration>) {
+        self.state.lease_configuration = configuration;
+    }
+
+    /// Returns the [`LeaseTable`] of `self`, which should be shared with the other devices with
+    /// the same seed.
+    #[inline]
+    pub fn leases(&self) -> &LeaseTable<C> {
+        self.state.leases()
+    }
+
+    /// Returns the [`Quarantine`] of `self`, with the notes which match the detection heuristics
+    /// of `self` but could not be opened.
+    #[inline]
+    pub fn quarantine(&self) -> &Quarantine<C> {
+        self.state.quarantine()
+    }
+
+    /// Sets the maximum number of notes kept in the [`Quarantine`] of `self` to `capacity`,
+    /// dropping the oldest notes if there are more than `capacity`-many of them.
+    #[inline]
+    pub fn set_quarantine_capacity(&mut self, capacity: usize) {
+        self.state.quarantine.set_capacity(capacity);
+    }
+
+    /// Returns the [`CapabilityTable`] of `self`, with the capabilities which were granted and not
+    /// revoked yet.
+    #[inline]
+    pub fn capabilities(&self) -> &CapabilityTable {
+        self.state.capabilities()
+    }
+
+    /// Grants a capability with `scope` which expires at `expires_at`, in milliseconds since the
+    /// Unix epoch, returning its token. See the [`capability`] module for more.
+    #[inline]
+    pub fn grant_capability(&mut self, scope: Scope, expires_at: Option<u64>) -> CapabilityToken {
+        self.state
+            .capabilities
+            .grant(Capability::new(scope, expires_at), &mut self.state.rng)
+    }
+
+    /// Revokes the capability granted with `token`, returning `false` if it was already revoked.
+    #[inline]
+    pub fn revoke_capability(&mut self, token: &CapabilityToken) -> bool {
+        self.state.capabilities.revoke(token).is_some()
+    }
+
+    /// Returns a [`ScopedSigner`] which serves the requests allowed by the capability granted with
+    /// `token`, if it can still be used at time `now`, in milliseconds since the Unix epoch.
+    #[inline]
+    pub fn scoped(
+        &mut self,
+        token: &CapabilityToken,
+        now: u64,
+    ) -> Result<ScopedSigner<'_, C>, AuthorizationError> {
+        let scope = self.state.capabilities.authorize(token, now)?;
+        Ok(ScopedSigner::new(self, scope))
+    }
+
+    /// Merges the [`LeaseTable`] shared by another device with the same seed into the one of
+    /// `self`, returning the leases of `self` which were lost to the other device. The
+    /// transactions spending the notes of the lost leases would be rejected by the ledger, so they
+    /// should be dropped and signed again.
+    #[inline]
+    pub fn merge_leases(&mut self, leases: LeaseTable<C>) -> Vec<Lease<C>> {
+        let device = self
+            .state
+            .lease_configuration
+            .map(|configuration| configuration.device)
+            .unwrap_or_default();
+        self.state.leases.merge(leases, &device)
+    }
+
+    /// Signs the `transaction` like [`sign`](Self::sign), leasing the spent notes at time `now`,
+    /// in milliseconds since the Unix epoch. The notes leased by other devices are handled
+    /// according to the [`ConflictPolicy`](lease::ConflictPolicy) of the
+    /// [`LeaseConfiguration`] of `self`. Without a configuration, this method is the same as
+    /// [`sign`](Self::sign).
+    #[inline]
+    pub fn sign_with_lease(
+        &mut self,
+        transaction: Transaction<C>,
+        now: u64,
+    ) -> Result<SignResponse<C>, SignError<C>> {
+        let configuration = match self.state.lease_configuration {
+            Some(configuration) => configuration,
+            _ => return self.sign(transaction),
+        };
+        functions::sign_with_lease(
+            &self.parameters,
+            self.state.accounts.as_ref(),
+            self.state.authorization_context.as_mut(),
+            &self.state.assets,
+            &mut self.state.utxo_accumulator,
+            &self.state.checkpoint,
+            &mut self.state.membership_proofs,
+            &self.state.privacy_strategy,
+            self.state.splitting_strategy.as_ref(),
+            self.state.avoid_zero_notes,
+            &mut self.state.leases,
+            &configuration,
+            transaction,
+            &mut self.state.receipts,
+            now,
+            &mut self.state.rng,
+        )
+    }
+
+    /// Sets the [`PrivacyStrategy`] which [`sign`](Self::sign) uses to randomize the choices that
+    /// would otherwise tell the transactions of `self` apart from the ones of other wallets.
+    #[inline]
+    pub fn set_privacy_strategy(&mut self, strategy: PrivacyStrategy) {
+        self.state.privacy_strategy = strategy;
+    }
+
+    /// Sets the [`SplittingStrategy`] which [`sign`](Self::sign) uses to split the payments to
+    /// other addresses into standardized denominations, or sends them in one piece if `strategy`
+    /// is `None`. Withdraws to public accounts are never split, since their amount is public.
+    #[inline]
+    pub fn set_splitting_strategy(&mut self, strategy: Option<SplittingStrategy<C::AssetValue>>) {
+        self.state.splitting_strategy = strategy;
+    }
+
+    /// Estimates the cost of paying `value` to another address with the [`SplittingStrategy`] of
+    /// `self`, returning `None` if `self` does not split its payments.
+    #[inline]
+    pub fn split_estimate(&self, value: C::AssetValue) -> Option<SplitEstimate> {
+        Some(self.state.splitting_strategy.as_ref()?.estimate(value))
+    }
+
+    /// Sets whether `self` avoids zero-value notes. When set, [`sign`](Self::sign) spends one
+    /// more note whenever the change would be zero and the transfer has a sender to spare, so that
+    /// the change note is not a zero-value note, and [`sync`](Self::sync) keeps the zero-value
+    /// notes which could not be avoided out of the spendable assets. The transfers are then padded
+    /// with fresh zero-value senders instead of spending those notes.
+    #[inline]
+    pub fn set_avoid_zero_notes(&mut self, avoid_zero_notes: bool) {
+        self.state.avoid_zero_notes = avoid_zero_notes;
+    }
+
+    /// Maintains precomputed membership proofs for the `capacity` largest spendable assets of each
+    /// asset id, so that [`sign`](Self::sign) can skip the
+    /// [`UtxoAccumulator`](Configuration::UtxoAccumulator) walk for transactions which only spend
+    /// those assets. The proofs are recomputed whenever `self` synchronizes with the ledger. A
+    /// `capacity` of zero disables the precomputation.
+    #[inline]
+    pub fn precompute_membership_proofs(&mut self, capacity: usize) {
+        self.state.membership_proofs = MembershipProofCache::new(capacity);
+        self.refresh_membership_proofs();
+    }
+
+    /// Recomputes the membership proofs in `self.state.membership_proofs` at the current
+    /// checkpoint, if the accounts are loaded.
+    #[inline]
+    fn refresh_membership_proofs(&mut self) {
+        match self.state.accounts.as_ref() {
+            Some(accounts) => functions::precompute_membership_proofs(
+                accounts,
+                &self.parameters.parameters,
+                &self.state.assets,
+                &self.state.checkpoint,
+                &self.state.utxo_accumulator,
+                &mut self.state.membership_proofs,
+                &mut self.state.rng,
+            ),
+            _ => self.state.membership_proofs.reset(None),
+        }
+    }
+
+    /// Returns the payment receipts for all the payments to other addresses signed by `self`, so
+    /// that they can be exported and presented to the recipients later.
+    #[inline]
+    pub fn receipts(&self) -> &[PaymentReceipt<C>] {
+        self.state.receipts()
+    }
+
+    /// Sets the [`RetentionPolicy`] of the payment receipts of `self` to `policy`, or keeps them
+    /// forever if `policy` is `None`.
+    #[inline]
+    pub fn set_retention_policy(&mut self, policy: Option<RetentionPolicy>) {
+        self.state.retention_policy = policy;
+    }
+
+    /// Prunes the payment receipts of `self` which have expired at time `now` under its
+    /// [`RetentionPolicy`], returning how many were compacted or dropped. Pruned receipts can no
+    /// longer be used to prove their payments. See [`retention`] for more.
+    #[inline]
+    pub fn prune_receipts(&mut self, now: u64) -> PruneSummary {
+        match &self.state.retention_policy {
+            Some(policy) => self
+                .state
+                .receipt_archive
+                .prune(&mut self.state.receipts, policy, now),
+            _ => Default::default(),
+        }
+    }
+
+    /// Returns the metadata of the payment receipts pruned from `self`, in signing order.
+    #[inline]
+    pub fn receipt_history(&self) -> &[ReceiptMetadata<C>] {
+        self.state.receipt_archive.history()
+    }
+
     /// Returns a vector with the [`IdentityProof`] corresponding to each [`IdentifiedAsset`] in `identified_assets`.
     #[inline]
     pub fn batched_identity_proof(
@@ -1463,7 +3286,13 @@ where
                 .ok_or(SignError::MissingProofAuthorizationKey)?,
             &self.state.assets,
             &mut self.state.utxo_accumulator,
+            &self.state.checkpoint,
+            &mut self.state.membership_proofs,
+            &self.state.privacy_strategy,
+            self.state.splitting_strategy.as_ref(),
+            self.state.avoid_zero_notes,
             transaction,
+            &mut self.state.receipts,
             &mut self.state.rng,
         )
     }
@@ -1487,6 +3316,10 @@ where
     {
         if let Some(storage_state) = storage_state {
             storage_state.update_signer(self);
+            self.state
+                .ledger_snapshot
+                .update_from_utxo_accumulator(&self.state.utxo_accumulator);
+            self.refresh_membership_proofs();
             return true;
         }
         false
@@ -1510,7 +3343,7 @@ where
         &mut self,
         request: SyncRequest<C, C::Checkpoint>,
     ) -> Result<SyncResponse<C, C::Checkpoint>, SyncError<C::Checkpoint>> {
-        functions::sbt_sync(
+        let response = functions::sbt_sync(
             &self.parameters,
             self.state
                 .authorization_context
@@ -1518,8 +3351,11 @@ where
                 .ok_or(SyncError::MissingProofAuthorizationKey)?,
             &mut self.state.assets,
             &mut self.state.checkpoint,
+            &mut self.state.quarantine,
             request,
-        )
+        )?;
+        self.refresh_membership_proofs();
+        Ok(response)
     }
 
     /// Prunes the [`UtxoAccumulator`](Configuration::UtxoAccumulator), deleting any data which
@@ -1570,6 +3406,30 @@ where
         Box::pin(async move { Ok(self.sign(request.transaction)) })
     }
 
+    #[inline]
+    fn sign_batch(
+        &mut self,
+        request: SignBatchRequest<Self::AssetMetadata, C>,
+    ) -> LocalBoxFutureResult<'_, SignBatchResult<C>, Self::Error> {
+        Box::pin(async move {
+            Ok(self.sign_batch(
+                request
+                    .0
+                    .into_iter()
+                    .map(|request| request.transaction)
+                    .collect(),
+            ))
+        })
+    }
+
+    #[inline]
+    fn review(
+        &mut self,
+        request: SignRequest<Self::AssetMetadata, C>,
+    ) -> LocalBoxFutureResult<'_, ReviewResult<C>, Self::Error> {
+        Box::pin(async move { Ok(Signer::review(self, request.transaction)) })
+    }
+
     #[inline]
     fn address(&mut self) -> LocalBoxFutureResult<Option<Address<C>>, Self::Error> {
         Box::pin(async move { Ok(self.address()) })
@@ -0,0 +1,276 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Privacy Strategies
+//!
+//! The protocol leaves some choices to the signer which are visible to the ledger or to the
+//! receivers of a payment, like the position of the change note among the receivers, the order in
+//! which the selected notes are merged when they do not fit into a single transfer, or the moment
+//! at which the posts are broadcast. A signer which always makes these choices in the same way can
+//! be told apart from other wallets. A [`PrivacyStrategy`] selects which of them are randomized.
+//!
+//! The number of receivers of every transfer is fixed by its [`TransferShape`], so the number of
+//! change notes is the same for all the transactions of the same shape and does not need to be
+//! randomized.
+//!
+//! The amounts of the payments are hidden from the ledger but not from their receivers, and a
+//! receiver which is paid the exact amount of every invoice can tell apart the wallets which pay
+//! it by their change patterns. A [`SplittingStrategy`] breaks the payments into standardized
+//! denominations instead, at the cost of one more transfer for every extra piece.
+//!
+//! [`TransferShape`]: crate::transfer::canonical::TransferShape
+
+use core::{cmp::Ordering, time::Duration};
+use manta_crypto::rand::RngCore;
+use manta_util::{num::CheckedSub, vec::Vec};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Privacy Strategy
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PrivacyStrategy {
+    /// Receiver Shuffling
+    ///
+    /// Randomizes the position of the change note among the receivers of the transfers.
+    pub shuffle_receivers: bool,
+
+    /// Sender Shuffling
+    ///
+    /// Randomizes the order in which the selected notes are spent, and so which of them are merged
+    /// together when they do not fit into a single transfer.
+    pub shuffle_senders: bool,
+
+    /// Maximum Broadcast Delay in Milliseconds
+    ///
+    /// Upper bound of the delays returned by [`broadcast_delay`](Self::broadcast_delay).
+    pub maximum_broadcast_delay: u64,
+}
+
+impl PrivacyStrategy {
+    /// Deterministic Strategy
+    ///
+    /// Makes every choice in the same way, which keeps the signed transactions reproducible. This
+    /// is the default strategy.
+    pub const DETERMINISTIC: Self = Self {
+        shuffle_receivers: false,
+        shuffle_senders: false,
+        maximum_broadcast_delay: 0,
+    };
+
+    /// Balanced Strategy
+    ///
+    /// Randomizes the shape of the transactions and delays their broadcast by up to five seconds,
+    /// which is short enough for interactive wallets.
+    pub const BALANCED: Self = Self {
+        shuffle_receivers: true,
+        shuffle_senders: true,
+        maximum_broadcast_delay: 5_000,
+    };
+
+    /// Paranoid Strategy
+    ///
+    /// Randomizes the shape of the transactions and delays their broadcast by up to two minutes,
+    /// for wallets which do not need their transactions to be posted right away.
+    pub const PARANOID: Self = Self {
+        shuffle_receivers: true,
+        shuffle_senders: true,
+        maximum_broadcast_delay: 120_000,
+    };
+
+    /// Shuffles `receivers` if receiver shuffling is enabled.
+    #[inline]
+    pub fn order_receivers<T, R>(&self, receivers: &mut [T], rng: &mut R)
+    where
+        R: RngCore + ?Sized,
+    {
+        if self.shuffle_receivers {
+            shuffle(receivers, rng);
+        }
+    }
+
+    /// Shuffles `senders` if sender shuffling is enabled.
+    #[inline]
+    pub fn order_senders<T, R>(&self, senders: &mut [T], rng: &mut R)
+    where
+        R: RngCore + ?Sized,
+    {
+        if self.shuffle_senders {
+            shuffle(senders, rng);
+        }
+    }
+
+    /// Samples the delay to wait for before broadcasting the next batch of posts, uniformly from
+    /// zero up to the maximum broadcast delay.
+    #[inline]
+    pub fn broadcast_delay<R>(&self, rng: &mut R) -> Duration
+    where
+        R: RngCore + ?Sized,
+    {
+        match self.maximum_broadcast_delay.checked_add(1) {
+            Some(bound) => Duration::from_millis(sample_below(bound, rng)),
+            _ => Duration::from_millis(rng.next_u64()),
+        }
+    }
+}
+
+/// Splitting Strategy
+///
+/// Splits the payments into the denominations of a ladder, from the largest one down, so that the
+/// receivers only see standardized amounts. Every piece after the first is paid by one more
+/// private transfer, which spends the change of the previous one.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SplittingStrategy<V> {
+    /// Denomination Ladder in Decreasing Order
+    denominations: Vec<V>,
+
+    /// Maximum Number of Pieces of a Payment
+    maximum_outputs: usize,
+}
+
+impl<V> SplittingStrategy<V>
+where
+    V: Clone + Default + PartialOrd,
+{
+    /// Builds a new [`SplittingStrategy`] over the `denominations` ladder which splits every
+    /// payment into at most `maximum_outputs` pieces. The zero denominations are discarded and
+    /// `maximum_outputs` is at least one.
+    #[inline]
+    pub fn new(mut denominations: Vec<V>, maximum_outputs: usize) -> Self {
+        denominations.retain(|denomination| *denomination > V::default());
+        denominations.sort_by(|lhs, rhs| rhs.partial_cmp(lhs).unwrap_or(Ordering::Equal));
+        denominations.dedup();
+        Self {
+            denominations,
+            maximum_outputs: maximum_outputs.max(1),
+        }
+    }
+
+    /// Returns the denominations of `self` in decreasing order.
+    #[inline]
+    pub fn denominations(&self) -> &[V] {
+        &self.denominations
+    }
+
+    /// Returns the maximum number of pieces which `self` splits a payment into.
+    #[inline]
+    pub fn maximum_outputs(&self) -> usize {
+        self.maximum_outputs
+    }
+
+    /// Splits `value` into pieces which add up to it, taking as many of the largest denominations
+    /// as fit before moving down the ladder. When the pieces run out or `value` is not a sum of
+    /// denominations, the last piece is whatever is left of `value`.
+    #[inline]
+    pub fn split(&self, value: V) -> Vec<V>
+    where
+        V: CheckedSub<Output = V>,
+    {
+        let mut pieces = Vec::new();
+        let mut remaining = value;
+        for denomination in &self.denominations {
+            while pieces.len() + 1 < self.maximum_outputs && remaining >= *denomination {
+                remaining = remaining
+                    .checked_sub(denomination.clone())
+                    .expect("Subtracting a smaller denomination is not allowed to fail.");
+                pieces.push(denomination.clone());
+            }
+        }
+        if pieces.is_empty() || remaining > V::default() {
+            pieces.push(remaining);
+        }
+        pieces
+    }
+
+    /// Estimates the cost of paying `value` with `self`.
+    #[inline]
+    pub fn estimate(&self, value: V) -> SplitEstimate
+    where
+        V: CheckedSub<Output = V>,
+    {
+        let pieces = self.split(value);
+        SplitEstimate {
+            outputs: pieces.len(),
+            extra_posts: pieces.len() - 1,
+            standardized: pieces
+                .iter()
+                .all(|piece| self.denominations.contains(piece)),
+        }
+    }
+}
+
+/// Split Estimate
+///
+/// This `struct` is created by the [`estimate`](SplittingStrategy::estimate) method on
+/// [`SplittingStrategy`]. See its documentation for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SplitEstimate {
+    /// Number of Pieces Received by the Payee
+    pub outputs: usize,
+
+    /// Number of Transfers Added to the Payment
+    ///
+    /// Every extra transfer carries its own proof, so this is also the number of extra proofs the
+    /// signer has to generate.
+    pub extra_posts: usize,
+
+    /// Standardized Amount Flag
+    ///
+    /// This flag is `true` when every piece is a denomination of the ladder, so that the receiver
+    /// learns nothing about the amount beyond the denominations it is made of.
+    pub standardized: bool,
+}
+
+/// Samples a uniformly random integer below `bound`, which must not be zero.
+#[inline]
+fn sample_below<R>(bound: u64, rng: &mut R) -> u64
+where
+    R: RngCore + ?Sized,
+{
+    let zone = u64::MAX - (u64::MAX - bound + 1) % bound;
+    loop {
+        let sample = rng.next_u64();
+        if sample <= zone {
+            return sample % bound;
+        }
+    }
+}
+
+/// Shuffles `slice` in place with the Fisher-Yates algorithm.
+#[inline]
+pub(super) fn shuffle<T, R>(slice: &mut [T], rng: &mut R)
+where
+    R: RngCore + ?Sized,
+{
+    for i in (1..slice.len()).rev() {
+        slice.swap(i, sample_below(i as u64 + 1, rng) as usize);
+    }
+}
@@ -0,0 +1,231 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Note Quarantine
+//!
+//! During synchronization, the signer skips every note which it cannot open. Most of them were
+//! sent to other addresses, but a note which matches the detection heuristics of the signer and
+//! still fails to open is either malformed or hits a bug of the opening procedure, and in both
+//! cases it may hold funds of the signer. Such notes are kept in a [`Quarantine`] instead of being
+//! dropped, counted in the [`SyncResponse`](super::SyncResponse)s, and can be opened again with
+//! [`Signer::reprocess_quarantine`](super::Signer::reprocess_quarantine) after the wallet is
+//! upgraded.
+//!
+//! # Detection Heuristics
+//!
+//! The detection heuristics are not exact: with the address partitions of the current protocol,
+//! about one in every 256 notes sent to other addresses also matches them. A [`Quarantine`] keeps
+//! only its latest [`capacity`](Quarantine::capacity)-many notes, and a sudden rise of the number
+//! of quarantined notes is a better signal than the number itself.
+
+use crate::{
+    transfer::{utxo::NoteOpenError, Note, Utxo},
+    wallet::signer::{Configuration, ImportResponse},
+};
+use alloc::collections::VecDeque;
+use core::{fmt::Debug, hash::Hash};
+use manta_util::cmp::Independence;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Quarantined Note
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Utxo<C>: Deserialize<'de>, Note<C>: Deserialize<'de>",
+            serialize = "Utxo<C>: Serialize, Note<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Utxo<C>: Clone, Note<C>: Clone"),
+    Debug(bound = "Utxo<C>: Debug, Note<C>: Debug"),
+    Eq(bound = "Utxo<C>: Eq, Note<C>: Eq"),
+    Hash(bound = "Utxo<C>: Hash, Note<C>: Hash"),
+    PartialEq(bound = "Utxo<C>: PartialEq, Note<C>: PartialEq")
+)]
+pub struct QuarantinedNote<C>
+where
+    C: Configuration,
+{
+    /// Unspent Transaction Output
+    pub utxo: Utxo<C>,
+
+    /// Note
+    pub note: Note<C>,
+
+    /// Reason
+    ///
+    /// Reason why the note could not be opened the last time it was processed.
+    pub reason: NoteOpenError,
+}
+
+/// Note Quarantine
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "QuarantinedNote<C>: Deserialize<'de>",
+            serialize = "QuarantinedNote<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "QuarantinedNote<C>: Clone"),
+    Debug(bound = "QuarantinedNote<C>: Debug"),
+    Eq(bound = "QuarantinedNote<C>: Eq"),
+    Hash(bound = "QuarantinedNote<C>: Hash"),
+    PartialEq(bound = "QuarantinedNote<C>: PartialEq")
+)]
+pub struct Quarantine<C>
+where
+    C: Configuration,
+{
+    /// Quarantined Notes
+    ///
+    /// The notes are kept in the order they were quarantined.
+    notes: VecDeque<QuarantinedNote<C>>,
+
+    /// Maximum Number of Quarantined Notes
+    capacity: usize,
+}
+
+impl<C> Quarantine<C>
+where
+    C: Configuration,
+{
+    /// Default Capacity
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    /// Builds a new empty [`Quarantine`] which keeps at most `capacity`-many notes.
+    #[inline]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            notes: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Returns the maximum number of notes kept in `self`.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Sets the maximum number of notes kept in `self` to `capacity`, dropping the oldest notes
+    /// if there are more than `capacity`-many of them.
+    #[inline]
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.prune();
+    }
+
+    /// Returns the number of notes in `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.notes.len()
+    }
+
+    /// Returns `true` if there are no notes in `self`.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty()
+    }
+
+    /// Returns an iterator over the notes in `self`, from the oldest to the latest.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &QuarantinedNote<C>> {
+        self.notes.iter()
+    }
+
+    /// Returns the number of notes in `self` which were quarantined for `reason`.
+    #[inline]
+    pub fn count(&self, reason: NoteOpenError) -> usize {
+        self.notes
+            .iter()
+            .filter(|quarantined| quarantined.reason == reason)
+            .count()
+    }
+
+    /// Quarantines `note` posted with `utxo`, which could not be opened for `reason`, returning
+    /// `false` if it was already in `self`.
+    #[inline]
+    pub fn insert(&mut self, utxo: Utxo<C>, note: Note<C>, reason: NoteOpenError) -> bool {
+        if self
+            .notes
+            .iter()
+            .any(|quarantined| quarantined.utxo.is_related(&utxo))
+        {
+            return false;
+        }
+        self.notes.push_back(QuarantinedNote { utxo, note, reason });
+        self.prune();
+        true
+    }
+
+    /// Removes all the notes from `self`, returning them from the oldest to the latest.
+    #[inline]
+    pub fn take(&mut self) -> VecDeque<QuarantinedNote<C>> {
+        core::mem::take(&mut self.notes)
+    }
+
+    /// Drops the oldest notes of `self` until it keeps at most `capacity`-many of them.
+    #[inline]
+    fn prune(&mut self) {
+        while self.notes.len() > self.capacity {
+            self.notes.pop_front();
+        }
+    }
+}
+
+impl<C> Default for Quarantine<C>
+where
+    C: Configuration,
+{
+    #[inline]
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+/// Quarantine Reprocessing Response
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ReprocessResponse {
+    /// Recovered Notes
+    ///
+    /// The [`ImportStatus`](super::ImportStatus) of every quarantined note which could be opened,
+    /// in quarantine order. These notes are imported like the ones given to
+    /// [`Signer::import_notes`](super::Signer::import_notes).
+    pub recovered: ImportResponse,
+
+    /// Number of Notes Left in the Quarantine
+    pub remaining: usize,
+}
@@ -0,0 +1,253 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Receipt Retention
+//!
+//! The signer forgets its own notes as soon as it synchronizes their nullifiers, but it keeps a
+//! [`PaymentReceipt`] with the opening of every note it pays to another address, so the state of
+//! an active signer grows with every payment it ever signed. A [`RetentionPolicy`] bounds this
+//! growth: the pruning pass streams through the receipts in signing order and, for the ones older
+//! than the maximum age of the policy, either keeps their [`ReceiptMetadata`] in the
+//! [`ReceiptArchive`] of the signer or drops them entirely.
+//!
+//! Pruned receipts lose their opening, so the payments they record can no longer be proven to
+//! their recipients. Pruning never touches the notes of the signer, so a full rescan from the seed
+//! still recovers all of its assets, and the [`Utxo`] kept in the metadata of a payment is enough
+//! to find it again on the ledger while rebuilding the transaction history.
+//!
+//! # Clocks
+//!
+//! The signer has no clock, so the caller gives the time of every pruning pass, in milliseconds
+//! since the Unix epoch. Receipts are dated by the first pruning pass which sees them, so callers
+//! should prune regularly, for instance after every synchronization.
+
+use crate::{
+    transfer::{Asset, PaymentReceipt, TransactionId, Utxo},
+    wallet::signer::Configuration,
+};
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Milliseconds in a Day
+pub const DAY: u64 = 24 * 60 * 60 * 1000;
+
+/// Retention Policy
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct RetentionPolicy {
+    /// Maximum Age in Milliseconds
+    ///
+    /// Time for which the full receipts are kept after they are first seen by a pruning pass.
+    pub max_age: u64,
+
+    /// Metadata Retention
+    ///
+    /// Keeps the [`ReceiptMetadata`] of the pruned receipts for the transaction history when
+    /// `true`, and drops them entirely otherwise.
+    pub keep_metadata: bool,
+}
+
+impl RetentionPolicy {
+    /// Builds a new [`RetentionPolicy`] which keeps the full receipts for `days` days.
+    #[inline]
+    pub const fn days(days: u64, keep_metadata: bool) -> Self {
+        Self {
+            max_age: days.saturating_mul(DAY),
+            keep_metadata,
+        }
+    }
+
+    /// Returns `true` if a receipt first seen at `date` has expired at time `now`.
+    #[inline]
+    pub const fn is_expired(&self, date: u64, now: u64) -> bool {
+        date.saturating_add(self.max_age) <= now
+    }
+}
+
+/// Receipt Metadata
+///
+/// Part of a [`PaymentReceipt`] which is kept for the transaction history after the receipt is
+/// pruned. It does not have the opening of the payment, so it cannot be used to prove it.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "TransactionId<C>: Deserialize<'de>, Asset<C>: Deserialize<'de>, Utxo<C>: Deserialize<'de>",
+            serialize = "TransactionId<C>: Serialize, Asset<C>: Serialize, Utxo<C>: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "TransactionId<C>: Clone, Asset<C>: Clone, Utxo<C>: Clone"),
+    Copy(bound = "TransactionId<C>: Copy, Asset<C>: Copy, Utxo<C>: Copy"),
+    Debug(bound = "TransactionId<C>: Debug, Asset<C>: Debug, Utxo<C>: Debug"),
+    Eq(bound = "TransactionId<C>: Eq, Asset<C>: Eq, Utxo<C>: Eq"),
+    Hash(bound = "TransactionId<C>: Hash, Asset<C>: Hash, Utxo<C>: Hash"),
+    PartialEq(bound = "TransactionId<C>: PartialEq, Asset<C>: PartialEq, Utxo<C>: PartialEq")
+)]
+pub struct ReceiptMetadata<C>
+where
+    C: Configuration,
+{
+    /// Transaction Id
+    pub transaction_id: TransactionId<C>,
+
+    /// Recipient Address Commitment
+    pub address_commitment: Utxo<C>,
+
+    /// Paid Asset
+    pub asset: Asset<C>,
+
+    /// Unspent Transaction Output
+    ///
+    /// UTXO registered on the ledger for the recipient, which identifies the payment on-chain.
+    pub utxo: Utxo<C>,
+}
+
+impl<C> From<PaymentReceipt<C>> for ReceiptMetadata<C>
+where
+    C: Configuration,
+{
+    #[inline]
+    fn from(receipt: PaymentReceipt<C>) -> Self {
+        Self {
+            transaction_id: receipt.transaction_id,
+            address_commitment: receipt.address_commitment,
+            asset: receipt.identified_asset.asset,
+            utxo: receipt.utxo,
+        }
+    }
+}
+
+/// Pruning Summary
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PruneSummary {
+    /// Number of receipts which were compacted into their [`ReceiptMetadata`]
+    pub compacted: usize,
+
+    /// Number of receipts which were dropped entirely
+    pub dropped: usize,
+}
+
+impl PruneSummary {
+    /// Returns the number of receipts which were pruned.
+    #[inline]
+    pub fn pruned(&self) -> usize {
+        self.compacted + self.dropped
+    }
+}
+
+/// Receipt Archive
+///
+/// Dates of the receipts of a signer and metadata of the receipts it already pruned.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "ReceiptMetadata<C>: Deserialize<'de>",
+            serialize = "ReceiptMetadata<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "ReceiptMetadata<C>: Clone"),
+    Debug(bound = "ReceiptMetadata<C>: Debug"),
+    Default(bound = ""),
+    Eq(bound = "ReceiptMetadata<C>: Eq"),
+    Hash(bound = "ReceiptMetadata<C>: Hash"),
+    PartialEq(bound = "ReceiptMetadata<C>: PartialEq")
+)]
+pub struct ReceiptArchive<C>
+where
+    C: Configuration,
+{
+    /// Receipt Dates
+    ///
+    /// Time of the first pruning pass which saw each one of the receipts which are still kept, in
+    /// signing order.
+    dates: Vec<u64>,
+
+    /// Receipt History
+    ///
+    /// Metadata of the pruned receipts, in signing order.
+    history: Vec<ReceiptMetadata<C>>,
+}
+
+impl<C> ReceiptArchive<C>
+where
+    C: Configuration,
+{
+    /// Returns the metadata of the receipts pruned from `self`, in signing order.
+    #[inline]
+    pub fn history(&self) -> &[ReceiptMetadata<C>] {
+        &self.history
+    }
+
+    /// Returns the dates of the receipts which are still kept, in signing order.
+    #[inline]
+    pub fn dates(&self) -> &[u64] {
+        &self.dates
+    }
+
+    /// Prunes the `receipts` which have expired at time `now` under `policy`, dating the ones
+    /// which were signed since the last pass with `now`.
+    ///
+    /// Receipts are dated in signing order, so the pass stops at the first receipt which is
+    /// still retained and leaves all the later ones untouched.
+    #[inline]
+    pub fn prune(
+        &mut self,
+        receipts: &mut Vec<PaymentReceipt<C>>,
+        policy: &RetentionPolicy,
+        now: u64,
+    ) -> PruneSummary {
+        self.dates.resize(receipts.len(), now);
+        let expired = self
+            .dates
+            .iter()
+            .take_while(|date| policy.is_expired(**date, now))
+            .count();
+        self.dates.drain(..expired);
+        let pruned = receipts.drain(..expired);
+        if policy.keep_metadata {
+            self.history.extend(pruned.map(Into::into));
+            PruneSummary {
+                compacted: expired,
+                dropped: 0,
+            }
+        } else {
+            PruneSummary {
+                compacted: 0,
+                dropped: expired,
+            }
+        }
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction Review
+//!
+//! Before a transaction is signed, the signer can plan it against its current assets and return
+//! a [`TransactionSummary`] describing what signing it would do: which notes are spent, who
+//! receives what, how the private balance changes and what the ledger learns about it. Wallets
+//! show the summary on their confirmation screens, so that users approve the transaction from the
+//! data of the signer instead of from the request they sent to it.
+
+use crate::transfer::{Address, Asset, Configuration, Identifier};
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Transaction Recipient
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Address<C>: Deserialize<'de>, C::AccountId: Deserialize<'de>",
+            serialize = "Address<C>: Serialize, C::AccountId: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Address<C>: Clone, C::AccountId: Clone"),
+    Copy(bound = "Address<C>: Copy, C::AccountId: Copy"),
+    Debug(bound = "Address<C>: Debug, C::AccountId: Debug"),
+    Eq(bound = "Address<C>: Eq, C::AccountId: Eq"),
+    Hash(bound = "Address<C>: Hash, C::AccountId: Hash"),
+    PartialEq(bound = "Address<C>: PartialEq, C::AccountId: PartialEq")
+)]
+pub enum Recipient<C>
+where
+    C: Configuration,
+{
+    /// Signer
+    ///
+    /// The asset goes to the private balance of the signer itself, either as a deposit or as the
+    /// change of a withdraw.
+    Signer,
+
+    /// Private Address
+    Address(Address<C>),
+
+    /// Public Account
+    PublicAccount(C::AccountId),
+}
+
+/// Balance Change
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BalanceChange<V> {
+    /// Balance Increase
+    Increase(V),
+
+    /// Balance Decrease
+    Decrease(V),
+}
+
+/// Privacy Note
+///
+/// Information about a transaction which becomes public once it is posted to the ledger.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum PrivacyNote {
+    /// Reveals the Asset Id
+    RevealsAssetId,
+
+    /// Reveals the Amount
+    RevealsAmount,
+
+    /// Reveals the Public Account
+    ///
+    /// The public account which the asset is taken from or sent to is public.
+    RevealsPublicAccount,
+
+    /// Links Notes
+    ///
+    /// The selected notes do not fit into a single transfer, so they are first merged by extra
+    /// transfers which show the ledger that they are spent together.
+    LinksNotes,
+}
+
+/// Transaction Summary
+///
+/// This `struct` is created by the [`review`](super::Connection::review) method on
+/// [`Connection`](super::Connection). See its documentation for more.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = r"
+                Identifier<C>: Deserialize<'de>,
+                Asset<C>: Deserialize<'de>,
+                Address<C>: Deserialize<'de>,
+                C::AccountId: Deserialize<'de>,
+                C::AssetId: Deserialize<'de>,
+                C::AssetValue: Deserialize<'de>
+            ",
+            serialize = r"
+                Identifier<C>: Serialize,
+                Asset<C>: Serialize,
+                Address<C>: Serialize,
+                C::AccountId: Serialize,
+                C::AssetId: Serialize,
+                C::AssetValue: Serialize
+            ",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = r"
+        Identifier<C>: Clone,
+        Asset<C>: Clone,
+        Address<C>: Clone,
+        C::AccountId: Clone,
+        C::AssetId: Clone,
+        C::AssetValue: Clone
+    "),
+    Debug(bound = r"
+        Identifier<C>: Debug,
+        Asset<C>: Debug,
+        Address<C>: Debug,
+        C::AccountId: Debug,
+        C::AssetId: Debug,
+        C::AssetValue: Debug
+    "),
+    Eq(bound = r"
+        Identifier<C>: Eq,
+        Asset<C>: Eq,
+        Address<C>: Eq,
+        C::AccountId: Eq,
+        C::AssetId: Eq,
+        C::AssetValue: Eq
+    "),
+    Hash(bound = r"
+        Identifier<C>: Hash,
+        Asset<C>: Hash,
+        Address<C>: Hash,
+        C::AccountId: Hash,
+        C::AssetId: Hash,
+        C::AssetValue: Hash
+    "),
+    PartialEq(bound = r"
+        Identifier<C>: PartialEq,
+        Asset<C>: PartialEq,
+        Address<C>: PartialEq,
+        C::AccountId: PartialEq,
+        C::AssetId: PartialEq,
+        C::AssetValue: PartialEq
+    ")
+)]
+pub struct TransactionSummary<C>
+where
+    C: Configuration,
+{
+    /// Spent Notes
+    ///
+    /// Notes of the signer consumed by the transaction.
+    pub inputs: Vec<(Identifier<C>, Asset<C>)>,
+
+    /// Recipients
+    ///
+    /// Assets created by the transaction, together with their recipients, including any non-zero
+    /// change sent back to the [`Signer`](Recipient::Signer). Zero-value notes which only pad the
+    /// transfers are left out.
+    pub recipients: Vec<(Recipient<C>, Asset<C>)>,
+
+    /// Post Count
+    ///
+    /// Number of transfer posts sent to the ledger. The protocol does not charge any fee inside
+    /// the posts, so the fees of the transaction are the ones the ledger charges for each post.
+    pub posts: usize,
+
+    /// Private Balance Changes
+    ///
+    /// Net change of the private balance of the signer for each asset id.
+    pub balance_changes: Vec<(C::AssetId, BalanceChange<C::AssetValue>)>,
+
+    /// Privacy Notes
+    pub privacy_notes: Vec<PrivacyNote>,
+}
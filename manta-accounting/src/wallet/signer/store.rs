@@ -0,0 +1,382 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Asset Map Persistence
+//!
+//! The [`AssetMap`] of a signer lives in memory. To keep it across restarts, the signer is
+//! configured with a [`JournaledAssetMap`], which records every change made to the map since it
+//! was last persisted in an [`AssetMapBatch`]. The batch is then written to an
+//! [`AssetMapStore`], which applies it as a single transaction, so that a crash in the middle of a
+//! write never leaves the store with only part of a signing or synchronization step.
+
+use crate::asset::{Asset, AssetList, AssetMap, BTreeAssetMap, Selection};
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    convert::Infallible,
+    fmt::Debug,
+    hash::Hash,
+    ops::{AddAssign, Sub},
+};
+use manta_util::{future::LocalBoxFutureResult, num::CheckedAdd};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Asset Map Batch
+///
+/// Changes made to an [`AssetMap`] which are applied to an [`AssetMapStore`] all at once.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "K: Deserialize<'de>, I: Deserialize<'de>, V: Deserialize<'de>",
+            serialize = "K: Serialize, I: Serialize, V: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "K: Clone, I: Clone, V: Clone"),
+    Debug(bound = "K: Debug, I: Debug, V: Debug"),
+    Default(bound = ""),
+    Eq(bound = "K: Eq, I: Eq, V: Eq"),
+    Hash(bound = "K: Hash, I: Hash, V: Hash"),
+    PartialEq(bound = "K: PartialEq, I: PartialEq, V: PartialEq")
+)]
+pub struct AssetMapBatch<K, I, V> {
+    /// Inserted Assets
+    pub insertions: Vec<(K, Asset<I, V>)>,
+
+    /// Removed Assets
+    pub removals: Vec<(K, Asset<I, V>)>,
+}
+
+impl<K, I, V> AssetMapBatch<K, I, V> {
+    /// Returns `true` if `self` has no changes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.insertions.is_empty() && self.removals.is_empty()
+    }
+
+    /// Removes all the changes from `self`.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.insertions.clear();
+        self.removals.clear();
+    }
+}
+
+impl<K, I, V> AssetMapBatch<K, I, V>
+where
+    K: PartialEq,
+    I: PartialEq,
+    V: PartialEq,
+{
+    /// Records the insertion of `asset` at the `key`, cancelling an earlier removal of the same
+    /// asset.
+    #[inline]
+    pub fn insert(&mut self, key: K, asset: Asset<I, V>) {
+        let entry = (key, asset);
+        match self.removals.iter().position(|removal| removal == &entry) {
+            Some(index) => {
+                self.removals.swap_remove(index);
+            }
+            _ => self.insertions.push(entry),
+        }
+    }
+
+    /// Records the removal of `asset` from the `key`, cancelling an earlier insertion of the same
+    /// asset.
+    #[inline]
+    pub fn remove(&mut self, key: K, asset: Asset<I, V>) {
+        let entry = (key, asset);
+        match self
+            .insertions
+            .iter()
+            .position(|insertion| insertion == &entry)
+        {
+            Some(index) => {
+                self.insertions.swap_remove(index);
+            }
+            _ => self.removals.push(entry),
+        }
+    }
+}
+
+/// Asset Map Store
+///
+/// Persistent storage for the entries of an [`AssetMap`].
+///
+/// # Implementation Note
+///
+/// The [`apply`](Self::apply) and [`clear`](Self::clear) methods must be transactional: when they
+/// fail, the store must be left exactly as it was before the call.
+pub trait AssetMapStore<K, I, V> {
+    /// Error Type
+    type Error;
+
+    /// Loads every entry from the store.
+    #[allow(clippy::type_complexity)] // NOTE: Clippy is too harsh here.
+    fn load(&mut self) -> LocalBoxFutureResult<'_, Vec<(K, Asset<I, V>)>, Self::Error>;
+
+    /// Applies all the changes in `batch` to the store at once.
+    fn apply<'s>(
+        &'s mut self,
+        batch: &'s AssetMapBatch<K, I, V>,
+    ) -> LocalBoxFutureResult<'s, (), Self::Error>;
+
+    /// Removes every entry from the store at once.
+    fn clear(&mut self) -> LocalBoxFutureResult<'_, (), Self::Error>;
+}
+
+impl<K, I, V> AssetMapStore<K, I, V> for BTreeAssetMap<K, I, V>
+where
+    K: Clone + Ord,
+    I: Clone + Ord,
+    V: AddAssign
+        + CheckedAdd<Output = V>
+        + Clone
+        + Default
+        + Ord
+        + Sub<Output = V>
+        + for<'v> AddAssign<&'v V>,
+    for<'v> &'v V: Sub<Output = V>,
+{
+    type Error = Infallible;
+
+    #[inline]
+    fn load(&mut self) -> LocalBoxFutureResult<'_, Vec<(K, Asset<I, V>)>, Self::Error> {
+        Box::pin(async move { Ok(self.entries()) })
+    }
+
+    #[inline]
+    fn apply<'s>(
+        &'s mut self,
+        batch: &'s AssetMapBatch<K, I, V>,
+    ) -> LocalBoxFutureResult<'s, (), Self::Error> {
+        Box::pin(async move {
+            self.remove_all(batch.removals.iter().cloned());
+            self.insert_all(batch.insertions.iter().cloned());
+            Ok(())
+        })
+    }
+
+    #[inline]
+    fn clear(&mut self) -> LocalBoxFutureResult<'_, (), Self::Error> {
+        Box::pin(async move {
+            BTreeAssetMap::clear(self);
+            Ok(())
+        })
+    }
+}
+
+/// Journaled Asset Map
+///
+/// [`AssetMap`] which records the changes made to it since it was last persisted to an
+/// [`AssetMapStore`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = r"
+                M: Deserialize<'de>,
+                M::Key: Deserialize<'de>,
+                I: Deserialize<'de>,
+                V: Deserialize<'de>
+            ",
+            serialize = "M: Serialize, M::Key: Serialize, I: Serialize, V: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "M: Clone, M::Key: Clone, I: Clone, V: Clone"),
+    Debug(bound = "M: Debug, M::Key: Debug, I: Debug, V: Debug"),
+    Default(bound = ""),
+    Eq(bound = "M: Eq, M::Key: Eq, I: Eq, V: Eq"),
+    Hash(bound = "M: Hash, M::Key: Hash, I: Hash, V: Hash"),
+    PartialEq(bound = "M: PartialEq, M::Key: PartialEq, I: PartialEq, V: PartialEq")
+)]
+pub struct JournaledAssetMap<M, I, V>
+where
+    M: AssetMap<I, V>,
+{
+    /// Asset Map
+    map: M,
+
+    /// Changes since the Last Persistence
+    batch: AssetMapBatch<M::Key, I, V>,
+}
+
+impl<M, I, V> JournaledAssetMap<M, I, V>
+where
+    M: AssetMap<I, V>,
+{
+    /// Builds a new [`JournaledAssetMap`] from the persisted `entries`.
+    #[inline]
+    pub fn from_entries<E>(entries: E) -> Self
+    where
+        E: IntoIterator<Item = (M::Key, Asset<I, V>)>,
+    {
+        let mut map = M::default();
+        map.insert_all(entries);
+        Self {
+            map,
+            batch: Default::default(),
+        }
+    }
+
+    /// Loads a [`JournaledAssetMap`] from the entries in `store`.
+    #[inline]
+    pub async fn load<S>(store: &mut S) -> Result<Self, S::Error>
+    where
+        S: AssetMapStore<M::Key, I, V>,
+    {
+        Ok(Self::from_entries(store.load().await?))
+    }
+
+    /// Returns the underlying asset map.
+    #[inline]
+    pub fn map(&self) -> &M {
+        &self.map
+    }
+
+    /// Returns the changes made to `self` since it was last persisted.
+    #[inline]
+    pub fn batch(&self) -> &AssetMapBatch<M::Key, I, V> {
+        &self.batch
+    }
+
+    /// Writes the changes made to `self` since it was last persisted to `store`. If the store
+    /// fails to apply them, they are kept in `self` and written by the next call.
+    #[inline]
+    pub async fn persist<S>(&mut self, store: &mut S) -> Result<(), S::Error>
+    where
+        S: AssetMapStore<M::Key, I, V>,
+    {
+        if !self.batch.is_empty() {
+            store.apply(&self.batch).await?;
+            self.batch.clear();
+        }
+        Ok(())
+    }
+
+    /// Replaces the entries of `store` with the ones of `self`, clearing the changes recorded
+    /// since the last persistence.
+    #[inline]
+    pub async fn overwrite<S>(&mut self, store: &mut S) -> Result<(), S::Error>
+    where
+        S: AssetMapStore<M::Key, I, V>,
+    {
+        let batch = AssetMapBatch {
+            insertions: self.map.entries(),
+            removals: Vec::new(),
+        };
+        store.clear().await?;
+        store.apply(&batch).await?;
+        self.batch.clear();
+        Ok(())
+    }
+}
+
+impl<M, I, V> AssetMap<I, V> for JournaledAssetMap<M, I, V>
+where
+    M: AssetMap<I, V>,
+    M::Key: Clone + PartialEq,
+    I: Clone + PartialEq,
+    V: Clone + PartialEq,
+{
+    type Key = M::Key;
+
+    #[inline]
+    fn assets(&self) -> AssetList<I, V> {
+        self.map.assets()
+    }
+
+    #[inline]
+    fn select(&self, asset: &Asset<I, V>) -> Selection<I, V, Self> {
+        let selection = self.map.select(asset);
+        Selection::new(selection.change, selection.values)
+    }
+
+    #[inline]
+    fn zeroes(&self, n: usize, id: &I) -> Vec<Self::Key> {
+        self.map.zeroes(n, id)
+    }
+
+    #[inline]
+    fn largest(&self, n: usize, id: &I) -> Vec<(Self::Key, V)> {
+        self.map.largest(n, id)
+    }
+
+    #[inline]
+    fn contains(&self, key: &Self::Key, asset: &Asset<I, V>) -> bool {
+        self.map.contains(key, asset)
+    }
+
+    #[inline]
+    fn entries(&self) -> Vec<(Self::Key, Asset<I, V>)> {
+        self.map.entries()
+    }
+
+    #[inline]
+    fn insert(&mut self, key: Self::Key, asset: Asset<I, V>) {
+        if !self.map.contains(&key, &asset) {
+            self.batch.insert(key.clone(), asset.clone());
+            self.map.insert(key, asset);
+        }
+    }
+
+    #[inline]
+    fn remove(&mut self, key: Self::Key, asset: Asset<I, V>) -> bool {
+        let removed = self.map.remove(key.clone(), asset.clone());
+        if removed {
+            self.batch.remove(key, asset);
+        }
+        removed
+    }
+
+    #[inline]
+    fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&Self::Key, &mut Vec<Asset<I, V>>) -> bool,
+    {
+        let batch = &mut self.batch;
+        self.map.retain(|key, assets| {
+            let before = assets.clone();
+            let keep = f(key, assets);
+            for asset in &before {
+                if !keep || !assets.contains(asset) {
+                    batch.remove(key.clone(), asset.clone());
+                }
+            }
+            if keep {
+                for asset in assets.iter() {
+                    if !before.contains(asset) {
+                        batch.insert(key.clone(), asset.clone());
+                    }
+                }
+            }
+            keep
+        });
+    }
+}
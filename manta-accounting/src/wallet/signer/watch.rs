@@ -0,0 +1,181 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Nullifier Watch-Lists
+//!
+//! A custodian which wants to be alerted whenever one of its notes is spent, for instance to
+//! detect the theft of its keys, can hand a [`NullifierWatchList`] to an external monitoring
+//! service. The watch-list is made of the salted hashes of the nullifiers of the notes, so the
+//! service can [`detect`](NullifierWatchList::detect) them in the nullifiers posted to the ledger
+//! without learning the nullifiers before they are revealed. The service reports the detected
+//! [`Digest`]s back to the custodian, which maps them to the spent notes locally with
+//! [`Signer::watched_note`](super::Signer::watched_note).
+//!
+//! Every watch-list is hashed with a fresh [`WatchListSalt`], so the watch-lists of different
+//! custodians, or of the same custodian at different times, cannot be linked with each other.
+
+use crate::{
+    transfer::{self, Nullifier},
+    wallet::signer::Configuration,
+};
+use alloc::vec::Vec;
+use core::{fmt::Debug, hash::Hash};
+use manta_crypto::rand::{RngCore, Sample};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Watch-List Salt
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct WatchListSalt(pub [u8; 32]);
+
+impl Sample for WatchListSalt {
+    #[inline]
+    fn sample<R>(_: (), rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        let mut salt = [0; 32];
+        rng.fill_bytes(&mut salt);
+        Self(salt)
+    }
+}
+
+/// Watch-List Hash Function
+pub trait WatchListHash<C>
+where
+    C: transfer::Configuration + ?Sized,
+{
+    /// Digest Type
+    type Digest: Clone + Ord;
+
+    /// Hashes `nullifier` with `salt`.
+    ///
+    /// # Implementation Note
+    ///
+    /// The digest must only depend on the part of `nullifier` which is revealed on the ledger for
+    /// every spend of the same note, so that the digests computed by the signer match the ones
+    /// computed by the monitoring service from the posted nullifiers.
+    fn hash(salt: &WatchListSalt, nullifier: &Nullifier<C>) -> Self::Digest;
+}
+
+/// Watch-List Digest Type
+pub type Digest<C> = <<C as Configuration>::WatchListHash as WatchListHash<C>>::Digest;
+
+/// Nullifier Watch-List
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "Digest<C>: Deserialize<'de>",
+            serialize = "Digest<C>: Serialize"
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Debug(bound = "Digest<C>: Debug"),
+    Eq(bound = ""),
+    Hash(bound = "Digest<C>: Hash"),
+    PartialEq(bound = "")
+)]
+pub struct NullifierWatchList<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Salt
+    salt: WatchListSalt,
+
+    /// Nullifier Digests
+    ///
+    /// The digests are sorted, so that their order does not reveal the order of the notes.
+    digests: Vec<Digest<C>>,
+}
+
+impl<C> NullifierWatchList<C>
+where
+    C: Configuration + ?Sized,
+{
+    /// Builds a new [`NullifierWatchList`] by hashing `nullifiers` with `salt`.
+    #[inline]
+    pub fn new<'n, I>(salt: WatchListSalt, nullifiers: I) -> Self
+    where
+        Nullifier<C>: 'n,
+        I: IntoIterator<Item = &'n Nullifier<C>>,
+    {
+        let mut digests = nullifiers
+            .into_iter()
+            .map(|nullifier| C::WatchListHash::hash(&salt, nullifier))
+            .collect::<Vec<_>>();
+        digests.sort();
+        digests.dedup();
+        Self { salt, digests }
+    }
+
+    /// Returns the salt which the nullifiers of `self` are hashed with.
+    #[inline]
+    pub fn salt(&self) -> &WatchListSalt {
+        &self.salt
+    }
+
+    /// Returns the nullifier digests of `self`, in increasing order.
+    #[inline]
+    pub fn digests(&self) -> &[Digest<C>] {
+        &self.digests
+    }
+
+    /// Returns the number of nullifiers watched by `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.digests.len()
+    }
+
+    /// Returns `true` if `self` does not watch any nullifier.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.digests.is_empty()
+    }
+
+    /// Returns `true` if `digest` is watched by `self`.
+    #[inline]
+    pub fn contains(&self, digest: &Digest<C>) -> bool {
+        self.digests.binary_search(digest).is_ok()
+    }
+
+    /// Returns the digests of the `nullifiers` posted to the ledger which are watched by `self`.
+    /// This is the check run by the monitoring service for every new batch of nullifiers.
+    #[inline]
+    pub fn detect<'n, I>(&self, nullifiers: I) -> Vec<Digest<C>>
+    where
+        Nullifier<C>: 'n,
+        I: IntoIterator<Item = &'n Nullifier<C>>,
+    {
+        nullifiers
+            .into_iter()
+            .map(|nullifier| C::WatchListHash::hash(&self.salt, nullifier))
+            .filter(|digest| self.contains(digest))
+            .collect()
+    }
+}
@@ -30,7 +30,11 @@ use manta_pay::{
         utxo::{Config, IncomingBaseAES, InnerHashDomainTag},
         ConstraintField,
     },
-    crypto::poseidon::hash::Hasher,
+    crypto::poseidon::{
+        hash::Hasher,
+        matrix::{MatrixOperations, SquareMatrix},
+        mds::{factor_to_sparse_matrixes, MdsMatrices},
+    },
 };
 
 #[inline]
@@ -74,5 +78,38 @@ fn aes_decryption(c: &mut Criterion) {
     });
 }
 
-criterion_group!(crypto, poseidon_hash, aes_decryption);
+#[inline]
+fn mds_matrix_operations(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bench");
+    let m = black_box(MdsMatrices::<Fp<ConstraintField>>::generate_mds(5));
+    let mut out = SquareMatrix::identity(5);
+    group.bench_function("MDS Matrix Multiplication", |b| {
+        b.iter(|| {
+            let _ = black_box(m.matmul(&m));
+        })
+    });
+    group.bench_function("MDS Matrix Multiplication In Place", |b| {
+        b.iter(|| {
+            let _ = black_box(m.matmul_into(&m, &mut out));
+        })
+    });
+    group.bench_function("MDS Matrix Transpose In Place", |b| {
+        b.iter(|| {
+            out.transpose_in_place();
+            black_box(&out);
+        })
+    });
+    group.bench_function("MDS Matrix Inverse", |b| {
+        b.iter(|| {
+            let _ = black_box(m.inverse());
+        })
+    });
+    group.bench_function("MDS Sparse Matrix Factorization", |b| {
+        b.iter(|| {
+            let _ = black_box(factor_to_sparse_matrixes(m.clone(), 56));
+        })
+    });
+}
+
+criterion_group!(crypto, poseidon_hash, aes_decryption, mds_matrix_operations);
 criterion_main!(crypto);
@@ -16,6 +16,11 @@
 
 //! Transfer Benchmarking Suite
 
+use instant::Instant;
+use manta_accounting::transfer::{
+    batch::JoinProfile,
+    canonical::{PrivateTransferShape, Shape},
+};
 use manta_crypto::rand::{OsRng, Rand};
 use manta_pay::{
     config::{self, MultiProvingContext, MultiVerifyingContext, Parameters, UtxoAccumulatorModel},
@@ -120,6 +125,19 @@ pub fn prove_to_public(context: &Context) -> TransferPost {
     )
 }
 
+/// Measures the average proving time of a private transfer over `samples` runs, which is the
+/// circuit used to join notes, returning the [`JoinProfile`] for a [`BatchPlanner`].
+///
+/// [`BatchPlanner`]: manta_accounting::transfer::batch::BatchPlanner
+pub fn private_transfer_join_profile(context: &Context, samples: u32) -> JoinProfile {
+    let samples = samples.max(1);
+    let start = Instant::now();
+    for _ in 0..samples {
+        prove_private_transfer(context);
+    }
+    JoinProfile::new(PrivateTransferShape::SENDERS, start.elapsed() / samples)
+}
+
 /// Verifies a to_private [`TransferPost`] given the [`Context`].
 #[wasm_bindgen]
 pub fn verify_to_private(context: &Context, transferpost: &TransferPost) {
@@ -279,6 +279,19 @@ pub trait FromItemsAndWitnesses: Accumulator {
         witnesses: Vec<Self::Witness>,
     ) -> Self;
 
+    /// Builds a new [`Self`] from the `frontier` of every subaccumulator and the `items` which
+    /// should stay provable, together with their `witnesses`.
+    ///
+    /// The `frontier` has one entry per subaccumulator, which is either `None` if the
+    /// subaccumulator is empty, or its last item and the witness of that item. The resulting
+    /// accumulator does not store any other item, so its size only depends on the number of
+    /// `items` and not on the number of items inserted into the accumulator so far.
+    fn from_frontier_and_witnesses(
+        model: &Self::Model,
+        frontier: Vec<Option<(Self::Item, Self::Witness)>>,
+        items: Vec<(Self::Item, Self::Witness)>,
+    ) -> Self;
+
     /// Groups `items` by the subaccumulator they belong to.
     // TODO: move this to the model.
     fn sort_items(items: Vec<Self::Item>) -> Vec<Vec<Self::Item>>;
@@ -42,7 +42,11 @@ use crate::{
 };
 use alloc::vec::Vec;
 use core::{borrow::Borrow, marker::PhantomData};
-use manta_util::{codec, AsBytes};
+use manta_util::{
+    codec,
+    zeroize::{zeroize_with_default, Zeroize},
+    AsBytes,
+};
 
 #[cfg(feature = "serde")]
 use manta_util::serde::{Deserialize, Serialize, Serializer};
@@ -259,6 +263,16 @@ pub struct Group<C>(
 where
     C: ProjectiveCurve;
 
+impl<C> Zeroize for Group<C>
+where
+    C: ProjectiveCurve,
+{
+    #[inline]
+    fn zeroize(&mut self) {
+        zeroize_with_default(self)
+    }
+}
+
 impl<C> ToConstraintField<ConstraintField<C>> for Group<C>
 where
     C: ProjectiveCurve,
@@ -19,12 +19,13 @@
 use crate::{
     algebra,
     arkworks::{
-        ff::{Field, FpParameters, PrimeField, ToConstraintField},
+        ff::{BigInteger, Field, FpParameters, PrimeField, ToConstraintField},
         serialize::{ArkReader, ArkWriter, SerializationError},
     },
     constraint::{Input, ProofSystem},
     eclair::{
         self,
+        bits::{Bits, Pack},
         bool::{Bool, ConditionalSelect},
     },
     rand::{RngCore, Sample},
@@ -34,6 +35,7 @@ use core::iter;
 use manta_util::{
     byte_count,
     codec::{Decode, DecodeError, Encode, Read, Write},
+    zeroize::{zeroize_with_default, Zeroize},
     SizeLimit,
 };
 
@@ -74,6 +76,16 @@ where
     }
 }
 
+impl<F> Zeroize for Fp<F>
+where
+    F: Field,
+{
+    #[inline]
+    fn zeroize(&mut self) {
+        zeroize_with_default(self)
+    }
+}
+
 impl<F> ToConstraintField<F> for Fp<F>
 where
     F: PrimeField,
@@ -191,6 +203,31 @@ where
     }
 }
 
+impl<F, const N: usize> Pack<Fp<F>, N> for ()
+where
+    F: PrimeField,
+{
+    #[inline]
+    fn pack(&mut self, bits: &Bits<N>) -> Fp<F> {
+        assert!(
+            N < F::Params::MODULUS_BITS as usize,
+            "N must be strictly less than modulus bits of `F`."
+        );
+        Fp(F::from_repr(F::BigInt::from_bits_le(&bits.0))
+            .expect("N is less than modulus bits of `F`."))
+    }
+
+    #[inline]
+    fn unpack(&mut self, value: &Fp<F>) -> Bits<N> {
+        let bits = value.0.into_repr().to_bits_le();
+        assert!(
+            bits.iter().skip(N).all(|bit| !bit),
+            "The value does not fit into the bit vector."
+        );
+        Bits(core::array::from_fn(|i| bits[i]))
+    }
+}
+
 impl<F> Sample for Fp<F>
 where
     F: Field,
@@ -33,16 +33,17 @@ use crate::{
             },
         },
     },
-    constraint::measure::{Count, Measure},
+    constraint::measure::{Count, Measure, Profile, Profiler},
     eclair::{
         self,
         alloc::{
             mode::{self, Public, Secret},
-            Constant, Variable,
+            Allocate, Allocator, Constant, Variable,
         },
+        bits::{Bits, Pack},
         bool::{Assert, ConditionalSelect, ConditionalSwap},
         num::{AssertWithinBitRange, Zero},
-        ops::{Add, BitAnd, BitOr, Rem},
+        ops::{Add, BitAnd, BitOr, BitXor, Not, Rem},
         Has, NonNative,
     },
 };
@@ -91,7 +92,7 @@ where
 /// Arkworks Rank-1 Constraint System
 #[derive(derivative::Derivative)]
 #[derivative(Clone, Debug)]
-pub struct R1CS<F>(ConstraintSystemRef<F>)
+pub struct R1CS<F>(ConstraintSystemRef<F>, Option<Profiler>)
 where
     F: PrimeField;
 
@@ -103,7 +104,23 @@ where
     /// optimization goal or synthesis mode.
     #[inline]
     pub fn new_unchecked(constraint_system: ConstraintSystemRef<F>) -> Self {
-        Self(constraint_system)
+        Self(constraint_system, None)
+    }
+
+    /// Turns on the instrumented mode of `self`, which records a [`Region`] for every
+    /// [`Profile`] region the circuit enters.
+    ///
+    /// [`Region`]: crate::constraint::measure::Region
+    #[inline]
+    pub fn instrumented(mut self) -> Self {
+        self.1 = Some(Profiler::new());
+        self
+    }
+
+    /// Returns the region profiler of `self` if it is in the instrumented mode.
+    #[inline]
+    pub fn profiler(&self) -> Option<&Profiler> {
+        self.1.as_ref()
     }
 
     /// Constructs a new constraint system which is ready for unknown variables.
@@ -125,6 +142,24 @@ where
         Self::new_unchecked(constraint_system)
     }
 
+    /// Constructs a new constraint system which is ready for known variables but only records
+    /// their assignment, skipping the constraint matrices, for proof systems which already know
+    /// the structure of the circuit.
+    ///
+    /// # Warning
+    ///
+    /// Constraints are not recorded in this mode, so [`is_satisfied`](Self::is_satisfied) does
+    /// not check anything.
+    #[inline]
+    pub fn for_witnesses() -> Self {
+        let constraint_system = ConstraintSystem::new_ref();
+        constraint_system.set_optimization_goal(OptimizationGoal::Constraints);
+        constraint_system.set_mode(SynthesisMode::Prove {
+            construct_matrices: false,
+        });
+        Self::new_unchecked(constraint_system)
+    }
+
     /// Check if all constraints are satisfied.
     #[inline]
     pub fn is_satisfied(&self) -> bool {
@@ -196,6 +231,27 @@ where
     }
 }
 
+impl<F> Profile for R1CS<F>
+where
+    F: PrimeField,
+{
+    #[inline]
+    fn enter(&mut self, label: &'static str) {
+        let before = self.measure();
+        if let Some(profiler) = self.1.as_mut() {
+            profiler.enter(label, before);
+        }
+    }
+
+    #[inline]
+    fn exit(&mut self) {
+        let after = self.measure();
+        if let Some(profiler) = self.1.as_mut() {
+            profiler.exit(after);
+        }
+    }
+}
+
 impl<F> ConstraintSynthesizer<F> for R1CS<F>
 where
     F: PrimeField,
@@ -293,6 +349,48 @@ where
     }
 }
 
+impl<F> BitXor<Self, R1CS<F>> for Boolean<F>
+where
+    F: PrimeField,
+{
+    type Output = Self;
+
+    #[inline]
+    fn bitxor(self, rhs: Self, compiler: &mut R1CS<F>) -> Self::Output {
+        let _ = compiler;
+        self.xor(&rhs).expect("Bitwise XOR is not allowed to fail.")
+    }
+}
+
+impl<F> Not<R1CS<F>> for Boolean<F>
+where
+    F: PrimeField,
+{
+    type Output = Self;
+
+    #[inline]
+    fn not(self, compiler: &mut R1CS<F>) -> Self::Output {
+        let _ = compiler;
+        Boolean::not(&self)
+    }
+}
+
+impl<F> ConditionalSelect<R1CS<F>> for Boolean<F>
+where
+    F: PrimeField,
+{
+    #[inline]
+    fn select(
+        bit: &Boolean<F>,
+        true_value: &Self,
+        false_value: &Self,
+        compiler: &mut R1CS<F>,
+    ) -> Self {
+        let _ = compiler;
+        conditionally_select(bit, true_value, false_value)
+    }
+}
+
 impl<F> eclair::cmp::PartialEq<Self, R1CS<F>> for Boolean<F>
 where
     F: PrimeField,
@@ -325,6 +423,35 @@ where
     }
 }
 
+impl<F, const N: usize> Pack<FpVar<F>, N> for R1CS<F>
+where
+    F: PrimeField,
+{
+    #[inline]
+    fn pack(&mut self, bits: &Bits<N, Self>) -> FpVar<F> {
+        assert!(
+            N < F::Params::MODULUS_BITS as usize,
+            "N must be strictly less than modulus bits of `F`."
+        );
+        Boolean::le_bits_to_fp_var(&bits.0).expect("Bit packing is not allowed to fail.")
+    }
+
+    #[inline]
+    fn unpack(&mut self, value: &FpVar<F>) -> Bits<N, Self> {
+        let bits = match value.value() {
+            Ok(value) => {
+                let bits = value.into_repr().to_bits_le();
+                Bits(core::array::from_fn(|i| bits[i])).as_known::<Secret, _>(self)
+            }
+            _ => self.allocate_unknown::<Secret, _>(),
+        };
+        self.pack(&bits)
+            .enforce_equal(value)
+            .expect("Enforcing equality is not allowed to fail.");
+        bits
+    }
+}
+
 impl<F> Constant<R1CS<F>> for FpVar<F>
 where
     F: PrimeField,
@@ -429,6 +556,22 @@ where
             conditionally_select(bit, lhs, rhs),
         )
     }
+
+    /// Swaps `lhs` and `rhs` whenever `bit == true` using a single constraint.
+    ///
+    /// Instead of selecting each output separately, the difference `bit * (rhs - lhs)` is computed
+    /// once and moved from one side to the other, which only costs linear combinations.
+    #[inline]
+    fn swap_compact(
+        bit: &Boolean<F>,
+        lhs: &Self,
+        rhs: &Self,
+        compiler: &mut R1CS<F>,
+    ) -> (Self, Self) {
+        let _ = compiler;
+        let difference = FpVar::from(bit.clone()) * (rhs - lhs);
+        (lhs + &difference, rhs - difference)
+    }
 }
 
 impl<F> Add<Self, R1CS<F>> for FpVar<F>
@@ -556,7 +699,7 @@ mod tests {
     use super::*;
     use crate::{
         arkworks::{bn254::Fr, ff::BigInteger},
-        eclair::alloc::Allocate,
+        eclair::{alloc::Allocate, bool::AssertEq},
         rand::{OsRng, Rand, RngCore},
     };
     use alloc::vec::Vec;
@@ -634,4 +777,80 @@ mod tests {
         test_assert_within_range::<_, Fr, 64, 32>(&mut rng);
         test_assert_within_range::<_, Fr, 128, 32>(&mut rng);
     }
+
+    /// Tests that the bit vector operations in [`R1CS`] match the native ones.
+    #[test]
+    fn bits_match_native() {
+        let mut rng = OsRng;
+        let mut cs = R1CS::<Fr>::for_proofs();
+        let (lhs, rhs) = (rng.gen::<_, u64>(), rng.gen::<_, u64>());
+        let native = |value: &u64| Bits::<64>::unpack(value, &mut ());
+        let field = |value: &u64| Fp(Fr::from(*value));
+        let lhs_var = field(&lhs).as_known::<Secret, FpVar<_>>(&mut cs);
+        let rhs_var = field(&rhs).as_known::<Secret, FpVar<_>>(&mut cs);
+        let lhs_bits = Bits::<64, _>::unpack(&lhs_var, &mut cs);
+        let rhs_bits = Bits::<64, _>::unpack(&rhs_var, &mut cs);
+        for (bits, expected) in [
+            (lhs_bits.not(&mut cs), !lhs),
+            (lhs_bits.and(&rhs_bits, &mut cs), lhs & rhs),
+            (lhs_bits.or(&rhs_bits, &mut cs), lhs | rhs),
+            (lhs_bits.xor(&rhs_bits, &mut cs), lhs ^ rhs),
+        ] {
+            assert_eq!(
+                bits.pack::<FpVar<_>>(&mut cs).value().unwrap(),
+                native(&expected).pack::<Fp<Fr>>(&mut ()).0,
+                "Packed bits should match the native result."
+            );
+        }
+        let bit = true.as_known::<Secret, Boolean<_>>(&mut cs);
+        let selected = Bits::select(&bit, &rhs_bits, &lhs_bits, &mut cs);
+        cs.assert_eq(&selected, &rhs_bits);
+        assert!(
+            cs.is_satisfied(),
+            "Bit vector constraints should be satisfied."
+        );
+        let large = Fp(Fr::from(u64::MAX) + Fr::from(1u8)).as_known::<Secret, FpVar<_>>(&mut cs);
+        Bits::<64, _>::unpack(&large, &mut cs);
+        assert!(
+            !cs.is_satisfied(),
+            "Unpacking should fail for values out of range."
+        );
+    }
+
+    /// Tests that [`ConditionalSwap`] for [`FpVar`] matches the native swap, and that the compact
+    /// swap only costs one constraint, half of the default swap which selects each output
+    /// separately.
+    #[test]
+    fn conditional_swap_constraints() {
+        let mut rng = OsRng;
+        for bit in [false, true] {
+            let mut cs = R1CS::<Fr>::for_proofs();
+            let (lhs, rhs) = (rng.gen::<_, Fp<Fr>>(), rng.gen::<_, Fp<Fr>>());
+            let lhs_var = lhs.as_known::<Secret, FpVar<_>>(&mut cs);
+            let rhs_var = rhs.as_known::<Secret, FpVar<_>>(&mut cs);
+            let bit_var = bit.as_known::<Secret, Boolean<_>>(&mut cs);
+            let (expected_first, expected_second) = if bit { (rhs, lhs) } else { (lhs, rhs) };
+            for (swap, constraints) in [
+                (
+                    <FpVar<_> as ConditionalSwap<_>>::swap
+                        as fn(&_, &_, &_, &mut R1CS<Fr>) -> (FpVar<_>, FpVar<_>),
+                    2,
+                ),
+                (ConditionalSwap::swap_compact, 1),
+            ] {
+                let before = cs.constraint_count();
+                let (first, second) = swap(&bit_var, &lhs_var, &rhs_var, &mut cs);
+                assert_eq!(cs.constraint_count() - before, constraints);
+                assert_eq!(
+                    first.value().expect("Variable has a known value."),
+                    expected_first.0
+                );
+                assert_eq!(
+                    second.value().expect("Variable has a known value."),
+                    expected_second.0
+                );
+            }
+            assert!(cs.is_satisfied(), "Swap constraints should be satisfied.");
+        }
+    }
 }
@@ -15,6 +15,12 @@
 // along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Groth16 Proof System
+//!
+//! Proofs are only built when the `groth16-prover` feature is enabled. Without it, this module is
+//! a verification-only backend: the proving key types and the multi-scalar multiplications of the
+//! prover are never compiled, [`ProvingContext`] has no values, and [`Groth16`] can only verify
+//! proofs against a [`VerifyingContext`] which was built elsewhere. Ledger nodes, which never build
+//! proofs, can use this backend to keep the proving machinery out of their binaries.
 
 use crate::{
     arkworks::{
@@ -27,25 +33,51 @@ use crate::{
         },
     },
     constraint::{Input, ProofSystem},
-    rand::{CryptoRng, RngCore, SizedRng},
+    rand::{CryptoRng, RngCore},
 };
 use alloc::vec::Vec;
-use ark_groth16::{Groth16 as ArkGroth16, PreparedVerifyingKey, ProvingKey, VerifyingKey};
+use ark_groth16::{Groth16 as ArkGroth16, PreparedVerifyingKey, VerifyingKey};
 use ark_snark::SNARK;
 use core::{
+    fmt::{self, Debug, Formatter},
     hash::{Hash, Hasher},
     marker::PhantomData,
 };
-use manta_util::codec::{self, DecodeError};
+use manta_util::{
+    codec::{self, DecodeError},
+    fmt::HexPrefix,
+};
+
+#[cfg(feature = "groth16-prover")]
+use {
+    crate::{
+        arkworks::{
+            ec::{AffineCurve, ProjectiveCurve},
+            ff::{PrimeField, UniformRand},
+            msm,
+            relations::r1cs::{ConstraintMatrices, ConstraintSystemRef},
+        },
+        rand::SizedRng,
+    },
+    ark_groth16::ProvingKey,
+    ark_poly::{EvaluationDomain, GeneralEvaluationDomain},
+    core::{borrow::Borrow, mem},
+};
+
+#[cfg(not(feature = "groth16-prover"))]
+use core::convert::Infallible;
 
 #[cfg(feature = "scale")]
 use crate::crypto::ecc::arkworks::Group;
 
 #[cfg(feature = "serde")]
-use {
-    crate::arkworks::serialize::{canonical_deserialize, canonical_serialize},
-    manta_util::serde::{Deserialize, Serialize, Serializer},
-};
+use manta_util::serde::{Deserialize, Serialize, Serializer};
+
+#[cfg(all(feature = "serde", feature = "groth16-prover"))]
+use crate::arkworks::serialize::{canonical_deserialize, canonical_serialize};
+
+#[cfg(all(feature = "serde", not(feature = "groth16-prover")))]
+use manta_util::serde::{de, Deserializer};
 
 /// Proof System Error
 ///
@@ -59,6 +91,79 @@ use {
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 pub struct Error;
 
+/// Group Element Compression
+///
+/// Compressed group elements only store their `x`-coordinate and a sign bit, so they take roughly
+/// half the space of uncompressed ones, but every element has to be decompressed with a square
+/// root when it is decoded. Decoding with [`deserialize_detected`] accepts both encodings.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Compression {
+    /// Compressed Group Elements
+    #[default]
+    Compressed,
+
+    /// Uncompressed Group Elements
+    Uncompressed,
+}
+
+impl Compression {
+    /// Serializes `value` into bytes using `self` as the group element encoding.
+    #[inline]
+    pub fn serialize<T>(self, value: &T) -> Vec<u8>
+    where
+        T: CanonicalSerialize,
+    {
+        let mut buffer = Vec::new();
+        match self {
+            Self::Compressed => value.serialize(&mut buffer),
+            Self::Uncompressed => value.serialize_uncompressed(&mut buffer),
+        }
+        .expect("Serialization is not allowed to fail.");
+        buffer
+    }
+
+    /// Deserializes a value from `bytes` using `self` as the group element encoding, returning an
+    /// error if `bytes` is not consumed completely.
+    #[inline]
+    pub fn deserialize<T>(self, mut bytes: &[u8]) -> Result<T, SerializationError>
+    where
+        T: CanonicalDeserialize,
+    {
+        let value = match self {
+            Self::Compressed => T::deserialize(&mut bytes)?,
+            Self::Uncompressed => T::deserialize_uncompressed(&mut bytes)?,
+        };
+        if bytes.is_empty() {
+            Ok(value)
+        } else {
+            Err(SerializationError::InvalidData)
+        }
+    }
+}
+
+/// Deserializes a value from `bytes`, detecting whether its group elements were compressed.
+///
+/// The compressed and uncompressed encodings of a value have different lengths, so at most one of
+/// them consumes `bytes` completely.
+#[inline]
+pub fn deserialize_detected<T>(bytes: &[u8]) -> Result<(T, Compression), SerializationError>
+where
+    T: CanonicalDeserialize,
+{
+    match Compression::Compressed.deserialize(bytes) {
+        Ok(value) => Ok((value, Compression::Compressed)),
+        Err(err) => match Compression::Uncompressed.deserialize(bytes) {
+            Ok(value) => Ok((value, Compression::Uncompressed)),
+            _ => Err(err),
+        },
+    }
+}
+
 /// Groth16 Proof
 #[cfg_attr(
     feature = "serde",
@@ -71,7 +176,7 @@ pub struct Error;
     )
 )]
 #[derive(derivative::Derivative)]
-#[derivative(Clone, Debug, Default, Eq, PartialEq)]
+#[derivative(Clone, Default(bound = ""), Eq, PartialEq)]
 pub struct Proof<E>(
     /// Groth16 Proof
     #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_proof::<E, _>"))]
@@ -80,6 +185,36 @@ pub struct Proof<E>(
 where
     E: PairingEngine;
 
+impl<E> Proof<E>
+where
+    E: PairingEngine,
+{
+    /// Converts `self` into bytes using `compression` for its group elements.
+    #[inline]
+    pub fn to_bytes(&self, compression: Compression) -> Vec<u8> {
+        compression.serialize(&self.0)
+    }
+
+    /// Builds a [`Proof`] from `bytes`, accepting both compressed and uncompressed group
+    /// elements.
+    #[inline]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SerializationError> {
+        deserialize_detected(bytes).map(|(proof, _)| Self(proof))
+    }
+}
+
+impl<E> Debug for Proof<E>
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("Proof")
+            .field(&HexPrefix(self.to_bytes(Compression::Compressed)))
+            .finish()
+    }
+}
+
 impl<E> Hash for Proof<E>
 where
     E: PairingEngine,
@@ -156,6 +291,48 @@ where
     }
 }
 
+/// Proof [`Decode`](codec::Decode) Error
+#[derive(Debug)]
+pub enum ProofDecodeError {
+    /// Missing Length Prefix
+    MissingLength,
+
+    /// Length Prefix Exceeds the Length of an Uncompressed Proof
+    ExceededLength(u64),
+
+    /// Missing Proof Bytes
+    MissingBytes,
+
+    /// Serialization Error
+    Serialization(SerializationError),
+}
+
+impl<E> codec::Decode for Proof<E>
+where
+    E: PairingEngine,
+{
+    type Error = ProofDecodeError;
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: codec::Read,
+    {
+        let len = <u64 as codec::Decode>::decode(&mut reader)
+            .map_err(|err| err.map_decode(|_| ProofDecodeError::MissingLength))?;
+        if len > ark_groth16::Proof::<E>::default().uncompressed_size() as u64 {
+            return Err(DecodeError::Decode(ProofDecodeError::ExceededLength(len)));
+        }
+        let mut bytes = alloc::vec![0; len as usize];
+        reader.read_exact(&mut bytes).map_err(|err| match err {
+            codec::ReadExactError::Read(err) => DecodeError::Read(err),
+            _ => DecodeError::Decode(ProofDecodeError::MissingBytes),
+        })?;
+        Self::from_bytes(&bytes)
+            .map_err(|err| DecodeError::Decode(ProofDecodeError::Serialization(err)))
+    }
+}
+
 impl<E> codec::Encode for Proof<E>
 where
     E: PairingEngine,
@@ -177,7 +354,7 @@ where
 
     #[inline]
     fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
-        CanonicalDeserialize::deserialize(&mut bytes.as_slice()).map(Self)
+        Self::from_bytes(&bytes)
     }
 }
 
@@ -205,13 +382,183 @@ where
     serializer.serialize_bytes(&proof_as_bytes::<E>(proof))
 }
 
+/// Minimum Chunk Size of the Low-Memory Prover
+///
+/// Below this size, the multi-scalar multiplications of the prover spend more time adding up the
+/// results of their chunks than they save in memory.
+#[cfg(feature = "groth16-prover")]
+pub const MIN_CHUNK_SIZE: usize = 1 << 10;
+
+/// Circuit Size
+///
+/// Sizes of a constraint system which determine the memory needed to build a proof for it.
+#[cfg(feature = "groth16-prover")]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct CircuitSize {
+    /// Number of Constraints
+    pub constraints: usize,
+
+    /// Number of Instance Variables
+    pub instance_variables: usize,
+
+    /// Number of Witness Variables
+    pub witness_variables: usize,
+}
+
+#[cfg(feature = "groth16-prover")]
+impl CircuitSize {
+    /// Returns the size of the constraint system of `compiler`.
+    #[inline]
+    pub fn from_compiler<F>(compiler: &R1CS<F>) -> Self
+    where
+        F: PrimeField,
+    {
+        let cs = compiler.as_ref();
+        Self {
+            constraints: cs.num_constraints(),
+            instance_variables: cs.num_instance_variables(),
+            witness_variables: cs.num_witness_variables(),
+        }
+    }
+
+    /// Returns the number of variables of `self`.
+    #[inline]
+    pub fn variables(&self) -> usize {
+        self.instance_variables + self.witness_variables
+    }
+
+    /// Returns the size of the evaluation domain of the quotient polynomial of `self`.
+    #[inline]
+    pub fn domain_size(&self) -> usize {
+        (self.constraints + self.instance_variables).next_power_of_two()
+    }
+
+    /// Returns an estimate of the number of bytes allocated by the prover while building a proof
+    /// for `self` with `strategy`, on top of the constraint system and the proving key.
+    ///
+    /// The estimate covers the peak of the witness polynomial evaluation and of the multi-scalar
+    /// multiplications, which run one after the other. Multi-threaded provers compute several
+    /// bucket windows at once, so their peak is higher than this estimate.
+    #[inline]
+    pub fn memory<E>(&self, strategy: ProvingStrategy) -> usize
+    where
+        E: PairingEngine,
+    {
+        let field = mem::size_of::<E::Fr>();
+        let big_int = mem::size_of::<<E::Fr as PrimeField>::BigInt>();
+        let variables = self.variables();
+        let domain_size = self.domain_size();
+        let (witness_map, msm) = match strategy {
+            ProvingStrategy::Default => (
+                (variables + 3 * domain_size) * field,
+                (2 * variables + domain_size) * big_int
+                    + msm::bucket_memory::<E::G2Affine>(variables.max(domain_size)),
+            ),
+            ProvingStrategy::LowMemory { chunk_size } => (
+                3 * domain_size * field,
+                domain_size * field + msm::chunked_msm_memory::<E::G2Affine>(chunk_size),
+            ),
+        };
+        witness_map.max(msm)
+    }
+}
+
+/// Proving Strategy
+#[cfg(feature = "groth16-prover")]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ProvingStrategy {
+    /// Default Strategy
+    ///
+    /// Builds proofs with the prover of arkworks, which keeps the constraint matrices and several
+    /// copies of the witness in memory for the whole proof.
+    #[default]
+    Default,
+
+    /// Low-Memory Strategy
+    ///
+    /// Evaluates the witness polynomials while it consumes the constraint matrices, and computes
+    /// the multi-scalar multiplications in chunks of `chunk_size` terms, converting the witness
+    /// one chunk at a time, so proofs take longer to build.
+    LowMemory {
+        /// Number of Terms of every Multi-Scalar Multiplication Chunk
+        chunk_size: usize,
+    },
+}
+
+/// Prover Configuration
+///
+/// Settings of the prover of a [`ProvingContext`], which depend on the device that builds the
+/// proofs rather than on the circuit, so they are neither serialized with the proving context nor
+/// compared by its equality.
+#[cfg(feature = "groth16-prover")]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ProverConfiguration {
+    /// Memory Budget in Bytes
+    ///
+    /// Memory that the prover can allocate on top of the constraint system and the proving key.
+    /// Without a budget, proofs are always built with [`ProvingStrategy::Default`].
+    pub memory_budget: Option<usize>,
+}
+
+#[cfg(feature = "groth16-prover")]
+impl ProverConfiguration {
+    /// Builds a new [`ProverConfiguration`] with a `memory_budget` in bytes.
+    #[inline]
+    pub fn with_memory_budget(memory_budget: usize) -> Self {
+        Self {
+            memory_budget: Some(memory_budget),
+        }
+    }
+
+    /// Returns the [`ProvingStrategy`] for a circuit of the given `size` under `self`.
+    ///
+    /// The default strategy is used whenever it fits in the memory budget. Otherwise, the
+    /// low-memory strategy is used with the largest power-of-two chunk size which fits in the
+    /// budget, but never with chunks smaller than [`MIN_CHUNK_SIZE`], so budgets which are too
+    /// small for any strategy get the smallest one.
+    #[inline]
+    pub fn strategy<E>(&self, size: &CircuitSize) -> ProvingStrategy
+    where
+        E: PairingEngine,
+    {
+        match self.memory_budget {
+            Some(budget) if size.memory::<E>(ProvingStrategy::Default) > budget => {
+                let mut chunk_size = size.variables().max(size.domain_size()).next_power_of_two();
+                while chunk_size > MIN_CHUNK_SIZE
+                    && size.memory::<E>(ProvingStrategy::LowMemory { chunk_size }) > budget
+                {
+                    chunk_size /= 2;
+                }
+                ProvingStrategy::LowMemory { chunk_size }
+            }
+            _ => ProvingStrategy::Default,
+        }
+    }
+}
+
 /// Proving Context
+#[cfg(feature = "groth16-prover")]
 #[cfg_attr(
     feature = "serde",
     derive(Deserialize, Serialize),
     serde(crate = "manta_util::serde", deny_unknown_fields)
 )]
-#[derive(derivative::Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derive(derivative::Derivative)]
 #[derivative(Clone, Debug, Eq, PartialEq)]
 pub struct ProvingContext<E>
 where
@@ -225,25 +572,93 @@ where
         )
     )]
     proving_key: ProvingKey<E>,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[derivative(PartialEq = "ignore")]
+    configuration: ProverConfiguration,
+
+    /// Synthesis Cache
+    ///
+    /// Constraint matrices of the circuit of the proving key, which do not depend on the witness,
+    /// so that the proofs built with `self` only have to assign it. They are rebuilt from the
+    /// circuit rather than serialized with the proving key.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    synthesis: Option<ConstraintMatrices<E::Fr>>,
 }
 
+#[cfg(feature = "groth16-prover")]
 impl<E> ProvingContext<E>
 where
     E: PairingEngine,
 {
-    /// Builds a new [`ProvingContext`] from `proving_key`.
+    /// Builds a new [`ProvingContext`] from `proving_key` with the default
+    /// [`ProverConfiguration`].
     #[inline]
     pub fn new(proving_key: ProvingKey<E>) -> Self {
-        Self { proving_key }
+        Self {
+            proving_key,
+            configuration: Default::default(),
+            synthesis: None,
+        }
+    }
+
+    /// Returns the [`ProverConfiguration`] of `self`.
+    #[inline]
+    pub fn configuration(&self) -> &ProverConfiguration {
+        &self.configuration
     }
 
-    /// Returns the [`VerifyingContext`] for `self`.
+    /// Replaces the [`ProverConfiguration`] of `self` with `configuration`.
     #[inline]
-    pub fn get_verifying_context(&self) -> Result<VerifyingContext<E>, SynthesisError> {
+    pub fn set_configuration(&mut self, configuration: ProverConfiguration) {
+        self.configuration = configuration;
+    }
+
+    /// Returns the [`ProvingStrategy`] that `self` uses to build a proof for `compiler`.
+    #[inline]
+    pub fn strategy(&self, compiler: &R1CS<E::Fr>) -> ProvingStrategy {
+        self.configuration
+            .strategy::<E>(&CircuitSize::from_compiler(compiler))
+    }
+
+    /// Returns `true` if `self` has cached the structure of its circuit with
+    /// [`ProofSystem::cache_synthesis`].
+    #[inline]
+    pub fn is_synthesis_cached(&self) -> bool {
+        self.synthesis.is_some()
+    }
+
+    /// Drops the structure of the circuit cached in `self`, so that the following proofs are built
+    /// from the full constraint system again.
+    #[inline]
+    pub fn clear_synthesis_cache(&mut self) {
+        self.synthesis = None;
+    }
+
+    /// Returns `true` if the constraint `matrices` have the number of variables of the circuit of
+    /// the proving key of `self`.
+    #[inline]
+    fn matches(&self, matrices: &ConstraintMatrices<E::Fr>) -> bool {
+        matrices.num_instance_variables == self.proving_key.vk.gamma_abc_g1.len()
+            && matrices.num_witness_variables == self.proving_key.l_query.len()
+    }
+
+    /// Extracts the [`VerifyingContext`] for `self` from the verifying key embedded in the proving
+    /// key, so that it never has to be distributed separately from `self`.
+    #[inline]
+    pub fn verifying_context(&self) -> Result<VerifyingContext<E>, SynthesisError> {
         VerifyingContext::from_proving_context(self)
     }
+
+    /// Returns the verifying key embedded in the proving key of `self`.
+    #[inline]
+    pub fn verifying_key(&self) -> &VerifyingKey<E> {
+        &self.proving_key.vk
+    }
 }
 
+#[cfg(feature = "groth16-prover")]
 impl<E> Hash for ProvingContext<E>
 where
     E: PairingEngine,
@@ -265,6 +680,77 @@ where
     }
 }
 
+#[cfg(feature = "groth16-prover")]
+impl<E> CanonicalSerialize for ProvingContext<E>
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn serialize<W>(&self, writer: W) -> Result<(), SerializationError>
+    where
+        W: Write,
+    {
+        self.proving_key.serialize(writer)
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.proving_key.serialized_size()
+    }
+
+    #[inline]
+    fn serialize_uncompressed<W>(&self, writer: W) -> Result<(), SerializationError>
+    where
+        W: Write,
+    {
+        self.proving_key.serialize_uncompressed(writer)
+    }
+
+    #[inline]
+    fn serialize_unchecked<W>(&self, writer: W) -> Result<(), SerializationError>
+    where
+        W: Write,
+    {
+        self.proving_key.serialize_unchecked(writer)
+    }
+
+    #[inline]
+    fn uncompressed_size(&self) -> usize {
+        self.proving_key.uncompressed_size()
+    }
+}
+
+#[cfg(feature = "groth16-prover")]
+impl<E> CanonicalDeserialize for ProvingContext<E>
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn deserialize<R>(reader: R) -> Result<Self, SerializationError>
+    where
+        R: Read,
+    {
+        CanonicalDeserialize::deserialize(reader).map(Self::new)
+    }
+
+    #[inline]
+    fn deserialize_uncompressed<R>(reader: R) -> Result<Self, SerializationError>
+    where
+        R: Read,
+    {
+        CanonicalDeserialize::deserialize_uncompressed(reader).map(Self::new)
+    }
+
+    #[inline]
+    fn deserialize_unchecked<R>(reader: R) -> Result<Self, SerializationError>
+    where
+        R: Read,
+    {
+        CanonicalDeserialize::deserialize_unchecked(reader).map(Self::new)
+    }
+}
+
+#[cfg(feature = "groth16-prover")]
 impl<E> codec::Decode for ProvingContext<E>
 where
     E: PairingEngine,
@@ -287,6 +773,7 @@ where
     }
 }
 
+#[cfg(feature = "groth16-prover")]
 impl<E> codec::Encode for ProvingContext<E>
 where
     E: PairingEngine,
@@ -302,6 +789,189 @@ where
     }
 }
 
+/// Proving Context
+///
+/// Verification-only builds never build proofs, so this type has no values and every operation
+/// which needs a proving context is unreachable.
+#[cfg(not(feature = "groth16-prover"))]
+pub struct ProvingContext<E>(Infallible, PhantomData<E>)
+where
+    E: PairingEngine;
+
+#[cfg(not(feature = "groth16-prover"))]
+impl<E> ProvingContext<E>
+where
+    E: PairingEngine,
+{
+    /// Returns the value of any type from `self`, which has no values.
+    #[inline]
+    fn unreachable<T>(&self) -> T {
+        match self.0 {}
+    }
+
+    /// Extracts the [`VerifyingContext`] for `self` from the verifying key embedded in the proving
+    /// key.
+    #[inline]
+    pub fn verifying_context(&self) -> Result<VerifyingContext<E>, SynthesisError> {
+        self.unreachable()
+    }
+
+    /// Returns the verifying key embedded in the proving key of `self`.
+    #[inline]
+    pub fn verifying_key(&self) -> &VerifyingKey<E> {
+        self.unreachable()
+    }
+}
+
+#[cfg(not(feature = "groth16-prover"))]
+impl<E> Clone for ProvingContext<E>
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        self.unreachable()
+    }
+}
+
+#[cfg(not(feature = "groth16-prover"))]
+impl<E> Debug for ProvingContext<E>
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let _ = f;
+        self.unreachable()
+    }
+}
+
+#[cfg(not(feature = "groth16-prover"))]
+impl<E> Eq for ProvingContext<E> where E: PairingEngine {}
+
+#[cfg(not(feature = "groth16-prover"))]
+impl<E> PartialEq for ProvingContext<E>
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn eq(&self, rhs: &Self) -> bool {
+        let _ = rhs;
+        self.unreachable()
+    }
+}
+
+#[cfg(not(feature = "groth16-prover"))]
+impl<E> Hash for ProvingContext<E>
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let _ = state;
+        self.unreachable()
+    }
+}
+
+#[cfg(not(feature = "groth16-prover"))]
+impl<E> CanonicalSerialize for ProvingContext<E>
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn serialize<W>(&self, writer: W) -> Result<(), SerializationError>
+    where
+        W: Write,
+    {
+        let _ = writer;
+        self.unreachable()
+    }
+
+    #[inline]
+    fn serialized_size(&self) -> usize {
+        self.unreachable()
+    }
+}
+
+#[cfg(not(feature = "groth16-prover"))]
+impl<E> CanonicalDeserialize for ProvingContext<E>
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn deserialize<R>(reader: R) -> Result<Self, SerializationError>
+    where
+        R: Read,
+    {
+        let _ = reader;
+        Err(SerializationError::InvalidData)
+    }
+}
+
+#[cfg(not(feature = "groth16-prover"))]
+impl<E> codec::Decode for ProvingContext<E>
+where
+    E: PairingEngine,
+{
+    type Error = SerializationError;
+
+    #[inline]
+    fn decode<R>(reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: codec::Read,
+    {
+        let _ = reader;
+        Err(DecodeError::Decode(SerializationError::InvalidData))
+    }
+}
+
+#[cfg(not(feature = "groth16-prover"))]
+impl<E> codec::Encode for ProvingContext<E>
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn encode<W>(&self, writer: W) -> Result<(), W::Error>
+    where
+        W: codec::Write,
+    {
+        let _ = writer;
+        self.unreachable()
+    }
+}
+
+#[cfg(all(feature = "serde", not(feature = "groth16-prover")))]
+impl<E> Serialize for ProvingContext<E>
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let _ = serializer;
+        self.unreachable()
+    }
+}
+
+#[cfg(all(feature = "serde", not(feature = "groth16-prover")))]
+impl<'de, E> Deserialize<'de> for ProvingContext<E>
+where
+    E: PairingEngine,
+{
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let _ = deserializer;
+        Err(de::Error::custom(
+            "Proving contexts are not available in verification-only builds.",
+        ))
+    }
+}
+
 /// Verifying Context
 #[derive(derivative::Derivative)]
 #[derivative(Clone, Debug, Default)]
@@ -325,12 +995,37 @@ where
         Ok(Self(ArkGroth16::process_vk(verifying_key)?))
     }
 
+    /// Returns the verifying key of `self`.
+    #[inline]
+    pub fn verifying_key(&self) -> &VerifyingKey<E> {
+        &self.0.vk
+    }
+
     /// Returns the [`VerifyingContext`] for `proving_context`.
     #[inline]
     pub fn from_proving_context(
         proving_context: &ProvingContext<E>,
     ) -> Result<Self, SynthesisError> {
-        Self::new(&proving_context.proving_key.vk)
+        Self::new(proving_context.verifying_key())
+    }
+
+    /// Converts `self` into bytes using `compression` for its group elements.
+    #[inline]
+    pub fn to_bytes(&self, compression: Compression) -> Vec<u8>
+    where
+        for<'s> E::G2Prepared: HasSerialization<'s>,
+    {
+        compression.serialize(self)
+    }
+
+    /// Builds a [`VerifyingContext`] from `bytes`, accepting both compressed and uncompressed
+    /// group elements.
+    #[inline]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SerializationError>
+    where
+        E::G2Prepared: HasDeserialization,
+    {
+        deserialize_detected(bytes).map(|(context, _)| context)
     }
 }
 
@@ -538,7 +1233,254 @@ where
     }
 }
 
+/// Computes the evaluations of the quotient polynomial of a constraint system over the coset of
+/// its evaluation domain, as in the prover of arkworks, from the rows of its constraint matrices
+/// `a`, `b`, and `c` and its `instance` and `witness` assignments. Rows are evaluated one at a time
+/// and dropped right after when the matrices are given by value, and the assignments are never
+/// copied.
+#[cfg(feature = "groth16-prover")]
+#[inline]
+fn witness_map<F, M>(
+    num_constraints: usize,
+    a: M,
+    b: M,
+    c: M,
+    instance: &[F],
+    witness: &[F],
+) -> Result<Vec<F>, SynthesisError>
+where
+    F: PrimeField,
+    M: IntoIterator,
+    M::Item: AsRef<[(F, usize)]>,
+{
+    let num_instance_variables = instance.len();
+    let domain = GeneralEvaluationDomain::<F>::new(num_constraints + num_instance_variables)
+        .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+    let domain_size = domain.size();
+    let evaluate = |row: M::Item| -> F {
+        row.as_ref()
+            .iter()
+            .map(|(coefficient, index)| {
+                *coefficient
+                    * if *index < num_instance_variables {
+                        instance[*index]
+                    } else {
+                        witness[*index - num_instance_variables]
+                    }
+            })
+            .sum()
+    };
+    let evaluations = |matrix: M, tail: &[F]| {
+        let mut evaluations = Vec::with_capacity(domain_size);
+        evaluations.extend(matrix.into_iter().map(&evaluate));
+        evaluations.extend_from_slice(tail);
+        evaluations.resize(domain_size, F::zero());
+        evaluations
+    };
+    let mut a = evaluations(a, instance);
+    let mut b = evaluations(b, &[]);
+    let mut c = evaluations(c, &[]);
+    domain.ifft_in_place(&mut a);
+    domain.coset_fft_in_place(&mut a);
+    domain.ifft_in_place(&mut b);
+    domain.coset_fft_in_place(&mut b);
+    a.iter_mut().zip(b).for_each(|(a, b)| *a *= b);
+    domain.ifft_in_place(&mut c);
+    domain.coset_fft_in_place(&mut c);
+    a.iter_mut().zip(c).for_each(|(a, c)| *a -= c);
+    domain.divide_by_vanishing_poly_on_coset_in_place(&mut a);
+    domain.coset_ifft_in_place(&mut a);
+    Ok(a)
+}
+
+/// Computes the evaluations of the quotient polynomial of the constraint system `cs` over the
+/// coset of its evaluation domain, as in the prover of arkworks, except that the constraint
+/// matrices are consumed while they are evaluated and the witness is never copied.
+#[cfg(feature = "groth16-prover")]
+#[inline]
+fn low_memory_witness_map<F>(cs: &ConstraintSystemRef<F>) -> Result<Vec<F>, SynthesisError>
+where
+    F: PrimeField,
+{
+    let ConstraintMatrices {
+        num_constraints,
+        a,
+        b,
+        c,
+        ..
+    } = cs.to_matrices().ok_or(SynthesisError::MissingCS)?;
+    let cs = cs.borrow().ok_or(SynthesisError::MissingCS)?;
+    witness_map(
+        num_constraints,
+        a,
+        b,
+        c,
+        &cs.instance_assignment,
+        &cs.witness_assignment,
+    )
+}
+
+/// Returns `initial` plus the linear combination of `query` with the full assignment of a
+/// constraint system, whose first variable is the constant one, plus `vk_param`.
+#[cfg(feature = "groth16-prover")]
+#[inline]
+fn calculate_coefficient<G, I>(
+    initial: G::Projective,
+    query: &[G],
+    vk_param: G,
+    assignment: I,
+    chunk_size: usize,
+) -> G::Projective
+where
+    G: AffineCurve,
+    I: IntoIterator,
+    I::Item: Borrow<G::ScalarField>,
+{
+    let mut result = initial;
+    result.add_assign_mixed(&query[0]);
+    result += msm::chunked_msm(&query[1..], assignment, chunk_size);
+    result.add_assign_mixed(&vk_param);
+    result
+}
+
+/// Builds a proof for `compiler` with the [`ProvingStrategy::LowMemory`] strategy.
+#[cfg(feature = "groth16-prover")]
+#[inline]
+fn prove_low_memory<E, R>(
+    proving_key: &ProvingKey<E>,
+    compiler: R1CS<E::Fr>,
+    chunk_size: usize,
+    rng: &mut R,
+) -> Result<ark_groth16::Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    R: RngCore + ?Sized,
+{
+    let r = E::Fr::rand(rng);
+    let s = E::Fr::rand(rng);
+    let cs = compiler.as_ref().clone();
+    cs.finalize();
+    let h = low_memory_witness_map(&cs)?;
+    let cs = cs.borrow().ok_or(SynthesisError::MissingCS)?;
+    Ok(build_proof(
+        proving_key,
+        h,
+        &cs.instance_assignment[1..],
+        &cs.witness_assignment,
+        chunk_size,
+        r,
+        s,
+    ))
+}
+
+/// Builds a proof for `compiler`, which only assigns the witness of the circuit, against the
+/// constraint `matrices` cached in the proving context, with the multi-scalar multiplications
+/// computed in chunks of `chunk_size` terms.
+#[cfg(feature = "groth16-prover")]
+#[inline]
+fn prove_cached<E, R>(
+    proving_key: &ProvingKey<E>,
+    matrices: &ConstraintMatrices<E::Fr>,
+    compiler: R1CS<E::Fr>,
+    chunk_size: usize,
+    rng: &mut R,
+) -> Result<ark_groth16::Proof<E>, SynthesisError>
+where
+    E: PairingEngine,
+    R: RngCore + ?Sized,
+{
+    let r = E::Fr::rand(rng);
+    let s = E::Fr::rand(rng);
+    let cs = compiler
+        .as_ref()
+        .borrow()
+        .ok_or(SynthesisError::MissingCS)?;
+    if cs.instance_assignment.len() != matrices.num_instance_variables
+        || cs.witness_assignment.len() != matrices.num_witness_variables
+    {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+    let h = witness_map(
+        matrices.num_constraints,
+        &matrices.a,
+        &matrices.b,
+        &matrices.c,
+        &cs.instance_assignment,
+        &cs.witness_assignment,
+    )?;
+    Ok(build_proof(
+        proving_key,
+        h,
+        &cs.instance_assignment[1..],
+        &cs.witness_assignment,
+        chunk_size,
+        r,
+        s,
+    ))
+}
+
+/// Builds a proof from the evaluations `h` of the quotient polynomial, the `instance` assignment
+/// without its leading constant, and the `witness` assignment of a constraint system, with the
+/// randomness `r` and `s`, computing the multi-scalar multiplications in chunks of `chunk_size`
+/// terms.
+#[cfg(feature = "groth16-prover")]
+#[inline]
+fn build_proof<E>(
+    proving_key: &ProvingKey<E>,
+    h: Vec<E::Fr>,
+    instance: &[E::Fr],
+    witness: &[E::Fr],
+    chunk_size: usize,
+    r: E::Fr,
+    s: E::Fr,
+) -> ark_groth16::Proof<E>
+where
+    E: PairingEngine,
+{
+    let h_acc = msm::chunked_msm(&proving_key.h_query, &h, chunk_size);
+    drop(h);
+    let l_aux_acc = msm::chunked_msm(&proving_key.l_query, witness, chunk_size);
+    let g_a = calculate_coefficient(
+        proving_key.delta_g1.mul(r),
+        &proving_key.a_query,
+        proving_key.vk.alpha_g1,
+        instance.iter().chain(witness),
+        chunk_size,
+    );
+    let g1_b = calculate_coefficient(
+        proving_key.delta_g1.mul(s),
+        &proving_key.b_g1_query,
+        proving_key.beta_g1,
+        instance.iter().chain(witness),
+        chunk_size,
+    );
+    let g2_b = calculate_coefficient(
+        proving_key.vk.delta_g2.mul(s),
+        &proving_key.b_g2_query,
+        proving_key.vk.beta_g2,
+        instance.iter().chain(witness),
+        chunk_size,
+    );
+    let mut g_c = g_a.into_affine().mul(s);
+    g_c += g1_b.into_affine().mul(r);
+    g_c -= proving_key.delta_g1.mul(r * s);
+    g_c += l_aux_acc;
+    g_c += h_acc;
+    ark_groth16::Proof {
+        a: g_a.into_affine(),
+        b: g2_b.into_affine(),
+        c: g_c.into_affine(),
+    }
+}
+
 /// Arkworks Groth16 Proof System
+///
+/// Proofs are built with the [`ProvingStrategy`] that the [`ProverConfiguration`] of their
+/// [`ProvingContext`] selects for the size of their circuit. Proving contexts which have cached the
+/// constraint matrices of their circuit with [`ProofSystem::cache_synthesis`] also build proofs for
+/// the compilers of [`ProofSystem::cached_proof_compiler`], which only record the assignment of
+/// the variables, so that the structure of the circuit is synthesized once per proving context
+/// instead of once per proof.
 #[derive(derivative::Derivative)]
 #[derivative(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Groth16<E>(PhantomData<E>)
@@ -567,6 +1509,7 @@ where
         Self::Compiler::for_proofs()
     }
 
+    #[cfg(feature = "groth16-prover")]
     #[inline]
     fn compile<R>(
         public_parameters: &Self::PublicParameters,
@@ -585,6 +1528,21 @@ where
         ))
     }
 
+    #[cfg(not(feature = "groth16-prover"))]
+    #[inline]
+    fn compile<R>(
+        public_parameters: &Self::PublicParameters,
+        compiler: Self::Compiler,
+        rng: &mut R,
+    ) -> Result<(Self::ProvingContext, Self::VerifyingContext), Self::Error>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let _ = (public_parameters, compiler, rng);
+        Err(Error)
+    }
+
+    #[cfg(feature = "groth16-prover")]
     #[inline]
     fn prove<R>(
         context: &Self::ProvingContext,
@@ -594,9 +1552,68 @@ where
     where
         R: CryptoRng + RngCore + ?Sized,
     {
-        ArkGroth16::prove(&context.proving_key, compiler, &mut SizedRng(rng))
-            .map(Proof)
-            .map_err(|_| Error)
+        let strategy = context.strategy(&compiler);
+        if !compiler.as_ref().should_construct_matrices() {
+            let matrices = context.synthesis.as_ref().ok_or(Error)?;
+            let chunk_size = match strategy {
+                ProvingStrategy::Default => usize::MAX,
+                ProvingStrategy::LowMemory { chunk_size } => chunk_size,
+            };
+            return prove_cached(&context.proving_key, matrices, compiler, chunk_size, rng)
+                .map(Proof)
+                .map_err(|_| Error);
+        }
+        match strategy {
+            ProvingStrategy::Default => {
+                ArkGroth16::prove(&context.proving_key, compiler, &mut SizedRng(rng))
+            }
+            ProvingStrategy::LowMemory { chunk_size } => {
+                prove_low_memory(&context.proving_key, compiler, chunk_size, rng)
+            }
+        }
+        .map(Proof)
+        .map_err(|_| Error)
+    }
+
+    #[cfg(not(feature = "groth16-prover"))]
+    #[inline]
+    fn prove<R>(
+        context: &Self::ProvingContext,
+        compiler: Self::Compiler,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error>
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        let _ = (compiler, rng);
+        context.unreachable()
+    }
+
+    #[cfg(feature = "groth16-prover")]
+    #[inline]
+    fn cache_synthesis(context: &mut Self::ProvingContext, compiler: Self::Compiler) -> bool {
+        let cs = compiler.as_ref();
+        if !cs.is_in_setup_mode() {
+            return false;
+        }
+        cs.finalize();
+        match cs.to_matrices() {
+            Some(matrices) if context.matches(&matrices) => {
+                context.synthesis = Some(matrices);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[cfg(feature = "groth16-prover")]
+    #[inline]
+    fn cached_proof_compiler(context: &Self::ProvingContext) -> Self::Compiler {
+        if context.is_synthesis_cached() {
+            Self::Compiler::for_witnesses()
+        } else {
+            Self::proof_compiler()
+        }
     }
 
     #[inline]
@@ -627,3 +1644,43 @@ macro_rules! public_input_impl {
 }
 
 public_input_impl!(bool, u8, u16, u32, u64, u128);
+
+#[cfg(all(test, feature = "ark-bn254", not(feature = "groth16-prover")))]
+mod test {
+    use super::*;
+    use crate::{arkworks::bn254::Bn254, rand::OsRng};
+    use manta_util::codec::{Decode, Encode};
+
+    /// Checks that proving contexts cannot be decoded in verification-only builds.
+    #[test]
+    fn proving_context_decoding_fails() {
+        let bytes = [0u8; 256];
+        assert!(matches!(
+            ProvingContext::<Bn254>::from_vec(bytes.to_vec()),
+            Err(SerializationError::InvalidData)
+        ));
+        assert!(matches!(
+            <ProvingContext<Bn254> as CanonicalDeserialize>::deserialize(&bytes[..]),
+            Err(SerializationError::InvalidData)
+        ));
+    }
+
+    /// Checks that circuits cannot be compiled in verification-only builds.
+    #[test]
+    fn compile_fails() {
+        assert!(
+            Groth16::<Bn254>::compile(&(), Groth16::<Bn254>::context_compiler(), &mut OsRng)
+                .is_err()
+        );
+    }
+
+    /// Checks that verifying contexts are still built and encoded in verification-only builds.
+    #[test]
+    fn verifying_context_round_trip() {
+        let verifying_context = VerifyingContext::<Bn254>::new(&Default::default())
+            .expect("Preparing the verifying key is not allowed to fail.");
+        let decoded = VerifyingContext::<Bn254>::from_vec(verifying_context.to_vec())
+            .expect("Decoding the verifying context is not allowed to fail.");
+        assert_eq!(decoded.verifying_key(), verifying_context.verifying_key());
+    }
+}
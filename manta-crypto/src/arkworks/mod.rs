@@ -37,6 +37,7 @@ pub use ark_ed_on_bn254 as ed_on_bn254;
 pub mod algebra;
 pub mod constraint;
 pub mod ff;
+pub mod msm;
 pub mod pairing;
 pub mod rand;
 pub mod ratio;
@@ -0,0 +1,136 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Chunked Multi-Scalar Multiplication
+//!
+//! The variable-base multi-scalar multiplication of arkworks takes all of its scalars in their big
+//! integer representation and sizes its buckets after the number of scalars, so its memory grows
+//! with the size of its input. [`chunked_msm`] splits the input into chunks of bounded size,
+//! converts the scalars of one chunk at a time and adds up the results, which bounds the memory of
+//! the multiplication at the cost of smaller, and therefore slower, bucket windows.
+
+use crate::arkworks::{
+    ec::{msm::VariableBaseMSM, AffineCurve},
+    ff::{PrimeField, Zero},
+};
+use alloc::vec::Vec;
+use core::{borrow::Borrow, mem};
+
+/// Returns the bucket window size, in bits, of a multi-scalar multiplication over `size` scalars.
+#[inline]
+pub fn window_size(size: usize) -> usize {
+    if size < 32 {
+        3
+    } else {
+        let log2 = (usize::BITS - (size - 1).leading_zeros()) as usize;
+        log2 * 69 / 100 + 2
+    }
+}
+
+/// Returns the number of bytes taken by the buckets of one window of a multi-scalar
+/// multiplication over `size` scalars with bases in `G`.
+#[inline]
+pub fn bucket_memory<G>(size: usize) -> usize
+where
+    G: AffineCurve,
+{
+    ((1 << window_size(size)) - 1) * mem::size_of::<G::Projective>()
+}
+
+/// Returns the number of bytes taken by a [`chunked_msm`] with bases in `G` and chunks of
+/// `chunk_size` scalars, on top of its bases and scalars.
+#[inline]
+pub fn chunked_msm_memory<G>(chunk_size: usize) -> usize
+where
+    G: AffineCurve,
+{
+    chunk_size * mem::size_of::<<G::ScalarField as PrimeField>::BigInt>()
+        + bucket_memory::<G>(chunk_size)
+}
+
+/// Computes the multi-scalar multiplication of `bases` and `scalars` in chunks of at most
+/// `chunk_size` terms. Extra bases or scalars are ignored, as in [`VariableBaseMSM`].
+///
+/// # Panics
+///
+/// This function panics if `chunk_size` is zero.
+#[inline]
+pub fn chunked_msm<G, I>(bases: &[G], scalars: I, chunk_size: usize) -> G::Projective
+where
+    G: AffineCurve,
+    I: IntoIterator,
+    I::Item: Borrow<G::ScalarField>,
+{
+    assert!(chunk_size > 0, "Chunks must have at least one term.");
+    let mut scalars = scalars.into_iter();
+    let mut buffer = Vec::with_capacity(chunk_size.min(bases.len()));
+    let mut result = G::Projective::zero();
+    for chunk in bases.chunks(chunk_size) {
+        buffer.clear();
+        buffer.extend(
+            scalars
+                .by_ref()
+                .take(chunk.len())
+                .map(|scalar| scalar.borrow().into_repr()),
+        );
+        if buffer.is_empty() {
+            break;
+        }
+        result += VariableBaseMSM::multi_scalar_mul(&chunk[..buffer.len()], &buffer);
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rand::{OsRng, Rand, Sample};
+
+    /// Checks that [`chunked_msm`] agrees with the multi-scalar multiplication of arkworks for
+    /// chunks which do and do not divide the number of terms.
+    #[inline]
+    fn check_chunked_msm<G>()
+    where
+        G: AffineCurve + Sample,
+        G::ScalarField: Sample,
+    {
+        let mut rng = OsRng;
+        let bases = (0..100).map(|_| rng.gen()).collect::<Vec<G>>();
+        let scalars = (0..90).map(|_| rng.gen()).collect::<Vec<G::ScalarField>>();
+        let expected = VariableBaseMSM::multi_scalar_mul(
+            &bases,
+            &scalars
+                .iter()
+                .map(|scalar| scalar.into_repr())
+                .collect::<Vec<_>>(),
+        );
+        for chunk_size in [1, 7, 32, 90, 128] {
+            assert_eq!(
+                chunked_msm(&bases, &scalars, chunk_size),
+                expected,
+                "Chunked multi-scalar multiplication should not depend on the chunk size."
+            );
+        }
+    }
+
+    /// Checks chunked multi-scalar multiplication over the groups of BN254.
+    #[cfg(feature = "ark-bn254")]
+    #[test]
+    fn bn254_chunked_msm() {
+        check_chunked_msm::<crate::arkworks::bn254::G1Affine>();
+        check_chunked_msm::<crate::arkworks::bn254::G2Affine>();
+    }
+}
@@ -19,8 +19,6 @@
 // FIXME: Leverage the type system to constrain allocation to only unknown modes for verifier
 //        generation and only known modes for proof generation, instead of relying on the `for_*`
 //        methods to "do the right thing".
-// TODO:  Find ways to enforce public input structure, since it's very easy to extend the input
-//        vector by the wrong amount or in the wrong order.
 
 use crate::rand::{CryptoRng, RngCore};
 
@@ -94,6 +92,28 @@ pub trait ProofSystem {
     where
         R: CryptoRng + RngCore + ?Sized;
 
+    /// Caches the structure of the constraint system in `compiler`, which was built against
+    /// unknown variables as for [`compile`](Self::compile), in the proving `context`, so that the
+    /// compilers returned by [`cached_proof_compiler`](Self::cached_proof_compiler) only have to
+    /// assign the witness of the proofs built with `context`.
+    ///
+    /// Returns `false` if the proof system does not support cached synthesis or if `compiler` was
+    /// not built for the circuit of `context`, in which case `context` is left unchanged.
+    #[inline]
+    fn cache_synthesis(context: &mut Self::ProvingContext, compiler: Self::Compiler) -> bool {
+        let _ = (context, compiler);
+        false
+    }
+
+    /// Returns a compiler which is setup to build a proof with `context`, reusing the structure
+    /// cached in `context` by [`cache_synthesis`](Self::cache_synthesis) if there is one, and
+    /// otherwise the same compiler as [`proof_compiler`](Self::proof_compiler).
+    #[inline]
+    fn cached_proof_compiler(context: &Self::ProvingContext) -> Self::Compiler {
+        let _ = context;
+        Self::proof_compiler()
+    }
+
     /// Verifies that a proof generated from this proof system is valid.
     fn verify(
         context: &Self::VerifyingContext,
@@ -134,6 +154,51 @@ where
     }
 }
 
+/// Implements [`Input`] for a type by declaring the order in which its parts extend the input.
+///
+/// Every part is listed with the type it extends the input as, followed by an expression which
+/// borrows it from the value bound to the identifier between the bars. The types become
+/// [`HasInput`] bounds on the proof system, so the bounds and the order of the calls to
+/// [`HasInput::extend`] cannot go out of sync. The bounds on the type parameters other than the
+/// proof system go in brackets after `where`.
+///
+/// ```ignore
+/// impl_input! {
+///     impl<S, L> Input<P> for SenderPostingKey<S, L>
+///     where [S: Spend, L: SenderLedger<S> + ?Sized]
+///     |key| {
+///         UtxoAccumulatorOutput<S> => key.utxo_accumulator_output.as_ref(),
+///         S::Nullifier => key.nullifier.as_ref(),
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! impl_input {
+    (
+        impl<$($generic:ident),* $(,)?> Input<$proof_system:ident> for $type:ty
+        $(where [$($bounds:tt)*])?
+        |$this:ident| {
+            $($input_type:ty => $value:expr),+ $(,)?
+        }
+    ) => {
+        impl<$($generic,)* $proof_system> $crate::constraint::Input<$proof_system> for $type
+        where
+            $proof_system: $($crate::constraint::HasInput<$input_type> +)+ ?Sized,
+            $($($bounds)*)?
+        {
+            #[inline]
+            fn extend(&self, input: &mut $proof_system::Input) {
+                let $this = self;
+                $(
+                    <$proof_system as $crate::constraint::HasInput<$input_type>>::extend(
+                        input, $value,
+                    );
+                )+
+            }
+        }
+    };
+}
+
 /// Constraint System Measurement
 pub mod measure {
     use crate::eclair::alloc::mode::{Constant, Public, Secret};
@@ -293,6 +358,267 @@ pub mod measure {
         }
     }
 
+    /// Region Profiling
+    ///
+    /// Circuits call [`enter`](Self::enter) and [`exit`](Self::exit) around their main gadgets so
+    /// that compilers running in an instrumented mode can attribute constraints, variables, and
+    /// memory to each gadget. Regions can be nested. The default implementations do nothing, so
+    /// compilers without instrumentation only need an empty `impl`.
+    pub trait Profile {
+        /// Marks the start of the region with the given `label`.
+        #[inline]
+        fn enter(&mut self, label: &'static str) {
+            let _ = label;
+        }
+
+        /// Marks the end of the most recently entered region.
+        #[inline]
+        fn exit(&mut self) {}
+
+        /// Runs `f` inside of the region with the given `label`.
+        #[inline]
+        fn region<T, F>(&mut self, label: &'static str, f: F) -> T
+        where
+            Self: Sized,
+            F: FnOnce(&mut Self) -> T,
+        {
+            self.enter(label);
+            let value = f(self);
+            self.exit();
+            value
+        }
+    }
+
+    impl Profile for () {}
+
+    /// Region Measurement
+    #[cfg_attr(
+        feature = "serde",
+        derive(Deserialize, Serialize),
+        serde(crate = "manta_util::serde", deny_unknown_fields)
+    )]
+    #[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+    pub struct Region {
+        /// Labels of the Enclosing Regions and of this Region Separated by `/`
+        pub path: String,
+
+        /// Number of Times the Region was Entered
+        pub count: usize,
+
+        /// Size of the Constraints and Variables Added Inside of the Region
+        pub size: Size,
+
+        /// Peak Heap Memory in Bytes Allocated Inside of the Region
+        ///
+        /// This is only measured if the [`TrackingAllocator`](memory::TrackingAllocator) is the
+        /// global allocator. For repeated regions, it is the largest peak over all of them.
+        pub peak_memory: Option<usize>,
+    }
+
+    /// Open Region
+    #[derive(Clone, Debug)]
+    struct OpenRegion {
+        /// Region Label
+        label: &'static str,
+
+        /// Measurement when Entering the Region
+        before: Size,
+
+        /// Allocated Memory and Peak of the Enclosing Region when Entering the Region
+        memory: Option<(usize, usize)>,
+    }
+
+    /// Region Profiler
+    ///
+    /// The profiler records a [`Region`] for every region that is entered and exited on an
+    /// instrumented compiler. Compilers store a profiler and forward their [`Profile`] calls to
+    /// [`enter`](Self::enter) and [`exit`](Self::exit) together with their current measurement.
+    #[derive(Clone, Debug, Default)]
+    pub struct Profiler {
+        /// Stack of Open Regions
+        stack: Vec<OpenRegion>,
+
+        /// Closed Regions in the Order they were Closed
+        regions: Vec<Region>,
+    }
+
+    impl Profiler {
+        /// Builds a new empty [`Profiler`].
+        #[inline]
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Opens the region with the given `label` in a compiler whose measurement is `before`.
+        #[inline]
+        pub fn enter(&mut self, label: &'static str, before: Size) {
+            self.stack.push(OpenRegion {
+                label,
+                before,
+                memory: memory::enter(),
+            });
+        }
+
+        /// Closes the most recently opened region in a compiler whose measurement is `after`.
+        ///
+        /// # Panics
+        ///
+        /// This method panics if there is no open region.
+        #[inline]
+        pub fn exit(&mut self, after: Size) {
+            let region = self
+                .stack
+                .pop()
+                .expect("Exiting a region requires a matching call to enter it.");
+            let mut path = self
+                .stack
+                .iter()
+                .map(|region| region.label)
+                .collect::<Vec<_>>()
+                .join("/");
+            if !path.is_empty() {
+                path.push('/');
+            }
+            path.push_str(region.label);
+            self.regions.push(Region {
+                path,
+                count: 1,
+                size: after
+                    .checked_sub(region.before)
+                    .expect("Measurements should increase when adding more constraints."),
+                peak_memory: region.memory.map(memory::exit),
+            });
+        }
+
+        /// Returns the regions that were closed, in the order they were closed.
+        #[inline]
+        pub fn regions(&self) -> &[Region] {
+            &self.regions
+        }
+
+        /// Returns the regions aggregated by their path, in the order each path was first closed.
+        #[inline]
+        pub fn summary(&self) -> Vec<Region> {
+            let mut summary = Vec::<Region>::new();
+            for region in &self.regions {
+                match summary.iter_mut().find(|r| r.path == region.path) {
+                    Some(entry) => {
+                        entry.count += 1;
+                        entry.size += region.size;
+                        entry.peak_memory = entry.peak_memory.max(region.peak_memory);
+                    }
+                    _ => summary.push(region.clone()),
+                }
+            }
+            summary
+        }
+    }
+
+    /// Heap Memory Tracking
+    ///
+    /// Peak memory can only be measured by the global allocator, so binaries and tests that want
+    /// memory in their [`Region`]s need to install the [`TrackingAllocator`]:
+    ///
+    /// ```ignore
+    /// #[global_allocator]
+    /// static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+    /// ```
+    ///
+    /// The counters are shared by all threads, so the measurements are only meaningful when a
+    /// single circuit is built at a time.
+    pub mod memory {
+        #[cfg(feature = "std")]
+        use std::{
+            alloc::{GlobalAlloc, Layout, System},
+            sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+        };
+
+        /// Tracking Flag
+        #[cfg(feature = "std")]
+        static TRACKING: AtomicBool = AtomicBool::new(false);
+
+        /// Currently Allocated Bytes
+        #[cfg(feature = "std")]
+        static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+        /// Peak Allocated Bytes
+        #[cfg(feature = "std")]
+        static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+        /// Tracking Allocator
+        ///
+        /// This allocator forwards to the [`System`] allocator and counts the bytes that are
+        /// currently allocated and their peak.
+        #[cfg(feature = "std")]
+        #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+        #[derive(Clone, Copy, Debug, Default)]
+        pub struct TrackingAllocator;
+
+        #[cfg(feature = "std")]
+        #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+        unsafe impl GlobalAlloc for TrackingAllocator {
+            #[inline]
+            unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+                let ptr = System.alloc(layout);
+                if !ptr.is_null() {
+                    TRACKING.store(true, Ordering::Relaxed);
+                    let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed);
+                    PEAK.fetch_max(current + layout.size(), Ordering::Relaxed);
+                }
+                ptr
+            }
+
+            #[inline]
+            unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+                System.dealloc(ptr, layout);
+                CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+            }
+        }
+
+        /// Returns the number of bytes currently allocated, or `None` if the
+        /// [`TrackingAllocator`] is not the global allocator.
+        #[inline]
+        pub fn current() -> Option<usize> {
+            #[cfg(feature = "std")]
+            if TRACKING.load(Ordering::Relaxed) {
+                return Some(CURRENT.load(Ordering::Relaxed));
+            }
+            None
+        }
+
+        /// Starts measuring the peak of a new region, returning the allocated bytes and the peak
+        /// of the enclosing region, which have to be passed to [`exit`].
+        #[cfg(feature = "std")]
+        #[inline]
+        pub(super) fn enter() -> Option<(usize, usize)> {
+            let current = current()?;
+            Some((current, PEAK.swap(current, Ordering::Relaxed)))
+        }
+
+        /// Starts measuring the peak of a new region, which is not possible without the standard
+        /// library.
+        #[cfg(not(feature = "std"))]
+        #[inline]
+        pub(super) fn enter() -> Option<(usize, usize)> {
+            None
+        }
+
+        /// Stops measuring the peak of a region started with [`enter`], returning the peak number
+        /// of bytes allocated inside of it and restoring the peak of the enclosing region.
+        #[inline]
+        pub(super) fn exit((current, enclosing_peak): (usize, usize)) -> usize {
+            #[cfg(feature = "std")]
+            return PEAK
+                .fetch_max(enclosing_peak, Ordering::Relaxed)
+                .saturating_sub(current);
+            #[cfg(not(feature = "std"))]
+            {
+                let _ = (current, enclosing_peak);
+                unreachable!("Memory is not tracked without the standard library.")
+            }
+        }
+    }
+
     /// Prints the measurement of the call to `f` with the given `label`.
     #[cfg(feature = "std")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
@@ -389,7 +715,12 @@ pub mod measure {
 #[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
 pub mod test {
     use super::*;
-    use core::fmt::Debug;
+    use alloc::sync::Arc;
+    use core::{
+        fmt::Debug,
+        marker::PhantomData,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
 
     /// Checks that attempting to verify `proof` against fuzzed inputs fails.
     #[inline]
@@ -408,4 +739,335 @@ pub mod test {
             "Proof remained valid after fuzzing."
         );
     }
+
+    /// Scripted Call Behavior
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+    pub enum Behavior {
+        /// Every call succeeds.
+        #[default]
+        Succeed,
+
+        /// Every call fails.
+        Fail,
+
+        /// Only the call with the given index fails, counting from zero.
+        FailOn(usize),
+
+        /// Every call succeeds after blocking the current thread for the given duration.
+        #[cfg(feature = "std")]
+        #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+        Delay(core::time::Duration),
+
+        /// Every call succeeds but verification rejects every proof.
+        ///
+        /// Proving calls with this behavior succeed as with [`Succeed`](Self::Succeed).
+        Reject,
+    }
+
+    impl Behavior {
+        /// Runs the `call`-th call of `stage` according to `self`.
+        #[inline]
+        fn run(&self, stage: Stage, call: usize) -> Result<bool, ScriptedError> {
+            match self {
+                Self::Fail => Err(ScriptedError { stage, call }),
+                Self::FailOn(index) if *index == call => Err(ScriptedError { stage, call }),
+                #[cfg(feature = "std")]
+                Self::Delay(duration) => {
+                    std::thread::sleep(*duration);
+                    Ok(true)
+                }
+                Self::Reject => Ok(false),
+                _ => Ok(true),
+            }
+        }
+    }
+
+    /// Scripted Proof System Stage
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub enum Stage {
+        /// Proof Generation
+        Proving,
+
+        /// Proof Verification
+        Verification,
+    }
+
+    /// Scripted Proof System Error
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub struct ScriptedError {
+        /// Stage which Failed
+        pub stage: Stage,
+
+        /// Index of the Failed Call in its Stage
+        pub call: usize,
+    }
+
+    /// Proof System Script
+    ///
+    /// The script is used as public parameters and as proving and verifying contexts of the
+    /// [`Scripted`] proof system, so the contexts compiled from it share its call counters.
+    #[derive(Clone, Debug, Default)]
+    pub struct Script {
+        /// Proving Behavior
+        prove: Behavior,
+
+        /// Verification Behavior
+        verify: Behavior,
+
+        /// Proving Call Counter
+        proofs: Arc<AtomicUsize>,
+
+        /// Verification Call Counter
+        verifications: Arc<AtomicUsize>,
+    }
+
+    impl Script {
+        /// Builds a new [`Script`] which runs proving calls with `prove` and verification calls
+        /// with `verify`.
+        #[inline]
+        pub fn new(prove: Behavior, verify: Behavior) -> Self {
+            Self {
+                prove,
+                verify,
+                proofs: Default::default(),
+                verifications: Default::default(),
+            }
+        }
+
+        /// Returns the number of proving calls made with `self` so far.
+        #[inline]
+        pub fn proofs(&self) -> usize {
+            self.proofs.load(Ordering::SeqCst)
+        }
+
+        /// Returns the number of verification calls made with `self` so far.
+        #[inline]
+        pub fn verifications(&self) -> usize {
+            self.verifications.load(Ordering::SeqCst)
+        }
+
+        /// Runs the next proving call.
+        #[inline]
+        fn prove(&self) -> Result<(), ScriptedError> {
+            self.prove
+                .run(Stage::Proving, self.proofs.fetch_add(1, Ordering::SeqCst))
+                .map(|_| ())
+        }
+
+        /// Runs the next verification call.
+        #[inline]
+        fn verify(&self) -> Result<bool, ScriptedError> {
+            self.verify.run(
+                Stage::Verification,
+                self.verifications.fetch_add(1, Ordering::SeqCst),
+            )
+        }
+    }
+
+    /// Scripted Proof System
+    ///
+    /// Test double for the proof system `P` which reuses its compiler and input types, so the
+    /// circuits of `P` are still built, but which skips all the proving work and instead fails or
+    /// succeeds as described by a [`Script`]. Proofs carry no data, so only the behavior of the
+    /// script decides whether they verify.
+    #[derive(derivative::Derivative)]
+    #[derivative(
+        Clone(bound = ""),
+        Copy(bound = ""),
+        Debug(bound = ""),
+        Default(bound = ""),
+        Eq(bound = ""),
+        Hash(bound = ""),
+        PartialEq(bound = "")
+    )]
+    pub struct Scripted<P>(PhantomData<P>)
+    where
+        P: ProofSystem;
+
+    impl<P> ProofSystem for Scripted<P>
+    where
+        P: ProofSystem,
+    {
+        type Compiler = P::Compiler;
+        type PublicParameters = Script;
+        type ProvingContext = Script;
+        type VerifyingContext = Script;
+        type Input = P::Input;
+        type Proof = ();
+        type Error = ScriptedError;
+
+        #[inline]
+        fn context_compiler() -> Self::Compiler {
+            P::context_compiler()
+        }
+
+        #[inline]
+        fn proof_compiler() -> Self::Compiler {
+            P::proof_compiler()
+        }
+
+        #[inline]
+        fn compile<R>(
+            public_parameters: &Self::PublicParameters,
+            compiler: Self::Compiler,
+            rng: &mut R,
+        ) -> Result<(Self::ProvingContext, Self::VerifyingContext), Self::Error>
+        where
+            R: CryptoRng + RngCore + ?Sized,
+        {
+            let _ = (compiler, rng);
+            Ok((public_parameters.clone(), public_parameters.clone()))
+        }
+
+        #[inline]
+        fn prove<R>(
+            context: &Self::ProvingContext,
+            compiler: Self::Compiler,
+            rng: &mut R,
+        ) -> Result<Self::Proof, Self::Error>
+        where
+            R: CryptoRng + RngCore + ?Sized,
+        {
+            let _ = (compiler, rng);
+            context.prove()
+        }
+
+        #[inline]
+        fn verify(
+            context: &Self::VerifyingContext,
+            input: &Self::Input,
+            proof: &Self::Proof,
+        ) -> Result<bool, Self::Error> {
+            let _ = (input, proof);
+            context.verify()
+        }
+    }
+
+    /// Implements [`Input`] for [`Scripted`] proof systems by forwarding to the inner proof
+    /// system, for types which only implement [`Input`] for concrete proof systems.
+    macro_rules! scripted_input_impl {
+        ($($type:tt),* $(,)?) => {
+            $(
+                impl<P> Input<Scripted<P>> for $type
+                where
+                    P: ProofSystem,
+                    $type: Input<P>,
+                {
+                    #[inline]
+                    fn extend(&self, input: &mut P::Input) {
+                        Input::<P>::extend(self, input)
+                    }
+                }
+            )*
+        };
+    }
+
+    scripted_input_impl!(bool, u8, u16, u32, u64, u128);
+
+    /// Checks that [`Scripted`] proof systems follow their [`Script`] and share its call counters
+    /// between the contexts compiled from it.
+    #[test]
+    fn scripted_proof_system_follows_script() {
+        type Mock = Scripted<Recorder>;
+        let mut rng = crate::rand::OsRng;
+        let script = Script::new(Behavior::FailOn(1), Behavior::Reject);
+        let (proving_context, verifying_context) =
+            Mock::compile(&script, (), &mut rng).expect("Compiling never fails.");
+        assert_eq!(Mock::prove(&proving_context, (), &mut rng), Ok(()));
+        assert_eq!(
+            Mock::prove(&proving_context, (), &mut rng),
+            Err(ScriptedError {
+                stage: Stage::Proving,
+                call: 1
+            })
+        );
+        assert_eq!(Mock::prove(&proving_context, (), &mut rng), Ok(()));
+        assert_eq!(
+            Mock::verify(&verifying_context, &Default::default(), &()),
+            Ok(false)
+        );
+        assert_eq!(script.proofs(), 3);
+        assert_eq!(script.verifications(), 1);
+    }
+    /// Input Recording Proof System
+    #[cfg(test)]
+    struct Recorder;
+
+    #[cfg(test)]
+    impl ProofSystem for Recorder {
+        type Compiler = ();
+        type PublicParameters = ();
+        type ProvingContext = ();
+        type VerifyingContext = ();
+        type Input = alloc::vec::Vec<u8>;
+        type Proof = ();
+        type Error = ();
+
+        #[inline]
+        fn context_compiler() -> Self::Compiler {}
+
+        #[inline]
+        fn proof_compiler() -> Self::Compiler {}
+
+        #[inline]
+        fn compile<R>(_: &(), _: (), _: &mut R) -> Result<((), ()), ()>
+        where
+            R: CryptoRng + RngCore + ?Sized,
+        {
+            Ok(((), ()))
+        }
+
+        #[inline]
+        fn prove<R>(_: &(), _: (), _: &mut R) -> Result<(), ()>
+        where
+            R: CryptoRng + RngCore + ?Sized,
+        {
+            Ok(())
+        }
+
+        #[inline]
+        fn verify(_: &(), _: &Self::Input, _: &()) -> Result<bool, ()> {
+            Ok(true)
+        }
+    }
+
+    /// Checks that [`impl_input!`](crate::impl_input) extends the input in the declared order,
+    /// which need not be the order of the fields.
+    #[test]
+    fn impl_input_extends_in_declared_order() {
+        /// Input Byte
+        struct Byte(u8);
+
+        impl Input<Recorder> for Byte {
+            #[inline]
+            fn extend(&self, input: &mut alloc::vec::Vec<u8>) {
+                input.push(self.0);
+            }
+        }
+
+        /// Pair of Input Bytes
+        struct Pair {
+            /// First Byte
+            first: Byte,
+
+            /// Second Byte
+            second: Byte,
+        }
+
+        crate::impl_input! {
+            impl<> Input<P> for Pair
+            |pair| {
+                Byte => &pair.second,
+                Byte => &pair.first,
+            }
+        }
+
+        let pair = Pair {
+            first: Byte(1),
+            second: Byte(2),
+        };
+        let mut input = alloc::vec::Vec::new();
+        Input::<Recorder>::extend(&pair, &mut input);
+        assert_eq!(input, [2, 1]);
+    }
 }
@@ -0,0 +1,251 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Bit Vectors
+//!
+//! In this module, we define [`Bits`], a fixed-size vector of [`Bool`]s inside of a compiler, with
+//! the bitwise operations from [`Boolean`] and the [`Pack`] abstraction to convert between bit
+//! vectors and the values they encode. Bits are always stored in little-endian order, so the bit
+//! at index `0` is the least significant one.
+
+use crate::eclair::{
+    alloc::{Allocate, Allocator, Constant, Variable},
+    bool::{Assert, AssertEq, Bool, Boolean, ConditionalSelect},
+    cmp::PartialEq,
+    ops::{BitAnd, BitOr, BitXor, Not},
+    Has,
+};
+use core::{array, fmt::Debug, hash::Hash, slice};
+
+/// Bit Vector with `N` Bits Inside of the Compiler
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "Bool<COM>: Clone"),
+    Copy(bound = "Bool<COM>: Copy"),
+    Debug(bound = "Bool<COM>: Debug"),
+    Eq(bound = "Bool<COM>: Eq"),
+    Hash(bound = "Bool<COM>: Hash"),
+    PartialEq(bound = "Bool<COM>: core::cmp::PartialEq")
+)]
+pub struct Bits<const N: usize, COM = ()>(pub [Bool<COM>; N])
+where
+    COM: Has<bool> + ?Sized;
+
+impl<const N: usize, COM> Bits<N, COM>
+where
+    COM: Has<bool> + ?Sized,
+{
+    /// Builds a new [`Bits`] from the little-endian `bits`.
+    #[inline]
+    pub fn new(bits: [Bool<COM>; N]) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the underlying little-endian bits of `self`.
+    #[inline]
+    pub fn into_inner(self) -> [Bool<COM>; N] {
+        self.0
+    }
+
+    /// Returns the bit of `self` at `index`, where index `0` is the least significant bit.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&Bool<COM>> {
+        self.0.get(index)
+    }
+
+    /// Returns an iterator over the bits of `self` from the least significant to the most
+    /// significant.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, Bool<COM>> {
+        self.0.iter()
+    }
+
+    /// Packs `self` into a value of type `T` using `compiler`. See [`Pack::pack`] for more.
+    #[inline]
+    pub fn pack<T>(&self, compiler: &mut COM) -> T
+    where
+        COM: Pack<T, N>,
+    {
+        compiler.pack(self)
+    }
+
+    /// Unpacks `value` into its bits using `compiler`. See [`Pack::unpack`] for more.
+    #[inline]
+    pub fn unpack<T>(value: &T, compiler: &mut COM) -> Self
+    where
+        COM: Pack<T, N>,
+    {
+        compiler.unpack(value)
+    }
+}
+
+impl<const N: usize, COM> Bits<N, COM>
+where
+    COM: Has<bool> + ?Sized,
+    Bool<COM>: Boolean<COM>,
+{
+    /// Applies the bitwise operation `f` to `self` and `rhs`.
+    #[inline]
+    fn zip_with<F>(&self, rhs: &Self, compiler: &mut COM, mut f: F) -> Self
+    where
+        F: FnMut(Bool<COM>, Bool<COM>, &mut COM) -> Bool<COM>,
+    {
+        Self(array::from_fn(|i| {
+            f(self.0[i].clone(), rhs.0[i].clone(), compiler)
+        }))
+    }
+
+    /// Returns the bitwise negation of `self`.
+    #[inline]
+    pub fn not(&self, compiler: &mut COM) -> Self {
+        Self(array::from_fn(|i| self.0[i].clone().not(compiler)))
+    }
+
+    /// Returns the bitwise AND of `self` and `rhs`.
+    #[inline]
+    pub fn and(&self, rhs: &Self, compiler: &mut COM) -> Self {
+        self.zip_with(rhs, compiler, BitAnd::bitand)
+    }
+
+    /// Returns the bitwise OR of `self` and `rhs`.
+    #[inline]
+    pub fn or(&self, rhs: &Self, compiler: &mut COM) -> Self {
+        self.zip_with(rhs, compiler, BitOr::bitor)
+    }
+
+    /// Returns the bitwise XOR of `self` and `rhs`.
+    #[inline]
+    pub fn xor(&self, rhs: &Self, compiler: &mut COM) -> Self {
+        self.zip_with(rhs, compiler, BitXor::bitxor)
+    }
+}
+
+impl<const N: usize, COM> ConditionalSelect<COM> for Bits<N, COM>
+where
+    COM: Has<bool> + ?Sized,
+    Bool<COM>: ConditionalSelect<COM>,
+{
+    #[inline]
+    fn select(bit: &Bool<COM>, true_value: &Self, false_value: &Self, compiler: &mut COM) -> Self {
+        Self(array::from_fn(|i| {
+            Bool::<COM>::select(bit, &true_value.0[i], &false_value.0[i], compiler)
+        }))
+    }
+}
+
+impl<const N: usize, COM> PartialEq<Self, COM> for Bits<N, COM>
+where
+    COM: Has<bool>,
+    Bool<COM>: Constant<COM, Type = bool>
+        + BitAnd<Bool<COM>, COM, Output = Bool<COM>>
+        + PartialEq<Bool<COM>, COM>,
+{
+    #[inline]
+    fn eq(&self, rhs: &Self, compiler: &mut COM) -> Bool<COM> {
+        let mut are_equal = true.as_constant::<Bool<COM>>(compiler);
+        for (lhs, rhs) in self.iter().zip(rhs.iter()) {
+            are_equal = are_equal.bitand(lhs.eq(rhs, compiler), compiler);
+        }
+        are_equal
+    }
+
+    #[inline]
+    fn assert_equal(&self, rhs: &Self, compiler: &mut COM)
+    where
+        COM: Assert,
+    {
+        for (lhs, rhs) in self.iter().zip(rhs.iter()) {
+            compiler.assert_eq(lhs, rhs);
+        }
+    }
+}
+
+impl<const N: usize, COM> Constant<COM> for Bits<N, COM>
+where
+    COM: Has<bool> + ?Sized,
+    Bool<COM>: Constant<COM, Type = bool>,
+{
+    type Type = Bits<N>;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self(this.0.as_constant(compiler))
+    }
+}
+
+impl<const N: usize, M, COM> Variable<M, COM> for Bits<N, COM>
+where
+    COM: Has<bool> + ?Sized,
+    Bool<COM>: Variable<M, COM, Type = bool>,
+{
+    type Type = Bits<N>;
+
+    #[inline]
+    fn new_known(this: &Self::Type, compiler: &mut COM) -> Self {
+        Self(this.0.as_known(compiler))
+    }
+
+    #[inline]
+    fn new_unknown(compiler: &mut COM) -> Self {
+        Self(compiler.allocate_unknown())
+    }
+}
+
+/// Bit Packing
+///
+/// This `trait` converts between [`Bits`] and the values of type `T` they encode in little-endian
+/// order. Only values smaller than `2^N` have an `N`-bit representation.
+pub trait Pack<T, const N: usize>: Has<bool> {
+    /// Packs the little-endian `bits` into a value of type `T`.
+    fn pack(&mut self, bits: &Bits<N, Self>) -> T;
+
+    /// Unpacks `value` into its `N` little-endian bits, asserting that `value` is smaller than
+    /// `2^N`.
+    fn unpack(&mut self, value: &T) -> Bits<N, Self>;
+}
+
+/// Implements [`Pack`] for the native compiler and the given unsigned integer `$type`.
+macro_rules! impl_pack {
+    ($($type:tt),* $(,)?) => {
+        $(
+            impl<const N: usize> Pack<$type, N> for () {
+                #[inline]
+                fn pack(&mut self, bits: &Bits<N>) -> $type {
+                    assert!(
+                        N <= $type::BITS as usize,
+                        "The bit vector is larger than the integer type."
+                    );
+                    bits.iter()
+                        .rev()
+                        .fold(0, |value, bit| (value << 1) | (*bit as $type))
+                }
+
+                #[inline]
+                fn unpack(&mut self, value: &$type) -> Bits<N> {
+                    assert!(
+                        N >= $type::BITS as usize || value >> N == 0,
+                        "The value does not fit into the bit vector."
+                    );
+                    Bits(array::from_fn(|i| {
+                        i < $type::BITS as usize && (value >> i) & 1 == 1
+                    }))
+                }
+            }
+        )*
+    };
+}
+
+impl_pack!(u8, u16, u32, u64, u128);
@@ -20,13 +20,46 @@
 //! types. In this module, we define the access interfaces needed to simulate the [`bool`] type with
 //! [`Bool`].
 
-use crate::eclair::{cmp::PartialEq, Has, Type};
+use crate::eclair::{
+    cmp::PartialEq,
+    ops::{BitAnd, BitOr, BitXor, Not},
+    Has, Type,
+};
 use alloc::vec::Vec;
 use manta_util::{iter::IteratorExt, vec::VecExt};
 
 /// Boolean Type Inside of the Compiler
 pub type Bool<COM = ()> = Type<COM, bool>;
 
+/// Boolean Algebra
+///
+/// This `trait` collects the operations that every [`Bool`] type supports, so that boolean logic
+/// can be written once against `Bool<COM>: Boolean<COM>` and run natively and inside of any
+/// compiler. It has a blanket implementation for every type with the required operations.
+pub trait Boolean<COM = ()>:
+    Clone
+    + ConditionalSelect<COM>
+    + Not<COM, Output = Self>
+    + BitAnd<Self, COM, Output = Self>
+    + BitOr<Self, COM, Output = Self>
+    + BitXor<Self, COM, Output = Self>
+where
+    COM: Has<bool, Type = Self> + ?Sized,
+{
+}
+
+impl<B, COM> Boolean<COM> for B
+where
+    B: Clone
+        + ConditionalSelect<COM>
+        + Not<COM, Output = Self>
+        + BitAnd<Self, COM, Output = Self>
+        + BitOr<Self, COM, Output = Self>
+        + BitXor<Self, COM, Output = Self>,
+    COM: Has<bool, Type = Self> + ?Sized,
+{
+}
+
 /// Assertion
 pub trait Assert: Has<bool> {
     /// Asserts that `bit` reduces to `true`.
@@ -169,4 +202,17 @@ where
     /// Swaps `lhs` and `rhs` whenever `bit == true` and keeps them in the same order when `bit ==
     /// false`.
     fn swap(bit: &Bool<COM>, lhs: &Self, rhs: &Self, compiler: &mut COM) -> (Self, Self);
+
+    /// Swaps `lhs` and `rhs` like [`swap`](Self::swap), with a possibly cheaper but different
+    /// circuit.
+    ///
+    /// # Note
+    ///
+    /// Circuits whose keys are already published must keep using [`swap`](Self::swap), since
+    /// replacing it changes their constraint system. By default, this method is the same as
+    /// [`swap`](Self::swap).
+    #[inline]
+    fn swap_compact(bit: &Bool<COM>, lhs: &Self, rhs: &Self, compiler: &mut COM) -> (Self, Self) {
+        Self::swap(bit, lhs, rhs, compiler)
+    }
 }
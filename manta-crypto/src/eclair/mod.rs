@@ -19,6 +19,7 @@
 use manta_util::{create_seal, seal};
 
 pub mod alloc;
+pub mod bits;
 pub mod bool;
 pub mod cmp;
 pub mod execution;
@@ -21,7 +21,6 @@
 //! encryption scheme inlines this complexity into the encryption interfaces.
 
 use crate::{
-    constraint::{HasInput, Input},
     eclair::{
         self,
         alloc::{
@@ -36,6 +35,7 @@ use crate::{
         self, CiphertextType, Decrypt, DecryptedPlaintextType, DecryptionKeyType, Derive, Encrypt,
         EncryptedMessage, EncryptionKeyType, HeaderType, PlaintextType, RandomnessType,
     },
+    impl_input,
     key::agreement::{
         self, EphemeralPublicKey, EphemeralPublicKeyType, EphemeralSecretKey,
         EphemeralSecretKeyType, PublicKeyType, SecretKeyType,
@@ -304,6 +304,27 @@ where
     }
 }
 
+impl<K, E> Decode for Ciphertext<K, E>
+where
+    K: EphemeralPublicKeyType,
+    K::EphemeralPublicKey: Decode,
+    E: CiphertextType,
+    E::Ciphertext: Decode,
+{
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Ok(Self::new(
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+        ))
+    }
+}
+
 impl<K, E> Encode for Ciphertext<K, E>
 where
     K: EphemeralPublicKeyType,
@@ -322,16 +343,12 @@ where
     }
 }
 
-impl<K, E, P> Input<P> for Ciphertext<K, E>
-where
-    K: EphemeralPublicKeyType,
-    E: CiphertextType,
-    P: HasInput<K::EphemeralPublicKey> + HasInput<E::Ciphertext> + ?Sized,
-{
-    #[inline]
-    fn extend(&self, input: &mut P::Input) {
-        P::extend(input, &self.ephemeral_public_key);
-        P::extend(input, &self.ciphertext);
+impl_input! {
+    impl<K, E> Input<P> for Ciphertext<K, E>
+    where [K: EphemeralPublicKeyType, E: CiphertextType]
+    |ciphertext| {
+        K::EphemeralPublicKey => &ciphertext.ephemeral_public_key,
+        E::Ciphertext => &ciphertext.ciphertext,
     }
 }
 
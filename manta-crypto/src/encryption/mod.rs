@@ -21,7 +21,7 @@
 //! [`Decrypt`] `trait`s for more.
 
 use crate::{
-    constraint::{HasInput, Input, ProofSystem},
+    constraint::{Input, ProofSystem},
     eclair::{
         self,
         alloc::{
@@ -32,10 +32,11 @@ use crate::{
         ops::BitAnd,
         Has,
     },
+    impl_input,
     rand::{Rand, RngCore, Sample},
 };
 use core::{fmt::Debug, hash::Hash, marker::PhantomData};
-use manta_util::codec::{Encode, Write};
+use manta_util::codec::{Decode, DecodeError, Encode, Read, Write};
 
 #[cfg(feature = "serde")]
 use manta_util::serde::{Deserialize, Serialize};
@@ -132,6 +133,19 @@ where
     }
 }
 
+impl Decode for EmptyHeader {
+    type Error = core::convert::Infallible;
+
+    #[inline]
+    fn decode<R>(reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        let _ = reader;
+        Ok(Self::default())
+    }
+}
+
 impl Encode for EmptyHeader {
     #[inline]
     fn encode<W>(&self, writer: W) -> Result<(), W::Error>
@@ -842,6 +856,26 @@ where
     }
 }
 
+impl<E> Decode for EncryptedMessage<E>
+where
+    E: CiphertextType + HeaderType,
+    E::Header: Decode,
+    E::Ciphertext: Decode,
+{
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Ok(Self::new(
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+        ))
+    }
+}
+
 impl<E> Encode for EncryptedMessage<E>
 where
     E: CiphertextType + HeaderType,
@@ -859,15 +893,12 @@ where
     }
 }
 
-impl<E, P> Input<P> for EncryptedMessage<E>
-where
-    E: CiphertextType + HeaderType,
-    P: HasInput<E::Header> + HasInput<E::Ciphertext> + ?Sized,
-{
-    #[inline]
-    fn extend(&self, input: &mut P::Input) {
-        P::extend(input, &self.header);
-        P::extend(input, &self.ciphertext);
+impl_input! {
+    impl<E> Input<P> for EncryptedMessage<E>
+    where [E: CiphertextType + HeaderType]
+    |message| {
+        E::Header => &message.header,
+        E::Ciphertext => &message.ciphertext,
     }
 }
 
@@ -35,7 +35,9 @@ pub mod merkle_tree;
 pub mod password;
 pub mod permutation;
 pub mod rand;
+pub mod sigma;
 pub mod signature;
+pub mod transcript;
 
 #[cfg(feature = "arkworks")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "arkworks")))]
@@ -276,6 +276,110 @@ where
     pub fn push(&mut self, leaf: &Leaf<C>) -> bool {
         self.forest.get_tree_mut(leaf).push(&self.parameters, leaf)
     }
+
+    /// Forks `self`, returning a [`ForestOverlay`] which pushes leaves without modifying `self`.
+    #[inline]
+    pub fn fork(&self) -> ForestOverlay<'_, C, F> {
+        ForestOverlay::new(self)
+    }
+
+    /// Replaces the trees of `self` with the `trees` committed by a [`ForestOverlay`] of `self`.
+    ///
+    /// See [`ForestOverlay::commit`] for more.
+    #[inline]
+    pub fn merge<I>(&mut self, trees: I)
+    where
+        I: IntoIterator<Item = (C::Index, F::Tree)>,
+    {
+        for (index, tree) in trees {
+            *self.forest.get_mut(index) = tree;
+        }
+    }
+}
+
+/// Merkle Forest Overlay
+///
+/// Copy-on-write view of a [`MerkleForest`] which reads through to the trees of the base forest
+/// and only clones a tree the first time a leaf is pushed into it. An overlay is built with
+/// [`MerkleForest::fork`] and is either [`commit`](Self::commit)ted or
+/// [`discard`](Self::discard)ed.
+#[derive(derivative::Derivative)]
+#[derivative(Debug(bound = "MerkleForest<C, F>: Debug, C::Index: Debug, F::Tree: Debug"))]
+pub struct ForestOverlay<'f, C, F>
+where
+    C: Configuration + ?Sized,
+    F: Forest<C>,
+{
+    /// Base Forest
+    base: &'f MerkleForest<C, F>,
+
+    /// Modified Trees
+    trees: Vec<(C::Index, F::Tree)>,
+}
+
+impl<'f, C, F> ForestOverlay<'f, C, F>
+where
+    C: Configuration + ?Sized,
+    F: Forest<C>,
+{
+    /// Builds a new [`ForestOverlay`] on top of `base` with no modified trees.
+    #[inline]
+    fn new(base: &'f MerkleForest<C, F>) -> Self {
+        Self {
+            base,
+            trees: Vec::new(),
+        }
+    }
+
+    /// Returns the base forest of `self`.
+    #[inline]
+    pub fn base(&self) -> &'f MerkleForest<C, F> {
+        self.base
+    }
+
+    /// Returns a shared reference to the tree at the given `index` as seen by `self`.
+    ///
+    /// # Panics
+    ///
+    /// This method is allowed to panic if `index` is out-of-bounds.
+    #[inline]
+    pub fn get(&self, index: C::Index) -> &F::Tree {
+        match self.trees.iter().find(|(i, _)| *i == index) {
+            Some((_, tree)) => tree,
+            _ => self.base.forest.get(index),
+        }
+    }
+
+    /// Inserts `leaf` at the next available leaf node of the tree corresponding with `leaf`,
+    /// cloning the tree from the base forest if it was not modified yet. Returns `false` if the
+    /// leaf could not be inserted because its tree has exhausted its capacity.
+    #[inline]
+    pub fn push(&mut self, leaf: &Leaf<C>) -> bool
+    where
+        F::Tree: Clone,
+    {
+        let index = C::tree_index(leaf);
+        let position = match self.trees.iter().position(|(i, _)| *i == index) {
+            Some(position) => position,
+            _ => {
+                let tree = self.base.forest.get_tree(leaf).clone();
+                self.trees.push((index, tree));
+                self.trees.len() - 1
+            }
+        };
+        self.trees[position].1.push(&self.base.parameters, leaf)
+    }
+
+    /// Finishes the overlay, returning the trees modified by `self` with their indices so that
+    /// they can replace the trees of the base forest with [`MerkleForest::merge`].
+    #[inline]
+    pub fn commit(self) -> Vec<(C::Index, F::Tree)> {
+        self.trees
+    }
+
+    /// Drops the trees modified by `self`, leaving the base forest untouched.
+    #[inline]
+    pub fn discard(self) {}
 }
 
 impl<C, F> AsMut<F> for MerkleForest<C, F>
@@ -539,6 +643,12 @@ where
             __: PhantomData,
         }
     }
+
+    /// Returns the underlying array of trees.
+    #[inline]
+    pub fn into_inner(self) -> BoxArray<T, N> {
+        self.array
+    }
 }
 
 macro_rules! impl_from_items_and_witnesses {
@@ -572,6 +682,34 @@ macro_rules! impl_from_items_and_witnesses {
                         }),
                 ))
             }
+
+            /// Builds a new [`TreeArray`] from the `frontier` of every tree and the `leaves` and
+            /// `paths` which should stay provable, without checking that the paths are consistent
+            /// with each other.
+            #[inline]
+            pub fn from_frontier_unchecked(
+                parameters: &Parameters<C>,
+                frontier: Vec<Option<(Leaf<C>, Path<C>)>>,
+                leaves: Vec<(Leaf<C>, Path<C>)>,
+            ) -> Self {
+                let mut detached = Vec::<Vec<_>>::default();
+                detached.resize_with(N, Default::default);
+                for (leaf, path) in leaves {
+                    detached[C::tree_index(&leaf).into()].push((parameters.digest(&leaf), path));
+                }
+                <$tree_array>::new(BoxArray::from_iter(
+                    frontier
+                        .into_iter()
+                        .zip(detached.into_iter())
+                        .map(|(current, detached)| {
+                            <$tree_variant>::from_frontier_unchecked(
+                                parameters,
+                                current.map(|(leaf, path)| (parameters.digest(&leaf), path)),
+                                detached,
+                            )
+                        }),
+                ))
+            }
         }
 
         impl<C, const N: usize> FromItemsAndWitnesses for $forest
@@ -597,6 +735,19 @@ macro_rules! impl_from_items_and_witnesses {
                 )
             }
 
+            #[inline]
+            fn from_frontier_and_witnesses(
+                model: &Self::Model,
+                frontier: Vec<Option<(Self::Item, Self::Witness)>>,
+                items: Vec<(Self::Item, Self::Witness)>,
+            ) -> Self {
+                assert_eq!(frontier.len(), N);
+                Self::from_forest(
+                    <$tree_array>::from_frontier_unchecked(model, frontier, items),
+                    model.clone(),
+                )
+            }
+
             #[inline]
             fn sort_items(items: Vec<Self::Item>) -> Vec<Vec<Self::Item>> {
                 let mut result = Vec::<Vec<Self::Item>>::default();
@@ -251,11 +251,13 @@ impl Default for BaseContribution {
     serde(
         bound(
             deserialize = r"
+                LeafDigest<C>: Deserialize<'de>,
                 InnerDigest<C>: Deserialize<'de>,
                 M: Deserialize<'de>,
                 L: Deserialize<'de>,
             ",
             serialize = r"
+                LeafDigest<C>: Serialize,
                 InnerDigest<C>: Serialize,
                 M: Serialize,
                 L: Serialize,
@@ -267,12 +269,14 @@ impl Default for BaseContribution {
 )]
 #[derive(derivative::Derivative)]
 #[derivative(
-    Clone(bound = "InnerDigest<C>: Clone, M: Clone, L: Clone"),
-    Debug(bound = "InnerDigest<C>: Debug, M: Debug, L: Debug"),
+    Clone(bound = "LeafDigest<C>: Clone, InnerDigest<C>: Clone, M: Clone, L: Clone"),
+    Debug(bound = "LeafDigest<C>: Debug, InnerDigest<C>: Debug, M: Debug, L: Debug"),
     Default(bound = "InnerDigest<C>: Default, L: Default"),
-    Eq(bound = "InnerDigest<C>: Eq, M: Eq, L: Eq"),
-    Hash(bound = "InnerDigest<C>: Hash, M: Hash, L: Hash"),
-    PartialEq(bound = "InnerDigest<C>: PartialEq, M: PartialEq, L: PartialEq")
+    Eq(bound = "LeafDigest<C>: Eq, InnerDigest<C>: Eq, M: Eq, L: Eq"),
+    Hash(bound = "LeafDigest<C>: Hash, InnerDigest<C>: Hash, M: Hash, L: Hash"),
+    PartialEq(
+        bound = "LeafDigest<C>: PartialEq, InnerDigest<C>: PartialEq, M: PartialEq, L: PartialEq"
+    )
 )]
 struct Branch<C, M = BTreeMap<C>, L = LeafVec<C>>
 where
@@ -584,7 +588,9 @@ where
 /// Merkle Tree Fork
 #[derive(derivative::Derivative)]
 #[derivative(
-    Debug(bound = "P::Weak: Debug, L: Debug, InnerDigest<C>: Debug, M: Debug"),
+    Debug(
+        bound = "P::Weak: Debug, L: Debug, LeafDigest<C>: Debug, InnerDigest<C>: Debug, M: Debug"
+    ),
     Default(bound = "L: Default, InnerDigest<C>: Default")
 )]
 pub struct Fork<C, T, P = pointer::SingleThreaded, M = BTreeMap<C>, L = LeafVec<C>>
@@ -817,12 +823,14 @@ where
             deserialize = r"
                 T: Deserialize<'de>,
                 L: Deserialize<'de>,
+                LeafDigest<C>: Deserialize<'de>,
                 InnerDigest<C>: Deserialize<'de>,
                 M: Deserialize<'de>,
             ",
             serialize = r"
                 T: Serialize,
                 L: Serialize,
+                LeafDigest<C>: Serialize,
                 InnerDigest<C>: Serialize,
                 M: Serialize,
             ",
@@ -833,11 +841,13 @@ where
 )]
 #[derive(derivative::Derivative)]
 #[derivative(
-    Clone(bound = "T: Clone, L: Clone, InnerDigest<C>: Clone, M: Clone"),
-    Debug(bound = "T: Debug, L: Debug, InnerDigest<C>: Debug, M: Debug"),
-    Eq(bound = "T: Eq, L: Eq, InnerDigest<C>: Eq, M: Eq"),
-    Hash(bound = "T: Hash, L: Hash, InnerDigest<C>: Hash, M: Hash"),
-    PartialEq(bound = "T: PartialEq, L: PartialEq, InnerDigest<C>: PartialEq, M: PartialEq")
+    Clone(bound = "T: Clone, L: Clone, LeafDigest<C>: Clone, InnerDigest<C>: Clone, M: Clone"),
+    Debug(bound = "T: Debug, L: Debug, LeafDigest<C>: Debug, InnerDigest<C>: Debug, M: Debug"),
+    Eq(bound = "T: Eq, L: Eq, LeafDigest<C>: Eq, InnerDigest<C>: Eq, M: Eq"),
+    Hash(bound = "T: Hash, L: Hash, LeafDigest<C>: Hash, InnerDigest<C>: Hash, M: Hash"),
+    PartialEq(
+        bound = "T: PartialEq, L: PartialEq, LeafDigest<C>: PartialEq, InnerDigest<C>: PartialEq, M: PartialEq"
+    )
 )]
 pub struct ForkedTree<C, T, M = BTreeMap<C>, L = LeafVec<C>>
 where
@@ -1013,6 +1023,22 @@ where
             parameters,
         )
     }
+
+    /// Builds a new [`ForkedTree`] from the `current` leaf digest and its path, and the `detached`
+    /// leaf digests and their paths, without checking that the paths are consistent with each
+    /// other and that the path of the `current` leaf digest is a [`CurrentPath`]. See
+    /// [`Partial::from_frontier_unchecked`] for more.
+    #[inline]
+    pub fn from_frontier_unchecked(
+        parameters: &Parameters<C>,
+        current: Option<(LeafDigest<C>, Path<C>)>,
+        detached: Vec<(LeafDigest<C>, Path<C>)>,
+    ) -> Self {
+        Self::new(
+            Partial::from_frontier_unchecked(parameters, current, detached),
+            parameters,
+        )
+    }
 }
 
 impl<C, T, M, L> Tree<C> for ForkedTree<C, T, M, L>
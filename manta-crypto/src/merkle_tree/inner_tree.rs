@@ -1087,6 +1087,24 @@ where
         self.inner_tree.path(leaf_index)
     }
 
+    /// Inserts the inner digests of `path` which are missing from `self`, without checking that
+    /// `path` is consistent with the inner digests already stored in `self`. Sentinel values in
+    /// `path` are skipped.
+    #[inline]
+    pub fn extend_with_path_unchecked(&mut self, path: InnerPath<C>)
+    where
+        InnerDigest<C>: PartialEq,
+    {
+        for (node, digest) in InnerNodeIter::from_leaf::<C>(path.leaf_index).zip(path.path) {
+            let index = node.sibling().map_index();
+            if self.inner_tree.map_get(index).is_none()
+                && &digest != self.inner_tree.sentinel_source.get(index)
+            {
+                self.inner_tree.map.set(index, digest);
+            }
+        }
+    }
+
     /// Sets the starting leaf index in `self` to `default`.
     #[inline]
     pub fn reset_starting_leaf_index(&mut self, default: Node) {
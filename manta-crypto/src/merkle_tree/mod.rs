@@ -35,6 +35,10 @@ pub mod partial;
 pub mod path;
 pub mod single_path;
 
+#[cfg(all(feature = "parking_lot", feature = "std"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "parking_lot", feature = "std"))))]
+pub mod sharded;
+
 #[cfg(feature = "test")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
 pub mod test;
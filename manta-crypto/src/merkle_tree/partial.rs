@@ -41,8 +41,13 @@ pub type PartialMerkleTree<C, M = BTreeMap<C>> = MerkleTree<C, Partial<C, M>>;
     derive(Deserialize, Serialize),
     serde(
         bound(
-            deserialize = "L: Deserialize<'de>, InnerDigest<C>: Deserialize<'de>, M: Deserialize<'de>",
-            serialize = "L: Serialize, InnerDigest<C>: Serialize, M: Serialize"
+            deserialize = r"
+                L: Deserialize<'de>,
+                LeafDigest<C>: Deserialize<'de>,
+                InnerDigest<C>: Deserialize<'de>,
+                M: Deserialize<'de>,
+            ",
+            serialize = "L: Serialize, LeafDigest<C>: Serialize, InnerDigest<C>: Serialize, M: Serialize"
         ),
         crate = "manta_util::serde",
         deny_unknown_fields
@@ -50,12 +55,14 @@ pub type PartialMerkleTree<C, M = BTreeMap<C>> = MerkleTree<C, Partial<C, M>>;
 )]
 #[derive(derivative::Derivative)]
 #[derivative(
-    Clone(bound = "L: Clone, InnerDigest<C>: Clone, M: Clone"),
-    Debug(bound = "L: Debug, InnerDigest<C>: Debug, M: Debug"),
+    Clone(bound = "L: Clone, LeafDigest<C>: Clone, InnerDigest<C>: Clone, M: Clone"),
+    Debug(bound = "L: Debug, LeafDigest<C>: Debug, InnerDigest<C>: Debug, M: Debug"),
     Default(bound = "L: Default, InnerDigest<C>: Default, M: Default"),
-    Eq(bound = "L: Eq, InnerDigest<C>: Eq, M: Eq"),
-    Hash(bound = "L: Hash, InnerDigest<C>: Hash, M: Hash"),
-    PartialEq(bound = "L: PartialEq, InnerDigest<C>: PartialEq, M: PartialEq")
+    Eq(bound = "L: Eq, LeafDigest<C>: Eq, InnerDigest<C>: Eq, M: Eq"),
+    Hash(bound = "L: Hash, LeafDigest<C>: Hash, InnerDigest<C>: Hash, M: Hash"),
+    PartialEq(
+        bound = "L: PartialEq, LeafDigest<C>: PartialEq, InnerDigest<C>: PartialEq, M: PartialEq"
+    )
 )]
 pub struct Partial<C, M = BTreeMap<C>, L = LeafVec<C>>
 where
@@ -68,6 +75,14 @@ where
 
     /// Inner Digests
     inner_digests: PartialInnerTree<C, M>,
+
+    /// Detached Leaves
+    ///
+    /// Leaves before the starting leaf index which are still provable, stored as their index,
+    /// their leaf digest and the leaf digest of their sibling. See
+    /// [`from_frontier_unchecked`](Self::from_frontier_unchecked) for more.
+    #[cfg_attr(feature = "serde", serde(default))]
+    detached_leaves: Vec<(usize, LeafDigest<C>, LeafDigest<C>)>,
 }
 
 impl<C, M, L> Partial<C, M, L>
@@ -83,6 +98,7 @@ where
         Self {
             leaf_map,
             inner_digests,
+            detached_leaves: Vec::new(),
         }
     }
 
@@ -131,6 +147,63 @@ where
         }
     }
 
+    /// Builds a new [`Partial`] from the `current` leaf digest and its path, and the `detached`
+    /// leaf digests and their paths, without checking that the paths are consistent with each
+    /// other and that the path of the `current` leaf digest is a [`CurrentPath`].
+    ///
+    /// # Frontier Trees
+    ///
+    /// The tree only stores the frontier of the merkle tree given by the `current` leaf, and the
+    /// `detached` leaves which come before it. The detached leaves stay provable as new leaves are
+    /// appended to the tree, so the storage of the tree grows with the number of detached leaves
+    /// and the height of the tree instead of with the number of leaves. All the paths must be
+    /// computed against the same merkle root.
+    #[inline]
+    pub fn from_frontier_unchecked(
+        parameters: &Parameters<C>,
+        current: Option<(LeafDigest<C>, Path<C>)>,
+        detached: Vec<(LeafDigest<C>, Path<C>)>,
+    ) -> Self
+    where
+        M: Default,
+        LeafDigest<C>: Clone,
+        InnerDigest<C>: Clone + Default + PartialEq,
+    {
+        let (leaf_digest, path) = match current {
+            Some(current) => current,
+            _ => return Self::new_unchecked(LeafMap::from_vec(Vec::new()), Default::default()),
+        };
+        let leaf_index = path.inner_path.leaf_index;
+        let base = leaf_index.join_leaves(parameters, &leaf_digest, &path.sibling_digest);
+        let mut leaf_digests = Vec::with_capacity(2);
+        if leaf_index.is_right() {
+            leaf_digests.push(path.sibling_digest.clone());
+        }
+        leaf_digests.push(leaf_digest);
+        let mut partial_tree = Self::new_unchecked(
+            LeafMap::from_vec(leaf_digests),
+            PartialInnerTree::from_current(
+                parameters,
+                base,
+                CurrentPath::from_path_unchecked(path).inner_path,
+            ),
+        );
+        let starting_leaf_index = partial_tree.starting_leaf_index();
+        for (leaf_digest, path) in detached {
+            let index = path.inner_path.leaf_index.0;
+            if index >= starting_leaf_index || partial_tree.detached_leaf(index).is_some() {
+                continue;
+            }
+            partial_tree
+                .detached_leaves
+                .push((index, leaf_digest, path.sibling_digest));
+            partial_tree
+                .inner_digests
+                .extend_with_path_unchecked(path.inner_path);
+        }
+        partial_tree
+    }
+
     /// Returns the leaf digests currently stored in the merkle tree.
     ///
     /// # Note
@@ -160,6 +233,14 @@ where
         self.leaf_map.into_leaf_digests()
     }
 
+    /// Returns the detached leaf at `index`, if it is stored in the tree.
+    #[inline]
+    fn detached_leaf(&self, index: usize) -> Option<&(usize, LeafDigest<C>, LeafDigest<C>)> {
+        self.detached_leaves
+            .iter()
+            .find(move |(i, _, _)| *i == index)
+    }
+
     /// Returns the starting leaf [`Node`] for this tree.
     #[inline]
     pub fn starting_leaf_node(&self) -> Node {
@@ -193,22 +274,41 @@ where
     /// Returns the leaf digest at the given `index` in the tree.
     #[inline]
     pub fn leaf_digest(&self, index: usize) -> Option<&LeafDigest<C>> {
-        self.leaf_map.get(index - self.starting_leaf_index())
+        let starting_leaf_index = self.starting_leaf_index();
+        if index < starting_leaf_index {
+            self.detached_leaf(index)
+                .map(|(_, leaf_digest, _)| leaf_digest)
+        } else {
+            self.leaf_map.get(index - starting_leaf_index)
+        }
     }
 
     /// Returns the position of `leaf_digest` in the tree.
     #[inline]
-    pub fn position(&self, leaf_digest: &LeafDigest<C>) -> Option<usize> {
+    pub fn position(&self, leaf_digest: &LeafDigest<C>) -> Option<usize>
+    where
+        LeafDigest<C>: PartialEq,
+    {
         self.leaf_map
             .position(leaf_digest)
             .map(move |i| i + self.starting_leaf_index())
+            .or_else(|| {
+                self.detached_leaves
+                    .iter()
+                    .find(|(_, detached, _)| detached == leaf_digest)
+                    .map(|(index, _, _)| *index)
+            })
     }
 
     /// Returns the sibling leaf node to `index`.
     #[inline]
     pub fn get_leaf_sibling(&self, index: Node) -> Option<&LeafDigest<C>> {
-        self.leaf_map
-            .get((index - self.starting_leaf_index()).sibling().0)
+        let starting_leaf_index = self.starting_leaf_index();
+        if index.0 < starting_leaf_index {
+            self.detached_leaf(index.0).map(|(_, _, sibling)| sibling)
+        } else {
+            self.leaf_map.get((index - starting_leaf_index).sibling().0)
+        }
     }
 
     /// Returns an owned sibling leaf node to `index`.
@@ -385,31 +485,46 @@ where
         // reference of `self` in the loop and an immutable one in the `filter`
         // method.
         if let Some(current_index) = self.leaf_map.current_index() {
+            let starting_leaf_index = self.starting_leaf_index();
             let marked_indices = (0..current_index)
                 .filter(|index| self.leaf_map.is_marked(*index).unwrap_or(false))
                 .collect::<Vec<_>>();
             for index in marked_indices {
-                self.remove_path_at_index(index);
+                self.remove_path_at_index(starting_leaf_index + index);
             }
         }
     }
 
+    /// Checks whether the leaf at `index` is either already deleted or marked for removal. Leaves
+    /// before the starting leaf index are deleted unless they are detached leaves.
+    #[inline]
+    fn is_marked_or_removed(&self, index: usize) -> bool {
+        let starting_leaf_index = self.starting_leaf_index();
+        if index < starting_leaf_index {
+            self.detached_leaf(index).is_none()
+        } else {
+            self.leaf_map
+                .is_marked_or_removed(index - starting_leaf_index)
+        }
+    }
+
     /// Removes the [`Path`] above the leaf at `index`. Returns `false` if the leaf
     /// or its sibling are not marked for removal, or if either of them is the current leaf.
     #[inline]
     fn remove_path_at_index(&mut self, index: usize) -> bool {
         let sibling_index = Node(index).sibling().0;
-        if self.leaf_map.is_marked_or_removed(sibling_index)
-            && self.leaf_map.is_marked_or_removed(index)
-        {
-            self.leaf_map.remove(index);
-            self.leaf_map.remove(sibling_index);
+        if self.is_marked_or_removed(sibling_index) && self.is_marked_or_removed(index) {
+            let starting_leaf_index = self.starting_leaf_index();
+            if index >= starting_leaf_index {
+                self.leaf_map.remove(index - starting_leaf_index);
+                self.leaf_map.remove(sibling_index - starting_leaf_index);
+            }
             for inner_node in InnerNodeIter::from_leaf::<C>(Node(index)) {
                 let sibling_node = inner_node.sibling();
                 self.inner_digests.remove(sibling_node.map_index());
                 if sibling_node
                     .leaf_nodes(C::HEIGHT)
-                    .any(|x| !self.leaf_map.is_marked_or_removed(x.0))
+                    .any(|x| !self.is_marked_or_removed(x.0))
                 {
                     break;
                 }
@@ -424,11 +539,23 @@ where
     /// above it.
     #[inline]
     pub fn remove_path(&mut self, index: usize) -> bool {
-        match self.leaf_map.current_index() {
-            Some(current_index) if index <= current_index => (),
-            _ => return false,
-        };
-        self.leaf_map.mark(index);
+        let starting_leaf_index = self.starting_leaf_index();
+        if index < starting_leaf_index {
+            match self
+                .detached_leaves
+                .iter()
+                .position(move |(i, _, _)| *i == index)
+            {
+                Some(position) => self.detached_leaves.swap_remove(position),
+                _ => return false,
+            };
+        } else {
+            match self.leaf_map.current_index() {
+                Some(current_index) if index - starting_leaf_index <= current_index => (),
+                _ => return false,
+            };
+            self.leaf_map.mark(index - starting_leaf_index);
+        }
         self.remove_path_at_index(index)
     }
 }
@@ -473,9 +600,9 @@ where
     where
         F: FnOnce() -> Option<LeafDigest<C>>,
     {
-        let len = self.len();
+        let index = self.leaf_map.len();
         let result = self.maybe_push_digest(parameters, leaf_digest);
-        self.leaf_map.mark(len);
+        self.leaf_map.mark(index);
         result
     }
 
@@ -488,7 +615,7 @@ where
     where
         F: FnOnce() -> Vec<LeafDigest<C>>,
     {
-        let len = self.len();
+        let len = self.leaf_map.len();
         let (result, number_of_insertions) =
             self.batch_maybe_push_digest(parameters, leaf_digests)?;
         for index in len..len + number_of_insertions {
@@ -508,7 +635,7 @@ where
     C: Configuration + ?Sized,
     M: Default + InnerMap<C>,
     L: LeafMap<C>,
-    LeafDigest<C>: Clone + Default,
+    LeafDigest<C>: Clone + Default + PartialEq,
     InnerDigest<C>: Clone + Default + PartialEq,
 {
     #[inline]
@@ -540,7 +667,7 @@ where
         if index > 0 && index >= length {
             return Err(PathError::IndexTooLarge { length });
         }
-        if index < self.starting_leaf_index() {
+        if index < self.starting_leaf_index() && self.detached_leaf(index).is_none() {
             return Err(PathError::MissingPath);
         }
         Ok(self.path_unchecked(index))
@@ -992,8 +992,7 @@ pub mod constraint {
         where
             LeafDigest<C, COM>: ConditionalSwap<COM>,
         {
-            let (lhs, rhs) =
-                ConditionalSwap::swap(&self.leaf_index, leaf_digest, sibling_digest, compiler);
+            let (lhs, rhs) = Self::swap(&self.leaf_index, leaf_digest, sibling_digest, compiler);
             self.root_from_base(
                 parameters,
                 parameters.join_leaves_with(&lhs, &rhs, compiler),
@@ -1001,6 +1000,21 @@ pub mod constraint {
             )
         }
 
+        /// Swaps `lhs` and `rhs` whenever `bit == true`, using
+        /// [`swap_compact`](ConditionalSwap::swap_compact) if
+        /// [`COMPACT_SWAP`](crate::merkle_tree::HashConfiguration::COMPACT_SWAP) is set.
+        #[inline]
+        fn swap<T>(bit: &Bool<COM>, lhs: &T, rhs: &T, compiler: &mut COM) -> (T, T)
+        where
+            T: ConditionalSwap<COM>,
+        {
+            if C::COMPACT_SWAP {
+                T::swap_compact(bit, lhs, rhs, compiler)
+            } else {
+                T::swap(bit, lhs, rhs, compiler)
+            }
+        }
+
         /// Returns the folding algorithm for a path with `index` as its starting index.
         #[inline]
         fn fold_fn<'d>(
@@ -1012,7 +1026,7 @@ pub mod constraint {
             (&'d Bool<COM>, &'d InnerDigest<C, COM>),
         ) -> InnerDigest<C, COM> {
             move |acc, (b, d)| {
-                let (lhs, rhs) = ConditionalSwap::swap(b, &acc, d, compiler);
+                let (lhs, rhs) = Self::swap(b, &acc, d, compiler);
                 parameters.join_with(&lhs, &rhs, compiler)
             }
         }
@@ -0,0 +1,333 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sharded Merkle Forests
+//!
+//! A [`MerkleForest`] can only be mutated through an exclusive reference, so a node which applies
+//! blocks to its accumulator has to stop generating witnesses while it does so. The
+//! [`ShardedMerkleForest`] puts every tree of a [`TreeArray`] forest behind its own lock instead.
+//! Insertions only lock the tree that the leaf belongs to, so witnesses for leaves in the other
+//! trees can be generated concurrently, and readers of the same tree only wait for the insertions
+//! into that tree.
+
+use crate::{
+    accumulator::MembershipProof,
+    merkle_tree::{
+        forest::{Configuration, FixedIndex, MerkleForest, TreeArray},
+        tree::{Leaf, Parameters, Root, Tree},
+        InnerDigest, WithProofs,
+    },
+};
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use manta_util::BoxArray;
+use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Sharded Merkle Forest
+///
+/// Every tree of this forest is guarded by its own [`RwLock`], so all the methods only take a
+/// shared reference to the forest and can be called from many threads at once.
+pub struct ShardedMerkleForest<C, T, const N: usize>
+where
+    C: Configuration + ?Sized,
+    C::Index: FixedIndex<N>,
+    T: Tree<C>,
+{
+    /// Merkle Forest Parameters
+    parameters: Parameters<C>,
+
+    /// Locked Trees
+    shards: BoxArray<RwLock<T>, N>,
+}
+
+impl<C, T, const N: usize> ShardedMerkleForest<C, T, N>
+where
+    C: Configuration + ?Sized,
+    C::Index: FixedIndex<N>,
+    T: Tree<C>,
+{
+    /// Builds a new [`ShardedMerkleForest`] of empty trees from `parameters`.
+    #[inline]
+    pub fn new(parameters: Parameters<C>) -> Self {
+        Self {
+            shards: (0..N).map(|_| RwLock::new(T::new(&parameters))).collect(),
+            parameters,
+        }
+    }
+
+    /// Builds a new [`ShardedMerkleForest`] from the trees of `forest`.
+    #[inline]
+    pub fn from_forest(forest: MerkleForest<C, TreeArray<C, T, N>>) -> Self {
+        Self {
+            shards: forest
+                .forest
+                .into_inner()
+                .into_iter()
+                .map(RwLock::new)
+                .collect(),
+            parameters: forest.parameters,
+        }
+    }
+
+    /// Converts `self` back into a [`MerkleForest`] with exclusive access to all of its trees.
+    #[inline]
+    pub fn into_forest(self) -> MerkleForest<C, TreeArray<C, T, N>> {
+        MerkleForest::from_forest(
+            TreeArray::from(
+                self.shards
+                    .into_iter()
+                    .map(RwLock::into_inner)
+                    .collect::<BoxArray<_, N>>()
+                    .0,
+            ),
+            self.parameters,
+        )
+    }
+
+    /// Returns a shared reference to the parameters used by this merkle forest.
+    #[inline]
+    pub fn parameters(&self) -> &Parameters<C> {
+        &self.parameters
+    }
+
+    /// Returns the number of items in this merkle forest.
+    ///
+    /// Each tree is locked in turn, so the result may not reflect insertions which happen
+    /// concurrently with this call.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.read().len()).sum()
+    }
+
+    /// Returns `true` if this merkle forest is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|shard| shard.read().is_empty())
+    }
+
+    /// Returns the lock guarding the tree where `leaf` should be inserted.
+    #[inline]
+    fn shard(&self, leaf: &Leaf<C>) -> &RwLock<T> {
+        &self.shards[C::tree_index(leaf).into()]
+    }
+
+    /// Locks the tree at `index` for reading, blocking until there are no writers on that tree.
+    #[inline]
+    pub fn read(&self, index: C::Index) -> RwLockReadGuard<'_, T> {
+        self.shards[index.into()].read()
+    }
+
+    /// Locks the tree at `index` for writing, blocking until there are no other readers or writers
+    /// on that tree.
+    #[inline]
+    pub fn write(&self, index: C::Index) -> RwLockWriteGuard<'_, T> {
+        self.shards[index.into()].write()
+    }
+
+    /// Inserts `leaf` at the next available leaf node of the tree corresponding with `leaf`,
+    /// returning `false` if the leaf could not be inserted because its tree has exhausted its
+    /// capacity.
+    #[inline]
+    pub fn push(&self, leaf: &Leaf<C>) -> bool {
+        self.shard(leaf).write().push(&self.parameters, leaf)
+    }
+
+    /// Returns the root of the tree where `leaf` should be inserted.
+    #[inline]
+    pub fn output_from(&self, leaf: &Leaf<C>) -> Root<C>
+    where
+        InnerDigest<C>: Clone,
+    {
+        self.shard(leaf).read().root().clone()
+    }
+
+    /// Returns `true` if `root` is the current root of one of the trees in this forest.
+    #[inline]
+    pub fn contains_root(&self, root: &Root<C>) -> bool
+    where
+        InnerDigest<C>: PartialEq,
+    {
+        self.shards.iter().any(|shard| shard.read().root() == root)
+    }
+}
+
+impl<C, T, const N: usize> ShardedMerkleForest<C, T, N>
+where
+    C: Configuration + ?Sized,
+    C::Index: FixedIndex<N>,
+    T: Tree<C> + WithProofs<C>,
+    InnerDigest<C>: Clone + PartialEq,
+{
+    /// Inserts `leaf` provably at the next available leaf node of the tree corresponding with
+    /// `leaf`, returning `false` if the leaf could not be inserted because its tree has exhausted
+    /// its capacity.
+    #[inline]
+    pub fn insert(&self, leaf: &Leaf<C>) -> bool {
+        self.shard(leaf)
+            .write()
+            .push_provable(&self.parameters, leaf)
+    }
+
+    /// Inserts all of `leaves` provably, locking each tree only once for all the leaves that
+    /// belong to it. Returns `false` if some leaves could not be inserted because their tree has
+    /// exhausted its capacity.
+    #[inline]
+    pub fn batch_insert<'l, I>(&self, leaves: I) -> bool
+    where
+        Leaf<C>: 'l,
+        I: IntoIterator<Item = &'l Leaf<C>>,
+    {
+        let mut groups = Vec::<Vec<&Leaf<C>>>::new();
+        groups.resize_with(N, Default::default);
+        for leaf in leaves {
+            groups[C::tree_index(leaf).into()].push(leaf);
+        }
+        let mut result = true;
+        for (shard, group) in self.shards.iter().zip(groups) {
+            if !group.is_empty() {
+                result &= shard.write().batch_push_provable(&self.parameters, group);
+            }
+        }
+        result
+    }
+
+    /// Returns `true` if `leaf` was inserted provably into this forest.
+    #[inline]
+    pub fn contains(&self, leaf: &Leaf<C>) -> bool {
+        self.shard(leaf)
+            .read()
+            .contains(&self.parameters.digest(leaf))
+    }
+
+    /// Returns a membership proof for `leaf` if it was inserted provably into this forest. The
+    /// tree of `leaf` is locked for reading while the proof is built, so the witness and the
+    /// output of the proof are always consistent with each other.
+    #[inline]
+    pub fn prove(&self, leaf: &Leaf<C>) -> Option<MembershipProof<Parameters<C>>>
+    where
+        Parameters<C>: Clone,
+    {
+        let tree = self.shard(leaf).read();
+        Some(MembershipProof::new(
+            tree.path(
+                &self.parameters,
+                tree.position(&self.parameters.digest(leaf))?,
+            )
+            .ok()?,
+            tree.root().clone(),
+        ))
+    }
+}
+
+impl<C, T, const N: usize> Debug for ShardedMerkleForest<C, T, N>
+where
+    C: Configuration + ?Sized,
+    C::Index: FixedIndex<N>,
+    T: Debug + Tree<C>,
+    Parameters<C>: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("ShardedMerkleForest")
+            .field("parameters", &self.parameters)
+            .field("shards", &self.shards)
+            .finish()
+    }
+}
+
+impl<C, T, const N: usize> From<MerkleForest<C, TreeArray<C, T, N>>>
+    for ShardedMerkleForest<C, T, N>
+where
+    C: Configuration + ?Sized,
+    C::Index: FixedIndex<N>,
+    T: Tree<C>,
+{
+    #[inline]
+    fn from(forest: MerkleForest<C, TreeArray<C, T, N>>) -> Self {
+        Self::from_forest(forest)
+    }
+}
+
+/// Testing Suite
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        accumulator::Accumulator,
+        merkle_tree::{forest::TreeArrayMerkleForest, full, test::Test},
+        rand::{OsRng, Rand, Sample},
+    };
+    use std::thread;
+
+    /// Merkle Tree Configuration
+    type Config = Test<u64, 11>;
+
+    /// Full Merkle Tree
+    type Full = full::Full<Config>;
+
+    /// Tests that batch insertion into a [`ShardedMerkleForest`] yields the same trees as
+    /// sequential insertion into a [`MerkleForest`].
+    #[test]
+    fn batch_insertion_matches_forest() {
+        let mut rng = OsRng;
+        let parameters = Parameters::<Config>::sample(Default::default(), &mut rng);
+        let leaves = (0..256).map(|_| rng.gen()).collect::<Vec<u64>>();
+        let mut forest = TreeArrayMerkleForest::<Config, Full, 2>::new(parameters);
+        for leaf in &leaves {
+            forest.insert(leaf);
+        }
+        let sharded = ShardedMerkleForest::<Config, Full, 2>::new(parameters);
+        assert!(sharded.batch_insert(&leaves));
+        assert_eq!(
+            forest,
+            sharded.into_forest(),
+            "Sharded and sequential insertions should yield the same forest."
+        );
+    }
+
+    /// Tests that membership proofs can be generated while other threads insert into a
+    /// [`ShardedMerkleForest`].
+    #[test]
+    fn concurrent_proving_and_insertion() {
+        let mut rng = OsRng;
+        let parameters = Parameters::<Config>::sample(Default::default(), &mut rng);
+        let leaves = (0..512).map(|_| rng.gen()).collect::<Vec<u64>>();
+        let (inserted, pending) = leaves.split_at(256);
+        let sharded = ShardedMerkleForest::<Config, Full, 2>::new(parameters);
+        assert!(sharded.batch_insert(inserted));
+        thread::scope(|scope| {
+            for chunk in pending.chunks(64) {
+                let sharded = &sharded;
+                scope.spawn(move || {
+                    for leaf in chunk {
+                        assert!(sharded.insert(leaf));
+                    }
+                });
+            }
+            for chunk in inserted.chunks(64) {
+                let sharded = &sharded;
+                scope.spawn(move || {
+                    for leaf in chunk {
+                        let proof = sharded.prove(leaf).expect("Leaf was inserted provably.");
+                        assert!(proof.verify(sharded.parameters(), leaf, &mut ()));
+                    }
+                });
+            }
+        });
+        assert_eq!(sharded.len(), leaves.len());
+        assert!(leaves.iter().all(|leaf| sharded.contains(leaf)));
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Forest Overlays
+
+use crate::{
+    merkle_tree::{
+        forest::{self, Configuration as _, Forest as _},
+        full,
+        test::Test,
+        tree::Parameters,
+    },
+    rand::{OsRng, Rand, Sample},
+};
+
+/// Merkle Tree Height
+const HEIGHT: usize = 7;
+
+/// Merkle Tree Configuration
+type Config = Test<u64, HEIGHT>;
+
+/// Merkle Forest Type
+type Forest = forest::TreeArrayMerkleForest<Config, full::Full<Config>, 2>;
+
+/// Tests that pushing leaves through a [`ForestOverlay`](forest::ForestOverlay) yields the same
+/// forest as pushing them into the base forest, and that discarding the overlay leaves the base
+/// forest untouched.
+#[test]
+fn test_forest_overlay() {
+    let mut rng = OsRng;
+    let parameters = Parameters::<Config>::sample(Default::default(), &mut rng);
+    let mut forest = Forest::new(parameters);
+    for _ in 0..8 {
+        forest.push(&rng.gen());
+    }
+    let origin = forest.clone();
+    let leaves = (0..16).map(|_| rng.gen()).collect::<Vec<u64>>();
+    let mut overlay = forest.fork();
+    for leaf in &leaves {
+        assert!(overlay.push(leaf), "Leaves should fit in the overlay.");
+    }
+    let index = Config::tree_index(&leaves[0]);
+    assert_ne!(
+        overlay.get(index).root(),
+        forest.forest.get(index).root(),
+        "The overlay should see the leaves pushed into it."
+    );
+    overlay.discard();
+    assert_eq!(
+        forest, origin,
+        "Discarded overlays should not change the base forest."
+    );
+    let mut overlay = forest.fork();
+    for leaf in &leaves {
+        overlay.push(leaf);
+    }
+    let trees = overlay.commit();
+    forest.merge(trees);
+    let mut expected = origin;
+    for leaf in &leaves {
+        expected.push(leaf);
+    }
+    assert_eq!(
+        forest, expected,
+        "Committed overlays should match pushing into the base forest."
+    );
+}
@@ -30,6 +30,9 @@ use core::{fmt::Debug, hash::Hash, marker::PhantomData};
 #[cfg(test)]
 pub mod batch_insertion;
 
+#[cfg(test)]
+pub mod forest_overlay;
+
 #[cfg(test)]
 pub mod partial;
 
@@ -17,9 +17,9 @@
 //! Partial Merkle Tree Tests
 
 use crate::{
-    accumulator::{Accumulator, FromItemsAndWitnesses},
+    accumulator::{Accumulator, FromItemsAndWitnesses, OptimizedAccumulator},
     merkle_tree::{
-        forest::{Forest, TreeArrayMerkleForest},
+        forest::{Configuration, Forest, TreeArrayMerkleForest},
         fork::ForkedTree,
         full::{Full, FullMerkleTree},
         partial::{Partial, PartialMerkleTree},
@@ -29,6 +29,7 @@ use crate::{
     },
     rand::{OsRng, Rand, Sample},
 };
+use manta_util::persistence::Rollback;
 
 /// Merkle Tree Height
 const HEIGHT: usize = 7;
@@ -134,3 +135,77 @@ fn test_from_leaves_and_path_forest() {
         assert_eq!(forest.output_from(leaf), partial_forest.output_from(leaf));
     }
 }
+
+/// Tests that the forest consisting of [`Partial`] trees generated from the frontier and the
+/// [`Path`]s of some of the leaves keeps proving those leaves as new leaves are inserted.
+#[test]
+fn test_from_frontier_forest() {
+    let mut rng = OsRng;
+    let parameters = Parameters::<Config>::sample(Default::default(), &mut rng);
+    let mut forest =
+        TreeArrayMerkleForest::<Config, ForkedTree<Config, Full<Config>>, 2>::new(parameters);
+    let number_of_insertions = rng.gen_range(5..(1 << (HEIGHT - 2)));
+    let insertions = (0..number_of_insertions)
+        .map(|_| rng.gen())
+        .collect::<Vec<u64>>();
+    for leaf in &insertions {
+        forest.insert(leaf);
+    }
+    let witness = |leaf: &u64| {
+        let tree = forest.forest.get(Config::tree_index(leaf));
+        let index = tree
+            .position(&parameters.digest(leaf))
+            .expect("The leaf was inserted into the forest.");
+        (
+            *leaf,
+            tree.path(&parameters, index)
+                .expect("Paths of the full tree are always available."),
+        )
+    };
+    let frontier = [BinaryIndex::Zero, BinaryIndex::One]
+        .into_iter()
+        .map(|index| {
+            insertions
+                .iter()
+                .rev()
+                .find(|leaf| Config::tree_index(leaf) == index)
+                .map(witness)
+        })
+        .collect::<Vec<_>>();
+    let owned = insertions
+        .iter()
+        .step_by(3)
+        .map(witness)
+        .collect::<Vec<_>>();
+    let mut frontier_forest =
+        TreeArrayMerkleForest::<_, ForkedTree<_, Partial<Config>>, 2>::from_frontier_and_witnesses(
+            &parameters,
+            frontier,
+            owned.clone(),
+        );
+    let check = |forest: &TreeArrayMerkleForest<_, _, 2>,
+                 frontier_forest: &TreeArrayMerkleForest<_, _, 2>| {
+        for (leaf, _) in &owned {
+            let proof = frontier_forest
+                .prove(leaf)
+                .expect("Owned leaves must be provable.");
+            assert!(
+                proof.verify(&parameters, leaf, &mut ()),
+                "Proofs of owned leaves must be valid."
+            );
+            assert_eq!(
+                forest.output_from(leaf),
+                Some(proof.into_output()),
+                "Roots must be equal."
+            );
+        }
+    };
+    check(&forest, &frontier_forest);
+    for _ in 0..rng.gen_range(1..(1 << (HEIGHT - 2))) {
+        let leaf = rng.gen::<_, u64>();
+        forest.insert(&leaf);
+        frontier_forest.insert_nonprovable(&leaf);
+    }
+    frontier_forest.commit();
+    check(&forest, &frontier_forest);
+}
@@ -140,6 +140,14 @@ pub trait HashConfiguration<COM = ()> {
 
     /// Inner Hash Type
     type InnerHash: InnerHash<COM, LeafDigest = <Self::LeafHash as LeafHash<COM>>::Output>;
+
+    /// Compact Swap Flag
+    ///
+    /// If this flag is set, paths order the digests of every level with
+    /// [`swap_compact`](crate::eclair::bool::ConditionalSwap::swap_compact) instead of
+    /// [`swap`](crate::eclair::bool::ConditionalSwap::swap). This changes the membership circuit,
+    /// so it can only be set for configurations whose proving keys are generated after the flag.
+    const COMPACT_SWAP: bool = false;
 }
 
 /// Merkle Tree Configuration
@@ -177,6 +185,7 @@ where
 {
     type LeafHash = C::LeafHash;
     type InnerHash = C::InnerHash;
+    const COMPACT_SWAP: bool = C::COMPACT_SWAP;
 }
 
 impl<C, COM, const HEIGHT: usize> Configuration<COM> for Config<C, COM, HEIGHT>
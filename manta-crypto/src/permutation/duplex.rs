@@ -20,7 +20,6 @@
 //       dropping it on decryption.
 
 use crate::{
-    constraint::{HasInput, Input},
     eclair::{
         self,
         alloc::{mode::Public, Allocate, Allocator, Constant, Variable},
@@ -32,6 +31,7 @@ use crate::{
         CiphertextType, Decrypt, DecryptedPlaintextType, DecryptionKeyType, Encrypt,
         EncryptionKeyType, HeaderType, PlaintextType, RandomnessType,
     },
+    impl_input,
     permutation::{
         sponge::{Read, Sponge, Write},
         PseudorandomPermutation,
@@ -185,6 +185,25 @@ where
     }
 }
 
+impl<T, C> Decode for Ciphertext<T, C>
+where
+    T: Decode,
+    C: Decode,
+{
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: codec::Read,
+    {
+        Ok(Self::new(
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+        ))
+    }
+}
+
 impl<T, C> Encode for Ciphertext<T, C>
 where
     T: Encode,
@@ -201,14 +220,11 @@ where
     }
 }
 
-impl<T, C, P> Input<P> for Ciphertext<T, C>
-where
-    P: HasInput<T> + HasInput<C> + ?Sized,
-{
-    #[inline]
-    fn extend(&self, input: &mut P::Input) {
-        P::extend(input, &self.tag);
-        P::extend(input, &self.message);
+impl_input! {
+    impl<T, C> Input<P> for Ciphertext<T, C>
+    |ciphertext| {
+        T => &ciphertext.tag,
+        C => &ciphertext.message,
     }
 }
 
@@ -210,6 +210,215 @@ where
     }
 }
 
+/// Random Number Generator Policy
+///
+/// An [`RngPolicy`] decides when a [`PolicyRng`] is reseeded and which entropy is mixed into its
+/// new seed. Reusing the output of a generator in two processes, for example after a process fork
+/// or after restoring a snapshot of a virtual machine, reveals secret randomness, so the policy
+/// also fingerprints the execution environment to detect these events.
+pub trait RngPolicy {
+    /// Fills `dest` with fresh entropy from the environment.
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), Error>;
+
+    /// Returns `true` if the generator should be reseeded after producing `bytes`-many bytes
+    /// since its last reseed.
+    fn should_reseed(&self, bytes: u64) -> bool;
+
+    /// Returns a fingerprint of the execution environment. Whenever the fingerprint changes, the
+    /// generator is reseeded before producing any more output.
+    fn fingerprint(&mut self) -> u64;
+}
+
+/// Policy-Guarded Random Number Generator
+///
+/// This generator wraps an `inner` seedable generator and follows the [`RngPolicy`] `P`: it is
+/// reseeded when it is built, when the policy asks for it and when the fingerprint of the
+/// execution environment changes. Every new seed is the output of the `inner` generator mixed with
+/// fresh entropy from the policy, so the generator stays secure as long as either of them is.
+#[derive(derivative::Derivative)]
+#[derivative(Clone(bound = "R: Clone, P: Clone"), Debug(bound = "P: Debug"))]
+pub struct PolicyRng<R, P> {
+    /// Inner Rng
+    #[derivative(Debug = "ignore")]
+    inner: R,
+
+    /// Reseeding Policy
+    policy: P,
+
+    /// Number of Bytes Produced since the Last Reseed
+    bytes: u64,
+
+    /// Fingerprint of the Execution Environment at the Last Reseed
+    fingerprint: u64,
+}
+
+impl<R, P> PolicyRng<R, P>
+where
+    R: RngCore + SeedableRng,
+    P: RngPolicy,
+{
+    /// Builds a new [`PolicyRng`] from `inner` and `policy`, mixing fresh entropy into `inner`
+    /// right away.
+    #[inline]
+    pub fn new(inner: R, policy: P) -> Result<Self, Error> {
+        let mut rng = Self {
+            inner,
+            policy,
+            bytes: 0,
+            fingerprint: 0,
+        };
+        rng.reseed()?;
+        Ok(rng)
+    }
+
+    /// Returns a shared reference to the reseeding policy of `self`.
+    #[inline]
+    pub fn policy(&self) -> &P {
+        &self.policy
+    }
+
+    /// Reseeds `self` with the output of the inner generator mixed with fresh entropy from the
+    /// policy.
+    #[inline]
+    pub fn reseed(&mut self) -> Result<(), Error> {
+        let mut seed = R::Seed::default();
+        let mut entropy = R::Seed::default();
+        self.inner.try_fill_bytes(seed.as_mut())?;
+        self.policy.fill_entropy(entropy.as_mut())?;
+        for (byte, mixed) in seed.as_mut().iter_mut().zip(entropy.as_mut().iter()) {
+            *byte ^= mixed;
+        }
+        self.inner = R::from_seed(seed);
+        self.bytes = 0;
+        self.fingerprint = self.policy.fingerprint();
+        Ok(())
+    }
+
+    /// Reseeds `self` if the policy asks for it or if the execution environment changed.
+    #[inline]
+    fn guard(&mut self) -> Result<(), Error> {
+        if self.policy.should_reseed(self.bytes) || self.policy.fingerprint() != self.fingerprint {
+            self.reseed()?;
+        }
+        Ok(())
+    }
+}
+
+impl<R, P> CryptoRng for PolicyRng<R, P> where R: CryptoRng {}
+
+impl<R, P> RngCore for PolicyRng<R, P>
+where
+    R: RngCore + SeedableRng,
+    P: RngPolicy,
+{
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest)
+            .expect("Reseeding the random number generator is not allowed to fail.");
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.guard()?;
+        self.inner.try_fill_bytes(dest)?;
+        self.bytes = self.bytes.saturating_add(dest.len() as u64);
+        Ok(())
+    }
+}
+
+impl<R, P> FromEntropy for PolicyRng<R, P>
+where
+    R: RngCore + SeedableRng,
+    P: Default + RngPolicy,
+{
+    /// Creates a new instance of `Self` whose whole seed comes from the entropy of the default
+    /// policy.
+    #[inline]
+    fn from_entropy() -> Self {
+        Self::new(R::from_seed(Default::default()), P::default())
+            .expect("Seeding the random number generator is not allowed to fail.")
+    }
+}
+
+/// Operating System Random Number Generator Policy
+///
+/// This policy mixes entropy from [`OsRng`] into the seed and reseeds the generator after every
+/// [`reseed_interval`](Self::reseed_interval)-many bytes. With the standard library, its
+/// fingerprint is made of the process id, which changes in the child of a process fork, and of the
+/// boot id of the kernel on Linux, which changes when a cloned virtual machine is booted.
+#[cfg(feature = "getrandom")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "getrandom")))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct OsRngPolicy {
+    /// Reseed Interval in Bytes
+    pub reseed_interval: u64,
+}
+
+#[cfg(feature = "getrandom")]
+impl OsRngPolicy {
+    /// Default Reseed Interval in Bytes
+    pub const DEFAULT_RESEED_INTERVAL: u64 = 1 << 20;
+
+    /// Boot Id Path on Linux
+    #[cfg(feature = "std")]
+    const BOOT_ID_PATH: &'static str = "/proc/sys/kernel/random/boot_id";
+
+    /// Builds a new [`OsRngPolicy`] which reseeds after every `reseed_interval`-many bytes.
+    #[inline]
+    pub fn new(reseed_interval: u64) -> Self {
+        Self { reseed_interval }
+    }
+}
+
+#[cfg(feature = "getrandom")]
+impl Default for OsRngPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_RESEED_INTERVAL)
+    }
+}
+
+#[cfg(feature = "getrandom")]
+impl RngPolicy for OsRngPolicy {
+    #[inline]
+    fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        OsRng.try_fill_bytes(dest)
+    }
+
+    #[inline]
+    fn should_reseed(&self, bytes: u64) -> bool {
+        bytes >= self.reseed_interval
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn fingerprint(&mut self) -> u64 {
+        use std::{collections::hash_map::DefaultHasher, fs, hash::Hasher, process};
+        let mut hasher = DefaultHasher::new();
+        hasher.write_u32(process::id());
+        if let Ok(boot_id) = fs::read(Self::BOOT_ID_PATH) {
+            hasher.write(&boot_id);
+        }
+        hasher.finish()
+    }
+
+    #[cfg(not(feature = "std"))]
+    #[inline]
+    fn fingerprint(&mut self) -> u64 {
+        0
+    }
+}
+
 /// Sampling Trait
 pub trait Sample<D = ()>: Sized {
     /// Returns a random value of type `Self`, sampled according to the given `distribution`,
@@ -659,3 +868,67 @@ pub mod fuzz {
         }
     }
 }
+
+/// Testing Suite
+#[cfg(all(test, feature = "rand_chacha"))]
+mod test {
+    use super::*;
+
+    /// Deterministic Policy with a Settable Fingerprint
+    #[derive(Clone, Debug, Default)]
+    struct TestPolicy {
+        /// Fingerprint
+        fingerprint: u64,
+
+        /// Number of Reseeds
+        reseeds: u64,
+    }
+
+    impl RngPolicy for TestPolicy {
+        #[inline]
+        fn fill_entropy(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+            self.reseeds += 1;
+            dest.fill((self.fingerprint ^ self.reseeds) as u8);
+            Ok(())
+        }
+
+        #[inline]
+        fn should_reseed(&self, bytes: u64) -> bool {
+            bytes >= 64
+        }
+
+        #[inline]
+        fn fingerprint(&mut self) -> u64 {
+            self.fingerprint
+        }
+    }
+
+    /// Tests that a copy of a [`PolicyRng`] whose fingerprint changed, as in the child of a
+    /// process fork, does not repeat the output of the original generator.
+    #[test]
+    fn fingerprint_change_reseeds() {
+        let mut parent = PolicyRng::<ChaCha20Rng, TestPolicy>::from_entropy();
+        let mut child = parent.clone();
+        let mut parent_bytes = [0; 32];
+        let mut child_bytes = [0; 32];
+        parent.fill_bytes(&mut parent_bytes);
+        child.fill_bytes(&mut child_bytes);
+        assert_eq!(parent_bytes, child_bytes);
+        child.policy.fingerprint = 2;
+        parent.fill_bytes(&mut parent_bytes);
+        child.fill_bytes(&mut child_bytes);
+        assert_ne!(parent_bytes, child_bytes);
+        assert_eq!((parent.policy().reseeds, child.policy().reseeds), (1, 2));
+    }
+
+    /// Tests that a [`PolicyRng`] is reseeded once it has produced enough bytes.
+    #[test]
+    fn reseed_schedule() {
+        let mut rng = PolicyRng::<ChaCha20Rng, TestPolicy>::from_entropy();
+        let mut bytes = [0; 32];
+        for _ in 0..4 {
+            rng.fill_bytes(&mut bytes);
+        }
+        assert_eq!(rng.policy().reseeds, 2);
+    }
+}
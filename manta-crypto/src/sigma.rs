@@ -0,0 +1,530 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sigma Protocols
+//!
+//! A sigma protocol proves knowledge of a witness for a statement in three moves: the prover sends
+//! a commitment to a random nonce, the verifier answers with a random challenge, and the prover
+//! sends a response which combines the nonce, the challenge and the witness. The [`Proof`] type
+//! makes these protocols non-interactive by taking the challenge from a [`Transcript`], which
+//! gives small proofs of simple algebraic relations without the cost of a SNARK circuit.
+//!
+//! This module implements the following protocols over a group with a scalar multiplication:
+//!
+//! - [`Schnorr`]: knowledge of the discrete logarithm of a point
+//! - [`DiscreteLogEquality`]: knowledge of a scalar which is the discrete logarithm of two points
+//!   with respect to two different bases
+//! - [`PedersenOpening`]: knowledge of the opening of a Pedersen commitment
+
+use crate::{
+    algebra::{Ring, ScalarMulGroup},
+    rand::{Rand, RngCore, Sample},
+    transcript::Transcript,
+};
+use core::{cmp, fmt::Debug, hash::Hash, marker::PhantomData};
+use manta_util::codec::{Decode, DecodeError, Encode, Read, Write};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Sigma Protocol
+pub trait SigmaProtocol {
+    /// Domain Separation Label
+    ///
+    /// This label is appended to the transcript before the statement, so that a proof for one
+    /// protocol is never accepted as a proof for another one.
+    const LABEL: &'static [u8];
+
+    /// Statement Type
+    type Statement;
+
+    /// Witness Type
+    type Witness;
+
+    /// Nonce Type
+    type Nonce;
+
+    /// Commitment Type
+    type Commitment;
+
+    /// Challenge Type
+    type Challenge;
+
+    /// Response Type
+    type Response;
+
+    /// Computes the commitment to `nonce` sent in the first move of the protocol.
+    fn commit(&self, nonce: &Self::Nonce) -> Self::Commitment;
+
+    /// Computes the response to `challenge` for `witness` and `nonce`.
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        nonce: &Self::Nonce,
+        challenge: &Self::Challenge,
+    ) -> Self::Response;
+
+    /// Checks that `commitment`, `challenge` and `response` are an accepting conversation for
+    /// `statement`.
+    fn verify(
+        &self,
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> bool;
+}
+
+/// Non-Interactive Sigma Protocol Proof
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "C: Deserialize<'de>, R: Deserialize<'de>",
+            serialize = "C: Serialize, R: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "C: Clone, R: Clone"),
+    Copy(bound = "C: Copy, R: Copy"),
+    Debug(bound = "C: Debug, R: Debug"),
+    Eq(bound = "C: Eq, R: Eq"),
+    Hash(bound = "C: Hash, R: Hash"),
+    PartialEq(bound = "C: cmp::PartialEq, R: cmp::PartialEq")
+)]
+pub struct Proof<C, R> {
+    /// Commitment
+    pub commitment: C,
+
+    /// Response
+    pub response: R,
+}
+
+impl<C, R> Proof<C, R> {
+    /// Appends the statement and the commitment of a run of `protocol` to `transcript` and returns
+    /// the challenge for them.
+    #[inline]
+    fn challenge<P, T>(
+        protocol: &P,
+        transcript: &mut T,
+        statement: &P::Statement,
+        commitment: &C,
+    ) -> P::Challenge
+    where
+        P: SigmaProtocol<Commitment = C, Response = R>,
+        P::Statement: Encode,
+        C: Encode,
+        T: Transcript<Challenge = P::Challenge>,
+    {
+        let _ = protocol;
+        transcript.append_message(b"sigma-protocol", P::LABEL);
+        transcript.append(b"statement", statement);
+        transcript.append(b"commitment", commitment);
+        transcript.challenge(b"challenge")
+    }
+
+    /// Proves that `witness` is a witness for `statement` under `protocol`, taking the challenge
+    /// from `transcript`.
+    #[inline]
+    pub fn prove<P, T, G>(
+        protocol: &P,
+        transcript: &mut T,
+        statement: &P::Statement,
+        witness: &P::Witness,
+        rng: &mut G,
+    ) -> Self
+    where
+        P: SigmaProtocol<Commitment = C, Response = R>,
+        P::Statement: Encode,
+        P::Nonce: Sample,
+        C: Encode,
+        T: Transcript<Challenge = P::Challenge>,
+        G: RngCore + ?Sized,
+    {
+        let nonce = rng.gen();
+        let commitment = protocol.commit(&nonce);
+        let challenge = Self::challenge(protocol, transcript, statement, &commitment);
+        Self {
+            response: protocol.respond(witness, &nonce, &challenge),
+            commitment,
+        }
+    }
+
+    /// Verifies that `self` is a proof for `statement` under `protocol`, taking the challenge
+    /// from `transcript`, which must hold the same messages as the transcript of the prover.
+    #[inline]
+    pub fn verify<P, T>(&self, protocol: &P, transcript: &mut T, statement: &P::Statement) -> bool
+    where
+        P: SigmaProtocol<Commitment = C, Response = R>,
+        P::Statement: Encode,
+        C: Encode,
+        T: Transcript<Challenge = P::Challenge>,
+    {
+        let challenge = Self::challenge(protocol, transcript, statement, &self.commitment);
+        protocol.verify(statement, &self.commitment, &challenge, &self.response)
+    }
+}
+
+impl<C, R> Decode for Proof<C, R>
+where
+    C: Decode,
+    R: Decode,
+{
+    type Error = ();
+
+    #[inline]
+    fn decode<D>(mut reader: D) -> Result<Self, DecodeError<D::Error, Self::Error>>
+    where
+        D: Read,
+    {
+        Ok(Self {
+            commitment: Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+            response: Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+        })
+    }
+}
+
+impl<C, R> Encode for Proof<C, R>
+where
+    C: Encode,
+    R: Encode,
+{
+    #[inline]
+    fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        self.commitment.encode(&mut writer)?;
+        self.response.encode(&mut writer)?;
+        Ok(())
+    }
+}
+
+/// Sigma Protocol Proof Type
+pub type ProtocolProof<P> = Proof<<P as SigmaProtocol>::Commitment, <P as SigmaProtocol>::Response>;
+
+/// Schnorr Proof of Knowledge
+///
+/// Proves knowledge of the scalar `x` such that `x * generator` is the statement.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(deserialize = "G: Deserialize<'de>", serialize = "G: Serialize"),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "G: Clone"),
+    Copy(bound = "G: Copy"),
+    Debug(bound = "G: Debug"),
+    Eq(bound = "G: Eq"),
+    Hash(bound = "G: Hash"),
+    PartialEq(bound = "G: cmp::PartialEq")
+)]
+pub struct Schnorr<S, G> {
+    /// Generator
+    pub generator: G,
+
+    /// Type Parameter Marker
+    #[cfg_attr(feature = "serde", serde(skip))]
+    __: PhantomData<S>,
+}
+
+impl<S, G> Schnorr<S, G> {
+    /// Builds a new [`Schnorr`] protocol over `generator`.
+    #[inline]
+    pub fn new(generator: G) -> Self {
+        Self {
+            generator,
+            __: PhantomData,
+        }
+    }
+}
+
+impl<S, G> SigmaProtocol for Schnorr<S, G>
+where
+    S: Ring,
+    G: cmp::PartialEq + ScalarMulGroup<S, Output = G>,
+{
+    const LABEL: &'static [u8] = b"schnorr";
+
+    type Statement = G;
+    type Witness = S;
+    type Nonce = S;
+    type Commitment = G;
+    type Challenge = S;
+    type Response = S;
+
+    #[inline]
+    fn commit(&self, nonce: &Self::Nonce) -> Self::Commitment {
+        self.generator.scalar_mul(nonce, &mut ())
+    }
+
+    #[inline]
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        nonce: &Self::Nonce,
+        challenge: &Self::Challenge,
+    ) -> Self::Response {
+        nonce.add(&challenge.mul(witness, &mut ()), &mut ())
+    }
+
+    #[inline]
+    fn verify(
+        &self,
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> bool {
+        self.generator.scalar_mul(response, &mut ())
+            == commitment.add(&statement.scalar_mul(challenge, &mut ()), &mut ())
+    }
+}
+
+/// Discrete Logarithm Equality Proof
+///
+/// Proves knowledge of the scalar `x` such that the statement is `[x * bases[0], x * bases[1]]`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(deserialize = "G: Deserialize<'de>", serialize = "G: Serialize"),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "G: Clone"),
+    Copy(bound = "G: Copy"),
+    Debug(bound = "G: Debug"),
+    Eq(bound = "G: Eq"),
+    Hash(bound = "G: Hash"),
+    PartialEq(bound = "G: cmp::PartialEq")
+)]
+pub struct DiscreteLogEquality<S, G> {
+    /// Bases
+    pub bases: [G; 2],
+
+    /// Type Parameter Marker
+    #[cfg_attr(feature = "serde", serde(skip))]
+    __: PhantomData<S>,
+}
+
+impl<S, G> DiscreteLogEquality<S, G> {
+    /// Builds a new [`DiscreteLogEquality`] protocol over `bases`.
+    #[inline]
+    pub fn new(bases: [G; 2]) -> Self {
+        Self {
+            bases,
+            __: PhantomData,
+        }
+    }
+}
+
+impl<S, G> SigmaProtocol for DiscreteLogEquality<S, G>
+where
+    S: Ring,
+    G: cmp::PartialEq + ScalarMulGroup<S, Output = G>,
+{
+    const LABEL: &'static [u8] = b"discrete-log-equality";
+
+    type Statement = [G; 2];
+    type Witness = S;
+    type Nonce = S;
+    type Commitment = [G; 2];
+    type Challenge = S;
+    type Response = S;
+
+    #[inline]
+    fn commit(&self, nonce: &Self::Nonce) -> Self::Commitment {
+        [
+            self.bases[0].scalar_mul(nonce, &mut ()),
+            self.bases[1].scalar_mul(nonce, &mut ()),
+        ]
+    }
+
+    #[inline]
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        nonce: &Self::Nonce,
+        challenge: &Self::Challenge,
+    ) -> Self::Response {
+        nonce.add(&challenge.mul(witness, &mut ()), &mut ())
+    }
+
+    #[inline]
+    fn verify(
+        &self,
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> bool {
+        self.bases.iter().zip(statement.iter().zip(commitment)).all(
+            |(base, (point, commitment))| {
+                base.scalar_mul(response, &mut ())
+                    == commitment.add(&point.scalar_mul(challenge, &mut ()), &mut ())
+            },
+        )
+    }
+}
+
+/// Pedersen Commitment Opening Proof
+///
+/// Proves knowledge of the value `v` and the blinding factor `r` such that the statement is the
+/// Pedersen commitment `v * generators[0] + r * generators[1]`. The witness is `[v, r]`.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(deserialize = "G: Deserialize<'de>", serialize = "G: Serialize"),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "G: Clone"),
+    Copy(bound = "G: Copy"),
+    Debug(bound = "G: Debug"),
+    Eq(bound = "G: Eq"),
+    Hash(bound = "G: Hash"),
+    PartialEq(bound = "G: cmp::PartialEq")
+)]
+pub struct PedersenOpening<S, G> {
+    /// Value and Blinding Factor Generators
+    pub generators: [G; 2],
+
+    /// Type Parameter Marker
+    #[cfg_attr(feature = "serde", serde(skip))]
+    __: PhantomData<S>,
+}
+
+impl<S, G> PedersenOpening<S, G> {
+    /// Builds a new [`PedersenOpening`] protocol over `generators`.
+    #[inline]
+    pub fn new(generators: [G; 2]) -> Self {
+        Self {
+            generators,
+            __: PhantomData,
+        }
+    }
+
+    /// Computes the Pedersen commitment to `value` with `blinding` factor.
+    #[inline]
+    pub fn commitment(&self, value: &S, blinding: &S) -> G
+    where
+        G: ScalarMulGroup<S, Output = G>,
+    {
+        self.generators[0]
+            .scalar_mul(value, &mut ())
+            .add(&self.generators[1].scalar_mul(blinding, &mut ()), &mut ())
+    }
+}
+
+impl<S, G> SigmaProtocol for PedersenOpening<S, G>
+where
+    S: Clone + Ring,
+    G: cmp::PartialEq + ScalarMulGroup<S, Output = G>,
+{
+    const LABEL: &'static [u8] = b"pedersen-opening";
+
+    type Statement = G;
+    type Witness = [S; 2];
+    type Nonce = [S; 2];
+    type Commitment = G;
+    type Challenge = S;
+    type Response = [S; 2];
+
+    #[inline]
+    fn commit(&self, nonce: &Self::Nonce) -> Self::Commitment {
+        self.commitment(&nonce[0], &nonce[1])
+    }
+
+    #[inline]
+    fn respond(
+        &self,
+        witness: &Self::Witness,
+        nonce: &Self::Nonce,
+        challenge: &Self::Challenge,
+    ) -> Self::Response {
+        [
+            nonce[0].add(&challenge.mul(&witness[0], &mut ()), &mut ()),
+            nonce[1].add(&challenge.mul(&witness[1], &mut ()), &mut ()),
+        ]
+    }
+
+    #[inline]
+    fn verify(
+        &self,
+        statement: &Self::Statement,
+        commitment: &Self::Commitment,
+        challenge: &Self::Challenge,
+        response: &Self::Response,
+    ) -> bool {
+        self.commitment(&response[0], &response[1])
+            == commitment.add(&statement.scalar_mul(challenge, &mut ()), &mut ())
+    }
+}
+
+/// Testing Framework
+#[cfg(feature = "test")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
+pub mod test {
+    use super::*;
+
+    /// Proves `statement` with `witness` under `protocol` using a fresh copy of `transcript` and
+    /// checks that the proof is accepted for `statement` and rejected for `other_statement`.
+    #[inline]
+    pub fn completeness_and_soundness<P, T, G>(
+        protocol: &P,
+        transcript: &T,
+        statement: &P::Statement,
+        other_statement: &P::Statement,
+        witness: &P::Witness,
+        rng: &mut G,
+    ) where
+        P: SigmaProtocol,
+        P::Statement: Encode,
+        P::Nonce: Sample,
+        P::Commitment: Encode,
+        T: Clone + Transcript<Challenge = P::Challenge>,
+        G: RngCore + ?Sized,
+    {
+        let proof =
+            ProtocolProof::<P>::prove(protocol, &mut transcript.clone(), statement, witness, rng);
+        assert!(
+            proof.verify(protocol, &mut transcript.clone(), statement),
+            "The proof should be accepted for its statement."
+        );
+        assert!(
+            !proof.verify(protocol, &mut transcript.clone(), other_statement),
+            "The proof should be rejected for a different statement."
+        );
+    }
+}
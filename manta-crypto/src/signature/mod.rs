@@ -40,6 +40,7 @@ use core::{fmt::Debug, hash::Hash};
 use manta_util::serde::{Deserialize, Serialize};
 
 pub mod convert;
+pub mod winternitz;
 
 /// Signing Key
 pub trait SigningKeyType {
@@ -284,6 +285,7 @@ pub mod schnorr {
         rand::{Rand, RngCore, Sample},
     };
     use core::{cmp, fmt::Debug, hash::Hash, marker::PhantomData};
+    use manta_util::codec::{Decode, DecodeError, Encode, Read, Write};
 
     /// Schnorr Signature Hash Function
     pub trait HashFunction<COM = ()>: PreimageResistance {
@@ -352,6 +354,41 @@ pub mod schnorr {
         pub nonce_point: G,
     }
 
+    impl<S, G> Decode for Signature<S, G>
+    where
+        S: Decode,
+        G: Decode,
+    {
+        type Error = ();
+
+        #[inline]
+        fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+        where
+            R: Read,
+        {
+            Ok(Self {
+                scalar: Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+                nonce_point: Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+            })
+        }
+    }
+
+    impl<S, G> Encode for Signature<S, G>
+    where
+        S: Encode,
+        G: Encode,
+    {
+        #[inline]
+        fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
+        where
+            W: Write,
+        {
+            self.scalar.encode(&mut writer)?;
+            self.nonce_point.encode(&mut writer)?;
+            Ok(())
+        }
+    }
+
     /// Schnorr Signature Scheme
     #[derive(derivative::Derivative)]
     #[derivative(
@@ -0,0 +1,256 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Winternitz One-Time Signatures
+//!
+//! The Winternitz scheme is a hash-based signature scheme, so its security only relies on the
+//! preimage resistance of the underlying [`HashFunction`], which is believed to hold against
+//! quantum adversaries. Each signing key derives [`CHAINS`]-many hash chains of length
+//! [`CHAIN_LENGTH`], and a signature reveals one point on each chain, selected by the base-`16`
+//! digits of the message digest and of their checksum.
+//!
+//! # Limitations
+//!
+//! Winternitz signatures are one-time signatures: signing two different messages with the same
+//! signing key reveals enough of the hash chains to forge signatures on other messages.
+
+use crate::signature::{
+    Derive, MessageType, RandomnessType, Sign, SignatureType, SigningKeyType, Verify,
+    VerifyingKeyType,
+};
+use alloc::vec::Vec;
+use core::{
+    array,
+    fmt::{self, Debug, Formatter},
+};
+use manta_util::fmt::HexPrefix;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Digest Size in Bytes
+pub const DIGEST_SIZE: usize = 32;
+
+/// Digest Type
+pub type Digest = [u8; DIGEST_SIZE];
+
+/// Hash Chain Length
+pub const CHAIN_LENGTH: u8 = 16;
+
+/// Number of Message Chains
+///
+/// Every byte of the message digest is split into two base-`16` digits.
+pub const MESSAGE_CHAINS: usize = 2 * DIGEST_SIZE;
+
+/// Number of Checksum Chains
+///
+/// The checksum is at most `MESSAGE_CHAINS * 15 = 960`, which has three base-`16` digits.
+pub const CHECKSUM_CHAINS: usize = 3;
+
+/// Total Number of Hash Chains
+pub const CHAINS: usize = MESSAGE_CHAINS + CHECKSUM_CHAINS;
+
+/// Winternitz Hash Function
+pub trait HashFunction {
+    /// Hashes `input` into a [`Digest`], using `tweak` for domain separation.
+    fn hash(&self, tweak: &[u8], input: &[u8]) -> Digest;
+}
+
+impl<H> HashFunction for &H
+where
+    H: HashFunction,
+{
+    #[inline]
+    fn hash(&self, tweak: &[u8], input: &[u8]) -> Digest {
+        (*self).hash(tweak, input)
+    }
+}
+
+/// Winternitz Signature
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Default, Eq, Hash, PartialEq)]
+pub struct Signature {
+    /// Hash Chain Points
+    ///
+    /// There is one point for every hash chain, so a well-formed signature has [`CHAINS`]-many
+    /// points.
+    pub points: Vec<Digest>,
+}
+
+impl Debug for Signature {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Signature")
+            .field("points", &HexPrefix(self.points.concat()))
+            .finish()
+    }
+}
+
+/// Winternitz Signature Scheme
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Winternitz<H> {
+    /// Hash Function
+    pub hash_function: H,
+}
+
+impl<H> Winternitz<H>
+where
+    H: HashFunction,
+{
+    /// Builds a new [`Winternitz`] signature scheme over `hash_function`.
+    #[inline]
+    pub fn new(hash_function: H) -> Self {
+        Self { hash_function }
+    }
+
+    /// Returns the base-`16` digits of `message` followed by the digits of their checksum.
+    #[inline]
+    fn digits(message: &Digest) -> [u8; CHAINS] {
+        let mut digits = [0; CHAINS];
+        for (i, byte) in message.iter().enumerate() {
+            digits[2 * i] = byte >> 4;
+            digits[2 * i + 1] = byte & 0x0f;
+        }
+        let checksum = digits[..MESSAGE_CHAINS]
+            .iter()
+            .map(|digit| usize::from(CHAIN_LENGTH - 1 - digit))
+            .sum::<usize>();
+        for i in 0..CHECKSUM_CHAINS {
+            digits[MESSAGE_CHAINS + i] =
+                ((checksum >> (4 * (CHECKSUM_CHAINS - 1 - i))) & 0x0f) as u8;
+        }
+        digits
+    }
+
+    /// Returns the start of the hash chain at `index` for `signing_key`.
+    #[inline]
+    fn chain_start(&self, signing_key: &Digest, index: usize) -> Digest {
+        self.hash_function.hash(&[0, index as u8], signing_key)
+    }
+
+    /// Walks the hash chain at `index` from `point` at position `start` to position `end`.
+    #[inline]
+    fn walk(&self, index: usize, mut point: Digest, start: u8, end: u8) -> Digest {
+        for position in start..end {
+            point = self.hash_function.hash(&[1, index as u8, position], &point);
+        }
+        point
+    }
+
+    /// Compresses the ends of the hash chains into a verifying key.
+    #[inline]
+    fn compress<I>(&self, ends: I) -> Digest
+    where
+        I: IntoIterator<Item = Digest>,
+    {
+        self.hash_function
+            .hash(&[2], &ends.into_iter().flatten().collect::<Vec<_>>())
+    }
+}
+
+impl<H> SigningKeyType for Winternitz<H> {
+    type SigningKey = Digest;
+}
+
+impl<H> VerifyingKeyType for Winternitz<H> {
+    type VerifyingKey = Digest;
+}
+
+impl<H> MessageType for Winternitz<H> {
+    type Message = Digest;
+}
+
+impl<H> SignatureType for Winternitz<H> {
+    type Signature = Signature;
+}
+
+impl<H> RandomnessType for Winternitz<H> {
+    type Randomness = ();
+}
+
+impl<H> Derive for Winternitz<H>
+where
+    H: HashFunction,
+{
+    #[inline]
+    fn derive(&self, signing_key: &Self::SigningKey, compiler: &mut ()) -> Self::VerifyingKey {
+        let _ = compiler;
+        self.compress(
+            (0..CHAINS)
+                .map(|i| self.walk(i, self.chain_start(signing_key, i), 0, CHAIN_LENGTH - 1)),
+        )
+    }
+}
+
+impl<H> Sign for Winternitz<H>
+where
+    H: HashFunction,
+{
+    #[inline]
+    fn sign(
+        &self,
+        signing_key: &Self::SigningKey,
+        randomness: &Self::Randomness,
+        message: &Self::Message,
+        compiler: &mut (),
+    ) -> Self::Signature {
+        let _ = (randomness, compiler);
+        let digits = Self::digits(message);
+        Signature {
+            points: array::from_fn::<_, CHAINS, _>(|i| {
+                self.walk(i, self.chain_start(signing_key, i), 0, digits[i])
+            })
+            .into(),
+        }
+    }
+}
+
+impl<H> Verify for Winternitz<H>
+where
+    H: HashFunction,
+{
+    type Verification = bool;
+
+    #[inline]
+    fn verify(
+        &self,
+        verifying_key: &Self::VerifyingKey,
+        message: &Self::Message,
+        signature: &Self::Signature,
+        compiler: &mut (),
+    ) -> Self::Verification {
+        let _ = compiler;
+        if signature.points.len() != CHAINS {
+            return false;
+        }
+        let digits = Self::digits(message);
+        let ends = signature
+            .points
+            .iter()
+            .enumerate()
+            .map(|(i, point)| self.walk(i, *point, digits[i], CHAIN_LENGTH - 1));
+        &self.compress(ends) == verifying_key
+    }
+}
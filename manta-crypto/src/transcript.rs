@@ -0,0 +1,69 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fiat-Shamir Transcripts
+//!
+//! A [`Transcript`] records the messages sent by the prover of an interactive protocol and
+//! replaces the random challenges of the verifier with values derived from them, which makes the
+//! protocol non-interactive. Every message and challenge carries a label, so that the same bytes
+//! appended under different labels give different challenges.
+
+use manta_util::codec::Encode;
+
+/// Fiat-Shamir Transcript
+pub trait Transcript {
+    /// Challenge Type
+    type Challenge;
+
+    /// Appends `message` to `self` under `label`.
+    ///
+    /// # Implementation Note
+    ///
+    /// Implementations must encode `label` and `message` injectively, for example by prefixing
+    /// them with their lengths, so that no two different sequences of labeled messages are
+    /// recorded the same way.
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]);
+
+    /// Appends the encoding of `value` to `self` under `label`.
+    #[inline]
+    fn append<T>(&mut self, label: &'static [u8], value: &T)
+    where
+        T: Encode,
+    {
+        self.append_message(label, &value.to_vec())
+    }
+
+    /// Returns the challenge under `label` for all the messages appended to `self` so far. The
+    /// challenge is recorded in `self`, so that the next challenge depends on it.
+    fn challenge(&mut self, label: &'static [u8]) -> Self::Challenge;
+}
+
+impl<T> Transcript for &mut T
+where
+    T: Transcript,
+{
+    type Challenge = T::Challenge;
+
+    #[inline]
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        (**self).append_message(label, message)
+    }
+
+    #[inline]
+    fn challenge(&mut self, label: &'static [u8]) -> Self::Challenge {
+        (**self).challenge(label)
+    }
+}
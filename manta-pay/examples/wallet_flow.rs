@@ -0,0 +1,230 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! End-to-End Wallet Flow
+//!
+//! Runs two wallets against the reference ledger of the simulation using only the public APIs of
+//! `manta-pay` and `manta-accounting`:
+//!
+//! 1. Alice converts some public assets into a private note.
+//! 2. Alice sends part of that note to Bob with a private transfer.
+//! 3. Bob converts the received note back into public assets.
+//! 4. Alice proves the ownership of a Manta Pay address with an identity proof.
+//!
+//! Every step checks the balances of the wallets and of the public accounts, so this example also
+//! runs as an integration test with
+//!
+//! ```sh
+//! cargo test --release --features groth16,simulation --example wallet_flow
+//! ```
+
+use manta_accounting::{
+    transfer::{canonical::Transaction, canonical::TransactionData, IdentifiedAsset, Identifier},
+    wallet::{ledger::Write, signer::SignResponse, Wallet},
+};
+use manta_crypto::rand::{CryptoRng, OsRng, Rand, RngCore};
+use manta_pay::{
+    config::{
+        utxo::{AssetId, AssetValue},
+        AccountId, Asset, Config,
+    },
+    parameters,
+    signer::base::{identity_verification, Signer},
+    simulation::{
+        account_id_from_u64,
+        ledger::{Ledger, LedgerConnection, SharedLedger},
+        sample_signer,
+    },
+};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Simulation Wallet Type
+type SimulationWallet = Wallet<Config, LedgerConnection, Signer>;
+
+/// Asset Id of the Example
+const ASSET_ID: u128 = 1;
+
+/// Starting Public Balance of the Accounts
+const STARTING_BALANCE: AssetValue = 1000;
+
+/// Returns the public balance of `account` for the asset of the example.
+#[inline]
+async fn public_balance(ledger: &SharedLedger, account: AccountId) -> AssetValue {
+    ledger
+        .read()
+        .await
+        .public_balances(account)
+        .map(|balances| balances.value(&AssetId::from(ASSET_ID)))
+        .unwrap_or_default()
+}
+
+/// Returns the private balance of `wallet` for the asset of the example, after synchronizing it
+/// with the ledger.
+#[inline]
+async fn private_balance(wallet: &mut SimulationWallet) -> AssetValue {
+    wallet
+        .sync()
+        .await
+        .expect("Synchronizing with the ledger is not allowed to fail.");
+    wallet.balance(&AssetId::from(ASSET_ID))
+}
+
+/// Runs the end-to-end flow, panicking if any of the checks fails.
+#[inline]
+async fn run<R>(rng: &mut R)
+where
+    R: CryptoRng + RngCore + ?Sized,
+{
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        parameters::generate().expect("Generating the parameters is not allowed to fail.");
+    let (alice_account, bob_account) = (account_id_from_u64(0), account_id_from_u64(1));
+    let id = AssetId::from(ASSET_ID);
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model.clone(),
+        verifying_context.clone(),
+        parameters.clone(),
+    );
+    ledger.set_public_balance(alice_account, id, STARTING_BALANCE);
+    ledger.set_public_balance(bob_account, id, STARTING_BALANCE);
+    let ledger: SharedLedger = Arc::new(RwLock::new(ledger));
+    let mut alice = Wallet::start(
+        LedgerConnection::new(alice_account, ledger.clone()),
+        sample_signer(&proving_context, &parameters, &utxo_accumulator_model, rng),
+    )
+    .await
+    .expect("Starting a wallet is not allowed to fail.");
+    let mut bob = Wallet::start(
+        LedgerConnection::new(bob_account, ledger.clone()),
+        sample_signer(&proving_context, &parameters, &utxo_accumulator_model, rng),
+    )
+    .await
+    .expect("Starting a wallet is not allowed to fail.");
+
+    assert!(
+        alice
+            .post(Transaction::ToPrivate(Asset::new(id, 100)), None)
+            .await
+            .expect("Posting a ToPrivate transaction is not allowed to fail."),
+        "The ledger should accept the ToPrivate transaction."
+    );
+    assert_eq!(private_balance(&mut alice).await, 100);
+    assert_eq!(public_balance(&ledger, alice_account).await, 900);
+
+    let bob_address = bob
+        .address()
+        .await
+        .expect("Getting the address of a wallet is not allowed to fail.")
+        .expect("The signer of the wallet has a spending key.");
+    let SignResponse { posts } = alice
+        .sign(
+            Transaction::PrivateTransfer(Asset::new(id, 40), bob_address),
+            None,
+        )
+        .await
+        .expect("Signing a PrivateTransfer transaction is not allowed to fail.");
+    assert!(
+        LedgerConnection::new(alice_account, ledger.clone())
+            .write(posts.clone())
+            .await
+            .expect("Writing to the ledger is not allowed to fail."),
+        "The ledger should accept the PrivateTransfer transaction."
+    );
+    assert_eq!(private_balance(&mut alice).await, 60);
+    assert_eq!(private_balance(&mut bob).await, 40);
+    let received = bob
+        .transaction_data(posts)
+        .await
+        .expect("Computing the transaction data is not allowed to fail.")
+        .0
+        .into_iter()
+        .flatten()
+        .flat_map(|data| match data {
+            TransactionData::PrivateTransfer(assets) => assets,
+            _ => panic!("Bob should only see a PrivateTransfer transaction."),
+        })
+        .map(|(_, asset)| asset)
+        .collect::<Vec<_>>();
+    assert_eq!(received, [Asset::new(id, 40)]);
+
+    assert!(
+        bob.post(Transaction::ToPublic(Asset::new(id, 40), bob_account), None)
+            .await
+            .expect("Posting a ToPublic transaction is not allowed to fail."),
+        "The ledger should accept the ToPublic transaction."
+    );
+    assert_eq!(private_balance(&mut bob).await, 0);
+    assert_eq!(public_balance(&ledger, bob_account).await, 1040);
+
+    let alice_address = alice
+        .address()
+        .await
+        .expect("Getting the address of a wallet is not allowed to fail.")
+        .expect("The signer of the wallet has a spending key.");
+    let virtual_asset = IdentifiedAsset::<Config>::new(
+        Identifier::<Config>::new(false, false, rng.gen()),
+        Asset::new(id, 1),
+    );
+    let identity_proof = alice
+        .identity_proof(vec![(virtual_asset, alice_account)])
+        .await
+        .expect("Computing an identity proof is not allowed to fail.")
+        .0
+        .pop()
+        .flatten()
+        .expect("The signer of the wallet has a spending key.");
+    assert!(
+        identity_verification(
+            &identity_proof,
+            &parameters,
+            &verifying_context.to_public,
+            &utxo_accumulator_model,
+            virtual_asset,
+            alice_address,
+            alice_account,
+        )
+        .is_ok(),
+        "The identity proof of Alice should verify against the address of Alice."
+    );
+    assert!(
+        identity_verification(
+            &identity_proof,
+            &parameters,
+            &verifying_context.to_public,
+            &utxo_accumulator_model,
+            virtual_asset,
+            bob_address,
+            alice_account,
+        )
+        .is_err(),
+        "The identity proof of Alice should not verify against the address of Bob."
+    );
+}
+
+/// Runs the end-to-end flow.
+pub fn main() {
+    tokio::runtime::Builder::new_multi_thread()
+        .build()
+        .expect("Unable to start `tokio` runtime.")
+        .block_on(run(&mut OsRng));
+    println!("End-to-end wallet flow completed.");
+}
+
+/// Checks the end-to-end flow.
+#[test]
+fn wallet_flow() {
+    main()
+}
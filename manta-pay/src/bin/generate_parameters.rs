@@ -19,6 +19,7 @@
 // TODO: Deduplicate the per-circuit proving context and verifying context serialization code.
 // TODO: Print some statistics about the parameters and circuits and into a stats file as well.
 
+use manta_accounting::transfer::canonical::TransferShape;
 use manta_pay::{
     config::{utxo::protocol::BaseParameters, Parameters},
     parameters,
@@ -161,59 +162,47 @@ pub fn main() -> io::Result<()> {
 
     proving_context
         .to_private
-        .encode(IoWriter(
-            OpenOptions::new()
-                .create(true)
-                .write(true)
-                .open(proving_context_dir.join("to-private.lfs"))?,
-        ))
+        .encode(IoWriter(OpenOptions::new().create(true).write(true).open(
+            proving_context_dir.join(format!("{}.lfs", TransferShape::ToPrivate.identifier())),
+        )?))
         .unwrap();
     verifying_context
         .to_private
-        .encode(IoWriter(
-            OpenOptions::new()
-                .create(true)
-                .write(true)
-                .open(verifying_context_dir.join("to-private.dat"))?,
-        ))
+        .encode(IoWriter(OpenOptions::new().create(true).write(true).open(
+            verifying_context_dir.join(format!("{}.dat", TransferShape::ToPrivate.identifier())),
+        )?))
         .unwrap();
 
     proving_context
         .private_transfer
-        .encode(IoWriter(
-            OpenOptions::new()
-                .create(true)
-                .write(true)
-                .open(proving_context_dir.join("private-transfer.lfs"))?,
-        ))
+        .encode(IoWriter(OpenOptions::new().create(true).write(true).open(
+            proving_context_dir.join(format!(
+                "{}.lfs",
+                TransferShape::PrivateTransfer.identifier()
+            )),
+        )?))
         .unwrap();
     verifying_context
         .private_transfer
-        .encode(IoWriter(
-            OpenOptions::new()
-                .create(true)
-                .write(true)
-                .open(verifying_context_dir.join("private-transfer.dat"))?,
-        ))
+        .encode(IoWriter(OpenOptions::new().create(true).write(true).open(
+            verifying_context_dir.join(format!(
+                "{}.dat",
+                TransferShape::PrivateTransfer.identifier()
+            )),
+        )?))
         .unwrap();
 
     proving_context
         .to_public
-        .encode(IoWriter(
-            OpenOptions::new()
-                .create(true)
-                .write(true)
-                .open(proving_context_dir.join("to-public.lfs"))?,
-        ))
+        .encode(IoWriter(OpenOptions::new().create(true).write(true).open(
+            proving_context_dir.join(format!("{}.lfs", TransferShape::ToPublic.identifier())),
+        )?))
         .unwrap();
     verifying_context
         .to_public
-        .encode(IoWriter(
-            OpenOptions::new()
-                .create(true)
-                .write(true)
-                .open(verifying_context_dir.join("to-public.dat"))?,
-        ))
+        .encode(IoWriter(OpenOptions::new().create(true).write(true).open(
+            verifying_context_dir.join(format!("{}.dat", TransferShape::ToPublic.identifier())),
+        )?))
         .unwrap();
 
     Ok(())
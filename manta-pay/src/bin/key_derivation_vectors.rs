@@ -0,0 +1,67 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Key Derivation Test Vectors
+//!
+//! Prints the reference key derivation test vectors as JSON with `generate`, or checks the vectors
+//! produced by another implementation with `check <VECTORS_FILE>`.
+
+use manta_pay::{
+    parameters::load_transfer_parameters,
+    test::vectors::{validate, TestVectors},
+};
+use std::{env, fs, process};
+
+/// Prints the reference key derivation test vectors as JSON when called with `generate`, or checks
+/// the vectors in the file given after `check` against the reference implementation.
+#[inline]
+pub fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let parameters = load_transfer_parameters();
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [] | ["generate"] => println!(
+            "{}",
+            serde_json::to_string_pretty(&TestVectors::reference(&parameters))
+                .expect("Serializing the test vectors is not allowed to fail.")
+        ),
+        ["check", path] => {
+            let file = fs::read_to_string(path).expect("Unable to read the test vectors file.");
+            let vectors = serde_json::from_str(&file).unwrap_or_else(|err| {
+                eprintln!("Invalid test vectors: {err}");
+                process::exit(2);
+            });
+            let discrepancies = validate(&parameters, &vectors);
+            for discrepancy in &discrepancies {
+                println!("{discrepancy}");
+            }
+            if discrepancies.is_empty() {
+                println!("{path}: OK");
+            } else {
+                println!("{path}: MISMATCH");
+                process::exit(1);
+            }
+        }
+        _ => {
+            eprintln!("Usage: key_derivation_vectors [generate | check <VECTORS_FILE>]");
+            process::exit(2);
+        }
+    }
+}
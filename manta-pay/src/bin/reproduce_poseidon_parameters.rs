@@ -0,0 +1,46 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reproduce Poseidon Parameters
+
+use manta_pay::crypto::poseidon::reproduce::reproduce;
+use std::{env, fs, process};
+
+/// Reproduces the Poseidon parameters for the seed specification given as the first argument and
+/// prints the reproducibility report. If a file is given as the second argument, its contents are
+/// compared byte-for-byte against the reproduced parameters.
+#[inline]
+pub fn main() {
+    let mut args = env::args().skip(1);
+    let specification = args.next().unwrap_or_else(|| {
+        eprintln!("Usage: reproduce_poseidon_parameters <SPECIFICATION> [PARAMETERS_FILE]");
+        process::exit(2);
+    });
+    let reproduction = reproduce(&specification).unwrap_or_else(|err| {
+        eprintln!("Invalid seed specification: {err}");
+        process::exit(2);
+    });
+    println!("{}", reproduction.report);
+    if let Some(path) = args.next() {
+        let parameters = fs::read(&path).expect("Unable to read the parameters file.");
+        if reproduction.matches(&parameters) {
+            println!("{path}: OK");
+        } else {
+            println!("{path}: MISMATCH");
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,31 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signer RPC Description
+//!
+//! Prints the description of the signer RPC as JSON, see [`rpc`] for more.
+
+use manta_pay::signer::rpc;
+
+/// Prints the description of the signer RPC as JSON.
+#[inline]
+pub fn main() {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&rpc::description())
+            .expect("Serializing the signer RPC description is not allowed to fail.")
+    );
+}
@@ -18,31 +18,79 @@
 
 use clap::{error::ErrorKind, CommandFactory, Parser};
 use manta_crypto::rand::OsRng;
-use manta_pay::{parameters::load_parameters, simulation::Simulation};
+use manta_pay::{
+    parameters::load_parameters,
+    simulation::{
+        metrics::{write_metrics_csv, Scoring},
+        Simulation,
+    },
+};
+use std::{fs, path::PathBuf};
+
+/// Simulation Arguments
+#[derive(Debug, Parser)]
+struct Arguments {
+    /// Simulation Configuration
+    #[command(flatten)]
+    simulation: Simulation,
+
+    /// Label of the Run in the Exported Metrics
+    #[arg(long, default_value = "")]
+    label: String,
+
+    /// Path to Export the Run Metrics as CSV
+    #[arg(long)]
+    metrics_csv: Option<PathBuf>,
+
+    /// Path to Export the Run Metrics as JSON
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    #[arg(long)]
+    metrics_json: Option<PathBuf>,
+}
 
 /// Runs the Manta Pay simulation.
 pub fn main() {
-    let simulation = Simulation::parse();
+    let arguments = Arguments::parse();
     let mut rng = OsRng;
     let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
     let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
         load_parameters(directory.path()).expect("Unable to load parameters");
-    match tokio::runtime::Builder::new_multi_thread()
+    let mut metrics = match tokio::runtime::Builder::new_multi_thread()
         .worker_threads(6)
         .build()
     {
-        Ok(runtime) => runtime.block_on(simulation.run(
+        Ok(runtime) => runtime.block_on(arguments.simulation.measure(
             &parameters,
             &utxo_accumulator_model,
             &proving_context,
             verifying_context,
             &mut rng,
         )),
-        Err(err) => Simulation::command()
+        Err(err) => Arguments::command()
             .error(
                 ErrorKind::Io,
                 format_args!("Unable to start `tokio` runtime: {err}"),
             )
             .exit(),
+    };
+    metrics.label = arguments.label;
+    println!("Score: {:.2}", metrics.score());
+    if let Some(path) = arguments.metrics_csv {
+        let mut csv = String::new();
+        write_metrics_csv(&mut csv, &[metrics.clone()], &Scoring::default())
+            .expect("Writing to a string is not allowed to fail.");
+        fs::write(path, csv).expect("Unable to write the metrics CSV file.");
+    }
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    if let Some(path) = arguments.metrics_json {
+        fs::write(
+            path,
+            serde_json::to_vec_pretty(&metrics).expect("Unable to serialize the metrics."),
+        )
+        .expect("Unable to write the metrics JSON file.");
     }
+    assert!(
+        metrics.balances_match,
+        "ERROR: Simulation balance mismatch. Funds before and after the simulation do not match."
+    );
 }
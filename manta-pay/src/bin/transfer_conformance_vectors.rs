@@ -0,0 +1,126 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transfer Protocol Conformance Vectors
+//!
+//! Prints the reference transfer conformance vectors as JSON with `generate`, checks the vectors
+//! produced by another implementation with `check <VECTORS_FILE>`, or checks the encoded post
+//! built by another implementation from the inputs of the vector at `<INDEX>` with
+//! `check-post <VECTORS_FILE> <INDEX> <POST_FILE>`.
+
+use manta_pay::{
+    config::{MultiVerifyingContext, TransferPost},
+    parameters::{
+        load_private_transfer_verifying_context, load_to_private_verifying_context,
+        load_to_public_verifying_context, load_transfer_parameters, load_utxo_accumulator_model,
+    },
+    test::{
+        conformance::{validate, validate_post, ConformanceVectors},
+        vectors::Discrepancy,
+    },
+};
+use manta_util::codec::Decode;
+use std::{env, fs, process};
+
+/// Reads the conformance vectors in the file at `path`, exiting if they are not valid JSON.
+#[inline]
+fn read_vectors(path: &str) -> ConformanceVectors {
+    let file = fs::read_to_string(path).expect("Unable to read the conformance vectors file.");
+    serde_json::from_str::<ConformanceVectors>(&file).unwrap_or_else(|err| {
+        eprintln!("Invalid conformance vectors: {err}");
+        process::exit(2);
+    })
+}
+
+/// Prints `discrepancies` and the verdict for `path`, exiting if there is any discrepancy.
+#[inline]
+fn report(path: &str, discrepancies: &[Discrepancy]) {
+    for discrepancy in discrepancies {
+        println!("{discrepancy}");
+    }
+    if discrepancies.is_empty() {
+        println!("{path}: OK");
+    } else {
+        println!("{path}: MISMATCH");
+        process::exit(1);
+    }
+}
+
+/// Prints the reference transfer conformance vectors as JSON when called with `generate`, checks
+/// the vectors in the file given after `check` against the reference implementation, or checks the
+/// post in the file given after `check-post` against one of the vectors.
+#[inline]
+pub fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let parameters = load_transfer_parameters();
+    let utxo_accumulator_model = load_utxo_accumulator_model();
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [] | ["generate"] => println!(
+            "{}",
+            serde_json::to_string_pretty(&ConformanceVectors::reference(
+                &parameters,
+                &utxo_accumulator_model
+            ))
+            .expect("Serializing the conformance vectors is not allowed to fail.")
+        ),
+        ["check", path] => report(
+            path,
+            &validate(&parameters, &utxo_accumulator_model, &read_vectors(path)),
+        ),
+        ["check-post", path, index, post_path] => {
+            let vectors = read_vectors(path);
+            let vector = match index
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| vectors.vectors.get(i))
+            {
+                Some(vector) => vector,
+                _ => {
+                    eprintln!("Invalid vector index: {index}");
+                    process::exit(2);
+                }
+            };
+            let post = TransferPost::from_vec(
+                fs::read(post_path).expect("Unable to read the transfer post file."),
+            )
+            .unwrap_or_else(|_| {
+                eprintln!("Invalid transfer post encoding.");
+                process::exit(2);
+            });
+            let verifying_context = MultiVerifyingContext {
+                to_private: load_to_private_verifying_context(),
+                private_transfer: load_private_transfer_verifying_context(),
+                to_public: load_to_public_verifying_context(),
+                private_swap: None,
+            };
+            report(
+                post_path,
+                &validate_post(&parameters, &verifying_context, vector, &post),
+            )
+        }
+        _ => {
+            eprintln!(
+                "Usage: transfer_conformance_vectors [generate | check <VECTORS_FILE> | check-post <VECTORS_FILE> <INDEX> <POST_FILE>]"
+            );
+            process::exit(2);
+        }
+    }
+}
@@ -0,0 +1,295 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta-Pay Compliance Tag Configuration
+//!
+//! Compliance tags of [`Config`](super::Config) are field elements, which are bound into UTXOs by
+//! hashing them with the UTXO commitment randomness with Poseidon. See the [`compliance`] module
+//! for the tag policies which can be enforced on transfers.
+
+use crate::{
+    config::{poseidon::Spec2 as Poseidon2, utxo::AssociatedData, Compiler, ConstraintField},
+    crypto::poseidon::{self, hash::Hasher, ParameterFieldType},
+    domain,
+};
+use manta_crypto::{
+    arkworks::constraint::{fp::Fp, FpVar},
+    eclair::alloc::{Allocate, Constant},
+    hash::ArrayHashFunction,
+    rand::{Rand, RngCore, Sample},
+};
+use manta_util::codec::{Decode, DecodeError, Encode, Read, Write};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+pub use manta_accounting::transfer::compliance::{self, Inherit, TagPolicy, Uniform};
+
+/// Compliance Tag
+pub type ComplianceTag = Fp<ConstraintField>;
+
+/// Compliance Tag Variable
+pub type ComplianceTagVar = FpVar<ConstraintField>;
+
+/// Tagged Associated Data
+pub type TaggedAssociatedData = compliance::Tagged<AssociatedData, ComplianceTag>;
+
+/// Tag Binding Domain Tag
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct TagBindingDomainTag;
+
+impl poseidon::hash::DomainTag<Poseidon2> for TagBindingDomainTag {
+    #[inline]
+    fn domain_tag() -> <Poseidon2 as ParameterFieldType>::ParameterField {
+        Fp(domain::TAG_BINDING.value.into())
+    }
+}
+
+impl<COM> Constant<COM> for TagBindingDomainTag {
+    type Type = Self;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
+        let _ = (this, compiler);
+        Self
+    }
+}
+
+/// Tag Binding Type
+type TagBindingType<COM = ()> = Hasher<Poseidon2, TagBindingDomainTag, 2, COM>;
+
+/// Tag Binding
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(
+        bound(
+            deserialize = "TagBindingType<COM>: Deserialize<'de>",
+            serialize = "TagBindingType<COM>: Serialize",
+        ),
+        crate = "manta_util::serde",
+        deny_unknown_fields
+    )
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = "TagBindingType<COM>: Clone"),
+    Debug(bound = "TagBindingType<COM>: core::fmt::Debug"),
+    Default(bound = "TagBindingType<COM>: Default"),
+    Eq(bound = "TagBindingType<COM>: Eq"),
+    PartialEq(bound = "TagBindingType<COM>: PartialEq")
+)]
+pub struct TagBinding<COM = ()>(TagBindingType<COM>)
+where
+    Poseidon2: poseidon::Specification<COM>;
+
+impl Encode for TagBinding {
+    #[inline]
+    fn encode<W>(&self, writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        self.0.encode(writer)
+    }
+}
+
+impl Decode for TagBinding {
+    type Error = <Fp<ConstraintField> as Decode>::Error;
+
+    #[inline]
+    fn decode<R>(reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Ok(Self(Decode::decode(reader)?))
+    }
+}
+
+impl Sample for TagBinding {
+    #[inline]
+    fn sample<R>(distribution: (), rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        Self(rng.sample(distribution))
+    }
+}
+
+impl Constant<Compiler> for TagBinding<Compiler> {
+    type Type = TagBinding;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut Compiler) -> Self {
+        Self(this.0.as_constant(compiler))
+    }
+}
+
+impl compliance::TagBinding for TagBinding {
+    type Tag = ComplianceTag;
+    type Randomness = Fp<ConstraintField>;
+
+    #[inline]
+    fn bind(
+        &self,
+        randomness: &Self::Randomness,
+        tag: &Self::Tag,
+        compiler: &mut (),
+    ) -> Self::Randomness {
+        self.0.hash([randomness, tag], compiler)
+    }
+}
+
+impl compliance::TagBinding<Compiler> for TagBinding<Compiler> {
+    type Tag = ComplianceTagVar;
+    type Randomness = FpVar<ConstraintField>;
+
+    #[inline]
+    fn bind(
+        &self,
+        randomness: &Self::Randomness,
+        tag: &Self::Tag,
+        compiler: &mut Compiler,
+    ) -> Self::Randomness {
+        self.0.hash([randomness, tag], compiler)
+    }
+}
+
+/// Testing Framework
+#[cfg(test)]
+pub mod test {
+    use crate::config::{
+        compliance::{ComplianceTag, ComplianceTagVar, Inherit, TagBinding, TagPolicy, Uniform},
+        utxo::UtxoCommitmentScheme,
+        Compiler, ConstraintField, Group,
+    };
+    use alloc::vec::Vec;
+    use manta_accounting::transfer::{
+        compliance::TagBinding as _, utxo::protocol::UtxoCommitmentScheme as _,
+    };
+    use manta_crypto::{
+        arkworks::{
+            constraint::{fp::Fp, FpVar},
+            r1cs_std::R1CSVar,
+        },
+        eclair::alloc::{mode::Secret, Allocate},
+        rand::{OsRng, Rand},
+    };
+
+    /// Allocates `tags` as secret variables in `compiler`.
+    #[inline]
+    fn allocate_tags(tags: &[ComplianceTag], compiler: &mut Compiler) -> Vec<ComplianceTagVar> {
+        tags.iter()
+            .map(|tag| tag.as_known::<Secret, ComplianceTagVar>(compiler))
+            .collect()
+    }
+
+    /// Returns `true` if the constraints of `policy` are satisfied for `inputs` and `outputs`.
+    #[inline]
+    fn is_satisfied<P>(policy: &P, inputs: &[ComplianceTag], outputs: &[ComplianceTag]) -> bool
+    where
+        P: TagPolicy<ComplianceTagVar, Compiler>,
+    {
+        let mut compiler = Compiler::for_proofs();
+        let inputs = allocate_tags(inputs, &mut compiler);
+        let outputs = allocate_tags(outputs, &mut compiler);
+        policy.assert_propagation(&inputs, &outputs, &mut compiler);
+        compiler.is_satisfied()
+    }
+
+    /// Checks that tags are bound into UTXO commitments and that the native and circuit bindings
+    /// agree.
+    #[test]
+    fn check_tag_binding() {
+        let mut rng = OsRng;
+        let binding: TagBinding = rng.gen();
+        let scheme: UtxoCommitmentScheme = rng.gen();
+        let randomness: Fp<ConstraintField> = rng.gen();
+        let tag: ComplianceTag = rng.gen();
+        let asset_id: Fp<ConstraintField> = rng.gen();
+        let asset_value: u128 = rng.gen();
+        let receiving_key: Group = rng.gen();
+        let bound_randomness = binding.bind(&randomness, &tag, &mut ());
+        assert_ne!(
+            scheme.commit(
+                &bound_randomness,
+                &asset_id,
+                &asset_value,
+                &receiving_key,
+                &mut ()
+            ),
+            scheme.commit(
+                &binding.bind(&randomness, &rng.gen(), &mut ()),
+                &asset_id,
+                &asset_value,
+                &receiving_key,
+                &mut ()
+            ),
+            "UTXOs of notes with different tags should differ."
+        );
+        let mut compiler = Compiler::for_proofs();
+        let binding_var = binding.as_constant::<TagBinding<Compiler>>(&mut compiler);
+        let bound_randomness_var = binding_var.bind(
+            &randomness.as_known::<Secret, FpVar<_>>(&mut compiler),
+            &tag.as_known::<Secret, ComplianceTagVar>(&mut compiler),
+            &mut compiler,
+        );
+        assert_eq!(
+            bound_randomness.0,
+            bound_randomness_var
+                .value()
+                .expect("Variable has a known value."),
+            "Native and circuit bindings should agree."
+        );
+        assert!(compiler.is_satisfied(), "Constraints should be satisfied.");
+    }
+
+    /// Checks that the [`Uniform`] and [`Inherit`] policies are enforced natively and in-circuit.
+    #[test]
+    fn check_tag_policies() {
+        let mut rng = OsRng;
+        let [first, second, other] = [(); 3].map(|_| rng.gen::<_, ComplianceTag>());
+        assert!(Uniform.permits(&[first, first], &first, &mut ()));
+        assert!(!Uniform.permits(&[first, second], &first, &mut ()));
+        assert!(Inherit.permits(&[first, second], &second, &mut ()));
+        assert!(!Inherit.permits(&[first, second], &other, &mut ()));
+        assert!(!Inherit.permits(&[], &first, &mut ()));
+        assert!(
+            is_satisfied(&Uniform, &[first, first], &[first, first]),
+            "Uniform tags should be accepted."
+        );
+        assert!(
+            is_satisfied(&Uniform, &[], &[other]),
+            "Transfers without inputs should accept any tag."
+        );
+        assert!(
+            !is_satisfied(&Uniform, &[first, second], &[first, first]),
+            "Mixed tags should be rejected."
+        );
+        assert!(
+            is_satisfied(&Inherit, &[first, second], &[second, first]),
+            "Inherited tags should be accepted."
+        );
+        assert!(
+            !is_satisfied(&Inherit, &[first, second], &[first, other]),
+            "Tags which are not inherited should be rejected."
+        );
+    }
+}
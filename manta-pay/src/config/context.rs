@@ -0,0 +1,123 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Proving and Verifying Context Consistency
+//!
+//! Signers only hold proving contexts and ledgers only hold verifying contexts, so when the two
+//! are distributed separately a signer can end up building proofs which the ledger rejects. The
+//! verifying context of a proving context can always be extracted from it with
+//! [`verifying_context`], and the functions of this module compare such extracted contexts against
+//! the verifying contexts of the ledger by their [`VerifyingContextChecksum`].
+//!
+//! [`verifying_context`]: manta_crypto::arkworks::groth16::ProvingContext::verifying_context
+
+use crate::{
+    config::{MultiProvingContext, MultiVerifyingContext, ProvingContext, VerifyingContext},
+    domain,
+};
+use blake2::{Blake2s256, Digest};
+use manta_accounting::transfer::canonical::TransferShape;
+use manta_crypto::arkworks::{groth16::Compression, serialize::CanonicalSerialize};
+
+/// Verifying Context Checksum
+///
+/// The Blake2s hash of the compressed verifying key of a [`VerifyingContext`]. The other components
+/// of a [`VerifyingContext`] are derived from its verifying key, so two verifying contexts with the
+/// same checksum accept the same proofs.
+pub type VerifyingContextChecksum = [u8; 32];
+
+/// Returns the checksum of `verifying_key`.
+#[inline]
+fn verifying_key_checksum<T>(verifying_key: &T) -> VerifyingContextChecksum
+where
+    T: CanonicalSerialize,
+{
+    let mut hasher = Blake2s256::new();
+    Digest::update(&mut hasher, domain::VERIFYING_CONTEXT_CHECKSUM.bytes);
+    Digest::update(
+        &mut hasher,
+        Compression::Compressed.serialize(verifying_key),
+    );
+    hasher.finalize().into()
+}
+
+/// Returns the [`VerifyingContextChecksum`] of `verifying_context`.
+#[inline]
+pub fn verifying_context_checksum(
+    verifying_context: &VerifyingContext,
+) -> VerifyingContextChecksum {
+    verifying_key_checksum(verifying_context.verifying_key())
+}
+
+/// Returns the [`VerifyingContextChecksum`] of the verifying context extracted from
+/// `proving_context`, without preparing that verifying context.
+#[inline]
+pub fn proving_context_checksum(proving_context: &ProvingContext) -> VerifyingContextChecksum {
+    verifying_key_checksum(proving_context.verifying_key())
+}
+
+/// Returns `true` if the verifying context extracted from `proving_context` has the given
+/// `checksum`.
+#[inline]
+pub fn check_proving_context(
+    proving_context: &ProvingContext,
+    checksum: &VerifyingContextChecksum,
+) -> bool {
+    proving_context_checksum(proving_context) == *checksum
+}
+
+/// Context Mismatch Error
+///
+/// This `struct` is the error state of [`check_contexts`], holding the first [`TransferShape`]
+/// whose proving context does not match its verifying context.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ContextMismatch(pub TransferShape);
+
+/// Checks that every proving context in `proving_context` matches the verifying context of the
+/// same [`TransferShape`] in `verifying_context`, comparing their [`VerifyingContextChecksum`]s.
+/// The optional [`PrivateSwap`](TransferShape::PrivateSwap) contexts match if both of them are
+/// missing.
+///
+/// Signers and ledgers which load their contexts from separate sources should call this function
+/// on startup.
+#[inline]
+pub fn check_contexts(
+    proving_context: &MultiProvingContext,
+    verifying_context: &MultiVerifyingContext,
+) -> Result<(), ContextMismatch> {
+    for shape in [
+        TransferShape::ToPrivate,
+        TransferShape::PrivateTransfer,
+        TransferShape::ToPublic,
+        TransferShape::PrivateSwap,
+    ] {
+        let matches = match (
+            proving_context.try_select(shape),
+            verifying_context.try_select(shape),
+        ) {
+            (Some(proving_context), Some(verifying_context)) => check_proving_context(
+                proving_context,
+                &verifying_context_checksum(verifying_context),
+            ),
+            (None, None) => true,
+            _ => false,
+        };
+        if !matches {
+            return Err(ContextMismatch(shape));
+        }
+    }
+    Ok(())
+}
@@ -16,6 +16,7 @@
 
 //! Manta-Pay Configuration
 
+use crate::crypto::transcript::Blake2sTranscript;
 use manta_accounting::transfer;
 use manta_crypto::{
     arkworks::{
@@ -28,12 +29,26 @@ use manta_crypto::{
         groth16,
     },
     merkle_tree::path,
+    sigma,
 };
 
+use crate::domain;
+use blake2::{Blake2s256, Digest};
+use manta_util::codec::Encode;
+
 #[cfg(feature = "bs58")]
-use {alloc::string::String, manta_util::codec::Encode};
+use alloc::string::String;
+
+#[cfg(feature = "hybrid-authorization")]
+use manta_crypto::signature::winternitz;
+
+#[cfg(feature = "payment-channels")]
+use alloc::vec::Vec;
 
+pub mod compliance;
+pub mod context;
 pub mod poseidon;
+pub mod statement;
 pub mod utxo;
 
 /// Pairing Curve Type
@@ -63,6 +78,18 @@ pub type Group = algebra::Group<GroupCurve>;
 /// Embedded Group Variable Type
 pub type GroupVar = algebra::GroupVar<GroupCurve, GroupCurveVar>;
 
+/// Sigma Protocol Transcript Type
+pub type SigmaTranscript = Blake2sTranscript<EmbeddedScalarField>;
+
+/// Schnorr Proof of Knowledge Protocol Type
+pub type SchnorrProtocol = sigma::Schnorr<EmbeddedScalar, Group>;
+
+/// Discrete Logarithm Equality Protocol Type
+pub type DiscreteLogEqualityProtocol = sigma::DiscreteLogEquality<EmbeddedScalar, Group>;
+
+/// Pedersen Opening Protocol Type
+pub type PedersenOpeningProtocol = sigma::PedersenOpening<EmbeddedScalar, Group>;
+
 /// Constraint Field
 pub type ConstraintField = bn254::Fr;
 
@@ -84,6 +111,35 @@ pub type ProofSystemError = groth16::Error;
 /// Account Identifier
 pub type AccountId = [u8; 32];
 
+/// Transaction Id
+///
+/// The Blake2s hash of the encoded posts of a transaction.
+pub type TransactionId = [u8; 32];
+
+/// Transaction Id Hash
+///
+/// Hashes the encoded posts of a transaction into its [`TransactionId`].
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TransactionIdHash;
+
+impl<C> transfer::TransactionIdFunction<C> for TransactionIdHash
+where
+    C: transfer::Configuration,
+    transfer::TransferPost<C>: Encode,
+{
+    type TransactionId = TransactionId;
+
+    #[inline]
+    fn transaction_id(posts: &[transfer::TransferPost<C>]) -> Self::TransactionId {
+        let mut hasher = Blake2s256::new();
+        Digest::update(&mut hasher, domain::TRANSACTION_ID.bytes);
+        for post in posts {
+            Digest::update(&mut hasher, post.to_vec());
+        }
+        hasher.finalize().into()
+    }
+}
+
 /// Transfer Configuration
 #[derive(derivative::Derivative)]
 #[derivative(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
@@ -105,6 +161,10 @@ impl transfer::Configuration for Config {
     type UtxoAccumulatorWitness = utxo::UtxoAccumulatorWitness;
     type UtxoAccumulatorOutput = utxo::UtxoAccumulatorOutput;
     type UtxoAccumulatorItemHash = utxo::UtxoAccumulatorItemHash;
+    type TransactionIdFunction = TransactionIdHash;
+    type SpendingKey = utxo::SpendingKey;
+    type AuthorizationContext = utxo::AuthorizationContext;
+    type DecryptionKey = utxo::DecryptionKey;
     type Parameters = utxo::Parameters;
     type AuthorizationContextVar = utxo::AuthorizationContextVar;
     type AuthorizationProofVar = utxo::AuthorizationProofVar;
@@ -122,6 +182,52 @@ impl transfer::Configuration for Config {
     type ProofSystem = ProofSystem;
 }
 
+#[cfg(feature = "hybrid-authorization")]
+impl transfer::hybrid::Configuration for Config {
+    type PostQuantumSignatureScheme = PostQuantumSignatureScheme;
+
+    #[inline]
+    fn derive_post_quantum_signing_key(
+        spending_key: &SpendingKey,
+        authorization_key: &Group,
+    ) -> winternitz::Digest {
+        let mut hasher = Blake2s256::new();
+        Digest::update(&mut hasher, domain::WINTERNITZ_KEY.bytes);
+        Digest::update(&mut hasher, spending_key.to_vec());
+        Digest::update(&mut hasher, authorization_key.to_vec());
+        hasher.finalize().into()
+    }
+
+    #[inline]
+    fn post_quantum_message(
+        body_with_accounts: &transfer::BodyWithAccountsRef<Self>,
+        authorization_key: &Group,
+    ) -> winternitz::Digest {
+        let mut hasher = Blake2s256::new();
+        Digest::update(&mut hasher, domain::WINTERNITZ_MESSAGE.bytes);
+        Digest::update(&mut hasher, body_with_accounts.to_vec());
+        Digest::update(&mut hasher, authorization_key.to_vec());
+        hasher.finalize().into()
+    }
+}
+
+#[cfg(feature = "payment-channels")]
+impl transfer::channel::Configuration for Config {
+    type StateSignatureScheme = utxo::SignatureScheme;
+
+    #[inline]
+    fn state_message(state: &ChannelState) -> Vec<u8> {
+        let mut message = domain::CHANNEL_STATE.bytes.to_vec();
+        message.extend_from_slice(&state.channel.0);
+        message.extend_from_slice(&state.sequence.to_le_bytes());
+        for balance in &state.balances {
+            message.extend_from_slice(&balance.to_le_bytes());
+        }
+        message.push(state.is_final.into());
+        message
+    }
+}
+
 /// Transfer Parameters
 pub type Parameters = transfer::Parameters<Config>;
 
@@ -161,6 +267,12 @@ pub type Utxo = transfer::Utxo<Config>;
 /// Note Type
 pub type Note = transfer::Note<Config>;
 
+/// Encrypted Memo Type
+pub type Memo = transfer::Memo<Config>;
+
+/// Memo Plaintext Type
+pub type MemoPlaintext = transfer::MemoPlaintext<Config>;
+
 /// Nullifier Type
 pub type Nullifier = transfer::Nullifier<Config>;
 
@@ -191,6 +303,12 @@ pub type PrivateTransfer = transfer::canonical::PrivateTransfer<Config>;
 /// To-Public Transfer Type
 pub type ToPublic = transfer::canonical::ToPublic<Config>;
 
+/// Private Swap Transfer Type
+pub type PrivateSwap = transfer::canonical::PrivateSwap<Config>;
+
+/// Dynamic Transfer Type
+pub type DynamicTransfer = transfer::dynamic::DynamicTransfer<Config>;
+
 /// Proving Context Type
 pub type ProvingContext = transfer::ProvingContext<Config>;
 
@@ -203,6 +321,14 @@ pub type MultiProvingContext = transfer::canonical::MultiProvingContext<Config>;
 /// Multi-Verifying Context Type
 pub type MultiVerifyingContext = transfer::canonical::MultiVerifyingContext<Config>;
 
+/// Proving Context Registry Type
+pub type ProvingContextRegistry = transfer::dynamic::ProvingContextRegistry<Config>;
+
+/// Verifying Context Provider Type
+#[cfg(feature = "std")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+pub type VerifyingContextProvider<L> = transfer::dynamic::VerifyingContextProvider<Config, L>;
+
 /// Transaction Type
 pub type Transaction = transfer::canonical::Transaction<Config>;
 
@@ -221,9 +347,38 @@ pub type IdentifiedAsset = transfer::IdentifiedAsset<Config>;
 /// Identity Type
 pub type IdentityProof = transfer::IdentityProof<Config>;
 
+/// Ledger Anchor Type
+pub type LedgerAnchor = transfer::LedgerAnchor<Config>;
+
+/// Holdings Type
+pub type Holdings = transfer::holdings::Holdings<Config>;
+
+/// Holdings Proof Type
+pub type HoldingsProof = transfer::holdings::HoldingsProof<Config>;
+
 /// Transaction Data Type
 pub type TransactionData = transfer::canonical::TransactionData<Config>;
 
+/// Post-Quantum Signature Scheme
+#[cfg(feature = "hybrid-authorization")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "hybrid-authorization")))]
+pub type PostQuantumSignatureScheme = winternitz::Winternitz<utxo::PostQuantumHashFunction>;
+
+/// Hybrid Transfer Post Type
+#[cfg(feature = "hybrid-authorization")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "hybrid-authorization")))]
+pub type HybridTransferPost = transfer::hybrid::HybridTransferPost<Config>;
+
+/// Payment Channel Type
+#[cfg(feature = "payment-channels")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "payment-channels")))]
+pub type Channel = transfer::channel::Channel<Config>;
+
+/// Payment Channel State Type
+#[cfg(feature = "payment-channels")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "payment-channels")))]
+pub type ChannelState = transfer::channel::ChannelState<Config>;
+
 /// Converts an [`Address`] into a base58-encoded string.
 #[cfg(feature = "bs58")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "bs58")))]
@@ -53,7 +53,7 @@ where
 {
     type Field = ConstraintField;
 
-    const SBOX_EXPONENT: u64 = 5;
+    type SBox = poseidon::sbox::Quintic;
 }
 
 impl<const ARITY: usize, COM> Constant<COM> for Spec<ARITY> {
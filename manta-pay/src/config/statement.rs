@@ -0,0 +1,308 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Circuit Statements
+//!
+//! A [`CircuitStatement`] is a machine-readable description of the statement proven by the
+//! transfer circuit of one [`TransferShape`]: the layout of its public input with the meaning of
+//! each entry, and the constraint groups of the circuit with their sizes. Statements are read off
+//! the compiled circuits themselves, so auditors can serialize them and diff them against the
+//! protocol specification to catch changes in the implemented statement.
+
+use crate::config::{
+    utxo::{AssetIdVar, AssetValueVar},
+    Compiler, Config, FullParametersRef, Parameters, PrivateSwap, PrivateTransfer, ToPrivate,
+    ToPublic, UtxoAccumulatorModel,
+};
+use alloc::{format, string::String, vec::Vec};
+use manta_accounting::transfer::{
+    canonical::TransferShape, has_public_participants, requires_authorization, AuthorizationVar,
+    ReceiverVar, SenderVar,
+};
+use manta_crypto::{
+    constraint::measure::{Measure, Size},
+    eclair::alloc::{mode::Public, Allocator},
+};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Public Input Entry
+///
+/// Range of field elements of the public input which holds one value of the transfer.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PublicInput {
+    /// Name of the Value, like `senders[0]`
+    pub name: String,
+
+    /// Meaning of the Value
+    pub description: String,
+
+    /// Index of the First Field Element of the Value
+    pub offset: usize,
+
+    /// Number of Field Elements of the Value
+    pub length: usize,
+}
+
+/// Constraint Group
+///
+/// Constraints and variables added by one of the gadgets of the circuit.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ConstraintGroup {
+    /// Labels of the Enclosing Groups and of this Group Separated by `/`
+    pub path: String,
+
+    /// Number of Instances of the Group in the Circuit
+    pub count: usize,
+
+    /// Size of all the Instances of the Group
+    pub size: Size,
+}
+
+/// Circuit Statement
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CircuitStatement {
+    /// Transfer Shape
+    pub shape: TransferShape,
+
+    /// Identifier of the Proving and Verifying Contexts
+    pub identifier: String,
+
+    /// Public Input Layout
+    ///
+    /// The entries are in the order in which the verifier reads them, without gaps.
+    pub public_inputs: Vec<PublicInput>,
+
+    /// Constraint Groups
+    ///
+    /// The groups are in the order in which they are first closed while building the circuit,
+    /// so nested groups come before the group enclosing them.
+    pub constraint_groups: Vec<ConstraintGroup>,
+
+    /// Size of the Whole Circuit
+    ///
+    /// The public variables of the circuit include the constant one, so there is one more of them
+    /// than there are field elements in the public input.
+    pub size: Size,
+}
+
+impl CircuitStatement {
+    /// Returns the number of field elements of the public input of `self`.
+    #[inline]
+    pub fn public_input_length(&self) -> usize {
+        self.public_inputs.iter().map(|input| input.length).sum()
+    }
+
+    /// Returns the constraint group of `self` with the given `path`, if any.
+    #[inline]
+    pub fn constraint_group(&self, path: &str) -> Option<&ConstraintGroup> {
+        self.constraint_groups
+            .iter()
+            .find(|group| group.path == path)
+    }
+}
+
+/// Public Input Layout Builder
+struct PublicInputLayout {
+    /// Compiler used to Measure the Values
+    compiler: Compiler,
+
+    /// Layout
+    public_inputs: Vec<PublicInput>,
+}
+
+impl PublicInputLayout {
+    /// Builds a new empty [`PublicInputLayout`].
+    #[inline]
+    fn new() -> Self {
+        Self {
+            compiler: Compiler::for_contexts(),
+            public_inputs: Vec::new(),
+        }
+    }
+
+    /// Appends the value with `name` and `description` to the layout, measuring its length as the
+    /// number of public variables that `allocate` adds to the compiler.
+    #[inline]
+    fn push<F>(&mut self, name: String, description: &str, allocate: F)
+    where
+        F: FnOnce(&mut Compiler),
+    {
+        let before = self.compiler.measure();
+        allocate(&mut self.compiler);
+        let length = self
+            .compiler
+            .measure()
+            .checked_sub(before)
+            .and_then(|size| size.public_variable_count)
+            .expect("Measurements should increase when adding more constraints.");
+        let offset = self
+            .public_inputs
+            .last()
+            .map(|input| input.offset + input.length)
+            .unwrap_or_default();
+        self.public_inputs.push(PublicInput {
+            name,
+            description: description.into(),
+            offset,
+            length,
+        });
+    }
+}
+
+/// Returns the public input layout of the circuit with the given `shape`.
+#[inline]
+fn public_inputs(shape: TransferShape) -> Vec<PublicInput> {
+    let (sources, senders, receivers, sinks) = shape.arity();
+    let mut layout = PublicInputLayout::new();
+    if requires_authorization(senders) {
+        layout.push(
+            "authorization_key".into(),
+            "Authorization key which must sign the transfer post, derived from the spending key \
+             of the senders with a secret randomness.",
+            |compiler| {
+                let _: AuthorizationVar<Config> = compiler.allocate_unknown();
+            },
+        );
+    }
+    if has_public_participants(sources, sinks) {
+        layout.push(
+            "asset_id".into(),
+            "Asset id shared by the sources, the sinks and every secret asset of the transfer.",
+            |compiler| {
+                let _ = compiler.allocate_unknown::<Public, AssetIdVar>();
+            },
+        );
+    }
+    for i in 0..sources {
+        layout.push(
+            format!("sources[{i}]"),
+            "Value withdrawn from the public account of the source.",
+            |compiler| {
+                let _ = compiler.allocate_unknown::<Public, AssetValueVar>();
+            },
+        );
+    }
+    for i in 0..senders {
+        layout.push(
+            format!("senders[{i}]"),
+            "Root of the UTXO accumulator which contains the spent UTXO, followed by the \
+             commitment to its nullifier and, during a nullifier migration, the flag which marks \
+             the nullifier of a legacy note.",
+            |compiler| {
+                let _: SenderVar<Config> = compiler.allocate_unknown();
+            },
+        );
+    }
+    for i in 0..receivers {
+        layout.push(
+            format!("receivers[{i}]"),
+            "New UTXO, made of its transparency flag, its public asset and its commitment, \
+             followed by the incoming note encrypted for its owner.",
+            |compiler| {
+                let _: ReceiverVar<Config> = compiler.allocate_unknown();
+            },
+        );
+    }
+    for i in 0..sinks {
+        layout.push(
+            format!("sinks[{i}]"),
+            "Value deposited into the public account of the sink.",
+            |compiler| {
+                let _ = compiler.allocate_unknown::<Public, AssetValueVar>();
+            },
+        );
+    }
+    layout.public_inputs
+}
+
+/// Builds the [`CircuitStatement`] of the circuit with the given `shape` for `parameters` and
+/// `utxo_accumulator_model`.
+#[inline]
+pub fn circuit_statement(
+    shape: TransferShape,
+    parameters: &Parameters,
+    utxo_accumulator_model: &UtxoAccumulatorModel,
+) -> CircuitStatement {
+    let full_parameters = FullParametersRef::new(parameters, utxo_accumulator_model);
+    let mut compiler = Compiler::for_contexts().instrumented();
+    match shape {
+        TransferShape::ToPrivate => {
+            ToPrivate::build_unknown_constraints(full_parameters, &mut compiler)
+        }
+        TransferShape::PrivateTransfer => {
+            PrivateTransfer::build_unknown_constraints(full_parameters, &mut compiler)
+        }
+        TransferShape::ToPublic => {
+            ToPublic::build_unknown_constraints(full_parameters, &mut compiler)
+        }
+        TransferShape::PrivateSwap => {
+            PrivateSwap::build_unknown_constraints(full_parameters, &mut compiler)
+        }
+    }
+    let constraint_groups = compiler
+        .profiler()
+        .expect("The compiler is instrumented.")
+        .summary()
+        .into_iter()
+        .map(|region| ConstraintGroup {
+            path: region.path,
+            count: region.count,
+            size: region.size,
+        })
+        .collect();
+    CircuitStatement {
+        shape,
+        identifier: shape.identifier().into(),
+        public_inputs: public_inputs(shape),
+        constraint_groups,
+        size: compiler.measure(),
+    }
+}
+
+/// Builds the [`CircuitStatement`]s of the circuits of every [`TransferShape`] for `parameters`
+/// and `utxo_accumulator_model`.
+#[inline]
+pub fn circuit_statements(
+    parameters: &Parameters,
+    utxo_accumulator_model: &UtxoAccumulatorModel,
+) -> Vec<CircuitStatement> {
+    [
+        TransferShape::ToPrivate,
+        TransferShape::PrivateTransfer,
+        TransferShape::ToPublic,
+        TransferShape::PrivateSwap,
+    ]
+    .into_iter()
+    .map(|shape| circuit_statement(shape, parameters, utxo_accumulator_model))
+    .collect()
+}
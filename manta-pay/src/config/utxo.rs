@@ -28,6 +28,7 @@ use crate::{
         encryption::aes,
         poseidon::{self, encryption::BlockArray, hash::Hasher, ParameterFieldType},
     },
+    domain,
 };
 use alloc::{vec, vec::Vec};
 use blake2::{
@@ -37,7 +38,7 @@ use blake2::{
 use core::{convert::Infallible, fmt::Debug, marker::PhantomData};
 use manta_accounting::{
     asset::{self, Asset},
-    wallet::ledger,
+    wallet::ledger::{self, LeafIndex, NoteIndex},
 };
 use manta_crypto::{
     accumulator::ItemHashFunction,
@@ -60,10 +61,18 @@ use manta_crypto::{
     signature::schnorr,
 };
 use manta_util::{
-    codec::{Decode, DecodeError, Encode, Read, Write},
+    codec::{
+        tagged::{self, Tag, TaggedEncoder, TaggedFields},
+        Decode, DecodeError, Encode, Read, Write,
+    },
+    into_array_unchecked,
+    zeroize::Zeroizing,
     Array,
 };
 
+#[cfg(feature = "hybrid-authorization")]
+use manta_crypto::signature::winternitz;
+
 #[cfg(feature = "serde")]
 use manta_util::serde::{Deserialize, Serialize};
 
@@ -84,6 +93,9 @@ pub type AssetValue = u128;
 /// Asset Value Variable Type
 pub type AssetValueVar = U128<FpVar<ConstraintField>>;
 
+/// Spending Key Type
+pub type SpendingKey = utxo::auth::SpendingKey<Parameters>;
+
 /// Authorization Context Type
 pub type AuthorizationContext = utxo::auth::AuthorizationContext<Parameters>;
 
@@ -111,6 +123,9 @@ pub type ViewingKey = EmbeddedScalar;
 /// Viewing Key Variable Type
 pub type ViewingKeyVar = EmbeddedScalarVar;
 
+/// Decryption Key Type
+pub type DecryptionKey = utxo::DecryptionKey<Parameters>;
+
 /// Receiving Key Type
 pub type ReceivingKey = Group;
 
@@ -171,6 +186,9 @@ pub type FullIncomingNote = protocol::FullIncomingNote<Config>;
 /// Outgoing Note Type
 pub type OutgoingNote = protocol::OutgoingNote<Config>;
 
+/// Encrypted Memo Type
+pub type Memo = protocol::Memo<Config>;
+
 /// Nullifier Type
 pub type Nullifier = utxo::Nullifier<Parameters>;
 
@@ -279,7 +297,7 @@ pub struct UtxoCommitmentSchemeDomainTag;
 impl poseidon::hash::DomainTag<Poseidon5> for UtxoCommitmentSchemeDomainTag {
     #[inline]
     fn domain_tag() -> <Poseidon5 as ParameterFieldType>::ParameterField {
-        Fp(0u8.into()) // FIXME: Use a real domain tag
+        Fp(domain::UTXO_COMMITMENT_SCHEME.value.into())
     }
 }
 
@@ -293,6 +311,25 @@ impl<COM> Constant<COM> for UtxoCommitmentSchemeDomainTag {
     }
 }
 
+/// Current Utxo Commitment Domain Tag
+///
+/// Domain tag of the hash which wraps a legacy UTXO commitment into the current one in
+/// [`UtxoCommitmentScheme::commit_current`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct CurrentUtxoCommitmentDomainTag;
+
+impl poseidon::hash::DomainTag<Poseidon5> for CurrentUtxoCommitmentDomainTag {
+    #[inline]
+    fn domain_tag() -> <Poseidon5 as ParameterFieldType>::ParameterField {
+        Fp(domain::CURRENT_UTXO_COMMITMENT.value.into())
+    }
+}
+
 /// Utxo Commitment Scheme Type
 type UtxoCommitmentSchemeType<COM = ()> = Hasher<Poseidon5, UtxoCommitmentSchemeDomainTag, 5, COM>;
 
@@ -391,6 +428,24 @@ impl protocol::UtxoCommitmentScheme for UtxoCommitmentScheme {
             compiler,
         )
     }
+
+    #[inline]
+    fn commit_current(
+        &self,
+        randomness: &Self::Randomness,
+        asset_id: &Self::AssetId,
+        asset_value: &Self::AssetValue,
+        receiving_key: &Self::ReceivingKey,
+        compiler: &mut (),
+    ) -> Self::Commitment {
+        let commitment = self.commit(randomness, asset_id, asset_value, receiving_key, compiler);
+        let zero = Fp(ConstraintField::from(0u8));
+        self.0
+            .hash_with_domain_tag::<CurrentUtxoCommitmentDomainTag>(
+                [&commitment, &zero, &zero, &zero, &zero],
+                compiler,
+            )
+    }
 }
 
 impl protocol::UtxoCommitmentScheme<Compiler> for UtxoCommitmentScheme<Compiler> {
@@ -420,6 +475,60 @@ impl protocol::UtxoCommitmentScheme<Compiler> for UtxoCommitmentScheme<Compiler>
             compiler,
         )
     }
+
+    #[inline]
+    fn commit_current(
+        &self,
+        randomness: &Self::Randomness,
+        asset_id: &Self::AssetId,
+        asset_value: &Self::AssetValue,
+        receiving_key: &Self::ReceivingKey,
+        compiler: &mut Compiler,
+    ) -> Self::Commitment {
+        let commitment = self.commit(randomness, asset_id, asset_value, receiving_key, compiler);
+        let zero = Fp(ConstraintField::from(0u8)).as_constant::<FpVar<_>>(compiler);
+        self.0
+            .hash_with_domain_tag::<CurrentUtxoCommitmentDomainTag>(
+                [&commitment, &zero, &zero, &zero, &zero],
+                compiler,
+            )
+    }
+}
+
+/// Utxo Commitment Scheme Configuration
+///
+/// Native UTXO commitment scheme of a [`Config`] together with its in-circuit variant. The
+/// [`Config`] uses the Poseidon [`UtxoCommitmentScheme`] by default, but can be instantiated with
+/// any other commitment scheme over the same field, like a Pedersen or Sinsemilla commitment, by
+/// implementing this trait for it and using `Config<(), S>` in place of [`Config`].
+///
+/// # Note
+///
+/// The randomness and the commitment are fixed to field elements since they are encrypted in the
+/// incoming notes and hashed into the UTXO accumulator with the rest of this configuration.
+pub trait UtxoCommitmentSchemeConfiguration:
+    protocol::UtxoCommitmentScheme<
+    AssetId = AssetId,
+    AssetValue = AssetValue,
+    ReceivingKey = ReceivingKey,
+    Randomness = Fp<ConstraintField>,
+    Commitment = Fp<ConstraintField>,
+>
+{
+    /// Utxo Commitment Scheme Variable Type
+    type Var: Constant<Compiler, Type = Self>
+        + protocol::UtxoCommitmentScheme<
+            Compiler,
+            AssetId = AssetIdVar,
+            AssetValue = AssetValueVar,
+            ReceivingKey = ReceivingKeyVar,
+            Randomness = FpVar<ConstraintField>,
+            Commitment = FpVar<ConstraintField>,
+        >;
+}
+
+impl UtxoCommitmentSchemeConfiguration for UtxoCommitmentScheme {
+    type Var = UtxoCommitmentScheme<Compiler>;
 }
 
 /// Viewing Key Derivation Function Domain Tag
@@ -434,7 +543,7 @@ pub struct ViewingKeyDerivationFunctionDomainTag;
 impl poseidon::hash::DomainTag<Poseidon2> for ViewingKeyDerivationFunctionDomainTag {
     #[inline]
     fn domain_tag() -> <Poseidon2 as ParameterFieldType>::ParameterField {
-        Fp(0u8.into()) // FIXME: Use a real domain tag
+        Fp(domain::VIEWING_KEY_DERIVATION_FUNCTION.value.into())
     }
 }
 
@@ -568,18 +677,30 @@ impl protocol::ViewingKeyDerivationFunction<Compiler> for ViewingKeyDerivationFu
     serde(crate = "manta_util::serde", deny_unknown_fields)
 )]
 #[derive(derivative::Derivative)]
-#[derivative(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct IncomingEncryptionSchemeConverter<COM = ()>(PhantomData<COM>);
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = ""),
+    Eq(bound = ""),
+    Hash(bound = ""),
+    Ord(bound = ""),
+    PartialEq(bound = ""),
+    PartialOrd(bound = "")
+)]
+pub struct IncomingEncryptionSchemeConverter<COM = (), U = UtxoCommitmentScheme>(
+    PhantomData<(COM, U)>,
+);
 
-impl encryption::HeaderType for IncomingEncryptionSchemeConverter {
+impl<U> encryption::HeaderType for IncomingEncryptionSchemeConverter<(), U> {
     type Header = encryption::EmptyHeader;
 }
 
-impl encryption::HeaderType for IncomingEncryptionSchemeConverter<Compiler> {
+impl<U> encryption::HeaderType for IncomingEncryptionSchemeConverter<Compiler, U> {
     type Header = encryption::EmptyHeader<Compiler>;
 }
 
-impl encryption::convert::header::Header for IncomingEncryptionSchemeConverter {
+impl<U> encryption::convert::header::Header for IncomingEncryptionSchemeConverter<(), U> {
     type TargetHeader = encryption::Header<IncomingPoseidonEncryptionScheme>;
 
     #[inline]
@@ -589,7 +710,9 @@ impl encryption::convert::header::Header for IncomingEncryptionSchemeConverter {
     }
 }
 
-impl encryption::convert::header::Header<Compiler> for IncomingEncryptionSchemeConverter<Compiler> {
+impl<U> encryption::convert::header::Header<Compiler>
+    for IncomingEncryptionSchemeConverter<Compiler, U>
+{
     type TargetHeader = encryption::Header<IncomingPoseidonEncryptionScheme<Compiler>>;
 
     #[inline]
@@ -599,15 +722,15 @@ impl encryption::convert::header::Header<Compiler> for IncomingEncryptionSchemeC
     }
 }
 
-impl encryption::EncryptionKeyType for IncomingEncryptionSchemeConverter {
+impl<U> encryption::EncryptionKeyType for IncomingEncryptionSchemeConverter<(), U> {
     type EncryptionKey = Group;
 }
 
-impl encryption::EncryptionKeyType for IncomingEncryptionSchemeConverter<Compiler> {
+impl<U> encryption::EncryptionKeyType for IncomingEncryptionSchemeConverter<Compiler, U> {
     type EncryptionKey = GroupVar;
 }
 
-impl encryption::convert::key::Encryption for IncomingEncryptionSchemeConverter {
+impl<U> encryption::convert::key::Encryption for IncomingEncryptionSchemeConverter<(), U> {
     type TargetEncryptionKey = encryption::EncryptionKey<IncomingPoseidonEncryptionScheme>;
 
     #[inline]
@@ -616,8 +739,8 @@ impl encryption::convert::key::Encryption for IncomingEncryptionSchemeConverter
     }
 }
 
-impl encryption::convert::key::Encryption<Compiler>
-    for IncomingEncryptionSchemeConverter<Compiler>
+impl<U> encryption::convert::key::Encryption<Compiler>
+    for IncomingEncryptionSchemeConverter<Compiler, U>
 {
     type TargetEncryptionKey =
         encryption::EncryptionKey<IncomingPoseidonEncryptionScheme<Compiler>>;
@@ -628,15 +751,15 @@ impl encryption::convert::key::Encryption<Compiler>
     }
 }
 
-impl encryption::DecryptionKeyType for IncomingEncryptionSchemeConverter {
+impl<U> encryption::DecryptionKeyType for IncomingEncryptionSchemeConverter<(), U> {
     type DecryptionKey = Group;
 }
 
-impl encryption::DecryptionKeyType for IncomingEncryptionSchemeConverter<Compiler> {
+impl<U> encryption::DecryptionKeyType for IncomingEncryptionSchemeConverter<Compiler, U> {
     type DecryptionKey = GroupVar;
 }
 
-impl encryption::convert::key::Decryption for IncomingEncryptionSchemeConverter {
+impl<U> encryption::convert::key::Decryption for IncomingEncryptionSchemeConverter<(), U> {
     type TargetDecryptionKey = encryption::DecryptionKey<IncomingPoseidonEncryptionScheme>;
 
     #[inline]
@@ -645,8 +768,8 @@ impl encryption::convert::key::Decryption for IncomingEncryptionSchemeConverter
     }
 }
 
-impl encryption::convert::key::Decryption<Compiler>
-    for IncomingEncryptionSchemeConverter<Compiler>
+impl<U> encryption::convert::key::Decryption<Compiler>
+    for IncomingEncryptionSchemeConverter<Compiler, U>
 {
     type TargetDecryptionKey =
         encryption::DecryptionKey<IncomingPoseidonEncryptionScheme<Compiler>>;
@@ -657,15 +780,24 @@ impl encryption::convert::key::Decryption<Compiler>
     }
 }
 
-impl encryption::PlaintextType for IncomingEncryptionSchemeConverter {
-    type Plaintext = protocol::IncomingPlaintext<Config>;
+impl<U> encryption::PlaintextType for IncomingEncryptionSchemeConverter<(), U>
+where
+    U: UtxoCommitmentSchemeConfiguration,
+{
+    type Plaintext = protocol::IncomingPlaintext<Config<(), U>>;
 }
 
-impl encryption::PlaintextType for IncomingEncryptionSchemeConverter<Compiler> {
-    type Plaintext = protocol::IncomingPlaintext<Config<Compiler>, Compiler>;
+impl<U> encryption::PlaintextType for IncomingEncryptionSchemeConverter<Compiler, U>
+where
+    U: UtxoCommitmentSchemeConfiguration,
+{
+    type Plaintext = protocol::IncomingPlaintext<Config<Compiler, U>, Compiler>;
 }
 
-impl encryption::convert::plaintext::Forward for IncomingEncryptionSchemeConverter {
+impl<U> encryption::convert::plaintext::Forward for IncomingEncryptionSchemeConverter<(), U>
+where
+    U: UtxoCommitmentSchemeConfiguration,
+{
     type TargetPlaintext = encryption::Plaintext<IncomingPoseidonEncryptionScheme>;
 
     #[inline]
@@ -684,8 +816,10 @@ impl encryption::convert::plaintext::Forward for IncomingEncryptionSchemeConvert
     }
 }
 
-impl encryption::convert::plaintext::Forward<Compiler>
-    for IncomingEncryptionSchemeConverter<Compiler>
+impl<U> encryption::convert::plaintext::Forward<Compiler>
+    for IncomingEncryptionSchemeConverter<Compiler, U>
+where
+    U: UtxoCommitmentSchemeConfiguration,
 {
     type TargetPlaintext = encryption::Plaintext<IncomingPoseidonEncryptionScheme<Compiler>>;
 
@@ -705,11 +839,17 @@ impl encryption::convert::plaintext::Forward<Compiler>
     }
 }
 
-impl encryption::DecryptedPlaintextType for IncomingEncryptionSchemeConverter {
+impl<U> encryption::DecryptedPlaintextType for IncomingEncryptionSchemeConverter<(), U>
+where
+    U: UtxoCommitmentSchemeConfiguration,
+{
     type DecryptedPlaintext = Option<<Self as encryption::PlaintextType>::Plaintext>;
 }
 
-impl encryption::convert::plaintext::Reverse for IncomingEncryptionSchemeConverter {
+impl<U> encryption::convert::plaintext::Reverse for IncomingEncryptionSchemeConverter<(), U>
+where
+    U: UtxoCommitmentSchemeConfiguration,
+{
     type TargetDecryptedPlaintext =
         encryption::DecryptedPlaintext<IncomingPoseidonEncryptionScheme>;
 
@@ -731,8 +871,8 @@ impl encryption::convert::plaintext::Reverse for IncomingEncryptionSchemeConvert
     }
 }
 
-impl<COM> Constant<COM> for IncomingEncryptionSchemeConverter<COM> {
-    type Type = IncomingEncryptionSchemeConverter;
+impl<COM, U> Constant<COM> for IncomingEncryptionSchemeConverter<COM, U> {
+    type Type = IncomingEncryptionSchemeConverter<(), U>;
 
     #[inline]
     fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
@@ -746,20 +886,42 @@ pub type IncomingPoseidonEncryptionScheme<COM = ()> =
     poseidon::encryption::FixedDuplexer<1, Poseidon3, COM>;
 
 /// Incoming Base Encryption Scheme
-pub type IncomingBaseEncryptionScheme<COM = ()> = encryption::convert::key::Converter<
-    encryption::convert::header::Converter<
-        encryption::convert::plaintext::Converter<
-            IncomingPoseidonEncryptionScheme<COM>,
-            IncomingEncryptionSchemeConverter<COM>,
+pub type IncomingBaseEncryptionScheme<COM = (), U = UtxoCommitmentScheme> =
+    encryption::convert::key::Converter<
+        encryption::convert::header::Converter<
+            encryption::convert::plaintext::Converter<
+                IncomingPoseidonEncryptionScheme<COM>,
+                IncomingEncryptionSchemeConverter<COM, U>,
+            >,
+            IncomingEncryptionSchemeConverter<COM, U>,
         >,
-        IncomingEncryptionSchemeConverter<COM>,
-    >,
-    IncomingEncryptionSchemeConverter<COM>,
->;
+        IncomingEncryptionSchemeConverter<COM, U>,
+    >;
 
 /// AES Plaintext Size
 pub const AES_PLAINTEXT_SIZE: usize = 80;
 
+/// Incoming Plaintext Version
+///
+/// Version of the fixed layout of [`AES_PLAINTEXT_SIZE`]-byte incoming plaintexts. Elements of
+/// [`ConstraintField`] fit in 254 bits, so the two most significant bits of the UTXO commitment
+/// randomness, which makes up the first 32 bytes of the plaintext, carry the version without
+/// growing the plaintext. Notes which were encrypted before the layout was versioned have these
+/// bits unset, which is the current version.
+pub const INCOMING_PLAINTEXT_VERSION: u8 = 0;
+
+/// Position of the [`INCOMING_PLAINTEXT_VERSION`] Bits in the Last Byte of the UTXO Commitment
+/// Randomness
+const INCOMING_PLAINTEXT_VERSION_SHIFT: u8 = 6;
+
+/// Returns the version of the layout of the incoming `plaintext`.
+///
+/// See [`INCOMING_PLAINTEXT_VERSION`] for more.
+#[inline]
+pub fn incoming_plaintext_version(plaintext: &[u8; AES_PLAINTEXT_SIZE]) -> u8 {
+    plaintext[31] >> INCOMING_PLAINTEXT_VERSION_SHIFT
+}
+
 /// AES Ciphertext Size
 pub const AES_CIPHERTEXT_SIZE: usize = AES_PLAINTEXT_SIZE + 16;
 
@@ -882,14 +1044,24 @@ impl<COM> encryption::Decrypt for IncomingAESEncryptionScheme<COM> {
     serde(crate = "manta_util::serde", deny_unknown_fields)
 )]
 #[derive(derivative::Derivative)]
-#[derivative(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct IncomingAESConverter<COM = ()>(PhantomData<COM>);
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = ""),
+    Eq(bound = ""),
+    Hash(bound = ""),
+    Ord(bound = ""),
+    PartialEq(bound = ""),
+    PartialOrd(bound = "")
+)]
+pub struct IncomingAESConverter<COM = (), U = UtxoCommitmentScheme>(PhantomData<(COM, U)>);
 
-impl<COM> encryption::HeaderType for IncomingAESConverter<COM> {
+impl<COM, U> encryption::HeaderType for IncomingAESConverter<COM, U> {
     type Header = encryption::EmptyHeader<COM>;
 }
 
-impl<COM> encryption::convert::header::Header<COM> for IncomingAESConverter<COM> {
+impl<COM, U> encryption::convert::header::Header<COM> for IncomingAESConverter<COM, U> {
     type TargetHeader = encryption::Header<IncomingAESEncryptionScheme<COM>>;
 
     #[inline]
@@ -898,27 +1070,27 @@ impl<COM> encryption::convert::header::Header<COM> for IncomingAESConverter<COM>
     }
 }
 
-impl encryption::EncryptionKeyType for IncomingAESConverter {
+impl<U> encryption::EncryptionKeyType for IncomingAESConverter<(), U> {
     type EncryptionKey = Group;
 }
 
-impl encryption::EncryptionKeyType for IncomingAESConverter<Compiler> {
+impl<U> encryption::EncryptionKeyType for IncomingAESConverter<Compiler, U> {
     type EncryptionKey = GroupVar;
 }
 
-impl encryption::convert::key::Encryption for IncomingAESConverter {
+impl<U> encryption::convert::key::Encryption for IncomingAESConverter<(), U> {
     type TargetEncryptionKey = encryption::EncryptionKey<IncomingAESEncryptionScheme>;
 
     #[inline]
     fn as_target(source: &Self::EncryptionKey, _: &mut ()) -> Self::TargetEncryptionKey {
-        let key = source.to_vec();
+        let key = Zeroizing::new(source.to_vec());
         let mut hasher = Blake2s256::new();
-        Digest::update(&mut hasher, key);
+        Digest::update(&mut hasher, key.as_slice());
         hasher.finalize().into()
     }
 }
 
-impl encryption::convert::key::Encryption<Compiler> for IncomingAESConverter<Compiler> {
+impl<U> encryption::convert::key::Encryption<Compiler> for IncomingAESConverter<Compiler, U> {
     type TargetEncryptionKey = encryption::EncryptionKey<IncomingAESEncryptionScheme<Compiler>>;
 
     #[inline]
@@ -930,27 +1102,27 @@ impl encryption::convert::key::Encryption<Compiler> for IncomingAESConverter<Com
     }
 }
 
-impl encryption::DecryptionKeyType for IncomingAESConverter {
+impl<U> encryption::DecryptionKeyType for IncomingAESConverter<(), U> {
     type DecryptionKey = Group;
 }
 
-impl encryption::DecryptionKeyType for IncomingAESConverter<Compiler> {
+impl<U> encryption::DecryptionKeyType for IncomingAESConverter<Compiler, U> {
     type DecryptionKey = GroupVar;
 }
 
-impl encryption::convert::key::Decryption for IncomingAESConverter {
+impl<U> encryption::convert::key::Decryption for IncomingAESConverter<(), U> {
     type TargetDecryptionKey = encryption::DecryptionKey<IncomingAESEncryptionScheme>;
 
     #[inline]
     fn as_target(source: &Self::DecryptionKey, _: &mut ()) -> Self::TargetDecryptionKey {
-        let key = source.to_vec();
+        let key = Zeroizing::new(source.to_vec());
         let mut hasher = Blake2s256::new();
-        Digest::update(&mut hasher, key);
+        Digest::update(&mut hasher, key.as_slice());
         hasher.finalize().into()
     }
 }
 
-impl encryption::convert::key::Decryption<Compiler> for IncomingAESConverter<Compiler> {
+impl<U> encryption::convert::key::Decryption<Compiler> for IncomingAESConverter<Compiler, U> {
     type TargetDecryptionKey = encryption::DecryptionKey<IncomingAESEncryptionScheme<Compiler>>;
 
     #[inline]
@@ -962,15 +1134,24 @@ impl encryption::convert::key::Decryption<Compiler> for IncomingAESConverter<Com
     }
 }
 
-impl encryption::PlaintextType for IncomingAESConverter {
-    type Plaintext = protocol::IncomingPlaintext<Config>;
+impl<U> encryption::PlaintextType for IncomingAESConverter<(), U>
+where
+    U: UtxoCommitmentSchemeConfiguration,
+{
+    type Plaintext = protocol::IncomingPlaintext<Config<(), U>>;
 }
 
-impl encryption::PlaintextType for IncomingAESConverter<Compiler> {
-    type Plaintext = protocol::IncomingPlaintext<Config<Compiler>, Compiler>;
+impl<U> encryption::PlaintextType for IncomingAESConverter<Compiler, U>
+where
+    U: UtxoCommitmentSchemeConfiguration,
+{
+    type Plaintext = protocol::IncomingPlaintext<Config<Compiler, U>, Compiler>;
 }
 
-impl encryption::convert::plaintext::Forward for IncomingAESConverter {
+impl<U> encryption::convert::plaintext::Forward for IncomingAESConverter<(), U>
+where
+    U: UtxoCommitmentSchemeConfiguration,
+{
     type TargetPlaintext = encryption::Plaintext<IncomingAESEncryptionScheme>;
 
     #[inline]
@@ -986,11 +1167,15 @@ impl encryption::convert::plaintext::Forward for IncomingAESConverter {
             target_plaintext.len(),
             AES_PLAINTEXT_SIZE
         );
+        target_plaintext[31] |= INCOMING_PLAINTEXT_VERSION << INCOMING_PLAINTEXT_VERSION_SHIFT;
         Array::from_unchecked(target_plaintext)
     }
 }
 
-impl encryption::convert::plaintext::Forward<Compiler> for IncomingAESConverter<Compiler> {
+impl<U> encryption::convert::plaintext::Forward<Compiler> for IncomingAESConverter<Compiler, U>
+where
+    U: UtxoCommitmentSchemeConfiguration,
+{
     type TargetPlaintext = encryption::Plaintext<IncomingAESEncryptionScheme<Compiler>>;
 
     #[inline]
@@ -1002,26 +1187,29 @@ impl encryption::convert::plaintext::Forward<Compiler> for IncomingAESConverter<
     }
 }
 
-impl encryption::DecryptedPlaintextType for IncomingAESConverter {
+impl<U> encryption::DecryptedPlaintextType for IncomingAESConverter<(), U>
+where
+    U: UtxoCommitmentSchemeConfiguration,
+{
     type DecryptedPlaintext = Option<<Self as encryption::PlaintextType>::Plaintext>;
 }
 
-impl encryption::convert::plaintext::Reverse for IncomingAESConverter {
+impl<U> encryption::convert::plaintext::Reverse for IncomingAESConverter<(), U>
+where
+    U: UtxoCommitmentSchemeConfiguration,
+{
     type TargetDecryptedPlaintext = encryption::DecryptedPlaintext<IncomingAESEncryptionScheme>;
 
     #[inline]
     fn into_source(target: Self::TargetDecryptedPlaintext, _: &mut ()) -> Self::DecryptedPlaintext {
-        let bytes_vector = target?.0;
-        let utxo_randomness_bytes = bytes_vector[0..32].to_vec();
-        let asset_id_bytes = bytes_vector[32..64].to_vec();
-        let asset_value_bytes =
-            manta_util::Array::<u8, 16>::from_vec(bytes_vector[64..80].to_vec()).0;
-        let utxo_randomness = Fp::<ConstraintField>::from_vec(utxo_randomness_bytes)
-            .expect("Error while converting the bytes into a field element.");
-        let asset_id = Fp::<ConstraintField>::from_vec(asset_id_bytes)
-            .expect("Error while converting the bytes into a field element.");
-        let asset_value = u128::from_le_bytes(asset_value_bytes);
-        let source_plaintext = protocol::IncomingPlaintext::<Config>::new(
+        let bytes_vector = Zeroizing::new(target?.0);
+        if incoming_plaintext_version(&bytes_vector) != INCOMING_PLAINTEXT_VERSION {
+            return None;
+        }
+        let utxo_randomness = Fp::<ConstraintField>::decode(&bytes_vector[0..32]).ok()?;
+        let asset_id = Fp::<ConstraintField>::decode(&bytes_vector[32..64]).ok()?;
+        let asset_value = u128::from_le_bytes(into_array_unchecked(&bytes_vector[64..80]));
+        let source_plaintext = protocol::IncomingPlaintext::<Config<(), U>>::new(
             utxo_randomness,
             asset::Asset {
                 id: asset_id,
@@ -1032,8 +1220,8 @@ impl encryption::convert::plaintext::Reverse for IncomingAESConverter {
     }
 }
 
-impl<COM> Constant<COM> for IncomingAESConverter<COM> {
-    type Type = IncomingAESConverter;
+impl<COM, U> Constant<COM> for IncomingAESConverter<COM, U> {
+    type Type = IncomingAESConverter<(), U>;
 
     #[inline]
     fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
@@ -1043,17 +1231,63 @@ impl<COM> Constant<COM> for IncomingAESConverter<COM> {
 }
 
 /// Incoming Base AES
-pub type IncomingBaseAES<COM = ()> = encryption::convert::key::Converter<
+pub type IncomingBaseAES<COM = (), U = UtxoCommitmentScheme> = encryption::convert::key::Converter<
     encryption::convert::header::Converter<
         encryption::convert::plaintext::Converter<
             IncomingAESEncryptionScheme<COM>,
-            IncomingAESConverter<COM>,
+            IncomingAESConverter<COM, U>,
         >,
-        IncomingAESConverter<COM>,
+        IncomingAESConverter<COM, U>,
     >,
-    IncomingAESConverter<COM>,
+    IncomingAESConverter<COM, U>,
 >;
 
+/// Tag of the UTXO Commitment Randomness in Tagged Incoming Plaintexts
+pub const INCOMING_PLAINTEXT_UTXO_COMMITMENT_RANDOMNESS_TAG: Tag = tagged::REQUIRED | 1;
+
+/// Tag of the Asset Id in Tagged Incoming Plaintexts
+pub const INCOMING_PLAINTEXT_ASSET_ID_TAG: Tag = tagged::REQUIRED | 2;
+
+/// Tag of the Asset Value in Tagged Incoming Plaintexts
+pub const INCOMING_PLAINTEXT_ASSET_VALUE_TAG: Tag = tagged::REQUIRED | 3;
+
+/// Encodes `plaintext` with the [`tagged`] encoding.
+///
+/// Unlike the fixed layout used by [`IncomingAESConverter`], new fields can be added to this
+/// encoding without breaking older decoders, as long as the new fields are optional.
+#[inline]
+pub fn encode_tagged_incoming_plaintext(
+    plaintext: &protocol::IncomingPlaintext<Config>,
+) -> Vec<u8> {
+    TaggedEncoder::new()
+        .field(
+            INCOMING_PLAINTEXT_UTXO_COMMITMENT_RANDOMNESS_TAG,
+            &plaintext.utxo_commitment_randomness,
+        )
+        .field(INCOMING_PLAINTEXT_ASSET_ID_TAG, &plaintext.asset.id)
+        .field(INCOMING_PLAINTEXT_ASSET_VALUE_TAG, &plaintext.asset.value)
+        .finish()
+}
+
+/// Decodes an incoming plaintext from its [`tagged`] encoding in `bytes`, skipping any optional
+/// fields that this version does not know about.
+#[inline]
+pub fn decode_tagged_incoming_plaintext(
+    bytes: &[u8],
+) -> Option<protocol::IncomingPlaintext<Config>> {
+    let mut fields = TaggedFields::from_vec(bytes.to_vec()).ok()?;
+    let utxo_commitment_randomness = fields
+        .required(INCOMING_PLAINTEXT_UTXO_COMMITMENT_RANDOMNESS_TAG)
+        .ok()?;
+    let id = fields.required(INCOMING_PLAINTEXT_ASSET_ID_TAG).ok()?;
+    let value = fields.required(INCOMING_PLAINTEXT_ASSET_VALUE_TAG).ok()?;
+    fields.finish().ok()?;
+    Some(protocol::IncomingPlaintext::new(
+        utxo_commitment_randomness,
+        asset::Asset { id, value },
+    ))
+}
+
 /// Utxo Accumulator Item Hash Domain Tag
 #[cfg_attr(
     feature = "serde",
@@ -1066,7 +1300,7 @@ pub struct UtxoAccumulatorItemHashDomainTag;
 impl poseidon::hash::DomainTag<Poseidon4> for UtxoAccumulatorItemHashDomainTag {
     #[inline]
     fn domain_tag() -> <Poseidon4 as ParameterFieldType>::ParameterField {
-        Fp(0u8.into()) // FIXME: Use a real domain tag
+        Fp(domain::UTXO_ACCUMULATOR_ITEM_HASH.value.into())
     }
 }
 
@@ -1152,11 +1386,14 @@ impl Constant<Compiler> for UtxoAccumulatorItemHash<Compiler> {
     }
 }
 
-impl ItemHashFunction<Utxo> for UtxoAccumulatorItemHash {
+impl<U> ItemHashFunction<protocol::Utxo<Config<(), U>>> for UtxoAccumulatorItemHash
+where
+    U: UtxoCommitmentSchemeConfiguration,
+{
     type Item = UtxoAccumulatorItem;
 
     #[inline]
-    fn item_hash(&self, value: &Utxo, compiler: &mut ()) -> Self::Item {
+    fn item_hash(&self, value: &protocol::Utxo<Config<(), U>>, compiler: &mut ()) -> Self::Item {
         self.0.hash(
             [
                 &Fp(value.is_transparent.into()),
@@ -1169,11 +1406,19 @@ impl ItemHashFunction<Utxo> for UtxoAccumulatorItemHash {
     }
 }
 
-impl ItemHashFunction<UtxoVar, Compiler> for UtxoAccumulatorItemHash<Compiler> {
+impl<U> ItemHashFunction<protocol::Utxo<Config<Compiler, U>, Compiler>, Compiler>
+    for UtxoAccumulatorItemHash<Compiler>
+where
+    U: UtxoCommitmentSchemeConfiguration,
+{
     type Item = UtxoAccumulatorItemVar;
 
     #[inline]
-    fn item_hash(&self, value: &UtxoVar, compiler: &mut Compiler) -> Self::Item {
+    fn item_hash(
+        &self,
+        value: &protocol::Utxo<Config<Compiler, U>, Compiler>,
+        compiler: &mut Compiler,
+    ) -> Self::Item {
         self.0.hash(
             [
                 &value.is_transparent.clone().into(),
@@ -1204,7 +1449,7 @@ pub struct InnerHashDomainTag;
 impl poseidon::hash::DomainTag<Poseidon2> for InnerHashDomainTag {
     #[inline]
     fn domain_tag() -> <Poseidon2 as ParameterFieldType>::ParameterField {
-        Fp(0u8.into()) // FIXME: Use a real domain tag
+        Fp(domain::INNER_HASH.value.into())
     }
 }
 
@@ -1324,7 +1569,7 @@ impl merkle_tree::forest::Configuration for MerkleTreeConfiguration {
     #[inline]
     fn tree_index(leaf: &merkle_tree::Leaf<Self>) -> Self::Index {
         let mut hasher = Blake2sVar::new(1).unwrap();
-        hasher.update(b"manta-v1.0.0/merkle-tree-shard-function");
+        hasher.update(domain::MERKLE_TREE_SHARD_FUNCTION.bytes);
         let mut buffer = Vec::new();
         leaf.0
             .serialize_unchecked(&mut buffer)
@@ -1378,7 +1623,7 @@ pub struct NullifierCommitmentSchemeDomainTag;
 impl poseidon::hash::DomainTag<Poseidon3> for NullifierCommitmentSchemeDomainTag {
     #[inline]
     fn domain_tag() -> <Poseidon3 as ParameterFieldType>::ParameterField {
-        Fp(0u8.into()) // FIXME: Use a real domain tag
+        Fp(domain::NULLIFIER_COMMITMENT_SCHEME.value.into())
     }
 }
 
@@ -1392,6 +1637,25 @@ impl<COM> Constant<COM> for NullifierCommitmentSchemeDomainTag {
     }
 }
 
+/// Asset-Scoped Nullifier Item Domain Tag
+///
+/// Domain tag of the hash which scopes a UTXO accumulator item to its asset id before it is
+/// committed to by [`NullifierCommitmentScheme::commit_with_asset_id`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct AssetScopedNullifierItemDomainTag;
+
+impl poseidon::hash::DomainTag<Poseidon3> for AssetScopedNullifierItemDomainTag {
+    #[inline]
+    fn domain_tag() -> <Poseidon3 as ParameterFieldType>::ParameterField {
+        Fp(domain::ASSET_SCOPED_NULLIFIER_ITEM.value.into())
+    }
+}
+
 /// Nullifier Commitment Scheme Type
 type NullifierCommitmentSchemeType<COM = ()> =
     Hasher<Poseidon3, NullifierCommitmentSchemeDomainTag, 3, COM>;
@@ -1467,6 +1731,7 @@ impl Constant<Compiler> for NullifierCommitmentScheme<Compiler> {
 impl protocol::NullifierCommitmentScheme for NullifierCommitmentScheme {
     type ProofAuthorizationKey = ProofAuthorizationKey;
     type UtxoAccumulatorItem = Fp<ConstraintField>;
+    type AssetId = AssetId;
     type Commitment = Fp<ConstraintField>;
 
     #[inline]
@@ -1485,11 +1750,29 @@ impl protocol::NullifierCommitmentScheme for NullifierCommitmentScheme {
             compiler,
         )
     }
+
+    #[inline]
+    fn commit_with_asset_id(
+        &self,
+        proof_authorization_key: &Self::ProofAuthorizationKey,
+        item: &Self::UtxoAccumulatorItem,
+        asset_id: &Self::AssetId,
+        compiler: &mut (),
+    ) -> Self::Commitment {
+        let scoped_item = self
+            .0
+            .hash_with_domain_tag::<AssetScopedNullifierItemDomainTag>(
+                [item, asset_id, &Fp(0u8.into())],
+                compiler,
+            );
+        self.commit(proof_authorization_key, &scoped_item, compiler)
+    }
 }
 
 impl protocol::NullifierCommitmentScheme<Compiler> for NullifierCommitmentScheme<Compiler> {
     type ProofAuthorizationKey = ProofAuthorizationKeyVar;
     type UtxoAccumulatorItem = FpVar<ConstraintField>;
+    type AssetId = AssetIdVar;
     type Commitment = FpVar<ConstraintField>;
 
     #[inline]
@@ -1508,6 +1791,24 @@ impl protocol::NullifierCommitmentScheme<Compiler> for NullifierCommitmentScheme
             compiler,
         )
     }
+
+    #[inline]
+    fn commit_with_asset_id(
+        &self,
+        proof_authorization_key: &Self::ProofAuthorizationKey,
+        item: &Self::UtxoAccumulatorItem,
+        asset_id: &Self::AssetId,
+        compiler: &mut Compiler,
+    ) -> Self::Commitment {
+        let zero = Fp(ConstraintField::from(0u8)).as_constant::<FpVar<_>>(compiler);
+        let scoped_item = self
+            .0
+            .hash_with_domain_tag::<AssetScopedNullifierItemDomainTag>(
+                [item, asset_id, &zero],
+                compiler,
+            );
+        self.commit(proof_authorization_key, &scoped_item, compiler)
+    }
 }
 
 /// Outgoing AES Plaintext Size
@@ -1664,9 +1965,9 @@ impl encryption::convert::key::Encryption for OutgoingAESConverter {
 
     #[inline]
     fn as_target(source: &Self::EncryptionKey, _: &mut ()) -> Self::TargetEncryptionKey {
-        let key = source.to_vec();
+        let key = Zeroizing::new(source.to_vec());
         let mut hasher = Blake2s256::new();
-        Digest::update(&mut hasher, key);
+        Digest::update(&mut hasher, key.as_slice());
         hasher.finalize().into()
     }
 }
@@ -1696,9 +1997,9 @@ impl encryption::convert::key::Decryption for OutgoingAESConverter {
 
     #[inline]
     fn as_target(source: &Self::DecryptionKey, _: &mut ()) -> Self::TargetDecryptionKey {
-        let key = source.to_vec();
+        let key = Zeroizing::new(source.to_vec());
         let mut hasher = Blake2s256::new();
-        Digest::update(&mut hasher, key);
+        Digest::update(&mut hasher, key.as_slice());
         hasher.finalize().into()
     }
 }
@@ -1763,15 +2064,12 @@ impl encryption::convert::plaintext::Reverse for OutgoingAESConverter {
 
     #[inline]
     fn into_source(target: Self::TargetDecryptedPlaintext, _: &mut ()) -> Self::DecryptedPlaintext {
-        let bytes_vector = target?.0;
-        let asset_id_bytes = bytes_vector[0..32].to_vec();
-        let asset_value_bytes = manta_util::Array::<u8, 16>::from_vec(
-            bytes_vector[32..OUT_AES_PLAINTEXT_SIZE].to_vec(),
-        )
-        .0;
-        let asset_id = Fp::<ConstraintField>::from_vec(asset_id_bytes)
+        let bytes_vector = Zeroizing::new(target?.0);
+        let asset_id = Fp::<ConstraintField>::decode(&bytes_vector[0..32])
             .expect("Error while converting the bytes into a field element.");
-        let asset_value = u128::from_le_bytes(asset_value_bytes);
+        let asset_value = u128::from_le_bytes(into_array_unchecked(
+            &bytes_vector[32..OUT_AES_PLAINTEXT_SIZE],
+        ));
         let source_plaintext = asset::Asset {
             id: asset_id,
             value: asset_value,
@@ -1802,23 +2100,122 @@ pub type OutgoingBaseAES<COM = ()> = encryption::convert::key::Converter<
     OutgoingAESConverter<COM>,
 >;
 
-/// Address Partition Function
+/// Memo Size
+///
+/// Size in bytes of the plaintext of a memo, which is padded by its sender.
+pub const MEMO_SIZE: usize = 64;
+
+/// Memo Ciphertext Size
+pub const MEMO_CIPHERTEXT_SIZE: usize = aes::ciphertext_size(MEMO_SIZE);
+
+/// Memo AES
+pub type MemoAes = aes::FixedNonceAesGcm<MEMO_SIZE, MEMO_CIPHERTEXT_SIZE>;
+
+/// Memo Plaintext
+pub type MemoPlaintext = Array<u8, MEMO_SIZE>;
+
+/// Memo AES Converter
+///
+/// Converts the shared secrets of the memo encryption scheme into [`MemoAes`] keys. Memos are
+/// only encrypted and decrypted natively, so there is no compiler version of this converter.
 #[cfg_attr(
     feature = "serde",
     derive(Deserialize, Serialize),
     serde(crate = "manta_util::serde", deny_unknown_fields)
 )]
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct AddressPartitionFunction;
+pub struct MemoAESConverter;
+
+impl MemoAESConverter {
+    /// Hashes the shared secret `key` into a [`MemoAes`] key.
+    #[inline]
+    fn hash_key(key: &Group) -> [u8; 32] {
+        let key = Zeroizing::new(key.to_vec());
+        let mut hasher = Blake2s256::new();
+        Digest::update(&mut hasher, key.as_slice());
+        hasher.finalize().into()
+    }
+}
+
+impl encryption::HeaderType for MemoAESConverter {
+    type Header = EmptyHeader;
+}
 
-impl protocol::AddressPartitionFunction for AddressPartitionFunction {
-    type Address = Address;
+impl encryption::convert::header::Header for MemoAESConverter {
+    type TargetHeader = encryption::Header<MemoAes>;
+
+    #[inline]
+    fn as_target(source: &Self::Header, _: &mut ()) -> Self::TargetHeader {
+        let _ = source;
+    }
+}
+
+impl encryption::EncryptionKeyType for MemoAESConverter {
+    type EncryptionKey = Group;
+}
+
+impl encryption::convert::key::Encryption for MemoAESConverter {
+    type TargetEncryptionKey = encryption::EncryptionKey<MemoAes>;
+
+    #[inline]
+    fn as_target(source: &Self::EncryptionKey, _: &mut ()) -> Self::TargetEncryptionKey {
+        Self::hash_key(source)
+    }
+}
+
+impl encryption::DecryptionKeyType for MemoAESConverter {
+    type DecryptionKey = Group;
+}
+
+impl encryption::convert::key::Decryption for MemoAESConverter {
+    type TargetDecryptionKey = encryption::DecryptionKey<MemoAes>;
+
+    #[inline]
+    fn as_target(source: &Self::DecryptionKey, _: &mut ()) -> Self::TargetDecryptionKey {
+        Self::hash_key(source)
+    }
+}
+
+/// Memo Base AES
+pub type MemoBaseAES = encryption::convert::key::Converter<
+    encryption::convert::header::Converter<MemoAes, MemoAESConverter>,
+    MemoAESConverter,
+>;
+
+/// Address Partition Function
+///
+/// The partition only depends on the receiving key of the address, so the same function is used
+/// for every UTXO commitment scheme `U` of the [`Config`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(derivative::Derivative)]
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = ""),
+    Eq(bound = ""),
+    Hash(bound = ""),
+    Ord(bound = ""),
+    PartialEq(bound = ""),
+    PartialOrd(bound = "")
+)]
+pub struct AddressPartitionFunction<U = UtxoCommitmentScheme>(PhantomData<U>);
+
+impl<U> protocol::AddressPartitionFunction for AddressPartitionFunction<U>
+where
+    U: UtxoCommitmentSchemeConfiguration,
+{
+    type Address = protocol::Address<Config<(), U>>;
     type Partition = u8;
 
     #[inline]
     fn partition(&self, address: &Self::Address) -> Self::Partition {
         let mut hasher = Blake2sVar::new(1).unwrap();
-        hasher.update(b"manta-v1.0.0/address-partition-function");
+        hasher.update(domain::ADDRESS_PARTITION_FUNCTION.bytes);
         let mut buffer = Vec::new();
         address
             .receiving_key
@@ -1834,7 +2231,7 @@ impl protocol::AddressPartitionFunction for AddressPartitionFunction {
     }
 }
 
-impl Encode for AddressPartitionFunction {
+impl<U> Encode for AddressPartitionFunction<U> {
     #[inline]
     fn encode<W>(&self, writer: W) -> Result<(), W::Error>
     where
@@ -1845,7 +2242,7 @@ impl Encode for AddressPartitionFunction {
     }
 }
 
-impl Decode for AddressPartitionFunction {
+impl<U> Decode for AddressPartitionFunction<U> {
     type Error = ();
 
     #[inline]
@@ -1854,18 +2251,18 @@ impl Decode for AddressPartitionFunction {
         R: Read,
     {
         let _ = reader;
-        Ok(Self)
+        Ok(Self::default())
     }
 }
 
-impl Sample for AddressPartitionFunction {
+impl<U> Sample for AddressPartitionFunction<U> {
     #[inline]
     fn sample<R>(distribution: (), rng: &mut R) -> Self
     where
         R: RngCore + ?Sized,
     {
         let _ = (distribution, rng);
-        Self
+        Self::default()
     }
 }
 
@@ -1894,7 +2291,7 @@ impl schnorr::HashFunction for SchnorrHashFunction {
         _: &mut (),
     ) -> EmbeddedScalar {
         let mut hasher = Blake2s256::new();
-        Digest::update(&mut hasher, b"manta-pay/1.0.0/Schnorr-hash");
+        Digest::update(&mut hasher, domain::SCHNORR_HASH.bytes);
         Digest::update(
             &mut hasher,
             affine_point_as_bytes::<GroupCurve>(&verifying_key.0),
@@ -1944,13 +2341,49 @@ impl Sample for SchnorrHashFunction {
     }
 }
 
+/// Post-Quantum Hash Function
+#[cfg(feature = "hybrid-authorization")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "hybrid-authorization")))]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PostQuantumHashFunction;
+
+#[cfg(feature = "hybrid-authorization")]
+impl winternitz::HashFunction for PostQuantumHashFunction {
+    #[inline]
+    fn hash(&self, tweak: &[u8], input: &[u8]) -> winternitz::Digest {
+        let mut hasher = Blake2s256::new();
+        Digest::update(&mut hasher, domain::WINTERNITZ_HASH.bytes);
+        Digest::update(&mut hasher, tweak);
+        Digest::update(&mut hasher, input);
+        hasher.finalize().into()
+    }
+}
+
 /// MantaPay Configuration
+///
+/// The UTXO commitment scheme `U` defaults to the Poseidon [`UtxoCommitmentScheme`], see
+/// [`UtxoCommitmentSchemeConfiguration`] for plugging in other commitment schemes.
 #[derive(derivative::Derivative)]
-#[derivative(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub struct Config<COM = ()>(PhantomData<COM>);
+#[derivative(
+    Clone(bound = ""),
+    Copy(bound = ""),
+    Debug(bound = ""),
+    Default(bound = ""),
+    Eq(bound = ""),
+    Hash(bound = ""),
+    Ord(bound = ""),
+    PartialEq(bound = ""),
+    PartialOrd(bound = "")
+)]
+pub struct Config<COM = (), U = UtxoCommitmentScheme>(PhantomData<(COM, U)>);
 
-impl<COM> Constant<COM> for Config<COM> {
-    type Type = Config;
+impl<COM, U> Constant<COM> for Config<COM, U> {
+    type Type = Config<(), U>;
 
     #[inline]
     fn new_constant(this: &Self::Type, compiler: &mut COM) -> Self {
@@ -1959,21 +2392,24 @@ impl<COM> Constant<COM> for Config<COM> {
     }
 }
 
-impl protocol::BaseConfiguration for Config {
+impl<U> protocol::BaseConfiguration for Config<(), U>
+where
+    U: UtxoCommitmentSchemeConfiguration,
+{
     type Bool = bool;
     type AssetId = AssetId;
     type AssetValue = AssetValue;
     type Scalar = EmbeddedScalar;
     type Group = Group;
     type GroupGenerator = GroupGenerator;
-    type UtxoCommitmentScheme = UtxoCommitmentScheme;
+    type UtxoCommitmentScheme = U;
     type ViewingKeyDerivationFunction = ViewingKeyDerivationFunction;
     type IncomingHeader = EmptyHeader;
     type IncomingCiphertext =
         <Self::IncomingBaseEncryptionScheme as encryption::CiphertextType>::Ciphertext;
-    type IncomingBaseEncryptionScheme = IncomingBaseEncryptionScheme;
+    type IncomingBaseEncryptionScheme = IncomingBaseEncryptionScheme<(), U>;
     type LightIncomingHeader = EmptyHeader;
-    type LightIncomingBaseEncryptionScheme = IncomingBaseAES;
+    type LightIncomingBaseEncryptionScheme = IncomingBaseAES<(), U>;
     type LightIncomingCiphertext =
         <Self::LightIncomingBaseEncryptionScheme as encryption::CiphertextType>::Ciphertext;
     type UtxoAccumulatorItemHash = UtxoAccumulatorItemHash;
@@ -1985,24 +2421,27 @@ impl protocol::BaseConfiguration for Config {
     type OutgoingBaseEncryptionScheme = OutgoingBaseAES;
 }
 
-impl protocol::BaseConfiguration<Compiler> for Config<Compiler> {
+impl<U> protocol::BaseConfiguration<Compiler> for Config<Compiler, U>
+where
+    U: UtxoCommitmentSchemeConfiguration,
+{
     type Bool = Boolean<ConstraintField>;
     type AssetId = AssetIdVar;
     type AssetValue = AssetValueVar;
     type Scalar = EmbeddedScalarVar;
     type Group = GroupVar;
     type GroupGenerator = GroupGeneratorVar;
-    type UtxoCommitmentScheme = UtxoCommitmentScheme<Compiler>;
+    type UtxoCommitmentScheme = U::Var;
     type ViewingKeyDerivationFunction = ViewingKeyDerivationFunction<Compiler>;
     type IncomingHeader = EmptyHeader<Compiler>;
     type IncomingCiphertext =
         <Self::IncomingBaseEncryptionScheme as encryption::CiphertextType>::Ciphertext;
-    type IncomingBaseEncryptionScheme = IncomingBaseEncryptionScheme<Compiler>;
+    type IncomingBaseEncryptionScheme = IncomingBaseEncryptionScheme<Compiler, U>;
     type LightIncomingHeader = EmptyHeader<Compiler>;
     type LightIncomingCiphertext =
         <Self::LightIncomingBaseEncryptionScheme as encryption::CiphertextType>::Ciphertext;
     type LightIncomingBaseEncryptionScheme =
-        encryption::UnsafeNoEncrypt<IncomingBaseAES<Compiler>, Compiler>;
+        encryption::UnsafeNoEncrypt<IncomingBaseAES<Compiler, U>, Compiler>;
     type UtxoAccumulatorItemHash = UtxoAccumulatorItemHash<Compiler>;
     type UtxoAccumulatorModel = UtxoAccumulatorModelVar;
     type NullifierCommitmentScheme = NullifierCommitmentScheme<Compiler>;
@@ -2013,9 +2452,15 @@ impl protocol::BaseConfiguration<Compiler> for Config<Compiler> {
         encryption::UnsafeNoEncrypt<OutgoingBaseAES<Compiler>, Compiler>;
 }
 
-impl protocol::Configuration for Config {
-    type AddressPartitionFunction = AddressPartitionFunction;
+impl<U> protocol::Configuration for Config<(), U>
+where
+    U: UtxoCommitmentSchemeConfiguration,
+{
+    type AddressPartitionFunction = AddressPartitionFunction<U>;
     type SchnorrHashFunction = SchnorrHashFunction;
+    type MemoPlaintext = MemoPlaintext;
+    type MemoHeader = EmptyHeader;
+    type MemoBaseEncryptionScheme = MemoBaseAES;
 }
 
 /// Checkpoint
@@ -2027,18 +2472,22 @@ impl protocol::Configuration for Config {
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Checkpoint {
     /// Receiver Index
-    pub receiver_index: Array<usize, { MerkleTreeConfiguration::FOREST_WIDTH }>,
+    ///
+    /// Leaf index of the next UTXO of every tree of the UTXO accumulator.
+    pub receiver_index: Array<LeafIndex, { MerkleTreeConfiguration::FOREST_WIDTH }>,
 
     /// Sender Index
-    pub sender_index: usize,
+    ///
+    /// Note index of the next nullifier revealed by the ledger.
+    pub sender_index: NoteIndex,
 }
 
 impl Checkpoint {
     /// Builds a new [`Checkpoint`] from `receiver_index` and `sender_index`.
     #[inline]
     pub fn new(
-        receiver_index: Array<usize, { MerkleTreeConfiguration::FOREST_WIDTH }>,
-        sender_index: usize,
+        receiver_index: Array<LeafIndex, { MerkleTreeConfiguration::FOREST_WIDTH }>,
+        sender_index: NoteIndex,
     ) -> Self {
         Self {
             receiver_index,
@@ -2050,7 +2499,10 @@ impl Checkpoint {
 impl Default for Checkpoint {
     #[inline]
     fn default() -> Self {
-        Self::new([0; MerkleTreeConfiguration::FOREST_WIDTH].into(), 0)
+        Self::new(
+            [LeafIndex::default(); MerkleTreeConfiguration::FOREST_WIDTH].into(),
+            NoteIndex::default(),
+        )
     }
 }
 
@@ -2058,14 +2510,39 @@ impl From<RawCheckpoint> for Checkpoint {
     #[inline]
     fn from(checkpoint: RawCheckpoint) -> Self {
         Self::new(
-            checkpoint.receiver_index.map(|i| i as usize).into(),
-            checkpoint.sender_index as usize,
+            checkpoint
+                .receiver_index
+                .map(|i| LeafIndex::new(i as usize))
+                .into(),
+            NoteIndex::new(checkpoint.sender_index as usize),
         )
     }
 }
 
 impl ledger::Checkpoint for Checkpoint {}
 
+impl Decode for Checkpoint {
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Ok(RawCheckpoint::decode(reader)?.into())
+    }
+}
+
+impl Encode for Checkpoint {
+    #[inline]
+    fn encode<W>(&self, writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        RawCheckpoint::from(*self).encode(writer)
+    }
+}
+
 /// Raw Checkpoint for Encoding and Decoding
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct RawCheckpoint {
@@ -2097,41 +2574,164 @@ impl Default for RawCheckpoint {
     }
 }
 
+impl Decode for RawCheckpoint {
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Ok(Self::new(
+            Decode::decode(&mut reader).map_err(|err| err.map_decode(|_| ()))?,
+            Decode::decode(&mut reader)?,
+        ))
+    }
+}
+
+impl Encode for RawCheckpoint {
+    #[inline]
+    fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        self.receiver_index.encode(&mut writer)?;
+        self.sender_index.encode(&mut writer)?;
+        Ok(())
+    }
+}
+
 impl From<Checkpoint> for RawCheckpoint {
     #[inline]
     fn from(checkpoint: Checkpoint) -> Self {
         Self::new(
-            (*checkpoint.receiver_index).map(|i| i as u64),
-            checkpoint.sender_index as u64,
+            (*checkpoint.receiver_index).map(|i| i.get() as u64),
+            checkpoint.sender_index.get() as u64,
         )
     }
 }
 
+/// Nullifier Set Commitment
+///
+/// Incremental commitment to the nullifiers of the ledger in the order in which they were spent.
+/// Appending a nullifier hashes it together with the previous commitment, so a light client which
+/// trusts the commitment at some [`Checkpoint`] can check the nullifiers it pulls from the ledger
+/// against the commitment at a later [`Checkpoint`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct NullifierSetCommitment(pub [u8; 32]);
+
+impl NullifierSetCommitment {
+    /// Appends `nullifier` to the set committed to by `self`.
+    #[inline]
+    pub fn append(&mut self, nullifier: &Nullifier) {
+        let mut hasher = Blake2s256::new();
+        Digest::update(&mut hasher, domain::NULLIFIER_SET_COMMITMENT.bytes);
+        Digest::update(&mut hasher, self.0);
+        Digest::update(&mut hasher, nullifier.to_vec());
+        self.0 = hasher.finalize().into();
+    }
+
+    /// Appends every nullifier in `nullifiers` to the set committed to by `self`.
+    #[inline]
+    pub fn extend<'n, I>(&mut self, nullifiers: I)
+    where
+        I: IntoIterator<Item = &'n Nullifier>,
+    {
+        for nullifier in nullifiers {
+            self.append(nullifier);
+        }
+    }
+
+    /// Returns the commitment to the set committed to by `self` extended by `nullifiers`.
+    #[inline]
+    pub fn extended<'n, I>(mut self, nullifiers: I) -> Self
+    where
+        I: IntoIterator<Item = &'n Nullifier>,
+    {
+        self.extend(nullifiers);
+        self
+    }
+}
+
+/// Committed Checkpoint
+///
+/// A [`Checkpoint`] of the ledger together with the commitment to its first
+/// [`sender_index`](Checkpoint::sender_index)-many nullifiers.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CommittedCheckpoint {
+    /// Checkpoint
+    pub checkpoint: Checkpoint,
+
+    /// Nullifier Set Commitment
+    pub nullifier_commitment: NullifierSetCommitment,
+}
+
+impl CommittedCheckpoint {
+    /// Builds a new [`CommittedCheckpoint`] from `checkpoint` and `nullifier_commitment`.
+    #[inline]
+    pub fn new(checkpoint: Checkpoint, nullifier_commitment: NullifierSetCommitment) -> Self {
+        Self {
+            checkpoint,
+            nullifier_commitment,
+        }
+    }
+
+    /// Returns `true` if `nullifiers`, pulled from the ledger starting at `self`, lead to the
+    /// `next` checkpoint and its nullifier set commitment.
+    #[inline]
+    pub fn verify_nullifiers(&self, nullifiers: &[Nullifier], next: &Self) -> bool {
+        self.checkpoint.sender_index.advance(nullifiers.len()) == next.checkpoint.sender_index
+            && self.nullifier_commitment.extended(nullifiers) == next.nullifier_commitment
+    }
+}
+
 /// Test
 #[cfg(test)]
 pub mod test {
     use crate::config::{
+        poseidon::Spec5 as Poseidon5,
         utxo::{
-            Config, IncomingBaseAES, IncomingBaseEncryptionScheme, OutgoingBaseAES,
-            AES_CIPHERTEXT_SIZE, OUT_AES_CIPHERTEXT_SIZE,
+            decode_tagged_incoming_plaintext, encode_tagged_incoming_plaintext, tagged, AssetId,
+            AssetIdVar, AssetValue, AssetValueVar, Config, CurrentUtxoCommitmentDomainTag,
+            IncomingBaseAES, IncomingBaseEncryptionScheme, NullifierCommitmentScheme,
+            OutgoingBaseAES, ReceivingKey, ReceivingKeyVar, TaggedEncoder,
+            UtxoCommitmentSchemeConfiguration, UtxoCommitmentSchemeType, AES_CIPHERTEXT_SIZE,
+            INCOMING_PLAINTEXT_ASSET_ID_TAG, INCOMING_PLAINTEXT_ASSET_VALUE_TAG,
+            INCOMING_PLAINTEXT_UTXO_COMMITMENT_RANDOMNESS_TAG, OUT_AES_CIPHERTEXT_SIZE,
         },
-        ConstraintField, EmbeddedScalar, Group,
+        Compiler, ConstraintField, EmbeddedScalar, Group, GroupVar,
     };
+    use crate::crypto::poseidon;
     use manta_accounting::{
         asset,
         transfer::utxo::{
             protocol::{
-                self, AddressPartitionFunction, UtxoCommitmentScheme, ViewingKeyDerivationFunction,
-                Visibility,
+                self, AddressPartitionFunction, NullifierCommitmentScheme as _,
+                UtxoCommitmentScheme, ViewingKeyDerivationFunction, Visibility,
             },
             UtxoReconstruct,
         },
     };
     use manta_crypto::{
         algebra::{HasGenerator, ScalarMul},
-        arkworks::constraint::fp::Fp,
+        arkworks::{
+            constraint::{fp::Fp, FpVar},
+            r1cs_std::R1CSVar,
+        },
+        eclair::alloc::{mode::Secret, Allocate, Constant},
         encryption::{Decrypt, EmptyHeader, Encrypt},
-        rand::{OsRng, Sample},
+        hash::ArrayHashFunction,
+        rand::{OsRng, Rand, RngCore, Sample},
     };
 
     /// Checks that encryption of light incoming notes is well-executed for [`Config`].
@@ -2175,6 +2775,48 @@ pub mod test {
         assert_eq!(new_asset_value, asset_value, "Asset value is not the same.");
     }
 
+    /// Checks that tagged incoming plaintexts round-trip, tolerate unknown optional fields, and
+    /// reject unknown required fields.
+    #[test]
+    fn check_tagged_incoming_plaintext() {
+        let mut rng = OsRng;
+        let plaintext = protocol::IncomingPlaintext::<Config>::new(
+            Fp::<ConstraintField>::gen(&mut rng),
+            asset::Asset {
+                id: Fp::<ConstraintField>::gen(&mut rng),
+                value: u128::gen(&mut rng),
+            },
+        );
+        let encoder = TaggedEncoder::new()
+            .field(
+                INCOMING_PLAINTEXT_UTXO_COMMITMENT_RANDOMNESS_TAG,
+                &plaintext.utxo_commitment_randomness,
+            )
+            .field(INCOMING_PLAINTEXT_ASSET_ID_TAG, &plaintext.asset.id)
+            .field(INCOMING_PLAINTEXT_ASSET_VALUE_TAG, &plaintext.asset.value);
+        assert_eq!(
+            encode_tagged_incoming_plaintext(&plaintext),
+            encoder.clone().finish(),
+            "Tagged plaintext encoding does not match its specification."
+        );
+        let decoded = decode_tagged_incoming_plaintext(&encoder.clone().finish())
+            .expect("Decoding a tagged plaintext should succeed.");
+        assert_eq!(
+            decoded.utxo_commitment_randomness,
+            plaintext.utxo_commitment_randomness
+        );
+        assert_eq!(decoded.asset, plaintext.asset);
+        let extended = encoder.clone().field(0x0100, &[7u8; 5]).finish();
+        let decoded = decode_tagged_incoming_plaintext(&extended)
+            .expect("Unknown optional fields should be skipped.");
+        assert_eq!(decoded.asset, plaintext.asset);
+        let extended = encoder.field(tagged::REQUIRED | 0x0100, &[7u8; 5]).finish();
+        assert!(
+            decode_tagged_incoming_plaintext(&extended).is_none(),
+            "Unknown required fields should be rejected."
+        );
+    }
+
     /// Checks that encryption of incoming notes is well-executed for [`Config`] with [`Compiler`].
     #[test]
     fn check_encryption_poseidon() {
@@ -2209,12 +2851,149 @@ pub mod test {
         assert_eq!(new_asset_value, asset_value, "Asset value is not the same.");
     }
 
-    /// Checks UTXOs associated with notes are consistent.
+    /// Utxo Commitment Scheme with Reversed Inputs
+    ///
+    /// Alternative to the default [`UtxoCommitmentScheme`](super::UtxoCommitmentScheme) used to
+    /// check that [`Config`] can be instantiated with other commitment schemes.
+    #[derive(derivative::Derivative)]
+    #[derivative(
+        Clone(bound = "UtxoCommitmentSchemeType<COM>: Clone"),
+        Debug(bound = "UtxoCommitmentSchemeType<COM>: core::fmt::Debug"),
+        Default(bound = "UtxoCommitmentSchemeType<COM>: Default")
+    )]
+    pub struct ReversedUtxoCommitmentScheme<COM = ()>(UtxoCommitmentSchemeType<COM>)
+    where
+        Poseidon5: poseidon::Specification<COM>;
+
+    impl Sample for ReversedUtxoCommitmentScheme {
+        #[inline]
+        fn sample<R>(distribution: (), rng: &mut R) -> Self
+        where
+            R: RngCore + ?Sized,
+        {
+            Self(rng.sample(distribution))
+        }
+    }
+
+    impl Constant<Compiler> for ReversedUtxoCommitmentScheme<Compiler> {
+        type Type = ReversedUtxoCommitmentScheme;
+
+        #[inline]
+        fn new_constant(this: &Self::Type, compiler: &mut Compiler) -> Self {
+            Self(this.0.as_constant(compiler))
+        }
+    }
+
+    impl UtxoCommitmentScheme for ReversedUtxoCommitmentScheme {
+        type AssetId = AssetId;
+        type AssetValue = AssetValue;
+        type ReceivingKey = ReceivingKey;
+        type Randomness = Fp<ConstraintField>;
+        type Commitment = Fp<ConstraintField>;
+
+        #[inline]
+        fn commit(
+            &self,
+            randomness: &Self::Randomness,
+            asset_id: &Self::AssetId,
+            asset_value: &Self::AssetValue,
+            receiving_key: &Self::ReceivingKey,
+            compiler: &mut (),
+        ) -> Self::Commitment {
+            self.0.hash(
+                [
+                    &Fp(receiving_key.0.y),
+                    &Fp(receiving_key.0.x),
+                    &Fp((*asset_value).into()),
+                    asset_id,
+                    randomness,
+                ],
+                compiler,
+            )
+        }
+
+        #[inline]
+        fn commit_current(
+            &self,
+            randomness: &Self::Randomness,
+            asset_id: &Self::AssetId,
+            asset_value: &Self::AssetValue,
+            receiving_key: &Self::ReceivingKey,
+            compiler: &mut (),
+        ) -> Self::Commitment {
+            let commitment =
+                self.commit(randomness, asset_id, asset_value, receiving_key, compiler);
+            let zero = Fp(ConstraintField::from(0u8));
+            self.0
+                .hash_with_domain_tag::<CurrentUtxoCommitmentDomainTag>(
+                    [&zero, &zero, &zero, &zero, &commitment],
+                    compiler,
+                )
+        }
+    }
+
+    impl UtxoCommitmentScheme<Compiler> for ReversedUtxoCommitmentScheme<Compiler> {
+        type AssetId = AssetIdVar;
+        type AssetValue = AssetValueVar;
+        type ReceivingKey = ReceivingKeyVar;
+        type Randomness = FpVar<ConstraintField>;
+        type Commitment = FpVar<ConstraintField>;
+
+        #[inline]
+        fn commit(
+            &self,
+            randomness: &Self::Randomness,
+            asset_id: &Self::AssetId,
+            asset_value: &Self::AssetValue,
+            receiving_key: &Self::ReceivingKey,
+            compiler: &mut Compiler,
+        ) -> Self::Commitment {
+            self.0.hash(
+                [
+                    &receiving_key.0.y,
+                    &receiving_key.0.x,
+                    asset_value.as_ref(),
+                    asset_id,
+                    randomness,
+                ],
+                compiler,
+            )
+        }
+
+        #[inline]
+        fn commit_current(
+            &self,
+            randomness: &Self::Randomness,
+            asset_id: &Self::AssetId,
+            asset_value: &Self::AssetValue,
+            receiving_key: &Self::ReceivingKey,
+            compiler: &mut Compiler,
+        ) -> Self::Commitment {
+            let commitment =
+                self.commit(randomness, asset_id, asset_value, receiving_key, compiler);
+            let zero = Fp(ConstraintField::from(0u8)).as_constant::<FpVar<_>>(compiler);
+            self.0
+                .hash_with_domain_tag::<CurrentUtxoCommitmentDomainTag>(
+                    [&zero, &zero, &zero, &zero, &commitment],
+                    compiler,
+                )
+        }
+    }
+
+    impl UtxoCommitmentSchemeConfiguration for ReversedUtxoCommitmentScheme {
+        type Var = ReversedUtxoCommitmentScheme<Compiler>;
+    }
+
+    /// Checks UTXOs associated with notes are consistent for [`Config`] with the UTXO commitment
+    /// scheme `U`.
     /// Checks that address partition function is working correctly, while opening notes.
-    #[test]
-    fn check_note_consistency() {
+    #[inline]
+    fn assert_note_consistency<U>()
+    where
+        U: UtxoCommitmentSchemeConfiguration + Sample,
+    {
         let mut rng = OsRng;
-        let parameters = protocol::Parameters::<Config>::gen(&mut rng);
+        let parameters = protocol::Parameters::<Config<(), U>>::gen(&mut rng);
         let group_generator = parameters.base.group_generator.generator();
         let spending_key = EmbeddedScalar::gen(&mut rng);
         let receiving_key = parameters.address_from_spending_key(&spending_key);
@@ -2237,10 +3016,10 @@ pub mod test {
             Visibility::Opaque
         };
         let plaintext =
-            protocol::IncomingPlaintext::<Config>::new(utxo_commitment_randomness, asset);
-        let secret = protocol::MintSecret::<Config>::new(
+            protocol::IncomingPlaintext::<Config<(), U>>::new(utxo_commitment_randomness, asset);
+        let secret = protocol::MintSecret::<Config<(), U>>::new(
             receiving_key.receiving_key,
-            protocol::IncomingRandomness::<Config>::sample(((), ()), &mut rng),
+            protocol::IncomingRandomness::<Config<(), U>>::sample(((), ()), &mut rng),
             plaintext,
         );
         let base_poseidon = parameters.base.incoming_base_encryption_scheme.clone();
@@ -2250,7 +3029,7 @@ pub mod test {
             .partition(&receiving_key);
         let incoming_note = secret.incoming_note(group_generator, &base_poseidon, &mut ());
         let light_incoming_note = secret.light_incoming_note(group_generator, &base_aes, &mut ());
-        let full_incoming_note = protocol::FullIncomingNote::<Config>::new(
+        let full_incoming_note = protocol::FullIncomingNote::<Config<(), U>>::new(
             address_partition,
             incoming_note,
             light_incoming_note,
@@ -2262,7 +3041,7 @@ pub mod test {
             &receiving_key.receiving_key,
             &mut (),
         );
-        let utxo = protocol::Utxo::<Config>::new(
+        let utxo = protocol::Utxo::<Config<(), U>>::new(
             is_transparent,
             associated_data.public(&asset),
             utxo_commitment,
@@ -2278,6 +3057,60 @@ pub mod test {
         assert_eq!(asset.id, new_asset.id, "Asset id is not the same.");
     }
 
+    /// Checks UTXOs associated with notes are consistent for [`Config`].
+    #[test]
+    fn check_note_consistency() {
+        assert_note_consistency::<super::UtxoCommitmentScheme>();
+    }
+
+    /// Checks UTXOs associated with notes are consistent for [`Config`] with a UTXO commitment
+    /// scheme other than the default one, and that its native and circuit commitments agree.
+    #[test]
+    fn check_pluggable_utxo_commitment_scheme() {
+        assert_note_consistency::<ReversedUtxoCommitmentScheme>();
+        let mut rng = OsRng;
+        let default_scheme = super::UtxoCommitmentScheme::gen(&mut rng);
+        let scheme = ReversedUtxoCommitmentScheme(default_scheme.0.clone());
+        let randomness = Fp::<ConstraintField>::gen(&mut rng);
+        let asset_id = Fp::<ConstraintField>::gen(&mut rng);
+        let asset_value = u128::gen(&mut rng);
+        let receiving_key = Group::gen(&mut rng);
+        let commitment = scheme.commit(
+            &randomness,
+            &asset_id,
+            &asset_value,
+            &receiving_key,
+            &mut (),
+        );
+        assert_ne!(
+            commitment,
+            default_scheme.commit(
+                &randomness,
+                &asset_id,
+                &asset_value,
+                &receiving_key,
+                &mut ()
+            ),
+            "Commitments of different schemes should differ."
+        );
+        let mut compiler = Compiler::for_proofs();
+        let scheme_var =
+            scheme.as_constant::<ReversedUtxoCommitmentScheme<Compiler>>(&mut compiler);
+        let commitment_var = scheme_var.commit(
+            &randomness.as_known::<Secret, FpVar<_>>(&mut compiler),
+            &asset_id.as_known::<Secret, FpVar<_>>(&mut compiler),
+            &asset_value.as_known::<Secret, AssetValueVar>(&mut compiler),
+            &receiving_key.as_known::<Secret, GroupVar>(&mut compiler),
+            &mut compiler,
+        );
+        assert_eq!(
+            commitment.0,
+            commitment_var.value().expect("Variable has a known value."),
+            "Native and circuit commitments should agree."
+        );
+        assert!(compiler.is_satisfied(), "Constraints should be satisfied.");
+    }
+
     /// Checks encryption is properly executed, i.e. that the ciphertext size is consistent with all the parameters, and that
     /// decryption is the inverse of encryption.
     #[test]
@@ -2307,4 +3140,86 @@ pub mod test {
         assert_eq!(new_asset_id, asset_id, "Asset ID is not the same.");
         assert_eq!(new_asset_value, asset_value, "Asset value is not the same.");
     }
+
+    /// Checks that asset-scoped nullifier commitments agree natively and in-circuit, and that they
+    /// depend on the asset id.
+    #[test]
+    fn check_asset_scoped_nullifier_commitment() {
+        let mut rng = OsRng;
+        let scheme = NullifierCommitmentScheme::gen(&mut rng);
+        let proof_authorization_key = Group::gen(&mut rng);
+        let item = Fp::<ConstraintField>::gen(&mut rng);
+        let asset_id = Fp::<ConstraintField>::gen(&mut rng);
+        let commitment =
+            scheme.commit_with_asset_id(&proof_authorization_key, &item, &asset_id, &mut ());
+        assert_ne!(
+            commitment,
+            scheme.commit(&proof_authorization_key, &item, &mut ()),
+            "Scoped and unscoped commitments should differ."
+        );
+        assert_ne!(
+            commitment,
+            scheme.commit_with_asset_id(&proof_authorization_key, &item, &rng.gen(), &mut ()),
+            "Commitments for different asset ids should differ."
+        );
+        let mut compiler = Compiler::for_proofs();
+        let scheme_var = scheme.as_constant::<NullifierCommitmentScheme<Compiler>>(&mut compiler);
+        let commitment_var = scheme_var.commit_with_asset_id(
+            &proof_authorization_key.as_known::<Secret, GroupVar>(&mut compiler),
+            &item.as_known::<Secret, FpVar<_>>(&mut compiler),
+            &asset_id.as_known::<Secret, FpVar<_>>(&mut compiler),
+            &mut compiler,
+        );
+        assert_eq!(
+            commitment.0,
+            commitment_var.value().expect("Variable has a known value."),
+            "Native and circuit commitments should agree."
+        );
+        assert!(compiler.is_satisfied(), "Constraints should be satisfied.");
+    }
+
+    /// Checks that the UTXO commitments of current notes agree natively and in-circuit, and that
+    /// they differ from the ones of legacy notes.
+    #[test]
+    fn check_current_utxo_commitment() {
+        let mut rng = OsRng;
+        let scheme = super::UtxoCommitmentScheme::gen(&mut rng);
+        let randomness = Fp::<ConstraintField>::gen(&mut rng);
+        let asset_id = Fp::<ConstraintField>::gen(&mut rng);
+        let asset_value = u128::gen(&mut rng);
+        let receiving_key = Group::gen(&mut rng);
+        let commitment = scheme.commit_current(
+            &randomness,
+            &asset_id,
+            &asset_value,
+            &receiving_key,
+            &mut (),
+        );
+        assert_ne!(
+            commitment,
+            scheme.commit(
+                &randomness,
+                &asset_id,
+                &asset_value,
+                &receiving_key,
+                &mut ()
+            ),
+            "Current and legacy commitments should differ."
+        );
+        let mut compiler = Compiler::for_proofs();
+        let scheme_var = scheme.as_constant::<super::UtxoCommitmentScheme<Compiler>>(&mut compiler);
+        let commitment_var = scheme_var.commit_current(
+            &randomness.as_known::<Secret, FpVar<_>>(&mut compiler),
+            &asset_id.as_known::<Secret, AssetIdVar>(&mut compiler),
+            &asset_value.as_known::<Secret, AssetValueVar>(&mut compiler),
+            &receiving_key.as_known::<Secret, GroupVar>(&mut compiler),
+            &mut compiler,
+        );
+        assert_eq!(
+            commitment.0,
+            commitment_var.value().expect("Variable has a known value."),
+            "Native and circuit commitments should agree."
+        );
+        assert!(compiler.is_satisfied(), "Constraints should be satisfied.");
+    }
 }
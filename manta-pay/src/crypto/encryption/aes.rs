@@ -32,6 +32,7 @@ use manta_crypto::{
 };
 use manta_util::{
     codec::{Decode, DecodeError, Encode, Read, Write},
+    zeroize::Zeroizing,
     Array,
 };
 
@@ -127,11 +128,13 @@ impl<const P: usize, const C: usize> Decrypt for FixedNonceAesGcm<P, C> {
         ciphertext: &Self::Ciphertext,
         _: &mut (),
     ) -> Self::DecryptedPlaintext {
-        Aes256Gcm::new_from_slice(decryption_key)
-            .expect("The key has the correct size.")
-            .decrypt(Nonce::from_slice(Self::NONCE), ciphertext.as_ref())
-            .ok()
-            .map(Array::from_unchecked)
+        let plaintext = Zeroizing::new(
+            Aes256Gcm::new_from_slice(decryption_key)
+                .expect("The key has the correct size.")
+                .decrypt(Nonce::from_slice(Self::NONCE), ciphertext.as_ref())
+                .ok()?,
+        );
+        Some(Array::from_unchecked(plaintext.as_slice()))
     }
 }
 
@@ -16,6 +16,7 @@
 
 //! Cryptographic Key Primitive Implementations
 
+use crate::domain;
 use blake2::{Blake2s, Digest};
 use manta_crypto::{
     key::kdf::KeyDerivationFunction,
@@ -34,7 +35,7 @@ impl KeyDerivationFunction for Blake2sKdf {
     fn derive(&self, key: &Self::Key, _: &mut ()) -> Self::Output {
         let mut hasher = Blake2s::new();
         hasher.update(key);
-        hasher.update(b"manta kdf instantiated with blake2s hash function");
+        hasher.update(domain::BLAKE2S_KDF.bytes);
         into_array_unchecked(hasher.finalize())
     }
 }
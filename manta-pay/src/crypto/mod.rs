@@ -19,3 +19,7 @@
 pub mod encryption;
 pub mod key;
 pub mod poseidon;
+
+#[cfg(feature = "arkworks")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "arkworks")))]
+pub mod transcript;
@@ -17,16 +17,20 @@
 //! Poseidon Arkworks Backend
 
 use crate::crypto::poseidon::{
-    self, encryption::BlockElement, hash::DomainTag, Constants, Field, FieldGeneration,
-    ParameterFieldType,
+    self,
+    encryption::BlockElement,
+    hash::DomainTag,
+    sbox::{Alpha, SBox},
+    Constants, Field, FieldGeneration, ParameterFieldType,
 };
 use manta_crypto::{
     arkworks::{
+        bls12_381, bn254,
         constraint::{fp::Fp, FpVar, R1CS},
         ff::{BigInteger, Field as _, FpParameters, PrimeField},
-        r1cs_std::fields::FieldVar,
+        r1cs_std::{fields::FieldVar, R1CSVar},
     },
-    eclair::alloc::Constant,
+    eclair::alloc::{mode::Secret, Allocate, Allocator, Constant},
 };
 
 /// Compiler Type.
@@ -37,8 +41,25 @@ pub trait Specification: Constants {
     /// Field Type
     type Field: PrimeField;
 
-    /// S-BOX Exponenet
-    const SBOX_EXPONENT: u64;
+    /// S-Box
+    type SBox: SBox;
+}
+
+/// Returns `true` if the S-box `B` is a permutation of the prime field `F`.
+#[inline]
+pub fn sbox_is_permutation<B, F>() -> bool
+where
+    B: SBox,
+    F: PrimeField,
+{
+    B::ALPHA.is_permutation_with(|alpha| {
+        let alpha = alpha as u128;
+        let remainder = F::Params::MODULUS
+            .to_bits_be()
+            .into_iter()
+            .fold(0, |remainder, bit| (2 * remainder + bit as u128) % alpha);
+        ((remainder + alpha - 1) % alpha) as u64
+    })
 }
 
 impl<F> Field for Fp<F>
@@ -103,6 +124,18 @@ where
     }
 }
 
+#[cfg(feature = "std")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+impl poseidon::registry::CurveField for Fp<bn254::Fr> {
+    const CURVE: poseidon::registry::Curve = poseidon::registry::Curve::Bn254;
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+impl poseidon::registry::CurveField for Fp<bls12_381::Fr> {
+    const CURVE: poseidon::registry::Curve = poseidon::registry::Curve::Bls12_381;
+}
+
 impl<S> ParameterFieldType for S
 where
     S: Specification,
@@ -148,7 +181,10 @@ where
 
     #[inline]
     fn apply_sbox(point: &mut Self::Field, _: &mut ()) {
-        point.0 = point.0.pow([Self::SBOX_EXPONENT, 0, 0, 0]);
+        match S::SBox::ALPHA {
+            Alpha::Power(alpha) => point.0 = point.0.pow([alpha]),
+            Alpha::Inverse => point.0 = point.0.inverse().unwrap_or_default(),
+        }
     }
 
     #[inline]
@@ -202,10 +238,34 @@ where
     }
 
     #[inline]
-    fn apply_sbox(point: &mut Self::Field, _: &mut Compiler<S>) {
-        *point = point
-            .pow_by_constant([Self::SBOX_EXPONENT])
-            .expect("Exponentiation is not allowed to fail.");
+    fn apply_sbox(point: &mut Self::Field, compiler: &mut Compiler<S>) {
+        match S::SBox::ALPHA {
+            Alpha::Power(alpha) => {
+                *point = point
+                    .pow_by_constant([alpha])
+                    .expect("Exponentiation is not allowed to fail.");
+            }
+            Alpha::Inverse => {
+                if let FpVar::Constant(value) = point {
+                    *value = value.inverse().unwrap_or_default();
+                    return;
+                }
+                let inverse: FpVar<_> = match point.value() {
+                    Ok(value) => {
+                        Fp(value.inverse().unwrap_or_default()).as_known::<Secret, _>(compiler)
+                    }
+                    _ => compiler.allocate_unknown::<Secret, _>(),
+                };
+                let product = &*point * &inverse;
+                product
+                    .mul_equals(point, point)
+                    .expect("Enforcing equality is not allowed to fail.");
+                product
+                    .mul_equals(&inverse, &inverse)
+                    .expect("Enforcing equality is not allowed to fail.");
+                *point = inverse;
+            }
+        }
     }
 
     #[inline]
@@ -16,7 +16,7 @@
 
 //! Poseidon Permutation Round Numbers
 
-use crate::crypto::poseidon::Specification;
+use crate::crypto::poseidon::{sbox::Alpha, Specification};
 
 #[cfg(feature = "std")]
 use {manta_util::num::Ceil, security::SecurityCondition};
@@ -53,14 +53,27 @@ impl Constants {
     /// This constant is denoted `M` in the Poseidon paper.
     pub const SECURITY_LEVEL: usize = 128;
 
-    /// Computes constants for a Poseidon implementation that achieves [`SECURITY_LEVEL`] bits of
-    /// security according to the claims in the Poseidon paper and current cryptanalytic efforts.
+    /// Computes constants for a Poseidon implementation with the quintic S-box that achieves
+    /// [`SECURITY_LEVEL`] bits of security according to the claims in the Poseidon paper and
+    /// current cryptanalytic efforts.
     ///
     /// [`SECURITY_LEVEL`]: Self::SECURITY_LEVEL
     #[cfg(feature = "std")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
     #[inline]
     pub fn from_arity(arity: usize) -> Self {
+        Self::from_arity_with_alpha(arity, Alpha::QUINTIC)
+    }
+
+    /// Computes constants for a Poseidon implementation with the S-box exponent `alpha` that
+    /// achieves [`SECURITY_LEVEL`] bits of security according to the claims in the Poseidon paper
+    /// and current cryptanalytic efforts.
+    ///
+    /// [`SECURITY_LEVEL`]: Self::SECURITY_LEVEL
+    #[cfg(feature = "std")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+    #[inline]
+    pub fn from_arity_with_alpha(arity: usize, alpha: Alpha) -> Self {
         let mut constants = Self {
             width: arity + 1,
             full_rounds: 0,
@@ -74,7 +87,7 @@ impl Constants {
                     full_rounds: rf,
                     partial_rounds: rp,
                 })
-                .are_secure()
+                .are_secure_with_alpha(alpha)
                 {
                     rf += 2;
                     rp = (1.075 * rp as f32).ceil() as usize;
@@ -136,13 +149,23 @@ impl Constants {
         }
     }
 
-    /// Returns `true` if `self` are secure constants under the conditions set out in the Poseidon
-    /// paper. See [`security`] for more.
+    /// Returns `true` if `self` are secure constants for the quintic S-box under the conditions
+    /// set out in the Poseidon paper. See [`security`] for more.
     #[cfg(feature = "std")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
     #[inline]
     pub fn are_secure(self) -> bool {
+        self.are_secure_with_alpha(Alpha::QUINTIC)
+    }
+
+    /// Returns `true` if `self` are secure constants for the S-box exponent `alpha` under the
+    /// conditions set out in the Poseidon paper. See [`security`] for more.
+    #[cfg(feature = "std")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+    #[inline]
+    pub fn are_secure_with_alpha(self, alpha: Alpha) -> bool {
         security::Full::is_secure(
+            alpha,
             self.full_rounds,
             self.width as f32,
             self.partial_rounds as f32,
@@ -154,25 +177,64 @@ impl Constants {
 
 /// Security
 pub mod security {
+    use crate::crypto::poseidon::sbox::Alpha;
+
     #[cfg(feature = "std")]
     use {core::cmp, manta_util::num::Ceil};
 
+    /// Returns `log_alpha(2) / divisor` truncated to two decimal places, which is how the
+    /// coefficients of the security conditions are stated in the Poseidon paper.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn coefficient(alpha: u64, divisor: f32) -> f32 {
+        (100.0 / ((alpha as f32).log2() * divisor)).floor() / 100.0
+    }
+
+    /// Returns the smallest number of full rounds `rf` such that `floor(rf * log2(width))` is at
+    /// least `bound`. This is how the conditions for the inverse S-box are turned into lower
+    /// bounds on the number of full rounds.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn inverse_full_rounds_lower_bound(width: f32, bound: f32) -> usize {
+        if bound <= 0.0 {
+            return 0;
+        }
+        let mut full_rounds = 0;
+        while ((full_rounds as f32) * width.log2()).floor() < bound {
+            full_rounds += 1;
+        }
+        full_rounds
+    }
+
     /// Security Conditions
     ///
     /// This `trait` is defined for each known attack on Poseidon. Details of these attacks is
     /// present in section 5.5 of the Poseidon paper.
     pub trait SecurityCondition {
         /// Computes the lower bound on the secure number of full rounds required for a poseidon
-        /// with `width` and `partial_rounds` constants and `n` prime field modulus bits for `m`
-        /// bits of security.
-        fn full_rounds_lower_bound(width: f32, partial_rounds: f32, n: f32, m: f32) -> usize;
+        /// with S-box exponent `alpha`, `width` and `partial_rounds` constants and `n` prime field
+        /// modulus bits for `m` bits of security.
+        fn full_rounds_lower_bound(
+            alpha: Alpha,
+            width: f32,
+            partial_rounds: f32,
+            n: f32,
+            m: f32,
+        ) -> usize;
 
-        /// Returns `true` if Poseidon with constants given by `full_rounds`, `width`,
-        /// `partial_rounds` are safe over a prime field with modulus bits `n` and target security
-        /// of `m` bits.
+        /// Returns `true` if Poseidon with S-box exponent `alpha` and constants given by
+        /// `full_rounds`, `width`, `partial_rounds` are safe over a prime field with modulus bits
+        /// `n` and target security of `m` bits.
         #[inline]
-        fn is_secure(full_rounds: usize, width: f32, partial_rounds: f32, n: f32, m: f32) -> bool {
-            full_rounds >= Self::full_rounds_lower_bound(width, partial_rounds, n, m)
+        fn is_secure(
+            alpha: Alpha,
+            full_rounds: usize,
+            width: f32,
+            partial_rounds: f32,
+            n: f32,
+            m: f32,
+        ) -> bool {
+            full_rounds >= Self::full_rounds_lower_bound(alpha, width, partial_rounds, n, m)
         }
     }
 
@@ -182,8 +244,12 @@ pub mod security {
 
     impl SecurityCondition for StatisticalAttack {
         #[inline]
-        fn full_rounds_lower_bound(width: f32, _: f32, n: f32, m: f32) -> usize {
-            if m <= (n - 3.0) * (width + 1.0) {
+        fn full_rounds_lower_bound(alpha: Alpha, width: f32, _: f32, n: f32, m: f32) -> usize {
+            let loss = match alpha {
+                Alpha::Power(alpha) => (alpha - 1) as f32 / 2.0,
+                Alpha::Inverse => 2.0,
+            };
+            if m <= (n - 1.0 - loss) * (width + 1.0) {
                 6
             } else {
                 10
@@ -199,8 +265,22 @@ pub mod security {
     #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
     impl SecurityCondition for InterpolationAttack {
         #[inline]
-        fn full_rounds_lower_bound(width: f32, partial_rounds: f32, _: f32, m: f32) -> usize {
-            Ceil::ceil(0.43 * m + width.log2() - partial_rounds)
+        fn full_rounds_lower_bound(
+            alpha: Alpha,
+            width: f32,
+            partial_rounds: f32,
+            n: f32,
+            m: f32,
+        ) -> usize {
+            match alpha {
+                Alpha::Power(alpha) => {
+                    Ceil::ceil(coefficient(alpha, 1.0) * m + width.log2() - partial_rounds)
+                }
+                Alpha::Inverse => inverse_full_rounds_lower_bound(
+                    width,
+                    1.0 + (0.5 * m.min(n)).ceil() + width.log2().ceil() - partial_rounds,
+                ),
+            }
         }
     }
 
@@ -212,8 +292,23 @@ pub mod security {
     #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
     impl SecurityCondition for GrobnerBasisFullPermutationAttack {
         #[inline]
-        fn full_rounds_lower_bound(_: f32, partial_rounds: f32, n: f32, _: f32) -> usize {
-            Ceil::ceil(0.21 * n - partial_rounds)
+        fn full_rounds_lower_bound(
+            alpha: Alpha,
+            width: f32,
+            partial_rounds: f32,
+            n: f32,
+            m: f32,
+        ) -> usize {
+            match alpha {
+                Alpha::Power(alpha) => Ceil::ceil(coefficient(alpha, 2.0) * n - partial_rounds),
+                Alpha::Inverse => inverse_full_rounds_lower_bound(
+                    width,
+                    width - 1.0
+                        + width.log2().ceil()
+                        + (m / (width + 1.0)).ceil().min((0.5 * n).ceil())
+                        - partial_rounds,
+                ),
+            }
         }
     }
 
@@ -225,8 +320,21 @@ pub mod security {
     #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
     impl SecurityCondition for GrobnerBasisPartialSBoxAttack {
         #[inline]
-        fn full_rounds_lower_bound(width: f32, partial_rounds: f32, n: f32, _: f32) -> usize {
-            Ceil::ceil((0.14 * n - 1.0 - partial_rounds) / (width - 1.0))
+        fn full_rounds_lower_bound(
+            alpha: Alpha,
+            width: f32,
+            partial_rounds: f32,
+            n: f32,
+            m: f32,
+        ) -> usize {
+            match alpha {
+                Alpha::Power(alpha) => {
+                    Ceil::ceil((coefficient(alpha, 3.0) * n - 1.0 - partial_rounds) / (width - 1.0))
+                }
+                Alpha::Inverse => {
+                    InterpolationAttack::full_rounds_lower_bound(alpha, width, partial_rounds, n, m)
+                }
+            }
         }
     }
 
@@ -244,20 +352,33 @@ pub mod security {
     #[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
     impl SecurityCondition for Full {
         #[inline]
-        fn full_rounds_lower_bound(width: f32, partial_rounds: f32, n: f32, m: f32) -> usize {
+        fn full_rounds_lower_bound(
+            alpha: Alpha,
+            width: f32,
+            partial_rounds: f32,
+            n: f32,
+            m: f32,
+        ) -> usize {
             let statistical =
-                StatisticalAttack::full_rounds_lower_bound(width, partial_rounds, n, m);
+                StatisticalAttack::full_rounds_lower_bound(alpha, width, partial_rounds, n, m);
             let interpolation =
-                InterpolationAttack::full_rounds_lower_bound(width, partial_rounds, n, m);
+                InterpolationAttack::full_rounds_lower_bound(alpha, width, partial_rounds, n, m);
             let grobner_basis_full_permutation =
                 GrobnerBasisFullPermutationAttack::full_rounds_lower_bound(
+                    alpha,
                     width,
                     partial_rounds,
                     n,
                     m,
                 );
             let grobner_basis_partial_sbox_attack =
-                GrobnerBasisPartialSBoxAttack::full_rounds_lower_bound(width, partial_rounds, n, m);
+                GrobnerBasisPartialSBoxAttack::full_rounds_lower_bound(
+                    alpha,
+                    width,
+                    partial_rounds,
+                    n,
+                    m,
+                );
             cmp::max(
                 statistical,
                 cmp::max(
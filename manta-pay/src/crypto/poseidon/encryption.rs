@@ -32,6 +32,7 @@ use manta_crypto::{
         ops::BitAnd,
         Has,
     },
+    impl_input,
     permutation::{
         duplex::{self, Setup, Types, Verify},
         sponge::{Read, Write},
@@ -320,6 +321,30 @@ where
     }
 }
 
+impl<S> Decode for CiphertextBlock<S>
+where
+    S: Specification,
+    S::Field: Decode,
+{
+    type Error = Option<<S::Field as Decode>::Error>;
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: codec::Read,
+    {
+        let len = u64::decode(&mut reader).map_err(|err| err.map_decode(|_| None))?;
+        if len != (S::WIDTH - 1) as u64 {
+            return Err(DecodeError::Decode(None));
+        }
+        let mut block = Vec::with_capacity(S::WIDTH - 1);
+        for _ in 0..len {
+            block.push(S::Field::decode(&mut reader).map_err(|err| err.map_decode(Some))?);
+        }
+        Ok(Self(block.into()))
+    }
+}
+
 impl<S> Encode for CiphertextBlock<S>
 where
     S: Specification,
@@ -396,6 +421,25 @@ impl<'b, B, const N: usize> IntoIterator for &'b BlockArray<B, N> {
     }
 }
 
+impl<B, const N: usize> Decode for BlockArray<B, N>
+where
+    B: Decode,
+{
+    type Error = B::Error;
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: codec::Read,
+    {
+        let mut blocks = Vec::with_capacity(N);
+        for _ in 0..N {
+            blocks.push(B::decode(&mut reader)?);
+        }
+        Ok(Self(BoxArray::from_vec(blocks)))
+    }
+}
+
 impl<B, const N: usize> Encode for BlockArray<B, N>
 where
     B: Encode,
@@ -532,6 +576,22 @@ where
     }
 }
 
+impl<S> Decode for Tag<S>
+where
+    S: Specification,
+    S::Field: Decode,
+{
+    type Error = <S::Field as Decode>::Error;
+
+    #[inline]
+    fn decode<R>(reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: codec::Read,
+    {
+        Ok(Self(Decode::decode(reader)?))
+    }
+}
+
 impl<S> Encode for Tag<S>
 where
     S: Specification,
@@ -546,14 +606,11 @@ where
     }
 }
 
-impl<S, P> Input<P> for Tag<S>
-where
-    S: Specification,
-    P: HasInput<S::Field> + ?Sized,
-{
-    #[inline]
-    fn extend(&self, input: &mut P::Input) {
-        P::extend(input, &self.0)
+impl_input! {
+    impl<S> Input<P> for Tag<S>
+    where [S: Specification]
+    |tag| {
+        S::Field => &tag.0,
     }
 }
 
@@ -120,6 +120,22 @@ where
             .permute_without_first_round(&mut state, compiler);
         state.0.into_vec()
     }
+
+    /// Computes the hash over `input` in the given `compiler` using `U` to generate the domain tag
+    /// in place of the domain tag of `self`, reusing the same permutation.
+    #[inline]
+    pub fn hash_with_domain_tag<U>(&self, input: [&S::Field; ARITY], compiler: &mut COM) -> S::Field
+    where
+        U: DomainTag<S>,
+    {
+        let domain_tag = S::from_parameter(U::domain_tag());
+        let mut state =
+            self.permutation
+                .first_round_with_domain_tag_unchecked(&domain_tag, input, compiler);
+        self.permutation
+            .permute_without_first_round(&mut state, compiler);
+        state.0.into_vec().take_first()
+    }
 }
 
 impl<S, T, const ARITY: usize, COM> Constant<COM> for Hasher<S, T, ARITY, COM>
@@ -237,7 +253,7 @@ mod test {
     {
         type Field = Fr;
 
-        const SBOX_EXPONENT: u64 = 5;
+        type SBox = poseidon::sbox::Quintic;
     }
 
     /// Arity 2 Poseidon Specification
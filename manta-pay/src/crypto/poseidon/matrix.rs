@@ -19,6 +19,7 @@
 use crate::crypto::poseidon::Field;
 use core::{
     fmt::Debug,
+    mem,
     ops::{Deref, Index, IndexMut},
     slice,
 };
@@ -61,6 +62,11 @@ pub trait MatrixOperations {
         Self: Sized,
         Self::Scalar: Clone + PartialEq;
 
+    /// Eliminates `column` from all rows of `self` in place, applying the same row operations to
+    /// `shadow`. This is the in-place variant of [`eliminate`](Self::eliminate) and leaves both
+    /// matrices untouched when it returns `None`.
+    fn eliminate_in_place(&mut self, column: usize, shadow: &mut Self) -> Option<()>;
+
     /// Returns an identity matrix of size `n*n`.
     fn identity(n: usize) -> Self;
 
@@ -70,6 +76,11 @@ pub trait MatrixOperations {
         Self: Sized,
         Self::Scalar: Clone;
 
+    /// Multiplies matrix `self` with matrix `other` on the right side, writing the product into
+    /// `out` and reusing its allocation. Returns `None` if the shapes of `self` and `other` do not
+    /// match, in which case `out` is left untouched.
+    fn matmul_into(&self, other: &Self, out: &mut Self) -> Option<()>;
+
     /// Elementwisely multiplies with `scalar`.
     fn mul_by_scalar(&self, scalar: Self::Scalar) -> Self;
 
@@ -78,6 +89,9 @@ pub trait MatrixOperations {
 
     /// Returns the transpose of the matrix.
     fn transpose(self) -> Self;
+
+    /// Transposes the matrix in place. Square matrices are transposed without allocating.
+    fn transpose_in_place(&mut self);
 }
 
 /// Row Major Matrix Representation.
@@ -262,30 +276,14 @@ where
     where
         Self::Scalar: Clone + PartialEq,
     {
-        let zero = F::zero();
-        let (pivot_index, pivot) = self.0.iter().enumerate().find(|(_, row)| {
-            (!F::eq(&row[column], &zero)) && (0..column).all(|j| F::eq(&row[j], &zero))
-        })?;
-        let inv_pivot = F::inverse(&pivot[column])
-            .expect("This should never fail since we have a non-zero `pivot_val` if we got here.");
-        let mut result = Vec::with_capacity(self.num_rows());
-        result.push(pivot.clone());
-        for (i, row) in self.rows().enumerate() {
-            if i == pivot_index {
-                continue;
-            };
-            let val = &row[column];
-            if F::eq(val, &zero) {
-                result.push(row.to_vec());
-            } else {
-                let factor = F::mul(val, &inv_pivot);
-                result.push(eliminate_row(row, &factor, pivot));
-                shadow[i] = eliminate_row(&shadow[i], &factor, &shadow[pivot_index]);
-            }
-        }
-        let pivot_row = shadow.0.remove(pivot_index);
-        shadow.0.insert(0, pivot_row);
-        Some(Self(result))
+        let mut result = self.clone();
+        result.eliminate_in_place(column, shadow)?;
+        Some(result)
+    }
+
+    #[inline]
+    fn eliminate_in_place(&mut self, column: usize, shadow: &mut Self) -> Option<()> {
+        eliminate_rows(&mut self.0, &mut shadow.0, column)
     }
 
     #[inline]
@@ -311,20 +309,27 @@ where
     where
         Self::Scalar: Clone,
     {
-        if self.num_rows() != other.num_columns() {
+        let mut result = Self(Vec::with_capacity(self.num_rows()));
+        self.matmul_into(other, &mut result)?;
+        Some(result)
+    }
+
+    #[inline]
+    fn matmul_into(&self, other: &Self, out: &mut Self) -> Option<()> {
+        if self.num_columns() != other.num_rows() {
             return None;
-        };
-        let other_transpose = other.clone().transpose();
-        Some(Self(
-            self.rows()
-                .map(|input_row| {
-                    other_transpose
-                        .rows()
-                        .map(|transposed_column| inner_product(input_row, transposed_column))
-                        .collect()
-                })
-                .collect(),
-        ))
+        }
+        let num_columns = other.num_columns();
+        out.0.resize_with(self.num_rows(), Vec::new);
+        for (row, out_row) in self.rows().zip(out.0.iter_mut()) {
+            out_row.clear();
+            out_row.extend((0..num_columns).map(|j| {
+                row.iter()
+                    .zip(other.column(j))
+                    .fold(F::zero(), |acc, (a, b)| F::add(&acc, &F::mul(a, b)))
+            }));
+        }
+        Some(())
     }
 
     #[inline]
@@ -338,15 +343,31 @@ where
     }
 
     #[inline]
-    fn transpose(self) -> Self {
-        let mut transposed_matrix =
-            allocate_matrix(self.num_columns(), self.num_rows(), Vec::with_capacity);
-        for row in self.0 {
-            for (j, elem) in row.into_iter().enumerate() {
-                transposed_matrix[j].push(elem);
+    fn transpose(mut self) -> Self {
+        self.transpose_in_place();
+        self
+    }
+
+    #[inline]
+    fn transpose_in_place(&mut self) {
+        if self.is_square() {
+            let size = self.num_rows();
+            for i in 0..size {
+                let (upper, lower) = self.0.split_at_mut(i + 1);
+                for (j, row) in lower.iter_mut().enumerate() {
+                    mem::swap(&mut upper[i][i + 1 + j], &mut row[i]);
+                }
+            }
+        } else {
+            let mut transposed_matrix =
+                allocate_matrix(self.num_columns(), self.num_rows(), Vec::with_capacity);
+            for row in mem::take(&mut self.0) {
+                for (j, elem) in row.into_iter().enumerate() {
+                    transposed_matrix[j].push(elem);
+                }
             }
+            self.0 = transposed_matrix;
         }
-        Self(transposed_matrix)
     }
 }
 
@@ -389,9 +410,10 @@ where
     where
         F: Clone + PartialEq,
     {
+        let mut matrix = self.clone();
         let mut shadow = Self::identity(self.num_rows());
-        self.upper_triangular(&mut shadow)?
-            .reduce_to_identity(&mut shadow)?;
+        matrix.upper_triangular_in_place(&mut shadow)?;
+        matrix.reduce_to_identity_in_place(&mut shadow)?;
         Some(shadow)
     }
 
@@ -404,6 +426,60 @@ where
         self.inverse().is_some()
     }
 
+    /// Returns `true` if `self` leaves a non-trivial subspace of `{x | x[0] = 0}` invariant.
+    ///
+    /// The largest such subspace is the kernel of the matrix with rows `e_0 * self^k` for
+    /// `0 <= k < size`, so it is non-trivial if and only if that matrix is not invertible.
+    #[inline]
+    fn has_invariant_inactive_subspace(&self) -> bool
+    where
+        F: Clone + PartialEq,
+    {
+        let size = self.num_rows();
+        let mut rows = Vec::with_capacity(size);
+        rows.push(Vec::allocate_with(size, F::zero));
+        rows[0][0] = F::one();
+        for k in 1..size {
+            let next = self
+                .mul_row_vec_at_left(&rows[k - 1])
+                .expect("Input matrix shapes match.");
+            rows.push(next);
+        }
+        !Self(Matrix(rows)).is_invertible()
+    }
+
+    /// Returns `true` if `self` passes the Poseidon MDS security checks over `rounds` rounds.
+    ///
+    /// The partial rounds of Poseidon only apply the S-box to the first element of the state, so
+    /// any subspace of `{x | x[0] = 0}` which is invariant under a power of the MDS matrix gives an
+    /// infinitely long subspace trail through the partial rounds, as described in "Proving
+    /// Resistance Against Infinitely Long Subspace Trails". This method checks that `self` is
+    /// invertible and that `self^i` has no such invariant subspace for every `1 <= i <= rounds`.
+    /// In particular, no power of `self` is a multiple of the identity or has an eigenvector in
+    /// that subspace. Poseidon parameter generation checks `rounds = 2 * width`.
+    #[inline]
+    pub fn passes_mds_security_checks(&self, rounds: usize) -> bool
+    where
+        F: Clone + PartialEq,
+    {
+        if !self.is_invertible() {
+            return false;
+        }
+        let mut power = self.clone();
+        let mut next = Self::identity(self.num_rows());
+        for i in 1..=rounds {
+            if power.has_invariant_inactive_subspace() {
+                return false;
+            }
+            if i < rounds {
+                self.matmul_into(&power, &mut next)
+                    .expect("Input matrix shapes match.");
+                mem::swap(&mut power, &mut next);
+            }
+        }
+        true
+    }
+
     /// Generates the `(i, j)` minor matrix by removing the `i`th row and `j`th column of `self`.
     #[inline]
     pub fn minor(&self, i: usize, j: usize) -> Option<Self>
@@ -432,59 +508,43 @@ where
         )))
     }
 
-    /// Reduces an upper triangular matrix `self.0` to an identity matrix. This function applies the
-    /// same computation on `shadow` matrix as `self.0`.
+    /// Reduces the upper triangular matrix `self` to an identity matrix in place. This function
+    /// applies the same row operations to the `shadow` matrix as to `self`.
     #[inline]
-    fn reduce_to_identity(&self, shadow: &mut Self) -> Option<Self>
+    fn reduce_to_identity_in_place(&mut self, shadow: &mut Self) -> Option<()>
     where
         F: Clone,
     {
-        let size = self.num_rows();
-        let mut result: Vec<Vec<F>> = Vec::with_capacity(size);
-        let mut shadow_result: Vec<Vec<F>> = Vec::with_capacity(size);
-        for i in 0..size {
-            let idx = size - i - 1;
-            let row = &self.0[idx];
-            let inv = F::inverse(&row[idx])?;
-            let mut normalized = scalar_vec_mul(&inv, row);
-            let mut shadow_normalized = scalar_vec_mul(&inv, &shadow[idx]);
-            for j in 0..i {
-                let idx = size - j - 1;
-                shadow_normalized = vec_sub(
-                    &shadow_normalized,
-                    &scalar_vec_mul(&normalized[idx], &shadow_result[j]),
-                );
-                normalized = vec_sub(&normalized, &scalar_vec_mul(&normalized[idx], &result[j]));
+        let rows = &mut (self.0).0;
+        let shadow_rows = &mut (shadow.0).0;
+        for i in (0..rows.len()).rev() {
+            let inv = F::inverse(&rows[i][i])?;
+            scalar_mul_assign(&inv, &mut rows[i]);
+            scalar_mul_assign(&inv, &mut shadow_rows[i]);
+            let (upper, lower) = rows.split_at_mut(i + 1);
+            let (shadow_upper, shadow_lower) = shadow_rows.split_at_mut(i + 1);
+            for (j, (row, shadow_row)) in lower.iter().zip(shadow_lower.iter()).enumerate() {
+                let factor = upper[i][i + 1 + j].clone();
+                sub_scaled_assign(&mut shadow_upper[i], &factor, shadow_row);
+                sub_scaled_assign(&mut upper[i], &factor, row);
             }
-            result.push(normalized);
-            shadow_result.push(shadow_normalized);
         }
-        result.reverse();
-        shadow_result.reverse();
-        *shadow = Self(Matrix(shadow_result));
-        Some(Self(Matrix(result)))
+        Some(())
     }
 
-    /// Generates the upper triangular matrix such that `self[i][j]` = 0 for all `j`>`i`.
+    /// Reduces `self` to an upper triangular matrix in place such that `self[i][j] = 0` for all
+    /// `j < i`. This function applies the same row operations to the `shadow` matrix as to `self`.
     #[inline]
-    fn upper_triangular(&self, shadow: &mut Self) -> Option<Self>
-    where
-        F: Clone + PartialEq,
-    {
+    fn upper_triangular_in_place(&mut self, shadow: &mut Self) -> Option<()> {
         let size = self.num_rows();
-        let mut result = Vec::with_capacity(size);
-        let mut shadow_result = Vec::with_capacity(size);
-        let mut current = self.0.clone();
-        let mut shadow_matrix = shadow.0.clone();
         for column in 0..(size - 1) {
-            current = current.eliminate(column, &mut shadow_matrix)?;
-            result.push(current.0.remove(0));
-            shadow_result.push(shadow_matrix.0.remove(0));
+            eliminate_rows(
+                &mut (self.0).0[column..],
+                &mut (shadow.0).0[column..],
+                column,
+            )?;
         }
-        result.push(current.0.take_first());
-        shadow_result.push(shadow_matrix.0.take_first());
-        *shadow = Self(Matrix(shadow_result));
-        Some(Self(Matrix(result)))
+        Some(())
     }
 }
 
@@ -534,6 +594,11 @@ where
         self.0.eliminate(column, &mut shadow.0).map(Self)
     }
 
+    #[inline]
+    fn eliminate_in_place(&mut self, column: usize, shadow: &mut Self) -> Option<()> {
+        self.0.eliminate_in_place(column, &mut shadow.0)
+    }
+
     #[inline]
     fn identity(n: usize) -> Self {
         Self(Matrix::identity(n))
@@ -547,6 +612,11 @@ where
         self.0.matmul(&other.0).map(Self)
     }
 
+    #[inline]
+    fn matmul_into(&self, other: &Self, out: &mut Self) -> Option<()> {
+        self.0.matmul_into(&other.0, &mut out.0)
+    }
+
     #[inline]
     fn mul_by_scalar(&self, scalar: Self::Scalar) -> Self {
         Self(self.0.mul_by_scalar(scalar))
@@ -561,6 +631,11 @@ where
     fn transpose(self) -> Self {
         Self(self.0.transpose())
     }
+
+    #[inline]
+    fn transpose_in_place(&mut self) {
+        self.0.transpose_in_place()
+    }
 }
 
 /// Computes the inner product of vector `a` and `b`.
@@ -601,13 +676,54 @@ where
     v.iter().map(|val| F::mul(scalar, val)).collect()
 }
 
-/// Eliminates `row` with `factor` multiplied by the `pivot`.
+/// Multiplies a vector `v` with `scalar` elementwise in place (i.e., `v[i] = scalar * v[i]`).
+#[inline]
+pub fn scalar_mul_assign<F>(scalar: &F, v: &mut [F])
+where
+    F: Field,
+{
+    for val in v {
+        *val = F::mul(scalar, val);
+    }
+}
+
+/// Subtracts `factor` multiplied by `pivot` from `row` elementwise in place (i.e.,
+/// `row[i] = row[i] - factor * pivot[i]`).
 #[inline]
-fn eliminate_row<F>(row: &[F], factor: &F, pivot: &[F]) -> Vec<F>
+fn sub_scaled_assign<F>(row: &mut [F], factor: &F, pivot: &[F])
 where
     F: Field,
 {
-    vec_sub(row, &scalar_vec_mul(factor, pivot))
+    for (val, pivot) in row.iter_mut().zip(pivot) {
+        *val = F::sub(val, &F::mul(factor, pivot));
+    }
+}
+
+/// Eliminates `column` from `rows` in place, moving the pivot row to the front and applying the
+/// same row operations to `shadow`. Returns `None` without modifying `rows` or `shadow` if no row
+/// has a non-zero entry in `column` and zeros in all the columns before it.
+#[inline]
+fn eliminate_rows<F>(rows: &mut [Vec<F>], shadow: &mut [Vec<F>], column: usize) -> Option<()>
+where
+    F: Field,
+{
+    let pivot_index = rows
+        .iter()
+        .position(|row| !row[column].is_zero() && row[..column].iter().all(F::is_zero))?;
+    rows[..=pivot_index].rotate_right(1);
+    shadow[..=pivot_index].rotate_right(1);
+    let (pivot, rows) = rows.split_first_mut().expect("The pivot row exists.");
+    let (shadow_pivot, shadow) = shadow.split_first_mut().expect("The pivot row exists.");
+    let inv_pivot = F::inverse(&pivot[column])
+        .expect("This should never fail since we have a non-zero `pivot_val` if we got here.");
+    for (row, shadow_row) in rows.iter_mut().zip(shadow) {
+        if !row[column].is_zero() {
+            let factor = F::mul(&row[column], &inv_pivot);
+            sub_scaled_assign(row, &factor, pivot);
+            sub_scaled_assign(shadow_row, &factor, shadow_pivot);
+        }
+    }
+    Some(())
 }
 
 /// Returns the kronecker delta of `i` and `j`.
@@ -627,6 +743,7 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::crypto::poseidon::mds::MdsMatrices;
     use manta_crypto::arkworks::{bls12_381::Fr, constraint::fp::Fp};
 
     /// Checks if generating minor matrix is correct.
@@ -773,8 +890,9 @@ mod test {
             vec![four, five, six],
             vec![seven, eight, eight],
         ]));
-        let upper_triangular_form = matrix
-            .upper_triangular(&mut SquareMatrix::identity(matrix.num_rows()))
+        let mut upper_triangular_form = matrix.clone();
+        upper_triangular_form
+            .upper_triangular_in_place(&mut SquareMatrix::identity(matrix.num_rows()))
             .expect("The upper triangular form for `matrix` should exist.");
         assert!(upper_triangular_form[0][0] != zero);
         assert!(upper_triangular_form[0][1] != zero);
@@ -921,9 +1039,9 @@ mod test {
             vec![seven, eight, eight],
         ]));
         let mut shadow = SquareMatrix::identity(m.num_columns());
-        let ut = m.upper_triangular(&mut shadow);
-        let res = ut
-            .and_then(|x: SquareMatrix<Fp<Fr>>| x.reduce_to_identity(&mut shadow))
+        let mut res = m.clone();
+        res.upper_triangular_in_place(&mut shadow)
+            .and_then(|_| res.reduce_to_identity_in_place(&mut shadow))
             .expect("This should generate an identity matrix as output.");
         assert!(res.is_identity());
         assert!(m
@@ -931,4 +1049,67 @@ mod test {
             .expect("Matrix shape matches.")
             .is_identity());
     }
+
+    /// Checks if `transpose_in_place` agrees with `transpose` on square and non-square matrices.
+    #[test]
+    fn transpose_in_place_is_correct() {
+        let values = (1..=6).map(|i| Fp(Fr::from(i as u64))).collect::<Vec<_>>();
+        let rectangle = Matrix::new_unchecked(vec![values[..3].to_vec(), values[3..].to_vec()]);
+        let mut transposed = rectangle.clone();
+        transposed.transpose_in_place();
+        assert_eq!(transposed.num_rows(), 3);
+        assert_eq!(transposed, rectangle.transpose());
+        let square = MdsMatrices::<Fp<Fr>>::generate_mds(4);
+        let mut transposed = square.clone();
+        transposed.transpose_in_place();
+        for i in 0..4 {
+            for j in 0..4 {
+                assert_eq!(transposed[i][j], square[j][i]);
+            }
+        }
+    }
+
+    /// Checks that the generated MDS matrices pass the security checks and that matrices with
+    /// invariant subspaces fail them.
+    #[test]
+    fn mds_security_checks_are_correct() {
+        for width in 2..7 {
+            assert!(
+                MdsMatrices::<Fp<Fr>>::generate_mds(width).passes_mds_security_checks(2 * width),
+                "Generated MDS matrix of width {width} should pass the security checks."
+            );
+        }
+        let zero = Fp(Fr::from(0u64));
+        let one = Fp(Fr::from(1u64));
+        let two = Fp(Fr::from(2u64));
+        let three = Fp(Fr::from(3u64));
+        let with_inactive_eigenvector = SquareMatrix::new_unchecked(Matrix::new_unchecked(vec![
+            vec![one, zero, two],
+            vec![two, three, zero],
+            vec![one, zero, one],
+        ]));
+        assert!(with_inactive_eigenvector.is_invertible());
+        assert!(!with_inactive_eigenvector.passes_mds_security_checks(6));
+        let with_scalar_square = SquareMatrix::new_unchecked(Matrix::new_unchecked(vec![
+            vec![zero, one],
+            vec![one, zero],
+        ]));
+        assert!(with_scalar_square.passes_mds_security_checks(1));
+        assert!(!with_scalar_square.passes_mds_security_checks(2));
+    }
+
+    /// Checks if `matmul_into` matches `matmul` and reuses buffers of any shape.
+    #[test]
+    fn matmul_into_is_correct() {
+        let m = MdsMatrices::<Fp<Fr>>::generate_mds(4);
+        let m_inv = m.inverse().expect("MDS matrices are invertible.");
+        let mut out = SquareMatrix::identity(2);
+        m.matmul_into(&m_inv, &mut out)
+            .expect("Input shapes match.");
+        assert!(out.is_identity());
+        m.matmul_into(&m, &mut out).expect("Input shapes match.");
+        assert_eq!(out, m.matmul(&m).expect("Input shapes match."));
+        let row = Matrix::new_unchecked(vec![vec![Fp(Fr::from(1u64)); 3]]);
+        assert!(row.matmul_into(&row, &mut out.0).is_none());
+    }
 }
@@ -239,17 +239,21 @@ pub fn factor_to_sparse_matrixes<F>(
 where
     F: Clone + Field + FieldGeneration + PartialEq,
 {
-    let (pre_sparse, mut sparse_matrices) = (0..n).fold(
-        (base_matrix.clone(), Vec::with_capacity(n)),
-        |(curr, mut acc), _| {
-            let derived = MdsMatrices::derive_mds_matrices(curr);
-            acc.push(derived.m_double_prime);
-            let new = base_matrix
-                .matmul(&derived.m_prime)
-                .expect("Input matrix shapes match.");
-            (new, acc)
-        },
-    );
+    let mut pre_sparse = base_matrix.clone();
+    let mut sparse_matrices = Vec::with_capacity(n);
+    for _ in 0..n {
+        let MdsMatrices {
+            m: mut next,
+            m_prime,
+            m_double_prime,
+            ..
+        } = MdsMatrices::derive_mds_matrices(pre_sparse);
+        sparse_matrices.push(m_double_prime);
+        base_matrix
+            .matmul_into(&m_prime, &mut next)
+            .expect("Input matrix shapes match.");
+        pre_sparse = next;
+    }
     sparse_matrices.reverse();
     let sparse_matrices = sparse_matrices
         .into_iter()
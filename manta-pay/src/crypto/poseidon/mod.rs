@@ -39,11 +39,20 @@ pub mod matrix;
 pub mod mds;
 pub mod preprocessing;
 pub mod round_constants;
+pub mod sbox;
 
 #[cfg(feature = "arkworks")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "arkworks")))]
 pub mod arkworks;
 
+#[cfg(feature = "std")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+pub mod registry;
+
+#[cfg(all(feature = "arkworks", feature = "std"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "arkworks", feature = "std"))))]
+pub mod reproduce;
+
 /// Field Element
 pub trait Field {
     /// Returns the additive identity of the field.
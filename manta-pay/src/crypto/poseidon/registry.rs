@@ -0,0 +1,476 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Poseidon Parameter Registry
+//!
+//! The [`Registry`] stores the encoded Poseidon parameters for every supported curve, width, and
+//! security level. The round numbers of the default parameters are embedded at compile time in
+//! [`DEFAULTS`], and their round constants and MDS matrices are generated on first use and cached
+//! for the lifetime of the registry. Parameters loaded at runtime, for example from the parameter
+//! manifest, are registered as overrides and are only accepted if they match the output of the
+//! in-crate generator for their round numbers.
+//!
+//! Onboarding a new curve only requires a new [`Curve`] variant, a [`CurveField`] implementation
+//! for its scalar field, and its rows in [`DEFAULTS`].
+
+use crate::crypto::poseidon::{
+    constants, matrix::MatrixOperations, mds::MdsMatrices,
+    round_constants::generate_round_constants, sbox::Alpha, Field, FieldGeneration, Permutation,
+    Specification,
+};
+use alloc::{sync::Arc, vec::Vec};
+use core::fmt;
+use manta_util::codec::{Decode, Encode};
+use std::{
+    collections::HashMap,
+    sync::{OnceLock, RwLock},
+};
+
+/// Supported Curves
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Curve {
+    /// BN254 Scalar Field
+    Bn254,
+
+    /// BLS12-381 Scalar Field
+    Bls12_381,
+}
+
+impl Curve {
+    /// Returns the name of `self` as it appears in the parameter manifest.
+    #[inline]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Bn254 => "bn254",
+            Self::Bls12_381 => "bls12-381",
+        }
+    }
+
+    /// Returns the curve whose [`name`](Self::name) is `name`.
+    #[inline]
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::Bn254, Self::Bls12_381]
+            .into_iter()
+            .find(|curve| curve.name() == name)
+    }
+}
+
+impl fmt::Display for Curve {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Curve Scalar Field
+///
+/// This `trait` identifies the [`Curve`] whose scalar field is used as the parameter field of a
+/// Poseidon [`Specification`].
+pub trait CurveField: Field + FieldGeneration {
+    /// Curve of the Field
+    const CURVE: Curve;
+}
+
+/// Registry Key
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Key {
+    /// Curve
+    pub curve: Curve,
+
+    /// Permutation Width
+    pub width: usize,
+
+    /// Security Level in Bits
+    pub security_level: usize,
+}
+
+impl Key {
+    /// Builds a new [`Key`] from `curve`, `width`, and `security_level`.
+    #[inline]
+    pub const fn new(curve: Curve, width: usize, security_level: usize) -> Self {
+        Self {
+            curve,
+            width,
+            security_level,
+        }
+    }
+
+    /// Builds the [`Key`] for the specification `S` at the given `security_level`.
+    #[inline]
+    pub fn from_specification<S>(security_level: usize) -> Self
+    where
+        S: Specification,
+        S::ParameterField: CurveField,
+    {
+        Self::new(
+            <S::ParameterField as CurveField>::CURVE,
+            S::WIDTH,
+            security_level,
+        )
+    }
+}
+
+/// Round Numbers
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Rounds {
+    /// Number of Full Rounds
+    pub full_rounds: usize,
+
+    /// Number of Partial Rounds
+    pub partial_rounds: usize,
+}
+
+impl Rounds {
+    /// Builds a new [`Rounds`] from `full_rounds` and `partial_rounds`.
+    #[inline]
+    pub const fn new(full_rounds: usize, partial_rounds: usize) -> Self {
+        Self {
+            full_rounds,
+            partial_rounds,
+        }
+    }
+
+    /// Returns the round numbers of the specification `S`.
+    #[inline]
+    pub fn from_specification<S>() -> Self
+    where
+        S: Specification,
+    {
+        Self::new(S::FULL_ROUNDS, S::PARTIAL_ROUNDS)
+    }
+}
+
+/// Default Parameters
+///
+/// These are the round numbers of the parameters that every [`Registry`] knows about without any
+/// overrides. The BN254 rows match the specifications in [`crate::config::poseidon`].
+pub const DEFAULTS: &[(Key, Rounds)] = &[
+    (Key::new(Curve::Bn254, 3, 128), Rounds::new(8, 55)),
+    (Key::new(Curve::Bn254, 4, 128), Rounds::new(8, 55)),
+    (Key::new(Curve::Bn254, 5, 128), Rounds::new(8, 56)),
+    (Key::new(Curve::Bn254, 6, 128), Rounds::new(8, 56)),
+    (Key::new(Curve::Bls12_381, 3, 128), Rounds::new(8, 55)),
+    (Key::new(Curve::Bls12_381, 4, 128), Rounds::new(8, 56)),
+    (Key::new(Curve::Bls12_381, 5, 128), Rounds::new(8, 56)),
+    (Key::new(Curve::Bls12_381, 6, 128), Rounds::new(8, 56)),
+];
+
+/// Registry Error
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RegistryError {
+    /// Key has no Default or Override Parameters
+    UnknownKey(Key),
+
+    /// Requested Round Numbers do not Match the Registered Ones
+    RoundsMismatch {
+        /// Registered Round Numbers
+        expected: Rounds,
+
+        /// Requested Round Numbers
+        found: Rounds,
+    },
+
+    /// Round Numbers are Insecure for the Key
+    Insecure(Key, Rounds),
+
+    /// Parameters do not Match the In-Crate Generator
+    GeneratorMismatch(Key),
+
+    /// MDS Matrix Fails the Security Checks
+    InsecureMds(Key),
+
+    /// Parameters could not be Decoded
+    Decode(Key),
+}
+
+impl fmt::Display for RegistryError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownKey(key) => {
+                write!(f, "No Poseidon parameters are registered for {key:?}.")
+            }
+            Self::RoundsMismatch { expected, found } => write!(
+                f,
+                "Registered round numbers {expected:?} do not match the requested {found:?}."
+            ),
+            Self::Insecure(key, rounds) => {
+                write!(f, "Round numbers {rounds:?} are insecure for {key:?}.")
+            }
+            Self::GeneratorMismatch(key) => write!(
+                f,
+                "Poseidon parameters for {key:?} do not match the in-crate generator."
+            ),
+            Self::InsecureMds(key) => write!(
+                f,
+                "The MDS matrix for {key:?} does not pass the security checks."
+            ),
+            Self::Decode(key) => write!(f, "Unable to decode the Poseidon parameters for {key:?}."),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+/// Registry Entry
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Entry {
+    /// Round Numbers
+    pub rounds: Rounds,
+
+    /// Encoded Additive Round Keys followed by the Encoded MDS Matrix
+    pub data: Vec<u8>,
+}
+
+impl Entry {
+    /// Generates the entry for `key` with `rounds` using the in-crate generator over the field `F`.
+    #[inline]
+    pub fn generate<F>(key: Key, rounds: Rounds) -> Self
+    where
+        F: Encode + Field + FieldGeneration,
+    {
+        let mut data = Vec::new();
+        for constant in
+            generate_round_constants::<F>(key.width, rounds.full_rounds, rounds.partial_rounds)
+        {
+            constant
+                .encode(&mut data)
+                .expect("Writing to a `Vec<u8>` cannot fail.");
+        }
+        for entry in MdsMatrices::<F>::generate_mds(key.width).to_row_major() {
+            entry
+                .encode(&mut data)
+                .expect("Writing to a `Vec<u8>` cannot fail.");
+        }
+        Self { rounds, data }
+    }
+}
+
+/// Poseidon Parameter Registry
+#[derive(Debug, Default)]
+pub struct Registry {
+    /// Override Entries
+    overrides: RwLock<HashMap<Key, Arc<Entry>>>,
+
+    /// Generated Default Entries
+    defaults: RwLock<HashMap<Key, Arc<Entry>>>,
+}
+
+impl Registry {
+    /// Builds a new [`Registry`] with only the [`DEFAULTS`] and no overrides.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the embedded default round numbers for `key`.
+    #[inline]
+    pub fn default_rounds(key: Key) -> Option<Rounds> {
+        DEFAULTS
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, rounds)| *rounds)
+    }
+
+    /// Returns `true` if `rounds` are secure for the curve and width of `key` at its security
+    /// level.
+    #[inline]
+    pub fn is_secure<F>(key: Key, rounds: Rounds) -> bool
+    where
+        F: FieldGeneration,
+    {
+        use constants::security::SecurityCondition;
+        constants::security::Full::is_secure(
+            Alpha::QUINTIC,
+            rounds.full_rounds,
+            key.width as f32,
+            rounds.partial_rounds as f32,
+            F::MODULUS_BITS as f32,
+            key.security_level as f32,
+        )
+    }
+
+    /// Returns the round numbers registered for `key`, preferring overrides over the defaults.
+    #[inline]
+    pub fn rounds(&self, key: Key) -> Option<Rounds> {
+        match self
+            .overrides
+            .read()
+            .expect("Lock is not poisoned.")
+            .get(&key)
+        {
+            Some(entry) => Some(entry.rounds),
+            _ => Self::default_rounds(key),
+        }
+    }
+
+    /// Registers the encoded parameters `data` with `rounds` as the override for `key`, after
+    /// checking that they are secure and match the in-crate generator over the field `F`. The MDS
+    /// matrix must also pass [`passes_mds_security_checks`] over `2 * key.width` rounds.
+    ///
+    /// [`passes_mds_security_checks`]: crate::crypto::poseidon::matrix::SquareMatrix::passes_mds_security_checks
+    #[inline]
+    pub fn insert_override<F>(
+        &self,
+        key: Key,
+        rounds: Rounds,
+        data: Vec<u8>,
+    ) -> Result<(), RegistryError>
+    where
+        F: Clone + CurveField + Encode + PartialEq,
+    {
+        if key.curve != F::CURVE {
+            return Err(RegistryError::UnknownKey(key));
+        }
+        if !Self::is_secure::<F>(key, rounds) {
+            return Err(RegistryError::Insecure(key, rounds));
+        }
+        if !MdsMatrices::<F>::generate_mds(key.width).passes_mds_security_checks(2 * key.width) {
+            return Err(RegistryError::InsecureMds(key));
+        }
+        if Entry::generate::<F>(key, rounds).data != data {
+            return Err(RegistryError::GeneratorMismatch(key));
+        }
+        self.overrides
+            .write()
+            .expect("Lock is not poisoned.")
+            .insert(key, Arc::new(Entry { rounds, data }));
+        Ok(())
+    }
+
+    /// Removes the override for `key`, returning it if it was present.
+    #[inline]
+    pub fn remove_override(&self, key: Key) -> Option<Arc<Entry>> {
+        self.overrides
+            .write()
+            .expect("Lock is not poisoned.")
+            .remove(&key)
+    }
+
+    /// Returns the entry for `key`, generating and caching the default entry over the field `F` if
+    /// there is no override.
+    #[inline]
+    pub fn entry<F>(&self, key: Key) -> Result<Arc<Entry>, RegistryError>
+    where
+        F: CurveField + Encode,
+    {
+        if key.curve != F::CURVE {
+            return Err(RegistryError::UnknownKey(key));
+        }
+        if let Some(entry) = self
+            .overrides
+            .read()
+            .expect("Lock is not poisoned.")
+            .get(&key)
+        {
+            return Ok(entry.clone());
+        }
+        if let Some(entry) = self
+            .defaults
+            .read()
+            .expect("Lock is not poisoned.")
+            .get(&key)
+        {
+            return Ok(entry.clone());
+        }
+        let rounds = Self::default_rounds(key).ok_or(RegistryError::UnknownKey(key))?;
+        Ok(self
+            .defaults
+            .write()
+            .expect("Lock is not poisoned.")
+            .entry(key)
+            .or_insert_with(|| Arc::new(Entry::generate::<F>(key, rounds)))
+            .clone())
+    }
+
+    /// Returns the permutation for the specification `S` at the given `security_level`.
+    #[inline]
+    pub fn permutation<S>(&self, security_level: usize) -> Result<Permutation<S>, RegistryError>
+    where
+        S: Specification,
+        S::ParameterField: CurveField + Decode + Encode,
+    {
+        let key = Key::from_specification::<S>(security_level);
+        let entry = self.entry::<S::ParameterField>(key)?;
+        let found = Rounds::from_specification::<S>();
+        if entry.rounds != found {
+            return Err(RegistryError::RoundsMismatch {
+                expected: entry.rounds,
+                found,
+            });
+        }
+        Permutation::from_vec(entry.data.clone()).map_err(|_| RegistryError::Decode(key))
+    }
+}
+
+/// Returns the global [`Registry`], building it on first use.
+#[inline]
+pub fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(Registry::new)
+}
+
+/// Testing Suite
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::poseidon::Spec;
+    use manta_crypto::rand::{OsRng, Rand};
+
+    /// Tests that the registry defaults match the sampled permutations of the BN254
+    /// specifications.
+    #[test]
+    fn defaults_match_sampled_permutations() {
+        let registry = Registry::new();
+        let mut rng = OsRng;
+        assert_eq!(
+            registry.permutation::<Spec<2>>(128).unwrap(),
+            rng.gen::<_, Permutation<Spec<2>>>(),
+        );
+        assert_eq!(
+            registry.permutation::<Spec<4>>(128).unwrap(),
+            rng.gen::<_, Permutation<Spec<4>>>(),
+        );
+    }
+
+    /// Tests that overrides are only accepted if they match the in-crate generator.
+    #[test]
+    fn overrides_are_validated() {
+        type F = <Spec<2> as crate::crypto::poseidon::ParameterFieldType>::ParameterField;
+        let registry = Registry::new();
+        let key = Key::new(Curve::Bn254, 3, 128);
+        let rounds = Rounds::new(8, 60);
+        let mut data = Entry::generate::<F>(key, rounds).data;
+        data[0] ^= 1;
+        assert_eq!(
+            registry.insert_override::<F>(key, rounds, data.clone()),
+            Err(RegistryError::GeneratorMismatch(key))
+        );
+        data[0] ^= 1;
+        assert_eq!(
+            registry.insert_override::<F>(key, Rounds::new(2, 10), data.clone()),
+            Err(RegistryError::Insecure(key, Rounds::new(2, 10)))
+        );
+        registry.insert_override::<F>(key, rounds, data).unwrap();
+        assert_eq!(registry.rounds(key), Some(rounds));
+        assert!(matches!(
+            registry.permutation::<Spec<2>>(128),
+            Err(RegistryError::RoundsMismatch { .. })
+        ));
+        registry.remove_override(key);
+        assert!(registry.permutation::<Spec<2>>(128).is_ok());
+    }
+}
@@ -0,0 +1,386 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reproducible Poseidon Parameter Generation
+//!
+//! Poseidon parameters are fully determined by the curve, the width, and the round numbers: the
+//! round constants are sampled from the Grain LFSR seeded with these values, and the MDS matrix is
+//! the Cauchy matrix of the width. The [`reproduce`] function runs this whole pipeline from a
+//! [`SeedSpecification`] string and returns the encoded parameters together with a [`Report`]
+//! of its inputs and the BLAKE2s digests of every intermediate output, so that anyone can
+//! regenerate shipped parameters byte-for-byte and compare the digests step by step.
+//!
+//! # Seed Specification Format
+//!
+//! A seed specification is a comma-separated list of `key=value` pairs with the keys `curve`,
+//! `width`, `full_rounds`, `partial_rounds`, and the optional `security_level` which defaults to
+//! `128`, for example `curve=bn254,width=3,full_rounds=8,partial_rounds=55`. The
+//! [`Display`](fmt::Display) implementation of [`SeedSpecification`] prints the canonical form
+//! with all the keys in this order.
+
+use crate::crypto::poseidon::{
+    matrix::MatrixOperations,
+    mds::MdsMatrices,
+    registry::{Curve, CurveField, Key, Registry, Rounds},
+    round_constants::generate_round_constants,
+};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use blake2::{Blake2s256, Digest};
+use core::{fmt, str::FromStr};
+use manta_crypto::arkworks::{bls12_381, bn254, constraint::fp::Fp};
+use manta_util::codec::Encode;
+
+/// Default Security Level in Bits
+pub const DEFAULT_SECURITY_LEVEL: usize = 128;
+
+/// Seed Specification Error
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum SpecificationError {
+    /// Entry is not of the Form `key=value`
+    Malformed(String),
+
+    /// Key is not Part of the Specification Format
+    UnknownKey(String),
+
+    /// Key Appears More than Once
+    DuplicateKey(&'static str),
+
+    /// Required Key is Missing
+    MissingKey(&'static str),
+
+    /// Value of the Key is Invalid
+    InvalidValue(&'static str, String),
+}
+
+impl fmt::Display for SpecificationError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Malformed(entry) => write!(f, "Entry `{entry}` is not of the form `key=value`."),
+            Self::UnknownKey(key) => write!(f, "Key `{key}` is unknown."),
+            Self::DuplicateKey(key) => write!(f, "Key `{key}` appears more than once."),
+            Self::MissingKey(key) => write!(f, "Key `{key}` is missing."),
+            Self::InvalidValue(key, value) => write!(f, "Value `{value}` is invalid for `{key}`."),
+        }
+    }
+}
+
+impl std::error::Error for SpecificationError {}
+
+/// Seed Specification
+///
+/// These are all the inputs of the Poseidon parameter generation pipeline. See the [module-level
+/// documentation](self) for the string format.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SeedSpecification {
+    /// Registry Key
+    pub key: Key,
+
+    /// Round Numbers
+    pub rounds: Rounds,
+}
+
+impl SeedSpecification {
+    /// Builds a new [`SeedSpecification`] from `key` and `rounds`.
+    #[inline]
+    pub const fn new(key: Key, rounds: Rounds) -> Self {
+        Self { key, rounds }
+    }
+
+    /// Parses the value of `key` as a number smaller than `2^bits`, which is the size of its
+    /// field in the Grain LFSR seed.
+    #[inline]
+    fn parse_number(
+        key: &'static str,
+        value: &str,
+        bits: u32,
+    ) -> Result<usize, SpecificationError> {
+        match value.parse::<usize>() {
+            Ok(number) if number < (1 << bits) => Ok(number),
+            _ => Err(SpecificationError::InvalidValue(key, value.into())),
+        }
+    }
+}
+
+impl fmt::Display for SeedSpecification {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "curve={},width={},full_rounds={},partial_rounds={},security_level={}",
+            self.key.curve,
+            self.key.width,
+            self.rounds.full_rounds,
+            self.rounds.partial_rounds,
+            self.key.security_level
+        )
+    }
+}
+
+impl FromStr for SeedSpecification {
+    type Err = SpecificationError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const KEYS: [&str; 5] = [
+            "curve",
+            "width",
+            "full_rounds",
+            "partial_rounds",
+            "security_level",
+        ];
+        let mut values = [None; 5];
+        for entry in s.split(',').map(str::trim) {
+            let (key, value) = entry
+                .split_once('=')
+                .ok_or_else(|| SpecificationError::Malformed(entry.into()))?;
+            let index = KEYS
+                .iter()
+                .position(|k| *k == key.trim())
+                .ok_or_else(|| SpecificationError::UnknownKey(key.trim().into()))?;
+            if values[index].replace(value.trim()).is_some() {
+                return Err(SpecificationError::DuplicateKey(KEYS[index]));
+            }
+        }
+        let value = |index: usize| values[index].ok_or(SpecificationError::MissingKey(KEYS[index]));
+        let curve = value(0)?;
+        let curve = Curve::from_name(curve)
+            .ok_or_else(|| SpecificationError::InvalidValue(KEYS[0], curve.into()))?;
+        let width = Self::parse_number(KEYS[1], value(1)?, 12)?;
+        if width < 2 {
+            return Err(SpecificationError::InvalidValue(KEYS[1], width.to_string()));
+        }
+        let full_rounds = Self::parse_number(KEYS[2], value(2)?, 10)?;
+        let partial_rounds = Self::parse_number(KEYS[3], value(3)?, 10)?;
+        let security_level = match values[4] {
+            Some(security_level) => security_level
+                .parse()
+                .map_err(|_| SpecificationError::InvalidValue(KEYS[4], security_level.into()))?,
+            _ => DEFAULT_SECURITY_LEVEL,
+        };
+        Ok(Self::new(
+            Key::new(curve, width, security_level),
+            Rounds::new(full_rounds, partial_rounds),
+        ))
+    }
+}
+
+/// BLAKE2s Digest
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Checksum(pub [u8; 32]);
+
+impl Checksum {
+    /// Computes the BLAKE2s digest of `bytes`.
+    #[inline]
+    pub fn compute(bytes: &[u8]) -> Self {
+        Self(Blake2s256::digest(bytes).into())
+    }
+}
+
+impl fmt::Display for Checksum {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Reproducibility Report
+///
+/// The report records the inputs of the generation pipeline and the digests of its outputs. Two
+/// reports generated from the same [`SeedSpecification`] are always equal.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Report {
+    /// Seed Specification
+    pub specification: SeedSpecification,
+
+    /// Number of Bits of the Field Modulus
+    pub modulus_bits: usize,
+
+    /// Number of Round Constants
+    pub round_constants: usize,
+
+    /// Digest of the Encoded Round Constants
+    pub round_constants_checksum: Checksum,
+
+    /// Digest of the Encoded MDS Matrix in Row-Major Order
+    pub mds_checksum: Checksum,
+
+    /// Digest of the Encoded Parameters
+    pub parameters_checksum: Checksum,
+
+    /// Round Numbers Satisfy the Security Conditions for the Quintic S-Box
+    pub secure_rounds: bool,
+
+    /// MDS Matrix Passes the Security Checks
+    pub secure_mds: bool,
+}
+
+impl fmt::Display for Report {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "specification: {}", self.specification)?;
+        writeln!(f, "modulus_bits: {}", self.modulus_bits)?;
+        writeln!(f, "round_constants: {}", self.round_constants)?;
+        writeln!(
+            f,
+            "round_constants_blake2s: {}",
+            self.round_constants_checksum
+        )?;
+        writeln!(f, "mds_blake2s: {}", self.mds_checksum)?;
+        writeln!(f, "parameters_blake2s: {}", self.parameters_checksum)?;
+        writeln!(f, "secure_rounds: {}", self.secure_rounds)?;
+        write!(f, "secure_mds: {}", self.secure_mds)
+    }
+}
+
+/// Reproduced Parameters
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Reproduction {
+    /// Encoded Additive Round Keys followed by the Encoded MDS Matrix
+    ///
+    /// This is the encoding of [`Permutation`](crate::crypto::poseidon::Permutation) and of the
+    /// data of a [`registry::Entry`](crate::crypto::poseidon::registry::Entry).
+    pub parameters: Vec<u8>,
+
+    /// Reproducibility Report
+    pub report: Report,
+}
+
+impl Reproduction {
+    /// Returns `true` if `parameters` are byte-for-byte equal to the reproduced parameters.
+    #[inline]
+    pub fn matches(&self, parameters: &[u8]) -> bool {
+        self.parameters == parameters
+    }
+
+    /// Runs the generation pipeline for `specification` over the field `F`.
+    #[inline]
+    fn generate<F>(specification: SeedSpecification) -> Self
+    where
+        F: Clone + CurveField + Encode + PartialEq,
+    {
+        let SeedSpecification { key, rounds } = specification;
+        let round_constants =
+            generate_round_constants::<F>(key.width, rounds.full_rounds, rounds.partial_rounds);
+        let mut parameters = Vec::new();
+        for constant in &round_constants {
+            constant
+                .encode(&mut parameters)
+                .expect("Writing to a `Vec<u8>` cannot fail.");
+        }
+        let round_constants_checksum = Checksum::compute(&parameters);
+        let mds = MdsMatrices::<F>::generate_mds(key.width);
+        let secure_mds = mds.passes_mds_security_checks(2 * key.width);
+        let mut mds_bytes = Vec::new();
+        for entry in mds.to_row_major() {
+            entry
+                .encode(&mut mds_bytes)
+                .expect("Writing to a `Vec<u8>` cannot fail.");
+        }
+        parameters.extend_from_slice(&mds_bytes);
+        Self {
+            report: Report {
+                specification,
+                modulus_bits: F::MODULUS_BITS,
+                round_constants: round_constants.len(),
+                round_constants_checksum,
+                mds_checksum: Checksum::compute(&mds_bytes),
+                parameters_checksum: Checksum::compute(&parameters),
+                secure_rounds: Registry::is_secure::<F>(key, rounds),
+                secure_mds,
+            },
+            parameters,
+        }
+    }
+}
+
+/// Reproduces the Poseidon parameters for `specification`. See the [module-level
+/// documentation](self) for the format of `specification`.
+#[inline]
+pub fn reproduce(specification: &str) -> Result<Reproduction, SpecificationError> {
+    let specification = specification.parse::<SeedSpecification>()?;
+    Ok(match specification.key.curve {
+        Curve::Bn254 => Reproduction::generate::<Fp<bn254::Fr>>(specification),
+        Curve::Bls12_381 => Reproduction::generate::<Fp<bls12_381::Fr>>(specification),
+    })
+}
+
+/// Testing Suite
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        config::poseidon::Spec,
+        crypto::poseidon::{registry::Entry, Permutation},
+    };
+    use manta_crypto::rand::{OsRng, Rand};
+    use manta_util::codec::Decode;
+
+    /// Tests that reproduced parameters match the sampled permutation and the registry entry.
+    #[test]
+    fn reproduction_matches_sampled_permutation() {
+        let reproduction = reproduce("curve=bn254, width=3, full_rounds=8, partial_rounds=55")
+            .expect("Specification is valid.");
+        assert_eq!(
+            Permutation::<Spec<2>>::from_vec(reproduction.parameters.clone())
+                .expect("Parameters should decode."),
+            OsRng.gen::<_, Permutation<Spec<2>>>()
+        );
+        let SeedSpecification { key, rounds } = reproduction.report.specification;
+        assert!(reproduction.matches(&Entry::generate::<Fp<bn254::Fr>>(key, rounds).data));
+        assert!(reproduction.report.secure_rounds);
+        assert!(reproduction.report.secure_mds);
+        assert_eq!(
+            reproduction,
+            reproduce(&reproduction.report.specification.to_string()).unwrap(),
+            "The canonical specification should reproduce the same parameters."
+        );
+    }
+
+    /// Tests that malformed specifications are rejected.
+    #[test]
+    fn malformed_specifications_are_rejected() {
+        assert_eq!(
+            reproduce("curve=bn254,width=3,full_rounds=8"),
+            Err(SpecificationError::MissingKey("partial_rounds"))
+        );
+        assert_eq!(
+            reproduce("curve=bn254,width=3,width=3,full_rounds=8,partial_rounds=55"),
+            Err(SpecificationError::DuplicateKey("width"))
+        );
+        assert_eq!(
+            reproduce("curve=bn254,width=3,alpha=5,full_rounds=8,partial_rounds=55"),
+            Err(SpecificationError::UnknownKey("alpha".into()))
+        );
+        assert_eq!(
+            reproduce("curve=pallas,width=3,full_rounds=8,partial_rounds=55"),
+            Err(SpecificationError::InvalidValue("curve", "pallas".into()))
+        );
+        assert_eq!(
+            reproduce("curve=bn254,width=3,full_rounds=1024,partial_rounds=55"),
+            Err(SpecificationError::InvalidValue(
+                "full_rounds",
+                "1024".into()
+            ))
+        );
+    }
+}
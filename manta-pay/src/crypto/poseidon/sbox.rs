@@ -0,0 +1,173 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Poseidon S-Boxes
+//!
+//! The Poseidon S-box is the power map `x^α`, which is only a permutation of the field if `α` is
+//! coprime to `p - 1`. The usual choices are `α = 3`, `α = 5`, or `α = 7`, depending on which is
+//! the smallest valid exponent for the field, and the inverse S-box `x^(-1)`, which maps zero to
+//! itself and is a permutation of every field. The S-box is chosen at the type level with the
+//! [`SBox`] trait, and the round numbers for each [`Alpha`] are computed in [`constants`].
+//!
+//! [`constants`]: super::constants
+
+/// S-Box Exponent
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Alpha {
+    /// Power Map `x^α` for a Positive `α`
+    Power(u64),
+
+    /// Inverse Map `x^(-1)` with `0^(-1) = 0`
+    Inverse,
+}
+
+impl Alpha {
+    /// Quintic S-Box Exponent
+    pub const QUINTIC: Self = Self::Power(5);
+
+    /// Returns `true` if the S-box with this exponent is a permutation of a field whose modulus
+    /// `p` satisfies `p - 1 = remainder (mod α)`, where `remainder` is computed by `rem`.
+    #[inline]
+    pub fn is_permutation_with<R>(self, rem: R) -> bool
+    where
+        R: FnOnce(u64) -> u64,
+    {
+        match self {
+            Self::Power(alpha) => alpha > 1 && gcd(alpha, rem(alpha)) == 1,
+            Self::Inverse => true,
+        }
+    }
+}
+
+/// Returns the greatest common divisor of `a` and `b`.
+#[inline]
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Poseidon S-Box
+pub trait SBox {
+    /// S-Box Exponent
+    const ALPHA: Alpha;
+}
+
+/// Power Map S-Box `x^ALPHA`
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Power<const ALPHA: u64>;
+
+impl<const ALPHA: u64> SBox for Power<ALPHA> {
+    const ALPHA: Alpha = Alpha::Power(ALPHA);
+}
+
+/// Cubic S-Box `x^3`
+pub type Cubic = Power<3>;
+
+/// Quintic S-Box `x^5`
+pub type Quintic = Power<5>;
+
+/// Septic S-Box `x^7`
+pub type Septic = Power<7>;
+
+/// Inverse S-Box `x^(-1)`
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Inverse;
+
+impl SBox for Inverse {
+    const ALPHA: Alpha = Alpha::Inverse;
+}
+
+/// Testing Suite
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::crypto::poseidon::{self, arkworks::sbox_is_permutation, constants::Constants};
+    use manta_crypto::{
+        arkworks::{
+            bls12_381, bn254,
+            constraint::{fp::Fp, FpVar, R1CS},
+            r1cs_std::R1CSVar,
+        },
+        eclair::alloc::{mode::Secret, Allocate},
+        rand::{OsRng, Rand},
+    };
+
+    /// Inverse S-Box Specification
+    struct Spec;
+
+    impl poseidon::Constants for Spec {
+        const WIDTH: usize = 3;
+        const FULL_ROUNDS: usize = 8;
+        const PARTIAL_ROUNDS: usize = 56;
+    }
+
+    impl poseidon::arkworks::Specification for Spec {
+        type Field = bn254::Fr;
+        type SBox = Inverse;
+    }
+
+    /// Tests that only the S-boxes whose exponent is coprime to `p - 1` are permutations.
+    #[test]
+    fn sbox_permutation_check_is_correct() {
+        assert!(!sbox_is_permutation::<Cubic, bn254::Fr>());
+        assert!(!sbox_is_permutation::<Cubic, bls12_381::Fr>());
+        assert!(sbox_is_permutation::<Quintic, bn254::Fr>());
+        assert!(sbox_is_permutation::<Quintic, bls12_381::Fr>());
+        assert!(sbox_is_permutation::<Septic, bn254::Fr>());
+        assert!(sbox_is_permutation::<Inverse, bls12_381::Fr>());
+        assert!(!sbox_is_permutation::<Power<1>, bn254::Fr>());
+    }
+
+    /// Tests that the native and circuit inverse S-boxes agree, including at zero.
+    #[test]
+    fn inverse_sbox_matches_circuit() {
+        let mut rng = OsRng;
+        let mut cs = R1CS::<bn254::Fr>::for_proofs();
+        for value in [Fp(bn254::Fr::from(0u8)), rng.gen(), rng.gen()] {
+            let mut native = value;
+            <Spec as poseidon::Specification>::apply_sbox(&mut native, &mut ());
+            let mut variable = value.as_known::<Secret, FpVar<_>>(&mut cs);
+            <Spec as poseidon::Specification<_>>::apply_sbox(&mut variable, &mut cs);
+            assert_eq!(
+                native.0,
+                variable.value().expect("Variable has a known value."),
+                "Native and circuit S-boxes should agree."
+            );
+        }
+        assert!(cs.is_satisfied(), "Inverse S-box constraints should hold.");
+    }
+
+    /// Tests that the round numbers computed for each S-box are secure and that the quintic
+    /// round numbers are unchanged.
+    #[test]
+    fn round_numbers_are_secure_for_every_sbox() {
+        for arity in 1..16 {
+            assert_eq!(
+                Constants::from_arity(arity),
+                Constants::from_arity_with_alpha(arity, Alpha::QUINTIC)
+            );
+            for alpha in [Cubic::ALPHA, Quintic::ALPHA, Septic::ALPHA, Inverse::ALPHA] {
+                let constants = Constants::from_arity_with_alpha(arity, alpha);
+                assert!(
+                    constants.are_secure_with_alpha(alpha),
+                    "Constants should be secure."
+                );
+            }
+        }
+    }
+}
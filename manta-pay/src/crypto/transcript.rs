@@ -0,0 +1,82 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fiat-Shamir Transcript Implementations
+
+use crate::domain;
+use blake2::{Blake2s256, Digest};
+use core::marker::PhantomData;
+use manta_crypto::{
+    arkworks::{constraint::fp::Fp, ff::PrimeField},
+    transcript::Transcript,
+};
+
+/// Blake2s Transcript
+///
+/// Records the labeled messages in a running Blake2s hash and reduces the hash into the prime
+/// field `F` for every challenge.
+#[derive(derivative::Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct Blake2sTranscript<F> {
+    /// Running Hash
+    hasher: Blake2s256,
+
+    /// Type Parameter Marker
+    __: PhantomData<F>,
+}
+
+impl<F> Blake2sTranscript<F> {
+    /// Builds a new [`Blake2sTranscript`] separated from the transcripts of other protocols by
+    /// `domain`.
+    #[inline]
+    pub fn new(domain: &'static [u8]) -> Self {
+        let mut transcript = Self {
+            hasher: Blake2s256::new(),
+            __: PhantomData,
+        };
+        transcript.update(domain::TRANSCRIPT.bytes, domain);
+        transcript
+    }
+
+    /// Updates the running hash with `label` and `message`, prefixed with their lengths.
+    #[inline]
+    fn update(&mut self, label: &[u8], message: &[u8]) {
+        Digest::update(&mut self.hasher, (label.len() as u64).to_le_bytes());
+        Digest::update(&mut self.hasher, label);
+        Digest::update(&mut self.hasher, (message.len() as u64).to_le_bytes());
+        Digest::update(&mut self.hasher, message);
+    }
+}
+
+impl<F> Transcript for Blake2sTranscript<F>
+where
+    F: PrimeField,
+{
+    type Challenge = Fp<F>;
+
+    #[inline]
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        self.update(label, message);
+    }
+
+    #[inline]
+    fn challenge(&mut self, label: &'static [u8]) -> Self::Challenge {
+        self.update(label, &[]);
+        let bytes: [u8; 32] = self.hasher.clone().finalize().into();
+        self.update(b"challenge", &bytes);
+        Fp(F::from_le_bytes_mod_order(&bytes))
+    }
+}
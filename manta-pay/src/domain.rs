@@ -0,0 +1,303 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Domain Separation Registry
+//!
+//! Every hash and signature in the protocol is separated from the others by a domain tag, so that
+//! an output computed for one purpose can never be replayed as an output for another. This module
+//! is the single place where these tags are defined:
+//!
+//! - [`DomainTag`]s are byte strings prepended to the inputs of the Blake2s hashes, and are listed
+//!   in [`BYTE_TAGS`].
+//! - [`FieldDomainTag`]s are the field elements which start the Poseidon hashes of the circuits,
+//!   and are listed in [`FIELD_TAGS`].
+//!
+//! New features claim a tag by adding a constant built with the `tag!` macro to this module and
+//! listing it in the registry, and [`check_byte_tags`] and [`check_field_tags`] reject the
+//! registry if the new tag collides with an existing one. Changing the hash of an existing feature
+//! is done by claiming a tag with a new version instead of editing the old one, which has to stay
+//! in the registry for as long as data hashed under it is accepted.
+
+use manta_accounting::transfer::utxo::protocol::OWNERSHIP_PROOF_DOMAIN_TAG;
+
+/// Builds a versioned [`DomainTag`] for the hash called `$name`, whose bytes are
+/// `manta-pay/$version/$name`.
+macro_rules! tag {
+    ($version:literal, $name:literal) => {
+        DomainTag {
+            name: $name,
+            version: Some($version),
+            bytes: concat!("manta-pay/", $version, "/", $name).as_bytes(),
+        }
+    };
+}
+
+/// Byte Domain Tag
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct DomainTag {
+    /// Name of the Hash
+    pub name: &'static str,
+
+    /// Version of the Tag
+    ///
+    /// Legacy tags which predate the registry do not follow the `manta-pay/<version>/<name>`
+    /// format and have no version.
+    pub version: Option<&'static str>,
+
+    /// Tag Bytes
+    pub bytes: &'static [u8],
+}
+
+impl DomainTag {
+    /// Builds a legacy [`DomainTag`] for the hash called `name` out of its existing `bytes`.
+    #[inline]
+    pub const fn legacy(name: &'static str, bytes: &'static [u8]) -> Self {
+        Self {
+            name,
+            version: None,
+            bytes,
+        }
+    }
+}
+
+/// Field Domain Tag
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FieldDomainTag {
+    /// Name of the Hash
+    pub name: &'static str,
+
+    /// Arity of the Poseidon Hash
+    pub arity: usize,
+
+    /// Tag Value
+    pub value: u8,
+
+    /// Legacy Flag
+    ///
+    /// Legacy tags are fixed by the proving keys of the published parameters, so they can only
+    /// change along with the parameters. They are allowed to collide with each other, but not with
+    /// any new tag.
+    pub legacy: bool,
+}
+
+/// Domain Tag Collision
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Collision {
+    /// Both tags are equal.
+    Equal(&'static str, &'static str),
+
+    /// The first tag is a prefix of the second, so a message hashed under the first tag can be
+    /// read as a message hashed under the second.
+    Prefix(&'static str, &'static str),
+}
+
+/// Checks that no two tags in `tags` are equal or a prefix of one another.
+#[inline]
+pub fn check_byte_tags(tags: &[DomainTag]) -> Result<(), Collision> {
+    for (i, lhs) in tags.iter().enumerate() {
+        for rhs in &tags[i + 1..] {
+            if lhs.bytes == rhs.bytes {
+                return Err(Collision::Equal(lhs.name, rhs.name));
+            } else if rhs.bytes.starts_with(lhs.bytes) {
+                return Err(Collision::Prefix(lhs.name, rhs.name));
+            } else if lhs.bytes.starts_with(rhs.bytes) {
+                return Err(Collision::Prefix(rhs.name, lhs.name));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks that no two tags in `tags` with the same arity have the same value, unless both of them
+/// are legacy tags.
+#[inline]
+pub fn check_field_tags(tags: &[FieldDomainTag]) -> Result<(), Collision> {
+    for (i, lhs) in tags.iter().enumerate() {
+        for rhs in &tags[i + 1..] {
+            if lhs.arity == rhs.arity && lhs.value == rhs.value && !(lhs.legacy && rhs.legacy) {
+                return Err(Collision::Equal(lhs.name, rhs.name));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Merkle Tree Shard Function Tag
+pub const MERKLE_TREE_SHARD_FUNCTION: DomainTag = DomainTag::legacy(
+    "merkle-tree-shard-function",
+    b"manta-v1.0.0/merkle-tree-shard-function",
+);
+
+/// Address Partition Function Tag
+pub const ADDRESS_PARTITION_FUNCTION: DomainTag = DomainTag::legacy(
+    "address-partition-function",
+    b"manta-v1.0.0/address-partition-function",
+);
+
+/// Address Ownership Proof Tag
+pub const ADDRESS_OWNERSHIP_PROOF: DomainTag =
+    DomainTag::legacy("address-ownership-proof", OWNERSHIP_PROOF_DOMAIN_TAG);
+
+/// Blake2s Key Derivation Function Tag
+pub const BLAKE2S_KDF: DomainTag = DomainTag::legacy(
+    "blake2s-kdf",
+    b"manta kdf instantiated with blake2s hash function",
+);
+
+/// Schnorr Signature Hash Tag
+pub const SCHNORR_HASH: DomainTag = tag!("1.0.0", "Schnorr-hash");
+
+/// Winternitz Signature Hash Tag
+pub const WINTERNITZ_HASH: DomainTag = tag!("1.0.0", "Winternitz-hash");
+
+/// Winternitz Key Derivation Tag
+pub const WINTERNITZ_KEY: DomainTag = tag!("1.0.0", "Winternitz-key");
+
+/// Winternitz Message Digest Tag
+pub const WINTERNITZ_MESSAGE: DomainTag = tag!("1.0.0", "Winternitz-message");
+
+/// Payment Channel State Tag
+pub const CHANNEL_STATE: DomainTag = tag!("1.0.0", "channel-state");
+
+/// Nullifier Set Commitment Tag
+pub const NULLIFIER_SET_COMMITMENT: DomainTag = tag!("1.0.0", "nullifier-set-commitment");
+
+/// Verifying Context Checksum Tag
+pub const VERIFYING_CONTEXT_CHECKSUM: DomainTag = tag!("1.0.0", "verifying-context-checksum");
+
+/// Fiat-Shamir Transcript Tag
+///
+/// Labels appended to a transcript are separated by this tag and by the domain of the
+/// transcript, so they do not need to be registered.
+pub const TRANSCRIPT: DomainTag = tag!("1.0.0", "transcript");
+
+/// Identity Address Commitment Tag
+pub const IDENTITY_ADDRESS_COMMITMENT: DomainTag = tag!("1.0.0", "identity-address-commitment");
+
+/// Identity Proof Digest Tag
+pub const IDENTITY_PROOF_DIGEST: DomainTag = tag!("1.0.0", "identity-proof-digest");
+
+/// Identity Log Tag
+pub const IDENTITY_LOG: DomainTag = tag!("1.0.0", "identity-log");
+
+/// Nullifier Watch List Tag
+pub const NULLIFIER_WATCH_LIST: DomainTag = tag!("1.0.0", "nullifier-watch-list");
+
+/// Ledger Anchor Tag
+pub const LEDGER_ANCHOR: DomainTag = tag!("1.0.0", "ledger-anchor");
+
+/// Transaction Identifier Tag
+pub const TRANSACTION_ID: DomainTag = tag!("1.0.0", "transaction-id");
+
+/// Byte Domain Tag Registry
+pub const BYTE_TAGS: &[DomainTag] = &[
+    MERKLE_TREE_SHARD_FUNCTION,
+    ADDRESS_PARTITION_FUNCTION,
+    ADDRESS_OWNERSHIP_PROOF,
+    BLAKE2S_KDF,
+    SCHNORR_HASH,
+    WINTERNITZ_HASH,
+    WINTERNITZ_KEY,
+    WINTERNITZ_MESSAGE,
+    CHANNEL_STATE,
+    NULLIFIER_SET_COMMITMENT,
+    VERIFYING_CONTEXT_CHECKSUM,
+    TRANSCRIPT,
+    IDENTITY_ADDRESS_COMMITMENT,
+    IDENTITY_PROOF_DIGEST,
+    IDENTITY_LOG,
+    NULLIFIER_WATCH_LIST,
+    LEDGER_ANCHOR,
+    TRANSACTION_ID,
+];
+
+/// UTXO Commitment Scheme Tag
+pub const UTXO_COMMITMENT_SCHEME: FieldDomainTag = FieldDomainTag {
+    name: "utxo-commitment-scheme",
+    arity: 5,
+    value: 0,
+    legacy: true,
+};
+
+/// Current UTXO Commitment Tag
+pub const CURRENT_UTXO_COMMITMENT: FieldDomainTag = FieldDomainTag {
+    name: "current-utxo-commitment",
+    arity: 5,
+    value: 1,
+    legacy: false,
+};
+
+/// Viewing Key Derivation Function Tag
+pub const VIEWING_KEY_DERIVATION_FUNCTION: FieldDomainTag = FieldDomainTag {
+    name: "viewing-key-derivation-function",
+    arity: 2,
+    value: 0,
+    legacy: true,
+};
+
+/// UTXO Accumulator Item Hash Tag
+pub const UTXO_ACCUMULATOR_ITEM_HASH: FieldDomainTag = FieldDomainTag {
+    name: "utxo-accumulator-item-hash",
+    arity: 4,
+    value: 0,
+    legacy: true,
+};
+
+/// Merkle Tree Inner Hash Tag
+pub const INNER_HASH: FieldDomainTag = FieldDomainTag {
+    name: "inner-hash",
+    arity: 2,
+    value: 0,
+    legacy: true,
+};
+
+/// Nullifier Commitment Scheme Tag
+pub const NULLIFIER_COMMITMENT_SCHEME: FieldDomainTag = FieldDomainTag {
+    name: "nullifier-commitment-scheme",
+    arity: 3,
+    value: 0,
+    legacy: true,
+};
+
+/// Asset-Scoped Nullifier Item Tag
+pub const ASSET_SCOPED_NULLIFIER_ITEM: FieldDomainTag = FieldDomainTag {
+    name: "asset-scoped-nullifier-item",
+    arity: 3,
+    value: 1,
+    legacy: false,
+};
+
+/// Compliance Tag Binding Tag
+pub const TAG_BINDING: FieldDomainTag = FieldDomainTag {
+    name: "tag-binding",
+    arity: 2,
+    value: 1,
+    legacy: false,
+};
+
+/// Field Domain Tag Registry
+///
+/// FIXME: The legacy tags should be replaced by distinct tags in the next parameters.
+pub const FIELD_TAGS: &[FieldDomainTag] = &[
+    UTXO_COMMITMENT_SCHEME,
+    CURRENT_UTXO_COMMITMENT,
+    VIEWING_KEY_DERIVATION_FUNCTION,
+    UTXO_ACCUMULATOR_ITEM_HASH,
+    INNER_HASH,
+    NULLIFIER_COMMITMENT_SCHEME,
+    ASSET_SCOPED_NULLIFIER_ITEM,
+    TAG_BINDING,
+];
@@ -26,12 +26,13 @@
 
 use alloc::{format, string::String, vec::Vec};
 use core::{
+    fmt::{self, Debug, Formatter},
     hash::{Hash, Hasher},
     marker::PhantomData,
 };
 use manta_accounting::key::{self, AccountIndex};
 use manta_crypto::rand::{CryptoRng, RngCore};
-use manta_util::{create_seal, seal, Array};
+use manta_util::{create_seal, fmt::Redacted, seal, zeroize::Zeroize, Array};
 
 #[cfg(feature = "serde")]
 use manta_util::serde::{Deserialize, Serialize, Serializer};
@@ -142,9 +143,11 @@ where
     C: CoinType,
 {
     /// Key Seed
+    #[derivative(Debug(format_with = "manta_util::fmt::redact"))]
     seed: SeedBytes,
 
     /// Mnemonic
+    #[derivative(Debug(format_with = "manta_util::fmt::redact"))]
     mnemonic: Mnemonic,
 
     /// Type Parameter Marker
@@ -200,6 +203,16 @@ where
     }
 }
 
+impl<C> Drop for KeySecret<C>
+where
+    C: CoinType,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.seed.zeroize();
+    }
+}
+
 /// Account type
 pub type Account<C = Manta> = key::Account<KeySecret<C>>;
 
@@ -230,7 +243,7 @@ where
     derive(Deserialize, Serialize),
     serde(crate = "manta_util::serde", deny_unknown_fields, try_from = "String")
 )]
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct Mnemonic(
     /// Underlying BIP39 Mnemonic
     #[cfg_attr(feature = "serde", serde(serialize_with = "Mnemonic::serialize"))]
@@ -282,6 +295,13 @@ impl AsRef<str> for Mnemonic {
     }
 }
 
+impl Debug for Mnemonic {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("Mnemonic").field(&Redacted).finish()
+    }
+}
+
 impl Eq for Mnemonic {}
 
 impl PartialEq for Mnemonic {
@@ -310,8 +330,10 @@ impl TryFrom<String> for Mnemonic {
 /// Testing framework
 #[cfg(test)]
 pub mod test {
-    use crate::key::{Calamari, CoinType, KeySecret, Manta, Testnet};
+    use crate::key::{Account, Calamari, CoinType, KeySecret, Manta, Testnet};
+    use alloc::format;
     use manta_crypto::rand::{ChaCha12Rng, SeedableRng};
+    use manta_util::fmt::REDACTED;
 
     /// Hardcoded testnet secret key value in bytes for the BIP32 0.3.0 version
     const SECRET_KEY_TESTNET: [u8; 32] = [
@@ -354,4 +376,27 @@ pub mod test {
         secret_key_generation::<Manta>(SECRET_KEY_MANTA);
         secret_key_generation::<Calamari>(SECRET_KEY_CALAMARI);
     }
+
+    /// Checks that the debug formatting of [`KeySecret`] and of the accounts built on top of it
+    /// never prints the mnemonic phrase.
+    #[test]
+    fn key_secret_debug_is_redacted() {
+        let mut rng = ChaCha12Rng::from_seed([0u8; 32]);
+        let key_secret = KeySecret::<Manta>::sample(&mut rng);
+        let account = Account::new(key_secret.clone(), Default::default());
+        for output in [
+            format!("{key_secret:?}"),
+            format!("{:?}", key_secret.expose_mnemonic()),
+            format!("{account:?}"),
+        ] {
+            assert!(
+                output.contains(REDACTED),
+                "Secrets should be replaced by the redacted placeholder."
+            );
+            assert!(
+                !output.contains(key_secret.expose_mnemonic().as_ref()),
+                "The mnemonic phrase should not be printed."
+            );
+        }
+    }
 }
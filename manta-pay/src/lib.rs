@@ -24,9 +24,10 @@
 extern crate alloc;
 
 pub mod crypto;
+pub mod domain;
 
-#[cfg(feature = "groth16")]
-#[cfg_attr(doc_cfg, doc(cfg(feature = "groth16")))]
+#[cfg(feature = "verify-only")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "verify-only")))]
 pub mod config;
 
 #[cfg(feature = "key")]
@@ -37,6 +38,10 @@ pub mod key;
 #[cfg_attr(doc_cfg, doc(cfg(all(feature = "parameters"))))]
 pub mod parameters;
 
+#[cfg(feature = "groth16")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "groth16")))]
+pub mod payment_request;
+
 #[cfg(feature = "groth16")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "groth16")))]
 pub mod signer;
@@ -45,6 +50,10 @@ pub mod signer;
 #[cfg_attr(doc_cfg, doc(cfg(all(feature = "groth16", feature = "simulation"))))]
 pub mod simulation;
 
+#[cfg(feature = "stream")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "stream")))]
+pub mod stream;
+
 #[cfg(any(test, feature = "test"))]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
 pub mod test;
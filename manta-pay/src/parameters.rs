@@ -22,16 +22,17 @@ use crate::config::{
     VerifyingContext,
 };
 use core::fmt::Debug;
+use manta_accounting::transfer::{canonical::TransferShape, dynamic::Shape};
 use manta_crypto::rand::{ChaCha20Rng, Rand, SeedableRng};
 use manta_parameters::Get;
 use manta_util::codec::Decode;
 
 #[cfg(feature = "download")]
-use manta_parameters::Download;
+use {crate::config::context::check_contexts, manta_parameters::Download};
 
 #[cfg(feature = "std")]
 use {
-    crate::config::ProvingContext,
+    crate::config::{ProvingContext, VerifyingContextProvider},
     manta_util::codec::IoReader,
     std::{fs::File, path::Path},
 };
@@ -79,11 +80,13 @@ pub fn generate_from_seed(
             to_private: to_private_proving_context,
             private_transfer: private_transfer_proving_context,
             to_public: to_public_proving_context,
+            private_swap: None,
         },
         MultiVerifyingContext {
             to_private: to_private_verifying_context,
             private_transfer: private_transfer_verifying_context,
             to_public: to_public_verifying_context,
+            private_swap: None,
         },
         parameters,
         utxo_accumulator_model,
@@ -119,13 +122,18 @@ pub fn load_parameters(
     ),
     ProofSystemError,
 > {
+    let proving_context = load_proving_context(directory);
+    let verifying_context = MultiVerifyingContext {
+        to_private: load_to_private_verifying_context(),
+        private_transfer: load_private_transfer_verifying_context(),
+        to_public: load_to_public_verifying_context(),
+        private_swap: None,
+    };
+    check_contexts(&proving_context, &verifying_context)
+        .expect("The downloaded proving and verifying contexts are not allowed to mismatch.");
     Ok((
-        load_proving_context(directory),
-        MultiVerifyingContext {
-            to_private: load_to_private_verifying_context(),
-            private_transfer: load_private_transfer_verifying_context(),
-            to_public: load_to_public_verifying_context(),
-        },
+        proving_context,
+        verifying_context,
         load_transfer_parameters(),
         load_utxo_accumulator_model(),
     ))
@@ -137,13 +145,16 @@ pub fn load_parameters(
 #[cfg_attr(doc_cfg, doc(cfg(feature = "download")))]
 #[inline]
 pub fn load_proving_context(directory: &Path) -> MultiProvingContext {
-    let to_private_path = directory.join("to-private.dat");
+    let to_private_path = directory.join(format!("{}.dat", TransferShape::ToPrivate.identifier()));
     manta_parameters::pay::proving::ToPrivate::download(&to_private_path)
         .expect("Unable to download ToPrivate proving context.");
-    let private_transfer_path = directory.join("private-transfer.dat");
+    let private_transfer_path = directory.join(format!(
+        "{}.dat",
+        TransferShape::PrivateTransfer.identifier()
+    ));
     manta_parameters::pay::proving::PrivateTransfer::download(&private_transfer_path)
         .expect("Unable to download PrivateTransfer proving context.");
-    let to_public_path = directory.join("to-public.dat");
+    let to_public_path = directory.join(format!("{}.dat", TransferShape::ToPublic.identifier()));
     manta_parameters::pay::proving::ToPublic::download(&to_public_path)
         .expect("Unable to download ToPublic proving context.");
     decode_proving_context(&to_private_path, &private_transfer_path, &to_public_path)
@@ -159,13 +170,16 @@ pub fn load_proving_context(directory: &Path) -> MultiProvingContext {
 #[cfg_attr(doc_cfg, doc(cfg(feature = "download")))]
 #[inline]
 pub fn try_load_proving_context(directory: &Path) -> MultiProvingContext {
-    let to_private_path = directory.join("to-private.dat");
+    let to_private_path = directory.join(format!("{}.dat", TransferShape::ToPrivate.identifier()));
     manta_parameters::pay::proving::ToPrivate::download_if_invalid(&to_private_path)
         .expect("Unable to download ToPrivate proving context.");
-    let private_transfer_path = directory.join("private-transfer.dat");
+    let private_transfer_path = directory.join(format!(
+        "{}.dat",
+        TransferShape::PrivateTransfer.identifier()
+    ));
     manta_parameters::pay::proving::PrivateTransfer::download_if_invalid(&private_transfer_path)
         .expect("Unable to download PrivateTransfer proving context.");
-    let to_public_path = directory.join("to-public.dat");
+    let to_public_path = directory.join(format!("{}.dat", TransferShape::ToPublic.identifier()));
     manta_parameters::pay::proving::ToPublic::download_if_invalid(&to_public_path)
         .expect("Unable to download ToPublic proving context.");
     decode_proving_context(&to_private_path, &private_transfer_path, &to_public_path)
@@ -195,6 +209,7 @@ pub fn decode_proving_context(
             File::open(to_public_path).expect("Unable to open ToPublic proving context file."),
         ))
         .expect("Unable to decode ToPublic proving context."),
+        private_swap: None,
     }
 }
 
@@ -225,6 +240,33 @@ pub fn load_to_public_verifying_context() -> VerifyingContext {
     .expect("Unable to decode ToPublic verifying context.")
 }
 
+/// Loads the verifying context of the canonical transfer with the given `shape` from
+/// [`manta_parameters`], returning `None` if `shape` is not canonical or if [`manta_parameters`]
+/// does not publish its context, which is the case for the optional
+/// [`PrivateSwap`](TransferShape::PrivateSwap) shape.
+#[inline]
+pub fn load_verifying_context(shape: &Shape) -> Option<VerifyingContext> {
+    match shape.canonical()? {
+        TransferShape::ToPrivate => Some(load_to_private_verifying_context()),
+        TransferShape::PrivateTransfer => Some(load_private_transfer_verifying_context()),
+        TransferShape::ToPublic => Some(load_to_public_verifying_context()),
+        TransferShape::PrivateSwap => None,
+    }
+}
+
+/// Builds a [`VerifyingContextProvider`] which loads the verifying contexts of the canonical
+/// transfers from [`manta_parameters`] on demand. The [`PrivateTransfer`] context is loaded and
+/// pinned right away, since it verifies most of the posts a ledger receives.
+#[cfg(feature = "std")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+#[inline]
+pub fn verifying_context_provider(
+) -> VerifyingContextProvider<fn(&Shape) -> Option<VerifyingContext>> {
+    let provider = VerifyingContextProvider::new(load_verifying_context as _);
+    provider.pin(TransferShape::PrivateTransfer.into());
+    provider
+}
+
 /// Load a [`Get`] object into an object of type `T`.
 #[inline]
 pub fn load_get_object<G, T>() -> T
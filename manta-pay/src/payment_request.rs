@@ -0,0 +1,196 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Payment Requests
+//!
+//! A [`PaymentRequest`] asks a wallet to send some [`Asset`] to an [`Address`]. Wallets exchange
+//! them as QR codes holding the compact binary payload built by
+//! [`to_qr_payload`](PaymentRequest::to_qr_payload), which is laid out as follows:
+//!
+//! | Bytes     | Content                                                          |
+//! |-----------|------------------------------------------------------------------|
+//! | `1`       | [`QR_PAYLOAD_VERSION`]                                           |
+//! | `32`      | Compressed receiving key of the [`Address`]                      |
+//! | `32`      | [`AssetId`]                                                      |
+//! | `1..=19`  | [`AssetValue`] as an unsigned LEB128 integer in its shortest form |
+//!
+//! Payloads are at most [`MAX_QR_PAYLOAD_LENGTH`] bytes long, so every payment request fits in a
+//! version 5 QR code in byte mode with error correction level `M`, which is still read reliably
+//! from phone screens and printed receipts.
+
+use crate::config::{Address, Asset, AssetId, AssetValue};
+use alloc::vec::Vec;
+use manta_util::codec::{Decode, Encode};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// QR Payload Version
+pub const QR_PAYLOAD_VERSION: u8 = 1;
+
+/// Length of the Encoded Receiving Key of an [`Address`]
+const ADDRESS_LENGTH: usize = 32;
+
+/// Length of an Encoded [`AssetId`]
+const ASSET_ID_LENGTH: usize = 32;
+
+/// Maximum Length of an Encoded [`AssetValue`]
+const MAX_ASSET_VALUE_LENGTH: usize = AssetValue::BITS.div_ceil(7) as usize;
+
+/// Maximum Length of a QR Payload
+///
+/// This is the byte mode capacity of a version 5 QR code with error correction level `M`.
+pub const MAX_QR_PAYLOAD_LENGTH: usize =
+    1 + ADDRESS_LENGTH + ASSET_ID_LENGTH + MAX_ASSET_VALUE_LENGTH;
+
+/// QR Payload Error
+///
+/// This `enum` is the error state of the [`from_qr_payload`](PaymentRequest::from_qr_payload)
+/// method of [`PaymentRequest`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum QrPayloadError {
+    /// Unsupported Version
+    ///
+    /// The payload is empty or starts with a version other than [`QR_PAYLOAD_VERSION`].
+    UnsupportedVersion(Option<u8>),
+
+    /// Invalid Address
+    InvalidAddress,
+
+    /// Invalid Asset Id
+    InvalidAssetId,
+
+    /// Invalid Asset Value
+    ///
+    /// The asset value is truncated, overflows [`AssetValue`], or is not in its shortest form.
+    InvalidAssetValue,
+
+    /// Trailing Bytes
+    ///
+    /// The payload has extra bytes after the asset value.
+    TrailingBytes,
+}
+
+/// Payment Request
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PaymentRequest {
+    /// Receiving Address
+    pub address: Address,
+
+    /// Requested Asset
+    pub asset: Asset,
+}
+
+impl PaymentRequest {
+    /// Builds a new [`PaymentRequest`] for `asset` to be sent to `address`.
+    #[inline]
+    pub fn new(address: Address, asset: Asset) -> Self {
+        Self { address, asset }
+    }
+
+    /// Encodes `self` into a QR payload.
+    #[inline]
+    pub fn to_qr_payload(&self) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(MAX_QR_PAYLOAD_LENGTH);
+        payload.push(QR_PAYLOAD_VERSION);
+        self.address
+            .receiving_key
+            .encode(&mut payload)
+            .expect("Encoding is not allowed to fail.");
+        self.asset
+            .id
+            .encode(&mut payload)
+            .expect("Encoding is not allowed to fail.");
+        encode_asset_value(self.asset.value, &mut payload);
+        payload
+    }
+
+    /// Decodes a [`PaymentRequest`] from the QR `payload`.
+    #[inline]
+    pub fn from_qr_payload(payload: &[u8]) -> Result<Self, QrPayloadError> {
+        let (version, payload) = payload
+            .split_first()
+            .ok_or(QrPayloadError::UnsupportedVersion(None))?;
+        if *version != QR_PAYLOAD_VERSION {
+            return Err(QrPayloadError::UnsupportedVersion(Some(*version)));
+        }
+        if payload.len() < ADDRESS_LENGTH {
+            return Err(QrPayloadError::InvalidAddress);
+        }
+        let (receiving_key, payload) = payload.split_at(ADDRESS_LENGTH);
+        let receiving_key =
+            Decode::decode(receiving_key).map_err(|_| QrPayloadError::InvalidAddress)?;
+        if payload.len() < ASSET_ID_LENGTH {
+            return Err(QrPayloadError::InvalidAssetId);
+        }
+        let (id, payload) = payload.split_at(ASSET_ID_LENGTH);
+        let id = AssetId::decode(id).map_err(|_| QrPayloadError::InvalidAssetId)?;
+        let (value, payload) = decode_asset_value(payload)?;
+        if !payload.is_empty() {
+            return Err(QrPayloadError::TrailingBytes);
+        }
+        Ok(Self::new(
+            Address::new(receiving_key),
+            Asset::new(id, value),
+        ))
+    }
+}
+
+/// Appends the shortest unsigned LEB128 encoding of `value` to `payload`.
+#[inline]
+fn encode_asset_value(mut value: AssetValue, payload: &mut Vec<u8>) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            payload.push(byte);
+            return;
+        }
+        payload.push(byte | 0x80);
+    }
+}
+
+/// Decodes an [`AssetValue`] from the front of `payload`, returning it with the rest of the
+/// payload.
+#[inline]
+fn decode_asset_value(payload: &[u8]) -> Result<(AssetValue, &[u8]), QrPayloadError> {
+    let mut value: AssetValue = 0;
+    for (i, byte) in payload.iter().enumerate().take(MAX_ASSET_VALUE_LENGTH) {
+        let bits = AssetValue::from(byte & 0x7f);
+        let shift = 7 * i as u32;
+        if bits.checked_shl(shift).map(|shifted| shifted >> shift) != Some(bits) {
+            return Err(QrPayloadError::InvalidAssetValue);
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            if i != 0 && *byte == 0 {
+                return Err(QrPayloadError::InvalidAssetValue);
+            }
+            return Ok((value, &payload[i + 1..]));
+        }
+    }
+    Err(QrPayloadError::InvalidAssetValue)
+}
@@ -19,21 +19,31 @@
 use crate::{
     config::{
         utxo::{self, MerkleTreeConfiguration},
-        AccountId, Address, Config, IdentifiedAsset, IdentityProof, Parameters,
-        UtxoAccumulatorModel, VerifyingContext,
+        AccountId, Address, Config, IdentifiedAsset, IdentityProof, Note, Nullifier, Parameters,
+        UtxoAccumulatorModel, UtxoAccumulatorOutput, VerifyingContext,
     },
+    domain,
     key::{CoinType, KeySecret, Testnet},
     signer::{AssetMetadata, Checkpoint},
 };
-use alloc::{collections::BTreeMap, vec, vec::Vec};
+use alloc::{
+    collections::{BTreeMap, VecDeque},
+    vec,
+    vec::Vec,
+};
+use blake2::{Blake2s256, Digest};
 use core::{cmp, mem};
 use manta_accounting::{
     asset::BTreeAssetMap,
     key::{AccountCollection, AccountIndex, DeriveAddresses},
-    transfer::{utxo::protocol, Identifier, IdentityVerificationError, SpendingKey},
+    transfer::{
+        utxo::protocol::{self, AddressPartitionFunction},
+        AnchorVerifier, Identifier, IdentityVerificationError, SpendingKey,
+    },
     wallet::{
         self,
-        signer::{self, SyncData},
+        ledger::LeafIndex,
+        signer::{self, watch::WatchListSalt, SyncData},
     },
 };
 use manta_crypto::{
@@ -44,8 +54,17 @@ use manta_crypto::{
         ff::{Fp256, PrimeField, Zero},
     },
     merkle_tree::{self, forest::Configuration},
-    rand::ChaCha20Rng,
+    rand::{ChaCha20Rng, OsRngPolicy, PolicyRng},
 };
+use manta_util::{
+    codec::Encode,
+    collections::filter::{BloomFilter, CuckooFilter, Filter},
+    vec::CapacityError,
+    zeroize::Zeroizing,
+};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
 
 impl<C> AccountCollection for KeySecret<C>
 where
@@ -56,7 +75,7 @@ where
     #[inline]
     fn spending_key(&self, index: &AccountIndex) -> Self::SpendingKey {
         Fp(Fp256::<FrParameters>::from_le_bytes_mod_order(
-            &self.xpr_secret_key(index).to_bytes(),
+            &*Zeroizing::new(self.xpr_secret_key(index).to_bytes()),
         ))
     }
 }
@@ -70,7 +89,9 @@ where
 
     #[inline]
     fn address(&self, parameters: &Self::Parameters, index: AccountIndex) -> Self::Address {
-        parameters.address_from_spending_key(&AccountCollection::spending_key(&self, &index))
+        parameters.address_from_spending_key(&Zeroizing::new(AccountCollection::spending_key(
+            &self, &index,
+        )))
     }
 }
 
@@ -90,7 +111,218 @@ impl wallet::signer::Configuration for Config {
     type UtxoAccumulator = UtxoAccumulator;
     type AssetMap = BTreeAssetMap<Identifier<Self>, Self::AssetId, Self::AssetValue>;
     type AssetMetadata = AssetMetadata;
-    type Rng = ChaCha20Rng;
+    type LedgerSnapshot = LedgerSnapshot;
+    type WatchListHash = WatchListHash;
+    type NoteDetection = NoteDetection;
+    type Rng = PolicyRng<ChaCha20Rng, OsRngPolicy>;
+}
+
+/// Ledger Snapshot
+///
+/// Lightweight view of the ledger made of the recent roots of the trees in the UTXO accumulator
+/// forest, a [`BloomFilter`] of the spent nullifiers, and a [`CuckooFilter`] of the pending
+/// nullifiers, which supports removing the pending nullifiers once they reach the ledger.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct LedgerSnapshot {
+    /// Recent UTXO Accumulator Outputs
+    ///
+    /// The outputs are stored from the oldest to the newest.
+    utxo_accumulator_outputs: VecDeque<UtxoAccumulatorOutput>,
+
+    /// Spent Nullifier Filter
+    nullifiers: BloomFilter,
+
+    /// Pending Nullifier Filter
+    pending_nullifiers: CuckooFilter,
+}
+
+impl LedgerSnapshot {
+    /// Number of Recent UTXO Accumulator Outputs
+    ///
+    /// Every tree in the forest has its own root, so we keep the last few roots of each tree.
+    pub const OUTPUT_WINDOW: usize = 4 * MerkleTreeConfiguration::FOREST_WIDTH;
+
+    /// Default Capacity of the Spent Nullifier Filter
+    pub const NULLIFIER_CAPACITY: usize = 1 << 16;
+
+    /// Default False Positive Rate of the Spent Nullifier Filter
+    pub const NULLIFIER_FALSE_POSITIVE_RATE: f64 = 1e-3;
+
+    /// Default Capacity of the Pending Nullifier Filter
+    pub const PENDING_NULLIFIER_CAPACITY: usize = 1 << 10;
+
+    /// Default False Positive Rate of the Pending Nullifier Filter
+    pub const PENDING_NULLIFIER_FALSE_POSITIVE_RATE: f64 = 1e-4;
+
+    /// Builds a new empty [`LedgerSnapshot`] whose spent nullifier filter holds
+    /// `nullifier_capacity`-many nullifiers with a false positive rate of at most
+    /// `nullifier_false_positive_rate`, and whose pending nullifier filter holds
+    /// `pending_nullifier_capacity`-many nullifiers with a false positive rate of at most
+    /// `pending_nullifier_false_positive_rate`.
+    #[inline]
+    pub fn new(
+        nullifier_capacity: usize,
+        nullifier_false_positive_rate: f64,
+        pending_nullifier_capacity: usize,
+        pending_nullifier_false_positive_rate: f64,
+    ) -> Self {
+        Self {
+            utxo_accumulator_outputs: VecDeque::with_capacity(Self::OUTPUT_WINDOW),
+            nullifiers: BloomFilter::with_false_positive_rate(
+                nullifier_capacity,
+                nullifier_false_positive_rate,
+            ),
+            pending_nullifiers: CuckooFilter::with_false_positive_rate(
+                pending_nullifier_capacity,
+                pending_nullifier_false_positive_rate,
+            ),
+        }
+    }
+
+    /// Returns the spent nullifier filter of `self`, whose [`Filter::saturation`] and
+    /// [`Filter::false_positive_rate`] tell when the snapshot should be rebuilt with a larger
+    /// capacity.
+    #[inline]
+    pub fn nullifier_filter(&self) -> &BloomFilter {
+        &self.nullifiers
+    }
+
+    /// Returns the pending nullifier filter of `self`.
+    #[inline]
+    pub fn pending_nullifier_filter(&self) -> &CuckooFilter {
+        &self.pending_nullifiers
+    }
+}
+
+impl Default for LedgerSnapshot {
+    #[inline]
+    fn default() -> Self {
+        Self::new(
+            Self::NULLIFIER_CAPACITY,
+            Self::NULLIFIER_FALSE_POSITIVE_RATE,
+            Self::PENDING_NULLIFIER_CAPACITY,
+            Self::PENDING_NULLIFIER_FALSE_POSITIVE_RATE,
+        )
+    }
+}
+
+impl signer::LedgerSnapshot<Config> for LedgerSnapshot {
+    type UtxoAccumulator = UtxoAccumulator;
+
+    #[inline]
+    fn update_from_utxo_accumulator(&mut self, utxo_accumulator: &Self::UtxoAccumulator) {
+        for tree in utxo_accumulator.forest.as_ref() {
+            let root = tree.root();
+            if !self.utxo_accumulator_outputs.contains(root) {
+                if self.utxo_accumulator_outputs.len() == Self::OUTPUT_WINDOW {
+                    self.utxo_accumulator_outputs.pop_front();
+                }
+                self.utxo_accumulator_outputs.push_back(*root);
+            }
+        }
+    }
+
+    /// Inserts `nullifier` into the spent nullifier filter and removes it from the pending
+    /// nullifier filter.
+    ///
+    /// # Limitations
+    ///
+    /// A nullifier which was never pending is still removed from the pending nullifier filter if
+    /// its fingerprint collides with a pending one, in which case the pending spend is forgotten.
+    /// This only weakens the pending spend check, it never rejects a valid transaction.
+    #[inline]
+    fn insert_nullifier(&mut self, nullifier: &Nullifier) {
+        self.nullifiers.insert(nullifier);
+        if self.pending_nullifiers.may_contain(nullifier) {
+            self.pending_nullifiers.remove(nullifier);
+        }
+    }
+
+    /// Inserts `nullifier` into the pending nullifier filter. The pending spend is not tracked if
+    /// the filter is saturated.
+    #[inline]
+    fn insert_pending_nullifier(&mut self, nullifier: &Nullifier) {
+        self.pending_nullifiers.insert(nullifier);
+    }
+
+    #[inline]
+    fn clear_pending_nullifiers(&mut self) {
+        self.pending_nullifiers.clear();
+    }
+
+    #[inline]
+    fn remove_pending_nullifier(&mut self, nullifier: &Nullifier) {
+        self.pending_nullifiers.remove(nullifier);
+    }
+
+    /// Returns `true` if `output` is one of the recent UTXO accumulator outputs of `self`, or if
+    /// it is the default output used by the zero-valued senders which pad a transaction.
+    #[inline]
+    fn has_utxo_accumulator_output(&self, output: &UtxoAccumulatorOutput) -> bool {
+        output == &Default::default() || self.utxo_accumulator_outputs.contains(output)
+    }
+
+    #[inline]
+    fn may_contain_nullifier(&self, nullifier: &Nullifier) -> bool {
+        self.nullifiers.may_contain(nullifier)
+    }
+
+    #[inline]
+    fn may_contain_pending_nullifier(&self, nullifier: &Nullifier) -> bool {
+        self.pending_nullifiers.may_contain(nullifier)
+    }
+}
+
+/// Nullifier Watch-List Hash Function
+///
+/// Hashes the salt and the nullifier commitment with Blake2s. The outgoing note which is posted
+/// together with the nullifier commitment is sampled anew for every spend, so it is left out of
+/// the digest.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct WatchListHash;
+
+impl signer::watch::WatchListHash<Config> for WatchListHash {
+    type Digest = [u8; 32];
+
+    #[inline]
+    fn hash(salt: &WatchListSalt, nullifier: &Nullifier) -> Self::Digest {
+        let mut hasher = Blake2s256::new();
+        Digest::update(&mut hasher, domain::NULLIFIER_WATCH_LIST.bytes);
+        Digest::update(&mut hasher, salt.0);
+        Digest::update(&mut hasher, nullifier.nullifier.commitment.to_vec());
+        hasher.finalize().into()
+    }
+}
+
+/// Note Detection
+///
+/// Detects notes by the one-byte address partition which every note carries in the clear, so
+/// blinded detection keys are drawn from 256 partitions.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct NoteDetection;
+
+impl signer::index::NoteDetection<Config> for NoteDetection {
+    type Partition = u8;
+
+    #[inline]
+    fn partitions() -> Vec<Self::Partition> {
+        (0..=u8::MAX).collect()
+    }
+
+    #[inline]
+    fn address_partition(parameters: &Parameters, address: &Address) -> Self::Partition {
+        parameters.address_partition_function.partition(address)
+    }
+
+    #[inline]
+    fn note_partition(note: &Note) -> Self::Partition {
+        note.address_partition
+    }
 }
 
 impl signer::Checkpoint<Config> for Checkpoint {
@@ -99,7 +331,7 @@ impl signer::Checkpoint<Config> for Checkpoint {
 
     #[inline]
     fn update_from_nullifiers(&mut self, count: usize) {
-        self.sender_index += count;
+        self.sender_index = self.sender_index.advance(count);
     }
 
     #[inline]
@@ -108,7 +340,7 @@ impl signer::Checkpoint<Config> for Checkpoint {
             .receiver_index
             .into_iter()
             .zip(utxo_accumulator.forest.as_ref())
-            .map(move |(i, t)| cmp::max(i, t.len()))
+            .map(move |(i, t)| cmp::max(i, LeafIndex::new(t.len())))
             .collect();
     }
 
@@ -124,7 +356,7 @@ impl signer::Checkpoint<Config> for Checkpoint {
             .receiver_index
             .into_iter()
             .zip(utxo_count.into_iter())
-            .map(|(a, b)| a + b)
+            .map(|(index, count)| index.advance(count))
             .collect();
     }
 
@@ -148,18 +380,18 @@ impl signer::Checkpoint<Config> for Checkpoint {
         for receiver in &data.utxo_note_data {
             let key =
                 MerkleTreeConfiguration::tree_index(&parameters.item_hash(&receiver.0, &mut ()));
-            updated_origin.receiver_index[key as usize] += 1;
+            let index = &mut updated_origin.receiver_index[key as usize];
+            *index = index.advance(1);
         }
-        updated_origin.sender_index += data.nullifier_data.len();
+        updated_origin.sender_index = updated_origin
+            .sender_index
+            .advance(data.nullifier_data.len());
         if signer_checkpoint > &updated_origin {
             *data = Default::default();
             return true;
         }
         let mut has_pruned = false;
-        match signer_checkpoint
-            .sender_index
-            .checked_sub(origin.sender_index)
-        {
+        match signer_checkpoint.sender_index.since(origin.sender_index) {
             Some(diff) => {
                 drop(data.nullifier_data.drain(0..diff));
                 if diff > 0 {
@@ -187,10 +419,13 @@ impl signer::Checkpoint<Config> for Checkpoint {
             .zip(signer_checkpoint.receiver_index)
             .enumerate()
         {
-            match index.checked_sub(origin_index) {
+            match index.since(origin_index) {
                 Some(diff) => {
                     if let Some(entries) = data_map.remove(&(i as u8)) {
-                        data.utxo_note_data.extend(entries.into_iter().skip(diff));
+                        data.utxo_note_data
+                            .try_extend(entries.into_iter().skip(diff))
+                            .map_err(CapacityError::forget)
+                            .expect("Pruning is not allowed to add new entries.");
                         if diff > 0 {
                             has_pruned = true;
                         }
@@ -244,3 +479,34 @@ pub fn identity_verification(
         public_account,
     )
 }
+
+/// Runs the anchored identity verification method on `identity_proof` with [`Config`] and
+/// [`UtxoAccumulator`], checking that `virtual_asset` is opaque and not zero for extra security.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub fn anchored_identity_verification<V>(
+    identity_proof: &IdentityProof,
+    parameters: &Parameters,
+    verifying_context: &VerifyingContext,
+    utxo_accumulator_model: &UtxoAccumulatorModel,
+    virtual_asset: IdentifiedAsset,
+    address: Address,
+    public_account: AccountId,
+    anchor_verifier: &V,
+) -> Result<(), IdentityVerificationError>
+where
+    V: AnchorVerifier<Config>,
+{
+    if virtual_asset.identifier.is_transparent || virtual_asset.asset.id.0.is_zero() {
+        return Err(IdentityVerificationError::InvalidVirtualAsset);
+    }
+    identity_proof.anchored_identity_verification::<UtxoAccumulator, _>(
+        parameters,
+        verifying_context,
+        utxo_accumulator_model,
+        virtual_asset,
+        address,
+        public_account,
+        anchor_verifier,
+    )
+}
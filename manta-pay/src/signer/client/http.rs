@@ -20,9 +20,15 @@ use crate::{
     config::{utxo::Address, Config, Parameters},
     signer::{
         client::network::{Message, Network},
+        rpc::{
+            AddressCommand, Command, IdentityCommand, InitialSyncCommand, ReviewCommand,
+            SbtSyncCommand, SignBatchCommand, SignCommand, SignWithTransactionDataCommand,
+            SyncCommand, TransactionDataCommand, TransferParametersCommand,
+        },
         AssetMetadata, Checkpoint, GetRequest, IdentityRequest, IdentityResponse,
-        InitialSyncRequest, SignError, SignRequest, SignResponse, SignWithTransactionDataResult,
-        SyncError, SyncRequest, SyncResponse, TransactionDataRequest, TransactionDataResponse,
+        InitialSyncRequest, ReviewResult, SignBatchRequest, SignBatchResult, SignError,
+        SignRequest, SignResponse, SignWithTransactionDataResult, SyncError, SyncRequest,
+        SyncResponse, TransactionDataRequest, TransactionDataResponse,
     },
 };
 use alloc::boxed::Box;
@@ -33,6 +39,9 @@ use manta_util::{
     serde::{de::DeserializeOwned, Serialize},
 };
 
+#[cfg(feature = "socks")]
+use manta_util::http::proxy::ProxyConfig;
+
 #[doc(inline)]
 pub use reqwest::Error;
 
@@ -61,6 +70,21 @@ impl Client {
         })
     }
 
+    /// Builds a new HTTP [`Client`] that connects to `server_url` through the SOCKS5 proxy
+    /// described by `proxy`.
+    #[cfg(feature = "socks")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "socks")))]
+    #[inline]
+    pub fn with_proxy<U>(server_url: U, proxy: &ProxyConfig) -> Result<Self, Error>
+    where
+        U: IntoUrl,
+    {
+        Ok(Self {
+            base: KnownUrlClient::with_proxy(server_url, proxy)?,
+            network: None,
+        })
+    }
+
     /// Sets the network that will be used to wrap HTTP requests.
     #[inline]
     pub fn set_network(&mut self, network: Option<Network>) {
@@ -87,6 +111,17 @@ impl Client {
     {
         self.base.post(command, &self.wrap_request(request)).await
     }
+
+    /// Sends the `request` of the [`Command`] `T` and returns its response.
+    #[inline]
+    pub async fn call<T>(&self, request: T::Request) -> reqwest::Result<T::Response>
+    where
+        T: Command,
+        T::Request: Serialize,
+        T::Response: DeserializeOwned,
+    {
+        self.post_request(T::NAME, request).await
+    }
 }
 
 impl signer::Connection<Config> for Client {
@@ -99,7 +134,7 @@ impl signer::Connection<Config> for Client {
         &mut self,
         request: SyncRequest,
     ) -> LocalBoxFutureResult<Result<SyncResponse, SyncError>, Self::Error> {
-        Box::pin(self.post_request("sync", request))
+        Box::pin(self.call::<SyncCommand>(request))
     }
 
     #[inline]
@@ -107,7 +142,7 @@ impl signer::Connection<Config> for Client {
         &mut self,
         request: SyncRequest,
     ) -> LocalBoxFutureResult<Result<SyncResponse, SyncError>, Self::Error> {
-        Box::pin(self.post_request("sbt_sync", request))
+        Box::pin(self.call::<SbtSyncCommand>(request))
     }
 
     #[inline]
@@ -115,7 +150,7 @@ impl signer::Connection<Config> for Client {
         &mut self,
         request: InitialSyncRequest,
     ) -> LocalBoxFutureResult<Result<SyncResponse, SyncError>, Self::Error> {
-        Box::pin(self.post_request("initial_sync", request))
+        Box::pin(self.call::<InitialSyncCommand>(request))
     }
 
     #[inline]
@@ -123,12 +158,28 @@ impl signer::Connection<Config> for Client {
         &mut self,
         request: SignRequest,
     ) -> LocalBoxFutureResult<Result<SignResponse, SignError>, Self::Error> {
-        Box::pin(self.post_request("sign", request))
+        Box::pin(self.call::<SignCommand>(request))
+    }
+
+    #[inline]
+    fn sign_batch(
+        &mut self,
+        request: SignBatchRequest,
+    ) -> LocalBoxFutureResult<'_, SignBatchResult, Self::Error> {
+        Box::pin(self.call::<SignBatchCommand>(request))
+    }
+
+    #[inline]
+    fn review(
+        &mut self,
+        request: SignRequest,
+    ) -> LocalBoxFutureResult<'_, ReviewResult, Self::Error> {
+        Box::pin(self.call::<ReviewCommand>(request))
     }
 
     #[inline]
     fn address(&mut self) -> LocalBoxFutureResult<Option<Address>, Self::Error> {
-        Box::pin(self.post_request("address", GetRequest::Get))
+        Box::pin(self.call::<AddressCommand>(GetRequest::Get))
     }
 
     #[inline]
@@ -136,7 +187,7 @@ impl signer::Connection<Config> for Client {
         &mut self,
         request: TransactionDataRequest,
     ) -> LocalBoxFutureResult<TransactionDataResponse, Self::Error> {
-        Box::pin(self.post_request("transaction_data", request))
+        Box::pin(self.call::<TransactionDataCommand>(request))
     }
 
     #[inline]
@@ -144,7 +195,7 @@ impl signer::Connection<Config> for Client {
         &mut self,
         request: IdentityRequest,
     ) -> LocalBoxFutureResult<IdentityResponse, Self::Error> {
-        Box::pin(self.post_request("identity", request))
+        Box::pin(self.call::<IdentityCommand>(request))
     }
 
     #[inline]
@@ -152,11 +203,11 @@ impl signer::Connection<Config> for Client {
         &mut self,
         request: SignRequest,
     ) -> LocalBoxFutureResult<SignWithTransactionDataResult, Self::Error> {
-        Box::pin(self.post_request("sign_with_transaction_data", request))
+        Box::pin(self.call::<SignWithTransactionDataCommand>(request))
     }
 
     #[inline]
     fn transfer_parameters(&mut self) -> LocalBoxFutureResult<Parameters, Self::Error> {
-        Box::pin(self.post_request("transfer_parameters", GetRequest::Get))
+        Box::pin(self.call::<TransferParametersCommand>(GetRequest::Get))
     }
 }
@@ -21,9 +21,15 @@
 use crate::{
     config::{utxo::Address, Config, Parameters},
     signer::{
+        rpc::{
+            AddressCommand, Command, IdentityCommand, InitialSyncCommand, ReviewCommand,
+            SbtSyncCommand, SignBatchCommand, SignCommand, SignWithTransactionDataCommand,
+            SyncCommand, TransactionDataCommand, TransferParametersCommand,
+        },
         AssetMetadata, Checkpoint, GetRequest, IdentityRequest, IdentityResponse,
-        InitialSyncRequest, SignError, SignRequest, SignResponse, SignWithTransactionDataResult,
-        SyncError, SyncRequest, SyncResponse, TransactionDataRequest, TransactionDataResponse,
+        InitialSyncRequest, ReviewResult, SignBatchRequest, SignBatchResult, SignError,
+        SignRequest, SignResponse, SignWithTransactionDataResult, SyncError, SyncRequest,
+        SyncResponse, TransactionDataRequest, TransactionDataResponse,
     },
 };
 use alloc::boxed::Box;
@@ -42,6 +48,14 @@ use tokio_tungstenite::{
     MaybeTlsStream, WebSocketStream,
 };
 
+#[cfg(feature = "socks")]
+use {
+    manta_util::http::proxy::ProxyConfig,
+    std::io,
+    tokio_socks::tcp::Socks5Stream,
+    tokio_tungstenite::{client_async_tls, tungstenite::error::UrlError},
+};
+
 /// Web Socket Error
 pub type WebSocketError = tungstenite::error::Error;
 
@@ -105,6 +119,44 @@ impl Client {
         Ok(Self(connect_async(url).await?.0))
     }
 
+    /// Builds a new [`Client`] from `url` which connects through the SOCKS5 proxy described by
+    /// `proxy`. The host name of `url` is resolved by the proxy, so it can be an onion address.
+    #[cfg(feature = "socks")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "socks")))]
+    #[inline]
+    pub async fn with_proxy<U>(url: U, proxy: &ProxyConfig) -> Result<Self, WebSocketError>
+    where
+        U: IntoClientRequest + Unpin,
+    {
+        let request = url.into_client_request()?;
+        let uri = request.uri();
+        let host = uri
+            .host()
+            .ok_or(WebSocketError::Url(UrlError::NoHostName))?
+            .to_owned();
+        let port = match uri.port_u16() {
+            Some(port) => port,
+            _ if uri.scheme_str() == Some("wss") => 443,
+            _ => 80,
+        };
+        let stream = match proxy.credentials() {
+            Some((username, password)) => {
+                Socks5Stream::connect_with_password(
+                    proxy.address.as_str(),
+                    (host.as_str(), port),
+                    &username,
+                    &password,
+                )
+                .await
+            }
+            _ => Socks5Stream::connect(proxy.address.as_str(), (host.as_str(), port)).await,
+        }
+        .map_err(|err| WebSocketError::Io(io::Error::other(err)))?;
+        Ok(Self(
+            client_async_tls(request, stream.into_inner()).await?.0,
+        ))
+    }
+
     /// Sends a `request` for the given `command` along the channel and waits for the response.
     #[inline]
     async fn send<S, D>(&mut self, command: &'static str, request: S) -> Result<D, Error>
@@ -125,6 +177,17 @@ impl Client {
             _ => Err(Error::EndOfStream),
         }
     }
+
+    /// Sends the `request` of the [`Command`] `T` along the channel and waits for its response.
+    #[inline]
+    async fn call<T>(&mut self, request: T::Request) -> Result<T::Response, Error>
+    where
+        T: Command,
+        T::Request: Serialize,
+        T::Response: DeserializeOwned,
+    {
+        self.send(T::NAME, request).await
+    }
 }
 
 impl signer::Connection<Config> for Client {
@@ -137,7 +200,7 @@ impl signer::Connection<Config> for Client {
         &mut self,
         request: SyncRequest,
     ) -> LocalBoxFutureResult<Result<SyncResponse, SyncError>, Self::Error> {
-        Box::pin(self.send("sync", request))
+        Box::pin(self.call::<SyncCommand>(request))
     }
 
     #[inline]
@@ -145,7 +208,7 @@ impl signer::Connection<Config> for Client {
         &mut self,
         request: SyncRequest,
     ) -> LocalBoxFutureResult<Result<SyncResponse, SyncError>, Self::Error> {
-        Box::pin(self.send("sbt_sync", request))
+        Box::pin(self.call::<SbtSyncCommand>(request))
     }
 
     #[inline]
@@ -153,7 +216,7 @@ impl signer::Connection<Config> for Client {
         &mut self,
         request: InitialSyncRequest,
     ) -> LocalBoxFutureResult<Result<SyncResponse, SyncError>, Self::Error> {
-        Box::pin(self.send("initial_sync", request))
+        Box::pin(self.call::<InitialSyncCommand>(request))
     }
 
     #[inline]
@@ -161,12 +224,28 @@ impl signer::Connection<Config> for Client {
         &mut self,
         request: SignRequest,
     ) -> LocalBoxFutureResult<Result<SignResponse, SignError>, Self::Error> {
-        Box::pin(self.send("sign", request))
+        Box::pin(self.call::<SignCommand>(request))
+    }
+
+    #[inline]
+    fn sign_batch(
+        &mut self,
+        request: SignBatchRequest,
+    ) -> LocalBoxFutureResult<'_, SignBatchResult, Self::Error> {
+        Box::pin(self.call::<SignBatchCommand>(request))
+    }
+
+    #[inline]
+    fn review(
+        &mut self,
+        request: SignRequest,
+    ) -> LocalBoxFutureResult<'_, ReviewResult, Self::Error> {
+        Box::pin(self.call::<ReviewCommand>(request))
     }
 
     #[inline]
     fn address(&mut self) -> LocalBoxFutureResult<Option<Address>, Self::Error> {
-        Box::pin(self.send("address", GetRequest::Get))
+        Box::pin(self.call::<AddressCommand>(GetRequest::Get))
     }
 
     #[inline]
@@ -174,7 +253,7 @@ impl signer::Connection<Config> for Client {
         &mut self,
         request: TransactionDataRequest,
     ) -> LocalBoxFutureResult<TransactionDataResponse, Self::Error> {
-        Box::pin(self.send("transaction_data", request))
+        Box::pin(self.call::<TransactionDataCommand>(request))
     }
 
     #[inline]
@@ -182,7 +261,7 @@ impl signer::Connection<Config> for Client {
         &mut self,
         request: IdentityRequest,
     ) -> LocalBoxFutureResult<IdentityResponse, Self::Error> {
-        Box::pin(self.send("identity", request))
+        Box::pin(self.call::<IdentityCommand>(request))
     }
 
     #[inline]
@@ -190,11 +269,11 @@ impl signer::Connection<Config> for Client {
         &mut self,
         request: SignRequest,
     ) -> LocalBoxFutureResult<SignWithTransactionDataResult, Self::Error> {
-        Box::pin(self.send("sign_with_transaction_data", request))
+        Box::pin(self.call::<SignWithTransactionDataCommand>(request))
     }
 
     #[inline]
     fn transfer_parameters(&mut self) -> LocalBoxFutureResult<Parameters, Self::Error> {
-        Box::pin(self.send("transfer_parameters", GetRequest::Get))
+        Box::pin(self.call::<TransferParametersCommand>(GetRequest::Get))
     }
 }
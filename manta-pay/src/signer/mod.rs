@@ -37,6 +37,14 @@ pub mod base;
 #[cfg_attr(doc_cfg, doc(cfg(feature = "wallet")))]
 pub mod functions;
 
+#[cfg(feature = "wallet")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "wallet")))]
+pub mod registry;
+
+pub mod rpc;
+
+pub mod store;
+
 /// Synchronization Request
 pub type SyncRequest = signer::SyncRequest<Config, Checkpoint>;
 
@@ -46,6 +54,12 @@ pub type InitialSyncData = signer::InitialSyncData<Config>;
 /// Initial Synchronization Request
 pub type InitialSyncRequest = signer::InitialSyncRequest<Config>;
 
+/// Frontier Synchronization Data
+pub type FrontierSyncData = signer::FrontierSyncData<Config>;
+
+/// Frontier Synchronization Request
+pub type FrontierSyncRequest = signer::FrontierSyncRequest<Config, Checkpoint>;
+
 /// Synchronization Response
 pub type SyncResponse = signer::SyncResponse<Config, Checkpoint>;
 
@@ -67,6 +81,24 @@ pub type SignError = signer::SignError<Config>;
 /// Signing Result
 pub type SignResult = signer::SignResult<Config>;
 
+/// Transaction Summary
+pub type TransactionSummary = signer::review::TransactionSummary<Config>;
+
+/// Review Result
+pub type ReviewResult = signer::ReviewResult<Config>;
+
+/// Batch Signing Request
+pub type SignBatchRequest = signer::SignBatchRequest<AssetMetadata, Config>;
+
+/// Batch Signing Response
+pub type SignBatchResponse = signer::SignBatchResponse<Config>;
+
+/// Batch Signing Error
+pub type SignBatchError = signer::SignBatchError<Config>;
+
+/// Batch Signing Result
+pub type SignBatchResult = signer::SignBatchResult<Config>;
+
 /// Transaction Data Request
 pub type TransactionDataRequest = signer::TransactionDataRequest<Config>;
 
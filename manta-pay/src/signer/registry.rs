@@ -0,0 +1,364 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Identity Attestation Registry
+//!
+//! Verifiers which accept [`IdentityProof`]s can record them in an [`IdentityRegistry`], an
+//! append-only log of [`Attestation`]s which bind an [`Address`] to a public account at some point
+//! in time. The log only stores the [`AddressCommitment`] of every address and the [`ProofDigest`]
+//! of every identity proof, so it can be published without revealing the addresses, and it never
+//! sees any spending key.
+//!
+//! Every [`Attestation`] is hashed together with the previous head of the log, so the latest
+//! [`LogDigest`] commits to the whole log. Auditors who received a [`LogDigest`] from the registry
+//! can check any copy of the log against it with [`verify_log`].
+
+use crate::{
+    config::{
+        AccountId, Address, IdentifiedAsset, IdentityProof, Parameters, UtxoAccumulatorModel,
+        VerifyingContext,
+    },
+    domain,
+    signer::base::identity_verification,
+};
+use alloc::vec::Vec;
+use blake2::{Blake2s256, Digest};
+use manta_accounting::transfer::IdentityVerificationError;
+use manta_util::codec::Encode;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Address Commitment
+///
+/// The Blake2s hash of an [`Address`], which identifies the address in an [`IdentityRegistry`]
+/// without revealing it.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct AddressCommitment(pub [u8; 32]);
+
+impl AddressCommitment {
+    /// Computes the [`AddressCommitment`] of `address`.
+    #[inline]
+    pub fn new(address: &Address) -> Self {
+        let mut hasher = Blake2s256::new();
+        Digest::update(&mut hasher, domain::IDENTITY_ADDRESS_COMMITMENT.bytes);
+        Digest::update(&mut hasher, address.receiving_key.to_vec());
+        Self(hasher.finalize().into())
+    }
+}
+
+/// Proof Digest
+///
+/// The Blake2s hash of the encoding of an [`IdentityProof`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ProofDigest(pub [u8; 32]);
+
+impl ProofDigest {
+    /// Computes the [`ProofDigest`] of `identity_proof`.
+    #[inline]
+    pub fn new(identity_proof: &IdentityProof) -> Self {
+        let mut hasher = Blake2s256::new();
+        Digest::update(&mut hasher, domain::IDENTITY_PROOF_DIGEST.bytes);
+        Digest::update(&mut hasher, identity_proof.transfer_post.to_vec());
+        Self(hasher.finalize().into())
+    }
+}
+
+/// Identity Attestation
+///
+/// Record of a verified [`IdentityProof`] for the address with
+/// [`address_commitment`](Self::address_commitment) and the account
+/// [`public_account`](Self::public_account).
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Attestation {
+    /// Address Commitment
+    pub address_commitment: AddressCommitment,
+
+    /// Public Account
+    pub public_account: AccountId,
+
+    /// Identity Proof Digest
+    pub proof_digest: ProofDigest,
+
+    /// Timestamp
+    ///
+    /// The time at which the identity proof was verified, in the units chosen by the registry.
+    pub timestamp: u64,
+}
+
+impl Attestation {
+    /// Returns `true` if `self` attests that `address` owns `public_account`.
+    #[inline]
+    pub fn binds(&self, address: &Address, public_account: &AccountId) -> bool {
+        self.address_commitment == AddressCommitment::new(address)
+            && self.public_account == *public_account
+    }
+}
+
+/// Log Digest
+///
+/// Head of the hash chain of an [`IdentityRegistry`]. The default value is the head of the empty
+/// log.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LogDigest(pub [u8; 32]);
+
+impl LogDigest {
+    /// Returns the head of the log after appending `attestation` to the log with head `self`.
+    #[inline]
+    pub fn append(&self, attestation: &Attestation) -> Self {
+        let mut hasher = Blake2s256::new();
+        Digest::update(&mut hasher, domain::IDENTITY_LOG.bytes);
+        Digest::update(&mut hasher, self.0);
+        Digest::update(&mut hasher, attestation.address_commitment.0);
+        Digest::update(&mut hasher, attestation.public_account);
+        Digest::update(&mut hasher, attestation.proof_digest.0);
+        Digest::update(&mut hasher, attestation.timestamp.to_le_bytes());
+        Self(hasher.finalize().into())
+    }
+}
+
+/// Returns `true` if `head` is the head of the log made of `attestations`.
+#[inline]
+pub fn verify_log<'a, I>(attestations: I, head: &LogDigest) -> bool
+where
+    I: IntoIterator<Item = &'a Attestation>,
+{
+    attestations
+        .into_iter()
+        .fold(LogDigest::default(), |digest, attestation| {
+            digest.append(attestation)
+        })
+        == *head
+}
+
+/// Registry Error
+///
+/// This `enum` is the error state of the [`record`](IdentityRegistry::record) method of
+/// [`IdentityRegistry`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RegistryError {
+    /// Identity Verification Error
+    ///
+    /// The identity proof did not verify, so it was not recorded.
+    Verification(IdentityVerificationError),
+
+    /// Stale Timestamp
+    ///
+    /// The timestamp is older than the timestamp of the latest attestation in the log.
+    StaleTimestamp {
+        /// Timestamp of the Latest Attestation
+        latest: u64,
+    },
+
+    /// Duplicate Proof
+    ///
+    /// The identity proof was already recorded.
+    DuplicateProof,
+}
+
+impl From<IdentityVerificationError> for RegistryError {
+    #[inline]
+    fn from(err: IdentityVerificationError) -> Self {
+        Self::Verification(err)
+    }
+}
+
+/// Identity Attestation Registry
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IdentityRegistry {
+    /// Attestations
+    ///
+    /// The attestations are kept in the order they were recorded.
+    attestations: Vec<Attestation>,
+
+    /// Log Heads
+    ///
+    /// The `i`-th head is the head of the log after recording the `i`-th attestation.
+    heads: Vec<LogDigest>,
+}
+
+impl IdentityRegistry {
+    /// Builds a new empty [`IdentityRegistry`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of attestations in `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.attestations.len()
+    }
+
+    /// Returns `true` if there are no attestations in `self`.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.attestations.is_empty()
+    }
+
+    /// Returns the attestations in `self`, from the oldest to the latest.
+    #[inline]
+    pub fn attestations(&self) -> &[Attestation] {
+        &self.attestations
+    }
+
+    /// Returns the attestation at `index` in `self`.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&Attestation> {
+        self.attestations.get(index)
+    }
+
+    /// Returns the current head of the log.
+    #[inline]
+    pub fn head(&self) -> LogDigest {
+        self.heads.last().copied().unwrap_or_default()
+    }
+
+    /// Returns the head of the log when it had `len`-many attestations, if `self` has that many.
+    #[inline]
+    pub fn head_at(&self, len: usize) -> Option<LogDigest> {
+        match len {
+            0 => Some(LogDigest::default()),
+            _ => self.heads.get(len - 1).copied(),
+        }
+    }
+
+    /// Verifies `identity_proof` for `address` against `virtual_asset` and `public_account` and
+    /// records it in `self` with `timestamp`, returning the index of the new attestation.
+    ///
+    /// # Note
+    ///
+    /// Timestamps must not decrease along the log, so the log can also be searched by time.
+    #[allow(clippy::too_many_arguments)] // FIXME: Use a better abstraction here.
+    #[inline]
+    pub fn record(
+        &mut self,
+        identity_proof: &IdentityProof,
+        parameters: &Parameters,
+        verifying_context: &VerifyingContext,
+        utxo_accumulator_model: &UtxoAccumulatorModel,
+        virtual_asset: IdentifiedAsset,
+        address: Address,
+        public_account: AccountId,
+        timestamp: u64,
+    ) -> Result<usize, RegistryError> {
+        if let Some(latest) = self.attestations.last() {
+            if timestamp < latest.timestamp {
+                return Err(RegistryError::StaleTimestamp {
+                    latest: latest.timestamp,
+                });
+            }
+        }
+        let proof_digest = ProofDigest::new(identity_proof);
+        if self
+            .attestations
+            .iter()
+            .any(|attestation| attestation.proof_digest == proof_digest)
+        {
+            return Err(RegistryError::DuplicateProof);
+        }
+        identity_verification(
+            identity_proof,
+            parameters,
+            verifying_context,
+            utxo_accumulator_model,
+            virtual_asset,
+            address,
+            public_account,
+        )?;
+        let attestation = Attestation {
+            address_commitment: AddressCommitment::new(&address),
+            public_account,
+            proof_digest,
+            timestamp,
+        };
+        self.heads.push(self.head().append(&attestation));
+        self.attestations.push(attestation);
+        Ok(self.attestations.len() - 1)
+    }
+
+    /// Returns an iterator over the attestations for `address`, from the oldest to the latest.
+    #[inline]
+    pub fn by_address(&self, address: &Address) -> impl Iterator<Item = &Attestation> {
+        let address_commitment = AddressCommitment::new(address);
+        self.attestations
+            .iter()
+            .filter(move |attestation| attestation.address_commitment == address_commitment)
+    }
+
+    /// Returns an iterator over the attestations for `public_account`, from the oldest to the
+    /// latest.
+    #[inline]
+    pub fn by_account<'s>(
+        &'s self,
+        public_account: &'s AccountId,
+    ) -> impl Iterator<Item = &'s Attestation> {
+        self.attestations
+            .iter()
+            .filter(move |attestation| attestation.public_account == *public_account)
+    }
+
+    /// Returns an iterator over the attestations recorded between `start` and `end`, inclusive.
+    #[inline]
+    pub fn between(&self, start: u64, end: u64) -> impl Iterator<Item = &Attestation> {
+        let first = self
+            .attestations
+            .partition_point(|attestation| attestation.timestamp < start);
+        self.attestations[first..]
+            .iter()
+            .take_while(move |attestation| attestation.timestamp <= end)
+    }
+
+    /// Returns the latest attestation that `address` owns `public_account`.
+    #[inline]
+    pub fn latest(&self, address: &Address, public_account: &AccountId) -> Option<&Attestation> {
+        let address_commitment = AddressCommitment::new(address);
+        self.attestations.iter().rev().find(|attestation| {
+            attestation.address_commitment == address_commitment
+                && attestation.public_account == *public_account
+        })
+    }
+}
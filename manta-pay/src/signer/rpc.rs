@@ -0,0 +1,386 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signer RPC Description
+//!
+//! The signer clients send every call of the signer
+//! [`Connection`](manta_accounting::wallet::signer::Connection) to the signer as a command, which
+//! has a name and serializes its request and response with `serde`. The commands are declared once,
+//! in the command table of this module, and the HTTP and WebSocket clients send their requests
+//! through the [`Command`]s of the table, so the compiler checks that the names and the request
+//! and response types of the table are the ones that the clients use.
+//!
+//! [`description`] returns a machine-readable description of the table, together with the codes of
+//! the errors that the signer returns and the envelopes of the transports, which the
+//! `signer_rpc_description` binary prints as JSON so that the clients in other languages can be
+//! generated from it.
+//!
+//! # Versioning
+//!
+//! The [`VERSION`] of the RPC is incremented whenever a command is added, or the name, the request
+//! type, or the response type of a command changes. Every command records the version which
+//! introduced it in its [`Command::SINCE`] constant. Error codes are never reused, so that clients
+//! built against an older version still recognize the errors they know about.
+
+use crate::{
+    config::{utxo::Address, Parameters},
+    signer::{
+        GetRequest, IdentityRequest, IdentityResponse, InitialSyncRequest, ReviewResult,
+        SignBatchRequest, SignBatchResult, SignError, SignRequest, SignResponse,
+        SignWithTransactionDataResult, SyncError, SyncRequest, SyncResponse,
+        TransactionDataRequest, TransactionDataResponse,
+    },
+};
+use alloc::{vec, vec::Vec};
+use core::{any, convert::Infallible};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::Serialize;
+
+/// Signer RPC Version
+pub const VERSION: u32 = 1;
+
+/// Type Description
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize),
+    serde(crate = "manta_util::serde")
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TypeDescription {
+    /// Type Name
+    ///
+    /// Name of the type alias in the [`signer`](crate::signer) module.
+    pub name: &'static str,
+
+    /// Type Path
+    ///
+    /// Full path of the type behind the alias, with its type parameters.
+    pub path: &'static str,
+}
+
+impl TypeDescription {
+    /// Builds a new [`TypeDescription`] for `T` which is named `name`.
+    #[inline]
+    pub fn new<T>(name: &'static str) -> Self
+    where
+        T: ?Sized,
+    {
+        Self {
+            name,
+            path: any::type_name::<T>(),
+        }
+    }
+}
+
+/// Error Description
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize),
+    serde(crate = "manta_util::serde")
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ErrorDescription {
+    /// Error Code
+    pub code: u16,
+
+    /// Error Variant
+    ///
+    /// Name of the variant of the error type, which is also its tag in the serialized error.
+    pub variant: &'static str,
+
+    /// Error Summary
+    pub summary: &'static str,
+}
+
+impl ErrorDescription {
+    /// Builds a new [`ErrorDescription`] for the `variant` with the given `code` and `summary`.
+    #[inline]
+    pub const fn new(code: u16, variant: &'static str, summary: &'static str) -> Self {
+        Self {
+            code,
+            variant,
+            summary,
+        }
+    }
+}
+
+/// Error Code
+///
+/// Error type of the responses of the signer, whose variants have stable numeric codes.
+pub trait ErrorCode {
+    /// Descriptions of the variants of the error type, in increasing order of code.
+    const VARIANTS: &'static [ErrorDescription];
+
+    /// Returns the code of `self`.
+    fn code(&self) -> u16;
+}
+
+impl ErrorCode for Infallible {
+    const VARIANTS: &'static [ErrorDescription] = &[];
+
+    #[inline]
+    fn code(&self) -> u16 {
+        match *self {}
+    }
+}
+
+impl ErrorCode for SyncError {
+    const VARIANTS: &'static [ErrorDescription] = &[
+        ErrorDescription::new(
+            100,
+            "InconsistentSynchronization",
+            "The signer checkpoint is behind the wallet checkpoint. The wallet can synchronize again from the returned signer checkpoint.",
+        ),
+        ErrorDescription::new(
+            101,
+            "MissingProofAuthorizationKey",
+            "The signer has no proof authorization key to synchronize with.",
+        ),
+    ];
+
+    #[inline]
+    fn code(&self) -> u16 {
+        match self {
+            Self::InconsistentSynchronization { .. } => 100,
+            Self::MissingProofAuthorizationKey => 101,
+        }
+    }
+}
+
+impl ErrorCode for SignError {
+    const VARIANTS: &'static [ErrorDescription] = &[
+        ErrorDescription::new(
+            200,
+            "InsufficientBalance",
+            "The signer does not own enough of the returned asset to sign the transaction.",
+        ),
+        ErrorDescription::new(
+            201,
+            "ProofSystemError",
+            "The signer could not build a proof for the transaction.",
+        ),
+        ErrorDescription::new(
+            202,
+            "MissingSpendingKey",
+            "The signer has no spending key to sign the transaction with.",
+        ),
+        ErrorDescription::new(
+            203,
+            "MissingProofAuthorizationKey",
+            "The signer has no proof authorization key to sign the transaction with.",
+        ),
+        ErrorDescription::new(
+            204,
+            "LedgerSnapshotError",
+            "The ledger snapshot of the signer does not allow the transaction.",
+        ),
+        ErrorDescription::new(
+            205,
+            "LeaseConflict",
+            "The transaction would spend a note which is leased by the returned device.",
+        ),
+        ErrorDescription::new(
+            206,
+            "BalanceError",
+            "The total value of the notes paying for the transaction does not fit in an asset value.",
+        ),
+    ];
+
+    #[inline]
+    fn code(&self) -> u16 {
+        match self {
+            Self::InsufficientBalance(_) => 200,
+            Self::ProofSystemError(_) => 201,
+            Self::MissingSpendingKey => 202,
+            Self::MissingProofAuthorizationKey => 203,
+            Self::LedgerSnapshotError(_) => 204,
+            Self::LeaseConflict(_) => 205,
+            Self::BalanceError(_) => 206,
+        }
+    }
+}
+
+/// Signer Command
+pub trait Command {
+    /// Command Name
+    ///
+    /// Name of the command, which is the route of the HTTP client and the command of the WebSocket
+    /// client.
+    const NAME: &'static str;
+
+    /// Command Summary
+    const SUMMARY: &'static str;
+
+    /// Version of the RPC which introduced the command
+    const SINCE: u32;
+
+    /// Request Type
+    type Request;
+
+    /// Response Type
+    type Response;
+
+    /// Error Type
+    ///
+    /// Type of the errors that the response of the command can carry.
+    type Error: ErrorCode;
+}
+
+/// Command Description
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize),
+    serde(crate = "manta_util::serde")
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CommandDescription {
+    /// Command Name
+    pub name: &'static str,
+
+    /// Command Summary
+    pub summary: &'static str,
+
+    /// Version of the RPC which introduced the command
+    pub since: u32,
+
+    /// Request Type
+    pub request: TypeDescription,
+
+    /// Response Type
+    pub response: TypeDescription,
+
+    /// Errors which the response of the command can carry
+    pub errors: Vec<ErrorDescription>,
+}
+
+/// Transport Description
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize),
+    serde(crate = "manta_util::serde")
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TransportDescription {
+    /// Transport Name
+    pub name: &'static str,
+
+    /// Request Envelope
+    ///
+    /// Shape of the messages which carry the requests, where `<command>` is the name of the
+    /// command and `<request>` is its serialized request.
+    pub envelope: &'static str,
+}
+
+/// Transports of the Signer Clients
+pub const TRANSPORTS: &[TransportDescription] = &[
+    TransportDescription {
+        name: "http",
+        envelope: r#"POST <url>/<command> {"network": <network>, "message": <request>}"#,
+    },
+    TransportDescription {
+        name: "websocket",
+        envelope: r#"{"command": <command>, "request": <request>}"#,
+    },
+];
+
+/// Signer RPC Description
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize),
+    serde(crate = "manta_util::serde")
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Description {
+    /// Signer RPC Version
+    pub version: u32,
+
+    /// Transports
+    pub transports: Vec<TransportDescription>,
+
+    /// Commands
+    pub commands: Vec<CommandDescription>,
+}
+
+/// Declares the signer command table.
+///
+/// Every row of the table declares a [`Command`] type with the name, the request and response
+/// types, the error type, the version which introduced it, and the summary of the command, and
+/// adds it to the [`description`] of the RPC.
+macro_rules! commands {
+    ($($command:ident: $name:literal ($request:ty) -> $response:ty, $error:ty, since $since:literal, $summary:literal;)*) => {
+        $(
+            #[doc = concat!("`", $name, "` Command")]
+            ///
+            #[doc = $summary]
+            #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+            pub struct $command;
+
+            impl Command for $command {
+                const NAME: &'static str = $name;
+                const SUMMARY: &'static str = $summary;
+                const SINCE: u32 = $since;
+                type Request = $request;
+                type Response = $response;
+                type Error = $error;
+            }
+        )*
+
+        /// Returns the description of the signer RPC.
+        #[inline]
+        pub fn description() -> Description {
+            Description {
+                version: VERSION,
+                transports: TRANSPORTS.to_vec(),
+                commands: vec![$(
+                    CommandDescription {
+                        name: $name,
+                        summary: $summary,
+                        since: $since,
+                        request: TypeDescription::new::<$request>(stringify!($request)),
+                        response: TypeDescription::new::<$response>(stringify!($response)),
+                        errors: <$error as ErrorCode>::VARIANTS.to_vec(),
+                    },
+                )*],
+            }
+        }
+    };
+}
+
+commands! {
+    SyncCommand: "sync" (SyncRequest) -> Result<SyncResponse, SyncError>, SyncError, since 1,
+        "Synchronizes the signer with the ledger data fetched by the wallet since its checkpoint.";
+    SbtSyncCommand: "sbt_sync" (SyncRequest) -> Result<SyncResponse, SyncError>, SyncError, since 1,
+        "Synchronizes the signer with the ledger data of a ledger which only holds soul-bound tokens, without updating its accumulator.";
+    InitialSyncCommand: "initial_sync" (InitialSyncRequest) -> Result<SyncResponse, SyncError>, SyncError, since 1,
+        "Performs the initial synchronization of a new signer with the full ledger data.";
+    SignCommand: "sign" (SignRequest) -> Result<SignResponse, SignError>, SignError, since 1,
+        "Signs a transaction and returns the transfer posts which execute it.";
+    SignBatchCommand: "sign_batch" (SignBatchRequest) -> SignBatchResult, SignError, since 1,
+        "Signs a batch of transactions, returning the index and error of every transaction which could not be signed.";
+    ReviewCommand: "review" (SignRequest) -> ReviewResult, SignError, since 1,
+        "Summarizes the transfers that signing a transaction would build, without signing it.";
+    AddressCommand: "address" (GetRequest) -> Option<Address>, Infallible, since 1,
+        "Returns the address of the signer.";
+    TransactionDataCommand: "transaction_data" (TransactionDataRequest) -> TransactionDataResponse, Infallible, since 1,
+        "Returns the transaction data of the transfer posts built by the signer.";
+    IdentityCommand: "identity" (IdentityRequest) -> IdentityResponse, Infallible, since 1,
+        "Returns the identity proofs of the requested assets.";
+    SignWithTransactionDataCommand: "sign_with_transaction_data" (SignRequest) -> SignWithTransactionDataResult, SignError, since 1,
+        "Signs a transaction and returns the transfer posts which execute it together with their transaction data.";
+    TransferParametersCommand: "transfer_parameters" (GetRequest) -> Parameters, Infallible, since 1,
+        "Returns the transfer parameters of the signer.";
+}
@@ -0,0 +1,170 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! IndexedDB Asset Map Store
+
+use crate::signer::store::{decode_entry, encode_entry, StoreError};
+use alloc::{
+    borrow::ToOwned,
+    boxed::Box,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::marker::PhantomData;
+use indexed_db_futures::{
+    js_sys::Uint8Array,
+    prelude::*,
+    web_sys::{wasm_bindgen::JsValue, DomException},
+};
+use manta_accounting::{
+    asset::Asset,
+    wallet::signer::store::{AssetMapBatch, AssetMapStore},
+};
+use manta_util::{
+    codec::{Decode, Encode},
+    future::LocalBoxFutureResult,
+};
+
+/// IndexedDB Store Error
+pub type Error = StoreError<DomException>;
+
+/// IndexedDB Asset Map Store
+///
+/// Stores the entries of an asset map as the keys of an object store of an IndexedDB database,
+/// applying every batch in a single read-write transaction.
+pub struct IndexedDbStore<K, I, V> {
+    /// Database
+    database: IdbDatabase,
+
+    /// Object Store Name
+    store_name: String,
+
+    /// Type Parameter Marker
+    __: PhantomData<(K, I, V)>,
+}
+
+impl<K, I, V> IndexedDbStore<K, I, V> {
+    /// Default Object Store Name
+    pub const DEFAULT_STORE_NAME: &'static str = "asset-map";
+
+    /// Database Version
+    pub const VERSION: u32 = 1;
+
+    /// Opens the database named `database_name`, creating the object store named `store_name`
+    /// if it does not exist yet, and builds a new [`IndexedDbStore`] on it.
+    #[inline]
+    pub async fn open(database_name: &str, store_name: &str) -> Result<Self, DomException> {
+        let mut request = IdbDatabase::open_u32(database_name, Self::VERSION)?;
+        let name = store_name.to_owned();
+        request.set_on_upgrade_needed(Some(
+            move |event: &IdbVersionChangeEvent| -> Result<(), JsValue> {
+                if !event.db().object_store_names().any(|store| store == name) {
+                    event.db().create_object_store(&name)?;
+                }
+                Ok(())
+            },
+        ));
+        Ok(Self {
+            database: request.await?,
+            store_name: store_name.to_string(),
+            __: PhantomData,
+        })
+    }
+
+    /// Returns the underlying database.
+    #[inline]
+    pub fn database(&self) -> &IdbDatabase {
+        &self.database
+    }
+
+    /// Runs `write` on the object store in a read-write transaction, aborting the transaction if
+    /// `write` fails.
+    #[inline]
+    async fn write<F>(&self, write: F) -> Result<(), Error>
+    where
+        F: FnOnce(&IdbObjectStore) -> Result<(), DomException>,
+    {
+        let transaction = self
+            .database
+            .transaction_on_one_with_mode(&self.store_name, IdbTransactionMode::Readwrite)?;
+        let result = transaction
+            .object_store(&self.store_name)
+            .and_then(|store| write(&store));
+        match result {
+            Ok(()) => Ok(transaction.await.into_result()?),
+            Err(err) => {
+                let _ = transaction.abort();
+                Err(err.into())
+            }
+        }
+    }
+}
+
+impl<K, I, V> AssetMapStore<K, I, V> for IndexedDbStore<K, I, V>
+where
+    K: Decode + Encode,
+    I: Decode + Encode,
+    V: Decode + Encode,
+{
+    type Error = Error;
+
+    #[inline]
+    fn load(&mut self) -> LocalBoxFutureResult<'_, Vec<(K, Asset<I, V>)>, Self::Error> {
+        Box::pin(async move {
+            let transaction = self.database.transaction_on_one(&self.store_name)?;
+            let keys = transaction
+                .object_store(&self.store_name)?
+                .get_all_keys()?
+                .await?;
+            keys.iter()
+                .map(|key| decode_entry(&Uint8Array::new(&key).to_vec()).ok_or(StoreError::Decode))
+                .collect()
+        })
+    }
+
+    #[inline]
+    fn apply<'s>(
+        &'s mut self,
+        batch: &'s AssetMapBatch<K, I, V>,
+    ) -> LocalBoxFutureResult<'s, (), Self::Error> {
+        Box::pin(async move {
+            self.write(|store| {
+                for (key, asset) in &batch.removals {
+                    store.delete_owned(Uint8Array::from(encode_entry(key, asset).as_slice()))?;
+                }
+                for (key, asset) in &batch.insertions {
+                    store.put_key_val_owned(
+                        Uint8Array::from(encode_entry(key, asset).as_slice()),
+                        &JsValue::TRUE,
+                    )?;
+                }
+                Ok(())
+            })
+            .await
+        })
+    }
+
+    #[inline]
+    fn clear(&mut self) -> LocalBoxFutureResult<'_, (), Self::Error> {
+        Box::pin(async move {
+            self.write(|store| {
+                store.clear()?;
+                Ok(())
+            })
+            .await
+        })
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Asset Map Store Implementations
+//!
+//! Every entry of an asset map is stored as a single database key made of the encoded key of the
+//! entry followed by its encoded asset, so that the same key can hold several assets.
+
+use crate::config::{AssetId, AssetValue, Identifier};
+use alloc::vec::Vec;
+use manta_accounting::{
+    asset::{Asset, BTreeAssetMap},
+    wallet::signer::store,
+};
+use manta_util::codec::{Decode, Encode};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+#[cfg(feature = "sled")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "sled")))]
+pub mod sled;
+
+#[cfg(all(feature = "indexed-db", target_arch = "wasm32"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "indexed-db", target_arch = "wasm32"))))]
+pub mod indexed_db;
+
+/// Journaled Asset Map
+pub type JournaledAssetMap =
+    store::JournaledAssetMap<BTreeAssetMap<Identifier, AssetId, AssetValue>, AssetId, AssetValue>;
+
+/// Store Error
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum StoreError<E> {
+    /// Backend Error
+    Backend(E),
+
+    /// Decoding Error
+    ///
+    /// An entry of the store could not be decoded.
+    Decode,
+}
+
+impl<E> From<E> for StoreError<E> {
+    #[inline]
+    fn from(err: E) -> Self {
+        Self::Backend(err)
+    }
+}
+
+/// Encodes `key` and `asset` into the database key of their entry.
+#[inline]
+pub fn encode_entry<K, I, V>(key: &K, asset: &Asset<I, V>) -> Vec<u8>
+where
+    K: Encode,
+    I: Encode,
+    V: Encode,
+{
+    let mut bytes = key.to_vec();
+    bytes.extend(asset.to_vec());
+    bytes
+}
+
+/// Decodes the key and the asset of an entry from its database key, returning `None` if `bytes`
+/// is not a well-formed entry.
+#[inline]
+pub fn decode_entry<K, I, V>(mut bytes: &[u8]) -> Option<(K, Asset<I, V>)>
+where
+    K: Decode,
+    I: Decode,
+    V: Decode,
+{
+    let key = K::decode(&mut bytes).ok()?;
+    let asset = Asset::decode(&mut bytes).ok()?;
+    bytes.is_empty().then_some((key, asset))
+}
@@ -0,0 +1,130 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sled Asset Map Store
+
+use crate::signer::store::{decode_entry, encode_entry, StoreError};
+use alloc::{boxed::Box, vec::Vec};
+use core::marker::PhantomData;
+use manta_accounting::{
+    asset::Asset,
+    wallet::signer::store::{AssetMapBatch, AssetMapStore},
+};
+use manta_util::{
+    codec::{Decode, Encode},
+    future::LocalBoxFutureResult,
+};
+use sled::{Batch, Db, Tree};
+
+/// Sled Store Error
+pub type Error = StoreError<sled::Error>;
+
+/// Sled Asset Map Store
+///
+/// Stores the entries of an asset map in a [`Tree`] of a sled database, applying every batch with
+/// a single atomic write.
+#[derive(derivative::Derivative)]
+#[derivative(Clone(bound = ""), Debug(bound = ""))]
+pub struct SledStore<K, I, V> {
+    /// Database Tree
+    tree: Tree,
+
+    /// Type Parameter Marker
+    __: PhantomData<(K, I, V)>,
+}
+
+impl<K, I, V> SledStore<K, I, V> {
+    /// Default Tree Name
+    pub const DEFAULT_TREE_NAME: &'static str = "asset-map";
+
+    /// Builds a new [`SledStore`] which stores the entries in `tree`.
+    #[inline]
+    pub fn new(tree: Tree) -> Self {
+        Self {
+            tree,
+            __: PhantomData,
+        }
+    }
+
+    /// Opens the tree named [`DEFAULT_TREE_NAME`](Self::DEFAULT_TREE_NAME) in `database` and
+    /// builds a new [`SledStore`] on it.
+    #[inline]
+    pub fn open(database: &Db) -> Result<Self, sled::Error> {
+        Ok(Self::new(database.open_tree(Self::DEFAULT_TREE_NAME)?))
+    }
+
+    /// Returns the underlying database tree.
+    #[inline]
+    pub fn tree(&self) -> &Tree {
+        &self.tree
+    }
+
+    /// Writes `batch` to the tree and waits for it to reach the disk.
+    #[inline]
+    async fn write(&self, batch: Batch) -> Result<(), Error> {
+        self.tree.apply_batch(batch)?;
+        self.tree.flush_async().await?;
+        Ok(())
+    }
+}
+
+impl<K, I, V> AssetMapStore<K, I, V> for SledStore<K, I, V>
+where
+    K: Decode + Encode,
+    I: Decode + Encode,
+    V: Decode + Encode,
+{
+    type Error = Error;
+
+    #[inline]
+    fn load(&mut self) -> LocalBoxFutureResult<'_, Vec<(K, Asset<I, V>)>, Self::Error> {
+        Box::pin(async move {
+            self.tree
+                .iter()
+                .keys()
+                .map(|key| decode_entry(&key?).ok_or(StoreError::Decode))
+                .collect()
+        })
+    }
+
+    #[inline]
+    fn apply<'s>(
+        &'s mut self,
+        batch: &'s AssetMapBatch<K, I, V>,
+    ) -> LocalBoxFutureResult<'s, (), Self::Error> {
+        Box::pin(async move {
+            let mut write = Batch::default();
+            for (key, asset) in &batch.removals {
+                write.remove(encode_entry(key, asset));
+            }
+            for (key, asset) in &batch.insertions {
+                write.insert(encode_entry(key, asset), &[]);
+            }
+            self.write(write).await
+        })
+    }
+
+    #[inline]
+    fn clear(&mut self) -> LocalBoxFutureResult<'_, (), Self::Error> {
+        Box::pin(async move {
+            let mut write = Batch::default();
+            for key in self.tree.iter().keys() {
+                write.remove(key?);
+            }
+            self.write(write).await
+        })
+    }
+}
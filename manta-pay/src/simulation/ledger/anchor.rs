@@ -0,0 +1,117 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Ledger Anchors
+//!
+//! The simulation ledger issues [`LedgerAnchor`]s for the current roots of its UTXO accumulator,
+//! which wallets embed into anchored identity proofs. Instead of a public-key signature, the
+//! simulation authenticates its anchors with a Blake2s hash keyed by an [`AnchorKey`], so the
+//! [`AnchorVerifier`] has to share the key with the ledger.
+
+use crate::{
+    config::{Config, LedgerAnchor, UtxoAccumulatorOutput},
+    domain,
+    simulation::ledger::{Ledger, MerkleForestIndex},
+};
+use blake2::{Blake2s256, Digest};
+use manta_accounting::transfer;
+use manta_crypto::{
+    merkle_tree::forest::Forest,
+    rand::{RngCore, Sample},
+};
+use manta_util::codec::Encode;
+
+/// Anchor Key
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct AnchorKey(pub [u8; 32]);
+
+impl AnchorKey {
+    /// Computes the keyed hash of `root` and `height` under `self`.
+    #[inline]
+    fn mac(&self, root: &UtxoAccumulatorOutput, height: u64) -> [u8; 32] {
+        let mut hasher = Blake2s256::new();
+        Digest::update(&mut hasher, domain::LEDGER_ANCHOR.bytes);
+        Digest::update(&mut hasher, self.0);
+        Digest::update(&mut hasher, root.to_vec());
+        Digest::update(&mut hasher, height.to_le_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Signs `root` at `height`, returning the [`LedgerAnchor`] which references them.
+    #[inline]
+    pub fn sign(&self, root: UtxoAccumulatorOutput, height: u64) -> LedgerAnchor {
+        let signature = self.mac(&root, height).to_vec();
+        LedgerAnchor {
+            root,
+            height,
+            signature,
+        }
+    }
+}
+
+impl Sample for AnchorKey {
+    #[inline]
+    fn sample<R>(_: (), rng: &mut R) -> Self
+    where
+        R: RngCore + ?Sized,
+    {
+        let mut key = [0; 32];
+        rng.fill_bytes(&mut key);
+        Self(key)
+    }
+}
+
+impl Ledger {
+    /// Returns the [`LedgerAnchor`] of the current root of the tree at `index` in the UTXO
+    /// accumulator, at the current height, signed with `key`.
+    #[inline]
+    pub fn anchor(&self, key: &AnchorKey, index: MerkleForestIndex) -> LedgerAnchor {
+        key.sign(*self.utxo_forest.forest.get(index).root(), self.height)
+    }
+}
+
+/// Anchor Verifier
+///
+/// Checks the anchors of a [`Ledger`] against its [`AnchorKey`] and its root history, so roots
+/// which were pruned from the history are no longer accepted.
+#[derive(Clone, Copy, Debug)]
+pub struct AnchorVerifier<'l> {
+    /// Anchor Key
+    key: &'l AnchorKey,
+
+    /// Ledger
+    ledger: &'l Ledger,
+}
+
+impl<'l> AnchorVerifier<'l> {
+    /// Builds a new [`AnchorVerifier`] for the anchors of `ledger` signed with `key`.
+    #[inline]
+    pub fn new(key: &'l AnchorKey, ledger: &'l Ledger) -> Self {
+        Self { key, ledger }
+    }
+}
+
+impl transfer::AnchorVerifier<Config> for AnchorVerifier<'_> {
+    #[inline]
+    fn has_valid_signature(&self, anchor: &LedgerAnchor) -> bool {
+        self.key.mac(&anchor.root, anchor.height)[..] == anchor.signature[..]
+    }
+
+    #[inline]
+    fn is_accepted_root(&self, root: &UtxoAccumulatorOutput, height: u64) -> bool {
+        height <= self.ledger.height && self.ledger.root_history.contains(root)
+    }
+}
@@ -0,0 +1,848 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Post Archives
+//!
+//! A [`PostArchive`] stores batches of [`TransferPost`]s in a columnar layout: the fields of the
+//! posts are split into one [`Column`] per kind of data, so that proofs, nullifiers, UTXOs and
+//! notes are stored next to values of the same kind. Columns are compressed independently, and
+//! consumers only decompress and decode the columns they need: [`Ledger::replay`] reads every
+//! column to rebuild the posts, while [`PostArchive::receivers`] and [`PostArchive::nullifiers`]
+//! never touch the proofs, which make up most of an archive.
+//!
+//! # Wire Format
+//!
+//! An archive starts with the [`MAGIC`] bytes and the little-endian `u16` [`VERSION`] of the
+//! format, followed by the little-endian `u64` numbers of batches and posts. Then, for every
+//! column in the order of [`Column::ALL`], it stores the [`Compression`] tag of the column as a
+//! byte, the little-endian `u64` lengths of the column before and after compression, and the
+//! compressed bytes of the column. The values in each column are [`Encode`]d one after the other:
+//!
+//! 1. [`Column::Batches`]: the account and the number of posts of every batch,
+//! 2. [`Column::Posts`]: the authorization signature, asset id, sources, sinks, sink accounts
+//!    and epoch of every post, with its numbers of senders and receivers,
+//! 3. [`Column::Proofs`]: the proof of every post,
+//! 4. [`Column::Roots`]: the UTXO accumulator output of every sender,
+//! 5. [`Column::Nullifiers`]: the nullifier of every sender,
+//! 6. [`Column::Utxos`]: the UTXO of every receiver,
+//! 7. [`Column::Notes`]: the note of every receiver, followed by its optional memo.
+//!
+//! The archive ends with the Blake2s digest of all the bytes before it.
+//!
+//! # Limitations
+//!
+//! Archives are built and read in memory, since every column has to be complete before the next
+//! one can be written. Field elements and proofs are close to uniformly random, so the
+//! [`RunLength`](Compression::RunLength) compression mostly shrinks the [`Column::Batches`] and
+//! [`Column::Posts`] columns, which are left uncompressed whenever compression does not help.
+
+use crate::{
+    config::{utxo::FullIncomingNote, AccountId, Nullifier, TransferPost, Utxo},
+    simulation::ledger::Ledger,
+};
+use alloc::vec::Vec;
+use blake2::{Blake2s256, Digest};
+use core::fmt;
+use manta_accounting::transfer::{
+    receiver::ReceiverPost, sender::SenderPost, PostParticipants, TransferPostBody,
+    MAX_POST_PARTICIPANTS,
+};
+use manta_util::codec::{Decode, Encode};
+
+/// Archive Magic Bytes
+pub const MAGIC: [u8; 8] = *b"MANTALPA";
+
+/// Archive Format Version
+pub const VERSION: u16 = 2;
+
+/// Maximum Column Length
+///
+/// This is the largest column in bytes, before or after compression, accepted by
+/// [`PostArchive::from_bytes`].
+pub const MAX_COLUMN_LENGTH: usize = 1 << 30;
+
+/// Archive Column
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Column {
+    /// Batch Accounts and Sizes
+    Batches,
+
+    /// Post Metadata
+    Posts,
+
+    /// Proofs
+    Proofs,
+
+    /// UTXO Accumulator Outputs of the Senders
+    Roots,
+
+    /// Nullifiers
+    Nullifiers,
+
+    /// UTXOs
+    Utxos,
+
+    /// Notes
+    Notes,
+}
+
+impl Column {
+    /// Columns in the order in which they are stored
+    pub const ALL: [Self; 7] = [
+        Self::Batches,
+        Self::Posts,
+        Self::Proofs,
+        Self::Roots,
+        Self::Nullifiers,
+        Self::Utxos,
+        Self::Notes,
+    ];
+}
+
+/// Column Compression
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Compression {
+    /// Uncompressed Column
+    #[default]
+    Raw,
+
+    /// Run-Length Compressed Column
+    ///
+    /// The column is a sequence of chunks which start with a control byte `c`. If `c < 0x80`, the
+    /// next `c + 1` bytes are copied as they are, otherwise the next byte is repeated
+    /// `c - 0x80 + 3` times.
+    RunLength,
+}
+
+impl Compression {
+    /// Shortest Run which is Compressed
+    const MIN_RUN: usize = 3;
+
+    /// Longest Compressed Run
+    const MAX_RUN: usize = 0x7f + Self::MIN_RUN;
+
+    /// Longest Literal Chunk
+    const MAX_LITERAL: usize = 0x80;
+
+    /// Returns the tag of `self` in the wire format.
+    #[inline]
+    fn tag(self) -> u8 {
+        match self {
+            Self::Raw => 0,
+            Self::RunLength => 1,
+        }
+    }
+
+    /// Returns the [`Compression`] with the given `tag` in the wire format.
+    #[inline]
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(Self::Raw),
+            1 => Some(Self::RunLength),
+            _ => None,
+        }
+    }
+
+    /// Appends the `literals` to `output` in chunks of at most [`MAX_LITERAL`](Self::MAX_LITERAL)
+    /// bytes.
+    #[inline]
+    fn write_literals(literals: &[u8], output: &mut Vec<u8>) {
+        for chunk in literals.chunks(Self::MAX_LITERAL) {
+            output.push((chunk.len() - 1) as u8);
+            output.extend_from_slice(chunk);
+        }
+    }
+
+    /// Compresses `bytes` with run-length compression.
+    #[inline]
+    fn compress(bytes: &[u8]) -> Vec<u8> {
+        let mut output = Vec::new();
+        let mut literal_start = 0;
+        let mut i = 0;
+        while i < bytes.len() {
+            let byte = bytes[i];
+            let mut run = 1;
+            while run < Self::MAX_RUN && bytes.get(i + run) == Some(&byte) {
+                run += 1;
+            }
+            if run >= Self::MIN_RUN {
+                Self::write_literals(&bytes[literal_start..i], &mut output);
+                output.push(0x80 | (run - Self::MIN_RUN) as u8);
+                output.push(byte);
+                literal_start = i + run;
+            }
+            i += run;
+        }
+        Self::write_literals(&bytes[literal_start..], &mut output);
+        output
+    }
+
+    /// Decompresses run-length compressed `bytes`, returning `None` if they are malformed or do
+    /// not decompress to exactly `length` bytes.
+    #[inline]
+    fn decompress(mut bytes: &[u8], length: usize) -> Option<Vec<u8>> {
+        let mut output = Vec::with_capacity(length.min(bytes.len().saturating_mul(Self::MAX_RUN)));
+        while let Some((&control, rest)) = bytes.split_first() {
+            if control < 0x80 {
+                let len = control as usize + 1;
+                output.extend_from_slice(rest.get(..len)?);
+                bytes = &rest[len..];
+            } else {
+                let (&byte, rest) = rest.split_first()?;
+                output.resize(
+                    output.len() + (control - 0x80) as usize + Self::MIN_RUN,
+                    byte,
+                );
+                bytes = rest;
+            }
+            if output.len() > length {
+                return None;
+            }
+        }
+        (output.len() == length).then_some(output)
+    }
+}
+
+/// Archive Error
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ArchiveError {
+    /// Invalid Magic Bytes
+    ///
+    /// The input does not start with [`MAGIC`], so it is not a post archive.
+    InvalidMagic,
+
+    /// Unsupported Version
+    ///
+    /// The archive was written in a version of the format which this build cannot read.
+    UnsupportedVersion(u16),
+
+    /// Unknown Compression Tag
+    UnknownCompression(u8),
+
+    /// Column Length Exceeds [`MAX_COLUMN_LENGTH`]
+    ColumnTooLarge(u64),
+
+    /// Truncated Archive
+    Truncated,
+
+    /// Malformed Column
+    ///
+    /// The column could not be decompressed, one of its values could not be decoded, or it does
+    /// not hold as many values as the other columns require.
+    MalformedColumn(Column),
+
+    /// Digest Mismatch
+    ///
+    /// The archive does not match the digest at its end, so it was corrupted.
+    DigestMismatch,
+}
+
+impl fmt::Display for ArchiveError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidMagic => write!(f, "Input is not a post archive."),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Unsupported archive version {version}.")
+            }
+            Self::UnknownCompression(tag) => write!(f, "Unknown column compression {tag}."),
+            Self::ColumnTooLarge(len) => write!(f, "Column length {len} exceeds the maximum."),
+            Self::Truncated => write!(f, "Post archive is truncated."),
+            Self::MalformedColumn(column) => write!(f, "Malformed {column:?} column."),
+            Self::DigestMismatch => write!(f, "Post archive does not match its digest."),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+/// Stored Column
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+struct StoredColumn {
+    /// Compression
+    compression: Compression,
+
+    /// Length before Compression
+    length: usize,
+
+    /// Stored Bytes
+    bytes: Vec<u8>,
+}
+
+impl StoredColumn {
+    /// Compresses `bytes` into a [`StoredColumn`], keeping them uncompressed if compression does
+    /// not make them shorter.
+    #[inline]
+    fn new(bytes: Vec<u8>) -> Self {
+        let length = bytes.len();
+        let compressed = Compression::compress(&bytes);
+        if compressed.len() < length {
+            Self {
+                compression: Compression::RunLength,
+                length,
+                bytes: compressed,
+            }
+        } else {
+            Self {
+                compression: Compression::Raw,
+                length,
+                bytes,
+            }
+        }
+    }
+
+    /// Returns the decompressed bytes of `self`, which is the `column` of its archive.
+    #[inline]
+    fn decompress(&self, column: Column) -> Result<ColumnReader, ArchiveError> {
+        let bytes = match self.compression {
+            Compression::Raw => self.bytes.clone(),
+            Compression::RunLength => Compression::decompress(&self.bytes, self.length)
+                .ok_or(ArchiveError::MalformedColumn(column))?,
+        };
+        Ok(ColumnReader {
+            column,
+            bytes,
+            position: 0,
+        })
+    }
+}
+
+/// Column Reader
+#[derive(Clone, Debug)]
+struct ColumnReader {
+    /// Column
+    column: Column,
+
+    /// Decompressed Bytes
+    bytes: Vec<u8>,
+
+    /// Position of the Next Value
+    position: usize,
+}
+
+impl ColumnReader {
+    /// Returns `true` if every value of the column was read.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.position == self.bytes.len()
+    }
+
+    /// Decodes the next value of the column.
+    #[inline]
+    fn read<T>(&mut self) -> Result<T, ArchiveError>
+    where
+        T: Decode,
+    {
+        let mut rest = &self.bytes[self.position..];
+        let value = T::decode(&mut rest).map_err(|_| ArchiveError::MalformedColumn(self.column))?;
+        self.position = self.bytes.len() - rest.len();
+        Ok(value)
+    }
+
+    /// Decodes the next vector of at most [`MAX_POST_PARTICIPANTS`] values of the column.
+    #[inline]
+    fn read_participants<T>(&mut self) -> Result<PostParticipants<T>, ArchiveError>
+    where
+        T: Decode,
+    {
+        self.read()
+    }
+
+    /// Decodes the next note of the column, followed by its optional memo.
+    #[inline]
+    fn read_note(&mut self) -> Result<FullIncomingNote, ArchiveError> {
+        let mut note = self.read::<FullIncomingNote>()?;
+        note.memo = self.read()?;
+        Ok(note)
+    }
+
+    /// Reads the number of participants of a post.
+    #[inline]
+    fn read_count(&mut self) -> Result<usize, ArchiveError> {
+        match self.read::<u8>()? as usize {
+            count if count <= MAX_POST_PARTICIPANTS => Ok(count),
+            _ => Err(ArchiveError::MalformedColumn(self.column)),
+        }
+    }
+
+    /// Checks that every value of the column was read.
+    #[inline]
+    fn finish(&self) -> Result<(), ArchiveError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(ArchiveError::MalformedColumn(self.column))
+        }
+    }
+}
+
+/// Archive Writer
+///
+/// Collects batches of posts into the columns of a [`PostArchive`].
+#[derive(Clone, Debug, Default)]
+pub struct ArchiveWriter {
+    /// Number of Batches
+    batches: u64,
+
+    /// Number of Posts
+    posts: u64,
+
+    /// Uncompressed Columns
+    columns: [Vec<u8>; 7],
+}
+
+impl ArchiveWriter {
+    /// Builds a new empty [`ArchiveWriter`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `value` to `column`.
+    #[inline]
+    fn write<T>(&mut self, column: Column, value: &T)
+    where
+        T: Encode + ?Sized,
+    {
+        value
+            .encode(&mut self.columns[column as usize])
+            .expect("Writing to a `Vec<u8>` cannot fail.");
+    }
+
+    /// Appends the batch of `posts` pushed by `account` to the archive.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if any of the `posts` has more than [`MAX_POST_PARTICIPANTS`] senders
+    /// or receivers, or pays a fee, which the ledger never accepts.
+    #[inline]
+    pub fn push(&mut self, account: AccountId, posts: &[TransferPost]) {
+        self.write(Column::Batches, &(account, posts.len() as u64));
+        for post in posts {
+            let body = &post.body;
+            assert!(
+                body.sender_posts.len() <= MAX_POST_PARTICIPANTS
+                    && body.receiver_posts.len() <= MAX_POST_PARTICIPANTS,
+                "Posts have at most `MAX_POST_PARTICIPANTS` senders and receivers."
+            );
+            assert!(body.fee.is_none(), "Posts do not pay fees.");
+            self.write(Column::Posts, &post.authorization_signature);
+            self.write(Column::Posts, &body.asset_id);
+            self.write(Column::Posts, &body.sources);
+            self.write(Column::Posts, &body.sinks);
+            self.write(Column::Posts, &post.sink_accounts);
+            self.write(Column::Posts, &post.epoch);
+            self.write(Column::Posts, &(body.sender_posts.len() as u8));
+            self.write(Column::Posts, &(body.receiver_posts.len() as u8));
+            self.write(Column::Proofs, &body.proof);
+            for sender_post in &body.sender_posts {
+                self.write(Column::Roots, &sender_post.utxo_accumulator_output);
+                self.write(Column::Nullifiers, &sender_post.nullifier);
+            }
+            for receiver_post in &body.receiver_posts {
+                self.write(Column::Utxos, &receiver_post.utxo);
+                self.write(Column::Notes, &receiver_post.note);
+                self.write(Column::Notes, &receiver_post.note.memo);
+            }
+        }
+        self.batches += 1;
+        self.posts += posts.len() as u64;
+    }
+
+    /// Compresses the columns of `self` into a [`PostArchive`].
+    #[inline]
+    pub fn finish(self) -> PostArchive {
+        PostArchive {
+            batches: self.batches,
+            posts: self.posts,
+            columns: self.columns.map(StoredColumn::new),
+        }
+    }
+}
+
+/// Post Archive
+///
+/// See the [module-level documentation](self) for the layout of an archive.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PostArchive {
+    /// Number of Batches
+    batches: u64,
+
+    /// Number of Posts
+    posts: u64,
+
+    /// Compressed Columns
+    columns: [StoredColumn; 7],
+}
+
+impl PostArchive {
+    /// Returns the number of batches in `self`.
+    #[inline]
+    pub fn batch_count(&self) -> u64 {
+        self.batches
+    }
+
+    /// Returns the number of posts in `self`.
+    #[inline]
+    pub fn post_count(&self) -> u64 {
+        self.posts
+    }
+
+    /// Returns the [`Compression`] of `column`.
+    #[inline]
+    pub fn compression(&self, column: Column) -> Compression {
+        self.columns[column as usize].compression
+    }
+
+    /// Returns the length in bytes of `column` before compression.
+    #[inline]
+    pub fn column_len(&self, column: Column) -> usize {
+        self.columns[column as usize].length
+    }
+
+    /// Returns the length in bytes of `column` as it is stored.
+    #[inline]
+    pub fn stored_column_len(&self, column: Column) -> usize {
+        self.columns[column as usize].bytes.len()
+    }
+
+    /// Decompresses `column` for reading.
+    #[inline]
+    fn reader(&self, column: Column) -> Result<ColumnReader, ArchiveError> {
+        self.columns[column as usize].decompress(column)
+    }
+
+    /// Returns an iterator over the batches of `self`, with the accounts which pushed them, in
+    /// the order they were written.
+    #[inline]
+    pub fn batches(&self) -> Result<Batches, ArchiveError> {
+        Ok(Batches {
+            readers: Column::ALL
+                .into_iter()
+                .map(|column| self.reader(column))
+                .collect::<Result<_, _>>()?,
+            done: false,
+        })
+    }
+
+    /// Returns an iterator over the UTXOs and notes of every receiver in `self`, in the order
+    /// they were posted, without decoding the other columns.
+    #[inline]
+    pub fn receivers(&self) -> Result<Receivers, ArchiveError> {
+        Ok(Receivers {
+            utxos: self.reader(Column::Utxos)?,
+            notes: self.reader(Column::Notes)?,
+            done: false,
+        })
+    }
+
+    /// Returns an iterator over the nullifiers of every sender in `self`, in the order they were
+    /// posted, without decoding the other columns.
+    #[inline]
+    pub fn nullifiers(&self) -> Result<Nullifiers, ArchiveError> {
+        Ok(Nullifiers {
+            nullifiers: self.reader(Column::Nullifiers)?,
+            done: false,
+        })
+    }
+
+    /// Returns the wire format of `self`.
+    #[inline]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.batches.to_le_bytes());
+        bytes.extend_from_slice(&self.posts.to_le_bytes());
+        for column in &self.columns {
+            bytes.push(column.compression.tag());
+            bytes.extend_from_slice(&(column.length as u64).to_le_bytes());
+            bytes.extend_from_slice(&(column.bytes.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(&column.bytes);
+        }
+        let digest = Blake2s256::digest(&bytes);
+        bytes.extend_from_slice(&digest);
+        bytes
+    }
+
+    /// Parses a [`PostArchive`] from its wire format in `bytes`, checking its digest. The columns
+    /// are only decompressed and decoded when they are iterated over.
+    #[inline]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ArchiveError> {
+        let mut input = ArchiveInput(bytes);
+        if input.take(MAGIC.len())? != MAGIC {
+            return Err(ArchiveError::InvalidMagic);
+        }
+        match u16::from_le_bytes(input.array()?) {
+            VERSION => {}
+            version => return Err(ArchiveError::UnsupportedVersion(version)),
+        }
+        let batches = u64::from_le_bytes(input.array()?);
+        let posts = u64::from_le_bytes(input.array()?);
+        let mut columns = <[StoredColumn; 7]>::default();
+        for column in &mut columns {
+            let [tag] = input.array()?;
+            column.compression =
+                Compression::from_tag(tag).ok_or(ArchiveError::UnknownCompression(tag))?;
+            column.length = input.length()?;
+            let stored_length = input.length()?;
+            column.bytes = input.take(stored_length)?.to_vec();
+        }
+        let body_length = bytes.len() - input.0.len();
+        let digest = input.array::<32>()?;
+        if !input.0.is_empty() || digest[..] != Blake2s256::digest(&bytes[..body_length])[..] {
+            return Err(ArchiveError::DigestMismatch);
+        }
+        Ok(Self {
+            batches,
+            posts,
+            columns,
+        })
+    }
+}
+
+impl FromIterator<(AccountId, Vec<TransferPost>)> for PostArchive {
+    #[inline]
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (AccountId, Vec<TransferPost>)>,
+    {
+        let mut writer = ArchiveWriter::new();
+        for (account, posts) in iter {
+            writer.push(account, &posts);
+        }
+        writer.finish()
+    }
+}
+
+/// Archive Input
+struct ArchiveInput<'b>(&'b [u8]);
+
+impl<'b> ArchiveInput<'b> {
+    /// Takes the next `len` bytes of the input.
+    #[inline]
+    fn take(&mut self, len: usize) -> Result<&'b [u8], ArchiveError> {
+        if self.0.len() < len {
+            return Err(ArchiveError::Truncated);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    /// Takes the next `N` bytes of the input.
+    #[inline]
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ArchiveError> {
+        Ok(self
+            .take(N)?
+            .try_into()
+            .expect("The slice has exactly `N` bytes."))
+    }
+
+    /// Takes the next column length of the input.
+    #[inline]
+    fn length(&mut self) -> Result<usize, ArchiveError> {
+        match u64::from_le_bytes(self.array()?) {
+            len if len > MAX_COLUMN_LENGTH as u64 => Err(ArchiveError::ColumnTooLarge(len)),
+            len => Ok(len as usize),
+        }
+    }
+}
+
+/// Batch Iterator
+///
+/// This `struct` is created by the [`batches`](PostArchive::batches) method on [`PostArchive`].
+/// See its documentation for more.
+#[derive(Clone, Debug)]
+pub struct Batches {
+    /// Column Readers
+    ///
+    /// There is one reader for every column, in the order of [`Column::ALL`].
+    readers: Vec<ColumnReader>,
+
+    /// Done Flag
+    done: bool,
+}
+
+impl Batches {
+    /// Returns the reader of `column`.
+    #[inline]
+    fn reader(&mut self, column: Column) -> &mut ColumnReader {
+        &mut self.readers[column as usize]
+    }
+
+    /// Decodes the next post.
+    #[inline]
+    fn read_post(&mut self) -> Result<TransferPost, ArchiveError> {
+        let posts = self.reader(Column::Posts);
+        let authorization_signature = posts.read()?;
+        let asset_id = posts.read()?;
+        let sources = posts.read_participants()?;
+        let sinks = posts.read_participants()?;
+        let sink_accounts = posts.read_participants()?;
+        let epoch = posts.read()?;
+        let senders = posts.read_count()?;
+        let receivers = posts.read_count()?;
+        let proof = self.reader(Column::Proofs).read()?;
+        let mut sender_posts = PostParticipants::with_capacity(senders);
+        for _ in 0..senders {
+            let utxo_accumulator_output = self.reader(Column::Roots).read()?;
+            let nullifier = self.reader(Column::Nullifiers).read()?;
+            sender_posts
+                .try_push(SenderPost::new(utxo_accumulator_output, nullifier))
+                .expect("The sender count was already checked against the bound.");
+        }
+        let mut receiver_posts = PostParticipants::with_capacity(receivers);
+        for _ in 0..receivers {
+            let utxo = self.reader(Column::Utxos).read()?;
+            let note = self.reader(Column::Notes).read_note()?;
+            receiver_posts
+                .try_push(ReceiverPost::new(utxo, note))
+                .expect("The receiver count was already checked against the bound.");
+        }
+        Ok(TransferPost {
+            authorization_signature,
+            body: TransferPostBody {
+                asset_id,
+                sources,
+                sender_posts,
+                receiver_posts,
+                sinks,
+                fee: None,
+                proof,
+            },
+            sink_accounts,
+            epoch,
+        })
+    }
+
+    /// Decodes the next batch, or checks that every column was read if there are no batches
+    /// left.
+    #[inline]
+    fn read_batch(&mut self) -> Result<Option<(AccountId, Vec<TransferPost>)>, ArchiveError> {
+        if self.reader(Column::Batches).is_empty() {
+            for reader in &self.readers {
+                reader.finish()?;
+            }
+            return Ok(None);
+        }
+        let (account, count) = self.reader(Column::Batches).read::<(AccountId, u64)>()?;
+        let mut posts = Vec::new();
+        for _ in 0..count {
+            posts.push(self.read_post()?);
+        }
+        Ok(Some((account, posts)))
+    }
+}
+
+impl Iterator for Batches {
+    type Item = Result<(AccountId, Vec<TransferPost>), ArchiveError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let batch = self.read_batch().transpose();
+        self.done = !matches!(batch, Some(Ok(_)));
+        batch
+    }
+}
+
+/// Receiver Iterator
+///
+/// This `struct` is created by the [`receivers`](PostArchive::receivers) method on
+/// [`PostArchive`]. See its documentation for more.
+#[derive(Clone, Debug)]
+pub struct Receivers {
+    /// UTXO Column Reader
+    utxos: ColumnReader,
+
+    /// Note Column Reader
+    notes: ColumnReader,
+
+    /// Done Flag
+    done: bool,
+}
+
+impl Receivers {
+    /// Decodes the next receiver, or checks that both columns were read if there are no
+    /// receivers left.
+    #[inline]
+    fn read_receiver(&mut self) -> Result<Option<(Utxo, FullIncomingNote)>, ArchiveError> {
+        if self.utxos.is_empty() {
+            self.notes.finish()?;
+            return Ok(None);
+        }
+        Ok(Some((self.utxos.read()?, self.notes.read_note()?)))
+    }
+}
+
+impl Iterator for Receivers {
+    type Item = Result<(Utxo, FullIncomingNote), ArchiveError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let receiver = self.read_receiver().transpose();
+        self.done = !matches!(receiver, Some(Ok(_)));
+        receiver
+    }
+}
+
+/// Nullifier Iterator
+///
+/// This `struct` is created by the [`nullifiers`](PostArchive::nullifiers) method on
+/// [`PostArchive`]. See its documentation for more.
+#[derive(Clone, Debug)]
+pub struct Nullifiers {
+    /// Nullifier Column Reader
+    nullifiers: ColumnReader,
+
+    /// Done Flag
+    done: bool,
+}
+
+impl Iterator for Nullifiers {
+    type Item = Result<Nullifier, ArchiveError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.nullifiers.is_empty() {
+            return None;
+        }
+        let nullifier = self.nullifiers.read();
+        self.done = nullifier.is_err();
+        Some(nullifier)
+    }
+}
+
+impl Ledger {
+    /// Pushes every batch of `archive` to the ledger as in [`push`](Self::push), in the order
+    /// they were archived, returning whether each of them was accepted.
+    ///
+    /// The posts are validated again, so the ledger must start from the state the archived
+    /// batches were first pushed to. If the archive is malformed, the batches before the
+    /// malformed one stay pushed.
+    #[inline]
+    pub fn replay(&mut self, archive: &PostArchive) -> Result<Vec<bool>, ArchiveError> {
+        archive
+            .batches()?
+            .map(|batch| batch.map(|(account, posts)| self.push(account, posts)))
+            .collect()
+    }
+}
@@ -0,0 +1,241 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pre-Funded Wallet Fixtures
+//!
+//! Test suites which need wallets holding private notes would otherwise have to sign and prove a
+//! [`ToPrivate`](crate::config::ToPrivate) transaction for every note. Instead, [`generate`] mints
+//! the notes of every [`WalletSpec`] directly into the ledger with a single trusted [`StateDiff`],
+//! so no proof is ever built, and returns the [`WalletFixture`]s from which the signers owning
+//! those notes can be rebuilt.
+//!
+//! Fixtures are deterministic: the same seed and the same specifications always produce the same
+//! mnemonics, accounts and UTXOs on a fresh ledger. Every wallet draws its randomness from its own
+//! [`ChaCha20Rng`] stream, so adding wallets at the end of the specifications does not change the
+//! wallets which come before them.
+
+use crate::{
+    config::{
+        utxo::MerkleTreeConfiguration, AccountId, Address, Asset, MultiProvingContext, Parameters,
+        Receiver, UtxoAccumulatorModel,
+    },
+    key::Mnemonic,
+    signer::{
+        base::Signer,
+        functions::{accounts_from_mnemonic, address_from_mnemonic, new_signer_from_model},
+        SyncRequest,
+    },
+    simulation::{
+        account_id_from_u64,
+        ledger::{Ledger, MerkleForestIndex, StateDiff},
+    },
+};
+use alloc::vec::Vec;
+use manta_accounting::{
+    transfer::diff::{BalanceDelta, InsertedUtxo},
+    wallet::ledger::LeafIndex,
+};
+use manta_crypto::{
+    accumulator::ItemHashFunction,
+    merkle_tree::forest::Configuration,
+    rand::{ChaCha20Rng, SeedableRng},
+};
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Wallet Specification
+///
+/// Private notes and public balances that a fixture wallet starts with.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct WalletSpec {
+    /// Private Notes
+    ///
+    /// Every asset in this list is minted as a separate note.
+    pub notes: Vec<Asset>,
+
+    /// Public Balances
+    pub public_balances: Vec<Asset>,
+}
+
+impl WalletSpec {
+    /// Adds a private note holding `asset` to `self`.
+    #[inline]
+    pub fn with_note(mut self, asset: Asset) -> Self {
+        self.notes.push(asset);
+        self
+    }
+
+    /// Adds `count`-many private notes to `self`, which hold `asset` split as evenly as possible.
+    /// The first notes receive the remainder of the split.
+    #[inline]
+    pub fn with_split_notes(mut self, asset: Asset, count: u128) -> Self {
+        if count == 0 {
+            return self;
+        }
+        let (quotient, remainder) = (asset.value / count, asset.value % count);
+        self.notes.extend((0..count).map(|i| {
+            Asset::new(
+                asset.id,
+                if i < remainder {
+                    quotient + 1
+                } else {
+                    quotient
+                },
+            )
+        }));
+        self
+    }
+
+    /// Adds a public balance of `asset` to `self`.
+    #[inline]
+    pub fn with_public_balance(mut self, asset: Asset) -> Self {
+        self.public_balances.push(asset);
+        self
+    }
+}
+
+/// Wallet Fixture
+#[derive(Clone, Debug)]
+pub struct WalletFixture {
+    /// Mnemonic
+    ///
+    /// Seed of the wallet, from which its spending key is derived.
+    pub mnemonic: Mnemonic,
+
+    /// Public Account
+    pub account: AccountId,
+
+    /// Address
+    pub address: Address,
+
+    /// Specification
+    pub spec: WalletSpec,
+}
+
+impl WalletFixture {
+    /// Builds a new signer for the wallet of `self`, which has not been synchronized yet.
+    #[inline]
+    pub fn signer(
+        &self,
+        proving_context: &MultiProvingContext,
+        parameters: &Parameters,
+        utxo_accumulator_model: &UtxoAccumulatorModel,
+    ) -> Signer {
+        let mut signer = new_signer_from_model(
+            parameters.clone(),
+            proving_context.clone(),
+            utxo_accumulator_model,
+        );
+        signer.load_accounts(accounts_from_mnemonic(self.mnemonic.clone()));
+        signer
+    }
+
+    /// Builds a new signer for the wallet of `self`, synchronized with `ledger` from the origin.
+    #[inline]
+    pub fn synced_signer(
+        &self,
+        proving_context: &MultiProvingContext,
+        parameters: &Parameters,
+        utxo_accumulator_model: &UtxoAccumulatorModel,
+        ledger: &Ledger,
+    ) -> Signer {
+        let mut signer = self.signer(proving_context, parameters, utxo_accumulator_model);
+        let origin_checkpoint = Default::default();
+        signer
+            .sync(SyncRequest {
+                data: ledger.pull(&origin_checkpoint).data,
+                origin_checkpoint,
+            })
+            .expect("Synchronizing a fixture signer with its ledger is not allowed to fail.");
+        signer
+    }
+}
+
+/// Generates one [`WalletFixture`] for each of the `specs`, deterministically from `seed`,
+/// minting their notes and depositing their public balances into `ledger` as a single block.
+///
+/// The account of the `i`-th fixture is [`account_id_from_u64`]`(i)`, so `ledger` should be a
+/// fresh ledger, or at least one whose first accounts are not used yet.
+#[inline]
+pub fn generate(ledger: &mut Ledger, seed: [u8; 32], specs: Vec<WalletSpec>) -> Vec<WalletFixture> {
+    let mut diff = StateDiff::default();
+    let mut next_indices = HashMap::<MerkleForestIndex, LeafIndex>::new();
+    let mut asset_notes = HashMap::new();
+    let fixtures = specs
+        .into_iter()
+        .enumerate()
+        .map(|(i, spec)| {
+            let mut rng = ChaCha20Rng::from_seed(seed);
+            rng.set_stream(i as u64);
+            let mnemonic = Mnemonic::sample(&mut rng);
+            let address = address_from_mnemonic(mnemonic.clone(), &ledger.parameters);
+            let account = account_id_from_u64(i as u64);
+            for asset in &spec.notes {
+                let post = Receiver::sample(
+                    &ledger.parameters,
+                    address,
+                    *asset,
+                    Default::default(),
+                    &mut rng,
+                )
+                .into_post();
+                let tree_index = MerkleTreeConfiguration::tree_index(
+                    &ledger.parameters.item_hash(&post.utxo, &mut ()),
+                );
+                let next_index = next_indices
+                    .entry(tree_index)
+                    .or_insert_with(|| ledger.next_utxo_index(tree_index));
+                diff.utxos.push(InsertedUtxo {
+                    index: (tree_index, *next_index),
+                    utxo: post.utxo,
+                    note: post.note,
+                });
+                *next_index = next_index.advance(1);
+                *asset_notes.entry(asset.id).or_insert(0) += 1;
+            }
+            diff.balance_deltas
+                .extend(
+                    spec.public_balances
+                        .iter()
+                        .map(|asset| BalanceDelta::Deposit {
+                            account,
+                            asset: *asset,
+                        }),
+                );
+            WalletFixture {
+                mnemonic,
+                account,
+                address,
+                spec,
+            }
+        })
+        .collect();
+    assert!(
+        ledger.apply(diff),
+        "The fixture notes are indexed against the current state of the ledger."
+    );
+    for (asset_id, notes) in asset_notes {
+        ledger.stats.record_asset_notes(asset_id, notes);
+    }
+    fixtures
+}
@@ -0,0 +1,554 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Speculative Ledger Forks
+//!
+//! A [`LedgerFork`] borrows a [`Ledger`] and records every state change made through it in a
+//! copy-on-write overlay. Posts can be validated and applied to the fork as if it were the ledger
+//! itself, for example when checking a candidate block in a mempool, and then the fork is either
+//! [`discard`](LedgerFork::discard)ed or [`commit`](LedgerFork::commit)ted into a [`StateDiff`].
+//! Only the state touched by the fork is copied: the nullifier set is read through a
+//! [`NullifierOverlay`], the UTXO forest through a [`ForestOverlay`] which only clones the trees
+//! that receive new leaves, and the UTXO set and the shards are never cloned.
+
+use crate::{
+    config::{
+        utxo::{self, AssetId, AssetValue, FullIncomingNote, MerkleTreeConfiguration, Parameters},
+        AccountId, Config, Nullifier, TransferPost, Utxo,
+    },
+    simulation::ledger::{
+        check_sink_accounts_with, check_source_accounts_with, history::UtxoAccumulatorRoots,
+        nullifiers::NullifierSet, public_participants, Ledger, MerkleForestIndex,
+        ReceiverLedgerError, SenderLedgerError, StateDiff, TransferLedgerError, Wrap, WrapPair,
+    },
+};
+use alloc::vec::Vec;
+use manta_accounting::transfer::{
+    diff::{BalanceDelta, InsertedUtxo},
+    fee::{FeeSchedule, InsufficientFee},
+    receiver::{ReceiverLedger, Registration, RegistrationError},
+    sender::SenderLedger,
+    utxo::protocol::{NullifierLookup, NullifierOverlay},
+    Asset, InvalidSinkAccount, InvalidSourceAccount, NotePolicy, SinkPostingKey, SourcePostingKey,
+    TransferLedger, TransferLedgerSuperPostingKey, TransferPostingKeyRef, UtxoAccumulatorOutput,
+};
+use manta_crypto::{
+    accumulator::ItemHashFunction,
+    merkle_tree::{
+        forest::{Configuration, FixedIndex, ForestOverlay, TreeArray},
+        single_path::SinglePath,
+    },
+};
+use std::collections::{HashMap, HashSet};
+
+/// UTXO Forest Overlay Type
+type UtxoForestOverlay<'l> = ForestOverlay<
+    'l,
+    MerkleTreeConfiguration,
+    TreeArray<
+        MerkleTreeConfiguration,
+        SinglePath<MerkleTreeConfiguration>,
+        { MerkleTreeConfiguration::FOREST_WIDTH },
+    >,
+>;
+
+/// Ledger Fork
+#[derive(Debug)]
+pub struct LedgerFork<'l> {
+    /// Base Ledger
+    ledger: &'l Ledger,
+
+    /// Nullifier Set
+    nullifiers: NullifierOverlay<'l, utxo::Config, NullifierSet>,
+
+    /// Registered UTXOs
+    utxos: HashSet<Utxo>,
+
+    /// Number of UTXOs Registered in Each Tree
+    utxo_counts: HashMap<MerkleForestIndex, usize>,
+
+    /// Recorded Changes
+    diff: StateDiff,
+
+    /// UTXO Forest
+    utxo_forest: UtxoForestOverlay<'l>,
+
+    /// Modified Account Table
+    accounts: HashMap<AccountId, HashMap<AssetId, AssetValue>>,
+
+    /// Number of Notes Registered by Each Account
+    registrations: HashMap<AccountId, usize>,
+
+    /// Registrant
+    ///
+    /// Account whose posts are being pushed, which registers their notes.
+    registrant: Option<AccountId>,
+
+    /// Pending Fee
+    ///
+    /// Fee of the post being pushed, which is reserved out of the public balance of the registrant
+    /// until the post is applied.
+    pending_fee: AssetValue,
+}
+
+impl<'l> LedgerFork<'l> {
+    /// Builds a new [`LedgerFork`] on top of `ledger`.
+    #[inline]
+    fn new(ledger: &'l Ledger) -> Self {
+        Self {
+            ledger,
+            nullifiers: ledger.nullifiers.fork(),
+            utxos: Default::default(),
+            utxo_counts: Default::default(),
+            diff: Default::default(),
+            utxo_forest: ledger.utxo_forest.fork(),
+            accounts: Default::default(),
+            registrations: Default::default(),
+            registrant: None,
+            pending_fee: 0,
+        }
+    }
+
+    /// Returns the public balance of `account` in assets with `id` as seen by `self`.
+    #[inline]
+    fn balance(&self, account: &AccountId, id: &AssetId) -> Option<AssetValue> {
+        match self.accounts.get(account) {
+            Some(balances) => balances.get(id).copied(),
+            _ => self.ledger.accounts.get(account)?.get(id).copied(),
+        }
+    }
+
+    /// Returns the public balances of `account` for modification, copying them from the base
+    /// ledger on first access.
+    #[inline]
+    fn balances_mut(&mut self, account: AccountId) -> Option<&mut HashMap<AssetId, AssetValue>> {
+        if !self.accounts.contains_key(&account) {
+            let balances = self.ledger.accounts.get(&account)?.clone();
+            self.accounts.insert(account, balances);
+        }
+        self.accounts.get_mut(&account)
+    }
+
+    /// Validates and applies `posts` sent from `account` to `self`, returning `false` as soon as
+    /// one of them is invalid. The posts before the invalid one stay applied to `self`.
+    #[inline]
+    pub fn push(&mut self, account: AccountId, posts: Vec<TransferPost>) -> bool {
+        self.registrant = Some(account);
+        let accepted = self.push_posts(account, posts);
+        self.registrant = None;
+        self.pending_fee = 0;
+        accepted
+    }
+
+    /// Validates and applies every element of `posts` to `self`, returning `false` as soon as one
+    /// of them is invalid.
+    #[inline]
+    fn push_posts(&mut self, account: AccountId, posts: Vec<TransferPost>) -> bool {
+        for post in posts {
+            let (sources, sinks) = match public_participants(account, &post) {
+                Some(participants) => participants,
+                _ => return false,
+            };
+            if !self.ledger.accepts_shape(&post) {
+                return false;
+            }
+            let parameters = match self.ledger.epoch_parameters(post.epoch) {
+                Ok(parameters) => parameters,
+                _ => return false,
+            };
+            let notes = post.body.receiver_posts.len();
+            self.pending_fee = self.ledger.post_fee(&post);
+            match post.validate(parameters, &*self, sources, sinks) {
+                Ok(posting_key) => {
+                    if posting_key.post(&mut *self, &()).is_err() {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+            self.charge_fee(account);
+            *self.registrations.entry(account).or_default() += notes;
+        }
+        true
+    }
+
+    /// Returns the part of the public balance of `account` in assets with `id` which is reserved
+    /// for the pending fee.
+    #[inline]
+    fn reserved_fee(&self, account: &AccountId, id: &AssetId) -> AssetValue {
+        match (self.ledger.fee_schedule, self.registrant) {
+            (Some(schedule), Some(registrant))
+                if registrant == *account && schedule.asset_id == *id =>
+            {
+                self.pending_fee
+            }
+            _ => 0,
+        }
+    }
+
+    /// Charges the pending fee to the public balance of `account`, recording it in the changes
+    /// made to `self`.
+    #[inline]
+    fn charge_fee(&mut self, account: AccountId) {
+        let fee = core::mem::take(&mut self.pending_fee);
+        if let (Some(schedule), true) = (self.ledger.fee_schedule, fee > 0) {
+            *self
+                .balances_mut(account)
+                .and_then(|balances| balances.get_mut(&schedule.asset_id))
+                .expect("The fee was checked when validating the post.") -= fee;
+            self.diff.balance_deltas.push(BalanceDelta::Withdraw {
+                account,
+                asset: Asset::<Config>::new(schedule.asset_id, fee),
+            });
+        }
+    }
+
+    /// Withdraws from the `sources` and deposits into the `sinks` in assets with `asset_id`,
+    /// recording every change in the changes made to `self`.
+    #[inline]
+    fn apply_public_balances(
+        &mut self,
+        asset_id: AssetId,
+        sources: Vec<WrapPair<AccountId, AssetValue>>,
+        sinks: Vec<WrapPair<AccountId, AssetValue>>,
+    ) -> Result<(), TransferLedgerError> {
+        for WrapPair(account_id, withdraw) in sources {
+            if self.ledger.is_frozen(&account_id) {
+                return Err(TransferLedgerError::FrozenAccount(account_id));
+            }
+            *self
+                .balances_mut(account_id)
+                .and_then(|balances| balances.get_mut(&asset_id))
+                .ok_or(TransferLedgerError::InvalidSourceAccount(
+                    InvalidSourceAccount {
+                        account_id,
+                        asset_id,
+                        withdraw,
+                    },
+                ))? -= withdraw;
+            self.diff.balance_deltas.push(BalanceDelta::Withdraw {
+                account: account_id,
+                asset: Asset::<Config>::new(asset_id, withdraw),
+            });
+        }
+        for WrapPair(account_id, deposit) in sinks {
+            if self.ledger.is_frozen(&account_id) {
+                return Err(TransferLedgerError::FrozenAccount(account_id));
+            }
+            *self
+                .balances_mut(account_id)
+                .ok_or(TransferLedgerError::InvalidSinkAccount(
+                    InvalidSinkAccount {
+                        account_id,
+                        asset_id,
+                        deposit,
+                    },
+                ))?
+                .entry(asset_id)
+                .or_default() += deposit;
+            self.diff.balance_deltas.push(BalanceDelta::Deposit {
+                account: account_id,
+                asset: Asset::<Config>::new(asset_id, deposit),
+            });
+        }
+        Ok(())
+    }
+
+    /// Applies the public balance change in `delta` to the balances of `self`, without recording
+    /// it in the changes made to `self`.
+    #[inline]
+    fn apply_balance_delta(&mut self, delta: BalanceDelta<Config>) {
+        match delta {
+            BalanceDelta::Withdraw { account, asset } => {
+                if let Some(balance) = self
+                    .balances_mut(account)
+                    .and_then(|balances| balances.get_mut(&asset.id))
+                {
+                    *balance -= asset.value;
+                }
+            }
+            BalanceDelta::Deposit { account, asset } => {
+                if let Some(balances) = self.balances_mut(account) {
+                    *balances.entry(asset.id).or_default() += asset.value;
+                }
+            }
+        }
+    }
+
+    /// Returns the roots of the trees of the UTXO forest as seen by `self`.
+    #[inline]
+    pub fn utxo_accumulator_roots(&self) -> UtxoAccumulatorRoots {
+        (0..MerkleTreeConfiguration::FOREST_WIDTH)
+            .map(|i| {
+                *self
+                    .utxo_forest
+                    .get(MerkleForestIndex::from_index(i))
+                    .root()
+            })
+            .collect()
+    }
+
+    /// Drops all the changes made to `self`, leaving the base ledger untouched.
+    #[inline]
+    pub fn discard(self) {
+        self.nullifiers.discard();
+        self.utxo_forest.discard();
+    }
+
+    /// Finishes the speculation, returning the changes made to `self` so that they can be written
+    /// to the base ledger with [`Ledger::apply`].
+    ///
+    /// The trees modified by `self` are dropped, since [`Ledger::apply`] pushes the inserted UTXOs
+    /// again to record every intermediate root in the root history of the ledger.
+    #[inline]
+    pub fn commit(self) -> StateDiff {
+        let mut diff = self.diff;
+        diff.nullifiers = self.nullifiers.commit();
+        self.utxo_forest.discard();
+        diff
+    }
+}
+
+impl Ledger {
+    /// Forks `self`, returning a [`LedgerFork`] which records changes without modifying `self`.
+    #[inline]
+    pub fn fork(&self) -> LedgerFork<'_> {
+        LedgerFork::new(self)
+    }
+}
+
+impl<'l> SenderLedger<Parameters> for LedgerFork<'l> {
+    type ValidNullifier = Wrap<Nullifier>;
+    type ValidUtxoAccumulatorOutput = Wrap<UtxoAccumulatorOutput<Config>>;
+    type SuperPostingKey = (Wrap<()>, ());
+    type Error = SenderLedgerError;
+
+    #[inline]
+    fn is_unspent(&self, nullifier: Nullifier) -> Result<Self::ValidNullifier, Self::Error> {
+        if nullifier.nullifier.is_legacy && !self.ledger.accepts_legacy_nullifiers() {
+            return Err(SenderLedgerError::LegacyNullifier);
+        }
+        if self.nullifiers.contains(&nullifier) {
+            Err(SenderLedgerError::AssetSpent)
+        } else {
+            Ok(Wrap(nullifier))
+        }
+    }
+
+    #[inline]
+    fn has_matching_utxo_accumulator_output(
+        &self,
+        output: UtxoAccumulatorOutput<Config>,
+    ) -> Result<Self::ValidUtxoAccumulatorOutput, Self::Error> {
+        if output == Default::default() {
+            return Ok(Wrap(output));
+        }
+        if (0..MerkleTreeConfiguration::FOREST_WIDTH).any(|i| {
+            self.utxo_forest
+                .get(MerkleForestIndex::from_index(i))
+                .root()
+                == &output
+        }) {
+            Ok(Wrap(output))
+        } else {
+            Err(SenderLedgerError::InvalidUtxoAccumulatorOutput)
+        }
+    }
+
+    #[inline]
+    fn spend(
+        &mut self,
+        super_key: &Self::SuperPostingKey,
+        utxo_accumulator_output: Self::ValidUtxoAccumulatorOutput,
+        nullifier: Self::ValidNullifier,
+    ) -> Result<(), Self::Error> {
+        let _ = (utxo_accumulator_output, super_key);
+        self.nullifiers.insert(nullifier.0);
+        Ok(())
+    }
+}
+
+impl<'l> ReceiverLedger<Parameters> for LedgerFork<'l> {
+    type ValidUtxo = Wrap<Utxo>;
+    type SuperPostingKey = (Wrap<()>, ());
+    type Error = ReceiverLedgerError;
+
+    #[inline]
+    fn is_not_registered(&self, utxo: Utxo) -> Result<Self::ValidUtxo, Self::Error> {
+        if self.utxos.contains(&utxo) || self.ledger.utxos.contains(&utxo) {
+            Err(ReceiverLedgerError::AssetRegistered)
+        } else {
+            Ok(Wrap(utxo))
+        }
+    }
+
+    #[inline]
+    fn check_registration(&self, registration: Registration) -> Result<(), RegistrationError> {
+        match (self.ledger.registration_limit, self.registrant) {
+            (Some(limit), Some(account)) => limit.check(
+                self.ledger.registered_notes(&account)
+                    + self
+                        .registrations
+                        .get(&account)
+                        .copied()
+                        .unwrap_or_default(),
+                registration.receivers,
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    #[inline]
+    fn register(
+        &mut self,
+        super_key: &Self::SuperPostingKey,
+        utxo: Self::ValidUtxo,
+        note: FullIncomingNote,
+    ) -> Result<(), Self::Error> {
+        let _ = super_key;
+        let utxo_hash = self.ledger.parameters.item_hash(&utxo.0, &mut ());
+        let tree_index = MerkleTreeConfiguration::tree_index(&utxo_hash);
+        let count = self.utxo_counts.entry(tree_index).or_default();
+        let index = (
+            tree_index,
+            self.ledger.next_utxo_index(tree_index).advance(*count),
+        );
+        *count += 1;
+        self.utxos.insert(utxo.0);
+        self.utxo_forest.push(&utxo_hash);
+        self.diff.utxos.push(InsertedUtxo {
+            index,
+            utxo: utxo.0,
+            note,
+        });
+        Ok(())
+    }
+}
+
+impl<'l> TransferLedger<Config> for LedgerFork<'l> {
+    type Event = ();
+    type ValidSourceAccount = WrapPair<AccountId, AssetValue>;
+    type ValidSinkAccount = WrapPair<AccountId, AssetValue>;
+    type ValidProof = Wrap<()>;
+    type SuperPostingKey = ();
+    type Error = TransferLedgerError;
+
+    #[inline]
+    fn check_source_accounts<I>(
+        &self,
+        asset_id: &AssetId,
+        sources: I,
+    ) -> Result<Vec<Self::ValidSourceAccount>, InvalidSourceAccount<Config, AccountId>>
+    where
+        I: Iterator<Item = (AccountId, AssetValue)>,
+    {
+        check_source_accounts_with(asset_id, sources, |account_id, asset_id| {
+            let balance = self.balance(account_id, asset_id)?;
+            Some(balance.saturating_sub(self.reserved_fee(account_id, asset_id)))
+        })
+    }
+
+    #[inline]
+    fn check_sink_accounts<I>(
+        &self,
+        asset_id: &AssetId,
+        sinks: I,
+    ) -> Result<Vec<Self::ValidSinkAccount>, InvalidSinkAccount<Config, AccountId>>
+    where
+        I: Iterator<Item = (AccountId, AssetValue)>,
+    {
+        check_sink_accounts_with(asset_id, sinks, |account_id| {
+            self.accounts.contains_key(account_id) || self.ledger.accounts.contains_key(account_id)
+        })
+    }
+
+    #[inline]
+    fn is_valid(
+        &self,
+        posting_key: TransferPostingKeyRef<Config, Self>,
+    ) -> Result<(Self::ValidProof, Self::Event), <Self as TransferLedger<Config>>::Error> {
+        self.ledger.verify(posting_key)?;
+        Ok((Wrap(()), ()))
+    }
+
+    #[inline]
+    fn update_public_balances(
+        &mut self,
+        super_key: &TransferLedgerSuperPostingKey<Config, Self>,
+        asset_id: AssetId,
+        sources: Vec<SourcePostingKey<Config, Self>>,
+        sinks: Vec<SinkPostingKey<Config, Self>>,
+        proof: Self::ValidProof,
+    ) -> Result<Vec<BalanceDelta<Config>>, <Self as TransferLedger<Config>>::Error> {
+        let _ = (proof, super_key);
+        let applied = self.diff.balance_deltas.len();
+        match self.apply_public_balances(asset_id, sources, sinks) {
+            Ok(()) => Ok(self.diff.balance_deltas[applied..].to_vec()),
+            Err(err) => {
+                let rejected = StateDiff {
+                    balance_deltas: self.diff.balance_deltas.split_off(applied),
+                    ..Default::default()
+                };
+                for delta in rejected.balance_rollback() {
+                    self.apply_balance_delta(delta);
+                }
+                Err(err)
+            }
+        }
+    }
+
+    #[inline]
+    fn rollback_public_balances<I>(
+        &mut self,
+        super_key: &TransferLedgerSuperPostingKey<Config, Self>,
+        rollback: I,
+    ) where
+        I: IntoIterator<Item = BalanceDelta<Config>>,
+    {
+        let _ = super_key;
+        for delta in rollback {
+            self.diff.balance_deltas.pop();
+            self.apply_balance_delta(delta);
+        }
+    }
+
+    #[inline]
+    fn note_policy(&self) -> Option<&dyn NotePolicy<Config>> {
+        self.ledger.note_policy()
+    }
+
+    #[inline]
+    fn fee_schedule(&self) -> Option<&FeeSchedule<AssetId, AssetValue>> {
+        self.ledger.fee_schedule.as_ref()
+    }
+
+    #[inline]
+    fn check_fee(&self, fee: &AssetValue) -> Result<(), InsufficientFee<AssetValue>> {
+        let available = match (self.ledger.fee_schedule, self.registrant) {
+            (Some(schedule), Some(registrant)) => self
+                .balance(&registrant, &schedule.asset_id)
+                .unwrap_or_default(),
+            _ => 0,
+        };
+        if available < *fee {
+            return Err(InsufficientFee {
+                required: *fee,
+                available,
+            });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! UTXO Accumulator Root History
+//!
+//! The [`Ledger`](super::Ledger) only stores the current path of every tree in its
+//! [`UtxoMerkleForest`], so it records the roots of the trees in a [`RootHistory`] as they grow.
+//! Every tree keeps the roots it had for its latest [`capacity`](RootHistory::capacity)-many leaf
+//! counts, and the older ones are pruned.
+
+use crate::{
+    config::{
+        utxo::{Checkpoint, MerkleTreeConfiguration},
+        Config,
+    },
+    simulation::ledger::{MerkleForestIndex, UtxoMerkleForest},
+};
+use alloc::{collections::VecDeque, vec::Vec};
+use manta_accounting::{transfer::UtxoAccumulatorOutput, wallet::ledger::RootHistoryError};
+use manta_crypto::merkle_tree::{
+    forest::{FixedIndex, Forest},
+    Tree,
+};
+use manta_util::Array;
+
+/// UTXO Accumulator Roots
+///
+/// The roots of every tree in the [`UtxoMerkleForest`], in the order of their indices.
+pub type UtxoAccumulatorRoots =
+    Array<UtxoAccumulatorOutput<Config>, { MerkleTreeConfiguration::FOREST_WIDTH }>;
+
+/// Tree Root History
+#[derive(Clone, Debug)]
+struct TreeHistory {
+    /// Leaf Count of the Oldest Root
+    offset: usize,
+
+    /// Roots
+    ///
+    /// The `i`-th root is the root of the tree when it had `offset + i`-many leaves.
+    roots: VecDeque<UtxoAccumulatorOutput<Config>>,
+}
+
+impl TreeHistory {
+    /// Returns the number of leaves of the tree after its latest recorded root.
+    #[inline]
+    fn leaf_count(&self) -> usize {
+        self.offset + self.roots.len() - 1
+    }
+}
+
+/// UTXO Accumulator Root History
+#[derive(Clone, Debug)]
+pub struct RootHistory {
+    /// Tree Histories
+    trees: Vec<TreeHistory>,
+
+    /// Number of Roots Kept for Each Tree
+    capacity: usize,
+}
+
+impl RootHistory {
+    /// Builds a new [`RootHistory`] starting from the current roots of `forest` and keeping
+    /// `capacity`-many roots for each tree.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `capacity` is zero.
+    #[inline]
+    pub fn new(forest: &UtxoMerkleForest, capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "The root history must keep at least one root."
+        );
+        Self {
+            trees: (0..MerkleTreeConfiguration::FOREST_WIDTH)
+                .map(|i| {
+                    let tree = forest.forest.get(MerkleForestIndex::from_index(i));
+                    TreeHistory {
+                        offset: tree.len(),
+                        roots: [*tree.root()].into_iter().collect(),
+                    }
+                })
+                .collect(),
+            capacity,
+        }
+    }
+
+    /// Returns the number of roots kept for each tree.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Sets the number of roots kept for each tree to `capacity`, pruning the oldest roots if
+    /// there are more than `capacity`-many of them.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `capacity` is zero.
+    #[inline]
+    pub fn set_capacity(&mut self, capacity: usize) {
+        assert!(
+            capacity > 0,
+            "The root history must keep at least one root."
+        );
+        self.capacity = capacity;
+        for index in 0..self.trees.len() {
+            self.prune(index);
+        }
+    }
+
+    /// Records `root` as the root of the tree at `index` after it grew by one leaf.
+    #[inline]
+    pub fn push(&mut self, index: MerkleForestIndex, root: UtxoAccumulatorOutput<Config>) {
+        let index = usize::from(index);
+        self.trees[index].roots.push_back(root);
+        self.prune(index);
+    }
+
+    /// Rewinds the history of the tree at `index` to the time it had `leaf_count`-many leaves and
+    /// `root` as its root, dropping the roots recorded after that.
+    #[inline]
+    pub fn rewind(
+        &mut self,
+        index: MerkleForestIndex,
+        leaf_count: usize,
+        root: UtxoAccumulatorOutput<Config>,
+    ) {
+        let tree = &mut self.trees[usize::from(index)];
+        if leaf_count < tree.offset {
+            tree.offset = leaf_count;
+            tree.roots.clear();
+            tree.roots.push_back(root);
+        } else {
+            tree.roots.truncate(leaf_count - tree.offset + 1);
+        }
+    }
+
+    /// Returns the root of the tree at `index` when it had `leaf_count`-many leaves.
+    #[inline]
+    pub fn root(
+        &self,
+        index: MerkleForestIndex,
+        leaf_count: usize,
+    ) -> Result<UtxoAccumulatorOutput<Config>, RootHistoryError> {
+        let tree = &self.trees[usize::from(index)];
+        if leaf_count < tree.offset {
+            Err(RootHistoryError::Pruned)
+        } else if leaf_count > tree.leaf_count() {
+            Err(RootHistoryError::Future)
+        } else {
+            Ok(tree.roots[leaf_count - tree.offset])
+        }
+    }
+
+    /// Returns `true` if `root` is one of the roots kept for any of the trees.
+    #[inline]
+    pub fn contains(&self, root: &UtxoAccumulatorOutput<Config>) -> bool {
+        self.trees.iter().any(|tree| tree.roots.contains(root))
+    }
+
+    /// Returns the roots of the trees when the ledger was at `checkpoint`.
+    ///
+    /// Since every tree keeps its own history, this method returns [`RootHistoryError::Pruned`]
+    /// as soon as one of the roots was pruned, even if the ledger still has the other ones.
+    #[inline]
+    pub fn roots_at(
+        &self,
+        checkpoint: &Checkpoint,
+    ) -> Result<UtxoAccumulatorRoots, RootHistoryError> {
+        Ok(checkpoint
+            .receiver_index
+            .iter()
+            .enumerate()
+            .map(|(i, leaf_count)| self.root(MerkleForestIndex::from_index(i), leaf_count.get()))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .collect())
+    }
+
+    /// Prunes the oldest roots of the tree at `index` until it keeps at most `capacity`-many of
+    /// them.
+    #[inline]
+    fn prune(&mut self, index: usize) {
+        let tree = &mut self.trees[index];
+        while tree.roots.len() > self.capacity {
+            tree.roots.pop_front();
+            tree.offset += 1;
+        }
+    }
+}
@@ -18,25 +18,38 @@
 
 use crate::{
     config::{
-        utxo::{AssetId, AssetValue},
+        utxo::{AssetId, AssetValue, CommittedCheckpoint},
         Config, TransferPost,
     },
-    simulation::ledger::{http::Request, AccountId, Checkpoint},
+    simulation::ledger::{
+        history::UtxoAccumulatorRoots,
+        http::{
+            session::{Frame, RequestId, SessionRequest, SessionResponse},
+            Request,
+        },
+        AccountId, Checkpoint, CheckpointHeader,
+    },
 };
+use alloc::collections::BTreeMap;
 use manta_accounting::{
     asset::AssetList,
+    transfer::fee::FeeSchedule,
     wallet::{
-        ledger::{self, ReadResponse},
+        ledger::{self, BlockStatus, LedgerTime, PoolStats, ReadResponse, RootHistoryError},
         signer::SyncData,
         test::PublicBalanceOracle,
     },
 };
 use manta_util::{
+    from_variant,
     future::{LocalBoxFuture, LocalBoxFutureResult},
     http::reqwest::{self, Error, IntoUrl, KnownUrlClient},
     serde::{de::DeserializeOwned, Serialize},
 };
 
+#[cfg(feature = "socks")]
+use manta_util::http::proxy::ProxyConfig;
+
 /// HTTP Ledger Client
 pub struct Client {
     /// Account Id
@@ -44,6 +57,12 @@ pub struct Client {
 
     /// Client Connection
     client: KnownUrlClient,
+
+    /// Checkpoint of the Latest Header
+    ///
+    /// Checkpoint of the latest [`CheckpointHeader`] returned by
+    /// [`next_header`](ledger::CheckpointStream::next_header).
+    header_checkpoint: Option<Checkpoint>,
 }
 
 impl Client {
@@ -56,6 +75,27 @@ impl Client {
         Ok(Self {
             account,
             client: KnownUrlClient::new(server_url)?,
+            header_checkpoint: None,
+        })
+    }
+
+    /// Builds a new HTTP [`Client`] that connects to `server_url` through the SOCKS5 proxy
+    /// described by `proxy`.
+    #[cfg(feature = "socks")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "socks")))]
+    #[inline]
+    pub fn with_proxy<U>(
+        account: AccountId,
+        server_url: U,
+        proxy: &ProxyConfig,
+    ) -> Result<Self, Error>
+    where
+        U: IntoUrl,
+    {
+        Ok(Self {
+            account,
+            client: KnownUrlClient::with_proxy(server_url, proxy)?,
+            header_checkpoint: None,
         })
     }
 
@@ -76,6 +116,12 @@ impl Client {
             )
             .await
     }
+
+    /// Returns the current checkpoint of the ledger together with its nullifier set commitment.
+    #[inline]
+    pub async fn checkpoint(&self) -> reqwest::Result<CommittedCheckpoint> {
+        self.client.post("checkpoint", &self.account).await
+    }
 }
 
 impl ledger::Connection for Client {
@@ -106,6 +152,65 @@ impl ledger::Write<Vec<TransferPost>> for Client {
     }
 }
 
+impl ledger::PoolStatsQuery<AssetId> for Client {
+    #[inline]
+    fn pool_stats(&mut self) -> LocalBoxFutureResult<'_, PoolStats<AssetId>, Self::Error> {
+        Box::pin(self.client.post("poolStats", &self.account))
+    }
+}
+
+impl ledger::BlockStatusQuery for Client {
+    #[inline]
+    fn block_status(&mut self) -> LocalBoxFutureResult<'_, BlockStatus, Self::Error> {
+        Box::pin(self.client.post("blockStatus", &self.account))
+    }
+}
+
+impl ledger::LedgerTimeQuery for Client {
+    #[inline]
+    fn ledger_time(&mut self) -> LocalBoxFutureResult<'_, LedgerTime, Self::Error> {
+        Box::pin(self.client.post("ledgerTime", &self.account))
+    }
+}
+
+impl ledger::RootHistoryQuery for Client {
+    type Checkpoint = Checkpoint;
+    type Output = UtxoAccumulatorRoots;
+
+    #[inline]
+    fn root_at<'s>(
+        &'s mut self,
+        checkpoint: &'s Self::Checkpoint,
+    ) -> LocalBoxFutureResult<'s, Result<Self::Output, RootHistoryError>, Self::Error> {
+        Box::pin(self.post_request("rootAt", checkpoint))
+    }
+}
+
+impl ledger::CheckpointStream for Client {
+    type Checkpoint = Checkpoint;
+    type Output = UtxoAccumulatorRoots;
+
+    #[inline]
+    fn next_header(&mut self) -> LocalBoxFutureResult<'_, CheckpointHeader, Self::Error> {
+        Box::pin(async move {
+            let header = self
+                .post_request::<_, CheckpointHeader>("nextCheckpoint", self.header_checkpoint)
+                .await?;
+            self.header_checkpoint = Some(header.checkpoint);
+            Ok(header)
+        })
+    }
+}
+
+impl ledger::FeeScheduleQuery<AssetId, AssetValue> for Client {
+    #[inline]
+    fn fee_schedule(
+        &mut self,
+    ) -> LocalBoxFutureResult<'_, Option<FeeSchedule<AssetId, AssetValue>>, Self::Error> {
+        Box::pin(self.client.post("feeSchedule", &self.account))
+    }
+}
+
 impl PublicBalanceOracle<Config> for Client {
     #[inline]
     fn public_balances(&self) -> LocalBoxFuture<Option<AssetList<AssetId, AssetValue>>> {
@@ -118,3 +223,190 @@ impl PublicBalanceOracle<Config> for Client {
         })
     }
 }
+
+/// Session Error
+#[derive(Debug)]
+pub enum SessionError {
+    /// Transport Error
+    Transport(Error),
+
+    /// Missing Response
+    ///
+    /// The server did not answer the request with this id.
+    MissingResponse(RequestId),
+
+    /// Unexpected Response
+    ///
+    /// The server answered the request with this id with a response of another kind.
+    UnexpectedResponse(RequestId),
+}
+
+from_variant!(SessionError, Transport, Error);
+
+/// Multiplexed Ledger Session
+///
+/// A [`Session`] sends all the pulls, pushes and queries of a wallet over the connection of a
+/// single [`Client`], batching the requests which are queued with [`enqueue`](Self::enqueue) into
+/// one round trip. See the [`session`](super::session) module for more.
+pub struct Session {
+    /// Client Connection
+    client: Client,
+
+    /// Next Request Id
+    next_id: RequestId,
+
+    /// Queued Requests
+    queue: Vec<Frame<SessionRequest>>,
+
+    /// Responses not yet Taken
+    responses: BTreeMap<RequestId, SessionResponse>,
+}
+
+impl Session {
+    /// Builds a new [`Session`] over `client`.
+    #[inline]
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            next_id: 0,
+            queue: Vec::new(),
+            responses: BTreeMap::new(),
+        }
+    }
+
+    /// Queues `request` until the next [`flush`](Self::flush), returning its [`RequestId`].
+    #[inline]
+    pub fn enqueue(&mut self, request: SessionRequest) -> RequestId {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        self.queue.push(Frame {
+            id,
+            payload: request,
+        });
+        id
+    }
+
+    /// Returns the number of queued requests.
+    #[inline]
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Sends all the queued requests in one round trip, keeping their responses until they are
+    /// taken with [`take`](Self::take). The queue is left untouched if the round trip fails.
+    #[inline]
+    pub async fn flush(&mut self) -> Result<(), Error> {
+        if self.queue.is_empty() {
+            return Ok(());
+        }
+        let responses: Vec<Frame<SessionResponse>> =
+            self.client.post_request("session", &self.queue).await?;
+        self.queue.clear();
+        self.responses.extend(
+            responses
+                .into_iter()
+                .map(|Frame { id, payload }| (id, payload)),
+        );
+        Ok(())
+    }
+
+    /// Takes the response to the request with the given `id`, if it has been received.
+    #[inline]
+    pub fn take(&mut self, id: RequestId) -> Option<SessionResponse> {
+        self.responses.remove(&id)
+    }
+
+    /// Drops all the responses which have not been taken yet.
+    #[inline]
+    pub fn clear_responses(&mut self) {
+        self.responses.clear();
+    }
+}
+
+/// Implements a ledger query for [`Session`] by sending `$request` and extracting the response
+/// with the `$response` variant, converted with `$map` if the variant is boxed.
+macro_rules! session_call {
+    ($self:ident, $request:expr, $response:ident) => {
+        session_call!($self, $request, $response, |response| response)
+    };
+    ($self:ident, $request:expr, $response:ident, $map:expr) => {
+        Box::pin(async move {
+            let id = $self.enqueue($request);
+            $self.flush().await?;
+            match $self.take(id) {
+                Some(SessionResponse::$response(response)) => Ok(($map)(response)),
+                Some(_) => Err(SessionError::UnexpectedResponse(id)),
+                _ => Err(SessionError::MissingResponse(id)),
+            }
+        })
+    };
+}
+
+impl ledger::Connection for Session {
+    type Error = SessionError;
+}
+
+impl ledger::Read<SyncData<Config>> for Session {
+    type Checkpoint = Checkpoint;
+
+    #[inline]
+    fn read<'s>(
+        &'s mut self,
+        checkpoint: &'s Self::Checkpoint,
+    ) -> LocalBoxFutureResult<'s, ReadResponse<SyncData<Config>>, Self::Error> {
+        session_call!(self, SessionRequest::Pull(*checkpoint), Pull)
+    }
+}
+
+impl ledger::Write<Vec<TransferPost>> for Session {
+    type Response = bool;
+
+    #[inline]
+    fn write(
+        &mut self,
+        posts: Vec<TransferPost>,
+    ) -> LocalBoxFutureResult<'_, Self::Response, Self::Error> {
+        session_call!(self, SessionRequest::Push(posts), Push)
+    }
+}
+
+impl ledger::PoolStatsQuery<AssetId> for Session {
+    #[inline]
+    fn pool_stats(&mut self) -> LocalBoxFutureResult<'_, PoolStats<AssetId>, Self::Error> {
+        session_call!(self, SessionRequest::PoolStats, PoolStats)
+    }
+}
+
+impl ledger::BlockStatusQuery for Session {
+    #[inline]
+    fn block_status(&mut self) -> LocalBoxFutureResult<'_, BlockStatus, Self::Error> {
+        session_call!(self, SessionRequest::BlockStatus, BlockStatus)
+    }
+}
+
+impl ledger::RootHistoryQuery for Session {
+    type Checkpoint = Checkpoint;
+    type Output = UtxoAccumulatorRoots;
+
+    #[inline]
+    fn root_at<'s>(
+        &'s mut self,
+        checkpoint: &'s Self::Checkpoint,
+    ) -> LocalBoxFutureResult<'s, Result<Self::Output, RootHistoryError>, Self::Error> {
+        session_call!(
+            self,
+            SessionRequest::RootAt(*checkpoint),
+            RootAt,
+            |response: Box<_>| *response
+        )
+    }
+}
+
+impl ledger::FeeScheduleQuery<AssetId, AssetValue> for Session {
+    #[inline]
+    fn fee_schedule(
+        &mut self,
+    ) -> LocalBoxFutureResult<'_, Option<FeeSchedule<AssetId, AssetValue>>, Self::Error> {
+        session_call!(self, SessionRequest::FeeSchedule, FeeSchedule)
+    }
+}
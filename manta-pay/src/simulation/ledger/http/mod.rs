@@ -21,6 +21,7 @@ use manta_util::serde::{Deserialize, Serialize};
 
 pub mod client;
 pub mod server;
+pub mod session;
 
 /// HTTP Request
 #[derive(Deserialize, Serialize)]
@@ -18,16 +18,27 @@
 
 use crate::{
     config::{
-        utxo::{AssetId, AssetValue},
+        utxo::{AssetId, AssetValue, CommittedCheckpoint},
         Config, TransferPost,
     },
-    simulation::ledger::{http::Request, AccountId, Checkpoint, Ledger, SharedLedger},
+    simulation::ledger::{
+        history::UtxoAccumulatorRoots,
+        http::{
+            session::{schedule, Frame, SessionRequest, SessionResponse},
+            Request,
+        },
+        AccountId, Checkpoint, CheckpointHeader, Ledger, SharedLedger,
+    },
 };
-use alloc::sync::Arc;
+use alloc::{boxed::Box, sync::Arc};
 use core::future::Future;
 use manta_accounting::{
     asset::AssetList,
-    wallet::{ledger::ReadResponse, signer::SyncData},
+    transfer::fee::FeeSchedule,
+    wallet::{
+        ledger::{BlockStatus, LedgerTime, PoolStats, ReadResponse, RootHistoryError},
+        signer::SyncData,
+    },
 };
 use manta_util::{
     http::tide::{self, listener::ToListener, Body, Response},
@@ -57,10 +68,10 @@ impl State {
         self.0.read().await.pull(&checkpoint)
     }
 
-    /// Pushes data to the ledger with the given `account` and `posts`.
+    /// Submits data to the ledger with the given `account` and `posts`.
     #[inline]
     async fn push(self, account: AccountId, posts: Vec<TransferPost>) -> bool {
-        self.0.write().await.push(account, posts)
+        self.0.write().await.submit(account, posts)
     }
 
     /// Returns the public balances associated to `account` if they exist.
@@ -68,6 +79,119 @@ impl State {
     async fn public_balances(self, account: AccountId) -> Option<AssetList<AssetId, AssetValue>> {
         self.0.read().await.public_balances(account)
     }
+
+    /// Returns the current checkpoint of the ledger together with its nullifier set commitment.
+    #[inline]
+    async fn checkpoint(self, account: AccountId) -> CommittedCheckpoint {
+        let _ = account;
+        self.0.read().await.checkpoint()
+    }
+
+    /// Returns the statistics of the shielded pool of the ledger.
+    #[inline]
+    async fn pool_stats(self, account: AccountId) -> PoolStats<AssetId> {
+        let _ = account;
+        self.0.read().await.pool_stats().clone()
+    }
+
+    /// Returns the block status of the ledger.
+    #[inline]
+    async fn block_status(self, account: AccountId) -> BlockStatus {
+        let _ = account;
+        self.0.read().await.block_status()
+    }
+
+    /// Returns the current time of the ledger.
+    #[inline]
+    async fn ledger_time(self, account: AccountId) -> LedgerTime {
+        let _ = account;
+        self.0.read().await.ledger_time()
+    }
+
+    /// Returns the roots of the UTXO forest when the ledger was at the given `checkpoint`.
+    #[inline]
+    async fn root_at(
+        self,
+        account: AccountId,
+        checkpoint: Checkpoint,
+    ) -> Result<UtxoAccumulatorRoots, RootHistoryError> {
+        let _ = account;
+        self.0.read().await.root_at(&checkpoint)
+    }
+
+    /// Returns the fee schedule of the ledger if it charges fees.
+    #[inline]
+    async fn fee_schedule(self, account: AccountId) -> Option<FeeSchedule<AssetId, AssetValue>> {
+        let _ = account;
+        self.0.read().await.fee_schedule
+    }
+
+    /// Returns the header of the ledger as soon as its checkpoint differs from `checkpoint`,
+    /// holding the request until the ledger changes otherwise. See
+    /// [`CheckpointStream`](manta_accounting::wallet::ledger::CheckpointStream) for more.
+    #[inline]
+    async fn next_header(
+        self,
+        account: AccountId,
+        checkpoint: Option<Checkpoint>,
+    ) -> CheckpointHeader {
+        let _ = account;
+        let mut headers = self.0.read().await.subscribe();
+        loop {
+            {
+                let header = headers.borrow_and_update();
+                if Some(&header.checkpoint) != checkpoint.as_ref() {
+                    return header.clone();
+                }
+            }
+            if headers.changed().await.is_err() {
+                return headers.borrow().clone();
+            }
+        }
+    }
+
+    /// Executes the multiplexed `frames` of a session by priority, returning the responses in
+    /// execution order. See the [`session`](super::session) module for more.
+    #[inline]
+    async fn session(
+        self,
+        account: AccountId,
+        mut frames: Vec<Frame<SessionRequest>>,
+    ) -> Vec<Frame<SessionResponse>> {
+        schedule(&mut frames);
+        let mut responses = Vec::with_capacity(frames.len());
+        for Frame { id, payload } in frames {
+            let state = self.clone();
+            let payload = match payload {
+                SessionRequest::Pull(checkpoint) => {
+                    SessionResponse::Pull(state.pull(account, checkpoint).await)
+                }
+                SessionRequest::Push(posts) => {
+                    SessionResponse::Push(state.push(account, posts).await)
+                }
+                SessionRequest::PublicBalances => {
+                    SessionResponse::PublicBalances(state.public_balances(account).await)
+                }
+                SessionRequest::Checkpoint => {
+                    SessionResponse::Checkpoint(Box::new(state.checkpoint(account).await))
+                }
+                SessionRequest::PoolStats => {
+                    SessionResponse::PoolStats(state.pool_stats(account).await)
+                }
+                SessionRequest::BlockStatus => {
+                    SessionResponse::BlockStatus(state.block_status(account).await)
+                }
+                SessionRequest::RootAt(checkpoint) => {
+                    SessionResponse::RootAt(Box::new(state.root_at(account, checkpoint).await))
+                }
+                SessionRequest::FeeSchedule => {
+                    SessionResponse::FeeSchedule(state.fee_schedule(account).await)
+                }
+            };
+            responses.push(Frame { id, payload });
+        }
+        responses
+    }
 }
 
 /// Ledger HTTP Server
@@ -83,6 +207,22 @@ impl Server {
         api.at("/push").post(|r| Self::execute_with(r, State::push));
         api.at("/publicBalances")
             .post(|r| Self::execute(r, State::public_balances));
+        api.at("/checkpoint")
+            .post(|r| Self::execute(r, State::checkpoint));
+        api.at("/poolStats")
+            .post(|r| Self::execute(r, State::pool_stats));
+        api.at("/blockStatus")
+            .post(|r| Self::execute(r, State::block_status));
+        api.at("/ledgerTime")
+            .post(|r| Self::execute(r, State::ledger_time));
+        api.at("/rootAt")
+            .post(|r| Self::execute_with(r, State::root_at));
+        api.at("/feeSchedule")
+            .post(|r| Self::execute(r, State::fee_schedule));
+        api.at("/nextCheckpoint")
+            .post(|r| Self::execute_with(r, State::next_header));
+        api.at("/session")
+            .post(|r| Self::execute_with(r, State::session));
         Self(api)
     }
 
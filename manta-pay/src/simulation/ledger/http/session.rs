@@ -0,0 +1,185 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Multiplexed Ledger Sessions
+//!
+//! Instead of sending one HTTP request for every pull, push and query, a [`Session`] queues its
+//! requests as [`Frame`]s tagged with a [`RequestId`] and sends all of them at once over the same
+//! connection. The server executes the frames by [`Priority`], so that pushes are never stuck
+//! behind bulk pulls, and returns the responses in execution order, interleaved with respect to
+//! the requests, with the ids of the requests they answer. Requests of the same priority are
+//! executed in the order in which they were queued, so a pull queued after a push already sees
+//! the posts of that push.
+//!
+//! [`Session`]: super::client::Session
+
+use crate::{
+    config::{
+        utxo::{AssetId, AssetValue, CommittedCheckpoint},
+        Config, TransferPost,
+    },
+    simulation::ledger::{history::UtxoAccumulatorRoots, Checkpoint},
+};
+use alloc::{boxed::Box, vec::Vec};
+use manta_accounting::{
+    asset::AssetList,
+    transfer::fee::FeeSchedule,
+    wallet::{
+        ledger::{BlockStatus, PoolStats, ReadResponse, RootHistoryError},
+        signer::SyncData,
+    },
+};
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Request Id
+///
+/// Identifier of a request in a session, which is echoed in the frame of its response.
+pub type RequestId = u64;
+
+/// Session Frame
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct Frame<T> {
+    /// Request Id
+    pub id: RequestId,
+
+    /// Frame Payload
+    pub payload: T,
+}
+
+/// Request Priority
+///
+/// Priorities are ordered from the most to the least urgent.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Priority {
+    /// Pushes of Transfer Posts
+    Push,
+
+    /// Small Queries
+    Query,
+
+    /// Bulk Pulls of Ledger Data
+    Pull,
+}
+
+/// Session Request
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub enum SessionRequest {
+    /// Pull from a Checkpoint
+    Pull(Checkpoint),
+
+    /// Push of Transfer Posts
+    Push(Vec<TransferPost>),
+
+    /// Public Balances Query
+    PublicBalances,
+
+    /// Checkpoint Query
+    Checkpoint,
+
+    /// Pool Statistics Query
+    PoolStats,
+
+    /// Block Status Query
+    BlockStatus,
+
+    /// Root History Query
+    RootAt(Checkpoint),
+
+    /// Fee Schedule Query
+    FeeSchedule,
+}
+
+impl SessionRequest {
+    /// Returns the [`Priority`] of `self`.
+    #[inline]
+    pub fn priority(&self) -> Priority {
+        match self {
+            Self::Push(_) => Priority::Push,
+            Self::Pull(_) => Priority::Pull,
+            _ => Priority::Query,
+        }
+    }
+}
+
+/// Session Response
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub enum SessionResponse {
+    /// Pull Response
+    Pull(ReadResponse<SyncData<Config>>),
+
+    /// Push Response
+    Push(bool),
+
+    /// Public Balances
+    PublicBalances(Option<AssetList<AssetId, AssetValue>>),
+
+    /// Checkpoint
+    Checkpoint(Box<CommittedCheckpoint>),
+
+    /// Pool Statistics
+    PoolStats(PoolStats<AssetId>),
+
+    /// Block Status
+    BlockStatus(BlockStatus),
+
+    /// Root History Response
+    RootAt(Box<Result<UtxoAccumulatorRoots, RootHistoryError>>),
+
+    /// Fee Schedule
+    FeeSchedule(Option<FeeSchedule<AssetId, AssetValue>>),
+}
+
+/// Sorts `frames` into their execution order: by [`Priority`], and in the order in which they
+/// were queued within the same priority.
+#[inline]
+pub fn schedule(frames: &mut [Frame<SessionRequest>]) {
+    frames.sort_by_key(|frame| frame.payload.priority());
+}
+
+/// Testing Suite
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Tests that pushes are scheduled before queries, and queries before pulls, keeping the
+    /// queueing order within every priority.
+    #[test]
+    fn pushes_are_scheduled_before_pulls() {
+        let mut frames = [
+            SessionRequest::Pull(Default::default()),
+            SessionRequest::PoolStats,
+            SessionRequest::Push(Vec::new()),
+            SessionRequest::Pull(Default::default()),
+            SessionRequest::BlockStatus,
+            SessionRequest::Push(Vec::new()),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(id, payload)| Frame {
+            id: id as RequestId,
+            payload,
+        })
+        .collect::<Vec<_>>();
+        schedule(&mut frames);
+        assert_eq!(
+            frames.iter().map(|frame| frame.id).collect::<Vec<_>>(),
+            [2, 5, 1, 4, 0, 3]
+        );
+    }
+}
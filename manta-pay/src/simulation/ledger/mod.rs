@@ -22,29 +22,43 @@
 use crate::{
     config::{
         utxo::{
-            AssetId, AssetValue, Checkpoint, FullIncomingNote, MerkleTreeConfiguration, Parameters,
+            self, AssetId, AssetValue, Checkpoint, CommittedCheckpoint, FullIncomingNote,
+            MerkleTreeConfiguration, NullifierSetCommitment, Parameters, UtxoAccumulatorItem,
         },
-        AccountId, Config, MultiVerifyingContext, Nullifier, ProofSystem, TransferPost, Utxo,
-        UtxoAccumulatorModel,
+        AccountId, Config, MultiVerifyingContext, Nullifier, ProofSystem, TransactionIdHash,
+        TransferPost, Utxo, UtxoAccumulatorModel,
+    },
+    signer::{FrontierSyncData, InitialSyncData},
+    simulation::ledger::{
+        history::{RootHistory, UtxoAccumulatorRoots},
+        nullifiers::{NullifierSet, NullifierStore},
     },
-    signer::InitialSyncData,
 };
-use alloc::{sync::Arc, vec::Vec};
-use core::convert::Infallible;
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::{convert::Infallible, time::Duration};
 use indexmap::IndexSet;
 use manta_accounting::{
     asset::{Asset, AssetList},
     transfer::{
         canonical::TransferShape,
-        receiver::{ReceiverLedger, ReceiverPostError},
+        diff::{self, BalanceDelta, InsertedUtxo},
+        epoch::{Epoch, EpochError, EpochRegistry, Height},
+        fee::{FeeSchedule, InsufficientFee},
+        receiver::{ReceiverLedger, ReceiverPostError, Registration, RegistrationError},
         sender::{SenderLedger, SenderPostError},
-        InvalidAuthorizationSignature, InvalidSinkAccount, InvalidSourceAccount, SinkPostingKey,
-        SourcePostingKey, TransferLedger, TransferLedgerSuperPostingKey, TransferPostError,
-        TransferPostingKeyRef, UtxoAccumulatorOutput,
+        upgrade::{ProtocolFeature, UpgradeDescriptor, UpgradeSchedule},
+        utxo::protocol::BaseConfiguration,
+        InvalidAuthorizationSignature, InvalidSinkAccount, InvalidSourceAccount, NotePolicy,
+        NotePolicyViolation, SinkPostingKey, SourcePostingKey, TransactionIdFunction,
+        TransferLedger, TransferLedgerSuperPostingKey, TransferPostError, TransferPostingKeyRef,
+        UtxoAccumulatorOutput,
     },
     wallet::{
-        ledger::{self, ReadResponse},
-        signer::SyncData,
+        ledger::{
+            self, BlockHeight, BlockStatus, BlockTime, LeafIndex, LedgerTime, NoteIndex, PoolStats,
+            ReadResponse, RootHistoryError,
+        },
+        signer::{SyncData, SyncEntries, MAX_SYNC_DATA_LENGTH},
         test::PublicBalanceOracle,
     },
 };
@@ -54,19 +68,59 @@ use manta_crypto::{
     merkle_tree::{
         self,
         forest::{Configuration, FixedIndex, Forest},
+        full::Full,
+        Tree, WithProofs,
     },
 };
-use manta_util::future::{LocalBoxFuture, LocalBoxFutureResult};
+use manta_util::{
+    future::{LocalBoxFuture, LocalBoxFutureResult},
+    vec::{all_unequal, CapacityError},
+};
 use std::collections::{HashMap, HashSet};
-use tokio::sync::RwLock;
+use tokio::sync::{watch, RwLock};
 
 #[cfg(feature = "serde")]
 use manta_util::serde::{Deserialize, Serialize};
 
+pub mod anchor;
+pub mod archive;
+pub mod fixtures;
+pub mod fork;
+pub mod history;
+pub mod network;
+pub mod nullifiers;
+pub mod snapshot;
+
 #[cfg(feature = "http")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "http")))]
 pub mod http;
 
+/// Collects the first [`MAX_SYNC_DATA_LENGTH`]-many elements of `iter` into [`SyncEntries`],
+/// returning them with `true` if `iter` had more elements left.
+#[inline]
+fn collect_sync_entries<T, I>(iter: I) -> (SyncEntries<T>, bool)
+where
+    I: IntoIterator<Item = T>,
+{
+    let mut iter = iter.into_iter().peekable();
+    let entries = SyncEntries::try_from_iter(iter.by_ref().take(MAX_SYNC_DATA_LENGTH))
+        .map_err(CapacityError::forget)
+        .expect(
+            "Taking at most `MAX_SYNC_DATA_LENGTH` elements is not allowed to exceed the bound.",
+        );
+    (entries, iter.peek().is_some())
+}
+
+/// Returns the public `balance` after applying the change in `delta` to it, or `None` if it would
+/// underflow or overflow.
+#[inline]
+fn balance_after(balance: AssetValue, delta: &BalanceDelta<Config>) -> Option<AssetValue> {
+    match delta {
+        BalanceDelta::Withdraw { asset, .. } => balance.checked_sub(asset.value),
+        BalanceDelta::Deposit { asset, .. } => balance.checked_add(asset.value),
+    }
+}
+
 /// Merkle Forest Index
 pub type MerkleForestIndex = <MerkleTreeConfiguration as Configuration>::Index;
 
@@ -77,6 +131,29 @@ pub type UtxoMerkleForest = merkle_tree::forest::TreeArrayMerkleForest<
     { MerkleTreeConfiguration::FOREST_WIDTH },
 >;
 
+/// UTXO Index
+///
+/// The index of a UTXO in the ledger is the index of its tree in the [`UtxoMerkleForest`] followed
+/// by its position in that tree.
+pub type UtxoIndex = (MerkleForestIndex, LeafIndex);
+
+/// Ledger State Difference
+pub type StateDiff = diff::StateDiff<Config, UtxoIndex>;
+
+pub use crate::config::TransactionId;
+
+/// Post Receipt Type
+pub type PostReceipt = ledger::PostReceipt<TransactionId, Checkpoint, UtxoIndex>;
+
+/// Checkpoint Header Type
+pub type CheckpointHeader = ledger::CheckpointHeader<Checkpoint, UtxoAccumulatorRoots>;
+
+/// Returns the [`TransactionId`] of `posts`.
+#[inline]
+pub fn transaction_id(posts: &[TransferPost]) -> TransactionId {
+    <TransactionIdHash as TransactionIdFunction<Config>>::transaction_id(posts)
+}
+
 /// Wrap Type
 #[cfg_attr(
     feature = "serde",
@@ -109,11 +186,83 @@ impl<L, R> AsRef<R> for WrapPair<L, R> {
     }
 }
 
+/// Dust Policy
+///
+/// [`NotePolicy`] of the [`Ledger`] which applies the same minimum value to the notes of every
+/// asset.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DustPolicy {
+    /// Minimum Note Value
+    pub minimum_value: Option<AssetValue>,
+
+    /// Maximum Number of Receivers per Post
+    pub maximum_receivers: Option<usize>,
+}
+
+impl NotePolicy<Config> for DustPolicy {
+    #[inline]
+    fn maximum_receivers(&self) -> Option<usize> {
+        self.maximum_receivers
+    }
+
+    #[inline]
+    fn minimum_value(&self, asset_id: &AssetId) -> Option<AssetValue> {
+        let _ = asset_id;
+        self.minimum_value
+    }
+}
+
+/// Registration Limit
+///
+/// Rate limit of the [`Ledger`] on the notes registered by each account: an account can register
+/// at most [`maximum_notes`](Self::maximum_notes)-many notes over the last
+/// [`window`](Self::window)-many heights.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct RegistrationLimit {
+    /// Maximum Number of Notes per Window
+    pub maximum_notes: usize,
+
+    /// Window Length
+    pub window: Height,
+}
+
+impl RegistrationLimit {
+    /// Checks that an account which has already registered `registered`-many notes in the current
+    /// window can register `requested`-many more.
+    #[inline]
+    pub fn check(&self, registered: usize, requested: usize) -> Result<(), RegistrationError> {
+        let remaining = self.maximum_notes.saturating_sub(registered);
+        if requested > remaining {
+            return Err(RegistrationError::RateLimited {
+                requested,
+                remaining,
+            });
+        }
+        Ok(())
+    }
+}
+
 /// Ledger
 #[derive(Debug)]
 pub struct Ledger {
-    /// Nullifier
-    nullifiers: IndexSet<Nullifier>,
+    /// Nullifier Set
+    nullifiers: NullifierSet,
+
+    /// Nullifier Set Commitments
+    ///
+    /// The `i`-th commitment is the commitment to the first `i`-many nullifiers, so there is
+    /// always one more commitment than there are nullifiers.
+    nullifier_commitments: Vec<NullifierSetCommitment>,
 
     /// UTXOs
     utxos: HashSet<Utxo>,
@@ -124,34 +273,603 @@ pub struct Ledger {
     /// UTXO Forest
     utxo_forest: UtxoMerkleForest,
 
+    /// UTXO Forest Root History
+    root_history: RootHistory,
+
     /// Account Table
     accounts: HashMap<AccountId, HashMap<AssetId, AssetValue>>,
 
-    /// Verifying Contexts
-    verifying_context: MultiVerifyingContext,
+    /// Frozen Accounts
+    ///
+    /// Accounts whose public balances cannot change. Like on ledgers which only look up the
+    /// account state when a transfer is applied, they are checked when the public balances of a
+    /// post are updated and not when the post is validated.
+    frozen_accounts: HashSet<AccountId>,
+
+    /// Parameter Epochs
+    ///
+    /// Every epoch holds the verifying contexts and parameters used to validate the posts which
+    /// are tagged with it.
+    epochs: EpochRegistry<(MultiVerifyingContext, Parameters)>,
+
+    /// Ledger Height
+    height: Height,
 
     /// UTXO Configuration Parameters
+    ///
+    /// These parameters are used to hash the UTXOs into the [`UtxoMerkleForest`], so they are
+    /// shared by all the epochs.
     parameters: Parameters,
+
+    /// Shielded Pool Statistics
+    stats: PoolStats<AssetId>,
+
+    /// Note Policy
+    note_policy: Option<DustPolicy>,
+
+    /// Registration Limit
+    registration_limit: Option<RegistrationLimit>,
+
+    /// Registrations
+    ///
+    /// Heights at which each account registered notes, with the number of notes registered.
+    registrations: HashMap<AccountId, Vec<(Height, usize)>>,
+
+    /// Registrant
+    ///
+    /// Account whose posts are being pushed, which registers their notes.
+    registrant: Option<AccountId>,
+
+    /// Fee Schedule
+    fee_schedule: Option<FeeSchedule<AssetId, AssetValue>>,
+
+    /// Pending Fee
+    ///
+    /// Fee of the post being pushed, which is reserved out of the public balance of the registrant
+    /// until the post is applied.
+    pending_fee: AssetValue,
+
+    /// Block Time Model
+    block_time: Option<BlockTime>,
+
+    /// Block Checkpoints
+    ///
+    /// The `i`-th checkpoint is the [`Checkpoint`] of the ledger after block `i` was produced, so
+    /// the first one is the checkpoint of the genesis block.
+    blocks: Vec<Checkpoint>,
+
+    /// Pending Batches
+    ///
+    /// Batches of posts which were written to the ledger and wait for the next block.
+    mempool: Vec<(AccountId, Vec<TransferPost>)>,
+
+    /// Time Elapsed since the Latest Block
+    elapsed: Duration,
+
+    /// Ledger Time in Milliseconds since the Unix Epoch
+    time: u64,
+
+    /// Legacy Nullifier Deadline
+    ///
+    /// Height from which the nullifiers of legacy notes are no longer accepted.
+    legacy_nullifier_deadline: Option<Height>,
+
+    /// Announced Upgrades
+    upgrades: Vec<UpgradeDescriptor>,
+
+    /// Checkpoint Header Channel
+    ///
+    /// Holds the [`CheckpointHeader`] of the confirmed state of the ledger, which is pushed to
+    /// every subscriber whenever it changes.
+    headers: watch::Sender<CheckpointHeader>,
 }
 
 impl Ledger {
-    /// Builds an empty [`Ledger`].
+    /// Default Epoch Grace Window
+    ///
+    /// Number of heights for which posts tagged with a retired epoch are still accepted.
+    pub const DEFAULT_EPOCH_GRACE_WINDOW: Height = 64;
+
+    /// Default Root History Capacity
+    ///
+    /// Number of roots kept for each tree of the [`UtxoMerkleForest`].
+    pub const DEFAULT_ROOT_HISTORY_CAPACITY: usize = 1024;
+
+    /// Builds an empty [`Ledger`] whose initial epoch `0` uses the `verifying_context` and the
+    /// `parameters`.
     #[inline]
     pub fn new(
         utxo_accumulator_model: UtxoAccumulatorModel,
         verifying_context: MultiVerifyingContext,
         parameters: Parameters,
     ) -> Self {
+        let mut epochs = EpochRegistry::new(Self::DEFAULT_EPOCH_GRACE_WINDOW);
+        epochs.activate(0, (verifying_context, parameters.clone()));
+        let utxo_forest = UtxoMerkleForest::new(utxo_accumulator_model);
+        let root_history = RootHistory::new(&utxo_forest, Self::DEFAULT_ROOT_HISTORY_CAPACITY);
+        let checkpoint = Checkpoint::default();
+        let (headers, _) = watch::channel(CheckpointHeader {
+            checkpoint,
+            root: root_history
+                .roots_at(&checkpoint)
+                .expect("The root history starts with the roots of the empty forest."),
+            height: Default::default(),
+        });
         Self {
             nullifiers: Default::default(),
+            nullifier_commitments: vec![Default::default()],
             utxos: Default::default(),
             shards: (0..MerkleTreeConfiguration::FOREST_WIDTH)
                 .map(move |i| (MerkleForestIndex::from_index(i), Default::default()))
                 .collect(),
-            utxo_forest: UtxoMerkleForest::new(utxo_accumulator_model),
+            utxo_forest,
+            root_history,
             accounts: Default::default(),
-            verifying_context,
+            frozen_accounts: Default::default(),
+            epochs,
+            height: 0,
             parameters,
+            stats: Default::default(),
+            note_policy: None,
+            registration_limit: None,
+            registrations: Default::default(),
+            registrant: None,
+            fee_schedule: None,
+            pending_fee: 0,
+            block_time: None,
+            blocks: Vec::new(),
+            mempool: Vec::new(),
+            elapsed: Duration::ZERO,
+            time: 0,
+            legacy_nullifier_deadline: None,
+            upgrades: Vec::new(),
+            headers,
+        }
+    }
+
+    /// Returns the statistics of the shielded pool of the ledger.
+    #[inline]
+    pub fn pool_stats(&self) -> &PoolStats<AssetId> {
+        &self.stats
+    }
+
+    /// Returns the current [`Checkpoint`] of the ledger together with the commitment to all of
+    /// its nullifiers.
+    #[inline]
+    pub fn checkpoint(&self) -> CommittedCheckpoint {
+        CommittedCheckpoint::new(
+            self.current_checkpoint(),
+            self.nullifier_commitments[self.nullifiers.len()],
+        )
+    }
+
+    /// Returns the [`NullifierSet`] of the ledger.
+    #[inline]
+    pub fn nullifiers(&self) -> &NullifierSet {
+        &self.nullifiers
+    }
+
+    /// Attaches `store` to the [`NullifierSet`] of the ledger, which sends it every nullifier
+    /// spent or reverted from now on, or detaches the current store if `store` is `None`.
+    #[inline]
+    pub fn set_nullifier_store(&mut self, store: Option<Box<dyn NullifierStore>>) {
+        self.nullifiers.set_store(store);
+    }
+
+    /// Returns the commitment to the first `sender_index`-many nullifiers of the ledger, if it has
+    /// that many nullifiers.
+    #[inline]
+    pub fn nullifier_commitment(&self, sender_index: NoteIndex) -> Option<NullifierSetCommitment> {
+        self.nullifier_commitments.get(sender_index.get()).copied()
+    }
+
+    /// Returns the roots that the trees of the [`UtxoMerkleForest`] had when the ledger was at
+    /// `checkpoint`, or a [`RootHistoryError`] if some of them were pruned from the root history
+    /// or `checkpoint` is ahead of the ledger.
+    #[inline]
+    pub fn root_at(
+        &self,
+        checkpoint: &Checkpoint,
+    ) -> Result<UtxoAccumulatorRoots, RootHistoryError> {
+        self.root_history.roots_at(checkpoint)
+    }
+
+    /// Sets the number of roots kept for each tree of the [`UtxoMerkleForest`] in the root
+    /// history, pruning the oldest ones.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `capacity` is zero.
+    #[inline]
+    pub fn set_root_history_capacity(&mut self, capacity: usize) {
+        self.root_history.set_capacity(capacity);
+    }
+
+    /// Subscribes to the [`CheckpointHeader`]s of the ledger. The receiver starts at the header
+    /// of the current confirmed state of the ledger and is notified of every later one.
+    #[inline]
+    pub fn subscribe(&self) -> watch::Receiver<CheckpointHeader> {
+        self.headers.subscribe()
+    }
+
+    /// Pushes the [`CheckpointHeader`] of the confirmed state of the ledger to its subscribers if
+    /// its checkpoint changed. Headers are only pushed while the root history still has the roots
+    /// of the confirmed checkpoint.
+    #[inline]
+    fn publish_header(&mut self) {
+        let checkpoint = self.confirmed_checkpoint();
+        let root = match self.root_history.roots_at(&checkpoint) {
+            Ok(root) => root,
+            _ => return,
+        };
+        let header = CheckpointHeader {
+            checkpoint,
+            root,
+            height: BlockHeight::new(self.height),
+        };
+        self.headers.send_if_modified(move |current| {
+            if current.checkpoint == header.checkpoint {
+                false
+            } else {
+                *current = header;
+                true
+            }
+        });
+    }
+
+    /// Returns the current height of the ledger, which is the number of batches of posts it has
+    /// accepted.
+    #[inline]
+    pub fn height(&self) -> Height {
+        self.height
+    }
+
+    /// Returns the latest active epoch of the ledger, which new posts should be tagged with.
+    #[inline]
+    pub fn latest_epoch(&self) -> Option<Epoch> {
+        self.epochs.latest().map(|(epoch, _)| epoch)
+    }
+
+    /// Activates `epoch` with the `verifying_context` and the `parameters`, replacing the previous
+    /// ones if `epoch` was already registered.
+    #[inline]
+    pub fn activate_epoch(
+        &mut self,
+        epoch: Epoch,
+        verifying_context: MultiVerifyingContext,
+        parameters: Parameters,
+    ) {
+        self.epochs.activate(epoch, (verifying_context, parameters));
+    }
+
+    /// Retires `epoch` at the current height, returning `false` if `epoch` is not registered or
+    /// was already retired. Posts tagged with `epoch` are still accepted until the end of the
+    /// grace window.
+    #[inline]
+    pub fn retire_epoch(&mut self, epoch: Epoch) -> bool {
+        self.epochs.retire(epoch, self.height)
+    }
+
+    /// Sets the number of heights for which posts tagged with a retired epoch are still accepted.
+    #[inline]
+    pub fn set_epoch_grace_window(&mut self, grace_window: Height) {
+        self.epochs.set_grace_window(grace_window);
+    }
+
+    /// Sets the height from which the nullifiers of legacy notes are no longer accepted, or
+    /// accepts them for as long as the nullifier migration lasts if `deadline` is `None`.
+    #[inline]
+    pub fn set_legacy_nullifier_deadline(&mut self, deadline: Option<Height>) {
+        self.legacy_nullifier_deadline = deadline;
+    }
+
+    /// Returns `true` if the ledger accepts the nullifiers of legacy notes at its current height.
+    /// They are never accepted outside of a nullifier migration.
+    #[inline]
+    pub fn accepts_legacy_nullifiers(&self) -> bool {
+        <utxo::Config as BaseConfiguration>::NULLIFIER_MIGRATION
+            && self
+                .legacy_nullifier_deadline
+                .is_none_or(|deadline| self.height < deadline)
+            && !self
+                .upgrade_schedule()
+                .is_deprecated(&ProtocolFeature::LegacyNullifiers)
+    }
+
+    /// Announces the `upgrade` of the protocol, which deprecates its features and enables its new
+    /// shapes once the ledger reaches its activation height.
+    #[inline]
+    pub fn announce_upgrade(&mut self, upgrade: UpgradeDescriptor) {
+        self.upgrades.push(upgrade);
+    }
+
+    /// Returns the [`UpgradeSchedule`] of the upgrades announced by the ledger at its current
+    /// height.
+    #[inline]
+    pub fn upgrade_schedule(&self) -> UpgradeSchedule {
+        UpgradeSchedule::new(self.height, self.upgrades.clone())
+    }
+
+    /// Sets the [`DustPolicy`] which the notes created by new posts must satisfy, or removes it
+    /// if `note_policy` is `None`.
+    #[inline]
+    pub fn set_note_policy(&mut self, note_policy: Option<DustPolicy>) {
+        self.note_policy = note_policy;
+    }
+
+    /// Sets the [`RegistrationLimit`] on the notes registered by each account, or removes it if
+    /// `registration_limit` is `None`.
+    #[inline]
+    pub fn set_registration_limit(&mut self, registration_limit: Option<RegistrationLimit>) {
+        self.registration_limit = registration_limit;
+    }
+
+    /// Sets the [`FeeSchedule`] of the fees charged for every post, which are paid out of the public
+    /// balance of the account pushing the posts, or stops charging fees if `fee_schedule` is
+    /// `None`.
+    #[inline]
+    pub fn set_fee_schedule(&mut self, fee_schedule: Option<FeeSchedule<AssetId, AssetValue>>) {
+        self.fee_schedule = fee_schedule;
+    }
+
+    /// Returns `true` if the shape of `post` is enabled by the upgrades announced by the ledger at
+    /// its current height.
+    #[inline]
+    fn accepts_shape(&self, post: &TransferPost) -> bool {
+        TransferShape::from_post(post)
+            .map(|shape| self.upgrade_schedule().is_enabled(shape))
+            .unwrap_or(false)
+    }
+
+    /// Returns the fee of `post` in the [`FeeSchedule`] of the ledger, or zero if the ledger does
+    /// not charge fees or `post` does not have a canonical shape.
+    #[inline]
+    fn post_fee(&self, post: &TransferPost) -> AssetValue {
+        self.fee_schedule
+            .and_then(|schedule| schedule.post_fee(post))
+            .unwrap_or_default()
+    }
+
+    /// Returns the part of the public balance of `account` in assets with `id` which is reserved
+    /// for the pending fee.
+    #[inline]
+    fn reserved_fee(&self, account: &AccountId, id: &AssetId) -> AssetValue {
+        match (self.fee_schedule, self.registrant) {
+            (Some(schedule), Some(registrant))
+                if registrant == *account && schedule.asset_id == *id =>
+            {
+                self.pending_fee
+            }
+            _ => 0,
+        }
+    }
+
+    /// Charges the pending fee to the public balance of `account`.
+    #[inline]
+    fn charge_fee(&mut self, account: AccountId) {
+        let fee = core::mem::take(&mut self.pending_fee);
+        if let (Some(schedule), true) = (self.fee_schedule, fee > 0) {
+            *self
+                .accounts
+                .get_mut(&account)
+                .and_then(|balances| balances.get_mut(&schedule.asset_id))
+                .expect("The fee was checked when validating the post.") -= fee;
+        }
+    }
+
+    /// Returns the first height of the window of the [`RegistrationLimit`] of the ledger, or `0`
+    /// if there is no limit.
+    #[inline]
+    fn registration_window_start(&self) -> Height {
+        match self.registration_limit {
+            Some(limit) => self.height.saturating_sub(limit.window.saturating_sub(1)),
+            _ => 0,
+        }
+    }
+
+    /// Returns the number of notes registered by `account` within the window of the
+    /// [`RegistrationLimit`] of the ledger, or all of them if there is no limit.
+    #[inline]
+    pub fn registered_notes(&self, account: &AccountId) -> usize {
+        let start = self.registration_window_start();
+        self.registrations
+            .get(account)
+            .map(|registrations| {
+                registrations
+                    .iter()
+                    .filter(|(height, _)| *height >= start)
+                    .map(|(_, notes)| notes)
+                    .sum()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Records that `account` registered `notes`-many notes at the current height, forgetting
+    /// the registrations which left the window of the [`RegistrationLimit`] of the ledger.
+    #[inline]
+    fn record_registration(&mut self, account: AccountId, notes: usize) {
+        if notes == 0 {
+            return;
+        }
+        let start = self.registration_window_start();
+        let registrations = self.registrations.entry(account).or_default();
+        registrations.retain(|(height, _)| *height >= start);
+        registrations.push((self.height, notes));
+    }
+
+    /// Sets the [`BlockTime`] model of the ledger, or removes it if `block_time` is `None`.
+    ///
+    /// Setting a model starts a new genesis block with all the data on the ledger, after which
+    /// the posts written with [`submit`](Self::submit) wait for the next block and [`pull`] only
+    /// returns the data of confirmed blocks. Removing the model pushes the pending batches right
+    /// away and makes [`pull`] return all the data again.
+    ///
+    /// # Limitations
+    ///
+    /// The [`initial_read`] and [`frontier_read`] methods do not wait for confirmations, so they
+    /// return the data of every produced block.
+    ///
+    /// [`pull`]: Self::pull
+    /// [`initial_read`]: Self::initial_read
+    /// [`frontier_read`]: Self::frontier_read
+    #[inline]
+    pub fn set_block_time(&mut self, block_time: Option<BlockTime>) {
+        self.block_time = block_time;
+        self.elapsed = Duration::ZERO;
+        self.blocks.clear();
+        match self.block_time {
+            Some(_) => self.blocks.push(self.current_checkpoint()),
+            _ => {
+                for (account, posts) in core::mem::take(&mut self.mempool) {
+                    self.push(account, posts);
+                }
+            }
+        }
+    }
+
+    /// Returns the [`BlockTime`] model of the ledger, if it orders posts into blocks.
+    #[inline]
+    pub fn block_time(&self) -> Option<&BlockTime> {
+        self.block_time.as_ref()
+    }
+
+    /// Returns the current [`BlockStatus`] of the ledger. A ledger without a [`BlockTime`] model
+    /// applies posts right away, so it always stays at its settled genesis block.
+    #[inline]
+    pub fn block_status(&self) -> BlockStatus {
+        let block_number = self.block_number();
+        let confirmed_block_number = match self.block_time {
+            Some(block_time) => {
+                let confirmed_block_number = block_time.confirmed_block(block_number);
+                if self.blocks[confirmed_block_number.get() as usize] == self.current_checkpoint() {
+                    block_number
+                } else {
+                    confirmed_block_number
+                }
+            }
+            _ => block_number,
+        };
+        BlockStatus {
+            block_number,
+            confirmed_block_number,
+            pending_batches: self.mempool.len() as u64,
+        }
+    }
+
+    /// Writes the `posts` of `account` to the ledger. If the ledger has a [`BlockTime`] model,
+    /// the `posts` are queued for the next block and `true` is returned, since they are only
+    /// validated when the block is produced. Otherwise, the `posts` are pushed right away as
+    /// in [`push`](Self::push).
+    #[inline]
+    pub fn submit(&mut self, account: AccountId, posts: Vec<TransferPost>) -> bool {
+        match self.block_time {
+            Some(_) => {
+                self.mempool.push((account, posts));
+                true
+            }
+            _ => self.push(account, posts),
+        }
+    }
+
+    /// Submits `posts` to the ledger like [`submit`](Self::submit), returning their
+    /// [`PostReceipt`] if they were accepted. Ledgers with a [`BlockTime`] model only accept posts
+    /// in later blocks, so this method returns `None` for them and the posts are left pending.
+    #[inline]
+    pub fn submit_with_receipt(
+        &mut self,
+        account: AccountId,
+        posts: Vec<TransferPost>,
+    ) -> Option<PostReceipt> {
+        if self.block_time.is_some() {
+            self.mempool.push((account, posts));
+            return None;
+        }
+        let transaction_id = transaction_id(&posts);
+        let origin_checkpoint = self.current_checkpoint();
+        let notes = posts
+            .iter()
+            .flat_map(|post| {
+                post.body
+                    .receiver_posts
+                    .iter()
+                    .map(|receiver_post| (receiver_post.utxo, receiver_post.note.clone()))
+            })
+            .collect::<Vec<_>>();
+        if !self.push(account, posts) {
+            return None;
+        }
+        let leaf_indices = notes
+            .into_iter()
+            .map(|entry| {
+                let tree_index = MerkleTreeConfiguration::tree_index(
+                    &self.parameters.item_hash(&entry.0, &mut ()),
+                );
+                let leaf_index = self.shards[&tree_index]
+                    .get_index_of(&entry)
+                    .expect("Accepted UTXOs are stored in the shard of their tree.");
+                (tree_index, LeafIndex::new(leaf_index))
+            })
+            .collect();
+        Some(PostReceipt {
+            transaction_id,
+            origin_checkpoint,
+            checkpoint: self.current_checkpoint(),
+            leaf_indices,
+        })
+    }
+
+    /// Produces a new block with all the pending batches, in the order they were submitted,
+    /// returning whether each of them was accepted. This method does nothing if the ledger does
+    /// not have a [`BlockTime`] model.
+    #[inline]
+    pub fn produce_block(&mut self) -> Vec<bool> {
+        if self.block_time.is_none() {
+            return Vec::new();
+        }
+        let accepted = core::mem::take(&mut self.mempool)
+            .into_iter()
+            .map(|(account, posts)| self.push(account, posts))
+            .collect();
+        self.blocks.push(self.current_checkpoint());
+        self.elapsed = Duration::ZERO;
+        self.publish_header();
+        accepted
+    }
+
+    /// Lets `elapsed` time pass, producing a block every [`BlockTime::interval`] and returning
+    /// whether each batch included in these blocks was accepted. If the ledger does not have a
+    /// [`BlockTime`] model, this method only moves its [`LedgerTime`] forward.
+    #[inline]
+    pub fn advance_time(&mut self, elapsed: Duration) -> Vec<bool> {
+        self.time = self
+            .time
+            .saturating_add(elapsed.as_millis().try_into().unwrap_or(u64::MAX));
+        let block_time = match self.block_time {
+            Some(block_time) => block_time,
+            _ => return Vec::new(),
+        };
+        let (blocks, until_next_block) = block_time.blocks_in(self.elapsed + elapsed);
+        let mut accepted = Vec::new();
+        for _ in 0..blocks {
+            accepted.extend(self.produce_block());
+        }
+        self.elapsed = block_time.interval - until_next_block;
+        accepted
+    }
+
+    /// Sets the time of the ledger to `timestamp`, in milliseconds since the Unix epoch. The
+    /// ledger time then moves forward with [`advance_time`](Self::advance_time).
+    #[inline]
+    pub fn set_time(&mut self, timestamp: u64) {
+        self.time = timestamp;
+    }
+
+    /// Returns the current [`LedgerTime`] of the ledger.
+    #[inline]
+    pub fn ledger_time(&self) -> LedgerTime {
+        LedgerTime {
+            height: BlockHeight::new(self.height),
+            timestamp: self.time,
         }
     }
 
@@ -167,57 +885,504 @@ impl Ledger {
         )
     }
 
-    /// Sets the public balance of `account` in assets with `id` to `value`.
+    /// Sets the public balance of `account` in assets with `id` to `value`.
+    #[inline]
+    pub fn set_public_balance(&mut self, account: AccountId, id: AssetId, value: AssetValue) {
+        assert_ne!(id, Default::default(), "Asset id can't be zero!");
+        self.accounts.entry(account).or_default().insert(id, value);
+    }
+
+    /// Freezes `account`, so that the posts which withdraw from it or deposit into it are
+    /// rejected when their public balances are updated.
+    #[inline]
+    pub fn freeze_account(&mut self, account: AccountId) {
+        self.frozen_accounts.insert(account);
+    }
+
+    /// Unfreezes `account`, returning `false` if it was not frozen.
+    #[inline]
+    pub fn unfreeze_account(&mut self, account: &AccountId) -> bool {
+        self.frozen_accounts.remove(account)
+    }
+
+    /// Returns `true` if `account` is frozen.
+    #[inline]
+    pub fn is_frozen(&self, account: &AccountId) -> bool {
+        self.frozen_accounts.contains(account)
+    }
+
+    /// Pulls the data from the ledger later than the given `checkpoint`. If the ledger has a
+    /// [`BlockTime`] model, only the data of the confirmed blocks is returned.
+    ///
+    /// At most [`MAX_SYNC_DATA_LENGTH`]-many entries are returned in each field of the
+    /// [`SyncData`]. The receivers are returned shard by shard, so a truncated response only
+    /// advances the checkpoint past the entries it contains, and `should_continue` is set to
+    /// request the rest.
+    #[inline]
+    pub fn pull(&self, checkpoint: &Checkpoint) -> ReadResponse<SyncData<Config>> {
+        let confirmed_checkpoint = self.confirmed_checkpoint();
+        let (utxo_note_data, more_receivers) = collect_sync_entries(
+            checkpoint
+                .receiver_index
+                .iter()
+                .copied()
+                .enumerate()
+                .flat_map(|(i, index)| {
+                    self.shards[&MerkleForestIndex::from_index(i)]
+                        .iter()
+                        .take(confirmed_checkpoint.receiver_index[i].get())
+                        .skip(index.get())
+                        .cloned()
+                }),
+        );
+        let (nullifier_data, more_senders) = collect_sync_entries(
+            self.nullifiers
+                .iter()
+                .take(confirmed_checkpoint.sender_index.get())
+                .skip(checkpoint.sender_index.get())
+                .cloned(),
+        );
+        ReadResponse {
+            should_continue: more_receivers || more_senders,
+            data: SyncData {
+                utxo_note_data,
+                nullifier_data,
+            },
+        }
+    }
+
+    /// Pushes the data from `posts` to the ledger.
+    #[inline]
+    pub fn push(&mut self, account: AccountId, posts: Vec<TransferPost>) -> bool {
+        let shard_lengths = self.shard_lengths();
+        let nullifier_count = self.nullifiers.len();
+        self.registrant = Some(account);
+        let accepted = self.push_posts(account, posts);
+        self.registrant = None;
+        self.pending_fee = 0;
+        self.record_batch(&shard_lengths, nullifier_count);
+        if accepted {
+            self.advance_height();
+        }
+        self.publish_header();
+        accepted
+    }
+
+    /// Validates and posts every element of `posts` to the ledger, returning `false` as soon as
+    /// one of them is invalid.
+    ///
+    /// The nullifiers of every post are looked up in the [`NullifierSet`] in one batch before its
+    /// proof is verified, so double spends are rejected without verifying their proofs.
+    #[inline]
+    fn push_posts(&mut self, account: AccountId, posts: Vec<TransferPost>) -> bool {
+        for post in posts {
+            let nullifiers = post
+                .body
+                .sender_posts
+                .iter()
+                .map(|sender_post| sender_post.nullifier)
+                .collect::<Vec<_>>();
+            if self.nullifiers.contains_many(&nullifiers).contains(&true) {
+                return false;
+            }
+            let (sources, sinks) = match public_participants(account, &post) {
+                Some(participants) => participants,
+                _ => return false,
+            };
+            if !self.accepts_shape(&post) {
+                return false;
+            }
+            self.pending_fee = self.post_fee(&post);
+            let parameters = match self.epoch_parameters(post.epoch) {
+                Ok(parameters) => parameters,
+                _ => return false,
+            };
+            let asset_notes = minted_asset_notes(&post);
+            let notes = post.body.receiver_posts.len();
+            match post.validate(parameters, &*self, sources, sinks) {
+                Ok(posting_key) => {
+                    if posting_key.post(&mut *self, &()).is_err() {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+            self.charge_fee(account);
+            self.record_registration(account, notes);
+            if let Some((asset_id, notes)) = asset_notes {
+                self.stats.record_asset_notes(asset_id, notes);
+            }
+        }
+        true
+    }
+
+    /// Pushes the data from `posts` to the ledger, returning the [`StateDiff`] of the whole batch.
+    /// Unlike [`push`](Self::push), either all the `posts` are applied or none of them are.
+    #[inline]
+    pub fn push_with_diff(
+        &mut self,
+        account: AccountId,
+        posts: Vec<TransferPost>,
+    ) -> Option<StateDiff> {
+        let asset_notes = posts
+            .iter()
+            .filter_map(minted_asset_notes)
+            .collect::<Vec<_>>();
+        let mut fork = self.fork();
+        if !fork.push(account, posts) {
+            return None;
+        }
+        let diff = fork.commit();
+        self.record_registration(account, diff.utxos.len());
+        assert!(
+            self.apply(diff.clone()),
+            "A state difference computed on a fork of the ledger must apply to the ledger."
+        );
+        for (asset_id, notes) in asset_notes {
+            self.stats.record_asset_notes(asset_id, notes);
+        }
+        Some(diff)
+    }
+
+    /// Returns the parameters of `epoch` if posts tagged with it are still accepted.
+    #[inline]
+    fn epoch_parameters(&self, epoch: Epoch) -> Result<&Parameters, EpochError> {
+        Ok(&self.epochs.get(epoch, self.height)?.1)
+    }
+
+    /// Returns the current [`Checkpoint`] of the ledger.
+    #[inline]
+    fn current_checkpoint(&self) -> Checkpoint {
+        Checkpoint::new(
+            self.shard_lengths()
+                .into_iter()
+                .map(LeafIndex::new)
+                .collect(),
+            NoteIndex::new(self.nullifiers.len()),
+        )
+    }
+
+    /// Returns the number of the latest block of the ledger.
+    #[inline]
+    fn block_number(&self) -> BlockHeight {
+        BlockHeight::new(self.blocks.len().saturating_sub(1) as u64)
+    }
+
+    /// Returns the [`Checkpoint`] of the latest confirmed block, or the current [`Checkpoint`]
+    /// if the ledger does not have a [`BlockTime`] model.
+    #[inline]
+    fn confirmed_checkpoint(&self) -> Checkpoint {
+        match self.block_time {
+            Some(block_time) => {
+                self.blocks[block_time.confirmed_block(self.block_number()).get() as usize]
+            }
+            _ => self.current_checkpoint(),
+        }
+    }
+
+    /// Returns the number of UTXOs in each tree of the [`UtxoMerkleForest`].
+    #[inline]
+    fn shard_lengths(&self) -> Vec<usize> {
+        (0..MerkleTreeConfiguration::FOREST_WIDTH)
+            .map(|i| self.shards[&MerkleForestIndex::from_index(i)].len())
+            .collect()
+    }
+
+    /// Records the notes and spends added to the ledger since it had the given `shard_lengths`
+    /// and `nullifier_count` as a new batch in the pool statistics, unless nothing was added.
+    #[inline]
+    fn record_batch(&mut self, shard_lengths: &[usize], nullifier_count: usize) {
+        let mut notes = 0;
+        let mut updated_roots = 0;
+        for (before, after) in shard_lengths.iter().zip(self.shard_lengths()) {
+            if after > *before {
+                notes += (after - before) as u64;
+                updated_roots += 1;
+            }
+        }
+        let spends = (self.nullifiers.len() - nullifier_count) as u64;
+        if notes > 0 || spends > 0 {
+            self.stats.record_batch(notes, spends, updated_roots);
+        }
+    }
+
+    /// Inserts `nullifier` into the nullifier set, extending the nullifier set commitment.
+    #[inline]
+    fn insert_nullifier(&mut self, nullifier: Nullifier) {
+        let commitment = self.nullifier_commitments[self.nullifiers.len()].extended([&nullifier]);
+        if self.nullifiers.insert(nullifier) {
+            self.nullifier_commitments.push(commitment);
+        }
+    }
+
+    /// Increases the height of the ledger and prunes the epochs whose grace window has ended.
+    #[inline]
+    fn advance_height(&mut self) {
+        self.height += 1;
+        self.epochs.prune(self.height);
+    }
+
+    /// Returns the index that the next UTXO inserted into the tree at `index` would have.
+    #[inline]
+    fn next_utxo_index(&self, index: MerkleForestIndex) -> LeafIndex {
+        LeafIndex::new(self.shards[&index].len())
+    }
+
+    /// Writes the changes in `diff` to `self`, returning `false` without changing `self` if the
+    /// UTXO indices in `diff` do not match the current state of `self`, if one of its nullifiers
+    /// was already spent, or if one of its balance changes would underflow or overflow a public
+    /// balance.
+    ///
+    /// # Validity
+    ///
+    /// This method does not validate the transfers which produced `diff`, so `diff` must come from
+    /// a trusted source, like a fork of `self` or a ledger node which already validated it.
+    #[inline]
+    pub fn apply(&mut self, diff: StateDiff) -> bool {
+        let mut next_indices = HashMap::<MerkleForestIndex, LeafIndex>::new();
+        for InsertedUtxo { index, utxo, .. } in &diff.utxos {
+            let tree_index =
+                MerkleTreeConfiguration::tree_index(&self.parameters.item_hash(utxo, &mut ()));
+            let next_index = next_indices
+                .entry(tree_index)
+                .or_insert_with(|| self.next_utxo_index(tree_index));
+            if *index != (tree_index, *next_index) {
+                return false;
+            }
+            *next_index = next_index.advance(1);
+        }
+        if !all_unequal(&diff.nullifiers, |p, q| {
+            p.nullifier.commitment == q.nullifier.commitment
+        }) || self
+            .nullifiers
+            .contains_many(&diff.nullifiers)
+            .into_iter()
+            .any(|spent| spent)
+            || !self.can_apply_balance_deltas(&diff.balance_deltas)
+        {
+            return false;
+        }
+        let shard_lengths = self.shard_lengths();
+        let nullifier_count = self.nullifiers.len();
+        for nullifier in diff.nullifiers {
+            self.insert_nullifier(nullifier);
+        }
+        for InsertedUtxo { utxo, note, .. } in diff.utxos {
+            let utxo_hash = self.parameters.item_hash(&utxo, &mut ());
+            self.shards
+                .get_mut(&MerkleTreeConfiguration::tree_index(&utxo_hash))
+                .expect("All the shards are initialized when building the ledger.")
+                .insert((utxo, note));
+            self.utxos.insert(utxo);
+            self.push_utxo_hash(&utxo_hash);
+        }
+        for delta in diff.balance_deltas {
+            assert!(
+                self.apply_balance_delta(delta),
+                "The balance changes were already checked against the public balances."
+            );
+        }
+        self.record_batch(&shard_lengths, nullifier_count);
+        self.advance_height();
+        self.publish_header();
+        true
+    }
+
+    /// Undoes the changes in `diff`, returning `false` without changing `self` if `diff` is not
+    /// the latest change applied to `self` or if undoing its balance changes would underflow or
+    /// overflow a public balance.
+    ///
+    /// The [`PoolStats`] of `self` are cumulative, so they still account for `diff`.
+    #[inline]
+    pub fn revert(&mut self, diff: &StateDiff) -> bool {
+        let nullifier_count = self.nullifiers.len();
+        if nullifier_count < diff.nullifiers.len()
+            || self
+                .nullifiers
+                .iter()
+                .skip(nullifier_count - diff.nullifiers.len())
+                .ne(diff.nullifiers.iter())
+        {
+            return false;
+        }
+        let mut next_indices = HashMap::<MerkleForestIndex, LeafIndex>::new();
+        for InsertedUtxo { index, utxo, .. } in diff.utxos.iter().rev() {
+            let next_index = next_indices
+                .entry(index.0)
+                .or_insert_with(|| self.next_utxo_index(index.0));
+            match next_index.get().checked_sub(1).map(LeafIndex::new) {
+                Some(last) if last == index.1 => *next_index = last,
+                _ => return false,
+            }
+            match self.shards[&index.0].get_index(index.1.get()) {
+                Some((stored, _)) if stored == utxo => {}
+                _ => return false,
+            }
+        }
+        let rollback = diff.balance_rollback().collect::<Vec<_>>();
+        if !self.can_apply_balance_deltas(&rollback) {
+            return false;
+        }
+        for _ in 0..diff.nullifiers.len() {
+            self.nullifiers.pop();
+        }
+        self.nullifier_commitments
+            .truncate(self.nullifiers.len() + 1);
+        for InsertedUtxo { index, utxo, .. } in diff.utxos.iter().rev() {
+            self.shards
+                .get_mut(&index.0)
+                .expect("All the shards are initialized when building the ledger.")
+                .pop();
+            self.utxos.remove(utxo);
+        }
+        for tree_index in next_indices.keys() {
+            self.rebuild_utxo_tree(*tree_index);
+        }
+        for delta in rollback {
+            assert!(
+                self.apply_balance_delta(delta),
+                "The balance changes were already checked against the public balances."
+            );
+        }
+        self.publish_header();
+        true
+    }
+
+    /// Rebuilds the tree at `index` in the UTXO forest from the UTXOs stored in its shard and
+    /// rewinds its root history accordingly.
+    #[inline]
+    fn rebuild_utxo_tree(&mut self, index: MerkleForestIndex) {
+        *self.utxo_forest.forest.get_mut(index) = Tree::new(self.utxo_forest.parameters());
+        for (utxo, _) in &self.shards[&index] {
+            let utxo_hash = self.parameters.item_hash(utxo, &mut ());
+            self.utxo_forest.push(&utxo_hash);
+        }
+        self.root_history.rewind(
+            index,
+            self.shards[&index].len(),
+            *self.utxo_forest.forest.get(index).root(),
+        );
+    }
+
+    /// Inserts `utxo_hash` into the [`UtxoMerkleForest`], recording the new root of its tree in
+    /// the root history.
     #[inline]
-    pub fn set_public_balance(&mut self, account: AccountId, id: AssetId, value: AssetValue) {
-        assert_ne!(id, Default::default(), "Asset id can't be zero!");
-        self.accounts.entry(account).or_default().insert(id, value);
+    fn push_utxo_hash(&mut self, utxo_hash: &UtxoAccumulatorItem) {
+        self.utxo_forest.push(utxo_hash);
+        let index = MerkleTreeConfiguration::tree_index(utxo_hash);
+        self.root_history
+            .push(index, *self.utxo_forest.forest.get(index).root());
     }
 
-    /// Pulls the data from the ledger later than the given `checkpoint`.
+    /// Withdraws from the `sources` and deposits into the `sinks` in assets with `asset_id`,
+    /// recording every change in `applied` so that they can be rolled back if a later one fails.
     #[inline]
-    pub fn pull(&self, checkpoint: &Checkpoint) -> ReadResponse<SyncData<Config>> {
-        let mut receivers = Vec::new();
-        for (i, mut index) in checkpoint.receiver_index.iter().copied().enumerate() {
-            let shard = &self.shards[&MerkleForestIndex::from_index(i)];
-            while let Some(entry) = shard.get_index(index) {
-                receivers.push(entry.clone());
-                index += 1;
+    fn apply_public_balances(
+        &mut self,
+        asset_id: AssetId,
+        sources: Vec<WrapPair<AccountId, AssetValue>>,
+        sinks: Vec<WrapPair<AccountId, AssetValue>>,
+        applied: &mut StateDiff,
+    ) -> Result<(), TransferLedgerError> {
+        for WrapPair(account_id, withdraw) in sources {
+            if self.is_frozen(&account_id) {
+                return Err(TransferLedgerError::FrozenAccount(account_id));
             }
+            *self
+                .accounts
+                .get_mut(&account_id)
+                .and_then(|balances| balances.get_mut(&asset_id))
+                .ok_or(TransferLedgerError::InvalidSourceAccount(
+                    InvalidSourceAccount {
+                        account_id,
+                        asset_id,
+                        withdraw,
+                    },
+                ))? -= withdraw;
+            applied.balance_deltas.push(BalanceDelta::Withdraw {
+                account: account_id,
+                asset: Asset::new(asset_id, withdraw),
+            });
         }
-        let senders = self
-            .nullifiers
-            .iter()
-            .skip(checkpoint.sender_index)
-            .cloned()
-            .collect();
-        ReadResponse {
-            should_continue: false,
-            data: SyncData {
-                utxo_note_data: receivers,
-                nullifier_data: senders,
-            },
+        for WrapPair(account_id, deposit) in sinks {
+            if self.is_frozen(&account_id) {
+                return Err(TransferLedgerError::FrozenAccount(account_id));
+            }
+            *self
+                .accounts
+                .get_mut(&account_id)
+                .ok_or(TransferLedgerError::InvalidSinkAccount(
+                    InvalidSinkAccount {
+                        account_id,
+                        asset_id,
+                        deposit,
+                    },
+                ))?
+                .entry(asset_id)
+                .or_default() += deposit;
+            applied.balance_deltas.push(BalanceDelta::Deposit {
+                account: account_id,
+                asset: Asset::new(asset_id, deposit),
+            });
         }
+        Ok(())
     }
 
-    /// Pushes the data from `posts` to the ledger.
+    /// Returns the public balance of `account` in assets with `id`, which is zero if it was
+    /// never set.
     #[inline]
-    pub fn push(&mut self, account: AccountId, posts: Vec<TransferPost>) -> bool {
-        for post in posts {
-            let (sources, sinks) = match TransferShape::from_post(&post) {
-                Some(TransferShape::ToPrivate) => (vec![account], vec![]),
-                Some(TransferShape::PrivateTransfer) => (vec![], vec![]),
-                Some(TransferShape::ToPublic) => (vec![], vec![account]),
-                _ => return false,
+    fn public_balance(&self, account: &AccountId, id: &AssetId) -> AssetValue {
+        self.accounts
+            .get(account)
+            .and_then(|balances| balances.get(id))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns `true` if every change in `deltas` can be applied in order to the public balances
+    /// of `self` without underflowing or overflowing any of them.
+    #[inline]
+    fn can_apply_balance_deltas<'d, I>(&self, deltas: I) -> bool
+    where
+        I: IntoIterator<Item = &'d BalanceDelta<Config>>,
+    {
+        let mut balances = HashMap::<(AccountId, AssetId), AssetValue>::new();
+        for delta in deltas {
+            let (account, asset) = match delta {
+                BalanceDelta::Withdraw { account, asset } => (account, asset),
+                BalanceDelta::Deposit { account, asset } => (account, asset),
             };
-            match post.validate(&self.parameters, &*self, sources, sinks) {
-                Ok(posting_key) => posting_key.post(&mut *self, &()).unwrap(),
+            let balance = balances
+                .entry((*account, asset.id))
+                .or_insert_with(|| self.public_balance(account, &asset.id));
+            match balance_after(*balance, delta) {
+                Some(updated) => *balance = updated,
                 _ => return false,
             }
         }
         true
     }
 
+    /// Applies the public balance change in `delta` to `self`, returning `false` without changing
+    /// `self` if it would underflow or overflow the balance.
+    #[inline]
+    fn apply_balance_delta(&mut self, delta: BalanceDelta<Config>) -> bool {
+        let (account, asset) = match &delta {
+            BalanceDelta::Withdraw { account, asset } => (*account, asset),
+            BalanceDelta::Deposit { account, asset } => (*account, asset),
+        };
+        match balance_after(self.public_balance(&account, &asset.id), &delta) {
+            Some(updated) => {
+                self.accounts
+                    .entry(account)
+                    .or_default()
+                    .insert(asset.id, updated);
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Pulls the data from the ledger necessary to perform an [`initial_sync`].
     ///
     /// [`initial_sync`]: manta_accounting::wallet::signer::Connection::initial_sync
@@ -254,11 +1419,191 @@ impl Ledger {
         }
     }
 
+    /// Reads the frontier of the UTXO forest, the membership proofs of the `utxos` which are
+    /// stored in `self` and the nullifiers later than `checkpoint`, for the frontier
+    /// synchronization of a signer.
+    ///
+    /// Since `self` only keeps the current path of every tree in the [`UtxoMerkleForest`], the
+    /// membership proofs are recomputed from the shards on every call. Like [`pull`](Self::pull),
+    /// at most [`MAX_SYNC_DATA_LENGTH`]-many membership proofs and nullifiers are returned, and
+    /// `should_continue` is set if some of them were left out.
+    ///
+    /// See [`Signer::frontier_sync`] for more.
+    ///
+    /// [`Signer::frontier_sync`]: manta_accounting::wallet::signer::Signer::frontier_sync
+    #[inline]
+    pub fn frontier_read(
+        &self,
+        checkpoint: &Checkpoint,
+        utxos: &[Utxo],
+    ) -> ReadResponse<FrontierSyncData> {
+        let parameters = self.utxo_forest.parameters();
+        let trees = (0..MerkleTreeConfiguration::FOREST_WIDTH)
+            .map(|i| {
+                let mut tree = Full::<MerkleTreeConfiguration>::new(parameters);
+                for (utxo, _) in &self.shards[&MerkleForestIndex::from_index(i)] {
+                    tree.push(parameters, &self.parameters.item_hash(utxo, &mut ()));
+                }
+                tree
+            })
+            .collect::<Vec<_>>();
+        let frontier_data = trees
+            .iter()
+            .enumerate()
+            .map(|(i, tree)| {
+                let (utxo, _) = self.shards[&MerkleForestIndex::from_index(i)].last()?;
+                Some((
+                    *utxo,
+                    tree.path(parameters, tree.len() - 1)
+                        .expect("Paths of the full tree are always available."),
+                ))
+            })
+            .collect();
+        let membership_proof_data = utxos.iter().filter_map(|utxo| {
+            let tree_index =
+                MerkleTreeConfiguration::tree_index(&self.parameters.item_hash(utxo, &mut ()));
+            let index = self.shards[&tree_index]
+                .iter()
+                .position(|(shard_utxo, _)| shard_utxo == utxo)?;
+            Some((
+                *utxo,
+                trees[tree_index as usize]
+                    .path(parameters, index)
+                    .expect("Paths of the full tree are always available."),
+            ))
+        });
+        let (membership_proof_data, more_membership_proofs) =
+            collect_sync_entries(membership_proof_data);
+        let (nullifier_data, more_nullifiers) = collect_sync_entries(
+            self.nullifiers
+                .iter()
+                .skip(checkpoint.sender_index.get())
+                .cloned(),
+        );
+        ReadResponse {
+            should_continue: more_membership_proofs || more_nullifiers,
+            data: FrontierSyncData {
+                frontier_data,
+                membership_proof_data,
+                nullifier_data,
+            },
+        }
+    }
+
     /// Returns the [`Utxo`]s in `self`.
     #[inline]
     pub fn utxos(&self) -> &HashSet<Utxo> {
         &self.utxos
     }
+
+    /// Verifies the transfer proof stored in `posting_key` against the verifying context for its
+    /// shape in its epoch. The ledger only has verifying contexts for the canonical shapes, which
+    /// do not pay fees, so posts with a fee are rejected, and so are private swaps in the epochs
+    /// without a [`PrivateSwap`](TransferShape::PrivateSwap) verifying context.
+    #[inline]
+    fn verify<L>(
+        &self,
+        posting_key: TransferPostingKeyRef<Config, L>,
+    ) -> Result<(), TransferLedgerError>
+    where
+        L: TransferLedger<Config> + ?Sized,
+    {
+        if posting_key.fee.is_some() {
+            return Err(TransferLedgerError::InvalidShape);
+        }
+        let transfershape = TransferShape::select(
+            posting_key.authorization_key.is_some(),
+            posting_key.asset_id.is_some(),
+            posting_key.sources.len(),
+            posting_key.senders.len(),
+            posting_key.receivers.len(),
+            posting_key.sinks.len(),
+        )
+        .ok_or(TransferLedgerError::InvalidShape)?;
+        let (verifying_context, _) = self.epochs.get(posting_key.epoch, self.height)?;
+        ProofSystem::verify(
+            verifying_context
+                .try_select(transfershape)
+                .ok_or(TransferLedgerError::InvalidShape)?,
+            &posting_key.generate_proof_input(),
+            &posting_key.proof,
+        )
+        .map_err(|_| TransferLedgerError::InvalidProof)?;
+        Ok(())
+    }
+}
+
+/// Returns the source and sink accounts of `post` when it is sent from `account`, or `None` if
+/// `post` does not have a canonical shape.
+#[inline]
+fn public_participants(
+    account: AccountId,
+    post: &TransferPost,
+) -> Option<(Vec<AccountId>, Vec<AccountId>)> {
+    match TransferShape::from_post(post)? {
+        TransferShape::ToPrivate => Some((vec![account], vec![])),
+        TransferShape::PrivateTransfer | TransferShape::PrivateSwap => Some((vec![], vec![])),
+        TransferShape::ToPublic => Some((vec![], vec![account])),
+    }
+}
+
+/// Returns the asset id of `post` and the number of notes it mints if its asset id is public.
+#[inline]
+fn minted_asset_notes(post: &TransferPost) -> Option<(AssetId, u64)> {
+    Some((post.body.asset_id?, post.body.receiver_posts.len() as u64))
+}
+
+/// Checks that every account in `sources` has enough public balance of `asset_id`, looking up
+/// balances with `balance`.
+#[inline]
+fn check_source_accounts_with<I, F>(
+    asset_id: &AssetId,
+    sources: I,
+    mut balance: F,
+) -> Result<Vec<WrapPair<AccountId, AssetValue>>, InvalidSourceAccount<Config, AccountId>>
+where
+    I: Iterator<Item = (AccountId, AssetValue)>,
+    F: FnMut(&AccountId, &AssetId) -> Option<AssetValue>,
+{
+    sources
+        .map(|(account_id, withdraw)| {
+            // FIXME: What about zero values in `sources`?
+            match balance(&account_id, asset_id) {
+                Some(balance) if balance >= withdraw => Ok(WrapPair(account_id, withdraw)),
+                _ => Err(InvalidSourceAccount {
+                    account_id,
+                    asset_id: *asset_id,
+                    withdraw,
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Checks that every account in `sinks` exists, looking up accounts with `has_account`.
+#[inline]
+fn check_sink_accounts_with<I, F>(
+    asset_id: &AssetId,
+    sinks: I,
+    mut has_account: F,
+) -> Result<Vec<WrapPair<AccountId, AssetValue>>, InvalidSinkAccount<Config, AccountId>>
+where
+    I: Iterator<Item = (AccountId, AssetValue)>,
+    F: FnMut(&AccountId) -> bool,
+{
+    sinks
+        .map(move |(account_id, deposit)| {
+            if has_account(&account_id) {
+                Ok(WrapPair(account_id, deposit))
+            } else {
+                Err(InvalidSinkAccount {
+                    account_id,
+                    asset_id: *asset_id,
+                    deposit,
+                })
+            }
+        })
+        .collect()
 }
 
 /// Sender Ledger Error
@@ -280,6 +1625,11 @@ pub enum SenderLedgerError {
     /// The asset has already been spent.
     AssetSpent,
 
+    /// Legacy Nullifier Error
+    ///
+    /// The nullifier belongs to a legacy note which is no longer accepted by the ledger.
+    LegacyNullifier,
+
     /// Unexpected Error
     UnexpectedError,
 }
@@ -289,6 +1639,7 @@ impl From<SenderLedgerError> for SenderPostError<SenderLedgerError> {
     fn from(value: SenderLedgerError) -> Self {
         match value {
             SenderLedgerError::AssetSpent => Self::AssetSpent,
+            SenderLedgerError::LegacyNullifier => Self::LegacyNullifier,
             SenderLedgerError::InvalidUtxoAccumulatorOutput => Self::InvalidUtxoAccumulatorOutput,
             SenderLedgerError::UnexpectedError => {
                 Self::UnexpectedError(SenderLedgerError::UnexpectedError)
@@ -367,6 +1718,22 @@ pub enum TransferLedgerError {
     /// Validity of the transfer could not be proved by the ledger.
     InvalidProof,
 
+    /// Invalid Epoch Error
+    ///
+    /// The parameter epoch of the [`TransferPost`] is unknown to the ledger or was retired.
+    InvalidEpoch(EpochError),
+
+    /// Note Policy Violation
+    ///
+    /// The notes created by the [`TransferPost`] violate the [`DustPolicy`] of the ledger.
+    NotePolicy(NotePolicyViolation<Config>),
+
+    /// Frozen Account Error
+    ///
+    /// The [`TransferPost`] withdraws from or deposits into a frozen account, so none of its
+    /// changes were applied.
+    FrozenAccount(AccountId),
+
     /// Unexpected Error
     ///
     /// An unexpected error occured.
@@ -396,6 +1763,9 @@ impl From<TransferLedgerError>
             TransferLedgerError::DuplicateSpend => Self::DuplicateSpend,
             TransferLedgerError::DuplicateMint => Self::DuplicateMint,
             TransferLedgerError::InvalidProof => Self::InvalidProof,
+            TransferLedgerError::InvalidEpoch(err) => Self::InvalidEpoch(err),
+            TransferLedgerError::NotePolicy(err) => Self::NotePolicy(err),
+            err @ TransferLedgerError::FrozenAccount(_) => Self::UnexpectedError(err),
             TransferLedgerError::UnexpectedError => {
                 Self::UnexpectedError(TransferLedgerError::UnexpectedError)
             }
@@ -417,6 +1787,13 @@ impl From<SenderLedgerError> for TransferLedgerError {
     }
 }
 
+impl From<EpochError> for TransferLedgerError {
+    #[inline]
+    fn from(value: EpochError) -> Self {
+        Self::InvalidEpoch(value)
+    }
+}
+
 impl SenderLedger<Parameters> for Ledger {
     type ValidNullifier = Wrap<Nullifier>;
     type ValidUtxoAccumulatorOutput = Wrap<UtxoAccumulatorOutput<Config>>;
@@ -425,6 +1802,11 @@ impl SenderLedger<Parameters> for Ledger {
 
     #[inline]
     fn is_unspent(&self, nullifier: Nullifier) -> Result<Self::ValidNullifier, Self::Error> {
+        if nullifier.nullifier.is_legacy && !self.accepts_legacy_nullifiers() {
+            return Err(SenderLedgerError::LegacyNullifier);
+        }
+        // NOTE: The outgoing notes of two spends of the same asset are different, so the
+        //       nullifier set only compares the nullifier commitments.
         if self.nullifiers.contains(&nullifier) {
             Err(SenderLedgerError::AssetSpent)
         } else {
@@ -456,7 +1838,7 @@ impl SenderLedger<Parameters> for Ledger {
         nullifier: Self::ValidNullifier,
     ) -> Result<(), Self::Error> {
         let _ = (utxo_accumulator_output, super_key);
-        self.nullifiers.insert(nullifier.0);
+        self.insert_nullifier(nullifier.0);
         Ok(())
     }
 }
@@ -475,6 +1857,16 @@ impl ReceiverLedger<Parameters> for Ledger {
         }
     }
 
+    #[inline]
+    fn check_registration(&self, registration: Registration) -> Result<(), RegistrationError> {
+        match (self.registration_limit, self.registrant) {
+            (Some(limit), Some(account)) => {
+                limit.check(self.registered_notes(&account), registration.receivers)
+            }
+            _ => Ok(()),
+        }
+    }
+
     #[inline]
     fn register(
         &mut self,
@@ -489,7 +1881,7 @@ impl ReceiverLedger<Parameters> for Ledger {
             .ok_or(ReceiverLedgerError::UnexpectedError)?
             .insert((utxo.0, note));
         self.utxos.insert(utxo.0);
-        self.utxo_forest.push(&utxo_hash);
+        self.push_utxo_hash(&utxo_hash);
         Ok(())
     }
 }
@@ -511,38 +1903,10 @@ impl TransferLedger<Config> for Ledger {
     where
         I: Iterator<Item = (AccountId, AssetValue)>,
     {
-        sources
-            .map(|(account_id, withdraw)| {
-                match self.accounts.get(&account_id) {
-                    Some(map) => match map.get(asset_id) {
-                        Some(balance) => {
-                            if balance >= &withdraw {
-                                Ok(WrapPair(account_id, withdraw))
-                            } else {
-                                Err(InvalidSourceAccount {
-                                    account_id,
-                                    asset_id: *asset_id,
-                                    withdraw,
-                                })
-                            }
-                        }
-                        _ => {
-                            // FIXME: What about zero values in `sources`?
-                            Err(InvalidSourceAccount {
-                                account_id,
-                                asset_id: *asset_id,
-                                withdraw,
-                            })
-                        }
-                    },
-                    _ => Err(InvalidSourceAccount {
-                        account_id,
-                        asset_id: *asset_id,
-                        withdraw,
-                    }),
-                }
-            })
-            .collect()
+        check_source_accounts_with(asset_id, sources, |account_id, asset_id| {
+            let balance = self.accounts.get(account_id)?.get(asset_id).copied()?;
+            Some(balance.saturating_sub(self.reserved_fee(account_id, asset_id)))
+        })
     }
 
     #[inline]
@@ -554,19 +1918,9 @@ impl TransferLedger<Config> for Ledger {
     where
         I: Iterator<Item = (AccountId, AssetValue)>,
     {
-        sinks
-            .map(move |(account_id, deposit)| {
-                if self.accounts.contains_key(&account_id) {
-                    Ok(WrapPair(account_id, deposit))
-                } else {
-                    Err(InvalidSinkAccount {
-                        account_id,
-                        asset_id: *asset_id,
-                        deposit,
-                    })
-                }
-            })
-            .collect()
+        check_sink_accounts_with(asset_id, sinks, |account_id| {
+            self.accounts.contains_key(account_id)
+        })
     }
 
     #[inline]
@@ -574,26 +1928,7 @@ impl TransferLedger<Config> for Ledger {
         &self,
         posting_key: TransferPostingKeyRef<Config, Self>,
     ) -> Result<(Self::ValidProof, Self::Event), <Self as TransferLedger<Config>>::Error> {
-        let transfershape = TransferShape::select(
-            posting_key.authorization_key.is_some(),
-            posting_key.asset_id.is_some(),
-            posting_key.sources.len(),
-            posting_key.senders.len(),
-            posting_key.receivers.len(),
-            posting_key.sinks.len(),
-        );
-        if transfershape.is_none() {
-            return Err(TransferLedgerError::InvalidShape);
-        }
-        let verifying_context = self
-            .verifying_context
-            .select(transfershape.expect("This never fails because of the check above."));
-        ProofSystem::verify(
-            verifying_context,
-            &posting_key.generate_proof_input(),
-            &posting_key.proof,
-        )
-        .map_err(|_| TransferLedgerError::InvalidProof)?;
+        self.verify(posting_key)?;
         Ok((Wrap(()), ()))
     }
 
@@ -605,41 +1940,66 @@ impl TransferLedger<Config> for Ledger {
         sources: Vec<SourcePostingKey<Config, Self>>,
         sinks: Vec<SinkPostingKey<Config, Self>>,
         proof: Self::ValidProof,
-    ) -> Result<(), <Self as TransferLedger<Config>>::Error> {
+    ) -> Result<Vec<BalanceDelta<Config>>, <Self as TransferLedger<Config>>::Error> {
         let _ = (proof, super_key);
-        for WrapPair(account_id, withdraw) in sources {
-            *self
-                .accounts
-                .get_mut(&account_id)
-                .ok_or(TransferLedgerError::InvalidSourceAccount(
-                    InvalidSourceAccount {
-                        account_id,
-                        asset_id,
-                        withdraw,
-                    },
-                ))?
-                .get_mut(&asset_id)
-                .ok_or(TransferLedgerError::InvalidSourceAccount(
-                    InvalidSourceAccount {
-                        account_id,
-                        asset_id,
-                        withdraw,
-                    },
-                ))? -= withdraw;
+        let mut applied = StateDiff::default();
+        match self.apply_public_balances(asset_id, sources, sinks, &mut applied) {
+            Ok(()) => Ok(applied.balance_deltas),
+            Err(err) => {
+                for delta in applied.balance_rollback() {
+                    assert!(
+                        self.apply_balance_delta(delta),
+                        "Rolling back the balance changes which were just applied cannot fail."
+                    );
+                }
+                Err(err)
+            }
         }
-        for WrapPair(account_id, deposit) in sinks {
-            *self
+    }
+
+    #[inline]
+    fn rollback_public_balances<I>(
+        &mut self,
+        super_key: &TransferLedgerSuperPostingKey<Config, Self>,
+        rollback: I,
+    ) where
+        I: IntoIterator<Item = BalanceDelta<Config>>,
+    {
+        let _ = super_key;
+        for delta in rollback {
+            assert!(
+                self.apply_balance_delta(delta),
+                "Rolling back the balance changes which were just applied cannot fail."
+            );
+        }
+    }
+
+    #[inline]
+    fn note_policy(&self) -> Option<&dyn NotePolicy<Config>> {
+        self.note_policy.as_ref().map(|policy| policy as _)
+    }
+
+    #[inline]
+    fn fee_schedule(&self) -> Option<&FeeSchedule<AssetId, AssetValue>> {
+        self.fee_schedule.as_ref()
+    }
+
+    #[inline]
+    fn check_fee(&self, fee: &AssetValue) -> Result<(), InsufficientFee<AssetValue>> {
+        let available = match (self.fee_schedule, self.registrant) {
+            (Some(schedule), Some(registrant)) => self
                 .accounts
-                .get_mut(&account_id)
-                .ok_or(TransferLedgerError::InvalidSinkAccount(
-                    InvalidSinkAccount {
-                        account_id,
-                        asset_id,
-                        deposit,
-                    },
-                ))?
-                .entry(asset_id)
-                .or_default() += deposit;
+                .get(&registrant)
+                .and_then(|balances| balances.get(&schedule.asset_id))
+                .copied()
+                .unwrap_or_default(),
+            _ => 0,
+        };
+        if available < *fee {
+            return Err(InsufficientFee {
+                required: *fee,
+                available,
+            });
         }
         Ok(())
     }
@@ -656,13 +2016,23 @@ pub struct LedgerConnection {
 
     /// Ledger Accessor
     ledger: SharedLedger,
+
+    /// Checkpoint Header Receiver
+    ///
+    /// Subscription to the [`CheckpointHeader`]s of the ledger, opened by the first call to
+    /// [`next_header`](ledger::CheckpointStream::next_header).
+    headers: Option<watch::Receiver<CheckpointHeader>>,
 }
 
 impl LedgerConnection {
     /// Builds a new [`LedgerConnection`] for `account` and `ledger`.
     #[inline]
     pub fn new(account: AccountId, ledger: SharedLedger) -> Self {
-        Self { account, ledger }
+        Self {
+            account,
+            ledger,
+            headers: None,
+        }
     }
 }
 
@@ -703,7 +2073,103 @@ impl ledger::Write<Vec<TransferPost>> for LedgerConnection {
         &mut self,
         posts: Vec<TransferPost>,
     ) -> LocalBoxFutureResult<Self::Response, Self::Error> {
-        Box::pin(async move { Ok(self.ledger.write().await.push(self.account, posts)) })
+        Box::pin(async move { Ok(self.ledger.write().await.submit(self.account, posts)) })
+    }
+}
+
+impl ledger::Submit<Vec<TransferPost>> for LedgerConnection {
+    type TransactionId = TransactionId;
+    type Checkpoint = Checkpoint;
+    type LeafIndex = UtxoIndex;
+
+    #[inline]
+    fn submit(
+        &mut self,
+        posts: Vec<TransferPost>,
+    ) -> LocalBoxFutureResult<'_, Option<PostReceipt>, Self::Error> {
+        Box::pin(async move {
+            Ok(self
+                .ledger
+                .write()
+                .await
+                .submit_with_receipt(self.account, posts))
+        })
+    }
+}
+
+impl ledger::FeeScheduleQuery<AssetId, AssetValue> for LedgerConnection {
+    #[inline]
+    fn fee_schedule(
+        &mut self,
+    ) -> LocalBoxFutureResult<'_, Option<FeeSchedule<AssetId, AssetValue>>, Self::Error> {
+        Box::pin(async move { Ok(self.ledger.read().await.fee_schedule) })
+    }
+}
+
+impl ledger::UpgradeQuery for LedgerConnection {
+    #[inline]
+    fn upgrades(&mut self) -> LocalBoxFutureResult<'_, UpgradeSchedule, Self::Error> {
+        Box::pin(async move { Ok(self.ledger.read().await.upgrade_schedule()) })
+    }
+}
+
+impl ledger::LedgerTimeQuery for LedgerConnection {
+    #[inline]
+    fn ledger_time(&mut self) -> LocalBoxFutureResult<'_, LedgerTime, Self::Error> {
+        Box::pin(async move { Ok(self.ledger.read().await.ledger_time()) })
+    }
+}
+
+impl ledger::PoolStatsQuery<AssetId> for LedgerConnection {
+    #[inline]
+    fn pool_stats(&mut self) -> LocalBoxFutureResult<'_, PoolStats<AssetId>, Self::Error> {
+        Box::pin(async move { Ok(self.ledger.read().await.pool_stats().clone()) })
+    }
+}
+
+impl ledger::BlockStatusQuery for LedgerConnection {
+    #[inline]
+    fn block_status(&mut self) -> LocalBoxFutureResult<'_, BlockStatus, Self::Error> {
+        Box::pin(async move { Ok(self.ledger.read().await.block_status()) })
+    }
+}
+
+impl ledger::RootHistoryQuery for LedgerConnection {
+    type Checkpoint = Checkpoint;
+    type Output = UtxoAccumulatorRoots;
+
+    #[inline]
+    fn root_at<'s>(
+        &'s mut self,
+        checkpoint: &'s Self::Checkpoint,
+    ) -> LocalBoxFutureResult<'s, Result<Self::Output, RootHistoryError>, Self::Error> {
+        Box::pin(async move { Ok(self.ledger.read().await.root_at(checkpoint)) })
+    }
+}
+
+impl ledger::CheckpointStream for LedgerConnection {
+    type Checkpoint = Checkpoint;
+    type Output = UtxoAccumulatorRoots;
+
+    #[inline]
+    fn next_header(&mut self) -> LocalBoxFutureResult<'_, CheckpointHeader, Self::Error> {
+        Box::pin(async move {
+            match &mut self.headers {
+                Some(headers) => {
+                    headers
+                        .changed()
+                        .await
+                        .expect("The ledger outlives the connections which share it.");
+                    Ok(headers.borrow_and_update().clone())
+                }
+                headers => {
+                    let mut receiver = self.ledger.read().await.subscribe();
+                    let header = receiver.borrow_and_update().clone();
+                    *headers = Some(receiver);
+                    Ok(header)
+                }
+            }
+        })
     }
 }
 
@@ -0,0 +1,622 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Simulated Network
+//!
+//! A [`LedgerConnection`](super::LedgerConnection) reaches the ledger instantly and never fails.
+//! [`NetworkConnection`]s instead route every request through a [`Network`] which applies a
+//! [`NetworkModel`] to it: requests and responses take some time to travel, can be lost, can be
+//! cut off by [`Partition`]s, and writes can be delayed past the writes sent after them. Wallets
+//! see these faults as [`NetworkError`]s, which lets the simulation exercise retries, gap
+//! detection and rebroadcasts.
+//!
+//! The [`Network`] runs on a virtual clock which only moves when messages travel or when it is
+//! [`advance`](Network::advance)d, and it drives the block production of the ledger with the same
+//! clock. All the randomness of the [`Network`] comes from a seeded generator, so a simulation
+//! which issues the same requests in the same order sees the same faults.
+
+use crate::{
+    config::{
+        utxo::{AssetId, AssetValue, Checkpoint},
+        AccountId, Config, TransferPost,
+    },
+    signer::InitialSyncData,
+    simulation::ledger::{history::UtxoAccumulatorRoots, Ledger, SharedLedger},
+};
+use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
+use core::time::Duration;
+use manta_accounting::{
+    asset::AssetList,
+    transfer::fee::FeeSchedule,
+    wallet::{
+        ledger::{self, BlockStatus, PoolStats, ReadResponse, RootHistoryError},
+        signer::SyncData,
+        test::PublicBalanceOracle,
+    },
+};
+use manta_crypto::rand::{ChaCha20Rng, Rand, SeedableRng};
+use manta_util::future::{LocalBoxFuture, LocalBoxFutureResult};
+use tokio::sync::RwLock;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Latency Distribution
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LatencyDistribution {
+    /// Constant Latency
+    Constant(Duration),
+
+    /// Uniform Latency between `minimum` and `maximum`, inclusive
+    Uniform {
+        /// Minimum Latency
+        minimum: Duration,
+
+        /// Maximum Latency
+        maximum: Duration,
+    },
+
+    /// Shifted Exponential Latency
+    ///
+    /// Every message takes `minimum` time plus an exponentially distributed delay with mean
+    /// `mean`, which models a link with rare but long stalls.
+    Exponential {
+        /// Minimum Latency
+        minimum: Duration,
+
+        /// Mean of the Extra Delay
+        mean: Duration,
+    },
+}
+
+impl LatencyDistribution {
+    /// Samples a latency from `self` using `rng`.
+    #[inline]
+    pub fn sample(&self, rng: &mut ChaCha20Rng) -> Duration {
+        match *self {
+            Self::Constant(latency) => latency,
+            Self::Uniform { minimum, maximum } => {
+                if maximum <= minimum {
+                    return minimum;
+                }
+                let spread = (maximum - minimum).as_nanos() as u64;
+                minimum + Duration::from_nanos(rng.gen_range(0..=spread))
+            }
+            Self::Exponential { minimum, mean } => {
+                let uniform = rng.gen_range(0.0..1.0f64);
+                minimum + mean.mul_f64(-(1.0 - uniform).ln())
+            }
+        }
+    }
+}
+
+impl Default for LatencyDistribution {
+    #[inline]
+    fn default() -> Self {
+        Self::Constant(Duration::ZERO)
+    }
+}
+
+/// Network Partition
+///
+/// During the time between [`start`](Self::start) and [`end`](Self::end), every message sent from
+/// or to the [`accounts`](Self::accounts) is lost. A partition with no accounts cuts off every
+/// connection to the ledger.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Partition {
+    /// Start Time
+    pub start: Duration,
+
+    /// End Time
+    pub end: Duration,
+
+    /// Isolated Accounts
+    pub accounts: Vec<AccountId>,
+}
+
+impl Partition {
+    /// Builds a new [`Partition`] which isolates `accounts` from `start` until `end`.
+    #[inline]
+    pub fn new(start: Duration, end: Duration, accounts: Vec<AccountId>) -> Self {
+        Self {
+            start,
+            end,
+            accounts,
+        }
+    }
+
+    /// Returns `true` if `self` isolates `account` at time `now`.
+    #[inline]
+    pub fn isolates(&self, account: &AccountId, now: Duration) -> bool {
+        self.start <= now
+            && now < self.end
+            && (self.accounts.is_empty() || self.accounts.contains(account))
+    }
+}
+
+/// Network Fault Model
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NetworkModel {
+    /// Latency of a Single Message
+    pub latency: LatencyDistribution,
+
+    /// Probability that a Request is Lost
+    pub request_loss: f64,
+
+    /// Probability that a Response is Lost
+    ///
+    /// The ledger has already served a request whose response is lost, so a lost write response
+    /// leaves the wallet unsure of whether its posts were accepted.
+    pub response_loss: f64,
+
+    /// Probability that a Write is Reordered
+    ///
+    /// A reordered write is held back for an extra [`reorder_delay`](Self::reorder_delay), so
+    /// writes sent after it can reach the ledger first. The wallet never gets its response.
+    pub reorder_probability: f64,
+
+    /// Extra Delay of Reordered Writes
+    pub reorder_delay: LatencyDistribution,
+
+    /// Request Timeout
+    ///
+    /// Requests whose round trip takes longer than this timeout fail with
+    /// [`NetworkError::Timeout`], whether or not the ledger served them.
+    pub timeout: Option<Duration>,
+
+    /// Partitions
+    pub partitions: Vec<Partition>,
+}
+
+impl NetworkModel {
+    /// Returns `true` if some partition of `self` isolates `account` at time `now`.
+    #[inline]
+    pub fn is_partitioned(&self, account: &AccountId, now: Duration) -> bool {
+        self.partitions
+            .iter()
+            .any(|partition| partition.isolates(account, now))
+    }
+}
+
+/// Network Error
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum NetworkError {
+    /// Partitioned
+    ///
+    /// The account was cut off from the ledger by a [`Partition`] when the request was sent or
+    /// when its response came back.
+    Partitioned,
+
+    /// Request Lost
+    ///
+    /// The request never reached the ledger.
+    RequestLost,
+
+    /// Response Lost
+    ///
+    /// The ledger served the request but its response was lost.
+    ResponseLost,
+
+    /// Timeout
+    ///
+    /// The response did not arrive within the timeout of the [`NetworkModel`]. The ledger may or
+    /// may not have served the request.
+    Timeout,
+}
+
+/// Network Statistics
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct NetworkStats {
+    /// Number of Requests Sent
+    pub requests: u64,
+
+    /// Number of Requests Dropped by Partitions
+    pub partitioned: u64,
+
+    /// Number of Requests Lost
+    pub requests_lost: u64,
+
+    /// Number of Responses Lost
+    pub responses_lost: u64,
+
+    /// Number of Reordered Writes
+    pub reordered: u64,
+
+    /// Number of Timeouts
+    pub timeouts: u64,
+}
+
+/// Delayed Write
+#[derive(Debug)]
+struct DelayedWrite {
+    /// Sending Account
+    account: AccountId,
+
+    /// Posts
+    posts: Vec<TransferPost>,
+}
+
+/// Simulated Network
+#[derive(Debug)]
+pub struct Network {
+    /// Ledger
+    ledger: SharedLedger,
+
+    /// Fault Model
+    model: NetworkModel,
+
+    /// Random Number Generator
+    rng: ChaCha20Rng,
+
+    /// Virtual Clock
+    now: Duration,
+
+    /// Writes in Flight
+    ///
+    /// Delayed writes are ordered by their delivery time and then by the order they were sent.
+    in_flight: BTreeMap<(Duration, u64), DelayedWrite>,
+
+    /// Statistics
+    stats: NetworkStats,
+}
+
+impl Network {
+    /// Builds a new [`Network`] in front of `ledger` which applies `model` to every message, drawing
+    /// its randomness from a generator seeded with `seed`.
+    #[inline]
+    pub fn new(ledger: SharedLedger, model: NetworkModel, seed: u64) -> Self {
+        Self {
+            ledger,
+            model,
+            rng: ChaCha20Rng::seed_from_u64(seed),
+            now: Duration::ZERO,
+            in_flight: Default::default(),
+            stats: Default::default(),
+        }
+    }
+
+    /// Returns the ledger behind `self`.
+    #[inline]
+    pub fn ledger(&self) -> &SharedLedger {
+        &self.ledger
+    }
+
+    /// Returns the fault model of `self`.
+    #[inline]
+    pub fn model(&self) -> &NetworkModel {
+        &self.model
+    }
+
+    /// Replaces the fault model of `self` with `model`. Writes in flight keep their delivery time.
+    #[inline]
+    pub fn set_model(&mut self, model: NetworkModel) {
+        self.model = model;
+    }
+
+    /// Returns the current time of the virtual clock of `self`.
+    #[inline]
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    /// Returns the statistics of `self`.
+    #[inline]
+    pub fn stats(&self) -> &NetworkStats {
+        &self.stats
+    }
+
+    /// Returns the number of delayed writes which have not reached the ledger yet.
+    #[inline]
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    /// Lets `elapsed` time pass, delivering the delayed writes which reach the ledger in the
+    /// meantime and letting the ledger produce its blocks.
+    #[inline]
+    pub async fn advance(&mut self, elapsed: Duration) {
+        let until = self.now + elapsed;
+        let mut ledger = self.ledger.write().await;
+        while let Some(entry) = self.in_flight.first_entry() {
+            let delivery = entry.key().0;
+            if delivery > until {
+                break;
+            }
+            let write = entry.remove();
+            ledger.advance_time(delivery - self.now);
+            self.now = delivery;
+            ledger.submit(write.account, write.posts);
+        }
+        ledger.advance_time(until - self.now);
+        self.now = until;
+    }
+
+    /// Delivers every delayed write, advancing the virtual clock until the last one reaches the
+    /// ledger.
+    #[inline]
+    pub async fn flush(&mut self) {
+        if let Some(((delivery, _), _)) = self.in_flight.last_key_value() {
+            let elapsed = *delivery - self.now;
+            self.advance(elapsed).await;
+        }
+    }
+
+    /// Samples `true` with probability `probability`.
+    #[inline]
+    fn sample_fault(&mut self, probability: f64) -> bool {
+        probability > 0.0 && self.rng.gen_range(0.0..1.0f64) < probability
+    }
+
+    /// Sends a request from `account` to the ledger, returning the time it took to reach the
+    /// ledger.
+    #[inline]
+    async fn send(&mut self, account: &AccountId) -> Result<Duration, NetworkError> {
+        self.stats.requests += 1;
+        if self.model.is_partitioned(account, self.now) {
+            self.stats.partitioned += 1;
+            return Err(NetworkError::Partitioned);
+        }
+        let latency = self.model.latency.sample(&mut self.rng);
+        if self.sample_fault(self.model.request_loss) {
+            self.stats.requests_lost += 1;
+            return Err(NetworkError::RequestLost);
+        }
+        self.advance(latency).await;
+        Ok(latency)
+    }
+
+    /// Sends the response of a request which took `request_latency` to reach the ledger back to
+    /// `account`.
+    #[inline]
+    async fn respond(
+        &mut self,
+        account: &AccountId,
+        request_latency: Duration,
+    ) -> Result<(), NetworkError> {
+        let latency = self.model.latency.sample(&mut self.rng);
+        self.advance(latency).await;
+        if self.model.is_partitioned(account, self.now) {
+            self.stats.partitioned += 1;
+            return Err(NetworkError::Partitioned);
+        }
+        if self.sample_fault(self.model.response_loss) {
+            self.stats.responses_lost += 1;
+            return Err(NetworkError::ResponseLost);
+        }
+        match self.model.timeout {
+            Some(timeout) if request_latency + latency > timeout => {
+                self.stats.timeouts += 1;
+                Err(NetworkError::Timeout)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Sends a read request from `account` which the ledger serves with `serve`.
+    #[inline]
+    async fn read<T, F>(&mut self, account: &AccountId, serve: F) -> Result<T, NetworkError>
+    where
+        F: FnOnce(&Ledger) -> T,
+    {
+        let request_latency = self.send(account).await?;
+        let response = serve(&*self.ledger.read().await);
+        self.respond(account, request_latency).await?;
+        Ok(response)
+    }
+
+    /// Sends a write request with `posts` from `account`.
+    #[inline]
+    async fn write(
+        &mut self,
+        account: AccountId,
+        posts: Vec<TransferPost>,
+    ) -> Result<bool, NetworkError> {
+        if self.sample_fault(self.model.reorder_probability) {
+            self.stats.requests += 1;
+            if self.model.is_partitioned(&account, self.now) {
+                self.stats.partitioned += 1;
+                return Err(NetworkError::Partitioned);
+            }
+            let delivery = self.now
+                + self.model.latency.sample(&mut self.rng)
+                + self.model.reorder_delay.sample(&mut self.rng);
+            self.in_flight.insert(
+                (delivery, self.stats.requests),
+                DelayedWrite { account, posts },
+            );
+            self.stats.reordered += 1;
+            self.stats.timeouts += 1;
+            return Err(NetworkError::Timeout);
+        }
+        let request_latency = self.send(&account).await?;
+        let response = self.ledger.write().await.submit(account, posts);
+        self.respond(&account, request_latency).await?;
+        Ok(response)
+    }
+}
+
+/// Shared Network
+pub type SharedNetwork = Arc<RwLock<Network>>;
+
+/// Network Connection
+///
+/// Ledger connection which goes through a [`Network`]. Its [`PublicBalanceOracle`] implementation
+/// reads the ledger directly, since it is only used to check the outcome of the simulation.
+#[derive(Clone, Debug)]
+pub struct NetworkConnection {
+    /// Ledger Account
+    account: AccountId,
+
+    /// Network Accessor
+    network: SharedNetwork,
+}
+
+impl NetworkConnection {
+    /// Builds a new [`NetworkConnection`] for `account` and `network`.
+    #[inline]
+    pub fn new(account: AccountId, network: SharedNetwork) -> Self {
+        Self { account, network }
+    }
+}
+
+impl ledger::Connection for NetworkConnection {
+    type Error = NetworkError;
+}
+
+impl ledger::Read<SyncData<Config>> for NetworkConnection {
+    type Checkpoint = Checkpoint;
+
+    #[inline]
+    fn read<'s>(
+        &'s mut self,
+        checkpoint: &'s Self::Checkpoint,
+    ) -> LocalBoxFutureResult<'s, ReadResponse<SyncData<Config>>, Self::Error> {
+        Box::pin(async move {
+            self.network
+                .write()
+                .await
+                .read(&self.account, |ledger| ledger.pull(checkpoint))
+                .await
+        })
+    }
+}
+
+impl ledger::Read<InitialSyncData> for NetworkConnection {
+    type Checkpoint = Checkpoint;
+
+    #[inline]
+    fn read<'s>(
+        &'s mut self,
+        checkpoint: &'s Self::Checkpoint,
+    ) -> LocalBoxFutureResult<'s, ReadResponse<InitialSyncData>, Self::Error> {
+        let _ = checkpoint;
+        Box::pin(async move {
+            self.network
+                .write()
+                .await
+                .read(&self.account, |ledger| ledger.initial_read())
+                .await
+        })
+    }
+}
+
+impl ledger::Write<Vec<TransferPost>> for NetworkConnection {
+    type Response = bool;
+
+    #[inline]
+    fn write(
+        &mut self,
+        posts: Vec<TransferPost>,
+    ) -> LocalBoxFutureResult<'_, Self::Response, Self::Error> {
+        Box::pin(async move { self.network.write().await.write(self.account, posts).await })
+    }
+}
+
+impl ledger::PoolStatsQuery<AssetId> for NetworkConnection {
+    #[inline]
+    fn pool_stats(&mut self) -> LocalBoxFutureResult<'_, PoolStats<AssetId>, Self::Error> {
+        Box::pin(async move {
+            self.network
+                .write()
+                .await
+                .read(&self.account, |ledger| ledger.pool_stats().clone())
+                .await
+        })
+    }
+}
+
+impl ledger::BlockStatusQuery for NetworkConnection {
+    #[inline]
+    fn block_status(&mut self) -> LocalBoxFutureResult<'_, BlockStatus, Self::Error> {
+        Box::pin(async move {
+            self.network
+                .write()
+                .await
+                .read(&self.account, |ledger| ledger.block_status())
+                .await
+        })
+    }
+}
+
+impl ledger::RootHistoryQuery for NetworkConnection {
+    type Checkpoint = Checkpoint;
+    type Output = UtxoAccumulatorRoots;
+
+    #[inline]
+    fn root_at<'s>(
+        &'s mut self,
+        checkpoint: &'s Self::Checkpoint,
+    ) -> LocalBoxFutureResult<'s, Result<Self::Output, RootHistoryError>, Self::Error> {
+        Box::pin(async move {
+            self.network
+                .write()
+                .await
+                .read(&self.account, |ledger| ledger.root_at(checkpoint))
+                .await
+        })
+    }
+}
+
+impl ledger::FeeScheduleQuery<AssetId, AssetValue> for NetworkConnection {
+    #[inline]
+    fn fee_schedule(
+        &mut self,
+    ) -> LocalBoxFutureResult<'_, Option<FeeSchedule<AssetId, AssetValue>>, Self::Error> {
+        Box::pin(async move {
+            self.network
+                .write()
+                .await
+                .read(&self.account, |ledger| ledger.fee_schedule)
+                .await
+        })
+    }
+}
+
+impl PublicBalanceOracle<Config> for NetworkConnection {
+    #[inline]
+    fn public_balances(&self) -> LocalBoxFuture<'_, Option<AssetList<AssetId, AssetValue>>> {
+        Box::pin(async move {
+            let ledger = self.network.read().await.ledger.clone();
+            let balances = ledger.read().await.public_balances(self.account);
+            balances
+        })
+    }
+}
@@ -0,0 +1,247 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sharded Nullifier Set
+//!
+//! The [`NullifierSet`] of the [`Ledger`](super::Ledger) keeps its nullifiers in the order they
+//! were spent, since checkpoints and nullifier set commitments refer to them by position, and
+//! indexes their commitments in shards selected by the hash of the commitment. Lookups only need
+//! shared access to the set, so [`NullifierSet::contains_many`] checks a whole batch of nullifiers
+//! with one task per shard, and the nullifiers of a block never wait on each other.
+//!
+//! # Persistence
+//!
+//! A [`NullifierStore`] attached to the set with [`NullifierSet::set_store`] is told about every
+//! nullifier which is inserted into the set or removed from it, so it can mirror the set in
+//! durable storage. The set is rebuilt from the stored nullifiers with [`NullifierSet::restore`].
+
+use crate::config::{utxo, Nullifier};
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+};
+use manta_accounting::transfer::utxo::protocol;
+use manta_util::rayon::iter::{
+    IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
+use std::collections::{hash_map::DefaultHasher, HashSet};
+
+/// Nullifier Commitment Type
+pub type NullifierCommitment = protocol::NullifierCommitment<utxo::Config>;
+
+/// Nullifier Store
+///
+/// Persistence hook of a [`NullifierSet`].
+pub trait NullifierStore: Send + Sync {
+    /// Persists `nullifier`, which was inserted into the set at `index`.
+    fn insert(&mut self, index: usize, nullifier: &Nullifier);
+
+    /// Discards the persisted nullifiers from `len` onwards, after they were removed from the set.
+    fn truncate(&mut self, len: usize);
+}
+
+/// Sharded Nullifier Set
+///
+/// Two nullifiers with the same commitment spend the same note, so the set only compares the
+/// commitments of its nullifiers.
+pub struct NullifierSet {
+    /// Nullifiers in Insertion Order
+    nullifiers: Vec<Nullifier>,
+
+    /// Shards
+    ///
+    /// Every shard holds the commitments of the nullifiers that hash into it.
+    shards: Vec<HashSet<NullifierCommitment>>,
+
+    /// Nullifier Store
+    store: Option<Box<dyn NullifierStore>>,
+}
+
+impl NullifierSet {
+    /// Default Number of Shards
+    pub const DEFAULT_SHARDS: usize = 16;
+
+    /// Builds an empty [`NullifierSet`] with `shards`-many shards.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `shards` is zero.
+    #[inline]
+    pub fn new(shards: usize) -> Self {
+        assert!(shards > 0, "Nullifier sets must have at least one shard.");
+        Self {
+            nullifiers: Vec::new(),
+            shards: (0..shards).map(|_| HashSet::new()).collect(),
+            store: None,
+        }
+    }
+
+    /// Builds a [`NullifierSet`] with `shards`-many shards from the `nullifiers` loaded from a
+    /// [`NullifierStore`], skipping the ones whose commitment is already in the set.
+    #[inline]
+    pub fn restore<I>(shards: usize, nullifiers: I) -> Self
+    where
+        I: IntoIterator<Item = Nullifier>,
+    {
+        let mut set = Self::new(shards);
+        for nullifier in nullifiers {
+            set.insert(nullifier);
+        }
+        set
+    }
+
+    /// Returns the number of nullifiers in `self`.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.nullifiers.len()
+    }
+
+    /// Returns `true` if `self` has no nullifiers.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.nullifiers.is_empty()
+    }
+
+    /// Returns the number of shards of `self`.
+    #[inline]
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns the nullifier at `index` in insertion order.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&Nullifier> {
+        self.nullifiers.get(index)
+    }
+
+    /// Returns an iterator over the nullifiers of `self` in insertion order.
+    #[inline]
+    pub fn iter(&self) -> core::slice::Iter<'_, Nullifier> {
+        self.nullifiers.iter()
+    }
+
+    /// Returns the index of the shard of `commitment`.
+    #[inline]
+    fn shard_index(&self, commitment: &NullifierCommitment) -> usize {
+        let mut hasher = DefaultHasher::new();
+        commitment.hash(&mut hasher);
+        (hasher.finish() % self.shards.len() as u64) as usize
+    }
+
+    /// Returns `true` if a nullifier with the same commitment as `nullifier` is in `self`.
+    #[inline]
+    pub fn contains(&self, nullifier: &Nullifier) -> bool {
+        let commitment = &nullifier.nullifier.commitment;
+        self.shards[self.shard_index(commitment)].contains(commitment)
+    }
+
+    /// Returns whether each one of the `nullifiers` is in `self`, as in
+    /// [`contains`](Self::contains), looking up the shards in parallel.
+    #[inline]
+    pub fn contains_many(&self, nullifiers: &[Nullifier]) -> Vec<bool> {
+        let mut queries = vec![Vec::new(); self.shards.len()];
+        for (i, nullifier) in nullifiers.iter().enumerate() {
+            queries[self.shard_index(&nullifier.nullifier.commitment)].push(i);
+        }
+        let found = queries
+            .into_par_iter()
+            .zip(self.shards.par_iter())
+            .map(|(queries, shard)| {
+                queries
+                    .into_iter()
+                    .filter(|i| shard.contains(&nullifiers[*i].nullifier.commitment))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut result = vec![false; nullifiers.len()];
+        for i in found.into_iter().flatten() {
+            result[i] = true;
+        }
+        result
+    }
+
+    /// Inserts `nullifier` at the end of `self`, returning `false` if a nullifier with the same
+    /// commitment is already in `self`.
+    #[inline]
+    pub fn insert(&mut self, nullifier: Nullifier) -> bool {
+        let commitment = nullifier.nullifier.commitment;
+        let shard_index = self.shard_index(&commitment);
+        if !self.shards[shard_index].insert(commitment) {
+            return false;
+        }
+        if let Some(store) = self.store.as_mut() {
+            store.insert(self.nullifiers.len(), &nullifier);
+        }
+        self.nullifiers.push(nullifier);
+        true
+    }
+
+    /// Removes the latest nullifier from `self` and returns it, if `self` is not empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<Nullifier> {
+        let nullifier = self.nullifiers.pop()?;
+        let commitment = &nullifier.nullifier.commitment;
+        let shard_index = self.shard_index(commitment);
+        self.shards[shard_index].remove(commitment);
+        if let Some(store) = self.store.as_mut() {
+            store.truncate(self.nullifiers.len());
+        }
+        Some(nullifier)
+    }
+
+    /// Attaches `store` to `self`, replacing the previous one, or detaches it if `store` is
+    /// `None`. The nullifiers which are already in `self` are not sent to the new store.
+    #[inline]
+    pub fn set_store(&mut self, store: Option<Box<dyn NullifierStore>>) {
+        self.store = store;
+    }
+}
+
+impl protocol::NullifierLookup<utxo::Config> for NullifierSet {
+    #[inline]
+    fn contains(&self, nullifier: &Nullifier) -> bool {
+        Self::contains(self, nullifier)
+    }
+}
+
+impl Debug for NullifierSet {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("NullifierSet")
+            .field("nullifiers", &self.nullifiers)
+            .field("shards", &self.shards.len())
+            .field("has_store", &self.store.is_some())
+            .finish()
+    }
+}
+
+impl Default for NullifierSet {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_SHARDS)
+    }
+}
+
+impl<'s> IntoIterator for &'s NullifierSet {
+    type Item = &'s Nullifier;
+    type IntoIter = core::slice::Iter<'s, Nullifier>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
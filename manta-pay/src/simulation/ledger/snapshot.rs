@@ -0,0 +1,492 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Ledger Snapshots
+//!
+//! A snapshot is a portable dump of the state of a [`Ledger`]: the UTXOs and notes of every tree
+//! of the [`UtxoMerkleForest`], the nullifiers, the public balances, the block checkpoints and the
+//! pool statistics. Test networks, forks and disaster recovery can start a new ledger from a
+//! snapshot with [`Ledger::import_snapshot`] instead of replaying every post ever accepted.
+//!
+//! # Wire Format
+//!
+//! A snapshot starts with the [`MAGIC`] bytes and the little-endian `u16` [`VERSION`] of the
+//! format, followed by a sequence of records. Every record is the little-endian `u64` length of
+//! its body followed by the body itself, which is the [`Encode`] representation of its value, as
+//! in the frames of [`manta_util::codec`] streams. The records are, in order:
+//!
+//! 1. the height of the ledger and the capacity of its root history,
+//! 2. for every tree of the [`UtxoMerkleForest`], the number of its UTXOs, the UTXOs together with
+//!    their notes in insertion order, and the root of the tree,
+//! 3. the number of nullifiers, the nullifiers in insertion order, and the commitment to all of
+//!    them,
+//! 4. the number of public balances and the balances themselves, sorted by account and asset id,
+//! 5. the [`BlockTime`] model of the ledger, if any, the number of block checkpoints and the
+//!    checkpoints themselves,
+//! 6. the fields of the [`PoolStats`] of the ledger.
+//!
+//! The snapshot ends with the Blake2s digest of all the bytes before it. The accumulator nodes are
+//! not written out: importing a snapshot rebuilds every tree from its UTXOs and checks it against
+//! the recorded root.
+//!
+//! # Limitations
+//!
+//! Snapshots only hold the state of the ledger, so the epochs other than the initial one, the note
+//! policy, the registration limit and the legacy nullifier deadline must be set again on the
+//! imported ledger. The pending batches of the mempool and the registrations counted by the
+//! registration limit are not part of the snapshot either.
+//!
+//! [`UtxoMerkleForest`]: super::UtxoMerkleForest
+
+use crate::{
+    config::{
+        utxo::{
+            Checkpoint, FullIncomingNote, MerkleTreeConfiguration, NullifierSetCommitment,
+            Parameters,
+        },
+        AccountId, AssetId, AssetValue, Config, MultiVerifyingContext, Nullifier, Utxo,
+        UtxoAccumulatorModel,
+    },
+    simulation::ledger::{Ledger, MerkleForestIndex},
+};
+use alloc::vec::Vec;
+use blake2::{Blake2s256, Digest};
+use core::{fmt, time::Duration};
+use manta_accounting::{
+    transfer::UtxoAccumulatorOutput,
+    wallet::ledger::{BlockTime, PoolStats},
+};
+use manta_crypto::{
+    accumulator::ItemHashFunction,
+    merkle_tree::forest::{Configuration, FixedIndex, Forest},
+};
+use manta_util::codec::{Decode, DecodeError, Encode};
+use std::io;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Snapshot Magic Bytes
+pub const MAGIC: [u8; 8] = *b"MANTALSS";
+
+/// Snapshot Format Version
+pub const VERSION: u16 = 1;
+
+/// Maximum Record Length
+///
+/// This is the largest record body in bytes accepted by [`Ledger::import_snapshot`].
+pub const MAX_RECORD_LENGTH: usize = 1 << 24;
+
+/// Snapshot Error
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// I/O Error
+    Io(io::Error),
+
+    /// Invalid Magic Bytes
+    ///
+    /// The input does not start with [`MAGIC`], so it is not a ledger snapshot.
+    InvalidMagic,
+
+    /// Unsupported Version
+    ///
+    /// The snapshot was written in a version of the format which this build cannot read.
+    UnsupportedVersion(u16),
+
+    /// Record Length Exceeds [`MAX_RECORD_LENGTH`]
+    RecordTooLarge(u64),
+
+    /// Malformed Record
+    ///
+    /// A record body could not be decoded or was not consumed completely.
+    MalformedRecord,
+
+    /// Inconsistent State
+    ///
+    /// The records decode correctly but do not describe a valid ledger, for example because a
+    /// UTXO is stored in the wrong tree or a tree does not match its recorded root.
+    InconsistentState,
+
+    /// Digest Mismatch
+    ///
+    /// The snapshot does not match the digest at its end, so it was corrupted or truncated.
+    DigestMismatch,
+}
+
+impl fmt::Display for SnapshotError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "I/O Error: {err}"),
+            Self::InvalidMagic => write!(f, "Input is not a ledger snapshot."),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Unsupported snapshot version {version}.")
+            }
+            Self::RecordTooLarge(len) => write!(f, "Record length {len} exceeds the maximum."),
+            Self::MalformedRecord => write!(f, "Malformed snapshot record."),
+            Self::InconsistentState => write!(f, "Snapshot does not describe a valid ledger."),
+            Self::DigestMismatch => write!(f, "Snapshot does not match its digest."),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<io::Error> for SnapshotError {
+    #[inline]
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Snapshot Writer
+struct SnapshotWriter<W> {
+    /// Writer
+    writer: W,
+
+    /// Record Buffer
+    buffer: Vec<u8>,
+
+    /// Digest of the Bytes Written so Far
+    hasher: Blake2s256,
+}
+
+impl<W> SnapshotWriter<W>
+where
+    W: AsyncWrite + Unpin,
+{
+    /// Builds a new [`SnapshotWriter`] over `writer`, writing the snapshot header.
+    #[inline]
+    async fn new(writer: W) -> io::Result<Self> {
+        let mut this = Self {
+            writer,
+            buffer: Vec::new(),
+            hasher: Blake2s256::new(),
+        };
+        let mut header = MAGIC.to_vec();
+        header.extend_from_slice(&VERSION.to_le_bytes());
+        this.write_raw(&header).await?;
+        Ok(this)
+    }
+
+    /// Writes `bytes` to the underlying writer, adding them to the digest.
+    #[inline]
+    async fn write_raw(&mut self, bytes: &[u8]) -> io::Result<()> {
+        Digest::update(&mut self.hasher, bytes);
+        self.writer.write_all(bytes).await
+    }
+
+    /// Writes `value` as a single record.
+    #[inline]
+    async fn write<T>(&mut self, value: &T) -> io::Result<()>
+    where
+        T: Encode + ?Sized,
+    {
+        let mut buffer = core::mem::take(&mut self.buffer);
+        buffer.clear();
+        value
+            .encode(&mut buffer)
+            .expect("Writing to a `Vec<u8>` cannot fail.");
+        self.write_raw(&(buffer.len() as u64).to_le_bytes()).await?;
+        self.write_raw(&buffer).await?;
+        self.buffer = buffer;
+        Ok(())
+    }
+
+    /// Writes the digest of the snapshot and flushes the underlying writer.
+    #[inline]
+    async fn finish(mut self) -> io::Result<()> {
+        let digest = self.hasher.finalize();
+        self.writer.write_all(&digest).await?;
+        self.writer.flush().await
+    }
+}
+
+/// Snapshot Reader
+struct SnapshotReader<R> {
+    /// Reader
+    reader: R,
+
+    /// Record Buffer
+    buffer: Vec<u8>,
+
+    /// Digest of the Bytes Read so Far
+    hasher: Blake2s256,
+}
+
+impl<R> SnapshotReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    /// Builds a new [`SnapshotReader`] over `reader`, checking the snapshot header.
+    #[inline]
+    async fn new(reader: R) -> Result<Self, SnapshotError> {
+        let mut this = Self {
+            reader,
+            buffer: Vec::new(),
+            hasher: Blake2s256::new(),
+        };
+        let mut magic = [0; MAGIC.len()];
+        this.read_raw(&mut magic).await?;
+        if magic != MAGIC {
+            return Err(SnapshotError::InvalidMagic);
+        }
+        let mut version = [0; 2];
+        this.read_raw(&mut version).await?;
+        match u16::from_le_bytes(version) {
+            VERSION => Ok(this),
+            version => Err(SnapshotError::UnsupportedVersion(version)),
+        }
+    }
+
+    /// Fills `output` from the underlying reader, adding the bytes to the digest.
+    #[inline]
+    async fn read_raw(&mut self, output: &mut [u8]) -> io::Result<()> {
+        self.reader.read_exact(output).await?;
+        Digest::update(&mut self.hasher, &*output);
+        Ok(())
+    }
+
+    /// Reads a single record and decodes it.
+    #[inline]
+    async fn read<T>(&mut self) -> Result<T, SnapshotError>
+    where
+        T: Decode,
+    {
+        let mut len = [0; 8];
+        self.read_raw(&mut len).await?;
+        let len = u64::from_le_bytes(len);
+        if len > MAX_RECORD_LENGTH as u64 {
+            return Err(SnapshotError::RecordTooLarge(len));
+        }
+        let mut buffer = core::mem::take(&mut self.buffer);
+        buffer.resize(len as usize, 0);
+        self.read_raw(&mut buffer).await?;
+        let mut body = buffer.as_slice();
+        let value = T::decode(&mut body).map_err(|err| match err {
+            DecodeError::Decode(_) => SnapshotError::MalformedRecord,
+            DecodeError::Read(err) => match err {},
+        });
+        let trailing = body.len();
+        self.buffer = buffer;
+        match value {
+            Ok(_) if trailing != 0 => Err(SnapshotError::MalformedRecord),
+            value => value,
+        }
+    }
+
+    /// Reads a record holding a number of items as a `u64`.
+    #[inline]
+    async fn read_len(&mut self) -> Result<usize, SnapshotError> {
+        usize::try_from(self.read::<u64>().await?).map_err(|_| SnapshotError::MalformedRecord)
+    }
+
+    /// Reads the digest at the end of the snapshot and checks it against the bytes read so far.
+    #[inline]
+    async fn finish(mut self) -> Result<(), SnapshotError> {
+        let mut digest = [0; 32];
+        self.reader.read_exact(&mut digest).await?;
+        if self.hasher.finalize()[..] != digest {
+            return Err(SnapshotError::DigestMismatch);
+        }
+        Ok(())
+    }
+}
+
+impl Ledger {
+    /// Writes a snapshot of `self` to `writer`. See the [module-level documentation](self) for
+    /// the format of the snapshot.
+    #[inline]
+    pub async fn export_snapshot<W>(&self, writer: W) -> io::Result<()>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut writer = SnapshotWriter::new(writer).await?;
+        writer.write(&self.height).await?;
+        writer.write(&(self.root_history.capacity() as u64)).await?;
+        for i in 0..MerkleTreeConfiguration::FOREST_WIDTH {
+            let index = MerkleForestIndex::from_index(i);
+            let shard = &self.shards[&index];
+            writer.write(&(shard.len() as u64)).await?;
+            for entry in shard {
+                writer.write(entry).await?;
+            }
+            writer
+                .write(self.utxo_forest.forest.get(index).root())
+                .await?;
+        }
+        writer.write(&(self.nullifiers.len() as u64)).await?;
+        for nullifier in &self.nullifiers {
+            writer.write(nullifier).await?;
+        }
+        writer
+            .write(&self.nullifier_commitments[self.nullifiers.len()].0)
+            .await?;
+        let mut balances = self
+            .accounts
+            .iter()
+            .flat_map(|(account, balances)| {
+                balances
+                    .iter()
+                    .map(move |(id, value)| (*account, (*id, *value)))
+            })
+            .collect::<Vec<_>>();
+        balances.sort_unstable_by_key(|(account, (id, _))| (*account, *id));
+        writer.write(&(balances.len() as u64)).await?;
+        for balance in &balances {
+            writer.write(balance).await?;
+        }
+        writer
+            .write(&self.block_time.map(|block_time| {
+                (
+                    block_time.interval.as_nanos() as u64,
+                    block_time.confirmation_depth,
+                )
+            }))
+            .await?;
+        writer.write(&(self.blocks.len() as u64)).await?;
+        for checkpoint in &self.blocks {
+            writer.write(checkpoint).await?;
+        }
+        writer.write(&self.stats.total_notes).await?;
+        writer.write(&self.stats.total_spends).await?;
+        writer.write(&self.stats.notes_per_asset).await?;
+        writer.write(&self.stats.spend_frequency).await?;
+        writer.write(&self.stats.root_updates).await?;
+        writer.write(&self.stats.batches).await?;
+        writer.finish().await
+    }
+
+    /// Builds a [`Ledger`] from the snapshot read from `reader`, whose initial epoch `0` uses the
+    /// `verifying_context` and the `parameters` as in [`new`](Self::new).
+    ///
+    /// The records of the snapshot are applied to the ledger as soon as they are read, so only
+    /// the ledger itself is kept in memory. The ledger is returned once the digest at the end of
+    /// the snapshot matches, and is dropped otherwise.
+    #[inline]
+    pub async fn import_snapshot<R>(
+        utxo_accumulator_model: UtxoAccumulatorModel,
+        verifying_context: MultiVerifyingContext,
+        parameters: Parameters,
+        reader: R,
+    ) -> Result<Self, SnapshotError>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let mut reader = SnapshotReader::new(reader).await?;
+        let mut ledger = Self::new(utxo_accumulator_model, verifying_context, parameters);
+        ledger.height = reader.read().await?;
+        match reader.read_len().await? {
+            0 => return Err(SnapshotError::InconsistentState),
+            capacity => ledger.set_root_history_capacity(capacity),
+        }
+        for i in 0..MerkleTreeConfiguration::FOREST_WIDTH {
+            let index = MerkleForestIndex::from_index(i);
+            for _ in 0..reader.read_len().await? {
+                let (utxo, note) = reader.read::<(Utxo, FullIncomingNote)>().await?;
+                ledger.insert_snapshot_utxo(index, utxo, note)?;
+            }
+            let root = reader.read::<UtxoAccumulatorOutput<Config>>().await?;
+            if *ledger.utxo_forest.forest.get(index).root() != root {
+                return Err(SnapshotError::InconsistentState);
+            }
+        }
+        for _ in 0..reader.read_len().await? {
+            let nullifier = reader.read::<Nullifier>().await?;
+            if ledger.nullifiers.contains(&nullifier) {
+                return Err(SnapshotError::InconsistentState);
+            }
+            ledger.insert_nullifier(nullifier);
+        }
+        let nullifier_commitment = NullifierSetCommitment(reader.read().await?);
+        if ledger.nullifier_commitments[ledger.nullifiers.len()] != nullifier_commitment {
+            return Err(SnapshotError::InconsistentState);
+        }
+        for _ in 0..reader.read_len().await? {
+            let (account, (id, value)) =
+                reader.read::<(AccountId, (AssetId, AssetValue))>().await?;
+            if id == Default::default() {
+                return Err(SnapshotError::InconsistentState);
+            }
+            ledger
+                .accounts
+                .entry(account)
+                .or_default()
+                .insert(id, value);
+        }
+        ledger.block_time = match reader.read::<Option<(u64, u64)>>().await? {
+            Some((0, _)) => return Err(SnapshotError::InconsistentState),
+            Some((interval, confirmation_depth)) => Some(BlockTime::new(
+                Duration::from_nanos(interval),
+                confirmation_depth,
+            )),
+            _ => None,
+        };
+        let current_checkpoint = ledger.current_checkpoint();
+        let mut previous = Checkpoint::default();
+        for _ in 0..reader.read_len().await? {
+            let checkpoint = reader.read::<Checkpoint>().await?;
+            if !precedes(&previous, &checkpoint) || !precedes(&checkpoint, &current_checkpoint) {
+                return Err(SnapshotError::InconsistentState);
+            }
+            ledger.blocks.push(checkpoint);
+            previous = checkpoint;
+        }
+        if ledger.block_time.is_some() == ledger.blocks.is_empty() {
+            return Err(SnapshotError::InconsistentState);
+        }
+        ledger.stats = PoolStats {
+            total_notes: reader.read().await?,
+            total_spends: reader.read().await?,
+            notes_per_asset: reader.read().await?,
+            spend_frequency: reader.read().await?,
+            root_updates: reader.read().await?,
+            batches: reader.read().await?,
+        };
+        reader.finish().await?;
+        Ok(ledger)
+    }
+
+    /// Inserts `utxo` and its `note` read from a snapshot into the tree at `index`, failing if
+    /// `utxo` does not belong to that tree or is already stored in the ledger.
+    #[inline]
+    fn insert_snapshot_utxo(
+        &mut self,
+        index: MerkleForestIndex,
+        utxo: Utxo,
+        note: FullIncomingNote,
+    ) -> Result<(), SnapshotError> {
+        let utxo_hash = self.parameters.item_hash(&utxo, &mut ());
+        if MerkleTreeConfiguration::tree_index(&utxo_hash) != index || !self.utxos.insert(utxo) {
+            return Err(SnapshotError::InconsistentState);
+        }
+        self.shards
+            .get_mut(&index)
+            .expect("All the shards are initialized when building the ledger.")
+            .insert((utxo, note));
+        self.push_utxo_hash(&utxo_hash);
+        Ok(())
+    }
+}
+
+/// Returns `true` if the ledger can reach `next` from `checkpoint` by only adding data to it.
+#[inline]
+fn precedes(checkpoint: &Checkpoint, next: &Checkpoint) -> bool {
+    checkpoint.sender_index <= next.sender_index
+        && checkpoint
+            .receiver_index
+            .iter()
+            .zip(next.receiver_index.iter())
+            .all(|(lhs, rhs)| lhs <= rhs)
+}
@@ -0,0 +1,486 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Simulation Metrics
+//!
+//! A [`Recorder`] collects the outcome of every action of a simulation run, and the time spent by
+//! the signers proving and synchronizing, measured by wrapping them in a [`MeteredSigner`]. At the
+//! end of the run, the recorder produces the [`RunMetrics`] of the run, which are exported as CSV
+//! rows by [`write_metrics_csv`] or as JSON by serializing them, and which a [`Scoring`] reduces to
+//! a single score, so that the runs of different branches can be compared.
+//!
+//! | Field                  | Contents                                                       |
+//! |------------------------|----------------------------------------------------------------|
+//! | `label`                | Label of the run, like the branch it was run on                |
+//! | `actor_count`          | Number of simulated wallets                                    |
+//! | `posts_accepted`       | Number of transactions accepted by the ledger                  |
+//! | `posts_rejected`       | Number of transactions rejected by the ledger after retrying   |
+//! | `errors`               | Number of actions which failed with a wallet error             |
+//! | `proofs`               | Number of transfer posts signed                                |
+//! | `average_proving_time` | Average time spent signing a transfer post, in milliseconds    |
+//! | `syncs`                | Number of signer synchronizations                              |
+//! | `average_sync_lag`     | Average time spent by a signer synchronizing, in milliseconds  |
+//! | `balances_match`       | Whether the total funds before and after the run are the same  |
+
+use crate::config::Config;
+use alloc::{boxed::Box, format, string::String, sync::Arc};
+use core::{
+    fmt::{self, Write},
+    time::Duration,
+};
+use manta_accounting::{
+    transfer::{Address, Parameters},
+    wallet::{
+        signer::{
+            self, IdentityRequest, IdentityResponse, InitialSyncRequest, ReviewResult,
+            SignBatchRequest, SignBatchResult, SignRequest, SignResult,
+            SignWithTransactionDataResult, SyncRequest, SyncResult, TransactionDataRequest,
+            TransactionDataResponse,
+        },
+        test::ActionType,
+    },
+};
+use manta_util::future::LocalBoxFutureResult;
+use parking_lot::Mutex;
+use std::time::Instant;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Run Metrics
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunMetrics {
+    /// Label of the Run
+    pub label: String,
+
+    /// Actor Count
+    pub actor_count: usize,
+
+    /// Accepted Posts
+    pub posts_accepted: u64,
+
+    /// Rejected Posts
+    pub posts_rejected: u64,
+
+    /// Wallet Errors
+    pub errors: u64,
+
+    /// Signed Transfer Posts
+    pub proofs: u64,
+
+    /// Average Proving Time in Milliseconds
+    pub average_proving_time: f64,
+
+    /// Signer Synchronizations
+    pub syncs: u64,
+
+    /// Average Synchronization Lag in Milliseconds
+    pub average_sync_lag: f64,
+
+    /// Balance Correctness
+    pub balances_match: bool,
+}
+
+impl RunMetrics {
+    /// Returns the share of the transactions posted during the run which the ledger accepted, or
+    /// `1` if no transaction was posted.
+    #[inline]
+    pub fn acceptance_rate(&self) -> f64 {
+        let total = self.posts_accepted + self.posts_rejected + self.errors;
+        if total == 0 {
+            1.0
+        } else {
+            self.posts_accepted as f64 / total as f64
+        }
+    }
+
+    /// Scores `self` with the default [`Scoring`].
+    #[inline]
+    pub fn score(&self) -> f64 {
+        Scoring::default().score(self)
+    }
+}
+
+/// Scoring
+///
+/// Scores runs between `0` and `100`. Runs whose balances do not match always score `0`, and the
+/// others are scored by the weighted average of their acceptance rate and of their proving and
+/// synchronization performance. The performance is `1` for runs as fast as the reference time or
+/// faster, and the ratio of the reference time to the measured time otherwise.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scoring {
+    /// Acceptance Rate Weight
+    pub acceptance_weight: f64,
+
+    /// Proving Performance Weight
+    pub proving_weight: f64,
+
+    /// Synchronization Performance Weight
+    pub sync_weight: f64,
+
+    /// Reference Proving Time in Milliseconds
+    pub reference_proving_time: f64,
+
+    /// Reference Synchronization Lag in Milliseconds
+    pub reference_sync_lag: f64,
+}
+
+impl Scoring {
+    /// Returns the performance of a measured `time` against a `reference` time.
+    #[inline]
+    fn performance(reference: f64, time: f64) -> f64 {
+        if time <= reference {
+            1.0
+        } else {
+            reference / time
+        }
+    }
+
+    /// Scores `metrics` between `0` and `100`.
+    #[inline]
+    pub fn score(&self, metrics: &RunMetrics) -> f64 {
+        if !metrics.balances_match {
+            return 0.0;
+        }
+        let total_weight = self.acceptance_weight + self.proving_weight + self.sync_weight;
+        if total_weight <= 0.0 {
+            return 100.0;
+        }
+        let weighted = self.acceptance_weight * metrics.acceptance_rate()
+            + self.proving_weight
+                * Self::performance(self.reference_proving_time, metrics.average_proving_time)
+            + self.sync_weight
+                * Self::performance(self.reference_sync_lag, metrics.average_sync_lag);
+        100.0 * weighted / total_weight
+    }
+}
+
+impl Default for Scoring {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            acceptance_weight: 2.0,
+            proving_weight: 1.0,
+            sync_weight: 1.0,
+            reference_proving_time: 1000.0,
+            reference_sync_lag: 100.0,
+        }
+    }
+}
+
+/// Counters
+#[derive(Clone, Copy, Debug, Default)]
+struct Counters {
+    /// Accepted Posts
+    posts_accepted: u64,
+
+    /// Rejected Posts
+    posts_rejected: u64,
+
+    /// Wallet Errors
+    errors: u64,
+
+    /// Signed Transfer Posts
+    proofs: u64,
+
+    /// Total Proving Time
+    proving_time: Duration,
+
+    /// Signer Synchronizations
+    syncs: u64,
+
+    /// Total Synchronization Time
+    sync_time: Duration,
+}
+
+/// Returns the average of `total` over `count` in milliseconds.
+#[inline]
+fn average_millis(total: Duration, count: u64) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        total.as_secs_f64() * 1000.0 / count as f64
+    }
+}
+
+/// Metrics Recorder
+///
+/// Recorders are shared between the [`MeteredSigner`]s and the event subscriber of a run by
+/// cloning them.
+#[derive(Clone, Debug, Default)]
+pub struct Recorder(Arc<Mutex<Counters>>);
+
+impl Recorder {
+    /// Records the outcome of an `action`, whose `value` is whether the ledger accepted its posts.
+    #[inline]
+    pub fn record_event<E>(&self, action: ActionType, value: &Result<bool, E>) {
+        let mut counters = self.0.lock();
+        match (action, value) {
+            (_, Err(_)) => counters.errors += 1,
+            (ActionType::Skip | ActionType::Restart, _) => {}
+            (_, Ok(true)) => counters.posts_accepted += 1,
+            (_, Ok(false)) => counters.posts_rejected += 1,
+        }
+    }
+
+    /// Records `proofs` transfer posts signed in `elapsed` time.
+    #[inline]
+    pub fn record_signing(&self, proofs: usize, elapsed: Duration) {
+        let mut counters = self.0.lock();
+        counters.proofs += proofs as u64;
+        counters.proving_time += elapsed;
+    }
+
+    /// Records a signer synchronization which took `elapsed` time.
+    #[inline]
+    pub fn record_sync(&self, elapsed: Duration) {
+        let mut counters = self.0.lock();
+        counters.syncs += 1;
+        counters.sync_time += elapsed;
+    }
+
+    /// Returns the [`RunMetrics`] recorded so far for a run of `actor_count` actors, given whether
+    /// its balances match.
+    #[inline]
+    pub fn metrics(&self, actor_count: usize, balances_match: bool) -> RunMetrics {
+        let counters = *self.0.lock();
+        RunMetrics {
+            label: Default::default(),
+            actor_count,
+            posts_accepted: counters.posts_accepted,
+            posts_rejected: counters.posts_rejected,
+            errors: counters.errors,
+            proofs: counters.proofs,
+            average_proving_time: average_millis(counters.proving_time, counters.proofs),
+            syncs: counters.syncs,
+            average_sync_lag: average_millis(counters.sync_time, counters.syncs),
+            balances_match,
+        }
+    }
+}
+
+/// Metered Signer
+///
+/// Signer connection which records the time spent by the signer it wraps in a [`Recorder`].
+pub struct MeteredSigner<S> {
+    /// Signer Connection
+    signer: S,
+
+    /// Metrics Recorder
+    recorder: Recorder,
+}
+
+impl<S> MeteredSigner<S> {
+    /// Builds a new [`MeteredSigner`] which records the time spent by `signer` in `recorder`.
+    #[inline]
+    pub fn new(signer: S, recorder: Recorder) -> Self {
+        Self { signer, recorder }
+    }
+
+    /// Returns the signer connection wrapped by `self`.
+    #[inline]
+    pub fn into_inner(self) -> S {
+        self.signer
+    }
+}
+
+impl<S> signer::Connection<Config> for MeteredSigner<S>
+where
+    S: signer::Connection<Config>,
+{
+    type AssetMetadata = S::AssetMetadata;
+    type Checkpoint = S::Checkpoint;
+    type Error = S::Error;
+
+    #[inline]
+    fn sync(
+        &mut self,
+        request: SyncRequest<Config, Self::Checkpoint>,
+    ) -> LocalBoxFutureResult<'_, SyncResult<Config, Self::Checkpoint>, Self::Error> {
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = self.signer.sync(request).await;
+            self.recorder.record_sync(start.elapsed());
+            result
+        })
+    }
+
+    #[inline]
+    fn sbt_sync(
+        &mut self,
+        request: SyncRequest<Config, Self::Checkpoint>,
+    ) -> LocalBoxFutureResult<'_, SyncResult<Config, Self::Checkpoint>, Self::Error> {
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = self.signer.sbt_sync(request).await;
+            self.recorder.record_sync(start.elapsed());
+            result
+        })
+    }
+
+    #[inline]
+    fn initial_sync(
+        &mut self,
+        request: InitialSyncRequest<Config>,
+    ) -> LocalBoxFutureResult<'_, SyncResult<Config, Self::Checkpoint>, Self::Error> {
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = self.signer.initial_sync(request).await;
+            self.recorder.record_sync(start.elapsed());
+            result
+        })
+    }
+
+    #[inline]
+    fn sign(
+        &mut self,
+        request: SignRequest<Self::AssetMetadata, Config>,
+    ) -> LocalBoxFutureResult<'_, SignResult<Config>, Self::Error> {
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = self.signer.sign(request).await;
+            if let Ok(Ok(response)) = &result {
+                self.recorder
+                    .record_signing(response.posts.len(), start.elapsed());
+            }
+            result
+        })
+    }
+
+    #[inline]
+    fn sign_batch(
+        &mut self,
+        request: SignBatchRequest<Self::AssetMetadata, Config>,
+    ) -> LocalBoxFutureResult<'_, SignBatchResult<Config>, Self::Error> {
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = self.signer.sign_batch(request).await;
+            if let Ok(Ok(response)) = &result {
+                let proofs = response.0.iter().map(|response| response.posts.len()).sum();
+                self.recorder.record_signing(proofs, start.elapsed());
+            }
+            result
+        })
+    }
+
+    #[inline]
+    fn review(
+        &mut self,
+        request: SignRequest<Self::AssetMetadata, Config>,
+    ) -> LocalBoxFutureResult<'_, ReviewResult<Config>, Self::Error> {
+        self.signer.review(request)
+    }
+
+    #[inline]
+    fn address(&mut self) -> LocalBoxFutureResult<'_, Option<Address<Config>>, Self::Error> {
+        self.signer.address()
+    }
+
+    #[inline]
+    fn transaction_data(
+        &mut self,
+        request: TransactionDataRequest<Config>,
+    ) -> LocalBoxFutureResult<'_, TransactionDataResponse<Config>, Self::Error> {
+        self.signer.transaction_data(request)
+    }
+
+    #[inline]
+    fn identity_proof(
+        &mut self,
+        request: IdentityRequest<Config>,
+    ) -> LocalBoxFutureResult<'_, IdentityResponse<Config>, Self::Error> {
+        self.signer.identity_proof(request)
+    }
+
+    #[inline]
+    fn sign_with_transaction_data(
+        &mut self,
+        request: SignRequest<Self::AssetMetadata, Config>,
+    ) -> LocalBoxFutureResult<'_, SignWithTransactionDataResult<Config>, Self::Error> {
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = self.signer.sign_with_transaction_data(request).await;
+            if let Ok(Ok(response)) = &result {
+                self.recorder
+                    .record_signing(response.0.len(), start.elapsed());
+            }
+            result
+        })
+    }
+
+    #[inline]
+    fn transfer_parameters(&mut self) -> LocalBoxFutureResult<'_, Parameters<Config>, Self::Error> {
+        self.signer.transfer_parameters()
+    }
+}
+
+/// Metrics CSV Columns
+const METRICS_COLUMNS: [&str; 11] = [
+    "label",
+    "actor_count",
+    "posts_accepted",
+    "posts_rejected",
+    "errors",
+    "proofs",
+    "average_proving_time",
+    "syncs",
+    "average_sync_lag",
+    "balances_match",
+    "score",
+];
+
+/// Writes `runs` to `writer` as CSV with a header row, adding the score of every run according
+/// to `scoring`.
+#[inline]
+pub fn write_metrics_csv<W>(writer: &mut W, runs: &[RunMetrics], scoring: &Scoring) -> fmt::Result
+where
+    W: Write,
+{
+    writeln!(writer, "{}", METRICS_COLUMNS.join(","))?;
+    for run in runs {
+        let label = if run.label.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", run.label.replace('"', "\"\""))
+        } else {
+            run.label.clone()
+        };
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{:.3},{},{:.3},{},{:.2}",
+            label,
+            run.actor_count,
+            run.posts_accepted,
+            run.posts_rejected,
+            run.errors,
+            run.proofs,
+            run.average_proving_time,
+            run.syncs,
+            run.average_sync_lag,
+            run.balances_match,
+            scoring.score(run),
+        )?;
+    }
+    Ok(())
+}
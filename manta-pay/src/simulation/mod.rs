@@ -18,13 +18,17 @@
 
 use crate::{
     config::{
+        context,
         utxo::{AssetId, AssetValue},
         AccountId, Config, MultiProvingContext, MultiVerifyingContext, Parameters,
         UtxoAccumulatorModel,
     },
     key::KeySecret,
     signer::{base::Signer, functions, InitialSyncData},
-    simulation::ledger::{Ledger, LedgerConnection},
+    simulation::{
+        ledger::{Ledger, LedgerConnection},
+        metrics::{MeteredSigner, Recorder, RunMetrics},
+    },
 };
 use alloc::{format, sync::Arc};
 use core::fmt::Debug;
@@ -46,6 +50,7 @@ use tokio::{
 };
 
 pub mod ledger;
+pub mod metrics;
 
 /// Creates an [`AccountId`] from `i`.
 #[inline]
@@ -131,6 +136,34 @@ impl Simulation {
     ) where
         R: CryptoRng + RngCore + ?Sized,
     {
+        assert_balances_match(
+            self.measure(
+                parameters,
+                utxo_accumulator_model,
+                proving_context,
+                verifying_context,
+                rng,
+            )
+            .await,
+        );
+    }
+
+    /// Runs the same simulation as [`run`](Self::run), returning its [`RunMetrics`] instead of
+    /// checking that its balances match.
+    #[inline]
+    pub async fn measure<R>(
+        &self,
+        parameters: &Parameters,
+        utxo_accumulator_model: &UtxoAccumulatorModel,
+        proving_context: &MultiProvingContext,
+        verifying_context: MultiVerifyingContext,
+        rng: &mut R,
+    ) -> RunMetrics
+    where
+        R: CryptoRng + RngCore + ?Sized,
+    {
+        context::check_contexts(proving_context, &verifying_context)
+            .expect("The proving and verifying contexts are not allowed to mismatch.");
         let mut ledger = Ledger::new(
             utxo_accumulator_model.clone(),
             verifying_context,
@@ -138,7 +171,7 @@ impl Simulation {
         );
         self.setup(&mut ledger);
         let ledger = Arc::new(RwLock::new(ledger));
-        self.run_with(
+        self.run_with_metrics(
             move |i| LedgerConnection::new(account_id_from_u64(i as u64), ledger.clone()),
             move |_| sample_signer(proving_context, parameters, utxo_accumulator_model, rng),
             move |i| account_id_from_u64(i as u64),
@@ -170,23 +203,72 @@ impl Simulation {
         GL: FnMut(usize) -> L,
         GS: FnMut(usize) -> S,
         GP: FnMut(usize) -> AccountId,
-        Error<Config, L, S>: Debug,
+        Error<Config, L, MeteredSigner<S>>: Debug,
+    {
+        assert_balances_match(self.run_with_metrics(ledger, signer, public_account).await);
+    }
+
+    /// Runs the simulation with the given ledger connections and signer connections like
+    /// [`run_with`](Self::run_with), returning its [`RunMetrics`] instead of checking that its
+    /// balances match.
+    #[inline]
+    pub async fn run_with_metrics<L, S, GL, GS, GP>(
+        &self,
+        ledger: GL,
+        mut signer: GS,
+        public_account: GP,
+    ) -> RunMetrics
+    where
+        L: wallet::test::Ledger<Config>
+            + PublicBalanceOracle<Config>
+            + wallet::ledger::Read<
+                InitialSyncData,
+                Checkpoint = <L as wallet::ledger::Read<SyncData<Config>>>::Checkpoint,
+            >,
+        S: wallet::signer::Connection<
+            Config,
+            Checkpoint = <L as wallet::ledger::Read<SyncData<Config>>>::Checkpoint,
+        >,
+        S::Checkpoint: signer::Checkpoint<Config>,
+        S::Error: Debug,
+        GL: FnMut(usize) -> L,
+        GS: FnMut(usize) -> S,
+        GP: FnMut(usize) -> AccountId,
+        Error<Config, L, MeteredSigner<S>>: Debug,
     {
-        assert!(
-            self.config()
-                .run::<_, _, _, AssetList<AssetId, AssetValue>, _, _, _, _, _, _, _>(ledger, signer, public_account, |_| ChaCha20Rng::from_entropy(), |event| {
+        let recorder = Recorder::default();
+        let signer_recorder = recorder.clone();
+        let event_recorder = &recorder;
+        let balances_match = self
+            .config()
+            .run::<_, _, _, AssetList<AssetId, AssetValue>, _, _, _, _, _, _, _>(
+                ledger,
+                move |i| MeteredSigner::new(signer(i), signer_recorder.clone()),
+                public_account,
+                |_| ChaCha20Rng::from_entropy(),
+                move |event| {
+                    event_recorder.record_event(event.event.action, &event.event.value);
                     let event = format!("{event:?}\n");
                     async move {
                         let _ = write_stdout(event.as_bytes()).await;
                     }
-                })
-                .await
-                .expect("An error occured during the simulation."),
-            "ERROR: Simulation balance mismatch. Funds before and after the simulation do not match."
-        );
+                },
+            )
+            .await
+            .expect("An error occured during the simulation.");
+        recorder.metrics(self.actor_count, balances_match)
     }
 }
 
+/// Asserts that the balances of the run with the given `metrics` match.
+#[inline]
+fn assert_balances_match(metrics: RunMetrics) {
+    assert!(
+        metrics.balances_match,
+        "ERROR: Simulation balance mismatch. Funds before and after the simulation do not match."
+    );
+}
+
 /// Writes `bytes` to STDOUT using `tokio`.
 #[inline]
 async fn write_stdout(bytes: &[u8]) -> io::Result<()> {
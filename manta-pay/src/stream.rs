@@ -0,0 +1,193 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Pay Streaming
+//!
+//! Streams [`TransferPost`]s, ledger synchronization responses and parameter files over
+//! asynchronous readers and writers, like tokio sockets, using the length-prefixed frames of
+//! [`manta_util::codec::stream`]. Every post, every synchronization entry and every parameter file
+//! chunk is sent in its own frame, so neither side has to buffer the whole message.
+
+use crate::config::{Config, TransferPost};
+use manta_accounting::wallet::{
+    ledger::ReadResponse,
+    signer::{SyncData, SyncEntries, MAX_SYNC_DATA_LENGTH},
+};
+use manta_util::{codec::Decode, vec::CapacityError};
+use std::{io, path::Path};
+use tokio::{
+    fs::File,
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+};
+
+#[doc(inline)]
+pub use manta_util::codec::stream::{FrameReader, FrameWriter, StreamError};
+
+/// Ledger Synchronization Response
+pub type SyncReadResponse = ReadResponse<SyncData<Config>>;
+
+/// Parameter File Chunk Length
+///
+/// Parameter files are streamed in frames of at most this many bytes.
+pub const PARAMETER_CHUNK_LENGTH: usize = 1 << 16;
+
+/// Writes `posts` to `writer` as a sequence of frames, one for each post.
+#[inline]
+pub async fn write_posts<W>(writer: &mut FrameWriter<W>, posts: &[TransferPost]) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    writer.write_sequence(posts).await?;
+    writer.flush().await
+}
+
+/// Reads a sequence of at most `max` posts from `reader`, calling `f` on each post as soon as it
+/// is decoded.
+#[inline]
+pub async fn read_posts<R, F>(
+    reader: &mut FrameReader<R>,
+    max: usize,
+    f: F,
+) -> Result<(), StreamError<<TransferPost as Decode>::Error>>
+where
+    R: AsyncRead + Unpin,
+    F: FnMut(TransferPost),
+{
+    reader.read_sequence_with(max, f).await
+}
+
+/// Writes `response` to `writer`, sending the continuation flag in the first frame, followed by
+/// one frame for each UTXO-note pair and one frame for each nullifier.
+#[inline]
+pub async fn write_sync_response<W>(
+    writer: &mut FrameWriter<W>,
+    response: &SyncReadResponse,
+) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    writer.write(&response.should_continue).await?;
+    writer
+        .write_sequence(response.data.utxo_note_data.iter())
+        .await?;
+    writer
+        .write_sequence(response.data.nullifier_data.iter())
+        .await?;
+    writer.flush().await
+}
+
+/// Reads a synchronization response written by [`write_sync_response`] from `reader`, rejecting
+/// responses with more than [`MAX_SYNC_DATA_LENGTH`] entries in either field before any of their
+/// entries are read.
+#[inline]
+pub async fn read_sync_response<R>(
+    reader: &mut FrameReader<R>,
+) -> Result<SyncReadResponse, StreamError<()>>
+where
+    R: AsyncRead + Unpin,
+{
+    let should_continue = reader
+        .read::<bool>()
+        .await
+        .map_err(|err| err.map_decode(|_| ()))?;
+    let mut data = SyncData::<Config>::default();
+    read_sync_entries(reader, &mut data.utxo_note_data).await?;
+    read_sync_entries(reader, &mut data.nullifier_data).await?;
+    Ok(ReadResponse {
+        should_continue,
+        data,
+    })
+}
+
+/// Reads a sequence of at most [`MAX_SYNC_DATA_LENGTH`] entries from `reader` into `entries`.
+#[inline]
+async fn read_sync_entries<R, T>(
+    reader: &mut FrameReader<R>,
+    entries: &mut SyncEntries<T>,
+) -> Result<(), StreamError<()>>
+where
+    R: AsyncRead + Unpin,
+    T: Decode,
+{
+    for _ in 0..reader.read_sequence_length(MAX_SYNC_DATA_LENGTH).await? {
+        entries
+            .try_push(reader.read().await.map_err(|err| err.map_decode(|_| ()))?)
+            .map_err(CapacityError::forget)
+            .expect("The sequence length was checked against the synchronization data bound.");
+    }
+    Ok(())
+}
+
+/// Streams the parameter file at `path` to `writer` in chunks of at most
+/// [`PARAMETER_CHUNK_LENGTH`] bytes.
+#[inline]
+pub async fn write_parameter_file<W, P>(writer: &mut FrameWriter<W>, path: P) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+    P: AsRef<Path>,
+{
+    let mut file = File::open(path).await?;
+    let mut remaining = file.metadata().await?.len() as usize;
+    writer
+        .write_sequence_length(remaining.div_ceil(PARAMETER_CHUNK_LENGTH))
+        .await?;
+    let mut chunk = vec![0; PARAMETER_CHUNK_LENGTH];
+    while remaining > 0 {
+        let len = remaining.min(PARAMETER_CHUNK_LENGTH);
+        file.read_exact(&mut chunk[..len]).await?;
+        writer.write(&chunk[..len]).await?;
+        remaining -= len;
+    }
+    writer.flush().await
+}
+
+/// Reads a parameter file written by [`write_parameter_file`] from `reader` into a new file at
+/// `path`, rejecting files longer than `max_length` bytes before any of their chunks are read.
+#[inline]
+pub async fn read_parameter_file<R, P>(
+    reader: &mut FrameReader<R>,
+    path: P,
+    max_length: usize,
+) -> Result<(), StreamError<()>>
+where
+    R: AsyncRead + Unpin,
+    P: AsRef<Path>,
+{
+    let chunks = reader
+        .read_sequence_length(max_length.div_ceil(PARAMETER_CHUNK_LENGTH))
+        .await?;
+    let mut file = File::create(path).await?;
+    let mut length = 0;
+    for _ in 0..chunks {
+        let chunk = reader
+            .read::<Vec<u8>>()
+            .await
+            .map_err(|err| err.map_decode(|_| ()))?;
+        if chunk.len() > PARAMETER_CHUNK_LENGTH {
+            return Err(StreamError::FrameTooLarge(chunk.len() as u64));
+        }
+        length += chunk.len();
+        if length > max_length {
+            return Err(StreamError::Io(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Parameter file exceeds the maximum length.",
+            )));
+        }
+        file.write_all(&chunk).await?;
+    }
+    file.flush().await?;
+    Ok(())
+}
@@ -18,10 +18,13 @@
 
 use crate::config::{AssetId, AssetValue};
 use manta_accounting::{
-    asset,
+    asset::{self, BalanceError, CheckedBalance},
     wallet::balance::{
         self,
-        test::{assert_full_withdraw_should_remove_entry, assert_valid_withdraw},
+        test::{
+            assert_full_withdraw_should_remove_entry, assert_overflowing_deposit_should_fail,
+            assert_valid_withdraw,
+        },
     },
 };
 use manta_crypto::rand::OsRng;
@@ -42,7 +45,8 @@ macro_rules! define_tests {
         $type:ty,
         $doc:expr,
         $valid_withdraw:ident,
-        $full_withdraw:ident
+        $full_withdraw:ident,
+        $overflowing_deposit:ident
         $(,)?)),*
      $(,)?) => {
         $(
@@ -65,6 +69,14 @@ macro_rules! define_tests {
             fn $full_withdraw() {
                 assert_full_withdraw_should_remove_entry::<_, _, $type, _>(&mut OsRng);
             }
+
+            #[doc = "Tests that overflowing deposits into an"]
+            #[doc = $doc]
+            #[doc = "balance state fail."]
+            #[test]
+            fn $overflowing_deposit() {
+                assert_overflowing_deposit_should_fail::<_, _, $type, _>(AssetValue::MAX, &mut OsRng);
+            }
         )*
     }
 }
@@ -75,12 +87,14 @@ define_tests!(
         "[`AssetList`]",
         asset_list_valid_withdraw,
         asset_list_full_withdraw,
+        asset_list_overflowing_deposit,
     ),
     (
         BTreeMapBalanceState,
         "[`BTreeMapBalanceState`]",
         btree_map_valid_withdraw,
         btree_map_full_withdraw,
+        btree_map_overflowing_deposit,
     ),
 );
 
@@ -97,3 +111,37 @@ fn hash_map_valid_withdraw() {
 fn hash_map_full_withdraw() {
     assert_full_withdraw_should_remove_entry::<_, _, HashMapBalanceState, _>(&mut OsRng);
 }
+
+/// Tests that overflowing deposits into a [`HashMapBalanceState`] balance state fail.
+#[cfg(feature = "std")]
+#[test]
+fn hash_map_overflowing_deposit() {
+    assert_overflowing_deposit_should_fail::<_, _, HashMapBalanceState, _>(
+        AssetValue::MAX,
+        &mut OsRng,
+    );
+}
+
+/// Tests that [`CheckedBalance`] reports overflows and underflows and saturates its display.
+#[test]
+fn checked_balance_arithmetic() {
+    let mut balance = CheckedBalance::new(AssetValue::MAX - 1);
+    assert_eq!(
+        balance.checked_add(1).map(|b| *b.value()),
+        Ok(AssetValue::MAX)
+    );
+    assert_eq!(balance.checked_add(1).err(), Some(BalanceError::Overflow));
+    assert_eq!(*balance.value(), AssetValue::MAX);
+    assert_eq!(
+        CheckedBalance::<AssetValue>::new(0).checked_sub(1).err(),
+        Some(BalanceError::Underflow)
+    );
+    balance.saturating_add(1);
+    assert!(balance.is_saturated());
+    assert_eq!(balance.to_string(), format!(">= {}", AssetValue::MAX));
+    assert_eq!(
+        CheckedBalance::<AssetValue>::try_sum([AssetValue::MAX, 1]),
+        Err(BalanceError::Overflow)
+    );
+    assert_eq!(CheckedBalance::<AssetValue>::try_sum([1, 2, 3]), Ok(6));
+}
@@ -0,0 +1,179 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Batch Planner Testing Suite
+
+use core::time::Duration;
+use manta_accounting::transfer::batch::{BatchPlan, BatchPlanner, BatchRound, JoinProfile};
+
+/// Two-Sender Join Profile taking one second
+const PAIR: JoinProfile = JoinProfile {
+    senders: 2,
+    proving_time: Duration::from_secs(1),
+};
+
+/// Four-Sender Join Profile taking two and a half seconds
+const QUAD: JoinProfile = JoinProfile {
+    senders: 4,
+    proving_time: Duration::from_millis(2500),
+};
+
+/// Asserts that `plan` merges `notes` notes into at most `target` notes and that its estimated
+/// time matches its rounds.
+#[inline]
+fn assert_consistent(plan: &BatchPlan, mut notes: usize, target: usize) {
+    for round in &plan.rounds {
+        assert!(round.joins > 0, "Rounds should not be empty.");
+        assert!(round.inputs <= notes, "Rounds cannot spend missing notes.");
+        assert!(round.inputs <= round.joins * round.profile.senders);
+        notes = round.remaining(notes);
+    }
+    assert!(notes <= target.max(1), "The plan should reach the target.");
+    assert_eq!(
+        plan.estimated_time,
+        plan.rounds
+            .iter()
+            .map(|round| round.estimated_time(plan.parallelism))
+            .sum::<Duration>()
+    );
+}
+
+/// Checks the rounds and the parallelism chosen for a single join circuit.
+#[test]
+fn single_profile_plan() {
+    let planner = BatchPlanner::new(vec![PAIR], 4);
+    let plan = planner
+        .fastest(8, 1)
+        .expect("The planner has a join profile.");
+    assert_eq!(
+        plan.rounds
+            .iter()
+            .map(|round| round.joins)
+            .collect::<Vec<_>>(),
+        [4, 2, 1],
+        "Pairs of notes should be merged round by round."
+    );
+    assert_eq!(plan.parallelism, 4);
+    assert_eq!(plan.estimated_time, Duration::from_secs(3));
+    assert_eq!(plan.joins(), 7);
+    assert_eq!(plan.proving_time(), Duration::from_secs(7));
+    assert_consistent(&plan, 8, 1);
+    let plan = BatchPlanner::new(vec![PAIR], 1)
+        .fastest(8, 1)
+        .expect("The planner has a join profile.");
+    assert_eq!(plan.parallelism, 1);
+    assert_eq!(plan.estimated_time, Duration::from_secs(7));
+}
+
+/// Checks that the planner picks the join circuit which is fastest for the available provers.
+#[test]
+fn fastest_plan_depends_on_parallelism() {
+    let plan = BatchPlanner::new(vec![PAIR, QUAD], 8)
+        .fastest(16, 1)
+        .expect("The planner has join profiles.");
+    assert_eq!(plan.rounds[0].profile, PAIR);
+    assert_eq!(plan.parallelism, 8);
+    assert_eq!(plan.estimated_time, Duration::from_secs(4));
+    assert_consistent(&plan, 16, 1);
+    let plan = BatchPlanner::new(vec![PAIR, QUAD], 1)
+        .fastest(16, 1)
+        .expect("The planner has join profiles.");
+    assert_eq!(plan.rounds[0].profile, QUAD);
+    assert_eq!(plan.joins(), 5);
+    assert_eq!(plan.estimated_time, Duration::from_millis(12500));
+    assert_consistent(&plan, 16, 1);
+}
+
+/// Checks that the planner uses the fewest provers which meet the budget.
+#[test]
+fn budget_plan_uses_fewest_provers() {
+    let planner = BatchPlanner::new(vec![PAIR, QUAD], 8);
+    let plan = planner
+        .plan(16, 1, Duration::from_secs(8))
+        .expect("Two provers are enough to meet the budget.");
+    assert_eq!(plan.parallelism, 2);
+    assert_eq!(plan.rounds[0].profile, QUAD);
+    assert_eq!(plan.estimated_time, Duration::from_millis(7500));
+    assert_consistent(&plan, 16, 1);
+    let plan = planner
+        .plan(16, 1, Duration::from_secs(60))
+        .expect("A single prover is enough to meet the budget.");
+    assert_eq!(plan.parallelism, 1);
+    assert_eq!(
+        planner.plan(16, 1, Duration::from_secs(1)),
+        None,
+        "No plan can meet the budget."
+    );
+}
+
+/// Checks the plans which do not need any join or need a single partial join.
+#[test]
+fn edge_case_plans() {
+    let planner = BatchPlanner::new(vec![QUAD], 4);
+    let plan = planner
+        .fastest(3, 4)
+        .expect("The planner has a join profile.");
+    assert!(
+        plan.rounds.is_empty(),
+        "No join is needed below the target."
+    );
+    assert_eq!(plan.estimated_time, Duration::ZERO);
+    assert_eq!(plan.parallelism, 1);
+    let plan = planner
+        .fastest(3, 1)
+        .expect("The planner has a join profile.");
+    assert_eq!(
+        plan.rounds,
+        [BatchRound {
+            profile: QUAD,
+            joins: 1,
+            inputs: 3,
+        }],
+        "Fewer notes than senders should be merged by a single padded join."
+    );
+    let plan = planner
+        .fastest(5, 0)
+        .expect("The planner has a join profile.");
+    assert_consistent(&plan, 5, 0);
+}
+
+/// Checks that profiles which cannot merge notes are dropped and that the parallelism is at
+/// least one.
+#[test]
+fn planner_construction() {
+    let planner = BatchPlanner::new(vec![JoinProfile::new(1, Duration::from_secs(1))], 0);
+    assert!(planner.profiles().is_empty());
+    assert_eq!(planner.maximum_parallelism(), 1);
+    assert_eq!(planner.fastest(8, 1), None);
+    assert_eq!(planner.plan(8, 1, Duration::MAX), None);
+}
+
+/// Checks that every plan reaches its target for a range of note counts, targets and provers.
+#[test]
+fn plans_reach_target() {
+    for parallelism in 1..=4 {
+        let planner = BatchPlanner::new(vec![PAIR, QUAD], parallelism);
+        for notes in 0..40 {
+            for target in 0..6 {
+                let plan = planner
+                    .fastest(notes, target)
+                    .expect("The planner has join profiles.");
+                assert!(plan.parallelism <= parallelism);
+                assert_consistent(&plan, notes, target);
+            }
+        }
+    }
+}
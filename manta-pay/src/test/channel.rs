@@ -0,0 +1,209 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Pay Payment Channel Testing
+
+use crate::{
+    config::{
+        utxo::SignatureScheme, Channel, ChannelState, Config, EmbeddedScalar, FullParametersRef,
+        Parameters, PrivateTransfer, SpendingKey, ToPrivate,
+    },
+    test::payment::UtxoAccumulator,
+};
+use manta_accounting::transfer::{
+    channel::{ChannelError, Direction, Party, StateSignature},
+    utxo::Spend,
+};
+use manta_crypto::{
+    accumulator::{Accumulator, ItemHashFunction},
+    rand::{OsRng, Rand},
+    signature::Derive,
+};
+
+/// Signs `state` with both `signing_keys`.
+#[inline]
+fn sign_state(
+    scheme: &SignatureScheme,
+    signing_keys: &[EmbeddedScalar; 2],
+    state: &ChannelState,
+) -> [StateSignature<Config>; 2] {
+    let mut rng = OsRng;
+    signing_keys.map(|signing_key| state.sign(scheme, &signing_key, &rng.gen()))
+}
+
+/// Tests that a bidirectional channel is updated off-chain, funded and closed cooperatively with
+/// valid transfers.
+#[test]
+fn bidirectional_channel_lifecycle() {
+    let mut rng = OsRng;
+    let parameters = rng.gen::<_, Parameters>();
+    let mut utxo_accumulator = UtxoAccumulator::new(rng.gen());
+    let utxo_accumulator_model = utxo_accumulator.model().clone();
+    let scheme = parameters.signature_scheme();
+    let channel_key = rng.gen::<_, SpendingKey>();
+    let signing_keys = [rng.gen(), rng.gen()];
+    let mut channel = Channel::new(
+        rng.gen(),
+        Direction::Bidirectional,
+        rng.gen(),
+        100,
+        parameters.address_from_spending_key(&channel_key),
+        [
+            parameters.address_from_spending_key(&rng.gen()),
+            parameters.address_from_spending_key(&rng.gen()),
+        ],
+        signing_keys.map(|signing_key| scheme.derive(&signing_key, &mut ())),
+    );
+    let first = channel
+        .pay(Party::Left, 60)
+        .expect("Left party owns the capacity.");
+    let signatures = sign_state(&scheme, &signing_keys, &first);
+    channel
+        .update(&scheme, first.clone(), signatures)
+        .expect("State is signed by both parties.");
+    assert_eq!(
+        channel.update(&scheme, first, signatures),
+        Err(ChannelError::StaleState),
+        "Replayed states should be rejected."
+    );
+    let second = channel.pay(Party::Right, 15).expect("Right party owns 60.");
+    let mut signatures = sign_state(&scheme, &signing_keys, &second);
+    signatures[1] = second.sign(&scheme, &rng.gen(), &rng.gen());
+    assert_eq!(
+        channel.check(&scheme, &second, &signatures),
+        Err(ChannelError::InvalidSignature(Party::Right)),
+        "States signed by another key should be rejected."
+    );
+    let signatures = sign_state(&scheme, &signing_keys, &second);
+    channel
+        .update(&scheme, second, signatures)
+        .expect("State is signed by both parties.");
+    assert_eq!(channel.state().balances, [55, 45]);
+    assert_eq!(
+        channel.pay(Party::Left, 56),
+        Err(ChannelError::InsufficientBalance)
+    );
+    let full_parameters = FullParametersRef::new(&parameters, &utxo_accumulator_model);
+    assert_eq!(
+        channel
+            .cooperative_close(&parameters, &channel_key, &utxo_accumulator, &mut rng)
+            .err(),
+        Some(ChannelError::NonFinalState),
+        "Channels can only be closed cooperatively from a final state."
+    );
+    let last = channel.propose_close().expect("Channel is open.");
+    let signatures = sign_state(&scheme, &signing_keys, &last);
+    channel
+        .update(&scheme, last, signatures)
+        .expect("State is signed by both parties.");
+    assert_eq!(channel.pay(Party::Left, 1), Err(ChannelError::Closed));
+    let (proving_context, verifying_context) =
+        ToPrivate::generate_context(&(), full_parameters, &mut rng)
+            .expect("Unable to create proving and verifying contexts.");
+    let funding = channel
+        .fund(&parameters, &mut rng)
+        .expect("Channel is not funded yet.")
+        .into_post(
+            full_parameters,
+            &proving_context,
+            None,
+            Vec::new(),
+            &mut rng,
+        )
+        .expect("Unable to build funding post.")
+        .expect("ToPrivate does not require authorization.");
+    assert!(
+        funding
+            .has_valid_proof(&verifying_context)
+            .expect("Unable to verify proof."),
+        "Funding proof should be valid."
+    );
+    assert_eq!(
+        channel
+            .cooperative_close(&parameters, &channel_key, &utxo_accumulator, &mut rng)
+            .err(),
+        Some(ChannelError::MissingFundingNote),
+        "Channels can only be closed after the funding note was posted."
+    );
+    utxo_accumulator.insert(
+        &parameters
+            .utxo_accumulator_item_hash()
+            .item_hash(&funding.body.receiver_posts[0].utxo, &mut ()),
+    );
+    let (proving_context, verifying_context) =
+        PrivateTransfer::generate_context(&(), full_parameters, &mut rng)
+            .expect("Unable to create proving and verifying contexts.");
+    let close = channel
+        .cooperative_close(&parameters, &channel_key, &utxo_accumulator, &mut rng)
+        .expect("Channel is funded and final.")
+        .into_post(
+            full_parameters,
+            &proving_context,
+            Some(&channel_key),
+            Vec::new(),
+            &mut rng,
+        )
+        .expect("Unable to build closing post.")
+        .expect("Closing transfer has an authorization.");
+    assert!(
+        close
+            .has_valid_proof(&verifying_context)
+            .expect("Unable to verify proof."),
+        "Closing proof should be valid."
+    );
+    assert_eq!(close.body.receiver_posts.len(), 2);
+}
+
+/// Tests that the right party cannot pay on a unidirectional channel.
+#[test]
+fn unidirectional_channel_direction() {
+    let mut rng = OsRng;
+    let parameters = rng.gen::<_, Parameters>();
+    let scheme = parameters.signature_scheme();
+    let signing_keys = [rng.gen(), rng.gen()];
+    let mut channel = Channel::new(
+        rng.gen(),
+        Direction::Unidirectional,
+        rng.gen(),
+        10,
+        parameters.address_from_spending_key(&rng.gen()),
+        [
+            parameters.address_from_spending_key(&rng.gen()),
+            parameters.address_from_spending_key(&rng.gen()),
+        ],
+        signing_keys.map(|signing_key| scheme.derive(&signing_key, &mut ())),
+    );
+    let state = channel
+        .pay(Party::Left, 4)
+        .expect("Left party owns the capacity.");
+    let signatures = sign_state(&scheme, &signing_keys, &state);
+    channel
+        .update(&scheme, state, signatures)
+        .expect("State is signed by both parties.");
+    assert_eq!(
+        channel.pay(Party::Right, 1),
+        Err(ChannelError::WrongDirection)
+    );
+    let mut refund = channel.state().clone();
+    refund.sequence += 1;
+    refund.balances = [10, 0];
+    let signatures = sign_state(&scheme, &signing_keys, &refund);
+    assert_eq!(
+        channel.update(&scheme, refund, signatures),
+        Err(ChannelError::WrongDirection),
+        "States paying the left party back should be rejected."
+    );
+}
@@ -0,0 +1,523 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Pay Codec Testing
+//!
+//! Round-trip tests for the wire format of the ledger types. The encodings of some sampled values
+//! are checked into `src/test/corpus`, one directory for each type, and serve as the seed corpus of
+//! the fuzzers. Every file in the corpus must decode and encode back to the same bytes, so a change
+//! of the wire format fails [`corpus_round_trip`] until the corpus is regenerated with
+//!
+//! ```sh
+//! cargo test --release --all-features -p manta-pay test::codec::generate_corpus -- --ignored
+//! ```
+
+use crate::{
+    config::{
+        utxo::{self, Checkpoint, MerkleTreeConfiguration},
+        Asset, AssetId, FullParametersRef, PrivateTransfer, ReceiverPost, SenderPost, ToPrivate,
+        ToPublic, TransferPost,
+    },
+    signer::{SyncRequest, SyncResponse},
+    test::payment::UtxoAccumulator,
+};
+use core::fmt::Debug;
+use manta_accounting::{
+    transfer::{utxo::protocol, PostParticipants},
+    wallet::{
+        ledger::{LeafIndex, NoteIndex},
+        signer::{BalanceUpdate, SyncData, SyncEntries, MAX_SYNC_DATA_LENGTH},
+    },
+};
+use manta_crypto::{
+    accumulator::Accumulator,
+    arkworks::constraint::fp::Fp,
+    rand::{CryptoRng, OsRng, Rand, RngCore},
+};
+use manta_util::codec::{BoundedVecDecodeError, Decode, DecodeError, Encode};
+use std::{fs, path::PathBuf};
+
+/// Returns the corpus directory for the type named `name`.
+#[inline]
+fn corpus_directory(name: &str) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "src", "test", "corpus", name]
+        .iter()
+        .collect()
+}
+
+/// Decodes the [`TransferPost`]s of the corpus, sorted by file name.
+#[inline]
+pub fn corpus_posts() -> Vec<TransferPost> {
+    let mut paths = fs::read_dir(corpus_directory("transfer_post"))
+        .expect("Unable to read the corpus.")
+        .map(|entry| entry.expect("Unable to read the corpus.").path())
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            TransferPost::from_vec(fs::read(path).expect("Unable to read the corpus."))
+                .expect("The corpus posts are not allowed to fail to decode.")
+        })
+        .collect()
+}
+
+/// Asserts that `value` decodes back from its encoding, and that the decoded value encodes to the
+/// same bytes, returning the encoding.
+#[inline]
+fn assert_round_trip<T>(value: &T) -> Vec<u8>
+where
+    T: Debug + Decode + Encode + PartialEq,
+    T::Error: Debug,
+{
+    let bytes = value.to_vec();
+    let decoded =
+        T::from_vec(bytes.clone()).expect("Decoding an encoded value is not allowed to fail.");
+    assert_eq!(
+        &decoded, value,
+        "The decoded value should be equal to the original one."
+    );
+    assert_eq!(
+        decoded.to_vec(),
+        bytes,
+        "The decoded value should encode to the original bytes."
+    );
+    bytes
+}
+
+/// Asserts that `bytes` is the canonical encoding of a value of type `T`, and that none of its
+/// truncations decode.
+#[inline]
+fn assert_canonical<T>(bytes: Vec<u8>)
+where
+    T: Decode + Encode,
+    T::Error: Debug,
+{
+    for len in [0, bytes.len() / 2, bytes.len().saturating_sub(1)] {
+        if len < bytes.len() {
+            assert!(
+                T::from_vec(bytes[..len].to_vec()).is_err(),
+                "Truncated encodings should not decode."
+            );
+        }
+    }
+    let value = T::from_vec(bytes.clone()).expect("Decoding the corpus is not allowed to fail.");
+    assert_eq!(
+        value.to_vec(),
+        bytes,
+        "The corpus should only contain canonical encodings."
+    );
+}
+
+/// Samples one [`TransferPost`] of each canonical transfer shape, in the order [`ToPrivate`],
+/// [`PrivateTransfer`] and [`ToPublic`].
+#[inline]
+fn sample_posts<R>(rng: &mut R) -> [TransferPost; 3]
+where
+    R: CryptoRng + RngCore + ?Sized,
+{
+    let parameters = rng.gen();
+    let mut utxo_accumulator = UtxoAccumulator::new(rng.gen());
+    let spending_key = rng.gen();
+    let (proving_context, _) = ToPrivate::generate_context(
+        &(),
+        FullParametersRef::new(&parameters, utxo_accumulator.model()),
+        rng,
+    )
+    .expect("Unable to create proving and verifying contexts.");
+    let to_private = ToPrivate::sample_post(
+        &proving_context,
+        &parameters,
+        &mut utxo_accumulator,
+        None,
+        rng,
+    )
+    .expect("Random ToPrivate should have produced a proof.")
+    .expect("Random ToPrivate should have generated a TransferPost.");
+    let (proving_context, _) = PrivateTransfer::generate_context(
+        &(),
+        FullParametersRef::new(&parameters, utxo_accumulator.model()),
+        rng,
+    )
+    .expect("Unable to create proving and verifying contexts.");
+    let private_transfer = PrivateTransfer::sample_post(
+        &proving_context,
+        &parameters,
+        &mut utxo_accumulator,
+        Some(&spending_key),
+        rng,
+    )
+    .expect("Random PrivateTransfer should have produced a proof.")
+    .expect("Random PrivateTransfer should have generated a TransferPost.");
+    let (proving_context, _) = ToPublic::generate_context(
+        &(),
+        FullParametersRef::new(&parameters, utxo_accumulator.model()),
+        rng,
+    )
+    .expect("Unable to create proving and verifying contexts.");
+    let to_public = ToPublic::sample_post(
+        &proving_context,
+        &parameters,
+        &mut utxo_accumulator,
+        Some(&spending_key),
+        rng,
+    )
+    .expect("Random ToPublic should have produced a proof.")
+    .expect("Random ToPublic should have generated a TransferPost.");
+    [to_private, private_transfer, to_public]
+}
+
+/// Samples a [`Checkpoint`].
+#[inline]
+fn sample_checkpoint<R>(rng: &mut R) -> Checkpoint
+where
+    R: RngCore + ?Sized,
+{
+    Checkpoint::new(
+        (0..MerkleTreeConfiguration::FOREST_WIDTH)
+            .map(|_| LeafIndex::new(rng.gen::<_, u32>() as usize))
+            .collect(),
+        NoteIndex::new(rng.gen::<_, u32>() as usize),
+    )
+}
+
+/// Samples a vector of `len`-many assets.
+#[inline]
+fn sample_assets<R>(len: usize, rng: &mut R) -> Vec<Asset>
+where
+    R: RngCore + ?Sized,
+{
+    (0..len)
+        .map(|_| Asset::new(AssetId::from(rng.gen::<_, u128>()), rng.gen()))
+        .collect()
+}
+
+/// Builds a [`SyncRequest`] with the notes and nullifiers posted by `posts`.
+#[inline]
+fn sync_request(origin_checkpoint: Checkpoint, posts: &[TransferPost]) -> SyncRequest {
+    SyncRequest {
+        origin_checkpoint,
+        data: SyncData {
+            utxo_note_data: SyncEntries::try_from_iter(
+                posts
+                    .iter()
+                    .flat_map(|post| &post.body.receiver_posts)
+                    .map(|post| (post.utxo, post.note.clone())),
+            )
+            .expect("The test posts are within the synchronization data bound."),
+            nullifier_data: SyncEntries::try_from_iter(
+                posts
+                    .iter()
+                    .flat_map(|post| &post.body.sender_posts)
+                    .map(|post| post.nullifier),
+            )
+            .expect("The test posts are within the synchronization data bound."),
+        },
+    }
+}
+
+/// Samples the [`SyncResponse`]s of the corpus, one for each kind of [`BalanceUpdate`].
+#[inline]
+fn sample_sync_responses<R>(rng: &mut R) -> [SyncResponse; 2]
+where
+    R: RngCore + ?Sized,
+{
+    [
+        SyncResponse {
+            checkpoint: sample_checkpoint(rng),
+            balance_update: BalanceUpdate::Partial {
+                deposit: sample_assets(2, rng),
+                withdraw: sample_assets(1, rng),
+            },
+            quarantined: rng.gen::<_, u16>() as usize,
+            duplicates: rng.gen::<_, u16>() as usize,
+        },
+        SyncResponse {
+            checkpoint: sample_checkpoint(rng),
+            balance_update: BalanceUpdate::Full {
+                assets: sample_assets(3, rng),
+            },
+            quarantined: 0,
+            duplicates: 0,
+        },
+    ]
+}
+
+/// Tests that the transfer posts and their sender and receiver posts survive an encoding round
+/// trip.
+#[test]
+fn transfer_post_round_trip() {
+    let mut rng = OsRng;
+    for post in sample_posts(&mut rng) {
+        assert_round_trip(&post);
+        for sender_post in &post.body.sender_posts {
+            assert_round_trip(sender_post);
+        }
+        for receiver_post in &post.body.receiver_posts {
+            assert_round_trip(receiver_post);
+        }
+    }
+}
+
+/// Tests that checkpoints and synchronization messages survive an encoding round trip.
+#[test]
+fn sync_message_round_trip() {
+    let mut rng = OsRng;
+    assert_round_trip(&Checkpoint::default());
+    assert_round_trip(&sample_checkpoint(&mut rng));
+    assert_round_trip(&sync_request(Default::default(), &[]));
+    assert_round_trip(&sync_request(
+        sample_checkpoint(&mut rng),
+        &sample_posts(&mut rng),
+    ));
+    for response in sample_sync_responses(&mut rng) {
+        assert_round_trip(&response);
+    }
+}
+
+/// Identifier of a transparent note with UTXO commitment randomness `42`, as serialized before
+/// the nullifier migration.
+#[cfg(feature = "serde")]
+const LEGACY_IDENTIFIER_JSON: &str = concat!(
+    r#"{"is_transparent":true,"utxo_commitment_randomness":"#,
+    "[42,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0]}",
+);
+
+/// Nullifier with commitment `7` and an empty outgoing note, as serialized before the nullifier
+/// migration.
+#[cfg(feature = "serde")]
+const LEGACY_NULLIFIER_JSON: &str = concat!(
+    r#"{"nullifier":{"commitment":[7,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0]},"#,
+    r#""outgoing_note":{"header":null,"ciphertext":{"ephemeral_public_key":"#,
+    "[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0],",
+    r#""ciphertext":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,"#,
+    "0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0]}}}",
+);
+
+/// Returns the nullifier with commitment `7` and an empty outgoing note.
+#[inline]
+fn legacy_nullifier() -> utxo::Nullifier {
+    utxo::Nullifier::new(
+        protocol::Nullifier::new(Fp(7u64.into()), false),
+        Default::default(),
+    )
+}
+
+/// Tests that nullifiers encoded before the nullifier migration decode with the legacy flag unset
+/// and encode back to the same bytes.
+#[test]
+fn legacy_nullifier_encoding_round_trip() {
+    let mut bytes = vec![0; 128];
+    bytes[0] = 7;
+    let nullifier = legacy_nullifier();
+    assert_eq!(nullifier.to_vec(), bytes, "The encoding should not change.");
+    assert_eq!(
+        utxo::Nullifier::from_vec(bytes).expect("Decoding is not allowed to fail."),
+        nullifier
+    );
+    let identifier = utxo::Identifier::new(true, false, Fp(42u64.into()));
+    let bytes = identifier.to_vec();
+    assert_eq!(bytes.len(), 33, "The legacy flag should not be encoded.");
+    assert_eq!(
+        utxo::Identifier::from_vec(bytes).expect("Decoding is not allowed to fail."),
+        identifier
+    );
+}
+
+/// Tests that identifiers and nullifiers serialized before the nullifier migration deserialize
+/// with the legacy flag unset, and that identifiers serialize back to the same JSON.
+#[cfg(feature = "serde")]
+#[test]
+fn legacy_serialization_round_trip() {
+    let identifier = serde_json::from_str::<utxo::Identifier>(LEGACY_IDENTIFIER_JSON)
+        .expect("Deserializing is not allowed to fail.");
+    assert_eq!(
+        identifier,
+        utxo::Identifier::new(true, false, Fp(42u64.into()))
+    );
+    assert_eq!(
+        serde_json::to_string(&identifier).expect("Serializing is not allowed to fail."),
+        LEGACY_IDENTIFIER_JSON
+    );
+    let nullifier = serde_json::from_str::<utxo::Nullifier>(LEGACY_NULLIFIER_JSON)
+        .expect("Deserializing is not allowed to fail.");
+    assert_eq!(nullifier, legacy_nullifier());
+    assert_eq!(
+        serde_json::from_str::<utxo::Nullifier>(
+            &serde_json::to_string(&nullifier).expect("Serializing is not allowed to fail.")
+        )
+        .expect("Deserializing is not allowed to fail."),
+        nullifier
+    );
+}
+
+/// Tests that the encodings of the transfer posts do not decode with an out-of-range [`Option`]
+/// tag or more participants than allowed.
+#[test]
+fn transfer_post_rejects_invalid_encodings() {
+    let [to_private, ..] = sample_posts(&mut OsRng);
+    let mut bytes = assert_round_trip(&to_private);
+    assert_eq!(bytes[0], 0, "ToPrivate posts should not be authorized.");
+    bytes[0] = 2;
+    assert!(
+        TransferPost::from_vec(bytes.clone()).is_err(),
+        "Out-of-range tags should not decode."
+    );
+    bytes[0] = 0;
+    let sources = 1 + to_private.body.asset_id.to_vec().len();
+    bytes[sources..sources + 8].copy_from_slice(&17u64.to_le_bytes());
+    assert!(
+        TransferPost::from_vec(bytes).is_err(),
+        "Posts with too many sources should not decode."
+    );
+}
+
+/// Tests that bounded vectors reject a length prefix above their bound before reading or
+/// allocating any of the elements.
+#[test]
+fn bounded_vec_rejects_oversized_lengths() {
+    for len in [17, u64::MAX] {
+        assert!(
+            matches!(
+                PostParticipants::<u8>::decode(len.to_vec().as_slice()),
+                Err(DecodeError::Decode(BoundedVecDecodeError::ExceededCapacity(l))) if l == len
+            ),
+            "A length prefix of {len} should be rejected before decoding any elements."
+        );
+    }
+}
+
+/// Tests that bounded vectors reject a serialized length above their bound before visiting any
+/// of the elements.
+#[cfg(feature = "serde")]
+#[test]
+fn bounded_vec_rejects_oversized_serde_lengths() {
+    let error = bincode::deserialize::<PostParticipants<u8>>(&u64::MAX.to_le_bytes())
+        .expect_err("Lengths above the bound should not deserialize.");
+    assert!(
+        error.to_string().contains("at most 16 elements"),
+        "The length should be rejected by the bounded visitor, not by reading elements: {error}"
+    );
+    let participants = PostParticipants::try_from_vec((0..16).collect())
+        .expect("Sixteen participants are within the bound.");
+    let bytes = bincode::serialize(&participants).expect("Serializing is not allowed to fail.");
+    assert_eq!(
+        bincode::deserialize::<PostParticipants<u8>>(&bytes)
+            .expect("Deserializing at the bound is not allowed to fail."),
+        participants
+    );
+}
+
+/// Tests that bounded vectors only preallocate a bounded number of bytes for untrusted lengths,
+/// even when the bound itself is much larger.
+#[test]
+fn bounded_vec_preallocates_cautiously() {
+    let entries = SyncEntries::<[u8; 64]>::with_cautious_capacity(MAX_SYNC_DATA_LENGTH);
+    assert!(
+        entries.capacity() * 64 <= SyncEntries::<[u8; 64]>::MAX_PREALLOCATION,
+        "A length hint at the bound should not preallocate more than the cautious limit."
+    );
+    assert!(
+        SyncEntries::<u8>::with_cautious_capacity(usize::MAX).capacity() <= MAX_SYNC_DATA_LENGTH,
+        "A length hint above the bound should be clamped to the bound."
+    );
+    assert!(
+        PostParticipants::<u8>::with_cautious_capacity(4).capacity() >= 4,
+        "Small length hints should still be preallocated."
+    );
+}
+
+/// Corpus Entry
+///
+/// The name of a corpus directory, and the function which checks its files.
+type CorpusEntry = (&'static str, fn(Vec<u8>));
+
+/// Corpus Directories
+const CORPUS: [CorpusEntry; 6] = [
+    ("checkpoint", assert_canonical::<Checkpoint>),
+    ("receiver_post", assert_canonical::<ReceiverPost>),
+    ("sender_post", assert_canonical::<SenderPost>),
+    ("sync_request", assert_canonical::<SyncRequest>),
+    ("sync_response", assert_canonical::<SyncResponse>),
+    ("transfer_post", assert_canonical::<TransferPost>),
+];
+
+/// Tests that every file in the corpus is the canonical encoding of a value of its type.
+#[test]
+fn corpus_round_trip() {
+    for (name, check) in CORPUS {
+        let mut count = 0;
+        for entry in fs::read_dir(corpus_directory(name)).expect("Unable to read the corpus.") {
+            check(
+                fs::read(entry.expect("Unable to read the corpus.").path())
+                    .expect("Unable to read the corpus."),
+            );
+            count += 1;
+        }
+        assert!(count > 0, "The {name} corpus should not be empty.");
+    }
+}
+
+/// Writes `value` into the corpus of the type named `name`, as the file `file`.
+#[inline]
+fn write_corpus<T>(name: &str, file: &str, value: &T)
+where
+    T: Debug + Decode + Encode + PartialEq,
+    T::Error: Debug,
+{
+    let directory = corpus_directory(name);
+    fs::create_dir_all(&directory).expect("Unable to create the corpus directory.");
+    fs::write(
+        directory.join(file).with_extension("bin"),
+        assert_round_trip(value),
+    )
+    .expect("Unable to write the corpus.");
+}
+
+/// Regenerates the corpus from freshly sampled values.
+#[ignore]
+#[test]
+fn generate_corpus() {
+    let mut rng = OsRng;
+    let posts = sample_posts(&mut rng);
+    for (shape, post) in ["to_private", "private_transfer", "to_public"]
+        .into_iter()
+        .zip(&posts)
+    {
+        write_corpus("transfer_post", shape, post);
+        for (i, sender_post) in post.body.sender_posts.iter().enumerate() {
+            write_corpus("sender_post", &format!("{shape}_{i}"), sender_post);
+        }
+        for (i, receiver_post) in post.body.receiver_posts.iter().enumerate() {
+            write_corpus("receiver_post", &format!("{shape}_{i}"), receiver_post);
+        }
+    }
+    write_corpus("checkpoint", "default", &Checkpoint::default());
+    write_corpus("checkpoint", "sample", &sample_checkpoint(&mut rng));
+    write_corpus(
+        "sync_request",
+        "empty",
+        &sync_request(Default::default(), &[]),
+    );
+    write_corpus(
+        "sync_request",
+        "sample",
+        &sync_request(sample_checkpoint(&mut rng), &posts),
+    );
+    let [partial, full] = sample_sync_responses(&mut rng);
+    write_corpus("sync_response", "partial", &partial);
+    write_corpus("sync_response", "full", &full);
+}
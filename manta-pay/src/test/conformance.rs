@@ -0,0 +1,652 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transfer Protocol Conformance Vectors
+//!
+//! This module generates the reference end-to-end vectors of the transfer protocol, one for each
+//! [`TransferShape`], and checks the vectors and posts produced by other implementations of the
+//! protocol, such as SDKs or hardware signers, against them. As in the [key derivation
+//! vectors](super::vectors), every cryptographic value is the lowercase hex string of its
+//! [`Encode`] representation.
+//!
+//! A vector is made of inputs, which are the spending key, the randomness seed, the notes being
+//! spent, and the values of the new notes, and of the outputs built from them: the UTXOs and notes
+//! of the receivers, the nullifiers of the senders, the public input of the validity proof, and
+//! the encoding of the whole [`TransferPost`]. All the randomness is drawn from a [`ChaCha20Rng`]
+//! seeded with [`seed`](TransferVector::seed), in the following order: the spends of the input
+//! notes, the new notes, the authorization, and the authorization signature.
+//!
+//! Validity proofs are randomized, so the [`post`](TransferVector::post) of a vector carries the
+//! default proof and is only a byte-exact reference for the rest of the post. To check a post with
+//! a real proof, build it from the inputs of the vector and pass it to [`validate_post`], which
+//! compares everything but the proof and the signature, and verifies both of them instead.
+
+use crate::{
+    config::{
+        AccountId, Asset, AssetId, AssetValue, Authorization, Config, ConstraintField, Identifier,
+        MultiVerifyingContext, Parameters, PrivateTransfer, Receiver, SpendingKey, ToPrivate,
+        ToPublic, TransferPost, UtxoAccumulatorModel,
+    },
+    key::{KeySecret, Mnemonic, Testnet},
+    test::{
+        payment::UtxoAccumulator,
+        vectors::{from_hex, to_hex, Discrepancy, MNEMONICS},
+    },
+};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use blake2::{Blake2s256, Digest};
+use manta_accounting::{
+    key::{AccountCollection, AccountIndex},
+    transfer::{canonical::TransferShape, utxo::auth::DeriveContext, PreSender},
+};
+use manta_crypto::{
+    arkworks::constraint::fp::Fp,
+    rand::{ChaCha20Rng, SeedableRng},
+};
+use manta_util::{
+    codec::Encode,
+    serde::{Deserialize, Serialize},
+};
+
+/// Conformance Vector Suite
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct ConformanceVectors {
+    /// Parameters Digest
+    ///
+    /// BLAKE2s digest of the encodings of the transfer parameters and of the UTXO accumulator
+    /// model the vectors were built with. See [`parameters_digest`] for more.
+    pub parameters_digest: String,
+
+    /// Transfer Vectors
+    pub vectors: Vec<TransferVector>,
+}
+
+impl ConformanceVectors {
+    /// Builds the reference [`ConformanceVectors`] for every [`TransferShape`], building the
+    /// outputs with `parameters` and `utxo_accumulator_model`.
+    #[inline]
+    pub fn reference(
+        parameters: &Parameters,
+        utxo_accumulator_model: &UtxoAccumulatorModel,
+    ) -> Self {
+        let (mnemonic, password) = MNEMONICS[0];
+        let spending_key = KeySecret::<Testnet>::new(
+            Mnemonic::new(mnemonic).expect("The reference mnemonic is valid."),
+            password,
+        )
+        .spending_key(&AccountIndex::new(0));
+        let note = |is_transparent, nonce: u128, asset_value: u128| NoteVector {
+            is_transparent,
+            utxo_commitment_randomness: to_hex(&Fp::<ConstraintField>::from(nonce)),
+            asset_value: asset_value.to_string(),
+        };
+        let template = |shape, seed: u8, senders, receivers: &[u128], public_value: u128, sinks| {
+            TransferVector {
+                shape,
+                spending_key: to_hex(&spending_key),
+                seed: to_hex(&[seed; 32]),
+                asset_id: to_hex(&AssetId::from(0x10u128)),
+                public_value: public_value.to_string(),
+                senders,
+                receivers: receivers.iter().map(ToString::to_string).collect(),
+                sink_accounts: sinks,
+                ..Default::default()
+            }
+        };
+        let vectors = [
+            template(TransferShape::ToPrivate, 1, vec![], &[100], 100, vec![]),
+            template(
+                TransferShape::PrivateTransfer,
+                2,
+                vec![note(true, 0x21, 60), note(false, 0x22, 40)],
+                &[70, 30],
+                0,
+                vec![],
+            ),
+            template(
+                TransferShape::ToPublic,
+                3,
+                vec![note(false, 0x31, 50), note(false, 0x32, 50)],
+                &[25],
+                75,
+                vec![to_hex(&[0x11u8; 32])],
+            ),
+        ]
+        .into_iter()
+        .map(|template| {
+            template
+                .derive(parameters, utxo_accumulator_model, "")
+                .expect("The reference inputs are valid.")
+        })
+        .collect();
+        Self {
+            parameters_digest: parameters_digest(parameters, utxo_accumulator_model),
+            vectors,
+        }
+    }
+}
+
+/// Transfer Vector
+///
+/// The inputs of a transfer vector are its [`shape`](Self::shape), the
+/// [`spending_key`](Self::spending_key) which owns every note, the randomness
+/// [`seed`](Self::seed), the [`asset_id`](Self::asset_id) of every note, the
+/// [`public_value`](Self::public_value), the [`senders`](Self::senders), the values of the
+/// [`receivers`](Self::receivers), and the [`sink_accounts`](Self::sink_accounts). The notes being
+/// spent are the only leaves of a fresh UTXO accumulator, inserted in order, and the new notes are
+/// sent to the address of the spending key. Since every note has the same asset id, there are no
+/// transfer vectors for the [`PrivateSwap`](TransferShape::PrivateSwap) shape.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct TransferVector {
+    /// Transfer Shape
+    pub shape: TransferShape,
+
+    /// Spending Key
+    pub spending_key: String,
+
+    /// Randomness Seed
+    pub seed: String,
+
+    /// Asset Id
+    pub asset_id: String,
+
+    /// Decimal Public Value
+    ///
+    /// Value of the source of a [`ToPrivate`] or of the sink of a [`ToPublic`], and zero for a
+    /// [`PrivateTransfer`].
+    pub public_value: String,
+
+    /// Notes being Spent
+    pub senders: Vec<NoteVector>,
+
+    /// Decimal Values of the New Notes
+    pub receivers: Vec<String>,
+
+    /// Sink Accounts
+    pub sink_accounts: Vec<String>,
+
+    /// UTXOs of the New Notes
+    pub utxos: Vec<String>,
+
+    /// Encrypted New Notes
+    pub notes: Vec<String>,
+
+    /// Nullifiers of the Notes being Spent
+    pub nullifiers: Vec<String>,
+
+    /// Public Input of the Validity Proof
+    pub proof_input: Vec<String>,
+
+    /// Transfer Post with the Default Proof
+    pub post: String,
+}
+
+impl Default for TransferVector {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            shape: TransferShape::ToPrivate,
+            spending_key: Default::default(),
+            seed: Default::default(),
+            asset_id: Default::default(),
+            public_value: Default::default(),
+            senders: Default::default(),
+            receivers: Default::default(),
+            sink_accounts: Default::default(),
+            utxos: Default::default(),
+            notes: Default::default(),
+            nullifiers: Default::default(),
+            proof_input: Default::default(),
+            post: Default::default(),
+        }
+    }
+}
+
+impl TransferVector {
+    /// Builds the outputs of `self` from its inputs using `parameters` and
+    /// `utxo_accumulator_model`, where `location` is the path to `self` used in error reports.
+    #[inline]
+    pub fn derive(
+        &self,
+        parameters: &Parameters,
+        utxo_accumulator_model: &UtxoAccumulatorModel,
+        location: &str,
+    ) -> Result<Self, Discrepancy> {
+        let invalid_input = |field| Discrepancy::invalid_input(location, field);
+        let spending_key = from_hex::<SpendingKey>(&self.spending_key)
+            .ok_or_else(|| invalid_input("spending_key"))?;
+        let seed = from_hex::<[u8; 32]>(&self.seed).ok_or_else(|| invalid_input("seed"))?;
+        let asset_id =
+            from_hex::<AssetId>(&self.asset_id).ok_or_else(|| invalid_input("asset_id"))?;
+        let public_value = self
+            .public_value
+            .parse::<AssetValue>()
+            .map_err(|_| invalid_input("public_value"))?;
+        let sink_accounts = self
+            .sink_accounts
+            .iter()
+            .map(|account| from_hex::<AccountId>(account))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| invalid_input("sink_accounts"))?;
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        let mut authorization_context = parameters.derive_context(&spending_key);
+        let address = parameters.address_from_spending_key(&spending_key);
+        let mut utxo_accumulator = UtxoAccumulator::new(utxo_accumulator_model.clone());
+        let pre_senders = self
+            .senders
+            .iter()
+            .enumerate()
+            .map(|(i, note)| {
+                let identifier = Identifier::new(
+                    note.is_transparent,
+                    false,
+                    from_hex(&note.utxo_commitment_randomness).ok_or_else(|| {
+                        Discrepancy::invalid_input(
+                            &format!("{location}.senders[{i}]"),
+                            "utxo_commitment_randomness",
+                        )
+                    })?,
+                );
+                let asset_value = note.asset_value.parse().map_err(|_| {
+                    Discrepancy::invalid_input(&format!("{location}.senders[{i}]"), "asset_value")
+                })?;
+                Ok(PreSender::<Config>::sample(
+                    parameters,
+                    &mut authorization_context,
+                    identifier,
+                    Asset::new(asset_id, asset_value),
+                    &mut rng,
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for pre_sender in &pre_senders {
+            pre_sender.insert_utxo(parameters, &mut utxo_accumulator);
+        }
+        let senders = pre_senders
+            .into_iter()
+            .map(|pre_sender| {
+                pre_sender
+                    .try_upgrade(parameters, &utxo_accumulator)
+                    .expect("The UTXOs of the senders were just inserted.")
+            })
+            .collect::<Vec<_>>();
+        let receivers = self
+            .receivers
+            .iter()
+            .map(|value| {
+                Ok(Receiver::sample(
+                    parameters,
+                    address,
+                    Asset::new(
+                        asset_id,
+                        value.parse().map_err(|_| invalid_input("receivers"))?,
+                    ),
+                    Default::default(),
+                    &mut rng,
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let public_asset = Asset::new(asset_id, public_value);
+        let post = match self.shape {
+            TransferShape::ToPrivate => {
+                if !senders.is_empty() {
+                    return Err(invalid_input("senders"));
+                }
+                let [receiver] = into_array(receivers).ok_or_else(|| invalid_input("receivers"))?;
+                ToPrivate::build(public_asset, receiver).into_post_with_proof(
+                    parameters,
+                    Default::default(),
+                    None,
+                    sink_accounts,
+                    &mut rng,
+                )
+            }
+            TransferShape::PrivateTransfer => {
+                let senders = into_array(senders).ok_or_else(|| invalid_input("senders"))?;
+                let receivers = into_array(receivers).ok_or_else(|| invalid_input("receivers"))?;
+                PrivateTransfer::build(
+                    Authorization::from_spending_key(parameters, &spending_key, &mut rng),
+                    senders,
+                    receivers,
+                )
+                .into_post_with_proof(
+                    parameters,
+                    Default::default(),
+                    Some(&spending_key),
+                    sink_accounts,
+                    &mut rng,
+                )
+            }
+            TransferShape::ToPublic => {
+                let senders = into_array(senders).ok_or_else(|| invalid_input("senders"))?;
+                let receivers = into_array(receivers).ok_or_else(|| invalid_input("receivers"))?;
+                ToPublic::build(
+                    Authorization::from_spending_key(parameters, &spending_key, &mut rng),
+                    senders,
+                    receivers,
+                    public_asset,
+                )
+                .into_post_with_proof(
+                    parameters,
+                    Default::default(),
+                    Some(&spending_key),
+                    sink_accounts,
+                    &mut rng,
+                )
+            }
+            TransferShape::PrivateSwap => return Err(invalid_input("shape")),
+        }
+        .expect("The transfer is authorized by the spending key of its senders.");
+        Ok(Self {
+            utxos: post
+                .body
+                .receiver_posts
+                .iter()
+                .map(|receiver_post| to_hex(&receiver_post.utxo))
+                .collect(),
+            notes: post
+                .body
+                .receiver_posts
+                .iter()
+                .map(|receiver_post| to_hex(&receiver_post.note))
+                .collect(),
+            nullifiers: post
+                .body
+                .sender_posts
+                .iter()
+                .map(|sender_post| to_hex(&sender_post.nullifier))
+                .collect(),
+            proof_input: proof_input_hex(&post),
+            post: to_hex(&post),
+            ..self.clone()
+        })
+    }
+}
+
+/// Note Vector
+///
+/// Note owned by the spending key of the [`TransferVector`] it belongs to, at the identifier made
+/// of its [`is_transparent`](Self::is_transparent) flag and its
+/// [`utxo_commitment_randomness`](Self::utxo_commitment_randomness).
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct NoteVector {
+    /// Transparency Flag
+    pub is_transparent: bool,
+
+    /// UTXO Commitment Randomness
+    pub utxo_commitment_randomness: String,
+
+    /// Decimal Asset Value
+    pub asset_value: String,
+}
+
+/// Returns the lowercase hex string of the BLAKE2s digest of the encodings of `parameters` and
+/// `utxo_accumulator_model`, concatenated in the order in which they are stored in
+/// [`manta_parameters`].
+#[inline]
+pub fn parameters_digest(
+    parameters: &Parameters,
+    utxo_accumulator_model: &UtxoAccumulatorModel,
+) -> String {
+    let base = &parameters.base;
+    let mut hasher = Blake2s256::new();
+    for bytes in [
+        base.group_generator.to_vec(),
+        base.utxo_commitment_scheme.to_vec(),
+        base.incoming_base_encryption_scheme.to_vec(),
+        base.light_incoming_base_encryption_scheme.to_vec(),
+        base.viewing_key_derivation_function.to_vec(),
+        base.utxo_accumulator_item_hash.to_vec(),
+        base.nullifier_commitment_scheme.to_vec(),
+        base.outgoing_base_encryption_scheme.to_vec(),
+        parameters.address_partition_function.to_vec(),
+        parameters.schnorr_hash_function.to_vec(),
+        utxo_accumulator_model.to_vec(),
+    ] {
+        Digest::update(&mut hasher, &bytes);
+    }
+    to_hex(&<[u8; 32]>::from(hasher.finalize()))
+}
+
+/// Validates the outputs of `vectors` against the reference implementation, building them again
+/// from the inputs of `vectors` with `parameters` and `utxo_accumulator_model` and returning every
+/// discrepancy.
+#[inline]
+pub fn validate(
+    parameters: &Parameters,
+    utxo_accumulator_model: &UtxoAccumulatorModel,
+    vectors: &ConformanceVectors,
+) -> Vec<Discrepancy> {
+    let mut discrepancies = Vec::new();
+    Discrepancy::check::<str>(
+        &mut discrepancies,
+        "suite",
+        "parameters_digest",
+        &parameters_digest(parameters, utxo_accumulator_model),
+        &vectors.parameters_digest,
+    );
+    for (i, actual) in vectors.vectors.iter().enumerate() {
+        let location = format!("vectors[{i}]");
+        match actual.derive(parameters, utxo_accumulator_model, &location) {
+            Ok(expected) => {
+                check_outputs(&mut discrepancies, &location, &expected, actual);
+                Discrepancy::check::<str>(
+                    &mut discrepancies,
+                    &location,
+                    "post",
+                    &expected.post,
+                    &actual.post,
+                );
+            }
+            Err(discrepancy) => discrepancies.push(discrepancy),
+        }
+    }
+    discrepancies
+}
+
+/// Validates `post`, built from the inputs of `vector` with a real validity proof, against
+/// `vector`, returning every discrepancy. Since the proof and the authorization signature are
+/// randomized, they are verified with `verifying_context` and `parameters` instead of being
+/// compared with the [`post`](TransferVector::post) of `vector`, and the remaining outputs are
+/// compared byte by byte.
+#[inline]
+pub fn validate_post(
+    parameters: &Parameters,
+    verifying_context: &MultiVerifyingContext,
+    vector: &TransferVector,
+    post: &TransferPost,
+) -> Vec<Discrepancy> {
+    let location = "post";
+    let mut discrepancies = Vec::new();
+    let shape = match TransferShape::from_post(post) {
+        Some(shape) if shape == vector.shape => shape,
+        _ => {
+            discrepancies.push(Discrepancy::invalid_input(location, "shape"));
+            return discrepancies;
+        }
+    };
+    let actual = TransferVector {
+        utxos: post
+            .body
+            .receiver_posts
+            .iter()
+            .map(|receiver_post| to_hex(&receiver_post.utxo))
+            .collect(),
+        notes: post
+            .body
+            .receiver_posts
+            .iter()
+            .map(|receiver_post| to_hex(&receiver_post.note))
+            .collect(),
+        nullifiers: post
+            .body
+            .sender_posts
+            .iter()
+            .map(|sender_post| to_hex(&sender_post.nullifier))
+            .collect(),
+        proof_input: proof_input_hex(post),
+        ..vector.clone()
+    };
+    check_outputs(&mut discrepancies, location, vector, &actual);
+    if post.has_valid_authorization_signature(parameters).is_err() {
+        discrepancies.push(Discrepancy::invalid_input(
+            location,
+            "authorization_signature",
+        ));
+    }
+    if !matches!(
+        verifying_context
+            .try_select(shape)
+            .map(|verifying_context| post.has_valid_proof(verifying_context)),
+        Some(Ok(true))
+    ) {
+        discrepancies.push(Discrepancy::invalid_input(location, "proof"));
+    }
+    discrepancies
+}
+
+/// Pushes a [`Discrepancy::Mismatch`] onto `discrepancies` for every output of `actual` which
+/// differs from `expected`, except for the post.
+#[inline]
+fn check_outputs(
+    discrepancies: &mut Vec<Discrepancy>,
+    location: &str,
+    expected: &TransferVector,
+    actual: &TransferVector,
+) {
+    for (field, expected, actual) in [
+        ("utxos", &expected.utxos, &actual.utxos),
+        ("notes", &expected.notes, &actual.notes),
+        ("nullifiers", &expected.nullifiers, &actual.nullifiers),
+        ("proof_input", &expected.proof_input, &actual.proof_input),
+    ] {
+        Discrepancy::check::<str>(
+            discrepancies,
+            location,
+            field,
+            &expected.join(","),
+            &actual.join(","),
+        );
+    }
+}
+
+/// Returns the lowercase hex strings of the public input of the validity proof of `post`.
+#[inline]
+fn proof_input_hex(post: &TransferPost) -> Vec<String> {
+    post.generate_proof_input()
+        .into_iter()
+        .map(|element| to_hex(&Fp(element)))
+        .collect()
+}
+
+/// Converts `vec` into an array, returning `None` if the lengths do not match.
+#[inline]
+fn into_array<T, const N: usize>(vec: Vec<T>) -> Option<[T; N]> {
+    vec.try_into().ok()
+}
+
+/// Testing Suite
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parameters::{
+        load_private_transfer_verifying_context, load_to_private_verifying_context,
+        load_to_public_verifying_context, load_transfer_parameters, load_utxo_accumulator_model,
+    };
+
+    /// Tests that the reference implementation still produces the published conformance vectors.
+    #[test]
+    fn reference_vectors_are_stable() {
+        let parameters = load_transfer_parameters();
+        let utxo_accumulator_model = load_utxo_accumulator_model();
+        let published =
+            serde_json::from_str::<ConformanceVectors>(include_str!("conformance.json"))
+                .expect("Unable to parse the published conformance vectors.");
+        assert_eq!(
+            ConformanceVectors::reference(&parameters, &utxo_accumulator_model),
+            published,
+            "The transfer protocol has drifted from the published conformance vectors."
+        );
+        assert!(validate(&parameters, &utxo_accumulator_model, &published).is_empty());
+    }
+
+    /// Tests that [`validate`] reports every mismatched output and invalid input.
+    #[test]
+    fn validation_reports_discrepancies() {
+        let parameters = load_transfer_parameters();
+        let utxo_accumulator_model = load_utxo_accumulator_model();
+        let mut vectors = ConformanceVectors::reference(&parameters, &utxo_accumulator_model);
+        vectors.vectors[0].notes[0].clear();
+        vectors.vectors[1].senders[1].asset_value = "invalid".into();
+        vectors.vectors[2].proof_input.pop();
+        let discrepancies = validate(&parameters, &utxo_accumulator_model, &vectors);
+        assert_eq!(discrepancies.len(), 3);
+        assert!(matches!(
+            &discrepancies[0],
+            Discrepancy::Mismatch { location, field: "notes", .. } if location == "vectors[0]"
+        ));
+        assert_eq!(
+            discrepancies[1],
+            Discrepancy::invalid_input("vectors[1].senders[1]", "asset_value")
+        );
+        assert!(matches!(
+            &discrepancies[2],
+            Discrepancy::Mismatch { location, field: "proof_input", .. } if location == "vectors[2]"
+        ));
+    }
+
+    /// Tests that [`validate_post`] accepts everything but the default proof of the reference
+    /// posts, and reports tampered posts.
+    #[test]
+    fn post_validation_checks_proofs() {
+        let parameters = load_transfer_parameters();
+        let verifying_context = MultiVerifyingContext {
+            to_private: load_to_private_verifying_context(),
+            private_transfer: load_private_transfer_verifying_context(),
+            to_public: load_to_public_verifying_context(),
+            private_swap: None,
+        };
+        let vectors = serde_json::from_str::<ConformanceVectors>(include_str!("conformance.json"))
+            .expect("Unable to parse the published conformance vectors.");
+        for (i, vector) in vectors.vectors.iter().enumerate() {
+            let mut post = from_hex::<TransferPost>(&vector.post)
+                .expect("The published posts are valid encodings.");
+            assert_eq!(
+                validate_post(&parameters, &verifying_context, vector, &post),
+                vec![Discrepancy::invalid_input("post", "proof")],
+            );
+            let other = &vectors.vectors[(i + 1) % vectors.vectors.len()];
+            post.body.receiver_posts[0].utxo =
+                from_hex(&other.utxos[0]).expect("The published UTXOs are valid encodings.");
+            assert!(
+                validate_post(&parameters, &verifying_context, vector, &post)
+                    .iter()
+                    .any(|discrepancy| matches!(
+                        discrepancy,
+                        Discrepancy::Mismatch { field: "utxos", .. }
+                    ))
+            );
+        }
+    }
+}
@@ -0,0 +1,270 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Native and Circuit Differential Testing
+//!
+//! Every gadget of the transfer circuit has a native counterpart which is used by wallets and
+//! ledgers. The tests in this module run both of them on the same random inputs, honest or
+//! tampered, and check that the native computation accepts exactly when the constraints are
+//! satisfied and that both of them compute the same outputs. A divergence between the two would
+//! otherwise only show up once proofs built against the trusted setup start failing.
+
+use crate::{
+    config::{
+        utxo::{ParametersVar, UtxoAccumulatorModelVar},
+        Asset, Authorization, Compiler, Config, Parameters, Receiver, Sender, SpendingKey,
+        UtxoAccumulatorModel,
+    },
+    test::payment::UtxoAccumulator,
+};
+use manta_accounting::transfer::{
+    internal_pair,
+    utxo::{auth::AssertAuthorized, DeriveMint},
+    AssetVar, AuthorizationVar, ReceiverVar, SenderVar,
+};
+use manta_crypto::{
+    accumulator::Accumulator,
+    eclair::{
+        alloc::{mode::Secret, Allocate},
+        bool::AssertEq,
+    },
+    rand::{OsRng, Rand, Sample},
+};
+use std::panic::{self, AssertUnwindSafe};
+
+/// Number of Random Inputs per Gadget
+const ROUNDS: usize = 4;
+
+/// Runs `native` and `circuit` on the same inputs, asserting that the native computation
+/// succeeds exactly when the constraints built by `circuit` are satisfied. The `circuit` receives
+/// the native output, if any, and should constrain its own output to be equal to it. Returns
+/// `true` if both computations accepted their inputs.
+#[inline]
+fn differential<T, N, C>(gadget: &str, native: N, circuit: C) -> bool
+where
+    N: FnOnce() -> T,
+    C: FnOnce(Option<&T>, &mut Compiler),
+{
+    let native_output = panic::catch_unwind(AssertUnwindSafe(native)).ok();
+    let mut compiler = Compiler::for_proofs();
+    circuit(native_output.as_ref(), &mut compiler);
+    assert_eq!(
+        native_output.is_some(),
+        compiler.is_satisfied(),
+        "Native and circuit computations of the {gadget} gadget diverged."
+    );
+    native_output.is_some()
+}
+
+/// Samples fresh parameters, a UTXO accumulator and an authorization for a random spending key.
+#[inline]
+fn sample_setup() -> (Parameters, UtxoAccumulator, SpendingKey, Authorization) {
+    let mut rng = OsRng;
+    let parameters = Parameters::gen(&mut rng);
+    let utxo_accumulator = UtxoAccumulator::new(UtxoAccumulatorModel::gen(&mut rng));
+    let spending_key = rng.gen();
+    let authorization = Authorization::from_spending_key(&parameters, &spending_key, &mut rng);
+    (parameters, utxo_accumulator, spending_key, authorization)
+}
+
+/// Checks the asset well-formedness of `receiver` natively and in-circuit.
+#[inline]
+fn check_receiver(parameters: &Parameters, receiver: &Receiver) -> bool {
+    differential(
+        "receiver",
+        || receiver.well_formed_asset(parameters, &mut ()),
+        |asset, compiler| {
+            let parameters = parameters.as_constant::<ParametersVar>(compiler);
+            let receiver = receiver.as_known::<_, ReceiverVar<Config>>(compiler);
+            let asset_var = receiver.well_formed_asset(&parameters, compiler);
+            if let Some(asset) = asset {
+                let expected = asset.as_known::<Secret, AssetVar<Config>>(compiler);
+                compiler.assert_eq(&asset_var, &expected);
+            }
+        },
+    )
+}
+
+/// Checks the asset well-formedness and UTXO membership of `sender` against `authorization`
+/// natively and in-circuit.
+#[inline]
+fn check_sender(
+    parameters: &Parameters,
+    utxo_accumulator_model: &UtxoAccumulatorModel,
+    authorization: &Authorization,
+    sender: &Sender,
+) -> bool {
+    differential(
+        "sender",
+        || {
+            sender.well_formed_asset(
+                parameters,
+                utxo_accumulator_model,
+                &mut authorization.context.clone(),
+                &mut (),
+            )
+        },
+        |asset, compiler| {
+            let parameters = parameters.as_constant::<ParametersVar>(compiler);
+            let utxo_accumulator_model =
+                utxo_accumulator_model.as_constant::<UtxoAccumulatorModelVar>(compiler);
+            let mut authorization = authorization.as_known::<_, AuthorizationVar<Config>>(compiler);
+            let sender = sender.as_known::<_, SenderVar<Config>>(compiler);
+            let asset_var = sender.well_formed_asset(
+                &parameters,
+                &utxo_accumulator_model,
+                &mut authorization.context,
+                compiler,
+            );
+            if let Some(asset) = asset {
+                let expected = asset.as_known::<Secret, AssetVar<Config>>(compiler);
+                compiler.assert_eq(&asset_var, &expected);
+            }
+        },
+    )
+}
+
+/// Checks `authorization` natively and in-circuit.
+#[inline]
+fn check_authorization(parameters: &Parameters, authorization: &Authorization) -> bool {
+    differential(
+        "authorization",
+        || {
+            parameters
+                .base
+                .assert_authorized(&authorization.context, &authorization.proof, &mut ())
+        },
+        |_, compiler| {
+            let parameters = parameters.as_constant::<ParametersVar>(compiler);
+            let authorization = authorization.as_known::<_, AuthorizationVar<Config>>(compiler);
+            authorization.assert_authorized(&parameters, compiler);
+        },
+    )
+}
+
+/// Tests that receivers are well-formed natively exactly when they are in-circuit, also when
+/// their UTXO does not match their secret.
+#[test]
+fn receiver_differential() {
+    let mut rng = OsRng;
+    for _ in 0..ROUNDS {
+        let (parameters, _, spending_key, _) = sample_setup();
+        let address = parameters.address_from_spending_key(&spending_key);
+        let (secret, utxo, note) = parameters.derive_mint(
+            address,
+            Asset::new(rng.gen(), rng.gen()),
+            Default::default(),
+            &mut rng,
+        );
+        let (_, other_utxo, _) = parameters.derive_mint(
+            address,
+            Asset::new(rng.gen(), rng.gen()),
+            Default::default(),
+            &mut rng,
+        );
+        assert!(
+            check_receiver(&parameters, &Receiver::new(secret, utxo, note.clone())),
+            "Honest receivers should be accepted."
+        );
+        assert!(
+            !check_receiver(&parameters, &Receiver::new(secret, other_utxo, note)),
+            "Receivers with a mismatched UTXO should be rejected."
+        );
+    }
+}
+
+/// Tests that senders are well-formed and members of the UTXO accumulator natively exactly when
+/// they are in-circuit, also when they are spent with the wrong authorization or carry the
+/// membership proof of another UTXO.
+#[test]
+fn sender_differential() {
+    let mut rng = OsRng;
+    for _ in 0..ROUNDS {
+        let (parameters, mut utxo_accumulator, spending_key, mut authorization) = sample_setup();
+        let address = parameters.address_from_spending_key(&spending_key);
+        let mut sample_pre_sender = || {
+            let (_, pre_sender) = internal_pair::<Config, _>(
+                &parameters,
+                &mut authorization.context,
+                address,
+                Asset::new(rng.gen(), rng.gen()),
+                Default::default(),
+                &mut rng,
+            );
+            assert!(
+                pre_sender.insert_utxo(&parameters, &mut utxo_accumulator),
+                "Inserting into the UTXO accumulator is not allowed to fail."
+            );
+            pre_sender
+        };
+        let pre_sender = sample_pre_sender();
+        let other_pre_sender = sample_pre_sender();
+        let utxo_accumulator_model = utxo_accumulator.model();
+        let sender = pre_sender
+            .try_upgrade(&parameters, &utxo_accumulator)
+            .expect("Getting the membership proof is not allowed to fail.");
+        assert!(
+            check_sender(&parameters, utxo_accumulator_model, &authorization, &sender),
+            "Honest senders should be accepted."
+        );
+        let other_authorization =
+            Authorization::from_spending_key(&parameters, &rng.gen(), &mut rng);
+        assert!(
+            !check_sender(
+                &parameters,
+                utxo_accumulator_model,
+                &other_authorization,
+                &sender,
+            ),
+            "Senders spent with the wrong authorization should be rejected."
+        );
+        let other_proof = other_pre_sender
+            .get_proof(&parameters, &utxo_accumulator)
+            .expect("Getting the membership proof is not allowed to fail.");
+        assert!(
+            !check_sender(
+                &parameters,
+                utxo_accumulator_model,
+                &authorization,
+                &pre_sender.upgrade(other_proof),
+            ),
+            "Senders with the membership proof of another UTXO should be rejected."
+        );
+    }
+}
+
+/// Tests that authorizations are accepted natively exactly when they are in-circuit, also when
+/// their context and proof come from different spending keys.
+#[test]
+fn authorization_differential() {
+    let mut rng = OsRng;
+    for _ in 0..ROUNDS {
+        let (parameters, _, _, authorization) = sample_setup();
+        let other_authorization =
+            Authorization::from_spending_key(&parameters, &rng.gen(), &mut rng);
+        assert!(
+            check_authorization(&parameters, &authorization),
+            "Honest authorizations should be accepted."
+        );
+        assert!(
+            !check_authorization(
+                &parameters,
+                &Authorization::new(authorization.context, other_authorization.proof),
+            ),
+            "Authorizations mixing two spending keys should be rejected."
+        );
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Pay Domain Separation Testing
+
+use crate::domain::{
+    check_byte_tags, check_field_tags, Collision, DomainTag, FieldDomainTag,
+    ASSET_SCOPED_NULLIFIER_ITEM, BYTE_TAGS, CURRENT_UTXO_COMMITMENT, FIELD_TAGS, INNER_HASH,
+    NULLIFIER_COMMITMENT_SCHEME, TRANSACTION_ID, UTXO_COMMITMENT_SCHEME,
+};
+
+/// Checks that the tags in the registry do not collide and that the versioned tags follow the
+/// `manta-pay/<version>/<name>` format.
+#[test]
+fn registry_collision_test() {
+    assert_eq!(check_byte_tags(BYTE_TAGS), Ok(()));
+    assert_eq!(check_field_tags(FIELD_TAGS), Ok(()));
+    for tag in BYTE_TAGS {
+        if let Some(version) = tag.version {
+            assert_eq!(
+                tag.bytes,
+                format!("manta-pay/{}/{}", version, tag.name).as_bytes(),
+                "Versioned tags should follow the registry format."
+            );
+        }
+    }
+}
+
+/// Checks that new tags which collide with the registry are rejected.
+#[test]
+fn claim_colliding_tag_test() {
+    let prefix = DomainTag {
+        name: "transaction",
+        version: Some("1.0.0"),
+        bytes: b"manta-pay/1.0.0/transaction",
+    };
+    let mut tags = BYTE_TAGS.to_vec();
+    tags.push(prefix);
+    assert_eq!(
+        check_byte_tags(&tags),
+        Err(Collision::Prefix(prefix.name, TRANSACTION_ID.name))
+    );
+    let upgraded = DomainTag {
+        name: TRANSACTION_ID.name,
+        version: Some("2.0.0"),
+        bytes: b"manta-pay/2.0.0/transaction-id",
+    };
+    tags.pop();
+    tags.push(upgraded);
+    assert_eq!(
+        check_byte_tags(&tags),
+        Ok(()),
+        "Claiming a new version of an existing tag should be allowed."
+    );
+    let mut tags = FIELD_TAGS.to_vec();
+    tags.push(FieldDomainTag {
+        name: "memo-hash",
+        arity: INNER_HASH.arity,
+        value: INNER_HASH.value,
+        legacy: false,
+    });
+    assert!(
+        check_field_tags(&tags).is_err(),
+        "New field tags are not allowed to collide with legacy tags."
+    );
+}
+
+/// Checks that the hashes which reuse a legacy Poseidon permutation are registered under tags of
+/// their own and that no other field tag shares their names or values.
+#[test]
+fn shared_permutation_tags_test() {
+    for (tag, legacy) in [
+        (ASSET_SCOPED_NULLIFIER_ITEM, NULLIFIER_COMMITMENT_SCHEME),
+        (CURRENT_UTXO_COMMITMENT, UTXO_COMMITMENT_SCHEME),
+    ] {
+        assert!(FIELD_TAGS.contains(&tag), "The tag should be registered.");
+        assert!(
+            !tag.legacy,
+            "New hashes are not allowed to use legacy tags."
+        );
+        assert_eq!(tag.arity, legacy.arity);
+        assert_ne!(tag.value, legacy.value);
+        assert_eq!(
+            FIELD_TAGS
+                .iter()
+                .filter(|other| other.name == tag.name
+                    || (other.arity == tag.arity && other.value == tag.value))
+                .count(),
+            1,
+            "The tag should be unique in the registry."
+        );
+        let mut tags = FIELD_TAGS.to_vec();
+        tags.retain(|other| other.name != tag.name);
+        tags.push(FieldDomainTag {
+            value: legacy.value,
+            ..tag
+        });
+        assert!(
+            check_field_tags(&tags).is_err(),
+            "Reusing the legacy tag value is not allowed."
+        );
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Probabilistic Filter Testing Suite
+
+use manta_util::collections::filter::{BloomFilter, CuckooFilter, Filter};
+
+/// Number of Items Inserted into the Sampled Filters
+const CAPACITY: u64 = 4096;
+
+/// Target False Positive Rate of the Sampled Filters
+const RATE: f64 = 1e-2;
+
+/// Inserts the first [`CAPACITY`]-many integers into `filter`, and checks that they are all
+/// reported as present and that the observed false positive rate over the next [`CAPACITY`]-many
+/// integers stays within twice the target [`RATE`].
+#[inline]
+fn check_filter<F>(filter: &mut F)
+where
+    F: Filter,
+{
+    assert!(filter.is_empty());
+    assert_eq!(filter.saturation(), 0.0);
+    for item in 0..CAPACITY {
+        assert!(filter.insert(&item), "The filter should accept {item}.");
+    }
+    assert_eq!(filter.len(), CAPACITY as usize);
+    for item in 0..CAPACITY {
+        assert!(
+            filter.may_contain(&item),
+            "Filters are not allowed to have false negatives."
+        );
+    }
+    let false_positives = (CAPACITY..2 * CAPACITY)
+        .filter(|item| filter.may_contain(item))
+        .count();
+    assert!(
+        (false_positives as f64) < 2.0 * RATE * CAPACITY as f64,
+        "Observed {false_positives} false positives out of {CAPACITY} queries."
+    );
+    let saturation = filter.saturation();
+    assert!(saturation > 0.0 && saturation <= 1.0);
+    let rate = filter.false_positive_rate();
+    assert!(
+        rate > 0.0 && rate < 2.0 * RATE,
+        "The estimated false positive rate {rate} should be close to the target."
+    );
+    filter.clear();
+    assert!(filter.is_empty());
+    assert_eq!(filter.saturation(), 0.0);
+    assert!((0..CAPACITY).all(|item| !filter.may_contain(&item)));
+}
+
+/// Checks the bloom filter sized for [`CAPACITY`] items at the target [`RATE`].
+#[test]
+fn bloom_filter() {
+    let mut filter = BloomFilter::with_false_positive_rate(CAPACITY as usize, RATE);
+    assert!(filter.bit_count() >= 9 * CAPACITY as usize);
+    assert_eq!(filter.hash_count(), 7);
+    check_filter(&mut filter);
+    assert_eq!(filter.set_bit_count(), 0);
+}
+
+/// Checks the cuckoo filter sized for [`CAPACITY`] items at the target [`RATE`], including the
+/// removal of items.
+#[test]
+fn cuckoo_filter() {
+    let mut filter = CuckooFilter::with_false_positive_rate(CAPACITY as usize, RATE);
+    assert!(filter.bucket_count().is_power_of_two());
+    assert!(filter.bucket_count() * 4 >= CAPACITY as usize);
+    assert_eq!(filter.fingerprint_bits(), 10);
+    check_filter(&mut filter);
+    for item in 0..CAPACITY {
+        assert!(filter.insert(&item));
+    }
+    for item in (0..CAPACITY).step_by(2) {
+        assert!(filter.remove(&item), "Inserted items should be removable.");
+    }
+    assert_eq!(filter.len(), CAPACITY as usize / 2);
+    assert!(
+        (1..CAPACITY)
+            .step_by(2)
+            .all(|item| filter.may_contain(&item)),
+        "Removing items is not allowed to remove the other items."
+    );
+    let remaining = (0..CAPACITY)
+        .step_by(2)
+        .filter(|item| filter.may_contain(item))
+        .count();
+    assert!(
+        (remaining as f64) < 2.0 * RATE * CAPACITY as f64,
+        "Removed items should no longer be reported, found {remaining}."
+    );
+}
+
+/// Checks that a saturated cuckoo filter rejects insertions until an item is removed.
+#[test]
+fn cuckoo_filter_saturation() {
+    let mut filter = CuckooFilter::new(2, 16);
+    let mut inserted = Vec::new();
+    for item in 0u64.. {
+        if !filter.insert(&item) {
+            break;
+        }
+        inserted.push(item);
+    }
+    assert_eq!(filter.len(), inserted.len());
+    assert!(
+        inserted.len() > 8,
+        "The victim slot should hold one item past the buckets."
+    );
+    assert!(inserted.iter().all(|item| filter.may_contain(item)));
+    assert!(
+        !filter.insert(&u64::MAX),
+        "A saturated filter should reject items."
+    );
+    assert!(filter.remove(&inserted[0]));
+    assert!(
+        filter.insert(&u64::MAX),
+        "Removing an item should make room for a new one."
+    );
+    assert!(inserted[1..].iter().all(|item| filter.may_contain(item)));
+    assert!(filter.may_contain(&u64::MAX));
+}
+
+/// Checks that filters keep their contents through serialization.
+#[cfg(feature = "serde_json")]
+#[test]
+fn filter_serde_round_trip() {
+    let mut bloom = BloomFilter::with_false_positive_rate(64, RATE);
+    let mut cuckoo = CuckooFilter::with_false_positive_rate(64, RATE);
+    for item in 0..64u64 {
+        bloom.insert(&item);
+        cuckoo.insert(&item);
+    }
+    let decoded_bloom: BloomFilter = serde_json::from_str(
+        &serde_json::to_string(&bloom).expect("Serializing filters is not allowed to fail."),
+    )
+    .expect("Deserializing filters is not allowed to fail.");
+    let decoded_cuckoo: CuckooFilter = serde_json::from_str(
+        &serde_json::to_string(&cuckoo).expect("Serializing filters is not allowed to fail."),
+    )
+    .expect("Deserializing filters is not allowed to fail.");
+    assert_eq!(decoded_bloom, bloom);
+    assert_eq!(decoded_cuckoo, cuckoo);
+    assert!((0..64u64).all(|item| decoded_bloom.may_contain(&item)));
+    assert!((0..64u64).all(|item| decoded_cuckoo.may_contain(&item)));
+}
+
+/// Checks that the ledger snapshot moves pending nullifiers to the spent nullifier filter once
+/// they reach the ledger, and reports the saturation of its filters.
+#[cfg(feature = "groth16")]
+#[test]
+fn ledger_snapshot_pending_nullifiers() {
+    use crate::{signer::base::LedgerSnapshot, test::codec::corpus_posts};
+    use manta_accounting::wallet::signer::LedgerSnapshot as _;
+    let nullifiers = corpus_posts()
+        .into_iter()
+        .flat_map(|post| post.body.sender_posts)
+        .map(|sender_post| sender_post.nullifier)
+        .collect::<Vec<_>>();
+    assert!(
+        nullifiers.len() >= 2,
+        "The corpus should reveal nullifiers."
+    );
+    let mut snapshot = LedgerSnapshot::new(16, RATE, 16, RATE);
+    for nullifier in &nullifiers {
+        assert!(!snapshot.may_contain_pending_nullifier(nullifier));
+        snapshot.insert_pending_nullifier(nullifier);
+        assert!(snapshot.may_contain_pending_nullifier(nullifier));
+        assert!(!snapshot.may_contain_nullifier(nullifier));
+    }
+    assert_eq!(snapshot.pending_nullifier_filter().len(), nullifiers.len());
+    snapshot.insert_nullifier(&nullifiers[0]);
+    assert!(snapshot.may_contain_nullifier(&nullifiers[0]));
+    assert!(!snapshot.may_contain_pending_nullifier(&nullifiers[0]));
+    assert!(snapshot.may_contain_pending_nullifier(&nullifiers[1]));
+    assert_eq!(snapshot.nullifier_filter().len(), 1);
+    assert!(snapshot.nullifier_filter().saturation() > 0.0);
+    snapshot.clear_pending_nullifiers();
+    assert!(snapshot.pending_nullifier_filter().is_empty());
+    assert!(nullifiers
+        .iter()
+        .all(|nullifier| !snapshot.may_contain_pending_nullifier(nullifier)));
+    assert!(snapshot.may_contain_nullifier(&nullifiers[0]));
+}
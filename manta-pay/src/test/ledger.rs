@@ -0,0 +1,128 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Ledger Index Testing Suite
+
+use core::time::Duration;
+use manta_accounting::wallet::ledger::{BlockHeight, BlockStatus, BlockTime, LeafIndex, NoteIndex};
+
+/// Checks the arithmetic and the conversions of the typed ledger indices.
+#[test]
+fn ledger_index_arithmetic() {
+    let leaf = LeafIndex::new(5);
+    assert_eq!(leaf.get(), 5);
+    assert_eq!(leaf.advance(3), LeafIndex::new(8));
+    assert_eq!(leaf.rewind(2), LeafIndex::new(3));
+    assert_eq!(
+        leaf.rewind(9),
+        LeafIndex::default(),
+        "Rewinding should stop at zero."
+    );
+    assert_eq!(leaf.advance(3).since(leaf), Some(3));
+    assert_eq!(
+        leaf.since(leaf.advance(1)),
+        None,
+        "An origin after the index is not allowed."
+    );
+    assert_eq!(NoteIndex::from(7).to_string(), "7");
+    assert_eq!(usize::from(NoteIndex::new(7)), 7);
+    assert!(NoteIndex::new(1) < NoteIndex::new(2));
+    assert_eq!(u64::from(BlockHeight::new(u64::MAX).rewind(u64::MAX)), 0);
+}
+
+/// Checks the confirmed block height and the number of unconfirmed blocks reported by the ledger.
+#[test]
+fn block_height_confirmation() {
+    let block_time = BlockTime::new(Duration::from_secs(6), 2);
+    assert_eq!(
+        block_time.confirmed_block(BlockHeight::new(10)),
+        BlockHeight::new(8)
+    );
+    assert_eq!(
+        block_time.confirmed_block(BlockHeight::new(1)),
+        BlockHeight::default(),
+        "The genesis block is always confirmed."
+    );
+    let status = BlockStatus {
+        block_number: BlockHeight::new(10),
+        confirmed_block_number: block_time.confirmed_block(BlockHeight::new(10)),
+        pending_batches: 0,
+    };
+    assert_eq!(status.unconfirmed_blocks(), 2);
+    assert!(!status.is_settled());
+    let status = BlockStatus {
+        confirmed_block_number: status.block_number,
+        ..status
+    };
+    assert_eq!(status.unconfirmed_blocks(), 0);
+    assert!(status.is_settled());
+}
+
+/// Checks that the typed indices serialize as plain integers, so that the wire format of the
+/// synchronization protocol does not change.
+#[cfg(feature = "serde_json")]
+#[test]
+fn ledger_index_serde() {
+    assert_eq!(
+        serde_json::to_string(&LeafIndex::new(42)).expect("Serializing is not allowed to fail."),
+        "42"
+    );
+    assert_eq!(
+        serde_json::from_str::<BlockHeight>("7").expect("Deserializing is not allowed to fail."),
+        BlockHeight::new(7)
+    );
+    let status = BlockStatus {
+        block_number: BlockHeight::new(3),
+        confirmed_block_number: BlockHeight::new(1),
+        pending_batches: 0,
+    };
+    assert_eq!(
+        serde_json::to_value(status).expect("Serializing is not allowed to fail."),
+        serde_json::json!({
+            "block_number": 3,
+            "confirmed_block_number": 1,
+            "pending_batches": 0,
+        })
+    );
+}
+
+/// Checks that checkpoints keep their typed indices through the raw encoding and that committed
+/// checkpoints advance the note index by the number of nullifiers.
+#[cfg(feature = "groth16")]
+#[test]
+fn checkpoint_indices() {
+    use crate::config::utxo::{Checkpoint, MerkleTreeConfiguration, RawCheckpoint};
+    use manta_util::codec::{Decode, Encode};
+    let checkpoint = Checkpoint::new(
+        (0..MerkleTreeConfiguration::FOREST_WIDTH)
+            .map(LeafIndex::new)
+            .collect(),
+        NoteIndex::new(17),
+    );
+    let raw = RawCheckpoint::from(checkpoint);
+    assert_eq!(raw.sender_index, 17);
+    assert_eq!(raw.receiver_index[1], 1);
+    assert_eq!(Checkpoint::from(raw), checkpoint);
+    assert_eq!(
+        Checkpoint::from_vec(checkpoint.to_vec()).expect("Decoding is not allowed to fail."),
+        checkpoint
+    );
+    assert_eq!(
+        Checkpoint::default().sender_index,
+        NoteIndex::default(),
+        "The default checkpoint starts at the first nullifier."
+    );
+}
@@ -19,12 +19,81 @@
 #[cfg(test)]
 pub mod balance;
 
+#[cfg(test)]
+pub mod batch;
+
+#[cfg(all(feature = "groth16", feature = "payment-channels", test))]
+pub mod channel;
+
+#[cfg(all(feature = "groth16", test))]
+pub mod codec;
+
 #[cfg(test)]
 pub mod compatibility;
 
+#[cfg(all(feature = "key", feature = "parameters", feature = "serde"))]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "key", feature = "parameters", feature = "serde")))
+)]
+pub mod conformance;
+
+#[cfg(test)]
+pub mod differential;
+
+#[cfg(test)]
+pub mod domain;
+
+#[cfg(test)]
+pub mod filter;
+
+#[cfg(test)]
+pub mod ledger;
+
+#[cfg(all(feature = "groth16", feature = "simulation", test))]
+pub mod network;
+
+#[cfg(all(feature = "groth16", test))]
+pub mod payment_request;
+
+#[cfg(test)]
+pub mod prefetch;
+
+#[cfg(all(
+    feature = "groth16",
+    feature = "http",
+    feature = "simulation",
+    feature = "socks",
+    feature = "websocket",
+    test
+))]
+pub mod proxy;
+
+#[cfg(all(feature = "groth16", test))]
+pub mod sigma;
+
+#[cfg(all(feature = "groth16", feature = "simulation", test))]
+pub mod simulation;
+
+#[cfg(all(feature = "groth16", feature = "simulation", feature = "sled", test))]
+pub mod store;
+
+#[cfg(all(feature = "groth16", feature = "simulation", feature = "stream", test))]
+pub mod stream;
+
 #[cfg(test)]
 pub mod transfer;
 
+#[cfg(all(feature = "key", feature = "parameters", feature = "serde"))]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "key", feature = "parameters", feature = "serde")))
+)]
+pub mod vectors;
+
+#[cfg(test)]
+pub mod zeroize;
+
 #[cfg(feature = "groth16")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "groth16")))]
 pub mod payment;
@@ -0,0 +1,180 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Simulated Network Testing Suite
+
+use crate::{
+    parameters::load_parameters,
+    simulation::{
+        account_id_from_u64,
+        ledger::{
+            network::{
+                LatencyDistribution, Network, NetworkConnection, NetworkError, NetworkModel,
+                Partition, SharedNetwork,
+            },
+            Ledger, SharedLedger,
+        },
+    },
+};
+use alloc::{sync::Arc, vec::Vec};
+use core::time::Duration;
+use manta_accounting::wallet::ledger::{BlockStatusQuery, BlockTime, Write};
+use tokio::sync::RwLock;
+
+/// Builds a new [`SharedLedger`] with freshly loaded parameters.
+#[inline]
+fn sample_ledger() -> SharedLedger {
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (_, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    Arc::new(RwLock::new(Ledger::new(
+        utxo_accumulator_model,
+        verifying_context,
+        parameters,
+    )))
+}
+
+/// Builds a new [`SharedNetwork`] in front of `ledger` with `model` and `seed`.
+#[inline]
+fn network(ledger: &SharedLedger, model: NetworkModel, seed: u64) -> SharedNetwork {
+    Arc::new(RwLock::new(Network::new(ledger.clone(), model, seed)))
+}
+
+/// Sends `count`-many block status requests from account `0` through `network`, returning
+/// their errors and the final time of the virtual clock.
+#[inline]
+async fn probe(network: &SharedNetwork, count: usize) -> (Vec<Option<NetworkError>>, Duration) {
+    let mut connection = NetworkConnection::new(account_id_from_u64(0), network.clone());
+    let mut errors = Vec::with_capacity(count);
+    for _ in 0..count {
+        errors.push(connection.block_status().await.err());
+    }
+    let now = network.read().await.now();
+    (errors, now)
+}
+
+/// Checks that the faults of a [`Network`] only depend on its seed and that partitions, timeouts,
+/// lost responses and reordered writes behave as described by the [`NetworkModel`].
+#[tokio::test]
+async fn network_fault_model_test() {
+    let ledger = sample_ledger();
+    let lossy = NetworkModel {
+        latency: LatencyDistribution::Exponential {
+            minimum: Duration::from_millis(10),
+            mean: Duration::from_millis(40),
+        },
+        request_loss: 0.2,
+        response_loss: 0.2,
+        ..Default::default()
+    };
+    let (errors, now) = probe(&network(&ledger, lossy.clone(), 7), 100).await;
+    assert_eq!(
+        (errors.clone(), now),
+        probe(&network(&ledger, lossy, 7), 100).await,
+        "Networks with the same seed should fail in the same way."
+    );
+    assert!(errors.iter().any(Option::is_none));
+    assert!(errors.contains(&Some(NetworkError::RequestLost)));
+    assert!(errors.contains(&Some(NetworkError::ResponseLost)));
+
+    let (alice, bob) = (account_id_from_u64(0), account_id_from_u64(1));
+    let partitioned = network(
+        &ledger,
+        NetworkModel {
+            latency: LatencyDistribution::Constant(Duration::from_millis(100)),
+            timeout: Some(Duration::from_millis(300)),
+            partitions: vec![Partition::new(
+                Duration::ZERO,
+                Duration::from_secs(1),
+                vec![alice],
+            )],
+            ..Default::default()
+        },
+        0,
+    );
+    let mut alice_connection = NetworkConnection::new(alice, partitioned.clone());
+    let mut bob_connection = NetworkConnection::new(bob, partitioned.clone());
+    assert_eq!(
+        alice_connection.block_status().await,
+        Err(NetworkError::Partitioned)
+    );
+    assert!(bob_connection.block_status().await.is_ok());
+    assert_eq!(partitioned.read().await.now(), Duration::from_millis(200));
+    partitioned
+        .write()
+        .await
+        .advance(Duration::from_secs(1))
+        .await;
+    assert!(alice_connection.block_status().await.is_ok());
+    let mut model = partitioned.read().await.model().clone();
+    model.latency = LatencyDistribution::Constant(Duration::from_millis(200));
+    partitioned.write().await.set_model(model);
+    assert_eq!(
+        bob_connection.block_status().await,
+        Err(NetworkError::Timeout)
+    );
+
+    ledger
+        .write()
+        .await
+        .set_block_time(Some(BlockTime::new(Duration::from_secs(6), 0)));
+    let reordering = network(
+        &ledger,
+        NetworkModel {
+            reorder_probability: 1.0,
+            reorder_delay: LatencyDistribution::Constant(Duration::from_secs(2)),
+            ..Default::default()
+        },
+        0,
+    );
+    let mut connection = NetworkConnection::new(alice, reordering.clone());
+    assert_eq!(
+        connection.write(Vec::new()).await,
+        Err(NetworkError::Timeout)
+    );
+    assert_eq!(reordering.read().await.in_flight(), 1);
+    assert_eq!(ledger.read().await.block_status().pending_batches, 0);
+    reordering.write().await.flush().await;
+    assert_eq!(reordering.read().await.in_flight(), 0);
+    assert_eq!(reordering.read().await.now(), Duration::from_secs(2));
+    assert_eq!(ledger.read().await.block_status().pending_batches, 1);
+
+    let unreliable = network(
+        &ledger,
+        NetworkModel {
+            response_loss: 1.0,
+            ..Default::default()
+        },
+        0,
+    );
+    let mut connection = NetworkConnection::new(alice, unreliable.clone());
+    assert_eq!(
+        connection.write(Vec::new()).await,
+        Err(NetworkError::ResponseLost)
+    );
+    assert_eq!(
+        ledger.read().await.block_status().pending_batches,
+        2,
+        "The ledger should keep writes whose responses were lost."
+    );
+    unreliable
+        .write()
+        .await
+        .advance(Duration::from_secs(6))
+        .await;
+    assert_eq!(ledger.read().await.block_status().pending_batches, 0);
+    assert_eq!(unreliable.read().await.stats().responses_lost, 1);
+}
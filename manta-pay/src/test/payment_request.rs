@@ -0,0 +1,106 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Payment Request Testing Suite
+
+use crate::{
+    config::{Address, Asset, AssetValue, Parameters},
+    payment_request::{PaymentRequest, QrPayloadError, MAX_QR_PAYLOAD_LENGTH, QR_PAYLOAD_VERSION},
+};
+use manta_crypto::rand::{OsRng, Rand};
+
+/// Samples a [`PaymentRequest`] for `value`.
+#[inline]
+fn sample_request(value: AssetValue) -> PaymentRequest {
+    let mut rng = OsRng;
+    let parameters = rng.gen::<_, Parameters>();
+    let address: Address = parameters.address_from_spending_key(&rng.gen());
+    PaymentRequest::new(address, Asset::new(rng.gen(), value))
+}
+
+/// Checks that QR payloads round trip and stay within [`MAX_QR_PAYLOAD_LENGTH`].
+#[test]
+fn qr_payload_round_trip() {
+    assert_eq!(MAX_QR_PAYLOAD_LENGTH, 84);
+    for (value, len) in [
+        (0, 66),
+        (127, 66),
+        (128, 67),
+        (1_000_000_000_000, 71),
+        (AssetValue::MAX, MAX_QR_PAYLOAD_LENGTH),
+    ] {
+        let request = sample_request(value);
+        let payload = request.to_qr_payload();
+        assert_eq!(payload[0], QR_PAYLOAD_VERSION);
+        assert_eq!(payload.len(), len, "Unexpected payload length for {value}.");
+        assert_eq!(PaymentRequest::from_qr_payload(&payload), Ok(request));
+    }
+}
+
+/// Checks that malformed QR payloads are rejected.
+#[test]
+fn qr_payload_rejects_invalid_payloads() {
+    let payload = sample_request(300).to_qr_payload();
+    assert_eq!(
+        PaymentRequest::from_qr_payload(&[]),
+        Err(QrPayloadError::UnsupportedVersion(None))
+    );
+    let mut wrong_version = payload.clone();
+    wrong_version[0] = QR_PAYLOAD_VERSION + 1;
+    assert_eq!(
+        PaymentRequest::from_qr_payload(&wrong_version),
+        Err(QrPayloadError::UnsupportedVersion(Some(
+            QR_PAYLOAD_VERSION + 1
+        )))
+    );
+    assert_eq!(
+        PaymentRequest::from_qr_payload(&payload[..20]),
+        Err(QrPayloadError::InvalidAddress)
+    );
+    assert_eq!(
+        PaymentRequest::from_qr_payload(&payload[..40]),
+        Err(QrPayloadError::InvalidAssetId)
+    );
+    let mut invalid_asset_id = payload.clone();
+    invalid_asset_id[33..65].fill(0xff);
+    assert_eq!(
+        PaymentRequest::from_qr_payload(&invalid_asset_id),
+        Err(QrPayloadError::InvalidAssetId)
+    );
+    assert_eq!(
+        PaymentRequest::from_qr_payload(&payload[..payload.len() - 1]),
+        Err(QrPayloadError::InvalidAssetValue)
+    );
+    let mut padded_value = payload[..65].to_vec();
+    padded_value.extend([0x80, 0x00]);
+    assert_eq!(
+        PaymentRequest::from_qr_payload(&padded_value),
+        Err(QrPayloadError::InvalidAssetValue)
+    );
+    let mut overflowing_value = payload[..65].to_vec();
+    overflowing_value.extend([0xff; 18]);
+    overflowing_value.push(0x04);
+    assert_eq!(
+        PaymentRequest::from_qr_payload(&overflowing_value),
+        Err(QrPayloadError::InvalidAssetValue)
+    );
+    let mut trailing = payload;
+    trailing.push(0);
+    assert_eq!(
+        PaymentRequest::from_qr_payload(&trailing),
+        Err(QrPayloadError::TrailingBytes)
+    );
+}
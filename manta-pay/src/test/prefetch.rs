@@ -0,0 +1,94 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Pay Prefetch Planning Testing
+
+use crate::config::{utxo::MerkleTreeConfiguration, AssetId};
+use core::time::Duration;
+use manta_accounting::wallet::{
+    ledger::PoolStats,
+    prefetch::{
+        DeviceThroughput, ItemSizes, LedgerTotals, SyncBudget, SyncCostModel, SyncMode,
+        SyncRequirements,
+    },
+};
+
+/// Builds a [`SyncCostModel`] for a ledger with a million notes and half as many spends, on a
+/// device with the given `bandwidth`.
+#[inline]
+fn sample_model(bandwidth: u64) -> SyncCostModel {
+    let mut stats = PoolStats::<AssetId>::default();
+    stats.record_batch(1_000_000, 500_000, 1);
+    SyncCostModel {
+        totals: LedgerTotals::from(&stats),
+        sizes: ItemSizes {
+            utxo: 81,
+            note: 300,
+            nullifier: 160,
+            leaf: 32,
+            path: 32 * 20,
+        },
+        forest_width: MerkleTreeConfiguration::FOREST_WIDTH as u64,
+        throughput: DeviceThroughput {
+            bandwidth,
+            decryptions: 10_000,
+            insertions: 100_000,
+        },
+    }
+}
+
+/// Checks that the estimates grow with the capability of the modes and that the plan picks the
+/// most capable mode which satisfies the requirements and fits the budget.
+#[test]
+fn prefetch_plan_test() {
+    let model = sample_model(1 << 20);
+    let estimates = SyncMode::ALL.map(|mode| model.estimate(mode, 10));
+    for window in estimates.windows(2) {
+        assert!(window[0].download < window[1].download);
+        assert!(window[0].duration < window[1].duration);
+    }
+    assert_eq!(estimates[1].storage, estimates[2].storage);
+
+    let requirements = SyncRequirements {
+        known_notes: 10,
+        ..Default::default()
+    };
+    let plan = model.plan(&requirements, &SyncBudget::default());
+    assert_eq!(plan.mode, SyncMode::FullPull);
+    assert!(plan.within_budget);
+    assert_eq!(plan.estimates.len(), 3);
+
+    let budget = SyncBudget {
+        duration: Some(Duration::from_secs(80)),
+        ..Default::default()
+    };
+    let plan = model.plan(&requirements, &budget);
+    assert_eq!(plan.mode, SyncMode::FrontierOnly);
+    assert!(plan.within_budget);
+
+    let discovering = SyncRequirements {
+        discover_new_notes: true,
+        ..requirements
+    };
+    let plan = model.plan(&discovering, &budget);
+    assert_eq!(plan.mode, SyncMode::Filters);
+    assert!(!plan.within_budget);
+
+    let plan = sample_model(0).plan(&requirements, &budget);
+    assert_eq!(plan.mode, SyncMode::FrontierOnly);
+    assert!(!plan.within_budget);
+    assert_eq!(plan.estimate.duration, Duration::MAX);
+}
@@ -0,0 +1,202 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! SOCKS5 Proxy Testing Suite
+
+use crate::signer::client::websocket::Client;
+use manta_util::http::{
+    proxy::{Isolation, ProxyConfig},
+    reqwest::KnownUrlClient,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+/// SOCKS5 Connection Request
+///
+/// This is what the [`accept`] proxy observed from a client.
+#[derive(Debug, Eq, PartialEq)]
+struct Request {
+    /// Username and Password
+    credentials: Option<(String, String)>,
+
+    /// Domain Name of the Destination
+    host: String,
+
+    /// Port of the Destination
+    port: u16,
+}
+
+/// Reads a byte string prefixed by its one-byte length from `stream`.
+#[inline]
+async fn read_string(stream: &mut tokio::net::TcpStream) -> String {
+    let len = stream.read_u8().await.expect("Unable to read the length.");
+    let mut bytes = vec![0; len as usize];
+    stream
+        .read_exact(&mut bytes)
+        .await
+        .expect("Unable to read the string.");
+    String::from_utf8(bytes).expect("The string should be valid UTF-8.")
+}
+
+/// Accepts one SOCKS5 connection on `listener`, records its credentials and destination, and
+/// refuses to connect it to the destination.
+#[inline]
+async fn accept(listener: &TcpListener) -> Request {
+    let (mut stream, _) = listener
+        .accept()
+        .await
+        .expect("Unable to accept a connection.");
+    assert_eq!(stream.read_u8().await.unwrap(), 5, "Expected SOCKS5.");
+    let method_count = stream.read_u8().await.unwrap();
+    let mut methods = vec![0; method_count as usize];
+    stream.read_exact(&mut methods).await.unwrap();
+    let credentials = if methods.contains(&0x02) {
+        stream.write_all(&[5, 0x02]).await.unwrap();
+        assert_eq!(stream.read_u8().await.unwrap(), 1);
+        let username = read_string(&mut stream).await;
+        let password = read_string(&mut stream).await;
+        stream.write_all(&[1, 0]).await.unwrap();
+        Some((username, password))
+    } else {
+        stream.write_all(&[5, 0x00]).await.unwrap();
+        None
+    };
+    let mut header = [0; 4];
+    stream.read_exact(&mut header).await.unwrap();
+    assert_eq!(header[..3], [5, 1, 0], "Expected a CONNECT request.");
+    assert_eq!(
+        header[3], 3,
+        "The destination should be sent as a domain name and resolved by the proxy."
+    );
+    let host = read_string(&mut stream).await;
+    let port = stream.read_u16().await.unwrap();
+    stream
+        .write_all(&[5, 5, 0, 1, 0, 0, 0, 0, 0, 0])
+        .await
+        .unwrap();
+    Request {
+        credentials,
+        host,
+        port,
+    }
+}
+
+/// Binds a new local [`TcpListener`] and returns it with a [`ProxyConfig`] pointing to it.
+#[inline]
+async fn local_proxy(isolation: Isolation) -> (TcpListener, ProxyConfig) {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("Unable to bind the proxy.");
+    let address = listener.local_addr().unwrap().to_string();
+    (
+        listener,
+        ProxyConfig::new(address).with_isolation(isolation),
+    )
+}
+
+/// Checks the proxy URL and the credentials chosen by each isolation policy.
+#[test]
+fn proxy_config() {
+    let tor = ProxyConfig::default();
+    assert_eq!(tor, ProxyConfig::tor());
+    assert_eq!(tor.url(), "socks5h://127.0.0.1:9050");
+    assert_eq!(tor.isolation, Isolation::PerConnection);
+    let first = tor
+        .credentials()
+        .expect("Isolated connections authenticate.");
+    let second = tor
+        .credentials()
+        .expect("Isolated connections authenticate.");
+    assert_ne!(
+        first, second,
+        "Every connection should use fresh credentials."
+    );
+    assert_eq!(
+        ProxyConfig::tor()
+            .with_isolation(Isolation::None)
+            .credentials(),
+        None
+    );
+    let token = ProxyConfig::tor().with_isolation(Isolation::Token("alice".into()));
+    assert_eq!(token.credentials(), token.credentials());
+    assert_ne!(
+        token.credentials(),
+        ProxyConfig::tor()
+            .with_isolation(Isolation::Token("bob".into()))
+            .credentials()
+    );
+}
+
+/// Checks that the HTTP client connects through the proxy and lets it resolve onion addresses.
+#[tokio::test]
+async fn http_client_uses_proxy() {
+    let (listener, proxy) = local_proxy(Isolation::Token("wallet".into())).await;
+    let client = KnownUrlClient::with_proxy("http://ledgerexample.onion:8080/", &proxy)
+        .expect("Building the client is not allowed to fail.");
+    let (response, request) = tokio::join!(client.post::<_, ()>("sync", &()), accept(&listener));
+    assert!(response.is_err(), "The proxy refused the connection.");
+    assert_eq!(
+        request,
+        Request {
+            credentials: proxy.credentials(),
+            host: "ledgerexample.onion".into(),
+            port: 8080,
+        }
+    );
+}
+
+/// Checks that the WebSocket client connects through the proxy with fresh credentials for every
+/// connection, and without credentials when isolation is disabled.
+#[tokio::test]
+async fn websocket_client_uses_proxy() {
+    let (listener, proxy) = local_proxy(Isolation::PerConnection).await;
+    let mut usernames = Vec::new();
+    for _ in 0..2 {
+        let (client, request) = tokio::join!(
+            Client::with_proxy("wss://signerexample.onion/", &proxy),
+            accept(&listener)
+        );
+        assert!(client.is_err(), "The proxy refused the connection.");
+        assert_eq!(request.host, "signerexample.onion");
+        assert_eq!(request.port, 443);
+        usernames.push(
+            request
+                .credentials
+                .expect("Isolated connections authenticate.")
+                .0,
+        );
+    }
+    assert_ne!(
+        usernames[0], usernames[1],
+        "Every connection should use fresh credentials."
+    );
+    let (listener, proxy) = local_proxy(Isolation::None).await;
+    let (client, request) = tokio::join!(
+        Client::with_proxy("ws://signerexample.onion:9988/", &proxy),
+        accept(&listener)
+    );
+    assert!(client.is_err(), "The proxy refused the connection.");
+    assert_eq!(
+        request,
+        Request {
+            credentials: None,
+            host: "signerexample.onion".into(),
+            port: 9988,
+        }
+    );
+}
@@ -0,0 +1,97 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sigma Protocol Testing Suite
+
+use crate::config::{
+    DiscreteLogEqualityProtocol, EmbeddedScalar, PedersenOpeningProtocol, SchnorrProtocol,
+    SigmaTranscript,
+};
+use manta_crypto::{
+    algebra::ScalarMul,
+    rand::{OsRng, Rand},
+    sigma::{test::completeness_and_soundness, ProtocolProof},
+    transcript::Transcript,
+};
+
+/// Checks that Schnorr proofs are accepted for their public key only.
+#[test]
+fn schnorr_proof() {
+    let mut rng = OsRng;
+    let protocol = SchnorrProtocol::new(rng.gen());
+    let secret_key: EmbeddedScalar = rng.gen();
+    completeness_and_soundness(
+        &protocol,
+        &SigmaTranscript::new(b"test"),
+        &protocol.generator.scalar_mul(&secret_key, &mut ()),
+        &rng.gen(),
+        &secret_key,
+        &mut rng,
+    );
+}
+
+/// Checks that discrete logarithm equality proofs are rejected when the two points do not share
+/// their discrete logarithm.
+#[test]
+fn discrete_log_equality_proof() {
+    let mut rng = OsRng;
+    let protocol = DiscreteLogEqualityProtocol::new([rng.gen(), rng.gen()]);
+    let secret: EmbeddedScalar = rng.gen();
+    let other_secret: EmbeddedScalar = rng.gen();
+    completeness_and_soundness(
+        &protocol,
+        &SigmaTranscript::new(b"test"),
+        &protocol.bases.map(|base| base.scalar_mul(&secret, &mut ())),
+        &[
+            protocol.bases[0].scalar_mul(&secret, &mut ()),
+            protocol.bases[1].scalar_mul(&other_secret, &mut ()),
+        ],
+        &secret,
+        &mut rng,
+    );
+}
+
+/// Checks that Pedersen opening proofs are accepted for the commitment they open only, and that
+/// they are bound to the transcript they were made with.
+#[test]
+fn pedersen_opening_proof() {
+    let mut rng = OsRng;
+    let protocol = PedersenOpeningProtocol::new([rng.gen(), rng.gen()]);
+    let opening: [EmbeddedScalar; 2] = [rng.gen(), rng.gen()];
+    let commitment = protocol.commitment(&opening[0], &opening[1]);
+    completeness_and_soundness(
+        &protocol,
+        &SigmaTranscript::new(b"test"),
+        &commitment,
+        &protocol.commitment(&rng.gen(), &opening[1]),
+        &opening,
+        &mut rng,
+    );
+    let mut transcript = SigmaTranscript::new(b"test");
+    transcript.append_message(b"context", b"reserve attestation");
+    let proof = ProtocolProof::<PedersenOpeningProtocol>::prove(
+        &protocol,
+        &mut transcript.clone(),
+        &commitment,
+        &opening,
+        &mut rng,
+    );
+    assert!(proof.verify(&protocol, &mut transcript, &commitment));
+    assert!(
+        !proof.verify(&protocol, &mut SigmaTranscript::new(b"test"), &commitment),
+        "The proof should be rejected for a different transcript."
+    );
+}
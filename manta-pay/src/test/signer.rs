@@ -17,22 +17,91 @@
 //! Signer Testing Suite
 
 use crate::{
-    config::{Asset, Config},
-    key::Mnemonic,
-    parameters::load_parameters,
+    config::{
+        utxo::{AssetId, Checkpoint},
+        AccountId, Address, Asset, AssetValue, Config, FullParametersRef, Holdings, IdentityProof,
+        Note, Nullifier, Parameters, Utxo, UtxoAccumulatorOutput,
+    },
+    key::{KeySecret, Mnemonic},
+    parameters::{load_parameters, load_transfer_parameters, load_utxo_accumulator_model},
     signer::{
-        base::identity_verification,
-        functions::{address_from_mnemonic, authorization_context_from_mnemonic},
+        base::{anchored_identity_verification, identity_verification, Signer, Wallet},
+        functions::{
+            accounts_from_mnemonic, address_from_mnemonic, authorization_context_from_mnemonic,
+            new_signer_from_model,
+        },
+        registry::{verify_log, IdentityRegistry, LogDigest, RegistryError},
+        rpc::{self, Command, ErrorCode, SignCommand},
+    },
+    simulation::{
+        ledger::{
+            anchor::{AnchorKey, AnchorVerifier},
+            archive::{ArchiveError, ArchiveWriter, Column, PostArchive},
+            fixtures::{self, WalletSpec},
+            nullifiers::{NullifierSet, NullifierStore},
+            snapshot::SnapshotError,
+            transaction_id, DustPolicy, Ledger, LedgerConnection, MerkleForestIndex,
+            ReceiverLedgerError, RegistrationLimit, SenderLedgerError, StateDiff, TransactionId,
+            TransferLedgerError,
+        },
+        sample_signer,
+    },
+};
+use alloc::{boxed::Box, sync::Arc};
+use core::{ops::Range, time::Duration};
+use manta_accounting::{
+    asset::AssetMap,
+    key::AccountTable,
+    transfer::{
+        canonical::{Transaction, TransferShape},
+        checked::CheckedLedger,
+        diff::BalanceDelta,
+        fee::{FeeSchedule, ShapeFee},
+        ownership::OwnershipChallenge,
+        receiver::{ReceiverLedger, ReceiverPostingKey, RegistrationError},
+        sender::{SenderLedger, SenderPostingKey},
+        upgrade::{ProtocolFeature, UpgradeDescriptor},
+        utxo::{protocol::AddressPartitionFunction, NoteOpenError},
+        IdentifiedAsset, Identifier, IdentityVerificationError, InvalidSinkAccount,
+        InvalidSourceAccount, NotePolicyViolation, SinkPostingKey, SourcePostingKey,
+        TransferLedger, TransferLedgerSuperPostingKey, TransferPostError, TransferPostingKeyRef,
+    },
+    wallet::{
+        clock::ClockSkew,
+        journal::{
+            read_annotations_csv, write_balances_csv, write_entries_csv, Annotation,
+            AnnotationRecord, Counterparty, Encoding, EntryKind, EntryRecord, Journal,
+        },
+        ledger::{BlockHeight, BlockTime, RootHistoryError},
+        operation::{plan_send, OperationStatus},
+        signer::{
+            capability::{AuthorizationError, CapabilityToken, Operation, Scope},
+            functions::item_hash,
+            health::{HealthSettings, ParameterVersions},
+            index::{CompletenessError, IndexRequest, NoteIndexer, RangeSummary},
+            lease::{ConflictPolicy, LeaseConfiguration},
+            privacy::{PrivacyStrategy, SplitEstimate, SplittingStrategy},
+            retention::{PruneSummary, RetentionPolicy, DAY},
+            review::{BalanceChange, PrivacyNote, Recipient},
+            BalanceUpdate, Connection, FrontierSyncRequest, ImportRequest, ImportResponse,
+            ImportStatus, LedgerSnapshotError, SignError, SignRequest, SyncData, SyncEntries,
+            SyncRequest, TransactionDataRequest, MAX_SYNC_DATA_LENGTH,
+        },
+        Error as WalletError,
     },
-    simulation::sample_signer,
 };
-use manta_accounting::transfer::{canonical::Transaction, IdentifiedAsset, Identifier};
 use manta_crypto::{
+    accumulator::Accumulator,
     algebra::HasGenerator,
     arkworks::constraint::fp::Fp,
+    merkle_tree::forest::FixedIndex,
     rand::{fuzz::Fuzz, OsRng, Rand},
 };
-use manta_util::vec::VecExt;
+use manta_util::{
+    codec::{Decode, Encode},
+    vec::VecExt,
+};
+use tokio::sync::RwLock;
 
 /// Checks the generation and verification of [`IdentityProof`](manta_accounting::transfer::IdentityProof)s.
 #[test]
@@ -47,7 +116,7 @@ fn identity_proof_test() {
         &utxo_accumulator_model,
         &mut rng,
     );
-    let identifier = Identifier::<Config>::new(false, rng.gen());
+    let identifier = Identifier::<Config>::new(false, false, rng.gen());
     let virtual_asset = IdentifiedAsset::<Config>::new(identifier, rng.gen());
     let public_account = rng.gen();
     let identity_proof = signer
@@ -74,7 +143,7 @@ fn identity_proof_test() {
             &verifying_context.to_public,
             &utxo_accumulator_model,
             IdentifiedAsset::<Config>::new(
-                Identifier::<Config>::new(true, identifier.utxo_commitment_randomness),
+                Identifier::<Config>::new(true, false, identifier.utxo_commitment_randomness),
                 virtual_asset.asset,
             ),
             address,
@@ -91,6 +160,7 @@ fn identity_proof_test() {
             &utxo_accumulator_model,
             IdentifiedAsset::<Config>::new(
                 Identifier::<Config>::new(
+                    false,
                     false,
                     Fp(identifier.utxo_commitment_randomness.0.fuzz(&mut rng)),
                 ),
@@ -120,6 +190,207 @@ fn identity_proof_test() {
     );
 }
 
+/// Checks that anchored [`IdentityProof`](manta_accounting::transfer::IdentityProof)s are bound
+/// to a signed root of the ledger.
+#[test]
+fn anchored_identity_proof_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let ledger = Ledger::new(
+        utxo_accumulator_model.clone(),
+        verifying_context.clone(),
+        parameters.clone(),
+    );
+    let key = rng.gen::<_, AnchorKey>();
+    let anchor_verifier = AnchorVerifier::new(&key, &ledger);
+    let anchor = ledger.anchor(&key, MerkleForestIndex::from_index(0));
+    let identifier = Identifier::<Config>::new(false, false, rng.gen());
+    let virtual_asset = IdentifiedAsset::<Config>::new(identifier, rng.gen());
+    let public_account = rng.gen();
+    let address = signer.address().expect("Sampled signer has a spending key");
+    let verify = |identity_proof: &IdentityProof| {
+        anchored_identity_verification(
+            identity_proof,
+            &parameters,
+            &verifying_context.to_public,
+            &utxo_accumulator_model,
+            virtual_asset,
+            address,
+            public_account,
+            &anchor_verifier,
+        )
+    };
+    let identity_proof = signer
+        .anchored_identity_proof(virtual_asset, public_account, anchor.clone())
+        .expect("Error producing anchored identity proof");
+    assert_eq!(verify(&identity_proof), Ok(()), "Verification failed");
+    let mut unbound_proof = identity_proof.clone();
+    unbound_proof.anchor = Some(key.sign(rng.gen(), anchor.height));
+    assert_eq!(
+        verify(&unbound_proof),
+        Err(IdentityVerificationError::UnboundAnchor),
+        "Anchors which are not bound to the proof should be rejected."
+    );
+    let mut forged_proof = identity_proof.clone();
+    forged_proof.anchor = Some(rng.gen::<_, AnchorKey>().sign(anchor.root, anchor.height));
+    assert_eq!(
+        verify(&forged_proof),
+        Err(IdentityVerificationError::InvalidAnchorSignature),
+        "Anchors signed with another key should be rejected."
+    );
+    let future_proof = signer
+        .anchored_identity_proof(
+            virtual_asset,
+            public_account,
+            key.sign(anchor.root, anchor.height + 1),
+        )
+        .expect("Error producing anchored identity proof");
+    assert_eq!(
+        verify(&future_proof),
+        Err(IdentityVerificationError::UnacceptedAnchorRoot),
+        "Anchors from heights the ledger has not reached should be rejected."
+    );
+    let unanchored_proof = signer
+        .identity_proof(virtual_asset, public_account)
+        .expect("Error producing identity proof");
+    assert_eq!(
+        verify(&unanchored_proof),
+        Err(IdentityVerificationError::MissingAnchor),
+        "Proofs without anchors should be rejected."
+    );
+}
+
+/// Records [`IdentityProof`](manta_accounting::transfer::IdentityProof)s in an
+/// [`IdentityRegistry`] and checks the queries and the hash chain of the log.
+#[test]
+fn identity_registry_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let address = signer.address().expect("Sampled signer has a spending key");
+    let virtual_asset = IdentifiedAsset::<Config>::new(
+        Identifier::<Config>::new(false, false, rng.gen()),
+        rng.gen(),
+    );
+    let (public_account, other_account) = (rng.gen(), rng.gen());
+    let identity_proof = signer
+        .identity_proof(virtual_asset, public_account)
+        .expect("Error producing identity proof");
+    let mut registry = IdentityRegistry::new();
+    assert_eq!(registry.head(), LogDigest::default());
+    assert!(matches!(
+        registry.record(
+            &identity_proof,
+            &parameters,
+            &verifying_context.to_public,
+            &utxo_accumulator_model,
+            virtual_asset,
+            address,
+            other_account,
+            10,
+        ),
+        Err(RegistryError::Verification(_))
+    ));
+    assert!(
+        registry.is_empty(),
+        "Rejected proofs should not be recorded."
+    );
+    assert_eq!(
+        registry.record(
+            &identity_proof,
+            &parameters,
+            &verifying_context.to_public,
+            &utxo_accumulator_model,
+            virtual_asset,
+            address,
+            public_account,
+            10,
+        ),
+        Ok(0)
+    );
+    assert_eq!(
+        registry.record(
+            &identity_proof,
+            &parameters,
+            &verifying_context.to_public,
+            &utxo_accumulator_model,
+            virtual_asset,
+            address,
+            public_account,
+            20,
+        ),
+        Err(RegistryError::DuplicateProof)
+    );
+    let second_proof = signer
+        .identity_proof(virtual_asset, other_account)
+        .expect("Error producing identity proof");
+    assert_eq!(
+        registry.record(
+            &second_proof,
+            &parameters,
+            &verifying_context.to_public,
+            &utxo_accumulator_model,
+            virtual_asset,
+            address,
+            other_account,
+            5,
+        ),
+        Err(RegistryError::StaleTimestamp { latest: 10 })
+    );
+    assert_eq!(
+        registry.record(
+            &second_proof,
+            &parameters,
+            &verifying_context.to_public,
+            &utxo_accumulator_model,
+            virtual_asset,
+            address,
+            other_account,
+            20,
+        ),
+        Ok(1)
+    );
+    assert_eq!(registry.len(), 2);
+    assert_eq!(registry.by_address(&address).count(), 2);
+    assert_eq!(registry.by_account(&public_account).count(), 1);
+    assert_eq!(registry.between(0, 15).count(), 1);
+    let latest = registry
+        .latest(&address, &other_account)
+        .expect("The second proof was recorded.");
+    assert!(latest.binds(&address, &other_account));
+    assert_eq!(latest.timestamp, 20);
+    assert!(verify_log(registry.attestations(), &registry.head()));
+    assert_eq!(
+        registry.head_at(1),
+        Some(LogDigest::default().append(&registry.attestations()[0]))
+    );
+    assert!(verify_log(
+        &registry.attestations()[..1],
+        &registry.head_at(1).expect("The log has one attestation.")
+    ));
+    let mut tampered = registry.attestations().to_vec();
+    tampered[0].public_account = other_account;
+    assert!(
+        !verify_log(&tampered, &registry.head()),
+        "Tampered logs should not match the head of the registry."
+    );
+}
+
 /// Signs a [`ToPrivate`](manta_accounting::transfer::canonical::ToPrivate) transaction, computes its
 /// [`TransactionData`](manta_accounting::transfer::canonical::TransactionData), and checks its correctness.
 #[test]
@@ -140,7 +411,7 @@ fn transaction_data_test() {
         .expect("Signing a ToPrivate transaction is not allowed to fail.")
         .0
         .take_first();
-    let utxo = response.0.body.receiver_posts.take_first().utxo;
+    let utxo = response.0.body.receiver_posts[0].utxo;
     assert!(
         response.1.check_transaction_data(
             &parameters,
@@ -151,24 +422,3658 @@ fn transaction_data_test() {
     );
 }
 
-/// Checks that both methods to derive a receiving key from a [`Mnemonic`] give
-/// the same result.
+/// Synchronizes `signer` with all the data in `ledger`.
+fn sync_with_ledger(signer: &mut Signer, ledger: &Ledger) {
+    let origin_checkpoint = Default::default();
+    signer
+        .sync(SyncRequest {
+            data: ledger.pull(&origin_checkpoint).data,
+            origin_checkpoint,
+        })
+        .expect("Synchronizing with the ledger is not allowed to fail.");
+}
+
+/// Checks that the signer precomputes membership proofs for its largest assets when it
+/// synchronizes, that transactions spending those assets are signed with the cached proofs, and
+/// that the other transactions fall back to the accumulator.
 #[test]
-pub fn derive_address_works() {
+fn precomputed_membership_proofs_test() {
     let mut rng = OsRng;
     let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
-    let (_, _, parameters, _) =
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
         load_parameters(directory.path()).expect("Failed to load parameters");
-    let mnemonic = Mnemonic::sample(&mut rng);
-    let receiving_key_1 = address_from_mnemonic(mnemonic.clone(), &parameters).receiving_key;
-    let receiving_key_2 = *authorization_context_from_mnemonic(mnemonic, &parameters)
-        .receiving_key(
-            parameters.base.group_generator.generator(),
-            &parameters.base.viewing_key_derivation_function,
-            &mut (),
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    signer.precompute_membership_proofs(2);
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    for value in [100, 200, 300] {
+        let posts = signer
+            .sign(Transaction::ToPrivate(Asset::new(id, value)))
+            .expect("Signing a ToPrivate transaction is not allowed to fail.")
+            .posts;
+        assert!(
+            ledger.push(account, posts),
+            "Invalid ToPrivate transaction."
+        );
+    }
+    sync_with_ledger(&mut signer, &ledger);
+    assert_eq!(signer.state().membership_proofs().len(), 2);
+    let posts = signer
+        .sign(Transaction::ToPublic(Asset::new(id, 250), account))
+        .expect("Signing a ToPublic transaction is not allowed to fail.")
+        .posts;
+    assert_eq!(posts.len(), 1, "The cached proof should be used directly.");
+    assert_eq!(signer.state().membership_proofs().len(), 1);
+    assert!(
+        ledger.push(account, posts),
+        "Invalid cached ToPublic transaction."
+    );
+    sync_with_ledger(&mut signer, &ledger);
+    assert_eq!(signer.state().membership_proofs().len(), 2);
+    let posts = signer
+        .sign(Transaction::ToPublic(Asset::new(id, 300), account))
+        .expect("Signing a ToPublic transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        posts.len() > 1,
+        "Three assets cannot be spent in a single transfer."
+    );
+    assert_eq!(signer.state().membership_proofs().len(), 2);
+    assert!(
+        ledger.push(account, posts),
+        "Invalid batched ToPublic transaction."
+    );
+}
+
+/// Checks that the signer reports the value received since its last backup and raises the flags
+/// of its [`SignerHealth`](manta_accounting::wallet::signer::health::SignerHealth) reports
+/// according to its [`HealthSettings`].
+#[test]
+fn signer_health_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    signer.set_health_settings(HealthSettings {
+        backup_interval: Some(1000),
+        unbacked_value_limit: Some(150),
+        sync_interval: Some(100),
+        minimum_parameter_versions: Some(ParameterVersions::new(1, 0)),
+    });
+    let health = signer.health(0);
+    assert!(
+        health.backup_due,
+        "Signers which were never backed up should be backed up."
+    );
+    assert!(
+        health.accumulator_stale,
+        "Signers which never synchronized should be stale."
+    );
+    assert!(health.parameters_outdated);
+    assert!(!health.membership_proofs_stale);
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let mut receive = |signer: &mut Signer, value, now| {
+        let posts = signer
+            .sign(Transaction::ToPrivate(Asset::new(id, value)))
+            .expect("Signing a ToPrivate transaction is not allowed to fail.")
+            .posts;
+        assert!(
+            ledger.push(account, posts),
+            "Invalid ToPrivate transaction."
         );
+        let origin_checkpoint = signer.health(now).checkpoint;
+        signer
+            .sync_at(
+                SyncRequest {
+                    data: ledger.pull(&origin_checkpoint).data,
+                    origin_checkpoint,
+                },
+                now,
+            )
+            .expect("Synchronizing with the ledger is not allowed to fail.");
+    };
+    receive(&mut signer, 100, 10);
+    let health = signer.health(20);
+    assert_eq!(health.last_sync, Some(10));
+    assert_eq!(health.unbacked_value, vec![Asset::new(id, 100)]);
+    assert!(!health.accumulator_stale);
+    signer.record_backup(20);
+    let health = signer.health(30);
+    assert_eq!(health.last_backup, Some(20));
+    assert!(health.unbacked_value.is_empty());
+    assert!(!health.backup_due);
+    receive(&mut signer, 200, 40);
+    let health = signer.health(50);
+    assert_eq!(health.unbacked_value, vec![Asset::new(id, 200)]);
+    assert!(
+        health.backup_due,
+        "Receiving more than the unbacked value limit should require a backup."
+    );
+    signer.record_backup(60);
+    assert!(!signer.health(1060).backup_due);
+    assert!(signer.health(1061).backup_due);
+    assert!(signer.health(141).accumulator_stale);
+}
+
+/// Checks that the signer accepts the posts that the ledger accepts, and that it reports posts
+/// built against an unknown ledger state, spending a pending asset, repeating a nullifier, or
+/// spending a spent asset.
+#[test]
+fn ledger_snapshot_check_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut unsynced_signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let posts = signer
+        .sign_and_check(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.push(account, posts),
+        "Invalid ToPrivate transaction."
+    );
+    sync_with_ledger(&mut signer, &ledger);
+    let posts = signer
+        .sign_and_check(Transaction::ToPublic(Asset::new(id, 50), account))
+        .expect("Signing a ToPublic transaction against a synchronized signer should succeed.")
+        .posts;
     assert_eq!(
-        receiving_key_1, receiving_key_2,
-        "Both receiving keys should be the same"
+        unsynced_signer.check_posts(&posts),
+        Err(LedgerSnapshotError::UnknownUtxoAccumulatorOutput { post: 0, sender: 0 })
+    );
+    assert_eq!(
+        signer.check_posts(&posts),
+        Err(LedgerSnapshotError::AssetPending { post: 0, sender: 0 })
+    );
+    signer.clear_pending_spends();
+    assert_eq!(
+        signer.check_posts(&[posts[0].clone(), posts[0].clone()]),
+        Err(LedgerSnapshotError::DuplicateNullifier { post: 1, sender: 0 })
+    );
+    assert!(
+        ledger.push(account, posts.clone()),
+        "Invalid ToPublic transaction."
+    );
+    sync_with_ledger(&mut signer, &ledger);
+    assert_eq!(
+        signer.check_posts(&posts),
+        Err(LedgerSnapshotError::AssetSpent { post: 0, sender: 0 })
+    );
+}
+
+/// Checks that a [`ToPublic`](Transaction::ToPublic) withdrawal into a frozen account is rejected
+/// without spending its notes, and that the signer can spend them again once it releases the
+/// rejected spends.
+#[test]
+fn rejected_to_public_refund_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let posts = signer
+        .sign_and_check(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.push(account, posts),
+        "Invalid ToPrivate transaction."
+    );
+    sync_with_ledger(&mut signer, &ledger);
+    ledger.freeze_account(account);
+    let checkpoint = ledger.checkpoint();
+    let balances = ledger.public_balances(account);
+    let posts = signer
+        .sign_and_check(Transaction::ToPublic(Asset::new(id, 50), account))
+        .expect("Signing a ToPublic transaction against a synchronized signer should succeed.")
+        .posts;
+    assert!(
+        !ledger.push(account, posts.clone()),
+        "Withdrawals into a frozen account should be rejected."
+    );
+    assert_eq!(
+        ledger.checkpoint(),
+        checkpoint,
+        "Rejected withdrawals should not spend any note."
+    );
+    assert_eq!(ledger.public_balances(account), balances);
+    sync_with_ledger(&mut signer, &ledger);
+    assert_eq!(
+        signer.check_posts(&posts),
+        Err(LedgerSnapshotError::AssetPending { post: 0, sender: 0 })
+    );
+    let senders = posts
+        .iter()
+        .map(|post| post.body.sender_posts.len())
+        .sum::<usize>();
+    assert_eq!(signer.release_rejected_spends(&posts), senders);
+    assert_eq!(signer.check_posts(&posts), Ok(()));
+    assert!(ledger.unfreeze_account(&account));
+    assert!(
+        ledger.push(account, posts.clone()),
+        "The released notes should be spendable again."
+    );
+    sync_with_ledger(&mut signer, &ledger);
+    assert_eq!(
+        signer.release_rejected_spends(&posts),
+        0,
+        "Spends which reached the ledger should not be released."
+    );
+}
+
+/// Ledger which decorates the simulation [`Ledger`] and rejects every spend, so that posting a
+/// transfer fails after its public balances were updated.
+struct SpendRejectingLedger(Ledger);
+
+impl SenderLedger<Parameters> for SpendRejectingLedger {
+    type ValidUtxoAccumulatorOutput =
+        <Ledger as SenderLedger<Parameters>>::ValidUtxoAccumulatorOutput;
+    type ValidNullifier = <Ledger as SenderLedger<Parameters>>::ValidNullifier;
+    type SuperPostingKey = <Ledger as SenderLedger<Parameters>>::SuperPostingKey;
+    type Error = SenderLedgerError;
+
+    #[inline]
+    fn is_unspent(&self, nullifier: Nullifier) -> Result<Self::ValidNullifier, Self::Error> {
+        self.0.is_unspent(nullifier)
+    }
+
+    #[inline]
+    fn has_matching_utxo_accumulator_output(
+        &self,
+        output: UtxoAccumulatorOutput,
+    ) -> Result<Self::ValidUtxoAccumulatorOutput, Self::Error> {
+        self.0.has_matching_utxo_accumulator_output(output)
+    }
+
+    #[inline]
+    fn spend(
+        &mut self,
+        super_key: &Self::SuperPostingKey,
+        utxo_accumulator_output: Self::ValidUtxoAccumulatorOutput,
+        nullifier: Self::ValidNullifier,
+    ) -> Result<(), Self::Error> {
+        let _ = (super_key, utxo_accumulator_output, nullifier);
+        Err(SenderLedgerError::UnexpectedError)
+    }
+}
+
+impl ReceiverLedger<Parameters> for SpendRejectingLedger {
+    type ValidUtxo = <Ledger as ReceiverLedger<Parameters>>::ValidUtxo;
+    type SuperPostingKey = <Ledger as ReceiverLedger<Parameters>>::SuperPostingKey;
+    type Error = ReceiverLedgerError;
+
+    #[inline]
+    fn is_not_registered(&self, utxo: Utxo) -> Result<Self::ValidUtxo, Self::Error> {
+        self.0.is_not_registered(utxo)
+    }
+
+    #[inline]
+    fn register_all<I>(
+        &mut self,
+        super_key: &Self::SuperPostingKey,
+        iter: I,
+    ) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = (Self::ValidUtxo, Note)>,
+    {
+        self.0.register_all(super_key, iter)
+    }
+}
+
+impl TransferLedger<Config> for SpendRejectingLedger {
+    type SuperPostingKey = ();
+    type Event = ();
+    type ValidSourceAccount = SourcePostingKey<Config, Ledger>;
+    type ValidSinkAccount = SinkPostingKey<Config, Ledger>;
+    type ValidProof = <Ledger as TransferLedger<Config>>::ValidProof;
+    type Error = TransferLedgerError;
+
+    #[inline]
+    fn check_source_accounts<I>(
+        &self,
+        asset_id: &AssetId,
+        sources: I,
+    ) -> Result<Vec<Self::ValidSourceAccount>, InvalidSourceAccount<Config, AccountId>>
+    where
+        I: Iterator<Item = (AccountId, AssetValue)>,
+    {
+        self.0.check_source_accounts(asset_id, sources)
+    }
+
+    #[inline]
+    fn check_sink_accounts<I>(
+        &self,
+        asset_id: &AssetId,
+        sinks: I,
+    ) -> Result<Vec<Self::ValidSinkAccount>, InvalidSinkAccount<Config, AccountId>>
+    where
+        I: Iterator<Item = (AccountId, AssetValue)>,
+    {
+        self.0.check_sink_accounts(asset_id, sinks)
+    }
+
+    #[inline]
+    fn is_valid(
+        &self,
+        posting_key: TransferPostingKeyRef<Config, Self>,
+    ) -> Result<(Self::ValidProof, Self::Event), TransferLedgerError> {
+        let senders = posting_key
+            .senders
+            .iter()
+            .cloned()
+            .map(SenderPostingKey::into_ledger)
+            .collect::<Vec<_>>();
+        let receivers = posting_key
+            .receivers
+            .iter()
+            .cloned()
+            .map(ReceiverPostingKey::into_ledger)
+            .collect::<Vec<_>>();
+        self.0.is_valid(TransferPostingKeyRef {
+            epoch: posting_key.epoch,
+            authorization_key: posting_key.authorization_key,
+            asset_id: posting_key.asset_id,
+            sources: posting_key.sources,
+            senders: &senders,
+            receivers: &receivers,
+            sinks: posting_key.sinks,
+            fee: posting_key.fee,
+            proof: posting_key.proof,
+        })
+    }
+
+    #[inline]
+    fn update_public_balances(
+        &mut self,
+        super_key: &TransferLedgerSuperPostingKey<Config, Self>,
+        asset_id: AssetId,
+        sources: Vec<SourcePostingKey<Config, Self>>,
+        sinks: Vec<SinkPostingKey<Config, Self>>,
+        proof: Self::ValidProof,
+    ) -> Result<Vec<BalanceDelta<Config>>, TransferLedgerError> {
+        self.0
+            .update_public_balances(super_key, asset_id, sources, sinks, proof)
+    }
+
+    #[inline]
+    fn rollback_public_balances<I>(
+        &mut self,
+        super_key: &TransferLedgerSuperPostingKey<Config, Self>,
+        rollback: I,
+    ) where
+        I: IntoIterator<Item = BalanceDelta<Config>>,
+    {
+        self.0.rollback_public_balances(super_key, rollback)
+    }
+}
+
+/// Checks that the public balance changes of a post are rolled back when its senders cannot be
+/// spent.
+#[test]
+fn rejected_spend_rollback_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model,
+        verifying_context,
+        parameters.clone(),
+    );
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.push(account, posts),
+        "Invalid ToPrivate transaction."
+    );
+    sync_with_ledger(&mut signer, &ledger);
+    let balances = ledger.public_balances(account);
+    let checkpoint = ledger.checkpoint();
+    let mut ledger = SpendRejectingLedger(ledger);
+    for post in signer
+        .sign(Transaction::ToPublic(Asset::new(id, 50), account))
+        .expect("Signing a ToPublic transaction is not allowed to fail.")
+        .posts
+    {
+        let sinks = match TransferShape::from_post(&post) {
+            Some(TransferShape::ToPublic) => vec![account],
+            _ => vec![],
+        };
+        let posting_key = post
+            .validate(&parameters, &ledger, vec![], sinks)
+            .expect("Posts signed by the signer should be valid.");
+        assert!(
+            posting_key.post(&mut ledger, &()).is_err(),
+            "The ledger rejects every spend."
+        );
+    }
+    assert_eq!(
+        ledger.0.public_balances(account),
+        balances,
+        "The deposits into the sink accounts should be rolled back."
+    );
+    assert_eq!(ledger.0.checkpoint(), checkpoint);
+}
+
+/// Checks that the ledger routes posts to the verifying context of their epoch, rejects unknown
+/// epochs, and keeps accepting a retired epoch until the end of its grace window.
+#[test]
+fn ledger_epoch_rotation_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model,
+        verifying_context.clone(),
+        parameters.clone(),
+    );
+    ledger.set_epoch_grace_window(1);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let mut sign = |value, epoch| {
+        signer
+            .sign(Transaction::ToPrivate(Asset::new(id, value)))
+            .expect("Signing a ToPrivate transaction is not allowed to fail.")
+            .posts
+            .into_iter()
+            .map(|post| post.with_epoch(epoch))
+            .collect::<Vec<_>>()
+    };
+    assert!(
+        !ledger.push(account, sign(100, 1)),
+        "Posts tagged with an unknown epoch should be rejected."
+    );
+    ledger.activate_epoch(1, verifying_context, parameters);
+    assert_eq!(ledger.latest_epoch(), Some(1));
+    assert!(
+        ledger.retire_epoch(0),
+        "The initial epoch should be active."
+    );
+    assert!(
+        ledger.push(account, sign(100, 0)),
+        "Posts tagged with a retired epoch should be accepted during its grace window."
+    );
+    assert!(
+        !ledger.push(account, sign(100, 0)),
+        "Posts tagged with a retired epoch should be rejected after its grace window."
+    );
+    assert!(
+        ledger.push(account, sign(100, 1)),
+        "Posts tagged with the latest epoch should be accepted."
+    );
+}
+
+/// Checks that the ledger accounts for the notes, spends and root updates of every batch in its
+/// pool statistics.
+#[test]
+fn pool_stats_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    for _ in 0..2 {
+        let posts = signer
+            .sign(Transaction::ToPrivate(Asset::new(id, 100)))
+            .expect("Signing a ToPrivate transaction is not allowed to fail.")
+            .posts;
+        assert!(
+            ledger.push(account, posts),
+            "Invalid ToPrivate transaction."
+        );
+    }
+    let stats = ledger.pool_stats();
+    assert_eq!(stats.total_notes, 2);
+    assert_eq!(stats.total_spends, 0);
+    assert_eq!(stats.notes(&id), 2);
+    assert_eq!(stats.unattributed_notes(), 0);
+    assert_eq!(stats.spend_frequency, vec![2]);
+    assert_eq!(stats.root_updates, 2);
+    sync_with_ledger(&mut signer, &ledger);
+    let posts = signer
+        .sign(Transaction::ToPublic(Asset::new(id, 150), account))
+        .expect("Signing a ToPublic transaction is not allowed to fail.")
+        .posts;
+    let spends = posts
+        .iter()
+        .map(|post| post.body.sender_posts.len() as u64)
+        .sum::<u64>();
+    let notes = posts
+        .iter()
+        .map(|post| post.body.receiver_posts.len() as u64)
+        .sum::<u64>();
+    assert!(
+        ledger.push_with_diff(account, posts).is_some(),
+        "Invalid ToPublic transaction."
+    );
+    let stats = ledger.pool_stats();
+    assert_eq!(stats.batches, 3);
+    assert_eq!(stats.total_notes, 2 + notes);
+    assert_eq!(stats.total_spends, spends);
+    assert_eq!(stats.spend_frequency[spends as usize], 1);
+    assert_eq!(stats.notes(&id), 2 + notes);
+    assert_eq!(stats.unattributed_notes(), 0);
+}
+
+/// Checks that the ledger returns the roots of the UTXO forest at the checkpoints kept in its root
+/// history, even after it moved past them, and that it reports the checkpoints out of its bounds.
+#[test]
+fn root_history_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let mut history = vec![];
+    for _ in 0..3 {
+        let checkpoint = ledger.checkpoint().checkpoint;
+        let roots = ledger
+            .root_at(&checkpoint)
+            .expect("The current checkpoint is always in the root history.");
+        history.push((checkpoint, roots));
+        let posts = signer
+            .sign(Transaction::ToPrivate(Asset::new(id, 100)))
+            .expect("Signing a ToPrivate transaction is not allowed to fail.")
+            .posts;
+        assert!(
+            ledger.push(account, posts),
+            "Invalid ToPrivate transaction."
+        );
+    }
+    for (checkpoint, roots) in &history {
+        assert_eq!(ledger.root_at(checkpoint).as_ref(), Ok(roots));
+    }
+    let checkpoint = ledger.checkpoint().checkpoint;
+    let roots = ledger
+        .root_at(&checkpoint)
+        .expect("The current checkpoint is always in the root history.");
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    let diff = ledger
+        .push_with_diff(account, posts)
+        .expect("Invalid ToPrivate transaction.");
+    let next_checkpoint = ledger.checkpoint().checkpoint;
+    assert!(ledger.root_at(&next_checkpoint).is_ok());
+    assert!(
+        ledger.revert(&diff),
+        "Reverting the latest batch should succeed."
+    );
+    assert_eq!(
+        ledger.root_at(&next_checkpoint),
+        Err(RootHistoryError::Future)
+    );
+    assert_eq!(ledger.root_at(&checkpoint), Ok(roots));
+    ledger.set_root_history_capacity(1);
+    assert_eq!(ledger.root_at(&checkpoint), Ok(roots));
+    assert_eq!(ledger.root_at(&history[0].0), Err(RootHistoryError::Pruned));
+}
+
+/// Checks that the nullifiers pulled from the ledger between two checkpoints match the nullifier
+/// set commitments of the checkpoints, and that reverting a batch restores the commitment.
+#[test]
+fn nullifier_set_commitment_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.push(account, posts),
+        "Invalid ToPrivate transaction."
+    );
+    sync_with_ledger(&mut signer, &ledger);
+    let origin = ledger.checkpoint();
+    let posts = signer
+        .sign(Transaction::ToPublic(Asset::new(id, 50), account))
+        .expect("Signing a ToPublic transaction is not allowed to fail.")
+        .posts;
+    let diff = ledger
+        .push_with_diff(account, posts)
+        .expect("Invalid ToPublic transaction.");
+    let next = ledger.checkpoint();
+    let mut nullifiers = ledger.pull(&origin.checkpoint).data.nullifier_data;
+    assert!(!nullifiers.is_empty(), "The transfer should spend a note.");
+    assert!(origin.verify_nullifiers(&nullifiers, &next));
+    assert_eq!(
+        ledger.nullifier_commitment(next.checkpoint.sender_index),
+        Some(next.nullifier_commitment)
+    );
+    nullifiers.pop();
+    assert!(!origin.verify_nullifiers(&nullifiers, &next));
+    assert!(
+        ledger.revert(&diff),
+        "Reverting the latest batch should succeed."
+    );
+    assert_eq!(ledger.checkpoint(), origin);
+}
+
+/// Checks that notes imported out-of-band are recovered when their UTXO is synchronized even if
+/// their on-chain note cannot be decrypted, that they become spendable, and that repeated imports
+/// are reported as duplicates.
+#[test]
+fn import_notes_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut other_signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let (post, transaction_data) = signer
+        .sign_with_transaction_data(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .0
+        .take_first();
+    let other_posts = other_signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.push(account, vec![post]),
+        "Invalid ToPrivate transaction."
+    );
+    assert!(
+        ledger.push(account, other_posts),
+        "Invalid ToPrivate transaction."
+    );
+    let (identifier, asset) = transaction_data.open().take_first();
+    let note = IdentifiedAsset::<Config>::new(identifier, asset);
+    let unknown_note = IdentifiedAsset::<Config>::new(rng.gen(), Asset::new(id, 100));
+    assert_eq!(
+        signer
+            .import_notes(ImportRequest(vec![note, note, unknown_note]))
+            .expect("Importing notes is not allowed to fail."),
+        ImportResponse(vec![
+            ImportStatus::Pending,
+            ImportStatus::Duplicate,
+            ImportStatus::Pending
+        ])
+    );
+    let origin_checkpoint = Default::default();
+    let mut data = ledger.pull(&origin_checkpoint).data;
+    assert_eq!(data.utxo_note_data.len(), 2);
+    let (lhs, rhs) = data.utxo_note_data.split_at_mut(1);
+    core::mem::swap(&mut lhs[0].1, &mut rhs[0].1);
+    signer
+        .sync(SyncRequest {
+            data,
+            origin_checkpoint,
+        })
+        .expect("Synchronizing with the ledger is not allowed to fail.");
+    assert_eq!(signer.state().pending_imports(), &[unknown_note]);
+    assert_eq!(
+        signer
+            .import_notes(ImportRequest(vec![note]))
+            .expect("Importing notes is not allowed to fail."),
+        ImportResponse(vec![ImportStatus::Duplicate])
+    );
+    let posts = signer
+        .sign(Transaction::ToPublic(Asset::new(id, 100), account))
+        .expect("Signing a ToPublic transaction for an imported note should succeed.")
+        .posts;
+    assert!(ledger.push(account, posts), "Invalid ToPublic transaction.");
+}
+
+/// Checks that the ledger rejects posts creating notes below the minimum value or more notes
+/// than allowed by its [`DustPolicy`].
+#[test]
+fn note_policy_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model,
+        verifying_context,
+        parameters.clone(),
+    );
+    ledger.set_note_policy(Some(DustPolicy {
+        minimum_value: Some(100),
+        maximum_receivers: Some(1),
+    }));
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let dust_post = signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 10)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts
+        .take_first();
+    assert_eq!(
+        dust_post
+            .validate(&parameters, &ledger, vec![account], vec![])
+            .err(),
+        Some(TransferPostError::NotePolicy(
+            NotePolicyViolation::ValueBelowMinimum {
+                asset_id: id,
+                value: 10,
+                minimum: 100,
+            }
+        ))
+    );
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 500)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.push(account, posts),
+        "ToPrivate transactions above the minimum value should be accepted."
+    );
+    sync_with_ledger(&mut signer, &ledger);
+    let address = signer
+        .address()
+        .expect("The signer should have a default address.");
+    let post = signer
+        .sign(Transaction::PrivateTransfer(Asset::new(id, 200), address))
+        .expect("Signing a PrivateTransfer transaction is not allowed to fail.")
+        .posts
+        .take_first();
+    assert_eq!(
+        post.validate(&parameters, &ledger, vec![], vec![]).err(),
+        Some(TransferPostError::NotePolicy(
+            NotePolicyViolation::TooManyReceivers {
+                receivers: 2,
+                maximum: 1,
+            }
+        ))
+    );
+    ledger.set_note_policy(None);
+    let posts = signer
+        .sign(Transaction::PrivateTransfer(Asset::new(id, 200), address))
+        .expect("Signing a PrivateTransfer transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.push(account, posts),
+        "PrivateTransfer transactions should be accepted without a note policy."
+    );
+}
+
+/// Checks that the ledger stops accounts from registering more notes than allowed by its
+/// [`RegistrationLimit`] until their earlier registrations leave the window.
+#[test]
+fn registration_limit_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let limit = RegistrationLimit {
+        maximum_notes: 2,
+        window: 3,
+    };
+    ledger.set_registration_limit(Some(limit));
+    let (spammer, other) = (rng.gen(), rng.gen());
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(spammer, id, 1000);
+    ledger.set_public_balance(other, id, 1000);
+    let mut to_private = || {
+        signer
+            .sign(Transaction::ToPrivate(Asset::new(id, 10)))
+            .expect("Signing a ToPrivate transaction is not allowed to fail.")
+            .posts
+    };
+    assert!(ledger.push(spammer, to_private()));
+    assert!(ledger.push_with_diff(spammer, to_private()).is_some());
+    assert_eq!(ledger.registered_notes(&spammer), 2);
+    assert!(
+        !ledger.push(spammer, to_private()),
+        "Posts above the registration limit should be rejected."
+    );
+    assert!(ledger.push_with_diff(spammer, to_private()).is_none());
+    assert_eq!(
+        limit.check(ledger.registered_notes(&spammer), 1),
+        Err(RegistrationError::RateLimited {
+            requested: 1,
+            remaining: 0,
+        })
+    );
+    assert!(
+        ledger.push(other, to_private()),
+        "The registration limit should only apply to the account which reached it."
+    );
+    assert_eq!(ledger.registered_notes(&spammer), 1);
+    assert!(
+        ledger.push(spammer, to_private()),
+        "Registrations which left the window should not count towards the limit."
+    );
+    ledger.set_registration_limit(None);
+    assert!(ledger.push(spammer, to_private()));
+}
+
+/// Legacy Note
+///
+/// [`Utxo`] and [`Note`] of an asset with id `7` and value `1000` sent to the first account of the
+/// `abandon ... about` mnemonic, encrypted before the incoming plaintext layout was versioned. See
+/// [`INCOMING_PLAINTEXT_VERSION`](crate::config::utxo::INCOMING_PLAINTEXT_VERSION) for more.
+const LEGACY_NOTE: &[u8] = include_bytes!("legacy_note.bin");
+
+/// Checks that a signer synchronizes a note which was encrypted before the incoming plaintext
+/// layout was versioned, and that the encoding of the note did not change.
+#[test]
+fn legacy_note_sync_test() {
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, _, _, _) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut reader = LEGACY_NOTE;
+    let utxo = Utxo::decode(&mut reader).expect("Decoding the legacy UTXO is not allowed to fail.");
+    let note = Note::decode(&mut reader).expect("Decoding the legacy note is not allowed to fail.");
+    assert!(
+        reader.is_empty(),
+        "The legacy note should be read completely."
+    );
+    assert_eq!(
+        [utxo.to_vec(), note.to_vec()].concat(),
+        LEGACY_NOTE,
+        "The encoding of notes should not change."
+    );
+    let mut signer = new_signer_from_model(
+        load_transfer_parameters(),
+        proving_context,
+        &load_utxo_accumulator_model(),
+    );
+    signer.load_accounts(accounts_from_mnemonic(
+        Mnemonic::new(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        )
+        .expect("The mnemonic is valid."),
+    ));
+    signer
+        .sync(SyncRequest {
+            origin_checkpoint: Default::default(),
+            data: SyncData {
+                utxo_note_data: SyncEntries::try_from_vec(vec![(utxo, note)])
+                    .expect("One note is within the synchronization data bound."),
+                nullifier_data: Default::default(),
+            },
+        })
+        .expect("Synchronizing the legacy note is not allowed to fail.");
+    assert_eq!(
+        signer
+            .state()
+            .assets()
+            .entries()
+            .into_iter()
+            .map(|(_, asset)| asset)
+            .collect::<Vec<_>>(),
+        vec![Asset::new(AssetId::from(7u128), 1000)],
+        "The legacy note should be recovered."
+    );
+}
+
+/// Checks that the ledger rejects the nullifiers of legacy notes outside of a nullifier
+/// migration, even when a legacy nullifier deadline is set.
+#[test]
+fn legacy_nullifier_test() {
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (_, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let nullifier = Nullifier::default();
+    let mut legacy_nullifier = nullifier;
+    legacy_nullifier.nullifier.is_legacy = true;
+    assert!(!ledger.accepts_legacy_nullifiers());
+    assert_eq!(
+        ledger.is_unspent(legacy_nullifier).err(),
+        Some(SenderLedgerError::LegacyNullifier),
+    );
+    ledger.set_legacy_nullifier_deadline(Some(ledger.height() + 1));
+    assert_eq!(
+        ledger.is_unspent(legacy_nullifier).err(),
+        Some(SenderLedgerError::LegacyNullifier),
+        "Legacy nullifiers should only be accepted during a nullifier migration."
+    );
+    assert!(ledger.is_unspent(nullifier).is_ok());
+}
+
+/// Nullifier Store which records the nullifiers it receives.
+#[derive(Clone, Default)]
+struct RecordingStore(Arc<std::sync::Mutex<Vec<Nullifier>>>);
+
+impl NullifierStore for RecordingStore {
+    #[inline]
+    fn insert(&mut self, index: usize, nullifier: &Nullifier) {
+        let mut nullifiers = self.0.lock().expect("The lock is never poisoned.");
+        assert_eq!(
+            index,
+            nullifiers.len(),
+            "Nullifiers should be stored in order."
+        );
+        nullifiers.push(*nullifier);
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        self.0
+            .lock()
+            .expect("The lock is never poisoned.")
+            .truncate(len);
+    }
+}
+
+/// Checks that the sharded [`NullifierSet`] answers batch lookups like single lookups, rejects
+/// nullifiers which are already spent, and keeps its [`NullifierStore`] in sync.
+#[test]
+fn nullifier_set_test() {
+    let mut rng = OsRng;
+    let nullifiers = (0..64)
+        .map(|_| {
+            let mut nullifier = Nullifier::default();
+            nullifier.nullifier.commitment = rng.gen();
+            nullifier
+        })
+        .collect::<Vec<_>>();
+    let store = RecordingStore::default();
+    let mut set = NullifierSet::new(4);
+    set.set_store(Some(Box::new(store.clone())));
+    for nullifier in &nullifiers[..32] {
+        assert!(
+            set.insert(*nullifier),
+            "Unspent nullifiers should be inserted."
+        );
+    }
+    assert!(
+        !set.insert(nullifiers[0]),
+        "Spent nullifiers should not be inserted twice."
+    );
+    assert_eq!(
+        set.contains_many(&nullifiers),
+        nullifiers
+            .iter()
+            .map(|nullifier| set.contains(nullifier))
+            .collect::<Vec<_>>(),
+        "Batch lookups should agree with single lookups."
+    );
+    assert_eq!(
+        set.contains_many(&nullifiers)
+            .iter()
+            .filter(|x| **x)
+            .count(),
+        32
+    );
+    assert_eq!(set.pop(), Some(nullifiers[31]));
+    assert!(!set.contains(&nullifiers[31]));
+    let stored = store.0.lock().expect("The lock is never poisoned.").clone();
+    assert_eq!(
+        stored,
+        &nullifiers[..31],
+        "The store should mirror the set."
+    );
+    let restored = NullifierSet::restore(NullifierSet::DEFAULT_SHARDS, stored);
+    assert!(
+        restored.iter().eq(set.iter()),
+        "Restored sets should keep the insertion order."
+    );
+    assert_eq!(
+        restored.contains_many(&nullifiers),
+        set.contains_many(&nullifiers),
+        "Lookups should not depend on the number of shards."
+    );
+}
+
+/// Checks that [`Ledger::pull`] truncates its responses at [`MAX_SYNC_DATA_LENGTH`] and asks for
+/// the rest with `should_continue`.
+#[test]
+fn ledger_pull_paging_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (_, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let mut nullifiers = (0..=MAX_SYNC_DATA_LENGTH).map(|_| {
+        let mut nullifier = Nullifier::default();
+        nullifier.nullifier.commitment = rng.gen();
+        nullifier
+    });
+    loop {
+        let chunk = nullifiers.by_ref().take(1 << 10).collect::<Vec<_>>();
+        if chunk.is_empty() {
+            break;
+        }
+        assert!(
+            ledger.apply(StateDiff {
+                nullifiers: chunk,
+                ..Default::default()
+            }),
+            "Fresh nullifiers should be applied."
+        );
+    }
+    let origin_checkpoint = Checkpoint::default();
+    let response = ledger.pull(&origin_checkpoint);
+    assert!(
+        response.should_continue,
+        "Truncated responses should ask for the rest."
+    );
+    assert_eq!(response.data.nullifier_data.len(), MAX_SYNC_DATA_LENGTH);
+    let checkpoint = Checkpoint::new(
+        origin_checkpoint.receiver_index,
+        origin_checkpoint
+            .sender_index
+            .advance(response.data.nullifier_data.len()),
+    );
+    let response = ledger.pull(&checkpoint);
+    assert!(
+        !response.should_continue,
+        "The last response should not ask for more data."
+    );
+    assert_eq!(response.data.nullifier_data.len(), 1);
+}
+
+/// Checks that a [`CheckedLedger`] accepts valid posts and tracks the nullifiers, UTXOs and
+/// shielded pool balances of the simulation ledger.
+#[test]
+fn checked_ledger_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model,
+        verifying_context,
+        parameters.clone(),
+    );
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let mut ledger = CheckedLedger::<Config, _>::new(ledger);
+    for transaction in [
+        Transaction::ToPrivate(Asset::new(id, 1000)),
+        Transaction::ToPublic(Asset::new(id, 400), account),
+    ] {
+        sync_with_ledger(&mut signer, ledger.ledger());
+        for post in signer
+            .sign(transaction)
+            .expect("Signing a transaction is not allowed to fail.")
+            .posts
+        {
+            let (sources, sinks) = match TransferShape::from_post(&post) {
+                Some(TransferShape::ToPrivate) => (vec![account], vec![]),
+                Some(TransferShape::ToPublic) => (vec![], vec![account]),
+                _ => (vec![], vec![]),
+            };
+            post.validate(&parameters, &ledger, sources, sinks)
+                .expect("Posts signed by the signer should be valid.")
+                .post(&mut ledger, &())
+                .expect("Valid posts should be posted.");
+        }
+    }
+    assert_eq!(ledger.shielded_balance(&id), 600);
+    assert!(!ledger.spent_nullifiers().is_empty());
+    assert_eq!(
+        ledger.registered_utxos().len(),
+        ledger.ledger().utxos().len(),
+        "Every UTXO of the ledger should be registered through the checked ledger."
+    );
+}
+
+/// Checks that a [`CheckedLedger`] panics when the ledger spends a nullifier twice.
+#[test]
+#[should_panic(expected = "The ledger is spending a nullifier which was already spent.")]
+fn checked_ledger_double_spend_test() {
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (_, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut ledger = CheckedLedger::<Config, _>::new(Ledger::new(
+        utxo_accumulator_model,
+        verifying_context,
+        parameters,
+    ));
+    let output = ledger
+        .has_matching_utxo_accumulator_output(Default::default())
+        .expect("The default output is always accepted by the simulation ledger.");
+    let nullifier = ledger
+        .is_unspent(Nullifier::default())
+        .expect("The nullifier was never spent.");
+    ledger
+        .spend(&Default::default(), output, nullifier)
+        .expect("Spending an unspent nullifier should succeed.");
+    let _ = ledger.spend(&Default::default(), output, nullifier);
+}
+
+/// Checks that transactions signed with a randomizing [`PrivacyStrategy`], which merge several
+/// notes before paying, are accepted by the ledger.
+#[test]
+fn privacy_strategy_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    signer.set_privacy_strategy(PrivacyStrategy::PARANOID);
+    assert_eq!(
+        signer.state().privacy_strategy(),
+        &PrivacyStrategy::PARANOID
+    );
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    for value in [10, 20, 30, 40] {
+        let posts = signer
+            .sign(Transaction::ToPrivate(Asset::new(id, value)))
+            .expect("Signing a ToPrivate transaction is not allowed to fail.")
+            .posts;
+        assert!(
+            ledger.push(account, posts),
+            "Invalid ToPrivate transaction."
+        );
+    }
+    sync_with_ledger(&mut signer, &ledger);
+    let address = signer
+        .address()
+        .expect("The signer should have a default address.");
+    let posts = signer
+        .sign(Transaction::PrivateTransfer(Asset::new(id, 90), address))
+        .expect("Signing a PrivateTransfer transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        posts.len() > 1,
+        "Paying with four notes requires merging some of them first."
+    );
+    assert!(
+        ledger.push(account, posts),
+        "Transactions signed with a randomizing privacy strategy should be accepted."
+    );
+    assert!(
+        PrivacyStrategy::PARANOID.broadcast_delay(&mut rng)
+            <= Duration::from_millis(PrivacyStrategy::PARANOID.maximum_broadcast_delay)
+    );
+    assert_eq!(
+        PrivacyStrategy::DETERMINISTIC.broadcast_delay(&mut rng),
+        Duration::ZERO
+    );
+}
+
+/// Checks that both methods to derive a receiving key from a [`Mnemonic`] give
+/// the same result.
+#[test]
+pub fn derive_address_works() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (_, _, parameters, _) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mnemonic = Mnemonic::sample(&mut rng);
+    let receiving_key_1 = address_from_mnemonic(mnemonic.clone(), &parameters).receiving_key;
+    let receiving_key_2 = *authorization_context_from_mnemonic(mnemonic, &parameters)
+        .receiving_key(
+            parameters.base.group_generator.generator(),
+            &parameters.base.viewing_key_derivation_function,
+            &mut (),
+        );
+    assert_eq!(
+        receiving_key_1, receiving_key_2,
+        "Both receiving keys should be the same"
+    );
+}
+
+/// Checks that discarding a ledger fork leaves the ledger untouched, and that the changes of a
+/// committed fork apply to the ledger with the same UTXO accumulator roots the fork had.
+#[test]
+fn ledger_fork_overlay_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.push(account, posts),
+        "Invalid ToPrivate transaction."
+    );
+    sync_with_ledger(&mut signer, &ledger);
+    let origin = ledger.checkpoint();
+    let origin_roots = ledger
+        .root_at(&origin.checkpoint)
+        .expect("The current checkpoint is always in the root history.");
+    let origin_balances = ledger.public_balances(account);
+    let posts = signer
+        .sign(Transaction::ToPublic(Asset::new(id, 50), account))
+        .expect("Signing a ToPublic transaction is not allowed to fail.")
+        .posts;
+    let mut fork = ledger.fork();
+    assert!(
+        fork.push(account, posts.clone()),
+        "Invalid ToPublic transaction."
+    );
+    assert_ne!(fork.utxo_accumulator_roots(), origin_roots);
+    fork.discard();
+    assert_eq!(ledger.checkpoint(), origin);
+    assert_eq!(ledger.root_at(&origin.checkpoint), Ok(origin_roots));
+    assert_eq!(ledger.public_balances(account), origin_balances);
+    let mut fork = ledger.fork();
+    assert!(
+        fork.push(account, posts.clone()),
+        "The discarded fork should not have spent the notes of the ToPublic transaction."
+    );
+    let roots = fork.utxo_accumulator_roots();
+    let diff = fork.commit();
+    assert!(
+        !diff.nullifiers.is_empty(),
+        "The transfer should spend a note."
+    );
+    assert!(
+        ledger.apply(diff),
+        "The changes of a fork should apply to its base ledger."
+    );
+    assert_eq!(ledger.root_at(&ledger.checkpoint().checkpoint), Ok(roots));
+    assert!(
+        !ledger.fork().push(account, posts),
+        "The committed fork should have spent the notes of the ToPublic transaction."
+    );
+}
+
+/// Checks that a state diff reverted from the ledger applies back to the same state, and that
+/// diffs which spend a nullifier twice or overdraw a public balance are rejected without changing
+/// the ledger.
+#[test]
+fn apply_revert_round_trip_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.push(account, posts),
+        "Invalid ToPrivate transaction."
+    );
+    let origin_checkpoint = Default::default();
+    signer
+        .sync(SyncRequest {
+            data: ledger.pull(&origin_checkpoint).data,
+            origin_checkpoint,
+        })
+        .expect("Synchronizing with the ledger is not allowed to fail.");
+    let origin_utxos = ledger.utxos().clone();
+    let origin_balances = ledger.public_balances(account);
+    let posts = signer
+        .sign(Transaction::ToPublic(Asset::new(id, 50), account))
+        .expect("Signing a ToPublic transaction is not allowed to fail.")
+        .posts;
+    let diff = ledger
+        .push_with_diff(account, posts)
+        .expect("Invalid ToPublic transaction.");
+    let next_utxos = ledger.utxos().clone();
+    let next_balances = ledger.public_balances(account);
+    assert_ne!(origin_balances, next_balances);
+    assert!(
+        !ledger.apply(diff.clone()),
+        "Applying a diff whose nullifiers were already spent should fail."
+    );
+    assert_eq!(ledger.utxos(), &next_utxos);
+    assert_eq!(ledger.public_balances(account), next_balances);
+    assert!(
+        ledger.revert(&diff),
+        "Reverting the latest batch should succeed."
+    );
+    assert_eq!(ledger.utxos(), &origin_utxos);
+    assert_eq!(ledger.public_balances(account), origin_balances);
+    let mut overdraft = diff.clone();
+    overdraft.balance_deltas.push(BalanceDelta::Withdraw {
+        account,
+        asset: Asset::new(id, 10_000),
+    });
+    assert!(
+        !ledger.apply(overdraft),
+        "Applying a diff which overdraws a public balance should fail."
+    );
+    assert_eq!(ledger.utxos(), &origin_utxos);
+    assert_eq!(ledger.public_balances(account), origin_balances);
+    assert!(
+        ledger.apply(diff),
+        "Applying the reverted diff should succeed."
+    );
+    assert_eq!(ledger.utxos(), &next_utxos);
+    assert_eq!(ledger.public_balances(account), next_balances);
+}
+
+/// Checks that a withdraw which pays another address emits a [`PaymentReceipt`] with the id of
+/// the transaction accepted by the ledger and a commitment which only opens to the recipient.
+///
+/// [`PaymentReceipt`]: manta_accounting::transfer::PaymentReceipt
+#[test]
+fn sign_withdraw_receipt_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut other_signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model,
+        verifying_context,
+        parameters.clone(),
+    );
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.push(account, posts),
+        "Invalid ToPrivate transaction."
+    );
+    assert!(
+        signer.receipts().is_empty(),
+        "A ToPrivate transaction pays no other address."
+    );
+    sync_with_ledger(&mut signer, &ledger);
+    let own_address = signer.address().expect("Sampled signer has a spending key");
+    let other_address = other_signer
+        .address()
+        .expect("Sampled signer has a spending key");
+    let posts = signer
+        .sign(Transaction::PrivateTransfer(
+            Asset::new(id, 40),
+            other_address,
+        ))
+        .expect("The signer owns enough notes for the transaction.")
+        .posts;
+    assert_eq!(signer.receipts().len(), 1);
+    let mut receipt = signer.receipts()[0];
+    assert_eq!(receipt.identified_asset.asset, Asset::new(id, 40));
+    assert!(
+        receipt.verify(&parameters, &other_address),
+        "Invalid payment receipt."
+    );
+    assert!(
+        !receipt.is_addressed_to(&parameters, &own_address),
+        "The address commitment should only open to the recipient."
+    );
+    assert!(
+        posts.iter().any(|post| receipt.is_paid_by(post)),
+        "The payment should be registered by the signed posts."
+    );
+    let post_receipt = ledger
+        .submit_with_receipt(account, posts)
+        .expect("Invalid PrivateTransfer.");
+    assert_eq!(
+        receipt.transaction_id, post_receipt.transaction_id,
+        "The receipt should carry the id the ledger assigns to the transaction."
+    );
+    sync_with_ledger(&mut signer, &ledger);
+    let posts = signer
+        .sign(Transaction::ToPublic(Asset::new(id, 10), account))
+        .expect("The signer owns enough notes for the transaction.")
+        .posts;
+    assert_eq!(
+        signer.receipts().len(),
+        1,
+        "A withdraw with the change sent to the signer pays no other address."
+    );
+    assert!(ledger.push(account, posts), "Invalid ToPublic transaction.");
+    receipt.address_opening = rng.gen();
+    assert!(
+        !receipt.verify(&parameters, &other_address),
+        "A receipt with the wrong opening should not verify."
+    );
+}
+
+/// Checks that a signer synchronized from the ledger frontier can spend the notes it imported and
+/// that it drops them once they are spent.
+#[test]
+fn frontier_sync_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut other_signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model,
+        verifying_context,
+        parameters.clone(),
+    );
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let mut notes = Vec::new();
+    for value in [100, 200] {
+        let other_posts = other_signer
+            .sign(Transaction::ToPrivate(Asset::new(id, 10)))
+            .expect("Signing a ToPrivate transaction is not allowed to fail.")
+            .posts;
+        assert!(
+            ledger.push(account, other_posts),
+            "Invalid ToPrivate transaction."
+        );
+        let (post, transaction_data) = signer
+            .sign_with_transaction_data(Transaction::ToPrivate(Asset::new(id, value)))
+            .expect("Signing a ToPrivate transaction is not allowed to fail.")
+            .0
+            .take_first();
+        assert!(
+            ledger.push(account, vec![post]),
+            "Invalid ToPrivate transaction."
+        );
+        let (identifier, asset) = transaction_data.open().take_first();
+        notes.push(IdentifiedAsset::<Config>::new(identifier, asset));
+    }
+    signer
+        .import_notes(ImportRequest(notes))
+        .expect("Importing notes is not allowed to fail.");
+    let owned_utxos = signer
+        .owned_utxos()
+        .expect("Sampled signer has a proof authorization key.");
+    assert_eq!(owned_utxos.len(), 2);
+    let response = signer
+        .frontier_sync(FrontierSyncRequest {
+            origin_checkpoint: Default::default(),
+            data: ledger.frontier_read(&Default::default(), &owned_utxos).data,
+        })
+        .expect("Synchronizing with the ledger frontier is not allowed to fail.");
+    assert!(signer.state().pending_imports().is_empty());
+    for utxo in &owned_utxos {
+        assert!(
+            signer
+                .state()
+                .utxo_accumulator()
+                .prove(&item_hash::<Config>(&parameters, utxo))
+                .is_some(),
+            "The owned UTXOs should be provable after frontier synchronization."
+        );
+    }
+    let posts = signer
+        .sign(Transaction::ToPublic(Asset::new(id, 300), account))
+        .expect("Signing a ToPublic transaction for synchronized notes should succeed.")
+        .posts;
+    assert!(ledger.push(account, posts), "Invalid ToPublic transaction.");
+    let checkpoint = response.checkpoint;
+    signer
+        .frontier_sync(FrontierSyncRequest {
+            origin_checkpoint: checkpoint,
+            data: ledger.frontier_read(&checkpoint, &owned_utxos).data,
+        })
+        .expect("Synchronizing with the ledger frontier is not allowed to fail.");
+    assert_eq!(
+        signer
+            .owned_utxos()
+            .expect("Sampled signer has a proof authorization key."),
+        Vec::new(),
+        "Spent notes should be dropped after frontier synchronization."
+    );
+}
+
+/// Checks that the change of a withdraw can be sent to the address of another signer, which
+/// recovers it on synchronization and can spend it, while the original signer keeps a receipt for
+/// the change and cannot spend it anymore.
+#[test]
+fn external_change_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut other_signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model,
+        verifying_context,
+        parameters.clone(),
+    );
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.push(account, posts),
+        "Invalid ToPrivate transaction."
+    );
+    sync_with_ledger(&mut signer, &ledger);
+    let other_address = other_signer
+        .address()
+        .expect("Sampled signer has a spending key");
+    let posts = signer
+        .sign_with_change_address(
+            Transaction::ToPublic(Asset::new(id, 30), account),
+            other_address,
+        )
+        .expect("Signing a ToPublic transaction is not allowed to fail.")
+        .posts;
+    let receipt = signer
+        .receipts()
+        .last()
+        .cloned()
+        .expect("The change sent to another address should have a receipt.");
+    assert_eq!(receipt.transaction_id, transaction_id(&posts));
+    assert_eq!(receipt.identified_asset.asset, Asset::new(id, 70));
+    assert!(
+        receipt.verify(&parameters, &other_address),
+        "Invalid change receipt."
+    );
+    assert!(
+        posts.iter().any(|post| receipt.is_paid_by(post)),
+        "The change receipt should be paid by the signed posts."
+    );
+    assert!(ledger.push(account, posts), "Invalid ToPublic transaction.");
+    sync_with_ledger(&mut signer, &ledger);
+    sync_with_ledger(&mut other_signer, &ledger);
+    assert!(
+        signer
+            .sign(Transaction::ToPublic(Asset::new(id, 1), account))
+            .is_err(),
+        "The change sent to another address should not be spendable."
+    );
+    let posts = other_signer
+        .sign(Transaction::ToPublic(Asset::new(id, 70), account))
+        .expect("Signing a ToPublic transaction for the received change should succeed.")
+        .posts;
+    assert!(ledger.push(account, posts), "Invalid ToPublic transaction.");
+}
+
+/// Checks that the retention policy of a signer compacts its expired payment receipts into
+/// metadata, or drops them, while keeping the receipts which have not expired yet.
+#[test]
+fn receipt_retention_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut other_signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.push(account, posts),
+        "Invalid ToPrivate transaction."
+    );
+    sync_with_ledger(&mut signer, &ledger);
+    let other_address = other_signer
+        .address()
+        .expect("Sampled signer has a spending key");
+    let mut pay = |signer: &mut Signer, value| {
+        let posts = signer
+            .sign(Transaction::PrivateTransfer(
+                Asset::new(id, value),
+                other_address,
+            ))
+            .expect("The signer owns enough notes for the transaction.")
+            .posts;
+        assert!(ledger.push(account, posts), "Invalid PrivateTransfer.");
+        sync_with_ledger(signer, &ledger);
+    };
+    pay(&mut signer, 10);
+    assert_eq!(signer.prune_receipts(0), PruneSummary::default());
+    assert_eq!(
+        signer.receipts().len(),
+        1,
+        "Receipts are kept without a policy."
+    );
+    let first_receipt = signer.receipts()[0];
+    signer.set_retention_policy(Some(RetentionPolicy::days(1, true)));
+    assert_eq!(signer.prune_receipts(0), PruneSummary::default());
+    pay(&mut signer, 20);
+    assert_eq!(
+        signer.prune_receipts(DAY),
+        PruneSummary {
+            compacted: 1,
+            dropped: 0,
+        }
+    );
+    assert_eq!(signer.receipts().len(), 1);
+    assert_eq!(
+        signer.receipts()[0].identified_asset.asset,
+        Asset::new(id, 20)
+    );
+    let history = signer.receipt_history();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].transaction_id, first_receipt.transaction_id);
+    assert_eq!(
+        history[0].address_commitment,
+        first_receipt.address_commitment
+    );
+    assert_eq!(history[0].asset, Asset::new(id, 10));
+    signer.set_retention_policy(Some(RetentionPolicy::days(1, false)));
+    assert_eq!(
+        signer.prune_receipts(2 * DAY),
+        PruneSummary {
+            compacted: 0,
+            dropped: 1,
+        }
+    );
+    assert!(signer.receipts().is_empty());
+    assert_eq!(signer.receipt_history().len(), 1);
+}
+
+/// Synchronizes `signer` with all the data in `ledger`, recording the balance update in `journal`
+/// at `timestamp`.
+fn sync_into_journal(
+    signer: &mut Signer,
+    ledger: &Ledger,
+    journal: &mut Journal<Config, TransactionId>,
+    timestamp: u64,
+) -> Range<u64> {
+    let origin_checkpoint = Default::default();
+    let response = signer
+        .sync(SyncRequest {
+            data: ledger.pull(&origin_checkpoint).data,
+            origin_checkpoint,
+        })
+        .expect("Synchronizing with the ledger is not allowed to fail.");
+    journal.record(&response.balance_update, Some(timestamp))
+}
+
+/// Text Encoding for the Journal Tests
+struct TestEncoding;
+
+impl Encoding<Config, TransactionId> for TestEncoding {
+    fn asset_id(&self, id: &AssetId) -> String {
+        format!("{id:?}")
+    }
+
+    fn asset_value(&self, value: &u128) -> String {
+        value.to_string()
+    }
+
+    fn transaction_id(&self, id: &TransactionId) -> String {
+        id.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    fn address(&self, address: &Address) -> String {
+        format!("{address:?}")
+    }
+
+    fn account(&self, account: &AccountId) -> String {
+        self.transaction_id(account)
+    }
+}
+
+/// Checks that the journal nets the balance updates of the signer per asset, that the entries and
+/// balances are exported, and that annotations round-trip through the CSV and JSON layouts.
+#[test]
+fn journal_export_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let other_address = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    )
+    .address()
+    .expect("Sampled signer has a spending key");
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let mut journal = Journal::default();
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    let deposit_id = transaction_id(&posts);
+    assert!(
+        ledger.push(account, posts),
+        "Invalid ToPrivate transaction."
+    );
+    let deposit = sync_into_journal(&mut signer, &ledger, &mut journal, 1000);
+    assert_eq!(deposit, 0..1);
+    journal.attribute(deposit, deposit_id, Some(Counterparty::Account(account)));
+    let posts = signer
+        .sign(Transaction::PrivateTransfer(
+            Asset::new(id, 30),
+            other_address,
+        ))
+        .expect("The signer owns enough notes for the transaction.")
+        .posts;
+    let payment_id = transaction_id(&posts);
+    assert!(ledger.push(account, posts), "Invalid PrivateTransfer.");
+    let payment = sync_into_journal(&mut signer, &ledger, &mut journal, 2000);
+    assert_eq!(payment, 1..2, "Payments should be netted into one entry.");
+    journal.attribute(
+        payment,
+        payment_id,
+        Some(Counterparty::Address(other_address)),
+    );
+    let entries = journal.entries();
+    assert_eq!(
+        entries
+            .iter()
+            .map(|entry| (entry.kind, entry.asset.value))
+            .collect::<Vec<_>>(),
+        vec![(EntryKind::Credit, 100), (EntryKind::Debit, 30)]
+    );
+    assert_eq!(journal.balances().get(&id), Some(&70));
+    let mut balances = String::new();
+    write_balances_csv(&mut balances, &journal.balance_records(&TestEncoding))
+        .expect("Writing to a string is not allowed to fail.");
+    assert_eq!(
+        balances,
+        format!("asset_id,value\n\"{id:?}\",70\n"),
+        "Fields containing commas should be quoted."
+    );
+    journal.annotate(
+        0,
+        Annotation {
+            label: Some("Salary, \"March\"".into()),
+            category: Some("Income".into()),
+        },
+    );
+    journal.annotate(
+        1,
+        Annotation {
+            label: None,
+            category: Some("Rent".into()),
+        },
+    );
+    let records = journal.entry_records(&TestEncoding);
+    assert_eq!(
+        records[1].transaction_id,
+        Some(TestEncoding.transaction_id(&payment_id))
+    );
+    let mut csv = String::new();
+    write_entries_csv(&mut csv, &records).expect("Writing to a string is not allowed to fail.");
+    assert!(csv.starts_with("index,timestamp,asset_id,kind,value,transaction_id,"));
+    assert!(csv.contains("\"Salary, \"\"March\"\"\""));
+    let mut imported = journal.clone();
+    for index in 0..2 {
+        imported.annotate(index, Default::default());
+    }
+    assert_eq!(
+        imported.import_annotations(
+            read_annotations_csv(&csv).expect("Exported entries are valid annotations.")
+        ),
+        Ok(2)
+    );
+    assert_eq!(
+        imported, journal,
+        "Annotations should round-trip through CSV."
+    );
+    let json = serde_json::to_string(&records).expect("Records are serializable.");
+    assert_eq!(
+        serde_json::from_str::<Vec<EntryRecord>>(&json).expect("Records are deserializable."),
+        records
+    );
+    let mut imported = journal.clone();
+    imported.annotate(0, Default::default());
+    assert_eq!(
+        imported.import_annotations(
+            serde_json::from_str::<Vec<AnnotationRecord>>(&json)
+                .expect("Exported entries are valid annotations.")
+        ),
+        Ok(2)
+    );
+    assert_eq!(
+        imported, journal,
+        "Annotations should round-trip through JSON."
+    );
+    assert!(imported
+        .import_annotations([AnnotationRecord {
+            index: 2,
+            ..Default::default()
+        }])
+        .is_err());
+}
+
+/// Checks that a signer with a splitting strategy pays another address in standardized pieces,
+/// one transfer per piece, that the review and the estimate of the payment match the signed
+/// posts, and that the receiver recovers every piece while the change stays spendable.
+#[test]
+fn splitting_strategy_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut other_signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model,
+        verifying_context,
+        parameters.clone(),
+    );
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.push(account, posts),
+        "Invalid ToPrivate transaction."
+    );
+    sync_with_ledger(&mut signer, &ledger);
+    let strategy = SplittingStrategy::new(vec![5, 0, 50, 20, 10, 20], 4);
+    assert_eq!(strategy.denominations(), &[50, 20, 10, 5]);
+    assert_eq!(strategy.split(75), vec![50, 20, 5]);
+    assert_eq!(strategy.split(97), vec![50, 20, 20, 7]);
+    assert_eq!(strategy.split(3), vec![3]);
+    assert_eq!(signer.split_estimate(75), None);
+    signer.set_splitting_strategy(Some(strategy));
+    assert_eq!(
+        signer.split_estimate(75),
+        Some(SplitEstimate {
+            outputs: 3,
+            extra_posts: 2,
+            standardized: true,
+        })
+    );
+    let other_address = other_signer
+        .address()
+        .expect("Sampled signer has a spending key");
+    let transaction = Transaction::PrivateTransfer(Asset::new(id, 75), other_address);
+    let summary = signer
+        .review(transaction)
+        .expect("The signer owns enough notes for the transaction.");
+    assert_eq!(
+        summary.recipients,
+        vec![
+            (Recipient::Address(other_address), Asset::new(id, 50)),
+            (Recipient::Address(other_address), Asset::new(id, 20)),
+            (Recipient::Address(other_address), Asset::new(id, 5)),
+            (Recipient::Signer, Asset::new(id, 25)),
+        ]
+    );
+    assert!(!summary.privacy_notes.contains(&PrivacyNote::LinksNotes));
+    let posts = signer
+        .sign(transaction)
+        .expect("The signer owns enough notes for the transaction.")
+        .posts;
+    assert_eq!(posts.len(), summary.posts);
+    assert_eq!(posts.len(), 3);
+    let receipts = signer.receipts();
+    let pieces = receipts[receipts.len() - 3..]
+        .iter()
+        .map(|receipt| {
+            assert_eq!(receipt.transaction_id, transaction_id(&posts));
+            assert!(
+                receipt.verify(&parameters, &other_address),
+                "Invalid payment receipt."
+            );
+            assert!(
+                posts.iter().any(|post| receipt.is_paid_by(post)),
+                "Every piece should be paid by one of the signed posts."
+            );
+            receipt.identified_asset.asset.value
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(pieces, vec![50, 20, 5]);
+    assert!(ledger.push(account, posts), "Invalid split payment.");
+    sync_with_ledger(&mut signer, &ledger);
+    sync_with_ledger(&mut other_signer, &ledger);
+    let mut received = other_signer
+        .state()
+        .assets()
+        .entries()
+        .into_iter()
+        .map(|(_, asset)| asset.value)
+        .filter(|value| *value != 0)
+        .collect::<Vec<_>>();
+    received.sort_unstable();
+    assert_eq!(received, vec![5, 20, 50]);
+    let posts = signer
+        .sign(Transaction::ToPublic(Asset::new(id, 25), account))
+        .expect("The change of the split payment should be spendable.")
+        .posts;
+    assert_eq!(
+        posts.len(),
+        1,
+        "Withdraws to public accounts are never split."
+    );
+    assert!(ledger.push(account, posts), "Invalid ToPublic transaction.");
+}
+
+/// Checks that a ledger with a fee schedule charges the fee of every post to the public balance of
+/// the account pushing it, and rejects the posts whose fee cannot be paid.
+#[test]
+fn fee_schedule_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model,
+        verifying_context,
+        parameters.clone(),
+    );
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    let fee_id = AssetId::from(2u128);
+    let schedule = FeeSchedule {
+        asset_id: fee_id,
+        to_private: ShapeFee {
+            base: 10,
+            per_sender: 0,
+            per_receiver: 1,
+        },
+        private_transfer: ShapeFee {
+            base: 20,
+            per_sender: 2,
+            per_receiver: 1,
+        },
+        to_public: ShapeFee {
+            base: 5,
+            per_sender: 1,
+            per_receiver: 1,
+        },
+        private_swap: ShapeFee {
+            base: 40,
+            per_sender: 2,
+            per_receiver: 1,
+        },
+    };
+    assert_eq!(schedule.fee(TransferShape::ToPrivate), Some(11));
+    assert_eq!(schedule.fee(TransferShape::PrivateTransfer), Some(26));
+    assert_eq!(schedule.fee(TransferShape::ToPublic), Some(8));
+    assert_eq!(schedule.fee(TransferShape::PrivateSwap), Some(52));
+    assert_eq!(
+        schedule.transaction_fee(TransferShape::ToPublic, 1),
+        Some(8)
+    );
+    assert_eq!(
+        schedule.transaction_fee(TransferShape::ToPublic, 3),
+        Some(60)
+    );
+    ledger.set_fee_schedule(Some(schedule));
+    ledger.set_public_balance(account, id, 1000);
+    ledger.set_public_balance(account, fee_id, 5);
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert_eq!(schedule.post_fee(&posts[0]), Some(11));
+    assert!(
+        !ledger.push(account, posts.clone()),
+        "The fee of the post is larger than the public balance of the account."
+    );
+    ledger.set_public_balance(account, fee_id, 100);
+    assert!(
+        ledger.push(account, posts),
+        "Invalid ToPrivate transaction."
+    );
+    let balances = ledger
+        .public_balances(account)
+        .expect("The account has public balances.");
+    assert_eq!(balances.value(&id), 900);
+    assert_eq!(balances.value(&fee_id), 89);
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(fee_id, 80)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        !ledger.push(account, posts),
+        "The fee is reserved out of the balance of the fee asset."
+    );
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(fee_id, 78)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.push(account, posts),
+        "Invalid ToPrivate transaction."
+    );
+    let balances = ledger
+        .public_balances(account)
+        .expect("The account has public balances.");
+    assert_eq!(balances.value(&fee_id), 0);
+}
+
+/// Checks that the posts written to a ledger with a block time model wait for the next block,
+/// that their assets only become spendable once the block is deep enough to be confirmed, and
+/// that the block status reports the pending batches and unconfirmed blocks along the way.
+#[test]
+fn block_confirmation_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let interval = Duration::from_secs(6);
+    ledger.set_block_time(Some(BlockTime::new(interval, 2)));
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.submit(account, posts),
+        "Submitting is not allowed to fail."
+    );
+    assert_eq!(ledger.block_status().pending_batches, 1);
+    assert!(
+        ledger.advance_time(interval / 2).is_empty(),
+        "No block should be produced before the end of the interval."
+    );
+    assert_eq!(ledger.advance_time(interval / 2), [true]);
+    let status = ledger.block_status();
+    assert_eq!(status.block_number, BlockHeight::new(1));
+    assert_eq!(status.unconfirmed_blocks(), 1);
+    assert!(!status.is_settled());
+    sync_with_ledger(&mut signer, &ledger);
+    assert!(
+        signer
+            .sign(Transaction::ToPublic(Asset::new(id, 100), account))
+            .is_err(),
+        "Unconfirmed assets should not be spendable."
+    );
+    assert!(ledger.advance_time(2 * interval).is_empty());
+    assert!(ledger.block_status().is_settled());
+    sync_with_ledger(&mut signer, &ledger);
+    let posts = signer
+        .sign(Transaction::ToPublic(Asset::new(id, 100), account))
+        .expect("Signing a ToPublic transaction for confirmed assets should succeed.")
+        .posts;
+    assert!(
+        ledger.submit(account, posts),
+        "Submitting is not allowed to fail."
+    );
+    assert_eq!(ledger.produce_block(), [true]);
+}
+
+/// Checks that two signers restored from the same key do not spend the same notes once they
+/// share their leases, that the lease acquired first wins when both devices lease the same note,
+/// and that the conflict policies avoid or report the notes leased by the other device.
+#[test]
+fn lease_conflict_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let key = KeySecret::sample(&mut rng);
+    let mut new_device = |policy| {
+        let mut signer = new_signer_from_model(
+            parameters.clone(),
+            proving_context.clone(),
+            &utxo_accumulator_model,
+        );
+        signer.load_accounts(AccountTable::new(key.clone()));
+        let device = rng.gen();
+        signer.set_lease_configuration(Some(LeaseConfiguration::new(device, 60_000, policy)));
+        (signer, device)
+    };
+    let (mut first, first_device) = new_device(ConflictPolicy::Avoid);
+    let (mut second, second_device) = new_device(ConflictPolicy::Avoid);
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model.clone(),
+        verifying_context,
+        parameters,
+    );
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    for value in [100, 50] {
+        let posts = first
+            .sign(Transaction::ToPrivate(Asset::new(id, value)))
+            .expect("Signing a ToPrivate transaction is not allowed to fail.")
+            .posts;
+        assert!(
+            ledger.push(account, posts),
+            "Invalid ToPrivate transaction."
+        );
+    }
+    sync_with_ledger(&mut first, &ledger);
+    sync_with_ledger(&mut second, &ledger);
+    let first_posts = first
+        .sign_with_lease(Transaction::ToPublic(Asset::new(id, 100), account), 1_000)
+        .expect("Signing a ToPublic transaction should succeed.")
+        .posts;
+    let second_posts = second
+        .sign_with_lease(Transaction::ToPublic(Asset::new(id, 100), account), 500)
+        .expect("Signing a ToPublic transaction should succeed.")
+        .posts;
+    let lost = first.merge_leases(second.leases().clone());
+    assert_eq!(lost.len(), 1, "The first device should lose its lease.");
+    assert_eq!(lost[0].device, first_device);
+    assert!(second.merge_leases(first.leases().clone()).is_empty());
+    assert_eq!(first.leases(), second.leases());
+    assert!(matches!(
+        first.sign_with_lease(Transaction::ToPublic(Asset::new(id, 100), account), 1_500),
+        Err(SignError::InsufficientBalance(_))
+    ));
+    first.set_lease_configuration(Some(LeaseConfiguration::new(
+        first_device,
+        60_000,
+        ConflictPolicy::Wait,
+    )));
+    assert_eq!(
+        first
+            .sign_with_lease(Transaction::ToPublic(Asset::new(id, 100), account), 1_500)
+            .expect_err("Spending a note leased by the second device should fail."),
+        SignError::LeaseConflict(second_device)
+    );
+    let posts = first
+        .sign_with_lease(Transaction::ToPublic(Asset::new(id, 50), account), 1_500)
+        .expect("Signing a ToPublic transaction for an unleased note should succeed.")
+        .posts;
+    assert!(
+        ledger.push(account, second_posts),
+        "Invalid ToPublic transaction."
+    );
+    assert!(ledger.push(account, posts), "Invalid ToPublic transaction.");
+    assert!(
+        !ledger.push(account, first_posts),
+        "The posts of a lost lease should be rejected."
+    );
+}
+
+/// Checks that the transactions of a batch are signed over disjoint notes so that the ledger
+/// accepts all of their posts, and that a batch with transactions which cannot be signed reports
+/// all of their failures.
+#[test]
+fn sign_batch_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer =
+        new_signer_from_model(parameters.clone(), proving_context, &utxo_accumulator_model);
+    signer.load_accounts(AccountTable::new(KeySecret::sample(&mut rng)));
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let posts = signer
+        .sign_batch(vec![
+            Transaction::ToPrivate(Asset::new(id, 100)),
+            Transaction::ToPrivate(Asset::new(id, 50)),
+        ])
+        .expect("Signing a batch of ToPrivate transactions is not allowed to fail.")
+        .posts();
+    assert!(
+        ledger.push(account, posts),
+        "Invalid ToPrivate transactions."
+    );
+    sync_with_ledger(&mut signer, &ledger);
+    let error = signer
+        .sign_batch(vec![
+            Transaction::ToPublic(Asset::new(id, 100), account),
+            Transaction::ToPublic(Asset::new(id, 100), account),
+            Transaction::ToPublic(Asset::new(id, 50), account),
+            Transaction::ToPublic(Asset::new(id, 10), account),
+        ])
+        .expect_err("The notes cannot pay for every transaction of the batch.");
+    assert_eq!(
+        error
+            .failures
+            .iter()
+            .map(|(index, _)| *index)
+            .collect::<Vec<_>>(),
+        vec![1, 3]
+    );
+    assert!(error
+        .failures
+        .iter()
+        .all(|(_, error)| matches!(error, SignError::InsufficientBalance(_))));
+    let response = signer
+        .sign_batch(vec![
+            Transaction::ToPublic(Asset::new(id, 100), account),
+            Transaction::ToPublic(Asset::new(id, 50), account),
+        ])
+        .expect("Signing a batch of ToPublic transactions over disjoint notes should succeed.");
+    assert_eq!(response.0.len(), 2);
+    for response in response.0 {
+        assert!(
+            ledger.push(account, response.posts),
+            "The transactions of a batch should not spend the same notes."
+        );
+    }
+}
+
+/// Checks that the summary returned by [`Signer::review`] describes the notes, recipients and
+/// posts of the transaction which is then signed.
+#[test]
+fn review_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer =
+        new_signer_from_model(parameters.clone(), proving_context, &utxo_accumulator_model);
+    signer.load_accounts(AccountTable::new(KeySecret::sample(&mut rng)));
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let summary = signer
+        .review(Transaction::ToPrivate(Asset::new(id, 10)))
+        .expect("Reviewing a ToPrivate transaction is not allowed to fail.");
+    assert!(summary.inputs.is_empty());
+    assert_eq!(
+        summary.recipients,
+        vec![(Recipient::Signer, Asset::new(id, 10))]
+    );
+    assert_eq!(
+        summary.balance_changes,
+        vec![(id, BalanceChange::Increase(10))]
+    );
+    assert!(summary.privacy_notes.contains(&PrivacyNote::RevealsAmount));
+    for _ in 0..6 {
+        let posts = signer
+            .sign(Transaction::ToPrivate(Asset::new(id, 10)))
+            .expect("Signing a ToPrivate transaction is not allowed to fail.")
+            .posts;
+        assert_eq!(posts.len(), summary.posts);
+        assert!(
+            ledger.push(account, posts),
+            "Invalid ToPrivate transaction."
+        );
+    }
+    sync_with_ledger(&mut signer, &ledger);
+    assert!(matches!(
+        signer.review(Transaction::ToPublic(Asset::new(id, 100), account)),
+        Err(SignError::InsufficientBalance(_))
+    ));
+    let transaction = Transaction::ToPublic(Asset::new(id, 55), account);
+    let summary = signer
+        .review(transaction)
+        .expect("The signer owns enough notes for the transaction.");
+    let mut inputs = summary.inputs.clone();
+    let mut entries = signer.state().assets().entries();
+    inputs.sort();
+    entries.sort();
+    assert_eq!(inputs, entries);
+    assert_eq!(
+        summary.recipients,
+        vec![
+            (Recipient::PublicAccount(account), Asset::new(id, 55)),
+            (Recipient::Signer, Asset::new(id, 5)),
+        ]
+    );
+    assert_eq!(
+        summary.balance_changes,
+        vec![(id, BalanceChange::Decrease(55))]
+    );
+    assert!(summary.privacy_notes.contains(&PrivacyNote::LinksNotes));
+    let posts = signer
+        .sign(transaction)
+        .expect("The signer owns enough notes for the transaction.")
+        .posts;
+    assert_eq!(posts.len(), summary.posts);
+    assert!(ledger.push(account, posts), "Invalid ToPublic transaction.");
+}
+
+/// Checks that a signer which avoids zero-value notes spends one more note to get a non-zero
+/// change, and that it keeps the zero-value change which could not be avoided out of its
+/// spendable notes.
+#[test]
+fn avoid_zero_notes_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    signer.set_avoid_zero_notes(true);
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    for value in [100, 50] {
+        let posts = signer
+            .sign(Transaction::ToPrivate(Asset::new(id, value)))
+            .expect("Signing a ToPrivate transaction is not allowed to fail.")
+            .posts;
+        assert!(
+            ledger.push(account, posts),
+            "Invalid ToPrivate transaction."
+        );
+    }
+    sync_with_ledger(&mut signer, &ledger);
+    for (value, owned) in [(100, 1), (50, 0)] {
+        let posts = signer
+            .sign(Transaction::ToPublic(Asset::new(id, value), account))
+            .expect("Signing a ToPublic transaction is not allowed to fail.")
+            .posts;
+        assert!(ledger.push(account, posts), "Invalid ToPublic transaction.");
+        sync_with_ledger(&mut signer, &ledger);
+        assert_eq!(
+            signer
+                .owned_utxos()
+                .expect("The signer has a proof authorization key.")
+                .len(),
+            owned,
+            "Zero-value notes should not be spendable."
+        );
+    }
+}
+
+/// Checks that a monitoring service detects the spends of the notes in a nullifier watch-list
+/// from the posted nullifiers, and that the signer maps the detected digests back to the notes.
+#[test]
+fn nullifier_watch_list_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    for value in [100, 50] {
+        let posts = signer
+            .sign(Transaction::ToPrivate(Asset::new(id, value)))
+            .expect("Signing a ToPrivate transaction is not allowed to fail.")
+            .posts;
+        assert!(
+            ledger.push(account, posts),
+            "Invalid ToPrivate transaction."
+        );
+    }
+    sync_with_ledger(&mut signer, &ledger);
+    let watch_list = signer
+        .nullifier_watch_list()
+        .expect("The signer has a proof authorization key.");
+    assert_eq!(watch_list.len(), 2);
+    let other_watch_list = signer
+        .nullifier_watch_list()
+        .expect("The signer has a proof authorization key.");
+    assert_ne!(
+        watch_list.digests(),
+        other_watch_list.digests(),
+        "Watch-lists with different salts should not be linkable."
+    );
+    let posts = signer
+        .sign(Transaction::ToPublic(Asset::new(id, 100), account))
+        .expect("Signing a ToPublic transaction is not allowed to fail.")
+        .posts;
+    let detected = watch_list.detect(
+        posts
+            .iter()
+            .flat_map(|post| post.body.sender_posts.iter())
+            .map(|sender| &sender.nullifier),
+    );
+    assert_eq!(detected.len(), 1, "Only the spent note should be detected.");
+    let note = signer
+        .watched_note(watch_list.salt(), &detected[0])
+        .expect("The detected digest should belong to a note of the signer.");
+    assert_eq!(note.asset, Asset::new(id, 100));
+    assert!(signer
+        .watched_note(other_watch_list.salt(), &detected[0])
+        .is_none());
+    assert!(ledger.push(account, posts), "Invalid ToPublic transaction.");
+}
+
+/// Checks that the notes which match the detection heuristics of the signer but cannot be opened
+/// are quarantined and counted once, and that they stay in the quarantine when reprocessing them
+/// still fails.
+#[test]
+fn quarantine_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    for value in [100, 50] {
+        let posts = signer
+            .sign(Transaction::ToPrivate(Asset::new(id, value)))
+            .expect("Signing a ToPrivate transaction is not allowed to fail.")
+            .posts;
+        assert!(
+            ledger.push(account, posts),
+            "Invalid ToPrivate transaction."
+        );
+    }
+    let origin_checkpoint = Default::default();
+    let mut data = ledger.pull(&origin_checkpoint).data;
+    assert_eq!(data.utxo_note_data.len(), 2);
+    let (lhs, rhs) = data.utxo_note_data.split_at_mut(1);
+    core::mem::swap(&mut lhs[0].1, &mut rhs[0].1);
+    let response = signer
+        .sync(SyncRequest {
+            data: data.clone(),
+            origin_checkpoint,
+        })
+        .expect("Synchronizing with the ledger is not allowed to fail.");
+    assert_eq!(response.quarantined, 2);
+    assert_eq!(
+        signer.quarantine().count(NoteOpenError::InconsistentUtxo),
+        2,
+        "Both notes decrypt but are posted with the wrong UTXO."
+    );
+    let response = signer
+        .sync(SyncRequest {
+            data,
+            origin_checkpoint,
+        })
+        .expect("Synchronizing with the ledger is not allowed to fail.");
+    assert_eq!(
+        response.quarantined, 0,
+        "Notes which are already quarantined should not be counted again."
+    );
+    let response = signer
+        .reprocess_quarantine()
+        .expect("Reprocessing the quarantine is not allowed to fail.");
+    assert_eq!(response.recovered, ImportResponse(vec![]));
+    assert_eq!(response.remaining, 2);
+    assert!(signer.state().pending_imports().is_empty());
+    signer.set_quarantine_capacity(1);
+    assert_eq!(signer.quarantine().len(), 1);
+}
+
+/// Checks that synchronizing the same notes twice does not deposit them twice, and that the
+/// duplicates are counted in the [`SyncResponse`](manta_accounting::wallet::signer::SyncResponse).
+#[test]
+fn duplicate_notes_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.push(account, posts),
+        "Invalid ToPrivate transaction."
+    );
+    let data = ledger.pull(&Default::default()).data;
+    let response = signer
+        .sync(SyncRequest {
+            data: data.clone(),
+            origin_checkpoint: Default::default(),
+        })
+        .expect("Synchronizing with the ledger is not allowed to fail.");
+    assert_eq!(response.duplicates, 0);
+    let response = signer
+        .sync(SyncRequest {
+            data,
+            origin_checkpoint: response.checkpoint,
+        })
+        .expect("Synchronizing with the ledger is not allowed to fail.");
+    assert_eq!(
+        response.duplicates, 1,
+        "The note was already synchronized and should be counted as a duplicate."
+    );
+    assert_eq!(
+        response.balance_update,
+        BalanceUpdate::Partial {
+            deposit: vec![],
+            withdraw: vec![],
+        },
+        "Duplicate notes should not be deposited again."
+    );
+    assert_eq!(
+        signer.state().assets().entries().len(),
+        1,
+        "The note should only be stored once."
+    );
+    assert_eq!(signer.reconcile_assets(), 0);
+}
+
+/// Checks that address ownership proofs are accepted for the address of their signer and the
+/// challenge they answer only.
+#[test]
+fn ownership_proof_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, _, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut other_signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let address = signer.address().expect("Sampled signer has a spending key");
+    let other_address = other_signer
+        .address()
+        .expect("Sampled signer has a spending key");
+    let challenge: OwnershipChallenge = rng.gen();
+    let proof = signer
+        .ownership_proof(&challenge)
+        .expect("Sampled signer has a spending key");
+    assert!(proof.verify(&parameters, &address, &challenge));
+    assert!(
+        !proof.verify(&parameters, &address, &rng.gen()),
+        "Proofs should only answer their own challenge."
+    );
+    assert!(
+        !proof.verify(&parameters, &other_address, &challenge),
+        "Proofs should only be valid for the address of their signer."
+    );
+    let other_proof = other_signer
+        .ownership_proof(&challenge)
+        .expect("Sampled signer has a spending key");
+    assert!(!other_proof.verify(&parameters, &address, &challenge));
+    assert!(other_proof.verify(&parameters, &other_address, &challenge));
+}
+
+/// Checks that a [`HoldingsProof`](crate::config::HoldingsProof) proves the ownership of a note
+/// of at least the threshold value without spending it.
+#[test]
+fn holdings_proof_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let (holdings_proving_context, holdings_verifying_context) = Holdings::generate_context(
+        &(),
+        FullParametersRef::new(&parameters, &utxo_accumulator_model),
+        &mut rng,
+    )
+    .expect("Unable to generate the holdings proving context.");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 100)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.push(account, posts),
+        "Invalid ToPrivate transaction."
+    );
+    signer
+        .sync(SyncRequest {
+            data: ledger.pull(&Default::default()).data,
+            origin_checkpoint: Default::default(),
+        })
+        .expect("Synchronizing with the ledger is not allowed to fail.");
+    let proof = signer
+        .holdings_proof(&holdings_proving_context, id, 60)
+        .expect("The signer owns a note of at least the threshold.");
+    assert!(proof
+        .has_valid_proof(&holdings_verifying_context)
+        .expect("Verifying the holdings proof is not allowed to fail."));
+    let mut forged_proof = proof.clone();
+    forged_proof.threshold = 101;
+    assert!(!forged_proof
+        .has_valid_proof(&holdings_verifying_context)
+        .expect("Verifying the holdings proof is not allowed to fail."));
+    assert!(
+        signer
+            .holdings_proof(&holdings_proving_context, id, 101)
+            .is_none(),
+        "The signer does not own a note of at least the threshold."
+    );
+    let posts = signer
+        .sign(Transaction::ToPublic(Asset::new(id, 100), account))
+        .expect("Signing a ToPublic transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        ledger.push(account, posts),
+        "The note should still be spendable after proving the holdings."
+    );
+}
+
+/// Checks that the requests served through [`Signer::scoped`](manta_accounting::wallet::signer::Signer::scoped)
+/// are restricted to the scope of the capability token, and that expired and revoked tokens are
+/// rejected.
+#[tokio::test]
+async fn capability_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, _, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let address = signer.address();
+    let receive_only = signer.grant_capability(Scope::ReceiveOnly, None);
+    let read_only = signer.grant_capability(Scope::ReadOnly, None);
+    let full_sign = signer.grant_capability(Scope::FullSign, Some(1000));
+    assert_eq!(signer.capabilities().len(), 3);
+    let sign_request = || SignRequest {
+        transaction: Transaction::ToPrivate(Asset::new(AssetId::from(1u128), 100)),
+        metadata: None,
+    };
+
+    let mut scoped = signer
+        .scoped(&receive_only, 0)
+        .expect("The receive-only token was granted.");
+    assert_eq!(scoped.address().await, Ok(address));
+    assert_eq!(
+        scoped
+            .transaction_data(TransactionDataRequest(Vec::new()))
+            .await
+            .err(),
+        Some(AuthorizationError::OutOfScope {
+            scope: Scope::ReceiveOnly,
+            operation: Operation::TransactionData
+        })
+    );
+
+    let mut scoped = signer
+        .scoped(&read_only, 0)
+        .expect("The read-only token was granted.");
+    assert_eq!(scoped.address().await, Ok(address));
+    assert!(scoped
+        .transaction_data(TransactionDataRequest(Vec::new()))
+        .await
+        .is_ok());
+    assert_eq!(
+        scoped.sign(sign_request()).await.err(),
+        Some(AuthorizationError::OutOfScope {
+            scope: Scope::ReadOnly,
+            operation: Operation::Sign
+        })
+    );
+
+    let posts = signer
+        .scoped(&full_sign, 999)
+        .expect("The full-sign token has not expired yet.")
+        .sign(sign_request())
+        .await
+        .expect("The full-sign token allows signing.")
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert_eq!(posts.len(), 1);
+    assert_eq!(
+        signer.scoped(&full_sign, 1000).err(),
+        Some(AuthorizationError::Expired)
+    );
+
+    assert!(signer.revoke_capability(&read_only));
+    assert!(!signer.revoke_capability(&read_only));
+    assert_eq!(
+        signer.scoped(&read_only, 0).err(),
+        Some(AuthorizationError::UnknownToken)
+    );
+    assert_eq!(
+        signer.scoped(&rng.gen::<_, CapabilityToken>(), 0).err(),
+        Some(AuthorizationError::UnknownToken)
+    );
+    assert_eq!(signer.state().capabilities().len(), 2);
+}
+
+/// Checks that a ledger imported from a snapshot has the same state as the exported one, keeps
+/// accepting posts, and that corrupted or truncated snapshots are rejected.
+#[tokio::test]
+async fn ledger_snapshot_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model.clone(),
+        verifying_context.clone(),
+        parameters.clone(),
+    );
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    for value in [100, 200] {
+        let posts = signer
+            .sign(Transaction::ToPrivate(Asset::new(id, value)))
+            .expect("Signing a ToPrivate transaction is not allowed to fail.")
+            .posts;
+        assert!(
+            ledger.push(account, posts),
+            "Invalid ToPrivate transaction."
+        );
+    }
+    sync_with_ledger(&mut signer, &ledger);
+    let posts = signer
+        .sign(Transaction::ToPublic(Asset::new(id, 250), account))
+        .expect("Signing a ToPublic transaction is not allowed to fail.")
+        .posts;
+    assert!(ledger.push(account, posts), "Invalid ToPublic transaction.");
+    ledger.set_block_time(Some(BlockTime::new(Duration::from_secs(6), 1)));
+
+    let mut snapshot = Vec::new();
+    ledger
+        .export_snapshot(&mut snapshot)
+        .await
+        .expect("Writing to a vector is not allowed to fail.");
+    let import = |snapshot: Vec<u8>| {
+        Ledger::import_snapshot(
+            utxo_accumulator_model.clone(),
+            verifying_context.clone(),
+            parameters.clone(),
+            std::io::Cursor::new(snapshot),
+        )
+    };
+    let mut imported = import(snapshot.clone())
+        .await
+        .expect("Importing an exported snapshot is not allowed to fail.");
+    assert_eq!(imported.checkpoint(), ledger.checkpoint());
+    assert_eq!(imported.height(), ledger.height());
+    assert_eq!(imported.utxos(), ledger.utxos());
+    assert_eq!(imported.pool_stats(), ledger.pool_stats());
+    assert_eq!(imported.block_status(), ledger.block_status());
+    assert_eq!(
+        imported.public_balances(account),
+        ledger.public_balances(account)
+    );
+    assert_eq!(
+        imported.pull(&Default::default()).data,
+        ledger.pull(&Default::default()).data
+    );
+    let checkpoint = ledger.checkpoint().checkpoint;
+    assert_eq!(imported.root_at(&checkpoint), ledger.root_at(&checkpoint));
+    let mut reexported = Vec::new();
+    imported
+        .export_snapshot(&mut reexported)
+        .await
+        .expect("Writing to a vector is not allowed to fail.");
+    assert_eq!(reexported, snapshot, "Snapshots should be deterministic.");
+
+    imported.set_block_time(None);
+    let posts = signer
+        .sign(Transaction::ToPrivate(Asset::new(id, 50)))
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        imported.push(account, posts),
+        "The imported ledger should accept new posts."
+    );
+
+    let mut corrupted = snapshot.clone();
+    *corrupted.last_mut().expect("Snapshots are never empty.") ^= 1;
+    assert!(matches!(
+        import(corrupted).await,
+        Err(SnapshotError::DigestMismatch)
+    ));
+    let mut corrupted = snapshot.clone();
+    corrupted[0] ^= 1;
+    assert!(matches!(
+        import(corrupted).await,
+        Err(SnapshotError::InvalidMagic)
+    ));
+    let mut truncated = snapshot;
+    truncated.truncate(truncated.len() / 2);
+    assert!(matches!(import(truncated).await, Err(SnapshotError::Io(_))));
+}
+
+/// Checks that replaying a post archive rebuilds the archived ledger, that its receivers and
+/// nullifiers can be scanned without decoding the posts, and that corrupted archives are rejected.
+#[test]
+fn post_archive_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let mut signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let new_ledger = || {
+        Ledger::new(
+            utxo_accumulator_model.clone(),
+            verifying_context.clone(),
+            parameters.clone(),
+        )
+    };
+    let mut ledger = new_ledger();
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    ledger.set_public_balance(account, id, 1000);
+    let mut writer = ArchiveWriter::new();
+    for value in [100, 200] {
+        let posts = signer
+            .sign(Transaction::ToPrivate(Asset::new(id, value)))
+            .expect("Signing a ToPrivate transaction is not allowed to fail.")
+            .posts;
+        writer.push(account, &posts);
+        assert!(
+            ledger.push(account, posts),
+            "Invalid ToPrivate transaction."
+        );
+    }
+    sync_with_ledger(&mut signer, &ledger);
+    let posts = signer
+        .sign(Transaction::ToPublic(Asset::new(id, 250), account))
+        .expect("Signing a ToPublic transaction is not allowed to fail.")
+        .posts;
+    writer.push(account, &posts);
+    assert!(ledger.push(account, posts), "Invalid ToPublic transaction.");
+    let archive = writer.finish();
+    assert_eq!(archive.batch_count(), 3);
+    assert!(
+        archive.stored_column_len(Column::Posts) < archive.column_len(Column::Posts),
+        "Post metadata should be compressed."
+    );
+
+    let bytes = archive.to_bytes();
+    let decoded = PostArchive::from_bytes(&bytes).expect("Decoding an archive should succeed.");
+    assert_eq!(decoded, archive);
+    let mut replayed = new_ledger();
+    replayed.set_public_balance(account, id, 1000);
+    assert_eq!(
+        replayed
+            .replay(&decoded)
+            .expect("Replaying an archive should succeed."),
+        vec![true; 3],
+        "Every archived batch should be accepted again."
+    );
+    assert_eq!(replayed.checkpoint(), ledger.checkpoint());
+    assert_eq!(replayed.utxos(), ledger.utxos());
+    assert_eq!(
+        replayed.public_balances(account),
+        ledger.public_balances(account)
+    );
+
+    let receivers = decoded
+        .receivers()
+        .expect("The receiver columns are well-formed.")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("The receiver columns are well-formed.");
+    let pulled = ledger.pull(&Default::default()).data;
+    assert_eq!(receivers.len(), pulled.utxo_note_data.len());
+    assert!(receivers
+        .iter()
+        .all(|receiver| pulled.utxo_note_data.contains(receiver)));
+    let nullifiers = decoded
+        .nullifiers()
+        .expect("The nullifier column is well-formed.")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("The nullifier column is well-formed.");
+    assert_eq!(nullifiers, pulled.nullifier_data.into_inner());
+    let mut indexer = NoteIndexer::<Config>::default();
+    indexer.extend(receivers);
+    assert_eq!(indexer.len(), ledger.utxos().len());
+
+    let mut corrupted = bytes.clone();
+    *corrupted.last_mut().expect("Archives are never empty.") ^= 1;
+    assert_eq!(
+        PostArchive::from_bytes(&corrupted),
+        Err(ArchiveError::DigestMismatch)
+    );
+    let mut corrupted = bytes.clone();
+    corrupted[0] ^= 1;
+    assert_eq!(
+        PostArchive::from_bytes(&corrupted),
+        Err(ArchiveError::InvalidMagic)
+    );
+    assert_eq!(
+        PostArchive::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(ArchiveError::Truncated)
+    );
+}
+
+/// Checks that wallet fixtures are deterministic and that the signers rebuilt from them own the
+/// notes and public balances of their specifications.
+#[test]
+fn wallet_fixtures_test() {
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let (id, other_id) = (AssetId::from(1u128), AssetId::from(2u128));
+    let specs = vec![
+        WalletSpec::default()
+            .with_split_notes(Asset::new(id, 100), 3)
+            .with_note(Asset::new(other_id, 7))
+            .with_public_balance(Asset::new(id, 1000)),
+        WalletSpec::default().with_note(Asset::new(other_id, 42)),
+    ];
+    let new_ledger = || {
+        Ledger::new(
+            utxo_accumulator_model.clone(),
+            verifying_context.clone(),
+            parameters.clone(),
+        )
+    };
+    let mut ledger = new_ledger();
+    let fixtures = fixtures::generate(&mut ledger, [7; 32], specs.clone());
+    let mut other_ledger = new_ledger();
+    let other_fixtures = fixtures::generate(&mut other_ledger, [7; 32], specs[..1].to_vec());
+    assert_eq!(
+        fixtures[0].mnemonic.as_ref(),
+        other_fixtures[0].mnemonic.as_ref()
+    );
+    assert_ne!(fixtures[0].mnemonic.as_ref(), fixtures[1].mnemonic.as_ref());
+    assert_eq!(ledger.utxos().len(), 5);
+    assert!(other_ledger.utxos().is_subset(ledger.utxos()));
+    for fixture in &fixtures {
+        let signer = fixture.synced_signer(
+            &proving_context,
+            &parameters,
+            &utxo_accumulator_model,
+            &ledger,
+        );
+        let mut notes = signer
+            .state()
+            .assets()
+            .entries()
+            .into_iter()
+            .map(|(_, asset)| asset)
+            .collect::<Vec<_>>();
+        notes.sort_unstable_by_key(|asset| (asset.id, asset.value));
+        let mut expected = fixture.spec.notes.clone();
+        expected.sort_unstable_by_key(|asset| (asset.id, asset.value));
+        assert_eq!(
+            notes, expected,
+            "The signer should own exactly its fixture notes."
+        );
+    }
+    assert_eq!(
+        ledger
+            .public_balances(fixtures[0].account)
+            .expect("The first fixture has a public balance.")
+            .value(&id),
+        1000
+    );
+}
+
+/// Checks that the notes served by an indexer for a blinded detection key include the notes of
+/// the signer, and that responses which withhold or reorder notes fail verification.
+#[test]
+fn blinded_note_index_test() {
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let id = AssetId::from(1u128);
+    let specs = (1..=8)
+        .map(|i| WalletSpec::default().with_split_notes(Asset::new(id, 100 * i), 2))
+        .collect();
+    let mut ledger = Ledger::new(
+        utxo_accumulator_model.clone(),
+        verifying_context,
+        parameters.clone(),
+    );
+    let fixtures = fixtures::generate(&mut ledger, [3; 32], specs);
+    let mut signer = fixtures[0].signer(&proving_context, &parameters, &utxo_accumulator_model);
+    let key = signer
+        .blinded_detection_key(16)
+        .expect("The signer has an authorization context.");
+    assert_eq!(key.len(), 16);
+    let partition = parameters
+        .address_partition_function
+        .partition(&fixtures[0].address);
+    assert!(key.contains(&partition));
+    let notes = ledger.pull(&Default::default()).data.utxo_note_data;
+    let mut indexer = NoteIndexer::<Config>::default();
+    indexer.extend(notes.iter().cloned());
+    let summary = RangeSummary::<Config>::new(0, &notes, 0.001);
+    let response = indexer.serve(&IndexRequest {
+        key: key.clone(),
+        range: summary.range(),
+    });
+    assert_eq!(summary.verify(&key, &response), Ok(()));
+    assert!(
+        response
+            .notes
+            .iter()
+            .filter(|note| note.note.address_partition == partition)
+            .count()
+            >= fixtures[0].spec.notes.len(),
+        "The notes of the signer should be served."
+    );
+    let mut withheld = response.clone();
+    withheld.notes.pop();
+    assert!(matches!(
+        summary.verify(&key, &withheld),
+        Err(CompletenessError::Incomplete { .. })
+    ));
+    let mut reordered = response;
+    reordered.notes.reverse();
+    if reordered.notes.len() > 1 {
+        assert!(matches!(
+            summary.verify(&key, &reordered),
+            Err(CompletenessError::Unordered(_))
+        ));
+    }
+}
+
+/// Checks that [`PostReceipt`](manta_accounting::wallet::ledger::PostReceipt)s locate the new
+/// UTXOs of the submitted posts and that the wallet catches up with them without reading from the
+/// ledger.
+#[tokio::test]
+async fn post_receipt_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    ledger.set_public_balance(account, id, 1000);
+    let ledger = Arc::new(RwLock::new(ledger));
+    let mut wallet = Wallet::new(LedgerConnection::new(account, ledger.clone()), signer);
+    let mut origin_checkpoint = Checkpoint::default();
+    let mut transaction_ids = Vec::new();
+    for (i, value) in [100, 200].into_iter().enumerate() {
+        let receipt = wallet
+            .submit(Transaction::ToPrivate(Asset::new(id, value)), None)
+            .await
+            .expect("Submitting a ToPrivate transaction is not allowed to fail.")
+            .expect("Ledgers without a block time model accept posts right away.");
+        assert_eq!(receipt.origin_checkpoint, origin_checkpoint);
+        assert_eq!(wallet.checkpoint(), &receipt.checkpoint);
+        assert_eq!(receipt.leaf_indices.len(), 1);
+        let ledger = ledger.read().await;
+        let utxo_note_data = ledger.pull(&receipt.origin_checkpoint).data.utxo_note_data;
+        assert_eq!(utxo_note_data.len(), 1);
+        let (tree_index, leaf_index) = receipt.leaf_indices[0];
+        assert_eq!(
+            leaf_index.get(),
+            receipt.checkpoint.receiver_index[usize::from(tree_index)].get() - 1,
+            "The new UTXO should be the last one of its tree."
+        );
+        assert_eq!(
+            receipt
+                .checkpoint
+                .receiver_index
+                .iter()
+                .map(|index| index.get())
+                .sum::<usize>(),
+            i + 1
+        );
+        transaction_ids.push(receipt.transaction_id);
+        origin_checkpoint = receipt.checkpoint;
+    }
+    assert_ne!(transaction_ids[0], transaction_ids[1]);
+    assert_eq!(wallet.balance(&id), 300);
+}
+
+/// Checks that the wallet follows the [`UpgradeDescriptor`]s announced by the ledger, warning
+/// about retiring shapes before the upgrade activates and refusing to sign them afterwards.
+#[tokio::test]
+async fn upgrade_schedule_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    ledger.set_public_balance(account, id, 1000);
+    let retired = ProtocolFeature::Shape(TransferShape::ToPublic);
+    ledger.announce_upgrade(UpgradeDescriptor {
+        epoch: 1,
+        activation_height: ledger.height() + 1,
+        parameters_hash: Some([1; 32]),
+        deprecated_features: vec![retired],
+        ..Default::default()
+    });
+    let ledger = Arc::new(RwLock::new(ledger));
+    let mut wallet = Wallet::new(LedgerConnection::new(account, ledger.clone()), signer);
+    wallet
+        .sync_upgrades()
+        .await
+        .expect("Pulling the upgrade schedule is not allowed to fail.");
+    assert!(wallet.upgrades().is_retiring(&retired));
+    assert!(wallet.upgrades().is_enabled(TransferShape::ToPublic));
+    assert_eq!(
+        wallet
+            .upgrades()
+            .parameters_update(&[0; 32])
+            .map(|upgrade| upgrade.epoch),
+        Some(1),
+        "Wallets with outdated parameters should be prompted to download the new ones."
+    );
+    assert!(wallet.upgrades().parameters_update(&[1; 32]).is_none());
+    assert!(wallet
+        .post(Transaction::ToPrivate(Asset::new(id, 500)), None)
+        .await
+        .expect("Posting a ToPrivate transaction is not allowed to fail."));
+    wallet
+        .sync()
+        .await
+        .expect("Synchronizing with the ledger is not allowed to fail.");
+    wallet
+        .sync_upgrades()
+        .await
+        .expect("Pulling the upgrade schedule is not allowed to fail.");
+    assert!(wallet.upgrades().is_deprecated(&retired));
+    assert!(matches!(
+        wallet
+            .sign(Transaction::ToPublic(Asset::new(id, 100), account), None)
+            .await,
+        Err(WalletError::DisabledShape(TransferShape::ToPublic))
+    ));
+    let posts = wallet
+        .signer_mut()
+        .sign(Transaction::ToPublic(Asset::new(id, 100), account))
+        .expect("Signing a ToPublic transaction is not allowed to fail.")
+        .posts;
+    assert!(
+        !ledger.write().await.push(account, posts),
+        "The ledger should reject the shapes deprecated by an active upgrade."
+    );
+}
+
+/// Checks that the wallet signs multi-asset operations jointly, reserves the assets they send
+/// while they are pending, and aborts them when the ledger rejects one of their transactions.
+#[tokio::test]
+async fn multi_asset_operation_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let receiver = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    )
+    .address()
+    .expect("Sampled signer has a spending key");
+    let account = rng.gen();
+    let (a, b) = (AssetId::from(1u128), AssetId::from(2u128));
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    ledger.set_public_balance(account, a, 1000);
+    ledger.set_public_balance(account, b, 1000);
+    let ledger = Arc::new(RwLock::new(ledger));
+    let mut wallet = Wallet::new(LedgerConnection::new(account, ledger.clone()), signer);
+    for id in [a, b] {
+        wallet
+            .submit(Transaction::ToPrivate(Asset::new(id, 400)), None)
+            .await
+            .expect("Submitting a ToPrivate transaction is not allowed to fail.")
+            .expect("Ledgers without a block time model accept posts right away.");
+    }
+    let (id, receipts) = wallet
+        .submit_operation(
+            plan_send(
+                [Asset::new(a, 60), Asset::new(b, 50), Asset::new(a, 40)],
+                receiver,
+            )
+            .into_iter()
+            .map(|transaction| (transaction, None)),
+        )
+        .await
+        .expect("Submitting a funded operation is not allowed to fail.");
+    assert_eq!(
+        receipts.len(),
+        2,
+        "The operation should send one transaction per asset."
+    );
+    assert_eq!(
+        wallet
+            .operations()
+            .get(&id)
+            .map(|operation| operation.status()),
+        Some(OperationStatus::Settled)
+    );
+    assert_eq!(wallet.balance(&a), 300);
+    assert_eq!(wallet.balance(&b), 350);
+    let (id, _) = wallet
+        .sign_operation([(
+            Transaction::PrivateTransfer(Asset::new(a, 200), receiver),
+            None,
+        )])
+        .await
+        .expect("Signing a funded operation is not allowed to fail.");
+    assert_eq!(wallet.available_balance(&a), 100);
+    assert_eq!(wallet.available_balance(&b), 350);
+    assert!(matches!(
+        wallet
+            .sign(
+                Transaction::PrivateTransfer(Asset::new(a, 150), receiver),
+                None
+            )
+            .await,
+        Err(WalletError::InsufficientBalance(_))
+    ));
+    assert!(wallet.abort_operation(&id, 0));
+    assert!(
+        !wallet.settle_operation(&id),
+        "Aborted operations cannot be settled."
+    );
+    assert_eq!(wallet.available_balance(&a), 300);
+    {
+        let mut ledger = ledger.write().await;
+        let activation_height = ledger.height() + 1;
+        ledger.announce_upgrade(UpgradeDescriptor {
+            epoch: 1,
+            activation_height,
+            deprecated_features: vec![ProtocolFeature::Shape(TransferShape::ToPublic)],
+            ..Default::default()
+        });
+    }
+    let result = wallet
+        .submit_operation([
+            (
+                Transaction::PrivateTransfer(Asset::new(a, 100), receiver),
+                None,
+            ),
+            (Transaction::ToPublic(Asset::new(b, 50), account), None),
+        ])
+        .await;
+    let id = match result {
+        Err(WalletError::OperationRejected(id, 1)) => id,
+        _ => panic!("The ToPublic transaction should be rejected after the upgrade activates."),
+    };
+    assert_eq!(
+        wallet
+            .operations()
+            .get(&id)
+            .map(|operation| operation.status()),
+        Some(OperationStatus::Aborted(1))
+    );
+    assert_eq!(wallet.operations().pending().count(), 0);
+    assert_eq!(wallet.balance(&a), 200);
+    assert_eq!(wallet.balance(&b), 350);
+    assert_eq!(wallet.available_balance(&a), wallet.balance(&a));
+    assert_eq!(wallet.available_balance(&b), wallet.balance(&b));
+}
+
+/// Checks that a wallet waiting for the checkpoint headers of the ledger synchronizes as soon as
+/// the ledger accepts new posts.
+#[tokio::test]
+async fn checkpoint_stream_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let account = rng.gen();
+    let id = AssetId::from(1u128);
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    ledger.set_public_balance(account, id, 1000);
+    let ledger = Arc::new(RwLock::new(ledger));
+    let mut wallet = Wallet::new(LedgerConnection::new(account, ledger.clone()), signer);
+    let posts = wallet
+        .sign(Transaction::ToPrivate(Asset::new(id, 100)), None)
+        .await
+        .expect("Signing a ToPrivate transaction is not allowed to fail.")
+        .posts;
+    let (header, accepted) = tokio::join!(wallet.sync_on_push(), async {
+        ledger.write().await.push(account, posts)
+    });
+    assert!(accepted, "Invalid ToPrivate transaction.");
+    let header = header.expect("Synchronizing on a pushed header is not allowed to fail.");
+    let ledger = ledger.read().await;
+    assert_eq!(header.checkpoint, ledger.checkpoint().checkpoint);
+    assert_eq!(header.height.get(), ledger.height());
+    assert_eq!(wallet.checkpoint(), &header.checkpoint);
+    assert_eq!(wallet.balance(&id), 100);
+}
+
+/// Checks that the wallet takes its time from the ledger, warning about local clocks which are
+/// too far from the ledger time and only using them to measure elapsed time.
+#[tokio::test]
+async fn ledger_time_test() {
+    let mut rng = OsRng;
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let (proving_context, verifying_context, parameters, utxo_accumulator_model) =
+        load_parameters(directory.path()).expect("Failed to load parameters");
+    let signer = sample_signer(
+        &proving_context,
+        &parameters,
+        &utxo_accumulator_model,
+        &mut rng,
+    );
+    let account = rng.gen();
+    let genesis = 1_650_000_000_000;
+    let mut ledger = Ledger::new(utxo_accumulator_model, verifying_context, parameters);
+    ledger.set_time(genesis);
+    let ledger = Arc::new(RwLock::new(ledger));
+    let mut wallet = Wallet::new(LedgerConnection::new(account, ledger.clone()), signer);
+    assert_eq!(wallet.now(genesis), None);
+    let local = genesis + 30 * DAY;
+    assert_eq!(
+        wallet
+            .sync_time(local)
+            .await
+            .expect("Pulling the ledger time is not allowed to fail."),
+        Some(ClockSkew::Ahead(30 * DAY)),
+        "A local clock a month ahead of the ledger should be reported."
+    );
+    assert_eq!(wallet.now(local + 1000), Some(genesis + 1000));
+    assert_eq!(
+        wallet.now(local - DAY),
+        Some(genesis),
+        "Turning the local clock back should not move the time before the ledger time."
+    );
+    ledger.write().await.advance_time(Duration::from_secs(60));
+    assert_eq!(
+        wallet
+            .sync_time(genesis + 61_000)
+            .await
+            .expect("Pulling the ledger time is not allowed to fail."),
+        None,
+        "A local clock within the tolerance should not be reported."
+    );
+    assert_eq!(
+        wallet.clock().ledger_time().map(|time| time.timestamp),
+        Some(genesis + 60_000)
+    );
+    assert_eq!(wallet.now(genesis + 62_000), Some(genesis + 61_000));
+}
+
+/// Checks that the signer RPC description has unique command names and error codes, and that the
+/// error codes and variants it describes are the ones of the signer errors.
+#[test]
+fn signer_rpc_description_test() {
+    let description = rpc::description();
+    assert_eq!(description.version, rpc::VERSION);
+    let mut names = description
+        .commands
+        .iter()
+        .map(|command| command.name)
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    names.dedup();
+    assert_eq!(
+        names.len(),
+        description.commands.len(),
+        "Command names should be unique."
+    );
+    let mut codes = Vec::new();
+    for errors in [
+        <crate::signer::SyncError as ErrorCode>::VARIANTS,
+        <crate::signer::SignError as ErrorCode>::VARIANTS,
+    ] {
+        assert!(
+            errors.windows(2).all(|pair| pair[0].code < pair[1].code),
+            "Error variants should be sorted by code."
+        );
+        codes.extend(errors.iter().map(|error| error.code));
+    }
+    let count = codes.len();
+    codes.sort_unstable();
+    codes.dedup();
+    assert_eq!(codes.len(), count, "Error codes should be unique.");
+    let sign = description
+        .commands
+        .iter()
+        .find(|command| command.name == SignCommand::NAME)
+        .expect("The sign command should be described.");
+    assert_eq!(sign.request.name, "SignRequest");
+    let error = crate::signer::SignError::MissingSpendingKey;
+    let variant = sign
+        .errors
+        .iter()
+        .find(|variant| variant.code == error.code())
+        .expect("The sign command should describe the missing spending key error.");
+    assert_eq!(
+        serde_json::to_value(error).expect("Serializing the error is not allowed to fail."),
+        variant.variant,
+        "The variant of an error description should be the tag of the serialized error."
     );
+    assert!(serde_json::to_string(&description)
+        .expect("Serializing the description is not allowed to fail.")
+        .contains("\"sign_with_transaction_data\""));
 }
@@ -0,0 +1,103 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Manta Pay Simulation Metrics Testing
+
+use crate::simulation::metrics::{write_metrics_csv, Recorder, RunMetrics, Scoring};
+use core::time::Duration;
+use manta_accounting::wallet::test::ActionType;
+
+/// Checks that the recorder counts the outcome of the posts and the average proving and
+/// synchronization times of a run.
+#[test]
+fn recorder_test() {
+    let recorder = Recorder::default();
+    recorder.record_event::<()>(ActionType::ToPrivate, &Ok(true));
+    recorder.record_event::<()>(ActionType::PrivateTransfer, &Ok(true));
+    recorder.record_event::<()>(ActionType::ToPublic, &Ok(false));
+    recorder.record_event(ActionType::PrivateTransfer, &Err(()));
+    recorder.record_event::<()>(ActionType::Skip, &Ok(true));
+    recorder.record_event::<()>(ActionType::Restart, &Ok(true));
+    recorder.record_signing(1, Duration::from_millis(300));
+    recorder.record_signing(2, Duration::from_millis(900));
+    recorder.record_sync(Duration::from_millis(10));
+    recorder.record_sync(Duration::from_millis(30));
+    let metrics = recorder.metrics(3, true);
+    assert_eq!(metrics.actor_count, 3);
+    assert_eq!(metrics.posts_accepted, 2);
+    assert_eq!(metrics.posts_rejected, 1);
+    assert_eq!(metrics.errors, 1);
+    assert_eq!(metrics.proofs, 3);
+    assert!((metrics.average_proving_time - 400.0).abs() < 1e-6);
+    assert_eq!(metrics.syncs, 2);
+    assert!((metrics.average_sync_lag - 20.0).abs() < 1e-6);
+    assert!((metrics.acceptance_rate() - 0.5).abs() < 1e-6);
+}
+
+/// Checks that runs whose balances do not match score zero, and that slower runs score lower.
+#[test]
+fn scoring_test() {
+    let scoring = Scoring::default();
+    let fast = RunMetrics {
+        posts_accepted: 10,
+        average_proving_time: scoring.reference_proving_time / 2.0,
+        average_sync_lag: scoring.reference_sync_lag,
+        balances_match: true,
+        ..Default::default()
+    };
+    assert!((scoring.score(&fast) - 100.0).abs() < 1e-6);
+    let slow = RunMetrics {
+        average_proving_time: scoring.reference_proving_time * 2.0,
+        ..fast.clone()
+    };
+    assert!(scoring.score(&slow) < scoring.score(&fast));
+    let incorrect = RunMetrics {
+        balances_match: false,
+        ..fast
+    };
+    assert_eq!(scoring.score(&incorrect), 0.0);
+}
+
+/// Checks that the metrics are exported as one CSV row per run after the header row.
+#[test]
+fn metrics_csv_test() {
+    let runs = [
+        RunMetrics {
+            label: "main".into(),
+            actor_count: 2,
+            posts_accepted: 4,
+            balances_match: true,
+            ..Default::default()
+        },
+        RunMetrics {
+            label: "feature, with comma".into(),
+            actor_count: 2,
+            ..Default::default()
+        },
+    ];
+    let mut csv = String::new();
+    write_metrics_csv(&mut csv, &runs, &Scoring::default()).expect("Unable to write CSV.");
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            "label,actor_count,posts_accepted,posts_rejected,errors,proofs,average_proving_time,\
+             syncs,average_sync_lag,balances_match,score",
+            "main,2,4,0,0,0,0.000,0,0.000,true,100.00",
+            "\"feature, with comma\",2,0,0,0,0,0.000,0,0.000,false,0.00",
+        ]
+    );
+}
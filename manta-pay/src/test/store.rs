@@ -0,0 +1,77 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Asset Map Store Testing Suite
+
+use crate::{
+    config::{utxo::AssetId, Asset, Identifier},
+    signer::store::{sled::SledStore, JournaledAssetMap},
+};
+use manta_accounting::{
+    asset::{AssetMap, BTreeAssetMap},
+    wallet::signer::store::AssetMapStore,
+};
+use manta_crypto::rand::{OsRng, Rand};
+
+/// Checks that the changes made to a [`JournaledAssetMap`] are persisted to a [`SledStore`], and
+/// that the map loaded back from the store matches the original one.
+#[tokio::test]
+async fn sled_store_round_trip() {
+    let mut rng = OsRng;
+    let database = sled::Config::new()
+        .temporary(true)
+        .open()
+        .expect("Unable to open temporary sled database.");
+    let mut store = SledStore::open(&database).expect("Unable to open the asset map tree.");
+    let mut mirror = BTreeAssetMap::default();
+    let mut map = JournaledAssetMap::load(&mut store)
+        .await
+        .expect("Unable to load the asset map.");
+    assert!(map.map().is_empty());
+    let entries = (0..8)
+        .map(|i| {
+            (
+                Identifier::new(false, false, rng.gen()),
+                Asset::new(AssetId::from(i % 3), rng.gen_range(1..1000)),
+            )
+        })
+        .collect::<Vec<_>>();
+    map.insert_all(entries.iter().copied());
+    map.remove(entries[0].0, entries[0].1);
+    map.retain(|key, _| key != &entries[1].0);
+    mirror.apply(map.batch()).await.expect("Infallible.");
+    map.persist(&mut store)
+        .await
+        .expect("Unable to persist the asset map.");
+    assert!(map.batch().is_empty());
+    assert_eq!(map.map(), &mirror);
+    let loaded = JournaledAssetMap::load(&mut store)
+        .await
+        .expect("Unable to load the asset map.");
+    assert_eq!(loaded.map(), map.map());
+    map.insert(entries[0].0, entries[0].1);
+    map.persist(&mut store)
+        .await
+        .expect("Unable to persist the asset map.");
+    map.overwrite(&mut store)
+        .await
+        .expect("Unable to overwrite the asset map.");
+    let loaded = JournaledAssetMap::load(&mut store)
+        .await
+        .expect("Unable to load the asset map.");
+    assert_eq!(loaded.map(), map.map());
+    assert_eq!(loaded.map().len(), 7);
+}
@@ -0,0 +1,255 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Streaming Testing Suite
+
+use crate::{
+    stream::{
+        read_parameter_file, read_posts, read_sync_response, write_parameter_file, write_posts,
+        write_sync_response, FrameReader, FrameWriter, StreamError, SyncReadResponse,
+        PARAMETER_CHUNK_LENGTH,
+    },
+    test::codec::corpus_posts,
+};
+use manta_accounting::wallet::{
+    ledger::ReadResponse,
+    signer::{SyncData, MAX_SYNC_DATA_LENGTH},
+};
+use manta_crypto::rand::{OsRng, RngCore};
+use std::{fs, io};
+
+/// Encodes `values` as a sequence of frames into a buffer.
+#[inline]
+async fn frames(values: &[u64]) -> Vec<u8> {
+    let mut writer = FrameWriter::new(Vec::new());
+    writer
+        .write_sequence(values)
+        .await
+        .expect("Writing to a buffer is not allowed to fail.");
+    writer.into_inner()
+}
+
+/// Checks that values round-trip through frames and that frames are length-prefixed.
+#[tokio::test]
+async fn frame_round_trip() {
+    let values = [0, 1, u64::MAX, 0x0123_4567_89ab_cdef];
+    let bytes = frames(&values).await;
+    assert_eq!(
+        bytes.len(),
+        8 + values.len() * (8 + 8),
+        "Every frame should carry a length prefix and the sequence a length header."
+    );
+    let mut reader = FrameReader::new(bytes.as_slice());
+    let mut decoded = Vec::new();
+    reader
+        .read_sequence_with::<u64, _>(values.len(), |value| decoded.push(value))
+        .await
+        .expect("Reading the frames back is not allowed to fail.");
+    assert_eq!(
+        decoded, values,
+        "The values should round-trip through frames."
+    );
+    assert!(
+        reader.into_inner().is_empty(),
+        "The reader should consume exactly the written frames."
+    );
+}
+
+/// Checks that a frame with a missing body byte fails with an unexpected end of file.
+#[tokio::test]
+async fn truncated_frame_fails() {
+    let mut bytes = frames(&[7]).await;
+    bytes.pop();
+    let mut reader = FrameReader::new(bytes.as_slice());
+    let len = reader
+        .read_sequence_length::<()>(1)
+        .await
+        .expect("The sequence header is complete.");
+    assert_eq!(len, 1);
+    match reader.read::<u64>().await {
+        Err(StreamError::Io(err)) => assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof),
+        result => panic!("Expected an unexpected end of file, found: {result:?}"),
+    }
+}
+
+/// Checks that a frame longer than the maximum frame length is rejected before its body is read.
+#[tokio::test]
+async fn oversized_frame_fails() {
+    let mut writer = FrameWriter::new(Vec::new());
+    writer
+        .write(&[1u8; 16])
+        .await
+        .expect("Writing to a buffer is not allowed to fail.");
+    let bytes = writer.into_inner();
+    let mut reader = FrameReader::with_max_frame_length(bytes.as_slice(), 15);
+    assert_eq!(reader.max_frame_length(), 15);
+    assert!(matches!(
+        reader.read::<[u8; 16]>().await,
+        Err(StreamError::FrameTooLarge(16))
+    ));
+    assert_eq!(
+        reader.into_inner().len(),
+        16,
+        "The body of an oversized frame should not be read."
+    );
+    let mut reader = FrameReader::with_max_frame_length(bytes.as_slice(), 16);
+    assert_eq!(
+        reader
+            .read::<[u8; 16]>()
+            .await
+            .expect("A frame of the maximum length should be accepted."),
+        [1; 16]
+    );
+}
+
+/// Checks that frames which are not consumed completely and sequences which are too long are
+/// rejected.
+#[tokio::test]
+async fn malformed_frames_fail() {
+    let bytes = frames(&[u64::MAX]).await;
+    let mut reader = FrameReader::new(&bytes[8..]);
+    assert!(matches!(
+        reader.read::<u32>().await,
+        Err(StreamError::TrailingBytes(4))
+    ));
+    let bytes = frames(&[1, 2, 3]).await;
+    let mut reader = FrameReader::new(bytes.as_slice());
+    assert!(matches!(
+        reader.read_sequence_with::<u64, _>(2, |_| {}).await,
+        Err(StreamError::SequenceTooLong(3))
+    ));
+}
+
+/// Checks that posts stream through a socket-like duplex channel which is smaller than a single
+/// post.
+#[tokio::test]
+async fn posts_round_trip() {
+    let posts = corpus_posts();
+    assert!(!posts.is_empty(), "The corpus should contain posts.");
+    let (client, server) = tokio::io::duplex(1024);
+    let mut writer = FrameWriter::new(client);
+    let mut reader = FrameReader::new(server);
+    let mut decoded = Vec::new();
+    let (written, read) = tokio::join!(
+        write_posts(&mut writer, &posts),
+        read_posts(&mut reader, posts.len(), |post| decoded.push(post)),
+    );
+    written.expect("Writing the posts is not allowed to fail.");
+    read.expect("Reading the posts is not allowed to fail.");
+    assert_eq!(
+        decoded, posts,
+        "The posts should round-trip through the stream."
+    );
+    let mut writer = FrameWriter::new(Vec::new());
+    write_posts(&mut writer, &posts)
+        .await
+        .expect("Writing to a buffer is not allowed to fail.");
+    let bytes = writer.into_inner();
+    assert!(
+        matches!(
+            read_posts(&mut FrameReader::new(bytes.as_slice()), posts.len() - 1, |_| {}).await,
+            Err(StreamError::SequenceTooLong(len)) if len == posts.len() as u64
+        ),
+        "Reading more posts than requested should fail."
+    );
+}
+
+/// Checks that synchronization responses stream through a duplex channel and that responses
+/// exceeding the synchronization data bound are rejected.
+#[tokio::test]
+async fn sync_response_round_trip() {
+    let response = SyncReadResponse {
+        should_continue: true,
+        data: SyncData::from_posts(&corpus_posts())
+            .expect("Building the corpus synchronization data is not allowed to fail."),
+    };
+    assert!(!response.data.utxo_note_data.is_empty());
+    assert!(!response.data.nullifier_data.is_empty());
+    let (client, server) = tokio::io::duplex(1024);
+    let mut writer = FrameWriter::new(client);
+    let mut reader = FrameReader::new(server);
+    let (written, read) = tokio::join!(
+        write_sync_response(&mut writer, &response),
+        read_sync_response(&mut reader),
+    );
+    written.expect("Writing the response is not allowed to fail.");
+    assert_eq!(
+        read.expect("Reading the response is not allowed to fail."),
+        response,
+        "The response should round-trip through the stream."
+    );
+    let mut writer = FrameWriter::new(Vec::new());
+    write_sync_response(&mut writer, &ReadResponse::default())
+        .await
+        .expect("Writing to a buffer is not allowed to fail.");
+    let mut bytes = writer.into_inner();
+    let header = bytes.len() - 16;
+    bytes[header..header + 8].copy_from_slice(&(MAX_SYNC_DATA_LENGTH as u64 + 1).to_le_bytes());
+    assert!(matches!(
+        read_sync_response(&mut FrameReader::new(bytes.as_slice())).await,
+        Err(StreamError::SequenceTooLong(len)) if len == MAX_SYNC_DATA_LENGTH as u64 + 1
+    ));
+}
+
+/// Checks that parameter files stream in chunks and that files above the maximum length are
+/// rejected.
+#[tokio::test]
+async fn parameter_file_round_trip() {
+    let directory = tempfile::tempdir().expect("Unable to generate temporary test directory.");
+    let source = directory.path().join("source.dat");
+    let target = directory.path().join("target.dat");
+    let mut contents = vec![0; 2 * PARAMETER_CHUNK_LENGTH + PARAMETER_CHUNK_LENGTH / 2];
+    OsRng.fill_bytes(&mut contents);
+    fs::write(&source, &contents).expect("Unable to write the parameter file.");
+    let (client, server) = tokio::io::duplex(1024);
+    let mut writer = FrameWriter::new(client);
+    let mut reader = FrameReader::new(server);
+    let (written, read) = tokio::join!(
+        write_parameter_file(&mut writer, &source),
+        read_parameter_file(&mut reader, &target, contents.len()),
+    );
+    written.expect("Writing the parameter file is not allowed to fail.");
+    read.expect("Reading the parameter file is not allowed to fail.");
+    assert_eq!(
+        fs::read(&target).expect("Unable to read the streamed parameter file."),
+        contents,
+        "The parameter file should round-trip through the stream."
+    );
+    let mut writer = FrameWriter::new(Vec::new());
+    write_parameter_file(&mut writer, &source)
+        .await
+        .expect("Writing to a buffer is not allowed to fail.");
+    let bytes = writer.into_inner();
+    assert!(matches!(
+        read_parameter_file(
+            &mut FrameReader::new(bytes.as_slice()),
+            &target,
+            2 * PARAMETER_CHUNK_LENGTH
+        )
+        .await,
+        Err(StreamError::SequenceTooLong(3))
+    ));
+    match read_parameter_file(
+        &mut FrameReader::new(bytes.as_slice()),
+        &target,
+        contents.len() - 1,
+    )
+    .await
+    {
+        Err(StreamError::Io(err)) => assert_eq!(err.kind(), io::ErrorKind::InvalidData),
+        result => panic!("Expected an invalid data error, found: {result:?}"),
+    }
+}
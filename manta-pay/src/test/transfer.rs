@@ -18,17 +18,41 @@
 
 use crate::{
     config::{
-        FullParametersRef, Parameters, PrivateTransfer, ProofSystem, ToPrivate, ToPublic,
-        TransferPost,
+        context::{
+            check_contexts, check_proving_context, verifying_context_checksum, ContextMismatch,
+        },
+        statement::{circuit_statement, circuit_statements},
+        utxo::{self, MerkleTreeConfiguration, UtxoAccumulatorItemVar, UtxoAccumulatorModelVar},
+        Asset, AssetId, Authorization, Compiler, Config, DynamicTransfer, FullParametersRef,
+        MemoPlaintext, MultiProvingContext, MultiVerifyingContext, Parameters, PrivateSwap,
+        PrivateTransfer, Proof, ProofSystem, ProvingContextRegistry, Receiver, SpendingKey,
+        ToPrivate, ToPublic, TransactionIdHash, TransferPost, UtxoAccumulatorModel,
+        VerifyingContext, VerifyingContextProvider,
     },
-    test::payment::UtxoAccumulator,
+    test::{codec::corpus_posts, payment::UtxoAccumulator},
+};
+use core::cell::Cell;
+use manta_accounting::transfer::{
+    self,
+    canonical::{self, TransferShape},
+    dynamic::{ProvingError, Shape, ShapeError, VerifyingError},
+    test::{validity_check_with_fuzzing, TransferDistribution},
+    utxo::{DeriveDecryptionKey, NoteMemo},
+    BodyWithAccountsRef, PostParticipants,
 };
-use manta_accounting::transfer::{test::validity_check_with_fuzzing, BodyWithAccountsRef};
 use manta_crypto::{
     accumulator::Accumulator,
-    constraint::{measure::Measure, ProofSystem as _},
+    arkworks::groth16::{Compression, ProverConfiguration, ProvingStrategy, MIN_CHUNK_SIZE},
+    constraint::{
+        measure::{Measure, Size},
+        test::{Behavior, Script, Scripted, ScriptedError, Stage},
+        ProofSystem as _,
+    },
+    eclair::alloc::{mode::Secret, Allocate, Allocator, Constant},
+    merkle_tree::{self, path::constraint::PathVar},
     rand::{fuzz::Fuzz, OsRng, Rand, Sample},
 };
+use manta_util::codec::{Decode, Encode};
 
 /// Tests the generation of proving/verifying contexts for [`ToPrivate`].
 #[test]
@@ -39,6 +63,95 @@ fn sample_to_private_context() {
     ProofSystem::compile(&(), cs, &mut rng).expect("Unable to generate ToPrivate context.");
 }
 
+/// Tests that the verifying context extracted from a proving context matches the verifying
+/// context generated with it, and only that one.
+#[test]
+fn extracted_verifying_context_consistency() {
+    let mut rng = OsRng;
+    let parameters = rng.gen();
+    let utxo_accumulator_model = rng.gen();
+    let full_parameters = FullParametersRef::new(&parameters, &utxo_accumulator_model);
+    let (proving_context, verifying_context) =
+        ToPrivate::generate_context(&(), full_parameters, &mut rng)
+            .expect("Unable to create proving and verifying contexts.");
+    let (other_proving_context, other_verifying_context) =
+        ToPrivate::generate_context(&(), full_parameters, &mut rng)
+            .expect("Unable to create proving and verifying contexts.");
+    let checksum = verifying_context_checksum(&verifying_context);
+    assert_eq!(
+        verifying_context_checksum(
+            &proving_context
+                .verifying_context()
+                .expect("Unable to extract the verifying context.")
+        ),
+        checksum,
+        "The extracted verifying context should match the generated one."
+    );
+    assert!(check_proving_context(&proving_context, &checksum));
+    assert!(!check_proving_context(
+        &proving_context,
+        &verifying_context_checksum(&other_verifying_context)
+    ));
+    let proving_context = MultiProvingContext {
+        to_private: proving_context.clone(),
+        private_transfer: proving_context.clone(),
+        to_public: other_proving_context,
+        private_swap: None,
+    };
+    let mut verifying_context = MultiVerifyingContext {
+        to_private: verifying_context.clone(),
+        private_transfer: verifying_context.clone(),
+        to_public: verifying_context,
+        private_swap: None,
+    };
+    assert_eq!(
+        check_contexts(&proving_context, &verifying_context),
+        Err(ContextMismatch(TransferShape::ToPublic))
+    );
+    verifying_context.to_public = other_verifying_context;
+    assert_eq!(check_contexts(&proving_context, &verifying_context), Ok(()));
+}
+
+/// Tests that proofs verify against a verifying context rebuilt from the verifying key alone, which
+/// is all that verification-only builds have access to.
+#[test]
+fn verifying_key_only_verification() {
+    let mut rng = OsRng;
+    let parameters = rng.gen();
+    let mut utxo_accumulator = UtxoAccumulator::new(rng.gen());
+    let (proving_context, verifying_context) = ToPrivate::generate_context(
+        &(),
+        FullParametersRef::new(&parameters, utxo_accumulator.model()),
+        &mut rng,
+    )
+    .expect("Unable to create proving and verifying contexts.");
+    assert_eq!(
+        proving_context.verifying_key(),
+        verifying_context.verifying_key(),
+        "The proving key should embed the generated verifying key."
+    );
+    let post = ToPrivate::sample_post(
+        &proving_context,
+        &parameters,
+        &mut utxo_accumulator,
+        None,
+        &mut rng,
+    )
+    .expect("Random ToPrivate should have produced a proof.")
+    .expect("Random ToPrivate should have generated a TransferPost.");
+    let rebuilt = VerifyingContext::new(verifying_context.verifying_key())
+        .expect("Unable to rebuild the verifying context.");
+    let decoded = VerifyingContext::from_vec(rebuilt.to_vec())
+        .expect("Decoding the verifying context is not allowed to fail.");
+    assert_eq!(
+        verifying_context_checksum(&decoded),
+        verifying_context_checksum(&verifying_context)
+    );
+    assert!(post
+        .has_valid_proof(&decoded)
+        .expect("Unable to verify proof."));
+}
+
 /// Tests the generation of proving/verifying contexts for [`PrivateTransfer`].
 #[test]
 fn sample_private_transfer_context() {
@@ -57,6 +170,285 @@ fn sample_to_public_context() {
     ProofSystem::compile(&(), cs, &mut rng).expect("Unable to generate ToPublic context.");
 }
 
+/// Tests that the instrumented compiler attributes the [`PrivateTransfer`] constraints to the
+/// circuit regions.
+#[test]
+fn profile_private_transfer_regions() {
+    let mut rng = OsRng;
+    let mut cs = Compiler::for_contexts().instrumented();
+    PrivateTransfer::build_unknown_constraints(
+        FullParametersRef::new(&rng.gen(), &rng.gen()),
+        &mut cs,
+    );
+    let summary = cs.profiler().expect("Compiler is instrumented.").summary();
+    assert!(
+        summary
+            .iter()
+            .all(|region| region.size.constraint_count > 0),
+        "Every region of the circuit should add constraints."
+    );
+    let size = |path: &str| {
+        summary
+            .iter()
+            .find(|region| region.path == path)
+            .map(|region| region.size)
+            .unwrap_or_default()
+    };
+    assert!(
+        size("sender/membership").constraint_count < size("sender").constraint_count,
+        "Membership proofs should be nested in the sender regions."
+    );
+    let count = |path: &str| {
+        summary
+            .iter()
+            .find(|region| region.path == path)
+            .map(|region| region.count)
+    };
+    assert_eq!(count("authorization"), Some(1));
+    assert_eq!(count("sender"), Some(2));
+    assert_eq!(count("sender/membership"), Some(2));
+    assert_eq!(count("receiver"), Some(2));
+    assert_eq!(count("balance"), Some(1));
+    let total = cs.measure();
+    let regions = summary
+        .iter()
+        .filter(|region| !region.path.contains('/'))
+        .fold(Size::default(), |size, region| size + region.size);
+    assert!(regions.constraint_count <= total.constraint_count);
+    assert!(regions.secret_variable_count <= total.secret_variable_count);
+}
+
+/// Tests that the [`CircuitStatement`](crate::config::statement::CircuitStatement)s describe the
+/// whole public input of the transfer circuits and attribute their constraints to the circuit
+/// regions.
+#[test]
+fn circuit_statements_match_circuits() {
+    let mut rng = OsRng;
+    let parameters = rng.gen();
+    let utxo_accumulator_model = rng.gen();
+    let statements = circuit_statements(&parameters, &utxo_accumulator_model);
+    assert_eq!(statements.len(), 4);
+    for statement in &statements {
+        let (sources, senders, receivers, sinks) = statement.shape.arity();
+        assert_eq!(statement.identifier, statement.shape.identifier());
+        assert_eq!(
+            Some(statement.public_input_length() + 1),
+            statement.size.public_variable_count,
+            "The public input of {:?} should cover every public variable but the constant one.",
+            statement.shape,
+        );
+        let mut offset = 0;
+        for input in &statement.public_inputs {
+            assert_eq!(input.offset, offset);
+            assert!(input.length > 0, "{} should not be empty.", input.name);
+            offset += input.length;
+        }
+        let count = |prefix: &str| {
+            statement
+                .public_inputs
+                .iter()
+                .filter(|input| input.name.starts_with(prefix))
+                .count()
+        };
+        assert_eq!(count("authorization_key"), usize::from(senders > 0));
+        assert_eq!(count("asset_id"), usize::from(sources + sinks > 0));
+        assert_eq!(
+            (
+                count("sources"),
+                count("senders"),
+                count("receivers"),
+                count("sinks")
+            ),
+            (sources, senders, receivers, sinks)
+        );
+        assert_eq!(
+            statement
+                .constraint_group("receiver")
+                .map(|group| group.count),
+            Some(receivers)
+        );
+        assert_eq!(
+            statement
+                .constraint_group("sender")
+                .map(|group| group.count),
+            (senders > 0).then_some(senders)
+        );
+    }
+    assert_eq!(
+        statements[1],
+        circuit_statement(
+            TransferShape::PrivateTransfer,
+            &parameters,
+            &utxo_accumulator_model
+        ),
+        "Circuit statements should be deterministic."
+    );
+}
+
+/// Tests that the canonical shape table assigns distinct identifiers and the expected arities to
+/// the transfer shapes, and that the shapes are recovered from their arities and from posts.
+#[test]
+fn canonical_shape_table() {
+    let shapes = [
+        (TransferShape::ToPrivate, "to-private", (1, 0, 1, 0)),
+        (
+            TransferShape::PrivateTransfer,
+            "private-transfer",
+            (0, 2, 2, 0),
+        ),
+        (TransferShape::ToPublic, "to-public", (0, 2, 1, 1)),
+        (TransferShape::PrivateSwap, "private-swap", (0, 4, 4, 0)),
+    ];
+    for (shape, identifier, arity) in shapes {
+        assert_eq!(shape.identifier(), identifier);
+        assert_eq!(shape.arity(), arity);
+        let (sources, senders, receivers, sinks) = arity;
+        let has_authorization = transfer::requires_authorization(senders);
+        let has_visible_asset_id = transfer::has_public_participants(sources, sinks);
+        assert_eq!(
+            TransferShape::select(
+                has_authorization,
+                has_visible_asset_id,
+                sources,
+                senders,
+                receivers,
+                sinks
+            ),
+            Some(shape)
+        );
+        assert_eq!(
+            TransferShape::select(
+                !has_authorization,
+                has_visible_asset_id,
+                sources,
+                senders,
+                receivers,
+                sinks
+            ),
+            None,
+            "The authorization of {shape:?} should be part of its shape."
+        );
+    }
+    assert_eq!(TransferShape::select(true, false, 0, 3, 3, 0), None);
+    for post in corpus_posts() {
+        let shape = TransferShape::from_post(&post).expect("Corpus posts have canonical shapes.");
+        assert_eq!(
+            shape.arity(),
+            (
+                post.body.sources.len(),
+                post.body.sender_posts.len(),
+                post.body.receiver_posts.len(),
+                post.body.sinks.len()
+            )
+        );
+    }
+}
+
+/// UTXO Accumulator Merkle Tree Configuration with Compact Swaps
+///
+/// Same as [`MerkleTreeConfiguration`] but with
+/// [`COMPACT_SWAP`](merkle_tree::HashConfiguration::COMPACT_SWAP) set.
+#[derive(Clone, Copy, Debug, Default)]
+struct CompactMerkleTreeConfiguration;
+
+impl merkle_tree::HashConfiguration for CompactMerkleTreeConfiguration {
+    type LeafHash = utxo::LeafHash;
+    type InnerHash = utxo::InnerHash;
+    const COMPACT_SWAP: bool = true;
+}
+
+impl merkle_tree::HashConfiguration<Compiler> for CompactMerkleTreeConfiguration {
+    type LeafHash = utxo::LeafHashVar;
+    type InnerHash = utxo::InnerHash<Compiler>;
+    const COMPACT_SWAP: bool = true;
+}
+
+impl merkle_tree::Configuration for CompactMerkleTreeConfiguration {
+    const HEIGHT: usize = <MerkleTreeConfiguration as merkle_tree::Configuration>::HEIGHT;
+}
+
+impl merkle_tree::Configuration<Compiler> for CompactMerkleTreeConfiguration {
+    const HEIGHT: usize = <MerkleTreeConfiguration as merkle_tree::Configuration>::HEIGHT;
+}
+
+impl Constant<Compiler> for CompactMerkleTreeConfiguration {
+    type Type = Self;
+
+    #[inline]
+    fn new_constant(this: &Self::Type, compiler: &mut Compiler) -> Self {
+        let _ = (this, compiler);
+        Self
+    }
+}
+
+/// Returns the number of constraints of a membership proof over `utxo_accumulator_model` for the
+/// merkle tree configuration `C`, and the number of constraints of a single hash of its path.
+#[inline]
+fn membership_constraints<C>(utxo_accumulator_model: &UtxoAccumulatorModel) -> (usize, usize)
+where
+    C: merkle_tree::Configuration<LeafHash = utxo::LeafHash, InnerHash = utxo::InnerHash>
+        + merkle_tree::Configuration<
+            Compiler,
+            LeafHash = utxo::LeafHashVar,
+            InnerHash = utxo::InnerHash<Compiler>,
+        > + Constant<Compiler, Type = C>,
+{
+    let mut compiler = Compiler::for_contexts();
+    let model = utxo_accumulator_model.as_constant::<UtxoAccumulatorModelVar>(&mut compiler);
+    let model = merkle_tree::Parameters::<C, Compiler>::new(model.leaf, model.inner);
+    let path = compiler.allocate_unknown::<Secret, PathVar<C, Compiler>>();
+    let leaf = compiler.allocate_unknown::<Secret, UtxoAccumulatorItemVar>();
+    let sibling = compiler.allocate_unknown::<Secret, UtxoAccumulatorItemVar>();
+    let before = compiler.constraint_count();
+    let _ = model.join_leaves_with(&leaf, &sibling, &mut compiler);
+    let hash = compiler.constraint_count() - before;
+    let before = compiler.constraint_count();
+    let _ = path.root(&model, &leaf, &mut compiler);
+    (compiler.constraint_count() - before, hash)
+}
+
+/// Measures the UTXO accumulator membership proofs, checking that the published configuration
+/// still orders the digests of a path by selecting each of them separately, and that the compact
+/// swap saves one constraint per hash.
+#[test]
+fn membership_proof_constraints() {
+    let mut rng = OsRng;
+    let parameters = rng.gen();
+    let utxo_accumulator_model: UtxoAccumulatorModel = rng.gen();
+    let hashes = <MerkleTreeConfiguration as merkle_tree::Configuration>::HEIGHT - 1;
+    let (membership, hash) =
+        membership_constraints::<MerkleTreeConfiguration>(&utxo_accumulator_model);
+    assert_eq!(
+        membership,
+        hashes * (hash + 2),
+        "Every level of the published path should cost one hash and two select constraints."
+    );
+    let (compact_membership, compact_hash) =
+        membership_constraints::<CompactMerkleTreeConfiguration>(&utxo_accumulator_model);
+    assert_eq!(compact_hash, hash);
+    assert_eq!(
+        membership - compact_membership,
+        hashes,
+        "Compact swaps should save one constraint per hash."
+    );
+    for statement in circuit_statements(&parameters, &utxo_accumulator_model) {
+        let (_, senders, _, _) = statement.shape.arity();
+        if senders == 0 {
+            assert!(statement.constraint_group("sender/membership").is_none());
+            continue;
+        }
+        let group = statement
+            .constraint_group("sender/membership")
+            .expect("Every sender proves the membership of its UTXO.");
+        assert_eq!(group.count, senders);
+        assert!(
+            group.size.constraint_count >= senders * membership,
+            "Every sender of {:?} should contain a membership proof.",
+            statement.shape
+        );
+    }
+}
+
 /// Tests the generation of a [`ToPrivate`].
 #[test]
 fn to_private() {
@@ -195,15 +587,17 @@ fn to_public_check_signature() {
         post.has_valid_authorization_signature(&parameters).is_ok(),
         "Invalid signature."
     );
-    let fuzzed_account = vec![post.sink_accounts[0]
+    let fuzzed_account = PostParticipants::try_from_array([post.sink_accounts[0]
         .to_vec()
         .fuzz(&mut rng)
         .try_into()
-        .expect("Getting an array from a vector of equal length is not allowed to fail")];
+        .expect("Getting an array from a vector of equal length is not allowed to fail")])
+    .expect("A single sink account is within the participant bound.");
     let new_post = TransferPost {
         authorization_signature: post.authorization_signature,
         body: post.body.clone(),
         sink_accounts: fuzzed_account,
+        epoch: post.epoch,
     };
     assert!(
         new_post
@@ -213,6 +607,98 @@ fn to_public_check_signature() {
     );
 }
 
+/// Checks that each [`AuthorizationPolicy`] accepts exactly the hybrid signatures it requires for a
+/// random [`PrivateTransfer`].
+///
+/// [`AuthorizationPolicy`]: manta_accounting::transfer::hybrid::AuthorizationPolicy
+#[cfg(feature = "hybrid-authorization")]
+#[test]
+fn private_transfer_check_hybrid_signature() {
+    use crate::config::{HybridTransferPost, PostQuantumSignatureScheme};
+    use manta_accounting::transfer::{hybrid::AuthorizationPolicy, InvalidAuthorizationSignature};
+    let mut rng = OsRng;
+    let parameters = rng.gen();
+    let mut utxo_accumulator = UtxoAccumulator::new(rng.gen());
+    let (proving_context, _) = PrivateTransfer::generate_context(
+        &(),
+        FullParametersRef::new(&parameters, utxo_accumulator.model()),
+        &mut rng,
+    )
+    .expect("Unable to create proving and verifying contexts.");
+    let spending_key = rng.gen();
+    let post = PrivateTransfer::sample_post(
+        &proving_context,
+        &parameters,
+        &mut utxo_accumulator,
+        Some(&spending_key),
+        &mut rng,
+    )
+    .expect("Random Private Transfer should have produced a proof.")
+    .expect("");
+    let scheme = PostQuantumSignatureScheme::default();
+    let post = HybridTransferPost::sign(&scheme, &spending_key, post);
+    let check = |post: &HybridTransferPost, policy| {
+        post.has_valid_authorization(&parameters, &scheme, policy)
+    };
+    for policy in [
+        AuthorizationPolicy::Classical,
+        AuthorizationPolicy::PostQuantum,
+        AuthorizationPolicy::Either,
+        AuthorizationPolicy::Both,
+    ] {
+        assert_eq!(check(&post, policy), Ok(()), "Invalid hybrid signature.");
+    }
+    let mut bad_post_quantum = post.clone();
+    bad_post_quantum
+        .post_quantum_signature
+        .as_mut()
+        .expect("The post-quantum signature was generated above.")
+        .signature
+        .points[0][0] ^= 1;
+    assert_eq!(
+        check(&bad_post_quantum, AuthorizationPolicy::Classical),
+        Ok(())
+    );
+    assert_eq!(
+        check(&bad_post_quantum, AuthorizationPolicy::PostQuantum),
+        Err(InvalidAuthorizationSignature::BadSignature)
+    );
+    assert_eq!(
+        check(&bad_post_quantum, AuthorizationPolicy::Either),
+        Ok(())
+    );
+    assert_eq!(
+        check(&bad_post_quantum, AuthorizationPolicy::Both),
+        Err(InvalidAuthorizationSignature::BadSignature)
+    );
+    let mut bad_classical = post.clone();
+    bad_classical
+        .post
+        .authorization_signature
+        .as_mut()
+        .expect("Private transfers are signed.")
+        .signature
+        .scalar = rng.gen();
+    assert_eq!(
+        check(&bad_classical, AuthorizationPolicy::Classical),
+        Err(InvalidAuthorizationSignature::BadSignature)
+    );
+    assert_eq!(
+        check(&bad_classical, AuthorizationPolicy::PostQuantum),
+        Ok(())
+    );
+    assert_eq!(check(&bad_classical, AuthorizationPolicy::Either), Ok(()));
+    assert_eq!(
+        check(&bad_classical, AuthorizationPolicy::Both),
+        Err(InvalidAuthorizationSignature::BadSignature)
+    );
+    let unsigned = HybridTransferPost::new_unchecked(post.post, None);
+    assert_eq!(
+        check(&unsigned, AuthorizationPolicy::PostQuantum),
+        Err(InvalidAuthorizationSignature::MissingSignature)
+    );
+}
+
 /// Checks that the zero signature is rejected for a random [`PrivateTransfer`].
 #[test]
 fn private_transfer_check_zero_signature() {
@@ -416,6 +902,563 @@ fn to_public_proof_validity() {
     validity_check_with_fuzzing(&verifying_context, &post, &mut rng);
 }
 
+/// Tests that a [`PrivateTransfer`] proof built with a memory budget which is too small for the
+/// default prover is built with the low-memory prover and is valid.
+#[test]
+fn private_transfer_low_memory_proof_validity() {
+    let mut rng = OsRng;
+    let parameters = rng.gen();
+    let mut utxo_accumulator = UtxoAccumulator::new(rng.gen());
+    let (mut proving_context, verifying_context) = PrivateTransfer::generate_context(
+        &(),
+        FullParametersRef::new(&parameters, utxo_accumulator.model()),
+        &mut rng,
+    )
+    .expect("Unable to create proving and verifying contexts.");
+    proving_context.set_configuration(ProverConfiguration::with_memory_budget(0));
+    assert_eq!(
+        proving_context.strategy(&PrivateTransfer::unknown_constraints(
+            FullParametersRef::new(&parameters, utxo_accumulator.model())
+        )),
+        ProvingStrategy::LowMemory {
+            chunk_size: MIN_CHUNK_SIZE
+        },
+        "Budgets which are too small for any strategy should get the smallest chunks."
+    );
+    let post = PrivateTransfer::sample_post(
+        &proving_context,
+        &parameters,
+        &mut utxo_accumulator,
+        Some(&rng.gen()),
+        &mut rng,
+    )
+    .expect("Random Private Transfer should have produced a proof.")
+    .expect("Random Private Transfer should have generated a TransferPost.");
+    validity_check_with_fuzzing(&verifying_context, &post, &mut rng);
+}
+
+/// Tests that [`PrivateTransfer`] proofs built against the circuit structure cached in their
+/// proving context are valid, with both proving strategies, and that the structure of another
+/// transfer shape is not cached.
+#[test]
+fn private_transfer_cached_synthesis_proof_validity() {
+    let mut rng = OsRng;
+    let parameters = rng.gen();
+    let mut utxo_accumulator = UtxoAccumulator::new(rng.gen());
+    let (mut proving_context, verifying_context) = PrivateTransfer::generate_context(
+        &(),
+        FullParametersRef::new(&parameters, utxo_accumulator.model()),
+        &mut rng,
+    )
+    .expect("Unable to create proving and verifying contexts.");
+    assert!(
+        !ToPrivate::cache_synthesis(
+            FullParametersRef::new(&parameters, utxo_accumulator.model()),
+            &mut proving_context
+        ),
+        "The ToPrivate circuit should not be cached in a PrivateTransfer proving context."
+    );
+    assert!(
+        PrivateTransfer::cache_synthesis(
+            FullParametersRef::new(&parameters, utxo_accumulator.model()),
+            &mut proving_context
+        ),
+        "The PrivateTransfer circuit should be cached in its own proving context."
+    );
+    assert!(proving_context.is_synthesis_cached());
+    for configuration in [
+        ProverConfiguration::default(),
+        ProverConfiguration::with_memory_budget(0),
+    ] {
+        proving_context.set_configuration(configuration);
+        for _ in 0..2 {
+            let post = PrivateTransfer::sample_post(
+                &proving_context,
+                &parameters,
+                &mut utxo_accumulator,
+                Some(&rng.gen()),
+                &mut rng,
+            )
+            .expect("Random Private Transfer should have produced a proof.")
+            .expect("Random Private Transfer should have generated a TransferPost.");
+            validity_check_with_fuzzing(&verifying_context, &post, &mut rng);
+        }
+    }
+}
+
+/// Tests that a [`DynamicTransfer`] with a shape that has no const generic transfer type is proven
+/// with the context registered for its shape, and that transfers of unregistered shapes are
+/// rejected.
+#[test]
+fn dynamic_transfer_proof_validity() {
+    let mut rng = OsRng;
+    let parameters = rng.gen();
+    let mut utxo_accumulator = UtxoAccumulator::new(rng.gen());
+    let spending_key = rng.gen();
+    let shape = Shape::new(0, 3, 2, 1);
+    let mut registry = ProvingContextRegistry::new();
+    let verifying_context = registry
+        .generate(
+            shape,
+            &(),
+            FullParametersRef::new(&parameters, utxo_accumulator.model()),
+            &mut rng,
+        )
+        .expect("Unable to create proving and verifying contexts.");
+    let transfer = DynamicTransfer::sample_with_shape(
+        shape,
+        TransferDistribution::from_spending_key(
+            &parameters,
+            &mut utxo_accumulator,
+            &spending_key,
+            &mut rng,
+        ),
+        &mut rng,
+    )
+    .expect("The shape is valid.");
+    assert_eq!(transfer.shape(), shape);
+    let post = registry
+        .into_post(
+            transfer,
+            FullParametersRef::new(&parameters, utxo_accumulator.model()),
+            Some(&spending_key),
+            vec![rng.gen()],
+            &mut rng,
+        )
+        .expect("Random DynamicTransfer should have produced a proof.")
+        .expect("Random DynamicTransfer should have generated a TransferPost.");
+    validity_check_with_fuzzing(&verifying_context, &post, &mut rng);
+    let transfer = DynamicTransfer::sample_with_shape(
+        TransferShape::ToPrivate.into(),
+        TransferDistribution::new(&parameters, &mut utxo_accumulator, None),
+        &mut rng,
+    )
+    .expect("The shape is valid.");
+    assert!(matches!(
+        registry.into_post(
+            transfer,
+            FullParametersRef::new(&parameters, utxo_accumulator.model()),
+            None,
+            Vec::new(),
+            &mut rng,
+        ),
+        Err(ProvingError::MissingContext(missing)) if missing == TransferShape::ToPrivate.into()
+    ));
+}
+
+/// Tests that a private transfer which pays a fee reveals its asset id and binds the fee in its
+/// proof.
+#[test]
+fn fee_transfer_proof_validity() {
+    let mut rng = OsRng;
+    let parameters = rng.gen();
+    let mut utxo_accumulator = UtxoAccumulator::new(rng.gen());
+    let spending_key = rng.gen();
+    let shape = Shape::new(0, 2, 2, 0).with_fee();
+    let mut registry = ProvingContextRegistry::new();
+    let verifying_context = registry
+        .generate(
+            shape,
+            &(),
+            FullParametersRef::new(&parameters, utxo_accumulator.model()),
+            &mut rng,
+        )
+        .expect("Unable to create proving and verifying contexts.");
+    let transfer = DynamicTransfer::sample_with_shape(
+        shape,
+        TransferDistribution::from_spending_key(
+            &parameters,
+            &mut utxo_accumulator,
+            &spending_key,
+            &mut rng,
+        ),
+        &mut rng,
+    )
+    .expect("The shape is valid.");
+    assert_eq!(transfer.shape(), shape);
+    let fee = transfer.fee().expect("The shape pays a fee.");
+    let post = registry
+        .into_post(
+            transfer,
+            FullParametersRef::new(&parameters, utxo_accumulator.model()),
+            Some(&spending_key),
+            Vec::new(),
+            &mut rng,
+        )
+        .expect("Random DynamicTransfer should have produced a proof.")
+        .expect("Random DynamicTransfer should have generated a TransferPost.");
+    assert_eq!(post.fee_asset(), Some(fee));
+    assert_eq!(
+        TransferShape::from_post(&post),
+        None,
+        "Posts which pay a fee do not have a canonical shape."
+    );
+    assert_eq!(
+        TransferPost::from_vec(post.to_vec()).ok().as_ref(),
+        Some(&post),
+        "Posts which pay a fee should survive an encoding round trip."
+    );
+    validity_check_with_fuzzing(&verifying_context, &post, &mut rng);
+    let mut unpaid = post.clone();
+    unpaid.body.asset_id = None;
+    unpaid.body.fee = None;
+    assert!(
+        !matches!(unpaid.has_valid_proof(&verifying_context), Ok(true)),
+        "The proof of a post which pays a fee should not verify without it."
+    );
+}
+
+/// Tests that a fee is rejected without a public asset id when building a transfer, and that the
+/// encoding of a post with such a fee keeps it, so that the post is not read back as a valid post
+/// without a fee.
+#[test]
+fn fee_without_asset_id() {
+    let mut rng = OsRng;
+    let shape = Shape::new(0, 2, 2, 0);
+    assert_eq!(shape.check(true, false), Ok(()));
+    assert_eq!(
+        shape.with_fee().check(true, false),
+        Err(ShapeError::MissingAssetId)
+    );
+    assert!(
+        std::panic::catch_unwind(|| PrivateTransfer::check_shape_with_fee(true, false, true))
+            .is_err(),
+        "Building a transfer with a fee but without an asset id should fail."
+    );
+    let mut post = corpus_posts()
+        .into_iter()
+        .find(|post| post.body.asset_id.is_none())
+        .expect("The corpus contains posts without an asset id.");
+    post.body.fee = Some(rng.gen());
+    assert_eq!(post.fee_asset(), None);
+    assert_eq!(
+        TransferPost::from_vec(post.to_vec()).ok().as_ref(),
+        Some(&post),
+        "The fee should survive an encoding round trip without an asset id."
+    );
+}
+
+/// Tests that memos attached to receivers are recovered by their owners only, and that they
+/// survive an encoding round trip of the posts which carry them.
+#[test]
+fn private_transfer_memo_round_trip() {
+    let mut rng = OsRng;
+    let parameters = Parameters::gen(&mut rng);
+    let mut utxo_accumulator = UtxoAccumulator::new(rng.gen());
+    let spending_key = rng.gen();
+    let address = parameters.address_from_spending_key(&spending_key);
+    let mut authorization = Authorization::from_spending_key(&parameters, &spending_key, &mut rng);
+    let decryption_key = parameters.derive_decryption_key(&mut authorization.context);
+    let plaintext = MemoPlaintext::from([7; utxo::MEMO_SIZE]);
+    let mut receiver = Receiver::sample(
+        &parameters,
+        address,
+        rng.gen(),
+        Default::default(),
+        &mut rng,
+    );
+    receiver.attach_memo(&parameters, &address, &plaintext, &mut rng);
+    let post = receiver.into_post();
+    let (_, _, memo) = parameters
+        .try_open_memo_with_check(&decryption_key, &post.utxo, &post.note)
+        .expect("The receiver should be able to open its note.");
+    assert_eq!(memo, Some(plaintext));
+    let mut receiver = Receiver::sample(
+        &parameters,
+        address,
+        rng.gen(),
+        Default::default(),
+        &mut rng,
+    );
+    receiver.attach_memo(
+        &parameters,
+        &parameters.address_from_spending_key(&rng.gen()),
+        &plaintext,
+        &mut rng,
+    );
+    let post = receiver.into_post();
+    let (_, _, memo) = parameters
+        .try_open_memo_with_check(&decryption_key, &post.utxo, &post.note)
+        .expect("The receiver should be able to open its note.");
+    assert_eq!(
+        memo, None,
+        "Memos encrypted to other addresses should not be recovered."
+    );
+
+    let (proving_context, _) = PrivateTransfer::generate_context(
+        &(),
+        FullParametersRef::new(&parameters, utxo_accumulator.model()),
+        &mut rng,
+    )
+    .expect("Unable to create proving and verifying contexts.");
+    let mut post = PrivateTransfer::sample_post(
+        &proving_context,
+        &parameters,
+        &mut utxo_accumulator,
+        Some(&spending_key),
+        &mut rng,
+    )
+    .expect("Random Private Transfer should have produced a proof.")
+    .expect("");
+    let unmarked = post.to_vec();
+    assert_eq!(
+        TransferPost::from_vec(unmarked.clone()).ok().as_ref(),
+        Some(&post)
+    );
+    let memo = parameters.encrypt_memo(&address, &plaintext, &mut rng);
+    post.body.receiver_posts[1].set_memo(Some(memo));
+    let marked = post.to_vec();
+    assert!(marked.len() > unmarked.len());
+    let decoded = TransferPost::from_vec(marked).expect("Posts with memos should decode.");
+    assert_eq!(decoded.body.receiver_posts[0].memo(), None);
+    assert_eq!(decoded.body.receiver_posts[1].memo(), Some(&memo));
+    assert_eq!(
+        decoded, post,
+        "Posts with memos should survive an encoding round trip."
+    );
+}
+
+/// Samples a [`PrivateSwap`] owned by `spending_key` which spends the `senders` and creates the
+/// `receivers`, with the first asset in the first group of each and the second asset in the
+/// other one.
+#[inline]
+fn sample_private_swap(
+    parameters: &Parameters,
+    utxo_accumulator: &mut UtxoAccumulator,
+    spending_key: &SpendingKey,
+    senders: [[Asset; 2]; 2],
+    receivers: [[Asset; 2]; 2],
+    rng: &mut OsRng,
+) -> PrivateSwap {
+    let mut authorization = Authorization::from_spending_key(parameters, spending_key, rng);
+    let address = parameters.address_from_spending_key(spending_key);
+    let senders = senders.map(|group| {
+        group.map(|asset| {
+            transfer::PreSender::<Config>::sample(
+                parameters,
+                &mut authorization.context,
+                rng.gen(),
+                asset,
+                rng,
+            )
+            .insert_and_upgrade(parameters, utxo_accumulator)
+            .expect("Insertion and upgrading should not fail.")
+        })
+    });
+    let receivers = receivers.map(|group| {
+        group.map(|asset| Receiver::sample(parameters, address, asset, Default::default(), rng))
+    });
+    PrivateSwap::build(authorization, senders, receivers)
+}
+
+/// Tests that a [`PrivateSwap`] moves two assets in one proof, with a balance equation for each
+/// of them, and that its posts are recognized as [`TransferShape::PrivateSwap`].
+#[test]
+fn private_swap_proof_validity() {
+    let mut rng = OsRng;
+    let parameters = Parameters::gen(&mut rng);
+    let mut utxo_accumulator = UtxoAccumulator::new(rng.gen());
+    let spending_key = rng.gen();
+    let first: AssetId = rng.gen();
+    let second: AssetId = rng.gen();
+    let (proving_context, verifying_context) = PrivateSwap::generate_context(
+        &(),
+        FullParametersRef::new(&parameters, utxo_accumulator.model()),
+        &mut rng,
+    )
+    .expect("Unable to create proving and verifying contexts.");
+    let swap = sample_private_swap(
+        &parameters,
+        &mut utxo_accumulator,
+        &spending_key,
+        [
+            [Asset::new(first, 3), Asset::new(first, 7)],
+            [Asset::new(second, 5), Asset::new(second, 5)],
+        ],
+        [
+            [Asset::new(first, 4), Asset::new(first, 6)],
+            [Asset::new(second, 1), Asset::new(second, 9)],
+        ],
+        &mut rng,
+    );
+    assert!(swap
+        .known_constraints(FullParametersRef::new(
+            &parameters,
+            utxo_accumulator.model()
+        ))
+        .is_satisfied());
+    let proof_input = swap.generate_proof_input();
+    let post = swap
+        .into_post(
+            FullParametersRef::new(&parameters, utxo_accumulator.model()),
+            &proving_context,
+            &spending_key,
+            &mut rng,
+        )
+        .expect("Random PrivateSwap should have produced a proof.")
+        .expect("Random PrivateSwap should have generated a TransferPost.");
+    assert_eq!(
+        TransferShape::from_post(&post),
+        Some(TransferShape::PrivateSwap)
+    );
+    assert_eq!(
+        post.body.asset_id, None,
+        "Private swaps do not reveal assets."
+    );
+    assert_eq!(post.generate_proof_input(), proof_input);
+    validity_check_with_fuzzing(&verifying_context, &post, &mut rng);
+    let mut multi_verifying_context = MultiVerifyingContext {
+        to_private: verifying_context.clone(),
+        private_transfer: verifying_context.clone(),
+        to_public: verifying_context.clone(),
+        private_swap: None,
+    };
+    assert!(multi_verifying_context
+        .try_select(TransferShape::PrivateSwap)
+        .is_none());
+    multi_verifying_context.private_swap = Some(verifying_context);
+    assert!(matches!(
+        multi_verifying_context
+            .try_select(TransferShape::PrivateSwap)
+            .map(|verifying_context| post.has_valid_proof(verifying_context)),
+        Some(Ok(true))
+    ));
+    for (senders, receivers) in [
+        (
+            [
+                [Asset::new(first, 3), Asset::new(first, 7)],
+                [Asset::new(second, 5), Asset::new(second, 5)],
+            ],
+            [
+                [Asset::new(first, 5), Asset::new(first, 6)],
+                [Asset::new(second, 1), Asset::new(second, 8)],
+            ],
+        ),
+        (
+            [
+                [Asset::new(first, 3), Asset::new(first, 7)],
+                [Asset::new(second, 5), Asset::new(second, 5)],
+            ],
+            [
+                [Asset::new(first, 4), Asset::new(second, 6)],
+                [Asset::new(second, 1), Asset::new(first, 9)],
+            ],
+        ),
+    ] {
+        assert!(
+            !sample_private_swap(
+                &parameters,
+                &mut utxo_accumulator,
+                &spending_key,
+                senders,
+                receivers,
+                &mut rng,
+            )
+            .known_constraints(FullParametersRef::new(
+                &parameters,
+                utxo_accumulator.model()
+            ))
+            .is_satisfied(),
+            "Every asset of a private swap should balance on its own."
+        );
+    }
+}
+
+/// Tests that [`DynamicTransfer`]s are built only with the shapes accepted by [`Transfer`].
+///
+/// [`Transfer`]: transfer::Transfer
+#[test]
+fn dynamic_transfer_shape_check() {
+    assert_eq!(
+        DynamicTransfer::new(None, None, vec![], vec![], vec![], vec![]).err(),
+        Some(ShapeError::EmptyInput)
+    );
+    assert_eq!(
+        Shape::new(1, 0, 0, 0).check(false, true),
+        Err(ShapeError::EmptyOutput)
+    );
+    assert_eq!(
+        Shape::new(0, 17, 1, 0).check(true, false),
+        Err(ShapeError::TooManyParticipants)
+    );
+    assert_eq!(
+        Shape::new(0, 2, 2, 0).check(false, false),
+        Err(ShapeError::MissingAuthorization)
+    );
+    assert_eq!(
+        Shape::new(1, 0, 1, 0).check(true, true),
+        Err(ShapeError::UnexpectedAuthorization)
+    );
+    assert_eq!(
+        Shape::new(0, 2, 1, 1).check(true, false),
+        Err(ShapeError::MissingAssetId)
+    );
+    assert_eq!(
+        Shape::new(0, 2, 2, 0).check(true, true),
+        Err(ShapeError::UnexpectedAssetId)
+    );
+    assert_eq!(
+        Shape::new(0, 2, 2, 0).with_fee().check(true, false),
+        Err(ShapeError::MissingAssetId)
+    );
+    assert_eq!(Shape::new(0, 2, 2, 0).with_fee().check(true, true), Ok(()));
+    for shape in [
+        TransferShape::ToPrivate,
+        TransferShape::PrivateTransfer,
+        TransferShape::ToPublic,
+    ] {
+        let shape = Shape::from(shape);
+        assert_eq!(
+            shape.check(
+                shape.requires_authorization(),
+                shape.has_public_participants()
+            ),
+            Ok(())
+        );
+    }
+}
+
+/// Tests that [`ToPrivate`] proofs and verifying contexts round-trip through both group element
+/// encodings and that the decoded values still verify.
+#[test]
+fn to_private_compression_round_trip() {
+    let mut rng = OsRng;
+    let parameters = rng.gen();
+    let mut utxo_accumulator = UtxoAccumulator::new(rng.gen());
+    let (proving_context, verifying_context) = ToPrivate::generate_context(
+        &(),
+        FullParametersRef::new(&parameters, utxo_accumulator.model()),
+        &mut rng,
+    )
+    .expect("Unable to create proving and verifying contexts.");
+    let mut post = ToPrivate::sample_post(
+        &proving_context,
+        &parameters,
+        &mut utxo_accumulator,
+        None,
+        &mut rng,
+    )
+    .expect("Random ToPrivate should have produced a proof.")
+    .expect("Random ToPrivate should have generated a TransferPost.");
+    let proof_size = |compression| post.body.proof.to_bytes(compression).len();
+    assert!(
+        proof_size(Compression::Compressed) < proof_size(Compression::Uncompressed),
+        "Compressed proofs should be smaller than uncompressed ones."
+    );
+    for compression in [Compression::Compressed, Compression::Uncompressed] {
+        let verifying_context =
+            VerifyingContext::from_bytes(&verifying_context.to_bytes(compression))
+                .expect("Unable to decode the verifying context.");
+        post.body.proof = Proof::from_bytes(&post.body.proof.to_bytes(compression))
+            .expect("Unable to decode the proof.");
+        assert!(
+            post.has_valid_proof(&verifying_context)
+                .expect("Unable to verify the proof."),
+            "The decoded proof should have been valid for the decoded verifying context."
+        );
+    }
+}
+
 /// Measures the length in bytes of the serde_json and bincode encodings of a
 /// [`ToPrivate`] [`TransferPost`](manta_accounting::transfer::TransferPost)
 #[test]
@@ -499,3 +1542,167 @@ fn to_public_byte_measurement() {
     println!("Serde_json encoding: {:?}", serde_json_encoding.len());
     println!("Bincode encoding: {:?}", bincode_encoding.len());
 }
+
+/// Scripted Transfer Configuration
+///
+/// Transfer configuration of [`Config`] whose proofs follow a [`Script`] instead of being built
+/// with [`ProofSystem`], for testing how proof system errors are surfaced.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ScriptedConfig;
+
+impl transfer::Configuration for ScriptedConfig {
+    type Compiler = Compiler;
+    type AssetId = utxo::AssetId;
+    type AssetValue = utxo::AssetValue;
+    type AccountId = <Config as transfer::Configuration>::AccountId;
+    type AssociatedData = utxo::AssociatedData;
+    type Utxo = utxo::Utxo;
+    type Nullifier = utxo::Nullifier;
+    type Identifier = utxo::Identifier;
+    type Address = utxo::Address;
+    type Note = utxo::Note;
+    type MintSecret = utxo::MintSecret;
+    type SpendSecret = utxo::SpendSecret;
+    type UtxoAccumulatorWitness = utxo::UtxoAccumulatorWitness;
+    type UtxoAccumulatorOutput = utxo::UtxoAccumulatorOutput;
+    type UtxoAccumulatorItemHash = utxo::UtxoAccumulatorItemHash;
+    type TransactionIdFunction = TransactionIdHash;
+    type SpendingKey = utxo::SpendingKey;
+    type AuthorizationContext = utxo::AuthorizationContext;
+    type DecryptionKey = utxo::DecryptionKey;
+    type Parameters = utxo::Parameters;
+    type AuthorizationContextVar = utxo::AuthorizationContextVar;
+    type AuthorizationProofVar = utxo::AuthorizationProofVar;
+    type AssetIdVar = utxo::AssetIdVar;
+    type AssetValueVar = utxo::AssetValueVar;
+    type UtxoVar = utxo::UtxoVar;
+    type NoteVar = utxo::NoteVar;
+    type NullifierVar = utxo::NullifierVar;
+    type UtxoAccumulatorWitnessVar = utxo::UtxoAccumulatorWitnessVar;
+    type UtxoAccumulatorOutputVar = utxo::UtxoAccumulatorOutputVar;
+    type UtxoAccumulatorModelVar = utxo::UtxoAccumulatorModelVar;
+    type MintSecretVar = utxo::MintSecretVar;
+    type SpendSecretVar = utxo::SpendSecretVar;
+    type ParametersVar = utxo::ParametersVar;
+    type ProofSystem = Scripted<ProofSystem>;
+}
+
+/// Tests that proving and verification failures of the proof system are surfaced by transfers
+/// as errors, and that rejected proofs are reported as invalid.
+#[test]
+fn scripted_proof_system_failures() {
+    let mut rng = OsRng;
+    let parameters = rng.gen();
+    let mut utxo_accumulator = UtxoAccumulator::new(rng.gen());
+    let script = Script::new(Behavior::FailOn(1), Behavior::Succeed);
+    assert_eq!(
+        canonical::ToPrivate::<ScriptedConfig>::sample_and_check_proof(
+            &script,
+            &parameters,
+            &mut utxo_accumulator,
+            None,
+            &mut rng
+        ),
+        Ok(true),
+        "The first proof should have been built and verified."
+    );
+    assert_eq!(
+        canonical::ToPrivate::<ScriptedConfig>::sample_and_check_proof(
+            &script,
+            &parameters,
+            &mut utxo_accumulator,
+            None,
+            &mut rng
+        ),
+        Err(ScriptedError {
+            stage: Stage::Proving,
+            call: 1
+        }),
+        "The second proof should have failed."
+    );
+    assert_eq!(script.proofs(), 2);
+    assert_eq!(script.verifications(), 1);
+    for (verify, expected) in [
+        (
+            Behavior::Fail,
+            Err(ScriptedError {
+                stage: Stage::Verification,
+                call: 0,
+            }),
+        ),
+        (Behavior::Reject, Ok(false)),
+    ] {
+        assert_eq!(
+            canonical::PrivateTransfer::<ScriptedConfig>::sample_and_check_proof(
+                &Script::new(Behavior::Succeed, verify),
+                &parameters,
+                &mut utxo_accumulator,
+                Some(&rng.gen()),
+                &mut rng
+            ),
+            expected,
+            "Verification should have followed the script."
+        );
+    }
+}
+
+/// Tests that a [`VerifyingContextProvider`] loads a verifying context on the first request for its
+/// shape, keeps pinned shapes loaded, and evicts the other shapes past its capacity.
+#[test]
+fn verifying_context_provider_loads_on_demand() {
+    let mut rng = OsRng;
+    let parameters = rng.gen();
+    let mut utxo_accumulator = UtxoAccumulator::new(rng.gen());
+    let (proving_context, verifying_context) = ToPrivate::generate_context(
+        &(),
+        FullParametersRef::new(&parameters, utxo_accumulator.model()),
+        &mut rng,
+    )
+    .expect("Unable to create proving and verifying contexts.");
+    let post = ToPrivate::sample_post(
+        &proving_context,
+        &parameters,
+        &mut utxo_accumulator,
+        None,
+        &mut rng,
+    )
+    .expect("Random ToPrivate should have produced a proof.")
+    .expect("Random ToPrivate should have generated a TransferPost.");
+    let to_private = Shape::from(TransferShape::ToPrivate);
+    let private_transfer = Shape::from(TransferShape::PrivateTransfer);
+    let to_public = Shape::from(TransferShape::ToPublic);
+    let loads = Cell::new(0);
+    let provider = VerifyingContextProvider::with_capacity(
+        |shape: &Shape| {
+            loads.set(loads.get() + 1);
+            shape.canonical().map(|_| verifying_context.clone())
+        },
+        1,
+    );
+    assert!(provider.loaded_shapes().is_empty());
+    assert!(provider
+        .has_valid_proof(&post)
+        .expect("Unable to verify proof."));
+    assert!(provider
+        .has_valid_proof(&post)
+        .expect("Unable to verify proof."));
+    assert_eq!(loads.get(), 1, "The context should have been loaded once.");
+    assert!(provider.pin(private_transfer).is_some());
+    assert!(provider.get(&to_public).is_some());
+    assert_eq!(loads.get(), 3);
+    assert!(!provider.is_loaded(&to_private));
+    assert!(provider.is_loaded(&to_public));
+    assert!(provider.is_pinned(&private_transfer));
+    assert!(provider.get(&Shape::new(0, 3, 2, 1)).is_none());
+    assert_eq!(provider.loaded_shapes(), vec![to_public, private_transfer]);
+    assert!(!provider.evict(&private_transfer));
+    assert!(provider.unpin(&private_transfer));
+    assert!(!provider.is_loaded(&to_public));
+    assert!(provider.evict(&private_transfer));
+    assert!(provider.loaded_shapes().is_empty());
+    assert!(matches!(
+        VerifyingContextProvider::new(|_: &Shape| -> Option<VerifyingContext> { None })
+            .has_valid_proof(&post),
+        Err(VerifyingError::MissingContext(missing)) if missing == to_private
+    ));
+}
@@ -0,0 +1,551 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Key Derivation Test Vectors
+//!
+//! This module generates the reference test vectors for the derivation pipeline which starts at a
+//! [`Mnemonic`] and ends at the spending keys, viewing keys, addresses, and example UTXOs and
+//! nullifiers of its accounts, and checks the vectors produced by other implementations of the
+//! same pipeline against it. Every cryptographic value in a vector is the lowercase hex string of
+//! its [`Encode`] representation, so the JSON form of [`TestVectors`] can be consumed by SDKs
+//! written in other languages.
+//!
+//! A vector is made of inputs, which are the mnemonic, password, coin type, account indices,
+//! UTXO identifiers, and assets, and the outputs derived from them. To check an external
+//! implementation, feed it the inputs of [`TestVectors::reference`], serialize its outputs in
+//! the same format, and pass them to [`validate`].
+
+use crate::{
+    config::{Asset, AssetId, AuthorizationContext, ConstraintField, Identifier, Parameters},
+    key::{
+        path_string, Calamari, CoinType, CoinTypeId, KeySecret, Manta, Mnemonic, Testnet,
+        CALAMARI_COIN_TYPE_ID, MANTA_COIN_TYPE_ID, TESTNET_COIN_TYPE_ID,
+    },
+};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt, str};
+use manta_accounting::{
+    key::{AccountCollection, AccountIndex, IndexType},
+    transfer::utxo::{auth::DeriveContext, DeriveSpend},
+};
+use manta_crypto::{
+    arkworks::constraint::fp::Fp,
+    rand::{ChaCha20Rng, SeedableRng},
+};
+use manta_util::{
+    codec::{Decode, Encode},
+    serde::{Deserialize, Serialize},
+};
+
+/// Reference Mnemonics and Passwords
+///
+/// The mnemonics are the first and last entries of the English BIP-0039 test vectors.
+pub const MNEMONICS: [(&str, &str); 2] = [
+    (
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        "",
+    ),
+    (
+        "scissors invite lock maple supreme raw rapid void congress muscle digital elegant little brisk hair mango congress clump",
+        "TREZOR",
+    ),
+];
+
+/// Number of Accounts per Reference Mnemonic
+pub const ACCOUNTS: IndexType = 3;
+
+/// Test Vector Suite
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct TestVectors {
+    /// Key Vectors
+    pub vectors: Vec<KeyVector>,
+}
+
+impl TestVectors {
+    /// Builds the reference [`TestVectors`] for the [`MNEMONICS`] and every coin type, deriving
+    /// the outputs with `parameters`.
+    #[inline]
+    pub fn reference(parameters: &Parameters) -> Self {
+        let mut vectors = Vec::new();
+        for coin_type in [
+            TESTNET_COIN_TYPE_ID,
+            MANTA_COIN_TYPE_ID,
+            CALAMARI_COIN_TYPE_ID,
+        ] {
+            for (mnemonic, password) in MNEMONICS {
+                let template = KeyVector {
+                    mnemonic: mnemonic.into(),
+                    password: password.into(),
+                    coin_type,
+                    accounts: (0..ACCOUNTS).map(AccountVector::template).collect(),
+                };
+                vectors.push(
+                    template
+                        .derive(parameters, "")
+                        .expect("The reference inputs are valid."),
+                );
+            }
+        }
+        Self { vectors }
+    }
+}
+
+/// Key Vector
+///
+/// The inputs of a key vector are the [`mnemonic`](Self::mnemonic), the
+/// [`password`](Self::password), and the [`coin_type`](Self::coin_type), together with the
+/// inputs of its accounts.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct KeyVector {
+    /// BIP-0039 Mnemonic Phrase
+    pub mnemonic: String,
+
+    /// BIP-0039 Password
+    pub password: String,
+
+    /// BIP-0044 Coin Type
+    pub coin_type: CoinTypeId,
+
+    /// Account Vectors
+    pub accounts: Vec<AccountVector>,
+}
+
+impl KeyVector {
+    /// Derives the outputs of `self` from its inputs using `parameters`, where `location` is the
+    /// path to `self` used in error reports.
+    #[inline]
+    pub fn derive(&self, parameters: &Parameters, location: &str) -> Result<Self, Discrepancy> {
+        match self.coin_type {
+            TESTNET_COIN_TYPE_ID => self.derive_with::<Testnet>(parameters, location),
+            MANTA_COIN_TYPE_ID => self.derive_with::<Manta>(parameters, location),
+            CALAMARI_COIN_TYPE_ID => self.derive_with::<Calamari>(parameters, location),
+            _ => Err(Discrepancy::invalid_input(location, "coin_type")),
+        }
+    }
+
+    /// Derives the outputs of `self` from its inputs for the coin type `C`.
+    #[inline]
+    fn derive_with<C>(&self, parameters: &Parameters, location: &str) -> Result<Self, Discrepancy>
+    where
+        C: CoinType,
+    {
+        let mnemonic = Mnemonic::new(&self.mnemonic)
+            .map_err(|_| Discrepancy::invalid_input(location, "mnemonic"))?;
+        let key = KeySecret::<C>::new(mnemonic, &self.password);
+        Ok(Self {
+            mnemonic: self.mnemonic.clone(),
+            password: self.password.clone(),
+            coin_type: self.coin_type,
+            accounts: self
+                .accounts
+                .iter()
+                .enumerate()
+                .map(|(i, account)| {
+                    account.derive(parameters, &key, &format!("{location}.accounts[{i}]"))
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Account Vector
+///
+/// The inputs of an account vector are its [`index`](Self::index) and the inputs of its UTXOs.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct AccountVector {
+    /// Account Index
+    pub index: IndexType,
+
+    /// BIP-0044 Derivation Path
+    pub path: String,
+
+    /// Spending Key
+    pub spending_key: String,
+
+    /// Viewing Key
+    pub viewing_key: String,
+
+    /// Address Receiving Key
+    pub address: String,
+
+    /// UTXO Vectors
+    pub utxos: Vec<UtxoVector>,
+}
+
+impl AccountVector {
+    /// Builds the reference inputs for the account at `index`, with one transparent and one
+    /// opaque UTXO.
+    #[inline]
+    fn template(index: IndexType) -> Self {
+        let seed = u128::from(index) << 8;
+        Self {
+            index,
+            utxos: [(true, 1), (false, 2)]
+                .into_iter()
+                .map(|(is_transparent, nonce)| UtxoVector {
+                    is_transparent,
+                    utxo_commitment_randomness: to_hex(&Fp::<ConstraintField>::from(
+                        seed | 0x10 | nonce,
+                    )),
+                    asset_id: to_hex(&AssetId::from(seed | 0x20 | nonce)),
+                    asset_value: (1000 * (seed | nonce)).to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Derives the outputs of `self` from its inputs using `parameters` and `key`.
+    #[inline]
+    fn derive<C>(
+        &self,
+        parameters: &Parameters,
+        key: &KeySecret<C>,
+        location: &str,
+    ) -> Result<Self, Discrepancy>
+    where
+        C: CoinType,
+    {
+        let index = AccountIndex::new(self.index);
+        let spending_key = key.spending_key(&index);
+        let mut authorization_context = parameters.derive_context(&spending_key);
+        let viewing_key = *authorization_context
+            .viewing_key(&parameters.base.viewing_key_derivation_function, &mut ());
+        let address = parameters.address_from_spending_key(&spending_key);
+        Ok(Self {
+            index: self.index,
+            path: path_string::<C>(index),
+            spending_key: to_hex(&spending_key),
+            viewing_key: to_hex(&viewing_key),
+            address: to_hex(&address.receiving_key),
+            utxos: self
+                .utxos
+                .iter()
+                .enumerate()
+                .map(|(i, utxo)| {
+                    utxo.derive(
+                        parameters,
+                        &mut authorization_context,
+                        &format!("{location}.utxos[{i}]"),
+                    )
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// UTXO Vector
+///
+/// The inputs of a UTXO vector are its [`is_transparent`](Self::is_transparent) flag, its
+/// [`utxo_commitment_randomness`](Self::utxo_commitment_randomness), and its asset. The UTXO is
+/// owned by the account it belongs to and the nullifier is the one that account would reveal when
+/// spending it.
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(crate = "manta_util::serde", deny_unknown_fields)]
+pub struct UtxoVector {
+    /// Transparency Flag
+    pub is_transparent: bool,
+
+    /// UTXO Commitment Randomness
+    pub utxo_commitment_randomness: String,
+
+    /// Asset Id
+    pub asset_id: String,
+
+    /// Decimal Asset Value
+    pub asset_value: String,
+
+    /// UTXO Commitment
+    pub utxo_commitment: String,
+
+    /// Nullifier Commitment
+    pub nullifier_commitment: String,
+}
+
+impl UtxoVector {
+    /// Derives the outputs of `self` from its inputs using `parameters` and
+    /// `authorization_context`.
+    #[inline]
+    fn derive(
+        &self,
+        parameters: &Parameters,
+        authorization_context: &mut AuthorizationContext,
+        location: &str,
+    ) -> Result<Self, Discrepancy> {
+        let identifier = Identifier::new(
+            self.is_transparent,
+            false,
+            from_hex(&self.utxo_commitment_randomness).ok_or_else(|| {
+                Discrepancy::invalid_input(location, "utxo_commitment_randomness")
+            })?,
+        );
+        let asset = Asset::new(
+            from_hex(&self.asset_id)
+                .ok_or_else(|| Discrepancy::invalid_input(location, "asset_id"))?,
+            self.asset_value
+                .parse()
+                .map_err(|_| Discrepancy::invalid_input(location, "asset_value"))?,
+        );
+        // NOTE: The randomness is only used for the outgoing note of the nullifier, which is not
+        //       part of the vector, so any seed gives the same outputs.
+        let (_, utxo, nullifier) = parameters.derive_spend(
+            authorization_context,
+            identifier,
+            asset,
+            &mut ChaCha20Rng::from_seed([0; 32]),
+        );
+        Ok(Self {
+            is_transparent: self.is_transparent,
+            utxo_commitment_randomness: self.utxo_commitment_randomness.clone(),
+            asset_id: self.asset_id.clone(),
+            asset_value: self.asset_value.clone(),
+            utxo_commitment: to_hex(&utxo.commitment),
+            nullifier_commitment: to_hex(&nullifier.nullifier.commitment),
+        })
+    }
+}
+
+/// Test Vector Discrepancy
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Discrepancy {
+    /// Invalid Input
+    InvalidInput {
+        /// Path to the Vector with the Invalid Input
+        location: String,
+
+        /// Name of the Invalid Field
+        field: &'static str,
+    },
+
+    /// Mismatched Output
+    Mismatch {
+        /// Path to the Vector with the Mismatched Output
+        location: String,
+
+        /// Name of the Mismatched Field
+        field: &'static str,
+
+        /// Output of the Reference Implementation
+        expected: String,
+
+        /// Output of the Implementation being Validated
+        actual: String,
+    },
+}
+
+impl Discrepancy {
+    /// Builds a new [`Discrepancy::InvalidInput`] for `field` at `location`.
+    #[inline]
+    pub(super) fn invalid_input(location: &str, field: &'static str) -> Self {
+        Self::InvalidInput {
+            location: location.into(),
+            field,
+        }
+    }
+
+    /// Pushes a [`Discrepancy::Mismatch`] for `field` at `location` onto `discrepancies` if
+    /// `expected` and `actual` differ.
+    #[inline]
+    pub(super) fn check<T>(
+        discrepancies: &mut Vec<Self>,
+        location: &str,
+        field: &'static str,
+        expected: &T,
+        actual: &T,
+    ) where
+        T: fmt::Display + PartialEq + ?Sized,
+    {
+        if expected != actual {
+            discrepancies.push(Self::Mismatch {
+                location: location.into(),
+                field,
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+            });
+        }
+    }
+}
+
+impl fmt::Display for Discrepancy {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidInput { location, field } => {
+                write!(f, "{location}.{field}: invalid input")
+            }
+            Self::Mismatch {
+                location,
+                field,
+                expected,
+                actual,
+            } => write!(f, "{location}.{field}: expected {expected}, found {actual}"),
+        }
+    }
+}
+
+/// Validates the outputs of `vectors` against the reference implementation, deriving them again
+/// from the inputs of `vectors` with `parameters` and returning every discrepancy.
+#[inline]
+pub fn validate(parameters: &Parameters, vectors: &TestVectors) -> Vec<Discrepancy> {
+    let mut discrepancies = Vec::new();
+    for (i, actual) in vectors.vectors.iter().enumerate() {
+        let location = format!("vectors[{i}]");
+        let expected = match actual.derive(parameters, &location) {
+            Ok(expected) => expected,
+            Err(discrepancy) => {
+                discrepancies.push(discrepancy);
+                continue;
+            }
+        };
+        for (j, (expected, actual)) in expected.accounts.iter().zip(&actual.accounts).enumerate() {
+            let location = format!("{location}.accounts[{j}]");
+            let check = Discrepancy::check::<str>;
+            check(
+                &mut discrepancies,
+                &location,
+                "path",
+                &expected.path,
+                &actual.path,
+            );
+            check(
+                &mut discrepancies,
+                &location,
+                "spending_key",
+                &expected.spending_key,
+                &actual.spending_key,
+            );
+            check(
+                &mut discrepancies,
+                &location,
+                "viewing_key",
+                &expected.viewing_key,
+                &actual.viewing_key,
+            );
+            check(
+                &mut discrepancies,
+                &location,
+                "address",
+                &expected.address,
+                &actual.address,
+            );
+            for (k, (expected, actual)) in expected.utxos.iter().zip(&actual.utxos).enumerate() {
+                let location = format!("{location}.utxos[{k}]");
+                check(
+                    &mut discrepancies,
+                    &location,
+                    "utxo_commitment",
+                    &expected.utxo_commitment,
+                    &actual.utxo_commitment,
+                );
+                check(
+                    &mut discrepancies,
+                    &location,
+                    "nullifier_commitment",
+                    &expected.nullifier_commitment,
+                    &actual.nullifier_commitment,
+                );
+            }
+        }
+    }
+    discrepancies
+}
+
+/// Returns the lowercase hex string of the [`Encode`] representation of `value`.
+#[inline]
+pub(super) fn to_hex<T>(value: &T) -> String
+where
+    T: Encode,
+{
+    value
+        .to_vec()
+        .into_iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Decodes a value from the lowercase hex string of its [`Encode`] representation.
+#[inline]
+pub(super) fn from_hex<T>(string: &str) -> Option<T>
+where
+    T: Decode,
+{
+    let bytes = string
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [_, _] => u8::from_str_radix(str::from_utf8(pair).ok()?, 16).ok(),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    T::from_vec(bytes).ok()
+}
+
+/// Testing Suite
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parameters::load_transfer_parameters;
+
+    /// Tests that the reference implementation still produces the published test vectors.
+    #[test]
+    fn reference_vectors_are_stable() {
+        let parameters = load_transfer_parameters();
+        let published = serde_json::from_str::<TestVectors>(include_str!("vectors.json"))
+            .expect("Unable to parse the published test vectors.");
+        assert_eq!(
+            TestVectors::reference(&parameters),
+            published,
+            "The key derivation pipeline has drifted from the published test vectors."
+        );
+        assert!(validate(&parameters, &published).is_empty());
+    }
+
+    /// Tests that [`validate`] reports every mismatched output and invalid input.
+    #[test]
+    fn validation_reports_discrepancies() {
+        let parameters = load_transfer_parameters();
+        let mut vectors = TestVectors::reference(&parameters);
+        vectors.vectors[0].accounts[1].spending_key = to_hex(&AssetId::from(1u128));
+        vectors.vectors[1].accounts[0].utxos[1]
+            .nullifier_commitment
+            .clear();
+        vectors.vectors[2].mnemonic = "invalid mnemonic".into();
+        let discrepancies = validate(&parameters, &vectors);
+        assert_eq!(discrepancies.len(), 3);
+        assert!(matches!(
+            &discrepancies[0],
+            Discrepancy::Mismatch { location, field: "spending_key", .. }
+                if location == "vectors[0].accounts[1]"
+        ));
+        assert!(matches!(
+            &discrepancies[1],
+            Discrepancy::Mismatch { location, field: "nullifier_commitment", .. }
+                if location == "vectors[1].accounts[0].utxos[1]"
+        ));
+        assert_eq!(
+            discrepancies[2],
+            Discrepancy::invalid_input("vectors[2]", "mnemonic")
+        );
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Zeroization Testing Suite
+
+use crate::config::{utxo, ConstraintField, Group, Parameters, SpendingKey};
+use core::cell::Cell;
+use manta_accounting::transfer::utxo::auth::DeriveContext;
+use manta_crypto::{
+    arkworks::constraint::fp::Fp,
+    rand::{OsRng, Rand},
+};
+use manta_util::{
+    zeroize::{Zeroize, Zeroizing},
+    Array,
+};
+use std::rc::Rc;
+
+/// Zeroization Probe
+///
+/// Records how many times it was zeroized.
+#[derive(Clone, Debug, Default)]
+struct Probe(Rc<Cell<usize>>);
+
+impl Zeroize for Probe {
+    #[inline]
+    fn zeroize(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+/// Checks that the basic types and containers are wiped by [`Zeroize`].
+#[test]
+fn zeroize_clears_values() {
+    let mut integer = u128::MAX;
+    integer.zeroize();
+    assert_eq!(integer, 0);
+    let mut bytes = [0xff; 32];
+    bytes.zeroize();
+    assert_eq!(bytes, [0; 32]);
+    let mut array = Array::<u64, 4>([1, 2, 3, 4]);
+    array.zeroize();
+    assert_eq!(array.0, [0; 4]);
+    let mut option = Some([7u8; 8]);
+    option.zeroize();
+    assert_eq!(option, None);
+    let mut vector = vec![1u8, 2, 3];
+    vector.zeroize();
+    assert!(vector.is_empty());
+    let mut string = String::from("seed phrase");
+    string.zeroize();
+    assert!(string.is_empty());
+}
+
+/// Checks that [`Zeroizing`] zeroizes its value exactly once when it is dropped and never prints
+/// it.
+#[test]
+fn zeroizing_wipes_on_drop() {
+    let probe = Probe::default();
+    let counter = probe.0.clone();
+    let wrapper = Zeroizing::new(probe);
+    assert_eq!(format!("{wrapper:?}"), "Zeroizing(<redacted>)");
+    assert_eq!(wrapper.0.get(), 0, "Dereferencing should not zeroize.");
+    drop(wrapper);
+    assert_eq!(counter.get(), 1, "Dropping the wrapper should zeroize it.");
+    let secret = Zeroizing::new(vec![42u8; 16]);
+    assert_eq!(secret.as_slice(), [42; 16]);
+}
+
+/// Checks that field elements, group elements and authorization contexts are reset to their
+/// default values.
+#[test]
+fn zeroize_secret_material() {
+    let mut rng = OsRng;
+    let mut scalar = Fp::<ConstraintField>::from(123456789u128);
+    scalar.zeroize();
+    assert_eq!(scalar, Fp::default());
+    let mut group = rng.gen::<_, Group>();
+    assert_ne!(group, Group::default());
+    group.zeroize();
+    assert_eq!(group, Group::default());
+    let parameters = rng.gen::<_, Parameters>();
+    let spending_key = rng.gen::<_, SpendingKey>();
+    let mut authorization_context = parameters.derive_context(&spending_key);
+    let zeroized = format!("{:?}", utxo::AuthorizationContext::new(Default::default()));
+    assert_ne!(format!("{authorization_context:?}"), zeroized);
+    authorization_context.zeroize();
+    assert_eq!(format!("{authorization_context:?}"), zeroized);
+}
@@ -18,13 +18,16 @@
 
 use clap::Parser;
 use core::fmt::Debug;
-use manta_crypto::arkworks::serialize::HasSerialization;
+use manta_crypto::arkworks::{pairing::Pairing, serialize::HasSerialization};
 use manta_trusted_setup::{
     ceremony::util::deserialize_from_file,
     groth16::{
         ceremony::{
-            config::ppot::Config, message::ContributeResponse, server::filename_format, Ceremony,
-            CeremonyError, UnexpectedError,
+            circuit::{self, CircuitDigest},
+            config::ppot::Config,
+            message::ContributeResponse,
+            server::filename_format,
+            Ceremony, CeremonyError, Circuits, UnexpectedError,
         },
         mpc::{util::extract_keys, verify_transform, Proof, State},
     },
@@ -69,12 +72,21 @@ fn main() {
 
 fn verify_ceremony<C>(path: &Path, start: u64) -> Result<(), CeremonyError<C>>
 where
-    C: Ceremony<Challenge = Array<u8, 64>>,
+    C: Ceremony<Challenge = Array<u8, 64>> + Circuits<<C as Pairing>::Scalar>,
     for<'s> C::G2Prepared: HasSerialization<'s>,
 {
     // Need to read from files, so get circuit names
     let names: Vec<String> =
         deserialize_from_file(path.join(r"circuit_names")).expect("Circuit names file is missing.");
+    // The transcript is only meaningful for the circuits it was prepared with
+    let digests: Vec<(String, CircuitDigest)> =
+        deserialize_from_file(path.join(r"circuit_digests"))
+            .expect("Circuit digests file is missing.");
+    println!("Rebuilding circuits {names:?}");
+    circuit::check_digests(&circuit::digests(&C::circuits()), &digests).map_err(|e| {
+        println!("The circuits do not match the transcript: {e:?}");
+        CeremonyError::BadRequest
+    })?;
     println!("Will verify contributions to {names:?}");
     // Keep track of verification times
     let mut verification_times = Vec::<Duration>::new();
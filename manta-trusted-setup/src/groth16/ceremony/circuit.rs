@@ -0,0 +1,230 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Deterministic Circuit Instances
+//!
+//! Phase 2 of the ceremony starts from the constraint matrices of every circuit, so anyone who
+//! checks a transcript has to rebuild exactly the matrices which the coordinator prepared it with.
+//! The [`compiler`] of this module pins the synthesis mode and the optimization goal instead of
+//! relying on the defaults of the proof system, and [`instantiate`] builds a circuit in it, with
+//! its variables allocated in the order in which the circuit declares them, and returns it along
+//! with the [`CircuitDigest`] of its finalized matrices. The coordinator records the digests of
+//! all the circuits when preparing the ceremony, and the verifier rebuilds the circuits and
+//! rejects the transcript with [`check_digests`] if any of them changed.
+
+use alloc::{string::String, vec::Vec};
+use blake2::{Blake2s256, Digest};
+use manta_crypto::arkworks::{
+    constraint::R1CS,
+    ff::{BigInteger, PrimeField},
+    relations::r1cs::{ConstraintSystem, Matrix, OptimizationGoal, SynthesisMode},
+};
+use manta_util::into_array_unchecked;
+
+/// Circuit Digest
+///
+/// Blake2s hash of the finalized constraint matrices of a circuit.
+pub type CircuitDigest = [u8; 32];
+
+/// Builds a new constraint system for unknown variables with a pinned synthesis mode and
+/// optimization goal.
+#[inline]
+pub fn compiler<F>() -> R1CS<F>
+where
+    F: PrimeField,
+{
+    let constraint_system = ConstraintSystem::new_ref();
+    constraint_system.set_optimization_goal(OptimizationGoal::Constraints);
+    constraint_system.set_mode(SynthesisMode::Setup);
+    R1CS::new_unchecked(constraint_system)
+}
+
+/// Appends `matrix` to `hasher`, row by row.
+#[inline]
+fn hash_matrix<F>(hasher: &mut Blake2s256, matrix: &Matrix<F>)
+where
+    F: PrimeField,
+{
+    hasher.update((matrix.len() as u64).to_le_bytes());
+    for row in matrix {
+        hasher.update((row.len() as u64).to_le_bytes());
+        for (coefficient, index) in row {
+            hasher.update(coefficient.into_repr().to_bytes_le());
+            hasher.update((*index as u64).to_le_bytes());
+        }
+    }
+}
+
+/// Finalizes `circuit` and returns the [`CircuitDigest`] of its constraint matrices.
+///
+/// # Panics
+///
+/// This function panics if `circuit` was not built for unknown variables, since the constraint
+/// matrices are only recorded in that mode.
+#[inline]
+pub fn digest<F>(circuit: &R1CS<F>) -> CircuitDigest
+where
+    F: PrimeField,
+{
+    let constraint_system = circuit.as_ref();
+    constraint_system.finalize();
+    let matrices = constraint_system
+        .to_matrices()
+        .expect("The constraint matrices are recorded for unknown variables.");
+    let mut hasher = Blake2s256::default();
+    hasher.update((matrices.num_instance_variables as u64).to_le_bytes());
+    hasher.update((matrices.num_witness_variables as u64).to_le_bytes());
+    hasher.update((matrices.num_constraints as u64).to_le_bytes());
+    hash_matrix(&mut hasher, &matrices.a);
+    hash_matrix(&mut hasher, &matrices.b);
+    hash_matrix(&mut hasher, &matrices.c);
+    into_array_unchecked(hasher.finalize())
+}
+
+/// Builds a circuit with `build` in a new [`compiler`], returning it with its [`CircuitDigest`].
+#[inline]
+pub fn instantiate<F, B>(build: B) -> (R1CS<F>, CircuitDigest)
+where
+    F: PrimeField,
+    B: FnOnce(&mut R1CS<F>),
+{
+    let mut circuit = compiler();
+    build(&mut circuit);
+    let digest = digest(&circuit);
+    (circuit, digest)
+}
+
+/// Circuit Digest Error
+///
+/// This `enum` is the error state of [`check_digests`].
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum DigestError {
+    /// Missing Circuit
+    ///
+    /// The circuit with this name is built, but there is no recorded digest for it.
+    Missing(String),
+
+    /// Unknown Circuit
+    ///
+    /// There is a recorded digest for the circuit with this name, but it is not built.
+    Unknown(String),
+
+    /// Digest Mismatch
+    Mismatch {
+        /// Circuit Name
+        name: String,
+
+        /// Recorded Digest
+        expected: CircuitDigest,
+
+        /// Digest of the Rebuilt Circuit
+        found: CircuitDigest,
+    },
+}
+
+/// Checks that the `digests` of the rebuilt circuits match the `expected` digests recorded when
+/// preparing the ceremony, both indexed by circuit name.
+#[inline]
+pub fn check_digests(
+    digests: &[(String, CircuitDigest)],
+    expected: &[(String, CircuitDigest)],
+) -> Result<(), DigestError> {
+    for (name, found) in digests {
+        match expected
+            .iter()
+            .find(|(expected_name, _)| expected_name == name)
+        {
+            Some((_, expected)) if expected == found => {}
+            Some((_, expected)) => {
+                return Err(DigestError::Mismatch {
+                    name: name.clone(),
+                    expected: *expected,
+                    found: *found,
+                })
+            }
+            _ => return Err(DigestError::Missing(name.clone())),
+        }
+    }
+    match expected
+        .iter()
+        .find(|(name, _)| !digests.iter().any(|(found_name, _)| found_name == name))
+    {
+        Some((name, _)) => Err(DigestError::Unknown(name.clone())),
+        _ => Ok(()),
+    }
+}
+
+/// Returns the names and the digests of `circuits`, finalizing them.
+#[inline]
+pub fn digests<F>(circuits: &[(R1CS<F>, String)]) -> Vec<(String, CircuitDigest)>
+where
+    F: PrimeField,
+{
+    circuits
+        .iter()
+        .map(|(circuit, name)| (name.clone(), digest(circuit)))
+        .collect()
+}
+
+/// Testing Suite
+#[cfg(all(test, feature = "client"))]
+mod test {
+    use super::*;
+    use crate::groth16::ceremony::config::ppot::{dummy_circuit, Config};
+    use alloc::{string::ToString, vec};
+    use manta_crypto::arkworks::pairing::Pairing;
+
+    /// Scalar Field of the Ceremony
+    type Scalar = <Config as Pairing>::Scalar;
+
+    /// Tests that instantiating the same circuit twice gives the same digest, and that a different
+    /// circuit is detected by [`check_digests`].
+    #[test]
+    fn instances_are_reproducible() {
+        let (_, lhs) = instantiate::<Scalar, _>(dummy_circuit);
+        let (_, rhs) = instantiate::<Scalar, _>(dummy_circuit);
+        assert_eq!(
+            lhs, rhs,
+            "Instances of the same circuit should have the same digest."
+        );
+        let (_, doubled) = instantiate::<Scalar, _>(|cs| {
+            dummy_circuit(cs);
+            dummy_circuit(cs);
+        });
+        assert_ne!(lhs, doubled);
+        let expected = vec![("dummy".to_string(), lhs)];
+        assert_eq!(
+            check_digests(&[("dummy".to_string(), rhs)], &expected),
+            Ok(())
+        );
+        assert_eq!(
+            check_digests(&[("dummy".to_string(), doubled)], &expected),
+            Err(DigestError::Mismatch {
+                name: "dummy".to_string(),
+                expected: lhs,
+                found: doubled,
+            })
+        );
+        assert_eq!(
+            check_digests(&[], &expected),
+            Err(DigestError::Unknown("dummy".to_string()))
+        );
+        assert_eq!(
+            check_digests(&[("other".to_string(), lhs)], &expected),
+            Err(DigestError::Missing("other".to_string()))
+        );
+    }
+}
@@ -24,6 +24,7 @@ use crate::{
     },
     groth16::{
         ceremony::{
+            circuit::instantiate,
             client::{self, Continue},
             message::ContributeResponse,
             Ceremony, CeremonyError, Circuits,
@@ -887,26 +888,20 @@ impl Ceremony for Config {
 impl Circuits<<Self as Pairing>::Scalar> for Config {
     #[inline]
     fn circuits() -> Vec<(R1CS<<Self as Pairing>::Scalar>, String)> {
+        let parameters = load_transfer_parameters();
+        let utxo_accumulator_model = load_utxo_accumulator_model();
+        let parameters = FullParametersRef::new(&parameters, &utxo_accumulator_model);
         vec![
             (
-                ToPrivate::unknown_constraints(FullParametersRef::new(
-                    &load_transfer_parameters(),
-                    &load_utxo_accumulator_model(),
-                )),
+                instantiate(|cs| ToPrivate::build_unknown_constraints(parameters, cs)).0,
                 "to_private".to_string(),
             ),
             (
-                ToPublic::unknown_constraints(FullParametersRef::new(
-                    &load_transfer_parameters(),
-                    &load_utxo_accumulator_model(),
-                )),
+                instantiate(|cs| ToPublic::build_unknown_constraints(parameters, cs)).0,
                 "to_public".to_string(),
             ),
             (
-                PrivateTransfer::unknown_constraints(FullParametersRef::new(
-                    &load_transfer_parameters(),
-                    &load_utxo_accumulator_model(),
-                )),
+                instantiate(|cs| PrivateTransfer::build_unknown_constraints(parameters, cs)).0,
                 "private_transfer".to_string(),
             ),
         ]
@@ -25,8 +25,8 @@ use crate::{
     },
     groth16::{
         ceremony::{
-            server::filename_format, Ceremony, CeremonyError, Circuits, Configuration, Metadata,
-            Queue, Round, UnexpectedError,
+            circuit, server::filename_format, Ceremony, CeremonyError, Circuits, Configuration,
+            Metadata, Queue, Round, UnexpectedError,
         },
         kzg,
         kzg::Accumulator,
@@ -441,7 +441,8 @@ where
 }
 
 /// Prepare by initalizing each circuit's prover key, challenge hash and saving
-/// to file. Creates a `_registry_0` file containing an empty registry.
+/// to file. Records the [`CircuitDigest`](circuit::CircuitDigest) of each circuit in the
+/// `circuit_digests` file. Creates a `_registry_0` file containing an empty registry.
 /// TODO: Generalize ProvingKeyHasher Output type and curves.
 pub fn prepare<C, R>(phase_one_param_path: PathBuf, target_path: PathBuf)
 where
@@ -479,8 +480,10 @@ where
         .expect("Cannot read Phase 1 accumulator from file");
 
     let round_number = 0u64;
+    let circuits = C::circuits();
+    let digests = circuit::digests(&circuits);
     let mut names = Vec::new();
-    for (circuit, name) in C::circuits().into_iter() {
+    for (circuit, name) in circuits.into_iter() {
         println!("Creating proving key for {name}");
         names.push(name.clone());
         let (challenge, state): (<C as ChallengeType>::Challenge, State<C>) =
@@ -513,6 +516,13 @@ where
     )
     .expect("Writing circuit names to disk should succeed.");
 
+    serialize_into_file(
+        OpenOptions::new().write(true).truncate(true).create(true),
+        &target_path.join(r"circuit_digests"),
+        &digests,
+    )
+    .expect("Writing circuit digests to disk should succeed.");
+
     serialize_into_file(
         OpenOptions::new().write(true).truncate(true).create(true),
         &target_path.join(r"round_number"),
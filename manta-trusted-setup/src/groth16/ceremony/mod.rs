@@ -37,6 +37,7 @@ use manta_util::{
     serde::{Deserialize, Serialize},
 };
 
+pub mod circuit;
 pub mod config;
 pub mod log;
 pub mod message;
@@ -91,6 +92,9 @@ where
     F: PrimeField,
 {
     /// Returns representations of the circuits used in this ceremony, each named.
+    ///
+    /// The circuits should be built with [`circuit::instantiate`], so that the ceremony verifier
+    /// rebuilds the same constraint matrices as the coordinator on any machine.
     fn circuits() -> Vec<(R1CS<F>, String)>;
 }
 
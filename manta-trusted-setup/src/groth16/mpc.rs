@@ -622,7 +622,7 @@ pub mod util {
                 message: "Unable to serialize prover key.".to_string(),
             })?;
         proving_context
-            .get_verifying_context()
+            .verifying_context()
             .expect("Should be able to extract verifying context.")
             .encode(IoWriter(vk_file))
             .map_err(|_| UnexpectedError::Serialization {
@@ -23,10 +23,21 @@ use core::{convert::Infallible, fmt::Debug, hash::Hash, marker::PhantomData};
 
 #[cfg(feature = "alloc")]
 use {
-    crate::{into_array_unchecked, vec::Vec},
+    crate::{
+        into_array_unchecked,
+        vec::{BoundedVec, CapacityError, Vec},
+    },
     alloc::boxed::Box,
 };
 
+#[cfg(all(feature = "std", feature = "tokio"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "std", feature = "tokio"))))]
+pub mod stream;
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub mod tagged;
+
 /// Implements [`Decode`] and [`Encode`] for a type with no data that implements [`Default`].
 #[macro_export]
 macro_rules! impl_empty_codec {
@@ -686,6 +697,21 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<T, const MAX: usize> Encode for BoundedVec<T, MAX>
+where
+    T: Encode,
+{
+    #[inline]
+    fn encode<W>(&self, writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        self.as_ref().encode(writer)
+    }
+}
+
 #[cfg(feature = "alloc")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
 impl<T> Encode for Box<[T]>
@@ -747,6 +773,21 @@ where
     }
 }
 
+impl<A, B> Encode for (A, B)
+where
+    A: Encode,
+    B: Encode,
+{
+    #[inline]
+    fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        self.0.encode(&mut writer)?;
+        self.1.encode(&mut writer)
+    }
+}
+
 /// Exact Size Encoding
 pub trait EncodeExactSize<const N: usize>: Encode {
     /// Converts `self` into an exactly known byte array.
@@ -792,6 +833,22 @@ impl<T> Decode for PhantomData<T> {
     }
 }
 
+impl Decode for bool {
+    type Error = ();
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        match u8::decode(&mut reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(DecodeError::Decode(())),
+        }
+    }
+}
+
 impl Decode for u8 {
     type Error = ();
 
@@ -897,6 +954,23 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<T, const N: usize> Decode for Array<T, N>
+where
+    T: Decode,
+{
+    type Error = Option<T::Error>;
+
+    #[inline]
+    fn decode<R>(reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Ok(Self(<[T; N]>::decode(reader)?))
+    }
+}
+
 #[cfg(feature = "alloc")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
 impl<T> Decode for Vec<T>
@@ -936,6 +1010,52 @@ where
     }
 }
 
+/// Bounded Vector [`Decode`] Error
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BoundedVecDecodeError<T> {
+    /// Missing Length Prefix
+    MissingLength,
+
+    /// Length Prefix Exceeds the Maximum Length
+    ExceededCapacity(u64),
+
+    /// Element Decoding Error
+    Element(T),
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<T, const MAX: usize> Decode for BoundedVec<T, MAX>
+where
+    T: Decode,
+{
+    type Error = BoundedVecDecodeError<T::Error>;
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        let len = u64::decode(&mut reader)
+            .map_err(|err| err.map_decode(|_| BoundedVecDecodeError::MissingLength))?;
+        if len > MAX as u64 {
+            return Err(DecodeError::Decode(
+                BoundedVecDecodeError::ExceededCapacity(len),
+            ));
+        }
+        let mut results = Self::with_cautious_capacity(len as usize);
+        for _ in 0..len {
+            let item = T::decode(&mut reader)
+                .map_err(|err| err.map_decode(BoundedVecDecodeError::Element))?;
+            results
+                .try_push(item)
+                .map_err(CapacityError::forget)
+                .expect("The length prefix was already checked against the bound.");
+        }
+        Ok(results)
+    }
+}
+
 /// Option [`Decode`] Error
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum OptionDecodeError<T> {
@@ -963,10 +1083,10 @@ where
         match u8::decode(&mut reader)
             .map_err(move |err| err.map_decode(move |_| OptionDecodeError::MissingByte))?
         {
-            0 => Ok(Some(T::decode(&mut reader).map_err(move |err| {
+            0 => Ok(None),
+            1 => Ok(Some(T::decode(&mut reader).map_err(move |err| {
                 err.map_decode(OptionDecodeError::SomeError)
             })?)),
-            1 => Ok(None),
             b => Err(DecodeError::Decode(OptionDecodeError::InvalidByte(b))),
         }
     }
@@ -1003,17 +1123,46 @@ where
         match u8::decode(&mut reader)
             .map_err(move |err| err.map_decode(move |_| ResultDecodeError::MissingByte))?
         {
-            0 => Ok(Ok(T::decode(&mut reader).map_err(move |err| {
-                err.map_decode(ResultDecodeError::OkError)
-            })?)),
-            1 => Ok(Err(E::decode(&mut reader).map_err(move |err| {
+            0 => Ok(Err(E::decode(&mut reader).map_err(move |err| {
                 err.map_decode(ResultDecodeError::ErrError)
             })?)),
+            1 => Ok(Ok(T::decode(&mut reader).map_err(move |err| {
+                err.map_decode(ResultDecodeError::OkError)
+            })?)),
             b => Err(DecodeError::Decode(ResultDecodeError::InvalidByte(b))),
         }
     }
 }
 
+/// Pair [`Decode`] Error
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PairDecodeError<A, B> {
+    /// First Component Error
+    First(A),
+
+    /// Second Component Error
+    Second(B),
+}
+
+impl<A, B> Decode for (A, B)
+where
+    A: Decode,
+    B: Decode,
+{
+    type Error = PairDecodeError<A::Error, B::Error>;
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        Ok((
+            A::decode(&mut reader).map_err(move |err| err.map_decode(PairDecodeError::First))?,
+            B::decode(&mut reader).map_err(move |err| err.map_decode(PairDecodeError::Second))?,
+        ))
+    }
+}
+
 /// Exact Size Decoding
 pub trait DecodeExactSize<const N: usize>: Decode {
     /// Converts a fixed-length byte array into a concrete value of type `Self`.
@@ -0,0 +1,286 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Asynchronous Streaming Adapters
+//!
+//! The [`Encode`] and [`Decode`] traits only work over the synchronous [`Read`](super::Read) and
+//! [`Write`](super::Write) traits. This module bridges them to asynchronous readers and writers by
+//! splitting messages into length-prefixed frames. Each frame is encoded or decoded on its own, so
+//! a long sequence of values, like the posts in a block or the entries of a synchronization
+//! response, can be streamed without buffering the whole message in memory.
+//!
+//! # Wire Format
+//!
+//! A frame is the little-endian `u64` length of its body followed by the body itself, which is the
+//! [`Encode`] representation of the value. A sequence is the little-endian `u64` number of frames
+//! followed by the frames themselves.
+
+use crate::{
+    codec::{Decode, DecodeError, Encode},
+    vec::Vec,
+};
+use core::fmt;
+use std::io;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Default Maximum Frame Length
+///
+/// This is the largest frame body in bytes that a [`FrameReader`] accepts unless it was configured
+/// with [`FrameReader::with_max_frame_length`].
+pub const DEFAULT_MAX_FRAME_LENGTH: usize = 1 << 24;
+
+/// Stream Error
+#[derive(Debug)]
+pub enum StreamError<D> {
+    /// I/O Error
+    Io(io::Error),
+
+    /// Frame Length Exceeds the Maximum Frame Length
+    FrameTooLarge(u64),
+
+    /// Sequence Length Exceeds the Requested Maximum Length
+    SequenceTooLong(u64),
+
+    /// Frame Body was not Consumed Completely
+    ///
+    /// The number of bytes left in the frame body after decoding is returned here.
+    TrailingBytes(usize),
+
+    /// Decoding Error
+    Decode(D),
+}
+
+impl<D> StreamError<D> {
+    /// Maps the [`Decode`](Self::Decode) variant over `f`.
+    #[inline]
+    pub fn map_decode<T, F>(self, f: F) -> StreamError<T>
+    where
+        F: FnOnce(D) -> T,
+    {
+        match self {
+            Self::Io(err) => StreamError::Io(err),
+            Self::FrameTooLarge(len) => StreamError::FrameTooLarge(len),
+            Self::SequenceTooLong(len) => StreamError::SequenceTooLong(len),
+            Self::TrailingBytes(len) => StreamError::TrailingBytes(len),
+            Self::Decode(err) => StreamError::Decode(f(err)),
+        }
+    }
+}
+
+impl<D> fmt::Display for StreamError<D>
+where
+    D: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "I/O Error: {err}"),
+            Self::FrameTooLarge(len) => write!(f, "Frame length {len} exceeds the maximum."),
+            Self::SequenceTooLong(len) => write!(f, "Sequence length {len} exceeds the maximum."),
+            Self::TrailingBytes(len) => write!(f, "Frame had {len} trailing bytes."),
+            Self::Decode(err) => write!(f, "Decoding Error: {err:?}"),
+        }
+    }
+}
+
+impl<D> std::error::Error for StreamError<D> where D: fmt::Debug {}
+
+impl<D> From<io::Error> for StreamError<D> {
+    #[inline]
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Frame Writer
+///
+/// This `struct` writes [`Encode`] values as length-prefixed frames into an asynchronous writer.
+/// The frame body is encoded into an internal buffer which is reused across frames.
+#[derive(Debug)]
+pub struct FrameWriter<W> {
+    /// Writer
+    writer: W,
+
+    /// Frame Buffer
+    buffer: Vec<u8>,
+}
+
+impl<W> FrameWriter<W>
+where
+    W: AsyncWrite + Unpin,
+{
+    /// Builds a new [`FrameWriter`] over `writer`.
+    #[inline]
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Writes `value` as a single frame.
+    #[inline]
+    pub async fn write<T>(&mut self, value: &T) -> io::Result<()>
+    where
+        T: Encode + ?Sized,
+    {
+        self.buffer.clear();
+        value
+            .encode(&mut self.buffer)
+            .expect("Writing to a `Vec<u8>` cannot fail.");
+        self.writer.write_u64_le(self.buffer.len() as u64).await?;
+        self.writer.write_all(&self.buffer).await
+    }
+
+    /// Writes the length header of a sequence of `len` frames. The caller must follow this with
+    /// exactly `len` calls to [`write`](Self::write).
+    #[inline]
+    pub async fn write_sequence_length(&mut self, len: usize) -> io::Result<()> {
+        self.writer.write_u64_le(len as u64).await
+    }
+
+    /// Writes all the values of `iter` as a sequence of frames.
+    #[inline]
+    pub async fn write_sequence<'t, T, I>(&mut self, iter: I) -> io::Result<()>
+    where
+        T: 't + Encode,
+        I: IntoIterator<Item = &'t T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        self.write_sequence_length(iter.len()).await?;
+        for value in iter {
+            self.write(value).await?;
+        }
+        Ok(())
+    }
+
+    /// Flushes the underlying writer.
+    #[inline]
+    pub async fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush().await
+    }
+
+    /// Returns the underlying writer.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Frame Reader
+///
+/// This `struct` reads length-prefixed frames from an asynchronous reader and decodes them as
+/// [`Decode`] values. Frames longer than the maximum frame length are rejected before any of their
+/// body is read.
+#[derive(Debug)]
+pub struct FrameReader<R> {
+    /// Reader
+    reader: R,
+
+    /// Frame Buffer
+    buffer: Vec<u8>,
+
+    /// Maximum Frame Length
+    max_frame_length: usize,
+}
+
+impl<R> FrameReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    /// Builds a new [`FrameReader`] over `reader` accepting frames of at most
+    /// [`DEFAULT_MAX_FRAME_LENGTH`] bytes.
+    #[inline]
+    pub fn new(reader: R) -> Self {
+        Self::with_max_frame_length(reader, DEFAULT_MAX_FRAME_LENGTH)
+    }
+
+    /// Builds a new [`FrameReader`] over `reader` accepting frames of at most `max_frame_length`
+    /// bytes.
+    #[inline]
+    pub fn with_max_frame_length(reader: R, max_frame_length: usize) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+            max_frame_length,
+        }
+    }
+
+    /// Returns the maximum frame length accepted by `self`.
+    #[inline]
+    pub fn max_frame_length(&self) -> usize {
+        self.max_frame_length
+    }
+
+    /// Reads a single frame and decodes it.
+    #[inline]
+    pub async fn read<T>(&mut self) -> Result<T, StreamError<T::Error>>
+    where
+        T: Decode,
+    {
+        let len = self.reader.read_u64_le().await?;
+        if len > self.max_frame_length as u64 {
+            return Err(StreamError::FrameTooLarge(len));
+        }
+        self.buffer.resize(len as usize, 0);
+        self.reader.read_exact(&mut self.buffer).await?;
+        let mut body = self.buffer.as_slice();
+        let value = T::decode(&mut body).map_err(|err| match err {
+            DecodeError::Decode(err) => StreamError::Decode(err),
+            DecodeError::Read(err) => match err {},
+        })?;
+        if !body.is_empty() {
+            return Err(StreamError::TrailingBytes(body.len()));
+        }
+        Ok(value)
+    }
+
+    /// Reads the length header of a sequence, failing if it is longer than `max`. The caller
+    /// should follow this with exactly as many calls to [`read`](Self::read) as returned here.
+    #[inline]
+    pub async fn read_sequence_length<D>(&mut self, max: usize) -> Result<usize, StreamError<D>> {
+        let len = self.reader.read_u64_le().await?;
+        if len > max as u64 {
+            return Err(StreamError::SequenceTooLong(len));
+        }
+        Ok(len as usize)
+    }
+
+    /// Reads a sequence of at most `max` frames, calling `f` on each decoded value as soon as it
+    /// is read.
+    #[inline]
+    pub async fn read_sequence_with<T, F>(
+        &mut self,
+        max: usize,
+        mut f: F,
+    ) -> Result<(), StreamError<T::Error>>
+    where
+        T: Decode,
+        F: FnMut(T),
+    {
+        for _ in 0..self.read_sequence_length(max).await? {
+            f(self.read().await?);
+        }
+        Ok(())
+    }
+
+    /// Returns the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
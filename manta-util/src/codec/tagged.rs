@@ -0,0 +1,298 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tagged Encodings
+//!
+//! A fixed-layout encoding cannot gain a field without breaking every decoder that was written for
+//! the old layout. The tagged encoding in this module stores every field as a tag-length-value
+//! record instead, so that a decoder can skip the fields it does not know about. Each tag also
+//! records whether the field is required: a decoder which meets an unknown required field must
+//! reject the whole message, because the field changes how the rest of the message should be
+//! interpreted, while unknown optional fields are ignored.
+//!
+//! # Wire Format
+//!
+//! A message is the little-endian `u16` number of fields followed by the fields themselves. Each
+//! field is its little-endian `u16` [`Tag`], the little-endian `u32` length of its body, and then
+//! the body itself, which is the [`Encode`] representation of the field value. Tags must be
+//! distinct within a message.
+
+use crate::{
+    codec::{Decode, DecodeError, Encode, Read, ReadExactError, Write},
+    vec::{Vec, VecExt},
+};
+use alloc::collections::BTreeMap;
+use core::{convert::Infallible, fmt};
+
+/// Field Tag
+pub type Tag = u16;
+
+/// Required Field Tag Bit
+///
+/// Tags with this bit set belong to fields that every decoder must understand.
+pub const REQUIRED: Tag = 1 << 15;
+
+/// Maximum Field Length
+///
+/// This is the largest field body in bytes that [`TaggedFields`] accepts when decoding.
+pub const MAX_FIELD_LENGTH: usize = 1 << 16;
+
+/// Returns `true` if `tag` belongs to a field that every decoder must understand.
+#[inline]
+pub const fn is_required(tag: Tag) -> bool {
+    tag & REQUIRED != 0
+}
+
+/// Tagged Decoding Error
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TaggedDecodeError<E> {
+    /// Message Ended Before All of its Fields were Read
+    Truncated,
+
+    /// Field Length Exceeds [`MAX_FIELD_LENGTH`]
+    FieldTooLong(Tag),
+
+    /// Field Tag Appears More than Once
+    DuplicateField(Tag),
+
+    /// Field Requested by the Decoder is Missing
+    MissingField(Tag),
+
+    /// Unknown Required Field
+    UnknownRequiredField(Tag),
+
+    /// Field Body was not Consumed Completely
+    TrailingBytes(Tag),
+
+    /// Field Decoding Error
+    Field(Tag, E),
+}
+
+impl<E> fmt::Display for TaggedDecodeError<E>
+where
+    E: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "Tagged message is truncated."),
+            Self::FieldTooLong(tag) => write!(f, "Field {tag:#06x} exceeds the maximum length."),
+            Self::DuplicateField(tag) => write!(f, "Field {tag:#06x} appears more than once."),
+            Self::MissingField(tag) => write!(f, "Field {tag:#06x} is missing."),
+            Self::UnknownRequiredField(tag) => {
+                write!(f, "Field {tag:#06x} is required but unknown.")
+            }
+            Self::TrailingBytes(tag) => write!(f, "Field {tag:#06x} has trailing bytes."),
+            Self::Field(tag, err) => write!(f, "Field {tag:#06x} Decoding Error: {err:?}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+impl<E> std::error::Error for TaggedDecodeError<E> where E: fmt::Debug {}
+
+impl TaggedDecodeError<Infallible> {
+    /// Converts `self` into an error for a field decoding error of type `E`.
+    #[inline]
+    pub fn lift<E>(self) -> TaggedDecodeError<E> {
+        match self {
+            Self::Truncated => TaggedDecodeError::Truncated,
+            Self::FieldTooLong(tag) => TaggedDecodeError::FieldTooLong(tag),
+            Self::DuplicateField(tag) => TaggedDecodeError::DuplicateField(tag),
+            Self::MissingField(tag) => TaggedDecodeError::MissingField(tag),
+            Self::UnknownRequiredField(tag) => TaggedDecodeError::UnknownRequiredField(tag),
+            Self::TrailingBytes(tag) => TaggedDecodeError::TrailingBytes(tag),
+            Self::Field(_, err) => match err {},
+        }
+    }
+}
+
+/// Tagged Encoder
+///
+/// This `struct` collects the fields of a tagged message. Fields are written in the order in
+/// which they are added.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TaggedEncoder {
+    /// Encoded Fields
+    fields: Vec<(Tag, Vec<u8>)>,
+}
+
+impl TaggedEncoder {
+    /// Builds a new empty [`TaggedEncoder`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `value` as the field with the given `tag`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `tag` was already added to `self`.
+    #[inline]
+    pub fn field<T>(mut self, tag: Tag, value: &T) -> Self
+    where
+        T: Encode + ?Sized,
+    {
+        assert!(
+            self.fields.iter().all(|(t, _)| *t != tag),
+            "Field {tag:#06x} was added more than once."
+        );
+        self.fields.push((tag, value.to_vec()));
+        self
+    }
+
+    /// Adds `value` as the field with the given `tag` if it is not `None`.
+    #[inline]
+    pub fn optional_field<T>(self, tag: Tag, value: Option<&T>) -> Self
+    where
+        T: Encode + ?Sized,
+    {
+        match value {
+            Some(value) => self.field(tag, value),
+            _ => self,
+        }
+    }
+
+    /// Returns the encoded message.
+    #[inline]
+    pub fn finish(self) -> Vec<u8> {
+        self.to_vec()
+    }
+}
+
+impl Encode for TaggedEncoder {
+    #[inline]
+    fn encode<W>(&self, mut writer: W) -> Result<(), W::Error>
+    where
+        W: Write,
+    {
+        (self.fields.len() as u16).encode(&mut writer)?;
+        for (tag, body) in &self.fields {
+            tag.encode(&mut writer)?;
+            (body.len() as u32).encode(&mut writer)?;
+            writer.write_ref(body)?;
+        }
+        Ok(())
+    }
+}
+
+/// Tagged Fields
+///
+/// This `struct` stores the decoded fields of a tagged message. Fields are taken out one by one
+/// with [`required`](Self::required) and [`optional`](Self::optional), and then
+/// [`finish`](Self::finish) checks that every field left over is optional.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct TaggedFields {
+    /// Field Bodies
+    fields: BTreeMap<Tag, Vec<u8>>,
+}
+
+impl TaggedFields {
+    /// Decodes the field body `bytes` stored with `tag`.
+    #[inline]
+    fn decode_field<T>(tag: Tag, bytes: Vec<u8>) -> Result<T, TaggedDecodeError<T::Error>>
+    where
+        T: Decode,
+    {
+        let mut body = bytes.as_slice();
+        let value = match T::decode(&mut body) {
+            Ok(value) => value,
+            Err(DecodeError::Decode(err)) => return Err(TaggedDecodeError::Field(tag, err)),
+            Err(DecodeError::Read(err)) => match err {},
+        };
+        if !body.is_empty() {
+            return Err(TaggedDecodeError::TrailingBytes(tag));
+        }
+        Ok(value)
+    }
+
+    /// Returns `true` if `self` has a field with the given `tag`.
+    #[inline]
+    pub fn contains(&self, tag: Tag) -> bool {
+        self.fields.contains_key(&tag)
+    }
+
+    /// Removes and decodes the field with the given `tag`, returning an error if it is missing.
+    #[inline]
+    pub fn required<T>(&mut self, tag: Tag) -> Result<T, TaggedDecodeError<T::Error>>
+    where
+        T: Decode,
+    {
+        match self.fields.remove(&tag) {
+            Some(bytes) => Self::decode_field(tag, bytes),
+            _ => Err(TaggedDecodeError::MissingField(tag)),
+        }
+    }
+
+    /// Removes and decodes the field with the given `tag`, returning `None` if it is missing.
+    #[inline]
+    pub fn optional<T>(&mut self, tag: Tag) -> Result<Option<T>, TaggedDecodeError<T::Error>>
+    where
+        T: Decode,
+    {
+        self.fields
+            .remove(&tag)
+            .map(|bytes| Self::decode_field(tag, bytes))
+            .transpose()
+    }
+
+    /// Checks that all the fields which were not taken out of `self` are optional, returning the
+    /// tags of the fields that were skipped.
+    #[inline]
+    pub fn finish(self) -> Result<Vec<Tag>, TaggedDecodeError<Infallible>> {
+        let skipped = self.fields.into_keys().collect::<Vec<_>>();
+        match skipped.iter().find(|tag| is_required(**tag)) {
+            Some(tag) => Err(TaggedDecodeError::UnknownRequiredField(*tag)),
+            _ => Ok(skipped),
+        }
+    }
+}
+
+impl Decode for TaggedFields {
+    type Error = TaggedDecodeError<Infallible>;
+
+    #[inline]
+    fn decode<R>(mut reader: R) -> Result<Self, DecodeError<R::Error, Self::Error>>
+    where
+        R: Read,
+    {
+        let truncated = |err| match err {
+            DecodeError::Read(err) => DecodeError::Read(err),
+            _ => DecodeError::Decode(TaggedDecodeError::Truncated),
+        };
+        let count = u16::decode(&mut reader).map_err(truncated)?;
+        let mut fields = BTreeMap::new();
+        for _ in 0..count {
+            let tag = u16::decode(&mut reader).map_err(truncated)?;
+            let len = u32::decode(&mut reader).map_err(truncated)? as usize;
+            if len > MAX_FIELD_LENGTH {
+                return Err(DecodeError::Decode(TaggedDecodeError::FieldTooLong(tag)));
+            }
+            let mut body = Vec::allocate_with(len, || 0);
+            match reader.read_exact(&mut body) {
+                Ok(()) => {}
+                Err(ReadExactError::Read(err)) => return Err(DecodeError::Read(err)),
+                Err(_) => return Err(DecodeError::Decode(TaggedDecodeError::Truncated)),
+            }
+            if fields.insert(tag, body).is_some() {
+                return Err(DecodeError::Decode(TaggedDecodeError::DuplicateField(tag)));
+            }
+        }
+        Ok(Self { fields })
+    }
+}
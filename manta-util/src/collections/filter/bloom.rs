@@ -0,0 +1,168 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Bloom Filters
+
+use crate::collections::filter::{assert_valid_rate, ceil, log2, powi, Filter, Fnv1a};
+use alloc::{vec, vec::Vec};
+use core::{f64::consts::LN_2, hash::Hash};
+
+#[cfg(feature = "serde-alloc")]
+use crate::serde::{Deserialize, Serialize};
+
+/// Bloom Filter
+///
+/// A [`BloomFilter`] is a fixed-size probabilistic set which answers membership queries with no
+/// false negatives: an item that was inserted is always reported as present, but an item that was
+/// not inserted can be reported as present with a probability that depends on the number of bits,
+/// the number of hash functions, and the number of inserted items. Items cannot be removed from a
+/// [`BloomFilter`], see [`CuckooFilter`](super::CuckooFilter) for a filter which supports removal.
+#[cfg_attr(
+    feature = "serde-alloc",
+    derive(Deserialize, Serialize),
+    serde(crate = "crate::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct BloomFilter {
+    /// Bit Array
+    bits: Vec<u64>,
+
+    /// Number of Hash Functions
+    hashes: u32,
+
+    /// Number of Inserted Items
+    len: usize,
+}
+
+impl BloomFilter {
+    /// Builds a new empty [`BloomFilter`] with at least `bits`-many bits and `hashes`-many hash
+    /// functions.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `bits` or `hashes` is zero.
+    #[inline]
+    pub fn new(bits: usize, hashes: u32) -> Self {
+        assert!(bits > 0, "Bloom filters must have at least one bit.");
+        assert!(
+            hashes > 0,
+            "Bloom filters must have at least one hash function."
+        );
+        Self {
+            bits: vec![0; bits.div_ceil(64)],
+            hashes,
+            len: 0,
+        }
+    }
+
+    /// Builds a new empty [`BloomFilter`] sized to hold `capacity`-many items with a false
+    /// positive rate of at most `rate`, using the optimal number of bits and hash functions.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `capacity` is zero or if `rate` is not strictly between zero and one.
+    #[inline]
+    pub fn with_false_positive_rate(capacity: usize, rate: f64) -> Self {
+        assert!(capacity > 0, "Bloom filters must have a positive capacity.");
+        assert_valid_rate(rate);
+        let bits = ceil(-(capacity as f64) * log2(rate) / LN_2);
+        let hashes = ceil(bits as f64 / capacity as f64 * LN_2 - 0.5).max(1);
+        Self::new(bits, hashes as u32)
+    }
+
+    /// Returns the number of bits in `self`.
+    #[inline]
+    pub fn bit_count(&self) -> usize {
+        self.bits.len() * 64
+    }
+
+    /// Returns the number of hash functions used by `self`.
+    #[inline]
+    pub fn hash_count(&self) -> u32 {
+        self.hashes
+    }
+
+    /// Returns the number of bits set in `self`.
+    #[inline]
+    pub fn set_bit_count(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns the bit indices of `item` in `self` using double hashing.
+    #[inline]
+    fn indices<T>(&self, item: &T) -> impl Iterator<Item = usize>
+    where
+        T: Hash + ?Sized,
+    {
+        let bit_count = self.bit_count() as u64;
+        let first = Fnv1a::hash(Fnv1a::OFFSET_BASIS, item);
+        let second = Fnv1a::hash(first, item) | 1;
+        (0..u64::from(self.hashes))
+            .map(move |i| (first.wrapping_add(i.wrapping_mul(second)) % bit_count) as usize)
+    }
+}
+
+impl Filter for BloomFilter {
+    #[inline]
+    fn insert<T>(&mut self, item: &T) -> bool
+    where
+        T: Hash + ?Sized,
+    {
+        for index in self.indices(item) {
+            self.bits[index / 64] |= 1 << (index % 64);
+        }
+        self.len += 1;
+        true
+    }
+
+    #[inline]
+    fn may_contain<T>(&self, item: &T) -> bool
+    where
+        T: Hash + ?Sized,
+    {
+        self.indices(item)
+            .all(|index| self.bits[index / 64] & (1 << (index % 64)) != 0)
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.bits.iter_mut().for_each(|word| *word = 0);
+        self.len = 0;
+    }
+
+    /// Returns the number of insertions into `self`, counting repeated insertions of the same item
+    /// separately.
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the fraction of the bits of `self` which are set.
+    #[inline]
+    fn saturation(&self) -> f64 {
+        self.set_bit_count() as f64 / self.bit_count() as f64
+    }
+
+    /// Returns the probability that all the bits of an item which was not inserted are set, given
+    /// the current fraction of set bits.
+    #[inline]
+    fn false_positive_rate(&self) -> f64 {
+        powi(self.saturation(), self.hashes)
+    }
+}
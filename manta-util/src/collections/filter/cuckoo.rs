@@ -0,0 +1,285 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Cuckoo Filters
+
+use crate::collections::filter::{assert_valid_rate, ceil, log2, powi, Filter, Fnv1a};
+use alloc::{vec, vec::Vec};
+use core::{hash::Hash, mem};
+
+#[cfg(feature = "serde-alloc")]
+use crate::serde::{Deserialize, Serialize};
+
+/// Number of Fingerprints in each Bucket
+pub const BUCKET_SIZE: usize = 4;
+
+/// Cuckoo Filter
+///
+/// A [`CuckooFilter`] stores a short fingerprint of every inserted item in one of two candidate
+/// buckets, relocating the fingerprints of earlier items when both buckets are full. Unlike the
+/// [`BloomFilter`](super::BloomFilter), it supports [`remove`](Self::remove), at the cost of
+/// rejecting insertions once it is close to saturation.
+#[cfg_attr(
+    feature = "serde-alloc",
+    derive(Deserialize, Serialize),
+    serde(crate = "crate::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CuckooFilter {
+    /// Buckets
+    ///
+    /// Empty slots are marked by the zero fingerprint, which is never used for an item.
+    buckets: Vec<[u32; BUCKET_SIZE]>,
+
+    /// Number of Bits in each Fingerprint
+    fingerprint_bits: u32,
+
+    /// Victim
+    ///
+    /// Bucket index and fingerprint of the item which could not be placed after the maximum
+    /// number of relocations. While it is occupied, every insertion is rejected.
+    victim: Option<(usize, u32)>,
+
+    /// Number of Inserted Items
+    len: usize,
+}
+
+impl CuckooFilter {
+    /// Maximum Number of Relocations for a Single Insertion
+    pub const MAX_KICKS: usize = 500;
+
+    /// Maximum Load Factor used for Sizing
+    ///
+    /// Cuckoo filters with buckets of four fingerprints reliably reach this load factor before
+    /// insertions start failing.
+    pub const MAX_LOAD_FACTOR: f64 = 0.95;
+
+    /// Builds a new empty [`CuckooFilter`] with at least `buckets`-many buckets, rounded up to a
+    /// power of two, and fingerprints of `fingerprint_bits`-many bits.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `buckets` is zero or if `fingerprint_bits` is not between `1` and
+    /// `32`.
+    #[inline]
+    pub fn new(buckets: usize, fingerprint_bits: u32) -> Self {
+        assert!(buckets > 0, "Cuckoo filters must have at least one bucket.");
+        assert!(
+            (1..=32).contains(&fingerprint_bits),
+            "Cuckoo filter fingerprints must have between 1 and 32 bits."
+        );
+        Self {
+            buckets: vec![[0; BUCKET_SIZE]; buckets.next_power_of_two()],
+            fingerprint_bits,
+            victim: None,
+            len: 0,
+        }
+    }
+
+    /// Builds a new empty [`CuckooFilter`] sized to hold `capacity`-many items with a false
+    /// positive rate of at most `rate`.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if `capacity` is zero or if `rate` is not strictly between zero and one.
+    #[inline]
+    pub fn with_false_positive_rate(capacity: usize, rate: f64) -> Self {
+        assert!(
+            capacity > 0,
+            "Cuckoo filters must have a positive capacity."
+        );
+        assert_valid_rate(rate);
+        let buckets = ceil(capacity as f64 / (BUCKET_SIZE as f64 * Self::MAX_LOAD_FACTOR));
+        let fingerprint_bits = ceil(log2(2.0 * BUCKET_SIZE as f64 / rate)).min(32);
+        Self::new(buckets, fingerprint_bits as u32)
+    }
+
+    /// Returns the number of buckets in `self`.
+    #[inline]
+    pub fn bucket_count(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Returns the number of bits in each fingerprint of `self`.
+    #[inline]
+    pub fn fingerprint_bits(&self) -> u32 {
+        self.fingerprint_bits
+    }
+
+    /// Removes one copy of `item` from `self`, returning `true` if a matching fingerprint was
+    /// found.
+    ///
+    /// # Limitations
+    ///
+    /// Only items which were inserted into `self` should be removed, otherwise the fingerprint of
+    /// a different item which collides with `item` can be removed instead, making that item a
+    /// false negative.
+    #[inline]
+    pub fn remove<T>(&mut self, item: &T) -> bool
+    where
+        T: Hash + ?Sized,
+    {
+        let (index, fingerprint) = self.index_and_fingerprint(item);
+        let alternate = self.alternate_index(index, fingerprint);
+        if self.victim == Some((index, fingerprint))
+            || self.victim == Some((alternate, fingerprint))
+        {
+            self.victim = None;
+            self.len -= 1;
+            return true;
+        }
+        for index in [index, alternate] {
+            if let Some(slot) = self.buckets[index]
+                .iter_mut()
+                .find(|slot| **slot == fingerprint)
+            {
+                *slot = 0;
+                self.len -= 1;
+                self.place_victim();
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns the bucket index and the fingerprint of `item`.
+    #[inline]
+    fn index_and_fingerprint<T>(&self, item: &T) -> (usize, u32)
+    where
+        T: Hash + ?Sized,
+    {
+        let hash = Fnv1a::hash(Fnv1a::OFFSET_BASIS, item);
+        let mask = u32::MAX >> (32 - self.fingerprint_bits);
+        let fingerprint = match (hash >> 32) as u32 & mask {
+            0 => 1,
+            fingerprint => fingerprint,
+        };
+        (hash as usize & (self.buckets.len() - 1), fingerprint)
+    }
+
+    /// Returns the other bucket index for `fingerprint` stored at `index`. This map is an
+    /// involution, so the original index can be recovered from the alternate one.
+    #[inline]
+    fn alternate_index(&self, index: usize, fingerprint: u32) -> usize {
+        (index ^ Fnv1a::hash(Fnv1a::OFFSET_BASIS, &fingerprint) as usize) & (self.buckets.len() - 1)
+    }
+
+    /// Places `fingerprint` into a free slot of the bucket at `index`, returning `false` if the
+    /// bucket is full.
+    #[inline]
+    fn place(&mut self, index: usize, fingerprint: u32) -> bool {
+        match self.buckets[index].iter_mut().find(|slot| **slot == 0) {
+            Some(slot) => {
+                *slot = fingerprint;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Moves the victim into one of its buckets if a slot was freed there.
+    #[inline]
+    fn place_victim(&mut self) {
+        if let Some((index, fingerprint)) = self.victim {
+            let alternate = self.alternate_index(index, fingerprint);
+            if self.place(index, fingerprint) || self.place(alternate, fingerprint) {
+                self.victim = None;
+            }
+        }
+    }
+
+    /// Returns `true` if the bucket at `index` holds `fingerprint`.
+    #[inline]
+    fn bucket_contains(&self, index: usize, fingerprint: u32) -> bool {
+        self.buckets[index].contains(&fingerprint)
+    }
+}
+
+impl Filter for CuckooFilter {
+    /// Inserts `item` into `self`, relocating the fingerprints of earlier items if both of its
+    /// buckets are full. If the relocations do not converge, the last displaced fingerprint is
+    /// kept aside and every later insertion fails until an item is removed.
+    #[inline]
+    fn insert<T>(&mut self, item: &T) -> bool
+    where
+        T: Hash + ?Sized,
+    {
+        if self.victim.is_some() {
+            return false;
+        }
+        let (index, mut fingerprint) = self.index_and_fingerprint(item);
+        let alternate = self.alternate_index(index, fingerprint);
+        if self.place(index, fingerprint) || self.place(alternate, fingerprint) {
+            self.len += 1;
+            return true;
+        }
+        let mut index = alternate;
+        for kick in 0..Self::MAX_KICKS {
+            mem::swap(
+                &mut fingerprint,
+                &mut self.buckets[index][kick % BUCKET_SIZE],
+            );
+            index = self.alternate_index(index, fingerprint);
+            if self.place(index, fingerprint) {
+                self.len += 1;
+                return true;
+            }
+        }
+        self.victim = Some((index, fingerprint));
+        self.len += 1;
+        true
+    }
+
+    #[inline]
+    fn may_contain<T>(&self, item: &T) -> bool
+    where
+        T: Hash + ?Sized,
+    {
+        let (index, fingerprint) = self.index_and_fingerprint(item);
+        let alternate = self.alternate_index(index, fingerprint);
+        self.bucket_contains(index, fingerprint)
+            || self.bucket_contains(alternate, fingerprint)
+            || self.victim == Some((index, fingerprint))
+            || self.victim == Some((alternate, fingerprint))
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.buckets
+            .iter_mut()
+            .for_each(|bucket| *bucket = [0; BUCKET_SIZE]);
+        self.victim = None;
+        self.len = 0;
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the fraction of the fingerprint slots of `self` which are occupied.
+    #[inline]
+    fn saturation(&self) -> f64 {
+        self.len as f64 / (self.buckets.len() * BUCKET_SIZE) as f64
+    }
+
+    /// Returns the probability that one of the occupied slots in the two buckets of an item which
+    /// was not inserted matches its fingerprint.
+    #[inline]
+    fn false_positive_rate(&self) -> f64 {
+        (2.0 * BUCKET_SIZE as f64 * self.saturation() * powi(0.5, self.fingerprint_bits)).min(1.0)
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Probabilistic Filters
+//!
+//! In this module, we define the [`Filter`] abstraction over fixed-size probabilistic sets and two
+//! implementations: the [`BloomFilter`], which is the most compact for insert-only sets, and the
+//! [`CuckooFilter`], which also supports removing items. Both filters are sized from their
+//! expected capacity and target false positive rate, and report their saturation so that callers
+//! can rebuild them before the false positive rate degrades.
+
+use core::hash::{Hash, Hasher};
+
+mod bloom;
+mod cuckoo;
+
+pub use bloom::*;
+pub use cuckoo::*;
+
+/// Probabilistic Filter
+///
+/// A [`Filter`] answers membership queries with no false negatives: an item that was inserted is
+/// always reported as present, but an item that was not inserted can be reported as present with
+/// a probability given by [`false_positive_rate`](Self::false_positive_rate).
+pub trait Filter {
+    /// Inserts `item` into `self`, returning `false` if `self` is too saturated to hold it.
+    fn insert<T>(&mut self, item: &T) -> bool
+    where
+        T: Hash + ?Sized;
+
+    /// Returns `true` if `item` may have been inserted into `self`, and `false` if it was
+    /// definitely not inserted.
+    fn may_contain<T>(&self, item: &T) -> bool
+    where
+        T: Hash + ?Sized;
+
+    /// Removes all the items from `self`.
+    fn clear(&mut self);
+
+    /// Returns the number of items inserted into `self`.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if no items were inserted into `self`.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the fraction of the storage of `self` which is in use, from `0` for an empty filter
+    /// to `1` for a saturated one.
+    fn saturation(&self) -> f64;
+
+    /// Returns the estimated false positive rate of `self` at its current saturation.
+    fn false_positive_rate(&self) -> f64;
+}
+
+/// Asserts that `rate` is a valid target false positive rate.
+#[inline]
+fn assert_valid_rate(rate: f64) {
+    assert!(
+        rate > 0.0 && rate < 1.0,
+        "The false positive rate must be strictly between zero and one."
+    );
+}
+
+/// Returns the smallest integer greater than or equal to the non-negative `x`.
+///
+/// We cannot use [`f64::ceil`] since it is only available with `std`.
+#[inline]
+fn ceil(x: f64) -> usize {
+    let truncated = x as usize;
+    if (truncated as f64) < x {
+        truncated + 1
+    } else {
+        truncated
+    }
+}
+
+/// Returns the base-two logarithm of the positive `x`.
+///
+/// We cannot use [`f64::log2`] since it is only available with `std`, so we compute the integer
+/// part by repeated halving and the fractional part bit by bit by repeated squaring.
+#[inline]
+fn log2(mut x: f64) -> f64 {
+    let mut result = 0.0;
+    while x >= 2.0 {
+        x /= 2.0;
+        result += 1.0;
+    }
+    while x < 1.0 {
+        x *= 2.0;
+        result -= 1.0;
+    }
+    let mut bit = 0.5;
+    for _ in 0..f64::MANTISSA_DIGITS {
+        x *= x;
+        if x >= 2.0 {
+            x /= 2.0;
+            result += bit;
+        }
+        bit /= 2.0;
+    }
+    result
+}
+
+/// Returns `base` raised to the power `exponent`.
+///
+/// We cannot use [`f64::powi`] since it is only available with `std`.
+#[inline]
+fn powi(base: f64, exponent: u32) -> f64 {
+    (0..exponent).fold(1.0, |power, _| power * base)
+}
+
+/// FNV-1a Hasher
+///
+/// We use this hasher instead of the default hasher from the standard library since it is
+/// available without `std` and is not randomly seeded, so that serialized filters remain valid
+/// when they are loaded again.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    /// Offset Basis
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+
+    /// Prime
+    const PRIME: u64 = 0x100000001b3;
+
+    /// Hashes `item` starting from the given `state`.
+    #[inline]
+    fn hash<T>(state: u64, item: &T) -> u64
+    where
+        T: Hash + ?Sized,
+    {
+        let mut hasher = Self(state);
+        item.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl Hasher for Fnv1a {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+}
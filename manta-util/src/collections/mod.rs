@@ -17,6 +17,7 @@
 //! Collection Types
 
 pub mod btree_map;
+pub mod filter;
 pub mod vec_deque;
 
 #[doc(inline)]
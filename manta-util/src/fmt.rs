@@ -0,0 +1,330 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Formatting Utilities
+//!
+//! Cryptographic types are usually either large, like proofs and ciphertexts, or secret, like
+//! spending keys and mnemonics, so their derived [`Debug`] implementations are of little use in
+//! logs. This module contains wrappers which print byte strings in a compact encoding and
+//! helpers which elide secrets entirely.
+
+use core::fmt::{self, Debug, Display, Formatter};
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+use {
+    alloc::vec::Vec,
+    core::marker::PhantomData,
+    serde::{
+        de::{self, Unexpected, Visitor},
+        Deserialize, Deserializer,
+    },
+};
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer};
+
+/// Redacted Value Placeholder
+pub const REDACTED: &str = "<redacted>";
+
+/// Number of Leading Bytes Printed by [`HexPrefix`]
+pub const HEX_PREFIX_LENGTH: usize = 8;
+
+/// Formats any value as [`REDACTED`].
+///
+/// This function can be used as the `format_with` argument of `derivative`'s [`Debug`]
+/// implementation for fields which store secrets.
+#[inline]
+pub fn redact<T>(value: &T, f: &mut Formatter) -> fmt::Result
+where
+    T: ?Sized,
+{
+    let _ = value;
+    f.write_str(REDACTED)
+}
+
+/// Redacted Value
+///
+/// Formats as [`REDACTED`], for use in manual [`Debug`] implementations of types which store
+/// secrets.
+#[derive(Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Redacted;
+
+impl Debug for Redacted {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+/// Writes `bytes` into `f` as lowercase hexadecimal digits without any prefix.
+#[inline]
+fn write_hex(bytes: &[u8], f: &mut Formatter) -> fmt::Result {
+    for byte in bytes {
+        write!(f, "{byte:02x}")?;
+    }
+    Ok(())
+}
+
+/// Decodes `string` from lowercase or uppercase hexadecimal digits, with an optional `0x` prefix.
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[inline]
+fn decode_hex(string: &str) -> Option<Vec<u8>> {
+    let digits = string.strip_prefix("0x").unwrap_or(string).as_bytes();
+    let pairs = digits.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+    pairs
+        .map(|pair| {
+            let high = char::from(pair[0]).to_digit(16)?;
+            let low = char::from(pair[1]).to_digit(16)?;
+            Some((high * 16 + low) as u8)
+        })
+        .collect()
+}
+
+/// Decodes `string` from the Bitcoin base58 alphabet.
+#[cfg(all(feature = "alloc", feature = "bs58", feature = "serde"))]
+#[inline]
+fn decode_base58(string: &str) -> Option<Vec<u8>> {
+    bs58::decode(string).into_vec().ok()
+}
+
+/// Hexadecimal Display Wrapper
+///
+/// Formats the underlying bytes as `0x`-prefixed lowercase hexadecimal digits, both for
+/// [`Display`] and [`Debug`]. When the `serde` feature is enabled, the wrapper serializes into
+/// the same string and deserializes from it.
+#[derive(Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DisplayHex<T>(
+    /// Underlying Bytes
+    pub T,
+);
+
+impl<T> Display for DisplayHex<T>
+where
+    T: AsRef<[u8]>,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("0x")?;
+        write_hex(self.0.as_ref(), f)
+    }
+}
+
+impl<T> Debug for DisplayHex<T>
+where
+    T: AsRef<[u8]>,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+impl<T> Serialize for DisplayHex<T>
+where
+    T: AsRef<[u8]>,
+{
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "alloc", feature = "serde"))))]
+impl<'de, T> Deserialize<'de> for DisplayHex<T>
+where
+    T: TryFrom<Vec<u8>>,
+{
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer
+            .deserialize_str(EncodedBytesVisitor::new("a hexadecimal string", decode_hex))
+            .map(Self)
+    }
+}
+
+/// Base58 Display Wrapper
+///
+/// Formats the underlying bytes in the Bitcoin base58 alphabet, both for [`Display`] and
+/// [`Debug`]. When the `serde` feature is enabled, the wrapper serializes into the same string
+/// and deserializes from it.
+#[cfg(feature = "bs58")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "bs58")))]
+#[derive(Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DisplayBase58<T>(
+    /// Underlying Bytes
+    pub T,
+);
+
+#[cfg(feature = "bs58")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "bs58")))]
+impl<T> Display for DisplayBase58<T>
+where
+    T: AsRef<[u8]>,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&bs58::encode(self.0.as_ref()).into_string())
+    }
+}
+
+#[cfg(feature = "bs58")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "bs58")))]
+impl<T> Debug for DisplayBase58<T>
+where
+    T: AsRef<[u8]>,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(all(feature = "bs58", feature = "serde"))]
+#[cfg_attr(doc_cfg, doc(cfg(all(feature = "bs58", feature = "serde"))))]
+impl<T> Serialize for DisplayBase58<T>
+where
+    T: AsRef<[u8]>,
+{
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "bs58", feature = "serde"))]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "alloc", feature = "bs58", feature = "serde")))
+)]
+impl<'de, T> Deserialize<'de> for DisplayBase58<T>
+where
+    T: TryFrom<Vec<u8>>,
+{
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer
+            .deserialize_str(EncodedBytesVisitor::new("a base58 string", decode_base58))
+            .map(Self)
+    }
+}
+
+/// Encoded Bytes Visitor
+///
+/// Decodes a string with `decode` and converts the resulting bytes into `T`.
+#[cfg(all(feature = "alloc", feature = "serde"))]
+struct EncodedBytesVisitor<T, F> {
+    /// Expected Encoding
+    expecting: &'static str,
+
+    /// Decoding Function
+    decode: F,
+
+    /// Type Parameter Marker
+    __: PhantomData<T>,
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl<T, F> EncodedBytesVisitor<T, F> {
+    /// Builds a new [`EncodedBytesVisitor`] from `expecting` and `decode`.
+    #[inline]
+    fn new(expecting: &'static str, decode: F) -> Self {
+        Self {
+            expecting,
+            decode,
+            __: PhantomData,
+        }
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl<'de, T, F> Visitor<'de> for EncodedBytesVisitor<T, F>
+where
+    T: TryFrom<Vec<u8>>,
+    F: FnOnce(&str) -> Option<Vec<u8>>,
+{
+    type Value = T;
+
+    #[inline]
+    fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str(self.expecting)
+    }
+
+    #[inline]
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let expecting = self.expecting;
+        (self.decode)(v)
+            .and_then(|bytes| T::try_from(bytes).ok())
+            .ok_or_else(|| E::invalid_value(Unexpected::Str(v), &expecting))
+    }
+}
+
+/// Hexadecimal Prefix Display Wrapper
+///
+/// Formats only the first [`HEX_PREFIX_LENGTH`] bytes of the underlying bytes as `0x`-prefixed
+/// lowercase hexadecimal digits followed by the total length, which is enough to tell large
+/// values like proofs apart in logs.
+#[derive(Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct HexPrefix<T>(
+    /// Underlying Bytes
+    pub T,
+);
+
+impl<T> Display for HexPrefix<T>
+where
+    T: AsRef<[u8]>,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let bytes = self.0.as_ref();
+        f.write_str("0x")?;
+        if bytes.len() <= HEX_PREFIX_LENGTH {
+            return write_hex(bytes, f);
+        }
+        write_hex(&bytes[..HEX_PREFIX_LENGTH], f)?;
+        write!(f, "..({} bytes)", bytes.len())
+    }
+}
+
+impl<T> Debug for HexPrefix<T>
+where
+    T: AsRef<[u8]>,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
@@ -16,6 +16,10 @@
 
 //! HTTP Utilities
 
+#[cfg(feature = "socks")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "socks")))]
+pub mod proxy;
+
 #[cfg(all(feature = "serde", feature = "tide"))]
 #[cfg_attr(doc_cfg, doc(cfg(all(feature = "serde", feature = "tide"))))]
 pub mod tide;
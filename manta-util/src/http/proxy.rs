@@ -0,0 +1,142 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! SOCKS5 Proxy Configuration
+//!
+//! Clients which connect through a [`ProxyConfig`] let the proxy resolve the host names of the
+//! servers, so that they can reach onion services when the proxy is a Tor client and so that no
+//! DNS query leaks the servers they connect to.
+
+use alloc::{format, string::String};
+use core::sync::atomic::{AtomicU64, Ordering};
+
+#[cfg(feature = "serde")]
+use crate::serde::{Deserialize, Serialize};
+
+/// Stream Isolation
+///
+/// Tor sends the streams which were opened with different SOCKS5 credentials over different
+/// circuits, so that the exit relays and the servers cannot link them together. The isolation
+/// policy decides which credentials every connection uses.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "crate::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Isolation {
+    /// No Isolation
+    ///
+    /// Connections are opened without credentials, so the proxy is free to share circuits
+    /// between them.
+    None,
+
+    /// Per-Connection Isolation
+    ///
+    /// Every connection is opened with fresh credentials, so it never shares a circuit with
+    /// another connection.
+    #[default]
+    PerConnection,
+
+    /// Token Isolation
+    ///
+    /// Connections are opened with credentials derived from the token, so only the connections
+    /// with the same token can share a circuit.
+    Token(String),
+}
+
+/// SOCKS5 Proxy Configuration
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "crate::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ProxyConfig {
+    /// Proxy Address
+    ///
+    /// Host and port of the SOCKS5 proxy, like `127.0.0.1:9050`.
+    pub address: String,
+
+    /// Stream Isolation
+    pub isolation: Isolation,
+}
+
+impl ProxyConfig {
+    /// Default Address of the SOCKS5 Proxy of a Tor Client
+    pub const TOR_ADDRESS: &'static str = "127.0.0.1:9050";
+
+    /// Builds a new [`ProxyConfig`] for the SOCKS5 proxy at `address` with per-connection stream
+    /// isolation.
+    #[inline]
+    pub fn new<A>(address: A) -> Self
+    where
+        A: Into<String>,
+    {
+        Self {
+            address: address.into(),
+            isolation: Default::default(),
+        }
+    }
+
+    /// Builds a new [`ProxyConfig`] for the SOCKS5 proxy of a local Tor client with per-connection
+    /// stream isolation.
+    #[inline]
+    pub fn tor() -> Self {
+        Self::new(Self::TOR_ADDRESS)
+    }
+
+    /// Sets the stream isolation policy of `self` to `isolation`.
+    #[inline]
+    pub fn with_isolation(mut self, isolation: Isolation) -> Self {
+        self.isolation = isolation;
+        self
+    }
+
+    /// Returns the proxy URL of `self`. The `socks5h` scheme makes the proxy resolve the host
+    /// names of the servers.
+    #[inline]
+    pub fn url(&self) -> String {
+        format!("socks5h://{}", self.address)
+    }
+
+    /// Returns the username and password that a new connection should use to authenticate with
+    /// the proxy, or `None` if it should not authenticate.
+    #[inline]
+    pub fn credentials(&self) -> Option<(String, String)> {
+        /// Connection Counter
+        static CONNECTIONS: AtomicU64 = AtomicU64::new(0);
+        match &self.isolation {
+            Isolation::None => None,
+            Isolation::PerConnection => Some((
+                format!(
+                    "manta-{}-{}",
+                    std::process::id(),
+                    CONNECTIONS.fetch_add(1, Ordering::Relaxed)
+                ),
+                String::from("manta"),
+            )),
+            Isolation::Token(token) => Some((format!("manta-{token}"), String::from("manta"))),
+        }
+    }
+}
+
+impl Default for ProxyConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::tor()
+    }
+}
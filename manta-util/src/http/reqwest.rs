@@ -19,6 +19,9 @@
 #[cfg(feature = "serde")]
 use crate::serde::{de::DeserializeOwned, Serialize};
 
+#[cfg(feature = "socks")]
+use crate::http::proxy::ProxyConfig;
+
 #[doc(inline)]
 pub use reqwest::*;
 
@@ -46,6 +49,25 @@ impl KnownUrlClient {
         })
     }
 
+    /// Builds a new HTTP [`KnownUrlClient`] that connects to `server_url` through the SOCKS5
+    /// proxy described by `proxy`.
+    #[cfg(feature = "socks")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "socks")))]
+    #[inline]
+    pub fn with_proxy<U>(server_url: U, proxy: &ProxyConfig) -> Result<Self>
+    where
+        U: IntoUrl,
+    {
+        let mut socks = Proxy::all(proxy.url())?;
+        if let Some((username, password)) = proxy.credentials() {
+            socks = socks.basic_auth(&username, &password);
+        }
+        Ok(Self {
+            client: Client::builder().proxy(socks).build()?,
+            server_url: server_url.into_url()?,
+        })
+    }
+
     /// Sends a new request asynchronously of type `command` with query string `request`.
     #[cfg(feature = "serde")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
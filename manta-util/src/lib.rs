@@ -32,6 +32,7 @@ mod sealed;
 pub mod cmp;
 pub mod codec;
 pub mod convert;
+pub mod fmt;
 pub mod future;
 pub mod http;
 pub mod iter;
@@ -40,6 +41,7 @@ pub mod ops;
 pub mod persistence;
 pub mod pointer;
 pub mod time;
+pub mod zeroize;
 
 #[cfg(feature = "alloc")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
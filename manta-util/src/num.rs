@@ -68,6 +68,16 @@ pub trait CheckedSub<Rhs = Self> {
     fn checked_sub(self, rhs: Rhs) -> Option<Self::Output>;
 }
 
+/// Saturating Addition
+pub trait SaturatingAdd<Rhs = Self> {
+    /// Output Type
+    type Output;
+
+    /// Saturating integer addition. Computes `self + rhs`, returning the maximum value instead
+    /// of overflowing.
+    fn saturating_add(self, rhs: Rhs) -> Self::Output;
+}
+
 /// Checked Increment
 pub trait CheckedIncrement {
     /// Increments `self` returning `None` if it would overflow.
@@ -102,6 +112,15 @@ macro_rules! impl_checked {
                 }
             }
 
+            impl SaturatingAdd for $type {
+                type Output = Self;
+
+                #[inline]
+                fn saturating_add(self, rhs: Self) -> Self::Output {
+                    self.saturating_add(rhs)
+                }
+            }
+
             impl CheckedIncrement for $type {
                 #[inline]
                 fn checked_increment(&mut self) -> Option<&mut Self> {
@@ -146,3 +165,15 @@ where
         self.clone().checked_sub(rhs.clone())
     }
 }
+
+impl<A> SaturatingAdd for &A
+where
+    A: Clone + SaturatingAdd,
+{
+    type Output = A::Output;
+
+    #[inline]
+    fn saturating_add(self, rhs: Self) -> Self::Output {
+        self.clone().saturating_add(rhs.clone())
+    }
+}
@@ -17,7 +17,22 @@
 //! Vectors
 
 use crate::create_seal;
-use core::iter::{once, repeat_with};
+use core::{
+    borrow::Borrow,
+    fmt,
+    iter::{once, repeat_with},
+    mem,
+    ops::{Deref, DerefMut, RangeBounds},
+};
+
+#[cfg(feature = "serde")]
+use {
+    crate::serde::{
+        de::{Error, SeqAccess, Visitor},
+        Deserialize, Deserializer, Serialize, Serializer,
+    },
+    core::marker::PhantomData,
+};
 
 #[doc(inline)]
 pub use alloc::vec::*;
@@ -123,3 +138,356 @@ where
     }
     true
 }
+
+/// Capacity Error
+///
+/// This `struct` is returned when an operation would grow a [`BoundedVec`] past its maximum
+/// length. The value which could not be inserted is returned to the caller.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct CapacityError<T> {
+    /// Maximum Length
+    pub max: usize,
+
+    /// Rejected Value
+    pub value: T,
+}
+
+impl<T> CapacityError<T> {
+    /// Builds a new [`CapacityError`] from `max` and the rejected `value`.
+    #[inline]
+    pub fn new(max: usize, value: T) -> Self {
+        Self { max, value }
+    }
+
+    /// Drops the rejected value from `self`, keeping only the maximum length.
+    #[inline]
+    pub fn forget(self) -> CapacityError<()> {
+        CapacityError::new(self.max, ())
+    }
+}
+
+impl<T> fmt::Display for CapacityError<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Exceeded the maximum length of {}.", self.max)
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "std")))]
+impl<T> std::error::Error for CapacityError<T> where T: fmt::Debug {}
+
+/// Bounded Vector
+///
+/// This `struct` wraps a [`Vec`] whose length is guaranteed to never exceed `MAX`. All of the
+/// operations which can grow the vector are fallible and return a [`CapacityError`] instead of
+/// growing past the bound. Decoding a [`BoundedVec`] checks the length before allocating, so it
+/// should be used for any container whose length is controlled by an untrusted party.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct BoundedVec<T, const MAX: usize>(Vec<T>);
+
+impl<T, const MAX: usize> BoundedVec<T, MAX> {
+    /// Maximum Length
+    pub const MAX: usize = MAX;
+
+    /// Builds a new empty [`BoundedVec`].
+    #[inline]
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Builds a new empty [`BoundedVec`] with space for at least `capacity` elements, clamping
+    /// `capacity` to the maximum length.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(Vec::with_capacity(capacity.min(MAX)))
+    }
+
+    /// Maximum Number of Bytes Preallocated from an Untrusted Length Hint
+    pub const MAX_PREALLOCATION: usize = 1024 * 1024;
+
+    /// Builds a new empty [`BoundedVec`] with space for `capacity` elements when `capacity` comes
+    /// from an untrusted length hint, clamping it to the maximum length and to at most
+    /// [`MAX_PREALLOCATION`](Self::MAX_PREALLOCATION) bytes of elements.
+    ///
+    /// # Note
+    ///
+    /// This mirrors the cautious preallocation used by `serde` for its own sequence visitors. The
+    /// vector still grows up to the maximum length as elements are actually pushed, so a peer
+    /// which lies about the length can only make us allocate what it really sends.
+    #[inline]
+    pub fn with_cautious_capacity(capacity: usize) -> Self {
+        let element_cap = Self::MAX_PREALLOCATION / mem::size_of::<T>().max(1);
+        Self::with_capacity(capacity.min(element_cap))
+    }
+
+    /// Builds a new [`BoundedVec`] from `vec` if its length does not exceed the maximum length.
+    #[inline]
+    pub fn try_from_vec(vec: Vec<T>) -> Result<Self, CapacityError<Vec<T>>> {
+        if vec.len() > MAX {
+            return Err(CapacityError::new(MAX, vec));
+        }
+        Ok(Self(vec))
+    }
+
+    /// Builds a new [`BoundedVec`] from `array` if `N` does not exceed the maximum length.
+    #[inline]
+    pub fn try_from_array<const N: usize>(array: [T; N]) -> Result<Self, CapacityError<[T; N]>> {
+        if N > MAX {
+            return Err(CapacityError::new(MAX, array));
+        }
+        Ok(Self(array.into()))
+    }
+
+    /// Collects `iter` into a new [`BoundedVec`], returning the first element which does not fit
+    /// if `iter` yields more than the maximum length.
+    #[inline]
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, CapacityError<T>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let iter = iter.into_iter();
+        let mut vec = Self::with_capacity(iter.size_hint().0);
+        for item in iter {
+            vec.try_push(item)?;
+        }
+        Ok(vec)
+    }
+
+    /// Returns the number of elements `self` can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+
+    /// Returns the number of elements which can still be pushed onto `self`.
+    #[inline]
+    pub fn remaining_capacity(&self) -> usize {
+        MAX - self.0.len()
+    }
+
+    /// Returns `true` if `self` has reached the maximum length.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.0.len() == MAX
+    }
+
+    /// Appends `value` to the end of `self` if `self` has not reached the maximum length.
+    #[inline]
+    pub fn try_push(&mut self, value: T) -> Result<(), CapacityError<T>> {
+        if self.is_full() {
+            return Err(CapacityError::new(MAX, value));
+        }
+        self.0.push(value);
+        Ok(())
+    }
+
+    /// Removes the last element from `self` and returns it, or `None` if `self` is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.0.pop()
+    }
+
+    /// Removes the elements in `range` from `self`, returning them as an iterator.
+    #[inline]
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.0.drain(range)
+    }
+
+    /// Shortens `self` to `len` elements, doing nothing if `len` is greater than the current length.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.0.truncate(len)
+    }
+
+    /// Extends `self` with the elements of `iter`, returning the first element which does not fit
+    /// if `iter` yields too many elements. The elements before the rejected one are kept.
+    #[inline]
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), CapacityError<T>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iter {
+            self.try_push(item)?;
+        }
+        Ok(())
+    }
+
+    /// Converts `self` into an array of length `N`, returning `self` back if its length is not
+    /// exactly `N`.
+    #[inline]
+    pub fn try_into_array<const N: usize>(self) -> Result<[T; N], Self> {
+        self.0.try_into().map_err(Self)
+    }
+
+    /// Returns the underlying vector of `self`.
+    #[inline]
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T, const MAX: usize> AsRef<[T]> for BoundedVec<T, MAX> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T, const MAX: usize> Borrow<[T]> for BoundedVec<T, MAX> {
+    #[inline]
+    fn borrow(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T, const MAX: usize> Default for BoundedVec<T, MAX> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const MAX: usize> Deref for BoundedVec<T, MAX> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, const MAX: usize> DerefMut for BoundedVec<T, MAX> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T, const MAX: usize> From<BoundedVec<T, MAX>> for Vec<T> {
+    #[inline]
+    fn from(vec: BoundedVec<T, MAX>) -> Self {
+        vec.0
+    }
+}
+
+impl<T, const MAX: usize> TryFrom<Vec<T>> for BoundedVec<T, MAX> {
+    type Error = CapacityError<Vec<T>>;
+
+    #[inline]
+    fn try_from(vec: Vec<T>) -> Result<Self, Self::Error> {
+        Self::try_from_vec(vec)
+    }
+}
+
+impl<T, const MAX: usize> IntoIterator for BoundedVec<T, MAX> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'t, T, const MAX: usize> IntoIterator for &'t BoundedVec<T, MAX> {
+    type Item = &'t T;
+    type IntoIter = core::slice::Iter<'t, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<'t, T, const MAX: usize> IntoIterator for &'t mut BoundedVec<T, MAX> {
+    type Item = &'t mut T;
+    type IntoIter = core::slice::IterMut<'t, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter_mut()
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+impl<T, const MAX: usize> Serialize for BoundedVec<T, MAX>
+where
+    T: Serialize,
+{
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.as_slice().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+impl<'de, T, const MAX: usize> Deserialize<'de> for BoundedVec<T, MAX>
+where
+    T: Deserialize<'de>,
+{
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        /// Bounded Sequence Visitor
+        struct BoundedVisitor<T, const MAX: usize>(PhantomData<T>);
+
+        impl<'de, T, const MAX: usize> Visitor<'de> for BoundedVisitor<T, MAX>
+        where
+            T: Deserialize<'de>,
+        {
+            type Value = BoundedVec<T, MAX>;
+
+            #[inline]
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a sequence of at most {MAX} elements")
+            }
+
+            #[inline]
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                if let Some(len) = seq.size_hint() {
+                    if len > MAX {
+                        return Err(A::Error::invalid_length(len, &self));
+                    }
+                }
+                let mut vec = BoundedVec::with_cautious_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(item) = seq.next_element()? {
+                    vec.try_push(item)
+                        .map_err(|_| A::Error::invalid_length(MAX + 1, &self))?;
+                }
+                Ok(vec)
+            }
+        }
+
+        deserializer.deserialize_seq(BoundedVisitor(PhantomData))
+    }
+}
+
+/// Deserializes a vector of at most `MAX` elements, failing before allocating if the input is
+/// longer than `MAX`.
+///
+/// This function can be used with `#[serde(deserialize_with = "...")]` to bound container fields
+/// whose type is [`Vec`].
+#[cfg(feature = "serde")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "serde")))]
+#[inline]
+pub fn deserialize_bounded<'de, D, T, const MAX: usize>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(BoundedVec::<T, MAX>::deserialize(deserializer)?.into_inner())
+}
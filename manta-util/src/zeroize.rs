@@ -0,0 +1,214 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Secret Memory Zeroization
+//!
+//! Secrets like spending keys and decrypted notes should not outlive their use in memory. The
+//! compiler is free to remove writes to memory which is never read again, so wiping a secret
+//! with a plain assignment right before it is dropped does not work. The [`Zeroize`] trait in
+//! this module uses volatile writes followed by a compiler fence instead, which are never elided.
+
+use crate::{fmt::Redacted, Array};
+use core::{
+    fmt::{self, Debug, Formatter},
+    ops::{Deref, DerefMut},
+    ptr,
+    sync::atomic::{self, Ordering},
+};
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+/// Secret Memory Zeroization
+pub trait Zeroize {
+    /// Overwrites `self` with zeros, or with some other value which does not depend on the
+    /// current value of `self`, in a way which is not removed by the optimizer.
+    fn zeroize(&mut self);
+}
+
+/// Overwrites `value` with its default value in a way which is not removed by the optimizer.
+///
+/// This function can be used to implement [`Zeroize`] for types whose default value does not
+/// carry any secret, like field elements or group elements whose default value is zero.
+#[inline]
+pub fn zeroize_with_default<T>(value: &mut T)
+where
+    T: Copy + Default,
+{
+    // SAFETY: `value` is a valid and aligned pointer since it comes from a mutable reference, and
+    // `T: Copy` has no destructor, so overwriting it without dropping the old value is sound.
+    unsafe { ptr::write_volatile(value, T::default()) };
+    atomic::compiler_fence(Ordering::SeqCst);
+}
+
+/// Implements [`Zeroize`] for `$type` using [`zeroize_with_default`].
+macro_rules! impl_zeroize_with_default {
+    ($($type:ty),* $(,)?) => {
+        $(
+            impl Zeroize for $type {
+                #[inline]
+                fn zeroize(&mut self) {
+                    zeroize_with_default(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_zeroize_with_default!(
+    bool, char, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+impl<T> Zeroize for [T]
+where
+    T: Zeroize,
+{
+    #[inline]
+    fn zeroize(&mut self) {
+        for element in self {
+            element.zeroize();
+        }
+    }
+}
+
+impl<T, const N: usize> Zeroize for [T; N]
+where
+    T: Zeroize,
+{
+    #[inline]
+    fn zeroize(&mut self) {
+        self.as_mut_slice().zeroize()
+    }
+}
+
+impl<T, const N: usize> Zeroize for Array<T, N>
+where
+    T: Zeroize,
+{
+    #[inline]
+    fn zeroize(&mut self) {
+        self.0.zeroize()
+    }
+}
+
+impl<T> Zeroize for Option<T>
+where
+    T: Zeroize,
+{
+    #[inline]
+    fn zeroize(&mut self) {
+        if let Some(value) = self {
+            value.zeroize();
+        }
+        *self = None;
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<T> Zeroize for Vec<T>
+where
+    T: Zeroize,
+{
+    /// Zeroizes every element of `self` and then clears it. The spare capacity of `self` is not
+    /// touched, so secrets should not be pushed into vectors which may reallocate.
+    #[inline]
+    fn zeroize(&mut self) {
+        self.as_mut_slice().zeroize();
+        self.clear();
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl Zeroize for String {
+    #[inline]
+    fn zeroize(&mut self) {
+        core::mem::take(self).into_bytes().zeroize()
+    }
+}
+
+/// Zeroizing Wrapper
+///
+/// Wraps a value which is zeroized when the wrapper is dropped. The wrapper dereferences to the
+/// underlying value and never prints it in its [`Debug`] implementation.
+#[derive(Clone, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Zeroizing<T>(T)
+where
+    T: Zeroize;
+
+impl<T> Zeroizing<T>
+where
+    T: Zeroize,
+{
+    /// Builds a new [`Zeroizing`] wrapper around `value`.
+    #[inline]
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> Debug for Zeroizing<T>
+where
+    T: Zeroize,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("Zeroizing").field(&Redacted).finish()
+    }
+}
+
+impl<T> Deref for Zeroizing<T>
+where
+    T: Zeroize,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Zeroizing<T>
+where
+    T: Zeroize,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for Zeroizing<T>
+where
+    T: Zeroize,
+{
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T> Drop for Zeroizing<T>
+where
+    T: Zeroize,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.0.zeroize()
+    }
+}